@@ -0,0 +1,223 @@
+//! A/B interface comparison.
+//!
+//! "Is the cable really better than Wi-Fi here?" and "what does the VPN
+//! cost?" are answered by running the same battery over each interface in
+//! turn: echo requests to one target, a short download and a few DNS
+//! lookups, with every socket bound to that interface's address. The two
+//! sets of figures are then set side by side metric by metric.
+
+use crate::{AbLeg, AbSide, Language};
+
+/// Two figures closer than this share of the larger one are a tie.
+const TIE_SHARE: f64 = 0.1;
+/// Loss is compared in percentage points instead: 0 % against 0.5 % is
+/// one lost echo, not a difference between the links.
+const LOSS_TIE_POINTS: f64 = 1.0;
+
+impl AbSide {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::B => "B",
+        }
+    }
+}
+
+impl AbLeg {
+    /// A leg from its echo replies, `None` for each lost one; the download
+    /// and DNS figures are filled in as those parts finish.
+    pub fn from_replies(side: AbSide, via: &str, source: &str, replies: &[Option<f64>]) -> Self {
+        let answered = replies.iter().flatten().copied().collect::<Vec<_>>();
+        let differences = replies
+            .windows(2)
+            .filter_map(|pair| Some((pair[0]? - pair[1]?).abs()))
+            .collect::<Vec<_>>();
+        Self {
+            side,
+            via: via.into(),
+            source: source.into(),
+            sent: replies.len() as u32,
+            received: answered.len() as u32,
+            latency_ms: (!answered.is_empty())
+                .then(|| answered.iter().sum::<f64>() / answered.len() as f64),
+            jitter_ms: (!differences.is_empty())
+                .then(|| differences.iter().sum::<f64>() / differences.len() as f64),
+            download_bytes_per_second: None,
+            dns_ms: None,
+            error: None,
+        }
+    }
+
+    /// Record the DNS lookup times that got an answer.
+    pub fn record_dns(&mut self, lookups: Vec<f64>) {
+        self.dns_ms = crate::dns_bench::median(lookups);
+    }
+
+    pub fn loss_percent(&self) -> Option<f64> {
+        (self.sent > 0).then(|| f64::from(self.sent - self.received) * 100.0 / f64::from(self.sent))
+    }
+}
+
+/// A row of the comparison table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbMetric {
+    Latency,
+    Jitter,
+    Loss,
+    Download,
+    Dns,
+}
+
+/// How one metric came out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbOutcome {
+    Better(AbSide),
+    Tie,
+}
+
+impl AbMetric {
+    pub const ALL: [Self; 5] = [
+        Self::Latency,
+        Self::Jitter,
+        Self::Loss,
+        Self::Download,
+        Self::Dns,
+    ];
+
+    pub const fn label(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::Latency, Language::Zh) => "平均延迟",
+            (Self::Jitter, Language::Zh) => "抖动",
+            (Self::Loss, Language::Zh) => "丢包",
+            (Self::Download, Language::Zh) => "下载",
+            (Self::Dns, Language::Zh) => "DNS 解析",
+            (Self::Latency, Language::En) => "Latency",
+            (Self::Jitter, Language::En) => "Jitter",
+            (Self::Loss, Language::En) => "Loss",
+            (Self::Download, Language::En) => "Download",
+            (Self::Dns, Language::En) => "DNS lookup",
+        }
+    }
+
+    pub fn value(self, leg: &AbLeg) -> Option<f64> {
+        match self {
+            Self::Latency => leg.latency_ms,
+            Self::Jitter => leg.jitter_ms,
+            Self::Loss => leg.loss_percent(),
+            Self::Download => leg.download_bytes_per_second,
+            Self::Dns => leg.dns_ms,
+        }
+    }
+
+    /// Which side did better; a side with no figure loses to one with a
+    /// figure, and `None` means neither has one.
+    pub fn outcome(self, a: &AbLeg, b: &AbLeg) -> Option<AbOutcome> {
+        let (a, b) = match (self.value(a), self.value(b)) {
+            (None, None) => return None,
+            (Some(_), None) => return Some(AbOutcome::Better(AbSide::A)),
+            (None, Some(_)) => return Some(AbOutcome::Better(AbSide::B)),
+            (Some(a), Some(b)) => (a, b),
+        };
+        let margin = match self {
+            Self::Loss => LOSS_TIE_POINTS,
+            _ => a.max(b) * TIE_SHARE,
+        };
+        if (a - b).abs() <= margin {
+            return Some(AbOutcome::Tie);
+        }
+        let a_better = if self == Self::Download { a > b } else { a < b };
+        Some(AbOutcome::Better(if a_better {
+            AbSide::A
+        } else {
+            AbSide::B
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbVerdict {
+    /// The side that won more metrics; `None` when they won as many.
+    pub winner: Option<AbSide>,
+    pub wins_a: usize,
+    pub wins_b: usize,
+}
+
+pub fn ab_verdict(a: &AbLeg, b: &AbLeg) -> AbVerdict {
+    let (mut wins_a, mut wins_b) = (0, 0);
+    for metric in AbMetric::ALL {
+        match metric.outcome(a, b) {
+            Some(AbOutcome::Better(AbSide::A)) => wins_a += 1,
+            Some(AbOutcome::Better(AbSide::B)) => wins_b += 1,
+            _ => {}
+        }
+    }
+    AbVerdict {
+        winner: match wins_a.cmp(&wins_b) {
+            std::cmp::Ordering::Greater => Some(AbSide::A),
+            std::cmp::Ordering::Less => Some(AbSide::B),
+            std::cmp::Ordering::Equal => None,
+        },
+        wins_a,
+        wins_b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close_figures_tie_and_the_side_with_more_wins_is_the_verdict() {
+        let mut wired = AbLeg::from_replies(
+            AbSide::A,
+            "eth0",
+            "192.168.1.20",
+            &[Some(4.0), Some(6.0), Some(5.0), Some(5.0)],
+        );
+        wired.download_bytes_per_second = Some(11_000_000.0);
+        wired.record_dns(vec![12.0, 30.0, 11.0]);
+        let mut wireless = AbLeg::from_replies(
+            AbSide::B,
+            "wlan0",
+            "192.168.1.21",
+            &[Some(20.0), None, Some(40.0), Some(24.0)],
+        );
+        wireless.download_bytes_per_second = Some(10_500_000.0);
+        wireless.record_dns(vec![12.5]);
+
+        assert_eq!(wired.latency_ms, Some(5.0));
+        assert_eq!(
+            wireless.jitter_ms,
+            Some(16.0),
+            "the lost echo breaks two pairs"
+        );
+        assert_eq!(wireless.loss_percent(), Some(25.0));
+        assert_eq!(
+            AbMetric::Latency.outcome(&wired, &wireless),
+            Some(AbOutcome::Better(AbSide::A))
+        );
+        assert_eq!(
+            AbMetric::Download.outcome(&wired, &wireless),
+            Some(AbOutcome::Tie)
+        );
+        assert_eq!(
+            AbMetric::Dns.outcome(&wired, &wireless),
+            Some(AbOutcome::Tie)
+        );
+
+        let verdict = ab_verdict(&wired, &wireless);
+        assert_eq!(
+            (verdict.winner, verdict.wins_a, verdict.wins_b),
+            (Some(AbSide::A), 3, 0)
+        );
+
+        // A side that could not download at all loses that row.
+        wired.download_bytes_per_second = None;
+        assert_eq!(
+            AbMetric::Download.outcome(&wired, &wireless),
+            Some(AbOutcome::Better(AbSide::B))
+        );
+        wireless.download_bytes_per_second = None;
+        assert_eq!(AbMetric::Download.outcome(&wired, &wireless), None);
+    }
+}
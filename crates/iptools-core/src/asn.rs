@@ -0,0 +1,129 @@
+//! Who routes the routers on a traced path.
+//!
+//! On request the trace view looks up the public hop addresses in a BGP
+//! data source: the prefix that covers each one, the AS announcing it, the
+//! AS holder's name and an AS path seen by a route collector. Private and
+//! shared address space is never sent out.
+
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{JobId, RuntimeError, TraceHop};
+
+/// Routing origin of one address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsnOrigin {
+    pub address: String,
+    /// Most specific announced prefix covering the address.
+    pub prefix: Option<String>,
+    /// `None` when nothing announces the address.
+    pub asn: Option<u32>,
+    pub holder: Option<String>,
+    /// One AS path towards the prefix, from a collector peer to the origin.
+    pub path: Vec<u32>,
+}
+
+impl AsnOrigin {
+    /// `AS15169 GOOGLE`, or `AS15169` without a holder; empty if unrouted.
+    pub fn label(&self) -> String {
+        match (self.asn, &self.holder) {
+            (Some(asn), Some(holder)) => format!("AS{asn} {holder}"),
+            (Some(asn), None) => format!("AS{asn}"),
+            (None, _) => String::new(),
+        }
+    }
+}
+
+/// Lookups for the trace view, kept across runs so a repeated trace over
+/// the same routers costs no more queries.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AsnState {
+    pub job: Option<JobId>,
+    pub origins: Vec<AsnOrigin>,
+    pub error: Option<RuntimeError>,
+}
+
+impl AsnState {
+    pub fn origin(&self, address: &str) -> Option<&AsnOrigin> {
+        self.origins.iter().find(|origin| origin.address == address)
+    }
+
+    /// Routable addresses among `hops` not looked up yet, in path order.
+    pub fn pending<'a>(&self, hops: impl IntoIterator<Item = &'a TraceHop>) -> Vec<String> {
+        let mut addresses: Vec<String> = Vec::new();
+        for address in hops.into_iter().filter_map(|hop| hop.address.as_deref()) {
+            if is_routable(address)
+                && self.origin(address).is_none()
+                && !addresses.iter().any(|known| known == address)
+            {
+                addresses.push(address.to_string());
+            }
+        }
+        addresses
+    }
+}
+
+/// Whether BGP data can say anything about `address`: private, shared
+/// (carrier-grade NAT), loopback, link-local and multicast space is local.
+pub fn is_routable(address: &str) -> bool {
+    match address.parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => {
+            let [first, second, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_multicast()
+                || v4.is_broadcast()
+                || (first == 100 && (64..128).contains(&second)))
+        }
+        Ok(IpAddr::V6(v6)) => {
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80)
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_new_public_addresses_are_looked_up() {
+        let hop = |ttl, address: Option<&str>| TraceHop {
+            ttl,
+            address: address.map(str::to_string),
+            hostname: None,
+            latency_ms: None,
+        };
+        let mut state = AsnState::default();
+        state.origins.push(AsnOrigin {
+            address: "193.0.6.139".into(),
+            prefix: Some("193.0.0.0/21".into()),
+            asn: Some(3333),
+            holder: Some("RIPE-NCC-AS".into()),
+            path: vec![6939, 3333],
+        });
+        let hops = [
+            hop(1, Some("192.168.1.1")),
+            hop(2, Some("100.72.0.1")),
+            hop(3, None),
+            hop(4, Some("8.8.8.8")),
+            hop(5, Some("193.0.6.139")),
+            hop(6, Some("8.8.8.8")),
+            hop(7, Some("2001:4860:4860::8888")),
+            hop(8, Some("fe80::1")),
+        ];
+        assert_eq!(state.pending(&hops), ["8.8.8.8", "2001:4860:4860::8888"]);
+        assert_eq!(
+            state.origin("193.0.6.139").unwrap().label(),
+            "AS3333 RIPE-NCC-AS"
+        );
+    }
+}
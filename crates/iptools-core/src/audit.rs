@@ -0,0 +1,445 @@
+//! Security audit of the local network.
+//!
+//! The audit adds nothing to scan on its own: it reads what the scanner
+//! already found, plus one DHCP probe, and lists what an attacker on the LAN
+//! would look for first. Cleartext logins (Telnet, FTP), file servers still
+//! speaking SMBv1, admin pages of devices known to ship with a default
+//! password, an address answered by two MACs and DHCP servers other than the
+//! router. Findings are ordered by [`AuditSeverity`] and can be saved as a
+//! Markdown report.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{JobId, Language, RuntimeError, ScanHost};
+
+pub const FTP_PORT: u16 = 21;
+pub const TELNET_PORT: u16 = 23;
+/// FTP and Telnet; port enrichment checks them for the audit.
+pub const AUDIT_PORTS: [u16; 2] = [FTP_PORT, TELNET_PORT];
+
+/// Lowercase fragments of the page title or `Server` header of admin pages
+/// that ship with a well-known default login. A heuristic: the finding asks
+/// the user to check, it does not try the password.
+const DEFAULT_LOGIN_MARKERS: [&str; 14] = [
+    "routeros",
+    "tp-link",
+    "tplink",
+    "netgear",
+    "d-link",
+    "linksys",
+    "zyxel",
+    "openwrt",
+    "luci",
+    "dd-wrt",
+    "hikvision",
+    "dahua",
+    "network camera",
+    "ip camera",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AuditSeverity {
+    High,
+    Medium,
+    Low,
+}
+
+impl AuditSeverity {
+    pub const fn label(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::High, Language::Zh) => "高",
+            (Self::Medium, Language::Zh) => "中",
+            (Self::Low, Language::Zh) => "低",
+            (Self::High, Language::En) => "High",
+            (Self::Medium, Language::En) => "Medium",
+            (Self::Low, Language::En) => "Low",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditCheck {
+    Telnet,
+    Ftp,
+    SmbV1,
+    DefaultLogin,
+    DuplicateIp,
+    RogueDhcp,
+}
+
+impl AuditCheck {
+    pub const fn title(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::Telnet, Language::Zh) => "开放 Telnet",
+            (Self::Ftp, Language::Zh) => "开放 FTP",
+            (Self::SmbV1, Language::Zh) => "接受 SMBv1",
+            (Self::DefaultLogin, Language::Zh) => "可能仍用默认密码的管理页",
+            (Self::DuplicateIp, Language::Zh) => "IP 地址冲突",
+            (Self::RogueDhcp, Language::Zh) => "可疑 DHCP 服务器",
+            (Self::Telnet, Language::En) => "Telnet open",
+            (Self::Ftp, Language::En) => "FTP open",
+            (Self::SmbV1, Language::En) => "SMBv1 accepted",
+            (Self::DefaultLogin, Language::En) => "Admin page with a default login",
+            (Self::DuplicateIp, Language::En) => "Duplicate IP address",
+            (Self::RogueDhcp, Language::En) => "Rogue DHCP server",
+        }
+    }
+
+    /// What to do about it, one sentence.
+    pub const fn advice(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::Telnet, Language::Zh) => "登录凭据以明文传输；改用 SSH 并关闭 Telnet。",
+            (Self::Ftp, Language::Zh) => "登录凭据以明文传输；改用 SFTP 或 FTPS。",
+            (Self::SmbV1, Language::Zh) => "SMBv1 有可蠕虫传播的漏洞；在设备上关闭它。",
+            (Self::DefaultLogin, Language::Zh) => {
+                "确认管理员密码已改过，且登录页不对访客网络开放。"
+            }
+            (Self::DuplicateIp, Language::Zh) => {
+                "两台设备在用同一地址，或有人在冒充它；核对 MAC 地址。"
+            }
+            (Self::RogueDhcp, Language::Zh) => {
+                "只应由路由器分配地址；找到并关闭多余的 DHCP 服务器。"
+            }
+            (Self::Telnet, Language::En) => {
+                "Logins cross the network in clear text; use SSH and turn Telnet off."
+            }
+            (Self::Ftp, Language::En) => {
+                "Logins cross the network in clear text; use SFTP or FTPS instead."
+            }
+            (Self::SmbV1, Language::En) => {
+                "SMBv1 has wormable vulnerabilities; disable it on the device."
+            }
+            (Self::DefaultLogin, Language::En) => {
+                "Make sure the admin password was changed and the page is not open to guests."
+            }
+            (Self::DuplicateIp, Language::En) => {
+                "Two devices share the address, or one is impersonating the other; check the MACs."
+            }
+            (Self::RogueDhcp, Language::En) => {
+                "Only the router should hand out addresses; find and disable the extra server."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditFinding {
+    pub severity: AuditSeverity,
+    pub check: AuditCheck,
+    /// The host or DHCP server the finding is about.
+    pub ip: String,
+    /// The facts behind it: the port, page title or MACs involved.
+    pub detail: String,
+}
+
+/// A DHCP server that answered the audit's DHCPDISCOVER.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DhcpServer {
+    /// Server identifier (option 54), or the sender when it gives none.
+    pub server: String,
+    pub offered: String,
+    /// Router option (3); empty when absent.
+    pub router: String,
+}
+
+/// The audit popup: the DHCP probe and the Markdown export.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AuditState {
+    pub dhcp_job: Option<JobId>,
+    /// Servers that answered the last probe; `None` until one finished.
+    pub dhcp: Option<Vec<DhcpServer>>,
+    pub dhcp_error: Option<RuntimeError>,
+    pub export_job: Option<JobId>,
+    /// Path of the last report written.
+    pub saved: Option<String>,
+    pub export_error: Option<RuntimeError>,
+    pub scroll: usize,
+}
+
+/// Findings over the scanned `hosts` and the DHCP servers that answered,
+/// most severe first. `gateway` is the default gateway of the active
+/// interface: the DHCP server home networks expect.
+pub fn audit_findings(
+    hosts: &[ScanHost],
+    dhcp: &[DhcpServer],
+    gateway: Option<&str>,
+) -> Vec<AuditFinding> {
+    let mut findings = Vec::new();
+    for host in hosts {
+        let mut add = |severity, check, detail: String| {
+            findings.push(AuditFinding {
+                severity,
+                check,
+                ip: host.ip.clone(),
+                detail,
+            });
+        };
+        if !host.other_macs.is_empty() {
+            add(
+                AuditSeverity::High,
+                AuditCheck::DuplicateIp,
+                std::iter::once(host.mac.as_str())
+                    .chain(host.other_macs.iter().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+        if host.open_ports.contains(&TELNET_PORT) {
+            add(
+                AuditSeverity::High,
+                AuditCheck::Telnet,
+                format!("TCP {TELNET_PORT}"),
+            );
+        }
+        if host.smb1 {
+            add(AuditSeverity::High, AuditCheck::SmbV1, "TCP 445".into());
+        }
+        if host.open_ports.contains(&FTP_PORT) {
+            add(
+                AuditSeverity::Medium,
+                AuditCheck::Ftp,
+                format!("TCP {FTP_PORT}"),
+            );
+        }
+        for service in &host.web {
+            let text = format!("{} {}", service.title, service.server).to_lowercase();
+            if DEFAULT_LOGIN_MARKERS
+                .iter()
+                .any(|marker| text.contains(marker))
+            {
+                let page = [service.title.trim(), service.server.trim()]
+                    .into_iter()
+                    .find(|text| !text.is_empty())
+                    .unwrap_or_default();
+                add(
+                    AuditSeverity::Medium,
+                    AuditCheck::DefaultLogin,
+                    format!("{page} · TCP {}", service.port),
+                );
+            }
+        }
+    }
+    findings.extend(dhcp_findings(dhcp, gateway));
+    findings.sort_by(|left, right| {
+        left.severity
+            .cmp(&right.severity)
+            .then_with(|| ip_order(&left.ip).cmp(&ip_order(&right.ip)))
+    });
+    findings
+}
+
+/// With the gateway among the answers every other server is rogue. Without
+/// it, several servers still disagree about who hands out addresses; a lone
+/// server that is not the gateway may be deliberate (a Pi-hole) and is only
+/// worth a look.
+fn dhcp_findings(dhcp: &[DhcpServer], gateway: Option<&str>) -> Vec<AuditFinding> {
+    let mut servers = dhcp.iter().collect::<Vec<_>>();
+    servers.sort_by_key(|server| ip_order(&server.server));
+    servers.dedup_by(|left, right| left.server == right.server);
+    let several = servers.len() > 1;
+    let gateway_answered =
+        gateway.is_some_and(|gateway| servers.iter().any(|server| server.server == gateway));
+    let severity = if gateway_answered {
+        AuditSeverity::High
+    } else if several {
+        AuditSeverity::Medium
+    } else {
+        AuditSeverity::Low
+    };
+    servers
+        .into_iter()
+        .filter(|server| match gateway {
+            Some(gateway) => server.server != gateway,
+            None => several,
+        })
+        .map(|server| AuditFinding {
+            severity,
+            check: AuditCheck::RogueDhcp,
+            ip: server.server.clone(),
+            detail: if server.router.is_empty() {
+                format!("offers {}", server.offered)
+            } else {
+                format!("offers {} via {}", server.offered, server.router)
+            },
+        })
+        .collect()
+}
+
+fn ip_order(ip: &str) -> (u8, u32) {
+    match ip.parse::<std::net::Ipv4Addr>() {
+        Ok(address) => (0, u32::from(address)),
+        Err(_) => (1, 0),
+    }
+}
+
+/// The findings as a Markdown report. `scanned_at` is when the newest host
+/// was seen; `dhcp` is how many DHCP servers answered, if the probe ran.
+pub fn audit_markdown(
+    findings: &[AuditFinding],
+    hosts: usize,
+    scanned_at: Option<&str>,
+    dhcp: Option<usize>,
+    language: Language,
+) -> String {
+    let zh = language == Language::Zh;
+    let mut out = String::from(if zh {
+        "# 局域网安全审计\n\n"
+    } else {
+        "# Network security audit\n\n"
+    });
+    let mut facts = vec![if zh {
+        format!("扫描主机 {hosts} 台")
+    } else {
+        format!("{hosts} hosts scanned")
+    }];
+    if let Some(at) = scanned_at {
+        facts.push(if zh {
+            format!("扫描于 {at}")
+        } else {
+            format!("scan of {at}")
+        });
+    }
+    facts.push(match (dhcp, zh) {
+        (Some(servers), true) => format!("{servers} 台 DHCP 服务器应答"),
+        (Some(servers), false) => format!("{servers} DHCP servers answered"),
+        (None, true) => "未探测 DHCP".into(),
+        (None, false) => "DHCP not probed".into(),
+    });
+    out.push_str(&facts.join(" · "));
+    out.push_str("\n\n");
+    if findings.is_empty() {
+        out.push_str(if zh {
+            "未发现问题。\n"
+        } else {
+            "No findings.\n"
+        });
+        return out;
+    }
+    out.push_str(if zh {
+        "| 级别 | 发现 | 主机 | 详情 | 建议 |\n"
+    } else {
+        "| Severity | Finding | Host | Detail | Advice |\n"
+    });
+    out.push_str("|---|---|---|---|---|\n");
+    for finding in findings {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            finding.severity.label(language),
+            finding.check.title(language),
+            finding.ip,
+            finding.detail.replace('|', "\\|"),
+            finding.check.advice(language),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WebService;
+
+    fn host(ip: &str) -> ScanHost {
+        ScanHost {
+            ip: ip.into(),
+            mac: "02:00:00:00:00:01".into(),
+            ..ScanHost::default()
+        }
+    }
+
+    fn dhcp(server: &str) -> DhcpServer {
+        DhcpServer {
+            server: server.into(),
+            offered: "192.168.1.100".into(),
+            router: server.into(),
+        }
+    }
+
+    #[test]
+    fn findings_come_most_severe_first() {
+        let mut printer = host("192.168.1.31");
+        printer.open_ports = vec![FTP_PORT, TELNET_PORT, 80];
+        let mut camera = host("192.168.1.40");
+        camera.web = vec![WebService {
+            port: 80,
+            title: "Network Camera".into(),
+            server: String::new(),
+        }];
+        let mut phone = host("192.168.1.52");
+        phone.other_macs = vec!["02:00:00:00:00:99".into()];
+        let mut nas = host("192.168.1.8");
+        nas.smb1 = true;
+
+        let findings = audit_findings(
+            &[nas, printer, camera, phone],
+            &[dhcp("192.168.1.1"), dhcp("192.168.1.77")],
+            Some("192.168.1.1"),
+        );
+        let summary = findings
+            .iter()
+            .map(|finding| (finding.severity, finding.check, finding.ip.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (AuditSeverity::High, AuditCheck::SmbV1, "192.168.1.8"),
+                (AuditSeverity::High, AuditCheck::Telnet, "192.168.1.31"),
+                (AuditSeverity::High, AuditCheck::DuplicateIp, "192.168.1.52"),
+                (AuditSeverity::High, AuditCheck::RogueDhcp, "192.168.1.77"),
+                (AuditSeverity::Medium, AuditCheck::Ftp, "192.168.1.31"),
+                (
+                    AuditSeverity::Medium,
+                    AuditCheck::DefaultLogin,
+                    "192.168.1.40"
+                ),
+            ]
+        );
+        assert_eq!(findings[2].detail, "02:00:00:00:00:01, 02:00:00:00:00:99");
+        assert_eq!(findings[5].detail, "Network Camera · TCP 80");
+
+        let markdown = audit_markdown(
+            &findings,
+            4,
+            Some("2026-10-16 21:30"),
+            Some(2),
+            Language::En,
+        );
+        assert!(markdown.starts_with(
+            "# Network security audit\n\n4 hosts scanned · scan of 2026-10-16 21:30 · 2 DHCP servers answered\n"
+        ));
+        assert_eq!(markdown.matches("\n| High |").count(), 4);
+        assert!(markdown.contains("| Medium | FTP open | 192.168.1.31 | TCP 21 |"));
+    }
+
+    #[test]
+    fn dhcp_servers_are_judged_against_the_gateway() {
+        let only = |servers: &[DhcpServer], gateway| {
+            dhcp_findings(servers, gateway)
+                .into_iter()
+                .map(|finding| (finding.severity, finding.ip))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(only(&[dhcp("192.168.1.1")], Some("192.168.1.1")), []);
+        assert_eq!(
+            only(&[dhcp("192.168.1.2")], Some("192.168.1.1")),
+            [(AuditSeverity::Low, "192.168.1.2".to_string())]
+        );
+        assert_eq!(only(&[dhcp("192.168.1.2")], None), []);
+        assert_eq!(
+            only(&[dhcp("192.168.1.2"), dhcp("192.168.1.3")], None).len(),
+            2
+        );
+        assert_eq!(
+            only(
+                &[dhcp("192.168.1.2"), dhcp("192.168.1.3")],
+                Some("192.168.1.1")
+            )[0]
+            .0,
+            AuditSeverity::Medium
+        );
+        assert_eq!(
+            audit_markdown(&[], 0, None, None, Language::En),
+            "# Network security audit\n\n0 hosts scanned · DHCP not probed\n\nNo findings.\n"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Pinned ping baselines.
+//!
+//! Whether switching the Wi-Fi channel or plugging in a cable helped is a
+//! before-and-after question, and the numbers from before are gone once the
+//! ping is restarted. `P` on the ping result pins the statistics it shows
+//! at that moment; the grid then puts the live figures beside them with the
+//! difference, across any number of restarts, until `P` unpins them.
+
+use crate::{PingSample, PingSummary};
+
+/// What the ping statistics grid shows.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PingStats {
+    pub last_ms: Option<u64>,
+    pub min_ms: Option<u64>,
+    pub average_ms: Option<f64>,
+    pub max_ms: Option<u64>,
+    /// Mean difference between consecutive replies.
+    pub jitter_ms: Option<f64>,
+    pub loss_percent: f64,
+    pub sent: u64,
+}
+
+impl PingStats {
+    /// The finished run's summary where there is one, otherwise the running
+    /// totals the latest sample carries.
+    pub fn of(samples: &[PingSample], summary: Option<&PingSummary>) -> Self {
+        let latest = samples.last();
+        let differences = samples
+            .windows(2)
+            .filter_map(|pair| Some(pair[0].latency_ms?.abs_diff(pair[1].latency_ms?) as f64))
+            .collect::<Vec<_>>();
+        Self {
+            last_ms: latest.and_then(|sample| sample.latency_ms),
+            min_ms: summary
+                .and_then(|summary| summary.min_ms)
+                .or_else(|| latest.and_then(|sample| sample.min_ms)),
+            average_ms: summary
+                .and_then(|summary| summary.average_ms)
+                .or_else(|| latest.and_then(|sample| sample.average_ms)),
+            max_ms: summary
+                .and_then(|summary| summary.max_ms)
+                .or_else(|| latest.and_then(|sample| sample.max_ms)),
+            jitter_ms: (!differences.is_empty())
+                .then(|| differences.iter().sum::<f64>() / differences.len() as f64),
+            loss_percent: summary
+                .map(|summary| summary.loss_percent)
+                .or_else(|| latest.map(|sample| sample.loss_percent))
+                .unwrap_or_default(),
+            sent: summary
+                .map(|summary| summary.sent)
+                .or_else(|| latest.map(|sample| sample.sent))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PingBaseline {
+    /// Target pinged when it was pinned; a baseline against another host
+    /// compares different paths, which the grid points out.
+    pub target: String,
+    /// Local wall-clock time, `YYYY-MM-DD HH:MM:SS`.
+    pub at: String,
+    pub stats: PingStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(sequence: u64, latency_ms: Option<u64>) -> PingSample {
+        PingSample {
+            sequence,
+            latency_ms,
+            ttl: Some(64),
+            size: 32,
+            sent: sequence + 1,
+            received: sequence + 1,
+            min_ms: Some(10),
+            average_ms: Some(20.0),
+            max_ms: Some(30),
+            loss_percent: 25.0,
+            route: Vec::new(),
+            timestamps: None,
+        }
+    }
+
+    #[test]
+    fn jitter_skips_lost_replies_and_the_summary_wins() {
+        let samples = [
+            sample(0, Some(10)),
+            sample(1, Some(30)),
+            sample(2, None),
+            sample(3, Some(20)),
+        ];
+        let running = PingStats::of(&samples, None);
+        assert_eq!(running.last_ms, Some(20));
+        assert_eq!(running.jitter_ms, Some(20.0), "only 10 → 30 is a pair");
+        assert_eq!((running.sent, running.loss_percent), (4, 25.0));
+
+        let summary = PingSummary {
+            sent: 5,
+            received: 3,
+            min_ms: Some(9),
+            average_ms: Some(19.0),
+            max_ms: Some(31),
+            loss_percent: 40.0,
+        };
+        let finished = PingStats::of(&samples, Some(&summary));
+        assert_eq!(finished.min_ms, Some(9));
+        assert_eq!((finished.sent, finished.loss_percent), (5, 40.0));
+        assert_eq!(PingStats::of(&[], None), PingStats::default());
+    }
+}
@@ -0,0 +1,136 @@
+//! Bluetooth LE devices advertising nearby.
+//!
+//! The Scanner's Bluetooth view lists what an IP sweep cannot see: phones,
+//! watches, headphones, beacons and trackers announce themselves in BLE
+//! advertisements whether or not they are on the network. Scanning needs a
+//! build with the `bluetooth` feature; [`crate::PlatformCapabilities`] says
+//! whether the view is offered.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{JobId, TaskStatus};
+
+/// How long one Bluetooth scan listens before it stops by itself.
+pub const BLUETOOTH_SCAN_SECS: u64 = 30;
+
+/// One advertising device, as of its latest advertisement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BluetoothDevice {
+    /// MAC address, or the platform's opaque identifier where the address is
+    /// hidden (macOS).
+    pub address: String,
+    pub name: Option<String>,
+    pub rssi_dbm: Option<i32>,
+    /// Transmit power the device advertises, if any.
+    pub tx_power_dbm: Option<i32>,
+    /// Bluetooth SIG company identifier from the manufacturer data.
+    pub manufacturer_id: Option<u16>,
+    /// Since the scan started.
+    pub last_seen_ms: u64,
+}
+
+impl BluetoothDevice {
+    pub fn manufacturer(&self) -> Option<&'static str> {
+        company_name(self.manufacturer_id?)
+    }
+}
+
+/// The vendors most often heard, by Bluetooth SIG company identifier.
+pub fn company_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        0x0002 => "Intel",
+        0x0006 => "Microsoft",
+        0x000F => "Broadcom",
+        0x004C => "Apple",
+        0x0059 => "Nordic Semiconductor",
+        0x0075 => "Samsung",
+        0x0087 => "Garmin",
+        0x009E => "Bose",
+        0x00E0 => "Google",
+        0x012D => "Sony",
+        0x0171 => "Amazon",
+        0x027D => "Huawei",
+        0x038F => "Xiaomi",
+        _ => return None,
+    })
+}
+
+/// The Bluetooth view of the Scanner page.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BluetoothState {
+    pub job: Option<JobId>,
+    pub status: TaskStatus,
+    /// Strongest signal first.
+    pub devices: Vec<BluetoothDevice>,
+    pub selected: usize,
+}
+
+impl BluetoothState {
+    /// Merge an advertisement into the list, keeping the name a device sent
+    /// earlier when this one has none and the selection on the same device.
+    pub fn record(&mut self, mut device: BluetoothDevice) {
+        let selected = self
+            .devices
+            .get(self.selected)
+            .map(|device| device.address.clone());
+        if let Some(index) = self
+            .devices
+            .iter()
+            .position(|known| known.address == device.address)
+        {
+            let known = self.devices.remove(index);
+            device.name = device.name.or(known.name);
+            device.manufacturer_id = device.manufacturer_id.or(known.manufacturer_id);
+            device.tx_power_dbm = device.tx_power_dbm.or(known.tx_power_dbm);
+        }
+        // Devices without a reading sort last.
+        let index = self.devices.partition_point(|known| {
+            known.rssi_dbm.unwrap_or(i32::MIN) >= device.rssi_dbm.unwrap_or(i32::MIN)
+        });
+        self.devices.insert(index, device);
+        if let Some(address) = selected {
+            self.selected = self
+                .devices
+                .iter()
+                .position(|device| device.address == address)
+                .unwrap_or(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(address: &str, name: Option<&str>, rssi_dbm: i32) -> BluetoothDevice {
+        BluetoothDevice {
+            address: address.into(),
+            name: name.map(Into::into),
+            rssi_dbm: Some(rssi_dbm),
+            tx_power_dbm: None,
+            manufacturer_id: None,
+            last_seen_ms: 0,
+        }
+    }
+
+    #[test]
+    fn advertisements_merge_by_address_strongest_first() {
+        let mut state = BluetoothState::default();
+        state.record(device("aa", Some("Watch"), -70));
+        state.record(BluetoothDevice {
+            manufacturer_id: Some(0x004C),
+            ..device("bb", None, -50)
+        });
+        state.selected = 1;
+        // The watch comes closer and its next advertisement has no name.
+        state.record(device("aa", None, -40));
+        let order = state
+            .devices
+            .iter()
+            .map(|device| (device.address.as_str(), device.name.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(order, [("aa", Some("Watch")), ("bb", None)]);
+        assert_eq!(state.selected, 0, "selection follows the watch");
+        assert_eq!(state.devices[1].manufacturer(), Some("Apple"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Latency-under-load ("bufferbloat") grading for the public speed test.
+
+use crate::{BufferbloatGrade, BufferbloatSummary, LatencySample, SpeedPhase};
+
+/// Upper bounds (exclusive) on added latency in milliseconds for grades A–E;
+/// anything slower is F.
+const GRADE_LIMITS_MS: [(f64, BufferbloatGrade); 5] = [
+    (5.0, BufferbloatGrade::A),
+    (30.0, BufferbloatGrade::B),
+    (60.0, BufferbloatGrade::C),
+    (200.0, BufferbloatGrade::D),
+    (400.0, BufferbloatGrade::E),
+];
+
+impl BufferbloatGrade {
+    /// Grade how much the loaded median exceeds the idle median.
+    pub fn from_increase_ms(increase_ms: f64) -> Self {
+        GRADE_LIMITS_MS
+            .iter()
+            .find(|(limit, _)| increase_ms < *limit)
+            .map_or(Self::F, |(_, grade)| *grade)
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::B => "B",
+            Self::C => "C",
+            Self::D => "D",
+            Self::E => "E",
+            Self::F => "F",
+        }
+    }
+}
+
+impl BufferbloatSummary {
+    /// Summarise probes by phase. Timed-out probes are ignored; no grade is
+    /// given without an idle baseline and at least one loaded phase.
+    pub fn from_samples(samples: &[LatencySample]) -> Self {
+        let idle_ms = phase_median(samples, SpeedPhase::Idle);
+        let download_ms = phase_median(samples, SpeedPhase::Download);
+        let upload_ms = phase_median(samples, SpeedPhase::Upload);
+        let loaded = match (download_ms, upload_ms) {
+            (Some(download), Some(upload)) => Some(download.max(upload)),
+            (loaded, None) | (None, loaded) => loaded,
+        };
+        let grade = idle_ms
+            .zip(loaded)
+            .map(|(idle, loaded)| BufferbloatGrade::from_increase_ms((loaded - idle).max(0.0)));
+        Self {
+            idle_ms,
+            download_ms,
+            upload_ms,
+            grade,
+        }
+    }
+}
+
+fn phase_median(samples: &[LatencySample], phase: SpeedPhase) -> Option<f64> {
+    let mut values = samples
+        .iter()
+        .filter(|sample| sample.phase == phase)
+        .filter_map(|sample| sample.rtt_ms)
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) as f64 / 2.0
+    } else {
+        values[middle] as f64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(phase: SpeedPhase, values: &[Option<u64>]) -> Vec<LatencySample> {
+        values
+            .iter()
+            .map(|rtt_ms| LatencySample {
+                phase,
+                rtt_ms: *rtt_ms,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grade_follows_the_worst_loaded_phase_against_the_idle_median() {
+        let mut probes = samples(SpeedPhase::Idle, &[Some(12), Some(10), Some(40), None]);
+        probes.extend(samples(
+            SpeedPhase::Download,
+            &[Some(40), Some(50), Some(45)],
+        ));
+        probes.extend(samples(SpeedPhase::Upload, &[Some(260), None, Some(240)]));
+        let summary = BufferbloatSummary::from_samples(&probes);
+        assert_eq!(summary.idle_ms, Some(12.0));
+        assert_eq!(summary.download_ms, Some(45.0));
+        assert_eq!(summary.upload_ms, Some(250.0));
+        assert_eq!(summary.grade, Some(BufferbloatGrade::E));
+
+        assert_eq!(BufferbloatGrade::from_increase_ms(0.0), BufferbloatGrade::A);
+        assert_eq!(
+            BufferbloatGrade::from_increase_ms(30.0),
+            BufferbloatGrade::C
+        );
+        assert_eq!(
+            BufferbloatGrade::from_increase_ms(400.0),
+            BufferbloatGrade::F
+        );
+    }
+
+    #[test]
+    fn missing_baseline_or_load_leaves_the_grade_empty() {
+        let idle_only = samples(SpeedPhase::Idle, &[Some(8)]);
+        assert_eq!(BufferbloatSummary::from_samples(&idle_only).grade, None);
+        let loaded_only = samples(SpeedPhase::Download, &[Some(80)]);
+        assert_eq!(BufferbloatSummary::from_samples(&loaded_only).grade, None);
+    }
+}
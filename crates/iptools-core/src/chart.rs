@@ -0,0 +1,194 @@
+//! Measurement history as a chart file for reports.
+//!
+//! The reducer turns the samples of the current tool into a [`Chart`]; the
+//! runtime draws it to a PNG or SVG file. Values stay integers in the units
+//! the samples use, and each axis says how to scale them for display, so a
+//! chart compares exactly like the effect carrying it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{JobId, PingSample, RuntimeError, SpeedPhase, SpeedSample};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChartFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+impl ChartFormat {
+    pub const ALL: [Self; 2] = [Self::Png, Self::Svg];
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::Png => Self::Svg,
+            Self::Svg => Self::Png,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartAxis {
+    pub label: String,
+    /// Raw values are divided by this for display.
+    pub scale: u64,
+}
+
+/// One line; a `None` value, such as a lost probe, leaves a gap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartSeries {
+    pub label: String,
+    pub points: Vec<(u64, Option<u64>)>,
+}
+
+/// A line chart ready to draw. Labels are English, since report readers
+/// and the fonts available to the renderer vary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chart {
+    /// Short name for the file, such as `ping`.
+    pub name: &'static str,
+    pub title: String,
+    pub x: ChartAxis,
+    pub y: ChartAxis,
+    pub series: Vec<ChartSeries>,
+}
+
+impl Chart {
+    pub fn points(&self) -> usize {
+        self.series.iter().map(|series| series.points.len()).sum()
+    }
+}
+
+/// Round-trip time per probe; losses break the line.
+pub fn ping_chart(target: &str, samples: &[PingSample]) -> Option<Chart> {
+    if samples.is_empty() {
+        return None;
+    }
+    Some(Chart {
+        name: "ping",
+        title: format!("Ping {target}"),
+        x: ChartAxis {
+            label: "Probe".into(),
+            scale: 1,
+        },
+        y: ChartAxis {
+            label: "Latency (ms)".into(),
+            scale: 1,
+        },
+        series: vec![ChartSeries {
+            label: "RTT".into(),
+            points: samples
+                .iter()
+                .map(|sample| (sample.sequence, sample.latency_ms))
+                .collect(),
+        }],
+    })
+}
+
+/// Throughput over time, one line per direction.
+pub fn speed_chart(server: Option<&str>, samples: &[SpeedSample]) -> Option<Chart> {
+    let series = [
+        (SpeedPhase::Download, "Download"),
+        (SpeedPhase::Upload, "Upload"),
+    ]
+    .into_iter()
+    .filter_map(|(phase, label)| {
+        let points = samples
+            .iter()
+            .filter(|sample| sample.phase == phase)
+            .map(|sample| {
+                (
+                    sample.elapsed_ms,
+                    Some(sample.bytes_per_second.saturating_mul(8)),
+                )
+            })
+            .collect::<Vec<_>>();
+        (!points.is_empty()).then(|| ChartSeries {
+            label: label.into(),
+            points,
+        })
+    })
+    .collect::<Vec<_>>();
+    if series.is_empty() {
+        return None;
+    }
+    Some(Chart {
+        name: "speed",
+        title: match server {
+            Some(server) => format!("Speed test · {server}"),
+            None => "Speed test".into(),
+        },
+        x: ChartAxis {
+            label: "Time (s)".into(),
+            scale: 1_000,
+        },
+        y: ChartAxis {
+            label: "Throughput (Mbit/s)".into(),
+            scale: 1_000_000,
+        },
+        series,
+    })
+}
+
+/// The export popup: the format to write and the outcome of the last write.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExportState {
+    pub format: ChartFormat,
+    pub job: Option<JobId>,
+    /// Path of the last file written.
+    pub saved: Option<String>,
+    pub error: Option<RuntimeError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lost_probes_leave_gaps_and_speed_splits_by_direction() {
+        let sample = |sequence, latency_ms| PingSample {
+            sequence,
+            latency_ms,
+            ttl: None,
+            size: 56,
+            route: Vec::new(),
+            timestamps: None,
+            sent: sequence,
+            received: sequence,
+            min_ms: None,
+            average_ms: None,
+            max_ms: None,
+            loss_percent: 0.0,
+        };
+        assert_eq!(ping_chart("example.com", &[]), None);
+        let chart = ping_chart("example.com", &[sample(1, Some(12)), sample(2, None)]).unwrap();
+        assert_eq!(chart.series[0].points, [(1, Some(12)), (2, None)]);
+
+        let speed = |elapsed_ms, phase| SpeedSample {
+            elapsed_ms,
+            bytes: 0,
+            bytes_per_second: 12_500_000,
+            phase,
+        };
+        let chart = speed_chart(
+            None,
+            &[
+                speed(500, SpeedPhase::Idle),
+                speed(1_000, SpeedPhase::Download),
+                speed(2_000, SpeedPhase::Upload),
+            ],
+        )
+        .unwrap();
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(chart.series[0].points, [(1_000, Some(100_000_000))]);
+        assert_eq!(chart.points(), 2);
+    }
+}
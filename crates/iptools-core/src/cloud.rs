@@ -0,0 +1,200 @@
+//! Cloud reachability matrix: is it me, or is us-east-1 down?
+//!
+//! A curated list of regional cloud API endpoints, CDN edges and anycast
+//! DNS resolvers is probed by TCP connect. One unreachable cell among many
+//! healthy ones points at the provider; most of the matrix failing points
+//! back at the local network.
+
+use crate::{CloudEndpoint, CloudGroup, CloudProbe};
+
+/// Regions of each cloud, in US East, US West, Europe, Asia order so the
+/// matrix columns line up.
+const CLOUD_REGIONS: [(CloudGroup, [&str; 4]); 3] = [
+    (
+        CloudGroup::Aws,
+        ["us-east-1", "us-west-2", "eu-central-1", "ap-northeast-1"],
+    ),
+    (
+        CloudGroup::Gcp,
+        ["us-east1", "us-west1", "europe-west1", "asia-east1"],
+    ),
+    (
+        CloudGroup::Azure,
+        ["eastus", "westus2", "westeurope", "japaneast"],
+    ),
+];
+
+/// CDN operators and a site each serves from its edge.
+const CDNS: [(&str, &str); 4] = [
+    ("Cloudflare", "www.cloudflare.com"),
+    ("Akamai", "www.akamai.com"),
+    ("Fastly", "www.fastly.com"),
+    ("CloudFront", "aws.amazon.com"),
+];
+
+/// Anycast resolvers, reached over TCP 53.
+const ANYCAST_DNS: [(&str, &str); 4] = [
+    ("1.1.1.1", "1.1.1.1"),
+    ("8.8.8.8", "8.8.8.8"),
+    ("9.9.9.9", "9.9.9.9"),
+    ("OpenDNS", "208.67.222.222"),
+];
+
+/// Below this share of reachable endpoints the failures are blamed on the
+/// local network rather than on each provider.
+const LOCAL_FAULT_SHARE: f64 = 0.5;
+
+impl CloudGroup {
+    pub const ALL: [Self; 5] = [Self::Aws, Self::Gcp, Self::Azure, Self::Cdn, Self::Dns];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Aws => "AWS",
+            Self::Gcp => "GCP",
+            Self::Azure => "Azure",
+            Self::Cdn => "CDN",
+            Self::Dns => "DNS",
+        }
+    }
+}
+
+/// The curated list, grouped in matrix order.
+pub fn cloud_endpoints() -> Vec<CloudEndpoint> {
+    let endpoint = |group, name: &str, host: String, port| CloudEndpoint {
+        group,
+        name: name.into(),
+        host,
+        port,
+    };
+    let mut endpoints = Vec::new();
+    for (group, regions) in CLOUD_REGIONS {
+        for region in regions {
+            // Regional API front ends: answered from inside the region.
+            let host = match group {
+                CloudGroup::Aws => format!("ec2.{region}.amazonaws.com"),
+                CloudGroup::Gcp => format!("{region}-run.googleapis.com"),
+                _ => format!("{region}.api.cognitive.microsoft.com"),
+            };
+            endpoints.push(endpoint(group, region, host, 443));
+        }
+    }
+    for (name, host) in CDNS {
+        endpoints.push(endpoint(CloudGroup::Cdn, name, host.into(), 443));
+    }
+    for (name, address) in ANYCAST_DNS {
+        endpoints.push(endpoint(CloudGroup::Dns, name, address.into(), 53));
+    }
+    endpoints
+}
+
+impl CloudProbe {
+    /// Fold connect attempts into a probe: `Ok` carries the connect time,
+    /// `Err` why the attempt failed.
+    pub fn from_attempts(endpoint: CloudEndpoint, attempts: &[Result<f64, String>]) -> Self {
+        let latencies = attempts
+            .iter()
+            .filter_map(|attempt| attempt.as_ref().ok().copied())
+            .collect::<Vec<_>>();
+        Self {
+            endpoint,
+            answered: latencies.len() as u32,
+            attempts: attempts.len() as u32,
+            latency_ms: crate::dns_bench::median(latencies),
+            error: attempts
+                .iter()
+                .rev()
+                .find_map(|attempt| attempt.as_ref().err().cloned()),
+        }
+    }
+
+    pub fn reachable(&self) -> bool {
+        self.answered > 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloudVerdict {
+    AllReachable,
+    /// Only these endpoints failed: most likely on the provider's side.
+    ProviderOutage(Vec<String>),
+    /// Most of the matrix failed: the problem is local.
+    LocalProblem,
+}
+
+/// Judges a finished matrix; `None` before any probe came back.
+pub fn cloud_verdict(probes: &[CloudProbe]) -> Option<CloudVerdict> {
+    if probes.is_empty() {
+        return None;
+    }
+    let failed = probes
+        .iter()
+        .filter(|probe| !probe.reachable())
+        .map(|probe| format!("{} {}", probe.endpoint.group.label(), probe.endpoint.name))
+        .collect::<Vec<_>>();
+    let reachable = probes.len() - failed.len();
+    Some(if failed.is_empty() {
+        CloudVerdict::AllReachable
+    } else if (reachable as f64) < probes.len() as f64 * LOCAL_FAULT_SHARE {
+        CloudVerdict::LocalProblem
+    } else {
+        CloudVerdict::ProviderOutage(failed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolated_failures_blame_the_provider_and_widespread_ones_the_network() {
+        let endpoints = cloud_endpoints();
+        assert_eq!(endpoints.len(), 20);
+        for group in CloudGroup::ALL {
+            assert_eq!(
+                endpoints
+                    .iter()
+                    .filter(|endpoint| endpoint.group == group)
+                    .count(),
+                4
+            );
+        }
+
+        let up = |endpoint: &CloudEndpoint| {
+            CloudProbe::from_attempts(
+                endpoint.clone(),
+                &[Ok(30.0), Err("timed out".into()), Ok(20.0)],
+            )
+        };
+        let down = |endpoint: &CloudEndpoint| {
+            CloudProbe::from_attempts(
+                endpoint.clone(),
+                &[Err("refused".into()), Err("timed out".into())],
+            )
+        };
+        let probe = up(&endpoints[0]);
+        assert_eq!((probe.answered, probe.attempts), (2, 3));
+        assert_eq!(probe.latency_ms, Some(25.0));
+        assert_eq!(probe.error.as_deref(), Some("timed out"));
+
+        let mut probes = endpoints.iter().map(up).collect::<Vec<_>>();
+        assert_eq!(cloud_verdict(&probes), Some(CloudVerdict::AllReachable));
+        probes[0] = down(&endpoints[0]);
+        assert_eq!(
+            cloud_verdict(&probes),
+            Some(CloudVerdict::ProviderOutage(vec!["AWS us-east-1".into()]))
+        );
+        let probes = endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                if index < 16 {
+                    down(endpoint)
+                } else {
+                    up(endpoint)
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(cloud_verdict(&probes), Some(CloudVerdict::LocalProblem));
+        assert_eq!(cloud_verdict(&[]), None);
+    }
+}
@@ -12,6 +12,13 @@ use crate::Language;
 
 pub type PersistedKeymap = BTreeMap<String, Vec<String>>;
 
+/// Schema version written by this build. Files saved before versioning carry
+/// no `version` field and read as 0.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Public IP endpoint formats understood by the runtimes.
+pub const ENDPOINT_KINDS: [&str; 3] = ["ipsb", "ipinfo", "plaintext"];
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Endpoint {
     pub url: String,
@@ -47,28 +54,227 @@ impl Default for PublicIpConfig {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ConfigData {
+    /// See [`CONFIG_VERSION`]; a missing field means a pre-versioning file.
+    #[serde(default)]
+    pub version: u32,
     pub language: Language,
     pub theme: crate::ThemeId,
+    pub color_mode: crate::ColorMode,
+    pub chart_style: crate::ChartStyle,
+    /// How dates and decimal numbers are shown and exported.
+    pub region_format: crate::RegionFormat,
+    pub clock_format: crate::ClockFormat,
+    /// Show the UTC time next to local timestamps.
+    pub show_utc: bool,
     pub scan_concurrency: usize,
+    /// Back the scanner off below `scan_concurrency` when probes start
+    /// timing out, as on congested Wi-Fi.
+    pub scan_adaptive: bool,
+    /// How many samples, log lines and hosts are kept, and the memory budget
+    /// for all of them together.
+    pub retention: crate::RetentionLimits,
+    /// Loss rate, in percent, at which an interface's drops and errors are
+    /// listed as a problem; 0 turns the alert off.
+    pub drop_alert_percent: u64,
+    pub low_power: crate::LowPowerMode,
+    /// Offline mode: no outbound internet requests unless a tool is started
+    /// by hand.
+    pub offline: bool,
+    /// SSH jump host, `[user@]host[:port]`, that the port scan and the web
+    /// check connect through; empty connects from this machine.
+    pub jump_host: String,
+    /// Look up the latest release at startup; off unless the user opts in.
+    pub check_updates: bool,
+    /// Visible tabs in order, by [`crate::Page::name`].
+    pub tabs: Vec<String>,
+    /// Features turned off for everyone using this file; the settings
+    /// editor never writes it.
+    pub policy: crate::PolicyConfig,
     pub keybindings: PersistedKeymap,
     pub session: SessionState,
     pub public_ip: PublicIpConfig,
+    /// Hosts and URLs shown as tiles on the Dashboard.
+    pub watch: Vec<crate::WatchTarget>,
 }
 
 impl Default for ConfigData {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             language: Language::En,
             theme: crate::ThemeId::Classic,
+            color_mode: crate::ColorMode::Standard,
+            chart_style: crate::ChartStyle::Blocks,
+            region_format: crate::RegionFormat::Iso,
+            clock_format: crate::ClockFormat::TwentyFourHour,
+            show_utc: false,
             scan_concurrency: 50,
+            scan_adaptive: true,
+            retention: crate::RetentionLimits::default(),
+            drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+            low_power: crate::LowPowerMode::Auto,
+            offline: false,
+            jump_host: String::new(),
+            check_updates: false,
+            tabs: crate::Page::ALL
+                .iter()
+                .map(|page| page.name().to_string())
+                .collect(),
+            policy: crate::PolicyConfig::default(),
             keybindings: PersistedKeymap::new(),
             session: SessionState::default(),
             public_ip: PublicIpConfig::default(),
+            watch: Vec::new(),
         }
     }
 }
 
+/// A problem found while loading the configuration, reported once at startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigIssue {
+    /// The file could not be parsed and defaults were used; the original was
+    /// copied to `backup` when that succeeded.
+    Malformed {
+        error: String,
+        backup: Option<String>,
+    },
+    /// Written by a newer build. Settings this build does not know are lost on
+    /// the next save, so the original was copied to `backup` first.
+    NewerVersion { found: u32, backup: Option<String> },
+    /// Outside 10–500; clamped.
+    ScanConcurrency { found: usize },
+    /// A `[retention]` value outside its range; clamped.
+    Retention { key: String, found: u64 },
+    /// `drop_alert_percent` outside 0–20; clamped.
+    DropAlert { found: u64 },
+    /// Unknown format or a URL that is not HTTP(S); the endpoint is skipped.
+    PublicIpEndpoint { url: String, kind: String },
+    /// Unknown action name (empty `key`) or a key that cannot be parsed; the
+    /// built-in binding stays in effect.
+    Keybinding { action: String, key: String },
+    /// Unknown name in `tabs`; it is dropped.
+    Tab { name: String },
+    /// Unknown feature name in `policy.disabled`; it is dropped.
+    PolicyFeature { name: String },
+    /// A watch target that cannot be checked, has a zero latency budget or
+    /// an unreadable maintenance window, or is past the tile limit; it is
+    /// dropped.
+    WatchTarget { target: String },
+    /// Not `[user@]host[:port]`; the jump host is turned off.
+    JumpHost { found: String },
+}
+
 impl ConfigData {
+    /// Upgrade a file written by an older build in place. Returns whether
+    /// anything changed and the file should be rewritten.
+    pub fn migrate(&mut self) -> bool {
+        if self.version >= CONFIG_VERSION {
+            return false;
+        }
+        // 0 → 1 only introduces the version field; every other field added
+        // since is filled in by Serde defaults.
+        self.version = CONFIG_VERSION;
+        true
+    }
+
+    /// Check the values Serde accepts but the application cannot use, and
+    /// replace them with usable ones.
+    pub fn validate(&mut self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        if !(10..=500).contains(&self.scan_concurrency) {
+            issues.push(ConfigIssue::ScanConcurrency {
+                found: self.scan_concurrency,
+            });
+            self.scan_concurrency = self.scan_concurrency.clamp(10, 500);
+        }
+        let retention = &mut self.retention;
+        for (key, value, (min, max)) in [
+            (
+                "samples",
+                &mut retention.samples,
+                crate::RETAIN_SAMPLES_RANGE,
+            ),
+            (
+                "scan_rows",
+                &mut retention.scan_rows,
+                crate::RETAIN_SCAN_ROWS_RANGE,
+            ),
+        ] {
+            if !(min..=max).contains(&(*value as u64)) {
+                issues.push(ConfigIssue::Retention {
+                    key: key.into(),
+                    found: *value as u64,
+                });
+                *value = (*value as u64).clamp(min, max) as usize;
+            }
+        }
+        let (min, max) = crate::RETAIN_MEMORY_MB_RANGE;
+        if !(min..=max).contains(&retention.memory_mb) {
+            issues.push(ConfigIssue::Retention {
+                key: "memory_mb".into(),
+                found: retention.memory_mb,
+            });
+            retention.memory_mb = retention.memory_mb.clamp(min, max);
+        }
+        let (min, max) = crate::DROP_ALERT_PERCENT_RANGE;
+        if !(min..=max).contains(&self.drop_alert_percent) {
+            issues.push(ConfigIssue::DropAlert {
+                found: self.drop_alert_percent,
+            });
+            self.drop_alert_percent = self.drop_alert_percent.clamp(min, max);
+        }
+        self.public_ip.endpoints.retain(|endpoint| {
+            let usable = ENDPOINT_KINDS.contains(&endpoint.kind.as_str())
+                && (endpoint.url.starts_with("https://") || endpoint.url.starts_with("http://"));
+            if !usable {
+                issues.push(ConfigIssue::PublicIpEndpoint {
+                    url: endpoint.url.clone(),
+                    kind: endpoint.kind.clone(),
+                });
+            }
+            usable
+        });
+        let tabs = self.tabs.iter().filter_map(|name| {
+            let page = crate::Page::from_name(name);
+            if page.is_none() {
+                issues.push(ConfigIssue::Tab { name: name.clone() });
+            }
+            page
+        });
+        self.tabs = crate::normalize_tabs(tabs.collect::<Vec<_>>())
+            .iter()
+            .map(|page| page.name().to_string())
+            .collect();
+        self.policy.disabled.retain(|name| {
+            let known = crate::Feature::from_name(name).is_some();
+            if !known {
+                issues.push(ConfigIssue::PolicyFeature { name: name.clone() });
+            }
+            known
+        });
+        let mut watched = 0;
+        self.watch.retain(|target| {
+            let usable = target.probe().is_some()
+                && target.budget_ms != Some(0)
+                && (target.maintenance.is_none() || target.window().is_some())
+                && watched < crate::MAX_WATCH_TARGETS;
+            if usable {
+                watched += 1;
+            } else {
+                issues.push(ConfigIssue::WatchTarget {
+                    target: target.target.clone(),
+                });
+            }
+            usable
+        });
+        if crate::parse_jump_host(&self.jump_host).is_err() {
+            issues.push(ConfigIssue::JumpHost {
+                found: std::mem::take(&mut self.jump_host),
+            });
+        }
+        issues
+    }
+
     /// Apply a persistence-only effect to the shared configuration schema.
     ///
     /// Native and Web stores call this same pure function, then persist the
@@ -78,13 +284,32 @@ impl ConfigData {
             crate::Effect::PersistPreferences(preferences) => {
                 self.language = preferences.language;
                 self.theme = preferences.theme;
+                self.color_mode = preferences.color_mode;
+                self.chart_style = preferences.chart_style;
+                self.region_format = preferences.region_format;
+                self.clock_format = preferences.clock_format;
+                self.show_utc = preferences.show_utc;
                 self.scan_concurrency = preferences.scan_concurrency;
+                self.scan_adaptive = preferences.scan_adaptive;
+                self.retention = preferences.retention;
+                self.drop_alert_percent = preferences.drop_alert_percent;
+                self.low_power = preferences.low_power;
+                self.offline = preferences.offline;
+                self.check_updates = preferences.check_updates;
+            }
+            crate::Effect::PersistPublicIp(public_ip) => self.public_ip = public_ip.clone(),
+            crate::Effect::PersistTabs(tabs) => {
+                self.tabs = tabs.iter().map(|page| page.name().to_string()).collect();
             }
+            crate::Effect::PersistWatch(targets) => self.watch = targets.clone(),
+            crate::Effect::PersistJumpHost(host) => self.jump_host = host.clone(),
             crate::Effect::PersistSession(update) => match update {
                 crate::SessionUpdate::Scanner(value) => self.session.scanner = value.clone(),
                 crate::SessionUpdate::CidrHistory(value) => {
                     self.session.history.cidrs = value.clone();
                 }
+                crate::SessionUpdate::Inventory(value) => self.session.inventory = value.clone(),
+                crate::SessionUpdate::Events(value) => self.session.events = value.clone(),
                 crate::SessionUpdate::Ping(value) => self.session.ping = value.clone(),
                 crate::SessionUpdate::Trace(value) => self.session.trace = value.clone(),
                 crate::SessionUpdate::PortScan(value) => {
@@ -96,6 +321,20 @@ impl ConfigData {
                 crate::SessionUpdate::LinkQuality(value) => {
                     self.session.link_quality = value.clone();
                 }
+                crate::SessionUpdate::Mtu(value) => self.session.mtu = value.clone(),
+                crate::SessionUpdate::Dscp(value) => self.session.dscp = value.clone(),
+                crate::SessionUpdate::Multicast(value) => self.session.multicast = value.clone(),
+                crate::SessionUpdate::Upnp(value) => self.session.upnp = value.clone(),
+                crate::SessionUpdate::Nat(value) => self.session.nat = value.clone(),
+                crate::SessionUpdate::Ipv6(value) => self.session.ipv6 = value.clone(),
+                crate::SessionUpdate::DnsBench(value) => self.session.dns_bench = value.clone(),
+                crate::SessionUpdate::DnsFilter(value) => self.session.dns_filter = value.clone(),
+                crate::SessionUpdate::Mail(value) => self.session.mail = value.clone(),
+                crate::SessionUpdate::Voip(value) => self.session.voip = value.clone(),
+                crate::SessionUpdate::Gaming(value) => self.session.gaming = value.clone(),
+                crate::SessionUpdate::Cloud(value) => self.session.cloud = value.clone(),
+                crate::SessionUpdate::Wifi(value) => self.session.wifi = value.clone(),
+                crate::SessionUpdate::AbTest(value) => self.session.ab_test = value.clone(),
                 crate::SessionUpdate::TargetHistory(value) => {
                     self.session.history.targets = value.clone();
                 }
@@ -103,6 +342,8 @@ impl ConfigData {
                 crate::SessionUpdate::Reset(ui) => {
                     self.session = SessionState {
                         ui: ui.clone(),
+                        inventory: std::mem::take(&mut self.session.inventory),
+                        events: std::mem::take(&mut self.session.events),
                         ..SessionState::default()
                     };
                 }
@@ -133,9 +374,37 @@ pub struct SessionState {
     pub trace: TracePersist,
     pub lan_speed: LanSpeedPersist,
     pub link_quality: LinkQualityPersist,
+    pub mtu: MtuPersist,
+    pub dscp: DscpPersist,
+    pub multicast: MulticastPersist,
+    pub upnp: UpnpPersist,
+    pub nat: NatPersist,
+    pub ipv6: Ipv6Persist,
+    pub dns_bench: DnsBenchPersist,
+    pub dns_filter: DnsFilterPersist,
+    pub mail: MailPersist,
+    pub voip: VoipPersist,
+    pub gaming: GamingPersist,
+    pub cloud: CloudPersist,
+    pub wifi: WifiPersist,
+    pub ab_test: AbTestPersist,
     pub adapter_edit: AdapterEditPersist,
     pub ui: UiPersist,
     pub history: HistoryPersist,
+    pub inventory: InventoryPersist,
+    pub events: EventLogPersist,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct InventoryPersist {
+    pub devices: Vec<crate::DeviceRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct EventLogPersist {
+    pub entries: Vec<crate::NetworkEvent>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -157,6 +426,9 @@ pub struct HistoryPersist {
 #[serde(default)]
 pub struct ScannerPersist {
     pub cidr: String,
+    pub enrich: bool,
+    /// DNS suffix for passive scans; empty for active probing.
+    pub dns_zone: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -166,6 +438,13 @@ pub struct PingPersist {
     pub interval_ms: u64,
     pub timeout_ms: u64,
     pub packet_size: u64,
+    pub mode: crate::PingMode,
+    pub dont_fragment: bool,
+    pub dscp: u8,
+    /// Where the target is said to be, checked against the RTT.
+    pub claimed_location: String,
+    /// Own location for that check; empty uses the public IP's city.
+    pub own_location: String,
 }
 
 impl Default for PingPersist {
@@ -175,6 +454,11 @@ impl Default for PingPersist {
             interval_ms: 1_000,
             timeout_ms: 2_000,
             packet_size: 32,
+            mode: crate::PingMode::Echo,
+            dont_fragment: false,
+            dscp: 0,
+            claimed_location: String::new(),
+            own_location: String::new(),
         }
     }
 }
@@ -205,6 +489,10 @@ pub struct TracePersist {
     pub target: String,
     pub max_hops: String,
     pub timeout_ms: String,
+    pub via: String,
+    /// Second trace shown beside the first; empty runs a single trace.
+    pub compare_target: String,
+    pub compare_via: String,
 }
 
 impl Default for TracePersist {
@@ -213,6 +501,270 @@ impl Default for TracePersist {
             target: "8.8.8.8".into(),
             max_hops: "30".into(),
             timeout_ms: "1000".into(),
+            via: String::new(),
+            compare_target: String::new(),
+            compare_via: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MtuPersist {
+    pub target: String,
+    pub max_mtu: String,
+    pub timeout_ms: String,
+}
+
+impl Default for MtuPersist {
+    fn default() -> Self {
+        Self {
+            target: "192.168.1.1".into(),
+            max_mtu: "9000".into(),
+            timeout_ms: "1000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DscpPersist {
+    pub target: String,
+    pub dscp: u8,
+    pub max_hops: String,
+    pub timeout_ms: String,
+}
+
+impl Default for DscpPersist {
+    fn default() -> Self {
+        Self {
+            target: "8.8.8.8".into(),
+            dscp: 46,
+            max_hops: "16".into(),
+            timeout_ms: "1000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MulticastPersist {
+    pub mode: crate::MulticastMode,
+    pub group: String,
+    pub port: String,
+    pub duration_secs: String,
+    pub rate_pps: String,
+}
+
+impl Default for MulticastPersist {
+    fn default() -> Self {
+        Self {
+            mode: crate::MulticastMode::Listen,
+            group: "239.255.42.99".into(),
+            port: "5004".into(),
+            duration_secs: "30".into(),
+            rate_pps: "20".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpnpPersist {
+    pub action: crate::UpnpAction,
+    pub protocol: crate::LanProtocol,
+    pub external_port: String,
+    pub internal_port: String,
+    pub lease_secs: String,
+}
+
+impl Default for UpnpPersist {
+    fn default() -> Self {
+        Self {
+            action: crate::UpnpAction::List,
+            protocol: crate::LanProtocol::Tcp,
+            external_port: "50505".into(),
+            internal_port: "50505".into(),
+            lease_secs: "3600".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NatPersist {
+    pub server: String,
+    pub secondary: String,
+    pub timeout_ms: String,
+}
+
+impl Default for NatPersist {
+    fn default() -> Self {
+        Self {
+            server: "stun.l.google.com:19302".into(),
+            secondary: "stun.cloudflare.com:3478".into(),
+            timeout_ms: "1000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ipv6Persist {
+    pub target: String,
+    pub timeout_ms: String,
+}
+
+impl Default for Ipv6Persist {
+    fn default() -> Self {
+        Self {
+            target: "ipv6.google.com".into(),
+            timeout_ms: "3000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DnsBenchPersist {
+    pub custom: String,
+    pub encrypted: bool,
+    pub rounds: String,
+    pub timeout_ms: String,
+}
+
+impl Default for DnsBenchPersist {
+    fn default() -> Self {
+        Self {
+            custom: String::new(),
+            encrypted: true,
+            rounds: "5".into(),
+            timeout_ms: "2000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DnsFilterPersist {
+    pub domain: String,
+    pub timeout_ms: String,
+}
+
+impl Default for DnsFilterPersist {
+    fn default() -> Self {
+        Self {
+            domain: String::new(),
+            timeout_ms: "2000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailPersist {
+    pub host: String,
+    pub timeout_ms: String,
+}
+
+impl Default for MailPersist {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            timeout_ms: "5000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoipPersist {
+    pub mode: crate::VoipMode,
+    pub peer: String,
+    pub port: String,
+    pub codec: crate::VoipCodec,
+    pub duration_secs: String,
+}
+
+impl Default for VoipPersist {
+    fn default() -> Self {
+        Self {
+            mode: crate::VoipMode::Probe,
+            peer: String::new(),
+            port: "50506".into(),
+            codec: crate::VoipCodec::G711,
+            duration_secs: "15".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamingPersist {
+    pub preset: crate::GamingPreset,
+    pub custom: String,
+    pub rounds: String,
+}
+
+impl Default for GamingPersist {
+    fn default() -> Self {
+        Self {
+            preset: crate::GamingPreset::All,
+            custom: String::new(),
+            rounds: "20".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudPersist {
+    pub attempts: String,
+    pub timeout_ms: String,
+}
+
+impl Default for CloudPersist {
+    fn default() -> Self {
+        Self {
+            attempts: "3".into(),
+            timeout_ms: "3000".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WifiPersist {
+    /// Adapter name; the first wireless adapter when it is gone.
+    pub adapter: String,
+    pub interval_secs: String,
+}
+
+impl Default for WifiPersist {
+    fn default() -> Self {
+        Self {
+            adapter: String::new(),
+            interval_secs: crate::DEFAULT_WIFI_INTERVAL_SECS.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbTestPersist {
+    pub first: String,
+    pub second: String,
+    pub target: String,
+    pub pings: String,
+}
+
+impl Default for AbTestPersist {
+    fn default() -> Self {
+        Self {
+            first: String::new(),
+            second: String::new(),
+            target: "1.1.1.1".into(),
+            pings: "10".into(),
         }
     }
 }
@@ -320,6 +872,38 @@ mod tests {
         assert_eq!(config.public_ip, PublicIpConfig::default());
     }
 
+    #[test]
+    fn unversioned_configs_migrate_and_invalid_values_are_repaired() {
+        let mut config: ConfigData = serde_json::from_str(
+            r#"{"scan_concurrency":5000,"retention":{"memory_mb":1},"public_ip":{"endpoints":[
+                {"url":"https://ipinfo.io/json","kind":"ipinfo"},
+                {"url":"ftp://example.com","kind":"plaintext"},
+                {"url":"https://example.com","kind":"xml"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.version, 0);
+        assert!(config.migrate());
+        assert!(!config.migrate());
+        assert_eq!(config.version, CONFIG_VERSION);
+
+        let issues = config.validate();
+        assert_eq!(issues.len(), 4);
+        assert_eq!(issues[0], ConfigIssue::ScanConcurrency { found: 5000 });
+        assert_eq!(config.scan_concurrency, 500);
+        assert_eq!(
+            issues[1],
+            ConfigIssue::Retention {
+                key: "memory_mb".into(),
+                found: 1
+            }
+        );
+        assert_eq!(config.retention.memory_mb, 16);
+        assert_eq!(config.retention.samples, 10_000);
+        assert_eq!(config.public_ip.endpoints.len(), 1);
+        assert!(config.validate().is_empty());
+        assert_eq!(ConfigData::default().version, CONFIG_VERSION);
+    }
+
     #[test]
     fn partial_session_fields_keep_other_defaults() {
         let session: SessionState =
@@ -416,12 +1000,27 @@ mod tests {
                     language: Language::Zh,
                     theme: crate::ThemeId::Nord,
                     scan_concurrency: 80,
+                    scan_adaptive: true,
+                    retention: crate::RetentionLimits::default(),
+                    drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+                    low_power: crate::LowPowerMode::On,
+                    offline: false,
+                    check_updates: false,
+                    color_mode: crate::ColorMode::Standard,
+                    chart_style: crate::ChartStyle::Blocks,
+                    region_format: crate::RegionFormat::European,
+                    clock_format: crate::ClockFormat::TwelveHour,
+                    show_utc: true,
                 },
             ))
         );
         assert_eq!(config.language, Language::Zh);
         assert_eq!(config.theme, crate::ThemeId::Nord);
         assert_eq!(config.scan_concurrency, 80);
+        assert_eq!(config.low_power, crate::LowPowerMode::On);
+        assert_eq!(config.region_format, crate::RegionFormat::European);
+        assert_eq!(config.clock_format, crate::ClockFormat::TwelveHour);
+        assert!(config.show_utc);
 
         assert!(
             config.apply_persistence_effect(&crate::Effect::PersistSession(
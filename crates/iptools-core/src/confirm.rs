@@ -0,0 +1,68 @@
+//! Confirmation before destructive actions.
+//!
+//! A module asks with [`AppModel::confirm`](crate::AppModel::confirm) instead
+//! of acting; the question opens as a [`Popup::Confirm`](crate::Popup::Confirm)
+//! and holds input until it is answered. Questions open on "No", so a stray
+//! Enter leaves things as they are. Questions with a [`Confirm::phrase`] take
+//! "Yes" only once that phrase has been typed. The renderer words each
+//! [`Confirm`] in the current language; the reducer runs it on "Yes".
+
+use crate::{GuardedScan, LanProtocol, ScanConcern};
+
+/// An action that waits for the user to confirm it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirm {
+    /// Forget remembered targets, parameters and input history.
+    ClearSession,
+    /// Put the settings page back to its defaults.
+    RestoreDefaults,
+    /// Remove a port mapping from the router.
+    DeleteUpnpMapping {
+        protocol: LanProtocol,
+        external_port: u16,
+    },
+    /// Scan a target the [scan guard](crate::ScanGuard) stopped, in its
+    /// normalized spelling.
+    ScanTarget {
+        scan: GuardedScan,
+        target: String,
+        concern: ScanConcern,
+    },
+}
+
+impl Confirm {
+    /// Text to type before "Yes" is accepted.
+    pub fn phrase(&self) -> Option<&str> {
+        match self {
+            Self::ScanTarget { target, .. } => Some(target),
+            Self::ClearSession | Self::RestoreDefaults | Self::DeleteUpnpMapping { .. } => None,
+        }
+    }
+}
+
+/// An open question.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    pub confirm: Confirm,
+    /// Whether "Yes" is selected.
+    pub yes: bool,
+    /// What has been typed towards the [`Confirm::phrase`].
+    pub typed: String,
+}
+
+impl Confirmation {
+    pub const fn new(confirm: Confirm) -> Self {
+        Self {
+            confirm,
+            yes: false,
+            typed: String::new(),
+        }
+    }
+
+    /// Whether "Yes" would be taken now.
+    pub fn can_accept(&self) -> bool {
+        self.confirm
+            .phrase()
+            .is_none_or(|phrase| self.typed.trim().eq_ignore_ascii_case(phrase))
+    }
+}
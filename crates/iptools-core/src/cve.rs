@@ -0,0 +1,243 @@
+//! Known-vulnerability hints for the software a scan identified.
+//!
+//! Banners and `Server` headers often name a product and version, such as
+//! `SSH-2.0-OpenSSH_7.2p2` or `nginx/1.18.0`. On request the device detail
+//! drawer asks a vulnerability index how many CVEs are recorded against
+//! that version. The count is a heuristic: distributions backport fixes
+//! without changing the version string, and a banner can lie.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{JobId, Language, RuntimeError, ScanHost};
+
+pub const SSH_PORT: u16 = 22;
+/// Ports whose greeting line the scan keeps for product identification.
+pub const BANNER_PORTS: [u16; 2] = [crate::FTP_PORT, SSH_PORT];
+
+/// First line a service sends on connect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceBanner {
+    pub port: u16,
+    pub text: String,
+}
+
+/// A product and version, named as the vulnerability index names it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Software {
+    /// Display name, e.g. `OpenSSH`.
+    pub name: String,
+    /// CPE vendor and product, e.g. `openbsd` and `openssh`.
+    pub vendor: String,
+    pub product: String,
+    /// Dotted release number without distribution suffixes.
+    pub version: String,
+}
+
+impl Software {
+    /// `OpenSSH 7.2`.
+    pub fn label(&self) -> String {
+        format!("{} {}", self.name, self.version)
+    }
+
+    /// CPE 2.3 name for any platform running this version.
+    pub fn cpe(&self) -> String {
+        format!(
+            "cpe:2.3:a:{}:{}:{}",
+            self.vendor, self.product, self.version
+        )
+    }
+}
+
+/// Lowercase text that precedes the version, with the display name and CPE
+/// vendor and product it stands for.
+const PRODUCTS: [(&str, &str, &str, &str); 9] = [
+    ("openssh_", "OpenSSH", "openbsd", "openssh"),
+    (
+        "dropbear_",
+        "Dropbear SSH",
+        "dropbear_ssh_project",
+        "dropbear_ssh",
+    ),
+    ("nginx/", "nginx", "f5", "nginx"),
+    ("apache/", "Apache httpd", "apache", "http_server"),
+    ("lighttpd/", "lighttpd", "lighttpd", "lighttpd"),
+    (
+        "microsoft-iis/",
+        "Microsoft IIS",
+        "microsoft",
+        "internet_information_services",
+    ),
+    ("mini_httpd/", "mini_httpd", "acme", "mini_httpd"),
+    ("vsftpd ", "vsftpd", "beasts", "vsftpd"),
+    ("proftpd ", "ProFTPD", "proftpd", "proftpd"),
+];
+
+/// The product a banner or header names, if it is one the index is asked
+/// about and the text carries a version.
+pub fn identify(text: &str) -> Option<Software> {
+    let lower = text.to_ascii_lowercase();
+    PRODUCTS.iter().find_map(|(marker, name, vendor, product)| {
+        let rest = &lower[lower.find(marker)? + marker.len()..];
+        let rest = rest.trim_start_matches(|character: char| {
+            character == ' ' || character == '/' || character == 'v'
+        });
+        let version = rest
+            .split(|character: char| !character.is_ascii_digit() && character != '.')
+            .next()?
+            .trim_end_matches('.');
+        version
+            .contains(|character: char| character.is_ascii_digit())
+            .then(|| Software {
+                name: (*name).to_string(),
+                vendor: (*vendor).to_string(),
+                product: (*product).to_string(),
+                version: version.to_string(),
+            })
+    })
+}
+
+/// Software named by the host's banners, web servers and RTSP servers,
+/// once each.
+pub fn host_software(host: &ScanHost) -> Vec<Software> {
+    let texts = host
+        .banners
+        .iter()
+        .map(|banner| banner.text.as_str())
+        .chain(host.web.iter().map(|web| web.server.as_str()))
+        .chain(host.iot.iter().map(|service| service.detail.as_str()));
+    let mut software: Vec<Software> = Vec::new();
+    for found in texts.filter_map(identify) {
+        if !software.contains(&found) {
+            software.push(found);
+        }
+    }
+    software
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CveSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl CveSeverity {
+    pub const ALL: [Self; 4] = [Self::Critical, Self::High, Self::Medium, Self::Low];
+
+    pub const fn label(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::Critical, Language::Zh) => "严重",
+            (Self::Critical, Language::En) => "critical",
+            (Self::High, Language::Zh) => "高危",
+            (Self::High, Language::En) => "high",
+            (Self::Medium, Language::Zh) => "中危",
+            (Self::Medium, Language::En) => "medium",
+            (Self::Low, Language::Zh) => "低危",
+            (Self::Low, Language::En) => "low",
+        }
+    }
+}
+
+/// CVEs the index lists against one version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CveHint {
+    pub software: Software,
+    pub total: u32,
+    /// Per [`CveSeverity::ALL`]; CVEs without a score are only in `total`.
+    pub by_severity: [u32; 4],
+}
+
+impl CveHint {
+    pub fn count(&self, severity: CveSeverity) -> u32 {
+        self.by_severity[severity as usize]
+    }
+
+    /// The most severe rating among the CVEs, if any is rated.
+    pub fn worst(&self) -> Option<CveSeverity> {
+        CveSeverity::ALL
+            .into_iter()
+            .find(|severity| self.count(*severity) > 0)
+    }
+}
+
+/// Lookups for the scanner's detail drawer, kept for the session so
+/// another host running the same version costs no query.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CveState {
+    pub job: Option<JobId>,
+    pub hints: Vec<CveHint>,
+    pub error: Option<RuntimeError>,
+}
+
+impl CveState {
+    pub fn hint(&self, software: &Software) -> Option<&CveHint> {
+        self.hints.iter().find(|hint| hint.software == *software)
+    }
+
+    /// Software on `host` not looked up yet.
+    pub fn pending(&self, host: &ScanHost) -> Vec<Software> {
+        host_software(host)
+            .into_iter()
+            .filter(|software| self.hint(software).is_none())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banners_and_headers_name_product_and_version() {
+        let ssh = identify("SSH-2.0-OpenSSH_7.2p2 Ubuntu-4ubuntu2.8").unwrap();
+        assert_eq!(ssh.label(), "OpenSSH 7.2");
+        assert_eq!(ssh.cpe(), "cpe:2.3:a:openbsd:openssh:7.2");
+        assert_eq!(
+            identify("Apache/2.4.29 (Ubuntu)").map(|software| software.cpe()),
+            Some("cpe:2.3:a:apache:http_server:2.4.29".into())
+        );
+        assert_eq!(
+            identify("220 (vsFTPd 3.0.3)").map(|software| software.version),
+            Some("3.0.3".into())
+        );
+        assert_eq!(identify("nginx"), None, "no version, nothing to ask");
+        assert_eq!(identify("Microsoft-HTTPAPI/2.0"), None);
+
+        let mut host = ScanHost {
+            banners: vec![ServiceBanner {
+                port: SSH_PORT,
+                text: "SSH-2.0-dropbear_2019.78".into(),
+            }],
+            web: vec![
+                crate::WebService {
+                    port: 80,
+                    title: String::new(),
+                    server: "lighttpd/1.4.59".into(),
+                },
+                crate::WebService {
+                    port: 8080,
+                    title: String::new(),
+                    server: "lighttpd/1.4.59".into(),
+                },
+            ],
+            ..ScanHost::default()
+        };
+        let labels: Vec<String> = host_software(&host).iter().map(Software::label).collect();
+        assert_eq!(labels, ["Dropbear SSH 2019.78", "lighttpd 1.4.59"]);
+
+        let mut state = CveState::default();
+        state.hints.push(CveHint {
+            software: identify("lighttpd/1.4.59").unwrap(),
+            total: 3,
+            by_severity: [0, 1, 2, 0],
+        });
+        assert_eq!(state.hints[0].worst(), Some(CveSeverity::High));
+        assert_eq!(
+            state.pending(&host),
+            [identify("dropbear_2019.78").unwrap()]
+        );
+        host.banners.clear();
+        assert!(state.pending(&host).is_empty());
+    }
+}
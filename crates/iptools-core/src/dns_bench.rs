@@ -0,0 +1,350 @@
+//! DNS benchmark query plan, per-resolver scoring and ranking.
+
+use crate::{DnsBenchRequest, DnsQueryKind, DnsResolverScore, DnsResolverSpec, DnsTransport};
+
+/// Public resolvers every benchmark includes after the system resolver.
+pub const PUBLIC_RESOLVERS: [(&str, &str); 3] = [
+    ("Cloudflare", "1.1.1.1"),
+    ("Google", "8.8.8.8"),
+    ("Quad9", "9.9.9.9"),
+];
+
+/// Encrypted endpoints benchmarked when the request asks for them, so their
+/// latency can be set against the same operators over UDP 53.
+pub const ENCRYPTED_RESOLVERS: [(&str, DnsTransport, &str); 4] = [
+    (
+        "Cloudflare",
+        DnsTransport::Https,
+        "https://cloudflare-dns.com/dns-query",
+    ),
+    (
+        "Google",
+        DnsTransport::Https,
+        "https://dns.google/dns-query",
+    ),
+    ("Cloudflare", DnsTransport::Tls, "cloudflare-dns.com:853"),
+    ("Google", DnsTransport::Tls, "dns.google:853"),
+];
+
+const DOT_PORT: u16 = 853;
+
+/// Popular names; each is asked once untimed before the cached queries so
+/// the resolver is guaranteed to hold them.
+pub const DNS_WARMUP_NAMES: [&str; 5] = [
+    "www.google.com",
+    "www.youtube.com",
+    "www.facebook.com",
+    "www.wikipedia.org",
+    "www.amazon.com",
+];
+
+const DOTCOM_NAMES: [&str; 10] = [
+    "www.bestbuy.com",
+    "www.etsy.com",
+    "www.zillow.com",
+    "www.weather.com",
+    "www.target.com",
+    "www.nytimes.com",
+    "www.imdb.com",
+    "www.yelp.com",
+    "www.espn.com",
+    "www.ebay.com",
+];
+
+/// Unsigned zones, so a validating resolver cannot synthesise the NXDOMAIN
+/// for an invented label from cached NSEC records.
+const UNCACHED_ZONES: [&str; 3] = ["google.com", "amazon.com", "microsoft.com"];
+
+/// Resolvers failing more than this share of queries rank after reliable ones.
+const RELIABLE_FAILURE_RATE: f64 = 0.1;
+
+impl DnsBenchRequest {
+    /// The system resolver, the public resolvers (plain, then encrypted when
+    /// enabled), then the custom one unless it repeats a listed address.
+    pub fn resolvers(&self) -> Vec<DnsResolverSpec> {
+        let mut resolvers = vec![DnsResolverSpec {
+            name: "System".into(),
+            transport: DnsTransport::System,
+            address: String::new(),
+        }];
+        resolvers.extend(PUBLIC_RESOLVERS.map(|(name, address)| DnsResolverSpec {
+            name: name.into(),
+            transport: DnsTransport::Udp,
+            address: address.into(),
+        }));
+        if self.encrypted {
+            resolvers.extend(ENCRYPTED_RESOLVERS.map(|(name, transport, address)| {
+                DnsResolverSpec {
+                    name: name.into(),
+                    transport,
+                    address: address.into(),
+                }
+            }));
+        }
+        let custom = self.custom.trim();
+        let (transport, address) = if custom.starts_with("https://") {
+            (DnsTransport::Https, custom.to_string())
+        } else if let Some(host) = custom.strip_prefix("tls://") {
+            let host = host.trim_end_matches('/');
+            let address = match host.rsplit_once(':') {
+                Some((_, port)) if port.parse::<u16>().is_ok() => host.to_string(),
+                _ => format!("{host}:{DOT_PORT}"),
+            };
+            (DnsTransport::Tls, address)
+        } else {
+            (DnsTransport::Udp, custom.to_string())
+        };
+        if !custom.is_empty() && !resolvers.iter().any(|resolver| resolver.address == address) {
+            resolvers.push(DnsResolverSpec {
+                name: "Custom".into(),
+                transport,
+                address,
+            });
+        }
+        resolvers
+    }
+
+    /// Timed queries for one resolver, interleaved by kind. Pass a different
+    /// `nonce` per resolver so no two share an uncached label.
+    pub fn queries(&self, nonce: u64) -> Vec<(DnsQueryKind, String)> {
+        (0..self.rounds as usize)
+            .flat_map(|round| {
+                [
+                    (
+                        DnsQueryKind::Cached,
+                        DNS_WARMUP_NAMES[round % DNS_WARMUP_NAMES.len()].to_string(),
+                    ),
+                    (
+                        DnsQueryKind::Uncached,
+                        format!(
+                            "iptools-{nonce:x}-{round}.{}",
+                            UNCACHED_ZONES[round % UNCACHED_ZONES.len()]
+                        ),
+                    ),
+                    (
+                        DnsQueryKind::Dotcom,
+                        DOTCOM_NAMES[round % DOTCOM_NAMES.len()].to_string(),
+                    ),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl DnsResolverSpec {
+    pub fn label(&self) -> String {
+        match self.transport {
+            DnsTransport::System => self.name.clone(),
+            DnsTransport::Udp => format!("{} {}", self.name, self.address),
+            DnsTransport::Https | DnsTransport::Tls => {
+                format!("{} {}", self.name, self.transport.label())
+            }
+        }
+    }
+}
+
+impl DnsTransport {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Udp => "UDP 53",
+            Self::Https => "DoH",
+            Self::Tls => "DoT",
+        }
+    }
+
+    pub const fn encrypted(self) -> bool {
+        matches!(self, Self::Https | Self::Tls)
+    }
+}
+
+/// Whether an encrypted transport got answers from this network.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncryptedDnsCheck {
+    pub transport: DnsTransport,
+    pub works: bool,
+    /// Best median over this transport minus the best plain UDP median.
+    pub overhead_ms: Option<f64>,
+}
+
+/// One check per encrypted transport that was benchmarked, DoH first.
+pub fn encrypted_dns_checks(scores: &[DnsResolverScore]) -> Vec<EncryptedDnsCheck> {
+    let best = |transport: DnsTransport| {
+        scores
+            .iter()
+            .filter(|score| score.resolver.transport == transport)
+            .filter_map(|score| score.median_ms)
+            .min_by(f64::total_cmp)
+    };
+    let udp = best(DnsTransport::Udp);
+    [DnsTransport::Https, DnsTransport::Tls]
+        .into_iter()
+        .filter(|transport| {
+            scores
+                .iter()
+                .any(|score| score.resolver.transport == *transport)
+        })
+        .map(|transport| {
+            let median = best(transport);
+            EncryptedDnsCheck {
+                transport,
+                works: median.is_some(),
+                overhead_ms: median.zip(udp).map(|(median, udp)| median - udp),
+            }
+        })
+        .collect()
+}
+
+/// Every plain UDP resolver stayed silent while an encrypted one answered:
+/// something on the path blocks or swallows port 53.
+pub fn udp_dns_blocked(scores: &[DnsResolverScore]) -> bool {
+    let mut udp = scores
+        .iter()
+        .filter(|score| score.resolver.transport == DnsTransport::Udp)
+        .peekable();
+    udp.peek().is_some()
+        && udp.all(|score| score.median_ms.is_none())
+        && encrypted_dns_checks(scores).iter().any(|check| check.works)
+}
+
+impl DnsResolverScore {
+    /// Score one resolver from `(kind, latency)` samples; `None` is a query
+    /// that failed or timed out.
+    pub fn from_samples(
+        resolver: DnsResolverSpec,
+        samples: &[(DnsQueryKind, Option<f64>)],
+    ) -> Self {
+        let median_of = |kind: Option<DnsQueryKind>| {
+            median(
+                samples
+                    .iter()
+                    .filter(|(sample, _)| kind.is_none_or(|kind| *sample == kind))
+                    .filter_map(|(_, latency)| *latency)
+                    .collect(),
+            )
+        };
+        Self {
+            resolver,
+            cached_ms: median_of(Some(DnsQueryKind::Cached)),
+            uncached_ms: median_of(Some(DnsQueryKind::Uncached)),
+            dotcom_ms: median_of(Some(DnsQueryKind::Dotcom)),
+            median_ms: median_of(None),
+            queries: samples.len() as u32,
+            failures: samples
+                .iter()
+                .filter(|(_, latency)| latency.is_none())
+                .count() as u32,
+        }
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        if self.queries == 0 {
+            0.0
+        } else {
+            f64::from(self.failures) / f64::from(self.queries)
+        }
+    }
+
+    pub fn reliable(&self) -> bool {
+        self.median_ms.is_some() && self.failure_rate() <= RELIABLE_FAILURE_RATE
+    }
+}
+
+/// Order best first: reliable resolvers before the rest, then by median.
+pub fn rank_dns_resolvers(scores: &mut [DnsResolverScore]) {
+    scores.sort_by(|left, right| {
+        right.reliable().cmp(&left.reliable()).then(
+            left.median_ms
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&right.median_ms.unwrap_or(f64::INFINITY)),
+        )
+    });
+}
+
+pub(crate) fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolvers_are_scored_and_unreliable_ones_rank_last() {
+        let request = DnsBenchRequest {
+            custom: " 8.8.8.8 ".into(),
+            encrypted: false,
+            rounds: 2,
+            ..DnsBenchRequest::default()
+        };
+        let resolvers = request.resolvers();
+        assert_eq!(resolvers.len(), 4);
+        let queries = request.queries(0xab);
+        assert_eq!(queries.len(), 6);
+        assert_eq!(queries[1].1, "iptools-ab-0.google.com");
+
+        let fast_but_lossy = DnsResolverScore::from_samples(
+            resolvers[1].clone(),
+            &[
+                (DnsQueryKind::Cached, Some(2.0)),
+                (DnsQueryKind::Uncached, None),
+                (DnsQueryKind::Dotcom, Some(4.0)),
+            ],
+        );
+        assert_eq!(fast_but_lossy.median_ms, Some(3.0));
+        assert_eq!(fast_but_lossy.uncached_ms, None);
+        let steady = DnsResolverScore::from_samples(
+            resolvers[2].clone(),
+            &[
+                (DnsQueryKind::Cached, Some(9.0)),
+                (DnsQueryKind::Uncached, Some(30.0)),
+                (DnsQueryKind::Dotcom, Some(12.0)),
+            ],
+        );
+        let silent =
+            DnsResolverScore::from_samples(resolvers[3].clone(), &[(DnsQueryKind::Cached, None)]);
+        let mut scores = vec![silent, fast_but_lossy, steady];
+        rank_dns_resolvers(&mut scores);
+        assert_eq!(scores[0].resolver.name, "Google");
+        assert_eq!(scores[1].resolver.name, "Cloudflare");
+        assert_eq!(scores[2].resolver.label(), "Quad9 9.9.9.9");
+    }
+
+    #[test]
+    fn encrypted_transports_are_checked_against_plain_udp() {
+        let request = DnsBenchRequest {
+            custom: "tls://dns.quad9.net".into(),
+            ..DnsBenchRequest::default()
+        };
+        let resolvers = request.resolvers();
+        assert_eq!(resolvers.len(), 9);
+        let custom = resolvers.last().unwrap();
+        assert_eq!(custom.transport, DnsTransport::Tls);
+        assert_eq!(custom.address, "dns.quad9.net:853");
+        assert_eq!(custom.label(), "Custom DoT");
+
+        let score = |index: usize, latency: Option<f64>| {
+            DnsResolverScore::from_samples(
+                resolvers[index].clone(),
+                &[(DnsQueryKind::Dotcom, latency)],
+            )
+        };
+        let mut scores = vec![score(1, Some(10.0)), score(4, Some(16.0)), score(6, None)];
+        let checks = encrypted_dns_checks(&scores);
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].works && checks[0].overhead_ms == Some(6.0));
+        assert!(!checks[1].works);
+        assert!(!udp_dns_blocked(&scores));
+        scores[0] = score(1, None);
+        assert!(udp_dns_blocked(&scores));
+    }
+}
@@ -0,0 +1,265 @@
+//! DNS filtering detection: probe names, answer classification and working
+//! out which upstream rewrites the answers.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use crate::{DnsFilterProbe, DnsFilterRequest, DnsFilterUpstream, DnsFilterVerdict};
+
+/// Always resolvable and on no blocklist; an upstream that cannot answer it is
+/// broken rather than filtering.
+pub const DNS_FILTER_CONTROL: &str = "example.com";
+
+/// Ad and tracker names on the common Pi-hole / AdGuard / ISP blocklists, run
+/// by different operators so an honest answer never shares an address.
+pub const DNS_FILTER_BLOCKLISTED: [&str; 4] = [
+    "ad.doubleclick.net",
+    "ib.adnxs.com",
+    "b.scorecardresearch.com",
+    "static.ads-twitter.com",
+];
+
+/// What one upstream said about one name, before classification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsAnswer {
+    Addresses(Vec<IpAddr>),
+    NxDomain,
+    /// Timed out, refused or failed.
+    NoAnswer,
+}
+
+impl DnsFilterRequest {
+    /// The control name first, then the blocklisted names and the custom one.
+    pub fn domains(&self) -> Vec<String> {
+        let mut domains = vec![DNS_FILTER_CONTROL.to_string()];
+        domains.extend(DNS_FILTER_BLOCKLISTED.map(String::from));
+        let custom = self
+            .domain
+            .trim()
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        if !custom.is_empty() && !domains.contains(&custom) {
+            domains.push(custom);
+        }
+        domains
+    }
+}
+
+impl DnsFilterUpstream {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Gateway => "router",
+            Self::Public => "1.1.1.1",
+        }
+    }
+}
+
+/// Classify one upstream's `answers` against what an unfiltered `reference`
+/// resolver returned for the same names (empty when it was unreachable).
+///
+/// Besides NXDOMAIN and unroutable sinkholes, a public address returned for
+/// two or more different names that the reference never gave out is taken
+/// for a filter's block page.
+pub fn classify_dns_filter(
+    upstream: DnsFilterUpstream,
+    answers: &[(String, DnsAnswer)],
+    reference: &[(String, DnsAnswer)],
+) -> Vec<DnsFilterProbe> {
+    let expected = |domain: &str| {
+        reference
+            .iter()
+            .find(|(name, _)| name == domain)
+            .map(|(_, answer)| answer)
+    };
+    let mut shared = HashMap::<IpAddr, usize>::new();
+    for (domain, answer) in answers {
+        if let DnsAnswer::Addresses(addresses) = answer {
+            for address in addresses {
+                let honest = matches!(
+                    expected(domain),
+                    Some(DnsAnswer::Addresses(known)) if known.contains(address)
+                );
+                if !honest && domain != DNS_FILTER_CONTROL {
+                    *shared.entry(*address).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    answers
+        .iter()
+        .map(|(domain, answer)| {
+            let (verdict, detail) = match answer {
+                DnsAnswer::NoAnswer => (DnsFilterVerdict::Failed, "no answer".to_string()),
+                DnsAnswer::NxDomain | DnsAnswer::Addresses(_)
+                    if missing(answer) && expected(domain).is_some_and(missing) =>
+                {
+                    (DnsFilterVerdict::Clean, "NXDOMAIN (expected)".to_string())
+                }
+                DnsAnswer::NxDomain => (DnsFilterVerdict::Blocked, "NXDOMAIN".to_string()),
+                DnsAnswer::Addresses(addresses) if addresses.is_empty() => {
+                    (DnsFilterVerdict::Blocked, "no records".to_string())
+                }
+                DnsAnswer::Addresses(addresses) => {
+                    if let Some(sinkhole) = addresses.iter().find(|address| !routable(address)) {
+                        (DnsFilterVerdict::Blocked, format!("sinkhole {sinkhole}"))
+                    } else if let Some(page) = addresses
+                        .iter()
+                        .find(|address| shared.get(address).is_some_and(|count| *count > 1))
+                    {
+                        (DnsFilterVerdict::Blocked, format!("block page {page}"))
+                    } else {
+                        (DnsFilterVerdict::Clean, addresses[0].to_string())
+                    }
+                }
+            };
+            DnsFilterProbe {
+                upstream,
+                domain: domain.clone(),
+                verdict,
+                detail,
+            }
+        })
+        .collect()
+}
+
+/// Blocklisted names this upstream sinkholed, and how many it answered at all.
+/// An upstream that fails the control name is broken and counts as neither.
+pub fn dns_filter_tally(probes: &[DnsFilterProbe], upstream: DnsFilterUpstream) -> (usize, usize) {
+    let own = || {
+        probes
+            .iter()
+            .filter(move |probe| probe.upstream == upstream)
+    };
+    let healthy = own().any(|probe| {
+        probe.domain == DNS_FILTER_CONTROL && probe.verdict == DnsFilterVerdict::Clean
+    });
+    if !healthy {
+        return (0, 0);
+    }
+    let tested = own().filter(|probe| {
+        probe.domain != DNS_FILTER_CONTROL && probe.verdict != DnsFilterVerdict::Failed
+    });
+    let blocked = tested
+        .clone()
+        .filter(|probe| probe.verdict == DnsFilterVerdict::Blocked)
+        .count();
+    (blocked, tested.count())
+}
+
+/// The outermost upstream that filters: anything inside it inherits the
+/// filtering, so that is where the blocklist lives.
+pub fn dns_filtering_upstream(probes: &[DnsFilterProbe]) -> Option<DnsFilterUpstream> {
+    [
+        DnsFilterUpstream::Public,
+        DnsFilterUpstream::Gateway,
+        DnsFilterUpstream::System,
+    ]
+    .into_iter()
+    .find(|upstream| dns_filter_tally(probes, *upstream).0 > 0)
+}
+
+fn missing(answer: &DnsAnswer) -> bool {
+    match answer {
+        DnsAnswer::NxDomain => true,
+        DnsAnswer::Addresses(addresses) => addresses.is_empty(),
+        DnsAnswer::NoAnswer => false,
+    }
+}
+
+/// Addresses no public name legitimately resolves to: what sinkholes hand out.
+fn routable(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(v4) => {
+            let [first, second, ..] = v4.octets();
+            !(v4.is_unspecified()
+                || v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                // 100.64.0.0/10, carrier-grade NAT
+                || (first == 100 && second & 0xC0 == 64))
+        }
+        // 2000::/3; covers ::, ::1, link-local and ULA sinkholes
+        IpAddr::V6(v6) => v6.segments()[0] & 0xe000 == 0x2000,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(list: &[&str]) -> DnsAnswer {
+        DnsAnswer::Addresses(
+            list.iter()
+                .map(|address| address.parse().unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn sinkholes_and_block_pages_are_blocked_and_attributed() {
+        let request = DnsFilterRequest {
+            domain: "Tracker.Example.".into(),
+            ..DnsFilterRequest::default()
+        };
+        let domains = request.domains();
+        assert_eq!(domains.len(), 6);
+        assert_eq!(domains[0], DNS_FILTER_CONTROL);
+        assert_eq!(domains[5], "tracker.example");
+
+        let reference = vec![
+            (domains[0].clone(), addresses(&["93.184.215.14"])),
+            (domains[1].clone(), addresses(&["142.250.1.1"])),
+            (domains[2].clone(), addresses(&["104.18.2.2"])),
+            (domains[5].clone(), DnsAnswer::NxDomain),
+        ];
+        let pihole = vec![
+            (domains[0].clone(), addresses(&["93.184.215.14"])),
+            (domains[1].clone(), addresses(&["0.0.0.0"])),
+            (domains[2].clone(), addresses(&["::"])),
+            (domains[3].clone(), DnsAnswer::NxDomain),
+            (domains[4].clone(), DnsAnswer::NoAnswer),
+            (domains[5].clone(), DnsAnswer::NxDomain),
+        ];
+        let mut probes = classify_dns_filter(DnsFilterUpstream::System, &pihole, &reference);
+        let verdicts = probes.iter().map(|probe| probe.verdict).collect::<Vec<_>>();
+        assert_eq!(
+            verdicts,
+            [
+                DnsFilterVerdict::Clean,
+                DnsFilterVerdict::Blocked,
+                DnsFilterVerdict::Blocked,
+                DnsFilterVerdict::Blocked,
+                DnsFilterVerdict::Failed,
+                DnsFilterVerdict::Clean,
+            ]
+        );
+        assert_eq!(probes[1].detail, "sinkhole 0.0.0.0");
+        assert_eq!(dns_filter_tally(&probes, DnsFilterUpstream::System), (3, 4));
+
+        // The ISP answers every blocklisted name with its own landing page.
+        let isp = vec![
+            (domains[0].clone(), addresses(&["93.184.215.14"])),
+            (domains[1].clone(), addresses(&["142.250.1.1"])),
+            (domains[2].clone(), addresses(&["203.0.113.7"])),
+            (domains[3].clone(), addresses(&["203.0.113.7"])),
+        ];
+        let gateway = classify_dns_filter(DnsFilterUpstream::Gateway, &isp, &reference);
+        assert_eq!(gateway[1].verdict, DnsFilterVerdict::Clean);
+        assert_eq!(gateway[2].detail, "block page 203.0.113.7");
+        probes.extend(gateway);
+        let public = vec![(domains[0].clone(), DnsAnswer::NoAnswer)];
+        probes.extend(classify_dns_filter(
+            DnsFilterUpstream::Public,
+            &public,
+            &reference,
+        ));
+        assert_eq!(dns_filter_tally(&probes, DnsFilterUpstream::Public), (0, 0));
+        assert_eq!(
+            dns_filtering_upstream(&probes),
+            Some(DnsFilterUpstream::Gateway)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Self-diagnostics.
+//!
+//! `iptools doctor` and the Settings page run the same checks: privileges,
+//! ICMP sockets, packet capture, whether the local firewall lets the app's
+//! own listeners through, the configuration file and outbound connectivity.
+//! Each check reports a status, a short factual detail, and a
+//! [`DoctorHint`] that renderers turn into a localized remediation.
+
+use serde::{Deserialize, Serialize};
+
+use crate::JobId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoctorCheckKind {
+    Privileges,
+    Icmp,
+    PacketCapture,
+    Firewall,
+    Config,
+    Connectivity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DoctorStatus {
+    Ok,
+    /// Works, but some tools are limited.
+    Warn,
+    /// Tools depending on this will fail.
+    Fail,
+}
+
+/// What the user can do about a check that is not fully ok.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoctorHint {
+    /// Linux: `setcap cap_net_raw+ep` on the binary, or run with sudo.
+    GrantRawSockets,
+    /// Windows: run the terminal as administrator.
+    RunAsAdministrator,
+    InstallLibpcap,
+    InstallNpcap,
+    /// Allow the app through the host firewall for LAN speed and multicast.
+    AllowThroughFirewall,
+    /// Another program holds the LAN speed port.
+    FreeLanSpeedPort,
+    /// Fix the listed problems in the configuration file.
+    FixConfig,
+    /// Names do not resolve: check the DNS servers.
+    CheckDns,
+    /// Nothing outside answers: check the cable, Wi-Fi, proxy or VPN.
+    CheckUplink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    pub kind: DoctorCheckKind,
+    pub status: DoctorStatus,
+    /// What was found, in plain terms (paths, counts, error text).
+    pub detail: String,
+    pub hint: Option<DoctorHint>,
+}
+
+impl DoctorCheck {
+    pub fn new(kind: DoctorCheckKind, status: DoctorStatus, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    pub fn hint(mut self, hint: DoctorHint) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+}
+
+/// Worst status of a report; an empty report is ok.
+pub fn doctor_status(checks: &[DoctorCheck]) -> DoctorStatus {
+    checks
+        .iter()
+        .map(|check| check.status)
+        .max()
+        .unwrap_or(DoctorStatus::Ok)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DoctorState {
+    pub job: Option<JobId>,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorState {
+    pub const fn running(&self) -> bool {
+        self.job.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_report_is_as_bad_as_its_worst_check() {
+        assert_eq!(doctor_status(&[]), DoctorStatus::Ok);
+        let checks = [
+            DoctorCheck::new(DoctorCheckKind::Config, DoctorStatus::Ok, "config.toml"),
+            DoctorCheck::new(DoctorCheckKind::Icmp, DoctorStatus::Fail, "EPERM")
+                .hint(DoctorHint::GrantRawSockets),
+            DoctorCheck::new(DoctorCheckKind::PacketCapture, DoctorStatus::Warn, ""),
+        ];
+        assert_eq!(doctor_status(&checks), DoctorStatus::Fail);
+        assert_eq!(checks[1].hint, Some(DoctorHint::GrantRawSockets));
+    }
+}
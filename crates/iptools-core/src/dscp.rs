@@ -0,0 +1,84 @@
+//! Verdict for the QoS / DSCP marking check.
+
+use crate::{DscpHop, DscpSummary, DscpVerdict};
+
+/// The DSCP field is the upper six bits of the IPv4 TOS byte.
+pub const fn dscp_from_tos(tos: u8) -> u8 {
+    tos >> 2
+}
+
+impl DscpSummary {
+    /// Walk the hops in TTL order and report the first one that saw a
+    /// different marking. Hops that reported nothing are skipped.
+    pub fn from_hops(sent: u8, hops: &[DscpHop]) -> Self {
+        let mut observed_hops = hops
+            .iter()
+            .filter_map(|hop| hop.observed.map(|observed| (hop.ttl, observed)))
+            .peekable();
+        let verdict = if observed_hops.peek().is_none() {
+            DscpVerdict::Unknown
+        } else {
+            observed_hops
+                .find(|(_, observed)| *observed != sent)
+                .map_or(DscpVerdict::Preserved, |(ttl, observed)| {
+                    DscpVerdict::Rewritten { ttl, observed }
+                })
+        };
+        Self {
+            sent,
+            reached: hops.iter().any(|hop| hop.reached),
+            verdict,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hop(ttl: u8, observed: Option<u8>, reached: bool) -> DscpHop {
+        DscpHop {
+            ttl,
+            address: observed.map(|_| format!("10.0.0.{ttl}")),
+            rtt_ms: observed.map(|_| u64::from(ttl)),
+            observed,
+            reached,
+        }
+    }
+
+    #[test]
+    fn first_hop_with_a_different_marking_is_reported() {
+        let preserved = [
+            hop(1, Some(46), false),
+            hop(2, None, false),
+            hop(3, Some(46), true),
+        ];
+        assert_eq!(
+            DscpSummary::from_hops(46, &preserved),
+            DscpSummary {
+                sent: 46,
+                reached: true,
+                verdict: DscpVerdict::Preserved,
+            }
+        );
+
+        let stripped = [
+            hop(1, Some(46), false),
+            hop(2, Some(0), false),
+            hop(3, Some(0), true),
+        ];
+        assert_eq!(
+            DscpSummary::from_hops(46, &stripped).verdict,
+            DscpVerdict::Rewritten {
+                ttl: 2,
+                observed: 0
+            }
+        );
+
+        let silent = [hop(1, None, false), hop(2, None, false)];
+        let summary = DscpSummary::from_hops(46, &silent);
+        assert_eq!(summary.verdict, DscpVerdict::Unknown);
+        assert!(!summary.reached);
+        assert_eq!(dscp_from_tos(0xB8), 46);
+    }
+}
@@ -9,7 +9,18 @@ use crate::{
 pub struct Preferences {
     pub language: crate::Language,
     pub theme: crate::ThemeId,
+    pub color_mode: crate::ColorMode,
+    pub chart_style: crate::ChartStyle,
+    pub region_format: crate::RegionFormat,
+    pub clock_format: crate::ClockFormat,
+    pub show_utc: bool,
     pub scan_concurrency: usize,
+    pub scan_adaptive: bool,
+    pub retention: crate::RetentionLimits,
+    pub drop_alert_percent: u64,
+    pub low_power: crate::LowPowerMode,
+    pub offline: bool,
+    pub check_updates: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -25,17 +36,43 @@ pub enum ToolKind {
     AdapterEdit,
     Traffic,
     Scanner,
+    Bluetooth,
     Ping,
     Trace,
     PortScan,
     PublicSpeed,
     LinkQuality,
     LanSpeed,
+    Mtu,
+    Dscp,
+    Multicast,
+    Upnp,
+    Nat,
+    Ipv6,
+    DnsBench,
+    DnsFilter,
+    Mail,
+    Voip,
+    Gaming,
+    Cloud,
+    Wifi,
+    AbTest,
+    Update,
+    Doctor,
+    WebCheck,
+    ChartExport,
+    Dhcp,
+    AuditExport,
+    Asn,
+    Cve,
+    Watch,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct DashboardRequest {
     pub public_ip: PublicIpConfig,
+    /// Offline mode: collect local facts only, never the public IP.
+    pub offline: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -112,6 +149,20 @@ impl From<DiagnosticTool> for ToolKind {
             DiagnosticTool::PublicSpeed => Self::PublicSpeed,
             DiagnosticTool::LinkQuality => Self::LinkQuality,
             DiagnosticTool::LanSpeed => Self::LanSpeed,
+            DiagnosticTool::Mtu => Self::Mtu,
+            DiagnosticTool::Dscp => Self::Dscp,
+            DiagnosticTool::Multicast => Self::Multicast,
+            DiagnosticTool::Upnp => Self::Upnp,
+            DiagnosticTool::Nat => Self::Nat,
+            DiagnosticTool::Ipv6 => Self::Ipv6,
+            DiagnosticTool::DnsBench => Self::DnsBench,
+            DiagnosticTool::DnsFilter => Self::DnsFilter,
+            DiagnosticTool::Mail => Self::Mail,
+            DiagnosticTool::Voip => Self::Voip,
+            DiagnosticTool::Gaming => Self::Gaming,
+            DiagnosticTool::Cloud => Self::Cloud,
+            DiagnosticTool::Wifi => Self::Wifi,
+            DiagnosticTool::AbTest => Self::AbTest,
         }
     }
 }
@@ -120,6 +171,94 @@ impl From<DiagnosticTool> for ToolKind {
 pub struct ScanRequest {
     pub cidr: String,
     pub concurrency: usize,
+    /// Treat `concurrency` as a ceiling: start there and back off while
+    /// probes time out or fail, then climb back as the link recovers.
+    #[serde(default)]
+    pub adaptive: bool,
+    /// Probe [`crate::SHARE_PORTS`], [`crate::WEB_PORTS`] and
+    /// [`crate::IOT_PORTS`] on every host found, listing its shares,
+    /// fingerprinting its web pages and asking printers, cameras, brokers
+    /// and controllers what they are.
+    #[serde(default)]
+    pub enrich: bool,
+    /// Find named hosts through DNS alone: transfer this zone when its
+    /// servers allow it, otherwise look up the PTR record of every address.
+    /// Nothing is sent to the hosts themselves.
+    #[serde(default)]
+    pub dns_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BluetoothRequest {
+    /// Stop listening after this long.
+    pub duration_secs: u64,
+}
+
+impl Default for BluetoothRequest {
+    fn default() -> Self {
+        Self {
+            duration_secs: crate::BLUETOOTH_SCAN_SECS,
+        }
+    }
+}
+
+/// What each ping probe asks of the path. Record route and timestamp are
+/// IPv4-only and decoded into the ping log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum PingMode {
+    #[default]
+    Echo,
+    RecordRoute,
+    Timestamp,
+}
+
+impl PingMode {
+    pub const ALL: [Self; 3] = [Self::Echo, Self::RecordRoute, Self::Timestamp];
+
+    pub const fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub const fn previous(self) -> Self {
+        Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Common DSCP code points, in the order the settings cycle through them.
+pub const DSCP_CLASSES: [(u8, &str); 8] = [
+    (0, "CS0"),
+    (8, "CS1"),
+    (10, "AF11"),
+    (18, "AF21"),
+    (26, "AF31"),
+    (34, "AF41"),
+    (46, "EF"),
+    (48, "CS6"),
+];
+
+pub fn dscp_name(dscp: u8) -> Option<&'static str> {
+    DSCP_CLASSES
+        .iter()
+        .find(|(value, _)| *value == dscp)
+        .map(|(_, name)| *name)
+}
+
+/// Step to the neighbouring entry of [`DSCP_CLASSES`]; unknown values restart
+/// from best effort.
+pub fn cycle_dscp(dscp: u8, forward: bool) -> u8 {
+    let len = DSCP_CLASSES.len();
+    let index = DSCP_CLASSES
+        .iter()
+        .position(|(value, _)| *value == dscp)
+        .map_or(0, |index| {
+            if forward {
+                (index + 1) % len
+            } else {
+                (index + len - 1) % len
+            }
+        });
+    DSCP_CLASSES[index].0
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -128,6 +267,20 @@ pub struct PingRequest {
     pub interval_ms: u64,
     pub timeout_ms: u64,
     pub packet_size: u64,
+    #[serde(default)]
+    pub mode: PingMode,
+    #[serde(default)]
+    pub dont_fragment: bool,
+    /// DSCP code point (upper six bits of the TOS byte).
+    #[serde(default)]
+    pub dscp: u8,
+}
+
+impl PingRequest {
+    /// Whether the probe needs more than a plain echo from the backend.
+    pub fn has_ip_options(&self) -> bool {
+        self.mode != PingMode::Echo || self.dont_fragment || self.dscp != 0
+    }
 }
 
 impl Default for PingRequest {
@@ -137,6 +290,9 @@ impl Default for PingRequest {
             interval_ms: 1_000,
             timeout_ms: 2_000,
             packet_size: 32,
+            mode: PingMode::Echo,
+            dont_fragment: false,
+            dscp: 0,
         }
     }
 }
@@ -146,6 +302,10 @@ pub struct TraceRequest {
     pub target: String,
     pub max_hops: u8,
     pub timeout_ms: u64,
+    /// Interface name or local IPv4 address the probes leave from; the
+    /// routing table decides when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
 }
 
 impl Default for TraceRequest {
@@ -154,10 +314,398 @@ impl Default for TraceRequest {
             target: "8.8.8.8".into(),
             max_hops: 30,
             timeout_ms: 1_000,
+            via: None,
+        }
+    }
+}
+
+/// Largest-frame probe: DF-set ICMP echoes of growing size towards a peer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtuRequest {
+    pub target: String,
+    /// Upper bound of the search, as an IP MTU (payload + 28 header bytes).
+    pub max_mtu: u16,
+    pub timeout_ms: u64,
+}
+
+impl Default for MtuRequest {
+    fn default() -> Self {
+        Self {
+            target: "192.168.1.1".into(),
+            max_mtu: 9_000,
+            timeout_ms: 1_000,
+        }
+    }
+}
+
+/// QoS marking check: TTL-limited echoes carrying one DSCP value towards a
+/// peer, so each hop's reply shows the marking that reached it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DscpRequest {
+    pub target: String,
+    pub dscp: u8,
+    pub max_hops: u8,
+    pub timeout_ms: u64,
+}
+
+impl Default for DscpRequest {
+    fn default() -> Self {
+        Self {
+            target: "8.8.8.8".into(),
+            dscp: 46,
+            max_hops: 16,
+            timeout_ms: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MulticastMode {
+    #[default]
+    Listen,
+    Send,
+}
+
+impl MulticastMode {
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::Listen => Self::Send,
+            Self::Send => Self::Listen,
+        }
+    }
+}
+
+/// Multicast tester: join a group and measure what arrives, or emit a
+/// numbered test stream another instance can listen to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MulticastRequest {
+    pub mode: MulticastMode,
+    pub group: String,
+    pub port: u16,
+    pub duration_secs: u64,
+    /// Packets per second in send mode.
+    pub rate_pps: u32,
+}
+
+impl Default for MulticastRequest {
+    fn default() -> Self {
+        Self {
+            mode: MulticastMode::Listen,
+            group: "239.255.42.99".into(),
+            port: 5_004,
+            duration_secs: 30,
+            rate_pps: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpnpAction {
+    #[default]
+    List,
+    Add,
+    Delete,
+}
+
+impl UpnpAction {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::List => Self::Add,
+            Self::Add => Self::Delete,
+            Self::Delete => Self::List,
+        }
+    }
+
+    pub const fn previous(self) -> Self {
+        match self {
+            Self::List => Self::Delete,
+            Self::Add => Self::List,
+            Self::Delete => Self::Add,
+        }
+    }
+}
+
+/// UPnP IGD port-mapping request. The internal client is always this host;
+/// the default ports match the LAN speed server so a mapping can be checked
+/// from outside with the client on another network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpnpRequest {
+    pub action: UpnpAction,
+    pub protocol: LanProtocol,
+    pub external_port: u16,
+    pub internal_port: u16,
+    /// Lease for new mappings; 0 asks the router for a permanent one.
+    pub lease_secs: u32,
+}
+
+impl Default for UpnpRequest {
+    fn default() -> Self {
+        Self {
+            action: UpnpAction::List,
+            protocol: LanProtocol::Tcp,
+            external_port: 50_505,
+            internal_port: 50_505,
+            lease_secs: 3_600,
+        }
+    }
+}
+
+/// STUN-based NAT classification. The secondary server must have a different
+/// address from the primary for the mapping test to mean anything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NatRequest {
+    pub server: String,
+    pub secondary: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for NatRequest {
+    fn default() -> Self {
+        Self {
+            server: "stun.l.google.com:19302".into(),
+            secondary: "stun.cloudflare.com:3478".into(),
+            timeout_ms: 1_000,
+        }
+    }
+}
+
+/// One-shot IPv6 readiness audit. The target should be a v6-only name so a
+/// dual-stack fallback cannot hide a broken IPv6 path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ipv6Request {
+    pub target: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for Ipv6Request {
+    fn default() -> Self {
+        Self {
+            target: "ipv6.google.com".into(),
+            timeout_ms: 3_000,
+        }
+    }
+}
+
+/// Benchmarks the system resolver and the well-known public resolvers, plus
+/// `custom` when it is set. A custom `https://` URL is queried as DoH and a
+/// `tls://host[:port]` as DoT; anything else is a plain UDP resolver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsBenchRequest {
+    pub custom: String,
+    /// Also benchmark the public DoH and DoT endpoints.
+    #[serde(default)]
+    pub encrypted: bool,
+    /// Timed queries per kind and resolver.
+    pub rounds: u32,
+    pub timeout_ms: u64,
+}
+
+impl Default for DnsBenchRequest {
+    fn default() -> Self {
+        Self {
+            custom: String::new(),
+            encrypted: true,
+            rounds: 5,
+            timeout_ms: 2_000,
+        }
+    }
+}
+
+/// Resolves blocklisted ad / tracker names through each upstream and checks
+/// the answers for sinkholes; `domain` adds one more suspect name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsFilterRequest {
+    pub domain: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for DnsFilterRequest {
+    fn default() -> Self {
+        Self {
+            domain: String::new(),
+            timeout_ms: 2_000,
+        }
+    }
+}
+
+/// Talks to every SMTP, IMAP and POP3 port of `host`: banner, STARTTLS,
+/// certificate and the login mechanisms on offer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailRequest {
+    pub host: String,
+    /// Per port, from connecting to the last reply.
+    pub timeout_ms: u64,
+}
+
+impl Default for MailRequest {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            timeout_ms: 5_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum VoipMode {
+    #[default]
+    Probe,
+    Reflect,
+}
+
+impl VoipMode {
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::Probe => Self::Reflect,
+            Self::Reflect => Self::Probe,
+        }
+    }
+}
+
+/// Voice codec whose packet size and E-model impairment the probe uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum VoipCodec {
+    #[default]
+    G711,
+    G729,
+}
+
+/// VoIP quality probe: send a 50 packet/s RTP-style stream to a UDP echo
+/// reflector (another iptools in reflect mode) and score the round trips,
+/// or reflect such a stream back to its sender.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoipRequest {
+    pub mode: VoipMode,
+    pub peer: String,
+    pub port: u16,
+    pub codec: VoipCodec,
+    pub duration_secs: u64,
+}
+
+impl Default for VoipRequest {
+    fn default() -> Self {
+        Self {
+            mode: VoipMode::Probe,
+            peer: String::new(),
+            port: 50_506,
+            codec: VoipCodec::G711,
+            duration_secs: 15,
+        }
+    }
+}
+
+/// Which built-in game server regions a gaming latency test includes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum GamingPreset {
+    #[default]
+    All,
+    Americas,
+    Europe,
+    AsiaPacific,
+    /// Only the custom endpoints.
+    Custom,
+}
+
+/// Gaming latency profile: time a burst of small probes to each region of
+/// the preset plus the `custom` endpoints and rank them by median latency.
+/// `custom` is a comma separated list of `[name=]host:port`; `udp://`
+/// entries expect a UDP echo, anything else is timed by TCP connect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GamingRequest {
+    pub preset: GamingPreset,
+    pub custom: String,
+    /// Probes per endpoint.
+    pub rounds: u32,
+    pub timeout_ms: u64,
+}
+
+impl Default for GamingRequest {
+    fn default() -> Self {
+        Self {
+            preset: GamingPreset::All,
+            custom: String::new(),
+            rounds: 20,
+            timeout_ms: 1_000,
         }
     }
 }
 
+/// Cloud reachability matrix: connect to every endpoint of the curated
+/// list `attempts` times and report which answer and how fast.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudRequest {
+    pub attempts: u32,
+    pub timeout_ms: u64,
+}
+
+impl Default for CloudRequest {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            timeout_ms: 3_000,
+        }
+    }
+}
+
+/// Wi-Fi monitor: sample the association of one wireless adapter every
+/// `interval_secs` until stopped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WifiRequest {
+    /// Adapter name, for display.
+    pub adapter: String,
+    /// What the platform WLAN query takes: the GUID on Windows, the
+    /// interface name elsewhere.
+    pub guid: String,
+    pub interval_secs: u64,
+}
+
+impl Default for WifiRequest {
+    fn default() -> Self {
+        Self {
+            adapter: String::new(),
+            guid: String::new(),
+            interval_secs: crate::DEFAULT_WIFI_INTERVAL_SECS,
+        }
+    }
+}
+
+/// A/B interface comparison: the same battery of pings to `target`, a
+/// short download and DNS lookups, once from each of two interfaces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbRequest {
+    /// Interface name or local IPv4 address of side A, as trace's `via`.
+    pub first: String,
+    pub second: String,
+    pub target: String,
+    /// Echo requests per side.
+    pub pings: u32,
+    pub timeout_ms: u64,
+}
+
+impl Default for AbRequest {
+    fn default() -> Self {
+        Self {
+            first: String::new(),
+            second: String::new(),
+            target: "1.1.1.1".into(),
+            pings: 10,
+            timeout_ms: 1_000,
+        }
+    }
+}
+
+/// One round of the Dashboard watch targets; each target is checked once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchRequest {
+    pub targets: Vec<WatchTarget>,
+    pub timeout_ms: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PortScanRequest {
     pub target: String,
@@ -165,6 +713,8 @@ pub struct PortScanRequest {
     pub end_port: u16,
     pub timeout_ms: u64,
     pub concurrency: usize,
+    /// SSH jump host to connect from; the target is then resolved there.
+    pub jump_host: Option<String>,
 }
 
 impl Default for PortScanRequest {
@@ -175,6 +725,7 @@ impl Default for PortScanRequest {
             end_port: 1_024,
             timeout_ms: 300,
             concurrency: 50,
+            jump_host: None,
         }
     }
 }
@@ -304,6 +855,13 @@ impl Default for LanSpeedRequest {
 pub enum Effect {
     PersistPreferences(Preferences),
     PersistSession(SessionUpdate),
+    PersistPublicIp(PublicIpConfig),
+    /// Visible tabs in order.
+    PersistTabs(Vec<crate::Page>),
+    /// Dashboard watch targets in tile order.
+    PersistWatch(Vec<WatchTarget>),
+    /// SSH jump host; empty turns it off.
+    PersistJumpHost(String),
     PersistAdapterEdit {
         guid: String,
         params: AdapterEditParams,
@@ -328,6 +886,11 @@ pub enum Effect {
         request: ScanRequest,
     },
     CancelScan(JobId),
+    StartBluetoothScan {
+        job: JobId,
+        request: BluetoothRequest,
+    },
+    StopBluetoothScan(JobId),
     StartPing {
         job: JobId,
         request: PingRequest,
@@ -358,17 +921,153 @@ pub enum Effect {
         request: LanSpeedRequest,
     },
     StopLanSpeed(JobId),
+    StartMtu {
+        job: JobId,
+        request: MtuRequest,
+    },
+    StopMtu(JobId),
+    StartDscp {
+        job: JobId,
+        request: DscpRequest,
+    },
+    StopDscp(JobId),
+    StartMulticast {
+        job: JobId,
+        request: MulticastRequest,
+    },
+    StopMulticast(JobId),
+    StartUpnp {
+        job: JobId,
+        request: UpnpRequest,
+    },
+    StopUpnp(JobId),
+    StartNat {
+        job: JobId,
+        request: NatRequest,
+    },
+    StopNat(JobId),
+    StartIpv6 {
+        job: JobId,
+        request: Ipv6Request,
+    },
+    StopIpv6(JobId),
+    StartDnsBench {
+        job: JobId,
+        request: DnsBenchRequest,
+    },
+    StopDnsBench(JobId),
+    StartDnsFilter {
+        job: JobId,
+        request: DnsFilterRequest,
+    },
+    StopDnsFilter(JobId),
+    StartMail {
+        job: JobId,
+        request: MailRequest,
+    },
+    StopMail(JobId),
+    StartVoip {
+        job: JobId,
+        request: VoipRequest,
+    },
+    StopVoip(JobId),
+    StartGaming {
+        job: JobId,
+        request: GamingRequest,
+    },
+    StopGaming(JobId),
+    StartCloud {
+        job: JobId,
+        request: CloudRequest,
+    },
+    StopCloud(JobId),
+    StartWifi {
+        job: JobId,
+        request: WifiRequest,
+    },
+    StopWifi(JobId),
+    StartAbTest {
+        job: JobId,
+        request: AbRequest,
+    },
+    StopAbTest(JobId),
+    /// Look up the latest published release.
+    CheckUpdate {
+        job: JobId,
+        use_system_proxy: bool,
+    },
+    /// Run the self-diagnostics.
+    RunDoctor {
+        job: JobId,
+    },
+    /// Web stage of a diagnostic preset.
+    StartWebCheck {
+        job: JobId,
+        request: crate::WebCheckRequest,
+    },
+    StopWebCheck(JobId),
+    /// Draw a chart to a new file.
+    ExportChart {
+        job: JobId,
+        chart: crate::Chart,
+        format: crate::ChartFormat,
+    },
+    /// Broadcast a DHCPDISCOVER and collect the offers for the audit.
+    ProbeDhcp {
+        job: JobId,
+    },
+    /// Write the audit report to a new Markdown file.
+    ExportAudit {
+        job: JobId,
+        markdown: String,
+    },
+    /// Routing origin of public trace hops, one [`RuntimeEvent::AsnFound`]
+    /// per address.
+    LookupAsn {
+        job: JobId,
+        addresses: Vec<String>,
+        use_system_proxy: bool,
+    },
+    /// Known CVEs of software a scanned host runs, one
+    /// [`RuntimeEvent::CveHintFound`] per version.
+    LookupCves {
+        job: JobId,
+        software: Vec<crate::Software>,
+        use_system_proxy: bool,
+    },
+    /// Check every watch target once, one [`RuntimeEvent::WatchChecked`]
+    /// each.
+    StartWatch {
+        job: JobId,
+        request: WatchRequest,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SessionUpdate {
     Scanner(crate::ScannerPersist),
     CidrHistory(Vec<String>),
+    Inventory(crate::InventoryPersist),
+    Events(crate::EventLogPersist),
     Ping(crate::PingPersist),
     Trace(crate::TracePersist),
     PortScan(crate::PortScanPersist),
     LanSpeed(crate::LanSpeedPersist),
     LinkQuality(crate::LinkQualityPersist),
+    Mtu(crate::MtuPersist),
+    Dscp(crate::DscpPersist),
+    Multicast(crate::MulticastPersist),
+    Upnp(crate::UpnpPersist),
+    Nat(crate::NatPersist),
+    Ipv6(crate::Ipv6Persist),
+    DnsBench(crate::DnsBenchPersist),
+    DnsFilter(crate::DnsFilterPersist),
+    Mail(crate::MailPersist),
+    Voip(crate::VoipPersist),
+    Gaming(crate::GamingPersist),
+    Cloud(crate::CloudPersist),
+    Wifi(crate::WifiPersist),
+    AbTest(crate::AbTestPersist),
     TargetHistory(Vec<String>),
     Ui(crate::UiPersist),
     Reset(crate::UiPersist),
@@ -386,63 +1085,548 @@ pub enum RuntimeErrorCode {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct RuntimeError {
-    pub code: RuntimeErrorCode,
-    pub message: String,
+pub struct RuntimeError {
+    pub code: RuntimeErrorCode,
+    pub message: String,
+}
+
+impl RuntimeError {
+    pub fn new(code: RuntimeErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingSample {
+    pub sequence: u64,
+    pub latency_ms: Option<u64>,
+    pub ttl: Option<u8>,
+    pub size: usize,
+    /// Addresses recorded by the IP Record Route option, in path order.
+    #[serde(default)]
+    pub route: Vec<String>,
+    #[serde(default)]
+    pub timestamps: Option<IcmpTimestamps>,
+    pub sent: u64,
+    pub received: u64,
+    pub min_ms: Option<u64>,
+    pub average_ms: Option<f64>,
+    pub max_ms: Option<u64>,
+    pub loss_percent: f64,
+}
+
+/// ICMP Timestamp Reply fields, in milliseconds since midnight UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IcmpTimestamps {
+    pub originate_ms: u32,
+    pub receive_ms: u32,
+    pub transmit_ms: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingSummary {
+    pub sent: u64,
+    pub received: u64,
+    pub min_ms: Option<u64>,
+    pub average_ms: Option<f64>,
+    pub max_ms: Option<u64>,
+    pub loss_percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceHop {
+    pub ttl: u8,
+    pub address: Option<String>,
+    pub hostname: Option<String>,
+    pub latency_ms: Option<u64>,
+}
+
+/// One DF-set echo of the MTU search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtuProbe {
+    /// IP packet size on the wire.
+    pub size: u16,
+    pub outcome: MtuOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MtuOutcome {
+    Carried {
+        rtt_ms: u64,
+    },
+    /// Refused locally or by a router ("fragmentation needed").
+    TooBig,
+    Lost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtuSummary {
+    /// Largest IP packet that made the round trip, if any did.
+    pub path_mtu: Option<u16>,
+    pub probes: u32,
+}
+
+impl MtuSummary {
+    /// Standard Ethernet frames carry 1500-byte packets; jumbo frames 9000.
+    pub fn jumbo(&self) -> bool {
+        self.path_mtu.is_some_and(|mtu| mtu >= 9_000)
+    }
+}
+
+/// One TTL step of the DSCP check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DscpHop {
+    pub ttl: u8,
+    pub address: Option<String>,
+    pub rtt_ms: Option<u64>,
+    /// DSCP in the header the hop quoted back (time exceeded) or, for the
+    /// peer itself, the marking it reflected in its echo reply.
+    pub observed: Option<u8>,
+    pub reached: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DscpVerdict {
+    /// Every hop that reported a marking saw the one we sent.
+    Preserved,
+    /// The first hop that saw a different marking.
+    Rewritten { ttl: u8, observed: u8 },
+    /// No hop reported a marking.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DscpSummary {
+    pub sent: u8,
+    pub reached: bool,
+    pub verdict: DscpVerdict,
+}
+
+/// Running multicast counters. In listen mode `packets` and `bytes` count
+/// what arrived; in send mode, what was sent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MulticastSample {
+    pub elapsed_ms: u64,
+    pub packets: u64,
+    pub bytes: u64,
+    /// Distinct source addresses heard from.
+    pub senders: u32,
+    /// Gaps in the sequence numbers of iptools test streams.
+    pub lost: u64,
+    /// Smoothed inter-arrival jitter (RFC 3550 style).
+    pub jitter_ms: Option<f64>,
+}
+
+/// The Internet Gateway Device answering SSDP discovery.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpnpGateway {
+    pub name: String,
+    /// `WANIPConnection` or `WANPPPConnection`, with version.
+    pub service: String,
+    /// External address as reported by the router, which may itself be
+    /// private when there is another NAT upstream.
+    pub external_ip: Option<String>,
+    /// This host's address on the router's LAN.
+    pub local_ip: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpnpMapping {
+    pub protocol: LanProtocol,
+    pub external_port: u16,
+    pub internal_client: String,
+    pub internal_port: u16,
+    pub description: String,
+    pub enabled: bool,
+    /// Remaining lease; 0 is permanent.
+    pub lease_secs: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpnpOutcome {
+    Listed,
+    Added {
+        protocol: LanProtocol,
+        external_port: u16,
+        internal_port: u16,
+    },
+    Deleted {
+        protocol: LanProtocol,
+        external_port: u16,
+    },
+}
+
+/// One STUN binding exchange.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StunProbe {
+    pub server: String,
+    /// Reflexive (public) address and port the server saw.
+    pub mapped: Option<String>,
+    pub rtt_ms: Option<u64>,
+}
+
+/// How the NAT decides which outside packets may come back in, from the
+/// RFC 5780 CHANGE-REQUEST tests. Only servers that advertise an alternate
+/// address can answer them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NatFiltering {
+    EndpointIndependent,
+    AddressDependent,
+    AddressAndPortDependent,
+    Untested,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NatType {
+    /// The mapped address is one of this host's own addresses.
+    Open,
+    FullCone,
+    RestrictedCone,
+    PortRestrictedCone,
+    /// Endpoint-independent mapping whose filtering could not be tested.
+    Cone,
+    Symmetric,
+    /// No STUN server answered over UDP.
+    Blocked,
+    /// Behind NAT, but there was no second answer to compare mappings.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NatSummary {
+    pub nat_type: NatType,
+    pub local: String,
+    pub mapped: Option<String>,
+    /// Whether a packet sent to our own mapped address came back in.
+    pub hairpin: Option<bool>,
+}
+
+/// Items of the IPv6 readiness checklist, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ipv6Check {
+    GlobalAddress,
+    DefaultRoute,
+    DnsAaaa,
+    Reachability,
+    PathMtu,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ipv6Verdict {
+    Pass,
+    Fail,
+    /// Not run, because an earlier check it depends on failed or the platform
+    /// cannot measure it.
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ipv6CheckResult {
+    pub check: Ipv6Check,
+    pub verdict: Ipv6Verdict,
+    pub detail: String,
+}
+
+/// How a benchmarked resolver is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DnsTransport {
+    /// The operating system's resolver, with whatever caching it does.
+    System,
+    /// Plain DNS over UDP port 53.
+    Udp,
+    /// DNS-over-HTTPS (RFC 8484); the address is the query URL.
+    Https,
+    /// DNS-over-TLS (RFC 7858); the address is `host:port`.
+    Tls,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsResolverSpec {
+    pub name: String,
+    pub transport: DnsTransport,
+    /// Server address, DoH URL or DoT `host:port`; empty for the system
+    /// resolver.
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DnsQueryKind {
+    /// A popular name asked again right after a warm-up query.
+    Cached,
+    /// A random label that no resolver can have cached.
+    Uncached,
+    /// Real but less popular .com names, as a typical browsing mix.
+    Dotcom,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsResolverScore {
+    pub resolver: DnsResolverSpec,
+    /// Median latency per query kind.
+    pub cached_ms: Option<f64>,
+    pub uncached_ms: Option<f64>,
+    pub dotcom_ms: Option<f64>,
+    /// Median over every answered query.
+    pub median_ms: Option<f64>,
+    pub queries: u32,
+    pub failures: u32,
+}
+
+/// Where a DNS filter check sends its queries, innermost first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DnsFilterUpstream {
+    /// The operating system's resolver: a Pi-hole or local blocklist shows here.
+    System,
+    /// The default gateway on UDP 53, usually forwarding to the ISP.
+    Gateway,
+    /// A public resolver on UDP 53; filtering here means the path intercepts port 53.
+    Public,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DnsFilterVerdict {
+    /// A real answer, or the same NXDOMAIN the reference resolver gives.
+    Clean,
+    /// Sinkholed: NXDOMAIN, an unroutable address or a shared block page.
+    Blocked,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsFilterProbe {
+    pub upstream: DnsFilterUpstream,
+    pub domain: String,
+    pub verdict: DnsFilterVerdict,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MailProtocol {
+    Smtp,
+    Imap,
+    Pop3,
+}
+
+/// What one mail port answered. A port that refused or timed out has no
+/// banner and an `error`; a certificate that does not verify leaves
+/// `tls_error` set and nothing learned over TLS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailCheck {
+    pub port: u16,
+    pub protocol: MailProtocol,
+    /// TLS from the first byte (465, 993, 995) rather than STARTTLS.
+    pub implicit_tls: bool,
+    /// First line of the greeting.
+    pub banner: Option<String>,
+    /// Whether a plaintext port offered STARTTLS; `None` on implicit TLS
+    /// ports and before the greeting.
+    pub starttls: Option<bool>,
+    pub tls: Option<crate::TlsSummary>,
+    /// Handshake or certificate failure.
+    pub tls_error: Option<String>,
+    /// Login mechanisms on the most secure channel reached.
+    pub auth: Vec<String>,
+    /// Passwords could be sent before TLS: PLAIN / LOGIN, IMAP `LOGIN`
+    /// without `LOGINDISABLED`, or POP3 `USER`.
+    pub cleartext_auth: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamingProtocol {
+    /// A UDP echo such as the AWS GameLift ping beacons.
+    Udp,
+    /// TCP connect time, for servers that answer nothing over UDP.
+    Tcp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GamingEndpoint {
+    pub region: String,
+    pub host: String,
+    pub port: u16,
+    pub protocol: GamingProtocol,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamingScore {
+    pub endpoint: GamingEndpoint,
+    pub median_ms: Option<f64>,
+    /// Mean difference between consecutive answered probes.
+    pub jitter_ms: Option<f64>,
+    pub probes: u32,
+    pub lost: u32,
+}
+
+/// One row of the cloud reachability matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CloudGroup {
+    Aws,
+    Gcp,
+    Azure,
+    Cdn,
+    Dns,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudEndpoint {
+    pub group: CloudGroup,
+    /// Region code or operator name.
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloudProbe {
+    pub endpoint: CloudEndpoint,
+    /// Median connect time over the attempts that succeeded.
+    pub latency_ms: Option<f64>,
+    pub answered: u32,
+    pub attempts: u32,
+    /// Why the last failed attempt failed.
+    pub error: Option<String>,
+}
+
+/// Which interface of an A/B comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbSide {
+    A,
+    B,
+}
+
+/// What one side of an A/B comparison measured. A metric stays `None` when
+/// that part of the battery got no answer at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbLeg {
+    pub side: AbSide,
+    /// The interface or address as entered.
+    pub via: String,
+    /// Local address every socket of the leg was bound to.
+    pub source: String,
+    pub sent: u32,
+    pub received: u32,
+    pub latency_ms: Option<f64>,
+    /// Mean difference between consecutive replies.
+    pub jitter_ms: Option<f64>,
+    pub download_bytes_per_second: Option<f64>,
+    /// Median lookup time.
+    pub dns_ms: Option<f64>,
+    /// Why a part of the battery failed, if one did.
+    pub error: Option<String>,
+}
+
+/// The association of a wireless adapter at one moment of a Wi-Fi monitor
+/// run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WifiSample {
+    /// Since the monitor started.
+    pub elapsed_ms: u64,
+    /// `None` while the adapter is not associated.
+    pub link: Option<WirelessSnapshot>,
+    /// Access points from the adapter's last scan, as cached by the OS;
+    /// empty when the platform cannot list them.
+    #[serde(default)]
+    pub nearby: Vec<crate::NearbyNetwork>,
 }
 
-impl RuntimeError {
-    pub fn new(code: RuntimeErrorCode, message: impl Into<String>) -> Self {
-        Self {
-            code,
-            message: message.into(),
-        }
-    }
+/// A host or URL the Dashboard keeps an eye on. `target` is a bare host
+/// (ICMP echo), `host:port` (TCP connect) or an `http(s)://` URL (GET).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct WatchTarget {
+    pub name: String,
+    pub target: String,
+    /// Expected latency; slower answers turn the tile amber. Without one a
+    /// fixed threshold applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_ms: Option<u32>,
+    /// Daily maintenance window in local time, `HH:MM-HH:MM`; outages
+    /// inside it raise no alert.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<String>,
 }
 
+/// Outcome of checking one watch target.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct PingSample {
-    pub sequence: u64,
-    pub latency_ms: Option<u64>,
-    pub ttl: Option<u8>,
-    pub size: usize,
-    pub sent: u64,
-    pub received: u64,
-    pub min_ms: Option<u64>,
-    pub average_ms: Option<f64>,
-    pub max_ms: Option<u64>,
-    pub loss_percent: f64,
+pub struct WatchCheck {
+    pub target: WatchTarget,
+    pub latency_ms: Option<f64>,
+    /// Why the check failed; `None` when the target answered.
+    pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct PingSummary {
+/// Running VoIP stream counters. A probe counts its own packets and the
+/// echoes; a reflector counts what it echoed in both `sent` and `received`
+/// and has no round trips.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VoipSample {
+    pub elapsed_ms: u64,
     pub sent: u64,
     pub received: u64,
-    pub min_ms: Option<u64>,
-    pub average_ms: Option<f64>,
-    pub max_ms: Option<u64>,
-    pub loss_percent: f64,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct TraceHop {
-    pub ttl: u8,
-    pub address: Option<String>,
-    pub hostname: Option<String>,
-    pub latency_ms: Option<u64>,
+    /// Packets that never came back: sequence gaps while running, every
+    /// missing echo once the stream has settled.
+    pub lost: u64,
+    pub out_of_order: u64,
+    pub rtt_ms: Option<f64>,
+    /// Smoothed variation of the round trip (RFC 3550 style).
+    pub jitter_ms: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PortScanResult {
     pub port: u16,
     pub service: String,
 }
 
+/// Which part of the public speed test a sample or latency probe belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpeedPhase {
+    Idle,
+    #[default]
+    Download,
+    Upload,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SpeedSample {
     pub elapsed_ms: u64,
     pub bytes: u64,
     pub bytes_per_second: u64,
+    #[serde(default)]
+    pub phase: SpeedPhase,
+}
+
+/// One latency probe taken before or alongside the transfer; `rtt_ms` is
+/// `None` when the probe timed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencySample {
+    pub phase: SpeedPhase,
+    pub rtt_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BufferbloatGrade {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+/// Median latency per phase and the grade derived from the worst loaded phase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BufferbloatSummary {
+    pub idle_ms: Option<f64>,
+    pub download_ms: Option<f64>,
+    pub upload_ms: Option<f64>,
+    pub grade: Option<BufferbloatGrade>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -450,6 +1634,10 @@ pub struct SpeedSummary {
     pub average_bytes_per_second: u64,
     pub peak_bytes_per_second: u64,
     pub total_bytes: u64,
+    #[serde(default)]
+    pub upload_bytes_per_second: Option<u64>,
+    #[serde(default)]
+    pub bufferbloat: Option<BufferbloatSummary>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -603,14 +1791,34 @@ pub enum RuntimeEvent {
         job: JobId,
         total: u64,
     },
+    /// Snapshot of the whole scan; the last one of a stopped scan shows
+    /// what was cancelled.
     ScanProgress {
         job: JobId,
-        current: u64,
-        total: u64,
+        progress: crate::ScanProgress,
     },
     ScanHostFound {
         job: JobId,
-        host: ScanHost,
+        host: Box<ScanHost>,
+    },
+    /// mDNS / SSDP discovery of the scan finished.
+    ScanServicesFound {
+        job: JobId,
+        services: Vec<crate::DiscoveredService>,
+    },
+    /// Port enrichment finished for a host reported earlier by `ScanHostFound`.
+    ScanHostEnriched {
+        job: JobId,
+        ip: String,
+        open_ports: Vec<u16>,
+        shares: Vec<crate::NetworkShare>,
+        web: Vec<crate::WebService>,
+        #[serde(default)]
+        iot: Vec<crate::IotService>,
+        #[serde(default)]
+        smb1: bool,
+        #[serde(default)]
+        banners: Vec<crate::ServiceBanner>,
     },
     ScanFinished {
         job: JobId,
@@ -618,6 +1826,18 @@ pub enum RuntimeEvent {
     ScanCancelled {
         job: JobId,
     },
+    /// An advertisement; the same device is reported again on each one.
+    BluetoothDeviceSeen {
+        job: JobId,
+        device: crate::BluetoothDevice,
+    },
+    BluetoothScanFinished {
+        job: JobId,
+    },
+    BluetoothScanFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
     PingStarted {
         job: JobId,
     },
@@ -679,6 +1899,10 @@ pub enum RuntimeEvent {
         job: JobId,
         sample: SpeedSample,
     },
+    PublicSpeedLatency {
+        job: JobId,
+        sample: LatencySample,
+    },
     PublicSpeedFinished {
         job: JobId,
         summary: SpeedSummary,
@@ -723,4 +1947,456 @@ pub enum RuntimeEvent {
         job: JobId,
         error: RuntimeError,
     },
+    MtuStarted {
+        job: JobId,
+    },
+    MtuProbe {
+        job: JobId,
+        probe: MtuProbe,
+    },
+    MtuFinished {
+        job: JobId,
+        summary: MtuSummary,
+    },
+    MtuFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    DscpStarted {
+        job: JobId,
+    },
+    DscpHop {
+        job: JobId,
+        hop: DscpHop,
+    },
+    DscpFinished {
+        job: JobId,
+        summary: DscpSummary,
+    },
+    DscpFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    MulticastStarted {
+        job: JobId,
+    },
+    MulticastSample {
+        job: JobId,
+        sample: MulticastSample,
+    },
+    MulticastFinished {
+        job: JobId,
+        summary: MulticastSample,
+    },
+    MulticastFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    UpnpStarted {
+        job: JobId,
+    },
+    UpnpGateway {
+        job: JobId,
+        gateway: UpnpGateway,
+    },
+    /// The full mapping table, re-read after any change.
+    UpnpMappings {
+        job: JobId,
+        mappings: Vec<UpnpMapping>,
+    },
+    UpnpFinished {
+        job: JobId,
+        outcome: UpnpOutcome,
+    },
+    UpnpFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    NatStarted {
+        job: JobId,
+    },
+    NatProbe {
+        job: JobId,
+        probe: StunProbe,
+    },
+    NatFinished {
+        job: JobId,
+        summary: NatSummary,
+    },
+    NatFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    Ipv6Started {
+        job: JobId,
+    },
+    Ipv6Result {
+        job: JobId,
+        result: Ipv6CheckResult,
+    },
+    Ipv6Finished {
+        job: JobId,
+    },
+    Ipv6Failed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    DnsBenchStarted {
+        job: JobId,
+        resolvers: u32,
+    },
+    DnsBenchScore {
+        job: JobId,
+        score: DnsResolverScore,
+    },
+    DnsBenchFinished {
+        job: JobId,
+    },
+    DnsBenchFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    DnsFilterStarted {
+        job: JobId,
+        upstreams: Vec<DnsFilterUpstream>,
+        domains: u32,
+    },
+    DnsFilterResult {
+        job: JobId,
+        probe: DnsFilterProbe,
+    },
+    DnsFilterFinished {
+        job: JobId,
+    },
+    DnsFilterFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    MailResult {
+        job: JobId,
+        check: MailCheck,
+    },
+    MailFinished {
+        job: JobId,
+    },
+    MailFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    /// The probe's peer address, or the reflector's listening address.
+    VoipStarted {
+        job: JobId,
+        endpoint: String,
+    },
+    VoipSample {
+        job: JobId,
+        sample: VoipSample,
+    },
+    VoipFinished {
+        job: JobId,
+        summary: VoipSample,
+    },
+    VoipFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    GamingStarted {
+        job: JobId,
+        endpoints: u32,
+    },
+    GamingScore {
+        job: JobId,
+        score: GamingScore,
+    },
+    GamingFinished {
+        job: JobId,
+    },
+    GamingFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    CloudStarted {
+        job: JobId,
+        endpoints: u32,
+    },
+    CloudResult {
+        job: JobId,
+        probe: CloudProbe,
+    },
+    CloudFinished {
+        job: JobId,
+    },
+    CloudFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    WifiSample {
+        job: JobId,
+        sample: WifiSample,
+    },
+    /// The monitor stopped; it only ends on request.
+    WifiFinished {
+        job: JobId,
+    },
+    WifiFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    AbTestStarted {
+        job: JobId,
+    },
+    /// One side finished its battery; A comes first.
+    AbTestLeg {
+        job: JobId,
+        leg: AbLeg,
+    },
+    AbTestFinished {
+        job: JobId,
+    },
+    AbTestFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    /// The latest published release, whether or not it is newer.
+    UpdateCheckFinished {
+        job: JobId,
+        release: crate::ReleaseInfo,
+    },
+    UpdateCheckFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    DoctorFinished {
+        job: JobId,
+        checks: Vec<crate::DoctorCheck>,
+    },
+    WebCheckFinished {
+        job: JobId,
+        results: Vec<crate::WebCheckResult>,
+    },
+    WebCheckFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    ChartExported {
+        job: JobId,
+        path: String,
+    },
+    ChartExportFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    DhcpProbeFinished {
+        job: JobId,
+        servers: Vec<crate::DhcpServer>,
+    },
+    DhcpProbeFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    AuditExported {
+        job: JobId,
+        path: String,
+    },
+    AuditExportFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    AsnFound {
+        job: JobId,
+        origin: crate::AsnOrigin,
+    },
+    AsnLookupFinished {
+        job: JobId,
+    },
+    AsnLookupFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    CveHintFound {
+        job: JobId,
+        hint: crate::CveHint,
+    },
+    CveLookupFinished {
+        job: JobId,
+    },
+    CveLookupFailed {
+        job: JobId,
+        error: RuntimeError,
+    },
+    WatchChecked {
+        job: JobId,
+        check: WatchCheck,
+    },
+    WatchFinished {
+        job: JobId,
+    },
+}
+
+impl RuntimeEvent {
+    /// The job and error of a failure event.
+    pub const fn failure(&self) -> Option<(JobId, &RuntimeError)> {
+        match self {
+            Self::DashboardRefreshFailed { job, error, .. }
+            | Self::AdaptersRefreshFailed { job, error, .. }
+            | Self::TrafficRefreshFailed { job, error, .. }
+            | Self::AdapterConfigFailed { job, error, .. }
+            | Self::BluetoothScanFailed { job, error, .. }
+            | Self::PingFailed { job, error, .. }
+            | Self::TraceFailed { job, error, .. }
+            | Self::PortScanFailed { job, error, .. }
+            | Self::PublicSpeedFailed { job, error, .. }
+            | Self::LinkQualityFailed { job, error, .. }
+            | Self::LanSpeedFailed { job, error, .. }
+            | Self::MtuFailed { job, error, .. }
+            | Self::DscpFailed { job, error, .. }
+            | Self::MulticastFailed { job, error, .. }
+            | Self::UpnpFailed { job, error, .. }
+            | Self::NatFailed { job, error, .. }
+            | Self::Ipv6Failed { job, error, .. }
+            | Self::DnsBenchFailed { job, error, .. }
+            | Self::DnsFilterFailed { job, error, .. }
+            | Self::MailFailed { job, error, .. }
+            | Self::VoipFailed { job, error, .. }
+            | Self::GamingFailed { job, error, .. }
+            | Self::CloudFailed { job, error, .. }
+            | Self::WifiFailed { job, error, .. }
+            | Self::AbTestFailed { job, error, .. }
+            | Self::UpdateCheckFailed { job, error, .. }
+            | Self::WebCheckFailed { job, error, .. }
+            | Self::ChartExportFailed { job, error, .. }
+            | Self::DhcpProbeFailed { job, error, .. }
+            | Self::AuditExportFailed { job, error, .. }
+            | Self::AsnLookupFailed { job, error, .. }
+            | Self::CveLookupFailed { job, error, .. } => Some((*job, error)),
+            _ => None,
+        }
+    }
+
+    /// The job kind this event belongs to, including unscoped background
+    /// pushes that carry no [`JobId`].
+    pub const fn tool(&self) -> ToolKind {
+        match self {
+            Self::DashboardUpdated(_)
+            | Self::DashboardRefreshFinished { .. }
+            | Self::DashboardRefreshFailed { .. }
+            | Self::DashboardRefreshCancelled { .. } => ToolKind::Dashboard,
+            Self::AdaptersUpdated(_)
+            | Self::AdaptersRefreshFinished { .. }
+            | Self::AdaptersRefreshFailed { .. }
+            | Self::AdaptersRefreshCancelled { .. } => ToolKind::Adapters,
+            Self::TrafficUpdated(_)
+            | Self::TrafficRefreshFinished { .. }
+            | Self::TrafficRefreshFailed { .. }
+            | Self::TrafficRefreshCancelled { .. } => ToolKind::Traffic,
+            Self::AdapterConfigStarted { .. }
+            | Self::AdapterConfigFinished { .. }
+            | Self::AdapterConfigFailed { .. } => ToolKind::AdapterEdit,
+            Self::ScanStarted { .. }
+            | Self::ScanProgress { .. }
+            | Self::ScanHostFound { .. }
+            | Self::ScanHostEnriched { .. }
+            | Self::ScanServicesFound { .. }
+            | Self::ScanFinished { .. }
+            | Self::ScanCancelled { .. } => ToolKind::Scanner,
+            Self::BluetoothDeviceSeen { .. }
+            | Self::BluetoothScanFinished { .. }
+            | Self::BluetoothScanFailed { .. } => ToolKind::Bluetooth,
+            Self::PingStarted { .. }
+            | Self::PingSample { .. }
+            | Self::PingFinished { .. }
+            | Self::PingFailed { .. } => ToolKind::Ping,
+            Self::TraceStarted { .. }
+            | Self::TraceHop { .. }
+            | Self::TraceFinished { .. }
+            | Self::TraceFailed { .. } => ToolKind::Trace,
+            Self::PortScanStarted { .. }
+            | Self::PortScanProgress { .. }
+            | Self::PortScanOpen { .. }
+            | Self::PortScanFinished { .. }
+            | Self::PortScanFailed { .. } => ToolKind::PortScan,
+            Self::PublicSpeedStarted { .. }
+            | Self::PublicSpeedSample { .. }
+            | Self::PublicSpeedLatency { .. }
+            | Self::PublicSpeedFinished { .. }
+            | Self::PublicSpeedFailed { .. } => ToolKind::PublicSpeed,
+            Self::LinkQualityStarted { .. }
+            | Self::LinkQualitySample { .. }
+            | Self::LinkQualityFinished { .. }
+            | Self::LinkQualityFailed { .. } => ToolKind::LinkQuality,
+            Self::LanSpeedStarted { .. }
+            | Self::LanSpeedStatus { .. }
+            | Self::LanSpeedSample { .. }
+            | Self::LanSpeedFinished { .. }
+            | Self::LanSpeedFailed { .. } => ToolKind::LanSpeed,
+            Self::MtuStarted { .. }
+            | Self::MtuProbe { .. }
+            | Self::MtuFinished { .. }
+            | Self::MtuFailed { .. } => ToolKind::Mtu,
+            Self::DscpStarted { .. }
+            | Self::DscpHop { .. }
+            | Self::DscpFinished { .. }
+            | Self::DscpFailed { .. } => ToolKind::Dscp,
+            Self::MulticastStarted { .. }
+            | Self::MulticastSample { .. }
+            | Self::MulticastFinished { .. }
+            | Self::MulticastFailed { .. } => ToolKind::Multicast,
+            Self::UpnpStarted { .. }
+            | Self::UpnpGateway { .. }
+            | Self::UpnpMappings { .. }
+            | Self::UpnpFinished { .. }
+            | Self::UpnpFailed { .. } => ToolKind::Upnp,
+            Self::NatStarted { .. }
+            | Self::NatProbe { .. }
+            | Self::NatFinished { .. }
+            | Self::NatFailed { .. } => ToolKind::Nat,
+            Self::Ipv6Started { .. }
+            | Self::Ipv6Result { .. }
+            | Self::Ipv6Finished { .. }
+            | Self::Ipv6Failed { .. } => ToolKind::Ipv6,
+            Self::DnsBenchStarted { .. }
+            | Self::DnsBenchScore { .. }
+            | Self::DnsBenchFinished { .. }
+            | Self::DnsBenchFailed { .. } => ToolKind::DnsBench,
+            Self::DnsFilterStarted { .. }
+            | Self::DnsFilterResult { .. }
+            | Self::DnsFilterFinished { .. }
+            | Self::DnsFilterFailed { .. } => ToolKind::DnsFilter,
+            Self::MailResult { .. } | Self::MailFinished { .. } | Self::MailFailed { .. } => {
+                ToolKind::Mail
+            }
+            Self::VoipStarted { .. }
+            | Self::VoipSample { .. }
+            | Self::VoipFinished { .. }
+            | Self::VoipFailed { .. } => ToolKind::Voip,
+            Self::GamingStarted { .. }
+            | Self::GamingScore { .. }
+            | Self::GamingFinished { .. }
+            | Self::GamingFailed { .. } => ToolKind::Gaming,
+            Self::CloudStarted { .. }
+            | Self::CloudResult { .. }
+            | Self::CloudFinished { .. }
+            | Self::CloudFailed { .. } => ToolKind::Cloud,
+            Self::WifiSample { .. } | Self::WifiFinished { .. } | Self::WifiFailed { .. } => {
+                ToolKind::Wifi
+            }
+            Self::AbTestStarted { .. }
+            | Self::AbTestLeg { .. }
+            | Self::AbTestFinished { .. }
+            | Self::AbTestFailed { .. } => ToolKind::AbTest,
+            Self::UpdateCheckFinished { .. } | Self::UpdateCheckFailed { .. } => ToolKind::Update,
+            Self::DoctorFinished { .. } => ToolKind::Doctor,
+            Self::WebCheckFinished { .. } | Self::WebCheckFailed { .. } => ToolKind::WebCheck,
+            Self::ChartExported { .. } | Self::ChartExportFailed { .. } => ToolKind::ChartExport,
+            Self::DhcpProbeFinished { .. } | Self::DhcpProbeFailed { .. } => ToolKind::Dhcp,
+            Self::AuditExported { .. } | Self::AuditExportFailed { .. } => ToolKind::AuditExport,
+            Self::AsnFound { .. }
+            | Self::AsnLookupFinished { .. }
+            | Self::AsnLookupFailed { .. } => ToolKind::Asn,
+            Self::CveHintFound { .. }
+            | Self::CveLookupFinished { .. }
+            | Self::CveLookupFailed { .. } => ToolKind::Cve,
+            Self::WatchChecked { .. } | Self::WatchFinished { .. } => ToolKind::Watch,
+        }
+    }
 }
@@ -0,0 +1,235 @@
+//! Date, time and number display formats.
+//!
+//! Timestamps are kept as local `YYYY-MM-DD HH:MM:SS` strings everywhere in
+//! the model and the persisted logs, so they sort and parse the same way
+//! whatever the user picked; the chosen [`RegionFormat`] and [`ClockFormat`]
+//! are applied only where a date, a time or a decimal number is shown or
+//! written out for people.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegionFormat {
+    /// `2026-07-12`, `1.5`.
+    #[default]
+    Iso,
+    /// `12.07.2026`, `1,5`.
+    European,
+    /// `07/12/2026`, `1.5`.
+    Us,
+}
+
+impl RegionFormat {
+    pub const ALL: [Self; 3] = [Self::Iso, Self::European, Self::Us];
+
+    pub const fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub const fn previous(self) -> Self {
+        Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub const fn decimal_mark(self) -> char {
+        match self {
+            Self::European => ',',
+            Self::Iso | Self::Us => '.',
+        }
+    }
+
+    /// Rewrite the date at the start of a `YYYY-MM-DD[ HH:MM[:SS]]` stamp;
+    /// anything else, such as the `—` placeholder, is returned unchanged.
+    pub fn date_time(self, stamp: &str) -> String {
+        let Some((year, month, day, rest)) = split_date(stamp) else {
+            return stamp.to_string();
+        };
+        match self {
+            Self::Iso => stamp.to_string(),
+            Self::European => format!("{day}.{month}.{year}{rest}"),
+            Self::Us => format!("{month}/{day}/{year}{rest}"),
+        }
+    }
+
+    /// One number with `precision` decimals and the chosen decimal mark.
+    /// Units and other text are added by the caller, so dots outside the
+    /// number are never touched.
+    pub fn decimal(self, value: f64, precision: usize) -> String {
+        let number = format!("{value:.precision$}");
+        match self.decimal_mark() {
+            '.' => number,
+            mark => number.replace('.', &mark.to_string()),
+        }
+    }
+}
+
+/// Whether times of day read `20:30:45` or `8:30:45 PM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ClockFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+    #[serde(rename = "12h")]
+    TwelveHour,
+}
+
+impl ClockFormat {
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::TwentyFourHour => Self::TwelveHour,
+            Self::TwelveHour => Self::TwentyFourHour,
+        }
+    }
+
+    /// Rewrite an `HH:MM[:SS]` time of day; anything else is returned
+    /// unchanged.
+    pub fn time(self, time: &str) -> String {
+        let bytes = time.as_bytes();
+        let hour = match (self, time.get(..2)) {
+            (Self::TwelveHour, Some(hour)) if bytes.get(2) == Some(&b':') => hour.parse::<u8>(),
+            _ => return time.to_string(),
+        };
+        match hour {
+            Ok(hour @ 0..=23) => format!(
+                "{}{} {}",
+                (hour + 11) % 12 + 1,
+                &time[2..],
+                if hour < 12 { "AM" } else { "PM" }
+            ),
+            _ => time.to_string(),
+        }
+    }
+}
+
+/// A local `YYYY-MM-DD HH:MM[:SS]` stamp with the chosen date format and
+/// clock; anything else gets only the date rewrite, which leaves it as is.
+pub fn display_stamp(stamp: &str, region: RegionFormat, clock: ClockFormat) -> String {
+    match stamp.split_once(' ') {
+        Some((date, time)) => format!("{} {}", region.date_time(date), clock.time(time)),
+        None => region.date_time(stamp),
+    }
+}
+
+/// The UTC time of a local `YYYY-MM-DD HH:MM[:SS]` stamp, in the same shape,
+/// given the local clock's offset east of UTC.
+pub fn utc_stamp(stamp: &str, utc_offset_minutes: i32) -> Option<String> {
+    let (year, month, day, rest) = split_date(stamp)?;
+    let time = rest.strip_prefix(' ')?;
+    let (hour, minute) = (time.get(..2)?, time.get(3..5)?);
+    if time.as_bytes().get(2) != Some(&b':') {
+        return None;
+    }
+    let seconds = time.get(5..).unwrap_or_default();
+    let days = days_from_civil(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+    let minutes = days * 1_440 + hour.parse::<i64>().ok()? * 60 + minute.parse::<i64>().ok()?
+        - i64::from(utc_offset_minutes);
+    let (year, month, day) = civil_from_days(minutes.div_euclid(1_440));
+    let minutes = minutes.rem_euclid(1_440);
+    Some(format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}{seconds}",
+        minutes / 60,
+        minutes % 60
+    ))
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn split_date(stamp: &str) -> Option<(&str, &str, &str, &str)> {
+    let date = stamp.get(..10)?;
+    let bytes = date.as_bytes();
+    let digits = |range: std::ops::Range<usize>| bytes[range].iter().all(u8::is_ascii_digit);
+    if !(digits(0..4) && bytes[4] == b'-' && digits(5..7) && bytes[7] == b'-' && digits(8..10)) {
+        return None;
+    }
+    Some((&date[..4], &date[5..7], &date[8..10], &stamp[10..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_and_decimals_follow_the_chosen_format() {
+        let stamp = "2026-07-12 20:30:45";
+        assert_eq!(RegionFormat::Iso.date_time(stamp), stamp);
+        assert_eq!(
+            RegionFormat::European.date_time(stamp),
+            "12.07.2026 20:30:45"
+        );
+        assert_eq!(RegionFormat::Us.date_time("2026-07-12"), "07/12/2026");
+        for untouched in ["—", "20:30:45", "2026-7-12 20:30"] {
+            assert_eq!(RegionFormat::Us.date_time(untouched), untouched);
+        }
+
+        assert_eq!(RegionFormat::European.decimal(3.25, 1), "3,2");
+        assert_eq!(RegionFormat::European.decimal(12.0, 2), "12,00");
+        assert_eq!(RegionFormat::Us.decimal(3.2, 1), "3.2");
+        assert_eq!(
+            display_stamp(
+                "2026-07-12 20:30:45",
+                RegionFormat::European,
+                ClockFormat::TwelveHour
+            ),
+            "12.07.2026 8:30:45 PM"
+        );
+        assert_eq!(
+            display_stamp("—", RegionFormat::Us, ClockFormat::TwelveHour),
+            "—"
+        );
+        assert_eq!(RegionFormat::Iso.next(), RegionFormat::European);
+        assert_eq!(RegionFormat::Iso.previous(), RegionFormat::Us);
+    }
+
+    #[test]
+    fn twelve_hour_clock_and_utc_conversion() {
+        let twelve = ClockFormat::TwelveHour;
+        assert_eq!(twelve.time("20:30:45"), "8:30:45 PM");
+        assert_eq!(twelve.time("00:05"), "12:05 AM");
+        assert_eq!(twelve.time("12:00:00"), "12:00:00 PM");
+        assert_eq!(twelve.time("—"), "—");
+        assert_eq!(ClockFormat::TwentyFourHour.time("20:30:45"), "20:30:45");
+
+        assert_eq!(
+            utc_stamp("2026-07-12 20:30:45", 120).as_deref(),
+            Some("2026-07-12 18:30:45")
+        );
+        // West of UTC the evening is already tomorrow; across a year and a
+        // leap day as well.
+        assert_eq!(
+            utc_stamp("2026-12-31 21:15", -300).as_deref(),
+            Some("2027-01-01 02:15")
+        );
+        assert_eq!(
+            utc_stamp("2028-03-01 01:00:00", 330).as_deref(),
+            Some("2028-02-29 19:30:00")
+        );
+        assert_eq!(utc_stamp("—", 0), None);
+        assert_eq!(utc_stamp("2026-07-12", 0), None);
+    }
+}
@@ -0,0 +1,235 @@
+//! Gaming latency profile: which game server region answers fastest and
+//! steadiest from here.
+//!
+//! The built-in regions are the AWS GameLift ping beacons, which echo any
+//! UDP datagram on port 7770 and sit in the same data centres as the game
+//! servers many titles run on. A game feels its median latency and the
+//! jitter around it, not the best single reply, so regions are ranked by
+//! the median and the jitter is shown beside it.
+
+use crate::{GamingEndpoint, GamingPreset, GamingProtocol, GamingRequest, GamingScore};
+
+pub const GAMELIFT_PING_PORT: u16 = 7770;
+
+/// Regions failing more than this share of probes rank after reliable ones.
+const RELIABLE_LOSS_RATE: f64 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Area {
+    Americas,
+    Europe,
+    AsiaPacific,
+}
+
+/// GameLift region code, display name and area.
+const GAMELIFT_REGIONS: [(&str, &str, Area); 16] = [
+    ("us-east-1", "US East (Virginia)", Area::Americas),
+    ("us-east-2", "US East (Ohio)", Area::Americas),
+    ("us-west-1", "US West (California)", Area::Americas),
+    ("us-west-2", "US West (Oregon)", Area::Americas),
+    ("ca-central-1", "Canada (Central)", Area::Americas),
+    ("sa-east-1", "South America (São Paulo)", Area::Americas),
+    ("eu-west-1", "Europe (Ireland)", Area::Europe),
+    ("eu-west-2", "Europe (London)", Area::Europe),
+    ("eu-central-1", "Europe (Frankfurt)", Area::Europe),
+    ("eu-north-1", "Europe (Stockholm)", Area::Europe),
+    ("ap-northeast-1", "Asia Pacific (Tokyo)", Area::AsiaPacific),
+    ("ap-northeast-2", "Asia Pacific (Seoul)", Area::AsiaPacific),
+    ("ap-east-1", "Asia Pacific (Hong Kong)", Area::AsiaPacific),
+    (
+        "ap-southeast-1",
+        "Asia Pacific (Singapore)",
+        Area::AsiaPacific,
+    ),
+    ("ap-southeast-2", "Asia Pacific (Sydney)", Area::AsiaPacific),
+    ("ap-south-1", "Asia Pacific (Mumbai)", Area::AsiaPacific),
+];
+
+impl GamingPreset {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::All => Self::Americas,
+            Self::Americas => Self::Europe,
+            Self::Europe => Self::AsiaPacific,
+            Self::AsiaPacific => Self::Custom,
+            Self::Custom => Self::All,
+        }
+    }
+
+    pub const fn previous(self) -> Self {
+        match self {
+            Self::All => Self::Custom,
+            Self::Americas => Self::All,
+            Self::Europe => Self::Americas,
+            Self::AsiaPacific => Self::Europe,
+            Self::Custom => Self::AsiaPacific,
+        }
+    }
+
+    fn includes(self, area: Area) -> bool {
+        match self {
+            Self::All => true,
+            Self::Americas => area == Area::Americas,
+            Self::Europe => area == Area::Europe,
+            Self::AsiaPacific => area == Area::AsiaPacific,
+            Self::Custom => false,
+        }
+    }
+}
+
+impl GamingRequest {
+    /// The preset's regions followed by the custom endpoints.
+    pub fn endpoints(&self) -> Result<Vec<GamingEndpoint>, String> {
+        let mut endpoints = GAMELIFT_REGIONS
+            .iter()
+            .filter(|(_, _, area)| self.preset.includes(*area))
+            .map(|(code, name, _)| GamingEndpoint {
+                region: (*name).into(),
+                host: format!("gamelift-ping.{code}.api.aws"),
+                port: GAMELIFT_PING_PORT,
+                protocol: GamingProtocol::Udp,
+            })
+            .collect::<Vec<_>>();
+        endpoints.extend(parse_gaming_endpoints(&self.custom)?);
+        Ok(endpoints)
+    }
+}
+
+/// Parses a comma separated `[name=][udp://|tcp://]host:port` list.
+pub fn parse_gaming_endpoints(text: &str) -> Result<Vec<GamingEndpoint>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, address) = match entry.split_once('=') {
+                Some((name, address)) => (Some(name.trim()), address.trim()),
+                None => (None, entry),
+            };
+            let (protocol, address) = if let Some(address) = address.strip_prefix("udp://") {
+                (GamingProtocol::Udp, address)
+            } else {
+                (
+                    GamingProtocol::Tcp,
+                    address.strip_prefix("tcp://").unwrap_or(address),
+                )
+            };
+            let (host, port) = address
+                .rsplit_once(':')
+                .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+                .filter(|(host, port)| !host.is_empty() && *port != 0)
+                .ok_or_else(|| format!("{entry} is not host:port"))?;
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            Ok(GamingEndpoint {
+                region: name.filter(|name| !name.is_empty()).unwrap_or(host).into(),
+                host: host.into(),
+                port,
+                protocol,
+            })
+        })
+        .collect()
+}
+
+impl GamingProtocol {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Udp => "UDP",
+            Self::Tcp => "TCP",
+        }
+    }
+}
+
+impl GamingScore {
+    /// Score one endpoint from its probes in send order; `None` is a probe
+    /// that got no answer in time.
+    pub fn from_samples(endpoint: GamingEndpoint, samples: &[Option<f64>]) -> Self {
+        let answered = samples.iter().flatten().copied().collect::<Vec<_>>();
+        let jitter_ms = (answered.len() > 1).then(|| {
+            answered
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .sum::<f64>()
+                / (answered.len() - 1) as f64
+        });
+        Self {
+            endpoint,
+            median_ms: crate::dns_bench::median(answered),
+            jitter_ms,
+            probes: samples.len() as u32,
+            lost: samples.iter().filter(|sample| sample.is_none()).count() as u32,
+        }
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            f64::from(self.lost) / f64::from(self.probes)
+        }
+    }
+
+    pub fn reliable(&self) -> bool {
+        self.median_ms.is_some() && self.loss_rate() <= RELIABLE_LOSS_RATE
+    }
+}
+
+/// Order best first: reliable endpoints before the rest, then by median.
+pub fn rank_gaming_scores(scores: &mut [GamingScore]) {
+    scores.sort_by(|left, right| {
+        right.reliable().cmp(&left.reliable()).then(
+            left.median_ms
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&right.median_ms.unwrap_or(f64::INFINITY)),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_and_custom_endpoints_are_ranked_by_median() {
+        let request = GamingRequest {
+            preset: GamingPreset::Europe,
+            custom: "LAN party=udp://192.0.2.5:7777, tcp://[2001:db8::1]:27015 ,".into(),
+            ..GamingRequest::default()
+        };
+        let endpoints = request.endpoints().unwrap();
+        assert_eq!(endpoints.len(), 6);
+        assert_eq!(endpoints[0].host, "gamelift-ping.eu-west-1.api.aws");
+        assert_eq!(
+            endpoints[4],
+            GamingEndpoint {
+                region: "LAN party".into(),
+                host: "192.0.2.5".into(),
+                port: 7777,
+                protocol: GamingProtocol::Udp,
+            }
+        );
+        assert_eq!(endpoints[5].host, "2001:db8::1");
+        assert_eq!(endpoints[5].protocol, GamingProtocol::Tcp);
+        assert!(parse_gaming_endpoints("server.example").is_err());
+        let custom_only = GamingRequest {
+            preset: GamingPreset::Custom,
+            ..GamingRequest::default()
+        };
+        assert!(custom_only.endpoints().unwrap().is_empty());
+        assert_eq!(GamingPreset::All.previous().next(), GamingPreset::All);
+
+        let steady = GamingScore::from_samples(
+            endpoints[0].clone(),
+            &[Some(30.0), Some(32.0), Some(30.0), Some(31.0)],
+        );
+        assert_eq!(steady.median_ms, Some(30.5));
+        assert_eq!(steady.jitter_ms, Some(5.0 / 3.0));
+        let lossy =
+            GamingScore::from_samples(endpoints[1].clone(), &[Some(12.0), None, None, Some(14.0)]);
+        let silent = GamingScore::from_samples(endpoints[2].clone(), &[None, None]);
+        assert_eq!(silent.jitter_ms, None);
+        let mut scores = vec![silent, lossy, steady];
+        rank_gaming_scores(&mut scores);
+        assert_eq!(scores[0].endpoint.region, "Europe (Ireland)");
+        assert_eq!(scores[1].loss_rate(), 0.5);
+        assert_eq!(scores[2].median_ms, None);
+    }
+}
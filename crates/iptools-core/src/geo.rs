@@ -0,0 +1,242 @@
+//! Whether a round trip time fits where a host is said to be.
+//!
+//! A reply cannot come back faster than light covers the distance twice, so
+//! a measured minimum RTT puts a hard limit on how far away the target can
+//! be. A "Frankfurt" server answering a Shanghai client in 3 ms is an
+//! anycast node or a wrong geolocation entry, whatever the database says.
+//! Light in fibre is about a third slower than in vacuum; an RTT between the
+//! two limits is possible in theory but not over any real cable.
+
+use crate::PublicIpInfo;
+
+/// Light in vacuum, in kilometres per millisecond.
+const LIGHT_KM_PER_MS: f64 = 299.792;
+/// Light in single-mode fibre (refractive index about 1.47).
+const FIBRE_KM_PER_MS: f64 = 204.0;
+const EARTH_RADIUS_KM: f64 = 6_371.0;
+
+/// A named point on the globe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// English name, Chinese name, latitude and longitude of the cities that
+/// server locations and IP geolocation usually name.
+const CITIES: &[(&str, &str, f64, f64)] = &[
+    ("Amsterdam", "阿姆斯特丹", 52.37, 4.90),
+    ("Ashburn", "阿什本", 39.04, -77.49),
+    ("Atlanta", "亚特兰大", 33.75, -84.39),
+    ("Auckland", "奥克兰", -36.85, 174.76),
+    ("Bangkok", "曼谷", 13.76, 100.50),
+    ("Beijing", "北京", 39.90, 116.41),
+    ("Berlin", "柏林", 52.52, 13.40),
+    ("Chengdu", "成都", 30.57, 104.07),
+    ("Chicago", "芝加哥", 41.88, -87.63),
+    ("Chongqing", "重庆", 29.56, 106.55),
+    ("Dallas", "达拉斯", 32.78, -96.80),
+    ("Dubai", "迪拜", 25.20, 55.27),
+    ("Frankfurt", "法兰克福", 50.11, 8.68),
+    ("Guangzhou", "广州", 23.13, 113.26),
+    ("Hangzhou", "杭州", 30.27, 120.16),
+    ("Hong Kong", "香港", 22.32, 114.17),
+    ("Istanbul", "伊斯坦布尔", 41.01, 28.98),
+    ("Jakarta", "雅加达", -6.21, 106.85),
+    ("Johannesburg", "约翰内斯堡", -26.20, 28.05),
+    ("Kuala Lumpur", "吉隆坡", 3.139, 101.687),
+    ("London", "伦敦", 51.51, -0.13),
+    ("Los Angeles", "洛杉矶", 34.05, -118.24),
+    ("Madrid", "马德里", 40.42, -3.70),
+    ("Miami", "迈阿密", 25.76, -80.19),
+    ("Milan", "米兰", 45.46, 9.19),
+    ("Moscow", "莫斯科", 55.76, 37.62),
+    ("Mumbai", "孟买", 19.08, 72.88),
+    ("Nanjing", "南京", 32.06, 118.80),
+    ("New York", "纽约", 40.71, -74.01),
+    ("Osaka", "大阪", 34.69, 135.50),
+    ("Paris", "巴黎", 48.86, 2.35),
+    ("San Jose", "圣何塞", 37.34, -121.89),
+    ("Sao Paulo", "圣保罗", -23.55, -46.63),
+    ("Seattle", "西雅图", 47.61, -122.33),
+    ("Seoul", "首尔", 37.57, 126.98),
+    ("Shanghai", "上海", 31.23, 121.47),
+    ("Shenzhen", "深圳", 22.54, 114.06),
+    ("Singapore", "新加坡", 1.35, 103.82),
+    ("Stockholm", "斯德哥尔摩", 59.33, 18.07),
+    ("Sydney", "悉尼", -33.87, 151.21),
+    ("Taipei", "台北", 25.03, 121.57),
+    ("Tokyo", "东京", 35.68, 139.69),
+    ("Toronto", "多伦多", 43.65, -79.38),
+    ("Warsaw", "华沙", 52.23, 21.01),
+    ("Wuhan", "武汉", 30.59, 114.31),
+    ("Xi'an", "西安", 34.34, 108.94),
+    ("Zurich", "苏黎世", 47.38, 8.54),
+];
+
+/// A city from the built-in list (case, spaces and punctuation ignored, a
+/// trailing 市 dropped) or a `latitude,longitude` pair.
+pub fn locate(text: &str) -> Option<Place> {
+    let text = text.trim();
+    if let Some((latitude, longitude)) = text.split_once(',')
+        && let (Ok(latitude), Ok(longitude)) = (
+            latitude.trim().parse::<f64>(),
+            longitude.trim().parse::<f64>(),
+        )
+    {
+        return ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then(
+            || Place {
+                name: format!("{latitude:.2},{longitude:.2}"),
+                latitude,
+                longitude,
+            },
+        );
+    }
+    let key = |name: &str| {
+        name.trim_end_matches('市')
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let wanted = key(text);
+    if wanted.is_empty() {
+        return None;
+    }
+    CITIES
+        .iter()
+        .find(|(english, chinese, ..)| key(english) == wanted || *chinese == wanted)
+        .map(|&(english, _, latitude, longitude)| Place {
+            name: english.into(),
+            latitude,
+            longitude,
+        })
+}
+
+/// Great-circle distance.
+pub fn distance_km(from: &Place, to: &Place) -> f64 {
+    let (lat1, lat2) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (to.longitude - from.longitude).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoVerdict {
+    /// Slower than light in fibre needs.
+    Plausible,
+    /// Faster than fibre allows, though not faster than light.
+    Unlikely,
+    /// Faster than light: the target is not where it is said to be.
+    Impossible,
+}
+
+/// A claimed location held against the fastest round trip seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoCheck {
+    pub claimed: Place,
+    pub origin: Place,
+    pub distance_km: f64,
+    pub rtt_ms: u64,
+}
+
+impl GeoCheck {
+    /// Least round trip light in vacuum needs for the distance.
+    pub fn light_floor_ms(&self) -> f64 {
+        2.0 * self.distance_km / LIGHT_KM_PER_MS
+    }
+
+    /// Least round trip over fibre laid along the great circle.
+    pub fn fibre_floor_ms(&self) -> f64 {
+        2.0 * self.distance_km / FIBRE_KM_PER_MS
+    }
+
+    /// How far away over fibre the target can be at most.
+    pub fn reach_km(&self) -> f64 {
+        self.rtt_ms as f64 * FIBRE_KM_PER_MS / 2.0
+    }
+
+    pub fn verdict(&self) -> GeoVerdict {
+        let rtt = self.rtt_ms as f64;
+        if rtt < self.light_floor_ms() {
+            GeoVerdict::Impossible
+        } else if rtt < self.fibre_floor_ms() {
+            GeoVerdict::Unlikely
+        } else {
+            GeoVerdict::Plausible
+        }
+    }
+}
+
+/// Why a claim could not be checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoProblem {
+    /// The text names neither a known city nor coordinates.
+    UnknownPlace(String),
+    /// No own location was given and the public IP lookup named none.
+    NoOrigin,
+}
+
+/// Checks `claimed` from `origin`, or from the city (then region) of the
+/// public IP when `origin` is empty.
+pub fn geo_check(
+    claimed: &str,
+    origin: &str,
+    public: Option<&PublicIpInfo>,
+    rtt_ms: u64,
+) -> Result<GeoCheck, GeoProblem> {
+    let claimed = locate(claimed).ok_or_else(|| GeoProblem::UnknownPlace(claimed.trim().into()))?;
+    let origin = if origin.trim().is_empty() {
+        public
+            .and_then(|info| locate(&info.city).or_else(|| locate(&info.region)))
+            .ok_or(GeoProblem::NoOrigin)?
+    } else {
+        locate(origin).ok_or_else(|| GeoProblem::UnknownPlace(origin.trim().into()))?
+    };
+    Ok(GeoCheck {
+        distance_km: distance_km(&origin, &claimed),
+        claimed,
+        origin,
+        rtt_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_replies_rule_out_distant_claims() {
+        assert_eq!(locate(" hong-kong ").unwrap().name, "Hong Kong");
+        assert_eq!(locate("杭州市").unwrap().name, "Hangzhou");
+        assert_eq!(locate("50.1, 8.7").unwrap().name, "50.10,8.70");
+        assert!(locate("95,0").is_none() && locate("Atlantis").is_none());
+
+        let check = geo_check("Frankfurt", "Shanghai", None, 3).unwrap();
+        assert!((8_800.0..9_000.0).contains(&check.distance_km));
+        assert_eq!(check.verdict(), GeoVerdict::Impossible);
+        assert!(check.reach_km() < 400.0);
+        let check = geo_check("Frankfurt", "Shanghai", None, 70).unwrap();
+        assert_eq!(check.verdict(), GeoVerdict::Unlikely);
+        let check = geo_check("Frankfurt", "Shanghai", None, 180).unwrap();
+        assert_eq!(check.verdict(), GeoVerdict::Plausible);
+
+        let public = PublicIpInfo {
+            ip: "203.0.113.7".into(),
+            city: String::new(),
+            region: "上海".into(),
+            country: "中国".into(),
+            isp: String::new(),
+        };
+        let check = geo_check("Shanghai", "", Some(&public), 1).unwrap();
+        assert_eq!(check.origin.name, "Shanghai");
+        assert_eq!(check.verdict(), GeoVerdict::Plausible);
+        assert_eq!(geo_check("Tokyo", "", None, 1), Err(GeoProblem::NoOrigin));
+        assert_eq!(
+            geo_check("Tokyo", "Gotham", None, 1),
+            Err(GeoProblem::UnknownPlace("Gotham".into()))
+        );
+    }
+}
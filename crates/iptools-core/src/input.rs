@@ -62,6 +62,8 @@ pub enum KeyCode {
     Delete,
     Home,
     End,
+    PageUp,
+    PageDown,
     Up,
     Down,
     Left,
@@ -105,10 +107,18 @@ pub enum MouseKind {
 pub enum Action {
     Quit,
     ToggleLanguage,
+    /// Presenter mode on or off.
+    ToggleRedaction,
     NextPage,
     PreviousPage,
     Up,
     Down,
+    /// Move the selection one screen of rows.
+    PageUp,
+    PageDown,
+    /// First or last row of the list.
+    Home,
+    End,
     Left,
     Right,
     Confirm,
@@ -117,7 +127,31 @@ pub enum Action {
     Edit,
     Toggle,
     Help,
+    /// Release notes of an available update.
+    ShowUpdate,
+    /// Diagnostic presets: the picker, or the report of the running one.
+    Presets,
+    /// Failures collected from every tool.
+    Problems,
+    /// Security audit of the scanned network.
+    Audit,
+    /// Address calculator: IP, IPv6, MAC and CIDR conversions.
+    Calculator,
+    /// Search the text of the focused pane.
+    Search,
+    /// Ping, Wi-Fi, interface and speed test events on one time axis.
+    Timeline,
+    /// Save the current tool's history as a chart.
+    Export,
+    /// Show a second page beside the current one, or close it.
+    ToggleSplit,
+    /// Move the focus to the other half of the split view.
+    SwitchPane,
+    /// Answer the open confirmation, e.g. by clicking one of its buttons.
+    Answer(bool),
     SelectPage(u8),
+    /// The tab at this position in the visible order, counting from zero.
+    SelectTab(u8),
     SelectDiagnostic(u8),
     FocusDiagnostic(crate::DiagnosticFocus),
     SelectDiagnosticField(usize, usize),
@@ -134,24 +168,53 @@ pub enum Action {
 }
 
 impl KeyEvent {
+    /// 1–9, plain or with Alt, jump to that tab. Fixed rather than rebindable;
+    /// while a field is being edited only the Alt form switches tabs.
+    pub fn tab_shortcut(self) -> Option<Action> {
+        match (self.code, self.modifiers) {
+            (KeyCode::Char(digit @ '1'..='9'), Modifiers { control: false, .. }) => {
+                Some(Action::SelectTab(digit as u8 - b'1'))
+            }
+            _ => None,
+        }
+    }
+
     /// Resolve built-in demo bindings without depending on a terminal library.
     pub fn action(self) -> Option<Action> {
         use KeyCode::*;
 
+        if let Some(action) = self.tab_shortcut() {
+            return Some(action);
+        }
         match (self.code, self.modifiers) {
-            (Char('c' | 'q'), Modifiers { control: true, .. }) => Some(Action::Quit),
+            (Char('c' | 'q'), Modifiers { control: true, .. }) | (F(10), _) => Some(Action::Quit),
             (Char('l'), Modifiers { control: true, .. }) => Some(Action::ToggleLanguage),
-            (Char('r'), Modifiers { control: true, .. }) => Some(Action::History),
+            (Char('p'), Modifiers { control: true, .. }) => Some(Action::ToggleRedaction),
+            (Char('r'), Modifiers { control: true, .. }) | (F(3), _) => Some(Action::History),
+            (Char('u'), Modifiers { control: true, .. }) => Some(Action::ShowUpdate),
+            (Char('s'), Modifiers { control: true, .. }) => Some(Action::Export),
+            (Char('t'), Modifiers { control: true, .. }) => Some(Action::ToggleSplit),
+            (Char('w'), Modifiers { control: true, .. }) => Some(Action::SwitchPane),
+            (Char('f'), Modifiers { control: true, .. }) => Some(Action::Search),
+            (F(4), _) => Some(Action::Presets),
+            (F(6), _) => Some(Action::Problems),
+            (F(7), _) => Some(Action::Audit),
+            (F(8), _) => Some(Action::Calculator),
+            (F(9), _) => Some(Action::Timeline),
             (Tab, Modifiers { shift: true, .. }) | (BackTab, _) => Some(Action::PreviousPage),
             (Tab, _) => Some(Action::NextPage),
             (Up | Char('w'), _) => Some(Action::Up),
             (Down | Char('s'), _) => Some(Action::Down),
+            (PageUp, _) => Some(Action::PageUp),
+            (PageDown, _) => Some(Action::PageDown),
+            (Home, _) => Some(Action::Home),
+            (End, _) => Some(Action::End),
             (Left | Char('a'), _) => Some(Action::Left),
             (Right | Char('d'), _) => Some(Action::Right),
             (Enter, _) => Some(Action::Confirm),
             (Esc, _) => Some(Action::Back),
-            (Char('r'), Modifiers { control: false, .. }) => Some(Action::Refresh),
-            (Char('e'), _) => Some(Action::Edit),
+            (Char('r'), Modifiers { control: false, .. }) | (F(5), _) => Some(Action::Refresh),
+            (Char('e') | F(2), _) => Some(Action::Edit),
             (Char(' '), _) => Some(Action::Toggle),
             (F(1), _) => Some(Action::Help),
             _ => None,
@@ -0,0 +1,91 @@
+//! Printer and IoT protocol probes of the scanner's port enrichment.
+//!
+//! Home and industrial networks are full of devices whose web page, if they
+//! have one, says little: printers, IP cameras, MQTT brokers and PLCs. Each
+//! of these speaks a protocol that answers a one-packet question with who it
+//! is, so an open port from [`IOT_PORTS`] gets that question and the answer
+//! tags the device type in the scanner inventory.
+
+use serde::{Deserialize, Serialize};
+
+/// Modbus/TCP, RTSP, IPP and MQTT; hosts with any open get a protocol probe.
+pub const IOT_PORTS: [u16; 4] = [502, 554, 631, 1883];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IotProtocol {
+    /// Internet Printing Protocol; the probe asks for the printer's name.
+    Ipp,
+    /// Real Time Streaming Protocol, served by IP cameras and NVRs.
+    Rtsp,
+    Mqtt,
+    /// Modbus/TCP; the probe reads the device identification.
+    Modbus,
+}
+
+impl IotProtocol {
+    pub const ALL: [Self; 4] = [Self::Modbus, Self::Rtsp, Self::Ipp, Self::Mqtt];
+
+    pub const fn port(self) -> u16 {
+        match self {
+            Self::Ipp => 631,
+            Self::Rtsp => 554,
+            Self::Mqtt => 1883,
+            Self::Modbus => 502,
+        }
+    }
+
+    pub fn from_port(port: u16) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|protocol| protocol.port() == port)
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Ipp => "IPP",
+            Self::Rtsp => "RTSP",
+            Self::Mqtt => "MQTT",
+            Self::Modbus => "Modbus",
+        }
+    }
+
+    pub const fn device_type(self) -> DeviceType {
+        match self {
+            Self::Ipp => DeviceType::Printer,
+            Self::Rtsp => DeviceType::Camera,
+            Self::Mqtt => DeviceType::MqttBroker,
+            Self::Modbus => DeviceType::Controller,
+        }
+    }
+}
+
+/// What a device is, as far as its protocols tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DeviceType {
+    Printer,
+    Camera,
+    MqttBroker,
+    /// PLC, inverter or other Modbus-speaking controller.
+    Controller,
+}
+
+/// One protocol a host answered on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IotService {
+    pub protocol: IotProtocol,
+    /// Printer name and model, RTSP `Server` header, whether the broker
+    /// takes anonymous clients, or Modbus vendor and product; empty when the
+    /// answer only confirmed the protocol.
+    pub detail: String,
+}
+
+/// The device types `services` point to, once each, in [`DeviceType`] order.
+pub fn device_types(services: &[IotService]) -> Vec<DeviceType> {
+    let mut types = services
+        .iter()
+        .map(|service| service.protocol.device_type())
+        .collect::<Vec<_>>();
+    types.sort_unstable();
+    types.dedup();
+    types
+}
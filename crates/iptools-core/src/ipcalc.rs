@@ -0,0 +1,460 @@
+//! Address calculator for subnet planning.
+//!
+//! One line of input, read by its shape: an IPv4 address gives its integer,
+//! hex and `in-addr.arpa` forms; an integer (or `0x` hex) gives the address
+//! back; an IPv6 address gives its compressed and expanded spellings, its
+//! integer and `ip6.arpa` name, and the MAC when the interface ID is an
+//! EUI-64; a MAC gives that EUI-64 and its link-local address; a CIDR gives
+//! the network, mask and host range; and a CIDR followed by an address or a
+//! second CIDR tells whether the first contains the second.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::Language;
+
+/// Longest input kept, enough for two expanded IPv6 networks.
+pub const CALCULATOR_INPUT_LIMIT: usize = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcField {
+    Address,
+    Integer,
+    Hex,
+    Compressed,
+    Expanded,
+    ReverseName,
+    Mac,
+    InterfaceId,
+    LinkLocal,
+    Network,
+    Netmask,
+    Broadcast,
+    HostRange,
+    Hosts,
+    Contains,
+}
+
+impl CalcField {
+    pub const fn label(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::Address, Language::Zh) => "地址",
+            (Self::Integer, Language::Zh) => "整数",
+            (Self::Hex, Language::Zh) => "十六进制",
+            (Self::Compressed, Language::Zh) => "压缩形式",
+            (Self::Expanded, Language::Zh) => "完整形式",
+            (Self::ReverseName, Language::Zh) => "反向解析名",
+            (Self::Mac, Language::Zh) => "MAC",
+            (Self::InterfaceId, Language::Zh) => "EUI-64 接口标识",
+            (Self::LinkLocal, Language::Zh) => "链路本地地址",
+            (Self::Network, Language::Zh) => "网络",
+            (Self::Netmask, Language::Zh) => "子网掩码",
+            (Self::Broadcast, Language::Zh) => "广播地址",
+            (Self::HostRange, Language::Zh) => "主机范围",
+            (Self::Hosts, Language::Zh) => "主机数",
+            (Self::Contains, Language::Zh) => "包含",
+            (Self::Address, Language::En) => "Address",
+            (Self::Integer, Language::En) => "Integer",
+            (Self::Hex, Language::En) => "Hex",
+            (Self::Compressed, Language::En) => "Compressed",
+            (Self::Expanded, Language::En) => "Expanded",
+            (Self::ReverseName, Language::En) => "Reverse DNS",
+            (Self::Mac, Language::En) => "MAC",
+            (Self::InterfaceId, Language::En) => "EUI-64 interface ID",
+            (Self::LinkLocal, Language::En) => "Link-local",
+            (Self::Network, Language::En) => "Network",
+            (Self::Netmask, Language::En) => "Netmask",
+            (Self::Broadcast, Language::En) => "Broadcast",
+            (Self::HostRange, Language::En) => "Host range",
+            (Self::Hosts, Language::En) => "Hosts",
+            (Self::Contains, Language::En) => "Contains",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalcRow {
+    pub field: CalcField,
+    pub value: String,
+}
+
+/// The calculator popup's input line.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CalculatorState {
+    pub input: String,
+    pub cursor: usize,
+}
+
+impl CalculatorState {
+    /// Rows for the current input; `None` when its shape is not one the
+    /// calculator reads.
+    pub fn rows(&self, language: Language) -> Option<Vec<CalcRow>> {
+        calculate(&self.input, language)
+    }
+}
+
+/// Characters any supported input is made of.
+pub fn calculator_char(character: char) -> bool {
+    character.is_ascii_hexdigit() || matches!(character, '.' | ':' | '-' | '/' | ' ' | 'x' | 'X')
+}
+
+/// Conversions for `input`; empty for blank input and `None` when it is not
+/// understood.
+pub fn calculate(input: &str, language: Language) -> Option<Vec<CalcRow>> {
+    let mut words = input.split_whitespace();
+    let rows = match (words.next(), words.next(), words.next()) {
+        (None, ..) => Vec::new(),
+        (Some(outer), Some(inner), None) => {
+            let contains = Block::parse(outer)?.contains(Block::parse(inner)?);
+            vec![row(
+                CalcField::Contains,
+                match (contains, language) {
+                    (true, Language::Zh) => "是",
+                    (false, Language::Zh) => "否",
+                    (true, Language::En) => "yes",
+                    (false, Language::En) => "no",
+                },
+            )]
+        }
+        (Some(word), None, None) => single(word)?,
+        _ => return None,
+    };
+    Some(rows)
+}
+
+fn row(field: CalcField, value: impl Into<String>) -> CalcRow {
+    CalcRow {
+        field,
+        value: value.into(),
+    }
+}
+
+fn single(word: &str) -> Option<Vec<CalcRow>> {
+    if word.contains('/') {
+        return Block::parse(word).map(Block::rows);
+    }
+    if let Ok(address) = word.parse::<Ipv4Addr>() {
+        return Some(ipv4_rows(address));
+    }
+    if let Ok(address) = word.parse::<Ipv6Addr>() {
+        return Some(ipv6_rows(address));
+    }
+    if let Some(mac) = parse_mac(word) {
+        let interface_id = eui64(mac);
+        let mut segments = [0; 8];
+        segments[0] = 0xfe80;
+        segments[4..].copy_from_slice(&interface_id);
+        return Some(vec![
+            row(CalcField::Mac, format_mac(mac)),
+            row(
+                CalcField::InterfaceId,
+                interface_id.map(|segment| format!("{segment:x}")).join(":"),
+            ),
+            row(CalcField::LinkLocal, Ipv6Addr::from(segments).to_string()),
+        ]);
+    }
+    let value = match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+        Some(hex) => u128::from_str_radix(hex, 16).ok()?,
+        None if word.bytes().all(|byte| byte.is_ascii_digit()) => word.parse().ok()?,
+        None => return None,
+    };
+    Some(match u32::try_from(value) {
+        Ok(value) => {
+            let mut rows = vec![row(CalcField::Address, Ipv4Addr::from(value).to_string())];
+            rows.extend(ipv4_rows(Ipv4Addr::from(value)).into_iter().skip(1));
+            rows
+        }
+        Err(_) => {
+            let mut rows = vec![row(CalcField::Address, Ipv6Addr::from(value).to_string())];
+            rows.extend(ipv6_rows(Ipv6Addr::from(value)).into_iter().skip(1));
+            rows
+        }
+    })
+}
+
+/// Integer first, so an integer input can swap it for the address.
+fn ipv4_rows(address: Ipv4Addr) -> Vec<CalcRow> {
+    let value = u32::from(address);
+    let [a, b, c, d] = address.octets();
+    vec![
+        row(CalcField::Integer, value.to_string()),
+        row(CalcField::Hex, format!("0x{value:08x}")),
+        row(
+            CalcField::ReverseName,
+            format!("{d}.{c}.{b}.{a}.in-addr.arpa"),
+        ),
+    ]
+}
+
+/// Integer first, as in [`ipv4_rows`].
+fn ipv6_rows(address: Ipv6Addr) -> Vec<CalcRow> {
+    let value = u128::from(address);
+    let nibbles = format!("{value:032x}");
+    let mut reverse: Vec<String> = nibbles.chars().rev().map(String::from).collect();
+    reverse.push("ip6.arpa".into());
+    let mut rows = vec![
+        row(CalcField::Integer, value.to_string()),
+        row(CalcField::Compressed, address.to_string()),
+        row(
+            CalcField::Expanded,
+            address
+                .segments()
+                .map(|segment| format!("{segment:04x}"))
+                .join(":"),
+        ),
+        row(CalcField::ReverseName, reverse.join(".")),
+    ];
+    let octets = address.octets();
+    if octets[11..13] == [0xff, 0xfe] {
+        let mac = [
+            octets[8] ^ 0x02,
+            octets[9],
+            octets[10],
+            octets[13],
+            octets[14],
+            octets[15],
+        ];
+        rows.push(row(CalcField::Mac, format_mac(mac)));
+    }
+    rows
+}
+
+/// `aa:bb:cc:dd:ee:ff`, `aa-bb-cc-dd-ee-ff` or Cisco's `aabb.ccdd.eeff`.
+fn parse_mac(text: &str) -> Option<[u8; 6]> {
+    let digits: String = if text.contains('.') {
+        let groups: Vec<&str> = text.split('.').collect();
+        if groups.len() != 3 || groups.iter().any(|group| group.len() != 4) {
+            return None;
+        }
+        groups.concat()
+    } else {
+        let groups: Vec<&str> = text.split([':', '-']).collect();
+        if groups.len() != 6 || groups.iter().any(|group| group.len() != 2) {
+            return None;
+        }
+        groups.concat()
+    };
+    if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut mac = [0; 6];
+    for (index, byte) in mac.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(mac)
+}
+
+fn format_mac(mac: [u8; 6]) -> String {
+    mac.map(|byte| format!("{byte:02x}")).join(":")
+}
+
+/// Modified EUI-64 (RFC 4291): `ff:fe` in the middle and the
+/// universal/local bit flipped.
+fn eui64(mac: [u8; 6]) -> [u16; 4] {
+    let [a, b, c, d, e, f] = mac;
+    [
+        u16::from_be_bytes([a ^ 0x02, b]),
+        u16::from_be_bytes([c, 0xff]),
+        u16::from_be_bytes([0xfe, d]),
+        u16::from_be_bytes([e, f]),
+    ]
+}
+
+/// An IPv4 or IPv6 network; a lone address is a one-address block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Block {
+    network: u128,
+    prefix: u8,
+    /// 32 or 128.
+    width: u8,
+}
+
+impl Block {
+    fn parse(text: &str) -> Option<Self> {
+        let (address, prefix) = match text.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u8>().ok()?)),
+            None => (text, None),
+        };
+        let (value, width) = match (address.parse::<Ipv4Addr>(), address.parse::<Ipv6Addr>()) {
+            (Ok(address), _) => (u128::from(u32::from(address)), 32),
+            (_, Ok(address)) => (u128::from(address), 128),
+            _ => return None,
+        };
+        let prefix = prefix.unwrap_or(width);
+        if prefix > width {
+            return None;
+        }
+        let block = Self {
+            network: 0,
+            prefix,
+            width,
+        };
+        Some(Self {
+            network: value & block.mask(),
+            ..block
+        })
+    }
+
+    fn mask(self) -> u128 {
+        u128::MAX
+            .checked_shl(u32::from(128 - self.prefix))
+            .unwrap_or(0)
+            >> (128 - self.width)
+    }
+
+    fn last(self) -> u128 {
+        self.network | (!self.mask() & (u128::MAX >> (128 - self.width)))
+    }
+
+    fn contains(self, other: Self) -> bool {
+        self.width == other.width
+            && other.prefix >= self.prefix
+            && other.network & self.mask() == self.network
+    }
+
+    fn spell(self, value: u128) -> String {
+        match u32::try_from(value) {
+            Ok(value) if self.width == 32 => Ipv4Addr::from(value).to_string(),
+            _ => Ipv6Addr::from(value).to_string(),
+        }
+    }
+
+    fn rows(self) -> Vec<CalcRow> {
+        let host_bits = self.width - self.prefix;
+        let mut rows = vec![row(
+            CalcField::Network,
+            format!("{}/{}", self.spell(self.network), self.prefix),
+        )];
+        if self.width == 32 {
+            // /31 point-to-point links and /32 hosts have no broadcast.
+            let (first, last, hosts): (u128, u128, u128) = match host_bits {
+                0 | 1 => (self.network, self.last(), 1 << host_bits),
+                _ => (self.network + 1, self.last() - 1, (1 << host_bits) - 2),
+            };
+            rows.push(row(CalcField::Netmask, self.spell(self.mask())));
+            if host_bits > 1 {
+                rows.push(row(CalcField::Broadcast, self.spell(self.last())));
+            }
+            rows.push(row(
+                CalcField::HostRange,
+                format!("{} – {}", self.spell(first), self.spell(last)),
+            ));
+            rows.push(row(CalcField::Hosts, hosts.to_string()));
+        } else {
+            rows.push(row(
+                CalcField::HostRange,
+                format!("{} – {}", self.spell(self.network), self.spell(self.last())),
+            ));
+            rows.push(row(
+                CalcField::Hosts,
+                match host_bits {
+                    0..64 => (1u128 << host_bits).to_string(),
+                    _ => format!("2^{host_bits}"),
+                },
+            ));
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(input: &str) -> Vec<(CalcField, String)> {
+        calculate(input, Language::En)
+            .unwrap_or_else(|| panic!("{input:?} not understood"))
+            .into_iter()
+            .map(|row| (row.field, row.value))
+            .collect()
+    }
+
+    fn value(input: &str, field: CalcField) -> String {
+        values(input)
+            .into_iter()
+            .find(|(found, _)| *found == field)
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("{input:?} has no {field:?}"))
+    }
+
+    #[test]
+    fn addresses_integers_and_reverse_names_convert_both_ways() {
+        assert_eq!(value("192.168.1.10", CalcField::Integer), "3232235786");
+        assert_eq!(value("192.168.1.10", CalcField::Hex), "0xc0a8010a");
+        assert_eq!(
+            value("192.168.1.10", CalcField::ReverseName),
+            "10.1.168.192.in-addr.arpa"
+        );
+        assert_eq!(value("3232235786", CalcField::Address), "192.168.1.10");
+        assert_eq!(value("0xC0A8010A", CalcField::Address), "192.168.1.10");
+        assert_eq!(
+            value("0x20010db8000000000000000000000001", CalcField::Address),
+            "2001:db8::1"
+        );
+
+        assert_eq!(
+            value("2001:0db8:0:0::0001", CalcField::Compressed),
+            "2001:db8::1"
+        );
+        assert_eq!(
+            value("2001:db8::1", CalcField::Expanded),
+            "2001:0db8:0000:0000:0000:0000:0000:0001"
+        );
+        assert_eq!(
+            value("2001:db8::1", CalcField::ReverseName),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+    }
+
+    #[test]
+    fn mac_and_eui64_round_trip() {
+        assert_eq!(
+            value("00-11-22-33-44-55", CalcField::InterfaceId),
+            "211:22ff:fe33:4455"
+        );
+        assert_eq!(
+            value("0011.2233.4455", CalcField::LinkLocal),
+            "fe80::211:22ff:fe33:4455"
+        );
+        assert_eq!(
+            value("fe80::211:22ff:fe33:4455", CalcField::Mac),
+            "00:11:22:33:44:55"
+        );
+        assert!(
+            !values("fe80::1")
+                .iter()
+                .any(|(field, _)| *field == CalcField::Mac)
+        );
+    }
+
+    #[test]
+    fn networks_report_their_range_and_containment() {
+        assert_eq!(
+            values("192.168.1.77/24"),
+            [
+                (CalcField::Network, "192.168.1.0/24".into()),
+                (CalcField::Netmask, "255.255.255.0".into()),
+                (CalcField::Broadcast, "192.168.1.255".into()),
+                (CalcField::HostRange, "192.168.1.1 – 192.168.1.254".into()),
+                (CalcField::Hosts, "254".into()),
+            ]
+        );
+        assert_eq!(value("10.0.0.0/31", CalcField::Hosts), "2");
+        assert_eq!(value("0.0.0.0/0", CalcField::Netmask), "0.0.0.0");
+        assert_eq!(value("2001:db8::/32", CalcField::Hosts), "2^96");
+        assert_eq!(value("2001:db8::/120", CalcField::Hosts), "256");
+
+        assert_eq!(value("10.0.0.0/8 10.20.30.40", CalcField::Contains), "yes");
+        assert_eq!(value("10.0.0.0/8 11.0.0.1", CalcField::Contains), "no");
+        assert_eq!(value("10.0.0.0/16 10.0.0.0/8", CalcField::Contains), "no");
+        assert_eq!(
+            value("2001:db8::/32 2001:db8:1::/48", CalcField::Contains),
+            "yes"
+        );
+        assert_eq!(value("10.0.0.0/8 ::1", CalcField::Contains), "no");
+
+        assert_eq!(calculate("  ", Language::En), Some(Vec::new()));
+        assert_eq!(calculate("10.0.0.0/33", Language::En), None);
+        assert_eq!(calculate("300.1.1.1", Language::En), None);
+        assert_eq!(calculate("a b c", Language::En), None);
+    }
+}
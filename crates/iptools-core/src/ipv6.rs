@@ -0,0 +1,77 @@
+//! IPv6 readiness checklist: address scope and the overall verdict.
+
+use std::net::Ipv6Addr;
+
+use crate::{Ipv6Check, Ipv6CheckResult, Ipv6Verdict};
+
+impl Ipv6Check {
+    pub const ALL: [Self; 5] = [
+        Self::GlobalAddress,
+        Self::DefaultRoute,
+        Self::DnsAaaa,
+        Self::Reachability,
+        Self::PathMtu,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::GlobalAddress => "global address",
+            Self::DefaultRoute => "default route",
+            Self::DnsAaaa => "DNS AAAA",
+            Self::Reachability => "reachability",
+            Self::PathMtu => "path MTU",
+        }
+    }
+}
+
+/// Whether `address` (optionally with a `%zone` or `/prefix`) is in 2000::/3,
+/// the global unicast range. Link-local, ULA and loopback addresses cannot
+/// reach the IPv6 internet on their own.
+pub fn is_global_ipv6(address: &str) -> bool {
+    address
+        .split(['%', '/'])
+        .next()
+        .and_then(|address| address.parse::<Ipv6Addr>().ok())
+        .is_some_and(|ip| ip.segments()[0] & 0xe000 == 0x2000)
+}
+
+/// Ready when the v6-only target was reached and nothing failed; checks the
+/// platform cannot run are skipped rather than held against the host.
+pub fn ipv6_ready(results: &[Ipv6CheckResult]) -> bool {
+    results
+        .iter()
+        .all(|result| result.verdict != Ipv6Verdict::Fail)
+        && results.iter().any(|result| {
+            result.check == Ipv6Check::Reachability && result.verdict == Ipv6Verdict::Pass
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_global_unicast_counts_and_reachability_decides_readiness() {
+        assert!(is_global_ipv6("2001:db8::1/64"));
+        assert!(is_global_ipv6("2606:4700::1111"));
+        assert!(!is_global_ipv6("fe80::1%eth0"));
+        assert!(!is_global_ipv6("fd00::1"));
+        assert!(!is_global_ipv6("::1"));
+        assert!(!is_global_ipv6("192.0.2.1"));
+
+        let result = |check, verdict| Ipv6CheckResult {
+            check,
+            verdict,
+            detail: String::new(),
+        };
+        let mut results = vec![
+            result(Ipv6Check::GlobalAddress, Ipv6Verdict::Pass),
+            result(Ipv6Check::Reachability, Ipv6Verdict::Pass),
+            result(Ipv6Check::PathMtu, Ipv6Verdict::Skipped),
+        ];
+        assert!(ipv6_ready(&results));
+        results[0].verdict = Ipv6Verdict::Fail;
+        assert!(!ipv6_ready(&results));
+        assert!(!ipv6_ready(&[]));
+    }
+}
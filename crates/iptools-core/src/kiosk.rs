@@ -0,0 +1,99 @@
+//! Kiosk mode: one view drawn full screen for a wall-mounted status
+//! monitor.
+//!
+//! There is no tab bar, footer or popup, and every input but Quit is
+//! ignored, so a stray key press on the kiosk keyboard changes nothing. The
+//! page the user last had open is left as it was for the next normal start.
+
+use crate::{Action, AppModel, InputEvent, Page};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KioskView {
+    /// The Dashboard under a large clock.
+    Dashboard,
+    /// Large download and upload rates over the interface table.
+    Traffic,
+    /// The Dashboard's watch tiles alone, with large latencies.
+    Monitor,
+}
+
+impl KioskView {
+    pub const ALL: [Self; 3] = [Self::Dashboard, Self::Traffic, Self::Monitor];
+
+    /// Name used by `--kiosk`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Dashboard => "dashboard",
+            Self::Traffic => "traffic",
+            Self::Monitor => "monitor",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|view| view.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The page whose module feeds the view; watch tiles live on the
+    /// Dashboard.
+    pub const fn page(self) -> Page {
+        match self {
+            Self::Dashboard | Self::Monitor => Page::Dashboard,
+            Self::Traffic => Page::Traffic,
+        }
+    }
+}
+
+impl AppModel {
+    /// Switch to kiosk mode; call before [`AppModel::bootstrap_effects`] so
+    /// the view's page is the one started.
+    pub fn enter_kiosk(&mut self, view: KioskView) {
+        self.kiosk = Some(view);
+        self.page = view.page();
+        self.popups.clear();
+    }
+
+    pub(crate) fn handle_kiosk_input(&mut self, input: InputEvent) {
+        if input.action() == Some(Action::Quit) {
+            self.running = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Effect, KeyCode, KeyEvent, Message, SessionUpdate};
+
+    #[test]
+    fn a_kiosk_ignores_everything_but_quit_and_keeps_the_last_tab() {
+        assert_eq!(KioskView::from_name(" Monitor"), Some(KioskView::Monitor));
+        assert_eq!(KioskView::from_name("scanner"), None);
+
+        let mut app = AppModel::default();
+        app.page = Page::Scanner;
+        let language = app.language;
+        app.enter_kiosk(KioskView::Traffic);
+        assert_eq!(app.page, Page::Traffic);
+        for action in [Action::NextPage, Action::Help, Action::ToggleLanguage] {
+            assert!(
+                app.update(Message::Input(InputEvent::Action(action)))
+                    .is_empty()
+            );
+        }
+        app.update(Message::Input(InputEvent::Key(KeyEvent::plain(
+            KeyCode::Char('3'),
+        ))));
+        assert_eq!(app.page, Page::Traffic);
+        assert_eq!(app.language, language);
+        assert!(app.popups.is_empty());
+        assert!(
+            !app.shutdown_effects()
+                .iter()
+                .any(|effect| matches!(effect, Effect::PersistSession(SessionUpdate::Ui(_))))
+        );
+        app.update(Message::Input(InputEvent::Action(Action::Quit)));
+        assert!(!app.running);
+    }
+}
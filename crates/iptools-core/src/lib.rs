@@ -1,15 +1,109 @@
 //! Platform-independent domain model and application state machine.
 
+mod ab_test;
+mod asn;
+mod audit;
+mod baseline;
+mod bluetooth;
+mod bufferbloat;
+mod chart;
+mod cloud;
 mod config;
+mod confirm;
+mod cve;
+mod dns_bench;
+mod dns_filter;
+mod doctor;
+mod dscp;
 mod effect;
+mod format;
+mod gaming;
+mod geo;
 mod input;
+mod iot;
+mod ipcalc;
+mod ipv6;
+mod kiosk;
+mod link_health;
 pub mod link_quality;
+mod mail;
 mod model;
+mod module;
+mod mtu;
+mod multicast;
+mod nat;
+mod netlog;
+mod overrides;
+mod pipeline;
+mod policy;
+mod popup;
+mod problem;
+mod redact;
+mod retention;
+mod scan_guard;
+mod scan_rate;
+mod search;
+mod secret;
+mod settings;
+mod split;
+mod target;
+mod timeline;
+mod trace_diff;
+mod update;
+mod voip;
+mod watch;
+mod wifi;
 
+pub use ab_test::*;
+pub use asn::*;
+pub use audit::*;
+pub use baseline::*;
+pub use bluetooth::*;
+pub use chart::*;
+pub use cloud::*;
 pub use config::*;
+pub use confirm::*;
+pub use cve::*;
+pub use dns_bench::*;
+pub use dns_filter::*;
+pub use doctor::*;
+pub use dscp::*;
 pub use effect::*;
+pub use format::*;
+pub use gaming::*;
+pub use geo::*;
 pub use input::*;
+pub use iot::*;
+pub use ipcalc::*;
+pub use ipv6::*;
+pub use kiosk::*;
+pub use link_health::*;
+pub use mail::*;
 pub use model::*;
+pub use module::*;
+pub use mtu::*;
+pub use multicast::*;
+pub use netlog::*;
+pub use overrides::*;
+pub use pipeline::*;
+pub use policy::*;
+pub use popup::*;
+pub use problem::*;
+pub use redact::*;
+pub use retention::*;
+pub use scan_guard::*;
+pub use scan_rate::*;
+pub use search::*;
+pub use secret::*;
+pub use settings::*;
+pub use split::*;
+pub use target::*;
+pub use timeline::*;
+pub use trace_diff::*;
+pub use update::*;
+pub use voip::*;
+pub use watch::*;
+pub use wifi::*;
 
 /// Version of the cross-platform application protocol.
 pub const ARCHITECTURE_VERSION: u8 = 4;
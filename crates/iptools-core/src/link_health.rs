@@ -0,0 +1,253 @@
+//! Packet loss on the interfaces themselves, from kernel link counters.
+//!
+//! Throughput alone hides a failing NIC, a bad cable or a duplex mismatch:
+//! the link still moves data, just with frames dropped, errored or collided
+//! along the way. Every traffic sample carries the counters since boot;
+//! [`LinkHealthState`] keeps a minute of them per interface, so the views
+//! can show what happened recently rather than since the machine started,
+//! and flags an interface once its loss rate crosses the alert threshold.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// Alert threshold, in percent of packets lost to drops and errors; 0 turns
+/// the alert off.
+pub const DROP_ALERT_PERCENT_RANGE: (u64, u64) = (0, 20);
+pub const DEFAULT_DROP_ALERT_PERCENT: u64 = 1;
+/// How far back the recent counts reach.
+pub const LINK_WINDOW_MS: u64 = 60_000;
+/// Packets a window needs before its loss rate can raise an alert; a few
+/// dropped frames on an idle link are noise, not a failing NIC.
+const ALERT_MIN_PACKETS: u64 = 500;
+
+/// Kernel counters of one interface, both directions summed. Platforms
+/// report different subsets; counters a platform lacks stay zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkCounters {
+    /// Packets delivered, received and sent.
+    pub packets: u64,
+    /// Packets discarded by the driver or kernel, e.g. full queues.
+    pub drops: u64,
+    /// Frames with CRC, length or other errors.
+    pub errors: u64,
+    /// Frames lost because the NIC's buffer overflowed; on Linux these are
+    /// also counted as errors.
+    pub overruns: u64,
+    /// Collisions, which only happen on half-duplex links.
+    pub collisions: u64,
+}
+
+impl LinkCounters {
+    /// Counts between `earlier` and `self`; `None` when a counter went
+    /// backwards, as after a driver reload.
+    pub fn since(&self, earlier: &Self) -> Option<Self> {
+        Some(Self {
+            packets: self.packets.checked_sub(earlier.packets)?,
+            drops: self.drops.checked_sub(earlier.drops)?,
+            errors: self.errors.checked_sub(earlier.errors)?,
+            overruns: self.overruns.checked_sub(earlier.overruns)?,
+            collisions: self.collisions.checked_sub(earlier.collisions)?,
+        })
+    }
+
+    /// Packets lost to drops and errors.
+    pub const fn lost(&self) -> u64 {
+        self.drops.saturating_add(self.errors)
+    }
+
+    /// Lost packets as a percentage of all packets seen, lost ones included.
+    pub fn loss_percent(&self) -> f64 {
+        let seen = self.packets.saturating_add(self.lost());
+        if seen == 0 {
+            0.0
+        } else {
+            self.lost() as f64 * 100.0 / seen as f64
+        }
+    }
+}
+
+/// Counts over the recent window of one interface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkWindow {
+    pub counts: LinkCounters,
+    pub seconds: u64,
+    /// The loss rate is over the alert threshold.
+    pub alerting: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct LinkHistory {
+    /// Oldest first; `(elapsed_ms, counters)`.
+    samples: VecDeque<(u64, LinkCounters)>,
+    alerting: bool,
+}
+
+/// An interface whose loss rate has just crossed the alert threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkAlert {
+    pub interface: String,
+    pub window: LinkWindow,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LinkHealthState {
+    interfaces: BTreeMap<String, LinkHistory>,
+}
+
+impl LinkHealthState {
+    /// Add a sample of every interface taken at `now_ms`. Returns the
+    /// interfaces that went over `threshold_percent` with this sample; one
+    /// stays flagged until its rate falls back under the threshold.
+    pub fn record<'a>(
+        &mut self,
+        now_ms: u64,
+        samples: impl IntoIterator<Item = (&'a str, LinkCounters)>,
+        threshold_percent: u64,
+    ) -> Vec<LinkAlert> {
+        let mut alerts = Vec::new();
+        let mut seen = Vec::new();
+        for (interface, counters) in samples {
+            seen.push(interface);
+            let history = self.interfaces.entry(interface.to_string()).or_default();
+            // A counter reset starts the window over.
+            if history
+                .samples
+                .back()
+                .is_some_and(|(_, last)| counters.since(last).is_none())
+            {
+                history.samples.clear();
+            }
+            history.samples.push_back((now_ms, counters));
+            while history.samples.len() > 2
+                && history
+                    .samples
+                    .get(1)
+                    .is_some_and(|(at, _)| now_ms.saturating_sub(*at) >= LINK_WINDOW_MS)
+            {
+                history.samples.pop_front();
+            }
+            let Some(window) = history.window() else {
+                continue;
+            };
+            let over = threshold_percent > 0
+                && window.counts.packets >= ALERT_MIN_PACKETS
+                && window.counts.loss_percent() > threshold_percent as f64;
+            if over && !history.alerting {
+                alerts.push(LinkAlert {
+                    interface: interface.to_string(),
+                    window: LinkWindow {
+                        alerting: true,
+                        ..window
+                    },
+                });
+            }
+            history.alerting = over;
+        }
+        self.interfaces
+            .retain(|interface, _| seen.contains(&interface.as_str()));
+        alerts
+    }
+
+    /// Counts over the recent window; `None` until two samples are in.
+    pub fn window(&self, interface: &str) -> Option<LinkWindow> {
+        let history = self.interfaces.get(interface)?;
+        history.window().map(|window| LinkWindow {
+            alerting: history.alerting,
+            ..window
+        })
+    }
+
+    /// The latest counters since boot.
+    pub fn totals(&self, interface: &str) -> Option<LinkCounters> {
+        self.interfaces
+            .get(interface)?
+            .samples
+            .back()
+            .map(|(_, counters)| *counters)
+    }
+}
+
+impl LinkHistory {
+    fn window(&self) -> Option<LinkWindow> {
+        let (first_at, first) = self.samples.front()?;
+        let (last_at, last) = self.samples.back()?;
+        if self.samples.len() < 2 {
+            return None;
+        }
+        Some(LinkWindow {
+            counts: last.since(first)?,
+            seconds: last_at.saturating_sub(*first_at) / 1_000,
+            alerting: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(packets: u64, drops: u64) -> LinkCounters {
+        LinkCounters {
+            packets,
+            drops,
+            ..LinkCounters::default()
+        }
+    }
+
+    #[test]
+    fn loss_over_the_window_alerts_once_and_resets_with_the_counters() {
+        let mut health = LinkHealthState::default();
+        assert!(
+            health
+                .record(0, [("eth0", counters(1_000, 10))], 1)
+                .is_empty()
+        );
+        assert_eq!(health.window("eth0"), None);
+
+        // 30 of 1 030 packets lost in the last second: 2.9 %.
+        let alerts = health.record(1_000, [("eth0", counters(2_000, 40))], 1);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].interface, "eth0");
+        assert_eq!(alerts[0].window.counts.drops, 30);
+        assert!((alerts[0].window.counts.loss_percent() - 2.91).abs() < 0.01);
+        assert!(health.window("eth0").unwrap().alerting);
+        // Still over: no second alert.
+        assert!(
+            health
+                .record(2_000, [("eth0", counters(3_000, 70))], 1)
+                .is_empty()
+        );
+
+        // Samples older than the window fall out of it.
+        health.record(70_000, [("eth0", counters(900_000, 70))], 1);
+        let window = health.window("eth0").unwrap();
+        assert_eq!(window.seconds, 68);
+        assert_eq!(window.counts.drops, 0);
+        assert!(!window.alerting);
+
+        // A driver reload zeroes the counters and starts over.
+        health.record(71_000, [("eth0", counters(5, 0))], 1);
+        assert_eq!(health.window("eth0"), None);
+        assert_eq!(health.totals("eth0"), Some(counters(5, 0)));
+
+        // Too few packets to judge, and a zero threshold never alerts.
+        let mut quiet = LinkHealthState::default();
+        quiet.record(0, [("wlan0", counters(0, 0))], 1);
+        assert!(
+            quiet
+                .record(1_000, [("wlan0", counters(100, 50))], 1)
+                .is_empty()
+        );
+        let mut off = LinkHealthState::default();
+        off.record(0, [("eth0", counters(0, 0))], 0);
+        assert!(
+            off.record(1_000, [("eth0", counters(10_000, 5_000))], 0)
+                .is_empty()
+        );
+        // Interfaces that disappear are forgotten.
+        off.record(2_000, [], 0);
+        assert_eq!(off.totals("eth0"), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Mail service checks: which SMTP, IMAP and POP3 ports a host exposes and
+//! whether each one protects logins with TLS.
+
+use crate::{MailCheck, MailProtocol};
+
+/// Port, protocol and whether TLS starts with the connection.
+pub const MAIL_PORTS: [(u16, MailProtocol, bool); 7] = [
+    (25, MailProtocol::Smtp, false),
+    (465, MailProtocol::Smtp, true),
+    (587, MailProtocol::Smtp, false),
+    (143, MailProtocol::Imap, false),
+    (993, MailProtocol::Imap, true),
+    (110, MailProtocol::Pop3, false),
+    (995, MailProtocol::Pop3, true),
+];
+
+impl MailProtocol {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Smtp => "SMTP",
+            Self::Imap => "IMAP",
+            Self::Pop3 => "POP3",
+        }
+    }
+}
+
+/// How a mail port stands, worst first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailVerdict {
+    /// The TLS handshake failed, usually on the certificate.
+    CertificateRejected,
+    /// Passwords can cross the network unencrypted.
+    CleartextLogin,
+    /// A plaintext port that cannot be upgraded.
+    NoStartTls,
+    /// Connected, but the conversation broke off.
+    Failed,
+    Secure,
+    Closed,
+}
+
+impl MailCheck {
+    /// An unanswered check for `port`.
+    pub fn new(port: u16, protocol: MailProtocol, implicit_tls: bool) -> Self {
+        Self {
+            port,
+            protocol,
+            implicit_tls,
+            banner: None,
+            starttls: None,
+            tls: None,
+            tls_error: None,
+            auth: Vec::new(),
+            cleartext_auth: false,
+            error: None,
+        }
+    }
+
+    /// The worst thing the check found; a rejected certificate outweighs
+    /// whatever the plaintext greeting offered.
+    pub fn verdict(&self) -> MailVerdict {
+        if self.tls_error.is_some() {
+            MailVerdict::CertificateRejected
+        } else if self.banner.is_none() && self.tls.is_none() {
+            MailVerdict::Closed
+        } else if self.cleartext_auth {
+            MailVerdict::CleartextLogin
+        } else if self.starttls == Some(false) {
+            MailVerdict::NoStartTls
+        } else if self.error.is_some() {
+            MailVerdict::Failed
+        } else {
+            MailVerdict::Secure
+        }
+    }
+}
+
+/// Open ports and how many of them need attention.
+pub fn mail_tally(checks: &[MailCheck]) -> (usize, usize) {
+    let open = checks
+        .iter()
+        .filter(|check| check.verdict() != MailVerdict::Closed);
+    let attention = open
+        .clone()
+        .filter(|check| check.verdict() != MailVerdict::Secure)
+        .count();
+    (open.count(), attention)
+}
+
+/// Password mechanisms that carry the secret itself rather than a hash.
+pub fn cleartext_mechanism(mechanism: &str) -> bool {
+    matches!(
+        mechanism.to_ascii_uppercase().as_str(),
+        "PLAIN" | "LOGIN" | "USER"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mail_ports_are_graded_by_what_protects_logins() {
+        let closed = MailCheck {
+            error: Some("connection refused".into()),
+            ..MailCheck::new(110, MailProtocol::Pop3, false)
+        };
+        assert_eq!(closed.verdict(), MailVerdict::Closed);
+
+        let upgraded = MailCheck {
+            banner: Some("220 mx.example ESMTP".into()),
+            starttls: Some(true),
+            tls: Some(crate::TlsSummary {
+                version: "TLSv1_3".into(),
+                cipher: "TLS13_AES_256_GCM_SHA384".into(),
+            }),
+            auth: vec!["PLAIN".into(), "LOGIN".into()],
+            ..MailCheck::new(587, MailProtocol::Smtp, false)
+        };
+        assert_eq!(upgraded.verdict(), MailVerdict::Secure);
+        let leaky = MailCheck {
+            cleartext_auth: true,
+            ..upgraded.clone()
+        };
+        assert_eq!(leaky.verdict(), MailVerdict::CleartextLogin);
+        let stuck = MailCheck {
+            starttls: Some(false),
+            tls: None,
+            auth: Vec::new(),
+            ..upgraded.clone()
+        };
+        assert_eq!(stuck.verdict(), MailVerdict::NoStartTls);
+        let rejected = MailCheck {
+            tls_error: Some("invalid peer certificate: Expired".into()),
+            ..MailCheck::new(993, MailProtocol::Imap, true)
+        };
+        assert_eq!(rejected.verdict(), MailVerdict::CertificateRejected);
+
+        assert_eq!(mail_tally(&[closed, upgraded, leaky, rejected]), (3, 2));
+        assert!(cleartext_mechanism("login") && !cleartext_mechanism("SCRAM-SHA-256"));
+    }
+}
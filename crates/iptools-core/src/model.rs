@@ -11,7 +11,22 @@ use crate::{
 pub enum Message {
     Input(InputEvent),
     Tick(u64),
-    Clock(String),
+    /// Local wall-clock time, `YYYY-MM-DD HH:MM:SS`, and how far the local
+    /// clock is ahead of UTC.
+    Clock {
+        local: String,
+        utc_offset_minutes: i32,
+    },
+    /// Power source change reported by the platform layer.
+    PowerSource {
+        on_battery: bool,
+    },
+    /// An interface appeared, disappeared or changed address, as notified by
+    /// the platform layer.
+    InterfacesChanged,
+    /// The user's locale and theme override files were read again, at
+    /// startup or after an edit.
+    OverridesLoaded(Box<crate::Overrides>),
     Runtime(RuntimeEvent),
 }
 
@@ -69,6 +84,129 @@ impl ThemeId {
     }
 }
 
+/// How semantic colours are drawn, on top of the theme. `ColorBlind` swaps
+/// green and red for a blue/orange pair that stays distinct under the common
+/// forms of colour blindness; `Monochrome` drops colour altogether. Both also
+/// spell states out with symbols where colour alone would carry them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorMode {
+    #[default]
+    Standard,
+    ColorBlind,
+    Monochrome,
+}
+
+impl ColorMode {
+    pub const ALL: [Self; 3] = [Self::Standard, Self::ColorBlind, Self::Monochrome];
+
+    pub const fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub const fn previous(self) -> Self {
+        Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// How latency and throughput histories are drawn. `Braille` plots a line
+/// at two by four dots per cell and needs a font with the braille block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChartStyle {
+    #[default]
+    Blocks,
+    Braille,
+}
+
+impl ChartStyle {
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::Blocks => Self::Braille,
+            Self::Braille => Self::Blocks,
+        }
+    }
+}
+
+/// Whether the native runner slows its tick and background polling down.
+/// `Auto` follows the power source reported by the platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum LowPowerMode {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl LowPowerMode {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::On, Self::Off];
+
+    pub const fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub const fn previous(self) -> Self {
+        Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// What the host platform can do. Android/Termux, for example, has neither raw
+/// ICMP nor a way to reconfigure adapters; tools needing them are shown as
+/// unavailable instead of failing when started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformCapabilities {
+    pub icmp: bool,
+    pub adapter_config: bool,
+    /// Bluetooth LE scanning; native builds only have it with the
+    /// `bluetooth` feature.
+    #[serde(default)]
+    pub bluetooth: bool,
+}
+
+impl Default for PlatformCapabilities {
+    fn default() -> Self {
+        Self {
+            icmp: true,
+            adapter_config: true,
+            bluetooth: true,
+        }
+    }
+}
+
+/// Colours the terminal can show. Renderers draw with RGB and named colours
+/// and reduce them to the nearest one the terminal has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+/// What the terminal can display, detected by the frontend at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerminalCaps {
+    pub colors: ColorDepth,
+    /// Arrows, block elements, bullets and check marks render. Without it
+    /// they are replaced with ASCII; box drawing is kept, as every console
+    /// code page has it.
+    pub unicode: bool,
+}
+
+impl Default for TerminalCaps {
+    fn default() -> Self {
+        Self {
+            colors: ColorDepth::TrueColor,
+            unicode: true,
+        }
+    }
+}
+
+/// Tick interval of the native runner in normal and low-power operation.
+pub const TICK_MS: u64 = 250;
+pub const LOW_POWER_TICK_MS: u64 = 1_000;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum Page {
     #[default]
@@ -77,16 +215,18 @@ pub enum Page {
     Scanner,
     Traffic,
     Diagnostics,
+    Events,
     Settings,
 }
 
 impl Page {
-    pub const ALL: [Self; 6] = [
+    pub const ALL: [Self; 7] = [
         Self::Dashboard,
         Self::Adapters,
         Self::Scanner,
         Self::Traffic,
         Self::Diagnostics,
+        Self::Events,
         Self::Settings,
     ];
 
@@ -94,13 +234,39 @@ impl Page {
         Self::ALL.get(index as usize).copied().unwrap_or_default()
     }
 
-    fn next(self) -> Self {
-        Self::from_index((self as u8 + 1) % Self::ALL.len() as u8)
+    /// Name used in the `tabs` configuration list.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Dashboard => "dashboard",
+            Self::Adapters => "adapters",
+            Self::Scanner => "scanner",
+            Self::Traffic => "traffic",
+            Self::Diagnostics => "diagnostics",
+            Self::Events => "events",
+            Self::Settings => "settings",
+        }
     }
 
-    fn previous(self) -> Self {
-        Self::from_index((self as u8 + Self::ALL.len() as u8 - 1) % Self::ALL.len() as u8)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|page| page.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Drop repeated tabs and make sure Settings stays reachable: without it a
+/// hidden tab could never be shown again.
+pub fn normalize_tabs(tabs: impl IntoIterator<Item = Page>) -> Vec<Page> {
+    let mut visible = Vec::new();
+    for page in tabs {
+        if !visible.contains(&page) {
+            visible.push(page);
+        }
+    }
+    if !visible.contains(&Page::Settings) {
+        visible.push(Page::Settings);
     }
+    visible
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -112,6 +278,20 @@ pub enum DiagnosticTool {
     PublicSpeed,
     LinkQuality,
     LanSpeed,
+    Mtu,
+    Dscp,
+    Multicast,
+    Upnp,
+    Nat,
+    Ipv6,
+    DnsBench,
+    DnsFilter,
+    Mail,
+    Voip,
+    Gaming,
+    Cloud,
+    Wifi,
+    AbTest,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -141,13 +321,27 @@ impl DiagnosticFocus {
 }
 
 impl DiagnosticTool {
-    pub const ALL: [Self; 6] = [
+    pub const ALL: [Self; 20] = [
         Self::Ping,
         Self::Trace,
         Self::PortScan,
         Self::LinkQuality,
         Self::PublicSpeed,
         Self::LanSpeed,
+        Self::Mtu,
+        Self::Dscp,
+        Self::Multicast,
+        Self::Upnp,
+        Self::Nat,
+        Self::Ipv6,
+        Self::DnsBench,
+        Self::DnsFilter,
+        Self::Mail,
+        Self::Voip,
+        Self::Gaming,
+        Self::Cloud,
+        Self::Wifi,
+        Self::AbTest,
     ];
 
     pub fn from_index(index: u8) -> Self {
@@ -171,12 +365,99 @@ pub struct AdapterInfo {
     pub dhcp_enabled: bool,
     pub is_physical: bool,
     pub link_speed_bps: Option<u64>,
+    /// What the link negotiated besides its speed; empty where the platform
+    /// does not report it.
+    pub media: LinkMedia,
     pub download_bps: u64,
     pub upload_bps: u64,
     pub total_download: u64,
     pub total_upload: u64,
 }
 
+impl AdapterInfo {
+    /// Whether the adapter is up with an IPv4 address; the status text is
+    /// free-form per platform, so only the known down markers are checked.
+    pub fn is_up(&self) -> bool {
+        let status = self.status.to_ascii_lowercase();
+        !self.ipv4.is_empty()
+            && self.ipv4 != "—"
+            && !["down", "disconnected", "standby", "internal"]
+                .iter()
+                .any(|marker| status.contains(marker))
+    }
+
+    /// Wi-Fi, going by the interface type or an associated SSID.
+    pub fn is_wireless(&self) -> bool {
+        let kind = self.kind.to_ascii_lowercase();
+        kind.contains("ieee80211") || kind.contains("wireless") || self.ssid.is_some()
+    }
+
+    /// A wired link that negotiated worse than its hardware allows: 100 Mbps
+    /// or less on a gigabit port, the usual cause of a slow LAN, or half
+    /// duplex, which collides under load.
+    pub fn link_warning(&self) -> Option<LinkWarning> {
+        if !self.is_up() || self.media.medium == Some(LinkMedium::Wireless) {
+            return None;
+        }
+        let speed = self.link_speed_bps?;
+        match self.media.max_speed_bps {
+            Some(max) if speed <= 100_000_000 && max >= 1_000_000_000 => {
+                Some(LinkWarning::Downshifted {
+                    speed_bps: speed,
+                    max_bps: max,
+                    partner_max_bps: self.media.partner_max_speed_bps,
+                })
+            }
+            _ if self.media.duplex == Some(Duplex::Half) => Some(LinkWarning::HalfDuplex),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Duplex {
+    Half,
+    Full,
+}
+
+/// The physical medium a driver reports for its port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkMedium {
+    /// Wired Ethernet whose port type is not reported.
+    Ethernet,
+    TwistedPair,
+    Fibre,
+    /// Direct-attach copper, as on SFP+ cables.
+    DirectAttach,
+    Wireless,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct LinkMedia {
+    pub duplex: Option<Duplex>,
+    pub medium: Option<LinkMedium>,
+    /// The fastest speed the hardware supports, in bit/s.
+    pub max_speed_bps: Option<u64>,
+    /// The fastest speed the other end advertised; only Linux reports it.
+    pub partner_max_speed_bps: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkWarning {
+    /// Negotiated far below the hardware's speed. When the other end offers
+    /// more too, the cable or a port is at fault rather than the switch.
+    Downshifted {
+        speed_bps: u64,
+        max_bps: u64,
+        partner_max_bps: Option<u64>,
+    },
+    HalfDuplex,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct AdaptersState {
     pub items: Vec<AdapterInfo>,
@@ -281,11 +562,16 @@ pub struct TrafficRow {
     pub total_upload: u64,
     pub session_download: u64,
     pub session_upload: u64,
+    /// Drop and error counters since boot, where the platform reports them.
+    pub link: Option<crate::LinkCounters>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct TrafficState {
     pub rows: Vec<TrafficRow>,
+    /// Changes whenever `rows` is replaced; renderers key cached cells on it.
+    #[serde(default)]
+    pub revision: u64,
     pub selected: usize,
     pub status: TaskStatus,
     pub error: Option<crate::RuntimeError>,
@@ -296,9 +582,267 @@ pub struct TrafficState {
 pub struct ScanHost {
     pub ip: String,
     pub mac: String,
+    /// Further MACs that answered ARP for `ip`: an address conflict, or a
+    /// device impersonating another.
+    #[serde(default)]
+    pub other_macs: Vec<String>,
     #[serde(default)]
     pub vendor: String,
     pub hostname: String,
+    /// Every name the resolvers returned, `hostname` first.
+    #[serde(default)]
+    pub names: Vec<HostName>,
+    /// Local `YYYY-MM-DD HH:MM` time the runtime found the host.
+    #[serde(default)]
+    pub seen_at: String,
+    /// Which of [`SHARE_PORTS`], [`WEB_PORTS`], [`crate::IOT_PORTS`] and
+    /// [`crate::AUDIT_PORTS`] accepted a connection, along with SSH; filled
+    /// in by the optional port-enrichment step, like `shares`, `web`, `iot`,
+    /// `smb1` and `banners`.
+    #[serde(default)]
+    pub open_ports: Vec<u16>,
+    #[serde(default)]
+    pub shares: Vec<NetworkShare>,
+    #[serde(default)]
+    pub web: Vec<WebService>,
+    #[serde(default)]
+    pub iot: Vec<crate::IotService>,
+    /// The SMB server accepted an SMB1 dialect.
+    #[serde(default)]
+    pub smb1: bool,
+    /// Greetings of the FTP and SSH servers.
+    #[serde(default)]
+    pub banners: Vec<crate::ServiceBanner>,
+    /// Port enrichment has finished for this host.
+    #[serde(default)]
+    pub enriched: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameSource {
+    Dns,
+    NetBios,
+    Mdns,
+}
+
+impl NameSource {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Dns => "DNS",
+            Self::NetBios => "NetBIOS",
+            Self::Mdns => "mDNS",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostName {
+    pub source: NameSource,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceProtocol {
+    Mdns,
+    Ssdp,
+}
+
+impl ServiceProtocol {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Mdns => "mDNS",
+            Self::Ssdp => "SSDP",
+        }
+    }
+}
+
+/// A service a host announced over mDNS / DNS-SD or answered an SSDP search
+/// with, e.g. `_ipp._tcp` or `MediaRenderer`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveredService {
+    pub ip: String,
+    pub protocol: ServiceProtocol,
+    pub name: String,
+}
+
+/// What the scanner remembers about a device across scans, keyed by MAC
+/// address (or IP when the MAC is unknown).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DeviceRecord {
+    pub key: String,
+    pub last_ip: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub note: String,
+    /// What protocol probes found the device to be; kept from the last scan
+    /// that probed it.
+    pub device_types: Vec<crate::DeviceType>,
+}
+
+impl ScanHost {
+    /// Inventory key: the MAC address, or the IP when no MAC was resolved.
+    pub fn device_key(&self) -> String {
+        let mac = self.mac.trim();
+        if mac.is_empty() || mac == "-" {
+            self.ip.clone()
+        } else {
+            mac.to_ascii_uppercase()
+        }
+    }
+}
+
+/// Work items of one scan phase; every item sits in exactly one bucket.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseProgress {
+    pub queued: u64,
+    pub in_flight: u64,
+    pub done: u64,
+    pub cancelled: u64,
+}
+
+impl PhaseProgress {
+    pub fn total(&self) -> u64 {
+        self.queued + self.in_flight + self.done + self.cancelled
+    }
+
+    /// Items that will not change bucket again.
+    pub fn settled(&self) -> u64 {
+        self.done + self.cancelled
+    }
+
+    /// Move one queued item in flight.
+    pub fn start(&mut self) {
+        self.queued = self.queued.saturating_sub(1);
+        self.in_flight += 1;
+    }
+
+    /// Settle one in-flight item; work whose result was discarded because the
+    /// scan stopped counts as cancelled.
+    pub fn finish(&mut self, kept: bool) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        if kept {
+            self.done += 1;
+        } else {
+            self.cancelled += 1;
+        }
+    }
+
+    fn cancel_remaining(&mut self) {
+        self.cancelled += self.queued + self.in_flight;
+        self.queued = 0;
+        self.in_flight = 0;
+    }
+}
+
+/// Scan progress by phase: the address sweep, name resolution of the hosts
+/// it found and, with enrichment on, their port probes. Later phases grow as
+/// hosts are found, so the totals only settle once the sweep is done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanProgress {
+    pub probe: PhaseProgress,
+    pub resolve: PhaseProgress,
+    pub enrich: PhaseProgress,
+    /// Addresses swept per second over the last second; 0 before the first
+    /// measurement.
+    #[serde(default)]
+    pub rate: u32,
+    /// Workers the adaptive limiter currently allows; 0 when the scan runs
+    /// at a fixed concurrency.
+    #[serde(default)]
+    pub workers: u32,
+    #[serde(default)]
+    pub method: ScanMethod,
+}
+
+/// How the scan finds hosts, chosen by the runtime from the request and the
+/// local interfaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScanMethod {
+    /// ARP on a directly attached subnet (TCP connects on Android).
+    #[default]
+    Local,
+    /// The target is not on a local subnet: ICMP echo and TCP connects,
+    /// without MAC addresses.
+    Routed,
+    /// Passive: the zone's servers allowed a transfer of its records.
+    ZoneTransfer,
+    /// Passive: one PTR lookup per address.
+    Ptr,
+}
+
+impl ScanProgress {
+    pub fn phases(&self) -> [PhaseProgress; 3] {
+        [self.probe, self.resolve, self.enrich]
+    }
+
+    /// Share of all known work that has settled, cancelled items included.
+    pub fn ratio(&self) -> f64 {
+        let (settled, total) = self
+            .phases()
+            .iter()
+            .fold((0, 0), |(settled, total), phase| {
+                (settled + phase.settled(), total + phase.total())
+            });
+        if total == 0 {
+            0.0
+        } else {
+            settled as f64 / total as f64
+        }
+    }
+
+    pub fn cancelled(&self) -> u64 {
+        self.phases().iter().map(|phase| phase.cancelled).sum()
+    }
+
+    /// Stopping a scan abandons whatever had not finished yet.
+    pub fn cancel_remaining(&mut self) {
+        self.probe.cancel_remaining();
+        self.resolve.cancel_remaining();
+        self.enrich.cancel_remaining();
+    }
+}
+
+/// SMB and NFS; hosts with either open get their shares enumerated.
+pub const SHARE_PORTS: [u16; 2] = [445, 2049];
+
+/// HTTP, HTTPS and the usual alternate admin port; hosts with any open get
+/// their page title and server header fetched.
+pub const WEB_PORTS: [u16; 3] = [80, 443, 8080];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareProtocol {
+    Smb,
+    Nfs,
+}
+
+impl ShareProtocol {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Smb => "SMB",
+            Self::Nfs => "NFS",
+        }
+    }
+}
+
+/// The landing page a host serves on one of [`WEB_PORTS`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebService {
+    pub port: u16,
+    /// `<title>` of the page, after redirects; empty when it has none.
+    pub title: String,
+    /// `Server` response header; empty when absent.
+    pub server: String,
+}
+
+/// One share a host lists to anonymous or guest clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkShare {
+    pub protocol: ShareProtocol,
+    pub name: String,
+    /// SMB share comment, or the clients an NFS export is limited to.
+    pub detail: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -318,6 +862,8 @@ pub struct DashboardInterface {
     pub ssid: Option<String>,
     pub is_physical: bool,
     pub dhcp_enabled: bool,
+    #[serde(default)]
+    pub gateway: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -381,6 +927,15 @@ impl Default for DashboardState {
     }
 }
 
+/// What the Scanner page lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ScannerView {
+    #[default]
+    Hosts,
+    /// Bluetooth LE devices advertising nearby.
+    Bluetooth,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScannerState {
     pub cidr: String,
@@ -392,11 +947,47 @@ pub struct ScannerState {
     pub history_open: bool,
     pub history_selected: usize,
     pub status: TaskStatus,
-    pub current: u64,
+    /// Addresses in the scanned range.
     pub total: u64,
+    #[serde(default)]
+    pub progress: ScanProgress,
     pub results: Vec<ScanHost>,
+    /// Hosts found by this scan in arrival order, so the retention limit can
+    /// drop the earliest ones from the IP-sorted `results`.
+    #[serde(skip)]
+    pub arrivals: std::collections::VecDeque<String>,
+    /// Hosts dropped by the retention limit during this scan.
+    #[serde(default)]
+    pub trimmed: usize,
+    /// Changes whenever `results` changes; renderers key cached cells on it.
+    #[serde(default)]
+    pub revision: u64,
     pub selected: usize,
     pub job: Option<JobId>,
+    /// Probe share and web ports on every host found.
+    #[serde(default)]
+    pub enrich: bool,
+    /// DNS suffix for passive scans; empty scans actively.
+    #[serde(default)]
+    pub dns_zone: String,
+    /// mDNS / SSDP answers from the current scan, matched to hosts by IP.
+    #[serde(default)]
+    pub services: Vec<DiscoveredService>,
+    /// Every device seen by earlier scans, with the user's notes.
+    #[serde(default)]
+    pub inventory: Vec<DeviceRecord>,
+    /// The device detail drawer is open for the selected host.
+    #[serde(default)]
+    pub detail_open: bool,
+    /// Note being typed for the selected device, with its cursor.
+    #[serde(default)]
+    pub note_input: Option<(String, usize)>,
+    #[serde(default)]
+    pub view: ScannerView,
+    #[serde(skip)]
+    pub bluetooth: crate::BluetoothState,
+    #[serde(skip)]
+    pub cve: crate::CveState,
 }
 
 impl Default for ScannerState {
@@ -410,11 +1001,89 @@ impl Default for ScannerState {
             history_open: false,
             history_selected: 0,
             status: TaskStatus::Idle,
-            current: 0,
             total: 0,
+            progress: ScanProgress::default(),
             results: Vec::new(),
+            arrivals: std::collections::VecDeque::new(),
+            trimmed: 0,
+            revision: 0,
             selected: 0,
             job: None,
+            enrich: false,
+            dns_zone: String::new(),
+            services: Vec::new(),
+            inventory: Vec::new(),
+            detail_open: false,
+            note_input: None,
+            view: ScannerView::Hosts,
+            bluetooth: crate::BluetoothState::default(),
+            cve: crate::CveState::default(),
+        }
+    }
+}
+
+/// Devices kept in the scanner inventory; the longest-unseen go first.
+const INVENTORY_LIMIT: usize = 1_024;
+
+/// Page length until a renderer reports the real one.
+const DEFAULT_PAGE_ROWS: usize = 10;
+
+impl ScannerState {
+    pub fn selected_host(&self) -> Option<&ScanHost> {
+        self.results.get(self.selected)
+    }
+
+    pub fn device_record(&self, host: &ScanHost) -> Option<&DeviceRecord> {
+        let key = host.device_key();
+        self.inventory.iter().find(|record| record.key == key)
+    }
+
+    /// Tag the device with what its protocol probes found. A scan where
+    /// nothing answered keeps the earlier tags: the device may just have been
+    /// asleep.
+    pub(crate) fn tag_device(&mut self, key: &str, types: Vec<crate::DeviceType>) {
+        if types.is_empty() {
+            return;
+        }
+        if let Some(record) = self.inventory.iter_mut().find(|record| record.key == key) {
+            record.device_types = types;
+        }
+    }
+
+    /// Record a sighting. `seen_at` stamps sort as text, so the earliest and
+    /// latest are kept whatever order the hosts arrive in.
+    pub(crate) fn remember(&mut self, host: &ScanHost) {
+        let key = host.device_key();
+        let index = match self.inventory.iter().position(|record| record.key == key) {
+            Some(index) => index,
+            None => {
+                self.inventory.push(DeviceRecord {
+                    key,
+                    ..DeviceRecord::default()
+                });
+                self.inventory.len() - 1
+            }
+        };
+        let record = &mut self.inventory[index];
+        record.last_ip = host.ip.clone();
+        if !host.seen_at.is_empty() {
+            if record.first_seen.is_empty() || host.seen_at < record.first_seen {
+                record.first_seen = host.seen_at.clone();
+            }
+            if host.seen_at > record.last_seen {
+                record.last_seen = host.seen_at.clone();
+            }
+        }
+        if self.inventory.len() > INVENTORY_LIMIT
+            && let Some(oldest) = self
+                .inventory
+                .iter()
+                .enumerate()
+                .filter(|(_, record)| record.note.is_empty())
+                .min_by(|(_, left), (_, right)| left.last_seen.cmp(&right.last_seen))
+                .map(|(index, _)| index)
+        {
+            self.inventory.remove(oldest);
         }
     }
 }
@@ -432,6 +1101,10 @@ pub struct DiagnosticCommonState {
     pub detail: String,
     pub log: Vec<String>,
     pub job: Option<JobId>,
+    /// Oldest samples and log lines dropped by the retention limits during
+    /// this run.
+    #[serde(default)]
+    pub trimmed: usize,
 }
 
 impl Default for DiagnosticCommonState {
@@ -444,6 +1117,7 @@ impl Default for DiagnosticCommonState {
             detail: String::new(),
             log: Vec::new(),
             job: None,
+            trimmed: 0,
         }
     }
 }
@@ -454,7 +1128,57 @@ pub struct PingState {
     pub common: DiagnosticCommonState,
     pub samples: Vec<crate::PingSample>,
     pub summary: Option<crate::PingSummary>,
+    /// Where the target is said to be; empty skips the RTT check.
+    pub claimed_location: String,
+    /// Own location for the check; empty uses the public IP's city.
+    pub own_location: String,
     pub config_selected: usize,
+    /// Statistics pinned for comparison; kept when the ping restarts.
+    #[serde(skip)]
+    pub baseline: Option<crate::PingBaseline>,
+}
+
+impl PingState {
+    pub fn stats(&self) -> crate::PingStats {
+        crate::PingStats::of(&self.samples, self.summary.as_ref())
+    }
+
+    /// Pin the statistics shown now, or unpin the pinned ones. Nothing is
+    /// pinned before the first probe.
+    pub fn toggle_baseline(&mut self, at: &str) {
+        let stats = self.stats();
+        self.baseline = if self.baseline.is_some() || stats.sent == 0 {
+            None
+        } else {
+            Some(crate::PingBaseline {
+                target: self.request.target.clone(),
+                at: at.to_string(),
+                stats,
+            })
+        };
+    }
+
+    /// The claimed location held against the fastest reply so far; `None`
+    /// until a location is claimed and a reply has come back.
+    pub fn geo_check(
+        &self,
+        public: Option<&crate::PublicIpInfo>,
+    ) -> Option<Result<crate::GeoCheck, crate::GeoProblem>> {
+        if self.claimed_location.trim().is_empty() {
+            return None;
+        }
+        let rtt = self
+            .summary
+            .as_ref()
+            .and_then(|summary| summary.min_ms)
+            .or_else(|| self.samples.last().and_then(|sample| sample.min_ms))?;
+        Some(crate::geo_check(
+            &self.claimed_location,
+            &self.own_location,
+            public,
+            rtt,
+        ))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -464,6 +1188,14 @@ pub struct TraceState {
     pub hops: Vec<crate::TraceHop>,
     pub max_hops_input: String,
     pub timeout_input: String,
+    pub via_input: String,
+    /// Target and interface of a second trace run beside the first; both
+    /// empty runs a single trace.
+    pub compare_target: String,
+    pub compare_via: String,
+    pub comparison: Option<crate::TraceComparison>,
+    /// Routing origins of the hops, looked up on request.
+    pub asn: crate::AsnState,
     pub config_selected: usize,
     pub selected: usize,
 }
@@ -477,12 +1209,33 @@ impl Default for TraceState {
             request,
             common: DiagnosticCommonState::default(),
             hops: Vec::new(),
+            via_input: String::new(),
+            compare_target: String::new(),
+            compare_via: String::new(),
+            comparison: None,
+            asn: crate::AsnState::default(),
             config_selected: 0,
             selected: 0,
         }
     }
 }
 
+impl TraceState {
+    /// Result rows: one per hop, or per TTL reached by either side of a
+    /// comparison.
+    pub fn rows(&self) -> usize {
+        let Some(comparison) = &self.comparison else {
+            return self.hops.len();
+        };
+        self.hops
+            .iter()
+            .chain(&comparison.hops)
+            .map(|hop| usize::from(hop.ttl))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct PortScanState {
     pub request: crate::PortScanRequest,
@@ -501,6 +1254,7 @@ pub struct PublicSpeedState {
     pub common: DiagnosticCommonState,
     pub server: Option<String>,
     pub samples: Vec<crate::SpeedSample>,
+    pub latency: Vec<crate::LatencySample>,
     pub summary: Option<crate::SpeedSummary>,
 }
 
@@ -531,14 +1285,414 @@ pub struct LanSpeedState {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct DiagnosticsState {
-    pub tool: DiagnosticTool,
+pub struct MtuState {
+    pub request: crate::MtuRequest,
+    pub common: DiagnosticCommonState,
+    pub probes: Vec<crate::MtuProbe>,
+    pub summary: Option<crate::MtuSummary>,
+    pub max_mtu_input: String,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for MtuState {
+    fn default() -> Self {
+        let request = crate::MtuRequest::default();
+        Self {
+            max_mtu_input: request.max_mtu.to_string(),
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            probes: Vec::new(),
+            summary: None,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DscpState {
+    pub request: crate::DscpRequest,
+    pub common: DiagnosticCommonState,
+    pub hops: Vec<crate::DscpHop>,
+    pub summary: Option<crate::DscpSummary>,
+    pub max_hops_input: String,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for DscpState {
+    fn default() -> Self {
+        let request = crate::DscpRequest::default();
+        Self {
+            max_hops_input: request.max_hops.to_string(),
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            hops: Vec::new(),
+            summary: None,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MulticastState {
+    pub request: crate::MulticastRequest,
+    pub common: DiagnosticCommonState,
+    pub samples: Vec<crate::MulticastSample>,
+    pub summary: Option<crate::MulticastSample>,
+    pub port_input: String,
+    pub duration_input: String,
+    pub rate_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for MulticastState {
+    fn default() -> Self {
+        let request = crate::MulticastRequest::default();
+        Self {
+            port_input: request.port.to_string(),
+            duration_input: request.duration_secs.to_string(),
+            rate_input: request.rate_pps.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            samples: Vec::new(),
+            summary: None,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpnpState {
+    pub request: crate::UpnpRequest,
+    pub common: DiagnosticCommonState,
+    pub gateway: Option<crate::UpnpGateway>,
+    pub mappings: Vec<crate::UpnpMapping>,
+    pub external_port_input: String,
+    pub internal_port_input: String,
+    pub lease_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for UpnpState {
+    fn default() -> Self {
+        let request = crate::UpnpRequest::default();
+        Self {
+            external_port_input: request.external_port.to_string(),
+            internal_port_input: request.internal_port.to_string(),
+            lease_input: request.lease_secs.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            gateway: None,
+            mappings: Vec::new(),
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NatState {
+    pub request: crate::NatRequest,
+    pub common: DiagnosticCommonState,
+    pub probes: Vec<crate::StunProbe>,
+    pub summary: Option<crate::NatSummary>,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for NatState {
+    fn default() -> Self {
+        let request = crate::NatRequest::default();
+        Self {
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            probes: Vec::new(),
+            summary: None,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ipv6State {
+    pub request: crate::Ipv6Request,
+    pub common: DiagnosticCommonState,
+    pub results: Vec<crate::Ipv6CheckResult>,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for Ipv6State {
+    fn default() -> Self {
+        let request = crate::Ipv6Request::default();
+        Self {
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            results: Vec::new(),
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsBenchState {
+    pub request: crate::DnsBenchRequest,
+    pub common: DiagnosticCommonState,
+    /// Scores as they arrive; ranked best first once the run finishes.
+    pub scores: Vec<crate::DnsResolverScore>,
+    pub resolvers: u32,
+    pub rounds_input: String,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for DnsBenchState {
+    fn default() -> Self {
+        let request = crate::DnsBenchRequest::default();
+        Self {
+            rounds_input: request.rounds.to_string(),
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            scores: Vec::new(),
+            resolvers: 0,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsFilterState {
+    pub request: crate::DnsFilterRequest,
+    pub common: DiagnosticCommonState,
+    /// Upstreams the runtime could reach, innermost first.
+    pub upstreams: Vec<crate::DnsFilterUpstream>,
+    pub domains: Vec<String>,
+    pub probes: Vec<crate::DnsFilterProbe>,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for DnsFilterState {
+    fn default() -> Self {
+        let request = crate::DnsFilterRequest::default();
+        Self {
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            upstreams: Vec::new(),
+            domains: Vec::new(),
+            probes: Vec::new(),
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MailState {
+    pub request: crate::MailRequest,
+    pub common: DiagnosticCommonState,
+    /// In [`crate::MAIL_PORTS`] order as they come in.
+    pub checks: Vec<crate::MailCheck>,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for MailState {
+    fn default() -> Self {
+        let request = crate::MailRequest::default();
+        Self {
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            checks: Vec::new(),
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoipState {
+    pub request: crate::VoipRequest,
+    pub common: DiagnosticCommonState,
+    pub endpoint: Option<String>,
+    pub samples: Vec<crate::VoipSample>,
+    pub summary: Option<crate::VoipSample>,
+    pub port_input: String,
+    pub duration_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for VoipState {
+    fn default() -> Self {
+        let request = crate::VoipRequest::default();
+        Self {
+            port_input: request.port.to_string(),
+            duration_input: request.duration_secs.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            endpoint: None,
+            samples: Vec::new(),
+            summary: None,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamingState {
+    pub request: crate::GamingRequest,
+    pub common: DiagnosticCommonState,
+    /// Scores as they arrive; ranked best first once the run finishes.
+    pub scores: Vec<crate::GamingScore>,
+    pub endpoints: u32,
+    pub rounds_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for GamingState {
+    fn default() -> Self {
+        let request = crate::GamingRequest::default();
+        Self {
+            rounds_input: request.rounds.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            scores: Vec::new(),
+            endpoints: 0,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloudState {
+    pub request: crate::CloudRequest,
+    pub common: DiagnosticCommonState,
+    pub probes: Vec<crate::CloudProbe>,
+    pub endpoints: u32,
+    pub attempts_input: String,
+    pub timeout_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for CloudState {
+    fn default() -> Self {
+        let request = crate::CloudRequest::default();
+        Self {
+            attempts_input: request.attempts.to_string(),
+            timeout_input: request.timeout_ms.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            probes: Vec::new(),
+            endpoints: 0,
+            config_selected: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbTestState {
+    pub request: crate::AbRequest,
+    pub common: DiagnosticCommonState,
+    /// Finished sides, A first.
+    pub legs: Vec<crate::AbLeg>,
+    pub pings_input: String,
+    pub config_selected: usize,
+}
+
+impl Default for AbTestState {
+    fn default() -> Self {
+        let request = crate::AbRequest::default();
+        Self {
+            pings_input: request.pings.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            legs: Vec::new(),
+            config_selected: 0,
+        }
+    }
+}
+
+impl AbTestState {
+    pub fn leg(&self, side: crate::AbSide) -> Option<&crate::AbLeg> {
+        self.legs.iter().find(|leg| leg.side == side)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WifiState {
+    pub request: crate::WifiRequest,
+    pub common: DiagnosticCommonState,
+    /// Wireless adapters to choose from, as `(name, guid)`.
+    pub adapters: Vec<(String, String)>,
+    pub selected_adapter: usize,
+    pub interval_input: String,
+    pub config_selected: usize,
+    pub samples: Vec<crate::WifiSample>,
+    /// Roams of this run, oldest first.
+    pub roams: Vec<crate::WifiRoam>,
+    /// Access points of the latest scan that heard any.
+    pub nearby: Vec<crate::NearbyNetwork>,
+}
+
+impl Default for WifiState {
+    fn default() -> Self {
+        let request = crate::WifiRequest::default();
+        Self {
+            interval_input: request.interval_secs.to_string(),
+            request,
+            common: DiagnosticCommonState::default(),
+            adapters: Vec::new(),
+            selected_adapter: 0,
+            config_selected: 0,
+            samples: Vec::new(),
+            roams: Vec::new(),
+            nearby: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsState {
+    pub tool: DiagnosticTool,
     pub ping: PingState,
     pub trace: TraceState,
     pub port_scan: PortScanState,
     pub public_speed: PublicSpeedState,
     pub link_quality: LinkQualityState,
     pub lan_speed: LanSpeedState,
+    #[serde(default)]
+    pub mtu: MtuState,
+    #[serde(default)]
+    pub dscp: DscpState,
+    #[serde(default)]
+    pub multicast: MulticastState,
+    #[serde(default)]
+    pub upnp: UpnpState,
+    #[serde(default)]
+    pub nat: NatState,
+    #[serde(default)]
+    pub ipv6: Ipv6State,
+    #[serde(default)]
+    pub dns_bench: DnsBenchState,
+    #[serde(default)]
+    pub dns_filter: DnsFilterState,
+    #[serde(default)]
+    pub mail: MailState,
+    #[serde(default)]
+    pub voip: VoipState,
+    #[serde(default)]
+    pub gaming: GamingState,
+    #[serde(default)]
+    pub cloud: CloudState,
+    #[serde(default)]
+    pub wifi: WifiState,
+    #[serde(default)]
+    pub ab_test: AbTestState,
     pub focused: bool,
     pub focus: DiagnosticFocus,
     pub cursor: usize,
@@ -557,6 +1711,20 @@ impl Default for DiagnosticsState {
             public_speed: PublicSpeedState::default(),
             link_quality: LinkQualityState::default(),
             lan_speed: LanSpeedState::default(),
+            mtu: MtuState::default(),
+            dscp: DscpState::default(),
+            multicast: MulticastState::default(),
+            upnp: UpnpState::default(),
+            nat: NatState::default(),
+            ipv6: Ipv6State::default(),
+            dns_bench: DnsBenchState::default(),
+            dns_filter: DnsFilterState::default(),
+            mail: MailState::default(),
+            voip: VoipState::default(),
+            gaming: GamingState::default(),
+            cloud: CloudState::default(),
+            wifi: WifiState::default(),
+            ab_test: AbTestState::default(),
             focused: false,
             focus: DiagnosticFocus::Menu,
             cursor: 0,
@@ -569,24 +1737,60 @@ impl Default for DiagnosticsState {
 
 impl DiagnosticsState {
     pub fn active_common(&self) -> &DiagnosticCommonState {
-        match self.tool {
+        self.common(self.tool)
+    }
+
+    pub fn common(&self, tool: DiagnosticTool) -> &DiagnosticCommonState {
+        match tool {
             DiagnosticTool::Ping => &self.ping.common,
             DiagnosticTool::Trace => &self.trace.common,
             DiagnosticTool::PortScan => &self.port_scan.common,
             DiagnosticTool::PublicSpeed => &self.public_speed.common,
             DiagnosticTool::LinkQuality => &self.link_quality.common,
             DiagnosticTool::LanSpeed => &self.lan_speed.common,
+            DiagnosticTool::Mtu => &self.mtu.common,
+            DiagnosticTool::Dscp => &self.dscp.common,
+            DiagnosticTool::Multicast => &self.multicast.common,
+            DiagnosticTool::Upnp => &self.upnp.common,
+            DiagnosticTool::Nat => &self.nat.common,
+            DiagnosticTool::Ipv6 => &self.ipv6.common,
+            DiagnosticTool::DnsBench => &self.dns_bench.common,
+            DiagnosticTool::DnsFilter => &self.dns_filter.common,
+            DiagnosticTool::Mail => &self.mail.common,
+            DiagnosticTool::Voip => &self.voip.common,
+            DiagnosticTool::Gaming => &self.gaming.common,
+            DiagnosticTool::Cloud => &self.cloud.common,
+            DiagnosticTool::Wifi => &self.wifi.common,
+            DiagnosticTool::AbTest => &self.ab_test.common,
         }
     }
 
     pub fn active_common_mut(&mut self) -> &mut DiagnosticCommonState {
-        match self.tool {
+        self.common_mut(self.tool)
+    }
+
+    pub fn common_mut(&mut self, tool: DiagnosticTool) -> &mut DiagnosticCommonState {
+        match tool {
             DiagnosticTool::Ping => &mut self.ping.common,
             DiagnosticTool::Trace => &mut self.trace.common,
             DiagnosticTool::PortScan => &mut self.port_scan.common,
             DiagnosticTool::PublicSpeed => &mut self.public_speed.common,
             DiagnosticTool::LinkQuality => &mut self.link_quality.common,
             DiagnosticTool::LanSpeed => &mut self.lan_speed.common,
+            DiagnosticTool::Mtu => &mut self.mtu.common,
+            DiagnosticTool::Dscp => &mut self.dscp.common,
+            DiagnosticTool::Multicast => &mut self.multicast.common,
+            DiagnosticTool::Upnp => &mut self.upnp.common,
+            DiagnosticTool::Nat => &mut self.nat.common,
+            DiagnosticTool::Ipv6 => &mut self.ipv6.common,
+            DiagnosticTool::DnsBench => &mut self.dns_bench.common,
+            DiagnosticTool::DnsFilter => &mut self.dns_filter.common,
+            DiagnosticTool::Mail => &mut self.mail.common,
+            DiagnosticTool::Voip => &mut self.voip.common,
+            DiagnosticTool::Gaming => &mut self.gaming.common,
+            DiagnosticTool::Cloud => &mut self.cloud.common,
+            DiagnosticTool::Wifi => &mut self.wifi.common,
+            DiagnosticTool::AbTest => &mut self.ab_test.common,
         }
     }
 
@@ -598,6 +1802,20 @@ impl DiagnosticsState {
             DiagnosticTool::PublicSpeed => "automatic endpoint",
             DiagnosticTool::LinkQuality => &self.link_quality.request.target,
             DiagnosticTool::LanSpeed => &self.lan_speed.request.peer,
+            DiagnosticTool::Mtu => &self.mtu.request.target,
+            DiagnosticTool::Dscp => &self.dscp.request.target,
+            DiagnosticTool::Multicast => &self.multicast.request.group,
+            DiagnosticTool::Upnp => "UPnP IGD",
+            DiagnosticTool::Nat => &self.nat.request.server,
+            DiagnosticTool::Ipv6 => &self.ipv6.request.target,
+            DiagnosticTool::DnsBench => &self.dns_bench.request.custom,
+            DiagnosticTool::DnsFilter => &self.dns_filter.request.domain,
+            DiagnosticTool::Mail => &self.mail.request.host,
+            DiagnosticTool::Voip => &self.voip.request.peer,
+            DiagnosticTool::Gaming => &self.gaming.request.custom,
+            DiagnosticTool::Cloud => "AWS / GCP / Azure",
+            DiagnosticTool::Wifi => &self.wifi.request.adapter,
+            DiagnosticTool::AbTest => &self.ab_test.request.target,
         }
     }
 }
@@ -610,24 +1828,171 @@ pub struct AppModel {
     pub page: Page,
     pub language: Language,
     pub theme: ThemeId,
-    pub show_help: bool,
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    #[serde(default)]
+    pub chart_style: ChartStyle,
+    #[serde(default)]
+    pub region_format: crate::RegionFormat,
+    #[serde(default)]
+    pub clock_format: crate::ClockFormat,
+    /// Show the UTC time next to local timestamps on the Dashboard and in
+    /// the event log.
+    #[serde(default)]
+    pub show_utc: bool,
+    /// From the latest clock reading; `None` until the platform sent one.
+    #[serde(skip)]
+    pub utc_offset_minutes: Option<i32>,
+    /// The environment asked for no colour (`NO_COLOR`); overrides
+    /// `color_mode` without being saved as it.
+    #[serde(skip)]
+    pub no_color: bool,
     pub dashboard: DashboardState,
+    /// Watch targets shown as tiles on the Dashboard and their latest
+    /// results.
+    #[serde(skip)]
+    pub watch: crate::WatchState,
     pub adapters: AdaptersState,
     pub scanner: ScannerState,
     pub traffic: TrafficState,
+    /// Recent drop and error counts per interface, fed by traffic samples.
+    #[serde(skip)]
+    pub link_health: crate::LinkHealthState,
     pub diagnostics: DiagnosticsState,
+    #[serde(default)]
+    pub events: crate::EventLogState,
     pub scan_concurrency: usize,
     #[serde(default)]
-    pub settings_selected: usize,
+    pub scan_adaptive: bool,
     #[serde(default)]
-    pub settings_just_reset: bool,
+    pub retention: crate::RetentionLimits,
+    /// Loss rate, in percent, at which an interface is listed as a problem;
+    /// 0 turns the alert off.
+    #[serde(default = "default_drop_alert_percent")]
+    pub drop_alert_percent: u64,
+    #[serde(default)]
+    pub settings: crate::SettingsState,
     #[serde(default)]
     pub keybindings: crate::PersistedKeymap,
     #[serde(default)]
+    pub capabilities: PlatformCapabilities,
+    /// Features the config turns off; see [`crate::permitted`].
+    #[serde(default)]
+    pub policy: crate::CapabilityPolicy,
+    /// Scan targets outside the LAN confirmed this session.
+    #[serde(skip)]
+    pub scan_guard: crate::ScanGuard,
+    #[serde(skip)]
+    pub terminal: TerminalCaps,
+    /// Rows the current list shows at once, reported by the renderer after
+    /// each frame; PageUp and PageDown move the selection this far.
+    #[serde(skip)]
+    pub page_rows: usize,
+    #[serde(default)]
+    pub low_power: LowPowerMode,
+    /// Offline mode: the public IP and its geolocation are never looked up.
+    #[serde(default)]
+    pub offline: bool,
+    /// SSH jump host the port scan and the web check run through; empty
+    /// runs them from this machine.
+    #[serde(default)]
+    pub jump_host: String,
+    /// Look up the latest release at startup.
+    #[serde(default)]
+    pub check_updates: bool,
+    #[serde(skip)]
+    pub update: crate::UpdateState,
+    /// Self-diagnostics report, run from the settings page.
+    #[serde(skip)]
+    pub doctor: crate::DoctorState,
+    /// Diagnostic preset running ping, trace, port scan and a web check in
+    /// turn.
+    #[serde(skip)]
+    pub pipeline: crate::PipelineState,
+    /// Failures of current jobs, newest first.
+    #[serde(skip)]
+    pub problems: crate::ProblemsState,
+    /// Chart export of the current tool's history.
+    #[serde(skip)]
+    pub export: crate::ExportState,
+    /// Security audit of the scanned network.
+    #[serde(skip)]
+    pub audit: crate::AuditState,
+    /// Address calculator input.
+    #[serde(skip)]
+    pub calculator: crate::CalculatorState,
+    /// `Ctrl+F` search over the focused pane.
+    #[serde(skip)]
+    pub search: crate::SearchState,
+    /// Ping, Wi-Fi, interface and speed test events of this session on one
+    /// time axis.
+    #[serde(skip)]
+    pub timeline: crate::TimelineState,
+    /// Popups above the current tab, bottom first; the last one receives
+    /// input.
+    #[serde(skip)]
+    pub popups: Vec<crate::Popup>,
+    /// Presenter mode: renderers draw [`AppModel::redacted`]. Never persisted,
+    /// so a restart always shows real values again.
+    #[serde(skip)]
+    pub redact: bool,
+    /// Kiosk mode: one view full screen, deaf to everything but Quit.
+    #[serde(skip)]
+    pub kiosk: Option<crate::KioskView>,
+    /// Second page shown beside [`AppModel::page`], which keeps the focus.
+    #[serde(skip)]
+    pub split: Option<crate::SplitView>,
+    /// Last power source reported by the runtime; only consulted in `Auto`.
+    #[serde(default)]
+    pub on_battery: bool,
+    /// Problems found while loading the configuration, shown in a popup at
+    /// startup until dismissed.
+    #[serde(skip)]
+    pub config_issues: Vec<crate::ConfigIssue>,
+    /// Text and colours from the user's override files, drawn on top of the
+    /// built-in translations and the selected theme.
+    #[serde(skip)]
+    pub overrides: crate::Overrides,
+    #[serde(default)]
     public_ip_config: crate::PublicIpConfig,
+    /// Visible tabs in display and Tab-cycling order; never empty and always
+    /// ends up containing Settings.
+    #[serde(default = "all_tabs")]
+    tabs: Vec<Page>,
+    /// Pages whose module has been initialised; the rest wait for a first
+    /// visit.
+    #[serde(skip)]
+    started: Vec<Page>,
     adapter_edit_persist: crate::AdapterEditPersist,
     adapter_history: Vec<String>,
     generation: u64,
+    /// Source for table revisions. Never reset, so a revision is never reused
+    /// for different data while a renderer holds a cache.
+    #[serde(default)]
+    pub(crate) revision_clock: u64,
+    /// Set by every message that can change what is rendered; see
+    /// [`AppModel::take_redraw`].
+    #[serde(skip, default = "redraw_default")]
+    redraw: bool,
+}
+
+const fn redraw_default() -> bool {
+    true
+}
+
+const fn default_drop_alert_percent() -> u64 {
+    crate::DEFAULT_DROP_ALERT_PERCENT
+}
+
+fn all_tabs() -> Vec<Page> {
+    Page::ALL.to_vec()
+}
+
+/// Whether a tab shortcut leaves an open editor: digits are typed into the
+/// field, Alt+digit still switches tabs.
+fn leaves_editor(input: &InputEvent) -> bool {
+    matches!(input.action(), Some(Action::SelectTab(_)))
+        && input.key().is_none_or(|key| key.modifiers.alt)
 }
 
 impl Default for AppModel {
@@ -639,20 +2004,60 @@ impl Default for AppModel {
             page: Page::Dashboard,
             language: Language::En,
             theme: ThemeId::Classic,
-            show_help: false,
+            color_mode: ColorMode::Standard,
+            chart_style: ChartStyle::Blocks,
+            region_format: crate::RegionFormat::Iso,
+            clock_format: crate::ClockFormat::TwentyFourHour,
+            show_utc: false,
+            utc_offset_minutes: None,
+            no_color: false,
             dashboard: DashboardState::default(),
+            watch: crate::WatchState::default(),
             adapters: AdaptersState::default(),
             scanner: ScannerState::default(),
             traffic: TrafficState::default(),
+            link_health: crate::LinkHealthState::default(),
             diagnostics: DiagnosticsState::default(),
+            events: crate::EventLogState::default(),
             scan_concurrency: 50,
-            settings_selected: 0,
-            settings_just_reset: false,
+            scan_adaptive: true,
+            retention: crate::RetentionLimits::default(),
+            drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+            settings: crate::SettingsState::default(),
             keybindings: crate::PersistedKeymap::new(),
+            capabilities: PlatformCapabilities::default(),
+            policy: crate::CapabilityPolicy::default(),
+            scan_guard: crate::ScanGuard::default(),
+            terminal: TerminalCaps::default(),
+            page_rows: DEFAULT_PAGE_ROWS,
+            low_power: LowPowerMode::Auto,
+            offline: false,
+            jump_host: String::new(),
+            check_updates: false,
+            update: crate::UpdateState::default(),
+            doctor: crate::DoctorState::default(),
+            pipeline: crate::PipelineState::default(),
+            problems: crate::ProblemsState::default(),
+            export: crate::ExportState::default(),
+            audit: crate::AuditState::default(),
+            calculator: crate::CalculatorState::default(),
+            search: crate::SearchState::default(),
+            timeline: crate::TimelineState::default(),
+            popups: Vec::new(),
+            redact: false,
+            kiosk: None,
+            split: None,
+            on_battery: false,
+            config_issues: Vec::new(),
+            overrides: crate::Overrides::default(),
             public_ip_config: crate::PublicIpConfig::default(),
+            tabs: all_tabs(),
+            started: Vec::new(),
             adapter_edit_persist: crate::AdapterEditPersist::default(),
             adapter_history: Vec::new(),
             generation: 0,
+            revision_clock: 0,
+            redraw: true,
         }
     }
 }
@@ -661,9 +2066,24 @@ impl AppModel {
     pub fn apply_config(&mut self, config: &crate::ConfigData) {
         self.language = config.language;
         self.theme = config.theme;
+        self.color_mode = config.color_mode;
+        self.chart_style = config.chart_style;
+        self.region_format = config.region_format;
+        self.clock_format = config.clock_format;
+        self.show_utc = config.show_utc;
         self.scan_concurrency = config.scan_concurrency.clamp(10, 500);
+        self.scan_adaptive = config.scan_adaptive;
+        self.retention = config.retention;
+        self.drop_alert_percent = config.drop_alert_percent;
         self.keybindings = config.keybindings.clone();
+        self.low_power = config.low_power;
+        self.offline = config.offline;
+        self.jump_host = config.jump_host.clone();
+        self.check_updates = config.check_updates;
+        self.policy = crate::CapabilityPolicy::from_config(&config.policy);
         self.public_ip_config = config.public_ip.clone();
+        self.watch.set_targets(config.watch.clone());
+        self.tabs = normalize_tabs(config.tabs.iter().filter_map(|name| Page::from_name(name)));
         self.adapter_edit_persist = config.session.adapter_edit.clone();
         self.adapter_history = config.session.history.adapter.clone();
         self.scanner.cidr = if config.session.scanner.cidr.trim().is_empty() {
@@ -672,6 +2092,10 @@ impl AppModel {
             config.session.scanner.cidr.clone()
         };
         self.scanner.cursor = self.scanner.cidr.len();
+        self.scanner.enrich = config.session.scanner.enrich;
+        self.scanner.dns_zone = config.session.scanner.dns_zone.clone();
+        self.scanner.inventory = config.session.inventory.devices.clone();
+        self.events.entries = config.session.events.entries.clone();
         self.scanner.auto_cidr = true;
         self.scanner.history = config.session.history.cidrs.clone();
         self.diagnostics.ping.request = crate::PingRequest {
@@ -679,11 +2103,83 @@ impl AppModel {
             interval_ms: config.session.ping.interval_ms.clamp(100, 10_000),
             timeout_ms: config.session.ping.timeout_ms.clamp(100, 10_000),
             packet_size: config.session.ping.packet_size.min(65_500),
+            mode: config.session.ping.mode,
+            dont_fragment: config.session.ping.dont_fragment,
+            dscp: config.session.ping.dscp.min(63),
         };
+        self.diagnostics.ping.claimed_location = config.session.ping.claimed_location.clone();
+        self.diagnostics.ping.own_location = config.session.ping.own_location.clone();
         self.diagnostics.trace.request.target = config.session.trace.target.clone();
         self.diagnostics.trace.max_hops_input = config.session.trace.max_hops.clone();
         self.diagnostics.trace.timeout_input = config.session.trace.timeout_ms.clone();
+        self.diagnostics.trace.via_input = config.session.trace.via.clone();
+        self.diagnostics.trace.compare_target = config.session.trace.compare_target.clone();
+        self.diagnostics.trace.compare_via = config.session.trace.compare_via.clone();
         self.sync_trace_request();
+        self.diagnostics.mtu.request.target = config.session.mtu.target.clone();
+        self.diagnostics.mtu.max_mtu_input = config.session.mtu.max_mtu.clone();
+        self.diagnostics.mtu.timeout_input = config.session.mtu.timeout_ms.clone();
+        self.sync_mtu_request();
+        self.diagnostics.dscp.request.target = config.session.dscp.target.clone();
+        self.diagnostics.dscp.request.dscp = config.session.dscp.dscp.min(63);
+        self.diagnostics.dscp.max_hops_input = config.session.dscp.max_hops.clone();
+        self.diagnostics.dscp.timeout_input = config.session.dscp.timeout_ms.clone();
+        self.sync_dscp_request();
+        let multicast = &config.session.multicast;
+        self.diagnostics.multicast.request.mode = multicast.mode;
+        self.diagnostics.multicast.request.group = multicast.group.clone();
+        self.diagnostics.multicast.port_input = multicast.port.clone();
+        self.diagnostics.multicast.duration_input = multicast.duration_secs.clone();
+        self.diagnostics.multicast.rate_input = multicast.rate_pps.clone();
+        self.sync_multicast_request();
+        let upnp = &config.session.upnp;
+        self.diagnostics.upnp.request.action = upnp.action;
+        self.diagnostics.upnp.request.protocol = upnp.protocol;
+        self.diagnostics.upnp.external_port_input = upnp.external_port.clone();
+        self.diagnostics.upnp.internal_port_input = upnp.internal_port.clone();
+        self.diagnostics.upnp.lease_input = upnp.lease_secs.clone();
+        self.sync_upnp_request();
+        self.diagnostics.nat.request.server = config.session.nat.server.clone();
+        self.diagnostics.nat.request.secondary = config.session.nat.secondary.clone();
+        self.diagnostics.nat.timeout_input = config.session.nat.timeout_ms.clone();
+        self.sync_nat_request();
+        self.diagnostics.ipv6.request.target = config.session.ipv6.target.clone();
+        self.diagnostics.ipv6.timeout_input = config.session.ipv6.timeout_ms.clone();
+        self.sync_ipv6_request();
+        self.diagnostics.dns_bench.request.custom = config.session.dns_bench.custom.clone();
+        self.diagnostics.dns_bench.request.encrypted = config.session.dns_bench.encrypted;
+        self.diagnostics.dns_bench.rounds_input = config.session.dns_bench.rounds.clone();
+        self.diagnostics.dns_bench.timeout_input = config.session.dns_bench.timeout_ms.clone();
+        self.sync_dns_bench_request();
+        self.diagnostics.dns_filter.request.domain = config.session.dns_filter.domain.clone();
+        self.diagnostics.dns_filter.timeout_input = config.session.dns_filter.timeout_ms.clone();
+        self.sync_dns_filter_request();
+        self.diagnostics.mail.request.host = config.session.mail.host.clone();
+        self.diagnostics.mail.timeout_input = config.session.mail.timeout_ms.clone();
+        self.sync_mail_request();
+        let voip = &config.session.voip;
+        self.diagnostics.voip.request.mode = voip.mode;
+        self.diagnostics.voip.request.peer = voip.peer.clone();
+        self.diagnostics.voip.request.codec = voip.codec;
+        self.diagnostics.voip.port_input = voip.port.clone();
+        self.diagnostics.voip.duration_input = voip.duration_secs.clone();
+        self.sync_voip_request();
+        self.diagnostics.gaming.request.preset = config.session.gaming.preset;
+        self.diagnostics.gaming.request.custom = config.session.gaming.custom.clone();
+        self.diagnostics.gaming.rounds_input = config.session.gaming.rounds.clone();
+        self.sync_gaming_request();
+        self.diagnostics.cloud.attempts_input = config.session.cloud.attempts.clone();
+        self.diagnostics.cloud.timeout_input = config.session.cloud.timeout_ms.clone();
+        self.sync_cloud_request();
+        self.diagnostics.wifi.request.adapter = config.session.wifi.adapter.clone();
+        self.diagnostics.wifi.interval_input = config.session.wifi.interval_secs.clone();
+        self.sync_wifi_request();
+        let ab_test = &config.session.ab_test;
+        self.diagnostics.ab_test.request.first = ab_test.first.clone();
+        self.diagnostics.ab_test.request.second = ab_test.second.clone();
+        self.diagnostics.ab_test.request.target = ab_test.target.clone();
+        self.diagnostics.ab_test.pings_input = ab_test.pings.clone();
+        self.sync_ab_test_request();
         self.diagnostics.port_scan.persist = config.session.port_scan.clone();
         self.sync_port_scan_request();
         self.diagnostics.lan_speed.persist = config.session.lan_speed.clone();
@@ -701,50 +2197,248 @@ impl AppModel {
         self.sync_link_quality_request();
         self.diagnostics.target_history = config.session.history.targets.clone();
         self.page = Page::from_index(config.session.ui.last_tab);
+        if !self.tabs.contains(&self.page) {
+            self.page = self.tabs[0];
+        }
         self.diagnostics.tool = DiagnosticTool::from_index(config.session.ui.last_diag_tool);
     }
 
+    /// Colour mode renderers should draw with.
+    pub const fn effective_color_mode(&self) -> ColorMode {
+        if self.no_color {
+            ColorMode::Monochrome
+        } else {
+            self.color_mode
+        }
+    }
+
     pub const fn preferences(&self) -> crate::Preferences {
         crate::Preferences {
             language: self.language,
             theme: self.theme,
+            color_mode: self.color_mode,
+            chart_style: self.chart_style,
+            region_format: self.region_format,
+            clock_format: self.clock_format,
+            show_utc: self.show_utc,
             scan_concurrency: self.scan_concurrency,
+            scan_adaptive: self.scan_adaptive,
+            retention: self.retention,
+            drop_alert_percent: self.drop_alert_percent,
+            low_power: self.low_power,
+            offline: self.offline,
+            check_updates: self.check_updates,
+        }
+    }
+
+    /// A stored `YYYY-MM-DD HH:MM[:SS]` stamp in the user's date format and
+    /// clock, followed by the UTC time when that is turned on. Earlier
+    /// stamps are converted with the current offset, so one from before a
+    /// daylight saving change is an hour off in UTC.
+    pub fn display_time(&self, stamp: &str) -> String {
+        let local =
+            |stamp: &str| crate::display_stamp(stamp, self.region_format, self.clock_format);
+        let utc = self
+            .utc_offset_minutes
+            .filter(|_| self.show_utc)
+            .and_then(|offset| crate::utc_stamp(stamp, offset));
+        match utc {
+            // The date is repeated only when UTC is already on another day.
+            Some(utc) if utc.get(..10) == stamp.get(..10) => {
+                format!(
+                    "{} ({} UTC)",
+                    local(stamp),
+                    self.clock_format.time(&utc[11..])
+                )
+            }
+            Some(utc) => format!("{} ({} UTC)", local(stamp), local(&utc)),
+            None => local(stamp),
+        }
+    }
+
+    /// Only the time of day of a stored stamp, or of a bare `HH:MM:SS`, in
+    /// the user's clock. For narrow columns where the date is implied; the
+    /// UTC time is left to the full stamps.
+    pub fn display_time_of_day(&self, stamp: &str) -> String {
+        let time = stamp.split_once(' ').map_or(stamp, |(_, time)| time);
+        self.clock_format.time(time)
+    }
+
+    /// Whether `tool` can be started: the platform supports it and the
+    /// capability policy does not turn it off.
+    pub fn tool_available(&self, tool: DiagnosticTool) -> bool {
+        self.tool_supported(tool) && crate::permitted(self, crate::ModuleId::Tool(tool))
+    }
+
+    /// Whether the platform can run `tool`, whatever the policy says.
+    pub const fn tool_supported(&self, tool: DiagnosticTool) -> bool {
+        match tool {
+            DiagnosticTool::Ping
+            | DiagnosticTool::Trace
+            | DiagnosticTool::LinkQuality
+            | DiagnosticTool::Mtu
+            | DiagnosticTool::Dscp
+            | DiagnosticTool::AbTest => self.capabilities.icmp,
+            DiagnosticTool::PortScan
+            | DiagnosticTool::PublicSpeed
+            | DiagnosticTool::LanSpeed
+            | DiagnosticTool::Multicast
+            | DiagnosticTool::Upnp
+            | DiagnosticTool::Nat
+            | DiagnosticTool::Ipv6
+            | DiagnosticTool::DnsBench
+            | DiagnosticTool::DnsFilter
+            | DiagnosticTool::Mail
+            | DiagnosticTool::Voip
+            | DiagnosticTool::Gaming
+            | DiagnosticTool::Cloud
+            | DiagnosticTool::Wifi => true,
+        }
+    }
+
+    pub const fn low_power_active(&self) -> bool {
+        match self.low_power {
+            LowPowerMode::Auto => self.on_battery,
+            LowPowerMode::On => true,
+            LowPowerMode::Off => false,
+        }
+    }
+
+    pub const fn tick_interval_ms(&self) -> u64 {
+        if self.low_power_active() {
+            LOW_POWER_TICK_MS
+        } else {
+            TICK_MS
         }
     }
 
+    /// Traffic counters only need polling while a page that shows them is
+    /// visible; the page resumes with a fresh sample. The Adapters page shows
+    /// the drop counters that come with them.
+    pub fn wants_traffic_polling(&self) -> bool {
+        self.shows(Page::Dashboard) || self.shows(Page::Traffic) || self.shows(Page::Adapters)
+    }
+
+    /// The adapter monitor (addresses, Wi-Fi signal) also feeds the event log,
+    /// so it only pauses on hidden pages in low-power mode.
+    pub fn wants_adapter_polling(&self) -> bool {
+        !self.low_power_active() || self.shows(Page::Dashboard) || self.shows(Page::Adapters)
+    }
+
     pub fn update(&mut self, message: Message) -> Vec<Effect> {
         match message {
-            Input(input) => self.handle_input(input),
+            Input(input) => {
+                self.redraw = true;
+                let mut effects = self.handle_input(input);
+                // A preset's tool stopped by hand ends that stage as well.
+                effects.extend(self.advance_pipeline());
+                effects
+            }
             Tick(delta) => {
                 self.elapsed_ms = self.elapsed_ms.saturating_add(delta);
+                self.schedule_watch()
+            }
+            Clock {
+                local,
+                utc_offset_minutes,
+            } => {
+                if self.dashboard.snapshot.observed_at != local {
+                    self.dashboard.snapshot.observed_at = local;
+                    self.redraw = true;
+                }
+                self.utc_offset_minutes = Some(utc_offset_minutes);
                 Vec::new()
             }
-            Clock(observed_at) => {
-                self.dashboard.snapshot.observed_at = observed_at;
+            PowerSource { on_battery } => {
+                if self.on_battery != on_battery {
+                    self.on_battery = on_battery;
+                    self.redraw = true;
+                }
                 Vec::new()
             }
-            Runtime(event) => {
-                self.handle_runtime(event);
+            InterfacesChanged => {
+                self.redraw = true;
+                self.recheck_network()
+            }
+            OverridesLoaded(overrides) => {
+                self.redraw = true;
+                self.overrides = *overrides;
                 Vec::new()
             }
+            Runtime(event) => {
+                self.redraw = true;
+                let effects = self.handle_runtime(event);
+                self.enforce_retention();
+                effects
+            }
         }
     }
 
+    /// Whether anything visible may have changed since the last call. Ticks
+    /// alone never request a frame, so an idle frontend can skip drawing.
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.redraw)
+    }
+
     fn handle_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        if self.kiosk.is_some() {
+            self.handle_kiosk_input(input);
+            return Vec::new();
+        }
+        if let Some(popup) = self.popups.last().cloned() {
+            return self.handle_popup_input(popup, input);
+        }
+        if self.search.editing {
+            return self.handle_search_input(input);
+        }
+        // Highlighted matches take n, N and Esc unless a field is being typed
+        // into.
+        if self.search.active()
+            && !self.entering_text()
+            && let Some(key) = input.key()
+            && !key.modifiers.control
+            && !key.modifiers.alt
+        {
+            match key.code {
+                KeyCode::Char('n') => {
+                    self.search.step += 1;
+                    return Vec::new();
+                }
+                KeyCode::Char('N') => {
+                    self.search.step -= 1;
+                    return Vec::new();
+                }
+                KeyCode::Esc => {
+                    self.search = crate::SearchState::default();
+                    return Vec::new();
+                }
+                _ => {}
+            }
+        }
         if self.page == Page::Adapters && self.adapters.edit.is_some() {
             let global = input.action();
-            if matches!(
-                global,
-                Some(
-                    Action::Quit
-                        | Action::ToggleLanguage
-                        | Action::Help
-                        | Action::NextPage
-                        | Action::PreviousPage
-                        | Action::SelectPage(_)
-                        | Action::ResetDemo
+            if leaves_editor(&input)
+                || matches!(
+                    global,
+                    Some(
+                        Action::Quit
+                            | Action::ToggleLanguage
+                            | Action::ToggleRedaction
+                            | Action::Help
+                            | Action::Problems
+                            | Action::Audit
+                            | Action::Calculator
+                            | Action::Timeline
+                            | Action::Search
+                            | Action::NextPage
+                            | Action::PreviousPage
+                            | Action::SelectPage(_)
+                            | Action::ToggleSplit
+                            | Action::SwitchPane
+                            | Action::ResetDemo
+                    )
                 )
-            ) {
+            {
                 return self.handle_action(global.expect("matched global action"));
             }
             return self.handle_adapter_edit_input(input);
@@ -764,7 +2458,12 @@ impl AppModel {
                 }
                 return Vec::new();
             }
-            if let Some(Action::FocusDiagnostic(focus)) = action {
+            if let Some(Action::FocusDiagnostic(focus)) = action.or_else(|| {
+                input
+                    .key()
+                    .and_then(|key| self.diagnostic_focus_shortcut(key))
+                    .map(Action::FocusDiagnostic)
+            }) {
                 self.diagnostics.focused = true;
                 self.diagnostics.focus = focus;
                 self.diagnostics.history_open = false;
@@ -778,20 +2477,53 @@ impl AppModel {
                 return Vec::new();
             }
             if self.diagnostics.focused {
-                if matches!(
-                    action,
-                    Some(
-                        Action::Quit
-                            | Action::ToggleLanguage
-                            | Action::Help
-                            | Action::SelectPage(_)
+                if leaves_editor(&input)
+                    || matches!(
+                        action,
+                        Some(
+                            Action::Quit
+                                | Action::ToggleLanguage
+                                | Action::ToggleRedaction
+                                | Action::Help
+                                | Action::Problems
+                                | Action::Audit
+                                | Action::Calculator
+                                | Action::Timeline
+                                | Action::Search
+                                | Action::Export
+                                | Action::SelectPage(_)
+                                | Action::ToggleSplit
+                                | Action::SwitchPane
+                        )
                     )
-                ) {
+                {
                     return self.handle_action(action.expect("matched global action"));
                 }
                 return self.handle_diagnostic_input(input);
             }
         }
+        if self.page == Page::Settings && self.settings.input.is_some() {
+            if leaves_editor(&input)
+                || matches!(
+                    input.action(),
+                    Some(Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)
+                ) && input.key().is_none_or(|key| key.modifiers.control)
+            {
+                return self.handle_action(input.action().expect("matched global action"));
+            }
+            return self.handle_settings_input(input);
+        }
+        if self.page == Page::Scanner && self.scanner.note_input.is_some() {
+            if leaves_editor(&input)
+                || matches!(
+                    input.action(),
+                    Some(Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)
+                ) && input.key().is_none_or(|key| key.modifiers.control)
+            {
+                return self.handle_action(input.action().expect("matched global action"));
+            }
+            return self.handle_note_input(input);
+        }
         if self.page == Page::Scanner {
             let action = input.action();
             if self.scanner.editing
@@ -804,18 +2536,28 @@ impl AppModel {
                     )
                 )
             {
-                if matches!(
-                    action,
-                    Some(
-                        Action::Quit
-                            | Action::ToggleLanguage
-                            | Action::Help
-                            | Action::NextPage
-                            | Action::PreviousPage
-                            | Action::SelectPage(_)
-                            | Action::ResetDemo
+                if leaves_editor(&input)
+                    || matches!(
+                        action,
+                        Some(
+                            Action::Quit
+                                | Action::ToggleLanguage
+                                | Action::ToggleRedaction
+                                | Action::Help
+                                | Action::Problems
+                                | Action::Audit
+                                | Action::Calculator
+                                | Action::Timeline
+                                | Action::Search
+                                | Action::NextPage
+                                | Action::PreviousPage
+                                | Action::SelectPage(_)
+                                | Action::ToggleSplit
+                                | Action::SwitchPane
+                                | Action::ResetDemo
+                        )
                     )
-                ) {
+                {
                     return self.handle_action(action.expect("matched global action"));
                 }
                 return self.handle_scanner_input(input);
@@ -826,6 +2568,54 @@ impl AppModel {
         action.map_or_else(Vec::new, |action| self.handle_action(action))
     }
 
+    /// Whether a page field is taking printable keys as text.
+    fn entering_text(&self) -> bool {
+        match self.page {
+            Page::Adapters => self.adapters.edit.is_some(),
+            Page::Diagnostics => {
+                self.diagnostics.focused && self.diagnostics.focus == DiagnosticFocus::Config
+            }
+            Page::Scanner => self.scanner.editing || self.scanner.note_input.is_some(),
+            Page::Settings => self.settings.input.is_some(),
+            _ => false,
+        }
+    }
+
+    /// The query line: keys edit it, Enter keeps the matches highlighted and
+    /// Esc drops the search.
+    fn handle_search_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        if let Some(key) = input.key()
+            && !key.modifiers.control
+        {
+            let search = &mut self.search;
+            match key.code {
+                KeyCode::Enter => search.editing = false,
+                KeyCode::Esc => *search = crate::SearchState::default(),
+                code => {
+                    if edit_ascii(
+                        &mut search.query,
+                        &mut search.cursor,
+                        code,
+                        crate::SEARCH_QUERY_LIMIT,
+                        |character| character.is_ascii_graphic() || character == ' ',
+                    ) {
+                        search.step = 0;
+                    }
+                }
+            }
+            return Vec::new();
+        }
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
+            }
+            Some(Action::Confirm | Action::Search) => self.search.editing = false,
+            Some(Action::Back) => self.search = crate::SearchState::default(),
+            _ => {}
+        }
+        Vec::new()
+    }
+
     fn handle_scanner_input(&mut self, input: InputEvent) -> Vec<Effect> {
         let action = input.action();
 
@@ -924,6 +2714,7 @@ impl AppModel {
                 &mut value,
                 &mut self.scanner.cursor,
                 key.code,
+                64,
                 |character| character.is_ascii_digit() || matches!(character, '.' | '/'),
             ) && value.len() <= 32
             {
@@ -939,11 +2730,163 @@ impl AppModel {
         vec![Effect::PersistSession(crate::SessionUpdate::Scanner(
             crate::ScannerPersist {
                 cidr: self.scanner.cidr.clone(),
+                enrich: self.scanner.enrich,
+                dns_zone: self.scanner.dns_zone.clone(),
             },
         ))]
     }
 
-    fn begin_adapter_edit(&mut self) -> Vec<Effect> {
+    fn persist_ping(&self) -> Effect {
+        let request = &self.diagnostics.ping.request;
+        Effect::PersistSession(crate::SessionUpdate::Ping(crate::PingPersist {
+            target: request.target.clone(),
+            interval_ms: request.interval_ms,
+            timeout_ms: request.timeout_ms,
+            packet_size: request.packet_size,
+            mode: request.mode,
+            dont_fragment: request.dont_fragment,
+            dscp: request.dscp,
+            claimed_location: self.diagnostics.ping.claimed_location.clone(),
+            own_location: self.diagnostics.ping.own_location.clone(),
+        }))
+    }
+
+    fn persist_events(&self) -> Effect {
+        Effect::PersistSession(crate::SessionUpdate::Events(crate::EventLogPersist {
+            entries: self.events.entries.clone(),
+        }))
+    }
+
+    fn persist_inventory(&self) -> Effect {
+        Effect::PersistSession(crate::SessionUpdate::Inventory(crate::InventoryPersist {
+            devices: self.scanner.inventory.clone(),
+        }))
+    }
+
+    /// Enter opens or closes the detail drawer once there is a device to
+    /// show; before that it starts the scan.
+    pub(crate) fn confirm_scanner(&mut self) -> Vec<Effect> {
+        if self.scanner.results.is_empty() {
+            return self.toggle_scan();
+        }
+        self.scanner.detail_open = !self.scanner.detail_open;
+        Vec::new()
+    }
+
+    /// Ask the vulnerability index about the software the selected host
+    /// announced. Versions already looked up are not asked again.
+    pub(crate) fn lookup_cves(&mut self) -> Vec<Effect> {
+        if self.scanner.cve.job.is_some() {
+            return Vec::new();
+        }
+        let Some(host) = self.scanner.selected_host() else {
+            return Vec::new();
+        };
+        let software = self.scanner.cve.pending(host);
+        if software.is_empty() {
+            return Vec::new();
+        }
+        let job = self.next_job(ToolKind::Cve);
+        self.scanner.cve.job = Some(job);
+        self.scanner.cve.error = None;
+        vec![Effect::LookupCves {
+            job,
+            software,
+            use_system_proxy: self.public_ip_config.use_system_proxy,
+        }]
+    }
+
+    pub(crate) fn begin_note_edit(&mut self) -> Vec<Effect> {
+        let Some(host) = self.scanner.selected_host() else {
+            return Vec::new();
+        };
+        let note = self
+            .scanner
+            .device_record(host)
+            .map(|record| record.note.clone())
+            .unwrap_or_default();
+        self.scanner.detail_open = true;
+        self.scanner.note_input = Some((note.clone(), note.len()));
+        Vec::new()
+    }
+
+    fn handle_note_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        let Some(key) = input.key() else {
+            return Vec::new();
+        };
+        match key.code {
+            KeyCode::Esc => self.scanner.note_input = None,
+            KeyCode::Enter => {
+                let Some((note, _)) = self.scanner.note_input.take() else {
+                    return Vec::new();
+                };
+                let Some(host) = self.scanner.selected_host().cloned() else {
+                    return Vec::new();
+                };
+                self.scanner.remember(&host);
+                let key = host.device_key();
+                if let Some(record) = self
+                    .scanner
+                    .inventory
+                    .iter_mut()
+                    .find(|record| record.key == key)
+                {
+                    record.note = note.trim().to_string();
+                }
+                self.scanner.revision = next_revision(&mut self.revision_clock);
+                return vec![self.persist_inventory()];
+            }
+            code => {
+                if let Some((note, cursor)) = self.scanner.note_input.as_mut() {
+                    edit_ascii(note, cursor, code, 64, |character| {
+                        character.is_ascii_graphic() || character == ' '
+                    });
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Switch the Scanner between the IP sweep and nearby Bluetooth devices,
+    /// where the platform can scan for them.
+    pub(crate) fn toggle_scanner_view(&mut self) -> Vec<Effect> {
+        if !self.capabilities.bluetooth {
+            return Vec::new();
+        }
+        self.scanner.view = match self.scanner.view {
+            ScannerView::Hosts => ScannerView::Bluetooth,
+            ScannerView::Bluetooth => ScannerView::Hosts,
+        };
+        self.scanner.detail_open = false;
+        Vec::new()
+    }
+
+    pub(crate) fn toggle_bluetooth_scan(&mut self) -> Vec<Effect> {
+        if let Some(job) = self.scanner.bluetooth.job.take() {
+            self.scanner.bluetooth.status = TaskStatus::Done;
+            return vec![Effect::StopBluetoothScan(job)];
+        }
+        let job = self.next_job(ToolKind::Bluetooth);
+        let bluetooth = &mut self.scanner.bluetooth;
+        bluetooth.job = Some(job);
+        bluetooth.status = TaskStatus::Running;
+        bluetooth.devices.clear();
+        bluetooth.selected = 0;
+        vec![Effect::StartBluetoothScan {
+            job,
+            request: crate::BluetoothRequest::default(),
+        }]
+    }
+
+    pub(crate) fn toggle_enrichment(&mut self) -> Vec<Effect> {
+        self.scanner.enrich = !self.scanner.enrich;
+        self.persist_scanner()
+    }
+
+    pub(crate) fn begin_adapter_edit(&mut self) -> Vec<Effect> {
+        if !self.capabilities.adapter_config {
+            return Vec::new();
+        }
         let Some(adapter) = self.adapters.items.get(self.adapters.selected).cloned() else {
             return Vec::new();
         };
@@ -1340,17 +3283,29 @@ impl AppModel {
                 }
                 _ => {}
             },
+            DiagnosticFocus::Main
+                if self.diagnostics.tool == DiagnosticTool::Ping
+                    && key.is_some_and(|key| {
+                        matches!(key.code, KeyCode::Char('p' | 'P')) && !key.modifiers.control
+                    }) =>
+            {
+                let at = self.dashboard.snapshot.observed_at.clone();
+                self.diagnostics.ping.toggle_baseline(&at);
+            }
             DiagnosticFocus::Main => match action {
                 Some(Action::Confirm | Action::Toggle) => return self.toggle_diagnostic(),
                 Some(Action::Up) if self.diagnostics.tool == DiagnosticTool::Trace => {
-                    let len = self.diagnostics.trace.hops.len();
+                    let len = self.diagnostics.trace.rows();
                     if len > 0 {
                         self.diagnostics.trace.selected =
                             wrap(self.diagnostics.trace.selected, len, -1);
                     }
                 }
+                Some(Action::Refresh) if self.diagnostics.tool == DiagnosticTool::Trace => {
+                    return self.lookup_trace_asns();
+                }
                 Some(Action::Down) if self.diagnostics.tool == DiagnosticTool::Trace => {
-                    let len = self.diagnostics.trace.hops.len();
+                    let len = self.diagnostics.trace.rows();
                     if len > 0 {
                         self.diagnostics.trace.selected =
                             wrap(self.diagnostics.trace.selected, len, 1);
@@ -1384,13 +3339,14 @@ impl AppModel {
     ) -> Vec<Effect> {
         let running = self.diagnostics.active_common().job.is_some();
         let selected = self.active_diagnostic_config_index();
-        let target_field = matches!(
+        let target_field = self.diagnostic_target_selected();
+        // Interface names may contain spaces, as on Windows, and so may
+        // city names.
+        let spaced_field = matches!(
             (self.diagnostics.tool, selected),
-            (
-                DiagnosticTool::Ping | DiagnosticTool::Trace | DiagnosticTool::PortScan,
-                0
-            ) | (DiagnosticTool::LinkQuality, 1)
-                | (DiagnosticTool::LanSpeed, 4)
+            (DiagnosticTool::Trace, 3 | 5)
+                | (DiagnosticTool::Ping, 7 | 8)
+                | (DiagnosticTool::AbTest, 0 | 1)
         );
 
         if action == Some(Action::History) && target_field && !running {
@@ -1432,10 +3388,11 @@ impl AppModel {
             return Vec::new();
         }
 
-        if !running && target_field {
+        if !running && (target_field || spaced_field) {
             if let Some(key) = key {
                 let current = self.active_diagnostic_field().to_string();
-                if key.code == KeyCode::Right
+                if target_field
+                    && key.code == KeyCode::Right
                     && !current.is_empty()
                     && self.diagnostics.cursor == current.len()
                     && let Some(value) = self
@@ -1452,9 +3409,13 @@ impl AppModel {
                     return self.persist_active_diagnostic();
                 }
                 let mut value = current;
-                if edit_ascii(&mut value, &mut self.diagnostics.cursor, key.code, |c| {
-                    c.is_ascii() && !c.is_control() && c != ' '
-                }) {
+                if edit_ascii(
+                    &mut value,
+                    &mut self.diagnostics.cursor,
+                    key.code,
+                    64,
+                    |c| c.is_ascii() && !c.is_control() && (spaced_field || c != ' '),
+                ) {
                     self.set_active_diagnostic_field(value);
                     return self.persist_active_diagnostic();
                 }
@@ -1462,17 +3423,40 @@ impl AppModel {
         } else if !running
             && matches!(
                 (self.diagnostics.tool, selected),
-                (DiagnosticTool::Trace, 1..)
+                (
+                    DiagnosticTool::Mtu
+                        | DiagnosticTool::Ipv6
+                        | DiagnosticTool::DnsFilter
+                        | DiagnosticTool::Mail,
+                    1..
+                ) | (DiagnosticTool::Trace, 1 | 2)
+                    | (
+                        DiagnosticTool::Dscp
+                            | DiagnosticTool::Multicast
+                            | DiagnosticTool::Upnp
+                            | DiagnosticTool::Nat
+                            | DiagnosticTool::DnsBench,
+                        2..
+                    )
                     | (DiagnosticTool::PortScan, 1..)
                     | (DiagnosticTool::LinkQuality, 2..)
                     | (DiagnosticTool::LanSpeed, 1 | 5..)
+                    | (DiagnosticTool::Voip, 1 | 4)
+                    | (DiagnosticTool::Gaming, 2)
+                    | (DiagnosticTool::Cloud, 0..)
+                    | (DiagnosticTool::Wifi, 1)
+                    | (DiagnosticTool::AbTest, 3)
             )
             && let Some(key) = key
         {
             let mut value = self.active_diagnostic_field().to_string();
-            if edit_ascii(&mut value, &mut self.diagnostics.cursor, key.code, |c| {
-                c.is_ascii_digit()
-            }) {
+            if edit_ascii(
+                &mut value,
+                &mut self.diagnostics.cursor,
+                key.code,
+                64,
+                |c| c.is_ascii_digit(),
+            ) {
                 self.set_active_diagnostic_field(value);
                 self.sync_active_diagnostic_request();
                 return self.persist_active_diagnostic();
@@ -1483,7 +3467,9 @@ impl AppModel {
             Some(Action::Up) => self.move_diagnostic_config(-1),
             Some(Action::Down) => self.move_diagnostic_config(1),
             Some(Action::Left | Action::Right)
-                if !running && self.diagnostics.tool == DiagnosticTool::Ping && selected > 0 =>
+                if !running
+                    && self.diagnostics.tool == DiagnosticTool::Ping
+                    && (1..=6).contains(&selected) =>
             {
                 let dir = if action == Some(Action::Left) { -1 } else { 1 };
                 let request = &mut self.diagnostics.ping.request;
@@ -1500,10 +3486,89 @@ impl AppModel {
                         request.packet_size =
                             ((request.packet_size as i64) + dir * 8).clamp(0, 65_500) as u64
                     }
+                    4 => {
+                        request.mode = if dir < 0 {
+                            request.mode.previous()
+                        } else {
+                            request.mode.next()
+                        }
+                    }
+                    5 => request.dont_fragment = !request.dont_fragment,
+                    6 => request.dscp = crate::cycle_dscp(request.dscp, dir > 0),
                     _ => {}
                 }
                 return self.persist_active_diagnostic();
             }
+            Some(Action::Left | Action::Right)
+                if !running
+                    && self.diagnostics.tool == DiagnosticTool::Multicast
+                    && selected == 0 =>
+            {
+                let request = &mut self.diagnostics.multicast.request;
+                request.mode = request.mode.toggle();
+                self.set_diagnostic_config_index(0);
+                return self.persist_active_diagnostic();
+            }
+            Some(Action::Left | Action::Right)
+                if !running
+                    && self.diagnostics.tool == DiagnosticTool::Voip
+                    && matches!(selected, 0 | 3) =>
+            {
+                let request = &mut self.diagnostics.voip.request;
+                if selected == 0 {
+                    request.mode = request.mode.toggle();
+                } else {
+                    request.codec = request.codec.toggle();
+                }
+                self.set_diagnostic_config_index(selected);
+                return self.persist_active_diagnostic();
+            }
+            Some(Action::Left | Action::Right)
+                if !running && self.diagnostics.tool == DiagnosticTool::Gaming && selected == 0 =>
+            {
+                let request = &mut self.diagnostics.gaming.request;
+                request.preset = if action == Some(Action::Left) {
+                    request.preset.previous()
+                } else {
+                    request.preset.next()
+                };
+                return self.persist_active_diagnostic();
+            }
+            Some(Action::Left | Action::Right)
+                if !running && self.diagnostics.tool == DiagnosticTool::Upnp && selected < 2 =>
+            {
+                let request = &mut self.diagnostics.upnp.request;
+                if selected == 0 {
+                    request.action = if action == Some(Action::Left) {
+                        request.action.previous()
+                    } else {
+                        request.action.next()
+                    };
+                } else {
+                    request.protocol = match request.protocol {
+                        crate::LanProtocol::Tcp => crate::LanProtocol::Udp,
+                        crate::LanProtocol::Udp => crate::LanProtocol::Tcp,
+                    };
+                }
+                self.set_diagnostic_config_index(selected);
+                return self.persist_active_diagnostic();
+            }
+            Some(Action::Left | Action::Right)
+                if !running
+                    && self.diagnostics.tool == DiagnosticTool::DnsBench
+                    && selected == 1 =>
+            {
+                let request = &mut self.diagnostics.dns_bench.request;
+                request.encrypted = !request.encrypted;
+                return self.persist_active_diagnostic();
+            }
+            Some(Action::Left | Action::Right)
+                if !running && self.diagnostics.tool == DiagnosticTool::Dscp && selected == 1 =>
+            {
+                let request = &mut self.diagnostics.dscp.request;
+                request.dscp = crate::cycle_dscp(request.dscp, action == Some(Action::Right));
+                return self.persist_active_diagnostic();
+            }
             Some(Action::Left | Action::Right)
                 if !running
                     && self.diagnostics.tool == DiagnosticTool::LinkQuality
@@ -1512,6 +3577,20 @@ impl AppModel {
                 self.switch_link_quality_adapter(if action == Some(Action::Left) { -1 } else { 1 });
                 return self.persist_active_diagnostic();
             }
+            Some(Action::Left | Action::Right)
+                if !running && self.diagnostics.tool == DiagnosticTool::Wifi && selected == 0 =>
+            {
+                let state = &mut self.diagnostics.wifi;
+                if !state.adapters.is_empty() {
+                    state.selected_adapter = wrap(
+                        state.selected_adapter,
+                        state.adapters.len(),
+                        if action == Some(Action::Left) { -1 } else { 1 },
+                    );
+                }
+                self.sync_wifi_request();
+                return self.persist_active_diagnostic();
+            }
             Some(Action::Left | Action::Right)
                 if !running && self.diagnostics.tool == DiagnosticTool::LanSpeed =>
             {
@@ -1565,22 +3644,103 @@ impl AppModel {
 
     fn diagnostic_config_count(&self) -> usize {
         match self.diagnostics.tool {
-            DiagnosticTool::Ping => 4,
-            DiagnosticTool::Trace => 3,
+            DiagnosticTool::Ping => 9,
+            DiagnosticTool::Trace => 6,
             DiagnosticTool::PortScan => 4,
             DiagnosticTool::LinkQuality => 6,
             DiagnosticTool::LanSpeed => self.lan_speed_config_count(),
+            DiagnosticTool::Mtu => 3,
+            DiagnosticTool::Dscp => 4,
+            DiagnosticTool::Multicast => match self.diagnostics.multicast.request.mode {
+                crate::MulticastMode::Listen => 4,
+                crate::MulticastMode::Send => 5,
+            },
+            DiagnosticTool::Nat => 3,
+            DiagnosticTool::Ipv6 => 2,
+            DiagnosticTool::DnsBench => 4,
+            DiagnosticTool::DnsFilter | DiagnosticTool::Mail => 2,
+            DiagnosticTool::Voip => match self.diagnostics.voip.request.mode {
+                crate::VoipMode::Probe => 5,
+                crate::VoipMode::Reflect => 2,
+            },
+            DiagnosticTool::Gaming => 3,
+            DiagnosticTool::Cloud | DiagnosticTool::Wifi => 2,
+            DiagnosticTool::AbTest => 4,
+            DiagnosticTool::Upnp => match self.diagnostics.upnp.request.action {
+                crate::UpnpAction::List => 1,
+                crate::UpnpAction::Delete => 3,
+                crate::UpnpAction::Add => 5,
+            },
             DiagnosticTool::PublicSpeed => 1,
         }
     }
 
-    fn active_diagnostic_config_index(&self) -> usize {
+    /// `m`, `v` and `c` jump to the tool menu, the result panel and the
+    /// parameters. The parameters take typed text, so there only the Alt
+    /// spelling jumps.
+    fn diagnostic_focus_shortcut(&self, key: crate::KeyEvent) -> Option<DiagnosticFocus> {
+        let KeyCode::Char(character) = key.code else {
+            return None;
+        };
+        let typing = self.diagnostics.focused && self.diagnostics.focus == DiagnosticFocus::Config;
+        if key.modifiers.control || typing && !key.modifiers.alt {
+            return None;
+        }
+        match character.to_ascii_lowercase() {
+            'm' => Some(DiagnosticFocus::Menu),
+            'v' => Some(DiagnosticFocus::Main),
+            'c' => Some(DiagnosticFocus::Config),
+            _ => None,
+        }
+    }
+
+    /// Whether the selected parameter is a host or address, which keeps a
+    /// history of earlier targets.
+    pub(crate) fn diagnostic_target_selected(&self) -> bool {
+        matches!(
+            (self.diagnostics.tool, self.active_diagnostic_config_index()),
+            (
+                DiagnosticTool::Ping
+                    | DiagnosticTool::Trace
+                    | DiagnosticTool::PortScan
+                    | DiagnosticTool::Mtu
+                    | DiagnosticTool::Dscp
+                    | DiagnosticTool::Ipv6
+                    | DiagnosticTool::DnsBench
+                    | DiagnosticTool::DnsFilter
+                    | DiagnosticTool::Mail,
+                0
+            ) | (DiagnosticTool::Nat, 0 | 1)
+                | (DiagnosticTool::LinkQuality | DiagnosticTool::Multicast, 1)
+                | (DiagnosticTool::LanSpeed | DiagnosticTool::Trace, 4)
+                | (DiagnosticTool::Voip, 2)
+                | (DiagnosticTool::Gaming, 1)
+                | (DiagnosticTool::AbTest, 2)
+        )
+    }
+
+    /// Row of the active tool's parameter form that is selected.
+    pub fn active_diagnostic_config_index(&self) -> usize {
         match self.diagnostics.tool {
             DiagnosticTool::Ping => self.diagnostics.ping.config_selected,
             DiagnosticTool::Trace => self.diagnostics.trace.config_selected,
             DiagnosticTool::PortScan => self.diagnostics.port_scan.config_selected,
             DiagnosticTool::LinkQuality => self.diagnostics.link_quality.config_selected,
             DiagnosticTool::LanSpeed => self.diagnostics.lan_speed.config_selected,
+            DiagnosticTool::Mtu => self.diagnostics.mtu.config_selected,
+            DiagnosticTool::Dscp => self.diagnostics.dscp.config_selected,
+            DiagnosticTool::Multicast => self.diagnostics.multicast.config_selected,
+            DiagnosticTool::Upnp => self.diagnostics.upnp.config_selected,
+            DiagnosticTool::Nat => self.diagnostics.nat.config_selected,
+            DiagnosticTool::Ipv6 => self.diagnostics.ipv6.config_selected,
+            DiagnosticTool::DnsBench => self.diagnostics.dns_bench.config_selected,
+            DiagnosticTool::DnsFilter => self.diagnostics.dns_filter.config_selected,
+            DiagnosticTool::Mail => self.diagnostics.mail.config_selected,
+            DiagnosticTool::Voip => self.diagnostics.voip.config_selected,
+            DiagnosticTool::Gaming => self.diagnostics.gaming.config_selected,
+            DiagnosticTool::Cloud => self.diagnostics.cloud.config_selected,
+            DiagnosticTool::Wifi => self.diagnostics.wifi.config_selected,
+            DiagnosticTool::AbTest => self.diagnostics.ab_test.config_selected,
             DiagnosticTool::PublicSpeed => 0,
         }
     }
@@ -1593,6 +3753,20 @@ impl AppModel {
             DiagnosticTool::PortScan => self.diagnostics.port_scan.config_selected = index,
             DiagnosticTool::LinkQuality => self.diagnostics.link_quality.config_selected = index,
             DiagnosticTool::LanSpeed => self.diagnostics.lan_speed.config_selected = index,
+            DiagnosticTool::Mtu => self.diagnostics.mtu.config_selected = index,
+            DiagnosticTool::Dscp => self.diagnostics.dscp.config_selected = index,
+            DiagnosticTool::Multicast => self.diagnostics.multicast.config_selected = index,
+            DiagnosticTool::Upnp => self.diagnostics.upnp.config_selected = index,
+            DiagnosticTool::Nat => self.diagnostics.nat.config_selected = index,
+            DiagnosticTool::Ipv6 => self.diagnostics.ipv6.config_selected = index,
+            DiagnosticTool::DnsBench => self.diagnostics.dns_bench.config_selected = index,
+            DiagnosticTool::DnsFilter => self.diagnostics.dns_filter.config_selected = index,
+            DiagnosticTool::Mail => self.diagnostics.mail.config_selected = index,
+            DiagnosticTool::Voip => self.diagnostics.voip.config_selected = index,
+            DiagnosticTool::Gaming => self.diagnostics.gaming.config_selected = index,
+            DiagnosticTool::Cloud => self.diagnostics.cloud.config_selected = index,
+            DiagnosticTool::Wifi => self.diagnostics.wifi.config_selected = index,
+            DiagnosticTool::AbTest => self.diagnostics.ab_test.config_selected = index,
             DiagnosticTool::PublicSpeed => {}
         }
         self.diagnostics.cursor = self.active_diagnostic_field().len();
@@ -1612,12 +3786,17 @@ impl AppModel {
         match self.diagnostics.tool {
             DiagnosticTool::Ping => match self.diagnostics.ping.config_selected {
                 0 => &self.diagnostics.ping.request.target,
+                7 => &self.diagnostics.ping.claimed_location,
+                8 => &self.diagnostics.ping.own_location,
                 _ => "",
             },
             DiagnosticTool::Trace => match self.diagnostics.trace.config_selected {
                 0 => &self.diagnostics.trace.request.target,
                 1 => &self.diagnostics.trace.max_hops_input,
-                _ => &self.diagnostics.trace.timeout_input,
+                2 => &self.diagnostics.trace.timeout_input,
+                3 => &self.diagnostics.trace.via_input,
+                4 => &self.diagnostics.trace.compare_target,
+                _ => &self.diagnostics.trace.compare_via,
             },
             DiagnosticTool::PortScan => match self.diagnostics.port_scan.config_selected {
                 0 => &self.diagnostics.port_scan.persist.target,
@@ -1642,20 +3821,97 @@ impl AppModel {
                 8 => &self.diagnostics.lan_speed.persist.rate,
                 _ => "",
             },
-            DiagnosticTool::PublicSpeed => "",
-        }
-    }
-
-    fn set_active_diagnostic_field(&mut self, value: String) {
-        match self.diagnostics.tool {
-            DiagnosticTool::Ping if self.diagnostics.ping.config_selected == 0 => {
-                self.diagnostics.ping.request.target = value;
-            }
-            DiagnosticTool::Ping => {}
+            DiagnosticTool::Mtu => match self.diagnostics.mtu.config_selected {
+                0 => &self.diagnostics.mtu.request.target,
+                1 => &self.diagnostics.mtu.max_mtu_input,
+                _ => &self.diagnostics.mtu.timeout_input,
+            },
+            DiagnosticTool::Dscp => match self.diagnostics.dscp.config_selected {
+                0 => &self.diagnostics.dscp.request.target,
+                1 => "",
+                2 => &self.diagnostics.dscp.max_hops_input,
+                _ => &self.diagnostics.dscp.timeout_input,
+            },
+            DiagnosticTool::Multicast => match self.diagnostics.multicast.config_selected {
+                0 => "",
+                1 => &self.diagnostics.multicast.request.group,
+                2 => &self.diagnostics.multicast.port_input,
+                3 => &self.diagnostics.multicast.duration_input,
+                _ => &self.diagnostics.multicast.rate_input,
+            },
+            DiagnosticTool::Upnp => match self.diagnostics.upnp.config_selected {
+                2 => &self.diagnostics.upnp.external_port_input,
+                3 => &self.diagnostics.upnp.internal_port_input,
+                4 => &self.diagnostics.upnp.lease_input,
+                _ => "",
+            },
+            DiagnosticTool::Nat => match self.diagnostics.nat.config_selected {
+                0 => &self.diagnostics.nat.request.server,
+                1 => &self.diagnostics.nat.request.secondary,
+                _ => &self.diagnostics.nat.timeout_input,
+            },
+            DiagnosticTool::Ipv6 => match self.diagnostics.ipv6.config_selected {
+                0 => &self.diagnostics.ipv6.request.target,
+                _ => &self.diagnostics.ipv6.timeout_input,
+            },
+            DiagnosticTool::DnsBench => match self.diagnostics.dns_bench.config_selected {
+                0 => &self.diagnostics.dns_bench.request.custom,
+                2 => &self.diagnostics.dns_bench.rounds_input,
+                3 => &self.diagnostics.dns_bench.timeout_input,
+                _ => "",
+            },
+            DiagnosticTool::DnsFilter => match self.diagnostics.dns_filter.config_selected {
+                0 => &self.diagnostics.dns_filter.request.domain,
+                _ => &self.diagnostics.dns_filter.timeout_input,
+            },
+            DiagnosticTool::Mail => match self.diagnostics.mail.config_selected {
+                0 => &self.diagnostics.mail.request.host,
+                _ => &self.diagnostics.mail.timeout_input,
+            },
+            DiagnosticTool::Voip => match self.diagnostics.voip.config_selected {
+                1 => &self.diagnostics.voip.port_input,
+                2 => &self.diagnostics.voip.request.peer,
+                4 => &self.diagnostics.voip.duration_input,
+                _ => "",
+            },
+            DiagnosticTool::Gaming => match self.diagnostics.gaming.config_selected {
+                1 => &self.diagnostics.gaming.request.custom,
+                2 => &self.diagnostics.gaming.rounds_input,
+                _ => "",
+            },
+            DiagnosticTool::Cloud => match self.diagnostics.cloud.config_selected {
+                0 => &self.diagnostics.cloud.attempts_input,
+                _ => &self.diagnostics.cloud.timeout_input,
+            },
+            DiagnosticTool::Wifi => match self.diagnostics.wifi.config_selected {
+                1 => &self.diagnostics.wifi.interval_input,
+                _ => "",
+            },
+            DiagnosticTool::AbTest => match self.diagnostics.ab_test.config_selected {
+                0 => &self.diagnostics.ab_test.request.first,
+                1 => &self.diagnostics.ab_test.request.second,
+                2 => &self.diagnostics.ab_test.request.target,
+                _ => &self.diagnostics.ab_test.pings_input,
+            },
+            DiagnosticTool::PublicSpeed => "",
+        }
+    }
+
+    fn set_active_diagnostic_field(&mut self, value: String) {
+        match self.diagnostics.tool {
+            DiagnosticTool::Ping => match self.diagnostics.ping.config_selected {
+                0 => self.diagnostics.ping.request.target = value,
+                7 => self.diagnostics.ping.claimed_location = value,
+                8 => self.diagnostics.ping.own_location = value,
+                _ => {}
+            },
             DiagnosticTool::Trace => match self.diagnostics.trace.config_selected {
                 0 => self.diagnostics.trace.request.target = value,
                 1 => self.diagnostics.trace.max_hops_input = value,
-                _ => self.diagnostics.trace.timeout_input = value,
+                2 => self.diagnostics.trace.timeout_input = value,
+                3 => self.diagnostics.trace.via_input = value,
+                4 => self.diagnostics.trace.compare_target = value,
+                _ => self.diagnostics.trace.compare_via = value,
             },
             DiagnosticTool::PortScan => match self.diagnostics.port_scan.config_selected {
                 0 => self.diagnostics.port_scan.persist.target = value,
@@ -1680,6 +3936,79 @@ impl AppModel {
                 8 => self.diagnostics.lan_speed.persist.rate = value,
                 _ => {}
             },
+            DiagnosticTool::Mtu => match self.diagnostics.mtu.config_selected {
+                0 => self.diagnostics.mtu.request.target = value,
+                1 => self.diagnostics.mtu.max_mtu_input = value,
+                _ => self.diagnostics.mtu.timeout_input = value,
+            },
+            DiagnosticTool::Dscp => match self.diagnostics.dscp.config_selected {
+                0 => self.diagnostics.dscp.request.target = value,
+                1 => {}
+                2 => self.diagnostics.dscp.max_hops_input = value,
+                _ => self.diagnostics.dscp.timeout_input = value,
+            },
+            DiagnosticTool::Multicast => match self.diagnostics.multicast.config_selected {
+                0 => {}
+                1 => self.diagnostics.multicast.request.group = value,
+                2 => self.diagnostics.multicast.port_input = value,
+                3 => self.diagnostics.multicast.duration_input = value,
+                _ => self.diagnostics.multicast.rate_input = value,
+            },
+            DiagnosticTool::Upnp => match self.diagnostics.upnp.config_selected {
+                2 => self.diagnostics.upnp.external_port_input = value,
+                3 => self.diagnostics.upnp.internal_port_input = value,
+                4 => self.diagnostics.upnp.lease_input = value,
+                _ => {}
+            },
+            DiagnosticTool::Nat => match self.diagnostics.nat.config_selected {
+                0 => self.diagnostics.nat.request.server = value,
+                1 => self.diagnostics.nat.request.secondary = value,
+                _ => self.diagnostics.nat.timeout_input = value,
+            },
+            DiagnosticTool::Ipv6 => match self.diagnostics.ipv6.config_selected {
+                0 => self.diagnostics.ipv6.request.target = value,
+                _ => self.diagnostics.ipv6.timeout_input = value,
+            },
+            DiagnosticTool::DnsBench => match self.diagnostics.dns_bench.config_selected {
+                0 => self.diagnostics.dns_bench.request.custom = value,
+                2 => self.diagnostics.dns_bench.rounds_input = value,
+                3 => self.diagnostics.dns_bench.timeout_input = value,
+                _ => {}
+            },
+            DiagnosticTool::DnsFilter => match self.diagnostics.dns_filter.config_selected {
+                0 => self.diagnostics.dns_filter.request.domain = value,
+                _ => self.diagnostics.dns_filter.timeout_input = value,
+            },
+            DiagnosticTool::Mail => match self.diagnostics.mail.config_selected {
+                0 => self.diagnostics.mail.request.host = value,
+                _ => self.diagnostics.mail.timeout_input = value,
+            },
+            DiagnosticTool::Voip => match self.diagnostics.voip.config_selected {
+                1 => self.diagnostics.voip.port_input = value,
+                2 => self.diagnostics.voip.request.peer = value,
+                4 => self.diagnostics.voip.duration_input = value,
+                _ => {}
+            },
+            DiagnosticTool::Gaming => match self.diagnostics.gaming.config_selected {
+                1 => self.diagnostics.gaming.request.custom = value,
+                2 => self.diagnostics.gaming.rounds_input = value,
+                _ => {}
+            },
+            DiagnosticTool::Cloud => match self.diagnostics.cloud.config_selected {
+                0 => self.diagnostics.cloud.attempts_input = value,
+                _ => self.diagnostics.cloud.timeout_input = value,
+            },
+            DiagnosticTool::Wifi => {
+                if self.diagnostics.wifi.config_selected == 1 {
+                    self.diagnostics.wifi.interval_input = value;
+                }
+            }
+            DiagnosticTool::AbTest => match self.diagnostics.ab_test.config_selected {
+                0 => self.diagnostics.ab_test.request.first = value,
+                1 => self.diagnostics.ab_test.request.second = value,
+                2 => self.diagnostics.ab_test.request.target = value,
+                _ => self.diagnostics.ab_test.pings_input = value,
+            },
             DiagnosticTool::PublicSpeed => {}
         }
         self.sync_active_diagnostic_request();
@@ -1700,6 +4029,186 @@ impl AppModel {
             .parse::<u64>()
             .unwrap_or(1_000)
             .clamp(100, 10_000);
+        let via = self.diagnostics.trace.via_input.trim();
+        self.diagnostics.trace.request.via = (!via.is_empty()).then(|| via.to_string());
+    }
+
+    fn sync_mtu_request(&mut self) {
+        self.diagnostics.mtu.request.max_mtu = self
+            .diagnostics
+            .mtu
+            .max_mtu_input
+            .parse::<u16>()
+            .unwrap_or(9_000)
+            .max(crate::MTU_FLOOR);
+        self.diagnostics.mtu.request.timeout_ms = self
+            .diagnostics
+            .mtu
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(1_000)
+            .clamp(100, 10_000);
+    }
+
+    fn sync_dscp_request(&mut self) {
+        self.diagnostics.dscp.request.max_hops = self
+            .diagnostics
+            .dscp
+            .max_hops_input
+            .parse::<u8>()
+            .unwrap_or(16)
+            .clamp(1, 64);
+        self.diagnostics.dscp.request.timeout_ms = self
+            .diagnostics
+            .dscp
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(1_000)
+            .clamp(100, 10_000);
+    }
+
+    fn sync_multicast_request(&mut self) {
+        let state = &mut self.diagnostics.multicast;
+        state.request.port = state.port_input.parse::<u16>().unwrap_or_default();
+        state.request.duration_secs = state
+            .duration_input
+            .parse::<u64>()
+            .unwrap_or(30)
+            .clamp(1, 3_600);
+        state.request.rate_pps = state
+            .rate_input
+            .parse::<u32>()
+            .unwrap_or(20)
+            .clamp(1, 1_000);
+    }
+
+    fn sync_upnp_request(&mut self) {
+        let state = &mut self.diagnostics.upnp;
+        state.request.external_port = state.external_port_input.parse::<u16>().unwrap_or_default();
+        state.request.internal_port = state.internal_port_input.parse::<u16>().unwrap_or_default();
+        state.request.lease_secs = state
+            .lease_input
+            .parse::<u32>()
+            .unwrap_or(3_600)
+            .min(604_800);
+    }
+
+    fn sync_nat_request(&mut self) {
+        self.diagnostics.nat.request.timeout_ms = self
+            .diagnostics
+            .nat
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(1_000)
+            .clamp(100, 10_000);
+    }
+
+    fn sync_ipv6_request(&mut self) {
+        self.diagnostics.ipv6.request.timeout_ms = self
+            .diagnostics
+            .ipv6
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(3_000)
+            .clamp(100, 30_000);
+    }
+
+    fn sync_dns_bench_request(&mut self) {
+        let state = &mut self.diagnostics.dns_bench;
+        state.request.rounds = state.rounds_input.parse::<u32>().unwrap_or(5).clamp(1, 50);
+        state.request.timeout_ms = state
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(2_000)
+            .clamp(100, 10_000);
+    }
+
+    fn sync_dns_filter_request(&mut self) {
+        let state = &mut self.diagnostics.dns_filter;
+        state.request.timeout_ms = state
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(2_000)
+            .clamp(100, 10_000);
+    }
+
+    fn sync_mail_request(&mut self) {
+        let state = &mut self.diagnostics.mail;
+        state.request.timeout_ms = state
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(5_000)
+            .clamp(500, 30_000);
+    }
+
+    fn sync_voip_request(&mut self) {
+        let state = &mut self.diagnostics.voip;
+        state.request.port = state.port_input.parse::<u16>().unwrap_or_default();
+        state.request.duration_secs = state
+            .duration_input
+            .parse::<u64>()
+            .unwrap_or(15)
+            .clamp(1, 600);
+    }
+
+    fn sync_gaming_request(&mut self) {
+        let state = &mut self.diagnostics.gaming;
+        state.request.rounds = state
+            .rounds_input
+            .parse::<u32>()
+            .unwrap_or(20)
+            .clamp(2, 100);
+    }
+
+    fn sync_cloud_request(&mut self) {
+        let state = &mut self.diagnostics.cloud;
+        state.request.attempts = state
+            .attempts_input
+            .parse::<u32>()
+            .unwrap_or(3)
+            .clamp(1, 10);
+        state.request.timeout_ms = state
+            .timeout_input
+            .parse::<u64>()
+            .unwrap_or(3_000)
+            .clamp(500, 10_000);
+    }
+
+    fn sync_ab_test_request(&mut self) {
+        let state = &mut self.diagnostics.ab_test;
+        state.request.pings = state.pings_input.parse::<u32>().unwrap_or(10).clamp(1, 100);
+    }
+
+    fn sync_wifi_request(&mut self) {
+        let state = &mut self.diagnostics.wifi;
+        let (min, max) = crate::WIFI_INTERVAL_SECS_RANGE;
+        state.request.interval_secs = state
+            .interval_input
+            .parse::<u64>()
+            .unwrap_or(crate::DEFAULT_WIFI_INTERVAL_SECS)
+            .clamp(min, max);
+        if let Some((name, guid)) = state.adapters.get(state.selected_adapter) {
+            state.request.adapter = name.clone();
+            state.request.guid = guid.clone();
+        }
+    }
+
+    /// List the wireless adapters, keeping the chosen one selected.
+    pub(crate) fn sync_wifi_adapters(&mut self) {
+        let state = &mut self.diagnostics.wifi;
+        state.adapters = self
+            .adapters
+            .items
+            .iter()
+            .filter(|adapter| adapter.is_wireless())
+            .map(|adapter| (adapter.name.clone(), adapter.guid.clone()))
+            .collect();
+        state.selected_adapter = state
+            .adapters
+            .iter()
+            .position(|(name, _)| *name == state.request.adapter)
+            .unwrap_or(0);
+        self.sync_wifi_request();
     }
 
     fn sync_port_scan_request(&mut self) {
@@ -1714,9 +4223,15 @@ impl AppModel {
                 .unwrap_or(300)
                 .clamp(20, 10_000),
             concurrency: self.scan_concurrency.clamp(1, 1_024),
+            jump_host: self.jump_host(),
         };
     }
 
+    /// The jump host for requests, when one is set.
+    fn jump_host(&self) -> Option<String> {
+        (!self.jump_host.is_empty()).then(|| self.jump_host.clone())
+    }
+
     fn lan_speed_config_count(&self) -> usize {
         if self.diagnostics.lan_speed.persist.mode != "client" {
             2
@@ -1764,7 +4279,7 @@ impl AppModel {
         };
     }
 
-    fn sync_link_quality_adapters(&mut self) {
+    pub(crate) fn sync_link_quality_adapters(&mut self) {
         self.stash_link_quality_params();
         let selected_key = self
             .diagnostics
@@ -1805,7 +4320,7 @@ impl AppModel {
         self.load_selected_link_quality_params();
     }
 
-    fn sync_scanner_cidr(&mut self, adapters: &[AdapterInfo]) {
+    pub(crate) fn sync_scanner_cidr(&mut self, adapters: &[AdapterInfo]) {
         if !self.scanner.auto_cidr {
             return;
         }
@@ -1931,25 +4446,35 @@ impl AppModel {
             DiagnosticTool::PortScan => self.sync_port_scan_request(),
             DiagnosticTool::LinkQuality => self.sync_link_quality_request(),
             DiagnosticTool::LanSpeed => self.sync_lan_speed_request(),
+            DiagnosticTool::Mtu => self.sync_mtu_request(),
+            DiagnosticTool::Dscp => self.sync_dscp_request(),
+            DiagnosticTool::Multicast => self.sync_multicast_request(),
+            DiagnosticTool::Upnp => self.sync_upnp_request(),
+            DiagnosticTool::Nat => self.sync_nat_request(),
+            DiagnosticTool::Ipv6 => self.sync_ipv6_request(),
+            DiagnosticTool::DnsBench => self.sync_dns_bench_request(),
+            DiagnosticTool::DnsFilter => self.sync_dns_filter_request(),
+            DiagnosticTool::Mail => self.sync_mail_request(),
+            DiagnosticTool::Voip => self.sync_voip_request(),
+            DiagnosticTool::Gaming => self.sync_gaming_request(),
+            DiagnosticTool::Cloud => self.sync_cloud_request(),
+            DiagnosticTool::Wifi => self.sync_wifi_request(),
+            DiagnosticTool::AbTest => self.sync_ab_test_request(),
             _ => {}
         }
     }
 
     fn persist_active_diagnostic(&self) -> Vec<Effect> {
         match self.diagnostics.tool {
-            DiagnosticTool::Ping => vec![Effect::PersistSession(crate::SessionUpdate::Ping(
-                crate::PingPersist {
-                    target: self.diagnostics.ping.request.target.clone(),
-                    interval_ms: self.diagnostics.ping.request.interval_ms,
-                    timeout_ms: self.diagnostics.ping.request.timeout_ms,
-                    packet_size: self.diagnostics.ping.request.packet_size,
-                },
-            ))],
+            DiagnosticTool::Ping => vec![self.persist_ping()],
             DiagnosticTool::Trace => vec![Effect::PersistSession(crate::SessionUpdate::Trace(
                 crate::TracePersist {
                     target: self.diagnostics.trace.request.target.clone(),
                     max_hops: self.diagnostics.trace.max_hops_input.clone(),
                     timeout_ms: self.diagnostics.trace.timeout_input.clone(),
+                    via: self.diagnostics.trace.via_input.clone(),
+                    compare_target: self.diagnostics.trace.compare_target.clone(),
+                    compare_via: self.diagnostics.trace.compare_via.clone(),
                 },
             ))],
             DiagnosticTool::PortScan => vec![Effect::PersistSession(
@@ -1961,11 +4486,117 @@ impl AppModel {
             DiagnosticTool::LanSpeed => vec![Effect::PersistSession(
                 crate::SessionUpdate::LanSpeed(self.diagnostics.lan_speed.persist.clone()),
             )],
+            DiagnosticTool::Mtu => vec![Effect::PersistSession(crate::SessionUpdate::Mtu(
+                crate::MtuPersist {
+                    target: self.diagnostics.mtu.request.target.clone(),
+                    max_mtu: self.diagnostics.mtu.max_mtu_input.clone(),
+                    timeout_ms: self.diagnostics.mtu.timeout_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Dscp => vec![Effect::PersistSession(crate::SessionUpdate::Dscp(
+                crate::DscpPersist {
+                    target: self.diagnostics.dscp.request.target.clone(),
+                    dscp: self.diagnostics.dscp.request.dscp,
+                    max_hops: self.diagnostics.dscp.max_hops_input.clone(),
+                    timeout_ms: self.diagnostics.dscp.timeout_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Multicast => vec![Effect::PersistSession(
+                crate::SessionUpdate::Multicast(crate::MulticastPersist {
+                    mode: self.diagnostics.multicast.request.mode,
+                    group: self.diagnostics.multicast.request.group.clone(),
+                    port: self.diagnostics.multicast.port_input.clone(),
+                    duration_secs: self.diagnostics.multicast.duration_input.clone(),
+                    rate_pps: self.diagnostics.multicast.rate_input.clone(),
+                }),
+            )],
+            DiagnosticTool::Upnp => vec![Effect::PersistSession(crate::SessionUpdate::Upnp(
+                crate::UpnpPersist {
+                    action: self.diagnostics.upnp.request.action,
+                    protocol: self.diagnostics.upnp.request.protocol,
+                    external_port: self.diagnostics.upnp.external_port_input.clone(),
+                    internal_port: self.diagnostics.upnp.internal_port_input.clone(),
+                    lease_secs: self.diagnostics.upnp.lease_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Nat => vec![Effect::PersistSession(crate::SessionUpdate::Nat(
+                crate::NatPersist {
+                    server: self.diagnostics.nat.request.server.clone(),
+                    secondary: self.diagnostics.nat.request.secondary.clone(),
+                    timeout_ms: self.diagnostics.nat.timeout_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Ipv6 => vec![Effect::PersistSession(crate::SessionUpdate::Ipv6(
+                crate::Ipv6Persist {
+                    target: self.diagnostics.ipv6.request.target.clone(),
+                    timeout_ms: self.diagnostics.ipv6.timeout_input.clone(),
+                },
+            ))],
+            DiagnosticTool::DnsBench => vec![Effect::PersistSession(
+                crate::SessionUpdate::DnsBench(crate::DnsBenchPersist {
+                    custom: self.diagnostics.dns_bench.request.custom.clone(),
+                    encrypted: self.diagnostics.dns_bench.request.encrypted,
+                    rounds: self.diagnostics.dns_bench.rounds_input.clone(),
+                    timeout_ms: self.diagnostics.dns_bench.timeout_input.clone(),
+                }),
+            )],
+            DiagnosticTool::DnsFilter => vec![Effect::PersistSession(
+                crate::SessionUpdate::DnsFilter(crate::DnsFilterPersist {
+                    domain: self.diagnostics.dns_filter.request.domain.clone(),
+                    timeout_ms: self.diagnostics.dns_filter.timeout_input.clone(),
+                }),
+            )],
+            DiagnosticTool::Mail => vec![Effect::PersistSession(crate::SessionUpdate::Mail(
+                crate::MailPersist {
+                    host: self.diagnostics.mail.request.host.clone(),
+                    timeout_ms: self.diagnostics.mail.timeout_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Voip => vec![Effect::PersistSession(crate::SessionUpdate::Voip(
+                crate::VoipPersist {
+                    mode: self.diagnostics.voip.request.mode,
+                    peer: self.diagnostics.voip.request.peer.clone(),
+                    port: self.diagnostics.voip.port_input.clone(),
+                    codec: self.diagnostics.voip.request.codec,
+                    duration_secs: self.diagnostics.voip.duration_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Gaming => vec![Effect::PersistSession(crate::SessionUpdate::Gaming(
+                crate::GamingPersist {
+                    preset: self.diagnostics.gaming.request.preset,
+                    custom: self.diagnostics.gaming.request.custom.clone(),
+                    rounds: self.diagnostics.gaming.rounds_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Cloud => vec![Effect::PersistSession(crate::SessionUpdate::Cloud(
+                crate::CloudPersist {
+                    attempts: self.diagnostics.cloud.attempts_input.clone(),
+                    timeout_ms: self.diagnostics.cloud.timeout_input.clone(),
+                },
+            ))],
+            DiagnosticTool::Wifi => vec![Effect::PersistSession(crate::SessionUpdate::Wifi(
+                crate::WifiPersist {
+                    adapter: self.diagnostics.wifi.request.adapter.clone(),
+                    interval_secs: self.diagnostics.wifi.interval_input.clone(),
+                },
+            ))],
+            DiagnosticTool::AbTest => vec![Effect::PersistSession(crate::SessionUpdate::AbTest(
+                crate::AbTestPersist {
+                    first: self.diagnostics.ab_test.request.first.clone(),
+                    second: self.diagnostics.ab_test.request.second.clone(),
+                    target: self.diagnostics.ab_test.request.target.clone(),
+                    pings: self.diagnostics.ab_test.pings_input.clone(),
+                },
+            ))],
             _ => Vec::new(),
         }
     }
 
     fn handle_action(&mut self, action: Action) -> Vec<Effect> {
+        if let Some(effects) = crate::module(crate::ModuleId::Page(self.page)).on_key(self, action)
+        {
+            return effects;
+        }
         use Action::*;
         match action {
             Quit => self.running = false,
@@ -1973,41 +4604,50 @@ impl AppModel {
                 self.language = self.language.toggle();
                 return vec![Effect::PersistPreferences(self.preferences())];
             }
-            NextPage => {
-                self.page = self.page.next();
-                return vec![self.persist_ui_effect()];
-            }
-            PreviousPage => {
-                self.page = self.page.previous();
-                return vec![self.persist_ui_effect()];
+            ToggleRedaction => self.redact = !self.redact,
+            ToggleSplit => return self.toggle_split(),
+            SwitchPane => return self.switch_pane(),
+            NextPage => return self.show_page(self.step_page(true)),
+            PreviousPage => return self.show_page(self.step_page(false)),
+            SelectTab(position) => {
+                let Some(&page) = self.tabs.get(position as usize) else {
+                    return Vec::new();
+                };
+                return self.show_page(page);
             }
             SelectPage(index) => {
-                self.page = Page::from_index(index);
-                return vec![self.persist_ui_effect()];
+                let page = Page::from_index(index);
+                if !self.tabs.contains(&page) {
+                    return Vec::new();
+                }
+                return self.show_page(page);
             }
-            Help => self.show_help = !self.show_help,
-            Back => self.show_help = false,
-            ResetDemo => {
-                *self = Self::default();
-                return vec![Effect::PersistPreferences(self.preferences())];
+            Help => self.open_popup(crate::Popup::Help),
+            ShowUpdate if self.update.available.is_some() => {
+                self.open_popup(crate::Popup::Update);
             }
-            Refresh => {
-                return match self.page {
-                    Page::Dashboard => self.refresh_dashboard(),
-                    Page::Adapters => self.refresh_adapters(),
-                    Page::Traffic => self.refresh_traffic(),
-                    Page::Scanner | Page::Diagnostics | Page::Settings => Vec::new(),
-                };
+            Problems => self.show_problems(),
+            Audit => return self.show_audit(),
+            Calculator => self.open_popup(crate::Popup::Calculator),
+            Timeline => self.open_popup(crate::Popup::Timeline),
+            Search => {
+                self.search.editing = true;
+                self.search.cursor = self.search.query.len();
             }
-            Edit if self.page == Page::Scanner => {
-                self.scanner.editing = true;
-                self.scanner.cursor = self.scanner.cidr.len();
-                self.scanner.history_open = false;
+            Export if self.current_chart().is_some() => {
+                self.export.saved = None;
+                self.export.error = None;
+                self.open_popup(crate::Popup::Export);
             }
-            Confirm | Toggle if self.page == Page::Scanner => return self.toggle_scan(),
-            Confirm if self.page == Page::Diagnostics => {
-                self.diagnostics.focused = true;
-                self.diagnostics.focus = DiagnosticFocus::Menu;
+            Presets => {
+                self.open_popup(crate::Popup::Pipeline);
+                self.pipeline.picking = !self.pipeline.running();
+            }
+            ResetDemo => {
+                let revision_clock = self.revision_clock;
+                *self = Self::default();
+                self.revision_clock = revision_clock;
+                return vec![Effect::PersistPreferences(self.preferences())];
             }
             SelectDiagnostic(index) => {
                 self.diagnostics.tool = DiagnosticTool::from_index(index);
@@ -2019,13 +4659,6 @@ impl AppModel {
             SelectAdapter(index) if !self.adapters.items.is_empty() => {
                 self.adapters.selected = index.min(self.adapters.items.len() - 1)
             }
-            SelectSetting(index) if self.page == Page::Settings => {
-                self.settings_selected = index.min(3);
-                self.settings_just_reset = false;
-            }
-            Edit | Confirm | Toggle if self.page == Page::Adapters => {
-                return self.begin_adapter_edit();
-            }
             Up => {
                 self.navigate(-1);
                 if self.page == Page::Diagnostics {
@@ -2038,16 +4671,20 @@ impl AppModel {
                     return vec![self.persist_ui_effect()];
                 }
             }
-            Left if self.page == Page::Settings => {
-                return self.change_setting(-1, false);
-            }
-            Right if self.page == Page::Settings => {
-                return self.change_setting(1, false);
-            }
-            Confirm | Toggle if self.page == Page::Settings => {
-                return self.change_setting(1, true);
+            PageUp | PageDown | Home | End => {
+                let delta = match action {
+                    PageUp => -(self.page_rows.max(1) as isize),
+                    PageDown => self.page_rows.max(1) as isize,
+                    Home => isize::MIN,
+                    _ => isize::MAX,
+                };
+                self.navigate_clamped(delta);
+                if self.page == Page::Diagnostics {
+                    return vec![self.persist_ui_effect()];
+                }
             }
-            Left
+            Refresh
+            | Left
             | Right
             | Edit
             | Confirm
@@ -2062,56 +4699,100 @@ impl AppModel {
             | SelectSetting(_)
             | FocusDiagnostic(_)
             | SelectDiagnosticField(_, _)
-            | SelectDiagnosticHistory(_) => {}
+            | SelectDiagnosticHistory(_)
+            | ShowUpdate
+            | Export
+            | Back
+            | Answer(_) => {}
         }
         Vec::new()
     }
 
     fn navigate(&mut self, delta: isize) {
+        self.step_selection(delta, wrap);
+    }
+
+    /// Page and Home/End movement: stops at either end of the list instead
+    /// of wrapping around.
+    fn navigate_clamped(&mut self, delta: isize) {
+        self.step_selection(delta, clamp_step);
+    }
+
+    fn step_selection(&mut self, delta: isize, step: fn(usize, usize, isize) -> usize) {
         match self.page {
             Page::Adapters if !self.adapters.items.is_empty() => {
                 self.adapters.selected =
-                    wrap(self.adapters.selected, self.adapters.items.len(), delta)
+                    step(self.adapters.selected, self.adapters.items.len(), delta)
             }
             Page::Traffic if !self.traffic.rows.is_empty() => {
-                self.traffic.selected = wrap(self.traffic.selected, self.traffic.rows.len(), delta)
+                self.traffic.selected = step(self.traffic.selected, self.traffic.rows.len(), delta)
+            }
+            Page::Scanner if self.scanner.view == ScannerView::Bluetooth => {
+                let bluetooth = &mut self.scanner.bluetooth;
+                if !bluetooth.devices.is_empty() {
+                    bluetooth.selected = step(bluetooth.selected, bluetooth.devices.len(), delta);
+                }
             }
             Page::Scanner if !self.scanner.results.is_empty() => {
                 self.scanner.selected =
-                    wrap(self.scanner.selected, self.scanner.results.len(), delta)
+                    step(self.scanner.selected, self.scanner.results.len(), delta)
             }
             Page::Diagnostics => {
                 let current = DiagnosticTool::ALL
                     .iter()
                     .position(|tool| *tool == self.diagnostics.tool)
                     .unwrap_or(0);
-                let index = wrap(current, DiagnosticTool::ALL.len(), delta);
+                let index = step(current, DiagnosticTool::ALL.len(), delta);
                 self.diagnostics.tool = DiagnosticTool::from_index(index as u8);
             }
+            Page::Events if !self.events.entries.is_empty() => {
+                self.events.selected = step(self.events.selected, self.events.entries.len(), delta)
+            }
             Page::Settings => {
-                self.settings_selected = wrap(self.settings_selected, 4, delta);
-                self.settings_just_reset = false;
+                self.settings.selected = step(self.settings.selected, crate::SETTINGS_ITEMS, delta);
+                self.settings.done = None;
+                self.settings.error = None;
             }
             _ => {}
         }
     }
 
-    fn change_setting(&mut self, direction: isize, activate: bool) -> Vec<Effect> {
-        self.settings_just_reset = false;
-        match self.settings_selected {
-            0 => {
+    pub(crate) fn change_setting(&mut self, direction: isize, activate: bool) -> Vec<Effect> {
+        self.settings.done = None;
+        self.settings.error = None;
+        let setting = self.settings.current();
+        match setting.kind() {
+            crate::SettingKind::Number { .. } | crate::SettingKind::Text if activate => {
+                let text = self.setting_text(setting);
+                let cursor = text.len();
+                self.settings.input = Some((text, cursor));
+                return Vec::new();
+            }
+            crate::SettingKind::Number { min, max, step } => {
+                let value = self.setting_number(setting) as i64 + direction as i64 * step as i64;
+                return self
+                    .set_setting_number(setting, value.clamp(min as i64, max as i64) as u64);
+            }
+            crate::SettingKind::Text => return Vec::new(),
+            crate::SettingKind::Action if !activate => return Vec::new(),
+            crate::SettingKind::Choice
+            | crate::SettingKind::Toggle
+            | crate::SettingKind::Action => {}
+        }
+        match setting {
+            crate::SettingId::Language => {
                 self.language = self.language.toggle();
                 vec![Effect::PersistPreferences(self.preferences())]
             }
-            1 => {
-                if direction < 0 {
-                    self.scan_concurrency = self.scan_concurrency.saturating_sub(10).max(10);
+            crate::SettingId::LowPower => {
+                self.low_power = if direction < 0 {
+                    self.low_power.previous()
                 } else {
-                    self.scan_concurrency = (self.scan_concurrency + 10).min(500);
-                }
+                    self.low_power.next()
+                };
                 vec![Effect::PersistPreferences(self.preferences())]
             }
-            2 => {
+            crate::SettingId::Theme => {
                 self.theme = if direction < 0 {
                     self.theme.previous()
                 } else {
@@ -2119,52 +4800,373 @@ impl AppModel {
                 };
                 vec![Effect::PersistPreferences(self.preferences())]
             }
-            3 if activate => {
-                self.reset_session_memory();
-                self.settings_just_reset = true;
-                vec![Effect::PersistSession(crate::SessionUpdate::Reset(
-                    crate::UiPersist {
-                        last_tab: self.page as u8,
-                        last_diag_tool: DiagnosticTool::ALL
-                            .iter()
-                            .position(|tool| *tool == self.diagnostics.tool)
-                            .unwrap_or(0) as u8,
-                    },
-                ))]
+            crate::SettingId::ChartStyle => {
+                self.chart_style = self.chart_style.toggle();
+                vec![Effect::PersistPreferences(self.preferences())]
             }
-            _ => Vec::new(),
+            crate::SettingId::RegionFormat => {
+                self.region_format = if direction < 0 {
+                    self.region_format.previous()
+                } else {
+                    self.region_format.next()
+                };
+                vec![Effect::PersistPreferences(self.preferences())]
+            }
+            crate::SettingId::ClockFormat => {
+                self.clock_format = self.clock_format.toggle();
+                vec![Effect::PersistPreferences(self.preferences())]
+            }
+            crate::SettingId::ShowUtc => {
+                self.show_utc = !self.show_utc;
+                vec![Effect::PersistPreferences(self.preferences())]
+            }
+            crate::SettingId::ColorMode => {
+                self.color_mode = if direction < 0 {
+                    self.color_mode.previous()
+                } else {
+                    self.color_mode.next()
+                };
+                vec![Effect::PersistPreferences(self.preferences())]
+            }
+            crate::SettingId::ScanEnrich => self.toggle_enrichment(),
+            crate::SettingId::ScanAdaptive => {
+                self.scan_adaptive = !self.scan_adaptive;
+                vec![Effect::PersistPreferences(self.preferences())]
+            }
+            crate::SettingId::Offline => {
+                self.offline = !self.offline;
+                let mut effects = vec![Effect::PersistPreferences(self.preferences())];
+                if self.offline {
+                    // Don't leave the last looked-up address on screen.
+                    self.dashboard.snapshot.public_info = None;
+                } else {
+                    effects.extend(self.refresh_dashboard());
+                }
+                effects
+            }
+            crate::SettingId::CheckUpdates => {
+                self.check_updates = !self.check_updates;
+                let mut effects = vec![Effect::PersistPreferences(self.preferences())];
+                if self.check_updates {
+                    effects.extend(self.check_update());
+                } else {
+                    self.update = crate::UpdateState::default();
+                }
+                effects
+            }
+            crate::SettingId::SystemProxy => {
+                self.public_ip_config.use_system_proxy = !self.public_ip_config.use_system_proxy;
+                vec![Effect::PersistPublicIp(self.public_ip_config.clone())]
+            }
+            crate::SettingId::ClearSession => {
+                self.confirm(crate::Confirm::ClearSession);
+                Vec::new()
+            }
+            crate::SettingId::RestoreDefaults => {
+                self.confirm(crate::Confirm::RestoreDefaults);
+                Vec::new()
+            }
+            crate::SettingId::Doctor => self.run_doctor(),
+            crate::SettingId::ScanConcurrency
+            | crate::SettingId::ScanDnsZone
+            | crate::SettingId::JumpHost
+            | crate::SettingId::PingTarget
+            | crate::SettingId::PingInterval
+            | crate::SettingId::PingTimeout
+            | crate::SettingId::PingPacketSize
+            | crate::SettingId::RetainSamples
+            | crate::SettingId::RetainScanRows
+            | crate::SettingId::RetainMemory
+            | crate::SettingId::DropAlert
+            | crate::SettingId::Tabs
+            | crate::SettingId::PublicIpEndpoints
+            | crate::SettingId::WatchTargets => Vec::new(),
         }
     }
 
-    fn reset_session_memory(&mut self) {
-        self.scanner = ScannerState::default();
-        self.diagnostics.ping.request = crate::PingRequest::default();
-        let trace = crate::TracePersist::default();
-        self.diagnostics.trace.request = crate::TraceRequest::default();
-        self.diagnostics.trace.max_hops_input = trace.max_hops;
-        self.diagnostics.trace.timeout_input = trace.timeout_ms;
-        self.diagnostics.port_scan.persist = crate::PortScanPersist::default();
-        self.sync_port_scan_request();
-        self.diagnostics.lan_speed.persist = crate::LanSpeedPersist::default();
-        self.sync_lan_speed_request();
-        self.diagnostics.link_quality.persist = crate::LinkQualityPersist::default();
-        self.diagnostics.link_quality.params = crate::LinkParams::default();
-        self.sync_link_quality_request();
-        self.diagnostics.target_history.clear();
-        self.diagnostics.history_open = false;
-        self.adapter_edit_persist = crate::AdapterEditPersist::default();
-        self.adapter_history.clear();
+    /// Visible tabs in the order the tab bar shows them.
+    pub fn tabs(&self) -> &[Page] {
+        &self.tabs
     }
 
-    fn persist_ui_effect(&self) -> Effect {
-        let diagnostic_index = DiagnosticTool::ALL
-            .iter()
-            .position(|tool| *tool == self.diagnostics.tool)
-            .unwrap_or(0) as u8;
-        Effect::PersistSession(crate::SessionUpdate::Ui(crate::UiPersist {
-            last_tab: self.page as u8,
-            last_diag_tool: diagnostic_index,
-        }))
+    /// The visible tab after (or before) the current one, wrapping. From a
+    /// page that is not in the list, the first (or last) tab.
+    fn step_page(&self, forward: bool) -> Page {
+        let count = self.tabs.len();
+        match self.tabs.iter().position(|page| *page == self.page) {
+            Some(index) if forward => self.tabs[(index + 1) % count],
+            Some(index) => self.tabs[(index + count - 1) % count],
+            None if forward => self.tabs[0],
+            None => self.tabs[count - 1],
+        }
+    }
+
+    /// The public IP lookup configuration shown and edited on the settings
+    /// page.
+    pub fn public_ip_config(&self) -> &crate::PublicIpConfig {
+        &self.public_ip_config
+    }
+
+    pub fn setting_number(&self, setting: crate::SettingId) -> u64 {
+        let ping = &self.diagnostics.ping.request;
+        match setting {
+            crate::SettingId::ScanConcurrency => self.scan_concurrency as u64,
+            crate::SettingId::PingInterval => ping.interval_ms,
+            crate::SettingId::PingTimeout => ping.timeout_ms,
+            crate::SettingId::PingPacketSize => ping.packet_size,
+            crate::SettingId::RetainSamples => self.retention.samples as u64,
+            crate::SettingId::RetainScanRows => self.retention.scan_rows as u64,
+            crate::SettingId::RetainMemory => self.retention.memory_mb,
+            crate::SettingId::DropAlert => self.drop_alert_percent,
+            _ => 0,
+        }
+    }
+
+    fn set_setting_number(&mut self, setting: crate::SettingId, value: u64) -> Vec<Effect> {
+        let ping = &mut self.diagnostics.ping.request;
+        match setting {
+            crate::SettingId::ScanConcurrency => {
+                self.scan_concurrency = value as usize;
+                return vec![Effect::PersistPreferences(self.preferences())];
+            }
+            crate::SettingId::RetainSamples => {
+                self.retention.samples = value as usize;
+                return self.apply_retention();
+            }
+            crate::SettingId::RetainScanRows => {
+                self.retention.scan_rows = value as usize;
+                return self.apply_retention();
+            }
+            crate::SettingId::RetainMemory => {
+                self.retention.memory_mb = value;
+                return self.apply_retention();
+            }
+            crate::SettingId::DropAlert => {
+                self.drop_alert_percent = value;
+                return vec![Effect::PersistPreferences(self.preferences())];
+            }
+            crate::SettingId::PingInterval => ping.interval_ms = value,
+            crate::SettingId::PingTimeout => ping.timeout_ms = value,
+            crate::SettingId::PingPacketSize => ping.packet_size = value,
+            _ => return Vec::new(),
+        }
+        vec![self.persist_ping()]
+    }
+
+    /// Lowered limits take effect at once rather than on the next sample.
+    fn apply_retention(&mut self) -> Vec<Effect> {
+        self.enforce_retention();
+        vec![Effect::PersistPreferences(self.preferences())]
+    }
+
+    /// Initial editor text for a number or text row.
+    fn setting_text(&self, setting: crate::SettingId) -> String {
+        match setting {
+            crate::SettingId::ScanDnsZone => self.scanner.dns_zone.clone(),
+            crate::SettingId::JumpHost => self.jump_host.clone(),
+            crate::SettingId::PingTarget => self.diagnostics.ping.request.target.clone(),
+            crate::SettingId::Tabs => crate::format_tabs(&self.tabs),
+            crate::SettingId::PublicIpEndpoints => {
+                crate::format_endpoints(&self.public_ip_config.endpoints)
+            }
+            crate::SettingId::WatchTargets => crate::format_watch_targets(&self.watch.targets()),
+            _ => self.setting_number(setting).to_string(),
+        }
+    }
+
+    fn handle_settings_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        if let Some(action @ Action::SelectSetting(_)) = input.action() {
+            self.settings.input = None;
+            self.settings.error = None;
+            return self.handle_action(action);
+        }
+        let Some(key) = input.key() else {
+            return Vec::new();
+        };
+        let setting = self.settings.current();
+        match key.code {
+            KeyCode::Esc => {
+                self.settings.input = None;
+                self.settings.error = None;
+            }
+            KeyCode::Enter => {
+                let Some((text, _)) = self.settings.input.as_ref() else {
+                    return Vec::new();
+                };
+                match self.commit_setting(setting, &text.clone()) {
+                    Ok(effects) => {
+                        self.settings.input = None;
+                        self.settings.error = None;
+                        return effects;
+                    }
+                    Err(error) => self.settings.error = Some(error),
+                }
+            }
+            code => {
+                let numeric = matches!(setting.kind(), crate::SettingKind::Number { .. });
+                let limit = if matches!(
+                    setting,
+                    crate::SettingId::Tabs
+                        | crate::SettingId::PublicIpEndpoints
+                        | crate::SettingId::WatchTargets
+                ) {
+                    512
+                } else {
+                    64
+                };
+                if let Some((text, cursor)) = self.settings.input.as_mut()
+                    && edit_ascii(text, cursor, code, limit, |character| {
+                        if numeric {
+                            character.is_ascii_digit()
+                        } else {
+                            character.is_ascii_graphic() || character == ' '
+                        }
+                    })
+                {
+                    self.settings.error = None;
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Validate a typed value and apply it; nothing changes on error.
+    fn commit_setting(
+        &mut self,
+        setting: crate::SettingId,
+        text: &str,
+    ) -> Result<Vec<Effect>, crate::SettingError> {
+        match setting.kind() {
+            crate::SettingKind::Number { min, max, .. } => {
+                let value = crate::parse_setting_number(text, min, max)?;
+                Ok(self.set_setting_number(setting, value))
+            }
+            _ if setting == crate::SettingId::ScanDnsZone => {
+                self.scanner.dns_zone = crate::parse_dns_zone(text)?;
+                Ok(self.persist_scanner())
+            }
+            _ if setting == crate::SettingId::JumpHost => {
+                self.jump_host = crate::parse_jump_host(text)?;
+                self.sync_port_scan_request();
+                Ok(vec![Effect::PersistJumpHost(self.jump_host.clone())])
+            }
+            _ if setting == crate::SettingId::PingTarget => {
+                self.diagnostics.ping.request.target = crate::parse_setting_host(text)?;
+                Ok(vec![self.persist_ping()])
+            }
+            _ if setting == crate::SettingId::Tabs => {
+                self.tabs = crate::parse_tabs(text)?;
+                Ok(vec![Effect::PersistTabs(self.tabs.clone())])
+            }
+            _ if setting == crate::SettingId::PublicIpEndpoints => {
+                self.public_ip_config.endpoints = crate::parse_endpoints(text)?;
+                Ok(vec![Effect::PersistPublicIp(self.public_ip_config.clone())])
+            }
+            _ if setting == crate::SettingId::WatchTargets => {
+                let targets = crate::parse_watch_targets(text)?;
+                self.watch.set_targets(targets.clone());
+                // New targets are checked on the next tick.
+                self.watch.next_check_ms = self.elapsed_ms;
+                Ok(vec![Effect::PersistWatch(targets)])
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Put every setting on the page back to its default. The interface
+    /// language is kept: whoever reads the page chose it. So are the watch
+    /// targets, which are the user's list rather than a preference.
+    fn restore_default_settings(&mut self) -> Vec<Effect> {
+        let defaults = crate::ConfigData::default();
+        self.theme = defaults.theme;
+        self.color_mode = defaults.color_mode;
+        self.chart_style = defaults.chart_style;
+        self.region_format = defaults.region_format;
+        self.clock_format = defaults.clock_format;
+        self.show_utc = defaults.show_utc;
+        self.scan_concurrency = defaults.scan_concurrency;
+        self.scan_adaptive = defaults.scan_adaptive;
+        self.retention = defaults.retention;
+        self.drop_alert_percent = defaults.drop_alert_percent;
+        self.low_power = defaults.low_power;
+        self.check_updates = defaults.check_updates;
+        self.update = crate::UpdateState::default();
+        self.public_ip_config = defaults.public_ip;
+        self.jump_host = defaults.jump_host;
+        self.sync_port_scan_request();
+        self.tabs = all_tabs();
+        let scanner = crate::ScannerPersist::default();
+        self.scanner.enrich = scanner.enrich;
+        self.scanner.dns_zone = scanner.dns_zone;
+        let ping = crate::PingPersist::default();
+        let request = &mut self.diagnostics.ping.request;
+        request.target = ping.target;
+        request.interval_ms = ping.interval_ms;
+        request.timeout_ms = ping.timeout_ms;
+        request.packet_size = ping.packet_size;
+        let mut effects = vec![Effect::PersistPreferences(self.preferences())];
+        effects.extend(self.persist_scanner());
+        effects.push(self.persist_ping());
+        effects.push(Effect::PersistPublicIp(self.public_ip_config.clone()));
+        effects.push(Effect::PersistJumpHost(self.jump_host.clone()));
+        effects.push(Effect::PersistTabs(self.tabs.clone()));
+        effects
+    }
+
+    fn reset_session_memory(&mut self) {
+        // Device notes are the user's data, not remembered parameters.
+        self.scanner = ScannerState {
+            inventory: std::mem::take(&mut self.scanner.inventory),
+            ..ScannerState::default()
+        };
+        self.scanner.revision = next_revision(&mut self.revision_clock);
+        self.diagnostics.ping.request = crate::PingRequest::default();
+        self.diagnostics.ping.claimed_location.clear();
+        self.diagnostics.ping.own_location.clear();
+        let trace = crate::TracePersist::default();
+        self.diagnostics.trace.request = crate::TraceRequest::default();
+        self.diagnostics.trace.max_hops_input = trace.max_hops;
+        self.diagnostics.trace.timeout_input = trace.timeout_ms;
+        self.diagnostics.trace.via_input = trace.via;
+        self.diagnostics.trace.compare_target = trace.compare_target;
+        self.diagnostics.trace.compare_via = trace.compare_via;
+        self.diagnostics.mtu = MtuState::default();
+        self.diagnostics.dscp = DscpState::default();
+        self.diagnostics.multicast = MulticastState::default();
+        self.diagnostics.upnp = UpnpState::default();
+        self.diagnostics.nat = NatState::default();
+        self.diagnostics.ipv6 = Ipv6State::default();
+        self.diagnostics.dns_bench = DnsBenchState::default();
+        self.diagnostics.dns_filter = DnsFilterState::default();
+        self.diagnostics.mail = MailState::default();
+        self.diagnostics.voip = VoipState::default();
+        self.diagnostics.gaming = GamingState::default();
+        self.diagnostics.cloud = CloudState::default();
+        self.diagnostics.wifi = WifiState::default();
+        self.diagnostics.ab_test = AbTestState::default();
+        self.sync_wifi_adapters();
+        self.diagnostics.port_scan.persist = crate::PortScanPersist::default();
+        self.sync_port_scan_request();
+        self.diagnostics.lan_speed.persist = crate::LanSpeedPersist::default();
+        self.sync_lan_speed_request();
+        self.diagnostics.link_quality.persist = crate::LinkQualityPersist::default();
+        self.diagnostics.link_quality.params = crate::LinkParams::default();
+        self.sync_link_quality_request();
+        self.diagnostics.target_history.clear();
+        self.diagnostics.history_open = false;
+        self.adapter_edit_persist = crate::AdapterEditPersist::default();
+        self.adapter_history.clear();
+    }
+
+    fn persist_ui_effect(&self) -> Effect {
+        let diagnostic_index = DiagnosticTool::ALL
+            .iter()
+            .position(|tool| *tool == self.diagnostics.tool)
+            .unwrap_or(0) as u8;
+        Effect::PersistSession(crate::SessionUpdate::Ui(crate::UiPersist {
+            last_tab: self.page as u8,
+            last_diag_tool: diagnostic_index,
+        }))
     }
 
     fn next_job(&mut self, tool: ToolKind) -> JobId {
@@ -2175,1726 +5177,5919 @@ impl AppModel {
         }
     }
 
+    /// Initialise background modules and the page shown first. Other pages
+    /// are initialised on their first visit.
     pub fn bootstrap_effects(&mut self) -> Vec<Effect> {
-        let mut effects = self.refresh_dashboard();
-        effects.extend(self.refresh_adapters());
-        effects.extend(self.refresh_traffic());
+        let mut effects = Vec::new();
+        for module in crate::MODULES.iter().filter(|module| module.background()) {
+            if let crate::ModuleId::Page(page) = module.id() {
+                self.started.push(page);
+            }
+            effects.extend(module.init(self));
+        }
+        if !self.started.contains(&self.page) {
+            effects.extend(self.enter(self.page));
+        }
+        effects.extend(self.check_update());
         effects
     }
 
-    pub fn refresh_adapters(&mut self) -> Vec<Effect> {
-        self.refresh_adapters_inner()
+    /// Look up the latest release when the user opted in and the app is not
+    /// offline.
+    fn check_update(&mut self) -> Vec<Effect> {
+        if !self.check_updates || self.offline {
+            return Vec::new();
+        }
+        let job = self.next_job(ToolKind::Update);
+        self.update.job = Some(job);
+        vec![Effect::CheckUpdate {
+            job,
+            use_system_proxy: self.public_ip_config.use_system_proxy,
+        }]
     }
 
-    pub fn refresh_traffic(&mut self) -> Vec<Effect> {
-        self.refresh_traffic_inner()
+    /// Open the self-diagnostics popup and run the checks again.
+    fn run_doctor(&mut self) -> Vec<Effect> {
+        let job = self.next_job(ToolKind::Doctor);
+        self.doctor.job = Some(job);
+        self.doctor.checks.clear();
+        self.open_popup(crate::Popup::Doctor);
+        vec![Effect::RunDoctor { job }]
     }
 
-    fn refresh_dashboard(&mut self) -> Vec<Effect> {
-        let job = self.next_job(ToolKind::Dashboard);
-        self.dashboard.job = Some(job);
-        self.dashboard.status = TaskStatus::Running;
-        self.dashboard.error = None;
-        vec![Effect::RefreshDashboard {
-            job,
-            request: crate::DashboardRequest {
-                public_ip: self.public_ip_config.clone(),
-            },
-        }]
+    /// Put `popup` on top of the stack, moving it there if it is already
+    /// open.
+    pub fn open_popup(&mut self, popup: crate::Popup) {
+        self.popups.retain(|open| *open != popup);
+        self.popups.push(popup);
     }
 
-    fn refresh_adapters_inner(&mut self) -> Vec<Effect> {
-        let job = self.next_job(ToolKind::Adapters);
-        self.adapters.job = Some(job);
-        self.adapters.status = TaskStatus::Running;
-        self.adapters.error = None;
-        vec![Effect::RefreshAdapters { job }]
+    pub fn close_popup(&mut self, popup: crate::Popup) {
+        self.popups.retain(|open| *open != popup);
     }
 
-    fn refresh_traffic_inner(&mut self) -> Vec<Effect> {
-        let job = self.next_job(ToolKind::Traffic);
-        self.traffic.job = Some(job);
-        self.traffic.status = TaskStatus::Running;
-        self.traffic.error = None;
-        vec![Effect::RefreshTraffic { job }]
+    pub fn popup_open(&self, popup: crate::Popup) -> bool {
+        self.popups.contains(&popup)
     }
 
-    fn toggle_scan(&mut self) -> Vec<Effect> {
-        if let Some(job) = self.scanner.job {
-            self.scanner.status = TaskStatus::Done;
-            self.scanner.job = None;
-            return vec![Effect::CancelScan(job)];
+    /// Show problems found while loading the configuration until dismissed.
+    pub fn show_config_issues(&mut self, issues: Vec<crate::ConfigIssue>) {
+        if !issues.is_empty() {
+            self.open_popup(crate::Popup::ConfigIssues);
         }
+        self.config_issues = issues;
+    }
 
-        let job = self.next_job(ToolKind::Scanner);
-        let cidr = self.scanner.cidr.trim().to_string();
-        if !cidr.is_empty() {
-            self.scanner.history.retain(|old| old != &cidr);
-            self.scanner.history.insert(0, cidr.clone());
-            self.scanner.history.truncate(15);
+    fn show_problems(&mut self) {
+        self.problems.mark_seen();
+        self.open_popup(crate::Popup::Problems);
+    }
+
+    /// Only the popup on top reacts; the page underneath waits.
+    fn handle_popup_input(&mut self, popup: crate::Popup, input: InputEvent) -> Vec<Effect> {
+        use crate::Popup;
+        match popup {
+            Popup::Confirm(_) => return self.handle_confirmation_input(input),
+            Popup::Problems => return self.handle_problems_input(input),
+            Popup::Pipeline => return self.handle_pipeline_input(input),
+            Popup::Export => return self.handle_export_input(input),
+            Popup::Audit => return self.handle_audit_input(input),
+            Popup::Calculator => return self.handle_calculator_input(input),
+            Popup::Timeline => return self.handle_timeline_input(input),
+            _ => {}
         }
-        self.scanner.editing = false;
-        self.scanner.history_open = false;
-        self.scanner.job = Some(job);
-        self.scanner.status = TaskStatus::Running;
-        self.scanner.current = 0;
-        self.scanner.total = 0;
-        self.scanner.results.clear();
-        vec![
-            Effect::PersistSession(crate::SessionUpdate::Scanner(crate::ScannerPersist {
-                cidr: self.scanner.cidr.clone(),
-            })),
-            Effect::PersistSession(crate::SessionUpdate::CidrHistory(
-                self.scanner.history.clone(),
-            )),
-            Effect::StartScan {
-                job,
-                request: ScanRequest {
-                    cidr: self.scanner.cidr.clone(),
-                    concurrency: self.scan_concurrency,
-                },
-            },
-        ]
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
+            }
+            Some(Action::Refresh) if popup == Popup::Doctor && !self.doctor.running() => {
+                return self.run_doctor();
+            }
+            Some(Action::Confirm | Action::Back) => self.close_popup(popup.clone()),
+            Some(Action::Help) if popup == Popup::Help => self.close_popup(Popup::Help),
+            Some(Action::ShowUpdate) if popup == Popup::Update => self.close_popup(Popup::Update),
+            _ => {}
+        }
+        if popup == Popup::ConfigIssues && !self.popup_open(popup) {
+            self.config_issues.clear();
+        }
+        Vec::new()
     }
 
-    fn toggle_diagnostic(&mut self) -> Vec<Effect> {
-        if let Some(job) = self.diagnostics.active_common().job {
-            let common = self.diagnostics.active_common_mut();
-            common.job = None;
-            common.status = TaskStatus::Done;
-            return vec![stop_effect(job)];
+    /// The history of the tool in view as a chart, if it has any.
+    pub fn current_chart(&self) -> Option<crate::Chart> {
+        if self.page != Page::Diagnostics {
+            return None;
+        }
+        let diagnostics = &self.diagnostics;
+        match diagnostics.tool {
+            DiagnosticTool::Ping => crate::ping_chart(
+                diagnostics.ping.request.target.trim(),
+                &diagnostics.ping.samples,
+            ),
+            DiagnosticTool::PublicSpeed => crate::speed_chart(
+                diagnostics.public_speed.server.as_deref(),
+                &diagnostics.public_speed.samples,
+            ),
+            _ => None,
         }
+    }
 
-        self.sync_active_diagnostic_request();
-        let target = match self.diagnostics.tool {
-            DiagnosticTool::Ping => Some(self.diagnostics.ping.request.target.trim().to_string()),
-            DiagnosticTool::Trace => Some(self.diagnostics.trace.request.target.trim().to_string()),
-            DiagnosticTool::PortScan => {
-                Some(self.diagnostics.port_scan.request.target.trim().to_string())
+    fn handle_export_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        let idle = self.export.job.is_none();
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
             }
-            DiagnosticTool::LinkQuality => Some(
-                self.diagnostics
-                    .link_quality
-                    .request
-                    .target
-                    .trim()
-                    .to_string(),
-            ),
-            DiagnosticTool::LanSpeed
-                if self.diagnostics.lan_speed.request.mode == crate::LanSpeedMode::Client =>
+            Some(Action::Left | Action::Right | Action::Up | Action::Down | Action::Toggle)
+                if idle =>
             {
-                Some(self.diagnostics.lan_speed.request.peer.trim().to_string())
+                self.export.format = self.export.format.toggle();
             }
-            _ => None,
-        };
-        if target.as_ref().is_some_and(String::is_empty) {
-            let common = self.diagnostics.active_common_mut();
-            let error = crate::RuntimeError::new(
-                crate::RuntimeErrorCode::InvalidRequest,
-                "target cannot be empty",
-            );
-            common.status = TaskStatus::Failed(error.message.clone());
-            common.detail = error.message.clone();
-            common.error = Some(error);
-            return Vec::new();
+            Some(Action::Confirm) if idle => {
+                let Some(chart) = self.current_chart() else {
+                    return Vec::new();
+                };
+                let job = self.next_job(ToolKind::ChartExport);
+                self.export.job = Some(job);
+                self.export.saved = None;
+                self.export.error = None;
+                return vec![Effect::ExportChart {
+                    job,
+                    chart,
+                    format: self.export.format,
+                }];
+            }
+            // A write in progress still reports into the popup when reopened.
+            Some(Action::Back | Action::Export) => self.close_popup(crate::Popup::Export),
+            _ => {}
         }
-        if self.diagnostics.tool == DiagnosticTool::LinkQuality
-            && self.diagnostics.link_quality.request.adapter.is_none()
-        {
-            let common = self.diagnostics.active_common_mut();
-            let error = crate::RuntimeError::new(
-                crate::RuntimeErrorCode::InvalidRequest,
-                "no active physical IPv4 adapter is available",
-            );
-            common.status = TaskStatus::Failed(error.message.clone());
-            common.detail = error.message.clone();
-            common.error = Some(error);
+        Vec::new()
+    }
+
+    /// Ask before `confirm` runs; the question goes on top of any popup.
+    pub(crate) fn confirm(&mut self, confirm: crate::Confirm) {
+        self.open_popup(crate::Popup::Confirm(crate::Confirmation::new(confirm)));
+    }
+
+    fn handle_confirmation_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        // Long enough for any hostname a scan can be aimed at.
+        const TYPED_LIMIT: usize = 253;
+
+        let Some(crate::Popup::Confirm(top)) = self.popups.last_mut() else {
             return Vec::new();
-        }
-        if self.diagnostics.tool == DiagnosticTool::PortScan {
-            let request = &self.diagnostics.port_scan.request;
-            if request.start_port == 0
-                || request.end_port == 0
-                || request.start_port > request.end_port
-            {
-                let common = self.diagnostics.active_common_mut();
-                let error = crate::RuntimeError::new(
-                    crate::RuntimeErrorCode::InvalidRequest,
-                    "port range must be between 1 and 65535 and start must not exceed end",
-                );
-                common.status = TaskStatus::Failed(error.message.clone());
-                common.detail = error.message.clone();
-                common.error = Some(error);
+        };
+        // A question answered by typing takes printable keys as text, so
+        // neither Y nor a bound letter answers it by accident.
+        if top.confirm.phrase().is_some()
+            && let Some(key) = input.key()
+            && !key.modifiers.control
+        {
+            let typed = match key.code {
+                KeyCode::Char(character) if !character.is_control() => {
+                    if top.typed.len() < TYPED_LIMIT {
+                        top.typed.push(character);
+                    }
+                    true
+                }
+                KeyCode::Backspace => top.typed.pop().is_some(),
+                _ => false,
+            };
+            if typed {
+                top.yes = top.can_accept();
                 return Vec::new();
             }
         }
-        if self.diagnostics.tool == DiagnosticTool::LanSpeed
-            && self.diagnostics.lan_speed.request.port == 0
-        {
-            let common = self.diagnostics.active_common_mut();
-            let error = crate::RuntimeError::new(
-                crate::RuntimeErrorCode::InvalidRequest,
-                "LAN speed port must be between 1 and 65535",
-            );
-            common.status = TaskStatus::Failed(error.message.clone());
-            common.detail = error.message.clone();
-            common.error = Some(error);
-            return Vec::new();
+        let answer = match input {
+            InputEvent::Key(crate::KeyEvent {
+                code: KeyCode::Char(key @ ('y' | 'Y' | 'n' | 'N')),
+                modifiers: crate::Modifiers { control: false, .. },
+            }) => Some(key.eq_ignore_ascii_case(&'y')),
+            _ => match input.action() {
+                Some(
+                    action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction),
+                ) => return self.handle_action(action),
+                Some(
+                    Action::Left
+                    | Action::Right
+                    | Action::Up
+                    | Action::Down
+                    | Action::NextPage
+                    | Action::PreviousPage,
+                ) => {
+                    top.yes = !top.yes && top.can_accept();
+                    None
+                }
+                Some(Action::Confirm) => Some(top.yes),
+                Some(Action::Answer(yes)) if !yes || top.can_accept() => Some(yes),
+                Some(Action::Answer(_)) => None,
+                Some(Action::Back) => Some(false),
+                _ => None,
+            },
+        };
+        match answer {
+            Some(yes) => {
+                let Some(crate::Popup::Confirm(confirmation)) = self.popups.pop() else {
+                    unreachable!("checked above");
+                };
+                if yes {
+                    self.run_confirmed(confirmation.confirm)
+                } else {
+                    Vec::new()
+                }
+            }
+            None => Vec::new(),
         }
+    }
 
-        let tool = ToolKind::from(self.diagnostics.tool);
-        let job = self.next_job(tool);
-        let common = self.diagnostics.active_common_mut();
-        common.job = Some(job);
-        common.status = TaskStatus::Running;
-        common.error = None;
-        common.progress = 0;
-        common.primary.clear();
-        common.detail.clear();
-        common.log.clear();
-
-        let effect = match self.diagnostics.tool {
-            DiagnosticTool::Ping => {
-                self.diagnostics.ping.samples.clear();
-                self.diagnostics.ping.summary = None;
-                Effect::StartPing {
-                    job,
-                    request: self.diagnostics.ping.request.clone(),
-                }
-            }
-            DiagnosticTool::Trace => {
-                self.diagnostics.trace.hops.clear();
-                Effect::StartTrace {
-                    job,
-                    request: self.diagnostics.trace.request.clone(),
-                }
-            }
-            DiagnosticTool::PortScan => {
-                self.diagnostics.port_scan.scanned = 0;
-                self.diagnostics.port_scan.total = 0;
-                self.diagnostics.port_scan.open_ports.clear();
-                self.diagnostics.port_scan.request.concurrency = self.scan_concurrency;
-                Effect::StartPortScan {
-                    job,
-                    request: self.diagnostics.port_scan.request.clone(),
-                }
+    fn run_confirmed(&mut self, confirm: crate::Confirm) -> Vec<Effect> {
+        match confirm {
+            crate::Confirm::ClearSession => {
+                self.reset_session_memory();
+                self.settings.done = Some(crate::SettingId::ClearSession);
+                vec![Effect::PersistSession(crate::SessionUpdate::Reset(
+                    crate::UiPersist {
+                        last_tab: self.page as u8,
+                        last_diag_tool: DiagnosticTool::ALL
+                            .iter()
+                            .position(|tool| *tool == self.diagnostics.tool)
+                            .unwrap_or(0) as u8,
+                    },
+                ))]
             }
-            DiagnosticTool::PublicSpeed => {
-                self.diagnostics.public_speed.samples.clear();
-                self.diagnostics.public_speed.summary = None;
-                Effect::StartPublicSpeed {
-                    job,
-                    request: self.diagnostics.public_speed.request.clone(),
-                }
+            crate::Confirm::RestoreDefaults => {
+                self.settings.done = Some(crate::SettingId::RestoreDefaults);
+                self.restore_default_settings()
             }
-            DiagnosticTool::LinkQuality => {
-                self.diagnostics.link_quality.samples.clear();
-                self.diagnostics.link_quality.summary = None;
-                self.diagnostics.link_quality.snapshot = None;
-                Effect::StartLinkQuality {
-                    job,
-                    request: self.diagnostics.link_quality.request.clone(),
-                }
+            // The tool may have been switched or started meanwhile; then the
+            // answer no longer applies.
+            crate::Confirm::DeleteUpnpMapping { .. }
+                if self.diagnostics.tool == DiagnosticTool::Upnp
+                    && self.diagnostics.upnp.common.job.is_none() =>
+            {
+                self.start_diagnostic()
             }
-            DiagnosticTool::LanSpeed => {
-                self.diagnostics.lan_speed.samples.clear();
-                self.diagnostics.lan_speed.summary = None;
-                self.diagnostics.lan_speed.endpoint.clear();
-                self.diagnostics.lan_speed.phase = None;
-                Effect::StartLanSpeed {
-                    job,
-                    request: self.diagnostics.lan_speed.request.clone(),
+            crate::Confirm::DeleteUpnpMapping { .. } => Vec::new(),
+            // Each start checks the guard again, now quiet for this target,
+            // and gives up if the scan was started meanwhile.
+            crate::Confirm::ScanTarget { scan, target, .. } => {
+                self.scan_guard.approve(target);
+                match scan {
+                    crate::GuardedScan::LanScan if self.scanner.job.is_none() => self.toggle_scan(),
+                    crate::GuardedScan::PortScan
+                        if self.diagnostics.tool == DiagnosticTool::PortScan
+                            && self.diagnostics.port_scan.common.job.is_none() =>
+                    {
+                        self.toggle_diagnostic()
+                    }
+                    crate::GuardedScan::Pipeline(preset) => self.run_pipeline(preset),
+                    _ => Vec::new(),
                 }
             }
-        };
-        let mut effects = vec![effect];
-        if let Some(target) = target {
-            self.diagnostics
-                .target_history
-                .retain(|value| value != &target);
-            self.diagnostics.target_history.insert(0, target);
-            self.diagnostics.target_history.truncate(15);
-            effects.push(Effect::PersistSession(crate::SessionUpdate::TargetHistory(
-                self.diagnostics.target_history.clone(),
-            )));
         }
-        effects
     }
 
-    fn handle_runtime(&mut self, event: RuntimeEvent) {
-        match event {
-            RuntimeEvent::DashboardUpdated(snapshot) => self.dashboard.snapshot = *snapshot,
-            RuntimeEvent::DashboardRefreshFinished { job, snapshot }
-                if self.dashboard.job == Some(job) =>
-            {
-                self.dashboard.snapshot = *snapshot;
-                self.dashboard.status = TaskStatus::Done;
-                self.dashboard.error = None;
-                self.dashboard.job = None;
+    /// Ask for `target` to be typed back before `scan` reaches beyond the
+    /// local network or covers a very large range. Returns whether it asked;
+    /// a target that does not parse is left for the start to report.
+    fn guard_scan(&mut self, scan: crate::GuardedScan, target: &str) -> bool {
+        // One host is spelled the same whether it came as an address or a /32.
+        let Ok(target) =
+            crate::Target::parse_host(target).or_else(|_| crate::Target::parse(target))
+        else {
+            return false;
+        };
+        let Some(concern) = self.scan_guard.check(&target) else {
+            return false;
+        };
+        self.confirm(crate::Confirm::ScanTarget {
+            scan,
+            target: target.to_string(),
+            concern,
+        });
+        true
+    }
+
+    fn handle_problems_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        if let InputEvent::Key(crate::KeyEvent {
+            code: KeyCode::Delete,
+            ..
+        }) = input
+        {
+            self.problems.clear();
+            return Vec::new();
+        }
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
             }
-            RuntimeEvent::DashboardRefreshFailed {
-                job,
-                snapshot,
-                error,
-            } if self.dashboard.job == Some(job) => {
-                self.dashboard.snapshot = *snapshot;
-                self.dashboard.status = TaskStatus::Failed(error.message.clone());
-                self.dashboard.error = Some(error);
-                self.dashboard.job = None;
-            }
-            RuntimeEvent::DashboardRefreshCancelled { job } if self.dashboard.job == Some(job) => {
-                self.dashboard.status = TaskStatus::Done;
-                self.dashboard.job = None;
-            }
-            RuntimeEvent::AdaptersUpdated(adapters) => {
-                self.sync_scanner_cidr(&adapters);
-                self.adapters.items = adapters;
-                self.sync_link_quality_adapters();
-            }
-            RuntimeEvent::TrafficUpdated(rows) => {
-                self.sync_dashboard_traffic(&rows);
-                self.traffic.rows = rows;
-            }
-            RuntimeEvent::AdaptersRefreshFinished { job, adapters }
-                if self.adapters.job == Some(job) =>
-            {
-                let selected_name = self
-                    .adapters
-                    .items
-                    .get(self.adapters.selected)
-                    .map(|adapter| adapter.name.as_str());
-                self.adapters.selected = selected_name
-                    .and_then(|name| adapters.iter().position(|adapter| adapter.name == name))
-                    .unwrap_or(0)
-                    .min(adapters.len().saturating_sub(1));
-                self.sync_scanner_cidr(&adapters);
-                self.adapters.items = adapters;
-                self.sync_link_quality_adapters();
-                self.adapters.status = TaskStatus::Done;
-                self.adapters.error = None;
-                self.adapters.job = None;
-            }
-            RuntimeEvent::AdaptersRefreshFailed { job, error }
-                if self.adapters.job == Some(job) =>
-            {
-                self.adapters.status = TaskStatus::Failed(error.message.clone());
-                self.adapters.error = Some(error);
-                self.adapters.job = None;
-            }
-            RuntimeEvent::AdaptersRefreshCancelled { job } if self.adapters.job == Some(job) => {
-                self.adapters.status = TaskStatus::Done;
-                self.adapters.job = None;
-            }
-            RuntimeEvent::TrafficRefreshFinished { job, rows } if self.traffic.job == Some(job) => {
-                self.sync_dashboard_traffic(&rows);
-                let selected_name = self
-                    .traffic
-                    .rows
-                    .get(self.traffic.selected)
-                    .map(|row| row.name.as_str());
-                self.traffic.selected = selected_name
-                    .and_then(|name| rows.iter().position(|row| row.name == name))
-                    .unwrap_or(0)
-                    .min(rows.len().saturating_sub(1));
-                self.traffic.rows = rows;
-                self.traffic.status = TaskStatus::Done;
-                self.traffic.error = None;
-                self.traffic.job = None;
-            }
-            RuntimeEvent::TrafficRefreshFailed { job, error } if self.traffic.job == Some(job) => {
-                self.traffic.status = TaskStatus::Failed(error.message.clone());
-                self.traffic.error = Some(error);
-                self.traffic.job = None;
-            }
-            RuntimeEvent::TrafficRefreshCancelled { job } if self.traffic.job == Some(job) => {
-                self.traffic.status = TaskStatus::Done;
-                self.traffic.job = None;
-            }
-            RuntimeEvent::AdapterConfigStarted { job }
-                if self.adapters.edit.as_ref().and_then(|edit| edit.job) == Some(job) =>
-            {
-                if let Some(edit) = self.adapters.edit.as_mut() {
-                    edit.phase = AdapterEditPhase::Applying;
-                }
+            Some(Action::Up) => self.problems.selected = self.problems.selected.saturating_sub(1),
+            Some(Action::Down) => {
+                self.problems.selected =
+                    (self.problems.selected + 1).min(self.problems.items.len().saturating_sub(1));
             }
-            RuntimeEvent::AdapterConfigFinished { job, outcome }
-                if self.adapters.edit.as_ref().and_then(|edit| edit.job) == Some(job) =>
-            {
-                if let Some(edit) = self.adapters.edit.as_mut() {
-                    edit.phase = AdapterEditPhase::Succeeded(outcome);
-                    edit.job = None;
-                }
+            Some(Action::Confirm | Action::Back | Action::Problems) => {
+                self.close_popup(crate::Popup::Problems);
             }
-            RuntimeEvent::AdapterConfigFailed { job, error }
-                if self.adapters.edit.as_ref().and_then(|edit| edit.job) == Some(job) =>
-            {
-                if let Some(edit) = self.adapters.edit.as_mut() {
-                    edit.phase = AdapterEditPhase::Failed(error);
-                    edit.job = None;
-                }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Open the audit; the DHCP probe runs the first time, later ones on
+    /// request.
+    fn show_audit(&mut self) -> Vec<Effect> {
+        self.audit.scroll = 0;
+        self.open_popup(crate::Popup::Audit);
+        if self.audit.dhcp.is_none() && self.audit.dhcp_job.is_none() {
+            return self.probe_dhcp();
+        }
+        Vec::new()
+    }
+
+    fn probe_dhcp(&mut self) -> Vec<Effect> {
+        let job = self.next_job(ToolKind::Dhcp);
+        self.audit.dhcp_job = Some(job);
+        self.audit.dhcp_error = None;
+        vec![Effect::ProbeDhcp { job }]
+    }
+
+    /// Audit findings over the current scan results and DHCP probe.
+    pub fn audit_findings(&self) -> Vec<crate::AuditFinding> {
+        crate::audit_findings(
+            &self.scanner.results,
+            self.audit.dhcp.as_deref().unwrap_or_default(),
+            self.dashboard
+                .snapshot
+                .active_interface
+                .as_ref()
+                .and_then(|interface| interface.gateway.as_deref()),
+        )
+    }
+
+    fn handle_audit_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
             }
-            RuntimeEvent::ScanStarted { job, total } if self.scanner.job == Some(job) => {
-                self.scanner.total = total;
-                self.scanner.status = TaskStatus::Running;
+            Some(Action::Up) => self.audit.scroll = self.audit.scroll.saturating_sub(1),
+            Some(Action::Down) => {
+                self.audit.scroll =
+                    (self.audit.scroll + 1).min(self.audit_findings().len().saturating_sub(1));
             }
-            RuntimeEvent::ScanProgress {
-                job,
-                current,
-                total,
-            } if self.scanner.job == Some(job) => {
-                self.scanner.current = current;
-                self.scanner.total = total;
-            }
-            RuntimeEvent::ScanHostFound { job, host } if self.scanner.job == Some(job) => {
-                let selected_ip = self
+            Some(Action::Refresh) if self.audit.dhcp_job.is_none() => return self.probe_dhcp(),
+            Some(Action::Export) if self.audit.export_job.is_none() => {
+                let findings = self.audit_findings();
+                let scanned_at = self
                     .scanner
                     .results
-                    .get(self.scanner.selected)
-                    .map(|host| host.ip.clone());
-                self.scanner.results.push(host);
-                self.scanner.results.sort_by(scan_host_ip_order);
-                if let Some(selected_ip) = selected_ip {
-                    self.scanner.selected = self
-                        .scanner
-                        .results
-                        .iter()
-                        .position(|host| host.ip == selected_ip)
-                        .unwrap_or(0);
-                }
-            }
-            RuntimeEvent::ScanFinished { job } | RuntimeEvent::ScanCancelled { job }
-                if self.scanner.job == Some(job) =>
-            {
-                self.scanner.status = TaskStatus::Done;
-                self.scanner.job = None;
-            }
-            RuntimeEvent::PingStarted { job } if self.diagnostics.ping.common.job == Some(job) => {
-                self.diagnostics.ping.common.status = TaskStatus::Running;
-            }
-            RuntimeEvent::PingSample { job, sample }
-                if self.diagnostics.ping.common.job == Some(job) =>
-            {
-                let primary = sample.latency_ms.map_or_else(
-                    || {
-                        format!(
-                            "seq={} {}",
-                            sample.sequence,
-                            if self.language == Language::Zh {
-                                "请求超时"
-                            } else {
-                                "request timed out"
-                            }
-                        )
-                    },
-                    |latency| {
-                        format!(
-                            "{} seq={} bytes={} ttl={} time={}ms",
-                            if self.language == Language::Zh {
-                                "回复"
-                            } else {
-                                "Reply"
-                            },
-                            sample.sequence,
-                            sample.size,
-                            sample.ttl.map_or_else(|| "—".into(), |ttl| ttl.to_string()),
-                            latency
-                        )
-                    },
-                );
-                let common = &mut self.diagnostics.ping.common;
-                common.progress = (sample.sequence.saturating_add(1) * 12).min(99) as u8;
-                common.primary = primary.clone();
-                common.detail = format!(
-                    "{} / {} received · {:.1}% loss · avg {:?} ms",
-                    sample.received, sample.sent, sample.loss_percent, sample.average_ms
-                );
-                common.log.push(primary);
-                self.diagnostics.ping.summary = Some(crate::PingSummary {
-                    sent: sample.sent,
-                    received: sample.received,
-                    min_ms: sample.min_ms,
-                    average_ms: sample.average_ms,
-                    max_ms: sample.max_ms,
-                    loss_percent: sample.loss_percent,
-                });
-                self.diagnostics.ping.samples.push(sample);
-            }
-            RuntimeEvent::PingFinished { job, summary }
-                if self.diagnostics.ping.common.job == Some(job) =>
-            {
-                finish_common(
-                    &mut self.diagnostics.ping.common,
-                    format!(
-                        "{} received / {} sent · {:.1}% loss",
-                        summary.received, summary.sent, summary.loss_percent
-                    ),
+                    .iter()
+                    .map(|host| host.seen_at.as_str())
+                    .filter(|at| !at.is_empty())
+                    .max()
+                    .map(|at| self.display_time(at));
+                let markdown = crate::audit_markdown(
+                    &findings,
+                    self.scanner.results.len(),
+                    scanned_at.as_deref(),
+                    self.audit.dhcp.as_ref().map(Vec::len),
+                    self.language,
                 );
-                self.diagnostics.ping.summary = Some(summary);
-            }
-            RuntimeEvent::PingFailed { job, error }
-                if self.diagnostics.ping.common.job == Some(job) =>
-            {
-                fail_common(&mut self.diagnostics.ping.common, error);
-            }
-            RuntimeEvent::TraceStarted { job }
-                if self.diagnostics.trace.common.job == Some(job) =>
-            {
-                self.diagnostics.trace.common.status = TaskStatus::Running;
+                let job = self.next_job(ToolKind::AuditExport);
+                self.audit.export_job = Some(job);
+                self.audit.saved = None;
+                self.audit.export_error = None;
+                return vec![Effect::ExportAudit { job, markdown }];
             }
-            RuntimeEvent::TraceHop { job, hop }
-                if self.diagnostics.trace.common.job == Some(job) =>
-            {
-                let primary = format!("hop {}: {}", hop.ttl, hop.address.as_deref().unwrap_or("*"));
-                let common = &mut self.diagnostics.trace.common;
-                common.progress = hop
-                    .ttl
-                    .saturating_mul(100)
-                    .checked_div(self.diagnostics.trace.request.max_hops.max(1))
-                    .unwrap_or(0)
-                    .min(99);
-                common.primary = primary.clone();
-                common.detail = hop
-                    .latency_ms
-                    .map_or_else(|| "timeout".into(), |latency| format!("{latency} ms"));
-                common.log.push(primary);
-                self.diagnostics.trace.hops.push(hop);
-            }
-            RuntimeEvent::TraceFinished { job, hops }
-                if self.diagnostics.trace.common.job == Some(job) =>
-            {
-                finish_common(
-                    &mut self.diagnostics.trace.common,
-                    format!("route completed in {hops} hops"),
-                );
+            // The probe and a write in progress still report when reopened.
+            Some(Action::Confirm | Action::Back | Action::Audit) => {
+                self.close_popup(crate::Popup::Audit);
             }
-            RuntimeEvent::TraceFailed { job, error }
-                if self.diagnostics.trace.common.job == Some(job) =>
-            {
-                fail_common(&mut self.diagnostics.trace.common, error);
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn handle_timeline_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
             }
-            RuntimeEvent::PortScanStarted { job, total }
-                if self.diagnostics.port_scan.common.job == Some(job) =>
-            {
-                self.diagnostics.port_scan.total = total;
-                self.diagnostics.port_scan.common.status = TaskStatus::Running;
+            Some(Action::Left | Action::Up) => self.timeline.zoom(false),
+            Some(Action::Right | Action::Down) => self.timeline.zoom(true),
+            Some(Action::Confirm | Action::Back | Action::Timeline) => {
+                self.close_popup(crate::Popup::Timeline);
             }
-            RuntimeEvent::PortScanProgress {
-                job,
-                scanned,
-                total,
-            } if self.diagnostics.port_scan.common.job == Some(job) => {
-                let state = &mut self.diagnostics.port_scan;
-                state.scanned = scanned;
-                state.total = total;
-                state.common.progress = scanned
-                    .saturating_mul(100)
-                    .checked_div(total.max(1))
-                    .unwrap_or(0)
-                    .min(100) as u8;
-                state.common.primary = format!("scanned {scanned} ports");
-                state.common.detail = format!("{} open", state.open_ports.len());
-            }
-            RuntimeEvent::PortScanOpen { job, result }
-                if self.diagnostics.port_scan.common.job == Some(job) =>
-            {
-                let state = &mut self.diagnostics.port_scan;
-                if let Err(index) = state
-                    .open_ports
-                    .binary_search_by_key(&result.port, |entry| entry.port)
-                {
-                    state.open_ports.insert(index, result.clone());
-                }
-                let line = format!("open: {} ({})", result.port, result.service);
-                state.common.primary = line.clone();
-                state.common.log.push(line);
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Keys edit the input line; its results are computed while drawing.
+    fn handle_calculator_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        if let Some(key) = input.key()
+            && !key.modifiers.control
+            && edit_ascii(
+                &mut self.calculator.input,
+                &mut self.calculator.cursor,
+                key.code,
+                crate::CALCULATOR_INPUT_LIMIT,
+                crate::calculator_char,
+            )
+        {
+            return Vec::new();
+        }
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
             }
-            RuntimeEvent::PortScanFinished {
-                job,
-                scanned,
-                total,
-                cancelled,
-            } if self.diagnostics.port_scan.common.job == Some(job) => {
-                let state = &mut self.diagnostics.port_scan;
-                state.scanned = scanned;
-                state.total = total;
-                finish_common(
-                    &mut state.common,
-                    format!(
-                        "{} · {} open ports",
-                        if cancelled { "cancelled" } else { "completed" },
-                        state.open_ports.len()
-                    ),
-                );
+            Some(Action::Back | Action::Calculator) => {
+                self.close_popup(crate::Popup::Calculator);
             }
-            RuntimeEvent::PortScanFailed { job, error }
-                if self.diagnostics.port_scan.common.job == Some(job) =>
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Whether `job` is the one its tool is waiting for, so a failure of an
+    /// older generation is not reported.
+    fn job_is_current(&self, job: JobId) -> bool {
+        let current = match job.tool {
+            ToolKind::Dashboard => self.dashboard.job,
+            ToolKind::Adapters => self.adapters.job,
+            ToolKind::AdapterEdit => self.adapters.edit.as_ref().and_then(|edit| edit.job),
+            ToolKind::Traffic => self.traffic.job,
+            ToolKind::Scanner => self.scanner.job,
+            ToolKind::Bluetooth => self.scanner.bluetooth.job,
+            ToolKind::Update => self.update.job,
+            ToolKind::Doctor => self.doctor.job,
+            ToolKind::WebCheck => self.pipeline.job,
+            ToolKind::ChartExport => self.export.job,
+            ToolKind::Dhcp => self.audit.dhcp_job,
+            ToolKind::AuditExport => self.audit.export_job,
+            ToolKind::Asn => self.diagnostics.trace.asn.job,
+            ToolKind::Cve => self.scanner.cve.job,
+            ToolKind::Watch => self.watch.job,
+            ToolKind::Trace
+                if self
+                    .diagnostics
+                    .trace
+                    .comparison
+                    .as_ref()
+                    .is_some_and(|comparison| comparison.job == Some(job)) =>
             {
-                fail_common(&mut self.diagnostics.port_scan.common, error);
+                Some(job)
             }
-            RuntimeEvent::PublicSpeedStarted { job, server }
-                if self.diagnostics.public_speed.common.job == Some(job) =>
-            {
-                self.diagnostics.public_speed.server = server;
-                self.diagnostics.public_speed.common.status = TaskStatus::Running;
+            tool => DiagnosticTool::ALL
+                .into_iter()
+                .find(|diagnostic| ToolKind::from(*diagnostic) == tool)
+                .and_then(|diagnostic| self.diagnostics.common(diagnostic).job),
+        };
+        current == Some(job)
+    }
+
+    /// Whether the view that reports `tool`'s own failures is on screen.
+    fn tool_is_visible(&self, tool: ToolKind) -> bool {
+        match tool {
+            ToolKind::Dashboard | ToolKind::Watch => self.shows(Page::Dashboard),
+            ToolKind::Adapters | ToolKind::AdapterEdit => self.shows(Page::Adapters),
+            ToolKind::Traffic => self.shows(Page::Traffic),
+            ToolKind::Scanner | ToolKind::Bluetooth | ToolKind::Cve => self.shows(Page::Scanner),
+            ToolKind::Update => self.popup_open(crate::Popup::Update),
+            ToolKind::Doctor => self.popup_open(crate::Popup::Doctor),
+            ToolKind::WebCheck => self.popup_open(crate::Popup::Pipeline),
+            ToolKind::ChartExport => self.popup_open(crate::Popup::Export),
+            ToolKind::Dhcp | ToolKind::AuditExport => self.popup_open(crate::Popup::Audit),
+            ToolKind::Asn => self.tool_is_visible(ToolKind::Trace),
+            tool => self.shows(Page::Diagnostics) && ToolKind::from(self.diagnostics.tool) == tool,
+        }
+    }
+
+    fn handle_pipeline_input(&mut self, input: InputEvent) -> Vec<Effect> {
+        let picking = self.pipeline.picking;
+        match input.action() {
+            Some(action @ (Action::Quit | Action::ToggleLanguage | Action::ToggleRedaction)) => {
+                return self.handle_action(action);
             }
-            RuntimeEvent::PublicSpeedSample { job, sample }
-                if self.diagnostics.public_speed.common.job == Some(job) =>
-            {
-                let state = &mut self.diagnostics.public_speed;
-                state.common.progress = sample
-                    .elapsed_ms
-                    .saturating_mul(100)
-                    .checked_div(state.request.max_duration_ms.max(1))
-                    .unwrap_or(0)
-                    .min(99) as u8;
-                state.common.primary = format!("{} B/s", sample.bytes_per_second);
-                state.common.detail = format!("{} bytes", sample.bytes);
-                state.samples.push(sample);
-            }
-            RuntimeEvent::PublicSpeedFinished { job, summary }
-                if self.diagnostics.public_speed.common.job == Some(job) =>
-            {
-                finish_common(
-                    &mut self.diagnostics.public_speed.common,
-                    format!(
-                        "average {} B/s · peak {} B/s",
-                        summary.average_bytes_per_second, summary.peak_bytes_per_second
-                    ),
-                );
-                self.diagnostics.public_speed.summary = Some(summary);
+            Some(Action::Up) if picking => {
+                self.pipeline.selected = self.pipeline.selected.saturating_sub(1);
             }
-            RuntimeEvent::PublicSpeedFailed { job, error }
-                if self.diagnostics.public_speed.common.job == Some(job) =>
-            {
-                fail_common(&mut self.diagnostics.public_speed.common, error);
+            Some(Action::Down) if picking => {
+                self.pipeline.selected =
+                    (self.pipeline.selected + 1).min(crate::PipelinePreset::ALL.len() - 1);
             }
-            RuntimeEvent::LinkQualityStarted { job, snapshot }
-                if self.diagnostics.link_quality.common.job == Some(job) =>
-            {
-                self.diagnostics.link_quality.common.status = TaskStatus::Running;
-                self.diagnostics.link_quality.snapshot = Some(*snapshot);
+            Some(Action::Confirm) if picking => {
+                return self.run_pipeline(crate::PipelinePreset::ALL[self.pipeline.selected]);
             }
-            RuntimeEvent::LinkQualitySample { job, sample }
-                if self.diagnostics.link_quality.common.job == Some(job) =>
-            {
-                let state = &mut self.diagnostics.link_quality;
-                state.common.progress = sample
-                    .sequence
-                    .saturating_mul(100)
-                    .checked_div(state.request.count.max(1))
-                    .unwrap_or(0)
-                    .min(99) as u8;
-                state.common.primary = format!("latency={:?} ms", sample.latency_ms);
-                state.common.detail = format!(
-                    "loss={:.1}% · rssi={:?}",
-                    sample.loss_percent, sample.rssi_dbm
-                );
-                if let Some(snapshot) = &state.snapshot {
-                    state.summary =
-                        Some(crate::link_quality::summary_from_sample(snapshot, &sample));
+            Some(Action::Toggle) if self.pipeline.running() => return self.stop_pipeline(),
+            Some(Action::Refresh) if !picking && !self.pipeline.running() => {
+                if let Some(preset) = self.pipeline.preset {
+                    return self.run_pipeline(preset);
                 }
-                state.samples.push(sample);
-            }
-            RuntimeEvent::LinkQualityFinished { job, summary }
-                if self.diagnostics.link_quality.common.job == Some(job) =>
-            {
-                finish_common(
-                    &mut self.diagnostics.link_quality.common,
-                    format!(
-                        "score {:.0}/100 · loss {:.1}%",
-                        summary.score, summary.loss_percent
-                    ),
-                );
-                self.diagnostics.link_quality.summary = Some(summary);
-            }
-            RuntimeEvent::LinkQualityFailed { job, error }
-                if self.diagnostics.link_quality.common.job == Some(job) =>
-            {
-                fail_common(&mut self.diagnostics.link_quality.common, error);
-            }
-            RuntimeEvent::LanSpeedStarted { job, endpoint }
-                if self.diagnostics.lan_speed.common.job == Some(job) =>
-            {
-                let state = &mut self.diagnostics.lan_speed;
-                state.common.status = TaskStatus::Running;
-                state.endpoint = endpoint;
-            }
-            RuntimeEvent::LanSpeedStatus { job, phase }
-                if self.diagnostics.lan_speed.common.job == Some(job) =>
-            {
-                self.diagnostics.lan_speed.phase = Some(phase);
-            }
-            RuntimeEvent::LanSpeedSample { job, sample }
-                if self.diagnostics.lan_speed.common.job == Some(job) =>
-            {
-                let state = &mut self.diagnostics.lan_speed;
-                state.common.progress = sample
-                    .elapsed_ms
-                    .saturating_mul(100)
-                    .checked_div(state.request.duration_secs.saturating_mul(1_000).max(1))
-                    .unwrap_or(0)
-                    .min(99) as u8;
-                state.common.primary =
-                    format!("tx={} bps · rx={} bps", sample.tx_bps, sample.rx_bps);
-                state.common.detail = format!(
-                    "loss={:?} · jitter={:?}",
-                    sample.loss_percent, sample.jitter_ms
-                );
-                state.samples.push(sample);
-            }
-            RuntimeEvent::LanSpeedFinished { job, summary }
-                if self.diagnostics.lan_speed.common.job == Some(job) =>
-            {
-                finish_common(
-                    &mut self.diagnostics.lan_speed.common,
-                    format!(
-                        "tx={} bytes · rx={} bytes",
-                        summary.tx_bytes, summary.rx_bytes
-                    ),
-                );
-                self.diagnostics.lan_speed.summary = Some(summary);
-                self.diagnostics.lan_speed.phase = None;
             }
-            RuntimeEvent::LanSpeedFailed { job, error }
-                if self.diagnostics.lan_speed.common.job == Some(job) =>
-            {
-                fail_common(&mut self.diagnostics.lan_speed.common, error);
+            // The preset keeps running in the background; F4 shows it again.
+            Some(Action::Confirm | Action::Back | Action::Presets) => {
+                self.close_popup(crate::Popup::Pipeline);
             }
             _ => {}
         }
+        Vec::new()
     }
 
-    fn sync_dashboard_traffic(&mut self, rows: &[TrafficRow]) {
-        let Some(interface) = self.dashboard.snapshot.active_interface.as_ref() else {
-            return;
-        };
-        let Some(row) = rows.iter().find(|row| row.name == interface.name) else {
-            return;
-        };
-        self.dashboard.snapshot.download_bps = row.download_bps;
-        self.dashboard.snapshot.upload_bps = row.upload_bps;
-        self.dashboard.snapshot.total_download = row.total_download;
-        self.dashboard.snapshot.total_upload = row.total_upload;
+    /// Target of a preset: the trace or port scan target when that tool is
+    /// selected, the ping target otherwise.
+    fn pipeline_target(&self) -> String {
+        let diagnostics = &self.diagnostics;
+        match diagnostics.tool {
+            DiagnosticTool::Trace => diagnostics.trace.request.target.trim().to_string(),
+            DiagnosticTool::PortScan => diagnostics.port_scan.persist.target.trim().to_string(),
+            _ => diagnostics.ping.request.target.trim().to_string(),
+        }
     }
-}
 
-fn scan_host_ip_order(left: &ScanHost, right: &ScanHost) -> Ordering {
-    match (left.ip.parse::<IpAddr>(), right.ip.parse::<IpAddr>()) {
-        (Ok(left), Ok(right)) => left.cmp(&right),
-        (Ok(_), Err(_)) => Ordering::Less,
-        (Err(_), Ok(_)) => Ordering::Greater,
-        (Err(_), Err(_)) => left.ip.cmp(&right.ip),
+    fn run_pipeline(&mut self, preset: crate::PipelinePreset) -> Vec<Effect> {
+        let target = self.pipeline_target();
+        if target.is_empty() {
+            return Vec::new();
+        }
+        if preset.stages().contains(&crate::PipelineStage::PortScan)
+            && self.tool_available(DiagnosticTool::PortScan)
+            && self.guard_scan(crate::GuardedScan::Pipeline(preset), &target)
+        {
+            return Vec::new();
+        }
+        self.pipeline.preset = Some(preset);
+        self.pipeline.target = target;
+        self.pipeline.picking = false;
+        self.pipeline.stages = preset
+            .stages()
+            .iter()
+            .map(|&stage| crate::StageReport {
+                stage,
+                status: crate::StageStatus::Pending,
+            })
+            .collect();
+        self.start_pipeline_from(0)
     }
-}
 
-fn active_network_cidr(adapter: &AdapterInfo) -> Option<String> {
-    let (_, prefix) = adapter.cidr.as_deref()?.rsplit_once('/')?;
-    let prefix = prefix.parse::<u8>().ok().filter(|prefix| *prefix <= 32)?;
-    let address = adapter.ipv4.parse::<std::net::Ipv4Addr>().ok()?;
-    let mask = if prefix == 0 {
-        0
-    } else {
-        u32::MAX << (32 - prefix)
-    };
-    let network = std::net::Ipv4Addr::from(u32::from(address) & mask);
-    Some(format!("{network}/{prefix}"))
-}
+    /// Start the first stage from `index` on that gets going; stages that
+    /// fail or have nothing to do on the spot are recorded and passed over.
+    fn start_pipeline_from(&mut self, mut index: usize) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        while index < self.pipeline.stages.len() {
+            effects.extend(self.start_pipeline_stage(index));
+            if self.pipeline.stages[index].status == crate::StageStatus::Running {
+                break;
+            }
+            index += 1;
+        }
+        effects
+    }
 
-fn finish_common(common: &mut DiagnosticCommonState, summary: String) {
-    common.status = TaskStatus::Done;
-    common.error = None;
-    common.progress = 100;
-    common.detail = summary;
-    common.job = None;
-}
+    fn start_pipeline_stage(&mut self, index: usize) -> Vec<Effect> {
+        use crate::StageStatus;
 
-fn fail_common(common: &mut DiagnosticCommonState, error: crate::RuntimeError) {
-    common.status = TaskStatus::Failed(error.message.clone());
-    common.detail = error.message.clone();
-    common.error = Some(error);
-    common.job = None;
-}
+        let stage = self.pipeline.stages[index].stage;
+        let target = self.pipeline.target.clone();
+        let Some(tool) = stage.tool() else {
+            let open = self.pipeline.open_ports();
+            let ports = crate::web_check_ports(open.as_deref());
+            if ports.is_empty() {
+                self.pipeline.stages[index].status = StageStatus::Skipped;
+                return Vec::new();
+            }
+            let job = self.next_job(ToolKind::WebCheck);
+            self.pipeline.job = Some(job);
+            self.pipeline.stages[index].status = StageStatus::Running;
+            return vec![Effect::StartWebCheck {
+                job,
+                request: crate::WebCheckRequest {
+                    target,
+                    ports,
+                    timeout_ms: crate::WEB_CHECK_TIMEOUT_MS,
+                    jump_host: self.jump_host(),
+                },
+            }];
+        };
 
-fn link_quality_adapter(adapter: &AdapterInfo) -> crate::LinkQualityAdapter {
-    let key = if !adapter.guid.is_empty() {
-        adapter.guid.clone()
-    } else if !adapter.mac.is_empty() {
-        adapter.mac.clone()
-    } else {
-        adapter.name.clone()
-    };
-    crate::LinkQualityAdapter {
-        key,
-        name: adapter.name.clone(),
-        guid: adapter.guid.clone(),
-        ipv4: adapter.ipv4.clone(),
-        is_wifi: adapter.kind.to_ascii_lowercase().contains("ieee80211") || adapter.ssid.is_some(),
-        link_speed_bps: adapter.link_speed_bps,
-        mac: adapter.mac.clone(),
+        // Without ICMP the ping and trace stages are passed over, not failed,
+        // and so is a port scan the policy turns off.
+        if !self.tool_available(tool) {
+            self.pipeline.stages[index].status = StageStatus::Skipped;
+            return Vec::new();
+        }
+        // The stage runs through the tool itself, with its own settings.
+        self.diagnostics.tool = tool;
+        match tool {
+            DiagnosticTool::Ping => self.diagnostics.ping.request.target = target,
+            DiagnosticTool::Trace => self.diagnostics.trace.request.target = target,
+            DiagnosticTool::PortScan => self.diagnostics.port_scan.persist.target = target,
+            _ => {}
+        }
+        let mut effects = Vec::new();
+        if self.diagnostics.active_common().job.is_some() {
+            effects.extend(self.toggle_diagnostic());
+        }
+        effects.extend(self.toggle_diagnostic());
+        let common = self.diagnostics.active_common();
+        self.pipeline.stages[index].status = if common.job.is_some() {
+            StageStatus::Running
+        } else {
+            StageStatus::Failed(common.detail.clone())
+        };
+        effects
     }
-}
 
-fn wrap(current: usize, len: usize, delta: isize) -> usize {
-    (current as isize + delta).rem_euclid(len as isize) as usize
-}
+    /// Record the running tool stage once its tool has stopped, and start
+    /// the next one.
+    fn advance_pipeline(&mut self) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        let Some(index) = self.pipeline.current() else {
+            return effects;
+        };
+        let stage = self.pipeline.stages[index].stage;
+        let Some(tool) = stage.tool() else {
+            return effects;
+        };
+        let ping = &mut self.diagnostics.ping;
+        if tool == DiagnosticTool::Ping
+            && ping.samples.len() >= crate::PIPELINE_PING_SAMPLES
+            && let Some(job) = ping.common.job
+        {
+            let summary = crate::summarize_pings(&ping.samples);
+            finish_common(
+                &mut ping.common,
+                format!(
+                    "{} received / {} sent · {:.1}% loss",
+                    summary.received, summary.sent, summary.loss_percent
+                ),
+            );
+            ping.summary = Some(summary);
+            effects.push(stop_effect(job));
+        }
+        if self.diagnostics.common(tool).job.is_some() {
+            return effects;
+        }
+        self.pipeline.stages[index].status = self.pipeline_stage_result(tool);
+        effects.extend(self.start_pipeline_from(index + 1));
+        effects
+    }
 
-fn stop_effect(job: JobId) -> Effect {
-    match job.tool {
-        ToolKind::Dashboard => unreachable!("dashboard refreshes are not diagnostic jobs"),
-        ToolKind::Adapters | ToolKind::AdapterEdit | ToolKind::Traffic => {
-            unreachable!("read-only refreshes are not diagnostic jobs")
+    fn pipeline_stage_result(&self, tool: DiagnosticTool) -> crate::StageStatus {
+        use crate::{StageOutcome, StageStatus};
+
+        if let TaskStatus::Failed(message) = &self.diagnostics.common(tool).status {
+            return StageStatus::Failed(message.clone());
         }
-        ToolKind::Ping => Effect::StopPing(job),
-        ToolKind::Trace => Effect::StopTrace(job),
-        ToolKind::PortScan => Effect::StopPortScan(job),
-        ToolKind::PublicSpeed => Effect::StopPublicSpeed(job),
-        ToolKind::LinkQuality => Effect::StopLinkQuality(job),
-        ToolKind::LanSpeed => Effect::StopLanSpeed(job),
-        ToolKind::Scanner => Effect::CancelScan(job),
+        let diagnostics = &self.diagnostics;
+        StageStatus::Done(match tool {
+            DiagnosticTool::Ping => {
+                let summary = diagnostics
+                    .ping
+                    .summary
+                    .clone()
+                    .unwrap_or_else(|| crate::summarize_pings(&diagnostics.ping.samples));
+                StageOutcome::Ping {
+                    sent: summary.sent,
+                    received: summary.received,
+                    average_ms: summary.average_ms,
+                }
+            }
+            DiagnosticTool::Trace => StageOutcome::Trace {
+                hops: diagnostics.trace.hops.len(),
+                last: diagnostics
+                    .trace
+                    .hops
+                    .iter()
+                    .rev()
+                    .find_map(|hop| hop.address.clone()),
+            },
+            _ => StageOutcome::PortScan {
+                open: diagnostics.port_scan.open_ports.clone(),
+            },
+        })
     }
-}
 
-fn non_empty(value: &str) -> Option<String> {
-    let value = value.trim();
-    (!value.is_empty()).then(|| value.to_string())
-}
+    fn handle_web_check_event(&mut self, event: RuntimeEvent) -> Vec<Effect> {
+        use crate::{StageOutcome, StageStatus};
 
-fn edit_ascii(
-    value: &mut String,
-    cursor: &mut usize,
-    code: KeyCode,
-    allow: impl Fn(char) -> bool,
-) -> bool {
-    *cursor = (*cursor).min(value.len());
-    match code {
-        KeyCode::Left => *cursor = cursor.saturating_sub(1),
-        KeyCode::Right => *cursor = (*cursor + 1).min(value.len()),
-        KeyCode::Home => *cursor = 0,
-        KeyCode::End => *cursor = value.len(),
-        KeyCode::Backspace if *cursor > 0 => {
-            *cursor -= 1;
-            value.remove(*cursor);
-            return true;
+        let status = match event {
+            RuntimeEvent::WebCheckFinished { job, results } if self.pipeline.job == Some(job) => {
+                StageStatus::Done(StageOutcome::Web(results))
+            }
+            RuntimeEvent::WebCheckFailed { job, error } if self.pipeline.job == Some(job) => {
+                StageStatus::Failed(error.message)
+            }
+            _ => return Vec::new(),
+        };
+        self.pipeline.job = None;
+        let Some(index) = self.pipeline.current() else {
+            return Vec::new();
+        };
+        self.pipeline.stages[index].status = status;
+        self.start_pipeline_from(index + 1)
+    }
+
+    /// Stop the running stage and skip the rest.
+    fn stop_pipeline(&mut self) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        if let Some(index) = self.pipeline.current() {
+            match self.pipeline.stages[index].stage.tool() {
+                Some(tool) => {
+                    let common = self.diagnostics.common_mut(tool);
+                    if let Some(job) = common.job.take() {
+                        common.status = TaskStatus::Done;
+                        effects.push(stop_effect(job));
+                        if tool == DiagnosticTool::Trace {
+                            effects.extend(self.stop_trace_comparison(Some(job)));
+                        }
+                    }
+                }
+                None => effects.extend(self.pipeline.job.take().map(stop_effect)),
+            }
         }
-        KeyCode::Delete if *cursor < value.len() => {
-            value.remove(*cursor);
-            return true;
+        for report in &mut self.pipeline.stages {
+            if matches!(
+                report.status,
+                crate::StageStatus::Pending | crate::StageStatus::Running
+            ) {
+                report.status = crate::StageStatus::Skipped;
+            }
         }
-        KeyCode::Char(character) if allow(character) && value.len() < 64 => {
-            value.insert(*cursor, character);
-            *cursor += 1;
-            return true;
+        effects
+    }
+
+    fn handle_update_event(&mut self, event: RuntimeEvent) {
+        match event {
+            RuntimeEvent::UpdateCheckFinished { job, release } if self.update.job == Some(job) => {
+                self.update.job = None;
+                self.update.available =
+                    crate::is_newer_version(&release.version, crate::CURRENT_VERSION)
+                        .then_some(release);
+            }
+            RuntimeEvent::UpdateCheckFailed { job, .. } if self.update.job == Some(job) => {
+                self.update.job = None;
+            }
+            _ => {}
         }
-        _ => {}
     }
-    false
-}
 
-fn adapter_defaults(adapter: &AdapterInfo) -> AdapterEditParams {
-    let prefix = adapter
-        .cidr
-        .as_deref()
-        .and_then(|cidr| cidr.rsplit_once('/'))
-        .and_then(|(_, prefix)| prefix.parse::<u8>().ok());
-    let mask = prefix.and_then(prefix_to_mask).unwrap_or_default();
-    let gateway = adapter
-        .ipv4
-        .parse::<std::net::Ipv4Addr>()
-        .ok()
-        .map(|ip| {
-            let mut octets = ip.octets();
-            octets[3] = 1;
-            std::net::Ipv4Addr::from(octets).to_string()
-        })
-        .unwrap_or_default();
-    AdapterEditParams {
-        use_dhcp: adapter.dhcp_enabled,
-        ip: adapter.ipv4.clone(),
-        mask,
-        gateway,
-        dns1: "8.8.8.8".into(),
-        dns2: "8.8.4.4".into(),
+    /// Effects to run on quit, once the runtime has stopped every job and its
+    /// last events were applied: save what the session gathered since the
+    /// last write, such as devices found by a scan that was still running.
+    pub fn shutdown_effects(&self) -> Vec<Effect> {
+        let mut effects = vec![self.persist_inventory(), self.persist_events()];
+        // A kiosk's page is not the tab to come back to.
+        if self.kiosk.is_none() {
+            effects.push(self.persist_ui_effect());
+        }
+        effects
     }
-}
 
-fn prefix_to_mask(prefix: u8) -> Option<String> {
-    if prefix > 32 {
-        return None;
+    /// Switch pages: the page left is suspended, the new one initialised on
+    /// its first visit and resumed on later ones. In a split view this
+    /// changes the focused pane; the other pane's page just takes the focus.
+    fn show_page(&mut self, page: Page) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        if self.split.is_some_and(|split| split.other == page) {
+            effects.extend(self.switch_pane());
+        } else if page != self.page {
+            crate::module(crate::ModuleId::Page(self.page)).suspend(self);
+            self.page = page;
+            effects.extend(self.enter(page));
+        }
+        effects.push(self.persist_ui_effect());
+        effects
     }
-    let value = if prefix == 0 {
-        0
-    } else {
-        u32::MAX << (32 - prefix)
-    };
-    Some(std::net::Ipv4Addr::from(value).to_string())
-}
 
-fn validate_adapter_params(params: &AdapterEditParams) -> Result<(), AdapterValidationError> {
-    crate::AdapterConfigRequest {
-        guid: "validation-only".into(),
-        name: String::new(),
-        use_dhcp: params.use_dhcp,
-        ip: params.ip.clone(),
-        mask: params.mask.clone(),
-        gateway: non_empty(&params.gateway),
-        dns: [&params.dns1, &params.dns2]
-            .into_iter()
-            .filter_map(|value| non_empty(value))
-            .collect(),
+    pub(crate) fn enter(&mut self, page: Page) -> Vec<Effect> {
+        let module = crate::module(crate::ModuleId::Page(page));
+        if self.started.contains(&page) {
+            module.resume(self)
+        } else {
+            self.started.push(page);
+            module.init(self)
+        }
     }
-    .validate()
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{InputEvent, KeyEvent};
+    pub fn refresh_adapters(&mut self) -> Vec<Effect> {
+        self.refresh_adapters_inner()
+    }
 
-    #[test]
-    fn navigation_wraps() {
-        let mut app = AppModel::default();
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::BackTab))));
-        assert_eq!(app.page, Page::Settings);
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
-        assert_eq!(app.page, Page::Dashboard);
+    pub fn refresh_traffic(&mut self) -> Vec<Effect> {
+        self.refresh_traffic_inner()
     }
 
-    #[test]
-    fn scanner_emits_effect_and_ignores_stale_events() {
-        let mut app = AppModel {
-            page: Page::Scanner,
-            ..AppModel::default()
-        };
-        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Some(Effect::StartScan { job, .. }) = effects
-            .iter()
-            .find(|effect| matches!(effect, Effect::StartScan { .. }))
-            .cloned()
-        else {
-            panic!("expected scan effect");
-        };
-        app.update(Runtime(RuntimeEvent::ScanHostFound {
-            job: JobId {
-                generation: job.generation + 1,
-                ..job
-            },
-            host: ScanHost::default(),
-        }));
-        assert!(app.scanner.results.is_empty());
-        app.update(Runtime(RuntimeEvent::ScanHostFound {
-            job,
-            host: ScanHost {
-                ip: "192.168.1.10".into(),
-                ..ScanHost::default()
-            },
-        }));
-        assert_eq!(app.scanner.results.len(), 1);
-        app.update(Runtime(RuntimeEvent::ScanHostFound {
+    /// Re-enumerate adapters and refresh the dashboard. Re-enumerating also
+    /// re-derives the scanner's default CIDR; the dashboard picks up the new
+    /// active interface, gateway and public IP.
+    pub(crate) fn recheck_network(&mut self) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        if self.adapters.edit.is_none() {
+            effects.extend(self.refresh_adapters());
+        }
+        effects.extend(self.refresh_dashboard());
+        effects
+    }
+
+    /// Start a round of watch checks once one is due. Offline mode pauses
+    /// watching, and a round still in flight holds back the next.
+    fn schedule_watch(&mut self) -> Vec<Effect> {
+        if self.watch.tiles.is_empty()
+            || self.offline
+            || self.watch.job.is_some()
+            || self.elapsed_ms < self.watch.next_check_ms
+        {
+            return Vec::new();
+        }
+        let job = self.next_job(ToolKind::Watch);
+        self.watch.job = Some(job);
+        self.watch.next_check_ms = self.elapsed_ms
+            + if self.low_power_active() {
+                crate::LOW_POWER_WATCH_INTERVAL_MS
+            } else {
+                crate::WATCH_INTERVAL_MS
+            };
+        vec![Effect::StartWatch {
             job,
-            host: ScanHost {
-                ip: "192.168.1.2".into(),
-                ..ScanHost::default()
+            request: crate::WatchRequest {
+                targets: self.watch.targets(),
+                timeout_ms: crate::WATCH_TIMEOUT_MS,
             },
-        }));
-        assert_eq!(
-            app.scanner
-                .results
-                .iter()
-                .map(|host| host.ip.as_str())
-                .collect::<Vec<_>>(),
-            ["192.168.1.2", "192.168.1.10"]
+        }]
+    }
+
+    /// File a watch result. A target that has just gone down is listed as a
+    /// problem unless a snooze or its maintenance window silences it.
+    pub(crate) fn record_watch(&mut self, check: crate::WatchCheck) {
+        let at = self.dashboard.snapshot.observed_at.clone();
+        let Some(index) = self.watch.record(check) else {
+            return;
+        };
+        let tile = &self.watch.tiles[index];
+        if self.watch.silence(tile, self.elapsed_ms, &at).is_some() {
+            return;
+        }
+        let message = format!(
+            "{} ({}) is down: {}",
+            tile.target.name,
+            tile.target.target,
+            tile.last()
+                .and_then(|check| check.error.as_deref())
+                .unwrap_or_default()
+        );
+        self.problems.record(
+            ToolKind::Watch,
+            &crate::RuntimeError::new(crate::RuntimeErrorCode::Network, message),
+            &at,
         );
-        assert_eq!(app.scanner.selected, 1);
     }
 
-    #[test]
-    fn scanner_restores_edit_mru_completion_and_panel_click_semantics() {
-        let mut config = crate::ConfigData::default();
-        config.session.scanner.cidr = "10.0.0.0/24".into();
-        config.session.history.cidrs = vec!["192.168.50.0/24".into(), "10.0.0.0/24".into()];
-        let mut app = AppModel::default();
-        app.apply_config(&config);
-        app.page = Page::Scanner;
+    pub(crate) fn refresh_dashboard(&mut self) -> Vec<Effect> {
+        let job = self.next_job(ToolKind::Dashboard);
+        self.dashboard.job = Some(job);
+        self.dashboard.status = TaskStatus::Running;
+        self.dashboard.error = None;
+        vec![Effect::RefreshDashboard {
+            job,
+            request: crate::DashboardRequest {
+                public_ip: self.public_ip_config.clone(),
+                offline: self.offline,
+            },
+        }]
+    }
+
+    fn refresh_adapters_inner(&mut self) -> Vec<Effect> {
+        let job = self.next_job(ToolKind::Adapters);
+        self.adapters.job = Some(job);
+        self.adapters.status = TaskStatus::Running;
+        self.adapters.error = None;
+        vec![Effect::RefreshAdapters { job }]
+    }
+
+    fn refresh_traffic_inner(&mut self) -> Vec<Effect> {
+        let job = self.next_job(ToolKind::Traffic);
+        self.traffic.job = Some(job);
+        self.traffic.status = TaskStatus::Running;
+        self.traffic.error = None;
+        vec![Effect::RefreshTraffic { job }]
+    }
+
+    pub(crate) fn toggle_scan(&mut self) -> Vec<Effect> {
+        if let Some(job) = self.scanner.job {
+            self.scanner.status = TaskStatus::Done;
+            self.scanner.job = None;
+            self.scanner.progress.cancel_remaining();
+            return vec![Effect::CancelScan(job), self.persist_inventory()];
+        }
+
+        if !crate::permitted(self, crate::ModuleId::Page(Page::Scanner)) {
+            self.scanner.status = TaskStatus::Failed(crate::POLICY_REFUSAL.into());
+            return Vec::new();
+        }
+        let cidr = match crate::Target::parse_hosts(&self.scanner.cidr) {
+            Ok(target) => target.to_string(),
+            Err(error) => {
+                self.scanner.status = TaskStatus::Failed(error.to_string());
+                return Vec::new();
+            }
+        };
+        if self.guard_scan(crate::GuardedScan::LanScan, &cidr) {
+            return Vec::new();
+        }
+        let job = self.next_job(ToolKind::Scanner);
+        self.scanner.cidr = cidr.clone();
+        self.scanner.cursor = self.scanner.cursor.min(cidr.len());
+        self.scanner.history.retain(|old| old != &cidr);
+        self.scanner.history.insert(0, cidr);
+        self.scanner.history.truncate(15);
+        self.scanner.editing = false;
+        self.scanner.history_open = false;
+        self.scanner.job = Some(job);
+        self.scanner.status = TaskStatus::Running;
+        self.scanner.total = 0;
+        self.scanner.progress = ScanProgress::default();
+        self.scanner.results.clear();
+        self.scanner.arrivals.clear();
+        self.scanner.trimmed = 0;
+        self.scanner.services.clear();
+        self.scanner.revision = next_revision(&mut self.revision_clock);
+        // A passive sweep must not touch the hosts, so it never probes ports.
+        let dns_zone = (!self.scanner.dns_zone.is_empty()).then(|| self.scanner.dns_zone.clone());
+        let mut effects = self.persist_scanner();
+        effects.extend([
+            Effect::PersistSession(crate::SessionUpdate::CidrHistory(
+                self.scanner.history.clone(),
+            )),
+            Effect::StartScan {
+                job,
+                request: ScanRequest {
+                    cidr: self.scanner.cidr.clone(),
+                    concurrency: self.scan_concurrency,
+                    adaptive: self.scan_adaptive,
+                    enrich: self.scanner.enrich && dns_zone.is_none(),
+                    dns_zone,
+                },
+            },
+        ]);
+        effects
+    }
+
+    fn toggle_diagnostic(&mut self) -> Vec<Effect> {
+        if let Some(job) = self.diagnostics.active_common().job {
+            let common = self.diagnostics.active_common_mut();
+            common.job = None;
+            common.status = TaskStatus::Done;
+            let mut effects = vec![stop_effect(job)];
+            if self.diagnostics.tool == DiagnosticTool::Trace {
+                effects.extend(self.stop_trace_comparison(Some(job)));
+            }
+            return effects;
+        }
+        if !self.tool_available(self.diagnostics.tool) {
+            return Vec::new();
+        }
+        self.sync_active_diagnostic_request();
+        let upnp = &self.diagnostics.upnp.request;
+        if self.diagnostics.tool == DiagnosticTool::Upnp && upnp.action == crate::UpnpAction::Delete
+        {
+            self.confirm(crate::Confirm::DeleteUpnpMapping {
+                protocol: upnp.protocol,
+                external_port: upnp.external_port,
+            });
+            return Vec::new();
+        }
+        self.start_diagnostic()
+    }
+
+    /// Start the selected tool with its current parameters.
+    fn start_diagnostic(&mut self) -> Vec<Effect> {
+        self.sync_active_diagnostic_request();
+        let target = match self.diagnostics.tool {
+            DiagnosticTool::Ping => Some(self.diagnostics.ping.request.target.trim().to_string()),
+            DiagnosticTool::Trace => Some(self.diagnostics.trace.request.target.trim().to_string()),
+            DiagnosticTool::Mtu => Some(self.diagnostics.mtu.request.target.trim().to_string()),
+            DiagnosticTool::Dscp => Some(self.diagnostics.dscp.request.target.trim().to_string()),
+            DiagnosticTool::Nat => Some(self.diagnostics.nat.request.server.trim().to_string()),
+            DiagnosticTool::Ipv6 => Some(self.diagnostics.ipv6.request.target.trim().to_string()),
+            DiagnosticTool::AbTest => {
+                Some(self.diagnostics.ab_test.request.target.trim().to_string())
+            }
+            // The custom resolver is optional; only a filled-in one joins the history.
+            DiagnosticTool::DnsBench => Some(self.diagnostics.dns_bench.request.custom.trim())
+                .filter(|custom| !custom.is_empty())
+                .map(str::to_string),
+            DiagnosticTool::DnsFilter => Some(self.diagnostics.dns_filter.request.domain.trim())
+                .filter(|domain| !domain.is_empty())
+                .map(str::to_string),
+            DiagnosticTool::Mail => Some(self.diagnostics.mail.request.host.trim().to_string()),
+            // A reflector has no peer; it answers whoever probes it.
+            DiagnosticTool::Voip => (self.diagnostics.voip.request.mode == crate::VoipMode::Probe)
+                .then(|| self.diagnostics.voip.request.peer.trim().to_string()),
+            DiagnosticTool::Gaming => Some(self.diagnostics.gaming.request.custom.trim())
+                .filter(|custom| !custom.is_empty())
+                .map(str::to_string),
+            DiagnosticTool::Multicast
+            | DiagnosticTool::Upnp
+            | DiagnosticTool::Cloud
+            | DiagnosticTool::Wifi => None,
+            DiagnosticTool::PortScan => {
+                Some(self.diagnostics.port_scan.request.target.trim().to_string())
+            }
+            DiagnosticTool::LinkQuality => Some(
+                self.diagnostics
+                    .link_quality
+                    .request
+                    .target
+                    .trim()
+                    .to_string(),
+            ),
+            DiagnosticTool::LanSpeed
+                if self.diagnostics.lan_speed.request.mode == crate::LanSpeedMode::Client =>
+            {
+                Some(self.diagnostics.lan_speed.request.peer.trim().to_string())
+            }
+            _ => None,
+        };
+        if target.as_ref().is_some_and(String::is_empty) {
+            let common = self.diagnostics.active_common_mut();
+            let error = crate::RuntimeError::new(
+                crate::RuntimeErrorCode::InvalidRequest,
+                "target cannot be empty",
+            );
+            common.status = TaskStatus::Failed(error.message.clone());
+            common.detail = error.message.clone();
+            common.error = Some(error);
+            return Vec::new();
+        }
+        // Tools aimed at one host reject ranges and typos here and keep the
+        // normalized spelling, so a pasted URL pings its host.
+        let target = match (self.diagnostics.tool, target) {
+            (
+                DiagnosticTool::Ping
+                | DiagnosticTool::Trace
+                | DiagnosticTool::PortScan
+                | DiagnosticTool::AbTest,
+                Some(target),
+            ) => match crate::Target::parse_host(&target) {
+                Ok(parsed) => {
+                    let normalized = parsed.to_string();
+                    match self.diagnostics.tool {
+                        DiagnosticTool::Ping => {
+                            self.diagnostics.ping.request.target = normalized.clone();
+                        }
+                        DiagnosticTool::Trace => {
+                            self.diagnostics.trace.request.target = normalized.clone();
+                        }
+                        DiagnosticTool::AbTest => {
+                            self.diagnostics.ab_test.request.target = normalized.clone();
+                        }
+                        _ => {
+                            let port_scan = &mut self.diagnostics.port_scan;
+                            port_scan.persist.target = normalized.clone();
+                            port_scan.request.target = normalized.clone();
+                        }
+                    }
+                    Some(normalized)
+                }
+                Err(error) => {
+                    let common = self.diagnostics.active_common_mut();
+                    let error = crate::RuntimeError::new(
+                        crate::RuntimeErrorCode::InvalidRequest,
+                        error.to_string(),
+                    );
+                    common.status = TaskStatus::Failed(error.message.clone());
+                    common.detail = error.message.clone();
+                    common.error = Some(error);
+                    return Vec::new();
+                }
+            },
+            (_, target) => target,
+        };
+        if self.diagnostics.tool == DiagnosticTool::AbTest {
+            let request = &self.diagnostics.ab_test.request;
+            let (first, second) = (request.first.trim(), request.second.trim());
+            let message = if first.is_empty() || second.is_empty() {
+                Some("both interfaces must be set")
+            } else if first == second {
+                Some("interfaces A and B must differ")
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                let common = self.diagnostics.active_common_mut();
+                let error =
+                    crate::RuntimeError::new(crate::RuntimeErrorCode::InvalidRequest, message);
+                common.status = TaskStatus::Failed(error.message.clone());
+                common.detail = error.message.clone();
+                common.error = Some(error);
+                return Vec::new();
+            }
+        }
+        if self.diagnostics.tool == DiagnosticTool::LinkQuality
+            && self.diagnostics.link_quality.request.adapter.is_none()
+        {
+            let common = self.diagnostics.active_common_mut();
+            let error = crate::RuntimeError::new(
+                crate::RuntimeErrorCode::InvalidRequest,
+                "no active physical IPv4 adapter is available",
+            );
+            common.status = TaskStatus::Failed(error.message.clone());
+            common.detail = error.message.clone();
+            common.error = Some(error);
+            return Vec::new();
+        }
+        if self.diagnostics.tool == DiagnosticTool::PortScan {
+            let request = &self.diagnostics.port_scan.request;
+            if request.start_port == 0
+                || request.end_port == 0
+                || request.start_port > request.end_port
+            {
+                let common = self.diagnostics.active_common_mut();
+                let error = crate::RuntimeError::new(
+                    crate::RuntimeErrorCode::InvalidRequest,
+                    "port range must be between 1 and 65535 and start must not exceed end",
+                );
+                common.status = TaskStatus::Failed(error.message.clone());
+                common.detail = error.message.clone();
+                common.error = Some(error);
+                return Vec::new();
+            }
+            // Asked only about a request that would otherwise start.
+            let target = request.target.clone();
+            if self.guard_scan(crate::GuardedScan::PortScan, &target) {
+                return Vec::new();
+            }
+        }
+        if self.diagnostics.tool == DiagnosticTool::LanSpeed
+            && self.diagnostics.lan_speed.request.port == 0
+        {
+            let common = self.diagnostics.active_common_mut();
+            let error = crate::RuntimeError::new(
+                crate::RuntimeErrorCode::InvalidRequest,
+                "LAN speed port must be between 1 and 65535",
+            );
+            common.status = TaskStatus::Failed(error.message.clone());
+            common.detail = error.message.clone();
+            common.error = Some(error);
+            return Vec::new();
+        }
+
+        let tool = ToolKind::from(self.diagnostics.tool);
+        let job = self.next_job(tool);
+        let common = self.diagnostics.active_common_mut();
+        common.job = Some(job);
+        common.status = TaskStatus::Running;
+        common.error = None;
+        common.progress = 0;
+        common.primary.clear();
+        common.detail.clear();
+        common.log.clear();
+        common.trimmed = 0;
+
+        let effect = match self.diagnostics.tool {
+            DiagnosticTool::Ping => {
+                self.diagnostics.ping.samples.clear();
+                self.diagnostics.ping.summary = None;
+                Effect::StartPing {
+                    job,
+                    request: self.diagnostics.ping.request.clone(),
+                }
+            }
+            DiagnosticTool::Trace => {
+                self.diagnostics.trace.hops.clear();
+                Effect::StartTrace {
+                    job,
+                    request: self.diagnostics.trace.request.clone(),
+                }
+            }
+            DiagnosticTool::PortScan => {
+                self.diagnostics.port_scan.scanned = 0;
+                self.diagnostics.port_scan.total = 0;
+                self.diagnostics.port_scan.open_ports.clear();
+                self.diagnostics.port_scan.request.concurrency = self.scan_concurrency;
+                Effect::StartPortScan {
+                    job,
+                    request: self.diagnostics.port_scan.request.clone(),
+                }
+            }
+            DiagnosticTool::PublicSpeed => {
+                self.diagnostics.public_speed.samples.clear();
+                self.diagnostics.public_speed.latency.clear();
+                self.diagnostics.public_speed.summary = None;
+                Effect::StartPublicSpeed {
+                    job,
+                    request: self.diagnostics.public_speed.request.clone(),
+                }
+            }
+            DiagnosticTool::LinkQuality => {
+                self.diagnostics.link_quality.samples.clear();
+                self.diagnostics.link_quality.summary = None;
+                self.diagnostics.link_quality.snapshot = None;
+                Effect::StartLinkQuality {
+                    job,
+                    request: self.diagnostics.link_quality.request.clone(),
+                }
+            }
+            DiagnosticTool::LanSpeed => {
+                self.diagnostics.lan_speed.samples.clear();
+                self.diagnostics.lan_speed.summary = None;
+                self.diagnostics.lan_speed.endpoint.clear();
+                self.diagnostics.lan_speed.phase = None;
+                Effect::StartLanSpeed {
+                    job,
+                    request: self.diagnostics.lan_speed.request.clone(),
+                }
+            }
+            DiagnosticTool::Mtu => {
+                self.diagnostics.mtu.probes.clear();
+                self.diagnostics.mtu.summary = None;
+                Effect::StartMtu {
+                    job,
+                    request: self.diagnostics.mtu.request.clone(),
+                }
+            }
+            DiagnosticTool::Dscp => {
+                self.diagnostics.dscp.hops.clear();
+                self.diagnostics.dscp.summary = None;
+                Effect::StartDscp {
+                    job,
+                    request: self.diagnostics.dscp.request.clone(),
+                }
+            }
+            DiagnosticTool::Multicast => {
+                self.diagnostics.multicast.samples.clear();
+                self.diagnostics.multicast.summary = None;
+                Effect::StartMulticast {
+                    job,
+                    request: self.diagnostics.multicast.request.clone(),
+                }
+            }
+            DiagnosticTool::Upnp => {
+                self.diagnostics.upnp.mappings.clear();
+                Effect::StartUpnp {
+                    job,
+                    request: self.diagnostics.upnp.request.clone(),
+                }
+            }
+            DiagnosticTool::Nat => {
+                self.diagnostics.nat.probes.clear();
+                self.diagnostics.nat.summary = None;
+                Effect::StartNat {
+                    job,
+                    request: self.diagnostics.nat.request.clone(),
+                }
+            }
+            DiagnosticTool::Ipv6 => {
+                self.diagnostics.ipv6.results.clear();
+                Effect::StartIpv6 {
+                    job,
+                    request: self.diagnostics.ipv6.request.clone(),
+                }
+            }
+            DiagnosticTool::DnsBench => {
+                self.diagnostics.dns_bench.scores.clear();
+                Effect::StartDnsBench {
+                    job,
+                    request: self.diagnostics.dns_bench.request.clone(),
+                }
+            }
+            DiagnosticTool::DnsFilter => {
+                let state = &mut self.diagnostics.dns_filter;
+                state.upstreams.clear();
+                state.domains.clear();
+                state.probes.clear();
+                Effect::StartDnsFilter {
+                    job,
+                    request: state.request.clone(),
+                }
+            }
+            DiagnosticTool::Mail => {
+                self.diagnostics.mail.checks.clear();
+                Effect::StartMail {
+                    job,
+                    request: self.diagnostics.mail.request.clone(),
+                }
+            }
+            DiagnosticTool::Voip => {
+                let state = &mut self.diagnostics.voip;
+                state.endpoint = None;
+                state.samples.clear();
+                state.summary = None;
+                Effect::StartVoip {
+                    job,
+                    request: state.request.clone(),
+                }
+            }
+            DiagnosticTool::Gaming => {
+                self.diagnostics.gaming.scores.clear();
+                self.diagnostics.gaming.endpoints = 0;
+                Effect::StartGaming {
+                    job,
+                    request: self.diagnostics.gaming.request.clone(),
+                }
+            }
+            DiagnosticTool::Cloud => {
+                self.diagnostics.cloud.probes.clear();
+                self.diagnostics.cloud.endpoints = 0;
+                Effect::StartCloud {
+                    job,
+                    request: self.diagnostics.cloud.request.clone(),
+                }
+            }
+            DiagnosticTool::Wifi => {
+                self.diagnostics.wifi.samples.clear();
+                self.diagnostics.wifi.roams.clear();
+                self.diagnostics.wifi.nearby.clear();
+                Effect::StartWifi {
+                    job,
+                    request: self.diagnostics.wifi.request.clone(),
+                }
+            }
+            DiagnosticTool::AbTest => {
+                self.diagnostics.ab_test.legs.clear();
+                Effect::StartAbTest {
+                    job,
+                    request: self.diagnostics.ab_test.request.clone(),
+                }
+            }
+        };
+        let mut effects = vec![effect];
+        if self.diagnostics.tool == DiagnosticTool::Trace {
+            effects.extend(self.start_trace_comparison());
+        }
+        if let Some(target) = target {
+            self.diagnostics
+                .target_history
+                .retain(|value| value != &target);
+            self.diagnostics.target_history.insert(0, target);
+            self.diagnostics.target_history.truncate(15);
+            effects.push(Effect::PersistSession(crate::SessionUpdate::TargetHistory(
+                self.diagnostics.target_history.clone(),
+            )));
+        }
+        effects
+    }
+
+    /// Start the second trace when a compare target or interface is filled
+    /// in. It inherits the first run's settings, including the interface
+    /// unless another one is given.
+    fn start_trace_comparison(&mut self) -> Vec<Effect> {
+        let mut effects: Vec<Effect> = self.stop_trace_comparison(None).into_iter().collect();
+        let state = &self.diagnostics.trace;
+        let compare_target = state.compare_target.trim();
+        let compare_via = state.compare_via.trim();
+        if compare_target.is_empty() && compare_via.is_empty() {
+            self.diagnostics.trace.comparison = None;
+            return effects;
+        }
+        let target = if compare_target.is_empty() {
+            Ok(state.request.target.clone())
+        } else {
+            crate::Target::parse_host(compare_target).map(|target| target.to_string())
+        };
+        let mut request = crate::TraceRequest {
+            target: compare_target.to_string(),
+            via: Some(compare_via)
+                .filter(|via| !via.is_empty())
+                .map(str::to_string)
+                .or_else(|| state.request.via.clone()),
+            ..state.request.clone()
+        };
+        match target {
+            Ok(target) => {
+                request.target = target;
+                let job = self.next_job(ToolKind::Trace);
+                self.diagnostics.trace.comparison =
+                    Some(crate::TraceComparison::new(job, request.clone()));
+                effects.push(Effect::StartTrace { job, request });
+            }
+            Err(error) => {
+                self.diagnostics.trace.comparison = Some(crate::TraceComparison {
+                    request,
+                    job: None,
+                    hops: Vec::new(),
+                    error: Some(crate::RuntimeError::new(
+                        crate::RuntimeErrorCode::InvalidRequest,
+                        error.to_string(),
+                    )),
+                });
+            }
+        }
+        effects
+    }
+
+    /// Look up who announces the public hops of both traces. Addresses
+    /// already known are not asked again.
+    fn lookup_trace_asns(&mut self) -> Vec<Effect> {
+        let state = &self.diagnostics.trace;
+        if state.asn.job.is_some() {
+            return Vec::new();
+        }
+        let comparison = state
+            .comparison
+            .iter()
+            .flat_map(|comparison| &comparison.hops);
+        let addresses = state.asn.pending(state.hops.iter().chain(comparison));
+        if addresses.is_empty() {
+            return Vec::new();
+        }
+        let job = self.next_job(ToolKind::Asn);
+        self.diagnostics.trace.asn.job = Some(job);
+        self.diagnostics.trace.asn.error = None;
+        vec![Effect::LookupAsn {
+            job,
+            addresses,
+            use_system_proxy: self.public_ip_config.use_system_proxy,
+        }]
+    }
+
+    /// Stop the comparison run, unless it is `stopped`, which the caller
+    /// has stopped already.
+    fn stop_trace_comparison(&mut self, stopped: Option<JobId>) -> Option<Effect> {
+        let job = self.diagnostics.trace.comparison.as_mut()?.job.take()?;
+        (Some(job) != stopped).then(|| stop_effect(job))
+    }
+
+    /// Estimated memory held by everything [`AppModel::retention`] caps.
+    pub fn retained_bytes(&self) -> usize {
+        let d = &self.diagnostics;
+        let logs: usize = DiagnosticTool::ALL
+            .iter()
+            .map(|tool| crate::estimated_bytes(&d.common(*tool).log))
+            .sum();
+        logs + crate::estimated_bytes(&d.ping.samples)
+            + crate::estimated_bytes(&d.public_speed.samples)
+            + crate::estimated_bytes(&d.public_speed.latency)
+            + crate::estimated_bytes(&d.link_quality.samples)
+            + crate::estimated_bytes(&d.lan_speed.samples)
+            + crate::estimated_bytes(&d.multicast.samples)
+            + crate::estimated_bytes(&d.voip.samples)
+            + crate::estimated_bytes(&d.wifi.samples)
+            + crate::estimated_bytes(&self.scanner.results)
+    }
+
+    /// Drop the oldest samples, log lines and hosts beyond
+    /// [`AppModel::retention`], counting them on the tool that lost them.
+    fn enforce_retention(&mut self) {
+        let limits = self.retention;
+        let pass = crate::RetentionPass::new(&limits, self.retained_bytes());
+        let d = &mut self.diagnostics;
+        d.ping.common.trimmed += pass.trim(&mut d.ping.samples, limits.samples);
+        d.public_speed.common.trimmed += pass.trim(&mut d.public_speed.samples, limits.samples)
+            + pass.trim(&mut d.public_speed.latency, limits.samples);
+        d.link_quality.common.trimmed += pass.trim(&mut d.link_quality.samples, limits.samples);
+        d.lan_speed.common.trimmed += pass.trim(&mut d.lan_speed.samples, limits.samples);
+        d.multicast.common.trimmed += pass.trim(&mut d.multicast.samples, limits.samples);
+        d.voip.common.trimmed += pass.trim(&mut d.voip.samples, limits.samples);
+        d.wifi.common.trimmed += pass.trim(&mut d.wifi.samples, limits.samples);
+        for tool in DiagnosticTool::ALL {
+            let common = d.common_mut(tool);
+            common.trimmed += pass.trim(&mut common.log, limits.samples);
+        }
+
+        let scanner = &mut self.scanner;
+        let limit = pass.limit(scanner.results.len(), limits.scan_rows);
+        let mut dropped = 0;
+        while scanner.results.len() > limit {
+            // Results are sorted by address; the arrival queue says which
+            // host was found first.
+            let Some(ip) = scanner.arrivals.pop_front() else {
+                break;
+            };
+            let probe = ScanHost {
+                ip,
+                ..ScanHost::default()
+            };
+            let index = scanner
+                .results
+                .partition_point(|host| scan_host_ip_order(host, &probe).is_lt());
+            if scanner
+                .results
+                .get(index)
+                .is_none_or(|host| host.ip != probe.ip)
+            {
+                continue;
+            }
+            scanner.results.remove(index);
+            if index < scanner.selected {
+                scanner.selected -= 1;
+            }
+            dropped += 1;
+        }
+        if dropped > 0 {
+            scanner.trimmed += dropped;
+            scanner.selected = scanner
+                .selected
+                .min(scanner.results.len().saturating_sub(1));
+            scanner.revision = next_revision(&mut self.revision_clock);
+        }
+    }
+
+    fn handle_runtime(&mut self, event: RuntimeEvent) -> Vec<Effect> {
+        if let Some((job, error)) = event.failure()
+            && self.job_is_current(job)
+        {
+            let at = self.dashboard.snapshot.observed_at.clone();
+            // Errors mean the tool cannot work at all, so they interrupt unless
+            // the tool's own view is already in front of the user.
+            if self.problems.record(job.tool, error, &at) == Some(crate::Severity::Error)
+                && !self.tool_is_visible(job.tool)
+            {
+                self.show_problems();
+            }
+        }
+        if event.tool() == ToolKind::Update {
+            self.handle_update_event(event);
+            return Vec::new();
+        }
+        if let RuntimeEvent::DoctorFinished { job, checks } = event {
+            if self.doctor.job == Some(job) {
+                self.doctor.job = None;
+                self.doctor.checks = checks;
+            }
+            return Vec::new();
+        }
+        if event.tool() == ToolKind::WebCheck {
+            return self.handle_web_check_event(event);
+        }
+        match event {
+            RuntimeEvent::ChartExported { job, path } if self.export.job == Some(job) => {
+                self.export.job = None;
+                self.export.saved = Some(path);
+                return Vec::new();
+            }
+            RuntimeEvent::ChartExportFailed { job, error } if self.export.job == Some(job) => {
+                self.export.job = None;
+                self.export.error = Some(error);
+                return Vec::new();
+            }
+            RuntimeEvent::ChartExported { .. } | RuntimeEvent::ChartExportFailed { .. } => {
+                return Vec::new();
+            }
+            RuntimeEvent::DhcpProbeFinished { job, servers }
+                if self.audit.dhcp_job == Some(job) =>
+            {
+                self.audit.dhcp_job = None;
+                self.audit.dhcp = Some(servers);
+                return Vec::new();
+            }
+            RuntimeEvent::DhcpProbeFailed { job, error } if self.audit.dhcp_job == Some(job) => {
+                self.audit.dhcp_job = None;
+                self.audit.dhcp_error = Some(error);
+                return Vec::new();
+            }
+            RuntimeEvent::AuditExported { job, path } if self.audit.export_job == Some(job) => {
+                self.audit.export_job = None;
+                self.audit.saved = Some(path);
+                return Vec::new();
+            }
+            RuntimeEvent::AuditExportFailed { job, error }
+                if self.audit.export_job == Some(job) =>
+            {
+                self.audit.export_job = None;
+                self.audit.export_error = Some(error);
+                return Vec::new();
+            }
+            RuntimeEvent::DhcpProbeFinished { .. }
+            | RuntimeEvent::DhcpProbeFailed { .. }
+            | RuntimeEvent::AuditExported { .. }
+            | RuntimeEvent::AuditExportFailed { .. } => {
+                return Vec::new();
+            }
+            RuntimeEvent::AsnFound { job, origin }
+                if self.diagnostics.trace.asn.job == Some(job) =>
+            {
+                self.diagnostics.trace.asn.origins.push(origin);
+                return Vec::new();
+            }
+            RuntimeEvent::AsnLookupFinished { job }
+                if self.diagnostics.trace.asn.job == Some(job) =>
+            {
+                self.diagnostics.trace.asn.job = None;
+                return Vec::new();
+            }
+            RuntimeEvent::AsnLookupFailed { job, error }
+                if self.diagnostics.trace.asn.job == Some(job) =>
+            {
+                self.diagnostics.trace.asn.job = None;
+                self.diagnostics.trace.asn.error = Some(error);
+                return Vec::new();
+            }
+            RuntimeEvent::AsnFound { .. }
+            | RuntimeEvent::AsnLookupFinished { .. }
+            | RuntimeEvent::AsnLookupFailed { .. } => return Vec::new(),
+            _ => {}
+        }
+        // The inventory is saved once per scan rather than per host.
+        let scan_ended = matches!(
+            event,
+            RuntimeEvent::ScanFinished { job } | RuntimeEvent::ScanCancelled { job }
+                if self.scanner.job == Some(job)
+        );
+        let adapters_listed = matches!(
+            event,
+            RuntimeEvent::AdaptersUpdated(_) | RuntimeEvent::AdaptersRefreshFinished { .. }
+        );
+        let dashboard_observed = matches!(
+            event,
+            RuntimeEvent::DashboardUpdated(_)
+                | RuntimeEvent::DashboardRefreshFinished { .. }
+                | RuntimeEvent::DashboardRefreshFailed { .. }
+        );
+        let roams = self.diagnostics.wifi.roams.len();
+        let pings = self.diagnostics.ping.samples.len();
+        let events_logged = self.events.logged();
+        let speed_finished = match &event {
+            RuntimeEvent::PublicSpeedFinished { job, .. } => {
+                self.diagnostics.public_speed.common.job == Some(*job)
+            }
+            RuntimeEvent::LanSpeedFinished { job, .. } => {
+                self.diagnostics.lan_speed.common.job == Some(*job)
+            }
+            _ => false,
+        }
+        .then(|| event.tool());
+        if let Some(module) = crate::module_for_job(event.tool()) {
+            module.update(self, event);
+        }
+        let mut effects = Vec::new();
+        if scan_ended {
+            effects.push(self.persist_inventory());
+        }
+        // Stale generations leave the state untouched and so log nothing.
+        let logged = if adapters_listed {
+            let at = self.dashboard.snapshot.observed_at.clone();
+            self.events.observe_adapters(&self.adapters.items, &at)
+        } else if let Some(roam) = self.diagnostics.wifi.roams.get(roams) {
+            self.events
+                .observe_roam(&self.diagnostics.wifi.request.adapter, roam)
+        } else {
+            dashboard_observed && self.events.observe_dashboard(&self.dashboard.snapshot)
+        };
+        if logged {
+            effects.push(self.persist_events());
+        }
+        self.record_timeline(pings, events_logged, speed_finished);
+        effects.extend(self.advance_pipeline());
+        effects
+    }
+
+    /// Put on the timeline what the last runtime event added: ping samples
+    /// past the first `pings`, change log entries since `events_logged` and
+    /// the summary of a speed test that just finished.
+    fn record_timeline(&mut self, pings: usize, events_logged: u64, speed: Option<ToolKind>) {
+        let now = self.elapsed_ms;
+        let at = &self.dashboard.snapshot.observed_at;
+        let timeline = &mut self.timeline;
+        for sample in self.diagnostics.ping.samples.iter().skip(pings) {
+            timeline.record_ping(now, sample.latency_ms);
+        }
+        for event in self.events.since(events_logged) {
+            timeline.record(now, &event.at, crate::TimelineKind::Network(event.clone()));
+        }
+        let summary = match speed {
+            Some(ToolKind::PublicSpeed) => self
+                .diagnostics
+                .public_speed
+                .summary
+                .clone()
+                .map(crate::TimelineKind::PublicSpeed),
+            Some(ToolKind::LanSpeed) => self
+                .diagnostics
+                .lan_speed
+                .summary
+                .clone()
+                .map(crate::TimelineKind::LanSpeed),
+            _ => None,
+        };
+        if let Some(kind) = summary {
+            timeline.record(now, at, kind);
+        }
+    }
+
+    /// Add a Wi-Fi monitor sample, noting a roam against the previous one.
+    /// A roam during a running ping is also marked in the ping log, right
+    /// where any latency spike it caused shows up.
+    pub(crate) fn record_wifi_sample(&mut self, mut sample: crate::WifiSample) {
+        let at = self.dashboard.snapshot.observed_at.clone();
+        let ping = &mut self.diagnostics.ping;
+        let state = &mut self.diagnostics.wifi;
+        let previous = state.samples.last().and_then(|last| last.link.as_ref());
+        match (previous, &sample.link) {
+            (Some(previous), Some(current)) => {
+                if let Some(mut roam) =
+                    crate::WifiRoam::between(previous, current, sample.elapsed_ms, &at)
+                {
+                    if ping.common.status == TaskStatus::Running {
+                        roam.ping_sequence = ping.samples.last().map(|last| last.sequence);
+                        ping.common
+                            .log
+                            .push(format!("  ⇄ Wi-Fi {}", roam.describe()));
+                    }
+                    state.common.log.push(format!("{at} {}", roam.describe()));
+                    state.roams.push(roam);
+                }
+            }
+            (Some(previous), None) => {
+                state
+                    .common
+                    .log
+                    .push(format!("{at} disconnected from {}", previous.ssid));
+            }
+            (None, Some(current)) if !state.samples.is_empty() => {
+                state.common.log.push(format!(
+                    "{at} associated with {} ({})",
+                    current.ssid, current.bssid
+                ));
+            }
+            _ => {}
+        }
+        state.common.status = TaskStatus::Running;
+        state.common.primary = match &sample.link {
+            Some(link) => format!(
+                "{} · {} · ch {} · {} dBm · tx {} Mbps",
+                link.ssid, link.bssid, link.channel, link.rssi_dbm, link.tx_rate_mbps
+            ),
+            None => "not associated".into(),
+        };
+        // Only the latest scan is worth keeping; retention counts samples.
+        if !sample.nearby.is_empty() {
+            state.nearby = std::mem::take(&mut sample.nearby);
+        }
+        state.samples.push(sample);
+        state.common.detail = match crate::rssi_range(&state.samples) {
+            Some((min, average, max)) => format!(
+                "{} roams · RSSI {min}/{average:.0}/{max} dBm",
+                state.roams.len()
+            ),
+            None => format!("{} roams", state.roams.len()),
+        };
+    }
+
+    /// Feed the drop counters of a traffic sample to the link health window
+    /// and list interfaces whose loss rate has just crossed the threshold.
+    pub(crate) fn record_link_health(&mut self, rows: &[TrafficRow]) {
+        let samples = rows
+            .iter()
+            .filter_map(|row| Some((row.name.as_str(), row.link?)));
+        let alerts = self
+            .link_health
+            .record(self.elapsed_ms, samples, self.drop_alert_percent);
+        let at = self.dashboard.snapshot.observed_at.clone();
+        for alert in alerts {
+            let counts = alert.window.counts;
+            let message = format!(
+                "{} is losing {:.1}% of packets: {} dropped, {} errors, {} collisions in {} s",
+                alert.interface,
+                counts.loss_percent(),
+                counts.drops,
+                counts.errors,
+                counts.collisions,
+                alert.window.seconds.max(1)
+            );
+            self.problems.record(
+                ToolKind::Traffic,
+                &crate::RuntimeError::new(crate::RuntimeErrorCode::Network, message),
+                &at,
+            );
+        }
+    }
+
+    pub(crate) fn sync_dashboard_traffic(&mut self, rows: &[TrafficRow]) {
+        let Some(interface) = self.dashboard.snapshot.active_interface.as_ref() else {
+            return;
+        };
+        let Some(row) = rows.iter().find(|row| row.name == interface.name) else {
+            return;
+        };
+        self.dashboard.snapshot.download_bps = row.download_bps;
+        self.dashboard.snapshot.upload_bps = row.upload_bps;
+        self.dashboard.snapshot.total_download = row.total_download;
+        self.dashboard.snapshot.total_upload = row.total_upload;
+    }
+}
+
+pub(crate) fn scan_host_ip_order(left: &ScanHost, right: &ScanHost) -> Ordering {
+    match (left.ip.parse::<IpAddr>(), right.ip.parse::<IpAddr>()) {
+        (Ok(left), Ok(right)) => left.cmp(&right),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => left.ip.cmp(&right.ip),
+    }
+}
+
+fn active_network_cidr(adapter: &AdapterInfo) -> Option<String> {
+    let (_, prefix) = adapter.cidr.as_deref()?.rsplit_once('/')?;
+    let prefix = prefix.parse::<u8>().ok().filter(|prefix| *prefix <= 32)?;
+    let address = adapter.ipv4.parse::<std::net::Ipv4Addr>().ok()?;
+    let mask = if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    };
+    let network = std::net::Ipv4Addr::from(u32::from(address) & mask);
+    Some(format!("{network}/{prefix}"))
+}
+
+pub(crate) fn next_revision(clock: &mut u64) -> u64 {
+    *clock = clock.wrapping_add(1);
+    *clock
+}
+
+pub(crate) fn finish_common(common: &mut DiagnosticCommonState, summary: String) {
+    common.status = TaskStatus::Done;
+    common.error = None;
+    common.progress = 100;
+    common.detail = summary;
+    common.job = None;
+}
+
+pub(crate) fn fail_common(common: &mut DiagnosticCommonState, error: crate::RuntimeError) {
+    common.status = TaskStatus::Failed(error.message.clone());
+    common.detail = error.message.clone();
+    common.error = Some(error);
+    common.job = None;
+}
+
+fn link_quality_adapter(adapter: &AdapterInfo) -> crate::LinkQualityAdapter {
+    let key = if !adapter.guid.is_empty() {
+        adapter.guid.clone()
+    } else if !adapter.mac.is_empty() {
+        adapter.mac.clone()
+    } else {
+        adapter.name.clone()
+    };
+    crate::LinkQualityAdapter {
+        key,
+        name: adapter.name.clone(),
+        guid: adapter.guid.clone(),
+        ipv4: adapter.ipv4.clone(),
+        is_wifi: adapter.kind.to_ascii_lowercase().contains("ieee80211") || adapter.ssid.is_some(),
+        link_speed_bps: adapter.link_speed_bps,
+        mac: adapter.mac.clone(),
+    }
+}
+
+fn wrap(current: usize, len: usize, delta: isize) -> usize {
+    (current as isize + delta).rem_euclid(len as isize) as usize
+}
+
+fn clamp_step(current: usize, len: usize, delta: isize) -> usize {
+    (current as isize)
+        .saturating_add(delta)
+        .clamp(0, len as isize - 1) as usize
+}
+
+fn stop_effect(job: JobId) -> Effect {
+    match job.tool {
+        ToolKind::Dashboard => unreachable!("dashboard refreshes are not diagnostic jobs"),
+        ToolKind::Adapters
+        | ToolKind::AdapterEdit
+        | ToolKind::Traffic
+        | ToolKind::Update
+        | ToolKind::Doctor
+        | ToolKind::ChartExport
+        | ToolKind::Dhcp
+        | ToolKind::AuditExport
+        | ToolKind::Asn
+        | ToolKind::Cve
+        | ToolKind::Watch => {
+            unreachable!("read-only refreshes are not diagnostic jobs")
+        }
+        ToolKind::WebCheck => Effect::StopWebCheck(job),
+        ToolKind::Ping => Effect::StopPing(job),
+        ToolKind::Trace => Effect::StopTrace(job),
+        ToolKind::PortScan => Effect::StopPortScan(job),
+        ToolKind::PublicSpeed => Effect::StopPublicSpeed(job),
+        ToolKind::LinkQuality => Effect::StopLinkQuality(job),
+        ToolKind::LanSpeed => Effect::StopLanSpeed(job),
+        ToolKind::Mtu => Effect::StopMtu(job),
+        ToolKind::Dscp => Effect::StopDscp(job),
+        ToolKind::Multicast => Effect::StopMulticast(job),
+        ToolKind::Upnp => Effect::StopUpnp(job),
+        ToolKind::Nat => Effect::StopNat(job),
+        ToolKind::Ipv6 => Effect::StopIpv6(job),
+        ToolKind::DnsBench => Effect::StopDnsBench(job),
+        ToolKind::DnsFilter => Effect::StopDnsFilter(job),
+        ToolKind::Mail => Effect::StopMail(job),
+        ToolKind::Voip => Effect::StopVoip(job),
+        ToolKind::Gaming => Effect::StopGaming(job),
+        ToolKind::Cloud => Effect::StopCloud(job),
+        ToolKind::Wifi => Effect::StopWifi(job),
+        ToolKind::AbTest => Effect::StopAbTest(job),
+        ToolKind::Scanner => Effect::CancelScan(job),
+        ToolKind::Bluetooth => Effect::StopBluetoothScan(job),
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn edit_ascii(
+    value: &mut String,
+    cursor: &mut usize,
+    code: KeyCode,
+    limit: usize,
+    allow: impl Fn(char) -> bool,
+) -> bool {
+    *cursor = (*cursor).min(value.len());
+    match code {
+        KeyCode::Left => *cursor = cursor.saturating_sub(1),
+        KeyCode::Right => *cursor = (*cursor + 1).min(value.len()),
+        KeyCode::Home => *cursor = 0,
+        KeyCode::End => *cursor = value.len(),
+        KeyCode::Backspace if *cursor > 0 => {
+            *cursor -= 1;
+            value.remove(*cursor);
+            return true;
+        }
+        KeyCode::Delete if *cursor < value.len() => {
+            value.remove(*cursor);
+            return true;
+        }
+        KeyCode::Char(character) if allow(character) && value.len() < limit => {
+            value.insert(*cursor, character);
+            *cursor += 1;
+            return true;
+        }
+        _ => {}
+    }
+    false
+}
+
+fn adapter_defaults(adapter: &AdapterInfo) -> AdapterEditParams {
+    let prefix = adapter
+        .cidr
+        .as_deref()
+        .and_then(|cidr| cidr.rsplit_once('/'))
+        .and_then(|(_, prefix)| prefix.parse::<u8>().ok());
+    let mask = prefix.and_then(prefix_to_mask).unwrap_or_default();
+    let gateway = adapter
+        .ipv4
+        .parse::<std::net::Ipv4Addr>()
+        .ok()
+        .map(|ip| {
+            let mut octets = ip.octets();
+            octets[3] = 1;
+            std::net::Ipv4Addr::from(octets).to_string()
+        })
+        .unwrap_or_default();
+    AdapterEditParams {
+        use_dhcp: adapter.dhcp_enabled,
+        ip: adapter.ipv4.clone(),
+        mask,
+        gateway,
+        dns1: "8.8.8.8".into(),
+        dns2: "8.8.4.4".into(),
+    }
+}
+
+fn prefix_to_mask(prefix: u8) -> Option<String> {
+    if prefix > 32 {
+        return None;
+    }
+    let value = if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    };
+    Some(std::net::Ipv4Addr::from(value).to_string())
+}
+
+fn validate_adapter_params(params: &AdapterEditParams) -> Result<(), AdapterValidationError> {
+    crate::AdapterConfigRequest {
+        guid: "validation-only".into(),
+        name: String::new(),
+        use_dhcp: params.use_dhcp,
+        ip: params.ip.clone(),
+        mask: params.mask.clone(),
+        gateway: non_empty(&params.gateway),
+        dns: [&params.dns1, &params.dns2]
+            .into_iter()
+            .filter_map(|value| non_empty(value))
+            .collect(),
+    }
+    .validate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InputEvent, KeyEvent};
+
+    #[test]
+    fn navigation_wraps() {
+        let mut app = AppModel::default();
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::BackTab))));
+        assert_eq!(app.page, Page::Settings);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
+        assert_eq!(app.page, Page::Dashboard);
+    }
+
+    #[test]
+    fn scanner_emits_effect_and_ignores_stale_events() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Some(Effect::StartScan { job, .. }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+            .cloned()
+        else {
+            panic!("expected scan effect");
+        };
+        app.update(Runtime(RuntimeEvent::ScanHostFound {
+            job: JobId {
+                generation: job.generation + 1,
+                ..job
+            },
+            host: Box::default(),
+        }));
+        assert!(app.scanner.results.is_empty());
+        app.update(Runtime(RuntimeEvent::ScanHostFound {
+            job,
+            host: Box::new(ScanHost {
+                ip: "192.168.1.10".into(),
+                ..ScanHost::default()
+            }),
+        }));
+        assert_eq!(app.scanner.results.len(), 1);
+        app.update(Runtime(RuntimeEvent::ScanHostFound {
+            job,
+            host: Box::new(ScanHost {
+                ip: "192.168.1.2".into(),
+                ..ScanHost::default()
+            }),
+        }));
+        assert_eq!(
+            app.scanner
+                .results
+                .iter()
+                .map(|host| host.ip.as_str())
+                .collect::<Vec<_>>(),
+            ["192.168.1.2", "192.168.1.10"]
+        );
+        assert_eq!(app.scanner.selected, 1);
+    }
+
+    #[test]
+    fn paging_keys_move_by_a_screen_and_stop_at_the_ends() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            page_rows: 25,
+            ..AppModel::default()
+        };
+        app.scanner.results = (0..1_000)
+            .map(|index| ScanHost {
+                ip: format!("10.0.{}.{}", index / 256, index % 256),
+                ..ScanHost::default()
+            })
+            .collect();
+        let key = |code| Input(InputEvent::Key(KeyEvent::plain(code)));
+        app.update(key(KeyCode::PageDown));
+        assert_eq!(app.scanner.selected, 25);
+        app.update(key(KeyCode::End));
+        assert_eq!(app.scanner.selected, 999);
+        app.update(key(KeyCode::PageDown));
+        assert_eq!(app.scanner.selected, 999);
+        app.update(key(KeyCode::PageUp));
+        assert_eq!(app.scanner.selected, 974);
+        app.update(key(KeyCode::Home));
+        assert_eq!(app.scanner.selected, 0);
+        app.update(key(KeyCode::PageUp));
+        assert_eq!(app.scanner.selected, 0);
+    }
+
+    #[test]
+    fn stopping_a_scan_reports_unfinished_work_as_cancelled() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Some(Effect::StartScan { job, .. }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+            .cloned()
+        else {
+            panic!("expected scan effect");
+        };
+        let progress = crate::ScanProgress {
+            probe: crate::PhaseProgress {
+                queued: 200,
+                in_flight: 4,
+                done: 50,
+                cancelled: 0,
+            },
+            resolve: crate::PhaseProgress {
+                in_flight: 1,
+                done: 2,
+                ..crate::PhaseProgress::default()
+            },
+            ..crate::ScanProgress::default()
+        };
+        app.update(Runtime(RuntimeEvent::ScanProgress { job, progress }));
+        assert_eq!(app.scanner.progress, progress);
+        assert!((progress.ratio() - 52.0 / 257.0).abs() < f64::EPSILON);
+
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        let stopped = app.scanner.progress;
+        assert_eq!(stopped.probe.settled(), 254);
+        assert_eq!(stopped.cancelled(), 205);
+        assert_eq!(stopped.ratio(), 1.0);
+        // Late snapshots from the stopped job must not resurrect in-flight work.
+        app.update(Runtime(RuntimeEvent::ScanProgress { job, progress }));
+        assert_eq!(app.scanner.progress, stopped);
+    }
+
+    #[test]
+    fn adaptive_concurrency_setting_reaches_the_scan_request() {
+        let mut app = AppModel::default();
+        assert!(app.scan_adaptive, "on by default");
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Settings as u8,
+        ))));
+        select_setting(&mut app, crate::SettingId::ScanAdaptive);
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistPreferences(crate::Preferences {
+                scan_adaptive: false,
+                ..
+            })]
+        ));
+
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Scanner as u8,
+        ))));
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Some(Effect::StartScan { request, .. }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+        else {
+            panic!("expected scan effect: {effects:?}");
+        };
+        assert!(!request.adaptive);
+        assert_eq!(request.concurrency, app.scan_concurrency);
+    }
+
+    #[test]
+    fn a_dns_zone_makes_scans_passive() {
+        let mut app = AppModel {
+            scanner: ScannerState {
+                enrich: true,
+                ..ScannerState::default()
+            },
+            ..AppModel::default()
+        };
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Settings as u8,
+        ))));
+        let key = |code| Input(InputEvent::Key(KeyEvent::plain(code)));
+        select_setting(&mut app, crate::SettingId::ScanDnsZone);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let text = "corp example".to_string();
+        app.settings.input = Some((text.clone(), text.len()));
+        assert!(app.update(key(KeyCode::Enter)).is_empty());
+        assert_eq!(app.settings.error, Some(crate::SettingError::InvalidDomain));
+        let text = "Corp.Example.com.".to_string();
+        app.settings.input = Some((text.clone(), text.len()));
+        assert!(matches!(
+            app.update(key(KeyCode::Enter)).as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Scanner(scanner))]
+                if scanner.dns_zone == "corp.example.com"
+        ));
+
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Scanner as u8,
+        ))));
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Some(Effect::StartScan { request, .. }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+        else {
+            panic!("expected scan effect: {effects:?}");
+        };
+        assert_eq!(request.dns_zone.as_deref(), Some("corp.example.com"));
+        assert!(!request.enrich, "a passive sweep never probes ports");
+    }
+
+    #[test]
+    fn port_enrichment_is_persisted_requested_and_merged_into_hosts() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        let effects = app.update(Input(InputEvent::Action(Action::Right)));
+        assert!(app.scanner.enrich);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Scanner(
+                crate::ScannerPersist { enrich: true, .. }
+            ))]
+        ));
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Some(Effect::StartScan { job, request }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+            .cloned()
+        else {
+            panic!("expected scan effect");
+        };
+        assert!(request.enrich);
+
+        app.update(Runtime(RuntimeEvent::ScanHostFound {
+            job,
+            host: Box::new(ScanHost {
+                ip: "192.168.1.8".into(),
+                ..ScanHost::default()
+            }),
+        }));
+        let revision = app.scanner.revision;
+        let share = crate::NetworkShare {
+            protocol: crate::ShareProtocol::Nfs,
+            name: "/srv/media".into(),
+            detail: "*".into(),
+        };
+        app.update(Runtime(RuntimeEvent::ScanHostEnriched {
+            job,
+            ip: "192.168.1.8".into(),
+            open_ports: vec![80, 2049],
+            shares: vec![share.clone()],
+            web: vec![crate::WebService {
+                port: 80,
+                title: "NAS Admin".into(),
+                server: "nginx".into(),
+            }],
+            iot: Vec::new(),
+            smb1: true,
+            banners: Vec::new(),
+        }));
+        let host = &app.scanner.results[0];
+        assert!(host.smb1);
+        assert!(host.enriched);
+        assert_eq!(host.open_ports, [80, 2049]);
+        assert_eq!(host.shares, [share]);
+        assert_eq!(host.web[0].title, "NAS Admin");
+        assert!(app.scanner.revision > revision);
+
+        let mut config = crate::ConfigData::default();
+        config.session.scanner.enrich = true;
+        let mut restored = AppModel::default();
+        restored.apply_config(&config);
+        assert!(restored.scanner.enrich);
+    }
+
+    #[test]
+    fn audit_probes_dhcp_once_and_exports_its_findings() {
+        let mut app = AppModel::default();
+        app.dashboard.snapshot.active_interface = Some(DashboardInterface {
+            gateway: Some("192.168.1.1".into()),
+            ..DashboardInterface::default()
+        });
+        app.scanner.results.push(ScanHost {
+            ip: "192.168.1.31".into(),
+            open_ports: vec![crate::TELNET_PORT],
+            seen_at: "2026-10-16 21:30".into(),
+            ..ScanHost::default()
+        });
+
+        let effects = app.update(Input(InputEvent::Action(Action::Audit)));
+        assert!(app.popup_open(crate::Popup::Audit));
+        let [Effect::ProbeDhcp { job }] = effects.as_slice() else {
+            panic!("expected a DHCP probe, got {effects:?}");
+        };
+        let server = |server: &str| crate::DhcpServer {
+            server: server.into(),
+            offered: "192.168.1.100".into(),
+            router: String::new(),
+        };
+        app.update(Runtime(RuntimeEvent::DhcpProbeFinished {
+            job: *job,
+            servers: vec![server("192.168.1.1"), server("192.168.1.77")],
+        }));
+        let checks = app
+            .audit_findings()
+            .into_iter()
+            .map(|finding| finding.check)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            checks,
+            [crate::AuditCheck::Telnet, crate::AuditCheck::RogueDhcp]
+        );
+
+        // Reopening keeps the probe result; Refresh runs it again.
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Audit)))
+                .is_empty()
+        );
+        assert!(matches!(
+            app.update(Input(InputEvent::Action(Action::Refresh)))
+                .as_slice(),
+            [Effect::ProbeDhcp { .. }]
+        ));
+        let effects = app.update(Input(InputEvent::Action(Action::Export)));
+        let [Effect::ExportAudit { job, markdown }] = effects.as_slice() else {
+            panic!("expected an export, got {effects:?}");
+        };
+        assert!(markdown.contains("scan of 2026-10-16 21:30 · 2 DHCP servers answered"));
+        assert!(markdown.contains("| High | Telnet open | 192.168.1.31 |"));
+        app.update(Runtime(RuntimeEvent::AuditExported {
+            job: *job,
+            path: "iptools-audit.md".into(),
+        }));
+        assert_eq!(app.audit.saved.as_deref(), Some("iptools-audit.md"));
+    }
+
+    #[test]
+    fn search_takes_the_query_then_steps_with_n_until_esc() {
+        let mut app = AppModel {
+            page: Page::Events,
+            ..AppModel::default()
+        };
+        let key =
+            |app: &mut AppModel, code| app.update(Input(InputEvent::Key(KeyEvent::plain(code))));
+        app.update(Input(InputEvent::Key(KeyEvent {
+            code: KeyCode::Char('f'),
+            modifiers: crate::Modifiers {
+                control: true,
+                ..crate::Modifiers::NONE
+            },
+        })));
+        assert!(app.search.editing);
+        // Digits and 's' would switch tabs or move down; here they are text.
+        for character in "eth1 s".chars() {
+            key(&mut app, KeyCode::Char(character));
+        }
+        key(&mut app, KeyCode::Backspace);
+        key(&mut app, KeyCode::Backspace);
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(app.search.query, "eth1");
+        assert!(!app.search.editing && app.search.active());
+        assert_eq!(app.page, Page::Events);
+
+        key(&mut app, KeyCode::Char('n'));
+        key(&mut app, KeyCode::Char('n'));
+        key(&mut app, KeyCode::Char('N'));
+        assert_eq!(app.search.step, 1);
+
+        // A field being typed into keeps its letters.
+        app.page = Page::Scanner;
+        app.scanner.editing = true;
+        key(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.search.step, 1);
+        app.scanner.editing = false;
+        app.page = Page::Events;
+
+        key(&mut app, KeyCode::Esc);
+        assert!(!app.search.active());
+        assert!(app.search.query.is_empty());
+    }
+
+    #[test]
+    fn calculator_keys_edit_its_input_instead_of_the_page() {
+        let mut app = AppModel::default();
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::F(8)))));
+        assert!(app.popup_open(crate::Popup::Calculator));
+
+        // 's' and 'd' are Down and Right elsewhere; here they are hex digits.
+        for character in "10.0.0.0/8 10.0.0.1d".chars() {
+            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(
+                character,
+            )))));
+        }
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
+        assert_eq!(app.calculator.input, "10.0.0.0/8 10.0.0.1");
+        let rows = app.calculator.rows(Language::En).unwrap();
+        assert_eq!(rows[0].field, crate::CalcField::Contains);
+        assert_eq!(rows[0].value, "yes");
+
+        let page = app.page;
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('q')))));
+        assert_eq!(app.calculator.input, "10.0.0.0/8 10.0.0.1", "not a digit");
+        assert_eq!(app.page, page);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Esc))));
+        assert!(!app.popup_open(crate::Popup::Calculator));
+        app.update(Input(InputEvent::Action(Action::Calculator)));
+        assert_eq!(
+            app.calculator.input, "10.0.0.0/8 10.0.0.1",
+            "kept until restart"
+        );
+    }
+
+    #[test]
+    fn protocol_probes_tag_the_device_in_the_inventory() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        app.scanner.enrich = true;
+        let host = ScanHost {
+            ip: "192.168.1.45".into(),
+            mac: "02:aa:00:00:00:2d".into(),
+            ..ScanHost::default()
+        };
+        let scan = |app: &mut AppModel, iot: Vec<crate::IotService>| {
+            let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+            let Some(Effect::StartScan { job, .. }) = effects
+                .iter()
+                .find(|effect| matches!(effect, Effect::StartScan { .. }))
+                .cloned()
+            else {
+                panic!("expected scan effect");
+            };
+            app.update(Runtime(RuntimeEvent::ScanHostFound {
+                job,
+                host: Box::new(host.clone()),
+            }));
+            app.update(Runtime(RuntimeEvent::ScanHostEnriched {
+                job,
+                ip: host.ip.clone(),
+                open_ports: vec![502, 1883],
+                shares: Vec::new(),
+                web: Vec::new(),
+                iot,
+                smb1: false,
+                banners: Vec::new(),
+            }));
+            app.update(Runtime(RuntimeEvent::ScanFinished { job }));
+        };
+        scan(
+            &mut app,
+            vec![
+                crate::IotService {
+                    protocol: crate::IotProtocol::Mqtt,
+                    detail: "anonymous clients accepted".into(),
+                },
+                crate::IotService {
+                    protocol: crate::IotProtocol::Modbus,
+                    detail: "Demo Energy SUN-5K".into(),
+                },
+            ],
+        );
+        assert_eq!(app.scanner.results[0].iot.len(), 2);
+        let tags = [crate::DeviceType::MqttBroker, crate::DeviceType::Controller];
+        assert_eq!(app.scanner.inventory[0].device_types, tags);
+
+        // Nothing answered this time; the device keeps its tags.
+        scan(&mut app, Vec::new());
+        assert_eq!(app.scanner.inventory[0].device_types, tags);
+    }
+
+    #[test]
+    fn scanner_drawer_keeps_an_inventory_with_notes_across_scans() {
+        let mut config = crate::ConfigData::default();
+        config.session.inventory.devices = vec![crate::DeviceRecord {
+            key: "02:AA:00:00:00:08".into(),
+            last_ip: "192.168.1.7".into(),
+            first_seen: "2025-12-01 09:00".into(),
+            last_seen: "2025-12-01 09:00".into(),
+            note: String::new(),
+            device_types: Vec::new(),
+        }];
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+        app.page = Page::Scanner;
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        let Some(Effect::StartScan { job, .. }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+            .cloned()
+        else {
+            panic!("Enter without results should start the scan");
+        };
+        app.update(Runtime(RuntimeEvent::ScanHostFound {
+            job,
+            host: Box::new(ScanHost {
+                ip: "192.168.1.8".into(),
+                mac: "02:aa:00:00:00:08".into(),
+                seen_at: "2026-01-15 10:24".into(),
+                ..ScanHost::default()
+            }),
+        }));
+        app.update(Runtime(RuntimeEvent::ScanServicesFound {
+            job,
+            services: vec![crate::DiscoveredService {
+                ip: "192.168.1.8".into(),
+                protocol: crate::ServiceProtocol::Mdns,
+                name: "_smb._tcp".into(),
+            }],
+        }));
+        assert_eq!(app.scanner.services.len(), 1);
+        let record = app.scanner.device_record(&app.scanner.results[0]).unwrap();
+        assert_eq!(record.first_seen, "2025-12-01 09:00");
+        assert_eq!(record.last_seen, "2026-01-15 10:24");
+        assert_eq!(record.last_ip, "192.168.1.8");
+        let effects = app.update(Runtime(RuntimeEvent::ScanFinished { job }));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Inventory(inventory))]
+                if inventory.devices.len() == 1
+        ));
+
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(app.scanner.detail_open);
+        app.update(Input(InputEvent::Action(Action::Edit)));
+        assert_eq!(app.scanner.note_input, Some((String::new(), 0)));
+        for character in "NAS q".chars() {
+            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(
+                character,
+            )))));
+        }
+        assert!(app.running);
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
+        assert_eq!(app.scanner.note_input, None);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Inventory(inventory))]
+                if inventory.devices[0].note == "NAS q"
+        ));
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(!app.scanner.detail_open);
+
+        app.reset_session_memory();
+        assert_eq!(app.scanner.inventory.len(), 1);
+    }
+
+    #[test]
+    fn scanner_restores_edit_mru_completion_and_panel_click_semantics() {
+        let mut config = crate::ConfigData::default();
+        config.session.scanner.cidr = "10.0.0.0/24".into();
+        config.session.history.cidrs = vec!["192.168.50.0/24".into(), "10.0.0.0/24".into()];
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+        app.page = Page::Scanner;
+
+        app.update(Input(InputEvent::Action(Action::SelectScannerInput(3))));
+        assert!(app.scanner.editing);
+        assert_eq!(app.scanner.cursor, 3);
+        app.update(Input(InputEvent::Action(Action::History)));
+        assert!(app.scanner.history_open);
+        let effects = app.update(Input(InputEvent::Action(Action::SelectScannerHistory(0))));
+        assert_eq!(app.scanner.cidr, "192.168.50.0/24");
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Scanner(_))]
+        ));
+
+        app.scanner.cidr = "10.".into();
+        app.scanner.cursor = app.scanner.cidr.len();
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Right))));
+        assert_eq!(app.scanner.cidr, "10.0.0.0/24");
+
+        let effects = app.update(Input(InputEvent::Action(Action::ActivateScannerPanel)));
+        assert!(!app.scanner.editing);
+        assert!(
+            !effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::StartScan { .. }))
+        );
+        let effects = app.update(Input(InputEvent::Action(Action::ActivateScannerPanel)));
+        assert!(
+            effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::StartScan { .. }))
+        );
+        assert!(effects.iter().any(|effect| matches!(
+            effect,
+            Effect::PersistSession(crate::SessionUpdate::CidrHistory(_))
+        )));
+    }
+
+    #[test]
+    fn scanner_defaults_to_the_active_adapter_network_until_user_edits_it() {
+        let mut config = crate::ConfigData::default();
+        config.session.scanner.cidr = "10.20.30.0/24".into();
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+        app.update(Runtime(RuntimeEvent::AdaptersUpdated(vec![AdapterInfo {
+            name: "WLAN".into(),
+            ipv4: "192.168.50.35".into(),
+            cidr: Some("192.168.50.35/24".into()),
+            status: "up".into(),
+            is_physical: true,
+            ..AdapterInfo::default()
+        }])));
+        assert_eq!(app.scanner.cidr, "192.168.50.0/24");
+
+        app.page = Page::Scanner;
+        app.scanner.editing = true;
+        app.scanner.cursor = app.scanner.cidr.len();
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
+        assert!(!app.scanner.auto_cidr);
+        let edited = app.scanner.cidr.clone();
+        app.update(Runtime(RuntimeEvent::AdaptersUpdated(vec![AdapterInfo {
+            ipv4: "172.16.8.9".into(),
+            cidr: Some("172.16.8.9/16".into()),
+            status: "up".into(),
+            is_physical: true,
+            ..AdapterInfo::default()
+        }])));
+        assert_eq!(app.scanner.cidr, edited);
+    }
+
+    #[test]
+    fn retention_limits_drop_the_oldest_rows_and_count_them() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            retention: crate::RetentionLimits {
+                samples: 100,
+                scan_rows: 1_000,
+                ..crate::RetentionLimits::default()
+            },
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job, .. } = effects[0].clone() else {
+            panic!("expected typed ping effect");
+        };
+        for sequence in 1..=150 {
+            app.update(Runtime(RuntimeEvent::PingSample {
+                job,
+                sample: crate::PingSample {
+                    sequence,
+                    latency_ms: Some(12),
+                    ttl: Some(64),
+                    size: 32,
+                    sent: sequence,
+                    received: sequence,
+                    min_ms: Some(12),
+                    average_ms: Some(12.0),
+                    max_ms: Some(12),
+                    loss_percent: 0.0,
+                    route: Vec::new(),
+                    timestamps: None,
+                },
+            }));
+        }
+        let ping = &app.diagnostics.ping;
+        assert_eq!(ping.samples.len(), 100);
+        assert_eq!(ping.samples[0].sequence, 51);
+        assert_eq!(ping.common.log.len(), 100);
+        assert_eq!(ping.common.trimmed, 100, "50 samples and 50 log lines");
+
+        app.page = Page::Scanner;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Some(Effect::StartScan { job, .. }) = effects
+            .iter()
+            .find(|effect| matches!(effect, Effect::StartScan { .. }))
+            .cloned()
+        else {
+            panic!("expected scan effect");
+        };
+        // Found from the top of the range down, so the earliest hosts sort last.
+        for index in (0..1_010u32).rev() {
+            app.update(Runtime(RuntimeEvent::ScanHostFound {
+                job,
+                host: Box::new(ScanHost {
+                    ip: std::net::Ipv4Addr::from(0x0a00_0000 + index).to_string(),
+                    ..ScanHost::default()
+                }),
+            }));
+        }
+        assert_eq!(app.scanner.results.len(), 1_000);
+        assert_eq!(app.scanner.trimmed, 10);
+        assert_eq!(app.scanner.results.last().unwrap().ip, "10.0.3.231");
+        assert_eq!(app.scanner.results[0].ip, "10.0.0.0");
+    }
+
+    #[test]
+    fn typed_ping_events_ignore_stale_and_post_cancel_samples() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job, request } = effects[0].clone() else {
+            panic!("expected typed ping effect");
+        };
+        assert_eq!(request, crate::PingRequest::default());
+
+        let sample = crate::PingSample {
+            sequence: 1,
+            latency_ms: Some(12),
+            ttl: Some(64),
+            size: 32,
+            sent: 2,
+            received: 2,
+            min_ms: Some(12),
+            average_ms: Some(12.0),
+            max_ms: Some(12),
+            loss_percent: 0.0,
+            route: Vec::new(),
+            timestamps: None,
+        };
+        app.update(Runtime(RuntimeEvent::PingSample {
+            job: JobId {
+                generation: job.generation + 1,
+                ..job
+            },
+            sample: sample.clone(),
+        }));
+        assert!(app.diagnostics.ping.samples.is_empty());
+
+        app.update(Runtime(RuntimeEvent::PingSample {
+            job,
+            sample: sample.clone(),
+        }));
+        assert_eq!(app.diagnostics.ping.samples.first(), Some(&sample));
+        assert_eq!(
+            app.diagnostics.ping.common.log.last().map(String::as_str),
+            Some("Reply seq=1 bytes=32 ttl=64 time=12ms")
+        );
+        app.update(Runtime(RuntimeEvent::PingSample {
+            job,
+            sample: crate::PingSample {
+                route: vec!["192.0.2.1".into(), "198.51.100.7".into()],
+                timestamps: Some(crate::IcmpTimestamps {
+                    originate_ms: 1_000,
+                    receive_ms: 1_006,
+                    transmit_ms: 1_006,
+                }),
+                ..sample.clone()
+            },
+        }));
+        let log = &app.diagnostics.ping.common.log;
+        assert_eq!(log[log.len() - 2], "  RR: 192.0.2.1 → 198.51.100.7");
+        assert_eq!(
+            log[log.len() - 1],
+            "  TS: originate=1000 receive=1006 transmit=1006 (Δ 6 ms)"
+        );
+
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Toggle))),
+            [Effect::StopPing(job)]
+        );
+        app.update(Runtime(RuntimeEvent::PingSample { job, sample }));
+        assert_eq!(app.diagnostics.ping.samples.len(), 2);
+
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job: restarted, .. } = effects[0] else {
+            panic!("expected restarted ping effect");
+        };
+        assert!(restarted.generation > job.generation);
+        let summary = crate::PingSummary {
+            sent: 4,
+            received: 4,
+            min_ms: Some(10),
+            average_ms: Some(12.5),
+            max_ms: Some(15),
+            loss_percent: 0.0,
+        };
+        app.update(Runtime(RuntimeEvent::PingFinished {
+            job,
+            summary: summary.clone(),
+        }));
+        assert_eq!(app.diagnostics.ping.common.job, Some(restarted));
+        app.update(Runtime(RuntimeEvent::PingFinished {
+            job: restarted,
+            summary: summary.clone(),
+        }));
+        assert_eq!(app.diagnostics.ping.summary, Some(summary));
+        assert_eq!(app.diagnostics.ping.common.status, TaskStatus::Done);
+    }
+
+    #[test]
+    fn trace_start_cancel_restart_ignores_stale_and_finishes_current_job() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Trace;
+
+        let first = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartTrace { job: first, .. } = first[0] else {
+            panic!("expected trace effect");
+        };
+        let hop = crate::TraceHop {
+            ttl: 1,
+            address: Some("192.0.2.1".into()),
+            hostname: None,
+            latency_ms: Some(12),
+        };
+        app.update(Runtime(RuntimeEvent::TraceHop {
+            job: first,
+            hop: hop.clone(),
+        }));
+        assert_eq!(app.diagnostics.trace.hops, [hop]);
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Toggle))),
+            [Effect::StopTrace(first)]
+        );
+
+        let restarted = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartTrace { job: restarted, .. } = restarted[0] else {
+            panic!("expected restarted trace effect");
+        };
+        app.update(Runtime(RuntimeEvent::TraceFinished {
+            job: first,
+            hops: 1,
+        }));
+        assert_eq!(app.diagnostics.trace.common.job, Some(restarted));
+        app.update(Runtime(RuntimeEvent::TraceFinished {
+            job: restarted,
+            hops: 8,
+        }));
+        assert_eq!(app.diagnostics.trace.common.status, TaskStatus::Done);
+        assert_eq!(app.diagnostics.trace.common.job, None);
+    }
+
+    #[test]
+    fn a_comparison_trace_runs_beside_the_first_until_both_end() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Trace;
+        app.diagnostics.trace.request.target = "cdn-a.example".into();
+        app.diagnostics.trace.via_input = "eth0".into();
+        app.diagnostics.trace.compare_target = "cdn-b.example".into();
+
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let [
+            Effect::StartTrace { job: first, .. },
+            Effect::StartTrace {
+                job: second,
+                request,
+            },
+            ..,
+        ] = effects.as_slice()
+        else {
+            panic!("expected two trace effects: {effects:?}");
+        };
+        let (first, second) = (*first, *second);
+        assert_eq!(request.target, "cdn-b.example");
+        assert_eq!(
+            request.via.as_deref(),
+            Some("eth0"),
+            "the interface carries over"
+        );
+
+        let hop = |ttl, address: &str| crate::TraceHop {
+            ttl,
+            address: Some(address.into()),
+            hostname: None,
+            latency_ms: Some(5),
+        };
+        for (job, address) in [(first, "203.0.113.1"), (second, "198.51.100.1")] {
+            app.update(Runtime(RuntimeEvent::TraceHop {
+                job,
+                hop: hop(1, "192.168.1.1"),
+            }));
+            app.update(Runtime(RuntimeEvent::TraceHop {
+                job,
+                hop: hop(2, address),
+            }));
+        }
+        let comparison = app.diagnostics.trace.comparison.clone().unwrap();
+        assert_eq!(app.diagnostics.trace.hops.len(), 2);
+        assert_eq!(
+            crate::divergence(&app.diagnostics.trace.hops, &comparison.hops),
+            Some(crate::TraceDivergence::At(2))
+        );
+
+        app.update(Runtime(RuntimeEvent::TraceFinished {
+            job: first,
+            hops: 2,
+        }));
+        assert_eq!(app.diagnostics.trace.common.status, TaskStatus::Running);
+        assert_eq!(app.diagnostics.trace.common.job, Some(second));
+        app.update(Runtime(RuntimeEvent::TraceFinished {
+            job: second,
+            hops: 2,
+        }));
+        assert_eq!(app.diagnostics.trace.common.status, TaskStatus::Done);
+        assert_eq!(
+            app.diagnostics.trace.common.detail,
+            "route completed in 2 hops"
+        );
+
+        // Stopping stops both runs.
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartTrace { job: first, .. } = effects[0] else {
+            panic!("expected trace effect");
+        };
+        let Effect::StartTrace { job: second, .. } = effects[1] else {
+            panic!("expected comparison effect");
+        };
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Toggle))),
+            [Effect::StopTrace(first), Effect::StopTrace(second)]
+        );
+
+        app.diagnostics.trace.compare_target = "10.0.0.0/8".into();
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        let comparison = app.diagnostics.trace.comparison.as_ref().unwrap();
+        assert_eq!(comparison.job, None, "a range is not a trace target");
+        assert!(comparison.error.is_some());
+    }
+
+    #[test]
+    fn asn_lookup_covers_new_public_hops_only() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Trace;
+        let hop = |ttl, address: &str| crate::TraceHop {
+            ttl,
+            address: Some(address.into()),
+            hostname: None,
+            latency_ms: Some(5),
+        };
+        app.diagnostics.trace.hops = vec![hop(1, "192.168.1.1"), hop(2, "193.0.6.139")];
+
+        let effects = app.update(Input(InputEvent::Action(Action::Refresh)));
+        let [Effect::LookupAsn { job, addresses, .. }] = effects.as_slice() else {
+            panic!("expected an ASN lookup: {effects:?}");
+        };
+        let job = *job;
+        assert_eq!(addresses, &["193.0.6.139"]);
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Refresh)))
+                .is_empty(),
+            "one lookup at a time"
+        );
+
+        let origin = crate::AsnOrigin {
+            address: "193.0.6.139".into(),
+            prefix: Some("193.0.0.0/21".into()),
+            asn: Some(3333),
+            holder: None,
+            path: vec![3333],
+        };
+        app.update(Runtime(RuntimeEvent::AsnFound {
+            job,
+            origin: origin.clone(),
+        }));
+        app.update(Runtime(RuntimeEvent::AsnLookupFinished { job }));
+        assert_eq!(app.diagnostics.trace.asn.job, None);
+        assert_eq!(app.diagnostics.trace.asn.origins, [origin]);
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Refresh)))
+                .is_empty(),
+            "known routers are not asked again"
+        );
+    }
+
+    #[test]
+    fn cve_lookup_asks_once_per_version_from_the_detail_drawer() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        app.scanner.results = vec![ScanHost {
+            ip: "192.168.1.20".into(),
+            banners: vec![crate::ServiceBanner {
+                port: crate::SSH_PORT,
+                text: "SSH-2.0-OpenSSH_7.2p2".into(),
+            }],
+            ..ScanHost::default()
+        }];
+        app.scanner.detail_open = true;
+
+        let effects = app.update(Input(InputEvent::Action(Action::Refresh)));
+        let [Effect::LookupCves { job, software, .. }] = effects.as_slice() else {
+            panic!("expected a CVE lookup: {effects:?}");
+        };
+        let (job, software) = (*job, software[0].clone());
+        assert_eq!(software.label(), "OpenSSH 7.2");
+
+        let hint = crate::CveHint {
+            software,
+            total: 12,
+            by_severity: [1, 4, 6, 1],
+        };
+        app.update(Runtime(RuntimeEvent::CveHintFound {
+            job,
+            hint: hint.clone(),
+        }));
+        app.update(Runtime(RuntimeEvent::CveLookupFinished { job }));
+        assert_eq!(app.scanner.cve.job, None);
+        assert_eq!(app.scanner.cve.hints, [hint]);
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Refresh)))
+                .is_empty(),
+            "known versions are not asked again"
+        );
+        assert!(app.scanner.detail_open);
+    }
+
+    #[test]
+    fn typed_failure_only_mutates_its_current_tool_generation() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Trace;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartTrace { job, request } = effects[0].clone() else {
+            panic!("expected typed trace effect");
+        };
+        assert_eq!(request, crate::TraceRequest::default());
+
+        app.update(Runtime(RuntimeEvent::TraceFailed {
+            job,
+            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Timeout, "trace timeout"),
+        }));
+        assert_eq!(
+            app.diagnostics.trace.common.status,
+            TaskStatus::Failed("trace timeout".into())
+        );
+        assert_eq!(app.diagnostics.trace.common.job, None);
+        assert_eq!(
+            app.diagnostics
+                .trace
+                .common
+                .error
+                .as_ref()
+                .map(|error| error.code),
+            Some(crate::RuntimeErrorCode::Timeout)
+        );
+        assert_eq!(app.diagnostics.ping.common.status, TaskStatus::Idle);
+    }
+
+    #[test]
+    fn port_scan_effect_carries_validated_shape_not_generic_text() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::PortScan;
+        app.scan_concurrency = 64;
+        app.diagnostics.port_scan.persist = crate::PortScanPersist {
+            target: "192.168.1.10".into(),
+            start_port: "20".into(),
+            end_port: "443".into(),
+            timeout_ms: "250".into(),
+        };
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPortScan { request, .. } = &effects[0] else {
+            panic!("expected typed port scan effect");
+        };
+        assert_eq!(request.start_port, 20);
+        assert_eq!(request.end_port, 443);
+        assert_eq!(request.concurrency, 64);
+    }
+
+    fn select_setting(app: &mut AppModel, setting: crate::SettingId) {
+        let index = crate::SETTINGS
+            .iter()
+            .position(|candidate| *candidate == setting)
+            .unwrap();
+        app.update(Input(InputEvent::Action(Action::SelectSetting(index))));
+        assert_eq!(app.settings.current(), setting);
+    }
+
+    #[test]
+    fn settings_emit_explicit_persistence_effects() {
+        let mut app = AppModel {
+            page: Page::Settings,
+            ..AppModel::default()
+        };
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Down)))
+                .is_empty()
+        );
+        assert_eq!(app.settings.current(), crate::SettingId::LowPower);
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Right))),
+            [Effect::PersistPreferences(crate::Preferences {
+                language: Language::En,
+                theme: ThemeId::Classic,
+                scan_concurrency: 50,
+                scan_adaptive: true,
+                retention: crate::RetentionLimits::default(),
+                drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: crate::LowPowerMode::On,
+                offline: false,
+                check_updates: false,
+                chart_style: crate::ChartStyle::Blocks,
+                region_format: crate::RegionFormat::Iso,
+                clock_format: crate::ClockFormat::TwentyFourHour,
+                show_utc: false,
+                color_mode: crate::ColorMode::Standard,
+            })]
+        );
+
+        select_setting(&mut app, crate::SettingId::ScanConcurrency);
+        assert_eq!(app.settings.current(), crate::SettingId::ScanConcurrency);
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Right))),
+            [Effect::PersistPreferences(crate::Preferences {
+                language: Language::En,
+                theme: ThemeId::Classic,
+                scan_concurrency: 60,
+                scan_adaptive: true,
+                retention: crate::RetentionLimits::default(),
+                drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: crate::LowPowerMode::On,
+                offline: false,
+                check_updates: false,
+                chart_style: crate::ChartStyle::Blocks,
+                region_format: crate::RegionFormat::Iso,
+                clock_format: crate::ClockFormat::TwentyFourHour,
+                show_utc: false,
+                color_mode: crate::ColorMode::Standard,
+            })]
+        );
+        assert_eq!(app.scan_concurrency, 60);
+
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::ToggleLanguage))),
+            [Effect::PersistPreferences(crate::Preferences {
+                language: Language::Zh,
+                theme: ThemeId::Classic,
+                scan_concurrency: 60,
+                scan_adaptive: true,
+                retention: crate::RetentionLimits::default(),
+                drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: crate::LowPowerMode::On,
+                offline: false,
+                check_updates: false,
+                chart_style: crate::ChartStyle::Blocks,
+                region_format: crate::RegionFormat::Iso,
+                clock_format: crate::ClockFormat::TwentyFourHour,
+                show_utc: false,
+                color_mode: crate::ColorMode::Standard,
+            })]
+        );
+
+        select_setting(&mut app, crate::SettingId::Theme);
+        assert_eq!(app.settings.current(), crate::SettingId::Theme);
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Confirm))),
+            [Effect::PersistPreferences(crate::Preferences {
+                language: Language::Zh,
+                theme: ThemeId::Nord,
+                scan_concurrency: 60,
+                scan_adaptive: true,
+                retention: crate::RetentionLimits::default(),
+                drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: crate::LowPowerMode::On,
+                offline: false,
+                check_updates: false,
+                chart_style: crate::ChartStyle::Blocks,
+                region_format: crate::RegionFormat::Iso,
+                clock_format: crate::ClockFormat::TwentyFourHour,
+                show_utc: false,
+                color_mode: crate::ColorMode::Standard,
+            })]
+        );
+        select_setting(&mut app, crate::SettingId::ColorMode);
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Confirm))),
+            [Effect::PersistPreferences(crate::Preferences {
+                language: Language::Zh,
+                theme: ThemeId::Nord,
+                scan_concurrency: 60,
+                scan_adaptive: true,
+                retention: crate::RetentionLimits::default(),
+                drop_alert_percent: crate::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: crate::LowPowerMode::On,
+                offline: false,
+                check_updates: false,
+                chart_style: crate::ChartStyle::Blocks,
+                region_format: crate::RegionFormat::Iso,
+                clock_format: crate::ClockFormat::TwentyFourHour,
+                show_utc: false,
+                color_mode: crate::ColorMode::ColorBlind,
+            })]
+        );
+        app.update(Input(InputEvent::Action(Action::Down)));
+        assert_eq!(app.settings.current(), crate::SettingId::PublicIpEndpoints);
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Confirm)))
+                .is_empty()
+        );
+        assert!(app.settings.input.is_some(), "text rows open an editor");
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Esc))));
+        assert!(app.settings.input.is_none());
+
+        app.diagnostics.ping.request.target = "remembered.example".into();
+        app.diagnostics.target_history = vec!["remembered.example".into()];
+        select_setting(&mut app, crate::SettingId::ClearSession);
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Confirm)))
+                .is_empty()
+        );
+        assert_eq!(
+            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('y'))))),
+            [Effect::PersistSession(crate::SessionUpdate::Reset(
+                crate::UiPersist {
+                    last_tab: Page::Settings as u8,
+                    last_diag_tool: 0,
+                }
+            ))]
+        );
+        assert_eq!(app.diagnostics.ping.request, crate::PingRequest::default());
+        assert!(app.diagnostics.target_history.is_empty());
+        assert_eq!(app.settings.done, Some(crate::SettingId::ClearSession));
+        assert_eq!(app.page, Page::Settings);
+
+        app.update(Input(InputEvent::Action(Action::SelectSetting(
+            crate::SETTINGS_ITEMS - 1,
+        ))));
+        app.update(Input(InputEvent::Action(Action::Down)));
+        assert_eq!(app.settings.selected, 0);
+        assert_eq!(app.settings.done, None);
+    }
+
+    #[test]
+    fn destructive_actions_wait_for_a_confirmation_that_defaults_to_no() {
+        let mut app = AppModel {
+            page: Page::Settings,
+            ..AppModel::default()
+        };
+        app.diagnostics.target_history = vec!["remembered.example".into()];
+        select_setting(&mut app, crate::SettingId::ClearSession);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert_eq!(
+            app.popups,
+            [crate::Popup::Confirm(crate::Confirmation::new(
+                crate::Confirm::ClearSession
+            ))]
+        );
+        // Enter on the default "No" keeps everything; so does Esc.
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Confirm)))
+                .is_empty()
+        );
+        assert!(app.popups.is_empty());
+        assert_eq!(app.diagnostics.target_history, ["remembered.example"]);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        app.update(Input(InputEvent::Action(Action::Down)));
+        assert_eq!(
+            app.settings.selected,
+            crate::SETTINGS
+                .iter()
+                .position(|setting| *setting == crate::SettingId::ClearSession)
+                .unwrap(),
+            "the question holds input"
+        );
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert_eq!(app.diagnostics.target_history, ["remembered.example"]);
+
+        // Moving to "Yes" and pressing Enter runs it.
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        app.update(Input(InputEvent::Action(Action::Right)));
+        assert!(matches!(
+            app.popups[..],
+            [crate::Popup::Confirm(crate::Confirmation { yes: true, .. })]
+        ));
+        assert_eq!(
+            app.update(Input(InputEvent::Action(Action::Confirm))).len(),
+            1
+        );
+        assert!(app.diagnostics.target_history.is_empty());
+
+        // Deleting a router mapping is asked first; listing is not.
+        app.page = Page::Diagnostics;
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Upnp;
+        app.diagnostics.upnp.request.action = crate::UpnpAction::Delete;
+        app.diagnostics.upnp.external_port_input = "8080".into();
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert_eq!(
+            app.popups,
+            [crate::Popup::Confirm(crate::Confirmation::new(
+                crate::Confirm::DeleteUpnpMapping {
+                    protocol: crate::LanProtocol::Tcp,
+                    external_port: 8080,
+                }
+            ))]
+        );
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('y')))));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::StartUpnp { request, .. }]
+                if request.action == crate::UpnpAction::Delete && request.external_port == 8080
+        ));
+    }
+
+    #[test]
+    fn offline_mode_hides_the_public_ip_and_keeps_refreshes_local() {
+        let mut app = AppModel {
+            page: Page::Settings,
+            ..AppModel::default()
+        };
+        app.dashboard.snapshot.public_info = Some(crate::PublicIpInfo {
+            ip: "203.0.113.7".into(),
+            ..crate::PublicIpInfo::default()
+        });
+        select_setting(&mut app, crate::SettingId::Offline);
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(app.offline);
+        assert!(app.dashboard.snapshot.public_info.is_none());
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistPreferences(crate::Preferences {
+                offline: true,
+                ..
+            })]
+        ));
+        assert!(matches!(
+            app.recheck_network().as_slice(),
+            [
+                Effect::RefreshAdapters { .. },
+                Effect::RefreshDashboard {
+                    request: crate::DashboardRequest { offline: true, .. },
+                    ..
+                }
+            ]
+        ));
+
+        // Going back online looks the address up again straight away.
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(matches!(
+            effects.as_slice(),
+            [
+                Effect::PersistPreferences(crate::Preferences { offline: false, .. }),
+                Effect::RefreshDashboard {
+                    request: crate::DashboardRequest { offline: false, .. },
+                    ..
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn opted_in_update_checks_offer_only_newer_releases() {
+        let mut app = AppModel::default();
+        assert!(
+            !app.bootstrap_effects()
+                .iter()
+                .any(|effect| matches!(effect, Effect::CheckUpdate { .. })),
+            "the check is opt-in"
+        );
+
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Settings as u8,
+        ))));
+        select_setting(&mut app, crate::SettingId::CheckUpdates);
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        let [
+            Effect::PersistPreferences(crate::Preferences {
+                check_updates: true,
+                ..
+            }),
+            Effect::CheckUpdate { job, .. },
+        ] = effects.as_slice()
+        else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        let release = |version: &str| crate::ReleaseInfo {
+            version: version.into(),
+            url: "https://github.com/newcovid/iptools/releases".into(),
+            notes: "- Faster scans".into(),
+        };
+        app.update(Runtime(RuntimeEvent::UpdateCheckFinished {
+            job: *job,
+            release: release(&format!("v{}", crate::CURRENT_VERSION)),
+        }));
+        assert_eq!(app.update.available, None, "the running version is current");
+        app.update(Input(InputEvent::Action(Action::ShowUpdate)));
+        assert!(!app.popup_open(crate::Popup::Update));
+
+        app.update(Runtime(RuntimeEvent::UpdateCheckFinished {
+            job: *job,
+            release: release("v999.0.0"),
+        }));
+        assert_eq!(app.update.available, None, "a finished job is not reused");
+        let job = app.check_update().iter().find_map(|effect| match effect {
+            Effect::CheckUpdate { job, .. } => Some(*job),
+            _ => None,
+        });
+        app.update(Runtime(RuntimeEvent::UpdateCheckFinished {
+            job: job.unwrap(),
+            release: release("v999.0.0"),
+        }));
+        assert_eq!(app.update.available, Some(release("v999.0.0")));
+
+        app.update(Input(InputEvent::Action(Action::ShowUpdate)));
+        assert!(app.popup_open(crate::Popup::Update));
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Down)))
+                .is_empty()
+        );
+        assert_eq!(app.settings.current(), crate::SettingId::CheckUpdates);
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(!app.popup_open(crate::Popup::Update));
+
+        app.offline = true;
+        assert!(app.check_update().is_empty(), "offline mode never checks");
+    }
+
+    #[test]
+    fn doctor_report_opens_from_settings_and_reruns_on_refresh() {
+        let mut app = AppModel::default();
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Settings as u8,
+        ))));
+        select_setting(&mut app, crate::SettingId::Doctor);
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        let [Effect::RunDoctor { job }] = effects.as_slice() else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        assert!(app.popup_open(crate::Popup::Doctor) && app.doctor.running());
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Refresh)))
+                .is_empty(),
+            "a running check is not restarted"
+        );
+
+        let checks = vec![crate::DoctorCheck::new(
+            crate::DoctorCheckKind::Icmp,
+            crate::DoctorStatus::Fail,
+            "EPERM",
+        )];
+        app.update(Runtime(RuntimeEvent::DoctorFinished {
+            job: *job,
+            checks: checks.clone(),
+        }));
+        assert!(!app.doctor.running());
+        assert_eq!(app.doctor.checks, checks);
+
+        let effects = app.update(Input(InputEvent::Action(Action::Refresh)));
+        let [Effect::RunDoctor { job: rerun }] = effects.as_slice() else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        assert_ne!(rerun, job);
+        app.update(Runtime(RuntimeEvent::DoctorFinished {
+            job: *job,
+            checks: Vec::new(),
+        }));
+        assert!(app.doctor.running(), "a stale report is ignored");
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(!app.popup_open(crate::Popup::Doctor));
+        assert_eq!(app.settings.current(), crate::SettingId::Doctor);
+    }
+
+    #[test]
+    fn a_preset_runs_its_stages_in_turn_and_collects_a_report() {
+        use crate::{StageOutcome, StageStatus};
+
+        let mut app = AppModel::default();
+        app.capabilities.icmp = true;
+        app.diagnostics.ping.request.target = "192.168.1.10".into();
+        app.update(Input(InputEvent::Action(Action::Presets)));
+        assert!(app.popup_open(crate::Popup::Pipeline) && app.pipeline.picking);
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        let [Effect::StartPing { job, request }, ..] = effects.as_slice() else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        assert_eq!(request.target, "192.168.1.10");
+        assert_eq!(
+            app.pipeline.preset,
+            Some(crate::PipelinePreset::FullHostCheck)
+        );
+
+        let mut effects = Vec::new();
+        for sequence in 0..crate::PIPELINE_PING_SAMPLES as u64 {
+            effects = app.update(Runtime(RuntimeEvent::PingSample {
+                job: *job,
+                sample: crate::PingSample {
+                    sequence,
+                    latency_ms: (sequence > 0).then_some(10),
+                    ttl: Some(64),
+                    size: 32,
+                    sent: sequence + 1,
+                    received: sequence,
+                    min_ms: Some(10),
+                    average_ms: Some(10.0),
+                    max_ms: Some(10),
+                    loss_percent: 0.0,
+                    route: Vec::new(),
+                    timestamps: None,
+                },
+            }));
+        }
+        let [
+            Effect::StopPing(stopped),
+            Effect::StartTrace { job: trace, .. },
+            ..,
+        ] = effects.as_slice()
+        else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        assert_eq!(stopped, job);
+        assert_eq!(
+            app.pipeline.stages[0].status,
+            StageStatus::Done(StageOutcome::Ping {
+                sent: 5,
+                received: 4,
+                average_ms: Some(10.0),
+            })
+        );
+
+        let effects = app.update(Runtime(RuntimeEvent::TraceFailed {
+            job: *trace,
+            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Network, "unreachable"),
+        }));
+        let [Effect::StartPortScan { job: scan, request }, ..] = effects.as_slice() else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        assert_eq!(request.target, "192.168.1.10");
+        assert_eq!(
+            app.pipeline.stages[1].status,
+            StageStatus::Failed("unreachable".into())
+        );
+
+        app.update(Runtime(RuntimeEvent::PortScanOpen {
+            job: *scan,
+            result: crate::PortScanResult {
+                port: 22,
+                service: "ssh".into(),
+            },
+        }));
+        let effects = app.update(Runtime(RuntimeEvent::PortScanFinished {
+            job: *scan,
+            scanned: 1_024,
+            total: 1_024,
+            cancelled: false,
+        }));
+        assert!(
+            !effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::StartWebCheck { .. })),
+            "no web port is open"
+        );
+        assert_eq!(app.pipeline.stages[3].status, StageStatus::Skipped);
+        assert!(!app.pipeline.running());
+
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(!app.popup_open(crate::Popup::Pipeline));
+        app.diagnostics.ping.request.target = "example.com".into();
+        app.diagnostics.tool = DiagnosticTool::Ping;
+        app.pipeline.selected = 2;
+        app.update(Input(InputEvent::Action(Action::Presets)));
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(matches!(effects.as_slice(), [Effect::StopPing(_), ..]));
+        assert!(
+            app.pipeline
+                .stages
+                .iter()
+                .all(|report| report.status == StageStatus::Skipped)
+        );
+    }
+
+    #[test]
+    fn typed_settings_are_validated_before_they_are_saved() {
+        let mut app = AppModel {
+            page: Page::Settings,
+            ..AppModel::default()
+        };
+        let key = |code| Input(InputEvent::Key(KeyEvent::plain(code)));
+        select_setting(&mut app, crate::SettingId::PingInterval);
+        assert_eq!(app.settings.current(), crate::SettingId::PingInterval);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert_eq!(app.settings.input, Some(("1000".into(), 4)));
+
+        for _ in 0..4 {
+            app.update(key(KeyCode::Backspace));
+        }
+        app.update(key(KeyCode::Char('x')));
+        app.update(key(KeyCode::Char('5')));
+        app.update(key(KeyCode::Char('0')));
+        assert_eq!(app.settings.input, Some(("50".into(), 2)));
+        assert!(app.update(key(KeyCode::Enter)).is_empty());
+        assert_eq!(
+            app.settings.error,
+            Some(crate::SettingError::OutOfRange {
+                min: 100,
+                max: 10_000
+            })
+        );
+        assert_eq!(app.diagnostics.ping.request.interval_ms, 1_000);
+
+        app.update(key(KeyCode::Char('0')));
+        assert_eq!(app.settings.error, None);
+        let effects = app.update(key(KeyCode::Enter));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Ping(ping))] if ping.interval_ms == 500
+        ));
+        assert!(app.settings.input.is_none());
+
+        select_setting(&mut app, crate::SettingId::PublicIpEndpoints);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let text = "plaintext https://ifconfig.me/ip".to_string();
+        app.settings.input = Some((text.clone(), text.len()));
+        let endpoints = vec![crate::Endpoint {
+            url: "https://ifconfig.me/ip".into(),
+            kind: "plaintext".into(),
+        }];
+        assert_eq!(
+            app.update(key(KeyCode::Enter)),
+            [Effect::PersistPublicIp(crate::PublicIpConfig {
+                endpoints: endpoints.clone(),
+                use_system_proxy: true,
+            })]
+        );
+        assert_eq!(app.public_ip_config().endpoints, endpoints);
+
+        // A jump host applies to the next port scan.
+        select_setting(&mut app, crate::SettingId::JumpHost);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        app.settings.input = Some(("ops@bastion -p 1".into(), 16));
+        assert!(app.update(key(KeyCode::Enter)).is_empty());
+        assert_eq!(
+            app.settings.error,
+            Some(crate::SettingError::InvalidJumpHost)
+        );
+        app.settings.input = Some(("ops@bastion:2222".into(), 16));
+        assert_eq!(
+            app.update(key(KeyCode::Enter)),
+            [Effect::PersistJumpHost("ops@bastion:2222".into())]
+        );
+        assert_eq!(
+            app.diagnostics.port_scan.request.jump_host.as_deref(),
+            Some("ops@bastion:2222")
+        );
+
+        // Restoring defaults keeps the language and reports every store.
+        app.language = Language::Zh;
+        app.scan_concurrency = 200;
+        select_setting(&mut app, crate::SettingId::RestoreDefaults);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let effects = app.update(Input(InputEvent::Action(Action::Answer(true))));
+        assert_eq!(effects.len(), 6);
+        assert_eq!(app.diagnostics.port_scan.request.jump_host, None);
+        assert_eq!(app.language, Language::Zh);
+        assert_eq!(app.scan_concurrency, 50);
+        assert_eq!(app.diagnostics.ping.request.interval_ms, 1_000);
+        assert_eq!(
+            effects[3],
+            Effect::PersistPublicIp(crate::PublicIpConfig::default())
+        );
+        assert_eq!(
+            effects.last(),
+            Some(&Effect::PersistTabs(Page::ALL.to_vec()))
+        );
+        assert_eq!(app.settings.done, Some(crate::SettingId::RestoreDefaults));
+    }
+
+    #[test]
+    fn watch_targets_are_checked_on_a_schedule_and_fill_the_tiles() {
+        let key = |code| Input(InputEvent::Key(KeyEvent::plain(code)));
+        let mut app = AppModel {
+            page: Page::Settings,
+            ..AppModel::default()
+        };
+        assert!(app.update(Tick(250)).is_empty());
+        select_setting(&mut app, crate::SettingId::WatchTargets);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let text = "NAS=192.168.1.10, bad host".to_string();
+        app.settings.input = Some((text.clone(), text.len()));
+        assert!(app.update(key(KeyCode::Enter)).is_empty());
+        assert_eq!(
+            app.settings.error,
+            Some(crate::SettingError::InvalidWatchTarget("bad host".into()))
+        );
+        let text = "NAS=192.168.1.10, VPN=vpn.example.com:443".to_string();
+        app.settings.input = Some((text.clone(), text.len()));
+        let targets = crate::parse_watch_targets(&text).unwrap();
+        assert_eq!(
+            app.update(key(KeyCode::Enter)),
+            [Effect::PersistWatch(targets.clone())]
+        );
+
+        let effects = app.update(Tick(250));
+        let [Effect::StartWatch { job, ref request }] = effects[..] else {
+            panic!("expected a watch round: {effects:?}");
+        };
+        assert_eq!(request.targets, targets);
+        // Nothing new while the round runs or before the next one is due.
+        assert!(app.update(Tick(250)).is_empty());
+        app.update(Runtime(RuntimeEvent::WatchChecked {
+            job,
+            check: crate::WatchCheck {
+                target: targets[1].clone(),
+                latency_ms: None,
+                error: Some("connection refused".into()),
+            },
+        }));
+        app.update(Runtime(RuntimeEvent::WatchFinished { job }));
+        assert_eq!(app.watch.tiles[0].health(), crate::WatchHealth::Pending);
+        assert_eq!(app.watch.tiles[1].health(), crate::WatchHealth::Down);
+        assert!(app.update(Tick(1_000)).is_empty());
+        assert!(matches!(
+            app.update(Tick(crate::WATCH_INTERVAL_MS))[..],
+            [Effect::StartWatch { .. }]
+        ));
+
+        // Offline mode pauses watching; a reloaded config keeps the tiles.
+        app.watch.job = None;
+        app.offline = true;
+        assert!(app.update(Tick(crate::WATCH_INTERVAL_MS)).is_empty());
+        let mut watch = targets;
+        watch.push(crate::WatchTarget {
+            name: "Files".into(),
+            target: "ftp://files.example.com".into(),
+            ..crate::WatchTarget::default()
+        });
+        watch.push(crate::WatchTarget {
+            name: "Printer".into(),
+            target: "192.168.1.20".into(),
+            budget_ms: Some(0),
+            ..crate::WatchTarget::default()
+        });
+        let mut config = crate::ConfigData {
+            watch,
+            ..crate::ConfigData::default()
+        };
+        assert_eq!(
+            config.validate(),
+            [
+                crate::ConfigIssue::WatchTarget {
+                    target: "ftp://files.example.com".into()
+                },
+                crate::ConfigIssue::WatchTarget {
+                    target: "192.168.1.20".into()
+                }
+            ]
+        );
+        app.apply_config(&config);
+        assert_eq!(app.watch.tiles.len(), 2);
+        assert_eq!(app.watch.tiles[1].checks.len(), 1);
+    }
+
+    #[test]
+    fn watch_outages_are_problems_unless_snoozed_or_in_maintenance() {
+        let mut app = AppModel::default();
+        app.update(Clock {
+            local: "2026-10-16 03:10:00".into(),
+            utc_offset_minutes: 0,
+        });
+        app.watch.set_targets(
+            crate::parse_watch_targets("Router=192.168.1.1@03:00-03:30, NAS=192.168.1.10").unwrap(),
+        );
+        let round = |app: &mut AppModel, up: bool| {
+            let effects = app.update(Tick(crate::WATCH_INTERVAL_MS));
+            let [Effect::StartWatch { job, ref request }] = effects[..] else {
+                panic!("expected a watch round: {effects:?}");
+            };
+            for target in request.targets.clone() {
+                app.update(Runtime(RuntimeEvent::WatchChecked {
+                    job,
+                    check: crate::WatchCheck {
+                        target,
+                        latency_ms: up.then_some(2.0),
+                        error: (!up).then(|| "no echo reply".into()),
+                    },
+                }));
+            }
+            app.update(Runtime(RuntimeEvent::WatchFinished { job }));
+        };
+
+        // The router reboots inside its window; the NAS is listed once
+        // however long it stays down.
+        round(&mut app, false);
+        round(&mut app, false);
+        let messages = |app: &AppModel| {
+            app.problems
+                .items
+                .iter()
+                .map(|problem| problem.message.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(&app),
+            ["NAS (192.168.1.10) is down: no echo reply"]
+        );
+        assert_eq!(app.problems.items[0].tool, ToolKind::Watch);
+        assert!(app.popups.is_empty());
+
+        // Snoozed from the Dashboard, the next outage stays quiet too.
+        round(&mut app, true);
+        app.problems.clear();
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert_eq!(app.watch.snooze_minutes, 15);
+        round(&mut app, false);
+        assert!(app.problems.items.is_empty());
+    }
+
+    #[test]
+    fn hidden_tabs_are_skipped_and_the_list_sets_the_cycling_order() {
+        let mut config = crate::ConfigData {
+            tabs: vec!["events".into(), "wifi".into(), "dashboard".into()],
+            ..crate::ConfigData::default()
+        };
+        config.session.ui.last_tab = Page::Scanner as u8;
+        assert_eq!(
+            config.validate(),
+            [crate::ConfigIssue::Tab {
+                name: "wifi".into()
+            }]
+        );
+        assert_eq!(config.tabs, ["events", "dashboard", "settings"]);
+
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+        assert_eq!(app.tabs(), [Page::Events, Page::Dashboard, Page::Settings]);
+        assert_eq!(app.page, Page::Events, "a hidden last tab falls back");
+        app.update(Input(InputEvent::Action(Action::NextPage)));
+        assert_eq!(app.page, Page::Dashboard);
+        app.update(Input(InputEvent::Action(Action::PreviousPage)));
+        app.update(Input(InputEvent::Action(Action::PreviousPage)));
+        assert_eq!(app.page, Page::Settings);
+        assert!(
+            app.update(Input(InputEvent::Action(Action::SelectPage(
+                Page::Scanner as u8
+            ))))
+            .is_empty()
+        );
+        assert_eq!(app.page, Page::Settings);
+
+        select_setting(&mut app, crate::SettingId::Tabs);
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let text = "scanner, events".to_string();
+        app.settings.input = Some((text.clone(), text.len()));
+        assert_eq!(
+            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter)))),
+            [Effect::PersistTabs(vec![
+                Page::Scanner,
+                Page::Events,
+                Page::Settings
+            ])]
+        );
+        app.update(Input(InputEvent::Action(Action::NextPage)));
+        assert_eq!(app.page, Page::Scanner);
+    }
+
+    #[test]
+    fn number_keys_jump_to_visible_tabs_and_alt_works_while_typing() {
+        let digit = |value: char, alt: bool| {
+            Input(InputEvent::Key(KeyEvent {
+                code: KeyCode::Char(value),
+                modifiers: crate::Modifiers {
+                    alt,
+                    ..crate::Modifiers::NONE
+                },
+            }))
+        };
+        let mut app = AppModel::default();
+        app.apply_config(&crate::ConfigData {
+            tabs: vec!["scanner".into(), "events".into()],
+            ..crate::ConfigData::default()
+        });
+        app.update(digit('2', false));
+        assert_eq!(app.page, Page::Events);
+        assert!(app.update(digit('9', false)).is_empty());
+        assert_eq!(app.page, Page::Events);
+
+        app.update(digit('1', false));
+        assert_eq!(app.page, Page::Scanner);
+        app.scanner.editing = true;
+        app.scanner.cidr.clear();
+        app.scanner.cursor = 0;
+        app.update(digit('3', false));
+        assert_eq!(app.scanner.cidr, "3", "digits are typed into the field");
+        assert_eq!(app.page, Page::Scanner);
+        app.update(digit('3', true));
+        assert_eq!(app.page, Page::Settings);
+
+        assert_eq!(
+            KeyEvent::plain(KeyCode::F(5)).action(),
+            Some(Action::Refresh)
+        );
+        assert_eq!(KeyEvent::plain(KeyCode::F(10)).action(), Some(Action::Quit));
+    }
+
+    #[test]
+    fn targets_are_checked_and_normalized_before_a_tool_starts() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::PortScan;
+        app.diagnostics.port_scan.persist.target = "10.0.0.0/24".into();
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert_eq!(
+            app.diagnostics.port_scan.common.status,
+            TaskStatus::Failed("10.0.0.0/24 covers several addresses; enter a single host".into())
+        );
+        app.diagnostics.port_scan.persist.target = "https://Router.lan:8443/admin".into();
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::StartPortScan { request, .. }, ..] if request.target == "Router.lan"
+        ));
+        assert_eq!(app.diagnostics.target_history[0], "Router.lan");
+
+        app.page = Page::Scanner;
+        app.scanner.cidr = "printer.lan".into();
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert!(matches!(app.scanner.status, TaskStatus::Failed(_)));
+        app.scanner.cidr = "192.168.1.77/24".into();
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(effects.iter().any(|effect| matches!(
+            effect,
+            Effect::StartScan { request, .. } if request.cidr == "192.168.1.0/24"
+        )));
+        assert_eq!(app.scanner.history[0], "192.168.1.0/24");
+    }
+
+    #[test]
+    fn unavailable_tools_and_adapter_edits_are_inert_not_failed() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            capabilities: PlatformCapabilities {
+                icmp: false,
+                adapter_config: false,
+                bluetooth: false,
+            },
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Ping;
+        app.diagnostics.ping.request.target = "192.0.2.1".into();
+        assert!(!app.tool_available(DiagnosticTool::Ping));
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert_eq!(app.diagnostics.ping.common.status, TaskStatus::Idle);
+
+        app.diagnostics.tool = DiagnosticTool::PortScan;
+        assert!(app.tool_available(DiagnosticTool::PortScan));
+
+        app.page = Page::Adapters;
+        app.adapters.items = vec![AdapterInfo {
+            guid: "eth0".into(),
+            ..AdapterInfo::default()
+        }];
+        app.update(Input(InputEvent::Action(Action::Edit)));
+        assert!(app.adapters.edit.is_none());
+    }
+
+    #[test]
+    fn scans_beyond_the_lan_wait_for_the_target_to_be_typed_back() {
+        let type_text = |app: &mut AppModel, text: &str| {
+            for character in text.chars() {
+                app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(
+                    character,
+                )))));
+            }
+        };
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        app.scanner.cidr = "19.168.1.7/24".into();
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        let Some(crate::Popup::Confirm(question)) = app.popups.last() else {
+            panic!("expected a confirmation, got {:?}", app.popups);
+        };
+        assert_eq!(
+            question.confirm,
+            crate::Confirm::ScanTarget {
+                scan: crate::GuardedScan::LanScan,
+                target: "19.168.1.0/24".into(),
+                concern: crate::ScanConcern::Public,
+            }
+        );
+        assert!(app.scanner.job.is_none());
+
+        // Y is text here, and Yes cannot be clicked before the target matches.
+        type_text(&mut app, "y");
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Answer(true))))
+                .is_empty()
+        );
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
+        type_text(&mut app, "19.168.1.0/24");
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(app.popups.is_empty());
+        assert!(
+            effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::StartScan { .. }))
+        );
 
-        app.update(Input(InputEvent::Action(Action::SelectScannerInput(3))));
-        assert!(app.scanner.editing);
-        assert_eq!(app.scanner.cursor, 3);
-        app.update(Input(InputEvent::Action(Action::History)));
-        assert!(app.scanner.history_open);
-        let effects = app.update(Input(InputEvent::Action(Action::SelectScannerHistory(0))));
-        assert_eq!(app.scanner.cidr, "192.168.50.0/24");
+        // Confirmed once, the target is not asked about again.
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(
+            effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::StartScan { .. }))
+        );
+
+        // A large private range asks too, and Esc leaves it unscanned.
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        app.scanner.cidr = "10.0.0.0/8".into();
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(matches!(
+            app.popups.last(),
+            Some(crate::Popup::Confirm(crate::Confirmation {
+                confirm: crate::Confirm::ScanTarget {
+                    concern: crate::ScanConcern::Large { .. },
+                    ..
+                },
+                ..
+            }))
+        ));
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Back)))
+                .is_empty()
+        );
+        assert!(app.popups.is_empty() && app.scanner.job.is_none());
+
+        app.page = Page::Diagnostics;
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::PortScan;
+        app.diagnostics.port_scan.persist.target = "Example.COM".into();
+        app.sync_port_scan_request();
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        type_text(&mut app, "example.com");
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
         assert!(matches!(
             effects.as_slice(),
-            [Effect::PersistSession(crate::SessionUpdate::Scanner(_))]
+            [Effect::StartPortScan { request, .. }, ..] if request.target == "Example.COM"
+        ));
+    }
+
+    #[test]
+    fn policy_refuses_probes_but_keeps_the_saved_inventory() {
+        let mut config = crate::ConfigData {
+            policy: crate::PolicyConfig {
+                disabled: vec!["lan-scan".into(), "port-scan".into(), "capture".into()],
+            },
+            ..crate::ConfigData::default()
+        };
+        assert_eq!(
+            config.validate(),
+            [crate::ConfigIssue::PolicyFeature {
+                name: "capture".into()
+            }]
+        );
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+
+        app.page = Page::Scanner;
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert_eq!(
+            app.scanner.status,
+            TaskStatus::Failed(crate::POLICY_REFUSAL.into())
+        );
+        app.scanner.results = vec![ScanHost {
+            ip: "192.168.1.10".into(),
+            ..ScanHost::default()
+        }];
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(app.scanner.detail_open, "the inventory is still browsable");
+
+        // Tools ask the registry too, so a preset pipeline skips the stage.
+        app.page = Page::Diagnostics;
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::PortScan;
+        app.diagnostics.port_scan.persist.target = "192.168.1.10".into();
+        assert!(app.tool_supported(DiagnosticTool::PortScan));
+        assert!(!app.tool_available(DiagnosticTool::PortScan));
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert!(app.tool_available(DiagnosticTool::Ping));
+    }
+
+    #[test]
+    fn low_power_follows_battery_in_auto_and_gates_background_polling() {
+        let mut app = AppModel::default();
+        assert_eq!(app.tick_interval_ms(), TICK_MS);
+        assert!(app.wants_traffic_polling());
+
+        app.take_redraw();
+        app.update(Message::PowerSource { on_battery: true });
+        assert!(app.take_redraw());
+        assert!(app.low_power_active());
+        assert_eq!(app.tick_interval_ms(), LOW_POWER_TICK_MS);
+        assert!(app.wants_traffic_polling());
+        assert!(app.wants_adapter_polling());
+
+        app.page = Page::Scanner;
+        assert!(!app.wants_traffic_polling());
+        assert!(!app.wants_adapter_polling());
+
+        app.low_power = LowPowerMode::Off;
+        assert!(!app.low_power_active());
+        assert!(
+            !app.wants_traffic_polling(),
+            "hidden pages never poll traffic"
+        );
+        assert!(app.wants_adapter_polling());
+
+        app.low_power = LowPowerMode::On;
+        app.update(Message::PowerSource { on_battery: false });
+        assert!(app.low_power_active());
+    }
+
+    #[test]
+    fn interface_change_refreshes_adapters_and_dashboard() {
+        // Unlike the polled refresh this is not limited to the Adapter page.
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        app.take_redraw();
+
+        let effects = app.update(Message::InterfacesChanged);
+        assert!(app.take_redraw());
+        assert!(matches!(
+            effects.as_slice(),
+            [
+                Effect::RefreshAdapters { .. },
+                Effect::RefreshDashboard { .. }
+            ]
+        ));
+        assert_eq!(app.dashboard.status, TaskStatus::Running);
+    }
+
+    #[test]
+    fn config_problem_popup_holds_input_until_dismissed() {
+        let mut app = AppModel::default();
+        app.show_config_issues(vec![crate::ConfigIssue::ScanConcurrency { found: 4000 }]);
+        let effects = app.update(Input(InputEvent::Action(Action::NextPage)));
+        assert!(effects.is_empty());
+        assert_eq!(app.page, Page::Dashboard);
+
+        let language = app.language;
+        app.update(Input(InputEvent::Action(Action::ToggleLanguage)));
+        assert_ne!(app.language, language);
+        assert_eq!(app.config_issues.len(), 1);
+
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(app.config_issues.is_empty());
+        app.update(Input(InputEvent::Action(Action::NextPage)));
+        assert_eq!(app.page, Page::Adapters);
+    }
+
+    #[test]
+    fn ping_history_exports_as_a_chart_in_the_chosen_format() {
+        let mut app = AppModel::default();
+        app.update(Input(InputEvent::Action(Action::Export)));
+        assert!(app.popups.is_empty(), "nothing to chart on the dashboard");
+
+        app.page = Page::Diagnostics;
+        app.diagnostics.ping.request.target = "example.com".into();
+        app.update(Input(InputEvent::Action(Action::Export)));
+        assert!(app.popups.is_empty(), "no samples yet");
+        app.diagnostics.ping.samples = (1..=3)
+            .map(|sequence| crate::PingSample {
+                sequence,
+                latency_ms: (sequence != 2).then_some(10 + sequence),
+                ttl: Some(64),
+                size: 32,
+                sent: sequence,
+                received: sequence,
+                min_ms: None,
+                average_ms: None,
+                max_ms: None,
+                loss_percent: 0.0,
+                route: Vec::new(),
+                timestamps: None,
+            })
+            .collect();
+        app.update(Input(InputEvent::Key(KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: crate::Modifiers {
+                control: true,
+                ..crate::Modifiers::NONE
+            },
+        })));
+        assert_eq!(app.popups, [crate::Popup::Export]);
+
+        app.update(Input(InputEvent::Action(Action::Right)));
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        let [
+            Effect::ExportChart {
+                job,
+                chart,
+                format: crate::ChartFormat::Svg,
+            },
+        ] = effects.as_slice()
+        else {
+            panic!("expected an SVG export, got {effects:?}");
+        };
+        assert_eq!(chart.title, "Ping example.com");
+        assert_eq!(chart.series[0].points[1], (2, None));
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Confirm)))
+                .is_empty(),
+            "one write at a time"
+        );
+
+        app.update(Runtime(RuntimeEvent::ChartExported {
+            job: *job,
+            path: "/tmp/iptools-ping.svg".into(),
+        }));
+        assert_eq!(app.export.job, None);
+        assert_eq!(app.export.saved.as_deref(), Some("/tmp/iptools-ping.svg"));
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(app.popups.is_empty());
+    }
+
+    #[test]
+    fn popups_stack_and_only_the_top_one_takes_input() {
+        use crate::Popup;
+
+        let mut app = AppModel::default();
+        app.update(Input(InputEvent::Action(Action::Presets)));
+        // The picker has no use for F1, so it opens nothing on top.
+        app.update(Input(InputEvent::Action(Action::Help)));
+        assert_eq!(app.popups, [Popup::Pipeline]);
+        app.open_popup(Popup::Help);
+        app.open_popup(Popup::Problems);
+        assert_eq!(app.popups, [Popup::Pipeline, Popup::Help, Popup::Problems]);
+
+        // Presets is not the problem list's key, so nothing underneath moves.
+        app.update(Input(InputEvent::Action(Action::Presets)));
+        app.update(Input(InputEvent::Action(Action::NextPage)));
+        assert_eq!(app.popups.len(), 3);
+        assert_eq!(app.page, Page::Dashboard);
+
+        // Closing the top hands input back to the one below it.
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert_eq!(app.popups, [Popup::Pipeline, Popup::Help]);
+        app.update(Input(InputEvent::Action(Action::Help)));
+        assert_eq!(app.popups, [Popup::Pipeline]);
+        app.update(Input(InputEvent::Action(Action::Presets)));
+        assert!(app.popups.is_empty());
+
+        // Opening one that is already open brings it to the top.
+        app.open_popup(Popup::Help);
+        app.open_popup(Popup::Problems);
+        app.open_popup(Popup::Help);
+        assert_eq!(app.popups, [Popup::Problems, Popup::Help]);
+    }
+
+    #[test]
+    fn current_failures_are_listed_and_only_errors_open_the_popup() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::Ping;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job, .. } = effects[0].clone() else {
+            panic!("expected ping effect");
+        };
+        let stale = JobId {
+            generation: job.generation + 100,
+            ..job
+        };
+        let denied =
+            crate::RuntimeError::new(crate::RuntimeErrorCode::PermissionDenied, "raw socket");
+        app.update(Runtime(RuntimeEvent::PingFailed {
+            job: stale,
+            error: denied.clone(),
+        }));
+        assert!(app.problems.items.is_empty());
+
+        app.update(Runtime(RuntimeEvent::PingFailed {
+            job,
+            error: denied.clone(),
+        }));
+        assert!(
+            !app.popup_open(crate::Popup::Problems),
+            "the ping view shows its own failure"
+        );
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job, .. } = effects[0].clone() else {
+            panic!("expected ping effect");
+        };
+        app.page = Page::Dashboard;
+        app.update(Runtime(RuntimeEvent::PingFailed { job, error: denied }));
+        assert!(app.popup_open(crate::Popup::Problems));
+        assert_eq!(app.problems.items[0].count, 2);
+        assert_eq!(app.problems.items[0].tool, ToolKind::Ping);
+        assert_eq!(app.problems.items[0].severity, crate::Severity::Error);
+
+        app.update(Input(InputEvent::Action(Action::NextPage)));
+        assert_eq!(app.page, Page::Dashboard, "the popup holds input");
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert!(!app.popup_open(crate::Popup::Problems));
+
+        app.page = Page::Diagnostics;
+        app.diagnostics.tool = DiagnosticTool::Trace;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartTrace { job, .. } = effects[0].clone() else {
+            panic!("expected trace effect");
+        };
+        app.update(Runtime(RuntimeEvent::TraceFailed {
+            job,
+            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Timeout, "trace timeout"),
+        }));
+        assert!(
+            !app.popup_open(crate::Popup::Problems),
+            "warnings only join the list"
+        );
+        assert_eq!(app.problems.items.len(), 2);
+        assert_eq!(app.problems.unseen, 1);
+
+        app.update(Input(InputEvent::Action(Action::Problems)));
+        assert!(app.popup_open(crate::Popup::Problems));
+        assert_eq!(app.problems.unseen, 0);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Delete))));
+        assert!(app.problems.items.is_empty());
+    }
+
+    #[test]
+    fn only_a_wired_link_below_its_hardware_speed_is_flagged() {
+        let mut adapter = AdapterInfo {
+            ipv4: "192.168.1.20".into(),
+            status: "up".into(),
+            link_speed_bps: Some(100_000_000),
+            media: LinkMedia {
+                duplex: Some(Duplex::Full),
+                medium: Some(LinkMedium::TwistedPair),
+                max_speed_bps: Some(1_000_000_000),
+                partner_max_speed_bps: Some(1_000_000_000),
+            },
+            ..AdapterInfo::default()
+        };
+        assert_eq!(
+            adapter.link_warning(),
+            Some(LinkWarning::Downshifted {
+                speed_bps: 100_000_000,
+                max_bps: 1_000_000_000,
+                partner_max_bps: Some(1_000_000_000),
+            })
+        );
+        // A Fast Ethernet card at its own top speed is fine.
+        adapter.media.max_speed_bps = Some(100_000_000);
+        assert_eq!(adapter.link_warning(), None);
+        adapter.media.duplex = Some(Duplex::Half);
+        assert_eq!(adapter.link_warning(), Some(LinkWarning::HalfDuplex));
+        adapter.status = "down".into();
+        assert_eq!(adapter.link_warning(), None);
+    }
+
+    #[test]
+    fn adapter_changes_are_appended_to_the_persisted_event_log() {
+        let mut config = crate::ConfigData::default();
+        config.session.events.entries = vec![crate::NetworkEvent {
+            at: "2026-01-14 09:00:00".into(),
+            kind: crate::NetworkEventKind::PublicIpChanged,
+            subject: String::new(),
+            from: "203.0.113.7".into(),
+            to: "198.51.100.9".into(),
+        }];
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+        let wifi = |status: &str| AdapterInfo {
+            name: "Wi-Fi".into(),
+            guid: "{wifi}".into(),
+            ipv4: "192.168.1.21".into(),
+            status: status.into(),
+            ..AdapterInfo::default()
+        };
+        assert!(
+            app.update(Runtime(RuntimeEvent::AdaptersUpdated(vec![wifi("up")])))
+                .is_empty()
+        );
+
+        let [Effect::RefreshAdapters { job }] = app.refresh_adapters()[..] else {
+            panic!("expected an adapter refresh");
+        };
+        let effects = app.update(Runtime(RuntimeEvent::AdaptersRefreshFinished {
+            job,
+            adapters: vec![wifi("disconnected")],
+        }));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Events(events))]
+                if events.entries.len() == 2
+                    && events.entries[1].kind == crate::NetworkEventKind::InterfaceDown
         ));
 
-        app.scanner.cidr = "10.".into();
-        app.scanner.cursor = app.scanner.cidr.len();
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Right))));
-        assert_eq!(app.scanner.cidr, "10.0.0.0/24");
+        // A stale generation changes nothing and logs nothing.
+        let effects = app.update(Runtime(RuntimeEvent::AdaptersRefreshFinished {
+            job,
+            adapters: vec![wifi("up")],
+        }));
+        assert!(effects.is_empty());
+        assert_eq!(app.events.entries.len(), 2);
+    }
+
+    #[test]
+    fn dashboard_refresh_uses_config_and_ignores_stale_generations() {
+        let mut app = AppModel::default();
+        app.apply_config(&crate::ConfigData {
+            public_ip: crate::PublicIpConfig {
+                endpoints: vec![crate::Endpoint {
+                    url: "http://127.0.0.1:9876/ip".into(),
+                    kind: "plaintext".into(),
+                }],
+                use_system_proxy: false,
+            },
+            ..crate::ConfigData::default()
+        });
+
+        let [first_effect] = app
+            .update(Input(InputEvent::Action(Action::Refresh)))
+            .try_into()
+            .unwrap();
+        let first = match first_effect {
+            Effect::RefreshDashboard { job, request } => {
+                assert_eq!(request.public_ip.endpoints[0].kind, "plaintext");
+                assert!(!request.public_ip.use_system_proxy);
+                job
+            }
+            other => panic!("unexpected effect: {other:?}"),
+        };
+        let [second_effect] = app
+            .update(Input(InputEvent::Action(Action::Refresh)))
+            .try_into()
+            .unwrap();
+        let second = match second_effect {
+            Effect::RefreshDashboard { job, .. } => job,
+            other => panic!("unexpected effect: {other:?}"),
+        };
+        assert!(second.generation > first.generation);
+
+        let stale = DashboardSnapshot {
+            hostname: "stale-host".into(),
+            ..DashboardSnapshot::default()
+        };
+        app.update(Runtime(RuntimeEvent::DashboardRefreshFinished {
+            job: first,
+            snapshot: Box::new(stale),
+        }));
+        assert_eq!(app.dashboard.job, Some(second));
+        assert_ne!(app.dashboard.snapshot.hostname, "stale-host");
+
+        let current = DashboardSnapshot {
+            hostname: "current-host".into(),
+            ..DashboardSnapshot::default()
+        };
+        app.update(Runtime(RuntimeEvent::DashboardRefreshFailed {
+            job: second,
+            snapshot: Box::new(current),
+            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Network, "offline"),
+        }));
+        assert_eq!(app.dashboard.job, None);
+        assert_eq!(app.dashboard.snapshot.hostname, "current-host");
+        assert!(matches!(app.dashboard.status, TaskStatus::Failed(_)));
+        assert_eq!(
+            app.dashboard.error.as_ref().unwrap().code,
+            crate::RuntimeErrorCode::Network
+        );
+    }
+
+    #[test]
+    fn only_visible_changes_request_a_redraw() {
+        let mut app = AppModel::default();
+        assert!(app.take_redraw());
+        assert!(!app.take_redraw());
+
+        app.update(Message::Tick(250));
+        assert!(!app.take_redraw());
 
-        let effects = app.update(Input(InputEvent::Action(Action::ActivateScannerPanel)));
-        assert!(!app.scanner.editing);
-        assert!(
-            !effects
-                .iter()
-                .any(|effect| matches!(effect, Effect::StartScan { .. }))
-        );
-        let effects = app.update(Input(InputEvent::Action(Action::ActivateScannerPanel)));
-        assert!(
-            effects
-                .iter()
-                .any(|effect| matches!(effect, Effect::StartScan { .. }))
-        );
-        assert!(effects.iter().any(|effect| matches!(
-            effect,
-            Effect::PersistSession(crate::SessionUpdate::CidrHistory(_))
-        )));
+        app.update(Message::Clock {
+            local: "2026-01-01 00:00:00".into(),
+            utc_offset_minutes: 0,
+        });
+        assert!(app.take_redraw());
+        app.update(Message::Clock {
+            local: "2026-01-01 00:00:00".into(),
+            utc_offset_minutes: 0,
+        });
+        assert!(!app.take_redraw());
+
+        app.update(Message::Input(InputEvent::Action(Action::NextPage)));
+        assert!(app.take_redraw());
+        app.update(Message::Runtime(RuntimeEvent::TrafficUpdated(Vec::new())));
+        assert!(app.take_redraw());
     }
 
     #[test]
-    fn scanner_defaults_to_the_active_adapter_network_until_user_edits_it() {
-        let mut config = crate::ConfigData::default();
-        config.session.scanner.cidr = "10.20.30.0/24".into();
+    fn clock_messages_refresh_the_dashboard_without_network_io() {
         let mut app = AppModel::default();
-        app.apply_config(&config);
-        app.update(Runtime(RuntimeEvent::AdaptersUpdated(vec![AdapterInfo {
-            name: "WLAN".into(),
-            ipv4: "192.168.50.35".into(),
-            cidr: Some("192.168.50.35/24".into()),
-            status: "up".into(),
-            is_physical: true,
-            ..AdapterInfo::default()
-        }])));
-        assert_eq!(app.scanner.cidr, "192.168.50.0/24");
+        let effects = app.update(Clock {
+            local: "2026-07-12 20:30:45".into(),
+            utc_offset_minutes: 0,
+        });
+        assert!(effects.is_empty());
+        assert_eq!(app.dashboard.snapshot.observed_at, "2026-07-12 20:30:45");
+    }
 
-        app.page = Page::Scanner;
-        app.scanner.editing = true;
-        app.scanner.cursor = app.scanner.cidr.len();
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
-        assert!(!app.scanner.auto_cidr);
-        let edited = app.scanner.cidr.clone();
-        app.update(Runtime(RuntimeEvent::AdaptersUpdated(vec![AdapterInfo {
-            ipv4: "172.16.8.9".into(),
-            cidr: Some("172.16.8.9/16".into()),
-            status: "up".into(),
-            is_physical: true,
-            ..AdapterInfo::default()
-        }])));
-        assert_eq!(app.scanner.cidr, edited);
+    #[test]
+    fn timestamps_follow_the_clock_format_and_can_add_utc() {
+        let mut app = AppModel::default();
+        let stamp = "2026-07-12 20:30:45";
+        app.show_utc = true;
+        assert_eq!(app.display_time(stamp), stamp, "no offset known yet");
+
+        app.update(Clock {
+            local: stamp.into(),
+            utc_offset_minutes: 120,
+        });
+        assert_eq!(
+            app.display_time(stamp),
+            "2026-07-12 20:30:45 (18:30:45 UTC)"
+        );
+        app.clock_format = crate::ClockFormat::TwelveHour;
+        app.region_format = crate::RegionFormat::Us;
+        assert_eq!(
+            app.display_time(stamp),
+            "07/12/2026 8:30:45 PM (6:30:45 PM UTC)"
+        );
+        app.update(Clock {
+            local: stamp.into(),
+            utc_offset_minutes: -300,
+        });
+        assert_eq!(
+            app.display_time(stamp),
+            "07/12/2026 8:30:45 PM (07/13/2026 1:30:45 AM UTC)"
+        );
+        app.show_utc = false;
+        assert_eq!(app.display_time("—"), "—");
+        assert_eq!(app.display_time(stamp), "07/12/2026 8:30:45 PM");
+        assert_eq!(app.display_time_of_day(stamp), "8:30:45 PM");
+        assert_eq!(app.display_time_of_day("09:05:00"), "9:05:00 AM");
     }
 
     #[test]
-    fn typed_ping_events_ignore_stale_and_post_cancel_samples() {
+    fn adapter_and_traffic_refreshes_are_job_scoped_and_preserve_selection() {
         let mut app = AppModel {
-            page: Page::Diagnostics,
+            page: Page::Adapters,
             ..AppModel::default()
         };
-        app.diagnostics.focused = true;
-        app.diagnostics.focus = DiagnosticFocus::Main;
-        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Effect::StartPing { job, request } = effects[0].clone() else {
-            panic!("expected typed ping effect");
+        app.adapters.items = vec![
+            AdapterInfo {
+                name: "Ethernet".into(),
+                ..AdapterInfo::default()
+            },
+            AdapterInfo {
+                name: "Wi-Fi".into(),
+                ..AdapterInfo::default()
+            },
+        ];
+        app.adapters.selected = 1;
+        let [first_effect] = app
+            .update(Input(InputEvent::Action(Action::Refresh)))
+            .try_into()
+            .unwrap();
+        let first = match first_effect {
+            Effect::RefreshAdapters { job } => job,
+            other => panic!("unexpected effect: {other:?}"),
         };
-        assert_eq!(request, crate::PingRequest::default());
-
-        let sample = crate::PingSample {
-            sequence: 1,
-            latency_ms: Some(12),
-            ttl: Some(64),
-            size: 32,
-            sent: 2,
-            received: 2,
-            min_ms: Some(12),
-            average_ms: Some(12.0),
-            max_ms: Some(12),
-            loss_percent: 0.0,
+        let [second_effect] = app
+            .update(Input(InputEvent::Action(Action::Refresh)))
+            .try_into()
+            .unwrap();
+        let second = match second_effect {
+            Effect::RefreshAdapters { job } => job,
+            other => panic!("unexpected effect: {other:?}"),
         };
-        app.update(Runtime(RuntimeEvent::PingSample {
-            job: JobId {
-                generation: job.generation + 1,
-                ..job
-            },
-            sample: sample.clone(),
+        app.update(Runtime(RuntimeEvent::AdaptersRefreshFinished {
+            job: first,
+            adapters: vec![AdapterInfo {
+                name: "stale".into(),
+                ..AdapterInfo::default()
+            }],
         }));
-        assert!(app.diagnostics.ping.samples.is_empty());
+        assert_eq!(app.adapters.job, Some(second));
+        assert_eq!(app.adapters.items[1].name, "Wi-Fi");
 
-        app.update(Runtime(RuntimeEvent::PingSample {
-            job,
-            sample: sample.clone(),
+        app.update(Runtime(RuntimeEvent::AdaptersRefreshFinished {
+            job: second,
+            adapters: vec![
+                AdapterInfo {
+                    name: "Wi-Fi".into(),
+                    ..AdapterInfo::default()
+                },
+                AdapterInfo {
+                    name: "Ethernet".into(),
+                    ..AdapterInfo::default()
+                },
+            ],
         }));
-        assert_eq!(app.diagnostics.ping.samples.first(), Some(&sample));
-        assert_eq!(
-            app.diagnostics.ping.common.log.last().map(String::as_str),
-            Some("Reply seq=1 bytes=32 ttl=64 time=12ms")
-        );
-
-        assert_eq!(
-            app.update(Input(InputEvent::Action(Action::Toggle))),
-            [Effect::StopPing(job)]
-        );
-        app.update(Runtime(RuntimeEvent::PingSample { job, sample }));
-        assert_eq!(app.diagnostics.ping.samples.len(), 1);
+        assert_eq!(app.adapters.selected, 0);
+        assert_eq!(app.adapters.status, TaskStatus::Done);
 
-        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Effect::StartPing { job: restarted, .. } = effects[0] else {
-            panic!("expected restarted ping effect");
-        };
-        assert!(restarted.generation > job.generation);
-        let summary = crate::PingSummary {
-            sent: 4,
-            received: 4,
-            min_ms: Some(10),
-            average_ms: Some(12.5),
-            max_ms: Some(15),
-            loss_percent: 0.0,
+        app.page = Page::Traffic;
+        let [effect] = app
+            .update(Input(InputEvent::Action(Action::Refresh)))
+            .try_into()
+            .unwrap();
+        let job = match effect {
+            Effect::RefreshTraffic { job } => job,
+            other => panic!("unexpected effect: {other:?}"),
         };
-        app.update(Runtime(RuntimeEvent::PingFinished {
+        app.update(Runtime(RuntimeEvent::TrafficRefreshFinished {
             job,
-            summary: summary.clone(),
-        }));
-        assert_eq!(app.diagnostics.ping.common.job, Some(restarted));
-        app.update(Runtime(RuntimeEvent::PingFinished {
-            job: restarted,
-            summary: summary.clone(),
+            rows: vec![TrafficRow {
+                name: "Wi-Fi".into(),
+                download_bps: 1_024,
+                ..TrafficRow::default()
+            }],
         }));
-        assert_eq!(app.diagnostics.ping.summary, Some(summary));
-        assert_eq!(app.diagnostics.ping.common.status, TaskStatus::Done);
+        assert_eq!(app.traffic.rows[0].download_bps, 1_024);
+        assert_eq!(app.traffic.status, TaskStatus::Done);
     }
 
     #[test]
-    fn trace_start_cancel_restart_ignores_stale_and_finishes_current_job() {
+    fn shared_model_loads_preferences_from_legacy_config_data() {
+        let config = crate::ConfigData {
+            language: Language::Zh,
+            scan_concurrency: 120,
+            ..crate::ConfigData::default()
+        };
+        let mut app = AppModel::default();
+        app.apply_config(&config);
+        assert_eq!(app.language, Language::Zh);
+        assert_eq!(app.scan_concurrency, 120);
+    }
+
+    fn adapter_app() -> AppModel {
         let mut app = AppModel {
-            page: Page::Diagnostics,
+            page: Page::Adapters,
             ..AppModel::default()
         };
-        app.diagnostics.focused = true;
-        app.diagnostics.focus = DiagnosticFocus::Main;
-        app.diagnostics.tool = DiagnosticTool::Trace;
+        app.adapters.items.push(AdapterInfo {
+            name: "Ethernet".into(),
+            guid: "adapter-guid".into(),
+            ipv4: "192.168.50.20".into(),
+            cidr: Some("192.168.50.20/24".into()),
+            dhcp_enabled: false,
+            status: "up".into(),
+            ..AdapterInfo::default()
+        });
+        app
+    }
 
-        let first = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Effect::StartTrace { job: first, .. } = first[0] else {
-            panic!("expected trace effect");
-        };
-        let hop = crate::TraceHop {
-            ttl: 1,
-            address: Some("192.0.2.1".into()),
-            hostname: None,
-            latency_ms: Some(12),
-        };
-        app.update(Runtime(RuntimeEvent::TraceHop {
-            job: first,
-            hop: hop.clone(),
-        }));
-        assert_eq!(app.diagnostics.trace.hops, [hop]);
+    #[test]
+    fn adapter_edit_preserves_defaults_and_validates_static_fields() {
+        let mut app = adapter_app();
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Edit)))
+                .is_empty()
+        );
+        let edit = app.adapters.edit.as_ref().unwrap();
+        assert_eq!(edit.params.ip, "192.168.50.20");
+        assert_eq!(edit.params.mask, "255.255.255.0");
+        assert_eq!(edit.params.gateway, "192.168.50.1");
+        assert_eq!(edit.params.dns1, "8.8.8.8");
+        assert_eq!(edit.selected, AdapterField::Mode);
+
+        app.adapters.edit.as_mut().unwrap().params.mask = "255.0.255.0".into();
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let edit = app.adapters.edit.as_ref().unwrap();
+        assert_eq!(edit.phase, AdapterEditPhase::Editing);
+        assert_eq!(edit.validation_error, Some(AdapterValidationError::Mask));
+
+        app.adapters.edit.as_mut().unwrap().params.mask = "255.255.255.0".into();
+        app.update(Input(InputEvent::Action(Action::Confirm)));
         assert_eq!(
-            app.update(Input(InputEvent::Action(Action::Toggle))),
-            [Effect::StopTrace(first)]
+            app.adapters.edit.as_ref().unwrap().phase,
+            AdapterEditPhase::Confirming
         );
+    }
 
-        let restarted = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Effect::StartTrace { job: restarted, .. } = restarted[0] else {
-            panic!("expected restarted trace effect");
+    #[test]
+    fn adapter_enter_and_space_keep_unambiguous_edit_entry() {
+        for action in [Action::Confirm, Action::Toggle] {
+            let mut app = adapter_app();
+            app.update(Input(InputEvent::Action(action)));
+            assert!(app.adapters.edit.is_some(), "{action:?}");
+        }
+    }
+
+    #[test]
+    fn adapter_apply_is_job_scoped_and_runtime_only_is_not_reported_as_failure() {
+        let mut app = adapter_app();
+        app.update(Input(InputEvent::Action(Action::Edit)));
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        assert!(matches!(effects[0], Effect::PersistAdapterEdit { .. }));
+        let Effect::ApplyAdapterConfig { job, ref request } = effects[1] else {
+            panic!("expected typed adapter apply effect");
         };
-        app.update(Runtime(RuntimeEvent::TraceFinished {
-            job: first,
-            hops: 1,
+        assert_eq!(request.guid, "adapter-guid");
+        assert_eq!(request.gateway.as_deref(), Some("192.168.50.1"));
+
+        app.update(Input(InputEvent::Action(Action::Back)));
+        assert_eq!(
+            app.adapters.edit.as_ref().unwrap().phase,
+            AdapterEditPhase::Applying
+        );
+        app.update(Runtime(RuntimeEvent::AdapterConfigFinished {
+            job: JobId {
+                generation: job.generation + 1,
+                ..job
+            },
+            outcome: crate::AdapterApplyOutcome::Persistent,
         }));
-        assert_eq!(app.diagnostics.trace.common.job, Some(restarted));
-        app.update(Runtime(RuntimeEvent::TraceFinished {
-            job: restarted,
-            hops: 8,
+        assert_eq!(
+            app.adapters.edit.as_ref().unwrap().phase,
+            AdapterEditPhase::Applying
+        );
+        app.update(Runtime(RuntimeEvent::AdapterConfigFinished {
+            job,
+            outcome: crate::AdapterApplyOutcome::RuntimeOnly,
         }));
-        assert_eq!(app.diagnostics.trace.common.status, TaskStatus::Done);
-        assert_eq!(app.diagnostics.trace.common.job, None);
+        assert_eq!(
+            app.adapters.edit.as_ref().unwrap().phase,
+            AdapterEditPhase::Succeeded(crate::AdapterApplyOutcome::RuntimeOnly)
+        );
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
+        assert!(app.adapters.edit.is_none());
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::RefreshAdapters { .. }]
+        ));
     }
 
     #[test]
-    fn typed_failure_only_mutates_its_current_tool_generation() {
-        let mut app = AppModel {
-            page: Page::Diagnostics,
-            ..AppModel::default()
-        };
-        app.diagnostics.focused = true;
-        app.diagnostics.focus = DiagnosticFocus::Main;
-        app.diagnostics.tool = DiagnosticTool::Trace;
-        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Effect::StartTrace { job, request } = effects[0].clone() else {
-            panic!("expected typed trace effect");
+    fn adapter_failure_returns_to_form_and_cursor_edits_emit_persistence() {
+        let mut app = adapter_app();
+        app.update(Input(InputEvent::Action(Action::Edit)));
+        app.update(Input(InputEvent::Action(Action::Confirm)));
+        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
+        let Effect::ApplyAdapterConfig { job, .. } = effects[1] else {
+            panic!()
         };
-        assert_eq!(request, crate::TraceRequest::default());
-
-        app.update(Runtime(RuntimeEvent::TraceFailed {
+        app.update(Runtime(RuntimeEvent::AdapterConfigFailed {
             job,
-            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Timeout, "trace timeout"),
+            error: crate::RuntimeError::new(
+                crate::RuntimeErrorCode::PermissionDenied,
+                "administrator required",
+            ),
         }));
+        assert!(matches!(
+            app.adapters.edit.as_ref().unwrap().phase,
+            AdapterEditPhase::Failed(_)
+        ));
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
         assert_eq!(
-            app.diagnostics.trace.common.status,
-            TaskStatus::Failed("trace timeout".into())
+            app.adapters.edit.as_ref().unwrap().phase,
+            AdapterEditPhase::Editing
         );
-        assert_eq!(app.diagnostics.trace.common.job, None);
+
+        app.update(Input(InputEvent::Action(Action::SelectAdapterField(
+            AdapterField::Ipv4,
+            0,
+        ))));
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('1')))));
         assert_eq!(
-            app.diagnostics
-                .trace
-                .common
-                .error
-                .as_ref()
-                .map(|error| error.code),
-            Some(crate::RuntimeErrorCode::Timeout)
+            app.adapters.edit.as_ref().unwrap().params.ip,
+            "1192.168.50.20"
         );
-        assert_eq!(app.diagnostics.ping.common.status, TaskStatus::Idle);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistAdapterEdit { .. }]
+        ));
     }
 
     #[test]
-    fn port_scan_effect_carries_validated_shape_not_generic_text() {
+    fn adapter_edit_loads_guid_scoped_persistence_and_history_escape_is_local() {
+        let mut app = adapter_app();
+        let params = AdapterEditParams {
+            use_dhcp: false,
+            ip: "10.0.0.8".into(),
+            mask: "255.255.255.0".into(),
+            gateway: "10.0.0.1".into(),
+            dns1: "1.1.1.1".into(),
+            dns2: String::new(),
+        };
+        let mut config = crate::ConfigData::default();
+        config
+            .session
+            .adapter_edit
+            .adapters
+            .insert("adapter-guid".into(), params.clone());
+        config.session.history.adapter = vec!["9.9.9.9".into()];
+        app.apply_config(&config);
+        app.page = Page::Adapters;
+        app.update(Input(InputEvent::Action(Action::Edit)));
+        assert_eq!(app.adapters.edit.as_ref().unwrap().params, params);
+        app.update(Input(InputEvent::Action(Action::SelectAdapterField(
+            AdapterField::Ipv4,
+            0,
+        ))));
+        app.update(Input(InputEvent::Action(Action::History)));
+        assert!(app.adapters.edit.as_ref().unwrap().history_open);
+        let effects = app.update(Input(InputEvent::Action(Action::SelectAdapterHistory(0))));
+        assert_eq!(app.adapters.edit.as_ref().unwrap().params.ip, "9.9.9.9");
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistAdapterEdit { .. }]
+        ));
+        app.update(Input(InputEvent::Action(Action::History)));
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Esc))));
+        assert!(!app.adapters.edit.as_ref().unwrap().history_open);
+        assert!(app.adapters.edit.is_some());
+
+        let edit = app.adapters.edit.as_mut().unwrap();
+        edit.params.ip = "9.".into();
+        edit.selected = AdapterField::Ipv4;
+        edit.cursor = 2;
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Right))));
+        assert_eq!(app.adapters.edit.as_ref().unwrap().params.ip, "9.9.9.9");
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistAdapterEdit { .. }]
+        ));
+    }
+
+    #[test]
+    fn adapter_edit_keeps_global_shortcuts_available() {
+        let mut app = adapter_app();
+        app.update(Input(InputEvent::Action(Action::Edit)));
+        assert!(app.adapters.edit.is_some());
+
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
+        assert_eq!(app.page, Page::Scanner);
+        app.page = Page::Adapters;
+        let effects = app.update(Input(InputEvent::Action(Action::ToggleLanguage)));
+        assert_eq!(app.language, Language::Zh);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistPreferences(_)]
+        ));
+        app.update(Input(InputEvent::Action(Action::Quit)));
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn diagnostics_focus_and_ping_config_keep_established_interaction() {
         let mut app = AppModel {
             page: Page::Diagnostics,
             ..AppModel::default()
         };
-        app.diagnostics.focused = true;
-        app.diagnostics.focus = DiagnosticFocus::Main;
-        app.diagnostics.tool = DiagnosticTool::PortScan;
-        app.scan_concurrency = 64;
-        app.diagnostics.port_scan.persist = crate::PortScanPersist {
-            target: "192.0.2.10".into(),
-            start_port: "20".into(),
-            end_port: "443".into(),
-            timeout_ms: "250".into(),
-        };
-        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
-        let Effect::StartPortScan { request, .. } = &effects[0] else {
-            panic!("expected typed port scan effect");
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
+        assert!(app.diagnostics.focused);
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Main);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::BackTab))));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
+
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(' ')))));
+        assert!(matches!(effects.first(), Some(Effect::StartPing { .. })));
+        assert!(matches!(
+            effects.get(1),
+            Some(Effect::PersistSession(crate::SessionUpdate::TargetHistory(
+                _
+            )))
+        ));
+        let Effect::StartPing { job, .. } = effects[0] else {
+            panic!()
         };
-        assert_eq!(request.start_port, 20);
-        assert_eq!(request.end_port, 443);
-        assert_eq!(request.concurrency, 64);
+        assert_eq!(
+            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(' '))))),
+            [Effect::StopPing(job)]
+        );
+
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Config);
+        app.diagnostics.cursor = 0;
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('x')))));
+        assert_eq!(app.diagnostics.ping.request.target, "x8.8.8.8");
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Ping(_))]
+        ));
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            1, 0,
+        ))));
+        let effects = app.update(Input(InputEvent::Action(Action::Right)));
+        assert_eq!(app.diagnostics.ping.request.interval_ms, 1_100);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Ping(_))]
+        ));
+
+        for (field, action) in [(4, Action::Left), (5, Action::Right), (6, Action::Left)] {
+            app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+                field, 0,
+            ))));
+            app.update(Input(InputEvent::Action(action)));
+        }
+        let request = &app.diagnostics.ping.request;
+        assert_eq!(request.mode, crate::PingMode::Timestamp);
+        assert!(request.dont_fragment);
+        assert_eq!(request.dscp, 48);
+        assert!(request.has_ip_options());
+
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Esc))));
+        assert!(!app.diagnostics.focused);
     }
 
     #[test]
-    fn settings_emit_explicit_persistence_effects() {
+    fn claimed_ping_location_is_typed_with_spaces_and_checked_against_the_rtt() {
         let mut app = AppModel {
-            page: Page::Settings,
+            page: Page::Diagnostics,
             ..AppModel::default()
         };
-        assert!(
-            app.update(Input(InputEvent::Action(Action::Down)))
-                .is_empty()
-        );
-        assert_eq!(app.settings_selected, 1);
-        assert_eq!(
-            app.update(Input(InputEvent::Action(Action::Right))),
-            [Effect::PersistPreferences(crate::Preferences {
-                language: Language::En,
-                theme: ThemeId::Classic,
-                scan_concurrency: 60,
-            })]
-        );
-        assert_eq!(app.scan_concurrency, 60);
-
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            7, 0,
+        ))));
+        let mut effects = Vec::new();
+        for character in "new york".chars() {
+            effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(
+                character,
+            )))));
+        }
+        assert_eq!(app.diagnostics.ping.claimed_location, "new york");
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Ping(ping))]
+                if ping.claimed_location == "new york"
+        ));
+        app.update(Input(InputEvent::Action(Action::Left)));
         assert_eq!(
-            app.update(Input(InputEvent::Action(Action::ToggleLanguage))),
-            [Effect::PersistPreferences(crate::Preferences {
-                language: Language::Zh,
-                theme: ThemeId::Classic,
-                scan_concurrency: 60,
-            })]
+            app.diagnostics.ping.request,
+            crate::PingRequest::default(),
+            "arrows move the cursor instead of adjusting"
         );
 
-        app.diagnostics.ping.request.target = "remembered.example".into();
-        app.diagnostics.target_history = vec!["remembered.example".into()];
-        app.update(Input(InputEvent::Action(Action::Down)));
-        assert_eq!(app.settings_selected, 2);
-        assert_eq!(
-            app.update(Input(InputEvent::Action(Action::Confirm))),
-            [Effect::PersistPreferences(crate::Preferences {
-                language: Language::Zh,
-                theme: ThemeId::Nord,
-                scan_concurrency: 60,
-            })]
-        );
-        app.update(Input(InputEvent::Action(Action::Down)));
-        assert_eq!(app.settings_selected, 3);
+        assert_eq!(app.diagnostics.ping.geo_check(None), None, "no reply yet");
+        app.diagnostics.ping.summary = Some(crate::PingSummary {
+            sent: 4,
+            received: 4,
+            loss_percent: 0.0,
+            min_ms: Some(2),
+            average_ms: Some(3.0),
+            max_ms: Some(5),
+        });
         assert_eq!(
-            app.update(Input(InputEvent::Action(Action::Confirm))),
-            [Effect::PersistSession(crate::SessionUpdate::Reset(
-                crate::UiPersist {
-                    last_tab: Page::Settings as u8,
-                    last_diag_tool: 0,
-                }
-            ))]
+            app.diagnostics.ping.geo_check(None),
+            Some(Err(crate::GeoProblem::NoOrigin))
         );
-        assert_eq!(app.diagnostics.ping.request, crate::PingRequest::default());
-        assert!(app.diagnostics.target_history.is_empty());
-        assert!(app.settings_just_reset);
-        assert_eq!(app.page, Page::Settings);
+        app.diagnostics.ping.own_location = "London".into();
+        let check = app.diagnostics.ping.geo_check(None).unwrap().unwrap();
+        assert_eq!(check.verdict(), crate::GeoVerdict::Impossible);
     }
 
     #[test]
-    fn dashboard_refresh_uses_config_and_ignores_stale_generations() {
-        let mut app = AppModel::default();
-        app.apply_config(&crate::ConfigData {
-            public_ip: crate::PublicIpConfig {
-                endpoints: vec![crate::Endpoint {
-                    url: "http://127.0.0.1:9876/ip".into(),
-                    kind: "plaintext".into(),
-                }],
-                use_system_proxy: false,
-            },
-            ..crate::ConfigData::default()
-        });
-
-        let [first_effect] = app
-            .update(Input(InputEvent::Action(Action::Refresh)))
-            .try_into()
-            .unwrap();
-        let first = match first_effect {
-            Effect::RefreshDashboard { job, request } => {
-                assert_eq!(request.public_ip.endpoints[0].kind, "plaintext");
-                assert!(!request.public_ip.use_system_proxy);
-                job
-            }
-            other => panic!("unexpected effect: {other:?}"),
-        };
-        let [second_effect] = app
-            .update(Input(InputEvent::Action(Action::Refresh)))
-            .try_into()
-            .unwrap();
-        let second = match second_effect {
-            Effect::RefreshDashboard { job, .. } => job,
-            other => panic!("unexpected effect: {other:?}"),
+    fn pinned_ping_baseline_survives_a_restart_until_unpinned() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
         };
-        assert!(second.generation > first.generation);
+        let key = |code| Input(InputEvent::Key(KeyEvent::plain(code)));
+        app.update(key(KeyCode::Char('v')));
+        app.update(key(KeyCode::Char('P')));
+        assert_eq!(app.diagnostics.ping.baseline, None, "nothing measured yet");
 
-        let stale = DashboardSnapshot {
-            hostname: "stale-host".into(),
-            ..DashboardSnapshot::default()
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job, .. } = effects[0] else {
+            panic!("expected ping start");
         };
-        app.update(Runtime(RuntimeEvent::DashboardRefreshFinished {
-            job: first,
-            snapshot: Box::new(stale),
+        app.update(Runtime(RuntimeEvent::PingSample {
+            job,
+            sample: crate::PingSample {
+                sequence: 0,
+                latency_ms: Some(48),
+                ttl: Some(64),
+                size: 32,
+                sent: 1,
+                received: 1,
+                min_ms: Some(48),
+                average_ms: Some(48.0),
+                max_ms: Some(48),
+                loss_percent: 0.0,
+                route: Vec::new(),
+                timestamps: None,
+            },
         }));
-        assert_eq!(app.dashboard.job, Some(second));
-        assert_ne!(app.dashboard.snapshot.hostname, "stale-host");
+        app.update(key(KeyCode::Char('p')));
+        let baseline = app.diagnostics.ping.baseline.clone().unwrap();
+        assert_eq!(baseline.target, "8.8.8.8");
+        assert_eq!(baseline.stats.average_ms, Some(48.0));
 
-        let current = DashboardSnapshot {
-            hostname: "current-host".into(),
-            ..DashboardSnapshot::default()
-        };
-        app.update(Runtime(RuntimeEvent::DashboardRefreshFailed {
-            job: second,
-            snapshot: Box::new(current),
-            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Network, "offline"),
-        }));
-        assert_eq!(app.dashboard.job, None);
-        assert_eq!(app.dashboard.snapshot.hostname, "current-host");
-        assert!(matches!(app.dashboard.status, TaskStatus::Failed(_)));
-        assert_eq!(
-            app.dashboard.error.as_ref().unwrap().code,
-            crate::RuntimeErrorCode::Network
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(app.diagnostics.ping.samples.is_empty());
+        assert_eq!(app.diagnostics.ping.baseline, Some(baseline));
+
+        app.update(Input(InputEvent::Action(Action::Toggle)));
+        app.update(key(KeyCode::Char('c')));
+        app.diagnostics.cursor = 0;
+        app.update(key(KeyCode::Char('p')));
+        assert_eq!(app.diagnostics.ping.request.target, "p8.8.8.8");
+        assert!(
+            app.diagnostics.ping.baseline.is_some(),
+            "typed, not unpinned"
         );
+        let mut alt = KeyEvent::plain(KeyCode::Char('v'));
+        alt.modifiers.alt = true;
+        app.update(Input(InputEvent::Key(alt)));
+        app.update(key(KeyCode::Char('p')));
+        assert_eq!(app.diagnostics.ping.baseline, None);
     }
 
     #[test]
-    fn clock_messages_refresh_the_dashboard_without_network_io() {
-        let mut app = AppModel::default();
-        let effects = app.update(Clock("2026-07-12 20:30:45".into()));
-        assert!(effects.is_empty());
-        assert_eq!(app.dashboard.snapshot.observed_at, "2026-07-12 20:30:45");
+    fn letter_shortcuts_jump_between_diagnostic_panes_but_type_in_parameters() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        let key = |code| Input(InputEvent::Key(KeyEvent::plain(code)));
+        app.update(key(KeyCode::Char('v')));
+        assert!(app.diagnostics.focused);
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Main);
+        app.update(key(KeyCode::Char('m')));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
+        app.update(key(KeyCode::Char('c')));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Config);
+
+        app.diagnostics.cursor = 0;
+        app.update(key(KeyCode::Char('m')));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Config);
+        assert_eq!(app.diagnostics.ping.request.target, "m8.8.8.8");
+        let mut alt = KeyEvent::plain(KeyCode::Char('m'));
+        alt.modifiers.alt = true;
+        app.update(Input(InputEvent::Key(alt)));
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
+        assert_eq!(app.diagnostics.ping.request.target, "m8.8.8.8");
     }
 
     #[test]
-    fn adapter_and_traffic_refreshes_are_job_scoped_and_preserve_selection() {
+    fn clicking_a_diagnostic_row_switches_tool_even_when_already_focused() {
         let mut app = AppModel {
-            page: Page::Adapters,
+            page: Page::Diagnostics,
             ..AppModel::default()
         };
-        app.adapters.items = vec![
-            AdapterInfo {
-                name: "Ethernet".into(),
-                ..AdapterInfo::default()
-            },
-            AdapterInfo {
-                name: "Wi-Fi".into(),
-                ..AdapterInfo::default()
-            },
-        ];
-        app.adapters.selected = 1;
-        let [first_effect] = app
-            .update(Input(InputEvent::Action(Action::Refresh)))
-            .try_into()
-            .unwrap();
-        let first = match first_effect {
-            Effect::RefreshAdapters { job } => job,
-            other => panic!("unexpected effect: {other:?}"),
-        };
-        let [second_effect] = app
-            .update(Input(InputEvent::Action(Action::Refresh)))
-            .try_into()
-            .unwrap();
-        let second = match second_effect {
-            Effect::RefreshAdapters { job } => job,
-            other => panic!("unexpected effect: {other:?}"),
-        };
-        app.update(Runtime(RuntimeEvent::AdaptersRefreshFinished {
-            job: first,
-            adapters: vec![AdapterInfo {
-                name: "stale".into(),
-                ..AdapterInfo::default()
-            }],
-        }));
-        assert_eq!(app.adapters.job, Some(second));
-        assert_eq!(app.adapters.items[1].name, "Wi-Fi");
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
 
-        app.update(Runtime(RuntimeEvent::AdaptersRefreshFinished {
-            job: second,
-            adapters: vec![
-                AdapterInfo {
-                    name: "Wi-Fi".into(),
-                    ..AdapterInfo::default()
-                },
-                AdapterInfo {
-                    name: "Ethernet".into(),
-                    ..AdapterInfo::default()
-                },
-            ],
-        }));
-        assert_eq!(app.adapters.selected, 0);
-        assert_eq!(app.adapters.status, TaskStatus::Done);
+        let effects = app.update(Input(InputEvent::Action(Action::SelectDiagnostic(
+            DiagnosticTool::Trace as u8,
+        ))));
 
-        app.page = Page::Traffic;
-        let [effect] = app
-            .update(Input(InputEvent::Action(Action::Refresh)))
-            .try_into()
-            .unwrap();
-        let job = match effect {
-            Effect::RefreshTraffic { job } => job,
-            other => panic!("unexpected effect: {other:?}"),
-        };
-        app.update(Runtime(RuntimeEvent::TrafficRefreshFinished {
-            job,
-            rows: vec![TrafficRow {
-                name: "Wi-Fi".into(),
-                download_bps: 1_024,
-                ..TrafficRow::default()
-            }],
-        }));
-        assert_eq!(app.traffic.rows[0].download_bps, 1_024);
-        assert_eq!(app.traffic.status, TaskStatus::Done);
+        assert_eq!(app.diagnostics.tool, DiagnosticTool::Trace);
+        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
+        assert!(matches!(effects.as_slice(), [Effect::PersistSession(_)]));
     }
 
     #[test]
-    fn shared_model_loads_preferences_from_legacy_config_data() {
-        let config = crate::ConfigData {
-            language: Language::Zh,
-            scan_concurrency: 120,
-            ..crate::ConfigData::default()
+    fn diagnostic_history_mouse_selection_fills_the_active_target() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
         };
-        let mut app = AppModel::default();
-        app.apply_config(&config);
-        assert_eq!(app.language, Language::Zh);
-        assert_eq!(app.scan_concurrency, 120);
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.target_history = vec!["1.1.1.1".into(), "8.8.8.8".into()];
+        app.diagnostics.history_open = true;
+        let effects = app.update(Input(InputEvent::Action(Action::SelectDiagnosticHistory(
+            1,
+        ))));
+        assert_eq!(app.diagnostics.ping.request.target, "8.8.8.8");
+        assert!(!app.diagnostics.history_open);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Ping(_))]
+        ));
     }
 
-    fn adapter_app() -> AppModel {
+    #[test]
+    fn dscp_check_cycles_marking_persists_and_reports_the_rewriting_hop() {
         let mut app = AppModel {
-            page: Page::Adapters,
+            page: Page::Diagnostics,
             ..AppModel::default()
         };
-        app.adapters.items.push(AdapterInfo {
-            name: "Ethernet".into(),
-            guid: "adapter-guid".into(),
-            ipv4: "192.168.50.20".into(),
-            cidr: Some("192.168.50.20/24".into()),
-            dhcp_enabled: false,
-            status: "up".into(),
-            ..AdapterInfo::default()
-        });
-        app
-    }
-
-    #[test]
-    fn adapter_edit_preserves_defaults_and_validates_static_fields() {
-        let mut app = adapter_app();
-        assert!(
-            app.update(Input(InputEvent::Action(Action::Edit)))
-                .is_empty()
-        );
-        let edit = app.adapters.edit.as_ref().unwrap();
-        assert_eq!(edit.params.ip, "192.168.50.20");
-        assert_eq!(edit.params.mask, "255.255.255.0");
-        assert_eq!(edit.params.gateway, "192.168.50.1");
-        assert_eq!(edit.params.dns1, "8.8.8.8");
-        assert_eq!(edit.selected, AdapterField::Mode);
-
-        app.adapters.edit.as_mut().unwrap().params.mask = "255.0.255.0".into();
-        app.update(Input(InputEvent::Action(Action::Confirm)));
-        let edit = app.adapters.edit.as_ref().unwrap();
-        assert_eq!(edit.phase, AdapterEditPhase::Editing);
-        assert_eq!(edit.validation_error, Some(AdapterValidationError::Mask));
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Dscp;
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            1, 0,
+        ))));
+        let effects = app.update(Input(InputEvent::Action(Action::Right)));
+        assert_eq!(app.diagnostics.dscp.request.dscp, 48);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Dscp(persist))] if persist.dscp == 48
+        ));
 
-        app.adapters.edit.as_mut().unwrap().params.mask = "255.255.255.0".into();
-        app.update(Input(InputEvent::Action(Action::Confirm)));
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartDscp { job, ref request } = effects[0] else {
+            panic!("expected dscp start");
+        };
+        assert_eq!(request.dscp, 48);
+        app.update(Runtime(RuntimeEvent::DscpHop {
+            job,
+            hop: crate::DscpHop {
+                ttl: 1,
+                address: Some("192.168.1.1".into()),
+                rtt_ms: Some(1),
+                observed: Some(0),
+                reached: false,
+            },
+        }));
+        assert_eq!(app.diagnostics.dscp.hops.len(), 1);
+        app.update(Runtime(RuntimeEvent::DscpFinished {
+            job,
+            summary: crate::DscpSummary::from_hops(48, &app.diagnostics.dscp.hops),
+        }));
+        assert_eq!(app.diagnostics.dscp.common.status, TaskStatus::Done);
         assert_eq!(
-            app.adapters.edit.as_ref().unwrap().phase,
-            AdapterEditPhase::Confirming
+            app.diagnostics.dscp.common.detail,
+            "marking rewritten to 0 from hop 1"
         );
     }
 
     #[test]
-    fn adapter_enter_and_space_keep_unambiguous_edit_entry() {
-        for action in [Action::Confirm, Action::Toggle] {
-            let mut app = adapter_app();
-            app.update(Input(InputEvent::Action(action)));
-            assert!(app.adapters.edit.is_some(), "{action:?}");
+    fn nat_check_edits_both_servers_and_reports_the_classification() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Nat;
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            1, 0,
+        ))));
+        app.diagnostics.cursor = app.diagnostics.nat.request.secondary.len();
+        for _ in 0..4 {
+            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
         }
-    }
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('9')))));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Nat(persist))]
+                if persist.secondary == "stun.cloudflare.com:9"
+        ));
 
-    #[test]
-    fn adapter_apply_is_job_scoped_and_runtime_only_is_not_reported_as_failure() {
-        let mut app = adapter_app();
-        app.update(Input(InputEvent::Action(Action::Edit)));
-        app.update(Input(InputEvent::Action(Action::Confirm)));
-        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
-        assert!(matches!(effects[0], Effect::PersistAdapterEdit { .. }));
-        let Effect::ApplyAdapterConfig { job, ref request } = effects[1] else {
-            panic!("expected typed adapter apply effect");
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartNat { job, ref request } = effects[0] else {
+            panic!("expected nat start");
         };
-        assert_eq!(request.guid, "adapter-guid");
-        assert_eq!(request.gateway.as_deref(), Some("192.168.50.1"));
-
-        app.update(Input(InputEvent::Action(Action::Back)));
-        assert_eq!(
-            app.adapters.edit.as_ref().unwrap().phase,
-            AdapterEditPhase::Applying
-        );
-        app.update(Runtime(RuntimeEvent::AdapterConfigFinished {
-            job: JobId {
-                generation: job.generation + 1,
-                ..job
+        assert_eq!(request.secondary, "stun.cloudflare.com:9");
+        app.update(Runtime(RuntimeEvent::NatProbe {
+            job,
+            probe: crate::StunProbe {
+                server: request.server.clone(),
+                mapped: Some("203.0.113.7:40001".into()),
+                rtt_ms: Some(12),
             },
-            outcome: crate::AdapterApplyOutcome::Persistent,
         }));
-        assert_eq!(
-            app.adapters.edit.as_ref().unwrap().phase,
-            AdapterEditPhase::Applying
-        );
-        app.update(Runtime(RuntimeEvent::AdapterConfigFinished {
+        app.update(Runtime(RuntimeEvent::NatFinished {
             job,
-            outcome: crate::AdapterApplyOutcome::RuntimeOnly,
+            summary: crate::NatSummary {
+                nat_type: crate::NatType::PortRestrictedCone,
+                local: "192.168.1.20:50000".into(),
+                mapped: Some("203.0.113.7:40001".into()),
+                hairpin: Some(true),
+            },
         }));
+        assert_eq!(app.diagnostics.nat.probes.len(), 1);
         assert_eq!(
-            app.adapters.edit.as_ref().unwrap().phase,
-            AdapterEditPhase::Succeeded(crate::AdapterApplyOutcome::RuntimeOnly)
+            app.diagnostics.nat.common.detail,
+            "port-restricted cone · mapped 203.0.113.7:40001 · hairpin ok"
         );
-        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
-        assert!(app.adapters.edit.is_none());
+    }
+
+    #[test]
+    fn ipv6_readiness_runs_the_checklist_and_grades_it() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Ipv6;
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            1, 0,
+        ))));
+        app.diagnostics.cursor = 4;
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
+        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('5')))));
         assert!(matches!(
             effects.as_slice(),
-            [Effect::RefreshAdapters { .. }]
+            [Effect::PersistSession(crate::SessionUpdate::Ipv6(persist))]
+                if persist.timeout_ms == "3005"
         ));
+
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartIpv6 { job, ref request } = effects[0] else {
+            panic!("expected ipv6 start");
+        };
+        assert_eq!(request.timeout_ms, 3_005);
+        for (check, verdict) in [
+            (crate::Ipv6Check::GlobalAddress, crate::Ipv6Verdict::Pass),
+            (crate::Ipv6Check::DefaultRoute, crate::Ipv6Verdict::Pass),
+            (crate::Ipv6Check::DnsAaaa, crate::Ipv6Verdict::Pass),
+            (crate::Ipv6Check::Reachability, crate::Ipv6Verdict::Fail),
+            (crate::Ipv6Check::PathMtu, crate::Ipv6Verdict::Skipped),
+        ] {
+            app.update(Runtime(RuntimeEvent::Ipv6Result {
+                job,
+                result: crate::Ipv6CheckResult {
+                    check,
+                    verdict,
+                    detail: "-".into(),
+                },
+            }));
+        }
+        app.update(Runtime(RuntimeEvent::Ipv6Finished { job }));
+        assert_eq!(app.diagnostics.ipv6.results.len(), 5);
+        assert_eq!(
+            app.diagnostics.ipv6.common.detail,
+            "IPv6 not ready · 3/5 checks passed"
+        );
     }
 
     #[test]
-    fn adapter_failure_returns_to_form_and_cursor_edits_emit_persistence() {
-        let mut app = adapter_app();
-        app.update(Input(InputEvent::Action(Action::Edit)));
-        app.update(Input(InputEvent::Action(Action::Confirm)));
-        let effects = app.update(Input(InputEvent::Action(Action::Confirm)));
-        let Effect::ApplyAdapterConfig { job, .. } = effects[1] else {
-            panic!()
+    fn dns_benchmark_ranks_scores_and_keeps_an_empty_custom_out_of_history() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
         };
-        app.update(Runtime(RuntimeEvent::AdapterConfigFailed {
-            job,
-            error: crate::RuntimeError::new(
-                crate::RuntimeErrorCode::PermissionDenied,
-                "administrator required",
-            ),
-        }));
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::DnsBench;
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            1, 0,
+        ))));
+        let effects = app.update(Input(InputEvent::Action(Action::Right)));
         assert!(matches!(
-            app.adapters.edit.as_ref().unwrap().phase,
-            AdapterEditPhase::Failed(_)
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::DnsBench(persist))]
+                if !persist.encrypted
         ));
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
+
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert_eq!(effects.len(), 1);
+        let Effect::StartDnsBench { job, ref request } = effects[0] else {
+            panic!("expected dns benchmark start");
+        };
+        let resolvers = request.resolvers();
+        app.update(Runtime(RuntimeEvent::DnsBenchStarted {
+            job,
+            resolvers: resolvers.len() as u32,
+        }));
+        for (resolver, latency) in resolvers.into_iter().zip([Some(20.0), Some(8.0), None]) {
+            app.update(Runtime(RuntimeEvent::DnsBenchScore {
+                job,
+                score: crate::DnsResolverScore::from_samples(
+                    resolver,
+                    &[(crate::DnsQueryKind::Dotcom, latency)],
+                ),
+            }));
+        }
+        assert_eq!(app.diagnostics.dns_bench.common.progress, 75);
+        app.update(Runtime(RuntimeEvent::DnsBenchFinished { job }));
         assert_eq!(
-            app.adapters.edit.as_ref().unwrap().phase,
-            AdapterEditPhase::Editing
+            app.diagnostics.dns_bench.common.detail,
+            "recommend Cloudflare 1.1.1.1 · 8.0 ms median · 0% failed"
         );
+        assert_eq!(app.diagnostics.dns_bench.scores[1].resolver.name, "System");
+        assert!(app.diagnostics.target_history.is_empty());
+    }
 
-        app.update(Input(InputEvent::Action(Action::SelectAdapterField(
-            AdapterField::Ipv4,
-            0,
-        ))));
-        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('1')))));
+    #[test]
+    fn dns_filter_attributes_sinkholes_and_remembers_the_suspect_domain() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        app.diagnostics.tool = DiagnosticTool::DnsFilter;
+        app.diagnostics.dns_filter.request.domain = "tracker.example".into();
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartDnsFilter { job, ref request } = effects[0] else {
+            panic!("expected dns filter start");
+        };
+        let domains = request.domains();
+        let upstreams = vec![
+            crate::DnsFilterUpstream::System,
+            crate::DnsFilterUpstream::Gateway,
+        ];
+        app.update(Runtime(RuntimeEvent::DnsFilterStarted {
+            job,
+            upstreams: upstreams.clone(),
+            domains: domains.len() as u32,
+        }));
+        for upstream in upstreams {
+            let answers = domains
+                .iter()
+                .enumerate()
+                .map(|(index, domain)| {
+                    let sinkholed = upstream == crate::DnsFilterUpstream::System && index > 0;
+                    let address = if sinkholed {
+                        "0.0.0.0".to_string()
+                    } else {
+                        format!("93.184.215.{index}")
+                    };
+                    (
+                        domain.clone(),
+                        crate::DnsAnswer::Addresses(vec![address.parse().unwrap()]),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for probe in crate::classify_dns_filter(upstream, &answers, &[]) {
+                app.update(Runtime(RuntimeEvent::DnsFilterResult { job, probe }));
+            }
+        }
+        assert_eq!(app.diagnostics.dns_filter.common.progress, 99);
+        app.update(Runtime(RuntimeEvent::DnsFilterFinished { job }));
         assert_eq!(
-            app.adapters.edit.as_ref().unwrap().params.ip,
-            "1192.168.50.20"
+            app.diagnostics.dns_filter.common.detail,
+            "filtered by the system resolver (Pi-hole / local blocklist) · 5/5 names blocked"
         );
-        assert!(matches!(
-            effects.as_slice(),
-            [Effect::PersistAdapterEdit { .. }]
-        ));
+        assert_eq!(app.diagnostics.target_history, ["tracker.example"]);
     }
 
     #[test]
-    fn adapter_edit_loads_guid_scoped_persistence_and_history_escape_is_local() {
-        let mut app = adapter_app();
-        let params = AdapterEditParams {
-            use_dhcp: false,
-            ip: "10.0.0.8".into(),
-            mask: "255.255.255.0".into(),
-            gateway: "10.0.0.1".into(),
-            dns1: "1.1.1.1".into(),
-            dns2: String::new(),
+    fn mail_checks_keep_port_order_and_count_what_needs_attention() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
         };
-        let mut config = crate::ConfigData::default();
-        config
-            .session
-            .adapter_edit
-            .adapters
-            .insert("adapter-guid".into(), params.clone());
-        config.session.history.adapter = vec!["9.9.9.9".into()];
-        app.apply_config(&config);
-        app.page = Page::Adapters;
-        app.update(Input(InputEvent::Action(Action::Edit)));
-        assert_eq!(app.adapters.edit.as_ref().unwrap().params, params);
-        app.update(Input(InputEvent::Action(Action::SelectAdapterField(
-            AdapterField::Ipv4,
-            0,
-        ))));
-        app.update(Input(InputEvent::Action(Action::History)));
-        assert!(app.adapters.edit.as_ref().unwrap().history_open);
-        let effects = app.update(Input(InputEvent::Action(Action::SelectAdapterHistory(0))));
-        assert_eq!(app.adapters.edit.as_ref().unwrap().params.ip, "9.9.9.9");
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Mail;
+        assert_eq!(app.diagnostic_config_count(), 2);
+        app.diagnostics.mail.request.host = "mail.example".into();
+        app.diagnostics.mail.config_selected = 1;
+        app.diagnostics.mail.timeout_input = "99".into();
+        let effects = app.persist_active_diagnostic();
         assert!(matches!(
             effects.as_slice(),
-            [Effect::PersistAdapterEdit { .. }]
+            [Effect::PersistSession(crate::SessionUpdate::Mail(persist))]
+                if persist.host == "mail.example" && persist.timeout_ms == "99"
         ));
-        app.update(Input(InputEvent::Action(Action::History)));
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Esc))));
-        assert!(!app.adapters.edit.as_ref().unwrap().history_open);
-        assert!(app.adapters.edit.is_some());
+        app.sync_active_diagnostic_request();
+        assert_eq!(app.diagnostics.mail.request.timeout_ms, 500);
 
-        let edit = app.adapters.edit.as_mut().unwrap();
-        edit.params.ip = "9.".into();
-        edit.selected = AdapterField::Ipv4;
-        edit.cursor = 2;
-        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Right))));
-        assert_eq!(app.adapters.edit.as_ref().unwrap().params.ip, "9.9.9.9");
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartMail { job, ref request } = effects[0] else {
+            panic!("expected mail start");
+        };
+        assert_eq!(request.host, "mail.example");
+        let secure = crate::MailCheck {
+            banner: Some("* OK IMAP4rev1 ready".into()),
+            starttls: Some(true),
+            tls: Some(crate::TlsSummary {
+                version: "TLSv1_3".into(),
+                cipher: "TLS13_AES_128_GCM_SHA256".into(),
+            }),
+            ..crate::MailCheck::new(143, crate::MailProtocol::Imap, false)
+        };
+        let leaky = crate::MailCheck {
+            banner: Some("+OK POP3 ready".into()),
+            starttls: Some(false),
+            auth: vec!["USER".into()],
+            cleartext_auth: true,
+            ..crate::MailCheck::new(110, crate::MailProtocol::Pop3, false)
+        };
+        let closed = crate::MailCheck {
+            error: Some("connection refused".into()),
+            ..crate::MailCheck::new(25, crate::MailProtocol::Smtp, false)
+        };
+        for check in [leaky, secure, closed] {
+            app.update(Runtime(RuntimeEvent::MailResult { job, check }));
+        }
+        let ports: Vec<u16> = app
+            .diagnostics
+            .mail
+            .checks
+            .iter()
+            .map(|check| check.port)
+            .collect();
+        assert_eq!(ports, [25, 143, 110]);
+        assert_eq!(app.diagnostics.mail.common.progress, 42);
+        app.update(Runtime(RuntimeEvent::MailFinished { job }));
+        assert_eq!(
+            app.diagnostics.mail.common.detail,
+            "2/7 ports open · 1 need attention"
+        );
+        assert_eq!(app.diagnostics.target_history, ["mail.example"]);
+    }
+
+    #[test]
+    fn voip_probe_scores_the_stream_and_reflect_mode_hides_the_peer() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Voip;
+        assert_eq!(app.diagnostic_config_count(), 5);
+        app.update(Input(InputEvent::Action(Action::Right)));
+        assert_eq!(app.diagnostics.voip.request.mode, crate::VoipMode::Reflect);
+        assert_eq!(app.diagnostic_config_count(), 2);
+        app.update(Input(InputEvent::Action(Action::Left)));
+
+        app.diagnostics.voip.config_selected = 3;
+        let effects = app.update(Input(InputEvent::Action(Action::Right)));
         assert!(matches!(
             effects.as_slice(),
-            [Effect::PersistAdapterEdit { .. }]
+            [Effect::PersistSession(crate::SessionUpdate::Voip(persist))]
+                if persist.codec == crate::VoipCodec::G729 && persist.mode == crate::VoipMode::Probe
         ));
+        app.diagnostics.voip.request.peer = "voip.example".into();
+        app.diagnostics.voip.config_selected = 4;
+        app.diagnostics.voip.duration_input = "9000".into();
+        app.sync_active_diagnostic_request();
+        assert_eq!(app.diagnostics.voip.request.duration_secs, 600);
+
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartVoip { job, ref request } = effects[0] else {
+            panic!("expected voip start");
+        };
+        assert_eq!(request.codec, crate::VoipCodec::G729);
+        app.update(Runtime(RuntimeEvent::VoipStarted {
+            job,
+            endpoint: "192.0.2.8:50506".into(),
+        }));
+        let summary = crate::VoipSample {
+            elapsed_ms: 16_000,
+            sent: 750,
+            received: 750,
+            rtt_ms: Some(40.0),
+            jitter_ms: Some(2.0),
+            ..crate::VoipSample::default()
+        };
+        app.update(Runtime(RuntimeEvent::VoipSample {
+            job,
+            sample: summary.clone(),
+        }));
+        assert_eq!(app.diagnostics.voip.common.progress, 2);
+        app.update(Runtime(RuntimeEvent::VoipFinished { job, summary }));
+        assert_eq!(
+            app.diagnostics.voip.common.detail,
+            "MOS 4.06 (R 81) · RTT 40.0 ms · jitter 2.0 ms · loss 0.0%"
+        );
+        assert_eq!(app.diagnostics.target_history, ["voip.example"]);
     }
 
     #[test]
-    fn adapter_edit_keeps_global_shortcuts_available() {
-        let mut app = adapter_app();
-        app.update(Input(InputEvent::Action(Action::Edit)));
-        assert!(app.adapters.edit.is_some());
-
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
-        assert_eq!(app.page, Page::Scanner);
-        app.page = Page::Adapters;
-        let effects = app.update(Input(InputEvent::Action(Action::ToggleLanguage)));
-        assert_eq!(app.language, Language::Zh);
+    fn gaming_preset_cycles_and_the_fastest_steady_region_wins() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Gaming;
+        app.update(Input(InputEvent::Action(Action::Left)));
+        let effects = app.update(Input(InputEvent::Action(Action::Left)));
         assert!(matches!(
             effects.as_slice(),
-            [Effect::PersistPreferences(_)]
+            [Effect::PersistSession(crate::SessionUpdate::Gaming(persist))]
+                if persist.preset == crate::GamingPreset::AsiaPacific
         ));
-        app.update(Input(InputEvent::Action(Action::Quit)));
-        assert!(!app.running);
+        app.diagnostics.gaming.request.custom = "Home server=udp://192.0.2.20:7777".into();
+        app.diagnostics.gaming.config_selected = 2;
+        app.diagnostics.gaming.rounds_input = "1".into();
+        app.sync_active_diagnostic_request();
+        assert_eq!(app.diagnostics.gaming.request.rounds, 2);
+
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartGaming { job, ref request } = effects[0] else {
+            panic!("expected gaming start");
+        };
+        let endpoints = request.endpoints().unwrap();
+        assert_eq!(endpoints.len(), 7);
+        app.update(Runtime(RuntimeEvent::GamingStarted {
+            job,
+            endpoints: endpoints.len() as u32,
+        }));
+        for (endpoint, samples) in endpoints.into_iter().rev().zip([
+            [Some(3.0), Some(5.0)],
+            [Some(60.0), Some(64.0)],
+            [None, Some(1.0)],
+        ]) {
+            app.update(Runtime(RuntimeEvent::GamingScore {
+                job,
+                score: crate::GamingScore::from_samples(endpoint, &samples),
+            }));
+        }
+        assert_eq!(app.diagnostics.gaming.common.progress, 42);
+        app.update(Runtime(RuntimeEvent::GamingFinished { job }));
+        assert_eq!(
+            app.diagnostics.gaming.common.detail,
+            "best Home server · 4.0 ms median · 2.0 ms jitter · 0% lost"
+        );
+        assert_eq!(app.diagnostics.gaming.scores[2].lost, 1);
+        assert_eq!(
+            app.diagnostics.target_history,
+            ["Home server=udp://192.0.2.20:7777"]
+        );
     }
 
     #[test]
-    fn diagnostics_focus_and_ping_config_keep_established_interaction() {
+    fn cloud_matrix_blames_an_isolated_failure_on_the_provider() {
         let mut app = AppModel {
             page: Page::Diagnostics,
             ..AppModel::default()
         };
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Enter))));
-        assert!(app.diagnostics.focused);
-        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
-        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Main);
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::BackTab))));
-        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
+        app.diagnostics.focused = true;
+        app.diagnostics.tool = DiagnosticTool::Cloud;
+        app.diagnostics.cloud.attempts_input = "50".into();
+        app.diagnostics.cloud.timeout_input = "100".into();
+        app.sync_active_diagnostic_request();
+        assert_eq!(app.diagnostics.cloud.request.attempts, 10);
+        assert_eq!(app.diagnostics.cloud.request.timeout_ms, 500);
 
-        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(' ')))));
-        assert!(matches!(effects.first(), Some(Effect::StartPing { .. })));
-        assert!(matches!(
-            effects.get(1),
-            Some(Effect::PersistSession(crate::SessionUpdate::TargetHistory(
-                _
-            )))
-        ));
-        let Effect::StartPing { job, .. } = effects[0] else {
-            panic!()
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartCloud { job, .. } = effects[0] else {
+            panic!("expected cloud start");
         };
+        let endpoints = crate::cloud_endpoints();
+        app.update(Runtime(RuntimeEvent::CloudStarted {
+            job,
+            endpoints: endpoints.len() as u32,
+        }));
+        for (index, endpoint) in endpoints.into_iter().enumerate() {
+            let attempts = if index == 11 {
+                vec![Err("timed out".to_string())]
+            } else {
+                vec![Ok(20.0 + index as f64)]
+            };
+            app.update(Runtime(RuntimeEvent::CloudResult {
+                job,
+                probe: crate::CloudProbe::from_attempts(endpoint, &attempts),
+            }));
+        }
+        assert_eq!(app.diagnostics.cloud.common.progress, 99);
+        assert_eq!(app.diagnostics.cloud.common.primary, "DNS OpenDNS: 39 ms");
+        app.update(Runtime(RuntimeEvent::CloudFinished { job }));
         assert_eq!(
-            app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char(' '))))),
-            [Effect::StopPing(job)]
+            app.diagnostics.cloud.common.detail,
+            "unreachable: Azure japaneast · likely on their side"
         );
+        assert!(app.diagnostics.target_history.is_empty());
+    }
 
-        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Tab))));
-        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Config);
-        app.diagnostics.cursor = 0;
-        let effects = app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('x')))));
-        assert_eq!(app.diagnostics.ping.request.target, "x8.8.8.8");
-        assert!(matches!(
-            effects.as_slice(),
-            [Effect::PersistSession(crate::SessionUpdate::Ping(_))]
-        ));
-        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
-            1, 0,
-        ))));
-        let effects = app.update(Input(InputEvent::Action(Action::Right)));
-        assert_eq!(app.diagnostics.ping.request.interval_ms, 1_100);
-        assert!(matches!(
-            effects.as_slice(),
-            [Effect::PersistSession(crate::SessionUpdate::Ping(_))]
-        ));
+    #[test]
+    fn ab_test_needs_two_interfaces_and_names_the_winner() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.diagnostics.focused = true;
+        app.diagnostics.tool = DiagnosticTool::AbTest;
+        app.diagnostics.focus = DiagnosticFocus::Config;
+        let key =
+            |app: &mut AppModel, code| app.update(Input(InputEvent::Key(KeyEvent::plain(code))));
+        for character in "Wi-Fi 2".chars() {
+            key(&mut app, KeyCode::Char(character));
+        }
+        assert_eq!(app.diagnostics.ab_test.request.first, "Wi-Fi 2");
+
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        assert!(
+            app.update(Input(InputEvent::Action(Action::Toggle)))
+                .is_empty()
+        );
+        assert_eq!(
+            app.diagnostics.ab_test.common.detail,
+            "both interfaces must be set"
+        );
+
+        app.diagnostics.ab_test.request.second = "Ethernet".into();
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartAbTest { job, ref request } = effects[0] else {
+            panic!("expected A/B start");
+        };
+        assert_eq!((request.target.as_str(), request.pings), ("1.1.1.1", 10));
+        assert_eq!(app.diagnostics.target_history, ["1.1.1.1"]);
+
+        app.update(Runtime(RuntimeEvent::AbTestStarted { job }));
+        let replies = |latency| [Some(latency), Some(latency + 2.0)];
+        let mut wireless =
+            crate::AbLeg::from_replies(crate::AbSide::A, "Wi-Fi 2", "192.168.1.21", &replies(30.0));
+        wireless.record_dns(vec![40.0]);
+        let mut wired =
+            crate::AbLeg::from_replies(crate::AbSide::B, "Ethernet", "192.168.1.20", &replies(3.0));
+        wired.record_dns(vec![12.0]);
+        app.update(Runtime(RuntimeEvent::AbTestLeg { job, leg: wireless }));
+        assert_eq!(app.diagnostics.ab_test.common.progress, 50);
+        app.update(Runtime(RuntimeEvent::AbTestLeg { job, leg: wired }));
+        assert_eq!(app.diagnostics.ab_test.common.primary, "B Ethernet: 4.0 ms");
+        app.update(Runtime(RuntimeEvent::AbTestFinished { job }));
+        assert_eq!(
+            app.diagnostics.ab_test.common.detail,
+            "B (Ethernet) better on 2 of 5 metrics"
+        );
+    }
+
+    #[test]
+    fn wifi_roams_reach_the_event_log_and_the_running_ping() {
+        let mut app = AppModel {
+            page: Page::Diagnostics,
+            ..AppModel::default()
+        };
+        app.update(Runtime(RuntimeEvent::AdaptersUpdated(vec![
+            AdapterInfo {
+                name: "Ethernet".into(),
+                guid: "eth0".into(),
+                kind: "wired".into(),
+                ..AdapterInfo::default()
+            },
+            AdapterInfo {
+                name: "Wi-Fi".into(),
+                guid: "wlan0".into(),
+                kind: "wireless".into(),
+                ..AdapterInfo::default()
+            },
+        ])));
+        assert_eq!(
+            app.diagnostics.wifi.adapters,
+            vec![("Wi-Fi".to_string(), "wlan0".to_string())]
+        );
+        app.diagnostics.focused = true;
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartPing { job: ping, .. } = effects[0].clone() else {
+            panic!("expected ping start");
+        };
+        app.update(Runtime(RuntimeEvent::PingSample {
+            job: ping,
+            sample: crate::PingSample {
+                sequence: 7,
+                latency_ms: Some(12),
+                ttl: Some(64),
+                size: 32,
+                sent: 7,
+                received: 7,
+                min_ms: Some(12),
+                average_ms: Some(12.0),
+                max_ms: Some(12),
+                loss_percent: 0.0,
+                route: Vec::new(),
+                timestamps: None,
+            },
+        }));
+
+        app.diagnostics.tool = DiagnosticTool::Wifi;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let Effect::StartWifi { job, ref request } = effects[0] else {
+            panic!("expected wifi start");
+        };
+        assert_eq!((request.guid.as_str(), request.interval_secs), ("wlan0", 3));
+        let link = |bssid: &str, channel, rssi_dbm| crate::WirelessSnapshot {
+            ssid: "HomeLab".into(),
+            bssid: bssid.into(),
+            signal_quality: 0,
+            rssi_dbm,
+            phy_type: "-".into(),
+            wifi_generation: 0,
+            band: "5 GHz".into(),
+            channel,
+            frequency_mhz: 0,
+            rx_rate_mbps: 0,
+            tx_rate_mbps: 433,
+            authentication: "-".into(),
+            cipher: "-".into(),
+        };
+        for (elapsed_ms, link) in [
+            (0, Some(link("aa:00:00:00:00:01", 36, -76))),
+            (3_000, Some(link("aa:00:00:00:00:02", 149, -50))),
+            (6_000, None),
+        ] {
+            app.update(Runtime(RuntimeEvent::WifiSample {
+                job,
+                sample: crate::WifiSample {
+                    elapsed_ms,
+                    link,
+                    nearby: Vec::new(),
+                },
+            }));
+        }
+        let wifi = &app.diagnostics.wifi;
+        assert_eq!(wifi.roams.len(), 1);
+        assert_eq!(wifi.roams[0].ping_sequence, Some(7));
+        assert_eq!(wifi.common.primary, "not associated");
+        assert_eq!(wifi.common.detail, "1 roams · RSSI -76/-63/-50 dBm");
+        assert!(
+            app.diagnostics
+                .ping
+                .common
+                .log
+                .last()
+                .unwrap()
+                .contains("⇄ Wi-Fi roamed aa:00:00:00:00:01 (ch 36, -76 dBm)")
+        );
+        let event = app.events.entries.last().unwrap();
+        assert_eq!(event.kind, crate::NetworkEventKind::WifiRoamed);
+        assert_eq!(
+            (event.subject.as_str(), event.to.as_str()),
+            ("Wi-Fi", "aa:00:00:00:00:02 (ch 149)")
+        );
 
+        // Both land on the timeline too, on the session clock.
+        let timeline = &app.timeline;
+        assert_eq!(
+            timeline.pings,
+            [crate::TimelinePing {
+                at_ms: 0,
+                latency_ms: Some(12)
+            }]
+        );
+        assert_eq!(timeline.marks.len(), 1);
+        assert_eq!(timeline.marks[0].lane(), crate::TimelineLane::Wifi);
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::F(9)))));
+        assert!(app.popup_open(crate::Popup::Timeline));
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Right))));
+        assert_eq!(app.timeline.span_ms(), 900_000);
         app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Esc))));
-        assert!(!app.diagnostics.focused);
+        assert!(!app.popup_open(crate::Popup::Timeline));
     }
 
     #[test]
-    fn clicking_a_diagnostic_row_switches_tool_even_when_already_focused() {
+    fn scanner_switches_to_bluetooth_only_where_it_can_scan() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            capabilities: crate::PlatformCapabilities {
+                bluetooth: false,
+                ..crate::PlatformCapabilities::default()
+            },
+            ..AppModel::default()
+        };
+        app.update(Input(InputEvent::Action(Action::Refresh)));
+        assert_eq!(app.scanner.view, ScannerView::Hosts);
+
+        app.capabilities.bluetooth = true;
+        app.update(Input(InputEvent::Action(Action::Refresh)));
+        assert_eq!(app.scanner.view, ScannerView::Bluetooth);
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        let [Effect::StartBluetoothScan { job, ref request }] = effects[..] else {
+            panic!("expected a Bluetooth scan: {effects:?}");
+        };
+        assert_eq!(request.duration_secs, crate::BLUETOOTH_SCAN_SECS);
+        assert!(app.scanner.job.is_none(), "the IP sweep is untouched");
+        let device = |address: &str, rssi_dbm| crate::BluetoothDevice {
+            address: address.into(),
+            name: None,
+            rssi_dbm: Some(rssi_dbm),
+            tx_power_dbm: None,
+            manufacturer_id: None,
+            last_seen_ms: 0,
+        };
+        for seen in [device("aa", -80), device("bb", -50)] {
+            app.update(Runtime(RuntimeEvent::BluetoothDeviceSeen {
+                job,
+                device: seen,
+            }));
+        }
+        assert_eq!(app.scanner.bluetooth.devices[0].address, "bb");
+        assert_eq!(
+            app.scanner.bluetooth.selected, 1,
+            "still on the first device"
+        );
+        app.update(Input(InputEvent::Action(Action::Down)));
+        assert_eq!(app.scanner.bluetooth.selected, 0);
+
+        app.update(Runtime(RuntimeEvent::BluetoothScanFailed {
+            job,
+            error: crate::RuntimeError::new(crate::RuntimeErrorCode::Internal, "adapter off"),
+        }));
+        assert_eq!(
+            app.scanner.bluetooth.status,
+            TaskStatus::Failed("adapter off".into())
+        );
+        assert!(app.scanner.bluetooth.job.is_none());
+        app.update(Input(InputEvent::Action(Action::Refresh)));
+        assert_eq!(app.scanner.view, ScannerView::Hosts);
+    }
+
+    #[test]
+    fn multicast_send_mode_adds_the_rate_field_and_persists() {
         let mut app = AppModel {
             page: Page::Diagnostics,
             ..AppModel::default()
         };
         app.diagnostics.focused = true;
         app.diagnostics.focus = DiagnosticFocus::Config;
+        app.diagnostics.tool = DiagnosticTool::Multicast;
+        assert_eq!(app.diagnostic_config_count(), 4);
+        let effects = app.update(Input(InputEvent::Action(Action::Right)));
+        assert_eq!(
+            app.diagnostics.multicast.request.mode,
+            crate::MulticastMode::Send
+        );
+        assert_eq!(app.diagnostic_config_count(), 5);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Multicast(persist))]
+                if persist.mode == crate::MulticastMode::Send
+        ));
 
-        let effects = app.update(Input(InputEvent::Action(Action::SelectDiagnostic(
-            DiagnosticTool::Trace as u8,
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            4, 0,
         ))));
-
-        assert_eq!(app.diagnostics.tool, DiagnosticTool::Trace);
-        assert_eq!(app.diagnostics.focus, DiagnosticFocus::Menu);
-        assert!(matches!(effects.as_slice(), [Effect::PersistSession(_)]));
+        app.diagnostics.cursor = 0;
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('1')))));
+        assert_eq!(app.diagnostics.multicast.request.rate_pps, 120);
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::StartMulticast { request, .. }]
+                if request.mode == crate::MulticastMode::Send && request.rate_pps == 120
+        ));
     }
 
     #[test]
-    fn diagnostic_history_mouse_selection_fills_the_active_target() {
+    fn upnp_action_changes_the_field_set_and_starts_with_the_chosen_protocol() {
         let mut app = AppModel {
             page: Page::Diagnostics,
             ..AppModel::default()
         };
         app.diagnostics.focused = true;
         app.diagnostics.focus = DiagnosticFocus::Config;
-        app.diagnostics.target_history = vec!["1.1.1.1".into(), "8.8.8.8".into()];
-        app.diagnostics.history_open = true;
-        let effects = app.update(Input(InputEvent::Action(Action::SelectDiagnosticHistory(
-            1,
+        app.diagnostics.tool = DiagnosticTool::Upnp;
+        assert_eq!(app.diagnostic_config_count(), 1);
+        app.update(Input(InputEvent::Action(Action::Left)));
+        assert_eq!(
+            app.diagnostics.upnp.request.action,
+            crate::UpnpAction::Delete
+        );
+        assert_eq!(app.diagnostic_config_count(), 3);
+        let effects = app.update(Input(InputEvent::Action(Action::Left)));
+        assert_eq!(app.diagnostic_config_count(), 5);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::PersistSession(crate::SessionUpdate::Upnp(persist))]
+                if persist.action == crate::UpnpAction::Add
+        ));
+
+        app.update(Input(InputEvent::Action(Action::Down)));
+        app.update(Input(InputEvent::Action(Action::Right)));
+        app.update(Input(InputEvent::Action(Action::SelectDiagnosticField(
+            2, 0,
         ))));
-        assert_eq!(app.diagnostics.ping.request.target, "8.8.8.8");
-        assert!(!app.diagnostics.history_open);
+        app.diagnostics.cursor = 5;
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Backspace))));
+        app.update(Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('6')))));
+        app.diagnostics.focus = DiagnosticFocus::Main;
+        let effects = app.update(Input(InputEvent::Action(Action::Toggle)));
         assert!(matches!(
             effects.as_slice(),
-            [Effect::PersistSession(crate::SessionUpdate::Ping(_))]
+            [Effect::StartUpnp { request, .. }]
+                if request.action == crate::UpnpAction::Add
+                    && request.protocol == crate::LanProtocol::Udp
+                    && request.external_port == 50_506
         ));
     }
 
@@ -3906,6 +11101,7 @@ mod tests {
             target: "trace.example".into(),
             max_hops: "999".into(),
             timeout_ms: "1".into(),
+            ..Default::default()
         };
         config.session.history.targets = vec!["trace.example".into()];
         app.apply_config(&config);
@@ -4051,6 +11247,7 @@ mod tests {
                 elapsed_ms: 500,
                 bytes: 2_000_000,
                 bytes_per_second: 4_000_000,
+                phase: crate::SpeedPhase::Download,
             },
         }));
         assert_eq!(
@@ -4066,15 +11263,39 @@ mod tests {
             error: crate::RuntimeError::new(crate::RuntimeErrorCode::Network, "stale"),
         }));
         assert_eq!(app.diagnostics.public_speed.common.job, Some(restarted));
+        assert!(app.diagnostics.public_speed.samples.is_empty());
+        let latency = crate::LatencySample {
+            phase: crate::SpeedPhase::Idle,
+            rtt_ms: Some(12),
+        };
+        app.update(Runtime(RuntimeEvent::PublicSpeedLatency {
+            job: restarted,
+            sample: latency,
+        }));
+        assert_eq!(app.diagnostics.public_speed.latency, [latency]);
         app.update(Runtime(RuntimeEvent::PublicSpeedFinished {
             job: restarted,
             summary: crate::SpeedSummary {
                 average_bytes_per_second: 4_000_000,
                 peak_bytes_per_second: 5_000_000,
                 total_bytes: 8_000_000,
+                upload_bytes_per_second: Some(1_000_000),
+                bufferbloat: Some(crate::BufferbloatSummary {
+                    idle_ms: Some(12.0),
+                    download_ms: Some(70.0),
+                    upload_ms: None,
+                    grade: Some(crate::BufferbloatGrade::C),
+                }),
             },
         }));
         assert_eq!(app.diagnostics.public_speed.common.status, TaskStatus::Done);
+        assert!(
+            app.diagnostics
+                .public_speed
+                .common
+                .detail
+                .ends_with("upload 1000000 B/s · bufferbloat C")
+        );
         let failed = app.update(Input(InputEvent::Action(Action::Toggle)));
         let Effect::StartPublicSpeed { job: failed, .. } = failed[0] else {
             panic!("expected public speed retry");
@@ -4181,7 +11402,7 @@ mod tests {
     fn port_scan_and_lan_speed_keep_raw_config_and_validate_at_execution() {
         let mut config = crate::ConfigData::default();
         config.session.port_scan = crate::PortScanPersist {
-            target: "scan.example".into(),
+            target: "scan.lan".into(),
             start_port: "200".into(),
             end_port: "100".into(),
             timeout_ms: "1".into(),
@@ -4385,4 +11606,48 @@ mod tests {
         assert_eq!(app.dashboard.snapshot.download_bps, 1_000);
         assert_eq!(app.dashboard.snapshot.total_upload, 5_000);
     }
+
+    #[test]
+    fn pages_start_on_first_visit_and_resume_with_fresh_data() {
+        let mut app = AppModel {
+            page: Page::Scanner,
+            ..AppModel::default()
+        };
+        let effects = app.bootstrap_effects();
+        assert!(
+            matches!(effects.as_slice(), [Effect::RefreshAdapters { .. }]),
+            "only the adapter list is loaded up front: {effects:?}"
+        );
+
+        app.scanner.history_open = true;
+        let effects = app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Dashboard as u8,
+        ))));
+        assert!(!app.scanner.history_open, "the hidden page was suspended");
+        assert!(matches!(
+            effects.as_slice(),
+            [
+                Effect::RefreshDashboard { .. },
+                Effect::RefreshTraffic { .. },
+                Effect::PersistSession(crate::SessionUpdate::Ui(_))
+            ]
+        ));
+
+        app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Events as u8,
+        ))));
+        let effects = app.update(Input(InputEvent::Action(Action::SelectPage(
+            Page::Dashboard as u8,
+        ))));
+        assert!(
+            matches!(
+                effects.as_slice(),
+                [
+                    Effect::RefreshTraffic { .. },
+                    Effect::PersistSession(crate::SessionUpdate::Ui(_))
+                ]
+            ),
+            "a second visit only catches up on paused polling: {effects:?}"
+        );
+    }
 }
@@ -0,0 +1,2588 @@
+//! Registry of tabs and diagnostic tools.
+//!
+//! Every page and diagnostic tool is described by one [`Module`]. The reducer
+//! routes bootstrap work, runtime events and page-local actions through
+//! [`MODULES`], and the renderer looks up titles, help text and footer key
+//! hints here, so a new
+//! tab or tool registers its behaviour in one place instead of extending the
+//! central `match` arms of `AppModel`.
+
+use crate::model::{fail_common, finish_common, next_revision, scan_host_ip_order};
+use crate::{
+    Action, AdapterEditPhase, AppModel, DiagnosticFocus, DiagnosticTool, Effect, Feature, Language,
+    Page, RuntimeEvent, ScannerView, SpeedPhase, TaskStatus, ToolKind,
+};
+
+/// Identifies a registered module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleId {
+    Page(Page),
+    Tool(DiagnosticTool),
+}
+
+/// Behaviour shared by every tab and diagnostic tool.
+///
+/// Rendering lives in `iptools-ui`, which dispatches on [`ModuleId`] so this
+/// crate stays free of Ratatui.
+pub trait Module: Sync {
+    fn id(&self) -> ModuleId;
+
+    /// Tab or menu label.
+    fn title(&self, language: Language) -> &'static str;
+
+    /// One-line usage hint shown in the help overlay.
+    fn help(&self, language: Language) -> &'static str;
+
+    /// Keys worth showing in the footer right now, most useful first. They
+    /// follow focus and mode, so the footer asks again on every frame.
+    fn hints(&self, _model: &AppModel) -> Vec<KeyHint> {
+        Vec::new()
+    }
+
+    /// Job kinds whose runtime events are delivered to [`Module::update`].
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[]
+    }
+
+    /// Effects to run the first time this page is shown, or at startup for
+    /// [`Module::background`] pages. Tool modules are not initialised.
+    fn init(&self, _model: &mut AppModel) -> Vec<Effect> {
+        Vec::new()
+    }
+
+    /// Whether [`Module::init`] runs at startup rather than on first visit,
+    /// because other pages build on this module's data.
+    fn background(&self) -> bool {
+        false
+    }
+
+    /// Effects to run when the page is shown again, to catch up on polling
+    /// that was paused while it was hidden.
+    fn resume(&self, _model: &mut AppModel) -> Vec<Effect> {
+        Vec::new()
+    }
+
+    /// Called when another page is shown in place of this one.
+    fn suspend(&self, _model: &mut AppModel) {}
+
+    /// Apply a runtime event owned by one of [`Module::jobs`]. Stale
+    /// generations must be ignored here.
+    fn update(&self, _model: &mut AppModel, _event: RuntimeEvent) {}
+
+    /// Handle an action while this module's page is active. Returning `None`
+    /// falls through to the global bindings.
+    fn on_key(&self, _model: &mut AppModel, _action: Action) -> Option<Vec<Effect>> {
+        None
+    }
+
+    /// Feature the capability policy can turn off, for modules that probe
+    /// other hosts. Only the probing is refused; what the module already
+    /// shows stays available.
+    fn feature(&self) -> Option<Feature> {
+        None
+    }
+}
+
+/// Every registered module, pages first in tab order, then diagnostic tools in
+/// menu order.
+pub static MODULES: [&dyn Module; 27] = [
+    &DashboardModule,
+    &AdaptersModule,
+    &ScannerModule,
+    &TrafficModule,
+    &DiagnosticsModule,
+    &EventsModule,
+    &SettingsModule,
+    &PingModule,
+    &TraceModule,
+    &PortScanModule,
+    &LinkQualityModule,
+    &PublicSpeedModule,
+    &LanSpeedModule,
+    &MtuModule,
+    &DscpModule,
+    &MulticastModule,
+    &UpnpModule,
+    &NatModule,
+    &Ipv6Module,
+    &DnsBenchModule,
+    &DnsFilterModule,
+    &MailModule,
+    &VoipModule,
+    &GamingModule,
+    &CloudModule,
+    &WifiModule,
+    &AbTestModule,
+];
+
+/// Look up a registered module.
+pub fn module(id: ModuleId) -> &'static dyn Module {
+    MODULES
+        .iter()
+        .copied()
+        .find(|module| module.id() == id)
+        .expect("every page and diagnostic tool is registered")
+}
+
+/// The module that owns runtime events for `tool`.
+pub fn module_for_job(tool: ToolKind) -> Option<&'static dyn Module> {
+    MODULES
+        .iter()
+        .copied()
+        .find(|module| module.jobs().contains(&tool))
+}
+
+/// Whether the capability policy lets module `id` start work. Every start
+/// of a policed module goes through here.
+pub fn permitted(model: &AppModel, id: ModuleId) -> bool {
+    module(id)
+        .feature()
+        .is_none_or(|feature| model.policy.allows(feature))
+}
+
+fn tr(language: Language, zh: &'static str, en: &'static str) -> &'static str {
+    match language {
+        Language::Zh => zh,
+        Language::En => en,
+    }
+}
+
+/// A key the footer suggests for the current state of a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyHint {
+    /// Keymap names of the bindings with the key shown when a binding is not
+    /// configured, so rebound keys appear as configured.
+    pub keys: &'static [(&'static str, &'static str)],
+    pub label: &'static str,
+    /// What a click on the hint does; `None` for keys that only work typed.
+    pub action: Option<Action>,
+}
+
+const CONFIRM: &[(&str, &str)] = &[("confirm", "Enter")];
+const BACK: &[(&str, &str)] = &[("back", "Esc")];
+const TOGGLE: &[(&str, &str)] = &[("toggle", "Space")];
+const EDIT: &[(&str, &str)] = &[("edit", "E")];
+const REFRESH: &[(&str, &str)] = &[("refresh", "R")];
+const HISTORY: &[(&str, &str)] = &[("history", "Ctrl+R")];
+const NEXT: &[(&str, &str)] = &[("next_tab", "Tab")];
+const UP_DOWN: &[(&str, &str)] = &[("up", "Up"), ("down", "Down")];
+const LEFT_RIGHT: &[(&str, &str)] = &[("left", "Left"), ("right", "Right")];
+const EXPORT: &[(&str, &str)] = &[("export", "Ctrl+S")];
+
+fn hint(
+    keys: &'static [(&'static str, &'static str)],
+    action: Option<Action>,
+    label: &'static str,
+) -> KeyHint {
+    KeyHint {
+        keys,
+        label,
+        action,
+    }
+}
+
+/// Hints while a list of earlier values is open under an input.
+fn history_hints(language: Language) -> Vec<KeyHint> {
+    vec![
+        hint(UP_DOWN, None, tr(language, "选择", "choose")),
+        hint(CONFIRM, Some(Action::Confirm), tr(language, "填入", "use")),
+        hint(BACK, Some(Action::Back), tr(language, "关闭", "close")),
+    ]
+}
+
+/// Offered once the tool in view has history to chart.
+fn export_hint(model: &AppModel) -> Vec<KeyHint> {
+    if model.current_chart().is_none() {
+        return Vec::new();
+    }
+    vec![hint(
+        EXPORT,
+        Some(Action::Export),
+        tr(model.language, "导出图表", "export chart"),
+    )]
+}
+
+fn start_stop(language: Language, running: bool) -> &'static str {
+    if running {
+        tr(language, "停止", "stop")
+    } else {
+        tr(language, "开始", "start")
+    }
+}
+
+struct DashboardModule;
+
+impl Module for DashboardModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Dashboard)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "概览", "Dashboard")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "概览：R 刷新主机、公网 IP 与当前网卡；空格 暂停监视目标的告警（15 分钟、1 小时、4 小时、关闭）",
+            "Dashboard: R refreshes host, public IP and active interface; Space snoozes watch alerts (15 min, 1 h, 4 h, off)",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let mut hints = vec![hint(
+            REFRESH,
+            Some(Action::Refresh),
+            tr(model.language, "刷新", "refresh"),
+        )];
+        if !model.watch.tiles.is_empty() {
+            hints.push(hint(
+                TOGGLE,
+                Some(Action::Toggle),
+                tr(model.language, "暂停告警", "snooze alerts"),
+            ));
+        }
+        hints
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Dashboard, ToolKind::Watch]
+    }
+
+    /// Live rates come from traffic polling, which stops while no page shows
+    /// them, so a fresh sample is taken on every visit.
+    fn init(&self, model: &mut AppModel) -> Vec<Effect> {
+        let mut effects = model.refresh_dashboard();
+        effects.extend(model.refresh_traffic());
+        effects
+    }
+
+    fn resume(&self, model: &mut AppModel) -> Vec<Effect> {
+        model.refresh_traffic()
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let dashboard = &mut model.dashboard;
+        let watch = &mut model.watch;
+        match event {
+            RuntimeEvent::WatchChecked { job, check } if watch.job == Some(job) => {
+                model.record_watch(check);
+            }
+            RuntimeEvent::WatchFinished { job } if watch.job == Some(job) => watch.job = None,
+            RuntimeEvent::DashboardUpdated(snapshot) => dashboard.snapshot = *snapshot,
+            RuntimeEvent::DashboardRefreshFinished { job, snapshot }
+                if dashboard.job == Some(job) =>
+            {
+                dashboard.snapshot = *snapshot;
+                dashboard.status = TaskStatus::Done;
+                dashboard.error = None;
+                dashboard.job = None;
+            }
+            RuntimeEvent::DashboardRefreshFailed {
+                job,
+                snapshot,
+                error,
+            } if dashboard.job == Some(job) => {
+                dashboard.snapshot = *snapshot;
+                dashboard.status = TaskStatus::Failed(error.message.clone());
+                dashboard.error = Some(error);
+                dashboard.job = None;
+            }
+            RuntimeEvent::DashboardRefreshCancelled { job } if dashboard.job == Some(job) => {
+                dashboard.status = TaskStatus::Done;
+                dashboard.job = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        match action {
+            Action::Refresh => Some(model.refresh_dashboard()),
+            Action::Toggle if !model.watch.tiles.is_empty() => {
+                model.watch.cycle_snooze(model.elapsed_ms);
+                Some(Vec::new())
+            }
+            _ => None,
+        }
+    }
+}
+
+struct AdaptersModule;
+
+impl Module for AdaptersModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Adapters)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "适配器", "Adapters")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "适配器：回车或 E 编辑 IPv4 配置，R 刷新",
+            "Adapters: Enter or E edits IPv4 settings, R refreshes",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let language = model.language;
+        let Some(edit) = &model.adapters.edit else {
+            return vec![
+                hint(EDIT, Some(Action::Edit), tr(language, "编辑", "edit")),
+                hint(
+                    REFRESH,
+                    Some(Action::Refresh),
+                    tr(language, "刷新", "refresh"),
+                ),
+            ];
+        };
+        match edit.phase {
+            AdapterEditPhase::Applying => Vec::new(),
+            AdapterEditPhase::Succeeded(_) => {
+                vec![hint(
+                    CONFIRM,
+                    Some(Action::Confirm),
+                    tr(language, "完成", "done"),
+                )]
+            }
+            AdapterEditPhase::Failed(_) => vec![hint(
+                CONFIRM,
+                Some(Action::Confirm),
+                tr(language, "返回表单", "back to the form"),
+            )],
+            AdapterEditPhase::Confirming => vec![
+                hint(
+                    CONFIRM,
+                    Some(Action::Confirm),
+                    tr(language, "应用", "apply"),
+                ),
+                hint(
+                    BACK,
+                    Some(Action::Back),
+                    tr(language, "返回修改", "keep editing"),
+                ),
+            ],
+            AdapterEditPhase::Editing if edit.history_open => history_hints(language),
+            AdapterEditPhase::Editing => {
+                let mut hints = vec![
+                    hint(UP_DOWN, None, tr(language, "切换字段", "field")),
+                    hint(
+                        CONFIRM,
+                        Some(Action::Confirm),
+                        tr(language, "检查并应用", "review"),
+                    ),
+                ];
+                if edit.selected == crate::AdapterField::Mode {
+                    hints.push(hint(
+                        LEFT_RIGHT,
+                        None,
+                        tr(language, "DHCP / 静态", "DHCP / static"),
+                    ));
+                } else if !edit.params.use_dhcp {
+                    hints.push(hint(
+                        HISTORY,
+                        Some(Action::History),
+                        tr(language, "历史", "history"),
+                    ));
+                }
+                hints.push(hint(
+                    BACK,
+                    Some(Action::Back),
+                    tr(language, "取消", "cancel"),
+                ));
+                hints
+            }
+        }
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Adapters, ToolKind::AdapterEdit]
+    }
+
+    fn init(&self, model: &mut AppModel) -> Vec<Effect> {
+        model.refresh_adapters()
+    }
+
+    /// The scanner's default range, link quality and the event log all start
+    /// from the adapter list.
+    fn background(&self) -> bool {
+        true
+    }
+
+    fn resume(&self, model: &mut AppModel) -> Vec<Effect> {
+        if model.adapters.edit.is_some() {
+            return Vec::new();
+        }
+        model.refresh_adapters()
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let edit_job = model.adapters.edit.as_ref().and_then(|edit| edit.job);
+        match event {
+            RuntimeEvent::AdaptersUpdated(adapters) => {
+                model.sync_scanner_cidr(&adapters);
+                model.adapters.items = adapters;
+                model.sync_link_quality_adapters();
+                model.sync_wifi_adapters();
+            }
+            RuntimeEvent::AdaptersRefreshFinished { job, adapters }
+                if model.adapters.job == Some(job) =>
+            {
+                let selected_name = model
+                    .adapters
+                    .items
+                    .get(model.adapters.selected)
+                    .map(|adapter| adapter.name.as_str());
+                model.adapters.selected = selected_name
+                    .and_then(|name| adapters.iter().position(|adapter| adapter.name == name))
+                    .unwrap_or(0)
+                    .min(adapters.len().saturating_sub(1));
+                model.sync_scanner_cidr(&adapters);
+                model.adapters.items = adapters;
+                model.sync_link_quality_adapters();
+                model.sync_wifi_adapters();
+                model.adapters.status = TaskStatus::Done;
+                model.adapters.error = None;
+                model.adapters.job = None;
+            }
+            RuntimeEvent::AdaptersRefreshFailed { job, error }
+                if model.adapters.job == Some(job) =>
+            {
+                model.adapters.status = TaskStatus::Failed(error.message.clone());
+                model.adapters.error = Some(error);
+                model.adapters.job = None;
+            }
+            RuntimeEvent::AdaptersRefreshCancelled { job } if model.adapters.job == Some(job) => {
+                model.adapters.status = TaskStatus::Done;
+                model.adapters.job = None;
+            }
+            RuntimeEvent::AdapterConfigStarted { job } if edit_job == Some(job) => {
+                if let Some(edit) = model.adapters.edit.as_mut() {
+                    edit.phase = AdapterEditPhase::Applying;
+                }
+            }
+            RuntimeEvent::AdapterConfigFinished { job, outcome } if edit_job == Some(job) => {
+                if let Some(edit) = model.adapters.edit.as_mut() {
+                    edit.phase = AdapterEditPhase::Succeeded(outcome);
+                    edit.job = None;
+                }
+            }
+            RuntimeEvent::AdapterConfigFailed { job, error } if edit_job == Some(job) => {
+                if let Some(edit) = model.adapters.edit.as_mut() {
+                    edit.phase = AdapterEditPhase::Failed(error);
+                    edit.job = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        match action {
+            Action::Refresh => Some(model.refresh_adapters()),
+            Action::Edit | Action::Confirm | Action::Toggle => Some(model.begin_adapter_edit()),
+            _ => None,
+        }
+    }
+}
+
+struct ScannerModule;
+
+impl Module for ScannerModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Scanner)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "扫描", "Scanner")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "扫描：E 编辑网段，空格开始/停止，回车展开设备详情（详情中 E 写备注），←/→ 开关共享与网页探测，R 切换到附近的蓝牙设备（如支持）",
+            "Scanner: E edits the CIDR, Space starts/stops, Enter opens device details (E there edits the note), Left/Right toggles share and web probing, R switches to nearby Bluetooth devices where supported",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let language = model.language;
+        let scanner = &model.scanner;
+        if scanner.note_input.is_some() {
+            return vec![
+                hint(CONFIRM, None, tr(language, "保存备注", "save note")),
+                hint(BACK, None, tr(language, "取消", "cancel")),
+            ];
+        }
+        if scanner.editing && scanner.history_open {
+            return history_hints(language);
+        }
+        if scanner.editing {
+            return vec![
+                hint(CONFIRM, Some(Action::Confirm), tr(language, "完成", "done")),
+                hint(
+                    HISTORY,
+                    Some(Action::History),
+                    tr(language, "历史", "history"),
+                ),
+            ];
+        }
+        if scanner.view == ScannerView::Bluetooth {
+            return vec![
+                hint(
+                    TOGGLE,
+                    Some(Action::Toggle),
+                    start_stop(language, scanner.bluetooth.job.is_some()),
+                ),
+                hint(
+                    REFRESH,
+                    Some(Action::Refresh),
+                    tr(language, "IP 主机", "IP hosts"),
+                ),
+            ];
+        }
+        if scanner.detail_open {
+            let mut hints = vec![hint(
+                EDIT,
+                Some(Action::Edit),
+                tr(language, "写备注", "note"),
+            )];
+            if scanner
+                .selected_host()
+                .is_some_and(|host| !scanner.cve.pending(host).is_empty())
+            {
+                hints.push(hint(
+                    REFRESH,
+                    Some(Action::Refresh),
+                    tr(language, "查询已知 CVE", "look up CVEs"),
+                ));
+            }
+            hints.push(hint(
+                BACK,
+                Some(Action::Back),
+                tr(language, "关闭详情", "close details"),
+            ));
+            return hints;
+        }
+        let mut hints = vec![
+            hint(
+                EDIT,
+                Some(Action::Edit),
+                tr(language, "编辑网段", "edit range"),
+            ),
+            hint(
+                TOGGLE,
+                Some(Action::Toggle),
+                start_stop(language, scanner.job.is_some()),
+            ),
+        ];
+        if !scanner.results.is_empty() {
+            hints.push(hint(
+                CONFIRM,
+                Some(Action::Confirm),
+                tr(language, "设备详情", "details"),
+            ));
+        }
+        hints.push(hint(
+            LEFT_RIGHT,
+            Some(Action::Right),
+            tr(language, "共享与网页探测", "share & web probes"),
+        ));
+        if model.capabilities.bluetooth {
+            hints.push(hint(
+                REFRESH,
+                Some(Action::Refresh),
+                tr(language, "蓝牙设备", "Bluetooth"),
+            ));
+        }
+        hints
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Scanner, ToolKind::Bluetooth, ToolKind::Cve]
+    }
+
+    fn feature(&self) -> Option<Feature> {
+        Some(Feature::LanScan)
+    }
+
+    fn suspend(&self, model: &mut AppModel) {
+        model.scanner.history_open = false;
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let scanner = &mut model.scanner;
+        let clock = &mut model.revision_clock;
+        match event {
+            RuntimeEvent::ScanStarted { job, total } if scanner.job == Some(job) => {
+                scanner.total = total;
+                scanner.status = TaskStatus::Running;
+            }
+            RuntimeEvent::ScanProgress { job, progress } if scanner.job == Some(job) => {
+                scanner.progress = progress;
+            }
+            RuntimeEvent::ScanHostFound { job, host } if scanner.job == Some(job) => {
+                scanner.remember(&host);
+                scanner.arrivals.push_back(host.ip.clone());
+                // Insert in place rather than re-sorting: a /16 finds tens of
+                // thousands of hosts, one event each.
+                let index = scanner
+                    .results
+                    .partition_point(|existing| scan_host_ip_order(existing, &host).is_le());
+                if index <= scanner.selected && !scanner.results.is_empty() {
+                    scanner.selected += 1;
+                }
+                scanner.results.insert(index, *host);
+                scanner.revision = next_revision(clock);
+            }
+            RuntimeEvent::ScanHostEnriched {
+                job,
+                ip,
+                open_ports,
+                shares,
+                web,
+                iot,
+                smb1,
+                banners,
+            } if scanner.job == Some(job) => {
+                if let Some(index) = scanner.results.iter().position(|host| host.ip == ip) {
+                    let host = &mut scanner.results[index];
+                    host.open_ports = open_ports;
+                    host.shares = shares;
+                    host.web = web;
+                    host.iot = iot;
+                    host.smb1 = smb1;
+                    host.banners = banners;
+                    host.enriched = true;
+                    let (key, types) = (host.device_key(), crate::device_types(&host.iot));
+                    scanner.tag_device(&key, types);
+                    scanner.revision = next_revision(clock);
+                }
+            }
+            RuntimeEvent::ScanServicesFound { job, services } if scanner.job == Some(job) => {
+                scanner.services.extend(services);
+                scanner.revision = next_revision(clock);
+            }
+            RuntimeEvent::ScanFinished { job } | RuntimeEvent::ScanCancelled { job }
+                if scanner.job == Some(job) =>
+            {
+                scanner.status = TaskStatus::Done;
+                scanner.job = None;
+            }
+            RuntimeEvent::BluetoothDeviceSeen { job, device }
+                if scanner.bluetooth.job == Some(job) =>
+            {
+                scanner.bluetooth.record(device);
+            }
+            RuntimeEvent::BluetoothScanFinished { job } if scanner.bluetooth.job == Some(job) => {
+                scanner.bluetooth.status = TaskStatus::Done;
+                scanner.bluetooth.job = None;
+            }
+            RuntimeEvent::BluetoothScanFailed { job, error }
+                if scanner.bluetooth.job == Some(job) =>
+            {
+                scanner.bluetooth.status = TaskStatus::Failed(error.message);
+                scanner.bluetooth.job = None;
+            }
+            RuntimeEvent::CveHintFound { job, hint } if scanner.cve.job == Some(job) => {
+                scanner.cve.hints.push(hint);
+            }
+            RuntimeEvent::CveLookupFinished { job } if scanner.cve.job == Some(job) => {
+                scanner.cve.job = None;
+            }
+            RuntimeEvent::CveLookupFailed { job, error } if scanner.cve.job == Some(job) => {
+                scanner.cve.job = None;
+                scanner.cve.error = Some(error);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        if model.scanner.view == ScannerView::Bluetooth {
+            return match action {
+                Action::Toggle => Some(model.toggle_bluetooth_scan()),
+                Action::Refresh => Some(model.toggle_scanner_view()),
+                Action::Edit | Action::Confirm | Action::Left | Action::Right => Some(Vec::new()),
+                _ => None,
+            };
+        }
+        match action {
+            Action::Refresh if !model.scanner.detail_open => Some(model.toggle_scanner_view()),
+            Action::Edit if model.scanner.detail_open => Some(model.begin_note_edit()),
+            Action::Refresh => Some(model.lookup_cves()),
+            Action::Back if model.scanner.detail_open => {
+                model.scanner.detail_open = false;
+                Some(Vec::new())
+            }
+            Action::Edit => {
+                model.scanner.editing = true;
+                model.scanner.cursor = model.scanner.cidr.len();
+                model.scanner.history_open = false;
+                Some(Vec::new())
+            }
+            Action::Confirm => Some(model.confirm_scanner()),
+            Action::Toggle => Some(model.toggle_scan()),
+            Action::Left | Action::Right => Some(model.toggle_enrichment()),
+            _ => None,
+        }
+    }
+}
+
+struct TrafficModule;
+
+impl Module for TrafficModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Traffic)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "流量", "Traffic")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "流量：上下选择网卡，R 刷新",
+            "Traffic: Up/Down selects an interface, R refreshes",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let language = model.language;
+        vec![
+            hint(UP_DOWN, None, tr(language, "选择网卡", "interface")),
+            hint(
+                REFRESH,
+                Some(Action::Refresh),
+                tr(language, "刷新", "refresh"),
+            ),
+        ]
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Traffic]
+    }
+
+    fn init(&self, model: &mut AppModel) -> Vec<Effect> {
+        model.refresh_traffic()
+    }
+
+    fn resume(&self, model: &mut AppModel) -> Vec<Effect> {
+        model.refresh_traffic()
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        match event {
+            RuntimeEvent::TrafficUpdated(rows) => {
+                model.sync_dashboard_traffic(&rows);
+                model.record_link_health(&rows);
+                model.traffic.rows = rows;
+                model.traffic.revision = next_revision(&mut model.revision_clock);
+            }
+            RuntimeEvent::TrafficRefreshFinished { job, rows }
+                if model.traffic.job == Some(job) =>
+            {
+                model.sync_dashboard_traffic(&rows);
+                model.record_link_health(&rows);
+                let traffic = &mut model.traffic;
+                let selected_name = traffic
+                    .rows
+                    .get(traffic.selected)
+                    .map(|row| row.name.as_str());
+                traffic.selected = selected_name
+                    .and_then(|name| rows.iter().position(|row| row.name == name))
+                    .unwrap_or(0)
+                    .min(rows.len().saturating_sub(1));
+                traffic.rows = rows;
+                traffic.revision = next_revision(&mut model.revision_clock);
+                traffic.status = TaskStatus::Done;
+                traffic.error = None;
+                traffic.job = None;
+            }
+            RuntimeEvent::TrafficRefreshFailed { job, error } if model.traffic.job == Some(job) => {
+                model.traffic.status = TaskStatus::Failed(error.message.clone());
+                model.traffic.error = Some(error);
+                model.traffic.job = None;
+            }
+            RuntimeEvent::TrafficRefreshCancelled { job } if model.traffic.job == Some(job) => {
+                model.traffic.status = TaskStatus::Done;
+                model.traffic.job = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        (action == Action::Refresh).then(|| model.refresh_traffic())
+    }
+}
+
+struct EventsModule;
+
+impl Module for EventsModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Events)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "事件", "Events")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "事件：网卡上下线、地址、网关与公网 IP 的变化记录；上下浏览，R 立即检查",
+            "Events: interface, address, gateway and public IP changes; Up/Down browses, R checks now",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let language = model.language;
+        vec![
+            hint(UP_DOWN, None, tr(language, "浏览", "browse")),
+            hint(
+                REFRESH,
+                Some(Action::Refresh),
+                tr(language, "立即检查", "check now"),
+            ),
+        ]
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        (action == Action::Refresh).then(|| model.recheck_network())
+    }
+}
+
+struct DiagnosticsModule;
+
+impl Module for DiagnosticsModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Diagnostics)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "诊断", "Diagnostics")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "诊断：回车进入，Tab 在工具、面板和参数之间切换，M / V / C 直接跳到工具列表、监控面板或参数（参数区内加 Alt）",
+            "Diagnostics: Enter focuses, Tab cycles tools, panel and parameters, M / V / C jump to the tools, visualization or parameters (with Alt while typing a parameter)",
+        )
+    }
+
+    /// The focus model: the tool menu, the result panel and the parameters
+    /// each take different keys, and Tab moves between them.
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let language = model.language;
+        let diagnostics = &model.diagnostics;
+        if !diagnostics.focused {
+            return vec![hint(
+                CONFIRM,
+                Some(Action::Confirm),
+                tr(language, "进入诊断工具", "open the tools"),
+            )];
+        }
+        if diagnostics.focus == DiagnosticFocus::Config && diagnostics.history_open {
+            return history_hints(language);
+        }
+        let running = diagnostics.active_common().job.is_some();
+        let (mut hints, next) = match diagnostics.focus {
+            DiagnosticFocus::Menu => (
+                vec![hint(UP_DOWN, None, tr(language, "选择工具", "tool"))],
+                tr(language, "结果面板", "results"),
+            ),
+            DiagnosticFocus::Main => (
+                vec![hint(
+                    TOGGLE,
+                    Some(Action::Toggle),
+                    start_stop(language, running),
+                )],
+                tr(language, "参数", "parameters"),
+            ),
+            DiagnosticFocus::Config => {
+                let mut hints = vec![hint(UP_DOWN, None, tr(language, "切换参数", "parameter"))];
+                if !running && model.diagnostic_target_selected() {
+                    hints.push(hint(
+                        HISTORY,
+                        Some(Action::History),
+                        tr(language, "历史", "history"),
+                    ));
+                }
+                (hints, tr(language, "工具列表", "tools"))
+            }
+        };
+        if diagnostics.focus != DiagnosticFocus::Menu {
+            hints.extend(module(ModuleId::Tool(diagnostics.tool)).hints(model));
+        }
+        hints.push(hint(NEXT, Some(Action::NextPage), next));
+        hints.push(hint(
+            BACK,
+            Some(Action::Back),
+            tr(language, "离开", "leave"),
+        ));
+        hints
+    }
+
+    fn suspend(&self, model: &mut AppModel) {
+        model.diagnostics.history_open = false;
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        (action == Action::Confirm).then(|| {
+            model.diagnostics.focused = true;
+            model.diagnostics.focus = DiagnosticFocus::Menu;
+            Vec::new()
+        })
+    }
+}
+
+struct SettingsModule;
+
+impl Module for SettingsModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Page(Page::Settings)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "设置", "Settings")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "设置：左右调整数值，回车输入数值或执行所选项",
+            "Settings: Left/Right adjusts, Enter types a value or runs the selection",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let language = model.language;
+        if model.settings.input.is_some() {
+            return vec![
+                hint(CONFIRM, None, tr(language, "保存", "save")),
+                hint(BACK, None, tr(language, "取消", "cancel")),
+            ];
+        }
+        vec![
+            hint(UP_DOWN, None, tr(language, "选择", "choose")),
+            hint(LEFT_RIGHT, None, tr(language, "调整", "adjust")),
+            hint(
+                CONFIRM,
+                Some(Action::Confirm),
+                tr(language, "输入或执行", "type or run"),
+            ),
+        ]
+    }
+
+    fn on_key(&self, model: &mut AppModel, action: Action) -> Option<Vec<Effect>> {
+        match action {
+            Action::SelectSetting(index) => {
+                model.settings.selected = index.min(crate::SETTINGS_ITEMS - 1);
+                model.settings.done = None;
+                model.settings.error = None;
+                Some(Vec::new())
+            }
+            Action::Left => Some(model.change_setting(-1, false)),
+            Action::Right => Some(model.change_setting(1, false)),
+            Action::Confirm | Action::Toggle => Some(model.change_setting(1, true)),
+            _ => None,
+        }
+    }
+}
+
+struct PingModule;
+
+impl Module for PingModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Ping)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "多功能 Ping", "Advanced Ping")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "Ping：持续发送 ICMP 回显并统计丢包与延迟；填写声称位置可按 RTT 校验其真伪",
+            "Ping: continuous ICMP echo with loss and latency; a claimed location is checked against the RTT",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let diagnostics = &model.diagnostics;
+        let adjustable = diagnostics.focus == DiagnosticFocus::Config
+            && (1..=6).contains(&diagnostics.ping.config_selected)
+            && diagnostics.ping.common.job.is_none();
+        if !adjustable {
+            return export_hint(model);
+        }
+        vec![hint(LEFT_RIGHT, None, tr(model.language, "调整", "adjust"))]
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Ping]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let zh = model.language == Language::Zh;
+        let state = &mut model.diagnostics.ping;
+        match event {
+            RuntimeEvent::PingStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::PingSample { job, sample } if state.common.job == Some(job) => {
+                let primary = sample.latency_ms.map_or_else(
+                    || {
+                        format!(
+                            "seq={} {}",
+                            sample.sequence,
+                            if zh {
+                                "请求超时"
+                            } else {
+                                "request timed out"
+                            }
+                        )
+                    },
+                    |latency| {
+                        format!(
+                            "{} seq={} bytes={} ttl={} time={}ms",
+                            if zh { "回复" } else { "Reply" },
+                            sample.sequence,
+                            sample.size,
+                            sample.ttl.map_or_else(|| "—".into(), |ttl| ttl.to_string()),
+                            latency
+                        )
+                    },
+                );
+                let common = &mut state.common;
+                common.progress = (sample.sequence.saturating_add(1) * 12).min(99) as u8;
+                common.primary = primary.clone();
+                common.detail = format!(
+                    "{} / {} received · {:.1}% loss · avg {:?} ms",
+                    sample.received, sample.sent, sample.loss_percent, sample.average_ms
+                );
+                common.log.push(primary);
+                if !sample.route.is_empty() {
+                    common
+                        .log
+                        .push(format!("  RR: {}", sample.route.join(" → ")));
+                }
+                if let Some(ts) = sample.timestamps {
+                    common.log.push(format!(
+                        "  TS: originate={} receive={} transmit={} (Δ {} ms)",
+                        ts.originate_ms,
+                        ts.receive_ms,
+                        ts.transmit_ms,
+                        ts.receive_ms as i64 - ts.originate_ms as i64
+                    ));
+                }
+                state.summary = Some(crate::PingSummary {
+                    sent: sample.sent,
+                    received: sample.received,
+                    min_ms: sample.min_ms,
+                    average_ms: sample.average_ms,
+                    max_ms: sample.max_ms,
+                    loss_percent: sample.loss_percent,
+                });
+                state.samples.push(sample);
+            }
+            RuntimeEvent::PingFinished { job, summary } if state.common.job == Some(job) => {
+                finish_common(
+                    &mut state.common,
+                    format!(
+                        "{} received / {} sent · {:.1}% loss",
+                        summary.received, summary.sent, summary.loss_percent
+                    ),
+                );
+                state.summary = Some(summary);
+            }
+            RuntimeEvent::PingFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct TraceModule;
+
+impl Module for TraceModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Trace)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "路由跟踪", "Trace Route")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "路由跟踪：逐跳递增 TTL 显示到目标的路径；R 查询各跳的 ASN；填写对比目标或网卡可并排比较两条路径",
+            "Trace: raises the TTL hop by hop; R looks up each hop's ASN; a compare target or interface lines two paths up",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let diagnostics = &model.diagnostics;
+        if diagnostics.focus != DiagnosticFocus::Main || diagnostics.trace.rows() == 0 {
+            return Vec::new();
+        }
+        let mut hints = vec![hint(UP_DOWN, None, tr(model.language, "选择一跳", "hop"))];
+        if diagnostics.trace.asn.job.is_none() {
+            hints.push(hint(
+                REFRESH,
+                Some(Action::Refresh),
+                tr(model.language, "查询 ASN", "look up ASNs"),
+            ));
+        }
+        hints
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Trace]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.trace;
+        let job = match &event {
+            RuntimeEvent::TraceStarted { job }
+            | RuntimeEvent::TraceHop { job, .. }
+            | RuntimeEvent::TraceFinished { job, .. }
+            | RuntimeEvent::TraceFailed { job, .. } => *job,
+            _ => return,
+        };
+        let comparison = state
+            .comparison
+            .as_mut()
+            .filter(|comparison| comparison.job == Some(job));
+        // The comparison keeps its own hops. Whichever run ends last ends
+        // the tool, so it reads as running until both paths are known.
+        if let Some(comparison) = comparison {
+            let ended = match event {
+                RuntimeEvent::TraceHop { hop, .. } => {
+                    comparison.hops.push(hop);
+                    false
+                }
+                RuntimeEvent::TraceFinished { .. } => true,
+                RuntimeEvent::TraceFailed { error, .. } => {
+                    comparison.error = Some(error);
+                    true
+                }
+                _ => false,
+            };
+            if ended {
+                let job = comparison.job.take();
+                if state.common.job == job {
+                    state.common.job = None;
+                    if state.common.status == TaskStatus::Running {
+                        let summary = std::mem::take(&mut state.common.primary);
+                        finish_common(&mut state.common, summary);
+                    }
+                }
+            }
+            return;
+        }
+        let comparing = state
+            .comparison
+            .as_ref()
+            .and_then(|comparison| comparison.job);
+        match event {
+            RuntimeEvent::TraceStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::TraceHop { job, hop } if state.common.job == Some(job) => {
+                let primary = format!("hop {}: {}", hop.ttl, hop.address.as_deref().unwrap_or("*"));
+                let common = &mut state.common;
+                common.progress = hop
+                    .ttl
+                    .saturating_mul(100)
+                    .checked_div(state.request.max_hops.max(1))
+                    .unwrap_or(0)
+                    .min(99);
+                common.primary = primary.clone();
+                common.detail = hop
+                    .latency_ms
+                    .map_or_else(|| "timeout".into(), |latency| format!("{latency} ms"));
+                common.log.push(primary);
+                state.hops.push(hop);
+            }
+            RuntimeEvent::TraceFinished { job, hops } if state.common.job == Some(job) => {
+                let summary = format!("route completed in {hops} hops");
+                match comparing {
+                    Some(other) => {
+                        state.common.job = Some(other);
+                        state.common.primary = summary;
+                    }
+                    None => finish_common(&mut state.common, summary),
+                }
+            }
+            RuntimeEvent::TraceFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+                state.common.job = comparing;
+            }
+            _ => {}
+        }
+    }
+}
+
+struct PortScanModule;
+
+impl Module for PortScanModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::PortScan)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "端口扫描", "Port Scan")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "端口扫描：对目标的端口范围做 TCP 连接探测",
+            "Port scan: TCP connect probes across the port range",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        let diagnostics = &model.diagnostics;
+        if diagnostics.focus != DiagnosticFocus::Main || diagnostics.port_scan.open_ports.is_empty()
+        {
+            return Vec::new();
+        }
+        vec![hint(UP_DOWN, None, tr(model.language, "选择端口", "port"))]
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::PortScan]
+    }
+
+    fn feature(&self) -> Option<Feature> {
+        Some(Feature::PortScan)
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.port_scan;
+        match event {
+            RuntimeEvent::PortScanStarted { job, total } if state.common.job == Some(job) => {
+                state.total = total;
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::PortScanProgress {
+                job,
+                scanned,
+                total,
+            } if state.common.job == Some(job) => {
+                state.scanned = scanned;
+                state.total = total;
+                state.common.progress = scanned
+                    .saturating_mul(100)
+                    .checked_div(total.max(1))
+                    .unwrap_or(0)
+                    .min(100) as u8;
+                state.common.primary = format!("scanned {scanned} ports");
+                state.common.detail = format!("{} open", state.open_ports.len());
+            }
+            RuntimeEvent::PortScanOpen { job, result } if state.common.job == Some(job) => {
+                if let Err(index) = state
+                    .open_ports
+                    .binary_search_by_key(&result.port, |entry| entry.port)
+                {
+                    state.open_ports.insert(index, result.clone());
+                }
+                let line = format!("open: {} ({})", result.port, result.service);
+                state.common.primary = line.clone();
+                state.common.log.push(line);
+            }
+            RuntimeEvent::PortScanFinished {
+                job,
+                scanned,
+                total,
+                cancelled,
+            } if state.common.job == Some(job) => {
+                state.scanned = scanned;
+                state.total = total;
+                finish_common(
+                    &mut state.common,
+                    format!(
+                        "{} · {} open ports",
+                        if cancelled { "cancelled" } else { "completed" },
+                        state.open_ports.len()
+                    ),
+                );
+            }
+            RuntimeEvent::PortScanFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct LinkQualityModule;
+
+impl Module for LinkQualityModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::LinkQuality)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "链路质量", "Link Quality")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "链路质量：结合延迟、抖动、丢包与信号强度评分",
+            "Link quality: scores latency, jitter, loss and signal strength",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::LinkQuality]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.link_quality;
+        match event {
+            RuntimeEvent::LinkQualityStarted { job, snapshot } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.snapshot = Some(*snapshot);
+            }
+            RuntimeEvent::LinkQualitySample { job, sample } if state.common.job == Some(job) => {
+                state.common.progress = sample
+                    .sequence
+                    .saturating_mul(100)
+                    .checked_div(state.request.count.max(1))
+                    .unwrap_or(0)
+                    .min(99) as u8;
+                state.common.primary = format!("latency={:?} ms", sample.latency_ms);
+                state.common.detail = format!(
+                    "loss={:.1}% · rssi={:?}",
+                    sample.loss_percent, sample.rssi_dbm
+                );
+                if let Some(snapshot) = &state.snapshot {
+                    state.summary =
+                        Some(crate::link_quality::summary_from_sample(snapshot, &sample));
+                }
+                state.samples.push(sample);
+            }
+            RuntimeEvent::LinkQualityFinished { job, summary } if state.common.job == Some(job) => {
+                finish_common(
+                    &mut state.common,
+                    format!(
+                        "score {:.0}/100 · loss {:.1}%",
+                        summary.score, summary.loss_percent
+                    ),
+                );
+                state.summary = Some(summary);
+            }
+            RuntimeEvent::LinkQualityFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct PublicSpeedModule;
+
+impl Module for PublicSpeedModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::PublicSpeed)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "公网测速", "Public Speed")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "公网测速：下载/上传吞吐，并对比空闲与负载下的延迟（缓冲膨胀评级）",
+            "Public speed: download/upload throughput plus idle vs loaded latency (bufferbloat grade)",
+        )
+    }
+
+    fn hints(&self, model: &AppModel) -> Vec<KeyHint> {
+        export_hint(model)
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::PublicSpeed]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.public_speed;
+        match event {
+            RuntimeEvent::PublicSpeedStarted { job, server } if state.common.job == Some(job) => {
+                state.server = server;
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::PublicSpeedSample { job, sample } if state.common.job == Some(job) => {
+                // The upload phase runs for half the download budget after it.
+                let planned = state.request.max_duration_ms.max(1).saturating_mul(3) / 2;
+                state.common.progress = sample
+                    .elapsed_ms
+                    .saturating_mul(100)
+                    .checked_div(planned)
+                    .unwrap_or(0)
+                    .min(99) as u8;
+                state.common.primary = format!("{} B/s", sample.bytes_per_second);
+                state.common.detail = match sample.phase {
+                    SpeedPhase::Upload => format!("upload {} bytes", sample.bytes),
+                    SpeedPhase::Idle | SpeedPhase::Download => format!("{} bytes", sample.bytes),
+                };
+                state.samples.push(sample);
+            }
+            RuntimeEvent::PublicSpeedLatency { job, sample } if state.common.job == Some(job) => {
+                state.latency.push(sample);
+            }
+            RuntimeEvent::PublicSpeedFinished { job, summary } if state.common.job == Some(job) => {
+                let mut detail = format!(
+                    "average {} B/s · peak {} B/s",
+                    summary.average_bytes_per_second, summary.peak_bytes_per_second
+                );
+                if let Some(upload) = summary.upload_bytes_per_second {
+                    detail.push_str(&format!(" · upload {upload} B/s"));
+                }
+                if let Some(grade) = summary.bufferbloat.as_ref().and_then(|value| value.grade) {
+                    detail.push_str(&format!(" · bufferbloat {}", grade.label()));
+                }
+                finish_common(&mut state.common, detail);
+                state.summary = Some(summary);
+            }
+            RuntimeEvent::PublicSpeedFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct LanSpeedModule;
+
+impl Module for LanSpeedModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::LanSpeed)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "内网测速", "LAN Speed")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "内网测速：在两台 iptools 之间测试 TCP/UDP 吞吐",
+            "LAN speed: TCP/UDP throughput between two iptools peers",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::LanSpeed]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.lan_speed;
+        match event {
+            RuntimeEvent::LanSpeedStarted { job, endpoint } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.endpoint = endpoint;
+            }
+            RuntimeEvent::LanSpeedStatus { job, phase } if state.common.job == Some(job) => {
+                state.phase = Some(phase);
+            }
+            RuntimeEvent::LanSpeedSample { job, sample } if state.common.job == Some(job) => {
+                state.common.progress = sample
+                    .elapsed_ms
+                    .saturating_mul(100)
+                    .checked_div(state.request.duration_secs.saturating_mul(1_000).max(1))
+                    .unwrap_or(0)
+                    .min(99) as u8;
+                state.common.primary =
+                    format!("tx={} bps · rx={} bps", sample.tx_bps, sample.rx_bps);
+                state.common.detail = format!(
+                    "loss={:?} · jitter={:?}",
+                    sample.loss_percent, sample.jitter_ms
+                );
+                state.samples.push(sample);
+            }
+            RuntimeEvent::LanSpeedFinished { job, summary } if state.common.job == Some(job) => {
+                finish_common(
+                    &mut state.common,
+                    format!(
+                        "tx={} bytes · rx={} bytes",
+                        summary.tx_bytes, summary.rx_bytes
+                    ),
+                );
+                state.summary = Some(summary);
+                state.phase = None;
+            }
+            RuntimeEvent::LanSpeedFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct MtuModule;
+
+impl Module for MtuModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Mtu)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "巨帧 / MTU", "Jumbo / MTU")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "巨帧 / MTU：以置 DF 位的递增包长探测对端，找出可端到端承载的最大包",
+            "Jumbo / MTU: DF-set probes of growing size find the largest packet the peer receives",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Mtu]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let zh = model.language == Language::Zh;
+        let state = &mut model.diagnostics.mtu;
+        match event {
+            RuntimeEvent::MtuStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::MtuProbe { job, probe } if state.common.job == Some(job) => {
+                let outcome = match probe.outcome {
+                    crate::MtuOutcome::Carried { rtt_ms } => {
+                        format!("{} {rtt_ms} ms", if zh { "通过" } else { "carried in" })
+                    }
+                    crate::MtuOutcome::TooBig => if zh {
+                        "需要分片，被拒绝"
+                    } else {
+                        "too big, needs fragmentation"
+                    }
+                    .into(),
+                    crate::MtuOutcome::Lost => if zh { "无回应" } else { "no reply" }.into(),
+                };
+                let primary = format!("{} B: {outcome}", probe.size);
+                state.probes.push(probe);
+                let steps = crate::MtuSearch::expected_probes(state.request.max_mtu);
+                let common = &mut state.common;
+                common.progress = (state.probes.len() * 100 / steps).min(99) as u8;
+                common.primary = primary.clone();
+                common.log.push(primary);
+            }
+            RuntimeEvent::MtuFinished { job, summary } if state.common.job == Some(job) => {
+                let detail = match summary.path_mtu {
+                    Some(mtu) => format!(
+                        "{} {mtu} B ({} {})",
+                        if zh {
+                            "最大承载"
+                        } else {
+                            "largest packet carried:"
+                        },
+                        mtu - crate::MTU_HEADER_BYTES,
+                        if zh { "字节载荷" } else { "payload bytes" }
+                    ),
+                    None => if zh {
+                        "对端未回应任何探测"
+                    } else {
+                        "peer answered no probe"
+                    }
+                    .into(),
+                };
+                state.summary = Some(summary);
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::MtuFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct DscpModule;
+
+impl Module for DscpModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Dscp)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "QoS / DSCP 标记", "QoS / DSCP")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "QoS / DSCP：逐跳发送带 DSCP 标记的探测，检查标记是否在上游被清除或改写",
+            "QoS / DSCP: hop-by-hop probes carrying a DSCP mark show where upstream strips or rewrites it",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Dscp]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let zh = model.language == Language::Zh;
+        let state = &mut model.diagnostics.dscp;
+        match event {
+            RuntimeEvent::DscpStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::DscpHop { job, hop } if state.common.job == Some(job) => {
+                let observed = hop.observed.map_or_else(
+                    || if zh { "未知" } else { "unknown" }.to_string(),
+                    |dscp| match crate::dscp_name(dscp) {
+                        Some(name) => format!("{dscp} ({name})"),
+                        None => dscp.to_string(),
+                    },
+                );
+                let line = match (&hop.address, hop.rtt_ms) {
+                    (Some(address), Some(rtt_ms)) => {
+                        format!("{:>2}  {address}  {rtt_ms} ms  DSCP {observed}", hop.ttl)
+                    }
+                    _ => format!("{:>2}  *", hop.ttl),
+                };
+                let common = &mut state.common;
+                common.progress = (u32::from(hop.ttl) * 100
+                    / u32::from(state.request.max_hops.max(1)))
+                .min(99) as u8;
+                common.primary = line.clone();
+                common.log.push(line);
+                state.hops.push(hop);
+            }
+            RuntimeEvent::DscpFinished { job, summary } if state.common.job == Some(job) => {
+                let detail = match summary.verdict {
+                    crate::DscpVerdict::Preserved => if zh {
+                        "标记沿途保留"
+                    } else {
+                        "marking preserved along the path"
+                    }
+                    .to_string(),
+                    crate::DscpVerdict::Rewritten { ttl, observed } => {
+                        if zh {
+                            format!("第 {ttl} 跳起标记变为 {observed}")
+                        } else {
+                            format!("marking rewritten to {observed} from hop {ttl}")
+                        }
+                    }
+                    crate::DscpVerdict::Unknown => if zh {
+                        "没有任何一跳回报标记"
+                    } else {
+                        "no hop reported the marking"
+                    }
+                    .to_string(),
+                };
+                state.summary = Some(summary);
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::DscpFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct MulticastModule;
+
+impl Module for MulticastModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Multicast)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "组播测试", "Multicast")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "组播测试：加入组播组统计收包与到达抖动，或发送编号测试流",
+            "Multicast: join a group to count packets and arrival jitter, or send a numbered test stream",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Multicast]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.multicast;
+        let sending = state.request.mode == crate::MulticastMode::Send;
+        match event {
+            RuntimeEvent::MulticastStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::MulticastSample { job, sample } if state.common.job == Some(job) => {
+                state.common.progress = (sample.elapsed_ms
+                    / state.request.duration_secs.max(1).saturating_mul(10))
+                .min(99) as u8;
+                state.common.primary = multicast_line(&sample, sending);
+                state.samples.push(sample);
+            }
+            RuntimeEvent::MulticastFinished { job, summary } if state.common.job == Some(job) => {
+                finish_common(&mut state.common, multicast_line(&summary, sending));
+                state.summary = Some(summary);
+            }
+            RuntimeEvent::MulticastFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn multicast_line(sample: &crate::MulticastSample, sending: bool) -> String {
+    if sending {
+        return format!("sent {} packets", sample.packets);
+    }
+    let mut line = format!(
+        "{} packets from {} senders · {} lost",
+        sample.packets, sample.senders, sample.lost
+    );
+    if let Some(jitter) = sample.jitter_ms {
+        line.push_str(&format!(" · jitter {jitter:.1} ms"));
+    }
+    line
+}
+
+struct UpnpModule;
+
+impl Module for UpnpModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Upnp)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "UPnP 映射", "UPnP Mapping")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "UPnP 端口映射：列出、添加或删除路由器上的映射，并显示路由器报告的外网 IP",
+            "UPnP mapping: list, add or delete router port mappings and show the router's external IP",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Upnp]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.upnp;
+        match event {
+            RuntimeEvent::UpnpStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::UpnpGateway { job, gateway } if state.common.job == Some(job) => {
+                state.common.progress = 40;
+                state.common.primary = format!(
+                    "{} · external {}",
+                    gateway.name,
+                    gateway.external_ip.as_deref().unwrap_or("unknown")
+                );
+                state.gateway = Some(gateway);
+            }
+            RuntimeEvent::UpnpMappings { job, mappings } if state.common.job == Some(job) => {
+                state.common.progress = 90;
+                state.mappings = mappings;
+            }
+            RuntimeEvent::UpnpFinished { job, outcome } if state.common.job == Some(job) => {
+                let detail = match outcome {
+                    crate::UpnpOutcome::Listed => format!("{} mappings", state.mappings.len()),
+                    crate::UpnpOutcome::Added {
+                        protocol,
+                        external_port,
+                        internal_port,
+                    } => format!(
+                        "mapped {} {external_port} → {}:{internal_port}",
+                        protocol_name(protocol),
+                        state
+                            .gateway
+                            .as_ref()
+                            .map_or("this host", |gateway| gateway.local_ip.as_str())
+                    ),
+                    crate::UpnpOutcome::Deleted {
+                        protocol,
+                        external_port,
+                    } => format!("removed {} {external_port}", protocol_name(protocol)),
+                };
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::UpnpFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct NatModule;
+
+impl Module for NatModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Nat)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "NAT 类型", "NAT Type")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "NAT 类型：通过 STUN 获取公网映射地址，判断映射与过滤行为并测试回环",
+            "NAT type: STUN reflexive address, mapping and filtering behaviour, and hairpinning",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Nat]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.nat;
+        match event {
+            RuntimeEvent::NatStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::NatProbe { job, probe } if state.common.job == Some(job) => {
+                state.common.progress = (state.common.progress + 30).min(90);
+                state.common.primary = format!(
+                    "{} → {}",
+                    probe.server,
+                    probe.mapped.as_deref().unwrap_or("no answer")
+                );
+                state.probes.push(probe);
+            }
+            RuntimeEvent::NatFinished { job, summary } if state.common.job == Some(job) => {
+                let mut detail = summary.nat_type.label().to_string();
+                if let Some(mapped) = &summary.mapped {
+                    detail.push_str(&format!(" · mapped {mapped}"));
+                }
+                if let Some(hairpin) = summary.hairpin {
+                    detail.push_str(if hairpin {
+                        " · hairpin ok"
+                    } else {
+                        " · no hairpin"
+                    });
+                }
+                finish_common(&mut state.common, detail);
+                state.summary = Some(summary);
+            }
+            RuntimeEvent::NatFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct Ipv6Module;
+
+impl Module for Ipv6Module {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Ipv6)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "IPv6 就绪", "IPv6 Ready")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "IPv6 就绪：检查全局地址、默认路由、AAAA 解析、纯 IPv6 目标连通性与路径 MTU",
+            "IPv6 readiness: global address, default route, AAAA lookup, v6-only reachability and path MTU",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Ipv6]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.ipv6;
+        match event {
+            RuntimeEvent::Ipv6Started { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::Ipv6Result { job, result } if state.common.job == Some(job) => {
+                state.common.progress = (state.common.progress + 20).min(95);
+                state.common.primary = format!("{}: {}", result.check.label(), result.detail);
+                state.results.push(result);
+            }
+            RuntimeEvent::Ipv6Finished { job } if state.common.job == Some(job) => {
+                let passed = state
+                    .results
+                    .iter()
+                    .filter(|result| result.verdict == crate::Ipv6Verdict::Pass)
+                    .count();
+                let verdict = if crate::ipv6_ready(&state.results) {
+                    "IPv6 ready"
+                } else {
+                    "IPv6 not ready"
+                };
+                finish_common(
+                    &mut state.common,
+                    format!(
+                        "{verdict} · {passed}/{} checks passed",
+                        crate::Ipv6Check::ALL.len()
+                    ),
+                );
+            }
+            RuntimeEvent::Ipv6Failed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct DnsBenchModule;
+
+impl Module for DnsBenchModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::DnsBench)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "DNS 测速", "DNS Benchmark")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "DNS 测速：比较系统、公共解析器与 DoH/DoT 的缓存、未缓存和常见站点查询延迟及失败率",
+            "DNS benchmark: cached, uncached and common-site latency and failures over UDP, DoH and DoT",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::DnsBench]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.dns_bench;
+        match event {
+            RuntimeEvent::DnsBenchStarted { job, resolvers } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.resolvers = resolvers;
+            }
+            RuntimeEvent::DnsBenchScore { job, score } if state.common.job == Some(job) => {
+                state.scores.push(score);
+                state.common.progress =
+                    (state.scores.len() as u32 * 100 / state.resolvers.max(1)).min(99) as u8;
+                if let Some(score) = state.scores.last() {
+                    state.common.primary = format!(
+                        "{}: {}",
+                        score.resolver.label(),
+                        score
+                            .median_ms
+                            .map_or_else(|| "no answer".into(), |median| format!("{median:.1} ms"))
+                    );
+                }
+            }
+            RuntimeEvent::DnsBenchFinished { job } if state.common.job == Some(job) => {
+                crate::rank_dns_resolvers(&mut state.scores);
+                let mut detail = match state.scores.first() {
+                    Some(best) if best.reliable() => format!(
+                        "recommend {} · {:.1} ms median · {:.0}% failed",
+                        best.resolver.label(),
+                        best.median_ms.unwrap_or_default(),
+                        best.failure_rate() * 100.0
+                    ),
+                    _ => "no resolver answered reliably".into(),
+                };
+                for check in crate::encrypted_dns_checks(&state.scores) {
+                    detail.push_str(&match (check.works, check.overhead_ms) {
+                        (true, Some(overhead)) => {
+                            format!(" · {} ok ({overhead:+.1} ms)", check.transport.label())
+                        }
+                        (true, None) => format!(" · {} ok", check.transport.label()),
+                        (false, _) => format!(" · {} failed", check.transport.label()),
+                    });
+                }
+                if crate::udp_dns_blocked(&state.scores) {
+                    detail.push_str(" · UDP 53 blocked");
+                }
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::DnsBenchFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct DnsFilterModule;
+
+impl Module for DnsFilterModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::DnsFilter)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "DNS 过滤", "DNS Filtering")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "DNS 过滤检测：解析广告 / 追踪域名，判断系统解析器、路由器或运营商是否改写应答",
+            "DNS filtering: resolve ad / tracker names to see whether a Pi-hole, the router or the ISP rewrites answers",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::DnsFilter]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.dns_filter;
+        match event {
+            RuntimeEvent::DnsFilterStarted {
+                job,
+                upstreams,
+                domains,
+            } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.upstreams = upstreams;
+                state.domains = state.request.domains();
+                state.domains.truncate(domains as usize);
+            }
+            RuntimeEvent::DnsFilterResult { job, probe } if state.common.job == Some(job) => {
+                state.common.primary = format!(
+                    "{} via {}: {}",
+                    probe.domain,
+                    probe.upstream.label(),
+                    probe.detail
+                );
+                state.probes.push(probe);
+                let total = state.upstreams.len() * state.domains.len();
+                state.common.progress = (state.probes.len() * 100 / total.max(1)).min(99) as u8;
+            }
+            RuntimeEvent::DnsFilterFinished { job } if state.common.job == Some(job) => {
+                let detail = match crate::dns_filtering_upstream(&state.probes) {
+                    Some(upstream) => {
+                        let (blocked, tested) = crate::dns_filter_tally(&state.probes, upstream);
+                        let culprit = match upstream {
+                            crate::DnsFilterUpstream::System => {
+                                "filtered by the system resolver (Pi-hole / local blocklist)"
+                            }
+                            crate::DnsFilterUpstream::Gateway => {
+                                "filtered by the router or the ISP resolver behind it"
+                            }
+                            crate::DnsFilterUpstream::Public => {
+                                "port 53 intercepted on the path (ISP filter)"
+                            }
+                        };
+                        format!("{culprit} · {blocked}/{tested} names blocked")
+                    }
+                    None if !state.probes.iter().any(|probe| {
+                        probe.domain == crate::DNS_FILTER_CONTROL
+                            && probe.verdict == crate::DnsFilterVerdict::Clean
+                    }) =>
+                    {
+                        "no upstream answered the control name".into()
+                    }
+                    None => "no DNS filtering detected".into(),
+                };
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::DnsFilterFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct MailModule;
+
+impl Module for MailModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Mail)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "邮件服务", "Mail Services")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "邮件服务检查：连接 SMTP / IMAP / POP3 端口，读取欢迎语，尝试 STARTTLS，列出登录方式并校验证书",
+            "Mail services: connect to the SMTP / IMAP / POP3 ports, read the banner, try STARTTLS, list login mechanisms and verify the certificate",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Mail]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.mail;
+        match event {
+            RuntimeEvent::MailResult { job, check } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.common.primary = format!(
+                    "{} {}: {}",
+                    check.protocol.label(),
+                    check.port,
+                    check
+                        .banner
+                        .as_deref()
+                        .or(check.error.as_deref())
+                        .unwrap_or("-")
+                );
+                let order = |port: u16| {
+                    crate::MAIL_PORTS
+                        .iter()
+                        .position(|&(known, ..)| known == port)
+                };
+                let at = state
+                    .checks
+                    .partition_point(|known| order(known.port) < order(check.port));
+                state.checks.insert(at, check);
+                state.common.progress =
+                    (state.checks.len() * 100 / crate::MAIL_PORTS.len()).min(99) as u8;
+            }
+            RuntimeEvent::MailFinished { job } if state.common.job == Some(job) => {
+                let (open, attention) = crate::mail_tally(&state.checks);
+                let detail = if open == 0 {
+                    "no mail port answered".to_string()
+                } else {
+                    format!(
+                        "{open}/{} ports open · {attention} need attention",
+                        crate::MAIL_PORTS.len()
+                    )
+                };
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::MailFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct VoipModule;
+
+impl Module for VoipModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Voip)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "VoIP 通话质量", "VoIP Quality")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "VoIP 通话质量：按语音编码发送 RTP 测试流到回显端（可以是另一台 iptools），由时延、抖动和丢包算出 MOS",
+            "VoIP quality: send a codec-sized RTP stream to an echo reflector (another iptools works) and score MOS from latency, jitter and loss",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Voip]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.voip;
+        let codec = state.request.codec;
+        let reflecting = state.request.mode == crate::VoipMode::Reflect;
+        match event {
+            RuntimeEvent::VoipStarted { job, endpoint } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.common.primary = endpoint.clone();
+                state.endpoint = Some(endpoint);
+            }
+            RuntimeEvent::VoipSample { job, sample } if state.common.job == Some(job) => {
+                // A reflector runs until stopped.
+                if !reflecting {
+                    state.common.progress = (sample.elapsed_ms
+                        / state.request.duration_secs.max(1).saturating_mul(10))
+                    .min(99) as u8;
+                }
+                state.common.primary = voip_line(&sample, codec, reflecting);
+                state.samples.push(sample);
+            }
+            RuntimeEvent::VoipFinished { job, summary } if state.common.job == Some(job) => {
+                finish_common(&mut state.common, voip_line(&summary, codec, reflecting));
+                state.summary = Some(summary);
+            }
+            RuntimeEvent::VoipFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn voip_line(sample: &crate::VoipSample, codec: crate::VoipCodec, reflecting: bool) -> String {
+    if reflecting {
+        return format!("reflected {} packets", sample.sent);
+    }
+    let Some(score) = sample.score(codec) else {
+        return format!("sent {} packets · no echo yet", sample.sent);
+    };
+    format!(
+        "MOS {:.2} (R {:.0}) · RTT {:.1} ms · jitter {:.1} ms · loss {:.1}%",
+        score.mos,
+        score.r_factor,
+        sample.rtt_ms.unwrap_or_default(),
+        sample.jitter_ms.unwrap_or_default(),
+        sample.loss_percent()
+    )
+}
+
+struct GamingModule;
+
+impl Module for GamingModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Gaming)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "游戏延迟", "Gaming Latency")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "游戏延迟：向各地区游戏服务器信标发送 UDP 探测，按中位延迟排名并显示抖动；自定义项写 名称=主机:端口，udp:// 表示回显，否则测 TCP 连接",
+            "Gaming latency: UDP probes to game server region beacons, ranked by median with jitter; custom entries are name=host:port, udp:// for an echo, else TCP connect",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Gaming]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.gaming;
+        match event {
+            RuntimeEvent::GamingStarted { job, endpoints } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.endpoints = endpoints;
+            }
+            RuntimeEvent::GamingScore { job, score } if state.common.job == Some(job) => {
+                state.common.primary = format!(
+                    "{}: {}",
+                    score.endpoint.region,
+                    score
+                        .median_ms
+                        .map_or_else(|| "no answer".into(), |median| format!("{median:.1} ms"))
+                );
+                state.scores.push(score);
+                state.common.progress =
+                    (state.scores.len() as u32 * 100 / state.endpoints.max(1)).min(99) as u8;
+            }
+            RuntimeEvent::GamingFinished { job } if state.common.job == Some(job) => {
+                crate::rank_gaming_scores(&mut state.scores);
+                let detail = match state.scores.first() {
+                    Some(best) if best.reliable() => format!(
+                        "best {} · {:.1} ms median · {:.1} ms jitter · {:.0}% lost",
+                        best.endpoint.region,
+                        best.median_ms.unwrap_or_default(),
+                        best.jitter_ms.unwrap_or_default(),
+                        best.loss_rate() * 100.0
+                    ),
+                    _ => "no region answered reliably".into(),
+                };
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::GamingFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct CloudModule;
+
+impl Module for CloudModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Cloud)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "云服务可达性", "Cloud Reachability")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "云服务可达性：连接 AWS / GCP / Azure 各地区端点、主要 CDN 与任播 DNS，判断是本地网络还是某个云区域出了问题",
+            "Cloud reachability: connects to AWS / GCP / Azure regions, major CDNs and anycast DNS to tell a local fault from a regional outage",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Cloud]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.cloud;
+        match event {
+            RuntimeEvent::CloudStarted { job, endpoints } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+                state.endpoints = endpoints;
+            }
+            RuntimeEvent::CloudResult { job, probe } if state.common.job == Some(job) => {
+                state.common.primary = format!(
+                    "{} {}: {}",
+                    probe.endpoint.group.label(),
+                    probe.endpoint.name,
+                    probe
+                        .latency_ms
+                        .map_or_else(|| "unreachable".into(), |ms| format!("{ms:.0} ms"))
+                );
+                state.probes.push(probe);
+                state.common.progress =
+                    (state.probes.len() as u32 * 100 / state.endpoints.max(1)).min(99) as u8;
+            }
+            RuntimeEvent::CloudFinished { job } if state.common.job == Some(job) => {
+                let reachable = state
+                    .probes
+                    .iter()
+                    .filter(|probe| probe.reachable())
+                    .count();
+                let detail = match crate::cloud_verdict(&state.probes) {
+                    Some(crate::CloudVerdict::AllReachable) => {
+                        format!("all {reachable} endpoints reachable")
+                    }
+                    Some(crate::CloudVerdict::ProviderOutage(failed)) => {
+                        format!("unreachable: {} · likely on their side", failed.join(", "))
+                    }
+                    Some(crate::CloudVerdict::LocalProblem) => format!(
+                        "{reachable}/{} reachable · likely your own network",
+                        state.probes.len()
+                    ),
+                    None => "no endpoint probed".into(),
+                };
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::CloudFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct WifiModule;
+
+impl Module for WifiModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::Wifi)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "Wi-Fi 监测", "Wi-Fi Monitor")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "Wi-Fi 监测：每隔几秒记录信号、发送速率、信道和 BSSID 并绘制曲线；在接入点之间漫游时记入事件日志，正在 Ping 时也在 Ping 输出里标出",
+            "Wi-Fi monitor: samples signal, tx rate, channel and BSSID every few seconds and charts them; roams between access points go to the event log and, while a ping runs, into its output",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::Wifi]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let job = model.diagnostics.wifi.common.job;
+        match event {
+            RuntimeEvent::WifiSample {
+                job: event_job,
+                sample,
+            } if job == Some(event_job) => {
+                model.record_wifi_sample(sample);
+            }
+            RuntimeEvent::WifiFinished { job: event_job } if job == Some(event_job) => {
+                let state = &mut model.diagnostics.wifi;
+                let detail = format!(
+                    "{} samples · {} roams",
+                    state.samples.len(),
+                    state.roams.len()
+                );
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::WifiFailed {
+                job: event_job,
+                error,
+            } if job == Some(event_job) => {
+                fail_common(&mut model.diagnostics.wifi.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+struct AbTestModule;
+
+impl Module for AbTestModule {
+    fn id(&self) -> ModuleId {
+        ModuleId::Tool(DiagnosticTool::AbTest)
+    }
+
+    fn title(&self, language: Language) -> &'static str {
+        tr(language, "A/B 网卡对比", "A/B Interfaces")
+    }
+
+    fn help(&self, language: Language) -> &'static str {
+        tr(
+            language,
+            "A/B 网卡对比：分别从两个网卡（如 Wi-Fi 与有线、VPN 开与关）绑定源地址跑同一组 Ping、下载测速和 DNS 解析，逐项对比并给出结论",
+            "A/B interfaces: runs the same pings, download and DNS lookups bound to each of two interfaces (Wi-Fi vs Ethernet, VPN on vs off) and compares them metric by metric",
+        )
+    }
+
+    fn jobs(&self) -> &'static [ToolKind] {
+        &[ToolKind::AbTest]
+    }
+
+    fn update(&self, model: &mut AppModel, event: RuntimeEvent) {
+        let state = &mut model.diagnostics.ab_test;
+        match event {
+            RuntimeEvent::AbTestStarted { job } if state.common.job == Some(job) => {
+                state.common.status = TaskStatus::Running;
+            }
+            RuntimeEvent::AbTestLeg { job, leg } if state.common.job == Some(job) => {
+                state.common.primary = format!(
+                    "{} {}: {}",
+                    leg.side.label(),
+                    leg.via,
+                    leg.latency_ms
+                        .map_or_else(|| "no reply".into(), |ms| format!("{ms:.1} ms"))
+                );
+                state.legs.retain(|kept| kept.side != leg.side);
+                state.legs.push(leg);
+                state.common.progress = (state.legs.len() * 50).min(99) as u8;
+            }
+            RuntimeEvent::AbTestFinished { job } if state.common.job == Some(job) => {
+                let detail = match (state.leg(crate::AbSide::A), state.leg(crate::AbSide::B)) {
+                    (Some(a), Some(b)) => {
+                        let verdict = crate::ab_verdict(a, b);
+                        match verdict.winner {
+                            Some(side) => {
+                                let (leg, wins) = match side {
+                                    crate::AbSide::A => (a, verdict.wins_a),
+                                    crate::AbSide::B => (b, verdict.wins_b),
+                                };
+                                format!(
+                                    "{} ({}) better on {wins} of {} metrics",
+                                    side.label(),
+                                    leg.via,
+                                    crate::AbMetric::ALL.len()
+                                )
+                            }
+                            None => "no clear winner".into(),
+                        }
+                    }
+                    _ => "incomplete comparison".into(),
+                };
+                finish_common(&mut state.common, detail);
+            }
+            RuntimeEvent::AbTestFailed { job, error } if state.common.job == Some(job) => {
+                fail_common(&mut state.common, error);
+            }
+            _ => {}
+        }
+    }
+}
+
+const fn protocol_name(protocol: crate::LanProtocol) -> &'static str {
+    match protocol {
+        crate::LanProtocol::Tcp => "TCP",
+        crate::LanProtocol::Udp => "UDP",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_page_tool_and_job_kind_is_registered_once() {
+        for page in Page::ALL {
+            assert_eq!(module(ModuleId::Page(page)).id(), ModuleId::Page(page));
+        }
+        for tool in DiagnosticTool::ALL {
+            assert_eq!(module(ModuleId::Tool(tool)).id(), ModuleId::Tool(tool));
+            assert!(module_for_job(ToolKind::from(tool)).is_some());
+        }
+        for tool in [
+            ToolKind::Dashboard,
+            ToolKind::Watch,
+            ToolKind::Adapters,
+            ToolKind::AdapterEdit,
+            ToolKind::Traffic,
+            ToolKind::Scanner,
+            ToolKind::Cve,
+        ] {
+            let owners = MODULES
+                .iter()
+                .filter(|module| module.jobs().contains(&tool))
+                .count();
+            assert_eq!(owners, 1, "{tool:?}");
+        }
+    }
+
+    #[test]
+    fn diagnostics_hints_follow_the_focus() {
+        let mut model = AppModel::default();
+        let labels = |model: &AppModel| {
+            module(ModuleId::Page(Page::Diagnostics))
+                .hints(model)
+                .iter()
+                .map(|hint| hint.label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(&model), ["open the tools"]);
+
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Menu;
+        assert_eq!(labels(&model), ["tool", "results", "leave"]);
+
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        assert_eq!(labels(&model), ["start", "parameters", "leave"]);
+
+        model.diagnostics.focus = DiagnosticFocus::Config;
+        model.diagnostics.ping.config_selected = 0;
+        assert_eq!(labels(&model), ["parameter", "history", "tools", "leave"]);
+        model.diagnostics.ping.config_selected = 1;
+        assert_eq!(labels(&model), ["parameter", "adjust", "tools", "leave"]);
+        model.diagnostics.history_open = true;
+        assert_eq!(labels(&model), ["choose", "use", "close"]);
+    }
+}
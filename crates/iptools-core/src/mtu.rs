@@ -0,0 +1,84 @@
+//! Bisection over IP packet sizes for the jumbo-frame / MTU probe.
+
+/// IPv4 guarantees every host accepts 576-byte datagrams; the search starts
+/// there so a silent peer is told apart from an MTU problem.
+pub const MTU_FLOOR: u16 = 576;
+
+/// IPv4 + ICMP header bytes added to every echo payload.
+pub const MTU_HEADER_BYTES: u16 = 28;
+
+/// Tracks the largest size that made the round trip and the smallest one that
+/// did not. Lost and refused probes both count as "not carried".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MtuSearch {
+    max: u16,
+    carried: Option<u16>,
+    refused: Option<u16>,
+}
+
+impl MtuSearch {
+    pub fn new(max_mtu: u16) -> Self {
+        Self {
+            max: max_mtu.max(MTU_FLOOR),
+            carried: None,
+            refused: None,
+        }
+    }
+
+    /// Next packet size to probe: the floor, then the ceiling, then the
+    /// midpoint of the open interval. `None` once the answer is known.
+    pub fn next(&self) -> Option<u16> {
+        let Some(low) = self.carried else {
+            return self.refused.is_none().then_some(MTU_FLOOR);
+        };
+        match self.refused {
+            None => (low < self.max).then_some(self.max),
+            Some(high) => (high - low > 1).then(|| low + (high - low) / 2),
+        }
+    }
+
+    pub fn record(&mut self, size: u16, carried: bool) {
+        if carried {
+            self.carried = Some(self.carried.map_or(size, |low| low.max(size)));
+        } else {
+            self.refused = Some(self.refused.map_or(size, |high| high.min(size)));
+        }
+    }
+
+    /// Largest packet carried so far.
+    pub const fn path_mtu(&self) -> Option<u16> {
+        self.carried
+    }
+
+    /// Upper bound on probes for a full search up to `max_mtu`.
+    pub fn expected_probes(max_mtu: u16) -> usize {
+        let span = max_mtu.saturating_sub(MTU_FLOOR).max(1);
+        (u16::BITS - span.leading_zeros() + 2) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(max_mtu: u16, path_mtu: u16) -> (Option<u16>, usize) {
+        let mut search = MtuSearch::new(max_mtu);
+        let mut probes = 0;
+        while let Some(size) = search.next() {
+            probes += 1;
+            search.record(size, size <= path_mtu);
+        }
+        (search.path_mtu(), probes)
+    }
+
+    #[test]
+    fn bisection_finds_the_exact_path_mtu_within_the_expected_budget() {
+        for path_mtu in [576, 1_400, 1_500, 4_470, 8_999, 9_000] {
+            let (found, probes) = run(9_000, path_mtu);
+            assert_eq!(found, Some(path_mtu));
+            assert!(probes <= MtuSearch::expected_probes(9_000), "{path_mtu}");
+        }
+        assert_eq!(run(9_000, 12_000), (Some(9_000), 2));
+        assert_eq!(run(9_000, 500), (None, 1));
+    }
+}
@@ -0,0 +1,116 @@
+//! Test-stream framing and receive statistics for the multicast tester.
+
+use std::collections::BTreeMap;
+
+use crate::MulticastSample;
+
+/// Prefix of every packet the tester sends, followed by a big-endian sequence
+/// number. Other traffic on the group is counted but has no sequence.
+pub const MULTICAST_MAGIC: &[u8; 8] = b"IPTOOLMC";
+
+/// Size of one test-stream datagram.
+pub const MULTICAST_PACKET_BYTES: usize = 64;
+
+pub fn encode_multicast_packet(sequence: u32) -> Vec<u8> {
+    let mut packet = vec![0u8; MULTICAST_PACKET_BYTES];
+    packet[..8].copy_from_slice(MULTICAST_MAGIC);
+    packet[8..12].copy_from_slice(&sequence.to_be_bytes());
+    packet
+}
+
+pub fn decode_multicast_packet(payload: &[u8]) -> Option<u32> {
+    let sequence = payload.strip_prefix(MULTICAST_MAGIC)?.get(..4)?;
+    Some(u32::from_be_bytes(sequence.try_into().ok()?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StreamRange {
+    first: u32,
+    last: u32,
+    received: u64,
+}
+
+/// Counts arrivals, distinct senders, sequence gaps per sender and the
+/// smoothed variation of the inter-arrival interval.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MulticastStats {
+    packets: u64,
+    bytes: u64,
+    senders: BTreeMap<String, Option<StreamRange>>,
+    last_arrival_ms: Option<f64>,
+    last_interval_ms: Option<f64>,
+    jitter_ms: Option<f64>,
+}
+
+impl MulticastStats {
+    pub fn record(&mut self, arrival_ms: f64, sender: &str, payload: &[u8]) {
+        self.packets += 1;
+        self.bytes += payload.len() as u64;
+        if let Some(last) = self.last_arrival_ms {
+            let interval = arrival_ms - last;
+            if let Some(previous) = self.last_interval_ms {
+                let deviation = (interval - previous).abs();
+                let jitter = self.jitter_ms.unwrap_or(0.0);
+                self.jitter_ms = Some(jitter + (deviation - jitter) / 16.0);
+            }
+            self.last_interval_ms = Some(interval);
+        }
+        self.last_arrival_ms = Some(arrival_ms);
+
+        let range = self.senders.entry(sender.to_string()).or_default();
+        if let Some(sequence) = decode_multicast_packet(payload) {
+            let current = range.get_or_insert(StreamRange {
+                first: sequence,
+                last: sequence,
+                received: 0,
+            });
+            current.first = current.first.min(sequence);
+            current.last = current.last.max(sequence);
+            current.received += 1;
+        }
+    }
+
+    /// Count one sent packet (send mode has no arrivals to time).
+    pub fn record_sent(&mut self, bytes: usize) {
+        self.packets += 1;
+        self.bytes += bytes as u64;
+    }
+
+    pub fn sample(&self, elapsed_ms: u64) -> MulticastSample {
+        MulticastSample {
+            elapsed_ms,
+            packets: self.packets,
+            bytes: self.bytes,
+            senders: self.senders.len() as u32,
+            lost: self
+                .senders
+                .values()
+                .flatten()
+                .map(|range| {
+                    (u64::from(range.last - range.first) + 1).saturating_sub(range.received)
+                })
+                .sum(),
+            jitter_ms: self.jitter_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_gaps_senders_and_jitter_are_tracked() {
+        let mut stats = MulticastStats::default();
+        for (arrival, sequence) in [(0.0, 1), (50.0, 2), (100.0, 3), (180.0, 5), (200.0, 6)] {
+            stats.record(arrival, "192.0.2.10", &encode_multicast_packet(sequence));
+        }
+        stats.record(210.0, "192.0.2.20", b"not a test packet");
+        let sample = stats.sample(1_000);
+        assert_eq!(sample.packets, 6);
+        assert_eq!(sample.senders, 2);
+        assert_eq!(sample.lost, 1);
+        assert!(sample.jitter_ms.is_some_and(|jitter| jitter > 0.0));
+        assert_eq!(decode_multicast_packet(b"IPTOOLMC"), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! NAT classification from STUN binding results.
+
+use crate::{NatFiltering, NatType};
+
+impl NatType {
+    /// Classify from the mapped addresses two servers reported for the same
+    /// local socket. Addresses are `ip:port`; `local_ips` are this host's own
+    /// addresses, used to recognise a public address with no NAT in front.
+    pub fn classify(
+        local_ips: &[&str],
+        first: Option<&str>,
+        second: Option<&str>,
+        filtering: NatFiltering,
+    ) -> Self {
+        let Some(first) = first else {
+            return Self::Blocked;
+        };
+        if local_ips.contains(&host_part(first)) {
+            return Self::Open;
+        }
+        match second {
+            None => Self::Unknown,
+            Some(second) if second != first => Self::Symmetric,
+            Some(_) => match filtering {
+                NatFiltering::EndpointIndependent => Self::FullCone,
+                NatFiltering::AddressDependent => Self::RestrictedCone,
+                NatFiltering::AddressAndPortDependent => Self::PortRestrictedCone,
+                NatFiltering::Untested => Self::Cone,
+            },
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Open => "open internet",
+            Self::FullCone => "full cone",
+            Self::RestrictedCone => "restricted cone",
+            Self::PortRestrictedCone => "port-restricted cone",
+            Self::Cone => "cone",
+            Self::Symmetric => "symmetric",
+            Self::Blocked => "UDP blocked",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+fn host_part(address: &str) -> &str {
+    address
+        .rsplit_once(':')
+        .map_or(address, |(host, _)| host)
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_and_filtering_results_pick_the_nat_type() {
+        let local = ["192.168.1.20"];
+        let mapped = Some("203.0.113.7:40001");
+        assert_eq!(
+            NatType::classify(&local, None, None, NatFiltering::Untested),
+            NatType::Blocked
+        );
+        assert_eq!(
+            NatType::classify(
+                &["203.0.113.7"],
+                mapped,
+                mapped,
+                NatFiltering::EndpointIndependent
+            ),
+            NatType::Open
+        );
+        assert_eq!(
+            NatType::classify(
+                &local,
+                mapped,
+                Some("203.0.113.7:40002"),
+                NatFiltering::Untested
+            ),
+            NatType::Symmetric
+        );
+        assert_eq!(
+            NatType::classify(&local, mapped, mapped, NatFiltering::AddressDependent),
+            NatType::RestrictedCone
+        );
+        assert_eq!(
+            NatType::classify(&local, mapped, mapped, NatFiltering::Untested),
+            NatType::Cone
+        );
+        assert_eq!(
+            NatType::classify(&local, mapped, None, NatFiltering::Untested),
+            NatType::Unknown
+        );
+        assert_eq!(
+            NatType::classify(
+                &["2001:db8::20"],
+                Some("[2001:db8::20]:40001"),
+                None,
+                NatFiltering::Untested
+            ),
+            NatType::Open
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Network change log.
+//!
+//! Successive adapter enumerations and dashboard snapshots are compared
+//! against the last observed state; every difference that matters after a
+//! connectivity blip (an interface going up or down, an address or default
+//! gateway change, a new public IP) becomes a timestamped [`NetworkEvent`].
+//! The first observation of each source only sets the baseline, so starting
+//! the application does not log every interface as new.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AdapterInfo, DashboardSnapshot};
+
+/// Events kept in the persisted log; the oldest are dropped first.
+pub const EVENT_LOG_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkEventKind {
+    InterfaceAdded,
+    InterfaceRemoved,
+    InterfaceUp,
+    InterfaceDown,
+    AddressChanged,
+    GatewayChanged,
+    PublicIpChanged,
+    /// The Wi-Fi monitor saw the adapter move to another access point.
+    WifiRoamed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkEvent {
+    /// Local wall-clock time, `YYYY-MM-DD HH:MM:SS`.
+    pub at: String,
+    pub kind: NetworkEventKind,
+    /// Interface name, or empty for host-wide changes such as the public IP.
+    pub subject: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// What the previous adapter enumeration showed for one interface. IPv6 is
+/// left out: temporary privacy addresses rotate on their own and would
+/// drown the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InterfaceSeen {
+    guid: String,
+    name: String,
+    up: bool,
+    ipv4: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct EventLogState {
+    /// Oldest first.
+    pub entries: Vec<NetworkEvent>,
+    /// Index into the newest-first view.
+    pub selected: usize,
+    #[serde(skip)]
+    interfaces: Option<Vec<InterfaceSeen>>,
+    #[serde(skip)]
+    gateway: Option<Option<String>>,
+    #[serde(skip)]
+    public_ip: Option<String>,
+    /// Events logged since start, including ones since dropped.
+    #[serde(skip)]
+    logged: u64,
+}
+
+impl EventLogState {
+    /// Compare an adapter enumeration with the previous one. Returns whether
+    /// anything was logged.
+    pub fn observe_adapters(&mut self, adapters: &[AdapterInfo], at: &str) -> bool {
+        let current = adapters
+            .iter()
+            .map(|adapter| InterfaceSeen {
+                guid: adapter.guid.clone(),
+                name: adapter.name.clone(),
+                up: adapter.is_up(),
+                ipv4: adapter.ipv4.clone(),
+            })
+            .collect::<Vec<_>>();
+        let Some(previous) = self.interfaces.replace(current.clone()) else {
+            return false;
+        };
+        let same = |left: &InterfaceSeen, right: &InterfaceSeen| {
+            if left.guid.is_empty() || right.guid.is_empty() {
+                left.name == right.name
+            } else {
+                left.guid == right.guid
+            }
+        };
+        let mut events = Vec::new();
+        for old in &previous {
+            if !current.iter().any(|new| same(old, new)) {
+                events.push((NetworkEventKind::InterfaceRemoved, old, address(old), ""));
+            }
+        }
+        for new in &current {
+            let Some(old) = previous.iter().find(|old| same(old, new)) else {
+                events.push((NetworkEventKind::InterfaceAdded, new, "", address(new)));
+                continue;
+            };
+            if old.up != new.up {
+                let kind = if new.up {
+                    NetworkEventKind::InterfaceUp
+                } else {
+                    NetworkEventKind::InterfaceDown
+                };
+                events.push((kind, new, address(old), address(new)));
+            } else if address(old) != address(new) {
+                events.push((
+                    NetworkEventKind::AddressChanged,
+                    new,
+                    address(old),
+                    address(new),
+                ));
+            }
+        }
+        let logged = !events.is_empty();
+        for (kind, interface, from, to) in events {
+            self.push(NetworkEvent {
+                at: at.to_string(),
+                kind,
+                subject: interface.name.clone(),
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+        logged
+    }
+
+    /// Compare the gateway and public IP of a dashboard snapshot with the
+    /// last known values. A failed public IP lookup keeps the old value.
+    pub fn observe_dashboard(&mut self, snapshot: &DashboardSnapshot) -> bool {
+        let mut logged = false;
+        let gateway = snapshot
+            .active_interface
+            .as_ref()
+            .and_then(|interface| interface.gateway.clone());
+        if let Some(previous) = self.gateway.replace(gateway.clone())
+            && previous != gateway
+        {
+            self.push(NetworkEvent {
+                at: snapshot.observed_at.clone(),
+                kind: NetworkEventKind::GatewayChanged,
+                subject: snapshot
+                    .active_interface
+                    .as_ref()
+                    .map(|interface| interface.name.clone())
+                    .unwrap_or_default(),
+                from: previous.unwrap_or_default(),
+                to: gateway.unwrap_or_default(),
+            });
+            logged = true;
+        }
+        if let Some(info) = &snapshot.public_info
+            && !info.ip.is_empty()
+            && let Some(previous) = self.public_ip.replace(info.ip.clone())
+            && previous != info.ip
+        {
+            self.push(NetworkEvent {
+                at: snapshot.observed_at.clone(),
+                kind: NetworkEventKind::PublicIpChanged,
+                subject: String::new(),
+                from: previous,
+                to: info.ip.clone(),
+            });
+            logged = true;
+        }
+        logged
+    }
+
+    /// Log a roam seen by the Wi-Fi monitor on `adapter`.
+    pub fn observe_roam(&mut self, adapter: &str, roam: &crate::WifiRoam) -> bool {
+        self.push(NetworkEvent {
+            at: roam.at.clone(),
+            kind: NetworkEventKind::WifiRoamed,
+            subject: adapter.to_string(),
+            from: format!("{} (ch {})", roam.from_bssid, roam.from_channel),
+            to: format!("{} (ch {})", roam.to_bssid, roam.to_channel),
+        });
+        true
+    }
+
+    fn push(&mut self, event: NetworkEvent) {
+        self.logged += 1;
+        self.entries.push(event);
+        if self.entries.len() > EVENT_LOG_LIMIT {
+            let excess = self.entries.len() - EVENT_LOG_LIMIT;
+            self.entries.drain(..excess);
+        }
+        // Keep the highlighted row on the same event as new ones arrive on top.
+        if self.selected > 0 {
+            self.selected = (self.selected + 1).min(self.entries.len() - 1);
+        }
+    }
+
+    /// Entries logged since the count was `logged` (see [`Self::logged`]),
+    /// as far as they are still kept.
+    pub fn since(&self, logged: u64) -> &[NetworkEvent] {
+        let new = usize::try_from(self.logged.saturating_sub(logged)).unwrap_or(usize::MAX);
+        &self.entries[self.entries.len().saturating_sub(new)..]
+    }
+
+    /// Number of events logged this session, for [`Self::since`].
+    pub const fn logged(&self) -> u64 {
+        self.logged
+    }
+
+    /// Entries newest first, as displayed.
+    pub fn newest_first(&self) -> impl Iterator<Item = &NetworkEvent> {
+        self.entries.iter().rev()
+    }
+}
+
+fn address(interface: &InterfaceSeen) -> &str {
+    match interface.ipv4.as_str() {
+        "—" => "",
+        ip => ip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DashboardInterface, PublicIpInfo};
+
+    fn adapter(name: &str, ipv4: &str, status: &str) -> AdapterInfo {
+        AdapterInfo {
+            name: name.into(),
+            guid: format!("{{{name}}}"),
+            ipv4: ipv4.into(),
+            status: status.into(),
+            ..AdapterInfo::default()
+        }
+    }
+
+    #[test]
+    fn adapter_changes_are_logged_after_the_baseline() {
+        let mut log = EventLogState::default();
+        let first = [
+            adapter("eth0", "192.168.1.20", "up"),
+            adapter("wlan0", "192.168.1.21", "up"),
+        ];
+        assert!(!log.observe_adapters(&first, "10:00"));
+        assert!(!log.observe_adapters(&first, "10:01"));
+
+        let second = [
+            adapter("eth0", "192.168.1.35", "up"),
+            adapter("wlan0", "—", "disconnected"),
+            adapter("usb0", "10.0.0.2", "up"),
+        ];
+        assert!(log.observe_adapters(&second, "10:02"));
+        let kinds = log
+            .entries
+            .iter()
+            .map(|event| (event.kind, event.subject.as_str(), event.to.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (NetworkEventKind::AddressChanged, "eth0", "192.168.1.35"),
+                (NetworkEventKind::InterfaceDown, "wlan0", ""),
+                (NetworkEventKind::InterfaceAdded, "usb0", "10.0.0.2"),
+            ]
+        );
+        assert_eq!(log.entries[1].from, "192.168.1.21");
+
+        assert!(log.observe_adapters(&second[..2], "10:03"));
+        assert_eq!(
+            log.newest_first().next().map(|event| event.kind),
+            Some(NetworkEventKind::InterfaceRemoved)
+        );
+    }
+
+    #[test]
+    fn gateway_and_public_ip_changes_survive_failed_lookups() {
+        let snapshot = |gateway: &str, public_ip: Option<&str>| DashboardSnapshot {
+            observed_at: "2026-01-15 10:24:00".into(),
+            active_interface: Some(DashboardInterface {
+                name: "eth0".into(),
+                gateway: Some(gateway.into()),
+                ..DashboardInterface::default()
+            }),
+            public_info: public_ip.map(|ip| PublicIpInfo {
+                ip: ip.into(),
+                ..PublicIpInfo::default()
+            }),
+            ..DashboardSnapshot::default()
+        };
+        let mut log = EventLogState::default();
+        assert!(!log.observe_dashboard(&snapshot("192.168.1.1", Some("203.0.113.7"))));
+        assert!(!log.observe_dashboard(&snapshot("192.168.1.1", None)));
+        assert!(log.observe_dashboard(&snapshot("192.168.1.254", Some("203.0.113.7"))));
+        assert!(log.observe_dashboard(&snapshot("192.168.1.254", Some("198.51.100.9"))));
+        let logged = log
+            .entries
+            .iter()
+            .map(|event| (event.kind, event.from.as_str(), event.to.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            logged,
+            [
+                (
+                    NetworkEventKind::GatewayChanged,
+                    "192.168.1.1",
+                    "192.168.1.254"
+                ),
+                (
+                    NetworkEventKind::PublicIpChanged,
+                    "203.0.113.7",
+                    "198.51.100.9"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn log_is_capped_at_the_oldest_end() {
+        let mut log = EventLogState::default();
+        for index in 0..EVENT_LOG_LIMIT + 3 {
+            log.push(NetworkEvent {
+                at: index.to_string(),
+                kind: NetworkEventKind::InterfaceUp,
+                subject: "eth0".into(),
+                from: String::new(),
+                to: String::new(),
+            });
+        }
+        assert_eq!(log.entries.len(), EVENT_LOG_LIMIT);
+        assert_eq!(log.entries[0].at, "3");
+    }
+}
@@ -0,0 +1,208 @@
+//! Interface text and colours the user keeps in files beside the config.
+//!
+//! The built-in translations and themes stay compiled in; a locale file
+//! replaces individual strings and a theme file individual palette colours
+//! on top of them. The platform layer reads and watches the files and hands
+//! over what it parsed with [`crate::Message::OverridesLoaded`]; the model
+//! only holds it, so the next frame shows an edit without a restart.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Language;
+
+/// Replacement text per language, keyed by the English text it replaces.
+/// English is the key in both tables because it is what every string the
+/// interface draws has in common, whichever language is showing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LocaleOverrides {
+    #[serde(rename = "en-US", default)]
+    pub en: BTreeMap<String, String>,
+    #[serde(rename = "zh-CN", default)]
+    pub zh: BTreeMap<String, String>,
+}
+
+impl LocaleOverrides {
+    pub fn texts(&self, language: Language) -> &BTreeMap<String, String> {
+        match language {
+            Language::En => &self.en,
+            Language::Zh => &self.zh,
+        }
+    }
+
+    /// The text to show for `english` in `language`, when the file has one.
+    pub fn get(&self, language: Language, english: &str) -> Option<&str> {
+        self.texts(language).get(english).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.en.is_empty() && self.zh.is_empty()
+    }
+
+    /// What a locale file lacks or carries in vain for `language`, given the
+    /// English text the interface draws. en-US is the reference: a string
+    /// it replaces that another language leaves alone is missing there, as
+    /// is an entry left empty; a key matching no interface text is unused.
+    pub fn gaps(&self, language: Language, known: &BTreeSet<String>) -> LocaleGaps {
+        let texts = self.texts(language);
+        let mut missing = texts
+            .iter()
+            .filter(|(_, text)| text.trim().is_empty())
+            .map(|(english, _)| english.clone())
+            .collect::<Vec<_>>();
+        if language != Language::En {
+            missing.extend(
+                self.en
+                    .keys()
+                    .filter(|english| !texts.contains_key(*english))
+                    .cloned(),
+            );
+            missing.sort();
+        }
+        LocaleGaps {
+            missing,
+            unused: texts
+                .keys()
+                .filter(|english| !known.contains(*english))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Keys of one language's table that need the translator's attention.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct LocaleGaps {
+    pub missing: Vec<String>,
+    pub unused: Vec<String>,
+}
+
+impl LocaleGaps {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unused.is_empty()
+    }
+}
+
+/// [`LocaleGaps`] of both languages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct LocaleAudit {
+    pub en: LocaleGaps,
+    pub zh: LocaleGaps,
+}
+
+impl LocaleAudit {
+    pub fn get(&self, language: Language) -> &LocaleGaps {
+        match language {
+            Language::En => &self.en,
+            Language::Zh => &self.zh,
+        }
+    }
+}
+
+/// A `#rrggbb` colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl TryFrom<String> for Rgb {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("\"{value}\" is not a #rrggbb colour");
+        let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+        if hex.len() != 6 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).map_err(|_| invalid());
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl From<Rgb> for String {
+    fn from(Rgb(red, green, blue): Rgb) -> Self {
+        format!("#{red:02x}{green:02x}{blue:02x}")
+    }
+}
+
+/// Palette colours that replace the selected theme's; the names are the
+/// roles the pages draw with, so one file recolours every theme alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeOverrides {
+    pub background: Option<Rgb>,
+    pub foreground: Option<Rgb>,
+    /// Highlighted rows and the selected tab.
+    pub selection: Option<Rgb>,
+    pub muted: Option<Rgb>,
+    pub subtle: Option<Rgb>,
+    /// Completion text ahead of the cursor.
+    pub ghost: Option<Rgb>,
+    pub green: Option<Rgb>,
+    /// Also stands in for blue.
+    pub cyan: Option<Rgb>,
+    pub yellow: Option<Rgb>,
+    pub red: Option<Rgb>,
+}
+
+impl ThemeOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What the override files held when they were last read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Overrides {
+    pub locale: LocaleOverrides,
+    pub theme: ThemeOverrides,
+    /// Gaps of the locale file against the interface, found by the platform
+    /// layer, which can draw every screen to see what text there is.
+    pub audit: LocaleAudit,
+    /// Files that could not be used, one line each with the file name; the
+    /// previous content of such a file stays in effect.
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colours_read_as_hex_and_write_back_the_same() {
+        assert_eq!(Rgb::try_from("#1e1E2e".to_string()), Ok(Rgb(30, 30, 46)));
+        assert_eq!(String::from(Rgb(30, 30, 46)), "#1e1e2e");
+        for invalid in ["1e1e2e", "#1e1e2", "#1e1e2g", "#+1e1e2"] {
+            assert!(Rgb::try_from(invalid.to_string()).is_err(), "{invalid}");
+        }
+        assert!(ThemeOverrides::default().is_empty());
+    }
+
+    #[test]
+    fn locale_text_is_looked_up_by_its_english_source() {
+        let mut locale = LocaleOverrides::default();
+        locale.zh.insert("Dashboard".into(), "总览".into());
+        assert_eq!(locale.get(Language::Zh, "Dashboard"), Some("总览"));
+        assert_eq!(locale.get(Language::En, "Dashboard"), None);
+        assert!(!locale.is_empty());
+    }
+
+    #[test]
+    fn gaps_compare_each_language_with_en_us_and_the_interface() {
+        let known = ["Dashboard", "Traffic", "Settings"]
+            .map(String::from)
+            .into();
+        let mut locale = LocaleOverrides::default();
+        locale.en.insert("Dashboard".into(), "Overview".into());
+        locale.en.insert("Traffic".into(), "Throughput".into());
+        locale.zh.insert("Traffic".into(), " ".into());
+        locale.zh.insert("Dashbord".into(), "总览".into());
+
+        assert_eq!(locale.gaps(Language::En, &known), LocaleGaps::default());
+        let zh = locale.gaps(Language::Zh, &known);
+        assert_eq!(zh.missing, ["Dashboard", "Traffic"]);
+        assert_eq!(zh.unused, ["Dashbord"]);
+        assert!(!zh.is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+//! Diagnostic presets.
+//!
+//! A preset runs several diagnostics against one target, one after another,
+//! and keeps a short outcome of each so a single report can answer "is this
+//! host up, how do I reach it and what does it serve" without driving every
+//! tool by hand. Ping, trace and port scan run through their own tools (with
+//! those tools' settings, and their views fill in as usual); the web stage is
+//! a small HTTP/TLS check that only exists as part of a preset.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DiagnosticTool, JobId, PingSample, PingSummary, PortScanResult, WEB_PORTS};
+
+/// Echoes the ping stage collects before moving on.
+pub const PIPELINE_PING_SAMPLES: usize = 5;
+/// Budget per port of the web stage: connect, handshake and response.
+pub const WEB_CHECK_TIMEOUT_MS: u64 = 3_000;
+
+/// Whether the web stage speaks TLS on this port.
+pub const fn web_port_uses_tls(port: u16) -> bool {
+    port == 443
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PipelinePreset {
+    /// Every stage.
+    FullHostCheck,
+    /// Is it up and which way does the traffic go.
+    Reachability,
+    /// Is the web server answering and is its certificate valid.
+    WebService,
+}
+
+impl PipelinePreset {
+    pub const ALL: [Self; 3] = [Self::FullHostCheck, Self::Reachability, Self::WebService];
+
+    pub const fn stages(self) -> &'static [PipelineStage] {
+        match self {
+            Self::FullHostCheck => &[
+                PipelineStage::Ping,
+                PipelineStage::Trace,
+                PipelineStage::PortScan,
+                PipelineStage::Web,
+            ],
+            Self::Reachability => &[PipelineStage::Ping, PipelineStage::Trace],
+            Self::WebService => &[PipelineStage::Ping, PipelineStage::Web],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PipelineStage {
+    Ping,
+    Trace,
+    PortScan,
+    /// HTTP/TLS check of the web ports.
+    Web,
+}
+
+impl PipelineStage {
+    /// The diagnostic tool that runs this stage, if it is one.
+    pub const fn tool(self) -> Option<DiagnosticTool> {
+        match self {
+            Self::Ping => Some(DiagnosticTool::Ping),
+            Self::Trace => Some(DiagnosticTool::Trace),
+            Self::PortScan => Some(DiagnosticTool::PortScan),
+            Self::Web => None,
+        }
+    }
+}
+
+/// What a finished stage found, kept small enough for one report line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StageOutcome {
+    Ping {
+        sent: u64,
+        received: u64,
+        average_ms: Option<f64>,
+    },
+    Trace {
+        hops: usize,
+        /// Last hop that answered.
+        last: Option<String>,
+    },
+    PortScan {
+        open: Vec<PortScanResult>,
+    },
+    Web(Vec<WebCheckResult>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StageStatus {
+    Pending,
+    Running,
+    Done(StageOutcome),
+    Failed(String),
+    /// Not run: the preset was stopped, or an earlier stage left nothing to
+    /// check.
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageReport {
+    pub stage: PipelineStage,
+    pub status: StageStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PipelineState {
+    /// Highlighted row of the preset picker.
+    pub selected: usize,
+    /// Preset of the current or last run.
+    pub preset: Option<PipelinePreset>,
+    pub target: String,
+    pub stages: Vec<StageReport>,
+    /// The web stage's job while it runs.
+    pub job: Option<JobId>,
+    /// The popup shows the preset picker rather than the report.
+    pub picking: bool,
+}
+
+impl PipelineState {
+    pub fn running(&self) -> bool {
+        self.current().is_some()
+    }
+
+    /// Index of the running stage.
+    pub fn current(&self) -> Option<usize> {
+        self.stages
+            .iter()
+            .position(|report| report.status == StageStatus::Running)
+    }
+
+    /// Open ports the port scan stage found, if it ran.
+    pub fn open_ports(&self) -> Option<Vec<u16>> {
+        self.stages.iter().find_map(|report| match &report.status {
+            StageStatus::Done(StageOutcome::PortScan { open }) => {
+                Some(open.iter().map(|port| port.port).collect())
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebCheckRequest {
+    pub target: String,
+    pub ports: Vec<u16>,
+    pub timeout_ms: u64,
+    /// SSH jump host to connect from, as for [`crate::PortScanRequest`].
+    pub jump_host: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsSummary {
+    /// Protocol version, e.g. `TLSv1_3`.
+    pub version: String,
+    pub cipher: String,
+}
+
+/// One port of the web stage. A TLS port whose certificate does not verify
+/// reports the handshake error and no status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebCheckResult {
+    pub port: u16,
+    pub tls: Option<TlsSummary>,
+    /// HTTP status code of `GET /`.
+    pub status: Option<u16>,
+    pub server: Option<String>,
+    pub title: Option<String>,
+    /// Connect to last byte read.
+    pub elapsed_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Ports the web stage checks: the open [`WEB_PORTS`] when a port scan ran,
+/// otherwise all of them.
+pub fn web_check_ports(open: Option<&[u16]>) -> Vec<u16> {
+    match open {
+        Some(open) => WEB_PORTS
+            .into_iter()
+            .filter(|port| open.contains(port))
+            .collect(),
+        None => WEB_PORTS.to_vec(),
+    }
+}
+
+/// Summary of the echoes a stopped ping collected.
+pub fn summarize_pings(samples: &[PingSample]) -> PingSummary {
+    let latencies: Vec<u64> = samples
+        .iter()
+        .filter_map(|sample| sample.latency_ms)
+        .collect();
+    let sent = samples.len() as u64;
+    let received = latencies.len() as u64;
+    PingSummary {
+        sent,
+        received,
+        min_ms: latencies.iter().min().copied(),
+        average_ms: (received > 0).then(|| latencies.iter().sum::<u64>() as f64 / received as f64),
+        max_ms: latencies.iter().max().copied(),
+        loss_percent: if sent == 0 {
+            0.0
+        } else {
+            (sent - received) as f64 * 100.0 / sent as f64
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_web_stage_checks_only_web_ports_the_scan_found_open() {
+        assert_eq!(web_check_ports(None), WEB_PORTS);
+        assert_eq!(web_check_ports(Some(&[22, 443, 3389])), [443]);
+        assert!(web_check_ports(Some(&[22])).is_empty());
+        assert!(web_port_uses_tls(443) && !web_port_uses_tls(8080));
+    }
+}
@@ -0,0 +1,115 @@
+//! Capability policy for machines shared with people who should only look.
+//!
+//! The `[policy]` table of the config file names features that send probes
+//! at other hosts, and the reducer refuses to start them. Passive views stay
+//! usable: a disabled LAN scan still shows the saved inventory, and the
+//! diagnostics menu still lists the port scan, marked as turned off. The
+//! check sits in the module registry ([`crate::permitted`]) rather than in
+//! the renderer, so neither a key binding, a click nor a preset pipeline
+//! gets around it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Language;
+
+/// Status left on a module whose start the policy refused.
+pub const POLICY_REFUSAL: &str = "turned off by this machine's policy";
+
+/// A feature the policy can turn off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Feature {
+    /// TCP connect probes across a port range (Diagnostics → Port Scan).
+    PortScan,
+    /// The Scanner tab's sweep: ARP, ping and service probes across a subnet.
+    LanScan,
+}
+
+impl Feature {
+    pub const ALL: [Self; 2] = [Self::PortScan, Self::LanScan];
+
+    /// Name used in the config file.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::PortScan => "port-scan",
+            Self::LanScan => "lan-scan",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|feature| feature.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub const fn label(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::PortScan, Language::Zh) => "端口扫描",
+            (Self::PortScan, Language::En) => "port scan",
+            (Self::LanScan, Language::Zh) => "局域网扫描",
+            (Self::LanScan, Language::En) => "LAN scan",
+        }
+    }
+}
+
+/// The `[policy]` table as written in the config file. Names are kept as
+/// text so a misspelt one is reported instead of discarding the whole file,
+/// and with it the policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PolicyConfig {
+    /// [`Feature::name`]s to turn off.
+    pub disabled: Vec<String>,
+}
+
+/// Features turned off on this machine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct CapabilityPolicy {
+    disabled: Vec<Feature>,
+}
+
+impl CapabilityPolicy {
+    /// Unknown names are skipped; [`crate::ConfigData::validate`] reports
+    /// them.
+    pub fn from_config(config: &PolicyConfig) -> Self {
+        let mut disabled = Vec::new();
+        for feature in config
+            .disabled
+            .iter()
+            .filter_map(|name| Feature::from_name(name))
+        {
+            if !disabled.contains(&feature) {
+                disabled.push(feature);
+            }
+        }
+        Self { disabled }
+    }
+
+    pub fn allows(&self, feature: Feature) -> bool {
+        !self.disabled.contains(&feature)
+    }
+
+    /// Turned-off features in config order.
+    pub fn disabled(&self) -> &[Feature] {
+        &self.disabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_read_once_each_and_unknown_ones_skipped() {
+        let policy = CapabilityPolicy::from_config(&PolicyConfig {
+            disabled: vec![
+                " Port-Scan".into(),
+                "packet-capture".into(),
+                "port-scan".into(),
+            ],
+        });
+        assert_eq!(policy.disabled(), [Feature::PortScan]);
+        assert!(!policy.allows(Feature::PortScan));
+        assert!(policy.allows(Feature::LanScan));
+        assert!(CapabilityPolicy::default().allows(Feature::PortScan));
+    }
+}
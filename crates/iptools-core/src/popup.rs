@@ -0,0 +1,34 @@
+//! Popups drawn above the current tab.
+//!
+//! [`AppModel::popups`](crate::AppModel::popups) is a stack: the last entry is
+//! drawn on top and receives every input until it closes, so transient
+//! interactions need no room in a page's layout. Quit, the language switch and
+//! presenter mode work from any popup.
+
+use crate::Confirmation;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Popup {
+    /// Key reference.
+    Help,
+    /// Release notes of an available update.
+    Update,
+    /// Self-diagnostics report.
+    Doctor,
+    /// Diagnostic preset picker, or the report of the current run.
+    Pipeline,
+    /// Failures collected from every tool.
+    Problems,
+    /// Chart export of the current tool's history.
+    Export,
+    /// Security findings over the scan results, exportable to Markdown.
+    Audit,
+    /// Address conversions for subnet planning.
+    Calculator,
+    /// Events of every tool on one time axis.
+    Timeline,
+    /// Problems found while loading the configuration.
+    ConfigIssues,
+    /// A question before a destructive action.
+    Confirm(Confirmation),
+}
@@ -0,0 +1,136 @@
+//! User-facing failures.
+//!
+//! Every tool reports failures as a [`RuntimeError`] and shows the current
+//! one in its own view, but a failure on a page that is not visible, or one
+//! replaced by the next attempt, used to go unnoticed. Failures of current
+//! jobs are also collected here: each [`RuntimeErrorCode`] carries a
+//! severity, errors open a dismissible popup and warnings only join the
+//! problem list behind it. The frontend keeps the technical detail in its
+//! log; the list holds what the user can act on.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RuntimeError, RuntimeErrorCode, ToolKind};
+
+/// Problems kept in the list; the oldest are dropped first.
+pub const PROBLEM_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    /// Bad input or an unreachable target: the tool's own view explains it.
+    Warning,
+    /// The tool cannot work here: missing permission or a local failure.
+    Error,
+}
+
+impl RuntimeErrorCode {
+    /// How loudly a failure is reported; a cancellation is not a failure.
+    pub const fn severity(self) -> Option<Severity> {
+        match self {
+            Self::Cancelled => None,
+            Self::InvalidRequest | Self::ResolveTarget | Self::Timeout | Self::Network => {
+                Some(Severity::Warning)
+            }
+            Self::PermissionDenied | Self::Internal => Some(Severity::Error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Problem {
+    pub tool: ToolKind,
+    pub severity: Severity,
+    pub code: RuntimeErrorCode,
+    pub message: String,
+    /// Local time of the latest occurrence, `HH:MM:SS`.
+    pub at: String,
+    /// Occurrences of the same failure since it was first listed.
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProblemsState {
+    /// Newest first.
+    pub items: Vec<Problem>,
+    pub selected: usize,
+    /// Listed since the popup was last opened.
+    pub unseen: usize,
+}
+
+impl ProblemsState {
+    /// List a failure, folding a repeat of a listed one into it; returns its
+    /// severity.
+    pub fn record(&mut self, tool: ToolKind, error: &RuntimeError, at: &str) -> Option<Severity> {
+        let severity = error.code.severity()?;
+        let at = at.rsplit(' ').next().unwrap_or_default().to_string();
+        let repeat = self
+            .items
+            .iter()
+            .position(|item| item.tool == tool && item.message == error.message);
+        let problem = match repeat {
+            Some(index) => {
+                let mut problem = self.items.remove(index);
+                problem.count = problem.count.saturating_add(1);
+                problem.at = at;
+                problem
+            }
+            None => {
+                self.unseen += 1;
+                Problem {
+                    tool,
+                    severity,
+                    code: error.code,
+                    message: error.message.clone(),
+                    at,
+                    count: 1,
+                }
+            }
+        };
+        self.items.insert(0, problem);
+        self.items.truncate(PROBLEM_LIMIT);
+        self.unseen = self.unseen.min(self.items.len());
+        Some(severity)
+    }
+
+    /// Select the newest problem and count every listed one as seen.
+    pub fn mark_seen(&mut self) {
+        self.selected = 0;
+        self.unseen = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.selected = 0;
+        self.unseen = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_fold_into_one_entry_and_cancellations_are_not_listed() {
+        let mut problems = ProblemsState::default();
+        let offline = RuntimeError::new(RuntimeErrorCode::Network, "offline");
+        assert_eq!(
+            problems.record(ToolKind::Dashboard, &offline, "2026-01-01 10:00:00"),
+            Some(Severity::Warning)
+        );
+        let denied = RuntimeError::new(RuntimeErrorCode::PermissionDenied, "raw socket");
+        assert_eq!(
+            problems.record(ToolKind::Ping, &denied, "2026-01-01 10:00:01"),
+            Some(Severity::Error)
+        );
+        problems.record(ToolKind::Dashboard, &offline, "2026-01-01 10:00:05");
+        assert_eq!(problems.items.len(), 2);
+        assert_eq!(problems.items[0].count, 2);
+        assert_eq!(problems.items[0].at, "10:00:05");
+        assert_eq!(problems.unseen, 2);
+
+        let cancelled = RuntimeError::new(RuntimeErrorCode::Cancelled, "stopped");
+        assert_eq!(problems.record(ToolKind::Ping, &cancelled, ""), None);
+        problems.mark_seen();
+        assert_eq!(problems.unseen, 0);
+    }
+}
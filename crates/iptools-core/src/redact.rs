@@ -0,0 +1,183 @@
+//! Presenter mode.
+//!
+//! While [`AppModel::redact`](crate::AppModel::redact) is on, renderers pass
+//! identifying values through these functions as they format them: public
+//! addresses, MAC addresses, SSIDs and host names are replaced with
+//! placeholders of the same shape, so screenshots and screen shares do
+//! not leak the network they were taken on. Placeholders are derived from a
+//! hash of the original value, so the same device keeps the same placeholder
+//! from frame to frame and distinct devices stay distinct. Private LAN
+//! addresses are left alone; they identify nothing outside the room.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// FNV-1a; stable across runs and platforms, unlike `DefaultHasher`.
+fn fingerprint(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10.
+                || (a == 100 && (64..128).contains(&b))
+                // Documentation ranges, where the placeholders come from.
+                || (a == 192 && b == 0 && c == 2)
+                || (a == 198 && b == 51 && c == 100)
+                || (a == 203 && b == 0 && c == 113))
+        }
+        IpAddr::V6(ip) => {
+            let [first, second, ..] = ip.segments();
+            first & 0xe000 == 0x2000 && !(first == 0x2001 && second == 0x0db8)
+        }
+    }
+}
+
+/// A public address becomes one from the documentation ranges
+/// (203.0.113.0/24, 2001:db8::/32); anything else is returned unchanged.
+pub fn redact_ip(value: &str) -> String {
+    let Ok(ip) = value.trim().parse::<IpAddr>() else {
+        return value.to_string();
+    };
+    if !is_public(ip) {
+        return value.to_string();
+    }
+    let hash = fingerprint(value.trim());
+    match ip {
+        IpAddr::V4(_) => Ipv4Addr::new(203, 0, 113, 1 + (hash % 254) as u8).to_string(),
+        IpAddr::V6(_) => {
+            Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, (hash >> 16) as u16, hash as u16).to_string()
+        }
+    }
+}
+
+/// A MAC address becomes a locally administered one (`02:…`), keeping the
+/// separator and letter case of the original. Anything else is returned
+/// unchanged.
+pub fn redact_mac(value: &str) -> String {
+    let value = value.trim();
+    let separator = match value.as_bytes().get(2) {
+        Some(b':') => ':',
+        Some(b'-') => '-',
+        _ => return value.to_string(),
+    };
+    let groups = value.split(separator).collect::<Vec<_>>();
+    if groups.len() != 6
+        || groups
+            .iter()
+            .any(|group| group.len() != 2 || !group.bytes().all(|byte| byte.is_ascii_hexdigit()))
+    {
+        return value.to_string();
+    }
+    let hash = fingerprint(&value.to_ascii_uppercase()).to_be_bytes();
+    let lower = value.bytes().any(|byte| byte.is_ascii_lowercase());
+    std::iter::once(0x02)
+        .chain(hash[..5].iter().copied())
+        .map(|byte| {
+            if lower {
+                format!("{byte:02x}")
+            } else {
+                format!("{byte:02X}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&separator.to_string())
+}
+
+/// An SSID becomes `WLAN-XXXX`.
+pub fn redact_ssid(value: &str) -> String {
+    placeholder(value, |tag| format!("WLAN-{tag:04X}"))
+}
+
+/// A host or device name becomes `host-xxxx`.
+pub fn redact_hostname(value: &str) -> String {
+    placeholder(value, |tag| format!("host-{tag:04x}"))
+}
+
+/// Empty and "unknown" markers stay as they are.
+fn placeholder(value: &str, format: impl Fn(u16) -> String) -> String {
+    match value.trim() {
+        "" | "-" | "—" | "*" => value.to_string(),
+        name => format(fingerprint(name) as u16),
+    }
+}
+
+/// Redact every address in free-form text, such as `a → b` or `ip:port`,
+/// leaving the words around them intact.
+pub fn redact_text(text: &str) -> String {
+    let is_token = |ch: char| ch.is_ascii_hexdigit() || matches!(ch, '.' | ':' | '-');
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_token) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|ch| !is_token(ch)).unwrap_or(rest.len());
+        let token = &rest[..end];
+        let trimmed = token.trim_end_matches(['.', ':', '-']);
+        output.push_str(&redact_token(trimmed));
+        output.push_str(&token[trimmed.len()..]);
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn redact_token(token: &str) -> String {
+    if token.parse::<IpAddr>().is_ok() {
+        return redact_ip(token);
+    }
+    if let Ok(SocketAddr::V4(socket)) = token.parse::<SocketAddr>() {
+        return format!("{}:{}", redact_ip(&socket.ip().to_string()), socket.port());
+    }
+    redact_mac(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_are_replaced_and_consistently() {
+        assert_eq!(redact_ip("192.168.1.20"), "192.168.1.20");
+        assert_eq!(redact_ip("100.72.0.9"), "100.72.0.9");
+        assert_eq!(redact_ip("fe80::1"), "fe80::1");
+        let masked = redact_ip("8.8.4.4");
+        assert!(masked.starts_with("203.0.113."), "{masked}");
+        assert_eq!(redact_ip("8.8.4.4"), masked);
+        assert_eq!(redact_ip(&masked), masked);
+        assert!(redact_ip("2400:cb00::1").starts_with("2001:db8::"));
+        assert_eq!(redact_ip("not an ip"), "not an ip");
+    }
+
+    #[test]
+    fn macs_keep_their_shape() {
+        let masked = redact_mac("3c:22:fb:12:34:56");
+        assert!(masked.starts_with("02:"), "{masked}");
+        assert_eq!(masked.len(), 17);
+        assert_eq!(masked, masked.to_ascii_lowercase());
+        let dashed = redact_mac("3C-22-FB-12-34-56");
+        assert!(dashed.starts_with("02-") && dashed == dashed.to_ascii_uppercase());
+        assert_eq!(redact_mac("-"), "-");
+    }
+
+    #[test]
+    fn free_text_keeps_words_and_ports() {
+        let text = redact_text("mapped 192.168.1.2 → 8.8.8.8:40001, via 10.0.0.1.");
+        assert!(
+            text.starts_with("mapped 192.168.1.2 → 203.0.113."),
+            "{text}"
+        );
+        assert!(text.contains(":40001, via 10.0.0.1."), "{text}");
+        assert_eq!(redact_text("2026-01-15 10:24"), "2026-01-15 10:24");
+    }
+}
@@ -0,0 +1,119 @@
+//! Retention limits for data that grows while tools run.
+//!
+//! A ping left running for a week or a scan of a large range would otherwise
+//! keep every sample, log line and host. Each buffer is capped by a row
+//! limit, and all of them together by a memory budget; when either is hit
+//! the oldest rows go first and the owning tool counts what it dropped, so
+//! the renderer can say the view is no longer complete.
+
+use serde::{Deserialize, Serialize};
+
+/// Samples and log lines kept per diagnostic tool.
+pub const RETAIN_SAMPLES_RANGE: (u64, u64) = (100, 1_000_000);
+/// Hosts kept in the scanner results.
+pub const RETAIN_SCAN_ROWS_RANGE: (u64, u64) = (1_000, 1_000_000);
+/// Budget, in MiB, for all retained rows together.
+pub const RETAIN_MEMORY_MB_RANGE: (u64, u64) = (16, 4_096);
+
+/// Heap a row owns beyond its inline size (text, small lists), as a flat
+/// allowance; the budget is an estimate, not an allocator measurement.
+const ROW_HEAP_BYTES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionLimits {
+    pub samples: usize,
+    pub scan_rows: usize,
+    pub memory_mb: u64,
+}
+
+impl Default for RetentionLimits {
+    fn default() -> Self {
+        Self {
+            samples: 10_000,
+            scan_rows: 100_000,
+            memory_mb: 256,
+        }
+    }
+}
+
+impl RetentionLimits {
+    pub const fn memory_bytes(&self) -> usize {
+        (self.memory_mb as usize).saturating_mul(1024 * 1024)
+    }
+}
+
+/// Estimated memory held by `rows`.
+pub fn estimated_bytes<T>(rows: &[T]) -> usize {
+    rows.len()
+        .saturating_mul(std::mem::size_of::<T>() + ROW_HEAP_BYTES)
+}
+
+/// One enforcement pass over every retained buffer, sharing the memory
+/// estimate taken at its start.
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionPass {
+    total: usize,
+    budget: usize,
+}
+
+impl RetentionPass {
+    /// `total` is the sum of [`estimated_bytes`] over all buffers.
+    pub const fn new(limits: &RetentionLimits, total: usize) -> Self {
+        Self {
+            total,
+            budget: limits.memory_bytes(),
+        }
+    }
+
+    /// Row limit for a buffer of `len` rows: `limit`, tightened in proportion
+    /// when the buffers together exceed the budget, so each gives up the same
+    /// share of its oldest rows.
+    pub fn limit(self, len: usize, limit: usize) -> usize {
+        if self.total <= self.budget {
+            return limit;
+        }
+        let kept = (len as u128 * self.budget as u128 / self.total as u128) as usize;
+        limit.min(kept)
+    }
+
+    /// Drop the oldest rows (the front) beyond the limit; returns how many
+    /// went.
+    pub fn trim<T>(self, rows: &mut Vec<T>, limit: usize) -> usize {
+        let excess = rows.len().saturating_sub(self.limit(rows.len(), limit));
+        rows.drain(..excess);
+        excess
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_oldest_rows_go_first_and_are_counted() {
+        let limits = RetentionLimits::default();
+        let pass = RetentionPass::new(&limits, 0);
+        let mut rows: Vec<u32> = (0..10).collect();
+        assert_eq!(pass.trim(&mut rows, 4), 6);
+        assert_eq!(rows, [6, 7, 8, 9]);
+        assert_eq!(pass.trim(&mut rows, 4), 0);
+    }
+
+    #[test]
+    fn an_exceeded_budget_shrinks_every_buffer_by_the_same_share() {
+        let limits = RetentionLimits {
+            memory_mb: 1,
+            ..RetentionLimits::default()
+        };
+        let mib = limits.memory_bytes();
+        assert_eq!(
+            RetentionPass::new(&limits, mib).limit(1_000, 10_000),
+            10_000
+        );
+        let pass = RetentionPass::new(&limits, 4 * mib);
+        assert_eq!(pass.limit(1_000, 10_000), 250);
+        assert_eq!(pass.limit(1_000, 100), 100);
+        assert!(estimated_bytes(&[0u64; 4]) >= 4 * 8);
+    }
+}
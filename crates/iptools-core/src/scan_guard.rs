@@ -0,0 +1,168 @@
+//! Guard against scanning hosts that are not yours.
+//!
+//! One slipped digit turns `192.168.1.0/24` into `19.168.1.0/24`, and a
+//! `/8` typed for a `/24` asks for sixteen million probes. Before the LAN
+//! scan, the port scan or a preset that port-scans starts, its target is
+//! checked: anything outside the private, link-local and loopback ranges,
+//! and any range larger than [`SCAN_GUARD_MAX_HOSTS`], waits until the
+//! target is typed back in a [`Confirm::ScanTarget`](crate::Confirm::ScanTarget)
+//! question. A confirmed target is not asked about again until restart.
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use crate::{PipelinePreset, Target};
+
+/// Most addresses a scan may cover without confirmation, a /20.
+pub const SCAN_GUARD_MAX_HOSTS: u64 = 4_096;
+
+/// IPv4 blocks a scan may reach unasked: RFC 1918, link-local and loopback.
+const LOCAL_IPV4: [(Ipv4Addr, u8); 5] = [
+    (Ipv4Addr::new(10, 0, 0, 0), 8),
+    (Ipv4Addr::new(172, 16, 0, 0), 12),
+    (Ipv4Addr::new(192, 168, 0, 0), 16),
+    (Ipv4Addr::new(169, 254, 0, 0), 16),
+    (Ipv4Addr::new(127, 0, 0, 0), 8),
+];
+
+/// Name suffixes home routers and mDNS hand out; a single-label name is
+/// local too.
+const LOCAL_SUFFIXES: [&str; 6] = [
+    ".local",
+    ".lan",
+    ".home",
+    ".home.arpa",
+    ".internal",
+    ".localdomain",
+];
+
+/// Why a target needs confirming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanConcern {
+    /// Reaches beyond the local ranges, or is a name that may resolve there.
+    Public,
+    /// Covers more than [`SCAN_GUARD_MAX_HOSTS`] addresses.
+    Large { hosts: u64 },
+}
+
+/// The start that waits for the answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardedScan {
+    LanScan,
+    PortScan,
+    Pipeline(PipelinePreset),
+}
+
+/// What there is to confirm about scanning `target`; `None` when it stays
+/// on the local network and within the size limit.
+pub fn scan_concern(target: &Target) -> Option<ScanConcern> {
+    match target {
+        Target::Address(address) => (!is_local_address(*address)).then_some(ScanConcern::Public),
+        Target::Host(name) => (!is_local_name(name)).then_some(ScanConcern::Public),
+        Target::Network { .. } | Target::Range { .. } => {
+            let (start, end) = target.ipv4_bounds()?;
+            let hosts = target.host_count();
+            if !LOCAL_IPV4.iter().any(|&(network, prefix)| {
+                in_block(start, network, prefix) && in_block(end, network, prefix)
+            }) {
+                Some(ScanConcern::Public)
+            } else if hosts > SCAN_GUARD_MAX_HOSTS {
+                Some(ScanConcern::Large { hosts })
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn in_block(address: Ipv4Addr, network: Ipv4Addr, prefix: u8) -> bool {
+    u32::from(address) >> (32 - prefix) == u32::from(network) >> (32 - prefix)
+}
+
+fn is_local_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => LOCAL_IPV4
+            .iter()
+            .any(|&(network, prefix)| in_block(address, network, prefix)),
+        IpAddr::V6(address) => {
+            address.is_loopback() || address.is_unicast_link_local() || address.is_unique_local()
+        }
+    }
+}
+
+fn is_local_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    !name.contains('.')
+        || name == "localhost"
+        || LOCAL_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// Targets confirmed this session.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScanGuard {
+    approved: Vec<String>,
+}
+
+impl ScanGuard {
+    /// Like [`scan_concern`], but quiet for a target confirmed before.
+    pub fn check(&self, target: &Target) -> Option<ScanConcern> {
+        let spelled = target.to_string();
+        if self.approved.contains(&spelled) {
+            return None;
+        }
+        scan_concern(target)
+    }
+
+    /// Stop asking about `target`, in its normalized spelling.
+    pub fn approve(&mut self, target: String) {
+        if !self.approved.contains(&target) {
+            self.approved.push(target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concern(input: &str) -> Option<ScanConcern> {
+        scan_concern(&Target::parse(input).unwrap())
+    }
+
+    #[test]
+    fn only_public_or_oversized_targets_need_confirming() {
+        assert_eq!(concern("192.168.1.0/24"), None);
+        assert_eq!(concern("10.0.0.1-10.0.3.254"), None);
+        assert_eq!(concern("172.31.255.1"), None);
+        assert_eq!(concern("169.254.10.20"), None);
+        assert_eq!(concern("fe80::1"), None);
+        assert_eq!(concern("fd12:3456::1"), None);
+        assert_eq!(concern("nas"), None);
+        assert_eq!(concern("Router.LAN"), None);
+        assert_eq!(concern("printer.home.arpa"), None);
+
+        assert_eq!(concern("19.168.1.0/24"), Some(ScanConcern::Public));
+        assert_eq!(concern("172.32.0.1"), Some(ScanConcern::Public));
+        assert_eq!(concern("100.64.0.1"), Some(ScanConcern::Public));
+        assert_eq!(
+            concern("192.168.255.0-192.169.0.10"),
+            Some(ScanConcern::Public),
+            "a range leaving the private block"
+        );
+        assert_eq!(concern("2001:db8::1"), Some(ScanConcern::Public));
+        assert_eq!(concern("example.com"), Some(ScanConcern::Public));
+        assert_eq!(
+            concern("10.0.0.0/8"),
+            Some(ScanConcern::Large { hosts: 16_777_214 })
+        );
+        assert_eq!(concern("192.168.0.0/20"), None, "4094 hosts");
+
+        let mut guard = ScanGuard::default();
+        let target = Target::parse("example.com").unwrap();
+        guard.approve(target.to_string());
+        assert_eq!(guard.check(&target), None);
+        assert_eq!(
+            guard.check(&Target::parse("example.org").unwrap()),
+            Some(ScanConcern::Public)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Adaptive scan concurrency.
+//!
+//! An additive-increase / multiplicative-decrease limiter over the scanner's
+//! probe workers. It starts at the configured concurrency, halves when too
+//! many probes in a window needed a retry or failed outright, and climbs back
+//! a step at a time while the link stays clean. Silent addresses are not a
+//! signal: most of a sparse subnet never answers, congested or not.
+
+/// Probes judged before each adjustment.
+pub const SCAN_RATE_WINDOW: usize = 32;
+/// Share of troubled probes in a window that triggers a back-off.
+const BACKOFF_PERCENT: usize = 10;
+/// Never drop below this many workers.
+pub const SCAN_RATE_FLOOR: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// Answered on the first attempt.
+    Clean,
+    /// Answered only after a retry, or the send itself failed.
+    Troubled,
+    /// Nothing answered; says nothing about the link.
+    Silent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveConcurrency {
+    ceiling: usize,
+    limit: usize,
+    clean: usize,
+    troubled: usize,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(ceiling: usize) -> Self {
+        let ceiling = ceiling.max(1);
+        Self {
+            ceiling,
+            limit: ceiling,
+            clean: 0,
+            troubled: 0,
+        }
+    }
+
+    /// Workers allowed to probe right now.
+    pub const fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn record(&mut self, outcome: ProbeOutcome) {
+        match outcome {
+            ProbeOutcome::Clean => self.clean += 1,
+            ProbeOutcome::Troubled => self.troubled += 1,
+            ProbeOutcome::Silent => return,
+        }
+        let judged = self.clean + self.troubled;
+        if judged < SCAN_RATE_WINDOW {
+            return;
+        }
+        let floor = SCAN_RATE_FLOOR.min(self.ceiling);
+        if self.troubled * 100 >= judged * BACKOFF_PERCENT {
+            self.limit = (self.limit / 2).max(floor);
+        } else if self.troubled == 0 {
+            let step = (self.ceiling / 10).max(1);
+            self.limit = (self.limit + step).min(self.ceiling);
+        }
+        self.clean = 0;
+        self.troubled = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(limiter: &mut AdaptiveConcurrency, troubled: usize) {
+        for index in 0..SCAN_RATE_WINDOW {
+            limiter.record(if index < troubled {
+                ProbeOutcome::Troubled
+            } else {
+                ProbeOutcome::Clean
+            });
+        }
+    }
+
+    #[test]
+    fn backs_off_on_retries_and_recovers_while_clean() {
+        let mut limiter = AdaptiveConcurrency::new(200);
+        for _ in 0..1_000 {
+            limiter.record(ProbeOutcome::Silent);
+        }
+        assert_eq!(limiter.limit(), 200, "silence is not congestion");
+
+        window(&mut limiter, 8);
+        assert_eq!(limiter.limit(), 100);
+        window(&mut limiter, 8);
+        assert_eq!(limiter.limit(), 50);
+        window(&mut limiter, 1);
+        assert_eq!(limiter.limit(), 50, "a stray retry holds the rate");
+        window(&mut limiter, 0);
+        assert_eq!(limiter.limit(), 70);
+        for _ in 0..10 {
+            window(&mut limiter, 0);
+        }
+        assert_eq!(limiter.limit(), 200, "never above the configured ceiling");
+        for _ in 0..10 {
+            window(&mut limiter, SCAN_RATE_WINDOW);
+        }
+        assert_eq!(limiter.limit(), SCAN_RATE_FLOOR);
+    }
+}
@@ -0,0 +1,54 @@
+//! Search over the text of the focused pane.
+//!
+//! `Ctrl+F` opens a query line in place of the footer. The renderer looks
+//! for the query in whatever the focused pane drew, so the ping log, the
+//! event log, the scan table and the trace output are searched the same way
+//! without a hook of their own. The reducer only keeps the query and which
+//! match is current; `n` and `N` step through the matches and Esc clears
+//! them.
+
+/// Longest query kept.
+pub const SEARCH_QUERY_LIMIT: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchState {
+    /// A regular expression, case-insensitive unless it has a capital
+    /// letter; one that does not compile is searched for as plain text.
+    pub query: String,
+    pub cursor: usize,
+    /// The query line takes every key until Enter or Esc.
+    pub editing: bool,
+    /// Matches stepped over with `n` (forwards) and `N` since the query last
+    /// changed. Only the renderer knows how many there are, so it wraps this
+    /// by the count it finds.
+    pub step: i64,
+}
+
+impl SearchState {
+    /// Whether the query line is shown and its matches highlighted.
+    pub fn active(&self) -> bool {
+        self.editing || !self.query.is_empty()
+    }
+
+    /// The current match among `count` in reading order.
+    pub fn current(&self, count: usize) -> Option<usize> {
+        let count = i64::try_from(count).ok().filter(|&count| count > 0)?;
+        usize::try_from(self.step.rem_euclid(count)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepping_wraps_in_both_directions() {
+        let mut search = SearchState::default();
+        assert_eq!(search.current(3), Some(0));
+        search.step = -1;
+        assert_eq!(search.current(3), Some(2));
+        search.step = 4;
+        assert_eq!(search.current(3), Some(1));
+        assert_eq!(search.current(0), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! References to secrets kept in the operating system's credential store.
+//!
+//! API tokens and passwords never go into the config file. A config value
+//! names them instead, as `${secret:NAME}` anywhere in a public IP endpoint
+//! or watch URL, and the native runtime fills the value in from the keyring
+//! (Windows Credential Manager, the Secret Service, the macOS Keychain)
+//! right before the request. The model, the settings editor and the saved
+//! config only ever see the reference.
+
+use crate::ConfigData;
+
+/// Keyring service the secrets are filed under.
+pub const SECRET_SERVICE: &str = "iptools";
+
+const OPEN: &str = "${secret:";
+
+/// Whether `name` can be stored and referenced: 1–64 ASCII letters,
+/// digits, `-`, `_` or `.`.
+pub fn is_secret_name(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "-_.".contains(character))
+}
+
+/// The `${secret:NAME}` placeholder for `name`.
+pub fn secret_reference(name: &str) -> String {
+    format!("{OPEN}{name}}}")
+}
+
+/// Names `text` refers to, in order of appearance.
+pub fn secret_names(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        rest = &rest[start + OPEN.len()..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        names.push(&rest[..end]);
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// `text` with every placeholder replaced by what `lookup` returns for its
+/// name. The first failed lookup is returned as is.
+pub fn expand_secrets<E>(
+    text: &str,
+    mut lookup: impl FnMut(&str) -> Result<String, E>,
+) -> Result<String, E> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        let after = &rest[start + OPEN.len()..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&lookup(&after[..end])?);
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Every secret the config refers to, once each.
+pub fn config_secret_names(config: &ConfigData) -> Vec<String> {
+    let texts = config
+        .public_ip
+        .endpoints
+        .iter()
+        .map(|endpoint| endpoint.url.as_str())
+        .chain(config.watch.iter().map(|target| target.target.as_str()));
+    let mut names: Vec<String> = Vec::new();
+    for name in texts.flat_map(secret_names) {
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_found_and_filled_in() {
+        let url = "https://ipinfo.io/json?token=${secret:ipinfo}&x=${secret:b.2}";
+        assert_eq!(secret_names(url), ["ipinfo", "b.2"]);
+        assert_eq!(
+            expand_secrets(url, |name| Ok::<_, ()>(name.to_uppercase())).unwrap(),
+            "https://ipinfo.io/json?token=IPINFO&x=B.2"
+        );
+        assert_eq!(
+            expand_secrets(url, |name| Err::<String, _>(name.to_string())),
+            Err("ipinfo".to_string())
+        );
+        assert_eq!(
+            expand_secrets("https://a/${secret:open", |_| Err::<String, ()>(())),
+            Ok("https://a/${secret:open".into()),
+            "an unterminated placeholder is plain text"
+        );
+        assert_eq!(secret_reference("ipinfo"), "${secret:ipinfo}");
+
+        assert!(is_secret_name("ddns-token_2.home"));
+        assert!(!is_secret_name(""));
+        assert!(!is_secret_name("has space"));
+        assert!(!is_secret_name("brace}"));
+
+        let mut config = ConfigData::default();
+        config.public_ip.endpoints[1].url = "https://ipinfo.io/json?token=${secret:ipinfo}".into();
+        config.watch = vec![crate::WatchTarget {
+            name: "api".into(),
+            target: "https://example.com/health?key=${secret:ipinfo}".into(),
+            budget_ms: None,
+            maintenance: None,
+        }];
+        assert_eq!(config_secret_names(&config), ["ipinfo"]);
+    }
+}
@@ -0,0 +1,495 @@
+//! Settings page layout.
+//!
+//! Every user-facing setting is one row, listed under a section and edited
+//! according to its [`SettingKind`]: choices cycle, toggles flip, numbers step
+//! with Left/Right or accept a typed value, text is typed. Typed values are
+//! validated before anything is applied or persisted; the renderer only
+//! formats what the model holds.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ENDPOINT_KINDS, Endpoint, Page};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettingsSection {
+    General,
+    Scanner,
+    Ping,
+    Network,
+    Monitoring,
+    Retention,
+    Appearance,
+    Accessibility,
+    Integrations,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettingId {
+    Language,
+    LowPower,
+    ClearSession,
+    RestoreDefaults,
+    Doctor,
+    ScanConcurrency,
+    ScanAdaptive,
+    ScanEnrich,
+    ScanDnsZone,
+    PingTarget,
+    PingInterval,
+    PingTimeout,
+    PingPacketSize,
+    Offline,
+    SystemProxy,
+    JumpHost,
+    WatchTargets,
+    DropAlert,
+    RetainSamples,
+    RetainScanRows,
+    RetainMemory,
+    Theme,
+    ChartStyle,
+    RegionFormat,
+    ClockFormat,
+    ShowUtc,
+    Tabs,
+    ColorMode,
+    PublicIpEndpoints,
+    CheckUpdates,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    /// Left/Right and Enter cycle through a fixed set of values.
+    Choice,
+    Toggle,
+    /// Left/Right step within the range; Enter types an exact value.
+    Number {
+        min: u64,
+        max: u64,
+        step: u64,
+    },
+    /// Enter opens a text editor.
+    Text,
+    /// Enter runs it.
+    Action,
+}
+
+/// Rows in display order; sections appear in the order of their first row.
+pub const SETTINGS: [SettingId; 30] = [
+    SettingId::Language,
+    SettingId::LowPower,
+    SettingId::ClearSession,
+    SettingId::RestoreDefaults,
+    SettingId::Doctor,
+    SettingId::ScanConcurrency,
+    SettingId::ScanAdaptive,
+    SettingId::ScanEnrich,
+    SettingId::ScanDnsZone,
+    SettingId::PingTarget,
+    SettingId::PingInterval,
+    SettingId::PingTimeout,
+    SettingId::PingPacketSize,
+    SettingId::Offline,
+    SettingId::SystemProxy,
+    SettingId::JumpHost,
+    SettingId::WatchTargets,
+    SettingId::DropAlert,
+    SettingId::RetainSamples,
+    SettingId::RetainScanRows,
+    SettingId::RetainMemory,
+    SettingId::Theme,
+    SettingId::ChartStyle,
+    SettingId::RegionFormat,
+    SettingId::ClockFormat,
+    SettingId::ShowUtc,
+    SettingId::Tabs,
+    SettingId::ColorMode,
+    SettingId::PublicIpEndpoints,
+    SettingId::CheckUpdates,
+];
+
+/// Number of rows on the settings page.
+pub const SETTINGS_ITEMS: usize = SETTINGS.len();
+
+impl SettingId {
+    pub const fn section(self) -> SettingsSection {
+        match self {
+            Self::Language
+            | Self::LowPower
+            | Self::ClearSession
+            | Self::RestoreDefaults
+            | Self::Doctor => SettingsSection::General,
+            Self::ScanConcurrency | Self::ScanAdaptive | Self::ScanEnrich | Self::ScanDnsZone => {
+                SettingsSection::Scanner
+            }
+            Self::PingTarget | Self::PingInterval | Self::PingTimeout | Self::PingPacketSize => {
+                SettingsSection::Ping
+            }
+            Self::Offline | Self::SystemProxy | Self::JumpHost => SettingsSection::Network,
+            Self::WatchTargets | Self::DropAlert => SettingsSection::Monitoring,
+            Self::RetainSamples | Self::RetainScanRows | Self::RetainMemory => {
+                SettingsSection::Retention
+            }
+            Self::Theme
+            | Self::ChartStyle
+            | Self::RegionFormat
+            | Self::ClockFormat
+            | Self::ShowUtc
+            | Self::Tabs => SettingsSection::Appearance,
+            Self::ColorMode => SettingsSection::Accessibility,
+            Self::PublicIpEndpoints | Self::CheckUpdates => SettingsSection::Integrations,
+        }
+    }
+
+    pub const fn kind(self) -> SettingKind {
+        match self {
+            Self::Language
+            | Self::LowPower
+            | Self::Theme
+            | Self::ChartStyle
+            | Self::RegionFormat
+            | Self::ClockFormat
+            | Self::ColorMode => SettingKind::Choice,
+            Self::ScanAdaptive
+            | Self::ScanEnrich
+            | Self::Offline
+            | Self::SystemProxy
+            | Self::ShowUtc
+            | Self::CheckUpdates => SettingKind::Toggle,
+            Self::ClearSession | Self::RestoreDefaults | Self::Doctor => SettingKind::Action,
+            Self::ScanDnsZone
+            | Self::JumpHost
+            | Self::PingTarget
+            | Self::Tabs
+            | Self::PublicIpEndpoints
+            | Self::WatchTargets => SettingKind::Text,
+            Self::ScanConcurrency => SettingKind::Number {
+                min: 10,
+                max: 500,
+                step: 10,
+            },
+            Self::PingInterval | Self::PingTimeout => SettingKind::Number {
+                min: 100,
+                max: 10_000,
+                step: 100,
+            },
+            Self::PingPacketSize => SettingKind::Number {
+                min: 0,
+                max: 65_500,
+                step: 8,
+            },
+            Self::DropAlert => SettingKind::Number {
+                min: crate::DROP_ALERT_PERCENT_RANGE.0,
+                max: crate::DROP_ALERT_PERCENT_RANGE.1,
+                step: 1,
+            },
+            Self::RetainSamples => SettingKind::Number {
+                min: crate::RETAIN_SAMPLES_RANGE.0,
+                max: crate::RETAIN_SAMPLES_RANGE.1,
+                step: 1_000,
+            },
+            Self::RetainScanRows => SettingKind::Number {
+                min: crate::RETAIN_SCAN_ROWS_RANGE.0,
+                max: crate::RETAIN_SCAN_ROWS_RANGE.1,
+                step: 10_000,
+            },
+            Self::RetainMemory => SettingKind::Number {
+                min: crate::RETAIN_MEMORY_MB_RANGE.0,
+                max: crate::RETAIN_MEMORY_MB_RANGE.1,
+                step: 16,
+            },
+        }
+    }
+}
+
+/// Why a typed value was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettingError {
+    NotANumber,
+    OutOfRange {
+        min: u64,
+        max: u64,
+    },
+    /// Not a single host that [`crate::Target::parse_host`] accepts.
+    InvalidHost,
+    /// Not a DNS name: a label is empty, too long or has other characters
+    /// than letters, digits and inner hyphens.
+    InvalidDomain,
+    /// Not `[user@]host[:port]`.
+    InvalidJumpHost,
+    /// The offending `kind url` entry of an endpoint list.
+    InvalidEndpoint(String),
+    /// A name in the tab list that is not a page.
+    UnknownTab(String),
+    /// The offending `name=target` entry of the watch list.
+    InvalidWatchTarget(String),
+    TooManyWatchTargets {
+        max: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SettingsState {
+    pub selected: usize,
+    /// Text being typed into the selected row and the cursor position.
+    pub input: Option<(String, usize)>,
+    /// Why the last typed value was rejected; the editor stays open.
+    pub error: Option<SettingError>,
+    /// Action row that just ran, shown as done until the selection moves.
+    pub done: Option<SettingId>,
+}
+
+impl SettingsState {
+    pub fn current(&self) -> SettingId {
+        SETTINGS[self.selected.min(SETTINGS_ITEMS - 1)]
+    }
+}
+
+pub fn parse_setting_number(text: &str, min: u64, max: u64) -> Result<u64, SettingError> {
+    let value = text
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| SettingError::NotANumber)?;
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(SettingError::OutOfRange { min, max })
+    }
+}
+
+pub fn parse_setting_host(text: &str) -> Result<String, SettingError> {
+    crate::Target::parse_host(text)
+        .map(|target| target.to_string())
+        .map_err(|_| SettingError::InvalidHost)
+}
+
+/// A DNS suffix such as `corp.example.com`, lowercased and without the
+/// trailing dot. Empty turns the DNS zone sweep off.
+pub fn parse_dns_zone(text: &str) -> Result<String, SettingError> {
+    let zone = text.trim().trim_end_matches('.').to_ascii_lowercase();
+    if zone.is_empty() {
+        return Ok(zone);
+    }
+    let valid_label = |label: &str| {
+        (1..=63).contains(&label.len())
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '-')
+    };
+    if zone.len() > 253 || !zone.split('.').all(valid_label) {
+        return Err(SettingError::InvalidDomain);
+    }
+    Ok(zone)
+}
+
+/// An SSH jump host, `[user@]host[:port]`; IPv6 hosts go in brackets when a
+/// port follows. Empty turns the jump host off.
+pub fn parse_jump_host(text: &str) -> Result<String, SettingError> {
+    let text = text.trim();
+    if !text.is_empty() {
+        ssh_destination(text).ok_or(SettingError::InvalidJumpHost)?;
+    }
+    Ok(text.to_string())
+}
+
+/// Split a jump host into the `user@host` that ssh takes as its destination
+/// (brackets dropped) and the port, if given.
+pub fn ssh_destination(jump_host: &str) -> Option<(String, Option<u16>)> {
+    let (user, rest) = match jump_host.rsplit_once('@') {
+        Some((user, rest)) => (Some(user), rest),
+        None => (None, jump_host),
+    };
+    let valid_user = |user: &str| {
+        !user.is_empty()
+            && !user.starts_with('-')
+            && user
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || "._-".contains(character))
+    };
+    if user.is_some_and(|user| !valid_user(user)) {
+        return None;
+    }
+    let (host, port) = match rest.strip_prefix('[') {
+        Some(bracketed) => match bracketed.split_once(']')? {
+            (host, "") => (host, None),
+            (host, port) => (host, Some(port.strip_prefix(':')?)),
+        },
+        None => match rest.split_once(':') {
+            Some((host, port)) if !port.contains(':') => (host, Some(port)),
+            _ => (rest, None),
+        },
+    };
+    let port = match port {
+        Some(port) => Some(port.parse::<u16>().ok().filter(|port| *port != 0)?),
+        None => None,
+    };
+    // A leading dash would reach ssh as an option.
+    if host.starts_with('-') || crate::Target::parse_host(host).is_err() {
+        return None;
+    }
+    let destination = match user {
+        Some(user) => format!("{user}@{host}"),
+        None => host.to_string(),
+    };
+    Some((destination, port))
+}
+
+/// Parse `kind url, kind url, …`. An empty list disables the public IP
+/// lookup.
+pub fn parse_endpoints(text: &str) -> Result<Vec<Endpoint>, SettingError> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = || SettingError::InvalidEndpoint(entry.to_string());
+            let (kind, url) = entry.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let url = url.trim();
+            if !ENDPOINT_KINDS.contains(&kind)
+                || !(url.starts_with("https://") || url.starts_with("http://"))
+                || url.contains(char::is_whitespace)
+            {
+                return Err(invalid());
+            }
+            Ok(Endpoint {
+                url: url.to_string(),
+                kind: kind.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn format_endpoints(endpoints: &[Endpoint]) -> String {
+    endpoints
+        .iter()
+        .map(|endpoint| format!("{} {}", endpoint.kind, endpoint.url))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse `dashboard, scanner, …` into the visible tabs in order. Settings is
+/// appended when left out.
+pub fn parse_tabs(text: &str) -> Result<Vec<Page>, SettingError> {
+    let pages = text
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| Page::from_name(name).ok_or_else(|| SettingError::UnknownTab(name.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(crate::normalize_tabs(pages))
+}
+
+pub fn format_tabs(tabs: &[Page]) -> String {
+    tabs.iter()
+        .map(|page| page.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_section_has_rows_in_one_contiguous_block() {
+        let mut seen = Vec::new();
+        for setting in SETTINGS {
+            let section = setting.section();
+            if seen.last() != Some(&section) {
+                assert!(!seen.contains(&section), "{section:?} is split");
+                seen.push(section);
+            }
+        }
+        assert_eq!(seen.len(), 9);
+    }
+
+    #[test]
+    fn typed_values_are_validated() {
+        assert_eq!(parse_setting_number(" 250 ", 10, 500), Ok(250));
+        assert_eq!(
+            parse_setting_number("5000", 10, 500),
+            Err(SettingError::OutOfRange { min: 10, max: 500 })
+        );
+        assert_eq!(
+            parse_setting_number("fast", 10, 500),
+            Err(SettingError::NotANumber)
+        );
+        assert_eq!(parse_setting_host("1.1.1.1"), Ok("1.1.1.1".into()));
+        assert_eq!(
+            parse_setting_host("one one"),
+            Err(SettingError::InvalidHost)
+        );
+        assert_eq!(
+            parse_dns_zone(" Corp.Example.COM. "),
+            Ok("corp.example.com".into())
+        );
+        assert_eq!(parse_dns_zone(""), Ok(String::new()));
+        for bad in [
+            "corp..example",
+            "-corp.example",
+            "corp example",
+            "corp_example.com",
+        ] {
+            assert_eq!(
+                parse_dns_zone(bad),
+                Err(SettingError::InvalidDomain),
+                "{bad}"
+            );
+        }
+        for good in [
+            "bastion",
+            "ops@10.0.0.1:2222",
+            "[2001:db8::1]:22",
+            "2001:db8::1",
+        ] {
+            assert_eq!(parse_jump_host(good), Ok(good.into()), "{good}");
+        }
+        assert_eq!(
+            ssh_destination("ops@[2001:db8::1]:2222"),
+            Some(("ops@2001:db8::1".into(), Some(2222)))
+        );
+        for bad in ["@host", "ops@host:0", "-oProxyCommand=x", "ops@bad host"] {
+            assert_eq!(
+                parse_jump_host(bad),
+                Err(SettingError::InvalidJumpHost),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn endpoint_lists_roundtrip_and_reject_bad_entries() {
+        let endpoints = crate::PublicIpConfig::default().endpoints;
+        let text = format_endpoints(&endpoints);
+        assert_eq!(
+            text,
+            "ipsb https://api.ip.sb/geoip, ipinfo https://ipinfo.io/json"
+        );
+        assert_eq!(parse_endpoints(&text), Ok(endpoints));
+        assert_eq!(parse_endpoints("  "), Ok(Vec::new()));
+        assert_eq!(
+            parse_endpoints("plaintext https://ifconfig.me/ip, json https://x.test"),
+            Err(SettingError::InvalidEndpoint("json https://x.test".into()))
+        );
+        assert_eq!(
+            parse_endpoints("ipsb ftp://api.ip.sb"),
+            Err(SettingError::InvalidEndpoint("ipsb ftp://api.ip.sb".into()))
+        );
+    }
+
+    #[test]
+    fn tab_lists_keep_settings_and_reject_unknown_pages() {
+        assert_eq!(parse_tabs(&format_tabs(&Page::ALL)), Ok(Page::ALL.to_vec()));
+        assert_eq!(
+            parse_tabs("Scanner, dashboard, scanner"),
+            Ok(vec![Page::Scanner, Page::Dashboard, Page::Settings])
+        );
+        assert_eq!(parse_tabs(""), Ok(vec![Page::Settings]));
+        assert_eq!(
+            parse_tabs("dashboard, wifi"),
+            Err(SettingError::UnknownTab("wifi".into()))
+        );
+    }
+}
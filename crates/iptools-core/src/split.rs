@@ -0,0 +1,128 @@
+//! Split view: two pages side by side, e.g. Ping in Diagnostics next to
+//! Traffic while chasing a saturated link.
+//!
+//! The focused pane is always [`AppModel::page`], so keys, hints and help
+//! follow it with no extra routing; the other pane's page is kept here and
+//! stays started, polled and drawn while the split is open. Switching focus
+//! swaps the two.
+
+use crate::{AppModel, Effect, Page};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitView {
+    /// The page of the pane without focus.
+    pub other: Page,
+    /// Where the focused page sits.
+    pub focus: Pane,
+}
+
+impl AppModel {
+    /// Left and right page while the split is open.
+    pub fn split_pages(&self) -> Option<(Page, Page)> {
+        self.split.map(|split| match split.focus {
+            Pane::Left => (self.page, split.other),
+            Pane::Right => (split.other, self.page),
+        })
+    }
+
+    /// Whether `page` is on screen, in either pane.
+    pub fn shows(&self, page: Page) -> bool {
+        self.page == page || self.split.is_some_and(|split| split.other == page)
+    }
+
+    /// Open the split with a companion page on the right, or close it and
+    /// keep the focused page.
+    pub(crate) fn toggle_split(&mut self) -> Vec<Effect> {
+        if let Some(split) = self.split.take() {
+            crate::module(crate::ModuleId::Page(split.other)).suspend(self);
+            return Vec::new();
+        }
+        let Some(other) = self.split_companion() else {
+            return Vec::new();
+        };
+        self.split = Some(SplitView {
+            other,
+            focus: Pane::Left,
+        });
+        self.enter(other)
+    }
+
+    /// Move focus to the other pane.
+    pub(crate) fn switch_pane(&mut self) -> Vec<Effect> {
+        let Some(split) = self.split else {
+            return Vec::new();
+        };
+        crate::module(crate::ModuleId::Page(self.page)).suspend(self);
+        self.split = Some(SplitView {
+            other: self.page,
+            focus: match split.focus {
+                Pane::Left => Pane::Right,
+                Pane::Right => Pane::Left,
+            },
+        });
+        self.page = split.other;
+        Vec::new()
+    }
+
+    /// Traffic goes next to anything else, Diagnostics next to Traffic; a
+    /// hidden tab falls back to the first visible one that differs.
+    fn split_companion(&self) -> Option<Page> {
+        let preferred = if self.page == Page::Traffic {
+            Page::Diagnostics
+        } else {
+            Page::Traffic
+        };
+        let tabs = self.tabs();
+        if tabs.contains(&preferred) {
+            return Some(preferred);
+        }
+        tabs.iter().copied().find(|page| *page != self.page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, InputEvent, Message};
+
+    fn act(app: &mut AppModel, action: Action) -> Vec<Effect> {
+        app.update(Message::Input(InputEvent::Action(action)))
+    }
+
+    #[test]
+    fn keys_follow_the_focused_pane_and_both_panes_stay_live() {
+        let mut app = AppModel::default();
+        act(&mut app, Action::SelectPage(Page::Diagnostics as u8));
+        act(&mut app, Action::ToggleSplit);
+        assert_eq!(app.split_pages(), Some((Page::Diagnostics, Page::Traffic)));
+        assert!(app.wants_traffic_polling());
+
+        act(&mut app, Action::SwitchPane);
+        assert_eq!(app.page, Page::Traffic);
+        assert_eq!(app.split_pages(), Some((Page::Diagnostics, Page::Traffic)));
+        // Tab changes only the focused pane; picking the other pane's page
+        // moves focus there instead of showing it twice.
+        act(&mut app, Action::SelectPage(Page::Scanner as u8));
+        assert_eq!(app.split_pages(), Some((Page::Diagnostics, Page::Scanner)));
+        assert!(!app.wants_traffic_polling());
+        act(&mut app, Action::SelectPage(Page::Diagnostics as u8));
+        assert_eq!(app.page, Page::Diagnostics);
+        assert_eq!(app.split_pages(), Some((Page::Diagnostics, Page::Scanner)));
+
+        act(&mut app, Action::ToggleSplit);
+        assert_eq!(app.split_pages(), None);
+        assert_eq!(app.page, Page::Diagnostics);
+        assert!(!app.shows(Page::Scanner));
+        assert!(act(&mut app, Action::SwitchPane).is_empty());
+
+        act(&mut app, Action::SelectPage(Page::Traffic as u8));
+        act(&mut app, Action::ToggleSplit);
+        assert_eq!(app.split_pages(), Some((Page::Traffic, Page::Diagnostics)));
+    }
+}
@@ -0,0 +1,369 @@
+//! Target parsing shared by every tool that takes a host or a range.
+//!
+//! Ping, trace and port scan each want one host; the scanner wants a set of
+//! IPv4 addresses. Both read the same forms (a hostname, an IPv4 or IPv6
+//! address, a CIDR network, an address range or a URL whose host is used),
+//! reject typos before a job starts, and normalize what they keep so the
+//! runtime and the history see one spelling per target.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+
+const MAX_HOSTNAME_LEN: usize = 253;
+const MAX_LABEL_LEN: usize = 63;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    Address(IpAddr),
+    /// A name left for the runtime to resolve, without a trailing dot.
+    Host(String),
+    /// An IPv4 network with its host bits cleared.
+    Network {
+        address: Ipv4Addr,
+        prefix: u8,
+    },
+    /// An inclusive IPv4 range, `start <= end`.
+    Range {
+        start: Ipv4Addr,
+        end: Ipv4Addr,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum TargetError {
+    #[error("target cannot be empty")]
+    Empty,
+    #[error("\"{0}\" is not a valid IPv4 address")]
+    Ipv4(String),
+    #[error("\"{0}\" is not a valid IPv6 address")]
+    Ipv6(String),
+    #[error("\"{name}\" is not a valid hostname: {reason}")]
+    Hostname { name: String, reason: &'static str },
+    #[error("prefix length must be between 0 and 32, got \"{0}\"")]
+    Prefix(String),
+    #[error("leave out the port and enter \"{0}\" alone")]
+    Port(String),
+    #[error("range {start}-{end} ends before it starts")]
+    Backwards { start: Ipv4Addr, end: Ipv4Addr },
+    #[error("{0} covers several addresses; enter a single host")]
+    NotAHost(String),
+    #[error("enter an IPv4 network, range or address such as 192.168.1.0/24 or 192.168.1.10-50")]
+    NotANetwork,
+}
+
+impl Target {
+    /// Any supported form.
+    pub fn parse(input: &str) -> Result<Self, TargetError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(TargetError::Empty);
+        }
+        if let Some((_, rest)) = input.split_once("://") {
+            return parse_url_host(rest);
+        }
+        if let Some((address, prefix)) = input.split_once('/') {
+            return parse_network(address, prefix);
+        }
+        if let Some((start, end)) = input.split_once('-')
+            && let Ok(start) = start.trim().parse::<Ipv4Addr>()
+        {
+            return parse_range(start, end.trim());
+        }
+        parse_single(input)
+    }
+
+    /// One host, for ping, trace and port scan. A /32 or a one-address range
+    /// counts as its address.
+    pub fn parse_host(input: &str) -> Result<Self, TargetError> {
+        match Self::parse(input)? {
+            target @ (Self::Address(_) | Self::Host(_)) => Ok(target),
+            target => match target.ipv4_bounds() {
+                Some((start, end)) if start == end => Ok(Self::Address(IpAddr::V4(start))),
+                _ => Err(TargetError::NotAHost(target.to_string())),
+            },
+        }
+    }
+
+    /// A set of IPv4 addresses, for the scanner. A lone address is its /32.
+    pub fn parse_hosts(input: &str) -> Result<Self, TargetError> {
+        match Self::parse(input)? {
+            Self::Address(IpAddr::V4(address)) => Ok(Self::Network {
+                address,
+                prefix: 32,
+            }),
+            Self::Address(IpAddr::V6(_)) | Self::Host(_) => Err(TargetError::NotANetwork),
+            target => Ok(target),
+        }
+    }
+
+    /// First and last IPv4 address covered.
+    pub fn ipv4_bounds(&self) -> Option<(Ipv4Addr, Ipv4Addr)> {
+        match *self {
+            Self::Address(IpAddr::V4(address)) => Some((address, address)),
+            Self::Network { address, prefix } => {
+                let start = u32::from(address);
+                Some((address, Ipv4Addr::from(start | !prefix_mask(prefix))))
+            }
+            Self::Range { start, end } => Some((start, end)),
+            Self::Address(IpAddr::V6(_)) | Self::Host(_) => None,
+        }
+    }
+
+    /// Smallest network holding every address covered, as address and
+    /// prefix.
+    pub fn enclosing_network(&self) -> Option<(Ipv4Addr, u8)> {
+        let (start, end) = self.ipv4_bounds()?;
+        let prefix = (u32::from(start) ^ u32::from(end)).leading_zeros() as u8;
+        Some((
+            Ipv4Addr::from(u32::from(start) & prefix_mask(prefix)),
+            prefix,
+        ))
+    }
+
+    /// Addresses worth probing: a network below /31 leaves out its network
+    /// and broadcast addresses, a range keeps every address it names.
+    pub fn ipv4_hosts(&self) -> impl Iterator<Item = Ipv4Addr> + use<> {
+        let (start, end) = match (self.ipv4_bounds(), self) {
+            (Some((start, end)), Self::Network { prefix, .. }) if *prefix < 31 => {
+                (u32::from(start) + 1, u32::from(end) - 1)
+            }
+            (Some((start, end)), _) => (u32::from(start), u32::from(end)),
+            (None, _) => (1, 0),
+        };
+        (start..=end).map(Ipv4Addr::from)
+    }
+
+    /// Number of addresses [`Self::ipv4_hosts`] yields.
+    pub fn host_count(&self) -> u64 {
+        match (self.ipv4_bounds(), self) {
+            (Some((start, end)), Self::Network { prefix, .. }) if *prefix < 31 => {
+                u64::from(u32::from(end) - u32::from(start)) - 1
+            }
+            (Some((start, end)), _) => u64::from(u32::from(end) - u32::from(start)) + 1,
+            (None, _) => 0,
+        }
+    }
+}
+
+/// The normalized spelling, which [`Target::parse`] reads back unchanged.
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Host(name) => f.write_str(name),
+            Self::Network { address, prefix } => write!(f, "{address}/{prefix}"),
+            Self::Range { start, end } => write!(f, "{start}-{end}"),
+        }
+    }
+}
+
+const fn prefix_mask(prefix: u8) -> u32 {
+    match prefix {
+        0 => 0,
+        prefix => u32::MAX << (32 - prefix as u32),
+    }
+}
+
+/// `user@host:port/path?query` after the scheme: only the host is kept.
+fn parse_url_host(rest: &str) -> Result<Target, TargetError> {
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if let Some(bracketed) = host.strip_prefix('[') {
+        bracketed
+            .split_once(']')
+            .map_or(host, |(address, _)| address)
+    } else {
+        host.split_once(':').map_or(host, |(name, _)| name)
+    };
+    if host.is_empty() {
+        return Err(TargetError::Hostname {
+            name: rest.into(),
+            reason: "the URL has no host",
+        });
+    }
+    parse_single(host)
+}
+
+fn parse_network(address: &str, prefix: &str) -> Result<Target, TargetError> {
+    let (address, prefix) = (address.trim(), prefix.trim());
+    if address.contains(':') {
+        return Err(match address.parse::<Ipv6Addr>() {
+            Ok(_) => TargetError::NotANetwork,
+            Err(_) => TargetError::Ipv6(address.into()),
+        });
+    }
+    let address = address
+        .parse::<Ipv4Addr>()
+        .map_err(|_| TargetError::Ipv4(address.into()))?;
+    let prefix = prefix
+        .parse::<u8>()
+        .ok()
+        .filter(|prefix| *prefix <= 32)
+        .ok_or_else(|| TargetError::Prefix(prefix.into()))?;
+    Ok(Target::Network {
+        address: Ipv4Addr::from(u32::from(address) & prefix_mask(prefix)),
+        prefix,
+    })
+}
+
+/// `a.b.c.d-e.f.g.h`, or `a.b.c.d-h` for a range within the last octet.
+fn parse_range(start: Ipv4Addr, end: &str) -> Result<Target, TargetError> {
+    let end = match (end.parse::<Ipv4Addr>(), end.parse::<u8>()) {
+        (Ok(end), _) => end,
+        (_, Ok(last)) => {
+            let [a, b, c, _] = start.octets();
+            Ipv4Addr::new(a, b, c, last)
+        }
+        _ => return Err(TargetError::Ipv4(end.into())),
+    };
+    if end < start {
+        return Err(TargetError::Backwards { start, end });
+    }
+    Ok(Target::Range { start, end })
+}
+
+/// An address or a hostname.
+fn parse_single(input: &str) -> Result<Target, TargetError> {
+    if let Some(inner) = input.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').unwrap_or(inner);
+        return inner
+            .parse::<Ipv6Addr>()
+            .map(|address| Target::Address(IpAddr::V6(address)))
+            .map_err(|_| TargetError::Ipv6(inner.into()));
+    }
+    if let Ok(address) = input.parse::<IpAddr>() {
+        return Ok(Target::Address(address));
+    }
+    if let Some((host, port)) = input.split_once(':') {
+        if !port.contains(':') && port.parse::<u16>().is_ok() {
+            return Err(TargetError::Port(host.into()));
+        }
+        return Err(TargetError::Ipv6(input.into()));
+    }
+    if input.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(TargetError::Ipv4(input.into()));
+    }
+    let name = input.strip_suffix('.').unwrap_or(input);
+    let invalid = |reason| {
+        Err(TargetError::Hostname {
+            name: input.into(),
+            reason,
+        })
+    };
+    if name.len() > MAX_HOSTNAME_LEN {
+        return invalid("it is longer than 253 characters");
+    }
+    for label in name.split('.') {
+        if label.is_empty() {
+            return invalid("it has an empty label");
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return invalid("a label is longer than 63 characters");
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return invalid("a label starts or ends with a hyphen");
+        }
+        // Non-ASCII letters pass for internationalized names; the resolver
+        // has the final say.
+        if !label
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return invalid("only letters, digits, '-' and '_' are allowed");
+        }
+    }
+    Ok(Target::Host(name.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(input: &str) -> String {
+        Target::parse(input).unwrap().to_string()
+    }
+
+    #[test]
+    fn every_form_normalizes_to_one_spelling() {
+        assert_eq!(parsed(" 192.168.1.5 "), "192.168.1.5");
+        assert_eq!(parsed("[2001:db8::1]"), "2001:db8::1");
+        assert_eq!(parsed("Router.lan."), "Router.lan");
+        assert_eq!(parsed("192.168.1.77/24"), "192.168.1.0/24");
+        assert_eq!(parsed("10.0.0.10-20"), "10.0.0.10-10.0.0.20");
+        assert_eq!(parsed("10.0.0.250-10.0.1.5"), "10.0.0.250-10.0.1.5");
+        assert_eq!(parsed("https://user@example.com:8443/a?b"), "example.com");
+        assert_eq!(parsed("http://[2001:db8::1]:8080/"), "2001:db8::1");
+        for input in ["192.168.1.0/24", "10.0.0.10-10.0.0.20", "example.com"] {
+            assert_eq!(parsed(&parsed(input)), parsed(input));
+        }
+    }
+
+    #[test]
+    fn typos_are_rejected_with_the_reason() {
+        let error = |input| Target::parse(input).unwrap_err();
+        assert_eq!(error("  "), TargetError::Empty);
+        assert_eq!(
+            error("192.168.1.300"),
+            TargetError::Ipv4("192.168.1.300".into())
+        );
+        assert_eq!(error("10.0.0.0/33"), TargetError::Prefix("33".into()));
+        assert_eq!(error("fe80::/64"), TargetError::NotANetwork);
+        assert_eq!(
+            error("example.com:443"),
+            TargetError::Port("example.com".into())
+        );
+        assert!(matches!(
+            error("10.0.0.20-10"),
+            TargetError::Backwards { .. }
+        ));
+        assert!(matches!(
+            error("bad_host-.lan"),
+            TargetError::Hostname { .. }
+        ));
+        assert!(matches!(error("a..b"), TargetError::Hostname { .. }));
+        assert!(matches!(error("http://"), TargetError::Hostname { .. }));
+        assert_eq!(
+            error("10.0.0.0/33").to_string(),
+            "prefix length must be between 0 and 32, got \"33\""
+        );
+    }
+
+    #[test]
+    fn single_host_and_scan_set_views_reject_the_other_kind() {
+        assert_eq!(
+            Target::parse_host("10.0.0.7/32"),
+            Ok(Target::Address("10.0.0.7".parse().unwrap()))
+        );
+        assert_eq!(
+            Target::parse_host("10.0.0.0/24"),
+            Err(TargetError::NotAHost("10.0.0.0/24".into()))
+        );
+        assert_eq!(
+            Target::parse_hosts("example.com"),
+            Err(TargetError::NotANetwork)
+        );
+
+        let network = Target::parse_hosts("192.168.1.0/30").unwrap();
+        assert_eq!(network.host_count(), 2);
+        assert_eq!(
+            network.ipv4_hosts().collect::<Vec<_>>(),
+            [Ipv4Addr::new(192, 168, 1, 1), Ipv4Addr::new(192, 168, 1, 2)]
+        );
+        let range = Target::parse_hosts("192.168.1.254-192.168.2.1").unwrap();
+        assert_eq!(range.host_count(), 4);
+        assert_eq!(range.ipv4_hosts().count(), 4);
+        assert_eq!(
+            range.enclosing_network(),
+            Some((Ipv4Addr::new(192, 168, 0, 0), 22))
+        );
+        assert_eq!(Target::parse_hosts("10.0.0.1").unwrap().host_count(), 1);
+        assert_eq!(
+            Target::parse_hosts("0.0.0.0/0").unwrap().host_count(),
+            (1 << 32) - 2
+        );
+    }
+}
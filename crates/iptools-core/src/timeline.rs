@@ -0,0 +1,203 @@
+//! Cross-tool timeline.
+//!
+//! Each tool keeps its own history, counted in samples rather than time, so
+//! "latency spiked exactly when the AP roamed" is hard to see from any one
+//! of them. The timeline stamps what the tools report with the session clock
+//! ([`AppModel::elapsed_ms`](crate::AppModel::elapsed_ms)) as it arrives:
+//! ping round trips and losses, Wi-Fi roams and interface changes from the
+//! network change log, and finished speed tests, so the view can lay them
+//! on one axis. It covers this session only.
+
+use crate::{LanSpeedSummary, Language, NetworkEvent, NetworkEventKind, SpeedSummary};
+
+/// Ping replies kept: two hours at one probe a second.
+pub const TIMELINE_PING_LIMIT: usize = 7_200;
+/// Other marks kept.
+pub const TIMELINE_MARK_LIMIT: usize = 500;
+/// Widths of the window the view shows, narrowest first.
+pub const TIMELINE_SPANS_MS: [u64; 4] = [60_000, 300_000, 900_000, 3_600_000];
+
+/// One row of the view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineLane {
+    /// Ping losses, under the latency chart.
+    Loss,
+    Wifi,
+    /// Interfaces going up, down, in or out, and address, gateway and public
+    /// IP changes.
+    Interfaces,
+    Speed,
+}
+
+impl TimelineLane {
+    pub const ALL: [Self; 4] = [Self::Loss, Self::Wifi, Self::Interfaces, Self::Speed];
+
+    pub const fn label(self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::Loss, Language::Zh) => "Ping 丢包",
+            (Self::Wifi, Language::Zh) => "Wi-Fi",
+            (Self::Interfaces, Language::Zh) => "网卡",
+            (Self::Speed, Language::Zh) => "测速",
+            (Self::Loss, Language::En) => "Ping loss",
+            (Self::Wifi, Language::En) => "Wi-Fi",
+            (Self::Interfaces, Language::En) => "Interfaces",
+            (Self::Speed, Language::En) => "Speed tests",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineKind {
+    /// An entry of the network change log, roams included.
+    Network(NetworkEvent),
+    PublicSpeed(SpeedSummary),
+    LanSpeed(LanSpeedSummary),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineMark {
+    /// Session clock when it was reported.
+    pub at_ms: u64,
+    /// Local wall-clock time, `YYYY-MM-DD HH:MM:SS`.
+    pub at: String,
+    pub kind: TimelineKind,
+}
+
+impl TimelineMark {
+    pub fn lane(&self) -> TimelineLane {
+        match &self.kind {
+            TimelineKind::Network(event) if event.kind == NetworkEventKind::WifiRoamed => {
+                TimelineLane::Wifi
+            }
+            TimelineKind::Network(_) => TimelineLane::Interfaces,
+            TimelineKind::PublicSpeed(_) | TimelineKind::LanSpeed(_) => TimelineLane::Speed,
+        }
+    }
+}
+
+/// A ping probe on the session clock; `None` when it was lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelinePing {
+    pub at_ms: u64,
+    pub latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineState {
+    /// Oldest first.
+    pub pings: Vec<TimelinePing>,
+    /// Oldest first.
+    pub marks: Vec<TimelineMark>,
+    /// Index into [`TIMELINE_SPANS_MS`]; the default shows five minutes.
+    pub span: usize,
+}
+
+impl Default for TimelineState {
+    fn default() -> Self {
+        Self {
+            pings: Vec::new(),
+            marks: Vec::new(),
+            span: 1,
+        }
+    }
+}
+
+impl TimelineState {
+    pub fn record_ping(&mut self, at_ms: u64, latency_ms: Option<u64>) {
+        self.pings.push(TimelinePing { at_ms, latency_ms });
+        if self.pings.len() > TIMELINE_PING_LIMIT {
+            let excess = self.pings.len() - TIMELINE_PING_LIMIT;
+            self.pings.drain(..excess);
+        }
+    }
+
+    pub fn record(&mut self, at_ms: u64, at: &str, kind: TimelineKind) {
+        self.marks.push(TimelineMark {
+            at_ms,
+            at: at.to_string(),
+            kind,
+        });
+        if self.marks.len() > TIMELINE_MARK_LIMIT {
+            let excess = self.marks.len() - TIMELINE_MARK_LIMIT;
+            self.marks.drain(..excess);
+        }
+    }
+
+    pub fn span_ms(&self) -> u64 {
+        TIMELINE_SPANS_MS[self.span.min(TIMELINE_SPANS_MS.len() - 1)]
+    }
+
+    /// Show a wider (`true`) or narrower window.
+    pub fn zoom(&mut self, wider: bool) {
+        self.span = if wider {
+            (self.span + 1).min(TIMELINE_SPANS_MS.len() - 1)
+        } else {
+            self.span.saturating_sub(1)
+        };
+    }
+
+    /// Split the window ending at `now_ms` into `columns` equal slices and
+    /// give each the slowest reply in it and whether a probe was lost there.
+    pub fn ping_columns(&self, now_ms: u64, columns: usize) -> Vec<(Option<u64>, bool)> {
+        let mut slices = vec![(None, false); columns];
+        for ping in &self.pings {
+            let Some(column) = self.column(ping.at_ms, now_ms, columns) else {
+                continue;
+            };
+            let (slowest, lost) = &mut slices[column];
+            match ping.latency_ms {
+                Some(latency) => {
+                    *slowest = Some(slowest.map_or(latency, |max: u64| max.max(latency)))
+                }
+                None => *lost = true,
+            }
+        }
+        slices
+    }
+
+    /// Column of the window ending at `now_ms` that `at_ms` falls in, if it
+    /// is inside.
+    pub fn column(&self, at_ms: u64, now_ms: u64, columns: usize) -> Option<usize> {
+        let span = self.span_ms();
+        let start = now_ms.saturating_sub(span);
+        if columns == 0 || at_ms < start || at_ms > now_ms {
+            return None;
+        }
+        let offset = u128::from(at_ms - start) * columns as u128 / u128::from(span);
+        Some((offset as usize).min(columns - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_land_in_the_column_of_their_time() {
+        let mut timeline = TimelineState {
+            span: 0,
+            ..TimelineState::default()
+        };
+        timeline.record_ping(140_000, Some(20));
+        timeline.record_ping(140_500, Some(180));
+        timeline.record_ping(141_000, None);
+        timeline.record_ping(159_999, Some(25));
+        timeline.record_ping(30_000, Some(999));
+
+        // One minute over 60 columns: a column a second, ending at 160 s.
+        let columns = timeline.ping_columns(160_000, 60);
+        assert_eq!(columns[0], (None, false));
+        assert_eq!(columns[40], (Some(180), false), "the slowest of the second");
+        assert_eq!(columns[41], (None, true));
+        assert_eq!(columns[59], (Some(25), false));
+        assert_eq!(timeline.column(30_000, 160_000, 60), None);
+
+        timeline.zoom(true);
+        timeline.zoom(true);
+        assert_eq!(timeline.span_ms(), 900_000);
+        timeline.zoom(false);
+        timeline.zoom(false);
+        timeline.zoom(false);
+        assert_eq!(timeline.span_ms(), 60_000);
+    }
+}
@@ -0,0 +1,104 @@
+//! A second trace run beside the first, to find where two paths part.
+//!
+//! Filling in a compare target or a second interface makes the trace tool
+//! start two jobs: the usual one, and a [`TraceComparison`] towards the other
+//! target or out of the other interface. Hops line up by TTL, and
+//! [`divergence`] names the first one where the two paths answer from
+//! different routers.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{JobId, RuntimeError, TraceHop, TraceRequest};
+
+/// The second run of a comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceComparison {
+    pub request: TraceRequest,
+    /// Running job; `None` once it has finished, failed or been stopped.
+    pub job: Option<JobId>,
+    pub hops: Vec<TraceHop>,
+    pub error: Option<RuntimeError>,
+}
+
+impl TraceComparison {
+    pub fn new(job: JobId, request: TraceRequest) -> Self {
+        Self {
+            request,
+            job: Some(job),
+            hops: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+/// Where two paths split, judged on the hops both sides heard from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceDivergence {
+    /// Every hop that answered on both sides was the same router, through
+    /// this TTL.
+    Shared(u8),
+    /// The first TTL answered by different routers.
+    At(u8),
+}
+
+/// A silent hop on either side proves nothing, so it neither splits the
+/// paths nor extends the shared part. `None` until both sides have a hop
+/// answered in common.
+pub fn divergence(left: &[TraceHop], right: &[TraceHop]) -> Option<TraceDivergence> {
+    let mut shared = None;
+    for hop in left {
+        let Some(address) = &hop.address else {
+            continue;
+        };
+        let Some(other) = right
+            .iter()
+            .find(|other| other.ttl == hop.ttl)
+            .and_then(|other| other.address.as_ref())
+        else {
+            continue;
+        };
+        if address != other {
+            return Some(TraceDivergence::At(hop.ttl));
+        }
+        shared = Some(TraceDivergence::Shared(hop.ttl));
+    }
+    shared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hops(addresses: &[Option<&str>]) -> Vec<TraceHop> {
+        addresses
+            .iter()
+            .zip(1..)
+            .map(|(address, ttl)| TraceHop {
+                ttl,
+                address: address.map(str::to_string),
+                hostname: None,
+                latency_ms: address.map(|_| u64::from(ttl) * 3),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn paths_split_at_the_first_router_that_differs() {
+        let gateway = Some("192.168.1.1");
+        let isp = Some("100.64.0.1");
+        let left = hops(&[gateway, None, isp, Some("203.0.113.1")]);
+        let right = hops(&[gateway, Some("10.0.0.1"), isp, Some("198.51.100.1")]);
+        assert_eq!(divergence(&left, &right), Some(TraceDivergence::At(4)));
+        assert_eq!(
+            divergence(&left[..3], &right),
+            Some(TraceDivergence::Shared(3)),
+            "a silent hop does not split the paths"
+        );
+        assert_eq!(divergence(&left, &[]), None);
+        assert_eq!(
+            divergence(&hops(&[gateway]), &hops(&[Some("192.168.8.1")])),
+            Some(TraceDivergence::At(1)),
+            "two uplinks differ from the first hop"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Opt-in release check.
+//!
+//! When enabled, the latest published release is looked up once at startup
+//! (and again when the setting is switched on). A release newer than this
+//! build shows a marker in the tab bar that opens its notes and download
+//! link; failures are silent, since nothing on screen depends on them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::JobId;
+
+/// Version of this build, compared against the latest release tag.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Release notes lines shown in the popup; the rest is on the release page.
+pub const RELEASE_NOTES_LINES: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseInfo {
+    /// Tag as published, such as `v0.5.0`.
+    pub version: String,
+    /// Release page with the changelog and downloads.
+    pub url: String,
+    /// Release notes, usually Markdown.
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UpdateState {
+    pub job: Option<JobId>,
+    /// Set only when the latest release is newer than [`CURRENT_VERSION`].
+    pub available: Option<ReleaseInfo>,
+}
+
+/// `major.minor.patch` of a tag such as `v1.2.3`. Pre-release and malformed
+/// tags yield `None` and are never offered.
+fn parse_version(tag: &str) -> Option<(u64, u64, u64)> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+    let mut parts = tag.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (
+        parts.next()??,
+        parts.next()??,
+        parts.next().unwrap_or(Some(0))?,
+    );
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether `candidate` is a release newer than `current`.
+pub fn is_newer_version(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+/// The first `limit` meaningful lines of Markdown release notes, with
+/// heading marks, list bullets and emphasis stripped.
+pub fn release_notes_summary(notes: &str, limit: usize) -> Vec<String> {
+    notes
+        .lines()
+        .map(|line| {
+            let line = line.trim().trim_start_matches('#').trim();
+            let line = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .map_or_else(|| line.to_string(), |item| format!("• {}", item.trim()));
+            line.replace("**", "").replace('`', "")
+        })
+        .filter(|line| !line.is_empty())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_strictly_newer_stable_releases_are_offered() {
+        assert!(is_newer_version("v0.5.0", "0.4.2"));
+        assert!(is_newer_version("0.4.10", "0.4.9"));
+        assert!(is_newer_version("v1.0", "0.9.9"));
+        assert!(!is_newer_version("v0.4.2", "0.4.2"));
+        assert!(!is_newer_version("v0.4.1", "0.4.2"));
+        assert!(!is_newer_version("v0.5.0-beta.1", "0.4.2"));
+        assert!(!is_newer_version("nightly", "0.4.2"));
+    }
+
+    #[test]
+    fn release_notes_are_flattened_to_plain_lines() {
+        let notes = "## What's new\r\n\r\n- **Scanner**: faster ARP\n* Fix `ping` on Windows\n\nFull changelog";
+        assert_eq!(
+            release_notes_summary(notes, 3),
+            [
+                "What's new",
+                "• Scanner: faster ARP",
+                "• Fix ping on Windows"
+            ]
+        );
+    }
+}
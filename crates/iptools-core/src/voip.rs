@@ -0,0 +1,283 @@
+//! RTP-style test stream and call-quality scoring for the VoIP probe.
+//!
+//! ICMP jitter says little about a call: routers deprioritise ping, and a
+//! handful of echoes a second never sees the queueing a 50 packet/s voice
+//! stream does. The probe sends real-sized RTP packets at the codec's
+//! packet rate, times each echo and rates the result with a simplified
+//! ITU-T G.107 E-model: delay (including the jitter buffer) and loss lower
+//! the R factor, which maps onto a 1–4.5 mean opinion score.
+
+use std::collections::HashSet;
+
+use crate::{VoipCodec, VoipSample};
+
+/// One voice frame every 20 ms.
+pub const VOIP_PACKET_INTERVAL_MS: u64 = 20;
+const RTP_HEADER_BYTES: usize = 12;
+/// Opens the payload of every test packet, so stray RTP on the port is
+/// told apart from our echoes.
+const VOIP_MAGIC: &[u8; 4] = b"IPTV";
+
+impl VoipCodec {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::G711 => "G.711",
+            Self::G729 => "G.729",
+        }
+    }
+
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::G711 => Self::G729,
+            Self::G729 => Self::G711,
+        }
+    }
+
+    /// RTP payload type.
+    const fn payload_type(self) -> u8 {
+        match self {
+            Self::G711 => 0,
+            Self::G729 => 18,
+        }
+    }
+
+    /// Payload bytes of one 20 ms frame.
+    pub const fn payload_bytes(self) -> usize {
+        match self {
+            Self::G711 => 160,
+            Self::G729 => 20,
+        }
+    }
+
+    /// Equipment impairment `Ie` and packet-loss robustness `Bpl` from
+    /// ITU-T G.113, both with packet loss concealment.
+    const fn impairment(self) -> (f64, f64) {
+        match self {
+            Self::G711 => (0.0, 25.1),
+            Self::G729 => (11.0, 19.0),
+        }
+    }
+
+    /// Frame plus look-ahead the encoder adds to the mouth-to-ear delay.
+    const fn coding_delay_ms(self) -> f64 {
+        match self {
+            Self::G711 => 20.0,
+            Self::G729 => 25.0,
+        }
+    }
+}
+
+/// An RTP packet carrying `sequence` and the send time in microseconds
+/// since the stream started.
+pub fn encode_voip_packet(codec: VoipCodec, ssrc: u32, sequence: u16, sent_us: u64) -> Vec<u8> {
+    let mut packet = vec![0u8; RTP_HEADER_BYTES + codec.payload_bytes()];
+    packet[0] = 0x80;
+    packet[1] = codec.payload_type();
+    packet[2..4].copy_from_slice(&sequence.to_be_bytes());
+    let timestamp = u32::from(sequence).wrapping_mul(160);
+    packet[4..8].copy_from_slice(&timestamp.to_be_bytes());
+    packet[8..12].copy_from_slice(&ssrc.to_be_bytes());
+    packet[12..16].copy_from_slice(VOIP_MAGIC);
+    packet[16..24].copy_from_slice(&sent_us.to_be_bytes());
+    packet
+}
+
+/// Sequence number and send time of one of our own packets coming back;
+/// anything else on the socket is ignored.
+pub fn decode_voip_packet(ssrc: u32, packet: &[u8]) -> Option<(u16, u64)> {
+    if packet.len() < 24
+        || packet[0] != 0x80
+        || packet[8..12] != ssrc.to_be_bytes()
+        || &packet[12..16] != VOIP_MAGIC
+    {
+        return None;
+    }
+    let sequence = u16::from_be_bytes([packet[2], packet[3]]);
+    let sent_us = u64::from_be_bytes(packet[16..24].try_into().ok()?);
+    Some((sequence, sent_us))
+}
+
+/// Echo bookkeeping for one probe stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoipStats {
+    sent: u64,
+    seen: HashSet<u16>,
+    highest: Option<u16>,
+    out_of_order: u64,
+    rtt_total_ms: f64,
+    last_rtt_ms: Option<f64>,
+    jitter_ms: Option<f64>,
+}
+
+impl VoipStats {
+    /// One packet sent, or on a reflector one packet echoed.
+    pub fn record_sent(&mut self) {
+        self.sent += 1;
+    }
+
+    /// Count an echo; duplicates are dropped like a jitter buffer would.
+    pub fn record_echo(&mut self, sequence: u16, rtt_ms: f64) {
+        if !self.seen.insert(sequence) {
+            return;
+        }
+        match self.highest {
+            Some(highest) if sequence < highest => self.out_of_order += 1,
+            _ => self.highest = Some(sequence),
+        }
+        self.rtt_total_ms += rtt_ms;
+        if let Some(last) = self.last_rtt_ms {
+            let jitter = self.jitter_ms.unwrap_or(0.0);
+            self.jitter_ms = Some(jitter + ((rtt_ms - last).abs() - jitter) / 16.0);
+        }
+        self.last_rtt_ms = Some(rtt_ms);
+    }
+
+    /// `settled` once the last echo had time to arrive, so every missing
+    /// packet counts as lost rather than only gaps below the newest echo.
+    pub fn sample(&self, elapsed_ms: u64, settled: bool) -> VoipSample {
+        let received = self.seen.len() as u64;
+        let lost = if settled {
+            self.sent.saturating_sub(received)
+        } else {
+            self.highest.map_or(0, |highest| {
+                (u64::from(highest) + 1).saturating_sub(received)
+            })
+        };
+        VoipSample {
+            elapsed_ms,
+            sent: self.sent,
+            received,
+            lost,
+            out_of_order: self.out_of_order,
+            rtt_ms: (received > 0).then(|| self.rtt_total_ms / received as f64),
+            jitter_ms: self.jitter_ms,
+        }
+    }
+
+    /// Reflector counters: what came in went straight back out.
+    pub fn reflected(&self, elapsed_ms: u64) -> VoipSample {
+        VoipSample {
+            elapsed_ms,
+            sent: self.sent,
+            received: self.sent,
+            ..VoipSample::default()
+        }
+    }
+}
+
+/// How a call over the measured path would sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoipScore {
+    /// Transmission rating, 0–100.
+    pub r_factor: f64,
+    /// Mean opinion score, 1–4.5.
+    pub mos: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoipGrade {
+    Excellent,
+    Good,
+    Fair,
+    Poor,
+    Bad,
+}
+
+impl VoipScore {
+    /// Bands of ITU-T G.109 by R factor.
+    pub fn grade(self) -> VoipGrade {
+        match self.r_factor {
+            r if r >= 90.0 => VoipGrade::Excellent,
+            r if r >= 80.0 => VoipGrade::Good,
+            r if r >= 70.0 => VoipGrade::Fair,
+            r if r >= 60.0 => VoipGrade::Poor,
+            _ => VoipGrade::Bad,
+        }
+    }
+}
+
+impl VoipSample {
+    /// Share of packets lost, in percent.
+    pub fn loss_percent(&self) -> f64 {
+        let expected = self.received + self.lost;
+        if expected == 0 {
+            0.0
+        } else {
+            self.lost as f64 * 100.0 / expected as f64
+        }
+    }
+
+    /// E-model score for `codec`; `None` until an echo came back.
+    pub fn score(&self, codec: VoipCodec) -> Option<VoipScore> {
+        let rtt = self.rtt_ms?;
+        let jitter = self.jitter_ms.unwrap_or(0.0);
+        // One way is half the round trip; the receiver's jitter buffer
+        // holds about two jitters' worth of audio on top.
+        let delay = rtt / 2.0 + 2.0 * jitter + codec.coding_delay_ms();
+        let delay_impairment = 0.024 * delay + 0.11 * (delay - 177.3).max(0.0);
+        let (ie, bpl) = codec.impairment();
+        let loss = self.loss_percent();
+        let loss_impairment = ie + (95.0 - ie) * loss / (loss + bpl);
+        let r_factor = (93.2 - delay_impairment - loss_impairment).clamp(0.0, 100.0);
+        let mos = 1.0 + 0.035 * r_factor + r_factor * (r_factor - 60.0) * (100.0 - r_factor) * 7e-6;
+        Some(VoipScore {
+            r_factor,
+            mos: mos.clamp(1.0, 4.5),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echoes_are_counted_once_and_scored_by_the_e_model() {
+        let packet = encode_voip_packet(VoipCodec::G711, 0xfeed, 7, 140_000);
+        assert_eq!(packet.len(), 172);
+        assert_eq!(decode_voip_packet(0xfeed, &packet), Some((7, 140_000)));
+        assert_eq!(decode_voip_packet(0xbeef, &packet), None);
+        assert_eq!(decode_voip_packet(0xfeed, b"\x80short"), None);
+
+        let mut stats = VoipStats::default();
+        for _ in 0..6 {
+            stats.record_sent();
+        }
+        for (sequence, rtt) in [(0, 20.0), (1, 22.0), (3, 20.0), (2, 30.0), (3, 20.0)] {
+            stats.record_echo(sequence, rtt);
+        }
+        let running = stats.sample(120, false);
+        assert_eq!(
+            (running.received, running.lost, running.out_of_order),
+            (4, 0, 1)
+        );
+        assert_eq!(running.rtt_ms, Some(23.0));
+        assert!(running.jitter_ms.is_some_and(|jitter| jitter > 0.0));
+        let settled = stats.sample(1_200, true);
+        assert_eq!(settled.lost, 2);
+        assert!((settled.loss_percent() - 100.0 / 3.0).abs() < 1e-9);
+
+        let clean = VoipSample {
+            received: 500,
+            rtt_ms: Some(30.0),
+            jitter_ms: Some(1.0),
+            ..VoipSample::default()
+        };
+        let score = clean.score(VoipCodec::G711).unwrap();
+        assert!((4.3..=4.5).contains(&score.mos), "{score:?}");
+        assert_eq!(score.grade(), VoipGrade::Excellent);
+        assert!(clean.score(VoipCodec::G729).unwrap().mos < score.mos);
+
+        let congested = VoipSample {
+            received: 450,
+            lost: 50,
+            rtt_ms: Some(300.0),
+            jitter_ms: Some(40.0),
+            ..VoipSample::default()
+        };
+        let score = congested.score(VoipCodec::G711).unwrap();
+        assert!(score.mos < 3.1, "{score:?}");
+        assert_eq!(score.grade(), VoipGrade::Bad);
+        assert_eq!(VoipSample::default().score(VoipCodec::G711), None);
+    }
+}
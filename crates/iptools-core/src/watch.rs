@@ -0,0 +1,518 @@
+//! Dashboard watch targets: a short list of hosts and URLs checked in the
+//! background and shown as green / amber / red tiles.
+//!
+//! The model schedules a round every [`WATCH_INTERVAL_MS`] (less often in
+//! low-power mode, never in offline mode); the runtime checks each target
+//! once and the last [`WATCH_HISTORY`] results of every target decide its
+//! colour. A target may carry a latency budget, so a link that is slow by
+//! nature (satellite, a far-away VPN) stays green while it is as fast as
+//! usual.
+//!
+//! A target that goes down is listed under Problems. Planned outages stay
+//! quiet: a target's daily maintenance window, or a snooze of every watch
+//! alert for a while, keeps the tile red but raises nothing.
+
+use std::collections::VecDeque;
+
+use crate::{JobId, SettingError, WatchCheck, WatchTarget};
+
+/// At most this many tiles fit the Dashboard.
+pub const MAX_WATCH_TARGETS: usize = 8;
+/// Results kept per target.
+pub const WATCH_HISTORY: usize = 10;
+pub const WATCH_INTERVAL_MS: u64 = 30_000;
+/// Low-power mode checks a quarter as often.
+pub const LOW_POWER_WATCH_INTERVAL_MS: u64 = 120_000;
+pub const WATCH_TIMEOUT_MS: u64 = 3_000;
+/// Answers slower than this turn a tile without a budget amber.
+const WATCH_SLOW_MS: f64 = 200.0;
+/// Space on the Dashboard steps the snooze through these lengths, then off.
+pub const WATCH_SNOOZE_MINUTES: [u32; 3] = [15, 60, 240];
+
+/// How a watch target is checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchProbe {
+    /// One ICMP echo.
+    Icmp(String),
+    Tcp(String, u16),
+    /// `GET`; any status below 400 is up.
+    Http(String),
+}
+
+impl WatchProbe {
+    fn parse(target: &str) -> Option<Self> {
+        if target.starts_with("https://") || target.starts_with("http://") {
+            let host = target.split("://").nth(1)?.split(['/', '?', '#']).next()?;
+            return (!host.is_empty() && !host.contains(char::is_whitespace))
+                .then(|| Self::Http(target.into()));
+        }
+        let host_port = if let Some(rest) = target.strip_prefix('[') {
+            // Bracketed IPv6, with or without a port.
+            let (host, port) = rest.split_once(']')?;
+            Some((host, port.strip_prefix(':')?))
+        } else {
+            target
+                .rsplit_once(':')
+                .filter(|(host, _)| !host.contains(':'))
+        };
+        match host_port {
+            Some((host, port)) => {
+                let port = port.parse::<u16>().ok().filter(|port| *port != 0)?;
+                crate::Target::parse_host(host).ok()?;
+                Some(Self::Tcp(host.into(), port))
+            }
+            None => {
+                crate::Target::parse_host(target).ok()?;
+                Some(Self::Icmp(target.into()))
+            }
+        }
+    }
+}
+
+impl WatchTarget {
+    pub fn probe(&self) -> Option<WatchProbe> {
+        WatchProbe::parse(&self.target)
+    }
+
+    /// The latency above which an answer counts as slow.
+    pub fn slow_ms(&self) -> f64 {
+        self.budget_ms.map_or(WATCH_SLOW_MS, f64::from)
+    }
+
+    pub fn window(&self) -> Option<WatchWindow> {
+        WatchWindow::parse(self.maintenance.as_deref()?)
+    }
+}
+
+/// A daily span of local time, in minutes since midnight; `end` before
+/// `start` runs past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchWindow {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl WatchWindow {
+    pub fn parse(text: &str) -> Option<Self> {
+        let (start, end) = text.split_once('-')?;
+        let window = Self {
+            start: minute_of_day(start)?,
+            end: minute_of_day(end)?,
+        };
+        (window.start != window.end).then_some(window)
+    }
+
+    /// Whether `observed_at` (`YYYY-MM-DD HH:MM:SS`) falls inside; an
+    /// unknown time never does.
+    pub fn contains(self, observed_at: &str) -> bool {
+        let Some(now) = observed_at
+            .rsplit(' ')
+            .next()
+            .and_then(|time| time.get(..5))
+            .and_then(minute_of_day)
+        else {
+            return false;
+        };
+        if self.start < self.end {
+            (self.start..self.end).contains(&now)
+        } else {
+            now >= self.start || now < self.end
+        }
+    }
+}
+
+/// `HH:MM` as minutes since midnight.
+fn minute_of_day(time: &str) -> Option<u16> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours = hours.parse::<u16>().ok().filter(|hours| *hours < 24)?;
+    let minutes = minutes
+        .parse::<u16>()
+        .ok()
+        .filter(|minutes| *minutes < 60)?;
+    Some(hours * 60 + minutes)
+}
+
+impl std::fmt::Display for WatchWindow {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60
+        )
+    }
+}
+
+/// Parse `name=target<budget@HH:MM-HH:MM, …`; a target without a name is
+/// named after itself, and the `<budget` in milliseconds and the
+/// `@maintenance` window are optional. An empty list turns watching off.
+pub fn parse_watch_targets(text: &str) -> Result<Vec<WatchTarget>, SettingError> {
+    let targets = text
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = || SettingError::InvalidWatchTarget(entry.to_string());
+            let (name, target) = match entry.split_once('=') {
+                Some((name, target)) => (name.trim(), target.trim()),
+                None => ("", entry),
+            };
+            // An `@` that does not start a window belongs to a URL.
+            let (target, maintenance) = match target.rsplit_once('@') {
+                Some((target, window)) if window.contains('-') => {
+                    let window = WatchWindow::parse(window).ok_or_else(invalid)?;
+                    (target.trim(), Some(window.to_string()))
+                }
+                _ => (target, None),
+            };
+            let (target, budget_ms) = match target.rsplit_once('<') {
+                Some((target, budget)) => {
+                    let budget = budget.trim();
+                    let budget = budget.strip_suffix("ms").unwrap_or(budget).trim();
+                    let budget = budget
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|budget| *budget > 0)
+                        .ok_or_else(invalid)?;
+                    (target.trim(), Some(budget))
+                }
+                None => (target, None),
+            };
+            if WatchProbe::parse(target).is_none() {
+                return Err(invalid());
+            }
+            Ok(WatchTarget {
+                name: if name.is_empty() { target } else { name }.into(),
+                target: target.into(),
+                budget_ms,
+                maintenance,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if targets.len() > MAX_WATCH_TARGETS {
+        return Err(SettingError::TooManyWatchTargets {
+            max: MAX_WATCH_TARGETS,
+        });
+    }
+    Ok(targets)
+}
+
+pub fn format_watch_targets(targets: &[WatchTarget]) -> String {
+    targets
+        .iter()
+        .map(|target| {
+            let mut entry = if target.name == target.target {
+                target.target.clone()
+            } else {
+                format!("{}={}", target.name, target.target)
+            };
+            if let Some(budget) = target.budget_ms {
+                entry.push_str(&format!("<{budget}"));
+            }
+            if let Some(window) = &target.maintenance {
+                entry.push_str(&format!("@{window}"));
+            }
+            entry
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchHealth {
+    /// Not checked yet.
+    Pending,
+    Up,
+    /// Answering, but over budget or after recent failures.
+    Degraded,
+    Down,
+}
+
+/// One Dashboard tile: a target and its latest results, oldest first.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WatchTile {
+    pub target: WatchTarget,
+    pub checks: VecDeque<WatchCheck>,
+}
+
+impl WatchTile {
+    pub fn last(&self) -> Option<&WatchCheck> {
+        self.checks.back()
+    }
+
+    pub fn health(&self) -> WatchHealth {
+        let Some(last) = self.last() else {
+            return WatchHealth::Pending;
+        };
+        if last.error.is_some() {
+            WatchHealth::Down
+        } else if self.checks.iter().any(|check| check.error.is_some())
+            || last.latency_ms.is_some_and(|ms| ms > self.target.slow_ms())
+        {
+            WatchHealth::Degraded
+        } else {
+            WatchHealth::Up
+        }
+    }
+
+    fn record(&mut self, check: WatchCheck) {
+        if self.checks.len() == WATCH_HISTORY {
+            self.checks.pop_front();
+        }
+        self.checks.push_back(check);
+    }
+}
+
+/// Why a tile raises no alert right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchSilence {
+    Snoozed { left_ms: u64 },
+    Maintenance(WatchWindow),
+}
+
+/// The monitoring engine's state: one tile per configured target and the
+/// round in flight.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WatchState {
+    pub tiles: Vec<WatchTile>,
+    pub job: Option<JobId>,
+    /// `elapsed_ms` at which the next round is due.
+    pub next_check_ms: u64,
+    /// Length of the current snooze, `0` for none.
+    pub snooze_minutes: u32,
+    /// `elapsed_ms` at which the snooze ends.
+    pub snoozed_until_ms: u64,
+}
+
+impl WatchState {
+    pub fn targets(&self) -> Vec<WatchTarget> {
+        self.tiles.iter().map(|tile| tile.target.clone()).collect()
+    }
+
+    /// Replace the target list; targets that stay keep their results.
+    pub fn set_targets(&mut self, targets: Vec<WatchTarget>) {
+        let mut previous = std::mem::take(&mut self.tiles);
+        self.tiles = targets
+            .into_iter()
+            .map(|target| {
+                let checks = previous
+                    .iter_mut()
+                    .find(|tile| tile.target.target == target.target)
+                    .map(|tile| std::mem::take(&mut tile.checks))
+                    .unwrap_or_default();
+                WatchTile { target, checks }
+            })
+            .collect();
+    }
+
+    /// File a result under its target and return the tile's index if it
+    /// has just gone down; results for a target removed since the round
+    /// started are dropped.
+    pub fn record(&mut self, check: WatchCheck) -> Option<usize> {
+        let index = self
+            .tiles
+            .iter()
+            .position(|tile| tile.target.target == check.target.target)?;
+        let tile = &mut self.tiles[index];
+        let was_down = tile.health() == WatchHealth::Down;
+        tile.record(check);
+        (!was_down && tile.health() == WatchHealth::Down).then_some(index)
+    }
+
+    /// Step to the next snooze length from now; past the longest, or once
+    /// the last snooze ran out, start over.
+    pub fn cycle_snooze(&mut self, elapsed_ms: u64) {
+        let current = if elapsed_ms < self.snoozed_until_ms {
+            self.snooze_minutes
+        } else {
+            0
+        };
+        self.snooze_minutes = match WATCH_SNOOZE_MINUTES
+            .iter()
+            .position(|minutes| *minutes == current)
+        {
+            Some(index) => WATCH_SNOOZE_MINUTES.get(index + 1).copied().unwrap_or(0),
+            None => WATCH_SNOOZE_MINUTES[0],
+        };
+        self.snoozed_until_ms = elapsed_ms + u64::from(self.snooze_minutes) * 60_000;
+    }
+
+    pub fn silence(
+        &self,
+        tile: &WatchTile,
+        elapsed_ms: u64,
+        observed_at: &str,
+    ) -> Option<WatchSilence> {
+        if elapsed_ms < self.snoozed_until_ms {
+            return Some(WatchSilence::Snoozed {
+                left_ms: self.snoozed_until_ms - elapsed_ms,
+            });
+        }
+        tile.target
+            .window()
+            .filter(|window| window.contains(observed_at))
+            .map(WatchSilence::Maintenance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_parse_by_shape_and_tiles_turn_amber_then_red() {
+        let targets = parse_watch_targets(
+            "NAS=192.168.1.10, VPN = vpn.example.com:443, [2001:db8::1]:22, Status=https://status.example.com/api",
+        )
+        .unwrap();
+        assert_eq!(
+            targets.iter().map(WatchTarget::probe).collect::<Vec<_>>(),
+            [
+                Some(WatchProbe::Icmp("192.168.1.10".into())),
+                Some(WatchProbe::Tcp("vpn.example.com".into(), 443)),
+                Some(WatchProbe::Tcp("2001:db8::1".into(), 22)),
+                Some(WatchProbe::Http("https://status.example.com/api".into())),
+            ]
+        );
+        assert_eq!(targets[2].name, "[2001:db8::1]:22");
+        assert_eq!(
+            format_watch_targets(&targets),
+            "NAS=192.168.1.10, VPN=vpn.example.com:443, [2001:db8::1]:22, Status=https://status.example.com/api"
+        );
+        assert_eq!(parse_watch_targets(" , ").unwrap(), []);
+        for invalid in [
+            "NAS=",
+            "host:0",
+            "bad host",
+            "ftp://files",
+            "https://",
+            "NAS=10.0.0.1<0",
+            "NAS=10.0.0.1<fast",
+        ] {
+            assert_eq!(
+                parse_watch_targets(invalid),
+                Err(SettingError::InvalidWatchTarget(invalid.into())),
+                "{invalid}"
+            );
+        }
+        assert_eq!(
+            parse_watch_targets(&["10.0.0.1"; 9].join(",")),
+            Err(SettingError::TooManyWatchTargets { max: 8 })
+        );
+
+        let mut state = WatchState::default();
+        state.set_targets(targets);
+        assert_eq!(state.tiles[0].health(), WatchHealth::Pending);
+        let check = |target: &WatchTarget, latency_ms: Option<f64>| WatchCheck {
+            target: target.clone(),
+            latency_ms,
+            error: latency_ms.is_none().then(|| "timed out".into()),
+        };
+        let nas = state.tiles[0].target.clone();
+        state.record(check(&nas, Some(2.0)));
+        assert_eq!(state.tiles[0].health(), WatchHealth::Up);
+        state.record(check(&nas, None));
+        assert_eq!(state.tiles[0].health(), WatchHealth::Down);
+        state.record(check(&nas, Some(2.0)));
+        assert_eq!(state.tiles[0].health(), WatchHealth::Degraded);
+        for _ in 0..WATCH_HISTORY {
+            state.record(check(&nas, Some(3.0)));
+        }
+        assert_eq!(state.tiles[0].checks.len(), WATCH_HISTORY);
+        assert_eq!(state.tiles[0].health(), WatchHealth::Up);
+        state.record(check(&nas, Some(450.0)));
+        assert_eq!(state.tiles[0].health(), WatchHealth::Degraded);
+
+        // A satellite link budgeted at 700 ms is fine at 600 ms; a gateway
+        // budgeted at 5 ms is not fine at 8.
+        let budgeted =
+            parse_watch_targets("Uplink=sat.example.net:443 < 700ms, Gateway=192.168.1.1<5")
+                .unwrap();
+        assert_eq!(budgeted[0].budget_ms, Some(700));
+        assert_eq!(
+            format_watch_targets(&budgeted),
+            "Uplink=sat.example.net:443<700, Gateway=192.168.1.1<5"
+        );
+        let tile = |target: &WatchTarget, latency| WatchTile {
+            target: target.clone(),
+            checks: [check(target, Some(latency))].into(),
+        };
+        assert_eq!(tile(&budgeted[0], 600.0).health(), WatchHealth::Up);
+        assert_eq!(tile(&budgeted[1], 8.0).health(), WatchHealth::Degraded);
+        assert_eq!(tile(&budgeted[1], 4.0).health(), WatchHealth::Up);
+
+        // Only the step into Down is reported.
+        assert!(state.record(check(&nas, None)).is_some());
+        assert!(state.record(check(&nas, None)).is_none());
+        assert!(state.record(check(&nas, Some(2.0))).is_none());
+
+        // Renaming keeps the history; a removed target's late result is
+        // dropped.
+        state.set_targets(parse_watch_targets("Storage=192.168.1.10").unwrap());
+        assert_eq!(state.tiles[0].checks.len(), WATCH_HISTORY);
+        state.record(check(&state.tiles[0].target.clone(), Some(1.0)));
+        let vpn = parse_watch_targets("vpn.example.com:443")
+            .unwrap()
+            .remove(0);
+        assert!(state.record(check(&vpn, None)).is_none());
+        assert_eq!(state.tiles.len(), 1);
+    }
+
+    #[test]
+    fn maintenance_windows_and_snoozes_silence_alerts() {
+        let targets = parse_watch_targets(
+            "Router=192.168.1.1<5@03:00-03:30, Backup=nas.lan@23:30-00:15, Admin=https://admin@router.lan/",
+        )
+        .unwrap();
+        assert_eq!(targets[0].budget_ms, Some(5));
+        assert_eq!(targets[0].maintenance.as_deref(), Some("03:00-03:30"));
+        assert_eq!(targets[2].target, "https://admin@router.lan/");
+        assert_eq!(
+            format_watch_targets(&targets),
+            "Router=192.168.1.1<5@03:00-03:30, Backup=nas.lan@23:30-00:15, Admin=https://admin@router.lan/"
+        );
+        for invalid in [
+            "Router=192.168.1.1@03:00-03:00",
+            "Router=192.168.1.1@25:00-01:00",
+        ] {
+            assert_eq!(
+                parse_watch_targets(invalid),
+                Err(SettingError::InvalidWatchTarget(invalid.into()))
+            );
+        }
+
+        let mut state = WatchState::default();
+        state.set_targets(targets);
+        let (router, backup) = (&state.tiles[0], &state.tiles[1]);
+        assert_eq!(
+            state.silence(router, 0, "2026-10-16 03:10:00"),
+            Some(WatchSilence::Maintenance(WatchWindow {
+                start: 180,
+                end: 210
+            }))
+        );
+        assert_eq!(state.silence(router, 0, "2026-10-16 03:30:00"), None);
+        assert!(state.silence(backup, 0, "2026-10-16 00:00:00").is_some());
+        assert_eq!(state.silence(backup, 0, "2026-10-16 12:00:00"), None);
+        assert_eq!(state.silence(backup, 0, ""), None);
+
+        state.cycle_snooze(1_000);
+        assert_eq!(state.snooze_minutes, 15);
+        let backup = &state.tiles[1];
+        assert_eq!(
+            state.silence(backup, 61_000, "2026-10-16 12:00:00"),
+            Some(WatchSilence::Snoozed { left_ms: 840_000 })
+        );
+        state.cycle_snooze(2_000);
+        state.cycle_snooze(3_000);
+        assert_eq!(state.snooze_minutes, 240);
+        state.cycle_snooze(4_000);
+        assert_eq!((state.snooze_minutes, state.snoozed_until_ms), (0, 4_000));
+        // A snooze that ran out starts over at the shortest length.
+        state.cycle_snooze(5_000);
+        state.cycle_snooze(5_000 + 15 * 60_000);
+        assert_eq!(state.snooze_minutes, 15);
+    }
+}
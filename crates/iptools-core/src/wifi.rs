@@ -0,0 +1,320 @@
+//! Wi-Fi signal history and roams between access points.
+//!
+//! The Wi-Fi monitor samples the association of one wireless adapter every
+//! few seconds. A roam is the BSSID changing between two samples while the
+//! adapter stays associated; each one is kept with the signal on both sides
+//! and, when a ping was running, the sequence number it happened at, so a
+//! latency spike in the ping log can be matched to the moment the client
+//! hopped to another access point.
+//!
+//! Each sample also carries the access points the adapter last heard in a
+//! scan; [`channel_occupancy`] turns them into per-channel load so the
+//! crowded channels and a quieter one to move the AP to can be shown.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WirelessSnapshot;
+
+/// Signal below which a sample counts as weak; most clients start looking
+/// for another access point around here.
+pub const WEAK_RSSI_DBM: i32 = -70;
+pub const WIFI_INTERVAL_SECS_RANGE: (u64, u64) = (1, 60);
+pub const DEFAULT_WIFI_INTERVAL_SECS: u64 = 3;
+
+/// A move from one access point to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WifiRoam {
+    /// Local wall-clock time, `YYYY-MM-DD HH:MM:SS`.
+    pub at: String,
+    pub elapsed_ms: u64,
+    pub ssid: String,
+    pub from_bssid: String,
+    pub to_bssid: String,
+    pub from_channel: u32,
+    pub to_channel: u32,
+    pub rssi_before_dbm: i32,
+    pub rssi_after_dbm: i32,
+    /// The last ping sequence number when a ping was running.
+    pub ping_sequence: Option<u64>,
+}
+
+impl WifiRoam {
+    /// The roam from `previous` to `current`, if the BSSID changed. A
+    /// missing BSSID on either side is a driver that does not report it,
+    /// not a roam.
+    pub fn between(
+        previous: &WirelessSnapshot,
+        current: &WirelessSnapshot,
+        elapsed_ms: u64,
+        at: &str,
+    ) -> Option<Self> {
+        let known = |bssid: &str| !bssid.is_empty() && bssid != "-";
+        if !known(&previous.bssid)
+            || !known(&current.bssid)
+            || previous.bssid.eq_ignore_ascii_case(&current.bssid)
+        {
+            return None;
+        }
+        Some(Self {
+            at: at.to_string(),
+            elapsed_ms,
+            ssid: current.ssid.clone(),
+            from_bssid: previous.bssid.clone(),
+            to_bssid: current.bssid.clone(),
+            from_channel: previous.channel,
+            to_channel: current.channel,
+            rssi_before_dbm: previous.rssi_dbm,
+            rssi_after_dbm: current.rssi_dbm,
+            ping_sequence: None,
+        })
+    }
+
+    /// One-line description for logs.
+    pub fn describe(&self) -> String {
+        format!(
+            "roamed {} (ch {}, {} dBm) → {} (ch {}, {} dBm)",
+            self.from_bssid,
+            self.from_channel,
+            self.rssi_before_dbm,
+            self.to_bssid,
+            self.to_channel,
+            self.rssi_after_dbm
+        )
+    }
+}
+
+/// An access point heard in the adapter's last scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearbyNetwork {
+    /// Empty for hidden networks.
+    pub ssid: String,
+    pub bssid: String,
+    pub channel: u32,
+    pub frequency_mhz: u32,
+    pub rssi_dbm: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WifiBand {
+    TwoPointFourGhz,
+    FiveGhz,
+}
+
+impl WifiBand {
+    pub const ALL: [Self; 2] = [Self::TwoPointFourGhz, Self::FiveGhz];
+
+    pub fn of(frequency_mhz: u32) -> Option<Self> {
+        match frequency_mhz {
+            2_400..=2_500 => Some(Self::TwoPointFourGhz),
+            5_150..=5_895 => Some(Self::FiveGhz),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TwoPointFourGhz => "2.4 GHz",
+            Self::FiveGhz => "5 GHz",
+        }
+    }
+
+    /// Channels shown in the occupancy chart.
+    pub fn channels(self) -> &'static [u32] {
+        match self {
+            Self::TwoPointFourGhz => &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+            Self::FiveGhz => &[36, 40, 44, 48, 149, 153, 157, 161, 165],
+        }
+    }
+
+    /// Channels worth moving an AP to: the three that do not overlap on
+    /// 2.4 GHz, and on 5 GHz the ones clear of radar detection (DFS), which
+    /// can force the AP off the channel without warning.
+    pub fn candidates(self) -> &'static [u32] {
+        match self {
+            Self::TwoPointFourGhz => &[1, 6, 11],
+            Self::FiveGhz => self.channels(),
+        }
+    }
+
+    /// How much a network on `other` interferes with `channel`: 2.4 GHz
+    /// channels are 5 MHz apart but 20 MHz wide, so neighbours up to four
+    /// channels away still overlap. 5 GHz channels do not overlap at 20 MHz.
+    fn overlap(self, channel: u32, other: u32) -> f64 {
+        let distance = channel.abs_diff(other);
+        match self {
+            Self::TwoPointFourGhz if distance < 5 => 1.0 - f64::from(distance) / 5.0,
+            Self::FiveGhz if distance == 0 => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Interference on one channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelLoad {
+    pub channel: u32,
+    /// Networks on exactly this channel.
+    pub networks: u32,
+    /// Overlap-weighted sum over every network, each weighted by how loud it
+    /// is: one at -40 dBm counts 1, one at -90 dBm barely at all.
+    pub load: f64,
+}
+
+/// Load on every channel of `band`, in channel order. On 5 GHz, occupied
+/// channels outside the usual list (DFS) are shown too. `own_bssid` is left
+/// out so the AP being judged does not count against itself.
+pub fn channel_occupancy(
+    nearby: &[NearbyNetwork],
+    band: WifiBand,
+    own_bssid: &str,
+) -> Vec<ChannelLoad> {
+    let others = nearby
+        .iter()
+        .filter(|network| {
+            WifiBand::of(network.frequency_mhz) == Some(band)
+                && !network.bssid.eq_ignore_ascii_case(own_bssid)
+        })
+        .collect::<Vec<_>>();
+    let mut channels = band.channels().to_vec();
+    channels.extend(others.iter().map(|network| network.channel));
+    channels.sort_unstable();
+    channels.dedup();
+    channels
+        .into_iter()
+        .map(|channel| ChannelLoad {
+            channel,
+            networks: others
+                .iter()
+                .filter(|network| network.channel == channel)
+                .count() as u32,
+            load: others
+                .iter()
+                .map(|network| {
+                    let loudness = (f64::from(network.rssi_dbm + 90) / 50.0).clamp(0.05, 1.0);
+                    loudness * band.overlap(channel, network.channel)
+                })
+                .sum(),
+        })
+        .collect()
+}
+
+/// The candidate channel with the least load; the lowest one on a tie.
+pub fn least_congested_channel(loads: &[ChannelLoad], band: WifiBand) -> Option<u32> {
+    loads
+        .iter()
+        .filter(|load| band.candidates().contains(&load.channel))
+        .min_by(|left, right| left.load.total_cmp(&right.load))
+        .map(|load| load.channel)
+}
+
+/// Minimum, mean and maximum RSSI over the associated samples.
+pub fn rssi_range(samples: &[crate::WifiSample]) -> Option<(i32, f64, i32)> {
+    let rssi = samples
+        .iter()
+        .filter_map(|sample| sample.link.as_ref().map(|link| link.rssi_dbm))
+        .collect::<Vec<_>>();
+    let min = *rssi.iter().min()?;
+    let max = *rssi.iter().max()?;
+    Some((
+        min,
+        rssi.iter().map(|value| f64::from(*value)).sum::<f64>() / rssi.len() as f64,
+        max,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WifiSample;
+
+    fn link(bssid: &str, channel: u32, rssi_dbm: i32) -> WirelessSnapshot {
+        WirelessSnapshot {
+            ssid: "HomeLab".into(),
+            bssid: bssid.into(),
+            signal_quality: 0,
+            rssi_dbm,
+            phy_type: "-".into(),
+            wifi_generation: 0,
+            band: "5 GHz".into(),
+            channel,
+            frequency_mhz: 0,
+            rx_rate_mbps: 0,
+            tx_rate_mbps: 0,
+            authentication: "-".into(),
+            cipher: "-".into(),
+        }
+    }
+
+    #[test]
+    fn only_a_changed_known_bssid_is_a_roam() {
+        let first = link("aa:bb:cc:00:00:01", 36, -74);
+        let second = link("aa:bb:cc:00:00:02", 149, -52);
+        let roam = WifiRoam::between(&first, &second, 9_000, "2026-10-16 21:30:00").unwrap();
+        assert_eq!(
+            roam.describe(),
+            "roamed aa:bb:cc:00:00:01 (ch 36, -74 dBm) → aa:bb:cc:00:00:02 (ch 149, -52 dBm)"
+        );
+        assert_eq!(
+            WifiRoam::between(&first, &link("AA:BB:CC:00:00:01", 36, -60), 0, ""),
+            None
+        );
+        assert_eq!(WifiRoam::between(&first, &link("", 36, -60), 0, ""), None);
+
+        let samples = [
+            WifiSample {
+                elapsed_ms: 0,
+                link: Some(first),
+                nearby: Vec::new(),
+            },
+            WifiSample {
+                elapsed_ms: 3_000,
+                link: None,
+                nearby: Vec::new(),
+            },
+            WifiSample {
+                elapsed_ms: 6_000,
+                link: Some(second),
+                nearby: Vec::new(),
+            },
+        ];
+        assert_eq!(rssi_range(&samples), Some((-74, -63.0, -52)));
+        assert_eq!(rssi_range(&samples[1..2]), None);
+    }
+
+    #[test]
+    fn overlapping_neighbours_crowd_a_24_ghz_channel() {
+        let network = |bssid: &str, channel: u32, rssi_dbm| NearbyNetwork {
+            ssid: String::new(),
+            bssid: bssid.into(),
+            channel,
+            frequency_mhz: if channel > 14 {
+                5_000 + channel * 5
+            } else {
+                2_407 + channel * 5
+            },
+            rssi_dbm,
+        };
+        let nearby = [
+            network("own", 6, -30),
+            network("a", 1, -40),
+            network("b", 3, -40),
+            network("c", 11, -85),
+            network("d", 36, -50),
+            network("e", 100, -60),
+        ];
+        let loads = channel_occupancy(&nearby, WifiBand::TwoPointFourGhz, "OWN");
+        assert_eq!(loads.len(), 13);
+        // Channel 3 overlaps channel 6 by two fifths; our own AP is left out.
+        assert_eq!(loads[5].networks, 0);
+        assert!((loads[5].load - 0.4).abs() < 1e-9);
+        assert_eq!(
+            least_congested_channel(&loads, WifiBand::TwoPointFourGhz),
+            Some(11)
+        );
+
+        let loads = channel_occupancy(&nearby, WifiBand::FiveGhz, "own");
+        assert_eq!(loads.len(), 10, "DFS channel 100 is occupied, so shown");
+        assert_eq!(loads[0].networks, 1);
+        assert_eq!(least_congested_channel(&loads, WifiBand::FiveGhz), Some(40));
+    }
+}
@@ -3,13 +3,25 @@
 use std::{collections::VecDeque, str::FromStr};
 
 use iptools_core::{
-    AdapterApplyOutcome, AdapterInfo, DashboardInterface, DashboardSnapshot, Effect, JobId,
-    LanSpeedMode, LanSpeedPhase, LanSpeedRequest, LanSpeedSample, LanSpeedSummary,
-    LinkQualityAdapter, LinkQualityGrade, LinkQualityRequest, LinkQualitySample,
-    LinkQualitySnapshot, LinkQualitySummary, PingRequest, PingSample, PingSummary, PortScanRequest,
-    PortScanResult, PublicIpInfo, PublicSpeedRequest, RuntimeError, RuntimeErrorCode, RuntimeEvent,
-    ScanHost, SpeedSample, SpeedSummary, ToolKind, TraceHop, TraceRequest, TrafficRow,
-    WirelessSnapshot,
+    AbLeg, AbRequest, AbSide, AdapterApplyOutcome, AdapterInfo, AsnOrigin, BluetoothDevice,
+    BluetoothRequest, BufferbloatSummary, CloudProbe, CloudRequest, CveHint, DNS_FILTER_CONTROL,
+    DashboardInterface, DashboardSnapshot, DhcpServer, DiscoveredService, DnsAnswer,
+    DnsBenchRequest, DnsFilterRequest, DnsFilterUpstream, DnsQueryKind, DnsResolverScore,
+    DoctorCheck, DoctorCheckKind, DoctorStatus, DscpHop, DscpRequest, DscpSummary, DscpVerdict,
+    Effect, GamingRequest, GamingScore, HostName, IcmpTimestamps, Ipv6Check, Ipv6CheckResult,
+    Ipv6Request, Ipv6Verdict, JobId, LanProtocol, LanSpeedMode, LanSpeedPhase, LanSpeedRequest,
+    LanSpeedSample, LanSpeedSummary, LatencySample, LinkCounters, LinkQualityAdapter,
+    LinkQualityGrade, LinkQualityRequest, LinkQualitySample, LinkQualitySnapshot,
+    LinkQualitySummary, MAIL_PORTS, MailCheck, MailRequest, MtuOutcome, MtuProbe, MtuRequest,
+    MtuSearch, MtuSummary, MulticastMode, MulticastRequest, MulticastSample, MulticastStats,
+    NameSource, NatFiltering, NatRequest, NatSummary, NatType, NearbyNetwork, PhaseProgress,
+    PingMode, PingRequest, PingSample, PingSummary, PortScanRequest, PortScanResult, PublicIpInfo,
+    PublicSpeedRequest, RuntimeError, RuntimeErrorCode, RuntimeEvent, ScanHost, ScanMethod,
+    ScanProgress, SpeedPhase, SpeedSample, SpeedSummary, StunProbe, TlsSummary, ToolKind, TraceHop,
+    TraceRequest, TrafficRow, UpnpAction, UpnpGateway, UpnpMapping, UpnpOutcome, UpnpRequest,
+    VoipMode, VoipRequest, VoipSample, VoipStats, WatchCheck, WatchProbe, WatchRequest,
+    WebCheckRequest, WebCheckResult, WifiRequest, WifiSample, WirelessSnapshot,
+    classify_dns_filter, encode_multicast_packet, is_global_ipv6,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -63,8 +75,26 @@ struct Scenario {
     download_bps: u64,
     upload_bps: u64,
     latency_ms: u64,
+    /// Largest packet the simulated LAN peer answers; standard Ethernet unless
+    /// the scenario wires jumbo frames.
+    #[serde(default = "ethernet_mtu")]
+    path_mtu: u16,
+    /// Packets per thousand the first adapter drops; a flaky link shows up
+    /// in the drop counters and trips the alert.
+    #[serde(default)]
+    drop_per_mille: u64,
     adapters: Vec<AdapterInfo>,
     scan_hosts: Vec<ScanHost>,
+    /// mDNS / SSDP answers heard while port enrichment is on.
+    #[serde(default)]
+    scan_services: Vec<DiscoveredService>,
+}
+
+/// Scenario sightings share one wall-clock stamp so snapshots stay stable.
+const DEMO_SEEN_AT: &str = "2026-01-15 10:24";
+
+const fn ethernet_mtu() -> u16 {
+    1_500
 }
 
 #[derive(Debug, Clone)]
@@ -79,10 +109,60 @@ pub struct DemoRuntime {
     scenario: Scenario,
     elapsed_ms: u64,
     pending: VecDeque<ScheduledEvent>,
+    rng: DemoRng,
+    /// The simulated router's mapping table, kept across UPnP jobs so an
+    /// added mapping shows up in the next listing.
+    upnp_mappings: Vec<UpnpMapping>,
+}
+
+/// SplitMix64 stream used to vary samples. Seed 0 disables variation so the
+/// built-in scenarios keep their canonical values.
+#[derive(Debug, Clone)]
+struct DemoRng {
+    seed: u64,
+    state: u64,
+}
+
+impl DemoRng {
+    const fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// `base` plus a deterministic offset in `0..=spread`.
+    fn jitter(&mut self, base: u64, spread: u64) -> u64 {
+        if self.seed == 0 || spread == 0 {
+            return base;
+        }
+        base.saturating_add(self.next() % (spread + 1))
+    }
+
+    /// `base` scaled by a deterministic factor in `[1 - percent%, 1 + percent%]`.
+    fn scale(&mut self, base: u64, percent: u64) -> u64 {
+        if self.seed == 0 || percent == 0 {
+            return base;
+        }
+        let factor = 100 - percent + self.next() % (percent * 2 + 1);
+        base.saturating_mul(factor) / 100
+    }
 }
 
 impl DemoRuntime {
     pub fn new(id: ScenarioId) -> Result<Self, ScenarioError> {
+        Self::with_seed(id, 0)
+    }
+
+    /// Like [`DemoRuntime::new`], but varies latency, traffic and discovery
+    /// timing with a reproducible stream derived from `seed`. Equal scenarios,
+    /// seeds and inputs always produce identical event sequences.
+    pub fn with_seed(id: ScenarioId, seed: u64) -> Result<Self, ScenarioError> {
         let json = match id {
             ScenarioId::HomeNetwork => include_str!("../scenarios/home-network.json"),
             ScenarioId::WifiDegraded => include_str!("../scenarios/wifi-degraded.json"),
@@ -90,10 +170,26 @@ impl DemoRuntime {
         };
         let scenario: Scenario = serde_json::from_str(json)?;
         debug_assert_eq!(scenario.id, id.as_str());
+        let upnp_mappings = scenario
+            .scan_hosts
+            .first()
+            .map(|host| UpnpMapping {
+                protocol: LanProtocol::Tcp,
+                external_port: 32_400,
+                internal_client: host.ip.clone(),
+                internal_port: 32_400,
+                description: "Media server".into(),
+                enabled: true,
+                lease_secs: 0,
+            })
+            .into_iter()
+            .collect();
         Ok(Self {
             scenario,
             elapsed_ms: 0,
             pending: VecDeque::new(),
+            rng: DemoRng::new(seed),
+            upnp_mappings,
         })
     }
 
@@ -101,6 +197,10 @@ impl DemoRuntime {
         &self.scenario.id
     }
 
+    pub const fn seed(&self) -> u64 {
+        self.rng.seed
+    }
+
     pub fn bootstrap(&self) -> Vec<RuntimeEvent> {
         vec![
             RuntimeEvent::DashboardUpdated(Box::new(self.dashboard_snapshot())),
@@ -113,11 +213,99 @@ impl DemoRuntime {
         match effect {
             Effect::PersistPreferences(_)
             | Effect::PersistSession(_)
+            | Effect::PersistPublicIp(_)
+            | Effect::PersistTabs(_)
+            | Effect::PersistWatch(_)
+            | Effect::PersistJumpHost(_)
             | Effect::PersistAdapterEdit { .. } => Vec::new(),
-            Effect::RefreshDashboard { job, .. } => {
+            Effect::RunDoctor { job } => vec![RuntimeEvent::DoctorFinished {
+                job,
+                checks: demo_doctor_report(),
+            }],
+            // Nothing leaves the simulation, files included.
+            Effect::ExportChart { job, .. } => vec![RuntimeEvent::ChartExportFailed {
+                job,
+                error: RuntimeError::new(
+                    RuntimeErrorCode::InvalidRequest,
+                    "charts are not saved in the demo",
+                ),
+            }],
+            Effect::ExportAudit { job, .. } => vec![RuntimeEvent::AuditExportFailed {
+                job,
+                error: RuntimeError::new(
+                    RuntimeErrorCode::InvalidRequest,
+                    "reports are not saved in the demo",
+                ),
+            }],
+            // The router and a second server someone plugged in: the audit's
+            // rogue DHCP finding.
+            Effect::ProbeDhcp { job } => vec![RuntimeEvent::DhcpProbeFinished {
+                job,
+                servers: [
+                    ("192.168.1.1", "192.168.1.120"),
+                    ("192.168.1.77", "10.0.0.50"),
+                ]
+                .into_iter()
+                .map(|(server, offered)| DhcpServer {
+                    server: server.into(),
+                    offered: offered.into(),
+                    router: server.into(),
+                })
+                .collect(),
+            }],
+            // Simulated origins from the documentation AS range: the first
+            // hops belong to the access network, the rest to a transit AS.
+            Effect::LookupAsn { job, addresses, .. } => addresses
+                .into_iter()
+                .enumerate()
+                .map(|(index, address)| {
+                    let (asn, holder) = if index < 2 {
+                        (64_500, "EXAMPLE-ACCESS")
+                    } else {
+                        (64_510, "EXAMPLE-TRANSIT")
+                    };
+                    RuntimeEvent::AsnFound {
+                        job,
+                        origin: AsnOrigin {
+                            prefix: Some(format!("{address}/24")),
+                            address,
+                            asn: Some(asn),
+                            holder: Some(holder.into()),
+                            path: vec![64_496, asn],
+                        },
+                    }
+                })
+                .chain([RuntimeEvent::AsnLookupFinished { job }])
+                .collect(),
+            // Simulated counts; the demo never asks a vulnerability index.
+            Effect::LookupCves { job, software, .. } => software
+                .into_iter()
+                .map(|software| RuntimeEvent::CveHintFound {
+                    job,
+                    hint: CveHint {
+                        software,
+                        total: 15,
+                        by_severity: [1, 4, 7, 2],
+                    },
+                })
+                .chain([RuntimeEvent::CveLookupFinished { job }])
+                .collect(),
+            // The demo never reaches the network, so there is nothing to offer.
+            Effect::CheckUpdate { job, .. } => vec![RuntimeEvent::UpdateCheckFailed {
+                job,
+                error: RuntimeError::new(
+                    RuntimeErrorCode::Network,
+                    "updates are not checked in the demo",
+                ),
+            }],
+            Effect::RefreshDashboard { job, request } => {
+                let mut snapshot = self.dashboard_snapshot();
+                if request.offline {
+                    snapshot.public_info = None;
+                }
                 vec![RuntimeEvent::DashboardRefreshFinished {
                     job,
-                    snapshot: Box::new(self.dashboard_snapshot()),
+                    snapshot: Box::new(snapshot),
                 }]
             }
             Effect::RefreshAdapters { job } => {
@@ -126,10 +314,14 @@ impl DemoRuntime {
                     adapters: self.scenario.adapters.clone(),
                 }]
             }
-            Effect::RefreshTraffic { job } => vec![RuntimeEvent::TrafficRefreshFinished {
-                job,
-                rows: self.traffic_rows(),
-            }],
+            Effect::RefreshTraffic { job } => {
+                let mut rows = self.traffic_rows();
+                for row in &mut rows {
+                    row.download_bps = self.rng.scale(row.download_bps, 35);
+                    row.upload_bps = self.rng.scale(row.upload_bps, 35);
+                }
+                vec![RuntimeEvent::TrafficRefreshFinished { job, rows }]
+            }
             Effect::ApplyAdapterConfig { job, request } => {
                 let mut events = vec![RuntimeEvent::AdapterConfigStarted { job }];
                 let result = self
@@ -167,34 +359,149 @@ impl DemoRuntime {
                 }
                 events
             }
-            Effect::StartScan { job, .. } => {
+            Effect::StartScan { job, request } => {
                 self.cancel_job(job);
                 let total = 254;
                 self.schedule(0, RuntimeEvent::ScanStarted { job, total });
-                let hosts = self.scenario.scan_hosts.clone();
-                for (index, host) in hosts.into_iter().enumerate() {
-                    let at = 350 * (index as u64 + 1);
+                // A DNS zone sweep only sees hosts with a name, and no MACs.
+                let method = if request.dns_zone.is_some() {
+                    ScanMethod::Ptr
+                } else {
+                    ScanMethod::Local
+                };
+                let hosts: Vec<ScanHost> = self
+                    .scenario
+                    .scan_hosts
+                    .iter()
+                    .filter(|host| method == ScanMethod::Local || !host.hostname.is_empty())
+                    .cloned()
+                    .map(|mut host| {
+                        if method != ScanMethod::Local {
+                            host.mac = "-".into();
+                            host.vendor = "-".into();
+                        }
+                        host
+                    })
+                    .collect();
+                let found = hosts.len() as u64;
+                // The simulated link never congests, so the limiter stays at
+                // the configured ceiling.
+                let workers = if request.adaptive {
+                    request.concurrency.min(total as usize) as u32
+                } else {
+                    0
+                };
+                for (index, mut host) in hosts.into_iter().enumerate() {
+                    let at = self.rng.jitter(350 * (index as u64 + 1), 120);
+                    // Earlier hosts are resolved and probed; this one is
+                    // still being named.
+                    let swept = (index as u64 + 1) * total / found;
+                    let earlier = index as u64;
                     self.schedule(
                         at,
                         RuntimeEvent::ScanProgress {
                             job,
-                            current: ((index + 1) as u64 * 254
-                                / self.scenario.scan_hosts.len() as u64),
-                            total,
+                            progress: ScanProgress {
+                                probe: PhaseProgress {
+                                    queued: total - swept,
+                                    done: swept,
+                                    ..PhaseProgress::default()
+                                },
+                                resolve: PhaseProgress {
+                                    in_flight: 1,
+                                    done: earlier,
+                                    ..PhaseProgress::default()
+                                },
+                                enrich: PhaseProgress {
+                                    queued: u64::from(request.enrich),
+                                    done: if request.enrich { earlier } else { 0 },
+                                    ..PhaseProgress::default()
+                                },
+                                rate: (swept * 1_000 / at.max(1)) as u32,
+                                workers,
+                                method,
+                            },
+                        },
+                    );
+                    // The scenario lists what each device exposes; the scan
+                    // only reveals it when port enrichment is on.
+                    let open_ports = std::mem::take(&mut host.open_ports);
+                    let shares = std::mem::take(&mut host.shares);
+                    let web = std::mem::take(&mut host.web);
+                    let iot = std::mem::take(&mut host.iot);
+                    let smb1 = std::mem::take(&mut host.smb1);
+                    let banners = std::mem::take(&mut host.banners);
+                    if host.names.is_empty() && !host.hostname.is_empty() {
+                        host.names.push(HostName {
+                            source: NameSource::Dns,
+                            name: host.hostname.clone(),
+                        });
+                    }
+                    if host.seen_at.is_empty() {
+                        host.seen_at = DEMO_SEEN_AT.into();
+                    }
+                    if request.enrich {
+                        self.schedule(
+                            at + 200,
+                            RuntimeEvent::ScanHostEnriched {
+                                job,
+                                ip: host.ip.clone(),
+                                open_ports,
+                                shares,
+                                web,
+                                iot,
+                                smb1,
+                                banners,
+                            },
+                        );
+                    }
+                    self.schedule(
+                        at + 80,
+                        RuntimeEvent::ScanHostFound {
+                            job,
+                            host: Box::new(host),
                         },
                     );
-                    self.schedule(at + 80, RuntimeEvent::ScanHostFound { job, host });
                 }
+                let finished_at = 350 * (found + 1);
+                if request.enrich {
+                    self.schedule(
+                        finished_at.saturating_sub(100),
+                        RuntimeEvent::ScanServicesFound {
+                            job,
+                            services: self.scenario.scan_services.clone(),
+                        },
+                    );
+                }
+                let settled = |done| PhaseProgress {
+                    done,
+                    ..PhaseProgress::default()
+                };
                 self.schedule(
-                    350 * (self.scenario.scan_hosts.len() as u64 + 1),
-                    RuntimeEvent::ScanFinished { job },
+                    finished_at,
+                    RuntimeEvent::ScanProgress {
+                        job,
+                        progress: ScanProgress {
+                            probe: settled(total),
+                            resolve: settled(found),
+                            enrich: settled(if request.enrich { found } else { 0 }),
+                            rate: 0,
+                            workers,
+                            method,
+                        },
+                    },
                 );
+                self.schedule(finished_at, RuntimeEvent::ScanFinished { job });
                 Vec::new()
             }
             Effect::CancelScan(job) => {
                 self.cancel_job(job);
                 vec![RuntimeEvent::ScanCancelled { job }]
             }
+            Effect::StartBluetoothScan { job, request } => {
+                self.start_bluetooth_scan(job, request);
+                Vec::new()
+            }
             Effect::StartPing { job, request } => {
                 self.start_ping(job, request);
                 Vec::new()
@@ -219,12 +526,92 @@ impl DemoRuntime {
                 self.start_lan_speed(job, request);
                 Vec::new()
             }
+            Effect::StartMtu { job, request } => {
+                self.start_mtu(job, request);
+                Vec::new()
+            }
+            Effect::StartDscp { job, request } => {
+                self.start_dscp(job, request);
+                Vec::new()
+            }
+            Effect::StartMulticast { job, request } => {
+                self.start_multicast(job, request);
+                Vec::new()
+            }
+            Effect::StartUpnp { job, request } => {
+                self.start_upnp(job, request);
+                Vec::new()
+            }
+            Effect::StartNat { job, request } => {
+                self.start_nat(job, request);
+                Vec::new()
+            }
+            Effect::StartIpv6 { job, request } => {
+                self.start_ipv6(job, request);
+                Vec::new()
+            }
+            Effect::StartDnsBench { job, request } => {
+                self.start_dns_bench(job, request);
+                Vec::new()
+            }
+            Effect::StartDnsFilter { job, request } => {
+                self.start_dns_filter(job, request);
+                Vec::new()
+            }
+            Effect::StartMail { job, request } => {
+                self.start_mail(job, request);
+                Vec::new()
+            }
+            Effect::StartVoip { job, request } => {
+                self.start_voip(job, request);
+                Vec::new()
+            }
+            Effect::StartGaming { job, request } => {
+                self.start_gaming(job, request);
+                Vec::new()
+            }
+            Effect::StartCloud { job, request } => {
+                self.start_cloud(job, request);
+                Vec::new()
+            }
+            Effect::StartWifi { job, request } => {
+                self.start_wifi(job, request);
+                Vec::new()
+            }
+            Effect::StartAbTest { job, request } => {
+                self.start_ab_test(job, request);
+                Vec::new()
+            }
+            Effect::StartWebCheck { job, request } => {
+                self.start_web_check(job, request);
+                Vec::new()
+            }
+            Effect::StartWatch { job, request } => {
+                self.start_watch(job, request);
+                Vec::new()
+            }
             Effect::StopPing(job)
             | Effect::StopTrace(job)
             | Effect::StopPortScan(job)
             | Effect::StopPublicSpeed(job)
             | Effect::StopLinkQuality(job)
-            | Effect::StopLanSpeed(job) => {
+            | Effect::StopLanSpeed(job)
+            | Effect::StopMtu(job)
+            | Effect::StopDscp(job)
+            | Effect::StopMulticast(job)
+            | Effect::StopUpnp(job)
+            | Effect::StopNat(job)
+            | Effect::StopIpv6(job)
+            | Effect::StopDnsBench(job)
+            | Effect::StopDnsFilter(job)
+            | Effect::StopMail(job)
+            | Effect::StopVoip(job)
+            | Effect::StopGaming(job)
+            | Effect::StopCloud(job)
+            | Effect::StopWifi(job)
+            | Effect::StopAbTest(job)
+            | Effect::StopBluetoothScan(job)
+            | Effect::StopWebCheck(job) => {
                 self.cancel_job(job);
                 vec![cancelled_event(job)]
             }
@@ -243,6 +630,11 @@ impl DemoRuntime {
                 ssid: None,
                 is_physical: true,
                 dhcp_enabled: true,
+                // Scenarios use home-router addressing: the gateway is `.1`.
+                gateway: adapter
+                    .ipv4
+                    .rsplit_once('.')
+                    .map(|(network, _)| format!("{network}.1")),
             });
         DashboardSnapshot {
             observed_at: "2026-01-15 10:24:00".into(),
@@ -283,16 +675,34 @@ impl DemoRuntime {
     fn start_ping(&mut self, job: JobId, request: PingRequest) {
         self.cancel_job(job);
         self.schedule(0, RuntimeEvent::PingStarted { job });
+        // Same documentation-range path as the simulated traceroute.
+        let route = match request.mode {
+            PingMode::RecordRoute => (1..=4).map(|hop| format!("192.0.2.{hop}")).collect(),
+            _ => Vec::new(),
+        };
         for step in 1..=8 {
+            let latency_ms = self
+                .rng
+                .jitter(self.scenario.latency_ms + step as u64 % 4, 6);
+            let timestamps = (request.mode == PingMode::Timestamp).then(|| {
+                let originate_ms = 43_200_000 + step * 1_000;
+                IcmpTimestamps {
+                    originate_ms,
+                    receive_ms: originate_ms + latency_ms as u32 / 2,
+                    transmit_ms: originate_ms + latency_ms as u32 / 2,
+                }
+            });
             self.schedule(
                 step as u64 * 320,
                 RuntimeEvent::PingSample {
                     job,
                     sample: PingSample {
                         sequence: step as u64,
-                        latency_ms: Some(self.scenario.latency_ms + step as u64 % 4),
+                        latency_ms: Some(latency_ms),
                         ttl: Some(64),
                         size: request.packet_size as usize,
+                        route: route.clone(),
+                        timestamps,
                         sent: step as u64,
                         received: step as u64,
                         min_ms: Some(self.scenario.latency_ms),
@@ -319,25 +729,866 @@ impl DemoRuntime {
         );
     }
 
-    fn start_trace(&mut self, job: JobId, request: TraceRequest) {
+    fn start_trace(&mut self, job: JobId, request: TraceRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::TraceStarted { job });
+        let hops = request.max_hops.min(8);
+        // Every path leaves through the same gateway and ISP hop; past them
+        // each target and interface takes a route of its own, so two traces
+        // compared side by side part at hop 3.
+        let lane = request
+            .target
+            .bytes()
+            .chain(request.via.iter().flat_map(|via| via.bytes()))
+            .fold(0_u8, |lane, byte| lane.wrapping_mul(31).wrapping_add(byte))
+            % 64;
+        for ttl in 1..=hops {
+            self.schedule(
+                ttl as u64 * 320,
+                RuntimeEvent::TraceHop {
+                    job,
+                    hop: TraceHop {
+                        ttl,
+                        address: Some(if ttl <= 2 {
+                            format!("192.0.2.{ttl}")
+                        } else {
+                            format!("100.{}.0.{ttl}", 64 + lane)
+                        }),
+                        hostname: None,
+                        latency_ms: Some(self.scenario.latency_ms / 2 + ttl as u64 * 2),
+                    },
+                },
+            );
+        }
+        self.schedule(2_900, RuntimeEvent::TraceFinished { job, hops });
+    }
+
+    fn start_mtu(&mut self, job: JobId, request: MtuRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::MtuStarted { job });
+        let mut search = MtuSearch::new(request.max_mtu);
+        let mut at = 0;
+        let mut probes = 0;
+        while let Some(size) = search.next() {
+            at += 240;
+            probes += 1;
+            let carried = size <= self.scenario.path_mtu;
+            search.record(size, carried);
+            let outcome = if carried {
+                MtuOutcome::Carried {
+                    rtt_ms: self.scenario.latency_ms / 4 + u64::from(size) / 3_000,
+                }
+            } else if size <= 1_500 {
+                MtuOutcome::TooBig
+            } else {
+                MtuOutcome::Lost
+            };
+            self.schedule(
+                at,
+                RuntimeEvent::MtuProbe {
+                    job,
+                    probe: MtuProbe { size, outcome },
+                },
+            );
+        }
+        self.schedule(
+            at + 240,
+            RuntimeEvent::MtuFinished {
+                job,
+                summary: MtuSummary {
+                    path_mtu: search.path_mtu(),
+                    probes,
+                },
+            },
+        );
+    }
+
+    /// The home gateway keeps the marking; the access network behind it
+    /// bleaches everything to best effort, as most consumer ISPs do.
+    fn start_dscp(&mut self, job: JobId, request: DscpRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::DscpStarted { job });
+        let hops = request.max_hops.min(6);
+        let mut sent = Vec::new();
+        for ttl in 1..=hops {
+            let hop = DscpHop {
+                ttl,
+                address: Some(if ttl == hops {
+                    request.target.clone()
+                } else {
+                    format!("192.0.2.{ttl}")
+                }),
+                rtt_ms: Some(self.scenario.latency_ms / 2 + u64::from(ttl) * 2),
+                observed: Some(if ttl <= 2 { request.dscp } else { 0 }),
+                reached: ttl == hops,
+            };
+            sent.push(hop.clone());
+            self.schedule(u64::from(ttl) * 320, RuntimeEvent::DscpHop { job, hop });
+        }
+        self.schedule(
+            u64::from(hops) * 320 + 240,
+            RuntimeEvent::DscpFinished {
+                job,
+                summary: DscpSummary::from_hops(request.dscp, &sent),
+            },
+        );
+    }
+
+    fn start_multicast(&mut self, job: JobId, request: MulticastRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::MulticastStarted { job });
+        let seconds = request.duration_secs.clamp(1, 8);
+        let rate = u64::from(request.rate_pps.max(1));
+        let interval = 1_000.0 / rate as f64;
+        let mut stats = MulticastStats::default();
+        let packet = encode_multicast_packet(0);
+        for second in 1..=seconds {
+            for index in 0..rate {
+                let sequence = ((second - 1) * rate + index) as u32;
+                match request.mode {
+                    MulticastMode::Send => stats.record_sent(packet.len()),
+                    // 一个发送方，每隔一包抖动 3 ms，第 2 秒丢一包
+                    MulticastMode::Listen if second == 2 && index == 5 => {}
+                    MulticastMode::Listen => {
+                        let wobble = if sequence.is_multiple_of(2) { 3.0 } else { 0.0 };
+                        stats.record(
+                            sequence as f64 * interval + wobble,
+                            "192.0.2.40",
+                            &encode_multicast_packet(sequence),
+                        );
+                    }
+                }
+            }
+            self.schedule(
+                second * 400,
+                RuntimeEvent::MulticastSample {
+                    job,
+                    sample: stats.sample(second * 1_000),
+                },
+            );
+        }
+        self.schedule(
+            seconds * 400 + 200,
+            RuntimeEvent::MulticastFinished {
+                job,
+                summary: stats.sample(seconds * 1_000),
+            },
+        );
+    }
+
+    fn start_voip(&mut self, job: JobId, request: VoipRequest) {
+        self.cancel_job(job);
+        let endpoint = match request.mode {
+            VoipMode::Probe => format!("{}:{}", request.peer, request.port),
+            VoipMode::Reflect => format!("0.0.0.0:{}", request.port),
+        };
+        self.schedule(0, RuntimeEvent::VoipStarted { job, endpoint });
+        let seconds = request.duration_secs.clamp(1, 8);
+        let mut stats = VoipStats::default();
+        for second in 1..=seconds {
+            for index in 0..50u64 {
+                stats.record_sent();
+                let sequence = (second - 1) * 50 + index;
+                // 第 3 秒丢两包
+                if request.mode == VoipMode::Probe && !(second == 3 && index < 2) {
+                    let rtt = self.rng.jitter(self.scenario.latency_ms, 4) as f64;
+                    stats.record_echo(sequence as u16, rtt);
+                }
+            }
+            let sample = match request.mode {
+                VoipMode::Probe => stats.sample(second * 1_000, false),
+                VoipMode::Reflect => stats.reflected(second * 1_000),
+            };
+            self.schedule(second * 400, RuntimeEvent::VoipSample { job, sample });
+        }
+        let summary = match request.mode {
+            VoipMode::Probe => stats.sample(seconds * 1_000, true),
+            VoipMode::Reflect => stats.reflected(seconds * 1_000),
+        };
+        self.schedule(
+            seconds * 400 + 200,
+            RuntimeEvent::VoipFinished { job, summary },
+        );
+    }
+
+    fn start_upnp(&mut self, job: JobId, request: UpnpRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::UpnpStarted { job });
+        let local_ip = self
+            .scenario
+            .adapters
+            .first()
+            .map(|adapter| adapter.ipv4.clone())
+            .unwrap_or_default();
+        self.schedule(
+            480,
+            RuntimeEvent::UpnpGateway {
+                job,
+                gateway: UpnpGateway {
+                    name: "Demo Router".into(),
+                    service: "WANIPConnection:1".into(),
+                    external_ip: Some(self.scenario.public_ip.clone()),
+                    local_ip: local_ip.clone(),
+                },
+            },
+        );
+        let same_key = |mapping: &UpnpMapping| {
+            mapping.protocol == request.protocol && mapping.external_port == request.external_port
+        };
+        let outcome = match request.action {
+            UpnpAction::List => UpnpOutcome::Listed,
+            UpnpAction::Add => {
+                self.upnp_mappings.retain(|mapping| !same_key(mapping));
+                self.upnp_mappings.push(UpnpMapping {
+                    protocol: request.protocol,
+                    external_port: request.external_port,
+                    internal_client: local_ip,
+                    internal_port: request.internal_port,
+                    description: "iptools".into(),
+                    enabled: true,
+                    lease_secs: request.lease_secs,
+                });
+                UpnpOutcome::Added {
+                    protocol: request.protocol,
+                    external_port: request.external_port,
+                    internal_port: request.internal_port,
+                }
+            }
+            UpnpAction::Delete => {
+                self.upnp_mappings.retain(|mapping| !same_key(mapping));
+                UpnpOutcome::Deleted {
+                    protocol: request.protocol,
+                    external_port: request.external_port,
+                }
+            }
+        };
+        self.schedule(
+            900,
+            RuntimeEvent::UpnpMappings {
+                job,
+                mappings: self.upnp_mappings.clone(),
+            },
+        );
+        self.schedule(1_000, RuntimeEvent::UpnpFinished { job, outcome });
+    }
+
+    fn start_nat(&mut self, job: JobId, request: NatRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::NatStarted { job });
+        let local_ip = self
+            .scenario
+            .adapters
+            .first()
+            .map(|adapter| adapter.ipv4.clone())
+            .unwrap_or_default();
+        let mapped = format!("{}:40001", self.scenario.public_ip);
+        for (index, server) in [request.server, request.secondary].into_iter().enumerate() {
+            let rtt_ms = self.rng.jitter(self.scenario.latency_ms + 4, 6);
+            self.schedule(
+                (index as u64 + 1) * 360,
+                RuntimeEvent::NatProbe {
+                    job,
+                    probe: StunProbe {
+                        server,
+                        mapped: Some(mapped.clone()),
+                        rtt_ms: Some(rtt_ms),
+                    },
+                },
+            );
+        }
+        self.schedule(
+            1_400,
+            RuntimeEvent::NatFinished {
+                job,
+                summary: NatSummary {
+                    nat_type: NatType::classify(
+                        &[local_ip.as_str()],
+                        Some(&mapped),
+                        Some(&mapped),
+                        NatFiltering::AddressAndPortDependent,
+                    ),
+                    local: format!("{local_ip}:50000"),
+                    mapped: Some(mapped),
+                    hairpin: Some(true),
+                },
+            },
+        );
+    }
+
+    fn start_ipv6(&mut self, job: JobId, request: Ipv6Request) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::Ipv6Started { job });
+        let address = self
+            .scenario
+            .adapters
+            .iter()
+            .flat_map(|adapter| &adapter.ipv6)
+            .find(|address| is_global_ipv6(address))
+            .cloned()
+            .unwrap_or_else(|| "2001:db8:1::20".into());
+        let rtt_ms = self.rng.jitter(self.scenario.latency_ms + 2, 4);
+        let results = [
+            (Ipv6Check::GlobalAddress, address),
+            (Ipv6Check::DefaultRoute, "via fe80::1".into()),
+            (
+                Ipv6Check::DnsAaaa,
+                format!("{} → 2001:db8:ffff::68", request.target.trim()),
+            ),
+            (Ipv6Check::Reachability, format!("TCP 443 in {rtt_ms} ms")),
+            (Ipv6Check::PathMtu, "≈1500 (MSS 1440)".into()),
+        ];
+        for (index, (check, detail)) in results.into_iter().enumerate() {
+            self.schedule(
+                (index as u64 + 1) * 240,
+                RuntimeEvent::Ipv6Result {
+                    job,
+                    result: Ipv6CheckResult {
+                        check,
+                        verdict: Ipv6Verdict::Pass,
+                        detail,
+                    },
+                },
+            );
+        }
+        self.schedule(1_400, RuntimeEvent::Ipv6Finished { job });
+    }
+
+    /// Plain HTTP redirects to HTTPS, which serves a valid certificate; the
+    /// alternate admin port is closed.
+    fn start_web_check(&mut self, job: JobId, request: WebCheckRequest) {
+        self.cancel_job(job);
+        let latency = self.scenario.latency_ms;
+        let results = request
+            .ports
+            .iter()
+            .map(|&port| {
+                let elapsed_ms = Some(self.rng.jitter(latency * 3 + 20, 10));
+                let result = WebCheckResult {
+                    port,
+                    tls: None,
+                    status: None,
+                    server: Some("nginx".into()),
+                    title: None,
+                    elapsed_ms,
+                    error: None,
+                };
+                match port {
+                    80 => WebCheckResult {
+                        status: Some(301),
+                        title: Some("301 Moved Permanently".into()),
+                        ..result
+                    },
+                    443 => WebCheckResult {
+                        tls: Some(TlsSummary {
+                            version: "TLSv1_3".into(),
+                            cipher: "TLS13_AES_128_GCM_SHA256".into(),
+                        }),
+                        status: Some(200),
+                        title: Some("Welcome".into()),
+                        ..result
+                    },
+                    _ => WebCheckResult {
+                        server: None,
+                        elapsed_ms: None,
+                        error: Some("Connection refused (os error 111)".into()),
+                        ..result
+                    },
+                }
+            })
+            .collect();
+        self.schedule(
+            latency * 3 + 400,
+            RuntimeEvent::WebCheckFinished { job, results },
+        );
+    }
+
+    fn start_dns_bench(&mut self, job: JobId, request: DnsBenchRequest) {
+        self.cancel_job(job);
+        let resolvers = request.resolvers();
+        self.schedule(
+            0,
+            RuntimeEvent::DnsBenchStarted {
+                job,
+                resolvers: resolvers.len() as u32,
+            },
+        );
+        let latency = self.scenario.latency_ms;
+        let count = resolvers.len() as u64;
+        for (index, resolver) in resolvers.into_iter().enumerate() {
+            // The system resolver sits on the router; public ones are farther out,
+            // and encrypted transports pay for framing and record decryption.
+            let overhead = if resolver.transport.encrypted() { 3 } else { 0 };
+            let base = latency / 2 + index as u64 * 4 + overhead;
+            let samples = request
+                .queries(index as u64)
+                .into_iter()
+                .map(|(kind, _)| {
+                    let ms = match kind {
+                        DnsQueryKind::Cached => self.rng.jitter(base / 2, 2),
+                        DnsQueryKind::Uncached => self.rng.jitter(base * 3, 12),
+                        DnsQueryKind::Dotcom => self.rng.jitter(base, 6),
+                    };
+                    (kind, Some(ms as f64))
+                })
+                .collect::<Vec<_>>();
+            self.schedule(
+                (index as u64 + 1) * 450,
+                RuntimeEvent::DnsBenchScore {
+                    job,
+                    score: DnsResolverScore::from_samples(resolver, &samples),
+                },
+            );
+        }
+        self.schedule((count + 1) * 450, RuntimeEvent::DnsBenchFinished { job });
+    }
+
+    fn start_gaming(&mut self, job: JobId, request: GamingRequest) {
+        self.cancel_job(job);
+        let endpoints = match request.endpoints() {
+            Ok(endpoints) => endpoints,
+            Err(error) => {
+                self.schedule(
+                    0,
+                    RuntimeEvent::GamingFailed {
+                        job,
+                        error: RuntimeError::new(RuntimeErrorCode::InvalidRequest, error),
+                    },
+                );
+                return;
+            }
+        };
+        self.schedule(
+            0,
+            RuntimeEvent::GamingStarted {
+                job,
+                endpoints: endpoints.len() as u32,
+            },
+        );
+        let latency = self.scenario.latency_ms;
+        let count = endpoints.len() as u64;
+        for (index, endpoint) in endpoints.into_iter().enumerate() {
+            // 区域远近按顺序错开；每隔几个区域抖动更大，第 9 个区域丢包
+            let base = latency + (index as u64 * 37) % 180;
+            let spread = 2 + (index as u64 % 4) * 3;
+            let samples = (0..request.rounds)
+                .map(|round| {
+                    (index % 9 != 8 || round % 4 != 0).then(|| self.rng.jitter(base, spread) as f64)
+                })
+                .collect::<Vec<_>>();
+            self.schedule(
+                (index as u64 + 1) * 200,
+                RuntimeEvent::GamingScore {
+                    job,
+                    score: GamingScore::from_samples(endpoint, &samples),
+                },
+            );
+        }
+        self.schedule((count + 1) * 200, RuntimeEvent::GamingFinished { job });
+    }
+
+    fn start_cloud(&mut self, job: JobId, request: CloudRequest) {
+        self.cancel_job(job);
+        let endpoints = iptools_core::cloud_endpoints();
+        self.schedule(
+            0,
+            RuntimeEvent::CloudStarted {
+                job,
+                endpoints: endpoints.len() as u32,
+            },
+        );
+        let latency = self.scenario.latency_ms;
+        let count = endpoints.len() as u64;
+        for (index, endpoint) in endpoints.into_iter().enumerate() {
+            // 按矩阵列（美东、美西、欧洲、亚洲）拉开距离；Azure 日本东部模拟区域故障
+            let base = latency + (index as u64 % 4) * 45;
+            let attempts = (0..request.attempts)
+                .map(|_| {
+                    if endpoint.name == "japaneast" {
+                        Err("connection timed out".to_string())
+                    } else {
+                        Ok(self.rng.jitter(base, 4) as f64)
+                    }
+                })
+                .collect::<Vec<_>>();
+            self.schedule(
+                (index as u64 + 1) * 120,
+                RuntimeEvent::CloudResult {
+                    job,
+                    probe: CloudProbe::from_attempts(endpoint, &attempts),
+                },
+            );
+        }
+        self.schedule((count + 1) * 120, RuntimeEvent::CloudFinished { job });
+    }
+
+    fn start_ab_test(&mut self, job: JobId, request: AbRequest) {
+        self.cancel_job(job);
+        self.schedule(0, RuntimeEvent::AbTestStarted { job });
+        let sides = [(AbSide::A, &request.first), (AbSide::B, &request.second)];
+        for (index, (side, via)) in sides.into_iter().enumerate() {
+            let via = via.trim();
+            let adapter = self
+                .scenario
+                .adapters
+                .iter()
+                .find(|adapter| {
+                    adapter.is_up()
+                        && (adapter.name.eq_ignore_ascii_case(via) || adapter.ipv4 == via)
+                })
+                .cloned();
+            let leg = match adapter {
+                Some(adapter) => self.ab_leg(side, via, &adapter, request.pings),
+                None => AbLeg {
+                    error: Some(format!("{via} has no IPv4 address")),
+                    ..AbLeg::from_replies(side, via, "", &[])
+                },
+            };
+            self.schedule(
+                (index as u64 + 1) * 3_000,
+                RuntimeEvent::AbTestLeg { job, leg },
+            );
+        }
+        self.schedule(6_000, RuntimeEvent::AbTestFinished { job });
+    }
+
+    /// 有线口最快最稳；Wi-Fi 延迟高、抖动大，每 5 个 Echo 丢 1 个；
+    /// 隧道多绕一段路，吞吐也打折扣
+    fn ab_leg(&mut self, side: AbSide, via: &str, adapter: &AdapterInfo, pings: u32) -> AbLeg {
+        let latency = self.scenario.latency_ms;
+        let (base, spread, share) = if adapter.is_wireless() {
+            (latency * 3, 12, 60)
+        } else if !adapter.is_physical {
+            (latency + 25, 3, 40)
+        } else {
+            (latency, 2, 100)
+        };
+        let replies = (0..pings)
+            .map(|sequence| {
+                (!adapter.is_wireless() || sequence % 5 != 4)
+                    .then(|| self.rng.jitter(base, spread) as f64)
+            })
+            .collect::<Vec<_>>();
+        let mut leg = AbLeg::from_replies(side, via, &adapter.ipv4, &replies);
+        leg.download_bytes_per_second =
+            Some(self.rng.scale(self.scenario.download_bps * share / 100, 5) as f64);
+        let lookups = (0..5)
+            .map(|_| self.rng.jitter(base + 5, spread) as f64)
+            .collect();
+        leg.record_dns(lookups);
+        leg
+    }
+
+    fn start_wifi(&mut self, job: JobId, request: WifiRequest) {
+        self.cancel_job(job);
+        let Some(ssid) = self
+            .scenario
+            .adapters
+            .iter()
+            .find(|adapter| adapter.guid == request.guid && adapter.is_wireless())
+            .map(|adapter| adapter.ssid.clone().unwrap_or_else(|| "Demo Wi-Fi".into()))
+        else {
+            self.schedule(
+                0,
+                RuntimeEvent::WifiFailed {
+                    job,
+                    error: RuntimeError::new(
+                        RuntimeErrorCode::InvalidRequest,
+                        "no demo wireless adapter is available",
+                    ),
+                },
+            );
+            return;
+        };
+        // 走远时信号逐渐变弱，第 6 次采样漫游到 5 GHz 的另一台 AP，第 10 次短暂掉线
+        let interval = request.interval_secs * 1_000;
+        // 邻居：2.4 GHz 挤在 1、4、6 信道，11 信道只有一个远处的 AP
+        let nearby = [
+            ("Neighbor-2G", "02:10:00:00:00:01", 1, -48),
+            ("Neighbor-2G", "02:10:00:00:00:02", 6, -62),
+            ("CoffeeShop", "02:10:00:00:00:03", 6, -66),
+            ("", "02:10:00:00:00:04", 4, -70),
+            ("Printer-Direct", "02:10:00:00:00:05", 11, -83),
+            ("Neighbor-5G", "02:10:00:00:00:06", 36, -67),
+            ("CoffeeShop-5G", "02:10:00:00:00:07", 44, -74),
+            ("Office", "02:10:00:00:00:08", 149, -80),
+            ("Office", "02:10:00:00:00:09", 100, -85),
+            (ssid.as_str(), "02:AA:BB:CC:DD:01", 6, -58),
+            (ssid.as_str(), "02:AA:BB:CC:DD:02", 149, -52),
+        ]
+        .map(|(name, bssid, channel, rssi_dbm)| NearbyNetwork {
+            ssid: name.into(),
+            bssid: bssid.into(),
+            channel,
+            frequency_mhz: if channel > 14 {
+                5_000 + channel * 5
+            } else {
+                2_407 + channel * 5
+            },
+            rssi_dbm,
+        });
+        for index in 0..12u64 {
+            let roamed = index >= 6;
+            let link = (index != 10).then(|| WirelessSnapshot {
+                ssid: ssid.clone(),
+                bssid: if roamed {
+                    "02:AA:BB:CC:DD:02".into()
+                } else {
+                    "02:AA:BB:CC:DD:01".into()
+                },
+                signal_quality: 0,
+                rssi_dbm: if roamed {
+                    -52 - self.rng.jitter(2, 2) as i32
+                } else {
+                    -58 - index as i32 * 4
+                },
+                phy_type: "802.11ax · Wi-Fi 6".into(),
+                wifi_generation: 6,
+                band: if roamed { "5 GHz" } else { "2.4 GHz" }.into(),
+                channel: if roamed { 149 } else { 6 },
+                frequency_mhz: if roamed { 5_745 } else { 2_437 },
+                rx_rate_mbps: if roamed { 866 } else { 144 },
+                tx_rate_mbps: if roamed { 780 } else { 130 - index as u32 * 15 },
+                authentication: "WPA2-Personal".into(),
+                cipher: "CCMP (AES)".into(),
+            });
+            self.schedule(
+                index * 300,
+                RuntimeEvent::WifiSample {
+                    job,
+                    sample: WifiSample {
+                        elapsed_ms: index * interval,
+                        link,
+                        nearby: nearby.to_vec(),
+                    },
+                },
+            );
+        }
+        self.schedule(12 * 300, RuntimeEvent::WifiFinished { job });
+    }
+
+    fn start_bluetooth_scan(&mut self, job: JobId, request: BluetoothRequest) {
+        self.cancel_job(job);
+        // 手机、手表、耳机每轮都广播；信标不带名字，只在第二轮后才听到电视
+        let devices = [
+            ("4C:21:8A:10:22:01", Some("iPhone"), -48, Some(0x004C), None),
+            (
+                "C8:3A:9E:40:11:02",
+                Some("Galaxy Watch6"),
+                -61,
+                Some(0x0075),
+                Some(4),
+            ),
+            (
+                "E4:17:D8:0A:7C:03",
+                Some("WH-1000XM5"),
+                -67,
+                Some(0x012D),
+                None,
+            ),
+            ("DC:A6:32:55:90:04", None, -79, Some(0x0059), Some(-12)),
+            (
+                "F0:5C:77:21:3B:05",
+                Some("[TV] Living Room"),
+                -84,
+                Some(0x0075),
+                None,
+            ),
+        ];
+        let rounds = request.duration_secs.clamp(1, 4);
+        for round in 0..rounds {
+            for (index, (address, name, rssi_dbm, manufacturer_id, tx_power_dbm)) in
+                devices.iter().enumerate()
+            {
+                if index == 4 && round < 2 {
+                    continue;
+                }
+                let at = round * 400 + index as u64 * 60;
+                let rssi_dbm = rssi_dbm - self.rng.jitter(0, 3) as i32;
+                self.schedule(
+                    at,
+                    RuntimeEvent::BluetoothDeviceSeen {
+                        job,
+                        device: BluetoothDevice {
+                            address: (*address).into(),
+                            name: name.map(Into::into),
+                            rssi_dbm: Some(rssi_dbm),
+                            tx_power_dbm: *tx_power_dbm,
+                            manufacturer_id: *manufacturer_id,
+                            last_seen_ms: at,
+                        },
+                    },
+                );
+            }
+        }
+        self.schedule(rounds * 400, RuntimeEvent::BluetoothScanFinished { job });
+    }
+
+    fn start_watch(&mut self, job: JobId, request: WatchRequest) {
+        self.cancel_job(job);
+        let latency = self.scenario.latency_ms;
+        let count = request.targets.len() as u64;
+        for (index, target) in request.targets.into_iter().enumerate() {
+            // 局域网地址几毫秒内应答，HTTP 多一次往返；保留的 .invalid / .test 域名解析失败
+            let (host, extra) = match target.probe() {
+                Some(WatchProbe::Icmp(host) | WatchProbe::Tcp(host, _)) => (host, 0),
+                Some(WatchProbe::Http(url)) => {
+                    let host = url
+                        .split("://")
+                        .nth(1)
+                        .and_then(|rest| rest.split(['/', ':', '?']).next())
+                        .unwrap_or_default();
+                    (host.to_string(), latency)
+                }
+                None => (String::new(), 0),
+            };
+            let check = if host.is_empty() || host.ends_with(".invalid") || host.ends_with(".test")
+            {
+                WatchCheck {
+                    target,
+                    latency_ms: None,
+                    error: Some(format!("{host} could not be resolved")),
+                }
+            } else {
+                let base = match host.parse::<std::net::IpAddr>() {
+                    Ok(std::net::IpAddr::V4(ip)) if ip.is_private() => 2,
+                    _ => latency,
+                };
+                WatchCheck {
+                    target,
+                    latency_ms: Some(self.rng.jitter(base + extra, 2) as f64),
+                    error: None,
+                }
+            };
+            self.schedule(
+                (index as u64 + 1) * 80,
+                RuntimeEvent::WatchChecked { job, check },
+            );
+        }
+        self.schedule((count + 1) * 80, RuntimeEvent::WatchFinished { job });
+    }
+
+    fn start_dns_filter(&mut self, job: JobId, request: DnsFilterRequest) {
+        self.cancel_job(job);
+        let domains = request.domains();
+        let upstreams = [
+            DnsFilterUpstream::System,
+            DnsFilterUpstream::Gateway,
+            DnsFilterUpstream::Public,
+        ];
+        self.schedule(
+            0,
+            RuntimeEvent::DnsFilterStarted {
+                job,
+                upstreams: upstreams.to_vec(),
+                domains: domains.len() as u32,
+            },
+        );
+        // A Pi-hole answers for the system resolver; the router and the
+        // public resolver pass every name through untouched.
+        let honest = domains
+            .iter()
+            .enumerate()
+            .map(|(index, domain)| {
+                let address = format!("198.51.100.{}", 10 + index);
+                (
+                    domain.clone(),
+                    DnsAnswer::Addresses(vec![address.parse().expect("valid address")]),
+                )
+            })
+            .collect::<Vec<_>>();
+        let sinkholed = honest
+            .iter()
+            .map(|(domain, answer)| {
+                let answer = if domain == DNS_FILTER_CONTROL {
+                    answer.clone()
+                } else {
+                    DnsAnswer::Addresses(vec![[0, 0, 0, 0].into()])
+                };
+                (domain.clone(), answer)
+            })
+            .collect::<Vec<_>>();
+        let latency = self.scenario.latency_ms;
+        let mut delay = 0;
+        for upstream in upstreams {
+            let answers = if upstream == DnsFilterUpstream::System {
+                &sinkholed
+            } else {
+                &honest
+            };
+            for probe in classify_dns_filter(upstream, answers, &honest) {
+                delay += self.rng.jitter(latency.max(20), 10);
+                self.schedule(delay, RuntimeEvent::DnsFilterResult { job, probe });
+            }
+        }
+        self.schedule(delay + 100, RuntimeEvent::DnsFilterFinished { job });
+    }
+
+    fn start_mail(&mut self, job: JobId, request: MailRequest) {
         self.cancel_job(job);
-        self.schedule(0, RuntimeEvent::TraceStarted { job });
-        let hops = request.max_hops.min(8);
-        for ttl in 1..=hops {
-            self.schedule(
-                ttl as u64 * 320,
-                RuntimeEvent::TraceHop {
-                    job,
-                    hop: TraceHop {
-                        ttl,
-                        address: Some(format!("192.0.2.{ttl}")),
-                        hostname: None,
-                        latency_ms: Some(self.scenario.latency_ms / 2 + ttl as u64 * 2),
-                    },
+        let host = match request.host.trim() {
+            "" => "mail.example.net",
+            host => host,
+        };
+        let tls = || TlsSummary {
+            version: "TLSv1_3".into(),
+            cipher: "TLS13_AES_256_GCM_SHA384".into(),
+        };
+        let smtp = format!("220 {host} ESMTP ready");
+        // Submission and IMAPS are in order; the old SMTPS certificate has
+        // lapsed, IMAP still accepts LOGIN in the clear and POP3 is off.
+        let checks = MAIL_PORTS.map(|(port, protocol, implicit_tls)| {
+            let check = MailCheck::new(port, protocol, implicit_tls);
+            match port {
+                25 => MailCheck {
+                    banner: Some(smtp.clone()),
+                    starttls: Some(true),
+                    tls: Some(tls()),
+                    ..check
                 },
-            );
+                587 => MailCheck {
+                    banner: Some(smtp.clone()),
+                    starttls: Some(true),
+                    tls: Some(tls()),
+                    auth: vec!["PLAIN".into(), "LOGIN".into()],
+                    ..check
+                },
+                465 => MailCheck {
+                    tls_error: Some("invalid peer certificate: Expired".into()),
+                    ..check
+                },
+                143 => MailCheck {
+                    banner: Some("* OK [CAPABILITY IMAP4rev1 STARTTLS] Dovecot ready.".into()),
+                    starttls: Some(true),
+                    tls: Some(tls()),
+                    auth: vec!["PLAIN".into(), "LOGIN".into()],
+                    cleartext_auth: true,
+                    ..check
+                },
+                993 => MailCheck {
+                    banner: Some("* OK Dovecot ready.".into()),
+                    tls: Some(tls()),
+                    auth: vec!["PLAIN".into()],
+                    ..check
+                },
+                995 => MailCheck {
+                    banner: Some("+OK Dovecot ready.".into()),
+                    tls: Some(tls()),
+                    auth: vec!["PLAIN".into(), "USER".into()],
+                    ..check
+                },
+                _ => MailCheck {
+                    error: Some("connection refused".into()),
+                    ..check
+                },
+            }
+        });
+        let latency = self.scenario.latency_ms;
+        let mut delay = 0;
+        for check in checks {
+            delay += self.rng.jitter(latency.max(20) * 3, 30);
+            self.schedule(delay, RuntimeEvent::MailResult { job, check });
         }
-        self.schedule(2_900, RuntimeEvent::TraceFinished { job, hops });
+        self.schedule(delay + 100, RuntimeEvent::MailFinished { job });
     }
 
     fn start_port_scan(&mut self, job: JobId, request: PortScanRequest) {
@@ -397,20 +1648,51 @@ impl DemoRuntime {
                 server: Some("demo.invalid".into()),
             },
         );
+        let mut latency = Vec::new();
+        let mut probe = |runtime: &mut Self, delay: u64, phase: SpeedPhase, rtt_ms: u64| {
+            let sample = LatencySample {
+                phase,
+                rtt_ms: Some(rtt_ms),
+            };
+            latency.push(sample);
+            runtime.schedule(delay, RuntimeEvent::PublicSpeedLatency { job, sample });
+        };
+        for step in 1..=3 {
+            probe(self, step * 80, SpeedPhase::Idle, 16 + step);
+        }
         for step in 1..=8 {
             let elapsed_ms = request.max_duration_ms.saturating_mul(step) / 8;
             let bytes_per_second = 4_000_000 + step * 800_000;
             self.schedule(
-                step * 320,
+                240 + step * 240,
                 RuntimeEvent::PublicSpeedSample {
                     job,
                     sample: SpeedSample {
                         elapsed_ms,
                         bytes: bytes_per_second * elapsed_ms / 1_000,
                         bytes_per_second,
+                        phase: SpeedPhase::Download,
+                    },
+                },
+            );
+            probe(self, 240 + step * 240, SpeedPhase::Download, 20 + step * 4);
+        }
+        for step in 1..=4 {
+            let phase_ms = request.max_duration_ms.saturating_mul(step) / 8;
+            let bytes_per_second = 1_500_000 + step * 100_000;
+            self.schedule(
+                2_160 + step * 160,
+                RuntimeEvent::PublicSpeedSample {
+                    job,
+                    sample: SpeedSample {
+                        elapsed_ms: request.max_duration_ms + phase_ms,
+                        bytes: bytes_per_second * phase_ms / 1_000,
+                        bytes_per_second,
+                        phase: SpeedPhase::Upload,
                     },
                 },
             );
+            probe(self, 2_160 + step * 160, SpeedPhase::Upload, 50 + step * 8);
         }
         self.schedule(
             2_900,
@@ -420,6 +1702,8 @@ impl DemoRuntime {
                     average_bytes_per_second: 9_200_000,
                     peak_bytes_per_second: 10_400_000,
                     total_bytes: 138_000_000,
+                    upload_bytes_per_second: Some(1_750_000),
+                    bufferbloat: Some(BufferbloatSummary::from_samples(&latency)),
                 },
             },
         );
@@ -638,14 +1922,32 @@ impl DemoRuntime {
             .adapters
             .iter()
             .enumerate()
-            .map(|(index, adapter)| TrafficRow {
-                name: adapter.name.clone(),
-                download_bps: self.scenario.download_bps / (index as u64 + 1),
-                upload_bps: self.scenario.upload_bps / (index as u64 + 1),
-                total_download: 8_589_934_592 * (index as u64 + 1),
-                total_upload: 1_610_612_736 * (index as u64 + 1),
-                session_download: 734_003_200 * (index as u64 + 1),
-                session_upload: 125_829_120 * (index as u64 + 1),
+            .map(|(index, adapter)| {
+                let download_bps = self.scenario.download_bps / (index as u64 + 1);
+                let upload_bps = self.scenario.upload_bps / (index as u64 + 1);
+                // Full-size frames at the simulated rate, on top of a boot-time base.
+                let packets =
+                    5_000_000 + (download_bps + upload_bps) / 1_500 * self.elapsed_ms / 1_000;
+                let drop_per_mille = if index == 0 {
+                    self.scenario.drop_per_mille
+                } else {
+                    0
+                };
+                TrafficRow {
+                    name: adapter.name.clone(),
+                    download_bps,
+                    upload_bps,
+                    total_download: 8_589_934_592 * (index as u64 + 1),
+                    total_upload: 1_610_612_736 * (index as u64 + 1),
+                    session_download: 734_003_200 * (index as u64 + 1),
+                    session_upload: 125_829_120 * (index as u64 + 1),
+                    link: Some(LinkCounters {
+                        packets,
+                        drops: packets * drop_per_mille / 1_000,
+                        errors: packets * drop_per_mille / 4_000,
+                        ..LinkCounters::default()
+                    }),
+                }
             })
             .collect()
     }
@@ -668,6 +1970,8 @@ fn event_job(event: &RuntimeEvent) -> Option<JobId> {
         | RuntimeEvent::ScanStarted { job, .. }
         | RuntimeEvent::ScanProgress { job, .. }
         | RuntimeEvent::ScanHostFound { job, .. }
+        | RuntimeEvent::ScanHostEnriched { job, .. }
+        | RuntimeEvent::ScanServicesFound { job, .. }
         | RuntimeEvent::ScanFinished { job }
         | RuntimeEvent::ScanCancelled { job }
         | RuntimeEvent::PingStarted { job }
@@ -685,6 +1989,7 @@ fn event_job(event: &RuntimeEvent) -> Option<JobId> {
         | RuntimeEvent::PortScanFailed { job, .. }
         | RuntimeEvent::PublicSpeedStarted { job, .. }
         | RuntimeEvent::PublicSpeedSample { job, .. }
+        | RuntimeEvent::PublicSpeedLatency { job, .. }
         | RuntimeEvent::PublicSpeedFinished { job, .. }
         | RuntimeEvent::PublicSpeedFailed { job, .. }
         | RuntimeEvent::LinkQualityStarted { job, .. }
@@ -695,11 +2000,88 @@ fn event_job(event: &RuntimeEvent) -> Option<JobId> {
         | RuntimeEvent::LanSpeedStatus { job, .. }
         | RuntimeEvent::LanSpeedSample { job, .. }
         | RuntimeEvent::LanSpeedFinished { job, .. }
-        | RuntimeEvent::LanSpeedFailed { job, .. } => Some(*job),
+        | RuntimeEvent::LanSpeedFailed { job, .. }
+        | RuntimeEvent::MtuStarted { job }
+        | RuntimeEvent::MtuProbe { job, .. }
+        | RuntimeEvent::MtuFinished { job, .. }
+        | RuntimeEvent::MtuFailed { job, .. }
+        | RuntimeEvent::DscpStarted { job }
+        | RuntimeEvent::DscpHop { job, .. }
+        | RuntimeEvent::DscpFinished { job, .. }
+        | RuntimeEvent::DscpFailed { job, .. }
+        | RuntimeEvent::MulticastStarted { job }
+        | RuntimeEvent::MulticastSample { job, .. }
+        | RuntimeEvent::MulticastFinished { job, .. }
+        | RuntimeEvent::MulticastFailed { job, .. }
+        | RuntimeEvent::UpnpStarted { job }
+        | RuntimeEvent::UpnpGateway { job, .. }
+        | RuntimeEvent::UpnpMappings { job, .. }
+        | RuntimeEvent::UpnpFinished { job, .. }
+        | RuntimeEvent::UpnpFailed { job, .. }
+        | RuntimeEvent::NatStarted { job }
+        | RuntimeEvent::NatProbe { job, .. }
+        | RuntimeEvent::NatFinished { job, .. }
+        | RuntimeEvent::NatFailed { job, .. }
+        | RuntimeEvent::Ipv6Started { job }
+        | RuntimeEvent::Ipv6Result { job, .. }
+        | RuntimeEvent::Ipv6Finished { job }
+        | RuntimeEvent::Ipv6Failed { job, .. }
+        | RuntimeEvent::DnsBenchStarted { job, .. }
+        | RuntimeEvent::DnsBenchScore { job, .. }
+        | RuntimeEvent::DnsBenchFinished { job }
+        | RuntimeEvent::DnsBenchFailed { job, .. }
+        | RuntimeEvent::DnsFilterStarted { job, .. }
+        | RuntimeEvent::DnsFilterResult { job, .. }
+        | RuntimeEvent::DnsFilterFinished { job }
+        | RuntimeEvent::DnsFilterFailed { job, .. }
+        | RuntimeEvent::MailResult { job, .. }
+        | RuntimeEvent::MailFinished { job }
+        | RuntimeEvent::MailFailed { job, .. }
+        | RuntimeEvent::VoipStarted { job, .. }
+        | RuntimeEvent::VoipSample { job, .. }
+        | RuntimeEvent::VoipFinished { job, .. }
+        | RuntimeEvent::VoipFailed { job, .. }
+        | RuntimeEvent::GamingStarted { job, .. }
+        | RuntimeEvent::GamingScore { job, .. }
+        | RuntimeEvent::GamingFinished { job }
+        | RuntimeEvent::GamingFailed { job, .. }
+        | RuntimeEvent::CloudStarted { job, .. }
+        | RuntimeEvent::CloudResult { job, .. }
+        | RuntimeEvent::CloudFinished { job }
+        | RuntimeEvent::CloudFailed { job, .. }
+        | RuntimeEvent::WifiSample { job, .. }
+        | RuntimeEvent::WifiFinished { job }
+        | RuntimeEvent::WifiFailed { job, .. }
+        | RuntimeEvent::AbTestStarted { job }
+        | RuntimeEvent::AbTestLeg { job, .. }
+        | RuntimeEvent::AbTestFinished { job }
+        | RuntimeEvent::AbTestFailed { job, .. }
+        | RuntimeEvent::BluetoothDeviceSeen { job, .. }
+        | RuntimeEvent::BluetoothScanFinished { job }
+        | RuntimeEvent::BluetoothScanFailed { job, .. }
+        | RuntimeEvent::WebCheckFinished { job, .. }
+        | RuntimeEvent::WebCheckFailed { job, .. }
+        | RuntimeEvent::WatchChecked { job, .. }
+        | RuntimeEvent::WatchFinished { job } => Some(*job),
         _ => None,
     }
 }
 
+/// Nothing on the host is probed; every check reports the simulation.
+fn demo_doctor_report() -> Vec<DoctorCheck> {
+    [
+        DoctorCheckKind::Privileges,
+        DoctorCheckKind::Icmp,
+        DoctorCheckKind::PacketCapture,
+        DoctorCheckKind::Firewall,
+        DoctorCheckKind::Config,
+        DoctorCheckKind::Connectivity,
+    ]
+    .into_iter()
+    .map(|kind| DoctorCheck::new(kind, DoctorStatus::Ok, "simulated"))
+    .collect()
+}
+
 fn cancelled_event(job: JobId) -> RuntimeEvent {
     match job.tool {
         ToolKind::Dashboard => RuntimeEvent::DashboardRefreshCancelled { job },
@@ -712,7 +2094,40 @@ fn cancelled_event(job: JobId) -> RuntimeEvent {
             ),
         },
         ToolKind::Traffic => RuntimeEvent::TrafficRefreshCancelled { job },
+        ToolKind::Doctor => RuntimeEvent::DoctorFinished {
+            job,
+            checks: Vec::new(),
+        },
+        ToolKind::Update => RuntimeEvent::UpdateCheckFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "update check cancelled"),
+        },
+        ToolKind::WebCheck => RuntimeEvent::WebCheckFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "web check cancelled"),
+        },
+        ToolKind::ChartExport => RuntimeEvent::ChartExportFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "chart export cancelled"),
+        },
+        ToolKind::Dhcp => RuntimeEvent::DhcpProbeFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "DHCP probe cancelled"),
+        },
+        ToolKind::AuditExport => RuntimeEvent::AuditExportFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "audit export cancelled"),
+        },
+        ToolKind::Asn => RuntimeEvent::AsnLookupFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "ASN lookup cancelled"),
+        },
+        ToolKind::Cve => RuntimeEvent::CveLookupFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::Cancelled, "CVE lookup cancelled"),
+        },
         ToolKind::Scanner => RuntimeEvent::ScanCancelled { job },
+        ToolKind::Bluetooth => RuntimeEvent::BluetoothScanFinished { job },
         ToolKind::Ping => RuntimeEvent::PingFinished {
             job,
             summary: PingSummary {
@@ -737,6 +2152,8 @@ fn cancelled_event(job: JobId) -> RuntimeEvent {
                 average_bytes_per_second: 0,
                 peak_bytes_per_second: 0,
                 total_bytes: 0,
+                upload_bytes_per_second: None,
+                bufferbloat: None,
             },
         },
         ToolKind::LinkQuality => RuntimeEvent::LinkQualityFinished {
@@ -773,6 +2190,51 @@ fn cancelled_event(job: JobId) -> RuntimeEvent {
                 out_of_order: None,
             },
         },
+        ToolKind::Mtu => RuntimeEvent::MtuFinished {
+            job,
+            summary: MtuSummary {
+                path_mtu: None,
+                probes: 0,
+            },
+        },
+        ToolKind::Dscp => RuntimeEvent::DscpFinished {
+            job,
+            summary: DscpSummary {
+                sent: 0,
+                reached: false,
+                verdict: DscpVerdict::Unknown,
+            },
+        },
+        ToolKind::Multicast => RuntimeEvent::MulticastFinished {
+            job,
+            summary: MulticastSample::default(),
+        },
+        ToolKind::Upnp => RuntimeEvent::UpnpFinished {
+            job,
+            outcome: UpnpOutcome::Listed,
+        },
+        ToolKind::Nat => RuntimeEvent::NatFinished {
+            job,
+            summary: NatSummary {
+                nat_type: NatType::Unknown,
+                local: String::new(),
+                mapped: None,
+                hairpin: None,
+            },
+        },
+        ToolKind::Ipv6 => RuntimeEvent::Ipv6Finished { job },
+        ToolKind::DnsBench => RuntimeEvent::DnsBenchFinished { job },
+        ToolKind::DnsFilter => RuntimeEvent::DnsFilterFinished { job },
+        ToolKind::Mail => RuntimeEvent::MailFinished { job },
+        ToolKind::Voip => RuntimeEvent::VoipFinished {
+            job,
+            summary: VoipSample::default(),
+        },
+        ToolKind::Gaming => RuntimeEvent::GamingFinished { job },
+        ToolKind::Cloud => RuntimeEvent::CloudFinished { job },
+        ToolKind::Wifi => RuntimeEvent::WifiFinished { job },
+        ToolKind::AbTest => RuntimeEvent::AbTestFinished { job },
+        ToolKind::Watch => RuntimeEvent::WatchFinished { job },
     }
 }
 
@@ -793,7 +2255,7 @@ mod tests {
     use super::*;
     use iptools_core::{
         AdapterConfigRequest, Effect, JobId, LanSpeedMode, LanSpeedRequest, PingRequest,
-        ScanRequest,
+        ScanRequest, dns_filtering_upstream, encrypted_dns_checks,
     };
 
     #[test]
@@ -817,6 +2279,9 @@ mod tests {
             request: ScanRequest {
                 cidr: "192.168.1.0/24".into(),
                 concurrency: 50,
+                adaptive: true,
+                enrich: false,
+                dns_zone: None,
             },
         });
         assert!(matches!(
@@ -829,6 +2294,59 @@ mod tests {
             [RuntimeEvent::ScanCancelled { .. }]
         ));
         assert!(runtime.advance(10_000).is_empty());
+
+        let job = JobId {
+            generation: 2,
+            ..job
+        };
+        runtime.dispatch(Effect::StartScan {
+            job,
+            request: ScanRequest {
+                cidr: "192.168.1.0/24".into(),
+                concurrency: 50,
+                adaptive: true,
+                enrich: true,
+                dns_zone: None,
+            },
+        });
+        let events = runtime.advance(10_000);
+        assert!(events.iter().all(|event| match event {
+            RuntimeEvent::ScanHostFound { host, .. } =>
+                host.shares.is_empty() && host.web.is_empty(),
+            _ => true,
+        }));
+        let shares = events
+            .iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::ScanHostEnriched { shares, .. } => Some(shares.len()),
+                _ => None,
+            })
+            .sum::<usize>();
+        assert_eq!(shares, 3);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::ScanHostEnriched { web, .. }
+                if web.iter().any(|service| service.title.contains("LaserJet"))
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::ScanHostEnriched { iot, .. }
+                if iptools_core::device_types(iot)
+                    == [iptools_core::DeviceType::MqttBroker, iptools_core::DeviceType::Controller]
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::ScanHostFound { host, .. }
+                if host.names.len() > 1 && host.seen_at == DEMO_SEEN_AT
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::ScanServicesFound { services, .. } if !services.is_empty()
+        )));
+        assert!(matches!(
+            events.last(),
+            Some(RuntimeEvent::ScanFinished { .. })
+        ));
     }
 
     #[test]
@@ -870,6 +2388,424 @@ mod tests {
                 .iter()
                 .all(|event| event_job(event) != Some(trace))
         );
+
+        let mtu = JobId {
+            tool: ToolKind::Mtu,
+            generation: 3,
+        };
+        for (scenario, expected) in [
+            (ScenarioId::HomeNetwork, 1_500),
+            (ScenarioId::MultiAdapter, 9_000),
+        ] {
+            let mut runtime = DemoRuntime::new(scenario).unwrap();
+            runtime.dispatch(Effect::StartMtu {
+                job: mtu,
+                request: MtuRequest::default(),
+            });
+            assert!(runtime.advance(10_000).iter().any(|event| matches!(
+                event,
+                RuntimeEvent::MtuFinished { job: current, summary }
+                    if *current == mtu && summary.path_mtu == Some(expected)
+            )));
+        }
+
+        let dscp = JobId {
+            tool: ToolKind::Dscp,
+            generation: 5,
+        };
+        runtime.dispatch(Effect::StartDscp {
+            job: dscp,
+            request: DscpRequest::default(),
+        });
+        assert!(runtime.advance(10_000).iter().any(|event| matches!(
+            event,
+            RuntimeEvent::DscpFinished { job: current, summary }
+                if *current == dscp
+                    && summary.reached
+                    && summary.verdict == DscpVerdict::Rewritten { ttl: 3, observed: 0 }
+        )));
+
+        let multicast = JobId {
+            tool: ToolKind::Multicast,
+            generation: 5,
+        };
+        runtime.dispatch(Effect::StartMulticast {
+            job: multicast,
+            request: MulticastRequest::default(),
+        });
+        assert!(runtime.advance(10_000).iter().any(|event| matches!(
+            event,
+            RuntimeEvent::MulticastFinished { job: current, summary }
+                if *current == multicast
+                    && summary.senders == 1
+                    && summary.lost == 1
+                    && summary.jitter_ms.is_some()
+        )));
+
+        let upnp = JobId {
+            tool: ToolKind::Upnp,
+            generation: 5,
+        };
+        runtime.dispatch(Effect::StartUpnp {
+            job: upnp,
+            request: UpnpRequest {
+                action: UpnpAction::Add,
+                ..UpnpRequest::default()
+            },
+        });
+        runtime.advance(10_000);
+        runtime.dispatch(Effect::StartUpnp {
+            job: upnp,
+            request: UpnpRequest::default(),
+        });
+        assert!(runtime.advance(10_000).iter().any(|event| matches!(
+            event,
+            RuntimeEvent::UpnpMappings { job: current, mappings }
+                if *current == upnp
+                    && mappings.iter().any(|mapping| mapping.external_port == 50_505)
+        )));
+
+        let nat = JobId {
+            tool: ToolKind::Nat,
+            generation: 5,
+        };
+        runtime.dispatch(Effect::StartNat {
+            job: nat,
+            request: NatRequest::default(),
+        });
+        assert!(runtime.advance(10_000).iter().any(|event| matches!(
+            event,
+            RuntimeEvent::NatFinished { job: current, summary }
+                if *current == nat && summary.nat_type == NatType::PortRestrictedCone
+        )));
+
+        let ipv6 = JobId {
+            tool: ToolKind::Ipv6,
+            generation: 5,
+        };
+        runtime.dispatch(Effect::StartIpv6 {
+            job: ipv6,
+            request: Ipv6Request::default(),
+        });
+        let events = runtime.advance(10_000);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(
+                    event,
+                    RuntimeEvent::Ipv6Result { job, result }
+                        if *job == ipv6 && result.verdict == Ipv6Verdict::Pass
+                ))
+                .count(),
+            Ipv6Check::ALL.len()
+        );
+
+        let dns = JobId {
+            tool: ToolKind::DnsBench,
+            generation: 5,
+        };
+        runtime.dispatch(Effect::StartDnsBench {
+            job: dns,
+            request: DnsBenchRequest::default(),
+        });
+        let scores = runtime
+            .advance(10_000)
+            .into_iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::DnsBenchScore { job, score } if job == dns => Some(score),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(scores.len(), 8);
+        assert!(scores.iter().all(|score| score.failures == 0));
+        assert!(
+            encrypted_dns_checks(&scores)
+                .iter()
+                .all(|check| check.works)
+        );
+
+        let filter = JobId {
+            tool: ToolKind::DnsFilter,
+            generation: 6,
+        };
+        runtime.dispatch(Effect::StartDnsFilter {
+            job: filter,
+            request: DnsFilterRequest::default(),
+        });
+        let probes = runtime
+            .advance(10_000)
+            .into_iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::DnsFilterResult { job, probe } if job == filter => Some(probe),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(probes.len(), 15);
+        assert_eq!(
+            dns_filtering_upstream(&probes),
+            Some(DnsFilterUpstream::System)
+        );
+
+        let mail = JobId {
+            tool: ToolKind::Mail,
+            generation: 7,
+        };
+        runtime.dispatch(Effect::StartMail {
+            job: mail,
+            request: MailRequest::default(),
+        });
+        let checks = runtime
+            .advance(10_000)
+            .into_iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::MailResult { job, check } if job == mail => Some(check),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(checks.len(), MAIL_PORTS.len());
+        assert_eq!(iptools_core::mail_tally(&checks), (6, 2));
+
+        let voip = JobId {
+            tool: ToolKind::Voip,
+            generation: 8,
+        };
+        runtime.dispatch(Effect::StartVoip {
+            job: voip,
+            request: VoipRequest {
+                peer: "192.0.2.9".into(),
+                duration_secs: 4,
+                ..VoipRequest::default()
+            },
+        });
+        let summary = runtime
+            .advance(10_000)
+            .into_iter()
+            .find_map(|event| match event {
+                RuntimeEvent::VoipFinished { job, summary } if job == voip => Some(summary),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            (summary.sent, summary.received, summary.lost),
+            (200, 198, 2)
+        );
+        assert!(summary.score(iptools_core::VoipCodec::G711).is_some());
+
+        let gaming = JobId {
+            tool: ToolKind::Gaming,
+            generation: 9,
+        };
+        runtime.dispatch(Effect::StartGaming {
+            job: gaming,
+            request: GamingRequest {
+                preset: iptools_core::GamingPreset::Europe,
+                custom: "LAN=udp://192.0.2.5:7777".into(),
+                ..GamingRequest::default()
+            },
+        });
+        let mut scores = runtime
+            .advance(10_000)
+            .into_iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::GamingScore { job, score } if job == gaming => Some(score),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(scores.len(), 5);
+        iptools_core::rank_gaming_scores(&mut scores);
+        assert_eq!(scores[0].endpoint.region, "Europe (Ireland)");
+        assert!(scores.iter().all(|score| score.jitter_ms.is_some()));
+
+        let cloud = JobId {
+            tool: ToolKind::Cloud,
+            generation: 10,
+        };
+        runtime.dispatch(Effect::StartCloud {
+            job: cloud,
+            request: CloudRequest::default(),
+        });
+        let probes = runtime
+            .advance(10_000)
+            .into_iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::CloudResult { job, probe } if job == cloud => Some(probe),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(probes.len(), 20);
+        assert_eq!(
+            iptools_core::cloud_verdict(&probes),
+            Some(iptools_core::CloudVerdict::ProviderOutage(vec![
+                "Azure japaneast".into()
+            ]))
+        );
+
+        let wifi = JobId {
+            tool: ToolKind::Wifi,
+            generation: 12,
+        };
+        runtime.dispatch(Effect::StartWifi {
+            job: wifi,
+            request: WifiRequest {
+                adapter: "Wi-Fi".into(),
+                guid: "field-wifi".into(),
+                interval_secs: 3,
+            },
+        });
+        let samples = runtime
+            .advance(10_000)
+            .into_iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::WifiSample { job, sample } if job == wifi => Some(sample),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let roams = samples
+            .windows(2)
+            .filter_map(|pair| {
+                iptools_core::WifiRoam::between(
+                    pair[0].link.as_ref()?,
+                    pair[1].link.as_ref()?,
+                    pair[1].elapsed_ms,
+                    "",
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(samples.len(), 12);
+        assert_eq!(roams.len(), 1);
+        assert_eq!((roams[0].from_channel, roams[0].to_channel), (6, 149));
+        assert!(samples.iter().any(|sample| sample.link.is_none()));
+        let loads = iptools_core::channel_occupancy(
+            &samples[0].nearby,
+            iptools_core::WifiBand::TwoPointFourGhz,
+            "02:AA:BB:CC:DD:01",
+        );
+        assert_eq!(
+            iptools_core::least_congested_channel(&loads, iptools_core::WifiBand::TwoPointFourGhz),
+            Some(11)
+        );
+
+        let bluetooth = JobId {
+            tool: ToolKind::Bluetooth,
+            generation: 13,
+        };
+        runtime.dispatch(Effect::StartBluetoothScan {
+            job: bluetooth,
+            request: BluetoothRequest::default(),
+        });
+        let mut state = iptools_core::BluetoothState {
+            job: Some(bluetooth),
+            ..Default::default()
+        };
+        for event in runtime.advance(10_000) {
+            if let RuntimeEvent::BluetoothDeviceSeen { job, device } = event
+                && job == bluetooth
+            {
+                state.record(device);
+            }
+        }
+        assert_eq!(state.devices.len(), 5);
+        assert_eq!(state.devices[0].name.as_deref(), Some("iPhone"));
+        assert_eq!(state.devices[0].manufacturer(), Some("Apple"));
+        assert!(state.devices.iter().any(|device| device.name.is_none()));
+
+        let watch = JobId {
+            tool: ToolKind::Watch,
+            generation: 11,
+        };
+        runtime.dispatch(Effect::StartWatch {
+            job: watch,
+            request: WatchRequest {
+                targets: iptools_core::parse_watch_targets(
+                    "NAS=192.168.1.10, Wiki=https://wiki.example.com/health, old-vpn.invalid:443",
+                )
+                .unwrap(),
+                timeout_ms: iptools_core::WATCH_TIMEOUT_MS,
+            },
+        });
+        let events = runtime.advance(10_000);
+        let checks = events
+            .iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::WatchChecked { job, check } if *job == watch => Some(check),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(checks.len(), 3);
+        assert!(checks[0].latency_ms.unwrap() < checks[1].latency_ms.unwrap());
+        assert_eq!(
+            checks[2].error.as_deref(),
+            Some("old-vpn.invalid could not be resolved")
+        );
+        assert!(events.contains(&RuntimeEvent::WatchFinished { job: watch }));
+
+        let speed = JobId {
+            tool: ToolKind::PublicSpeed,
+            generation: 4,
+        };
+        runtime.dispatch(Effect::StartPublicSpeed {
+            job: speed,
+            request: PublicSpeedRequest::default(),
+        });
+        let events = runtime.advance(10_000);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::PublicSpeedSample { job: current, sample }
+                if *current == speed && sample.phase == SpeedPhase::Upload
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::PublicSpeedFinished { job: current, summary }
+                if *current == speed
+                    && summary.bufferbloat.as_ref().and_then(|value| value.grade)
+                        == Some(iptools_core::BufferbloatGrade::C)
+        )));
+    }
+
+    #[test]
+    fn ab_test_puts_the_cable_ahead_of_wifi() {
+        let mut runtime = DemoRuntime::new(ScenarioId::MultiAdapter).unwrap();
+        let job = JobId {
+            tool: ToolKind::AbTest,
+            generation: 1,
+        };
+        runtime.dispatch(Effect::StartAbTest {
+            job,
+            request: AbRequest {
+                first: "Wi-Fi 6E".into(),
+                second: "172.16.10.24".into(),
+                ..AbRequest::default()
+            },
+        });
+        let events = runtime.advance(10_000);
+        let legs = events
+            .iter()
+            .filter_map(|event| match event {
+                RuntimeEvent::AbTestLeg { job: current, leg } if *current == job => Some(leg),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs[0].source, "192.168.8.24");
+        assert_eq!(legs[0].loss_percent(), Some(20.0));
+        let verdict = iptools_core::ab_verdict(legs[0], legs[1]);
+        assert_eq!(verdict.winner, Some(AbSide::B));
+        assert!(events.contains(&RuntimeEvent::AbTestFinished { job }));
+
+        runtime.dispatch(Effect::StartAbTest {
+            job,
+            request: AbRequest {
+                first: "Hyper-V".into(),
+                second: "WireGuard".into(),
+                ..AbRequest::default()
+            },
+        });
+        let events = runtime.advance(10_000);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::AbTestLeg { leg, .. }
+                if leg.side == AbSide::A && leg.error.as_deref() == Some("Hyper-V has no IPv4 address")
+        )));
     }
 
     #[test]
@@ -895,6 +2831,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn seeded_runtimes_vary_samples_reproducibly() {
+        let traffic = |seed| {
+            let mut runtime = DemoRuntime::with_seed(ScenarioId::HomeNetwork, seed).unwrap();
+            (0..4)
+                .map(|generation| {
+                    runtime.dispatch(Effect::RefreshTraffic {
+                        job: JobId {
+                            tool: ToolKind::Traffic,
+                            generation,
+                        },
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(traffic(42), traffic(42));
+        assert_ne!(traffic(42), traffic(7));
+        let canonical = traffic(0);
+        assert!(canonical.windows(2).all(|pair| {
+            let [
+                [RuntimeEvent::TrafficRefreshFinished { rows: left, .. }],
+                [RuntimeEvent::TrafficRefreshFinished { rows: right, .. }],
+            ] = [pair[0].as_slice(), pair[1].as_slice()]
+            else {
+                return false;
+            };
+            left == right
+        }));
+    }
+
     #[test]
     fn lan_speed_timeline_is_typed_deterministic_and_cancellable() {
         let mut first = DemoRuntime::new(ScenarioId::HomeNetwork).unwrap();
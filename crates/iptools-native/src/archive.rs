@@ -0,0 +1,271 @@
+//! 数据迁移：`iptools export` 把配置文件与历史库（设备清单、事件日志、监视、测速与流量记录）
+//! 打包成一个 JSON 归档，`iptools import` 在另一台机器上整体恢复。
+//!
+//! 导入会覆盖现有配置与历史库；两者都先备份为 `<文件名>.<时间>.bak`，备份不成就不导入。
+//! 历史库在一个事务里整体替换，提交后才写配置，中途失败时两者都保持原样。
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use iptools_core::CONFIG_VERSION;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ConfigData, FsConfigStore, write_atomic};
+use crate::store::{HistoryDump, HistoryStore};
+
+const ARCHIVE_FORMAT: &str = "iptools-archive";
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Archive {
+    format: String,
+    version: u32,
+    /// 本地时间 `YYYY-MM-DD HH:MM:SS`；只在显示时按配置的日期与时钟格式改写。
+    exported_at: String,
+    config: ConfigData,
+    #[serde(default)]
+    history: HistoryDump,
+}
+
+/// 归档内容的条数与导出时间，命令行据此报告结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ArchiveSummary {
+    /// 按归档中配置的日期与时钟格式显示的导出时间。
+    pub exported_at: String,
+    pub events: usize,
+    pub devices: usize,
+    pub watch_checks: usize,
+    pub speed_results: usize,
+    pub traffic_samples: usize,
+}
+
+impl ArchiveSummary {
+    fn of(archive: &Archive) -> Self {
+        let history = &archive.history;
+        Self {
+            exported_at: iptools_core::display_stamp(
+                &archive.exported_at,
+                archive.config.region_format,
+                archive.config.clock_format,
+            ),
+            events: history.events.len(),
+            devices: history.inventory.len(),
+            watch_checks: history.watch_checks.len(),
+            speed_results: history.speed_results.len(),
+            traffic_samples: history.traffic_samples.len(),
+        }
+    }
+}
+
+impl fmt::Display for ArchiveSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "配置、{} 台设备、{} 条事件、{} 条监视结果、{} 次测速、{} 条流量采样",
+            self.devices, self.events, self.watch_checks, self.speed_results, self.traffic_samples
+        )
+    }
+}
+
+/// 导出当前配置与历史库；历史库不存在时只导出配置。
+pub(crate) fn export(config_path: Option<&str>, file: &Path) -> Result<ArchiveSummary, String> {
+    let store = FsConfigStore::new(config_path);
+    let config = store
+        .load()?
+        .ok_or_else(|| format!("{} does not exist", store.path().display()))?;
+    let database = HistoryStore::path_beside(store.path());
+    let history = if database.exists() {
+        HistoryStore::open(&database)?.dump()?
+    } else {
+        HistoryDump::default()
+    };
+    let archive = Archive {
+        format: ARCHIVE_FORMAT.into(),
+        version: ARCHIVE_VERSION,
+        exported_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        config,
+        history,
+    };
+    let content = serde_json::to_vec_pretty(&archive).map_err(|error| error.to_string())?;
+    write_atomic(file, &content).map_err(|error| format!("{}: {error}", file.display()))?;
+    Ok(ArchiveSummary::of(&archive))
+}
+
+/// 用归档替换当前配置与历史库，返回导入的条数与原配置、原历史库的备份路径。
+/// 调用方须确保没有其他实例在运行，否则它退出时会写回旧数据。
+pub(crate) fn import(
+    config_path: Option<&str>,
+    file: &Path,
+) -> Result<(ArchiveSummary, Vec<PathBuf>), String> {
+    let content =
+        std::fs::read_to_string(file).map_err(|error| format!("{}: {error}", file.display()))?;
+    let mut archive: Archive = serde_json::from_str(&content)
+        .map_err(|error| format!("{} is not an iptools archive: {error}", file.display()))?;
+    if archive.format != ARCHIVE_FORMAT {
+        return Err(format!("{} is not an iptools archive", file.display()));
+    }
+    if archive.version > ARCHIVE_VERSION || archive.config.version > CONFIG_VERSION {
+        return Err(format!(
+            "{} was written by a newer iptools; upgrade before importing",
+            file.display()
+        ));
+    }
+    archive.config.migrate();
+
+    let store = FsConfigStore::new(config_path);
+    let mut backups = Vec::new();
+    if store.path().exists() {
+        let backup = store.try_backup().map_err(|error| {
+            format!(
+                "{}: backup failed, nothing imported: {error}",
+                store.path().display()
+            )
+        })?;
+        backups.push(backup);
+    }
+    let database = HistoryStore::path_beside(store.path());
+    let existed = database.exists();
+    let history = HistoryStore::open(&database)?;
+    if existed {
+        let backup = history.backup().map_err(|error| {
+            format!(
+                "{}: backup failed, nothing imported: {error}",
+                database.display()
+            )
+        })?;
+        backups.push(backup);
+    }
+    history.restore(&archive.history)?;
+    store
+        .save(&archive.config)
+        .map_err(|error| format!("{}: {error}", store.path().display()))?;
+    Ok((ArchiveSummary::of(&archive), backups))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "iptools-archive-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn an_export_restores_config_and_history_on_another_machine() {
+        let old = temp_dir("old");
+        let old_config = old.join("config.toml");
+        let mut config = ConfigData {
+            scan_concurrency: 120,
+            region_format: iptools_core::RegionFormat::European,
+            ..ConfigData::default()
+        };
+        config.watch = iptools_core::parse_watch_targets("nas=192.168.1.10:445").unwrap();
+        FsConfigStore::new(old_config.to_str())
+            .save(&config)
+            .unwrap();
+        let history = HistoryStore::beside(&old_config).unwrap();
+        history
+            .replace_inventory(&[iptools_core::DeviceRecord {
+                key: "AA:BB:CC:DD:EE:FF".into(),
+                last_ip: "192.168.1.20".into(),
+                note: "printer".into(),
+                ..Default::default()
+            }])
+            .unwrap();
+        history
+            .record_watch(
+                Local::now().naive_local(),
+                &iptools_core::WatchCheck {
+                    target: config.watch[0].clone(),
+                    latency_ms: Some(2.5),
+                    error: None,
+                },
+            )
+            .unwrap();
+        let expected = history.dump().unwrap();
+        drop(history);
+        let file = old.join("iptools-export.json");
+        let summary = export(old_config.to_str(), &file).unwrap();
+        assert_eq!((summary.devices, summary.watch_checks), (1, 1));
+        // 归档里是 ISO 时间，报告按配置的格式显示
+        let exported_at = Local::now().format("%d.%m.%Y").to_string();
+        assert!(
+            summary.exported_at.starts_with(&exported_at),
+            "{}",
+            summary.exported_at
+        );
+
+        // 新机器上已有一份默认配置：被替换并留下备份
+        let new = temp_dir("new");
+        let new_config = new.join("config.toml");
+        FsConfigStore::new(new_config.to_str())
+            .save(&ConfigData::default())
+            .unwrap();
+        HistoryStore::beside(&new_config)
+            .unwrap()
+            .replace_inventory(&[iptools_core::DeviceRecord {
+                key: "11:22:33:44:55:66".into(),
+                ..Default::default()
+            }])
+            .unwrap();
+        let (imported, backups) = import(new_config.to_str(), &file).unwrap();
+        assert_eq!(imported, summary);
+        assert_eq!(backups.len(), 2, "{backups:?}");
+        assert!(backups.iter().all(|backup| backup.exists()));
+        // 历史库的备份保留了被替换的设备
+        assert_eq!(
+            HistoryStore::open(&backups[1])
+                .unwrap()
+                .inventory()
+                .unwrap()[0]
+                .key,
+            "11:22:33:44:55:66"
+        );
+        let restored = FsConfigStore::new(new_config.to_str())
+            .load()
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored.scan_concurrency, 120);
+        assert_eq!(restored.watch, config.watch);
+        assert_eq!(
+            HistoryStore::beside(&new_config).unwrap().dump().unwrap(),
+            expected
+        );
+
+        // 历史库中途写不进去时，库与配置都保持原样
+        FsConfigStore::new(new_config.to_str())
+            .save(&ConfigData::default())
+            .unwrap();
+        let before = std::fs::read_to_string(&new_config).unwrap();
+        rusqlite::Connection::open(HistoryStore::path_beside(&new_config))
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER full BEFORE INSERT ON watch_checks \
+                 BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+            )
+            .unwrap();
+        let error = import(new_config.to_str(), &file).unwrap_err();
+        assert!(error.contains("disk full"), "{error}");
+        assert_eq!(std::fs::read_to_string(&new_config).unwrap(), before);
+        assert_eq!(
+            HistoryStore::beside(&new_config).unwrap().dump().unwrap(),
+            expected,
+            "the inventory written before the failure is rolled back"
+        );
+
+        std::fs::write(&file, r#"{"format":"other","version":1}"#).unwrap();
+        assert!(import(new_config.to_str(), &file).is_err());
+        std::fs::remove_dir_all(old).unwrap();
+        std::fs::remove_dir_all(new).unwrap();
+    }
+}
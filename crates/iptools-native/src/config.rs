@@ -1,7 +1,12 @@
 //! Native configuration storage.
 //!
 //! The serializable schema lives in `iptools-core`; this module owns only the
-//! filesystem path, system-language bootstrap and atomic persistence.
+//! filesystem path, system-language bootstrap, atomic persistence and the
+//! backup of a file that cannot be used as-is before it is rewritten.
+//!
+//! The file is TOML with generated explanatory comments. A `config.json` left
+//! by older releases is read once and rewritten as `config.toml`; an explicit
+//! `--config` path ending in `.json` keeps using JSON.
 
 use std::{
     fs,
@@ -10,11 +15,170 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use chrono::Local;
 pub use iptools_core::ConfigData;
+use iptools_core::{CONFIG_VERSION, ConfigIssue};
 
 use crate::keymap::KeyMap;
 
-const DEFAULT_CONFIG_PATH: &str = "config.json";
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+const LEGACY_CONFIG_PATH: &str = "config.json";
+
+/// 生成文件时写在对应键或表头之前的说明：（表名, 键名, 中文, 英文）。
+/// 键名为空表示表头；同名表头只注释第一次出现。
+const COMMENTS: &[(&str, &str, &str, &str)] = &[
+    (
+        "",
+        "version",
+        "配置格式版本，由程序维护，请勿修改。",
+        "Schema version, maintained by iptools; do not edit.",
+    ),
+    (
+        "",
+        "language",
+        "界面语言：\"Zh\" 或 \"En\"；Ctrl+L 切换后自动保存。",
+        "Interface language: \"Zh\" or \"En\"; saved when toggled with Ctrl+L.",
+    ),
+    (
+        "",
+        "theme",
+        "配色主题：classic、nord、catppuccin-mocha、dracula。",
+        "Colour theme: classic, nord, catppuccin-mocha or dracula.",
+    ),
+    (
+        "",
+        "chart_style",
+        "历史图表：blocks 为方块迷你图；braille 用盲文字符画折线，分辨率更高，需要字体支持。",
+        "History charts: blocks draws sparkline bars; braille draws a finer line with braille characters and needs font support.",
+    ),
+    (
+        "",
+        "region_format",
+        "日期与数字格式：iso 为 2026-07-12、1.5；european 为 12.07.2026、1,5；us 为 07/12/2026、1.5。用于概览、事件日志和导出的报告，保存的时间戳始终为 ISO。",
+        "Date and number format: iso is 2026-07-12 and 1.5; european is 12.07.2026 and 1,5; us is 07/12/2026 and 1.5. Used on the Dashboard, in the event log and in exported reports; saved timestamps stay ISO.",
+    ),
+    (
+        "",
+        "clock_format",
+        "时钟：\"24h\" 显示 20:30:45，\"12h\" 显示 8:30:45 PM。",
+        "Clock: \"24h\" shows 20:30:45, \"12h\" shows 8:30:45 PM.",
+    ),
+    (
+        "",
+        "show_utc",
+        "在概览和事件日志的本地时间后附上 UTC 时间，便于跨时区核对故障记录。",
+        "Add the UTC time after local times on the Dashboard and in the event log, so incident notes line up across time zones.",
+    ),
+    (
+        "",
+        "color_mode",
+        "色彩模式：standard；color-blind 用蓝/橙代替绿/红；monochrome 不使用颜色，以符号标注状态。设置 NO_COLOR 环境变量时始终为单色。",
+        "Colour mode: standard; color-blind uses blue/orange instead of green/red; monochrome drops colour and marks states with symbols. Always monochrome when NO_COLOR is set.",
+    ),
+    (
+        "",
+        "scan_concurrency",
+        "局域网扫描同时探测的主机数，10–500。调大更快，但较弱的路由器或 Wi-Fi 可能丢包、漏报主机。",
+        "Hosts probed at once by the LAN scanner, 10–500. Higher is faster, but weak routers or Wi-Fi may drop probes and miss hosts.",
+    ),
+    (
+        "",
+        "scan_adaptive",
+        "自动调节并发：以 scan_concurrency 为上限起步，探测需要重试或出错增多时减半，链路恢复后逐步回升。",
+        "Adaptive concurrency: start at scan_concurrency and halve it when probes need retries or fail, climbing back as the link recovers.",
+    ),
+    (
+        "",
+        "drop_alert_percent",
+        "丢包告警阈值（%），0–20：某块网卡最近一分钟的丢弃与错误帧超过该比例时记入「问题」列表（F6）；0 关闭告警。",
+        "Drop alert threshold in percent, 0–20: an interface whose drops and errors over the last minute exceed it is listed under Problems (F6); 0 turns the alert off.",
+    ),
+    (
+        "",
+        "low_power",
+        "省电模式：auto 在电池供电时降低刷新频率，on 始终开启，off 始终关闭。",
+        "Low-power mode: auto slows refreshes on battery, on and off force it.",
+    ),
+    (
+        "",
+        "offline",
+        "离线模式：true 时不自动访问互联网（公网 IP 与地理位置查询），手动启动的诊断工具不受影响。",
+        "Offline mode: when true nothing reaches the internet on its own (public IP and location lookups); diagnostics you start by hand still run.",
+    ),
+    (
+        "",
+        "jump_host",
+        "SSH 跳板机：[user@]host[:port]，留空从本机连接。设置后端口扫描与网页检查经它连接目标，目标名在跳板机一侧解析；需要密钥或 ssh-agent 登录。",
+        "SSH jump host: [user@]host[:port], empty to connect from this machine. When set, port scans and web checks reach the target through it and names resolve on the jump side; needs key or ssh-agent login.",
+    ),
+    (
+        "",
+        "check_updates",
+        "启动时查询 GitHub 上的最新版本；有新版时标签栏显示提示，Ctrl+U 查看更新说明与下载地址。离线模式下不查询。",
+        "Look up the latest release on GitHub at startup; a newer one shows a marker in the tab bar and Ctrl+U opens its notes and download link. Skipped in offline mode.",
+    ),
+    (
+        "",
+        "tabs",
+        "显示的标签页及顺序（Tab 键按此循环）：dashboard、adapters、scanner、traffic、diagnostics、events、settings。未列出的隐藏；settings 始终保留。",
+        "Visible tabs in order, also the Tab cycling order: dashboard, adapters, scanner, traffic, diagnostics, events, settings. Unlisted tabs are hidden; settings always stays.",
+    ),
+    (
+        "retention",
+        "",
+        "长时间运行时的数据上限：samples 为每个诊断工具保留的样本与日志行数（100–1000000），scan_rows 为保留的扫描结果数（1000–1000000），memory_mb 为以上数据合计的估算内存上限（16–4096 MiB）。超出时先丢弃最早的记录，界面会注明丢弃条数。",
+        "Limits for long runs: samples is the samples and log lines kept per diagnostic tool (100–1000000), scan_rows the scan results kept (1000–1000000), memory_mb an estimated memory budget for all of them together (16–4096 MiB). The oldest rows go first and the view notes how many were dropped.",
+    ),
+    (
+        "policy",
+        "",
+        "共用机器的功能策略：disabled 列出要关闭的主动探测功能，port-scan 为端口扫描，lan-scan 为扫描页的局域网扫描。已保存的结果仍可查看；程序不会改写此段。",
+        "Policy for shared machines: disabled lists active probing features to turn off, port-scan for the port scan and lan-scan for the Scanner tab's sweep. Saved results stay viewable; iptools never rewrites this table.",
+    ),
+    (
+        "keybindings",
+        "",
+        "快捷键：动作 = [组合键, ...]，如 quit = [\"Ctrl+c\", \"Ctrl+q\"]。修饰键为 Ctrl / Alt / Shift；删除某行即恢复该动作的默认绑定。",
+        "Key bindings: action = [combos, ...], e.g. quit = [\"Ctrl+c\", \"Ctrl+q\"]. Modifiers are Ctrl / Alt / Shift; delete a line to restore that action's default.",
+    ),
+    (
+        "session.",
+        "",
+        "以下 session.* 段由程序保存最近输入、历史与界面位置，一般无需手工修改。",
+        "The session.* tables below hold recent inputs, history and UI position, saved by iptools; they rarely need hand-editing.",
+    ),
+    (
+        "public_ip",
+        "",
+        "公网 IP 查询。use_system_proxy = true 时经系统代理访问。",
+        "Public IP lookup. With use_system_proxy = true requests go through the system proxy.",
+    ),
+    (
+        "public_ip.endpoints",
+        "",
+        "按顺序尝试的查询接口：url 须为 http(s)，kind 为 ipsb、ipinfo 或 plaintext（响应正文即 IP）。API 令牌写成 ${secret:名称}，用 iptools secret set 名称 存入系统钥匙串。",
+        "Endpoints tried in order: url must be http(s); kind is ipsb, ipinfo or plaintext (the body is the bare IP). Write API tokens as ${secret:NAME} and store them in the system keyring with iptools secret set NAME.",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    fn of(path: &Path) -> Self {
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+        {
+            Self::Json
+        } else {
+            Self::Toml
+        }
+    }
+}
 
 fn detect_system_language() -> iptools_core::Language {
     let tag = system_locale_tag().to_lowercase();
@@ -47,22 +211,77 @@ fn system_locale_tag() -> String {
 #[derive(Debug, Clone)]
 pub struct FsConfigStore {
     path: PathBuf,
+    /// 仅默认路径有：尚无 config.toml 时改读旧版 config.json。
+    legacy: Option<PathBuf>,
 }
 
 impl FsConfigStore {
     pub fn new(path: Option<&str>) -> Self {
-        Self {
-            path: PathBuf::from(path.unwrap_or(DEFAULT_CONFIG_PATH)),
+        match path {
+            Some(path) => Self {
+                path: PathBuf::from(path),
+                legacy: None,
+            },
+            None => Self {
+                path: PathBuf::from(DEFAULT_CONFIG_PATH),
+                legacy: Some(PathBuf::from(LEGACY_CONFIG_PATH)),
+            },
+        }
+    }
+
+    /// 实际读取的文件：通常就是 `path`，迁移前为旧版 config.json。
+    fn source(&self) -> &Path {
+        match &self.legacy {
+            Some(legacy) if !self.path.exists() && legacy.exists() => legacy,
+            _ => &self.path,
         }
     }
 
-    pub fn load(&self) -> Option<ConfigData> {
-        let content = fs::read_to_string(&self.path).ok()?;
-        serde_json::from_str(&content).ok()
+    /// 本次读取的是旧版 JSON 文件，保存后即迁移为 TOML。
+    pub fn is_legacy(&self) -> bool {
+        self.source() != self.path
+    }
+
+    /// 文件不存在时为 `Ok(None)`；读取或解析失败时返回带行号的错误信息。
+    pub fn load(&self) -> Result<Option<ConfigData>, String> {
+        let source = self.source();
+        let content = match fs::read_to_string(source) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.to_string()),
+        };
+        match Format::of(source) {
+            Format::Json => serde_json::from_str(&content).map_err(|error| error.to_string()),
+            Format::Toml => toml::from_str(&content).map_err(|error| toml_error(&content, &error)),
+        }
+        .map(Some)
+    }
+
+    /// 在改写前把当前文件复制为 `<文件名>.<时间>.bak`，返回副本路径；失败只记日志。
+    pub fn backup(&self) -> Option<PathBuf> {
+        self.try_backup()
+            .inspect_err(|error| {
+                tracing::warn!(path = %self.source().display(), %error, "failed to back up configuration");
+            })
+            .ok()
+    }
+
+    /// 同 [`Self::backup`]，但把失败交给调用方：没有备份就不该继续改写的场合用它。
+    pub fn try_backup(&self) -> std::io::Result<PathBuf> {
+        let source = self.source();
+        let backup = backup_path(source);
+        fs::copy(source, &backup)?;
+        Ok(backup)
     }
 
     pub fn save(&self, data: &ConfigData) -> std::io::Result<()> {
-        let content = serde_json::to_vec_pretty(data).map_err(std::io::Error::other)?;
+        let content = match Format::of(&self.path) {
+            Format::Json => serde_json::to_vec_pretty(data).map_err(std::io::Error::other)?,
+            Format::Toml => {
+                let plain = toml::to_string_pretty(data).map_err(std::io::Error::other)?;
+                annotate(&plain, data.language).into_bytes()
+            }
+        };
         write_atomic(&self.path, &content)
     }
 
@@ -71,12 +290,38 @@ impl FsConfigStore {
     }
 }
 
+/// TOML 解析错误的一行说明。默认的 Display 附带多行源码摘录，弹窗里只保留说明与行号。
+pub(crate) fn toml_error(content: &str, error: &toml::de::Error) -> String {
+    let message = error.message().trim_end_matches('\n');
+    match error.span() {
+        Some(span) => {
+            let line = content[..span.start].matches('\n').count() + 1;
+            format!("{message} at line {line}")
+        }
+        None => message.to_string(),
+    }
+}
+
+/// 配置文件的只读检查结果。
+#[derive(Debug, Clone)]
+pub struct ConfigInspection {
+    /// 实际读取的文件；迁移前为旧版 config.json。
+    pub path: PathBuf,
+    pub exists: bool,
+    /// 文件中的语言，读不到时为系统语言。
+    pub language: iptools_core::Language,
+    pub data: Option<ConfigData>,
+    pub issues: Vec<ConfigIssue>,
+}
+
 /// Compatibility facade for the current native `App` during vertical migration.
 /// Field access dereferences to the shared `ConfigData`; no serializable state is
 /// defined in this crate anymore.
 pub struct Config {
     data: ConfigData,
     store: FsConfigStore,
+    /// 加载时发现的问题，启动后以弹窗提示一次。
+    pub issues: Vec<ConfigIssue>,
 }
 
 impl Default for Config {
@@ -84,6 +329,7 @@ impl Default for Config {
         Self {
             data: ConfigData::default(),
             store: FsConfigStore::new(None),
+            issues: Vec::new(),
         }
     }
 }
@@ -104,15 +350,53 @@ impl DerefMut for Config {
 
 impl Config {
     pub fn load(path: Option<&str>) -> Self {
-        let store = FsConfigStore::new(path);
-        if let Some(mut data) = store.load() {
+        Self::load_from(FsConfigStore::new(path))
+    }
+
+    fn load_from(store: FsConfigStore) -> Self {
+        let legacy = store.is_legacy();
+        let mut issues = Vec::new();
+        let loaded = store.load().unwrap_or_else(|error| {
+            tracing::warn!(path = %store.path().display(), %error, "configuration is malformed; using defaults");
+            // 损坏的文件马上会被默认配置覆盖，先留一份副本
+            let backup = store.backup().map(|path| path.display().to_string());
+            issues.push(ConfigIssue::Malformed { error, backup });
+            None
+        });
+        if let Some(mut data) = loaded {
+            let mut rewrite = data.migrate() || legacy;
+            if data.version > CONFIG_VERSION {
+                let backup = store.backup().map(|path| path.display().to_string());
+                issues.push(ConfigIssue::NewerVersion {
+                    found: data.version,
+                    backup,
+                });
+                data.version = CONFIG_VERSION;
+            }
+            issues.extend(data.validate());
+            issues.extend(KeyMap::issues(&data.keybindings));
             if data.keybindings.is_empty() {
                 data.keybindings = KeyMap::default().to_persisted();
-                if let Err(error) = store.save(&data) {
-                    tracing::warn!(path = %store.path().display(), %error, "failed to persist default keybindings");
+                rewrite = true;
+            }
+            if rewrite {
+                match store.save(&data) {
+                    // 旧文件保留原处，回退到旧版本时仍可使用
+                    Ok(()) if legacy => tracing::info!(
+                        path = %store.path().display(),
+                        "migrated {LEGACY_CONFIG_PATH}"
+                    ),
+                    Ok(()) => {}
+                    Err(error) => {
+                        tracing::warn!(path = %store.path().display(), %error, "failed to persist upgraded configuration");
+                    }
                 }
             }
-            return Self { data, store };
+            return Self {
+                data,
+                store,
+                issues,
+            };
         }
 
         let mut data = ConfigData {
@@ -123,7 +407,53 @@ impl Config {
         if let Err(error) = store.save(&data) {
             tracing::warn!(path = %store.path().display(), %error, "failed to create configuration");
         }
-        Self { data, store }
+        Self {
+            data,
+            store,
+            issues,
+        }
+    }
+
+    /// 只读检查配置文件（`iptools doctor` 用）：不迁移、不备份、不写回。
+    pub fn inspect(path: Option<&str>) -> ConfigInspection {
+        let store = FsConfigStore::new(path);
+        let source = store.source().to_path_buf();
+        let mut issues = Vec::new();
+        let data = match store.load() {
+            Ok(data) => data,
+            Err(error) => {
+                issues.push(ConfigIssue::Malformed {
+                    error,
+                    backup: None,
+                });
+                None
+            }
+        };
+        if let Some(data) = &data {
+            if data.version > CONFIG_VERSION {
+                issues.push(ConfigIssue::NewerVersion {
+                    found: data.version,
+                    backup: None,
+                });
+            }
+            let mut checked = data.clone();
+            issues.extend(checked.validate());
+            issues.extend(KeyMap::issues(&data.keybindings));
+        }
+        ConfigInspection {
+            language: data
+                .as_ref()
+                .map_or_else(detect_system_language, |data| data.language),
+            exists: source.exists(),
+            path: source,
+            data,
+            issues,
+        }
+    }
+
+    /// 配置文件路径；历史库放在同一目录。
+    pub fn path(&self) -> &Path {
+        self.store.path()
     }
 
     pub fn keymap(&self) -> KeyMap {
@@ -137,7 +467,77 @@ impl Config {
     }
 }
 
-fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+/// 在序列化结果前插入说明注释。注释随每次保存重新生成，手写的注释不会保留。
+fn annotate(plain: &str, language: iptools_core::Language) -> String {
+    let comment = |table: &str, key: &str| {
+        COMMENTS
+            .iter()
+            .find(|(t, k, _, _)| {
+                *k == key && (*t == table || (t.ends_with('.') && table.starts_with(t)))
+            })
+            .map(|(_, _, zh, en)| match language {
+                iptools_core::Language::Zh => *zh,
+                iptools_core::Language::En => *en,
+            })
+    };
+    let mut out = String::from(match language {
+        iptools_core::Language::Zh => {
+            "# iptools 配置文件。以 # 开头的说明在每次保存时重新生成，自行添加的注释不会保留。\n\n"
+        }
+        iptools_core::Language::En => {
+            "# iptools configuration. Lines starting with # are regenerated on every save; comments you add are not kept.\n\n"
+        }
+    });
+    let mut table = String::new();
+    let mut commented = Vec::new();
+    for line in plain.lines() {
+        let header = line
+            .strip_prefix("[[")
+            .and_then(|rest| rest.strip_suffix("]]"))
+            .or_else(|| {
+                line.strip_prefix('[')
+                    .and_then(|rest| rest.strip_suffix(']'))
+            });
+        let note = if let Some(name) = header {
+            table = name.to_string();
+            comment(name, "").filter(|note| {
+                let first = !commented.contains(note);
+                commented.push(*note);
+                first
+            })
+        } else if table.is_empty() {
+            line.split_once(" = ").and_then(|(key, _)| comment("", key))
+        } else {
+            None
+        };
+        if let Some(note) = note {
+            out.push_str("# ");
+            out.push_str(note);
+            out.push('\n');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// `path` 旁还没有的 `<文件名>.<时间>.bak`；同一秒内再次备份时加上序号，不覆盖前一份。
+pub(crate) fn backup_path(path: &Path) -> PathBuf {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    (0..)
+        .map(|index| {
+            let mut name = path.file_name().unwrap_or_default().to_os_string();
+            match index {
+                0 => name.push(format!(".{stamp}.bak")),
+                _ => name.push(format!(".{stamp}-{index}.bak")),
+            }
+            path.with_file_name(name)
+        })
+        .find(|backup| !backup.exists())
+        .expect("an unused name exists")
+}
+
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
     let mut file = atomic_write_file::AtomicWriteFile::options().open(path)?;
     file.write_all(contents)?;
     file.commit()
@@ -157,21 +557,123 @@ mod tests {
 
     #[test]
     fn example_config_is_valid() {
-        serde_json::from_str::<ConfigData>(include_str!("../../../config.example.json"))
-            .expect("config.example.json must remain valid");
+        toml::from_str::<ConfigData>(include_str!("../../../config.example.toml"))
+            .expect("config.example.toml must remain valid");
+    }
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "iptools-{label}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn legacy_json_is_migrated_to_commented_toml() {
+        let dir = temp_dir("legacy");
+        std::fs::write(
+            dir.join("config.json"),
+            r#"{"language":"Zh","scan_concurrency":120,"keybindings":{"quit":["Ctrl+q"]}}"#,
+        )
+        .unwrap();
+        let store = || FsConfigStore {
+            path: dir.join("config.toml"),
+            legacy: Some(dir.join("config.json")),
+        };
+
+        let config = Config::load_from(store());
+        assert!(config.issues.is_empty());
+        assert_eq!(config.scan_concurrency, 120);
+        let written = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+        assert!(written.contains("# 局域网扫描同时探测的主机数"));
+        assert!(written.contains("scan_concurrency = 120"));
+        assert!(written.contains("quit = [\"Ctrl+q\"]"));
+        // 旧文件原样保留，之后只读 TOML
+        assert!(dir.join("config.json").exists());
+        assert!(!store().is_legacy());
+        assert_eq!(store().load(), Ok(Some(config.data.clone())));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn toml_errors_name_the_line() {
+        let dir = temp_dir("toml-error");
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "version = 1\nscan_concurrency = \"many\"\n").unwrap();
+
+        let config = Config::load(Some(path.to_str().unwrap()));
+        let [ConfigIssue::Malformed { error, backup }] = config.issues.as_slice() else {
+            panic!("expected a parse error, got {:?}", config.issues);
+        };
+        assert!(error.ends_with("at line 2"), "{error}");
+        assert!(!error.contains('|'));
+        assert!(backup.is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn inspecting_reports_issues_without_touching_the_file() {
+        let dir = temp_dir("inspect");
+        let path = dir.join("config.toml");
+        let original = "version = 1\nlanguage = \"En\"\nscan_concurrency = 5000\n";
+        std::fs::write(&path, original).unwrap();
+
+        let inspection = Config::inspect(Some(path.to_str().unwrap()));
+        assert!(inspection.exists);
+        assert_eq!(inspection.language, iptools_core::Language::En);
+        assert_eq!(
+            inspection.issues,
+            [ConfigIssue::ScanConcurrency { found: 5000 }]
+        );
+        // 不补全、不迁移、不留备份
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn malformed_config_is_backed_up_before_defaults_are_written() {
+        let dir = temp_dir("malformed");
+        let path = dir.join("config.json");
+        std::fs::write(&path, "{\"language\": \"Zh\",\n  \"scan_concurrency\": }").unwrap();
+
+        let config = Config::load(Some(path.to_str().unwrap()));
+        let [ConfigIssue::Malformed { error, backup }] = config.issues.as_slice() else {
+            panic!("expected a parse error, got {:?}", config.issues);
+        };
+        assert!(error.contains("line 2"));
+        let backup = backup.as_deref().expect("backup written");
+        assert!(
+            std::fs::read_to_string(backup)
+                .unwrap()
+                .contains("scan_concurrency")
+        );
+        // 默认配置已写回，下次启动不再报错
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(Config::load(Some(path.to_str().unwrap())).issues.is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
     fn filesystem_store_roundtrips_shared_data_atomically() {
         let path = std::env::temp_dir().join(format!(
-            "iptools-config-{}-{}.json",
+            "iptools-config-{}-{}.toml",
             std::process::id(),
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_nanos()
         ));
-        let store = FsConfigStore { path: path.clone() };
+        let store = FsConfigStore {
+            path: path.clone(),
+            legacy: None,
+        };
         let expected = ConfigData {
             language: iptools_core::Language::Zh,
             scan_concurrency: 120,
@@ -179,7 +681,7 @@ mod tests {
         };
 
         store.save(&expected).unwrap();
-        assert_eq!(store.load(), Some(expected));
+        assert_eq!(store.load(), Ok(Some(expected)));
         std::fs::remove_file(path).unwrap();
     }
 }
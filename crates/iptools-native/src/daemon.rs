@@ -0,0 +1,301 @@
+//! 守护进程：`iptools daemon` 在后台持续运行监视、扫描与流量采集，关掉终端也不中断；
+//! 界面通过本机连接接上来（守护进程运行时，直接运行 `iptools` 即自动连接）。
+//!
+//! 模型与绘制都在守护进程里：客户端只发送按键、点击与终端尺寸，收到的是现成的终端输出，
+//! 原样写到自己的终端。客户端里的退出键只断开连接；`iptools daemon --stop` 让守护进程
+//! 保存会话后退出。同一时间只有一个客户端，新连接会接管旧连接。
+//!
+//! 类 Unix 系统上本机连接经套接字，放在单实例锁旁边只对当前用户开放的目录里；Windows 上
+//! 经只监听 127.0.0.1 的 TCP 端口，地址与本次运行的令牌写在单实例锁旁边的端点文件里，
+//! 连接须像远程 TCP 一样认证。另一台机器上的界面可以经 SSH（远端运行
+//! `iptools daemon --bridge` 转接到本机连接）或带令牌认证的 TCP（`iptools daemon --listen`）
+//! 连接上来，见 [`remote`]。
+//!
+//! 连接建立后守护进程先发一行 [`ServerMessage::Hello`]；TCP 连接还要回答其中的质询，
+//! 认证通过前不会接管界面。此后客户端每行发一条 [`ClientMessage`]（TCP 连接上包在
+//! [`SignedMessage`] 里），守护进程只发终端输出。
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use anyhow::{Result, bail};
+use crossterm::event::{MouseButton, MouseEventKind};
+use iptools_core::{Action, InputEvent, TerminalCaps};
+use ratatui::{Terminal, backend::CrosstermBackend};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    config::Config,
+    event::{Event, EventHandler},
+    frontend,
+    keymap::KeyMap,
+};
+
+mod remote;
+mod server;
+
+pub(crate) use remote::RemoteTarget;
+pub(crate) use server::{bridge, connect, run, stop};
+
+/// 协议版本；两端不一致时客户端拒绝连接，而不是画出错乱的界面。
+const PROTOCOL_VERSION: u32 = 2;
+
+/// 客户端发给守护进程的消息，每行一个 JSON。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum ClientMessage {
+    /// TCP 连接对质询的回答，见 [`remote::proof`]。
+    Auth {
+        proof: String,
+    },
+    /// 认证后的第一条：终端尺寸与能力。
+    Attach {
+        width: u16,
+        height: u16,
+        terminal: TerminalCaps,
+        no_color: bool,
+    },
+    Resize {
+        width: u16,
+        height: u16,
+    },
+    Input(InputEvent),
+    /// 左键点击，由守护进程按它画出的界面做命中测试。
+    Click {
+        column: u16,
+        row: u16,
+    },
+    /// 保存会话并退出守护进程。
+    Stop,
+}
+
+/// 守护进程在开始发送终端输出之前发给客户端的消息，每行一个 JSON。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum ServerMessage {
+    Hello {
+        version: u32,
+        /// 守护进程所在机器的主机名，客户端用来提示连到了哪里。
+        host: String,
+        /// 需要认证时的随机质询。
+        challenge: Option<String>,
+    },
+    Accepted,
+    Rejected,
+}
+
+/// TCP 连接认证后客户端的每条消息：`body` 是 [`ClientMessage`] 的 JSON，`mac` 由本连接的
+/// [`remote::FrameKey`] 对序号与 `body` 计算。序号从 0 连续递增，重放或丢弃消息都会被发现。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SignedMessage {
+    seq: u64,
+    body: String,
+    mac: String,
+}
+
+/// 客户端一侧的发送端：有消息密钥（TCP 连接）时为每条消息编号并签名。
+struct Outgoing<W> {
+    writer: W,
+    key: Option<remote::FrameKey>,
+    seq: u64,
+}
+
+impl<W: Write> Outgoing<W> {
+    fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        let Some(key) = &self.key else {
+            return send(&mut self.writer, message);
+        };
+        let body = serde_json::to_string(message).map_err(io::Error::other)?;
+        let signed = SignedMessage {
+            seq: self.seq,
+            mac: key.sign(self.seq, &body),
+            body,
+        };
+        self.seq += 1;
+        send(&mut self.writer, &signed)
+    }
+}
+
+/// 客户端到守护进程的一条双向连接：本机连接、TCP，或 ssh 子进程的标准输入输出。
+pub(crate) struct Transport {
+    reader: Box<dyn Read + Send>,
+    writer: Box<dyn Write + Send>,
+    /// 断开连接，让读取终端输出的线程结束。
+    close: Box<dyn FnOnce() + Send>,
+    /// 守护进程要求认证时使用的令牌；没有时取环境变量 [`remote::TOKEN_ENV`]。
+    token: Option<String>,
+}
+
+fn send(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message).map_err(io::Error::other)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
+
+fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> io::Result<T> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    serde_json::from_str(&line).map_err(io::Error::other)
+}
+
+/// 读取守护进程的问候并在需要时认证，返回它所在的主机名与（认证时）本连接的消息密钥。
+fn handshake(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    token: Option<&str>,
+) -> Result<(String, Option<remote::FrameKey>)> {
+    let hello = match receive(reader) {
+        Ok(hello) => hello,
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+            bail!("连接在握手前关闭（对方没有运行 iptools daemon？）")
+        }
+        Err(error) => return Err(error.into()),
+    };
+    let ServerMessage::Hello {
+        version,
+        host,
+        challenge,
+    } = hello
+    else {
+        bail!("守护进程的应答无法识别");
+    };
+    if version != PROTOCOL_VERSION {
+        bail!(
+            "{host} 上的 iptools 版本不同（协议 {version}，本机 {PROTOCOL_VERSION}），请先统一版本"
+        );
+    }
+    let Some(challenge) = challenge else {
+        return Ok((host, None));
+    };
+    let Some(token) = token else {
+        bail!(
+            "{host} 要求令牌认证：把它的 {} 内容设为环境变量 {}",
+            remote::TOKEN_FILE,
+            remote::TOKEN_ENV
+        );
+    };
+    send(
+        writer,
+        &ClientMessage::Auth {
+            proof: remote::proof(token, &challenge),
+        },
+    )?;
+    match receive(reader)? {
+        ServerMessage::Accepted => Ok((host, Some(remote::FrameKey::derive(token, &challenge)))),
+        _ => bail!("{host} 拒绝了令牌"),
+    }
+}
+
+/// 在当前终端显示守护进程的界面，直到按退出键（只断开）或守护进程退出。
+pub(crate) async fn attach(config_path: Option<&str>, transport: Transport) -> Result<()> {
+    let keymap = Config::inspect(config_path)
+        .data
+        .map_or_else(KeyMap::default, |data| {
+            KeyMap::from_persisted(&data.keybindings)
+        });
+    let Transport {
+        reader,
+        mut writer,
+        close,
+        token,
+    } = transport;
+    let mut reader = BufReader::new(reader);
+    let token = token.or_else(|| std::env::var(remote::TOKEN_ENV).ok());
+    // 握手失败时还没有进入全屏界面，错误直接显示在终端里
+    let (host, key) = match handshake(&mut reader, &mut writer, token.as_deref()) {
+        Ok(accepted) => accepted,
+        Err(error) => {
+            close();
+            return Err(error);
+        }
+    };
+    let mut outgoing = Outgoing {
+        writer,
+        key,
+        seq: 0,
+    };
+    let (width, height) = crossterm::terminal::size()?;
+    outgoing.send(&ClientMessage::Attach {
+        width,
+        height,
+        terminal: frontend::terminal_caps(),
+        no_color: frontend::no_color(),
+    })?;
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    frontend::enter(&mut terminal)?;
+    // 守护进程的输出原样写到终端；连接断开时结束事件循环
+    let (closed_tx, mut closed) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = copy_frames(reader, io::stdout());
+        let _ = closed_tx.send(());
+    });
+    let mut events = EventHandler::new(1_000);
+    let detached = async {
+        loop {
+            let event = tokio::select! {
+                _ = &mut closed => return Ok::<_, anyhow::Error>(false),
+                event = events.next() => event?,
+            };
+            let message = match event {
+                Event::Key(key) => match frontend::mapped_key(key, &keymap) {
+                    Some(input) if input.action() == Some(Action::Quit) => return Ok(true),
+                    input => input.map(ClientMessage::Input),
+                },
+                Event::Mouse(mouse) => match mouse.kind {
+                    MouseEventKind::ScrollUp => {
+                        Some(ClientMessage::Input(InputEvent::Action(Action::Up)))
+                    }
+                    MouseEventKind::ScrollDown => {
+                        Some(ClientMessage::Input(InputEvent::Action(Action::Down)))
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => Some(ClientMessage::Click {
+                        column: mouse.column,
+                        row: mouse.row,
+                    }),
+                    _ => None,
+                },
+                Event::Resize => {
+                    let (width, height) = crossterm::terminal::size()?;
+                    Some(ClientMessage::Resize { width, height })
+                }
+                Event::Suspend => {
+                    frontend::suspend(&mut terminal)?;
+                    // 恢复后屏幕已清空，请守护进程整屏重画
+                    let (width, height) = crossterm::terminal::size()?;
+                    Some(ClientMessage::Resize { width, height })
+                }
+                Event::Quit => return Ok(true),
+                Event::Tick => None,
+            };
+            if let Some(message) = message
+                && outgoing.send(&message).is_err()
+            {
+                return Ok(false);
+            }
+        }
+    }
+    .await;
+
+    events.shutdown().await;
+    close();
+    frontend::exit(&mut terminal)?;
+    if detached? {
+        println!("已断开，{host} 上的守护进程继续在后台采集；再次运行同一命令即可重新连接。");
+    } else {
+        println!("与 {host} 的连接已结束：守护进程已退出，或界面被另一个终端接管。");
+    }
+    Ok(())
+}
+
+fn copy_frames(mut input: impl Read, mut output: impl Write) -> io::Result<()> {
+    let mut buffer = [0_u8; 16 * 1024];
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+        output.write_all(&buffer[..read])?;
+        output.flush()?;
+    }
+}
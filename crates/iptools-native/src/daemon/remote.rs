@@ -0,0 +1,306 @@
+//! 连接另一台机器上的守护进程：`iptools --remote` 在本地终端显示它的界面。
+//!
+//! - SSH（`user@host`、`ssh://user@host:2222`）：在远端运行 `iptools daemon --bridge`，
+//!   认证与加密都交给 SSH，远端守护进程不需要开放任何端口；
+//! - TCP（`tcp://host:7878`）：连接 `iptools daemon --listen` 打开的端口。守护进程发出随机
+//!   质询，客户端用共享令牌的 HMAC-SHA256 回答，令牌本身不经网络传送。此后客户端的每条
+//!   消息都带序号和以 [`FrameKey`] 计算的 MAC，连接被劫持后无法注入或重放按键。TCP 不加密，
+//!   画面以明文传回，跨不可信网络请用 SSH。
+//!
+//! 令牌在守护进程首次监听 TCP 时生成，保存在配置文件旁的 [`TOKEN_FILE`]，仅当前用户可读；
+//! 类 Unix 系统上其他用户可读的令牌文件被拒绝使用。客户端从环境变量 [`TOKEN_ENV`] 读取。
+//!
+//! Windows 上本机界面也经回环 TCP 连接守护进程，走同样的质询与消息签名，
+//! 只是令牌每次运行重新生成，写在端点文件里，不用配置文件旁的令牌。
+
+use std::fmt;
+use std::io;
+use std::net::{Shutdown, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use aws_lc_rs::{constant_time, hmac, rand};
+
+use super::Transport;
+
+pub(crate) const TOKEN_FILE: &str = "iptools-agent.token";
+pub(crate) const TOKEN_ENV: &str = "IPTOOLS_AGENT_TOKEN";
+
+/// 远程守护进程的地址。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RemoteTarget {
+    /// 交给系统 `ssh` 的目标，可以是 `~/.ssh/config` 里的别名。
+    Ssh {
+        destination: String,
+        port: Option<u16>,
+    },
+    /// `主机:端口`。
+    Tcp(String),
+}
+
+impl RemoteTarget {
+    /// 解析 `--remote` 参数；没有 `tcp://` 前缀的都按 SSH 目标处理。
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if let Some(address) = value.strip_prefix("tcp://") {
+            return match address.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                    Ok(Self::Tcp(address.to_string()))
+                }
+                _ => Err(format!("TCP 地址须为 tcp://主机:端口：{value}")),
+            };
+        }
+        let (destination, port) = match value.strip_prefix("ssh://") {
+            Some(rest) => match rest.rsplit_once(':') {
+                // 方括号内的冒号属于 IPv6 地址
+                Some((host, port)) if !port.contains(']') => (
+                    host,
+                    Some(
+                        port.parse::<u16>()
+                            .map_err(|_| format!("端口无效：{port}"))?,
+                    ),
+                ),
+                _ => (rest, None),
+            },
+            None => (value, None),
+        };
+        if destination.is_empty() || destination.starts_with('-') || destination.contains(' ') {
+            return Err(format!("无效的远程地址：{value}"));
+        }
+        Ok(Self::Ssh {
+            // ssh 不认方括号，IPv6 地址直接跟在 user@ 后面
+            destination: destination.replace(['[', ']'], ""),
+            port,
+        })
+    }
+
+    /// 建立连接；SSH 目标启动一个 `ssh` 子进程，密码或主机指纹提示照常出现在终端里。
+    pub(crate) fn connect(&self) -> io::Result<Transport> {
+        match self {
+            Self::Tcp(address) => {
+                let stream = TcpStream::connect(address)?;
+                stream.set_nodelay(true)?;
+                let reader = stream.try_clone()?;
+                let closer = stream.try_clone()?;
+                Ok(Transport {
+                    reader: Box::new(reader),
+                    writer: Box::new(stream),
+                    close: Box::new(move || {
+                        let _ = closer.shutdown(Shutdown::Both);
+                    }),
+                    token: None,
+                })
+            }
+            Self::Ssh { destination, port } => {
+                let mut command = Command::new("ssh");
+                command.arg("-T");
+                if let Some(port) = port {
+                    command.arg("-p").arg(port.to_string());
+                }
+                let mut child = command
+                    .arg(destination)
+                    .args(["iptools", "daemon", "--bridge"])
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::inherit())
+                    .spawn()?;
+                let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+                    return Err(io::Error::other("ssh pipes unavailable"));
+                };
+                Ok(Transport {
+                    reader: Box::new(stdout),
+                    writer: Box::new(stdin),
+                    close: Box::new(move || {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }),
+                    token: None,
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for RemoteTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ssh {
+                destination,
+                port: Some(port),
+            } => write!(f, "ssh://{destination}:{port}"),
+            Self::Ssh { destination, .. } => f.write_str(destination),
+            Self::Tcp(address) => write!(f, "tcp://{address}"),
+        }
+    }
+}
+
+/// 配置文件旁边的令牌文件路径。
+pub(crate) fn token_path(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .join(TOKEN_FILE)
+}
+
+/// 读取令牌；第一次监听时生成一个，只有当前用户可读。
+/// 类 Unix 系统上已有的令牌文件对其他用户开放时拒绝使用：令牌可能已经泄露，须由用户决定。
+/// Windows 上配置目录在用户自己的配置文件夹里，访问权限沿用它的 ACL。
+pub(crate) fn load_or_create_token(config_path: &Path) -> io::Result<String> {
+    use std::io::Write;
+
+    let path = token_path(config_path);
+    #[cfg(unix)]
+    refuse_shared_token(&path)?;
+    match std::fs::read_to_string(&path) {
+        Ok(token) if !token.trim().is_empty() => return Ok(token.trim().to_string()),
+        Ok(_) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error),
+    }
+    let token = random_hex();
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(&path)?
+        .write_all(format!("{token}\n").as_bytes())?;
+    Ok(token)
+}
+
+#[cfg(unix)]
+fn refuse_shared_token(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.permissions().mode() & 0o077 != 0 => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "令牌文件 {} 对其他用户可读（权限 {:o}）：确认未泄露后执行 chmod 600，\
+                 或删除它以生成新令牌",
+                path.display(),
+                metadata.permissions().mode() & 0o777
+            ),
+        )),
+        Ok(_) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+pub(super) fn challenge() -> String {
+    random_hex()
+}
+
+/// 对质询的回答：以令牌为密钥的 HMAC-SHA256，十六进制。
+pub(super) fn proof(token: &str, challenge: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, token.as_bytes());
+    hex(hmac::sign(&key, challenge.as_bytes()).as_ref())
+}
+
+/// 以恒定时间比较，避免按响应快慢逐字节猜出正确回答。
+pub(super) fn verify(token: &str, challenge: &str, proof: &str) -> bool {
+    constant_time::verify_slices_are_equal(
+        self::proof(token, challenge).as_bytes(),
+        proof.as_bytes(),
+    )
+    .is_ok()
+}
+
+/// 一次 TCP 连接的消息密钥：由令牌与本次质询导出，每个连接都不同，
+/// 也不同于明文传送的质询回答。
+pub(super) struct FrameKey(hmac::Key);
+
+impl FrameKey {
+    pub(super) fn derive(token: &str, challenge: &str) -> Self {
+        let token = hmac::Key::new(hmac::HMAC_SHA256, token.as_bytes());
+        let derived = hmac::sign(&token, format!("iptools frame key\n{challenge}").as_bytes());
+        Self(hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref()))
+    }
+
+    /// 第 `seq` 条消息的 MAC，十六进制。
+    pub(super) fn sign(&self, seq: u64, body: &str) -> String {
+        let mut context = hmac::Context::with_key(&self.0);
+        context.update(&seq.to_be_bytes());
+        context.update(body.as_bytes());
+        hex(context.sign().as_ref())
+    }
+
+    pub(super) fn verify(&self, seq: u64, body: &str, mac: &str) -> bool {
+        constant_time::verify_slices_are_equal(self.sign(seq, body).as_bytes(), mac.as_bytes())
+            .is_ok()
+    }
+}
+
+pub(super) fn random_hex() -> String {
+    let mut bytes = [0_u8; 32];
+    rand::fill(&mut bytes).expect("system random number generator");
+    hex(&bytes)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_targets_default_to_ssh_and_tcp_needs_a_port() {
+        assert_eq!(
+            RemoteTarget::parse("admin@router"),
+            Ok(RemoteTarget::Ssh {
+                destination: "admin@router".into(),
+                port: None
+            })
+        );
+        assert_eq!(
+            RemoteTarget::parse("ssh://ops@[2001:db8::1]:2222"),
+            Ok(RemoteTarget::Ssh {
+                destination: "ops@2001:db8::1".into(),
+                port: Some(2222)
+            })
+        );
+        assert_eq!(
+            RemoteTarget::parse("tcp://10.0.0.5:7878").map(|target| target.to_string()),
+            Ok("tcp://10.0.0.5:7878".into())
+        );
+        assert!(RemoteTarget::parse("tcp://10.0.0.5").is_err());
+        // 以 - 开头会被 ssh 当成选项
+        assert!(RemoteTarget::parse("-oProxyCommand=x").is_err());
+        assert!(RemoteTarget::parse("").is_err());
+    }
+
+    #[test]
+    fn frame_macs_bind_the_connection_sequence_and_body() {
+        let key = FrameKey::derive("secret", "challenge-1");
+        let mac = key.sign(0, "{\"Stop\":null}");
+        assert!(key.verify(0, "{\"Stop\":null}", &mac));
+        assert!(!key.verify(1, "{\"Stop\":null}", &mac));
+        assert!(!key.verify(0, "\"Stop\"", &mac));
+        assert!(!FrameKey::derive("secret", "challenge-2").verify(0, "{\"Stop\":null}", &mac));
+        // 明文传送的质询回答不能当作消息密钥
+        assert_ne!(mac, proof("secret", "challenge-1"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_token_file_other_users_can_read_is_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = std::env::temp_dir().join(format!("iptools-token-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let config = directory.join("config.toml");
+        let token = load_or_create_token(&config).unwrap();
+        assert_eq!(load_or_create_token(&config).unwrap(), token);
+
+        let path = token_path(&config);
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let error = load_or_create_token(&config).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,1010 @@
+//! 守护进程一侧：接受本机（类 Unix 系统经套接字，Windows 经回环 TCP）与（可选）远程 TCP 连接，
+//! 把模型画到当前客户端的终端。
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use iptools_core::{Action, Effect, InputEvent};
+use iptools_ui::UiState;
+use ratatui::{Terminal, TerminalOptions, Viewport, backend::CrosstermBackend, layout::Rect};
+use tokio::sync::mpsc;
+
+use super::{
+    ClientMessage, Outgoing, PROTOCOL_VERSION, ServerMessage, SignedMessage, Transport, handshake,
+    receive, remote, send,
+};
+use crate::{
+    event::{Event, EventHandler},
+    instance,
+    native_app::Session,
+};
+
+/// 客户端卡住时最多等待这么久，之后断开它，采集不受影响。
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// TCP 客户端须在这段时间内完成握手，否则断开；按整个握手计，不是每次读取。
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+/// 认证回答远小于此；未认证的客户端最多能让守护进程读这么多字节。
+const MAX_HANDSHAKE_BYTES: usize = 4096;
+/// 同时在握手中的 TCP 连接上限，超出的连接直接关闭，每个连接占一个线程。
+const MAX_PENDING_HANDSHAKES: usize = 8;
+/// 客户端报告的终端尺寸上限；画面缓冲按尺寸分配，不能任由对端指定。
+const MAX_TERMINAL_WIDTH: u16 = 1000;
+const MAX_TERMINAL_HEIGHT: u16 = 500;
+
+/// 客户端连接：类 Unix 系统上本机经套接字，其余经 TCP。
+enum Connection {
+    #[cfg(unix)]
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl Connection {
+    fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.try_clone().map(Self::Unix),
+            Self::Tcp(stream) => stream.try_clone().map(Self::Tcp),
+        }
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.shutdown(Shutdown::Both),
+            Self::Tcp(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+
+    /// 转为阻塞模式并设置读写超时，供读取线程与绘制使用。
+    fn set_blocking(&self, read_timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(read_timeout)?;
+                stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))
+            }
+            Self::Tcp(stream) => {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(read_timeout)?;
+                stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))
+            }
+        }
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.set_read_timeout(timeout),
+            Self::Tcp(stream) => stream.set_read_timeout(timeout),
+        }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+            Self::Tcp(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+            Self::Tcp(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+            Self::Tcp(stream) => stream.flush(),
+        }
+    }
+}
+
+/// 接收线程转交给主循环的连接事件；只有认证通过的连接才会 `Connected`。
+enum Incoming {
+    Connected(u64, Connection),
+    Message(u64, ClientMessage),
+    Closed(u64),
+}
+
+/// 当前连接的客户端；收到 `Attach` 之前还不知道终端尺寸，没有画面。
+struct Client {
+    id: u64,
+    connection: Connection,
+    terminal: Option<Terminal<CrosstermBackend<ClientOutput>>>,
+}
+
+/// 客户端断开后写入静默成功：断开由读取线程报告，Terminal 析构时恢复光标也不报错。
+/// 写入超时仍是错误，卡住的客户端会被断开。
+struct ClientOutput(Connection);
+
+impl Write for ClientOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0.write(buf) {
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::BrokenPipe
+                        | io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::NotConnected
+                ) =>
+            {
+                Ok(buf.len())
+            }
+            written => written,
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.connection.shutdown();
+    }
+}
+
+/// 到本机守护进程的连接。类 Unix 系统上经私有目录里的套接字，能连上即可信任；
+/// Windows 上经只监听回环地址的 TCP，须像远程连接一样用端点文件里的令牌认证，
+/// 同一台机器上的其他用户也能连到这个端口。
+pub(crate) struct Local {
+    #[cfg(unix)]
+    stream: UnixStream,
+    #[cfg(windows)]
+    stream: TcpStream,
+    token: Option<String>,
+}
+
+/// 套接字放在只对当前用户开放的目录里：bind 创建的套接字文件在 chmod 之前就无法被他人连接。
+#[cfg(unix)]
+fn socket_path() -> PathBuf {
+    instance::lock_path()
+        .with_extension("d")
+        .join("daemon.sock")
+}
+
+/// 端点文件记录守护进程的回环地址与本次运行的令牌，放在单实例锁旁边的用户临时目录里。
+#[cfg(windows)]
+fn endpoint_path() -> PathBuf {
+    instance::lock_path().with_extension("endpoint")
+}
+
+/// 连接正在运行的守护进程；没有时返回 `None`。
+#[cfg(unix)]
+pub(crate) fn connect() -> Option<Local> {
+    let stream = UnixStream::connect(socket_path()).ok()?;
+    Some(Local {
+        stream,
+        token: None,
+    })
+}
+
+/// 连接正在运行的守护进程；没有时返回 `None`。端点文件只接受回环地址，
+/// 被改写也不会把令牌送到别的机器。
+#[cfg(windows)]
+pub(crate) fn connect() -> Option<Local> {
+    let endpoint = std::fs::read_to_string(endpoint_path()).ok()?;
+    let (address, token) = endpoint.trim().split_once(' ')?;
+    let address = address
+        .parse::<std::net::SocketAddr>()
+        .ok()
+        .filter(|address| address.ip().is_loopback())?;
+    let stream = TcpStream::connect_timeout(&address, Duration::from_secs(2)).ok()?;
+    let _ = stream.set_nodelay(true);
+    Some(Local {
+        stream,
+        token: Some(token.to_string()),
+    })
+}
+
+/// 通知守护进程保存会话并退出。
+pub(crate) fn stop(local: Local) -> io::Result<()> {
+    let Transport {
+        reader,
+        writer,
+        close,
+        token,
+    } = Transport::local(local)?;
+    let mut writer = writer;
+    let result = handshake(&mut BufReader::new(reader), &mut writer, token.as_deref())
+        .map_err(io::Error::other)
+        .and_then(|(_, key)| {
+            Outgoing {
+                writer,
+                key,
+                seq: 0,
+            }
+            .send(&ClientMessage::Stop)
+        });
+    close();
+    result
+}
+
+impl Transport {
+    pub(crate) fn local(local: Local) -> io::Result<Self> {
+        let Local { stream, token } = local;
+        let reader = stream.try_clone()?;
+        let closer = stream.try_clone()?;
+        Ok(Self {
+            reader: Box::new(reader),
+            writer: Box::new(stream),
+            close: Box::new(move || {
+                let _ = closer.shutdown(Shutdown::Both);
+            }),
+            token,
+        })
+    }
+}
+
+/// `iptools daemon --bridge`：把标准输入输出接到本机守护进程。由另一台机器经 `ssh` 启动，
+/// 对那一端的认证交给 SSH，因此向标准输出发的问候不带质询；与本机守护进程之间
+/// 需要认证时（Windows）由这里完成，并为转发的每条消息签名。
+pub(crate) fn bridge() -> io::Result<()> {
+    let Some(local) = connect() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "没有正在运行的 iptools daemon",
+        ));
+    };
+    let Transport {
+        reader,
+        writer,
+        close,
+        token,
+    } = Transport::local(local)?;
+    let mut reader = BufReader::new(reader);
+    let mut writer = writer;
+    let (host, key) =
+        handshake(&mut reader, &mut writer, token.as_deref()).map_err(io::Error::other)?;
+    let mut stdout = io::stdout();
+    send(
+        &mut stdout,
+        &ServerMessage::Hello {
+            version: PROTOCOL_VERSION,
+            host,
+            challenge: None,
+        },
+    )?;
+    std::thread::spawn(move || {
+        let mut outgoing = Outgoing {
+            writer,
+            key,
+            seq: 0,
+        };
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            let Ok(message) = serde_json::from_str::<ClientMessage>(&line) else {
+                continue;
+            };
+            if outgoing.send(&message).is_err() {
+                break;
+            }
+        }
+        close();
+    });
+    super::copy_frames(reader, stdout)
+}
+
+/// 运行守护进程，直到 `--stop`、SIGTERM 或 SIGINT。调用方须持有单实例锁。
+/// `listen` 为 TCP 地址时同时接受远程连接，连接须用配置文件旁的令牌认证。
+pub(crate) async fn run(
+    config_path: Option<String>,
+    offline: bool,
+    listen: Option<String>,
+) -> Result<()> {
+    let tcp = match listen {
+        Some(address) => Some(tokio::net::TcpListener::bind(&address).await?),
+        None => None,
+    };
+    let mut session = Session::start(config_path, offline, None)?.always_poll_traffic();
+    let token = if tcp.is_some() {
+        Some(remote::load_or_create_token(session.config_path())?)
+    } else {
+        None
+    };
+    let host = sysinfo::System::host_name().unwrap_or_default();
+    let local = LocalListener::bind().await?;
+    let endpoint = local.path.clone();
+    eprintln!(
+        "iptools 守护进程已启动（PID {}），{}",
+        std::process::id(),
+        local.describe()
+    );
+    if let Some(tcp) = &tcp {
+        eprintln!(
+            "      远程连接：{}，令牌见 {}",
+            tcp.local_addr()?,
+            remote::token_path(session.config_path()).display()
+        );
+    }
+    eprintln!("      运行 iptools 连接界面，iptools daemon --stop 停止。");
+
+    let (incoming_tx, mut incoming) = mpsc::channel(64);
+    let pending = Arc::new(AtomicUsize::new(0));
+    let accept = tokio::spawn(async move {
+        let mut next_id = 0_u64;
+        loop {
+            let (connection, token) = tokio::select! {
+                accepted = local.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(error) => {
+                        tracing::warn!(%error, "cannot accept local daemon client");
+                        continue;
+                    }
+                },
+                accepted = accept_tcp(tcp.as_ref()) => match accepted {
+                    Ok(stream) => (stream.into_std().map(Connection::Tcp), token.clone()),
+                    Err(error) => {
+                        tracing::warn!(%error, "cannot accept remote daemon client");
+                        continue;
+                    }
+                },
+            };
+            let Ok(connection) = connection else {
+                continue;
+            };
+            // 需要认证的连接占用握手名额，本机套接字直接通过
+            let slot = match token {
+                Some(_) => match HandshakeSlot::take(&pending) {
+                    Some(slot) => Some(slot),
+                    None => {
+                        tracing::warn!(
+                            "too many daemon clients in the handshake; closing a new one"
+                        );
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            next_id += 1;
+            if let Err(error) = serve(
+                next_id,
+                connection,
+                token,
+                slot,
+                host.clone(),
+                incoming_tx.clone(),
+            ) {
+                tracing::warn!(%error, "cannot accept daemon client");
+            }
+        }
+    });
+
+    let mut events = EventHandler::headless(session.model.tick_interval_ms());
+    let mut ui = UiState::default();
+    let mut client: Option<Client> = None;
+    let mut redraw = false;
+    let run_result = async {
+        while session.model.running {
+            if let Some(attached) = client.as_mut()
+                && let Some(terminal) = attached.terminal.as_mut()
+                && (std::mem::take(&mut redraw) | session.model.take_redraw())
+            {
+                match terminal.draw(|frame| iptools_ui::render(frame, &session.model, &mut ui)) {
+                    Ok(_) => session.model.page_rows = ui.page_rows(),
+                    Err(error) => {
+                        tracing::warn!(%error, "daemon client stopped reading; detaching it");
+                        client = None;
+                    }
+                }
+            }
+            let effects = tokio::select! {
+                event = events.next() => match event? {
+                    Event::Tick => session.tick(),
+                    Event::Quit => {
+                        session.model.running = false;
+                        Vec::new()
+                    }
+                    _ => Vec::new(),
+                },
+                Some(message) = incoming.recv() => match message {
+                    Incoming::Connected(id, connection) => {
+                        // 新连接接管界面，旧客户端收到连接关闭后自行退出
+                        client = Some(Client { id, connection, terminal: None });
+                        Vec::new()
+                    }
+                    Incoming::Closed(id) => {
+                        if client.as_ref().is_some_and(|client| client.id == id) {
+                            client = None;
+                        }
+                        Vec::new()
+                    }
+                    Incoming::Message(id, message) => match client.as_mut() {
+                        Some(attached) if attached.id == id => {
+                            let (effects, detach) =
+                                handle(&mut session, &mut ui, attached, message, &mut redraw);
+                            if detach {
+                                client = None;
+                            }
+                            effects
+                        }
+                        _ => Vec::new(),
+                    },
+                },
+            };
+            session.dispatch(effects)?;
+            events.set_tick_rate(session.model.tick_interval_ms());
+        }
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+
+    accept.abort();
+    drop(client);
+    let _ = std::fs::remove_file(&endpoint);
+    events.shutdown().await;
+    session.shutdown().await;
+    run_result
+}
+
+/// 本机客户端的入口，见 [`Local`]。`path` 是客户端据以找到守护进程的文件，退出时删除。
+struct LocalListener {
+    #[cfg(unix)]
+    listener: tokio::net::UnixListener,
+    #[cfg(windows)]
+    listener: tokio::net::TcpListener,
+    #[cfg(windows)]
+    token: String,
+    path: PathBuf,
+}
+
+impl LocalListener {
+    #[cfg(unix)]
+    async fn bind() -> io::Result<Self> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = socket_path();
+        if let Some(dir) = path.parent() {
+            instance::prepare_private_dir(dir)?;
+        }
+        // 持有单实例锁时，残留的套接字文件只可能来自上次异常退出
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Self { listener, path })
+    }
+
+    /// 在回环地址的随机端口上监听，每次运行生成新令牌；端点文件先写临时文件再改名，
+    /// 客户端不会读到写了一半的内容。
+    #[cfg(windows)]
+    async fn bind() -> io::Result<Self> {
+        let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0)).await?;
+        let token = remote::random_hex();
+        let path = endpoint_path();
+        let partial = path.with_extension("endpoint.tmp");
+        std::fs::write(&partial, format!("{} {token}\n", listener.local_addr()?))?;
+        std::fs::rename(&partial, &path)?;
+        Ok(Self {
+            listener,
+            token,
+            path,
+        })
+    }
+
+    #[cfg(unix)]
+    fn describe(&self) -> String {
+        format!("套接字 {}", self.path.display())
+    }
+
+    #[cfg(windows)]
+    fn describe(&self) -> String {
+        match self.listener.local_addr() {
+            Ok(address) => format!("本机端口 {address}"),
+            Err(_) => format!("端点 {}", self.path.display()),
+        }
+    }
+
+    /// 接受一个本机客户端，连同认证它所需的令牌（类 Unix 系统上不需要）。
+    #[cfg(unix)]
+    async fn accept(&self) -> io::Result<(io::Result<Connection>, Option<String>)> {
+        let (stream, _) = self.listener.accept().await?;
+        Ok((stream.into_std().map(Connection::Unix), None))
+    }
+
+    #[cfg(windows)]
+    async fn accept(&self) -> io::Result<(io::Result<Connection>, Option<String>)> {
+        let (stream, _) = self.listener.accept().await?;
+        Ok((
+            stream.into_std().map(Connection::Tcp),
+            Some(self.token.clone()),
+        ))
+    }
+}
+
+/// 占用一个握手名额，握手结束（无论成败）时随析构归还。
+struct HandshakeSlot(Arc<AtomicUsize>);
+
+impl HandshakeSlot {
+    /// 名额已满时为 `None`。
+    fn take(pending: &Arc<AtomicUsize>) -> Option<Self> {
+        pending
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < MAX_PENDING_HANDSHAKES).then_some(count + 1)
+            })
+            .ok()?;
+        Some(Self(Arc::clone(pending)))
+    }
+}
+
+impl Drop for HandshakeSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// 握手阶段的读取：每次读之前把读超时设为到 `until` 的剩余时间，并限制总字节数，
+/// 逐字节慢慢发送或一直不换行的客户端都在期限内被断开。
+struct HandshakeReader<'a, R> {
+    reader: &'a mut R,
+    connection: &'a Connection,
+    until: Instant,
+    read: usize,
+}
+
+impl<R: BufRead> Read for HandshakeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl<R: BufRead> BufRead for HandshakeReader<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let remaining = self.until.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        if self.read >= MAX_HANDSHAKE_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "handshake message too long",
+            ));
+        }
+        self.connection.set_read_timeout(Some(remaining))?;
+        let limit = MAX_HANDSHAKE_BYTES - self.read;
+        let available = self.reader.fill_buf()?;
+        Ok(&available[..available.len().min(limit)])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.read += amount;
+        self.reader.consume(amount);
+    }
+}
+
+/// 限制在 [`MAX_TERMINAL_WIDTH`] × [`MAX_TERMINAL_HEIGHT`] 之内的终端区域。
+fn terminal_area(width: u16, height: u16) -> Rect {
+    Rect::new(
+        0,
+        0,
+        width.min(MAX_TERMINAL_WIDTH),
+        height.min(MAX_TERMINAL_HEIGHT),
+    )
+}
+
+/// 没有 TCP 监听时永不返回，使 `select!` 只等本机客户端。
+async fn accept_tcp(
+    listener: Option<&tokio::net::TcpListener>,
+) -> io::Result<tokio::net::TcpStream> {
+    match listener {
+        Some(listener) => listener.accept().await.map(|(stream, _)| stream),
+        None => std::future::pending().await,
+    }
+}
+
+/// 处理当前客户端的一条消息；第二个返回值为 `true` 时断开它。
+/// 客户端这一侧的错误只断开连接，不影响采集。
+fn handle(
+    session: &mut Session,
+    ui: &mut UiState,
+    client: &mut Client,
+    message: ClientMessage,
+    redraw: &mut bool,
+) -> (Vec<Effect>, bool) {
+    let effects = match message {
+        ClientMessage::Attach {
+            width,
+            height,
+            terminal,
+            no_color,
+        } => {
+            match remote_terminal(&client.connection, width, height) {
+                Ok(terminal) => client.terminal = Some(terminal),
+                Err(error) => {
+                    tracing::warn!(%error, "cannot draw for daemon client");
+                    return (Vec::new(), true);
+                }
+            }
+            session.model.terminal = terminal;
+            session.model.no_color = no_color;
+            *redraw = true;
+            Vec::new()
+        }
+        ClientMessage::Resize { width, height } => {
+            if let Some(terminal) = client.terminal.as_mut()
+                && terminal.resize(terminal_area(width, height)).is_err()
+            {
+                return (Vec::new(), true);
+            }
+            *redraw = true;
+            Vec::new()
+        }
+        // 客户端自己处理退出键；万一送到这里也只断开它
+        ClientMessage::Input(input) if input.action() == Some(Action::Quit) => {
+            return (Vec::new(), true);
+        }
+        ClientMessage::Input(input) => session.input(input),
+        ClientMessage::Click { column, row } => match ui.hit_test(column, row) {
+            Some(action) => session.input(InputEvent::Action(action)),
+            None => Vec::new(),
+        },
+        ClientMessage::Stop => {
+            session.model.running = false;
+            Vec::new()
+        }
+        // 认证只在握手阶段进行
+        ClientMessage::Auth { .. } => Vec::new(),
+    };
+    (effects, false)
+}
+
+/// 为新连接起一个读取线程：先问候并认证，通过后逐行解析消息转交主循环。
+/// `slot` 是需要认证的连接占用的握手名额，认证结束时归还。
+fn serve(
+    id: u64,
+    connection: Connection,
+    token: Option<String>,
+    slot: Option<HandshakeSlot>,
+    host: String,
+    incoming: mpsc::Sender<Incoming>,
+) -> io::Result<()> {
+    connection.set_blocking(token.as_ref().map(|_| AUTH_TIMEOUT))?;
+    let mut reader = BufReader::new(connection.try_clone()?);
+    std::thread::spawn(move || {
+        let mut writer = match connection.try_clone() {
+            Ok(writer) => writer,
+            Err(error) => {
+                tracing::debug!(%error, "daemon client left during the handshake");
+                return;
+            }
+        };
+        let mut handshake = HandshakeReader {
+            reader: &mut reader,
+            connection: &connection,
+            until: Instant::now() + AUTH_TIMEOUT,
+            read: 0,
+        };
+        let admission = authenticate(&mut handshake, &mut writer, &host, token.as_deref());
+        drop(slot);
+        let key = match admission {
+            Ok(Admission::Local) => None,
+            Ok(Admission::Remote(key)) => Some(key),
+            Ok(Admission::Rejected) => {
+                tracing::warn!("rejected remote daemon client with a wrong token");
+                return;
+            }
+            Err(error) => {
+                tracing::debug!(%error, "daemon client left during the handshake");
+                return;
+            }
+        };
+        if connection.set_read_timeout(None).is_err()
+            || incoming
+                .blocking_send(Incoming::Connected(id, connection))
+                .is_err()
+        {
+            return;
+        }
+        let mut seq = 0;
+        for line in reader.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            let message = match &key {
+                None => serde_json::from_str(&line),
+                // 远程消息验证失败时断开：连接可能已被劫持
+                Some(key) => match unseal(key, seq, &line) {
+                    Some(message) => {
+                        seq += 1;
+                        Ok(message)
+                    }
+                    None => {
+                        tracing::warn!(
+                            "dropping remote daemon client: message failed authentication"
+                        );
+                        break;
+                    }
+                },
+            };
+            match message {
+                Ok(message) => {
+                    if incoming
+                        .blocking_send(Incoming::Message(id, message))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                Err(error) => tracing::warn!(%error, "ignoring malformed daemon message"),
+            }
+        }
+        let _ = incoming.blocking_send(Incoming::Closed(id));
+    });
+    Ok(())
+}
+
+/// 握手的结果：本机套接字无需认证，TCP 连接通过后得到本连接的消息密钥。
+enum Admission {
+    Local,
+    Remote(Box<remote::FrameKey>),
+    Rejected,
+}
+
+/// 远程消息的第 `seq` 条：序号连续且 MAC 正确时才解析其内容。
+fn unseal(key: &remote::FrameKey, seq: u64, line: &str) -> Option<ClientMessage> {
+    let signed: SignedMessage = serde_json::from_str(line).ok()?;
+    if signed.seq != seq || !key.verify(seq, &signed.body, &signed.mac) {
+        return None;
+    }
+    serde_json::from_str(&signed.body).ok()
+}
+
+/// 发送问候；有令牌时要求客户端回答质询。
+fn authenticate(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    host: &str,
+    token: Option<&str>,
+) -> io::Result<Admission> {
+    let challenge = token.map(|_| remote::challenge());
+    send(
+        writer,
+        &ServerMessage::Hello {
+            version: PROTOCOL_VERSION,
+            host: host.to_string(),
+            challenge: challenge.clone(),
+        },
+    )?;
+    let (Some(token), Some(challenge)) = (token, challenge) else {
+        return Ok(Admission::Local);
+    };
+    let accepted = matches!(
+        receive(reader)?,
+        ClientMessage::Auth { proof } if remote::verify(token, &challenge, &proof)
+    );
+    let reply = if accepted {
+        ServerMessage::Accepted
+    } else {
+        ServerMessage::Rejected
+    };
+    send(writer, &reply)?;
+    Ok(if accepted {
+        Admission::Remote(Box::new(remote::FrameKey::derive(token, &challenge)))
+    } else {
+        Admission::Rejected
+    })
+}
+
+/// 画到客户端终端的 Terminal；尺寸由客户端告知，不查询守护进程自己的终端。
+fn remote_terminal(
+    connection: &Connection,
+    width: u16,
+    height: u16,
+) -> io::Result<Terminal<CrosstermBackend<ClientOutput>>> {
+    Terminal::with_options(
+        CrosstermBackend::new(ClientOutput(connection.try_clone()?)),
+        TerminalOptions {
+            viewport: Viewport::Fixed(terminal_area(width, height)),
+        },
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use iptools_core::{AppModel, KeyCode, KeyEvent, Language};
+
+    #[test]
+    fn an_attached_client_receives_frames_drawn_at_its_size() {
+        let message = ClientMessage::Input(InputEvent::Key(KeyEvent::plain(KeyCode::Char('r'))));
+        let (mut left, right) = UnixStream::pair().unwrap();
+        send(&mut left, &message).unwrap();
+        let received: ClientMessage = receive(&mut BufReader::new(&right)).unwrap();
+        assert_eq!(received, message);
+
+        let (daemon, mut client) = UnixStream::pair().unwrap();
+        let reader = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            client.read_to_end(&mut output).unwrap();
+            output
+        });
+        let mut terminal = remote_terminal(&Connection::Unix(daemon), 100, 30).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| iptools_ui::render(frame, &model, &mut ui))
+            .unwrap();
+        assert_eq!(terminal.get_frame().area(), Rect::new(0, 0, 100, 30));
+        drop(terminal);
+        let output = reader.join().unwrap();
+        let output = String::from_utf8_lossy(&output);
+        assert!(output.contains(" Dashboard "), "{output}");
+        // 点击测试用的是同一帧的布局：标签栏第一个标签
+        assert_eq!(ui.hit_test(3, 1), Some(Action::SelectPage(0)));
+    }
+
+    #[test]
+    fn unauthenticated_clients_get_a_bounded_handshake() {
+        let pending = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_PENDING_HANDSHAKES)
+            .map(|_| HandshakeSlot::take(&pending).unwrap())
+            .collect();
+        assert!(HandshakeSlot::take(&pending).is_none());
+        drop(slots);
+        assert!(HandshakeSlot::take(&pending).is_some());
+
+        // 一个字节一个字节地发送，整个握手仍在期限内结束
+        let (daemon, mut client) = UnixStream::pair().unwrap();
+        let trickle = std::thread::spawn(move || {
+            for _ in 0..20 {
+                if client.write_all(b" ").is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+        let connection = Connection::Unix(daemon);
+        let mut reader = BufReader::new(connection.try_clone().unwrap());
+        let started = Instant::now();
+        let mut handshake = HandshakeReader {
+            reader: &mut reader,
+            connection: &connection,
+            until: started + Duration::from_millis(200),
+            read: 0,
+        };
+        let error = receive::<ClientMessage>(&mut handshake).unwrap_err();
+        assert!(
+            matches!(
+                error.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ),
+            "{error}"
+        );
+        assert!(started.elapsed() < Duration::from_millis(500));
+        drop(connection);
+        drop(reader);
+        trickle.join().unwrap();
+
+        // 一直不换行的客户端最多被读 MAX_HANDSHAKE_BYTES 字节
+        let (daemon, mut client) = UnixStream::pair().unwrap();
+        let flood = std::thread::spawn(move || {
+            let _ = client.write_all(&[b'x'; MAX_HANDSHAKE_BYTES * 4]);
+        });
+        let connection = Connection::Unix(daemon);
+        let mut reader = BufReader::new(connection.try_clone().unwrap());
+        let mut handshake = HandshakeReader {
+            reader: &mut reader,
+            connection: &connection,
+            until: Instant::now() + AUTH_TIMEOUT,
+            read: 0,
+        };
+        let error = receive::<ClientMessage>(&mut handshake).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{error}");
+        drop(connection);
+        drop(reader);
+        flood.join().unwrap();
+
+        assert_eq!(
+            terminal_area(u16::MAX, u16::MAX),
+            Rect::new(0, 0, MAX_TERMINAL_WIDTH, MAX_TERMINAL_HEIGHT)
+        );
+        assert_eq!(terminal_area(120, 40), Rect::new(0, 0, 120, 40));
+    }
+
+    #[test]
+    fn remote_clients_must_answer_the_challenge_with_the_daemon_token() {
+        let connect = |daemon_token: Option<&'static str>, client_token: Option<&str>| {
+            let (daemon, client) = UnixStream::pair().unwrap();
+            let server = std::thread::spawn(move || {
+                let mut writer = daemon.try_clone().unwrap();
+                authenticate(
+                    &mut BufReader::new(daemon),
+                    &mut writer,
+                    "lab",
+                    daemon_token,
+                )
+                .unwrap()
+            });
+            let mut writer = client.try_clone().unwrap();
+            let result = handshake(&mut BufReader::new(client), &mut writer, client_token);
+            (
+                result.map_err(|error| error.to_string()),
+                server.join().unwrap(),
+            )
+        };
+
+        let (result, admission) = connect(None, None);
+        assert_eq!(result.unwrap().0, "lab");
+        assert!(matches!(admission, Admission::Local));
+        let (result, admission) = connect(Some("secret"), Some("secret"));
+        let (host, key) = result.unwrap();
+        assert_eq!(host, "lab");
+        let Admission::Remote(daemon_key) = admission else {
+            panic!("the right token was rejected");
+        };
+        let (rejected, admission) = connect(Some("secret"), Some("guess"));
+        assert!(matches!(admission, Admission::Rejected));
+        assert!(rejected.err().unwrap().contains("拒绝"));
+
+        // 认证后的每条消息都须按序带正确的 MAC
+        let mut outgoing = super::super::Outgoing {
+            writer: Vec::new(),
+            key,
+            seq: 0,
+        };
+        outgoing.send(&ClientMessage::Stop).unwrap();
+        outgoing
+            .send(&ClientMessage::Resize {
+                width: 80,
+                height: 24,
+            })
+            .unwrap();
+        let sent = String::from_utf8(outgoing.writer).unwrap();
+        let lines: Vec<_> = sent.lines().collect();
+        assert_eq!(unseal(&daemon_key, 0, lines[0]), Some(ClientMessage::Stop));
+        assert!(unseal(&daemon_key, 1, lines[1]).is_some());
+        // 重放、跳号与未签名的消息都被拒绝
+        assert_eq!(unseal(&daemon_key, 2, lines[0]), None);
+        assert_eq!(unseal(&daemon_key, 0, lines[1]), None);
+        assert_eq!(unseal(&daemon_key, 0, r#""Stop""#), None);
+        let forged = lines[1].replace("80", "81");
+        assert_eq!(unseal(&daemon_key, 1, &forged), None);
+    }
+
+    #[test]
+    fn local_clients_with_an_endpoint_token_stop_the_daemon_with_a_signed_message() {
+        let (daemon, client) = UnixStream::pair().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut reader = BufReader::new(daemon.try_clone().unwrap());
+            let mut writer = daemon;
+            let Admission::Remote(key) =
+                authenticate(&mut reader, &mut writer, "lab", Some("endpoint")).unwrap()
+            else {
+                panic!("the endpoint token was rejected");
+            };
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            unseal(&key, 0, line.trim_end())
+        });
+        stop(Local {
+            stream: client,
+            token: Some("endpoint".into()),
+        })
+        .unwrap();
+        assert_eq!(server.join().unwrap(), Some(ClientMessage::Stop));
+    }
+}
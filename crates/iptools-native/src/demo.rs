@@ -1,36 +1,62 @@
 use anyhow::Result;
-use iptools_core::{Action, AppModel, Effect, InputEvent, Message};
+use iptools_core::{AppModel, Effect, InputEvent, KioskView, Message};
 use iptools_demo::{DemoRuntime, ScenarioId};
 use iptools_ui::UiState;
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io;
+use std::path::PathBuf;
 
 use crate::config::Config;
 use crate::{
     event::{Event, EventHandler},
-    frontend,
+    frontend, record,
 };
 
-pub async fn run(scenario: ScenarioId, config_path: Option<String>) -> Result<()> {
+/// Demo traffic is resampled at the same cadence as the native runtime.
+const TRAFFIC_REFRESH_TICKS: u64 = 4;
+
+pub async fn run(
+    scenario: ScenarioId,
+    seed: u64,
+    config_path: Option<String>,
+    record: Option<PathBuf>,
+    kiosk: Option<KioskView>,
+) -> Result<()> {
     let mut config = Config::load(config_path.as_deref());
     let mut model = AppModel::default();
     model.apply_config(&config);
-    let mut runtime = DemoRuntime::new(scenario)?;
+    model.no_color = frontend::no_color();
+    model.terminal = frontend::terminal_caps();
+    model.show_config_issues(std::mem::take(&mut config.issues));
+    if let Some(view) = kiosk {
+        model.enter_kiosk(view);
+    }
+    let mut runtime = DemoRuntime::with_seed(scenario, seed)?;
     for event in runtime.bootstrap() {
         model.update(Message::Runtime(event));
     }
 
-    let backend = CrosstermBackend::new(io::stdout());
+    let backend = CrosstermBackend::new(record::stdout(record.as_deref())?);
     let mut terminal = Terminal::new(backend)?;
     let mut events = EventHandler::new(250);
     let mut ui = UiState::default();
+    let mut ticks = 0_u64;
     frontend::enter(&mut terminal)?;
 
+    let mut resized = false;
     while model.running {
-        terminal.draw(|frame| iptools_ui::render(frame, &model, &mut ui))?;
+        // Only draw when the model reports a visible change or the terminal
+        // was resized; idle ticks leave the previous frame on screen.
+        if std::mem::take(&mut resized) | model.take_redraw() {
+            terminal.draw(|frame| iptools_ui::render(frame, &model, &mut ui))?;
+            model.page_rows = ui.page_rows();
+        }
         let effects = match events.next().await? {
             Event::Tick => {
+                ticks = ticks.saturating_add(1);
                 let mut effects = model.update(Message::Tick(250));
+                if ticks.is_multiple_of(TRAFFIC_REFRESH_TICKS) {
+                    effects.extend(model.refresh_traffic());
+                }
                 for event in runtime.advance(250) {
                     effects.extend(model.update(Message::Runtime(event)));
                 }
@@ -38,20 +64,23 @@ pub async fn run(scenario: ScenarioId, config_path: Option<String>) -> Result<()
             }
             Event::Key(key) => frontend::plain_key(key)
                 .map_or_else(Vec::new, |input| model.update(Message::Input(input))),
-            Event::Mouse(mouse) => {
-                let action = match mouse.kind {
-                    crossterm::event::MouseEventKind::ScrollUp => Some(Action::Up),
-                    crossterm::event::MouseEventKind::ScrollDown => Some(Action::Down),
-                    crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
-                        ui.hit_test(mouse.column, mouse.row)
-                    }
-                    _ => None,
-                };
-                action.map_or_else(Vec::new, |action| {
+            Event::Mouse(mouse) => frontend::mouse_action(mouse, &ui)
+                .map_or_else(Vec::new, |action| {
                     model.update(Message::Input(InputEvent::Action(action)))
-                })
+                }),
+            Event::Resize => {
+                resized = true;
+                Vec::new()
+            }
+            Event::Quit => {
+                model.running = false;
+                Vec::new()
+            }
+            Event::Suspend => {
+                frontend::suspend(&mut terminal)?;
+                resized = true;
+                Vec::new()
             }
-            Event::Resize => Vec::new(),
         };
         dispatch_effects(&mut model, &mut runtime, &mut config, effects);
     }
@@ -86,7 +115,7 @@ mod tests {
     #[test]
     fn native_demo_persists_shared_preference_effects() {
         let path = std::env::temp_dir().join(format!(
-            "iptools-demo-settings-{}-{}.json",
+            "iptools-demo-settings-{}-{}.toml",
             std::process::id(),
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -104,11 +133,22 @@ mod tests {
                 language: Language::Zh,
                 theme: iptools_core::ThemeId::Nord,
                 scan_concurrency: 120,
+                scan_adaptive: true,
+                retention: iptools_core::RetentionLimits::default(),
+                drop_alert_percent: iptools_core::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: iptools_core::LowPowerMode::Auto,
+                offline: false,
+                check_updates: false,
+                color_mode: iptools_core::ColorMode::Standard,
+                chart_style: iptools_core::ChartStyle::Blocks,
+                region_format: iptools_core::RegionFormat::Iso,
+                clock_format: iptools_core::ClockFormat::TwentyFourHour,
+                show_utc: false,
             })],
         );
 
         let saved: iptools_core::ConfigData =
-            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
         assert_eq!(saved.language, Language::Zh);
         assert_eq!(saved.theme, iptools_core::ThemeId::Nord);
         assert_eq!(saved.scan_concurrency, 120);
@@ -174,11 +214,13 @@ mod tests {
             interval_ms: 500,
             timeout_ms: 900,
             packet_size: 64,
+            ..Default::default()
         };
         let trace = iptools_core::TracePersist {
             target: "trace.example".into(),
             max_hops: "12".into(),
             timeout_ms: "800".into(),
+            ..Default::default()
         };
         let port_scan = iptools_core::PortScanPersist {
             target: "ports.example".into(),
@@ -0,0 +1,381 @@
+//! 自我诊断：`iptools doctor` 与设置页的「自我诊断」共用这里的检查。
+//!
+//! 每项检查都是阻塞的、只读的（不改配置、不装驱动），总耗时受连通性检查的超时约束。
+//! 结论中的 `detail` 只写事实（路径、错误原文、耗时），怎么处理由界面按语言渲染
+//! [`DoctorHint`]。
+
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use iptools_core::{
+    CONFIG_VERSION, ConfigIssue, DoctorCheck, DoctorCheckKind, DoctorHint, DoctorStatus,
+};
+
+use crate::config::Config;
+
+/// 局域网测速服务端的默认端口，配置里的端口无效时使用。
+const LAN_SPEED_PORT: u16 = 50505;
+/// 外网连通性：先按域名连（同时验证 DNS），失败再直连 IP 区分 DNS 与链路问题。
+const CONNECTIVITY_HOST: &str = "api.github.com:443";
+const CONNECTIVITY_IP: SocketAddr =
+    SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 443);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 按固定顺序运行全部检查。
+pub fn run(config_path: Option<&str>) -> Vec<DoctorCheck> {
+    let inspection = Config::inspect(config_path);
+    let port = inspection
+        .data
+        .as_ref()
+        .and_then(|data| data.session.lan_speed.port.trim().parse().ok())
+        .unwrap_or(LAN_SPEED_PORT);
+    vec![
+        privileges(),
+        icmp(),
+        packet_capture(),
+        firewall(port),
+        config(&inspection),
+        connectivity(),
+    ]
+}
+
+fn privileges() -> DoctorCheck {
+    let kind = DoctorCheckKind::Privileges;
+    #[cfg(unix)]
+    {
+        let euid = unsafe { libc::geteuid() };
+        if euid == 0 {
+            DoctorCheck::new(kind, DoctorStatus::Ok, "root")
+        } else {
+            // 只影响修改网卡配置；需要原始套接字的工具由 ICMP 一项单独判断。
+            DoctorCheck::new(kind, DoctorStatus::Warn, format!("euid {euid}, not root"))
+                .hint(DoctorHint::RunAsAdministrator)
+        }
+    }
+    #[cfg(windows)]
+    {
+        // `net session` 只有管理员能执行成功，是不依赖额外 API 的提权判断。
+        let elevated = std::process::Command::new("net")
+            .arg("session")
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if elevated {
+            DoctorCheck::new(kind, DoctorStatus::Ok, "administrator")
+        } else {
+            DoctorCheck::new(kind, DoctorStatus::Warn, "not elevated")
+                .hint(DoctorHint::RunAsAdministrator)
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        DoctorCheck::new(kind, DoctorStatus::Warn, "unknown platform")
+    }
+}
+
+fn icmp() -> DoctorCheck {
+    let kind = DoctorCheckKind::Icmp;
+    #[cfg(target_os = "linux")]
+    {
+        if crate::utils::android::is_android() {
+            return DoctorCheck::new(
+                kind,
+                DoctorStatus::Fail,
+                "raw sockets unavailable on Android",
+            );
+        }
+        if crate::utils::net::has_cap_net_raw() {
+            return DoctorCheck::new(kind, DoctorStatus::Ok, "raw socket");
+        }
+        // 非特权 ICMP（ping_group_range）能让 Ping 工作，但扫描与 Trace 仍需原始套接字。
+        let datagram = socket2::Socket::new(
+            socket2::Domain::IPV4,
+            socket2::Type::DGRAM,
+            Some(socket2::Protocol::ICMPV4),
+        );
+        match datagram {
+            Ok(_) => DoctorCheck::new(kind, DoctorStatus::Warn, "datagram only, no CAP_NET_RAW")
+                .hint(DoctorHint::GrantRawSockets),
+            Err(error) => {
+                DoctorCheck::new(kind, DoctorStatus::Fail, format!("no CAP_NET_RAW: {error}"))
+                    .hint(DoctorHint::GrantRawSockets)
+            }
+        }
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let datagram = socket2::Socket::new(
+            socket2::Domain::IPV4,
+            socket2::Type::DGRAM,
+            Some(socket2::Protocol::ICMPV4),
+        );
+        match datagram {
+            Ok(_) => DoctorCheck::new(kind, DoctorStatus::Ok, "datagram socket"),
+            Err(error) => DoctorCheck::new(kind, DoctorStatus::Fail, error.to_string())
+                .hint(DoctorHint::GrantRawSockets),
+        }
+    }
+    #[cfg(windows)]
+    {
+        // Windows 走 IcmpSendEcho，不需要原始套接字。
+        DoctorCheck::new(kind, DoctorStatus::Ok, "ICMP helper API")
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        DoctorCheck::new(kind, DoctorStatus::Fail, "unsupported platform")
+    }
+}
+
+fn packet_capture() -> DoctorCheck {
+    let kind = DoctorCheckKind::PacketCapture;
+    #[cfg(target_os = "linux")]
+    {
+        const LIB_DIRS: &[&str] = &[
+            "/usr/lib",
+            "/usr/lib64",
+            "/lib",
+            "/lib64",
+            "/usr/local/lib",
+            "/usr/lib/x86_64-linux-gnu",
+            "/usr/lib/aarch64-linux-gnu",
+            "/lib/x86_64-linux-gnu",
+            "/lib/aarch64-linux-gnu",
+        ];
+        let found = LIB_DIRS.iter().find_map(|dir| {
+            std::fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
+                let name = entry.file_name();
+                name.to_string_lossy()
+                    .starts_with("libpcap.so")
+                    .then(|| entry.path())
+            })
+        });
+        match found {
+            Some(path) => DoctorCheck::new(kind, DoctorStatus::Ok, path.display().to_string()),
+            None => DoctorCheck::new(kind, DoctorStatus::Warn, "libpcap.so not found")
+                .hint(DoctorHint::InstallLibpcap),
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // 系统自带 libpcap，位于 dyld 共享缓存中，磁盘上没有独立文件。
+        DoctorCheck::new(kind, DoctorStatus::Ok, "libpcap (system)")
+    }
+    #[cfg(windows)]
+    {
+        let root = std::env::var_os("SystemRoot").unwrap_or_else(|| "C:\\Windows".into());
+        let path = std::path::Path::new(&root).join("System32\\Npcap\\wpcap.dll");
+        if path.exists() {
+            DoctorCheck::new(kind, DoctorStatus::Ok, path.display().to_string())
+        } else {
+            DoctorCheck::new(kind, DoctorStatus::Warn, "Npcap not installed")
+                .hint(DoctorHint::InstallNpcap)
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        DoctorCheck::new(kind, DoctorStatus::Warn, "not checked on this platform")
+    }
+}
+
+/// 本程序自己的监听能否工作：在局域网测速端口上监听 TCP/UDP 并从回环连入，
+/// 再看系统防火墙是否开启（开启时局域网内的对端可能连不进来）。
+fn firewall(port: u16) -> DoctorCheck {
+    let kind = DoctorCheckKind::Firewall;
+    if let Err(error) = loopback_roundtrip(port) {
+        let hint = if error.kind() == std::io::ErrorKind::AddrInUse {
+            DoctorHint::FreeLanSpeedPort
+        } else {
+            DoctorHint::AllowThroughFirewall
+        };
+        return DoctorCheck::new(kind, DoctorStatus::Warn, format!("port {port}: {error}"))
+            .hint(hint);
+    }
+    match host_firewall_enabled() {
+        Some(true) => DoctorCheck::new(
+            kind,
+            DoctorStatus::Warn,
+            format!("port {port} ok, host firewall enabled"),
+        )
+        .hint(DoctorHint::AllowThroughFirewall),
+        Some(false) => DoctorCheck::new(
+            kind,
+            DoctorStatus::Ok,
+            format!("port {port} ok, host firewall off"),
+        ),
+        None => DoctorCheck::new(kind, DoctorStatus::Ok, format!("port {port} ok")),
+    }
+}
+
+fn loopback_roundtrip(port: u16) -> std::io::Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    let _udp = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    TcpStream::connect_timeout(
+        &SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        CONNECT_TIMEOUT,
+    )?;
+    drop(listener);
+    Ok(())
+}
+
+/// `None` 表示无从判断（未安装或无权查询）。
+fn host_firewall_enabled() -> Option<bool> {
+    #[cfg(windows)]
+    {
+        let output = std::process::Command::new("netsh")
+            .args(["advfirewall", "show", "currentprofile", "state"])
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout).to_ascii_uppercase();
+        output.status.success().then(|| text.contains(" ON"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("/usr/libexec/ApplicationFirewall/socketfilterfw")
+            .arg("--getglobalstate")
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        output.status.success().then(|| text.contains("enabled"))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // firewalld 的状态查询无需 root；ufw/nftables 需要，无法可靠判断时不下结论。
+        let output = std::process::Command::new("firewall-cmd")
+            .arg("--state")
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        Some(text.trim() == "running")
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+fn config(inspection: &crate::config::ConfigInspection) -> DoctorCheck {
+    let kind = DoctorCheckKind::Config;
+    let path = inspection.path.display();
+    if !inspection.exists {
+        return DoctorCheck::new(kind, DoctorStatus::Ok, format!("{path} (not created yet)"));
+    }
+    if inspection.issues.is_empty() {
+        return DoctorCheck::new(kind, DoctorStatus::Ok, path.to_string());
+    }
+    let issues: Vec<String> = inspection.issues.iter().map(issue_summary).collect();
+    DoctorCheck::new(
+        kind,
+        DoctorStatus::Warn,
+        format!("{path}: {}", issues.join("; ")),
+    )
+    .hint(DoctorHint::FixConfig)
+}
+
+/// 面向命令行的一句话概括；完整说明由启动时的配置弹窗给出。
+fn issue_summary(issue: &ConfigIssue) -> String {
+    match issue {
+        ConfigIssue::Malformed { error, .. } => error.clone(),
+        ConfigIssue::NewerVersion { found, .. } => {
+            format!("version {found} > {CONFIG_VERSION}")
+        }
+        ConfigIssue::ScanConcurrency { found } => format!("scan_concurrency = {found}"),
+        ConfigIssue::Retention { key, found } => format!("retention.{key} = {found}"),
+        ConfigIssue::DropAlert { found } => format!("drop_alert_percent = {found}"),
+        ConfigIssue::PublicIpEndpoint { url, kind } => format!("public_ip {kind} {url}"),
+        ConfigIssue::Keybinding { action, key } if key.is_empty() => {
+            format!("keybindings.{action}")
+        }
+        ConfigIssue::Keybinding { action, key } => format!("keybindings.{action} = \"{key}\""),
+        ConfigIssue::Tab { name } => format!("tabs: {name}"),
+        ConfigIssue::PolicyFeature { name } => format!("policy.disabled: {name}"),
+        ConfigIssue::WatchTarget { target } => format!("watch: {target}"),
+        ConfigIssue::JumpHost { found } => format!("jump_host = \"{found}\""),
+    }
+}
+
+fn connectivity() -> DoctorCheck {
+    let kind = DoctorCheckKind::Connectivity;
+    let started = Instant::now();
+    let resolved = CONNECTIVITY_HOST
+        .to_socket_addrs()
+        .map(|addrs| addrs.collect::<Vec<_>>());
+    match resolved {
+        Ok(addrs) if !addrs.is_empty() => {
+            let mut last_error = None;
+            for addr in addrs {
+                match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                    Ok(_) => {
+                        return DoctorCheck::new(
+                            kind,
+                            DoctorStatus::Ok,
+                            format!(
+                                "{CONNECTIVITY_HOST} in {} ms",
+                                started.elapsed().as_millis()
+                            ),
+                        );
+                    }
+                    Err(error) => last_error = Some(error),
+                }
+            }
+            let error = last_error
+                .map(|error| error.to_string())
+                .unwrap_or_default();
+            DoctorCheck::new(
+                kind,
+                DoctorStatus::Fail,
+                format!("{CONNECTIVITY_HOST}: {error}"),
+            )
+            .hint(DoctorHint::CheckUplink)
+        }
+        resolved => {
+            let dns_error = match resolved {
+                Err(error) => error.to_string(),
+                Ok(_) => "no addresses".to_string(),
+            };
+            match TcpStream::connect_timeout(&CONNECTIVITY_IP, CONNECT_TIMEOUT) {
+                Ok(_) => DoctorCheck::new(
+                    kind,
+                    DoctorStatus::Warn,
+                    format!("DNS: {dns_error}; {CONNECTIVITY_IP} reachable"),
+                )
+                .hint(DoctorHint::CheckDns),
+                Err(error) => DoctorCheck::new(
+                    kind,
+                    DoctorStatus::Fail,
+                    format!("DNS: {dns_error}; {CONNECTIVITY_IP}: {error}"),
+                )
+                .hint(DoctorHint::CheckUplink),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_problems_are_summarised_by_key() {
+        let inspection = crate::config::ConfigInspection {
+            path: "config.toml".into(),
+            exists: true,
+            language: iptools_core::Language::En,
+            data: None,
+            issues: vec![
+                ConfigIssue::ScanConcurrency { found: 900 },
+                ConfigIssue::Keybinding {
+                    action: "quit".into(),
+                    key: "Hyper+q".into(),
+                },
+            ],
+        };
+        let check = config(&inspection);
+        assert_eq!(check.status, DoctorStatus::Warn);
+        assert_eq!(check.hint, Some(DoctorHint::FixConfig));
+        assert_eq!(
+            check.detail,
+            "config.toml: scan_concurrency = 900; keybindings.quit = \"Hyper+q\""
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use crossterm::event::{Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent};
+#[cfg(unix)]
+use crossterm::event::{KeyCode, KeyModifiers};
 use futures::StreamExt;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
 
 const EVENT_CAPACITY: usize = 128;
@@ -12,31 +14,63 @@ pub enum Event {
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize,
+    /// The process was asked to end (SIGTERM, SIGINT, SIGHUP, or the console
+    /// closing on Windows); the app should quit the normal way.
+    Quit,
+    /// Ctrl+Z or SIGTSTP: hand the terminal back and stop until resumed.
+    Suspend,
 }
 
 pub struct EventHandler {
     rx: mpsc::Receiver<Event>,
+    tick_rate: watch::Sender<Duration>,
     shutdown: CancellationToken,
     task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl EventHandler {
     pub fn new(tick_rate_ms: u64) -> Self {
-        let tick_rate = Duration::from_millis(tick_rate_ms);
+        Self::spawn(tick_rate_ms, true)
+    }
+
+    /// Ticks and signals only, for a process without a terminal of its own.
+    pub fn headless(tick_rate_ms: u64) -> Self {
+        Self::spawn(tick_rate_ms, false)
+    }
+
+    fn spawn(tick_rate_ms: u64, terminal: bool) -> Self {
+        let (tick_rate, mut tick_rate_rx) = watch::channel(Duration::from_millis(tick_rate_ms));
         let (tx, rx) = mpsc::channel(EVENT_CAPACITY);
         let shutdown = CancellationToken::new();
         let task_shutdown = shutdown.clone();
 
         let task = tokio::spawn(async move {
-            let mut reader = crossterm::event::EventStream::new();
-            let mut interval = tokio::time::interval(tick_rate);
+            let mut reader = terminal.then(crossterm::event::EventStream::new);
+            let mut interval = tokio::time::interval(*tick_rate_rx.borrow_and_update());
+            // Ticks missed while the process was stopped are dropped, not replayed.
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut signals = Signals::new(terminal)
+                .inspect_err(|error| tracing::warn!(%error, "cannot listen for signals"))
+                .ok();
 
             loop {
                 let tick_delay = interval.tick();
-                let crossterm_event = reader.next();
+                let crossterm_event = async {
+                    match reader.as_mut() {
+                        Some(reader) => reader.next().await,
+                        None => std::future::pending().await,
+                    }
+                };
 
                 tokio::select! {
                     _ = task_shutdown.cancelled() => break,
+                    Ok(()) = tick_rate_rx.changed() => {
+                        let period = *tick_rate_rx.borrow_and_update();
+                        interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                    }
+                    Some(evt) = next_signal(&mut signals) => {
+                        if tx.send(evt).await.is_err() { break; }
+                    }
                     _ = tick_delay => {
                         if tx.send(Event::Tick).await.is_err() { break; }
                     }
@@ -44,7 +78,7 @@ impl EventHandler {
                         match evt {
                             CrosstermEvent::Key(key) => {
                                 if key.kind == KeyEventKind::Press
-                                    && tx.send(Event::Key(key)).await.is_err()
+                                    && tx.send(key_event(key)).await.is_err()
                                 {
                                     break;
                                 }
@@ -66,11 +100,20 @@ impl EventHandler {
 
         Self {
             rx,
+            tick_rate,
             shutdown,
             task: Some(task),
         }
     }
 
+    /// Change the tick period; takes effect from the next tick.
+    pub fn set_tick_rate(&self, tick_rate_ms: u64) {
+        self.tick_rate.send_if_modified(|current| {
+            let next = Duration::from_millis(tick_rate_ms);
+            std::mem::replace(current, next) != next
+        });
+    }
+
     pub async fn next(&mut self) -> anyhow::Result<Event> {
         self.rx
             .recv()
@@ -88,6 +131,128 @@ impl EventHandler {
     }
 }
 
+/// Raw mode turns off the terminal's own Ctrl+Z handling, so the key is
+/// turned into a suspend here the way a shell user expects.
+#[cfg(unix)]
+fn key_event(key: KeyEvent) -> Event {
+    if key.code == KeyCode::Char('z') && key.modifiers == KeyModifiers::CONTROL {
+        Event::Suspend
+    } else {
+        Event::Key(key)
+    }
+}
+
+#[cfg(not(unix))]
+fn key_event(key: KeyEvent) -> Event {
+    Event::Key(key)
+}
+
+async fn next_signal(signals: &mut Option<Signals>) -> Option<Event> {
+    match signals {
+        Some(signals) => signals.recv().await,
+        None => None,
+    }
+}
+
+#[cfg(unix)]
+struct Signals {
+    /// Without a terminal, hangup and stop are swallowed: a daemon outlives
+    /// the terminal that started it.
+    terminal: bool,
+    terminate: tokio::signal::unix::Signal,
+    interrupt: tokio::signal::unix::Signal,
+    hangup: tokio::signal::unix::Signal,
+    stop: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Signals {
+    fn new(terminal: bool) -> std::io::Result<Self> {
+        use tokio::signal::unix::{SignalKind, signal};
+        Ok(Self {
+            terminal,
+            terminate: signal(SignalKind::terminate())?,
+            interrupt: signal(SignalKind::interrupt())?,
+            hangup: signal(SignalKind::hangup())?,
+            stop: signal(SignalKind::from_raw(libc::SIGTSTP))?,
+        })
+    }
+
+    async fn recv(&mut self) -> Option<Event> {
+        loop {
+            tokio::select! {
+                Some(()) = self.terminate.recv() => return Some(Event::Quit),
+                Some(()) = self.interrupt.recv() => return Some(Event::Quit),
+                Some(()) = self.hangup.recv() => if self.terminal {
+                    return Some(Event::Quit);
+                },
+                Some(()) = self.stop.recv() => if self.terminal {
+                    return Some(Event::Suspend);
+                },
+                else => return None,
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+struct Signals {
+    /// Without a terminal, logoff is ignored: a service hears every user's
+    /// logoff and outlives them.
+    terminal: bool,
+    close: tokio::signal::windows::CtrlClose,
+    shutdown: tokio::signal::windows::CtrlShutdown,
+    logoff: tokio::signal::windows::CtrlLogoff,
+    /// Ctrl+C and Ctrl+Break, only without a terminal: the TUI reads Ctrl+C
+    /// as a key, a daemon in a console window stops on it.
+    interrupt: Option<(
+        tokio::signal::windows::CtrlC,
+        tokio::signal::windows::CtrlBreak,
+    )>,
+}
+
+#[cfg(windows)]
+impl Signals {
+    fn new(terminal: bool) -> std::io::Result<Self> {
+        use tokio::signal::windows::{ctrl_break, ctrl_c, ctrl_close, ctrl_logoff, ctrl_shutdown};
+        Ok(Self {
+            terminal,
+            close: ctrl_close()?,
+            shutdown: ctrl_shutdown()?,
+            logoff: ctrl_logoff()?,
+            interrupt: if terminal {
+                None
+            } else {
+                Some((ctrl_c()?, ctrl_break()?))
+            },
+        })
+    }
+
+    async fn recv(&mut self) -> Option<Event> {
+        loop {
+            let interrupt = async {
+                match self.interrupt.as_mut() {
+                    Some((ctrl_c, ctrl_break)) => tokio::select! {
+                        Some(()) = ctrl_c.recv() => Some(()),
+                        Some(()) = ctrl_break.recv() => Some(()),
+                        else => None,
+                    },
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                Some(()) = self.close.recv() => return Some(Event::Quit),
+                Some(()) = self.shutdown.recv() => return Some(Event::Quit),
+                Some(()) = self.logoff.recv() => if self.terminal {
+                    return Some(Event::Quit);
+                },
+                Some(()) = interrupt => return Some(Event::Quit),
+                else => return None,
+            }
+        }
+    }
+}
+
 impl Drop for EventHandler {
     fn drop(&mut self) {
         self.shutdown.cancel();
@@ -96,3 +261,16 @@ impl Drop for EventHandler {
         }
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ctrl_z_suspends_and_plain_z_is_a_key() {
+        let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert!(matches!(key_event(ctrl_z), Event::Suspend));
+        let z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE);
+        assert!(matches!(key_event(z), Event::Key(key) if key == z));
+    }
+}
@@ -1,11 +1,17 @@
 use anyhow::Result;
 use crossterm::{
     cursor::SetCursorStyle,
-    event::{KeyCode as CrosstermKeyCode, KeyEvent as CrosstermKeyEvent, KeyModifiers},
+    event::{
+        KeyCode as CrosstermKeyCode, KeyEvent as CrosstermKeyEvent, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use iptools_core::{Action, Effect, InputEvent, KeyCode, KeyEvent, Modifiers};
+use iptools_core::{
+    Action, ColorDepth, Effect, InputEvent, KeyCode, KeyEvent, Modifiers, TerminalCaps,
+};
+use iptools_ui::UiState;
 use ratatui::Terminal;
 use std::io;
 
@@ -15,9 +21,14 @@ use crate::{
 };
 
 pub(crate) fn mapped_key(event: CrosstermKeyEvent, keymap: &KeyMap) -> Option<InputEvent> {
+    let key = convert_key(event)?;
     Some(InputEvent::MappedKey {
-        key: convert_key(event)?,
-        action: keymap.action_for(event).map(convert_action),
+        key,
+        // 数字键跳转标签页不可自定义；与用户绑定冲突时以绑定为准。
+        action: keymap
+            .action_for(event)
+            .map(convert_action)
+            .or_else(|| key.tab_shortcut()),
     })
 }
 
@@ -25,14 +36,29 @@ pub(crate) fn plain_key(event: CrosstermKeyEvent) -> Option<InputEvent> {
     convert_key(event).map(InputEvent::Key)
 }
 
+/// 滚轮映射为上下移动，左键点击交给上一帧界面的命中测试。
+pub(crate) fn mouse_action(event: MouseEvent, ui: &UiState) -> Option<Action> {
+    match event.kind {
+        MouseEventKind::ScrollUp => Some(Action::Up),
+        MouseEventKind::ScrollDown => Some(Action::Down),
+        MouseEventKind::Down(MouseButton::Left) => ui.hit_test(event.column, event.row),
+        _ => None,
+    }
+}
+
 fn convert_action(action: NativeAction) -> Action {
     match action {
         NativeAction::Quit => Action::Quit,
         NativeAction::ToggleLanguage => Action::ToggleLanguage,
+        NativeAction::ToggleRedaction => Action::ToggleRedaction,
         NativeAction::NextTab => Action::NextPage,
         NativeAction::PrevTab => Action::PreviousPage,
         NativeAction::Up => Action::Up,
         NativeAction::Down => Action::Down,
+        NativeAction::PageUp => Action::PageUp,
+        NativeAction::PageDown => Action::PageDown,
+        NativeAction::Home => Action::Home,
+        NativeAction::End => Action::End,
         NativeAction::Left => Action::Left,
         NativeAction::Right => Action::Right,
         NativeAction::Confirm => Action::Confirm,
@@ -42,6 +68,16 @@ fn convert_action(action: NativeAction) -> Action {
         NativeAction::Edit => Action::Edit,
         NativeAction::Toggle => Action::Toggle,
         NativeAction::Help => Action::Help,
+        NativeAction::ShowUpdate => Action::ShowUpdate,
+        NativeAction::Presets => Action::Presets,
+        NativeAction::Problems => Action::Problems,
+        NativeAction::Audit => Action::Audit,
+        NativeAction::Calculator => Action::Calculator,
+        NativeAction::Search => Action::Search,
+        NativeAction::Timeline => Action::Timeline,
+        NativeAction::Export => Action::Export,
+        NativeAction::ToggleSplit => Action::ToggleSplit,
+        NativeAction::SwitchPane => Action::SwitchPane,
     }
 }
 
@@ -57,6 +93,8 @@ fn convert_key(event: CrosstermKeyEvent) -> Option<KeyEvent> {
         CrosstermKeyCode::Delete => KeyCode::Delete,
         CrosstermKeyCode::Home => KeyCode::Home,
         CrosstermKeyCode::End => KeyCode::End,
+        CrosstermKeyCode::PageUp => KeyCode::PageUp,
+        CrosstermKeyCode::PageDown => KeyCode::PageDown,
         CrosstermKeyCode::Up => KeyCode::Up,
         CrosstermKeyCode::Down => KeyCode::Down,
         CrosstermKeyCode::Left => KeyCode::Left,
@@ -73,6 +111,53 @@ fn convert_key(event: CrosstermKeyEvent) -> Option<KeyEvent> {
     })
 }
 
+/// 遵循 <https://no-color.org>：`NO_COLOR` 设为非空值时以单色绘制，但不写入配置。
+pub(crate) fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// 启动时根据环境变量判断终端能力。
+pub(crate) fn terminal_caps() -> TerminalCaps {
+    detect_terminal(|name| std::env::var(name).ok(), cfg!(windows))
+}
+
+/// `COLORTERM=truecolor` 或 Windows Terminal 视为真彩色；`TERM` 含 256color 为 256 色；
+/// 其余按 terminfo 的保守值取 16 色。Unix 下 locale 不是 UTF-8、或 `TERM` 为
+/// linux / vt100 / dumb 等控制台时不使用 Unicode 符号；Windows 旧版控制台（conhost）
+/// 的默认字体缺少这些符号，同样回退为 ASCII。
+fn detect_terminal(var: impl Fn(&str) -> Option<String>, windows: bool) -> TerminalCaps {
+    let lower = |name| var(name).unwrap_or_default().to_ascii_lowercase();
+    let term = lower("TERM");
+    let colorterm = lower("COLORTERM");
+    let windows_terminal = var("WT_SESSION").is_some();
+    let conemu = lower("ConEmuANSI") == "on";
+    let legacy_console = windows && !windows_terminal && !conemu && term.is_empty();
+
+    let colors = if matches!(colorterm.as_str(), "truecolor" | "24bit") || windows_terminal {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") || conemu || legacy_console {
+        // Windows 10 起 conhost 支持 256 色
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    };
+    let unicode = if windows {
+        !legacy_console
+    } else {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .map(lower)
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let utf8 = locale.is_empty() || locale.contains("utf-8") || locale.contains("utf8");
+        utf8 && !matches!(
+            term.as_str(),
+            "linux" | "dumb" | "vt100" | "vt102" | "vt220" | "ansi"
+        )
+    };
+    TerminalCaps { colors, unicode }
+}
+
 pub(crate) fn persist_effect(config: &mut Config, effect: &Effect) -> bool {
     if !config.apply_persistence_effect(effect) {
         return false;
@@ -114,6 +199,29 @@ where
     Ok(())
 }
 
+/// 挂起进程（Ctrl+Z / SIGTSTP）：先还原终端，再用 SIGSTOP 停住自己；
+/// 收到 SIGCONT 后重新进入全屏模式，调用方负责重绘。
+#[cfg(unix)]
+pub(crate) fn suspend<B>(terminal: &mut Terminal<B>) -> Result<()>
+where
+    B: ratatui::backend::Backend,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    exit(terminal)?;
+    // SIGTSTP 已被事件线程接管，只能用无法捕获的 SIGSTOP 真正停下。
+    nix::sys::signal::raise(nix::sys::signal::Signal::SIGSTOP)?;
+    enter(terminal)
+}
+
+/// Windows 终端没有作业控制，挂起请求被忽略。
+#[cfg(not(unix))]
+pub(crate) fn suspend<B>(_terminal: &mut Terminal<B>) -> Result<()>
+where
+    B: ratatui::backend::Backend,
+{
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crossterm::event::{KeyCode as CrosstermKeyCode, KeyEvent as CrosstermKeyEvent};
@@ -133,4 +241,58 @@ mod tests {
         assert_eq!(input.key().map(|key| key.code), Some(KeyCode::Char('j')));
         assert_eq!(input.action(), Some(Action::Down));
     }
+
+    #[test]
+    fn digits_jump_to_tabs_unless_the_user_bound_them() {
+        let key = |code| CrosstermKeyEvent::new(code, KeyModifiers::NONE);
+        let map = KeyMap::default();
+        let input = mapped_key(key(CrosstermKeyCode::Char('2')), &map).unwrap();
+        assert_eq!(input.action(), Some(Action::SelectTab(1)));
+        let input = mapped_key(key(CrosstermKeyCode::F(5)), &map).unwrap();
+        assert_eq!(input.action(), Some(Action::Refresh));
+
+        let mut persisted = crate::keymap::PersistedKeymap::new();
+        persisted.insert("refresh".into(), vec!["2".into()]);
+        let map = KeyMap::from_persisted(&persisted);
+        let input = mapped_key(key(CrosstermKeyCode::Char('2')), &map).unwrap();
+        assert_eq!(input.action(), Some(Action::Refresh));
+    }
+
+    #[test]
+    fn terminal_capabilities_follow_the_environment() {
+        let detect = |vars: &[(&str, &str)], windows| {
+            detect_terminal(
+                |name| {
+                    vars.iter()
+                        .find(|(key, _)| *key == name)
+                        .map(|(_, value)| value.to_string())
+                },
+                windows,
+            )
+        };
+        let caps = detect(
+            &[
+                ("TERM", "xterm-256color"),
+                ("COLORTERM", "truecolor"),
+                ("LANG", "en_US.UTF-8"),
+            ],
+            false,
+        );
+        assert_eq!(caps, TerminalCaps::default());
+
+        let caps = detect(&[("TERM", "screen-256color"), ("LANG", "C.UTF-8")], false);
+        assert_eq!((caps.colors, caps.unicode), (ColorDepth::Ansi256, true));
+
+        let caps = detect(&[("TERM", "linux"), ("LANG", "en_US.UTF-8")], false);
+        assert_eq!((caps.colors, caps.unicode), (ColorDepth::Ansi16, false));
+
+        let caps = detect(&[("TERM", "xterm"), ("LC_ALL", "POSIX")], false);
+        assert_eq!((caps.colors, caps.unicode), (ColorDepth::Ansi16, false));
+
+        let caps = detect(&[("WT_SESSION", "0b1c")], true);
+        assert_eq!(caps, TerminalCaps::default());
+
+        let caps = detect(&[], true);
+        assert_eq!((caps.colors, caps.unicode), (ColorDepth::Ansi256, false));
+    }
 }
@@ -0,0 +1,227 @@
+//! 界面文字检查：用 Demo 场景逐页、逐个诊断工具以及帮助，把两种语言的界面各画一遍，
+//! 记下经 `tr` 绘制的全部英文原文，并找出英文界面上残留的中文。
+//!
+//! 内置翻译成对编译，不会缺键；需要核对的是用户的 `locale.toml`：以 en-US 表为基准，
+//! 其他语言少了的或留空的条目算缺失，原文在界面上找不到的条目算未用上。
+//! 启动时和每次改动后由覆盖文件的监视线程调用，`iptools i18n-check` 打印完整清单。
+
+use std::{collections::BTreeSet, path::Path, sync::OnceLock};
+
+use iptools_core::{
+    Action, AppModel, DiagnosticFocus, DiagnosticTool, Effect, InputEvent, Language, LocaleAudit,
+    LocaleOverrides, Message, Page, RuntimeEvent,
+};
+use iptools_demo::{DemoRuntime, ScenarioId};
+use iptools_ui::{LANGUAGE_SWITCH_ZH, UiState};
+use ratatui::{Terminal, backend::TestBackend};
+
+use crate::overrides::OverrideFiles;
+
+/// 诊断工具启动后再推进这么久才截取画面，让结果区有内容。
+const TOOL_RUN_MS: u64 = 3_000;
+
+/// 两种语言的界面各画一遍的结果，整个进程只算一次。
+pub struct Interface {
+    pub screens: usize,
+    /// 经 `tr` 绘制过的英文原文，即 locale.toml 可用的键。
+    pub text: BTreeSet<String>,
+    /// 英文界面上带中文的行，前缀为画面名称。
+    pub untranslated: Vec<String>,
+}
+
+pub fn interface() -> &'static Interface {
+    static INTERFACE: OnceLock<Interface> = OnceLock::new();
+    INTERFACE.get_or_init(|| {
+        let ((english, chinese), text) =
+            iptools_ui::record_interface_text(|| (screens(Language::En), screens(Language::Zh)));
+        let untranslated = english
+            .iter()
+            .flat_map(|(name, screen)| {
+                screen
+                    .lines()
+                    .filter(|line| has_chinese(&line.replace(LANGUAGE_SWITCH_ZH, "")))
+                    .map(move |line| format!("{name}: {}", line.trim()))
+            })
+            .collect();
+        Interface {
+            screens: english.len() + chinese.len(),
+            text,
+            untranslated,
+        }
+    })
+}
+
+/// locale.toml 两种语言各自的缺失与未用上的条目。
+pub fn audit(locale: &LocaleOverrides) -> LocaleAudit {
+    let known = &interface().text;
+    LocaleAudit {
+        en: locale.gaps(Language::En, known),
+        zh: locale.gaps(Language::Zh, known),
+    }
+}
+
+/// `iptools i18n-check` 的输出行，以及是否发现了问题。
+pub fn report(config: &Path) -> (Vec<String>, bool) {
+    let interface = interface();
+    let mut files = OverrideFiles::beside(config);
+    let overrides = files.load();
+    let mut lines = vec![format!(
+        "已渲染 {} 个画面，共 {} 条界面文字。",
+        interface.screens,
+        interface.text.len()
+    )];
+    let mut found = false;
+    if interface.untranslated.is_empty() {
+        lines.push("✓ 英文界面没有未翻译的中文".into());
+    } else {
+        found = true;
+        lines.push("✗ 英文界面上有未翻译的中文：".into());
+        lines.extend(
+            interface
+                .untranslated
+                .iter()
+                .map(|line| format!("    {line}")),
+        );
+    }
+    for error in &overrides.errors {
+        found = true;
+        lines.push(format!("✗ {error}"));
+    }
+    let locale_path = files.locale_path();
+    if overrides.locale.is_empty() {
+        lines.push(format!(
+            "没有 {} 或其中没有条目，只检查了内置翻译。",
+            locale_path.display()
+        ));
+        return (lines, found);
+    }
+    lines.push(format!("{}（以 en-US 为基准）：", locale_path.display()));
+    for language in [Language::En, Language::Zh] {
+        let gaps = overrides.audit.get(language);
+        found |= !gaps.is_empty();
+        lines.push(format!(
+            "{} {}：{} 条，缺 {} 条，{} 条未用上",
+            if gaps.is_empty() { '✓' } else { '✗' },
+            language.as_str(),
+            overrides.locale.texts(language).len(),
+            gaps.missing.len(),
+            gaps.unused.len()
+        ));
+        lines.extend(gaps.missing.iter().map(|key| format!("    缺：{key}")));
+        lines.extend(gaps.unused.iter().map(|key| format!("    未用上：{key}")));
+    }
+    (lines, found)
+}
+
+fn has_chinese(text: &str) -> bool {
+    text.chars().any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c))
+}
+
+/// 每个页面、每个运行过的诊断工具和帮助的画面：（名称, 文本）。
+fn screens(language: Language) -> Vec<(String, String)> {
+    let mut screens = Vec::new();
+    for page in Page::ALL {
+        let mut screen = Screen::new(language);
+        screen.act(Action::SelectPage(page as u8));
+        screens.push((format!("{page:?}"), screen.draw()));
+    }
+    for (index, tool) in DiagnosticTool::ALL.into_iter().enumerate() {
+        let mut screen = Screen::new(language);
+        screen.act(Action::SelectPage(Page::Diagnostics as u8));
+        screen.act(Action::SelectDiagnostic(index as u8));
+        screen.act(Action::FocusDiagnostic(DiagnosticFocus::Main));
+        screen.act(Action::Toggle);
+        screen.advance(TOOL_RUN_MS);
+        screens.push((format!("{tool:?}"), screen.draw()));
+    }
+    let mut screen = Screen::new(language);
+    screen.act(Action::Help);
+    screens.push(("Help".into(), screen.draw()));
+    screens
+}
+
+/// 由确定性的 Demo Runtime 驱动的一份模型，与快照测试的做法相同。
+struct Screen {
+    model: AppModel,
+    runtime: DemoRuntime,
+    ui: UiState,
+}
+
+impl Screen {
+    fn new(language: Language) -> Self {
+        let mut model = AppModel::default();
+        model.language = language;
+        let mut screen = Self {
+            model,
+            runtime: DemoRuntime::new(ScenarioId::MultiAdapter).expect("built-in scenario"),
+            ui: UiState::default(),
+        };
+        for event in screen.runtime.bootstrap() {
+            screen.deliver(event);
+        }
+        let effects = screen.model.bootstrap_effects();
+        screen.run(effects);
+        screen
+    }
+
+    fn act(&mut self, action: Action) {
+        let effects = self
+            .model
+            .update(Message::Input(InputEvent::Action(action)));
+        self.run(effects);
+    }
+
+    fn advance(&mut self, delta_ms: u64) {
+        for event in self.runtime.advance(delta_ms) {
+            self.deliver(event);
+        }
+    }
+
+    fn run(&mut self, effects: Vec<Effect>) {
+        for effect in effects {
+            for event in self.runtime.dispatch(effect) {
+                self.deliver(event);
+            }
+        }
+    }
+
+    fn deliver(&mut self, event: RuntimeEvent) {
+        let effects = self.model.update(Message::Runtime(event));
+        self.run(effects);
+    }
+
+    fn draw(&mut self) -> String {
+        let mut terminal =
+            Terminal::new(TestBackend::new(120, 36)).expect("test backend never fails");
+        terminal
+            .draw(|frame| iptools_ui::render(frame, &self.model, &mut self.ui))
+            .expect("test backend never fails");
+        terminal.backend().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_interface_walk_finds_the_keys_a_locale_file_can_use() {
+        let interface = interface();
+        assert!(
+            interface.untranslated.is_empty(),
+            "{:?}",
+            interface.untranslated
+        );
+        for english in ["Dashboard", "Settings", " Idle "] {
+            assert!(interface.text.contains(english), "{english}");
+        }
+
+        let mut locale = LocaleOverrides::default();
+        locale.en.insert("Settings".into(), "Preferences".into());
+        locale.zh.insert("Setings".into(), "偏好".into());
+        let audit = audit(&locale);
+        assert!(audit.en.is_empty());
+        assert_eq!(audit.zh.missing, ["Settings"]);
+        assert_eq!(audit.zh.unused, ["Setings"]);
+    }
+}
@@ -0,0 +1,222 @@
+//! 单实例检测：同一用户同时运行两份会重复探测网络、争抢配置文件。
+//!
+//! 锁文件放在用户运行目录：`XDG_RUNTIME_DIR`，没有时在系统临时目录下建一个按用户号命名、
+//! 只对本人开放的子目录（Windows 的临时目录本来就按用户区分，文件名带上用户名）。
+//! 持有期间加独占文件锁；进程退出（包括被杀）后由系统自动释放，不会留下僵尸锁。
+//! 共享的临时目录里名字可以猜到，已存在的目录与锁文件须属于当前用户才会使用。
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum InstanceError {
+    /// 另一个实例持有锁；`pid` 是它写入的进程号，读不到时为 `None`。
+    #[error("iptools is already running{}", .pid.map(|pid| format!(" (PID {pid})")).unwrap_or_default())]
+    Running { pid: Option<u32> },
+    #[error("cannot use instance lock {}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// 持有期间其他实例无法启动；drop 时释放。
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+/// 当前用户的锁文件路径。
+#[cfg(unix)]
+pub fn lock_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join("iptools.lock"),
+        None => std::env::temp_dir()
+            .join(format!("iptools-{}", nix::unistd::getuid()))
+            .join("iptools.lock"),
+    }
+}
+
+/// 服务里的守护进程用 [`pin_lock_path`] 固定下来的锁路径。
+#[cfg(windows)]
+static PINNED_LOCK_PATH: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// 让本进程的 [`lock_path`] 固定返回 `path`：服务进程的临时目录与用户名环境变量可能与
+/// 安装者的登录会话不同，用安装时记下的路径，界面才能找到服务里的守护进程。
+#[cfg(windows)]
+pub fn pin_lock_path(path: PathBuf) {
+    let _ = PINNED_LOCK_PATH.set(path);
+}
+
+/// 当前用户的锁文件路径。
+#[cfg(not(unix))]
+pub fn lock_path() -> PathBuf {
+    #[cfg(windows)]
+    if let Some(path) = PINNED_LOCK_PATH.get() {
+        return path.clone();
+    }
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    let user: String = user
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    if user.is_empty() {
+        std::env::temp_dir().join("iptools.lock")
+    } else {
+        std::env::temp_dir().join(format!("iptools-{user}.lock"))
+    }
+}
+
+/// 建好只对当前用户开放的目录（0700）。已存在的目录须属于当前用户且不是符号链接，
+/// 否则拒绝使用：在共享的临时目录里它可能是别人事先放下的。
+#[cfg(unix)]
+pub fn prepare_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(error) => return Err(error),
+    }
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != nix::unistd::getuid().as_raw() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} 不是当前用户的目录", dir.display()),
+        ));
+    }
+    if metadata.permissions().mode() & 0o077 != 0 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// 打开锁文件：不存在时以 O_EXCL 新建（0600）；已存在的须是当前用户的普通文件，
+/// 不跟随符号链接。
+#[cfg(unix)]
+fn open_lock_file(path: &Path) -> std::io::Result<File> {
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+
+    if let Some(dir) = path.parent() {
+        prepare_private_dir(dir)?;
+    }
+    match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+    {
+        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+        created => return created,
+    }
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() || metadata.uid() != nix::unistd::getuid().as_raw() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} 不是当前用户的文件", path.display()),
+        ));
+    }
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn open_lock_file(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+/// 取得独占锁并写入本进程号。
+pub fn acquire(path: &Path) -> Result<InstanceLock, InstanceError> {
+    let io_error = |source| InstanceError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut file = open_lock_file(path).map_err(io_error)?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            let mut text = String::new();
+            // Windows 上被锁定的区域不可读，此时只报告“已在运行”。
+            let pid = file
+                .read_to_string(&mut text)
+                .ok()
+                .and_then(|_| text.trim().parse().ok());
+            return Err(InstanceError::Running { pid });
+        }
+        Err(TryLockError::Error(error)) => return Err(io_error(error)),
+    }
+    file.set_len(0).map_err(io_error)?;
+    file.rewind().map_err(io_error)?;
+    write!(file, "{}", std::process::id()).map_err(io_error)?;
+    file.flush().map_err(io_error)?;
+    Ok(InstanceLock { _file: file })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_acquire_reports_the_running_pid_until_the_lock_is_dropped() {
+        let path = std::env::temp_dir()
+            .join(format!("iptools-instance-test-{}", std::process::id()))
+            .join("iptools.lock");
+        let lock = acquire(&path).expect("first instance takes the lock");
+        match acquire(&path) {
+            Err(InstanceError::Running { pid }) => {
+                if cfg!(unix) {
+                    assert_eq!(pid, Some(std::process::id()));
+                }
+            }
+            other => panic!("expected Running, got {other:?}"),
+        }
+        drop(lock);
+        let lock = acquire(&path).expect("lock is free again after drop");
+        drop(lock);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn the_lock_lives_in_a_directory_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("iptools-private-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let lock = acquire(&dir.join("iptools.lock")).unwrap();
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&dir.join("iptools.lock")), 0o600);
+        drop(lock);
+        // 已存在但过于宽松的目录被收紧
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        prepare_private_dir(&dir).unwrap();
+        assert_eq!(mode(&dir), 0o700);
+
+        // 指向别处的符号链接不被当成自己的锁文件或目录
+        let target = dir.join("elsewhere");
+        std::fs::write(&target, "").unwrap();
+        std::os::unix::fs::symlink(&target, dir.join("link.lock")).unwrap();
+        assert!(matches!(
+            acquire(&dir.join("link.lock")),
+            Err(InstanceError::Io { .. })
+        ));
+        let link = dir.with_extension("link");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(std::env::temp_dir(), &link).unwrap();
+        assert!(prepare_private_dir(&link).is_err());
+        std::fs::remove_file(&link).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,21 +1,21 @@
 //! 可由用户自定义的快捷键映射。
 //!
 //! 设计：将物理按键与"语义动作"(`Action`) 解耦。各模块只关心动作，
-//! 不再硬编码 `KeyCode`。用户可在 `config.json` 的 `keybindings` 段覆盖默认绑定，
+//! 不再硬编码 `KeyCode`。用户可在 `config.toml` 的 `keybindings` 表覆盖默认绑定，
 //! 形如：
-//! ```json
-//! "keybindings": {
-//!   "quit":  ["Ctrl+c", "Ctrl+q"],
-//!   "down":  ["Down", "j"],
-//!   "next_tab": ["Tab"]
-//! }
+//! ```toml
+//! [keybindings]
+//! quit = ["Ctrl+c", "Ctrl+q"]
+//! down = ["Down", "j"]
+//! next_tab = ["Tab"]
 //! ```
 //! 未覆盖的动作沿用内置默认值；无法解析的组合键被忽略（不会让程序崩溃）。
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use iptools_core::ConfigIssue;
 use std::collections::{BTreeMap, HashMap};
 
-/// 持久化形式：动作名 -> 组合键字符串列表。直接写入 / 读出 config.json。
+/// 持久化形式：动作名 -> 组合键字符串列表。直接写入 / 读出配置文件。
 pub type PersistedKeymap = BTreeMap<String, Vec<String>>;
 
 /// 全部语义动作。新增动作时记得同步 `name`/`from_name`/`ALL`/`default_combos`。
@@ -24,11 +24,16 @@ pub enum Action {
     // 全局
     Quit,
     ToggleLanguage,
+    ToggleRedaction,
     NextTab,
     PrevTab,
     // 通用导航 / 控制
     Up,
     Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
     Left,
     Right,
     Confirm,
@@ -38,6 +43,17 @@ pub enum Action {
     Edit,
     Toggle,
     Help,
+    ShowUpdate,
+    Presets,
+    Problems,
+    Audit,
+    Calculator,
+    Search,
+    Timeline,
+    Export,
+    // 分屏
+    ToggleSplit,
+    SwitchPane,
 }
 
 impl Action {
@@ -46,10 +62,15 @@ impl Action {
         match self {
             Action::Quit => "quit",
             Action::ToggleLanguage => "toggle_language",
+            Action::ToggleRedaction => "toggle_redaction",
             Action::NextTab => "next_tab",
             Action::PrevTab => "prev_tab",
             Action::Up => "up",
             Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Home => "home",
+            Action::End => "end",
             Action::Left => "left",
             Action::Right => "right",
             Action::Confirm => "confirm",
@@ -59,6 +80,16 @@ impl Action {
             Action::Edit => "edit",
             Action::Toggle => "toggle",
             Action::Help => "help",
+            Action::ShowUpdate => "show_update",
+            Action::Presets => "presets",
+            Action::Problems => "problems",
+            Action::Audit => "audit",
+            Action::Calculator => "calculator",
+            Action::Search => "search",
+            Action::Timeline => "timeline",
+            Action::Export => "export",
+            Action::ToggleSplit => "toggle_split",
+            Action::SwitchPane => "switch_pane",
         }
     }
 
@@ -67,13 +98,18 @@ impl Action {
     }
 
     /// 解析优先级顺序（全局动作在前）。`action_for` 按此顺序匹配。
-    pub const ALL: [Action; 15] = [
+    pub const ALL: [Action; 30] = [
         Action::Quit,
         Action::ToggleLanguage,
+        Action::ToggleRedaction,
         Action::NextTab,
         Action::PrevTab,
         Action::Up,
         Action::Down,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Home,
+        Action::End,
         Action::Left,
         Action::Right,
         Action::Confirm,
@@ -83,6 +119,16 @@ impl Action {
         Action::Edit,
         Action::Toggle,
         Action::Help,
+        Action::ShowUpdate,
+        Action::Presets,
+        Action::Problems,
+        Action::Audit,
+        Action::Calculator,
+        Action::Search,
+        Action::Timeline,
+        Action::Export,
+        Action::ToggleSplit,
+        Action::SwitchPane,
     ];
 
     fn default_combos(self) -> Vec<KeyCombo> {
@@ -96,8 +142,10 @@ impl Action {
             Action::Quit => vec![
                 c(Char('c'), KeyModifiers::CONTROL),
                 c(Char('q'), KeyModifiers::CONTROL),
+                plain(F(10)),
             ],
             Action::ToggleLanguage => vec![c(Char('l'), KeyModifiers::CONTROL)],
+            Action::ToggleRedaction => vec![c(Char('p'), KeyModifiers::CONTROL)],
             Action::NextTab => vec![plain(Tab)],
             // Shift+Tab 在不同终端可能上报为：BackTab(无修饰)、BackTab+Shift、
             // 或 Tab+Shift。三种都绑上，避免「上一标签页」在 Windows 终端失效。
@@ -108,15 +156,29 @@ impl Action {
             ],
             Action::Up => vec![plain(Up), plain(Char('w'))],
             Action::Down => vec![plain(Down), plain(Char('s'))],
+            Action::PageUp => vec![plain(PageUp)],
+            Action::PageDown => vec![plain(PageDown)],
+            Action::Home => vec![plain(Home)],
+            Action::End => vec![plain(End)],
             Action::Left => vec![plain(Left), plain(Char('a'))],
             Action::Right => vec![plain(Right), plain(Char('d'))],
             Action::Confirm => vec![plain(Enter)],
             Action::Back => vec![plain(Esc)],
-            Action::Refresh => vec![plain(Char('r'))],
-            Action::History => vec![c(Char('r'), KeyModifiers::CONTROL)],
-            Action::Edit => vec![plain(Char('e'))],
+            Action::Refresh => vec![plain(Char('r')), plain(F(5))],
+            Action::History => vec![c(Char('r'), KeyModifiers::CONTROL), plain(F(3))],
+            Action::Edit => vec![plain(Char('e')), plain(F(2))],
             Action::Toggle => vec![plain(Char(' '))],
             Action::Help => vec![plain(F(1))],
+            Action::ShowUpdate => vec![c(Char('u'), KeyModifiers::CONTROL)],
+            Action::Presets => vec![plain(F(4))],
+            Action::Problems => vec![plain(F(6))],
+            Action::Audit => vec![plain(F(7))],
+            Action::Calculator => vec![plain(F(8))],
+            Action::Search => vec![c(Char('f'), KeyModifiers::CONTROL)],
+            Action::Timeline => vec![plain(F(9))],
+            Action::Export => vec![c(Char('s'), KeyModifiers::CONTROL)],
+            Action::ToggleSplit => vec![c(Char('t'), KeyModifiers::CONTROL)],
+            Action::SwitchPane => vec![c(Char('w'), KeyModifiers::CONTROL)],
         }
     }
 }
@@ -305,6 +367,30 @@ impl KeyMap {
         km
     }
 
+    /// 列出 config 中无法识别的动作名与无法解析的按键；它们在 `from_persisted` 中被忽略。
+    pub fn issues(over: &PersistedKeymap) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        for (name, combos) in over {
+            if Action::from_name(name).is_none() {
+                issues.push(ConfigIssue::Keybinding {
+                    action: name.clone(),
+                    key: String::new(),
+                });
+                continue;
+            }
+            issues.extend(
+                combos
+                    .iter()
+                    .filter(|combo| KeyCombo::parse(combo).is_none())
+                    .map(|combo| ConfigIssue::Keybinding {
+                        action: name.clone(),
+                        key: combo.clone(),
+                    }),
+            );
+        }
+        issues
+    }
+
     /// 导出为持久化形式（含全部动作，便于用户在 config 中发现与编辑）。
     pub fn to_persisted(&self) -> PersistedKeymap {
         Action::ALL
@@ -376,6 +462,14 @@ mod tests {
             km.action_for(ev(KeyCode::Char('d'), KeyModifiers::NONE)),
             Some(Action::Right)
         );
+        assert_eq!(
+            km.action_for(ev(KeyCode::PageDown, KeyModifiers::NONE)),
+            Some(Action::PageDown)
+        );
+        assert_eq!(
+            km.action_for(ev(KeyCode::End, KeyModifiers::NONE)),
+            Some(Action::End)
+        );
     }
 
     #[test]
@@ -404,6 +498,20 @@ mod tests {
             km.action_for(ev(KeyCode::Char('c'), KeyModifiers::CONTROL)),
             Some(Action::Quit)
         );
+        over.insert("quiet".into(), vec!["q".into()]);
+        assert_eq!(
+            KeyMap::issues(&over),
+            [
+                ConfigIssue::Keybinding {
+                    action: "quiet".into(),
+                    key: String::new(),
+                },
+                ConfigIssue::Keybinding {
+                    action: "quit".into(),
+                    key: "NotAKey++".into(),
+                },
+            ]
+        );
     }
 
     #[test]
@@ -1,27 +1,45 @@
 #![allow(linker_messages)] // MSVC prints a harmless localized import-library notice for test bins.
 
+use std::path::PathBuf;
+
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use iptools_core::KioskView;
 use iptools_demo::ScenarioId;
 
+mod archive;
 mod config;
+mod daemon;
 mod demo;
+mod doctor;
 mod event;
 mod frontend;
+mod i18n;
+mod instance;
 mod keymap;
 mod modules;
 mod native_app;
+mod overrides;
+mod record;
 pub mod runtime;
+mod secrets;
+#[cfg(any(target_os = "linux", windows))]
+mod service;
+mod store;
 mod utils;
 
 /// 模块化、跨平台的网络工具箱。
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// 配置文件路径；默认使用当前目录下的 config.json。
+    /// 配置文件路径；默认使用当前目录下的 config.toml，以 .json 结尾时按 JSON 读写。
     #[arg(short, long, value_name = "FILE")]
     config: Option<String>,
 
+    /// 开启离线模式并写入配置：不再自动查询公网 IP 与地理位置，适合隔离网络。
+    #[arg(long)]
+    offline: bool,
+
     /// 使用确定性模拟数据运行，不访问真实网络或系统配置。
     #[arg(long)]
     demo: bool,
@@ -29,6 +47,110 @@ struct Args {
     /// 选择内置演示场景；仅与 --demo 一起使用。
     #[arg(long, value_enum, requires = "demo")]
     scenario: Option<ScenarioArg>,
+
+    /// 演示数据的随机种子；相同种子复现相同的流量、延迟和扫描节奏，0 使用场景原始数值。
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "demo")]
+    seed: u64,
+
+    /// 跳过单实例检查，允许同一用户同时运行多份（会重复探测网络）。
+    #[arg(long)]
+    allow_multiple: bool,
+
+    /// 把本次会话的画面连同时间录成 asciicast v2 文件，可用 asciinema play 回放，便于给偶发问题附上证据。
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// 全屏只显示一个视图（仪表盘、流量或监视卡片），大号数字、没有标签栏与按键提示，
+    /// 除退出外不响应按键，适合挂在墙上的状态屏。
+    #[arg(long, value_enum, value_name = "VIEW")]
+    kiosk: Option<KioskArg>,
+
+    /// 显示另一台机器上 iptools daemon 的界面：user@host 或 ssh://user@host:端口 经 SSH 连接，
+    /// tcp://主机:端口 连接 daemon --listen（令牌取自环境变量 IPTOOLS_AGENT_TOKEN）。
+    #[arg(
+        long,
+        value_name = "TARGET",
+        value_parser = daemon::RemoteTarget::parse,
+        conflicts_with_all = ["demo", "kiosk", "record"]
+    )]
+    remote: Option<daemon::RemoteTarget>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 在后台持续运行监视、扫描与流量采集，关掉终端也不中断；运行中再执行 iptools 即连接到它的界面。
+    Daemon {
+        /// 让正在运行的守护进程保存会话后退出。
+        #[arg(long)]
+        stop: bool,
+        /// 同时在这个地址接受其他机器的 --remote tcp:// 连接，如 0.0.0.0:7878；
+        /// 连接须持有配置文件旁 iptools-agent.token 中的令牌。
+        #[arg(long, value_name = "ADDR", conflicts_with = "stop")]
+        listen: Option<String>,
+        /// 把标准输入输出转接到本机正在运行的守护进程，供 --remote 经 SSH 调用。
+        #[arg(long, hide = true, conflicts_with_all = ["stop", "listen"])]
+        bridge: bool,
+        /// 由服务控制管理器启动，供 install-service 注册的 Windows 服务使用。
+        #[cfg(windows)]
+        #[arg(long, hide = true, conflicts_with_all = ["stop", "bridge"])]
+        service: bool,
+        /// 服务里的守护进程使用的单实例锁路径，由 install-service 记下。
+        #[cfg(windows)]
+        #[arg(long, hide = true, value_name = "FILE", requires = "service")]
+        lock: Option<PathBuf>,
+    },
+    /// 把守护进程注册为开机自启的服务（Linux 上是 systemd 服务，以当前用户身份运行并授予
+    /// CAP_NET_RAW，需要 sudo；Windows 上是以当前账户运行的系统服务，需要管理员权限）。
+    InstallService {
+        /// 守护进程同时监听的地址，同 daemon --listen。
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<String>,
+        /// 只打印生成的单元文件，不安装。
+        #[arg(long)]
+        print: bool,
+        /// 停用并删除已安装的服务。
+        #[arg(long, conflicts_with_all = ["listen", "print"])]
+        uninstall: bool,
+    },
+    /// 自我诊断：检查权限、ICMP、抓包库、防火墙、配置与外网连通性，有失败项时退出码为 1。
+    Doctor,
+    /// 把配置、设备清单、事件日志与监视/测速/流量记录导出为一个归档文件，便于迁移到另一台机器。
+    Export {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// 从 export 生成的归档恢复全部数据，替换当前配置（原文件先备份）与历史记录。
+    Import {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// 把每个页面与诊断工具的两种语言界面各画一遍，检查英文界面上未翻译的中文，
+    /// 并以 en-US 为基准列出配置文件旁 locale.toml 中缺失和未用上的条目；有问题时退出码为 1。
+    I18nCheck,
+    /// 管理系统钥匙串中的 API 令牌与密码；配置里以 ${secret:名称} 引用，不写明文。
+    Secret {
+        #[command(subcommand)]
+        action: SecretAction,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum SecretAction {
+    /// 从标准输入读一行存为密钥，值不经命令行参数，不会留在 shell 历史里。
+    Set {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// 从钥匙串删除密钥。
+    Delete {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// 列出配置引用的密钥及其是否已存入钥匙串，有缺失时退出码为 1。
+    Check,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -38,6 +160,23 @@ enum ScenarioArg {
     MultiAdapter,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum KioskArg {
+    Dashboard,
+    Traffic,
+    Monitor,
+}
+
+impl From<KioskArg> for KioskView {
+    fn from(value: KioskArg) -> Self {
+        match value {
+            KioskArg::Dashboard => Self::Dashboard,
+            KioskArg::Traffic => Self::Traffic,
+            KioskArg::Monitor => Self::Monitor,
+        }
+    }
+}
+
 impl From<ScenarioArg> for ScenarioId {
     fn from(value: ScenarioArg) -> Self {
         match value {
@@ -52,19 +191,127 @@ impl From<ScenarioArg> for ScenarioId {
 async fn main() -> Result<()> {
     init_tracing();
     let args = Args::parse();
+    match &args.command {
+        Some(Command::Daemon {
+            stop,
+            listen,
+            bridge,
+            ..
+        }) => {
+            #[cfg(windows)]
+            if let Some(Command::Daemon {
+                service: true,
+                lock,
+                ..
+            }) = &args.command
+            {
+                return run_service(
+                    args.config.clone(),
+                    args.offline,
+                    listen.clone(),
+                    lock.clone(),
+                )
+                .await;
+            }
+            return run_daemon(
+                args.config.clone(),
+                args.offline,
+                *stop,
+                listen.clone(),
+                *bridge,
+            )
+            .await;
+        }
+        Some(Command::InstallService {
+            listen,
+            print,
+            uninstall,
+        }) => {
+            return run_install_service(
+                args.config.as_deref(),
+                args.offline,
+                listen.clone(),
+                *print,
+                *uninstall,
+            );
+        }
+        Some(Command::Doctor) => return run_doctor(args.config.as_deref()).await,
+        Some(Command::Export { file }) => return run_export(args.config.as_deref(), file),
+        Some(Command::Import { file }) => return run_import(args.config.as_deref(), file),
+        Some(Command::I18nCheck) => {
+            let config = config::Config::inspect(args.config.as_deref()).path;
+            let (lines, found) = tokio::task::spawn_blocking(move || i18n::report(&config)).await?;
+            for line in lines {
+                println!("{line}");
+            }
+            if found {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::Secret { action }) => {
+            let (config, action) = (args.config.clone(), action.clone());
+            return tokio::task::spawn_blocking(move || run_secret(config.as_deref(), action))
+                .await?;
+        }
+        None => {}
+    }
+    if let Some(target) = &args.remote {
+        return run_remote(args.config.as_deref(), target).await;
+    }
     if args.demo {
         return demo::run(
             args.scenario.unwrap_or(ScenarioArg::HomeNetwork).into(),
+            args.seed,
             args.config,
+            args.record,
+            args.kiosk.map(Into::into),
         )
         .await;
     }
-    native_app::run(args.config).await?;
+    // 锁在整个会话期间持有；拿不到锁文件本身（如只读目录）时不阻止启动。
+    let _instance = if args.allow_multiple {
+        None
+    } else {
+        match instance::acquire(&instance::lock_path()) {
+            Ok(lock) => Some(lock),
+            Err(instance::InstanceError::Running { pid }) => {
+                if let Some(local) = daemon::connect() {
+                    return daemon::attach(
+                        args.config.as_deref(),
+                        daemon::Transport::local(local)?,
+                    )
+                    .await;
+                }
+                match pid {
+                    Some(pid) => {
+                        eprintln!("iptools 已在运行（PID {pid}），同一用户只允许一个实例。")
+                    }
+                    None => eprintln!("iptools 已在运行，同一用户只允许一个实例。"),
+                }
+                eprintln!(
+                    "      请切换到已打开的窗口，或关闭它后重试；确需多开请加 --allow-multiple。"
+                );
+                std::process::exit(1);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "single-instance check skipped");
+                None
+            }
+        }
+    };
+    native_app::run(
+        args.config,
+        args.offline,
+        args.record,
+        args.kiosk.map(Into::into),
+    )
+    .await?;
 
     // 终端恢复后再显示权限提示，避免信息被备用屏幕吞掉。
     #[cfg(target_os = "linux")]
     {
-        if !crate::utils::net::has_cap_net_raw() {
+        if !crate::utils::android::is_android() && !crate::utils::net::has_cap_net_raw() {
             eprintln!();
             eprintln!("提示：未检测到 CAP_NET_RAW —— 局域网扫描 / Ping / Trace / 链路质量需要它。");
             eprintln!(
@@ -76,6 +323,286 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// 守护进程与普通实例共用单实例锁：同一用户只有一份在采集。
+async fn run_daemon(
+    config_path: Option<String>,
+    offline: bool,
+    stop: bool,
+    listen: Option<String>,
+    bridge: bool,
+) -> Result<()> {
+    if bridge {
+        if let Err(error) = daemon::bridge() {
+            eprintln!("iptools 守护进程转接失败：{error}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if stop {
+        match daemon::connect().map(daemon::stop) {
+            Some(Ok(())) => println!("已通知守护进程保存会话并退出。"),
+            Some(Err(error)) => {
+                eprintln!("无法通知守护进程：{error}");
+                std::process::exit(1);
+            }
+            None => {
+                eprintln!("没有正在运行的守护进程。");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    let _instance = match instance::acquire(&instance::lock_path()) {
+        Ok(lock) => lock,
+        Err(instance::InstanceError::Running { .. }) => {
+            eprintln!("iptools 已在运行，请先退出界面（或 iptools daemon --stop）再启动守护进程。");
+            std::process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("无法启动守护进程：{error}");
+            std::process::exit(1);
+        }
+    };
+    daemon::run(config_path, offline, listen).await
+}
+
+#[cfg(target_os = "linux")]
+fn run_install_service(
+    config_path: Option<&str>,
+    offline: bool,
+    listen: Option<String>,
+    print: bool,
+    uninstall: bool,
+) -> Result<()> {
+    let result = if uninstall {
+        service::uninstall().map(|()| println!("已停用并删除 iptools 服务。"))
+    } else {
+        service::ServiceSpec::current(config_path, offline, listen).and_then(|spec| {
+            if print {
+                print!("{}", spec.unit());
+                return Ok(());
+            }
+            let path = service::install(&spec)?;
+            println!(
+                "已安装并启动 {}：以用户 {} 运行，配置 {}。",
+                path.display(),
+                spec.user,
+                spec.config.display()
+            );
+            println!("      开机后自动在后台采集；运行 iptools 即连接，systemctl status iptools 查看状态。");
+            Ok(())
+        })
+    };
+    if let Err(error) = result {
+        eprintln!("{error:#}");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 服务控制管理器启动的守护进程：锁路径固定为安装时记下的路径，其余与 `iptools daemon` 相同；
+/// 已有实例运行时以错误退出，由服务的失败重启稍后再试。
+#[cfg(windows)]
+async fn run_service(
+    config_path: Option<String>,
+    offline: bool,
+    listen: Option<String>,
+    lock: Option<PathBuf>,
+) -> Result<()> {
+    if let Some(lock) = lock {
+        instance::pin_lock_path(lock);
+    }
+    let config = config::Config::inspect(config_path.as_deref()).path;
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        service::run(&config, move || {
+            runtime.block_on(async {
+                let _instance = instance::acquire(&instance::lock_path())?;
+                daemon::run(config_path, offline, listen).await
+            })
+        })
+    })
+    .await?
+}
+
+#[cfg(windows)]
+fn run_install_service(
+    config_path: Option<&str>,
+    offline: bool,
+    listen: Option<String>,
+    print: bool,
+    uninstall: bool,
+) -> Result<()> {
+    let result = if uninstall {
+        service::uninstall().map(|()| println!("已停止并删除 iptools 服务。"))
+    } else {
+        service::ServiceSpec::current(config_path, offline, listen).and_then(|spec| {
+            if print {
+                print!("{}", spec.describe());
+                return Ok(());
+            }
+            service::install(&spec)?;
+            println!(
+                "已安装并启动 iptools 服务：以账户 {} 运行，配置 {}。",
+                spec.account,
+                spec.config.display()
+            );
+            println!(
+                "      开机后自动在后台采集；运行 iptools 即连接，services.msc 或 sc.exe query iptools 查看状态。"
+            );
+            Ok(())
+        })
+    };
+    if let Err(error) = result {
+        eprintln!("{error:#}");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn run_install_service(
+    _config_path: Option<&str>,
+    _offline: bool,
+    _listen: Option<String>,
+    _print: bool,
+    _uninstall: bool,
+) -> Result<()> {
+    eprintln!(
+        "install-service 目前只支持 Linux（systemd）与 Windows；其他系统可用 nohup 或系统自带的服务管理器运行 iptools daemon。"
+    );
+    std::process::exit(1);
+}
+
+/// 远程界面不占用单实例锁：本机可以同时运行自己的 iptools。
+async fn run_remote(config_path: Option<&str>, target: &daemon::RemoteTarget) -> Result<()> {
+    let result = match target.connect() {
+        Ok(transport) => daemon::attach(config_path, transport).await,
+        Err(error) => Err(error.into()),
+    };
+    if let Err(error) = result {
+        eprintln!("无法连接 {target}：{error}");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_doctor(config_path: Option<&str>) -> Result<()> {
+    let language = config::Config::inspect(config_path).language;
+    let path = config_path.map(str::to_string);
+    let checks = tokio::task::spawn_blocking(move || doctor::run(path.as_deref())).await?;
+    for line in iptools_ui::doctor_report_lines(language, &checks) {
+        println!("{line}");
+    }
+    if iptools_core::doctor_status(&checks) == iptools_core::DoctorStatus::Fail {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_export(config_path: Option<&str>, file: &std::path::Path) -> Result<()> {
+    match archive::export(config_path, file) {
+        Ok(summary) => {
+            println!("已导出{summary}到 {}", file.display());
+            Ok(())
+        }
+        Err(error) => {
+            eprintln!("导出失败：{error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_secret(config_path: Option<&str>, action: SecretAction) -> Result<()> {
+    let fail = |message: String| -> ! {
+        eprintln!("{message}");
+        std::process::exit(1);
+    };
+    match action {
+        SecretAction::Set { name } => {
+            eprintln!("输入 {name} 的值后回车：");
+            let mut value = String::new();
+            std::io::stdin().read_line(&mut value)?;
+            let value = value.trim_end_matches(['\r', '\n']);
+            if value.is_empty() {
+                fail("值为空，未保存。".into());
+            }
+            if let Err(error) = secrets::set(&name, value) {
+                fail(format!("保存失败：{error}"));
+            }
+            println!(
+                "已保存到系统钥匙串；在配置中以 {} 引用。",
+                iptools_core::secret_reference(&name)
+            );
+        }
+        SecretAction::Delete { name } => match secrets::delete(&name) {
+            Ok(true) => println!("已删除 {name}。"),
+            Ok(false) => println!("钥匙串中没有 {name}。"),
+            Err(error) => fail(format!("删除失败：{error}")),
+        },
+        SecretAction::Check => {
+            let names = config::Config::inspect(config_path)
+                .data
+                .map(|data| iptools_core::config_secret_names(&data))
+                .unwrap_or_default();
+            if names.is_empty() {
+                println!("配置没有引用任何密钥。");
+            }
+            let mut missing = false;
+            for name in names {
+                let status = match secrets::get(&name) {
+                    Ok(Some(_)) => "已保存".to_string(),
+                    Ok(None) => {
+                        missing = true;
+                        format!("缺失（iptools secret set {name}）")
+                    }
+                    Err(error) => {
+                        missing = true;
+                        format!("无法读取：{error}")
+                    }
+                };
+                println!("{name}: {status}");
+            }
+            if missing {
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 导入期间持有单实例锁：运行中的实例退出时会把旧数据写回去。
+fn run_import(config_path: Option<&str>, file: &std::path::Path) -> Result<()> {
+    let _instance = match instance::acquire(&instance::lock_path()) {
+        Ok(lock) => Some(lock),
+        Err(instance::InstanceError::Running { .. }) => {
+            eprintln!("iptools 正在运行，请先退出再导入。");
+            std::process::exit(1);
+        }
+        Err(error) => {
+            tracing::warn!(%error, "single-instance check skipped");
+            None
+        }
+    };
+    match archive::import(config_path, file) {
+        Ok((summary, backups)) => {
+            println!(
+                "已从 {}（{} 导出）导入{summary}",
+                file.display(),
+                summary.exported_at
+            );
+            for backup in backups {
+                println!("原数据已备份为 {}", backup.display());
+            }
+            Ok(())
+        }
+        Err(error) => {
+            eprintln!("导入失败：{error}");
+            std::process::exit(1);
+        }
+    }
+}
+
 fn init_tracing() {
     if std::env::var_os("RUST_LOG").is_some() {
         let _ = tracing_subscriber::fmt()
@@ -0,0 +1,242 @@
+//! A/B 网卡对比：先后从两个网卡跑同一组测试——一串 ICMP Echo、一段限时下载、
+//! 几次 DNS 解析——每个套接字都绑定到该网卡的 IPv4 地址，使流量确实经由它发出；
+//! 两边的数字交给核心层逐项比较。
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use iptools_core::{
+    AbLeg, AbRequest, AbSide, DNS_WARMUP_NAMES, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent,
+};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, sleep, timeout_at};
+use tokio_util::sync::CancellationToken;
+
+use super::dns_wire::{TYPE_A, udp_exchange};
+
+/// 下载对象：Cloudflare 测速端点按请求的字节数返回数据。
+const DOWNLOAD_URL: &str = "https://speed.cloudflare.com/__down?bytes=26214400";
+/// 下载阶段的时长上限；到点即按已收字节计算速率。
+const DOWNLOAD_WINDOW: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(6);
+/// 计时 DNS 查询发往的解析器。
+const DNS_SERVER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53);
+const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+const PING_INTERVAL: Duration = Duration::from_millis(200);
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: AbRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let destination = match super::resolve_target(&request.target).await {
+        Ok(addresses) => addresses.into_iter().find_map(|address| match address {
+            IpAddr::V4(address) => Some(address),
+            IpAddr::V6(_) => None,
+        }),
+        Err(error) => return send(RuntimeEvent::AbTestFailed { job, error }).await,
+    };
+    let Some(destination) = destination else {
+        return send(RuntimeEvent::AbTestFailed {
+            job,
+            error: RuntimeError::new(
+                RuntimeErrorCode::ResolveTarget,
+                "target did not resolve to an IPv4 address",
+            ),
+        })
+        .await;
+    };
+    send(RuntimeEvent::AbTestStarted { job }).await?;
+
+    let sides = [(AbSide::A, &request.first), (AbSide::B, &request.second)];
+    for (side, via) in sides {
+        // 中途取消时丢弃整条测试；已在阻塞线程里的 Echo 会在超时后自行结束
+        let leg = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            leg = run_leg(side, via, destination, &request) => leg,
+        };
+        send(RuntimeEvent::AbTestLeg { job, leg }).await?;
+    }
+    send(RuntimeEvent::AbTestFinished { job }).await
+}
+
+/// 某一部分失败不影响其余部分，失败原因记在 `error` 里，留空的指标由核心层判负。
+async fn run_leg(side: AbSide, via: &str, destination: Ipv4Addr, request: &AbRequest) -> AbLeg {
+    let Some(source) = super::source_address(via).await else {
+        return AbLeg {
+            error: Some(format!("{} has no IPv4 address", via.trim())),
+            ..AbLeg::from_replies(side, via.trim(), "", &[])
+        };
+    };
+    let mut errors = Vec::new();
+    let replies = match pings(source, destination, request).await {
+        Ok(replies) => replies,
+        Err(error) => {
+            errors.push(error);
+            Vec::new()
+        }
+    };
+    let mut leg = AbLeg::from_replies(side, via.trim(), &source.to_string(), &replies);
+    match download_rate(source, DOWNLOAD_URL, DOWNLOAD_WINDOW).await {
+        Ok(rate) => leg.download_bytes_per_second = Some(rate),
+        Err(error) => errors.push(format!("download: {error}")),
+    }
+    match dns_lookups(source, DNS_SERVER, &DNS_WARMUP_NAMES).await {
+        Ok(lookups) => leg.record_dns(lookups),
+        Err(error) => errors.push(format!("DNS: {error}")),
+    }
+    leg.error = (!errors.is_empty()).then(|| errors.join(" · "));
+    leg
+}
+
+/// 每个 Echo 的往返时延，丢失的记为 `None`。
+async fn pings(
+    source: Ipv4Addr,
+    destination: Ipv4Addr,
+    request: &AbRequest,
+) -> Result<Vec<Option<f64>>, String> {
+    let session = Arc::new(super::icmp::IcmpSession::open()?);
+    let timeout_ms = request.timeout_ms.clamp(100, 10_000) as u32;
+    let mut replies = Vec::with_capacity(request.pings as usize);
+    for sequence in 0..request.pings {
+        if sequence > 0 {
+            sleep(PING_INTERVAL).await;
+        }
+        let probe_session = Arc::clone(&session);
+        let result = tokio::task::spawn_blocking(move || {
+            super::icmp::echo_once_from(&probe_session, source, destination, 128, timeout_ms, 32)
+        })
+        .await
+        .map_err(|error| format!("ICMP probe task failed: {error}"))?;
+        if result.status == u32::MAX {
+            return Err("source-bound ICMP is unsupported or permission was denied".into());
+        }
+        replies.push(
+            result
+                .reached()
+                .then_some(result.rtt_ms)
+                .flatten()
+                .map(|ms| ms as f64),
+        );
+    }
+    Ok(replies)
+}
+
+/// 从响应头到达起计时，读满 `window` 或数据读完为止，返回字节/秒。
+async fn download_rate(source: Ipv4Addr, url: &str, window: Duration) -> Result<f64, String> {
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .local_address(IpAddr::V4(source))
+        .build()
+        .map_err(|error| error.to_string())?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|error| error.to_string())?;
+    let started = Instant::now();
+    let deadline = started + window;
+    let mut bytes = 0u64;
+    loop {
+        match timeout_at(deadline, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => bytes += chunk.len() as u64,
+            Ok(Ok(None)) | Err(_) => break,
+            Ok(Err(error)) => return Err(error.to_string()),
+        }
+    }
+    if bytes == 0 {
+        return Err("no data received".into());
+    }
+    Ok(bytes as f64 / started.elapsed().as_secs_f64().max(0.001))
+}
+
+/// 每个名字查询一次，返回得到应答的查询耗时（毫秒）；全部超时视为失败。
+async fn dns_lookups(
+    source: Ipv4Addr,
+    server: SocketAddr,
+    names: &[&str],
+) -> Result<Vec<f64>, String> {
+    let socket = UdpSocket::bind((source, 0))
+        .await
+        .map_err(|error| error.to_string())?;
+    socket
+        .connect(server)
+        .await
+        .map_err(|error| error.to_string())?;
+    let mut lookups = Vec::with_capacity(names.len());
+    for name in names {
+        let started = Instant::now();
+        if udp_exchange(&socket, name, TYPE_A, DNS_TIMEOUT)
+            .await
+            .is_some_and(|response| response.answered())
+        {
+            lookups.push(started.elapsed().as_secs_f64() * 1_000.0);
+        }
+    }
+    if lookups.is_empty() {
+        return Err(format!("{server} did not answer"));
+    }
+    Ok(lookups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn download_and_lookups_leave_from_the_bound_address() {
+        let loopback = Ipv4Addr::LOCALHOST;
+        let listener = tokio::net::TcpListener::bind((loopback, 0)).await.unwrap();
+        let http = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, peer) = listener.accept().await.unwrap();
+            assert_eq!(peer.ip(), IpAddr::V4(loopback));
+            let mut request = [0u8; 1_024];
+            let _ = stream.read(&mut request).await;
+            let body = vec![0u8; 64 * 1_024];
+            let header = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).await.unwrap();
+            stream.write_all(&body).await.unwrap();
+        });
+        let rate = download_rate(loopback, &format!("http://{http}/"), Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert!(rate > 0.0);
+
+        // 只把查询原样回送并置上应答位：NOERROR、无记录
+        let resolver = UdpSocket::bind((loopback, 0)).await.unwrap();
+        let server = resolver.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 512];
+            while let Ok((length, peer)) = resolver.recv_from(&mut buffer).await {
+                buffer[2] |= 0x80;
+                let _ = resolver.send_to(&buffer[..length], peer).await;
+            }
+        });
+        let lookups = dns_lookups(loopback, server, &["example.com", "example.org"])
+            .await
+            .unwrap();
+        assert_eq!(lookups.len(), 2);
+
+        // 不存在的源地址无法绑定
+        let unassigned = Ipv4Addr::new(203, 0, 113, 9);
+        assert!(
+            dns_lookups(unassigned, server, &["example.com"])
+                .await
+                .is_err()
+        );
+    }
+}
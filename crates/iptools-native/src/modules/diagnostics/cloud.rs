@@ -0,0 +1,119 @@
+//! 云服务可达性矩阵：并发向各云区域 API、主要 CDN 与任播 DNS 发起 TCP 连接，
+//! 记录每个端点的连接耗时或失败原因，由核心层判断是本地故障还是服务商故障。
+
+use std::net::SocketAddr;
+
+use futures::{StreamExt, stream};
+use iptools_core::{
+    CloudEndpoint, CloudProbe, CloudRequest, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent,
+    cloud_endpoints,
+};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, timeout};
+use tokio_util::sync::CancellationToken;
+
+/// 同时探测的端点数
+const PARALLEL_ENDPOINTS: usize = 8;
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: CloudRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    if request.attempts == 0 {
+        return send(RuntimeEvent::CloudFailed {
+            job,
+            error: RuntimeError::new(
+                RuntimeErrorCode::InvalidRequest,
+                "attempts must be at least 1",
+            ),
+        })
+        .await;
+    }
+    let endpoints = cloud_endpoints();
+    send(RuntimeEvent::CloudStarted {
+        job,
+        endpoints: endpoints.len() as u32,
+    })
+    .await?;
+
+    let budget = Duration::from_millis(request.timeout_ms.clamp(100, 10_000));
+    let mut probes = stream::iter(endpoints)
+        .map(|endpoint| probe(endpoint, request.attempts, budget))
+        .buffer_unordered(PARALLEL_ENDPOINTS);
+    loop {
+        let probe = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            probe = probes.next() => probe,
+        };
+        match probe {
+            Some(probe) => send(RuntimeEvent::CloudResult { job, probe }).await?,
+            None => break,
+        }
+    }
+    send(RuntimeEvent::CloudFinished { job }).await
+}
+
+/// 解析失败时每次尝试都记为同一个解析错误。
+async fn probe(endpoint: CloudEndpoint, attempts: u32, budget: Duration) -> CloudProbe {
+    let address = match super::resolve_target(&endpoint.host).await {
+        Ok(addresses) => match addresses.first() {
+            Some(&ip) => SocketAddr::new(ip, endpoint.port),
+            None => {
+                let error = format!("{} has no address", endpoint.host);
+                return CloudProbe::from_attempts(endpoint, &[Err(error)]);
+            }
+        },
+        Err(error) => return CloudProbe::from_attempts(endpoint, &[Err(error.message)]),
+    };
+    let results = connect_attempts(address, attempts, budget).await;
+    CloudProbe::from_attempts(endpoint, &results)
+}
+
+async fn connect_attempts(
+    address: SocketAddr,
+    attempts: u32,
+    budget: Duration,
+) -> Vec<Result<f64, String>> {
+    let mut results = Vec::with_capacity(attempts as usize);
+    for _ in 0..attempts {
+        let started = Instant::now();
+        results.push(match timeout(budget, TcpStream::connect(address)).await {
+            Ok(Ok(_)) => Ok(started.elapsed().as_secs_f64() * 1_000.0),
+            Ok(Err(error)) => Err(error.to_string()),
+            Err(_) => Err("connection timed out".into()),
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn connects_are_timed_and_refusals_recorded() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        tokio::spawn(async move { while listener.accept().await.is_ok() {} });
+        let results = connect_attempts(open, 3, Duration::from_millis(500)).await;
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+
+        // 监听后立即关闭的端口：连接被拒绝
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let results = connect_attempts(closed, 1, Duration::from_millis(500)).await;
+        assert!(results[0].is_err(), "{results:?}");
+    }
+}
@@ -0,0 +1,296 @@
+//! Native DNS resolver benchmark for the structured runtime.
+//!
+//! 逐个解析器顺序测量，避免彼此争用带宽：先对热门名字做一次不计时的预热，
+//! 再按“缓存 / 未缓存 / 常见站点”交替计时查询。系统解析器走 getaddrinfo，
+//! 其余直接向 UDP 53 发原始查询；DoH 以 POST 发送同样的报文，DoT 在一条
+//! 复用的 TLS 连接上按两字节长度前缀分帧。加密连接在预热阶段建立，计时不含握手。
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use iptools_core::{
+    DNS_WARMUP_NAMES, DnsResolverScore, DnsResolverSpec, DnsTransport, RuntimeError,
+    RuntimeErrorCode, RuntimeEvent,
+};
+use rustls_platform_verifier::BuilderVerifierExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{self, ClientConfig, pki_types::ServerName};
+use tokio_util::sync::CancellationToken;
+
+use super::dns_wire::{
+    TYPE_A, connect_udp, encode_query, https_exchange, parse_response, query_id, udp_exchange,
+};
+
+const DNS_PORT: u16 = 53;
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::DnsBenchRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let resolvers = request.resolvers();
+    for resolver in &resolvers {
+        let problem = match resolver.transport {
+            DnsTransport::System => None,
+            DnsTransport::Udp => server_address(&resolver.address)
+                .is_none()
+                .then_some("is not a resolver IP address"),
+            DnsTransport::Https => reqwest::Url::parse(&resolver.address)
+                .ok()
+                .filter(|url| url.scheme() == "https" && url.host().is_some())
+                .is_none()
+                .then_some("is not an https:// DoH URL"),
+            DnsTransport::Tls => tls_server_name(&resolver.address)
+                .is_none()
+                .then_some("is not a DoT host:port"),
+        };
+        if let Some(problem) = problem {
+            return send(RuntimeEvent::DnsBenchFailed {
+                job,
+                error: RuntimeError::new(
+                    RuntimeErrorCode::InvalidRequest,
+                    format!("{} {problem}", resolver.address),
+                ),
+            })
+            .await;
+        }
+    }
+    let clients = match Clients::new() {
+        Ok(clients) => clients,
+        Err(error) => {
+            return send(RuntimeEvent::DnsBenchFailed {
+                job,
+                error: RuntimeError::new(RuntimeErrorCode::Internal, error),
+            })
+            .await;
+        }
+    };
+    send(RuntimeEvent::DnsBenchStarted {
+        job,
+        resolvers: resolvers.len() as u32,
+    })
+    .await?;
+
+    let timeout = Duration::from_millis(request.timeout_ms.clamp(100, 10_000));
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    for (index, resolver) in resolvers.into_iter().enumerate() {
+        let queries = request.queries(seed.wrapping_add(index as u64));
+        let score = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            score = benchmark(resolver, &queries, timeout, &clients) => score,
+        };
+        send(RuntimeEvent::DnsBenchScore { job, score }).await?;
+    }
+    send(RuntimeEvent::DnsBenchFinished { job }).await
+}
+
+/// 整轮测速共用的加密客户端：DoH 的 HTTP 连接池与 DoT 的 TLS 配置。
+struct Clients {
+    https: reqwest::Client,
+    tls: TlsConnector,
+}
+
+impl Clients {
+    fn new() -> Result<Self, String> {
+        let https = reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .map_err(|error| error.to_string())?;
+        let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .and_then(|builder| builder.with_platform_verifier())
+            .map_err(|error| error.to_string())?
+            .with_no_client_auth();
+        Ok(Self {
+            https,
+            tls: TlsConnector::from(Arc::new(config)),
+        })
+    }
+}
+
+async fn benchmark(
+    resolver: DnsResolverSpec,
+    queries: &[(iptools_core::DnsQueryKind, String)],
+    timeout: Duration,
+    clients: &Clients,
+) -> DnsResolverScore {
+    let mut samples = Vec::with_capacity(queries.len());
+    match resolver.transport {
+        DnsTransport::System => {
+            for name in DNS_WARMUP_NAMES {
+                system_lookup(name.into(), timeout).await;
+            }
+            for (kind, name) in queries {
+                samples.push((*kind, system_lookup(name.clone(), timeout).await));
+            }
+        }
+        DnsTransport::Udp => {
+            let server = server_address(&resolver.address).expect("validated before the run");
+            let socket = connect_udp(server).await.ok();
+            if let Some(socket) = &socket {
+                for name in DNS_WARMUP_NAMES {
+                    udp_lookup(socket, name, timeout).await;
+                }
+            }
+            for (kind, name) in queries {
+                let latency = match &socket {
+                    Some(socket) => udp_lookup(socket, name, timeout).await,
+                    None => None,
+                };
+                samples.push((*kind, latency));
+            }
+        }
+        DnsTransport::Https => {
+            for name in DNS_WARMUP_NAMES {
+                https_lookup(&clients.https, &resolver.address, name, timeout).await;
+            }
+            for (kind, name) in queries {
+                let latency = https_lookup(&clients.https, &resolver.address, name, timeout).await;
+                samples.push((*kind, latency));
+            }
+        }
+        DnsTransport::Tls => {
+            let mut connection = DotConnection::new(&clients.tls, &resolver.address, timeout);
+            for name in DNS_WARMUP_NAMES {
+                connection.lookup(name).await;
+            }
+            for (kind, name) in queries {
+                samples.push((*kind, connection.lookup(name).await));
+            }
+        }
+    }
+    DnsResolverScore::from_samples(resolver, &samples)
+}
+
+fn server_address(address: &str) -> Option<SocketAddr> {
+    address
+        .parse::<SocketAddr>()
+        .ok()
+        .or_else(|| Some(SocketAddr::new(address.parse().ok()?, DNS_PORT)))
+}
+
+/// 返回毫秒延迟；超时、网络错误或 SERVFAIL/REFUSED 记为失败。
+async fn udp_lookup(socket: &UdpSocket, name: &str, timeout: Duration) -> Option<f64> {
+    let started = Instant::now();
+    udp_exchange(socket, name, TYPE_A, timeout)
+        .await?
+        .answered()
+        .then(|| started.elapsed().as_secs_f64() * 1_000.0)
+}
+
+async fn https_lookup(
+    client: &reqwest::Client,
+    url: &str,
+    name: &str,
+    timeout: Duration,
+) -> Option<f64> {
+    let started = Instant::now();
+    https_exchange(client, url, name, TYPE_A, timeout)
+        .await?
+        .answered()
+        .then(|| started.elapsed().as_secs_f64() * 1_000.0)
+}
+
+fn tls_server_name(address: &str) -> Option<ServerName<'static>> {
+    let (host, port) = address.rsplit_once(':')?;
+    port.parse::<u16>().ok()?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    ServerName::try_from(host.to_string()).ok()
+}
+
+/// 复用的 DoT 连接；读写出错或超时后丢弃，下一次查询时重新连接。
+struct DotConnection<'a> {
+    connector: &'a TlsConnector,
+    address: &'a str,
+    timeout: Duration,
+    stream: Option<TlsStream<TcpStream>>,
+}
+
+impl<'a> DotConnection<'a> {
+    fn new(connector: &'a TlsConnector, address: &'a str, timeout: Duration) -> Self {
+        Self {
+            connector,
+            address,
+            timeout,
+            stream: None,
+        }
+    }
+
+    async fn connect(&self) -> Option<TlsStream<TcpStream>> {
+        let server_name = tls_server_name(self.address)?;
+        tokio::time::timeout(self.timeout, async {
+            let stream = TcpStream::connect(self.address).await.ok()?;
+            self.connector.connect(server_name, stream).await.ok()
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    async fn lookup(&mut self, name: &str) -> Option<f64> {
+        if self.stream.is_none() {
+            self.stream = self.connect().await;
+        }
+        let stream = self.stream.as_mut()?;
+        let id = query_id();
+        let query = encode_query(id, name, TYPE_A)?;
+        let mut framed = Vec::with_capacity(2 + query.len());
+        framed.extend_from_slice(&(query.len() as u16).to_be_bytes());
+        framed.extend_from_slice(&query);
+        let started = Instant::now();
+        let exchange = async {
+            stream.write_all(&framed).await.ok()?;
+            loop {
+                let mut length = [0u8; 2];
+                stream.read_exact(&mut length).await.ok()?;
+                let mut message = vec![0u8; usize::from(u16::from_be_bytes(length))];
+                stream.read_exact(&mut message).await.ok()?;
+                if let Some(response) = parse_response(&message, id) {
+                    return Some(response.answered());
+                }
+            }
+        };
+        match tokio::time::timeout(self.timeout, exchange).await {
+            Ok(Some(answered)) => answered.then(|| started.elapsed().as_secs_f64() * 1_000.0),
+            // 连接状态未知（可能还有未读完的应答），换一条新连接
+            _ => {
+                self.stream = None;
+                None
+            }
+        }
+    }
+}
+
+/// getaddrinfo 无法取消：超时后阻塞线程自行结束，本次记为失败。
+async fn system_lookup(name: String, timeout: Duration) -> Option<f64> {
+    let started = Instant::now();
+    let lookup = tokio::task::spawn_blocking(move || {
+        match dns_lookup::getaddrinfo(Some(&name), None, None) {
+            Ok(_) => true,
+            // 不存在的名字（NXDOMAIN / NODATA）同样是完整的应答
+            Err(error) => matches!(
+                error.kind(),
+                dns_lookup::LookupErrorKind::NoName | dns_lookup::LookupErrorKind::NoData
+            ),
+        }
+    });
+    match tokio::time::timeout(timeout, lookup).await {
+        Ok(Ok(true)) => Some(started.elapsed().as_secs_f64() * 1_000.0),
+        _ => None,
+    }
+}
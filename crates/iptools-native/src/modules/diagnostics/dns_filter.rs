@@ -0,0 +1,193 @@
+//! Native DNS filtering detector for the structured runtime.
+//!
+//! 先经 Cloudflare DoH 取得不会被中途改写的参考应答，再依次经系统解析器、
+//! 默认网关与 1.1.1.1 的 UDP 53 解析同一组广告 / 追踪域名，交由核心逐项判定
+//! 并找出实际做过滤的那一级上游。
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use ipnetwork::Ipv4Network;
+use iptools_core::{
+    DnsAnswer, DnsFilterUpstream, RuntimeError, RuntimeErrorCode, RuntimeEvent, classify_dns_filter,
+};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::dns_wire::{
+    DnsResponse, RCODE_NOERROR, RCODE_NXDOMAIN, TYPE_A, connect_udp, encode_query, https_exchange,
+    udp_exchange,
+};
+use crate::utils::net;
+
+const REFERENCE_DOH: &str = "https://cloudflare-dns.com/dns-query";
+const PUBLIC_RESOLVER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53);
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::DnsFilterRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let domains = request.domains();
+    if let Some(domain) = domains
+        .iter()
+        .find(|domain| encode_query(0, domain, TYPE_A).is_none())
+    {
+        return send(RuntimeEvent::DnsFilterFailed {
+            job,
+            error: RuntimeError::new(
+                RuntimeErrorCode::InvalidRequest,
+                format!("{domain} is not a valid domain name"),
+            ),
+        })
+        .await;
+    }
+    let gateway = default_gateway().await;
+    let upstreams = [
+        Some(DnsFilterUpstream::System),
+        gateway.map(|_| DnsFilterUpstream::Gateway),
+        Some(DnsFilterUpstream::Public),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    send(RuntimeEvent::DnsFilterStarted {
+        job,
+        upstreams: upstreams.clone(),
+        domains: domains.len() as u32,
+    })
+    .await?;
+
+    let timeout = Duration::from_millis(request.timeout_ms.clamp(100, 10_000));
+    tokio::select! {
+        _ = cancellation.cancelled() => Ok(()),
+        result = detect(job, &domains, &upstreams, gateway, timeout, &events) => result,
+    }
+}
+
+async fn detect(
+    job: iptools_core::JobId,
+    domains: &[String],
+    upstreams: &[DnsFilterUpstream],
+    gateway: Option<Ipv4Addr>,
+    timeout: Duration,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    // 参考解析器不可达时留空，核心仍能凭 NXDOMAIN 与黑洞地址判定
+    let mut reference = Vec::new();
+    if let Ok(client) = reqwest::Client::builder().no_proxy().build() {
+        for domain in domains {
+            let response = https_exchange(&client, REFERENCE_DOH, domain, TYPE_A, timeout).await;
+            reference.push((domain.clone(), answer(response)));
+        }
+    }
+
+    for upstream in upstreams {
+        let mut answers = Vec::with_capacity(domains.len());
+        match upstream {
+            DnsFilterUpstream::System => {
+                for domain in domains {
+                    answers.push((domain.clone(), system_answer(domain.clone(), timeout).await));
+                }
+            }
+            DnsFilterUpstream::Gateway | DnsFilterUpstream::Public => {
+                let server = match (upstream, gateway) {
+                    (DnsFilterUpstream::Gateway, Some(gateway)) => {
+                        SocketAddr::new(gateway.into(), 53)
+                    }
+                    _ => PUBLIC_RESOLVER,
+                };
+                let socket = connect_udp(server).await.ok();
+                for domain in domains {
+                    answers.push((
+                        domain.clone(),
+                        udp_answer(socket.as_ref(), domain, timeout).await,
+                    ));
+                }
+            }
+        }
+        for probe in classify_dns_filter(*upstream, &answers, &reference) {
+            events
+                .send(RuntimeEvent::DnsFilterResult { job, probe })
+                .await
+                .map_err(|error| error.to_string())?;
+        }
+    }
+    events
+        .send(RuntimeEvent::DnsFilterFinished { job })
+        .await
+        .map_err(|error| error.to_string())
+}
+
+/// SERVFAIL / REFUSED 与超时一样视为没有应答。
+fn answer(response: Option<DnsResponse>) -> DnsAnswer {
+    match response {
+        Some(response) if response.rcode == RCODE_NXDOMAIN => DnsAnswer::NxDomain,
+        Some(response) if response.rcode == RCODE_NOERROR => {
+            DnsAnswer::Addresses(response.addresses)
+        }
+        _ => DnsAnswer::NoAnswer,
+    }
+}
+
+async fn udp_answer(socket: Option<&UdpSocket>, domain: &str, timeout: Duration) -> DnsAnswer {
+    match socket {
+        Some(socket) => answer(udp_exchange(socket, domain, TYPE_A, timeout).await),
+        None => DnsAnswer::NoAnswer,
+    }
+}
+
+/// 只取 IPv4 结果，与参考及 UDP 的 A 查询对齐。
+async fn system_answer(domain: String, timeout: Duration) -> DnsAnswer {
+    let lookup = tokio::task::spawn_blocking(move || {
+        match dns_lookup::getaddrinfo(Some(&domain), None, None) {
+            Ok(addresses) => {
+                let mut ipv4 = Vec::new();
+                for address in addresses.flatten() {
+                    let ip = address.sockaddr.ip();
+                    if ip.is_ipv4() && !ipv4.contains(&ip) {
+                        ipv4.push(ip);
+                    }
+                }
+                DnsAnswer::Addresses(ipv4)
+            }
+            Err(error) => match error.kind() {
+                dns_lookup::LookupErrorKind::NoName => DnsAnswer::NxDomain,
+                dns_lookup::LookupErrorKind::NoData => DnsAnswer::Addresses(Vec::new()),
+                _ => DnsAnswer::NoAnswer,
+            },
+        }
+    });
+    match tokio::time::timeout(timeout, lookup).await {
+        Ok(Ok(answer)) => answer,
+        _ => DnsAnswer::NoAnswer,
+    }
+}
+
+/// 出口网卡的默认网关，见 [`net::default_gateway`]。
+async fn default_gateway() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.ok()?;
+    socket.connect(PUBLIC_RESOLVER).await.ok()?;
+    let IpAddr::V4(source) = socket.local_addr().ok()?.ip() else {
+        return None;
+    };
+    let interface = net::interfaces()
+        .await
+        .ok()?
+        .into_iter()
+        .find(|interface| {
+            interface
+                .cidr
+                .as_deref()
+                .and_then(|cidr| cidr.parse::<Ipv4Network>().ok())
+                .is_some_and(|network| network.contains(source))
+        })?;
+    net::gateway(interface).await
+}
@@ -0,0 +1,203 @@
+//! Minimal DNS wire format shared by the DNS diagnostics.
+//!
+//! 只实现诊断需要的部分：单问题查询的编码，以及应答头、RCODE 与 A/AAAA
+//! 记录的解析（支持名字压缩指针）；另有经 UDP 与 DoH 完成一次问答的封装。
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::net::UdpSocket;
+
+pub(crate) const TYPE_A: u16 = 1;
+pub(crate) const TYPE_AAAA: u16 = 28;
+pub(crate) const RCODE_NOERROR: u8 = 0;
+pub(crate) const RCODE_NXDOMAIN: u8 = 3;
+const CLASS_IN: u16 = 1;
+/// 标准查询，期望递归（RD）
+const FLAGS_RECURSION_DESIRED: u16 = 0x0100;
+/// RFC 8484 规定的 DoH 报文媒体类型
+const DNS_MESSAGE: &str = "application/dns-message";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DnsResponse {
+    pub rcode: u8,
+    pub addresses: Vec<IpAddr>,
+}
+
+impl DnsResponse {
+    /// NOERROR 与 NXDOMAIN 都说明解析器完整地回答了问题。
+    pub(crate) fn answered(&self) -> bool {
+        matches!(self.rcode, RCODE_NOERROR | RCODE_NXDOMAIN)
+    }
+}
+
+pub(crate) fn query_id() -> u16 {
+    static COUNTER: AtomicU16 = AtomicU16::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    (nanos as u16 ^ (nanos >> 16) as u16).wrapping_add(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// 名字不合法（空标签或超长）时返回 None。
+pub(crate) fn encode_query(id: u16, name: &str, qtype: u16) -> Option<Vec<u8>> {
+    let name = name.trim_end_matches('.');
+    if name.is_empty() || name.len() > 253 {
+        return None;
+    }
+    let mut packet = Vec::with_capacity(18 + name.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&FLAGS_RECURSION_DESIRED.to_be_bytes());
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&qtype.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Some(packet)
+}
+
+/// 事务号不符或不是应答时返回 None，调用方应继续等待。
+pub(crate) fn parse_response(packet: &[u8], id: u16) -> Option<DnsResponse> {
+    let header = packet.get(..12)?;
+    if u16::from_be_bytes([header[0], header[1]]) != id || header[2] & 0x80 == 0 {
+        return None;
+    }
+    let rcode = header[3] & 0x0F;
+    let questions = u16::from_be_bytes([header[4], header[5]]);
+    let answers = u16::from_be_bytes([header[6], header[7]]);
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = skip_name(packet, offset)? + 4;
+    }
+    let mut addresses = Vec::new();
+    for _ in 0..answers {
+        offset = skip_name(packet, offset)?;
+        let fixed = packet.get(offset..offset + 10)?;
+        let kind = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let length = usize::from(u16::from_be_bytes([fixed[8], fixed[9]]));
+        let data = packet.get(offset + 10..offset + 10 + length)?;
+        match (kind, data.len()) {
+            (TYPE_A, 4) => addresses.push(Ipv4Addr::new(data[0], data[1], data[2], data[3]).into()),
+            (TYPE_AAAA, 16) => {
+                let octets: [u8; 16] = data.try_into().ok()?;
+                addresses.push(Ipv6Addr::from(octets).into());
+            }
+            _ => {}
+        }
+        offset += 10 + length;
+    }
+    Some(DnsResponse { rcode, addresses })
+}
+
+pub(crate) async fn connect_udp(server: SocketAddr) -> std::io::Result<UdpSocket> {
+    let unspecified: IpAddr = if server.is_ipv4() {
+        Ipv4Addr::UNSPECIFIED.into()
+    } else {
+        Ipv6Addr::UNSPECIFIED.into()
+    };
+    let socket = UdpSocket::bind((unspecified, 0)).await?;
+    socket.connect(server).await?;
+    Ok(socket)
+}
+
+/// 在已 connect 的套接字上问答一次；超时或网络错误返回 None。
+pub(crate) async fn udp_exchange(
+    socket: &UdpSocket,
+    name: &str,
+    qtype: u16,
+    timeout: Duration,
+) -> Option<DnsResponse> {
+    let id = query_id();
+    let query = encode_query(id, name, qtype)?;
+    socket.send(&query).await.ok()?;
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut buffer = [0u8; 1_500];
+    loop {
+        let length = tokio::time::timeout_at(deadline, socket.recv(&mut buffer))
+            .await
+            .ok()?
+            .ok()?;
+        // 之前超时查询的迟到应答事务号不同，丢弃后继续等
+        if let Some(response) = parse_response(&buffer[..length], id) {
+            return Some(response);
+        }
+    }
+}
+
+/// DoH 报文事务号固定为 0，便于 HTTP 缓存（RFC 8484 §4.1）；HTTP 错误返回 None。
+pub(crate) async fn https_exchange(
+    client: &reqwest::Client,
+    url: &str,
+    name: &str,
+    qtype: u16,
+    timeout: Duration,
+) -> Option<DnsResponse> {
+    let query = encode_query(0, name, qtype)?;
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, DNS_MESSAGE)
+        .header(reqwest::header::ACCEPT, DNS_MESSAGE)
+        .timeout(timeout)
+        .body(query)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    let body = response.bytes().await.ok()?;
+    parse_response(&body, 0)
+}
+
+fn skip_name(packet: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let length = *packet.get(offset)?;
+        match length {
+            0 => return Some(offset + 1),
+            // 压缩指针占两个字节，名字到此结束
+            length if length & 0xC0 == 0xC0 => return Some(offset + 2),
+            length => offset += 1 + usize::from(length),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_round_trips_through_a_compressed_answer() {
+        let query = encode_query(0x1234, "www.example.com.", TYPE_A).unwrap();
+        assert_eq!(query.len(), 12 + 17 + 4);
+        assert_eq!(&query[12..16], b"\x03www");
+        assert_eq!(encode_query(1, "bad..name", TYPE_A), None);
+
+        let mut response = query.clone();
+        response[2] |= 0x80;
+        response[7] = 2;
+        // 两条答案都用指针 0xC00C 指回问题中的名字
+        response.extend_from_slice(&[0xC0, 0x0C, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+        response.extend_from_slice(&[0xC0, 0x0C, 0, 28, 0, 1, 0, 0, 0, 60, 0, 16]);
+        response.extend_from_slice(&[
+            0x26, 0x06, 0x28, 0, 0x02, 0x20, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0x01,
+        ]);
+        let parsed = parse_response(&response, 0x1234).unwrap();
+        assert!(parsed.answered());
+        assert_eq!(
+            parsed.addresses,
+            [
+                "93.184.216.34".parse::<IpAddr>().unwrap(),
+                "2606:2800:220:1::1".parse().unwrap()
+            ]
+        );
+        assert_eq!(parse_response(&response, 0x4321), None);
+        assert_eq!(parse_response(&query, 0x1234), None);
+    }
+}
@@ -0,0 +1,104 @@
+//! Native QoS / DSCP marking check for the structured runtime.
+//!
+//! 逐跳递增 TTL 发送带 DSCP 标记的 Echo：中间路由器的 Time Exceeded 引用了
+//! 它收到的原始 IP 头，对端的 Echo Reply 多数会反射收到的标记，由此定位
+//! 标记在哪一跳被清除或改写。
+
+use std::sync::Arc;
+
+use iptools_core::{
+    DscpHop, DscpSummary, RuntimeError, RuntimeErrorCode, RuntimeEvent, dscp_from_tos,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::icmp::{IcmpSession, MarkProbe, probe_marking};
+use super::mtu::resolve_ipv4;
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::DscpRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+    let fail = |code, message: String| RuntimeEvent::DscpFailed {
+        job,
+        error: RuntimeError::new(code, message),
+    };
+
+    let target = request.target.trim();
+    if target.is_empty() {
+        return send(fail(
+            RuntimeErrorCode::InvalidRequest,
+            "target cannot be empty".into(),
+        ))
+        .await;
+    }
+    let dest = match resolve_ipv4(target, "the DSCP check needs an IPv4 target").await {
+        Ok(dest) => dest,
+        Err(message) => return send(fail(RuntimeErrorCode::ResolveTarget, message)).await,
+    };
+    send(RuntimeEvent::DscpStarted { job }).await?;
+
+    let session = match IcmpSession::open() {
+        Ok(session) => Arc::new(session),
+        Err(message) => return send(fail(RuntimeErrorCode::Internal, message)).await,
+    };
+    let timeout = request.timeout_ms.clamp(100, 10_000) as u32;
+    let dscp = request.dscp.min(63);
+    let mut hops = Vec::new();
+    for ttl in 1..=request.max_hops.clamp(1, 64) {
+        let session = session.clone();
+        let result = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            result = tokio::task::spawn_blocking(move || probe_marking(&session, dest, ttl, dscp, timeout)) => result,
+        };
+        let hop = match result {
+            Ok(Ok(MarkProbe::Reply {
+                from,
+                rtt_ms,
+                reached,
+                tos,
+            })) => DscpHop {
+                ttl,
+                address: Some(from.to_string()),
+                rtt_ms: Some(rtt_ms),
+                observed: tos.map(dscp_from_tos),
+                reached,
+            },
+            Ok(Ok(MarkProbe::Lost)) => DscpHop {
+                ttl,
+                address: None,
+                rtt_ms: None,
+                observed: None,
+                reached: false,
+            },
+            Ok(Err(message)) => {
+                let code = if message.contains("Operation not permitted") {
+                    RuntimeErrorCode::PermissionDenied
+                } else {
+                    RuntimeErrorCode::Network
+                };
+                return send(fail(code, message)).await;
+            }
+            Err(error) => {
+                return send(fail(RuntimeErrorCode::Internal, error.to_string())).await;
+            }
+        };
+        let reached = hop.reached;
+        hops.push(hop.clone());
+        send(RuntimeEvent::DscpHop { job, hop }).await?;
+        if reached {
+            break;
+        }
+    }
+    send(RuntimeEvent::DscpFinished {
+        job,
+        summary: DscpSummary::from_hops(dscp, &hops),
+    })
+    .await
+}
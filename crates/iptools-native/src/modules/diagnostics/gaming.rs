@@ -0,0 +1,191 @@
+//! 游戏延迟测试：对预设地区的 GameLift UDP 信标与自定义服务器逐个发送一串小探测包，
+//! 统计中位延迟、抖动与丢包，交给核心层排名。
+//!
+//! 各地区并发测试，但同一地区内的探测按固定间隔依次发送，模拟游戏的持续小包流量，
+//! 而不是一次性突发。
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use futures::{StreamExt, stream};
+use iptools_core::{
+    GamingEndpoint, GamingProtocol, GamingRequest, GamingScore, JobId, RuntimeError,
+    RuntimeErrorCode, RuntimeEvent,
+};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, timeout, timeout_at};
+use tokio_util::sync::CancellationToken;
+
+/// 同时测试的地区数
+const PARALLEL_REGIONS: usize = 8;
+/// 同一地区两次探测之间的间隔
+const PROBE_INTERVAL: Duration = Duration::from_millis(50);
+/// 探测包开头的标记，后跟 4 字节序号，用于把回包与探测对应起来
+const PROBE_MAGIC: &[u8; 8] = b"iptools ";
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: GamingRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let endpoints = match request.endpoints() {
+        Ok(endpoints) if !endpoints.is_empty() => endpoints,
+        Ok(_) => {
+            return send(RuntimeEvent::GamingFailed {
+                job,
+                error: RuntimeError::new(
+                    RuntimeErrorCode::InvalidRequest,
+                    "no regions selected and no custom servers given",
+                ),
+            })
+            .await;
+        }
+        Err(error) => {
+            return send(RuntimeEvent::GamingFailed {
+                job,
+                error: RuntimeError::new(RuntimeErrorCode::InvalidRequest, error),
+            })
+            .await;
+        }
+    };
+    send(RuntimeEvent::GamingStarted {
+        job,
+        endpoints: endpoints.len() as u32,
+    })
+    .await?;
+
+    let budget = Duration::from_millis(request.timeout_ms.clamp(100, 10_000));
+    let rounds = request.rounds.max(1);
+    let mut scores = stream::iter(endpoints)
+        .map(|endpoint| score(endpoint, rounds, budget))
+        .buffer_unordered(PARALLEL_REGIONS);
+    loop {
+        let score = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            score = scores.next() => score,
+        };
+        match score {
+            Some(score) => send(RuntimeEvent::GamingScore { job, score }).await?,
+            None => break,
+        }
+    }
+    send(RuntimeEvent::GamingFinished { job }).await
+}
+
+/// 解析失败或无法建立套接字时，该地区记为全部丢失。
+async fn score(endpoint: GamingEndpoint, rounds: u32, budget: Duration) -> GamingScore {
+    let lost = vec![None; rounds as usize];
+    let Ok(addresses) = super::resolve_target(&endpoint.host).await else {
+        return GamingScore::from_samples(endpoint, &lost);
+    };
+    let Some(&ip) = addresses.first() else {
+        return GamingScore::from_samples(endpoint, &lost);
+    };
+    let address = SocketAddr::new(ip, endpoint.port);
+    let samples = match endpoint.protocol {
+        GamingProtocol::Udp => match udp_samples(address, rounds, budget).await {
+            Ok(samples) => samples,
+            Err(_) => lost,
+        },
+        GamingProtocol::Tcp => tcp_samples(address, rounds, budget).await,
+    };
+    GamingScore::from_samples(endpoint, &samples)
+}
+
+async fn udp_samples(
+    address: SocketAddr,
+    rounds: u32,
+    budget: Duration,
+) -> std::io::Result<Vec<Option<f64>>> {
+    let local = if address.is_ipv4() {
+        SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)
+    } else {
+        SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(address).await?;
+    let mut buffer = [0u8; 512];
+    let mut samples = Vec::with_capacity(rounds as usize);
+    for round in 0..rounds {
+        if round > 0 {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+        let mut probe = PROBE_MAGIC.to_vec();
+        probe.extend_from_slice(&round.to_be_bytes());
+        let sent = Instant::now();
+        if socket.send(&probe).await.is_err() {
+            samples.push(None);
+            continue;
+        }
+        let deadline = sent + budget;
+        // 迟到的上一轮回包直接丢弃，只认本轮序号
+        let answer = loop {
+            match timeout_at(deadline, socket.recv(&mut buffer)).await {
+                Ok(Ok(length)) if buffer[..length] == probe[..] => {
+                    break Some(sent.elapsed().as_secs_f64() * 1_000.0);
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(_)) | Err(_) => break None,
+            }
+        };
+        samples.push(answer);
+    }
+    Ok(samples)
+}
+
+async fn tcp_samples(address: SocketAddr, rounds: u32, budget: Duration) -> Vec<Option<f64>> {
+    let mut samples = Vec::with_capacity(rounds as usize);
+    for round in 0..rounds {
+        if round > 0 {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+        let started = Instant::now();
+        samples.push(
+            matches!(
+                timeout(budget, TcpStream::connect(address)).await,
+                Ok(Ok(_))
+            )
+            .then(|| started.elapsed().as_secs_f64() * 1_000.0),
+        );
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn udp_echoes_and_tcp_connects_are_timed() {
+        let echo = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let echo_address = echo.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 512];
+            while let Ok((length, from)) = echo.recv_from(&mut buffer).await {
+                let _ = echo.send_to(&buffer[..length], from).await;
+            }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let tcp_address = listener.local_addr().unwrap();
+        tokio::spawn(async move { while listener.accept().await.is_ok() {} });
+
+        let budget = Duration::from_millis(500);
+        let udp = udp_samples(echo_address, 3, budget).await.unwrap();
+        assert!(udp.iter().all(Option::is_some), "{udp:?}");
+        let tcp = tcp_samples(tcp_address, 2, budget).await;
+        assert!(tcp.iter().all(Option::is_some), "{tcp:?}");
+
+        // 没有回显服务的端口：探测全部超时
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let lost = udp_samples(silent.local_addr().unwrap(), 2, Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert_eq!(lost, [None, None]);
+    }
+}
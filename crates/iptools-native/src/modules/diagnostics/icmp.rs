@@ -1,10 +1,15 @@
-//! 共享的单次 ICMP Echo 原语，供 traceroute 与链路质量复用，
-//! 把 Windows 的 `unsafe` FFI 收敛到一处。
+//! 共享的单次 ICMP Echo 原语，供 Ping、traceroute 与链路质量复用，
+//! 把 Windows 的 `unsafe` FFI 与 unix 的回包分发收敛到一处。
 //!
-//! 注意：`ping.rs` 维持自己的连续发包循环（载荷大小可调、复用 handle），
-//! 与此处一次性 echo 的用途不同，未合并。
+//! unix 下所有探测的回包都由 `dispatch` 的一个接收套接字（每个协议族一个）分发，
+//! Ping 也不再另开套接字；Windows 的 Ping 仍有自己的 `IcmpSendEcho` 循环。
 
+use std::collections::BTreeSet;
 use std::net::Ipv4Addr;
+use std::sync::{
+    Mutex, PoisonError,
+    atomic::{AtomicU16, Ordering},
+};
 
 /// ICMP 状态码（IP_STATUS）。
 pub const IP_SUCCESS: u32 = 0;
@@ -29,9 +34,400 @@ impl EchoResult {
     }
 }
 
+/// ICMP identifier 的分配表：已占用的号与下一个候选号。
+///
+/// 进程内只有一份（[`SESSION_IDS`]）；测试用自己的一份，不与并行的测试互相影响。
+#[derive(Debug)]
+pub struct SessionIds {
+    taken: Mutex<BTreeSet<u16>>,
+    next: AtomicU16,
+}
+
+/// 进程内已占用的 ICMP identifier。
+static SESSION_IDS: SessionIds = SessionIds::new();
+
+impl SessionIds {
+    pub const fn new() -> Self {
+        Self {
+            taken: Mutex::new(BTreeSet::new()),
+            next: AtomicU16::new(0),
+        }
+    }
+
+    /// 取一个未占用的 identifier；65535 个全被占用时为 `None`。
+    fn allocate(&self) -> Option<u16> {
+        let mut taken = self.taken.lock().unwrap_or_else(PoisonError::into_inner);
+        // 以进程号为起点，降低与同机其它 ping 进程撞号的概率。
+        let _ = self.next.compare_exchange(
+            0,
+            (std::process::id() & 0xFFFF) as u16 | 1,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+        // 0 是"尚未取号"的标记，不分配；回绕后跳过它，最多看一圈。
+        (0..=u16::MAX).find_map(|_| {
+            let candidate = self.next.fetch_add(1, Ordering::Relaxed);
+            (candidate != 0 && taken.insert(candidate)).then_some(candidate)
+        })
+    }
+
+    fn release(&self, id: u16) {
+        self.taken
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+    }
+}
+
+/// 一个 ICMP 会话：进程内唯一的 identifier 与会话自己的序号。
+///
+/// 同时运行的 Ping、路由跟踪与链路质量各开一个会话；会话释放后 identifier
+/// 才会被复用。unix 下回包由 `dispatch` 按 (id, seq) 交给发出它的探测；
+/// Windows 的 `IcmpSendEcho` 由系统按句柄匹配回包，不需要 identifier。
+#[derive(Debug)]
+pub struct IcmpSession {
+    ids: &'static SessionIds,
+    id: u16,
+    seq: AtomicU16,
+}
+
+impl IcmpSession {
+    pub fn open() -> Result<Self, String> {
+        Self::open_in(&SESSION_IDS)
+    }
+
+    /// 从 `ids` 取号；全部占用时报错而不是一直等下去。
+    pub fn open_in(ids: &'static SessionIds) -> Result<Self, String> {
+        let id = ids
+            .allocate()
+            .ok_or_else(|| "every ICMP identifier is in use".to_string())?;
+        Ok(Self {
+            ids,
+            id,
+            seq: AtomicU16::new(0),
+        })
+    }
+
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn next_seq(&self) -> u16 {
+        self.seq.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+impl Drop for IcmpSession {
+    fn drop(&mut self) {
+        self.ids.release(self.id);
+    }
+}
+
+/// unix：进程内唯一的 ICMP 接收端，ICMPv4 与 ICMPv6 各一个套接字。
+///
+/// raw ICMP 套接字会收到本机全部 ICMP 流量。发送仍用每次探测各自的套接字
+/// （TTL、TOS、DF、IP 选项与源地址都是套接字级选项），回包只由这里的一个
+/// 套接字接收，后台线程按 (id, seq) 交给登记了它的探测；无人等待的回包直接丢弃。
+/// 接收持续出错时线程退避重试，仍不恢复就退出并让等待中的探测立即失败，
+/// 下一次登记时再重新打开套接字。
+#[cfg(unix)]
+mod dispatch {
+    use std::collections::BTreeMap;
+    use std::mem::MaybeUninit;
+    use std::net::Ipv4Addr;
+    use std::sync::{Mutex, PoisonError, mpsc};
+    use std::time::{Duration, Instant};
+
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    use super::unix_icmp;
+
+    /// 接收连续失败这么多次后退出，期间每次等待的时间翻倍（10 ms 起，共约 2.5 秒）。
+    const MAX_FAILURES: u32 = 8;
+    const FIRST_BACKOFF: Duration = Duration::from_millis(10);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Family {
+        V4,
+        V6,
+    }
+
+    impl Family {
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    /// 一份回包。ICMPv4 含 IP 头；ICMPv6 套接字不交付 IP 头，跳数限制单独取出。
+    pub struct Reply {
+        pub data: Vec<u8>,
+        pub from: Option<Ipv4Addr>,
+        pub hop_limit: Option<u8>,
+    }
+
+    /// 回包无法送达：接收线程出错退出，等待中的探测不必等到超时。
+    #[derive(Debug)]
+    pub struct ReceiverFailed;
+
+    impl std::fmt::Display for ReceiverFailed {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("ICMP receive socket failed")
+        }
+    }
+
+    type Waiters = BTreeMap<(u16, u16), (Family, mpsc::Sender<Reply>)>;
+
+    /// 登记中的探测与两个接收线程的状态。进程内只有一份（[`DISPATCHER`]）；
+    /// 测试用自己的一份，模拟接收出错时不影响并行的真实探测。
+    pub struct Dispatcher {
+        waiters: Mutex<Waiters>,
+        /// 两个接收线程各自是否在运行。
+        running: Mutex<[bool; 2]>,
+    }
+
+    static DISPATCHER: Dispatcher = Dispatcher::new();
+
+    impl Dispatcher {
+        pub const fn new() -> Self {
+            Self {
+                waiters: Mutex::new(BTreeMap::new()),
+                running: Mutex::new([false; 2]),
+            }
+        }
+
+        fn waiters(&self) -> std::sync::MutexGuard<'_, Waiters> {
+            self.waiters.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        /// 确保 `family` 的接收线程在运行；没有 raw 套接字权限时为 `false`。
+        fn started(&'static self, family: Family) -> bool {
+            let mut running = self.running.lock().unwrap_or_else(PoisonError::into_inner);
+            if running[family.index()] {
+                return true;
+            }
+            let socket = match family {
+                Family::V4 => Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4)),
+                Family::V6 => Socket::new(Domain::IPV6, Type::RAW, Some(Protocol::ICMPV6))
+                    .and_then(|socket| {
+                        enable_hop_limit(&socket)?;
+                        Ok(socket)
+                    }),
+            };
+            let Ok(socket) = socket else {
+                return false;
+            };
+            running[family.index()] = std::thread::Builder::new()
+                .name("icmp-dispatch".into())
+                .spawn(move || {
+                    // 巨帧回包可达 9000+ 字节。
+                    let mut buf = vec![MaybeUninit::<u8>::uninit(); 65_536];
+                    self.receive(family, || match family {
+                        Family::V4 => recv_v4(&socket, &mut buf),
+                        Family::V6 => recv_with_hop_limit(&socket, &mut buf),
+                    });
+                })
+                .is_ok();
+            running[family.index()]
+        }
+
+        /// 接收线程的主循环：把 `next` 收到的回包交给登记了它的探测，直到持续出错。
+        fn receive(&self, family: Family, mut next: impl FnMut() -> std::io::Result<Reply>) {
+            let mut failures = 0;
+            loop {
+                let reply = match next() {
+                    Ok(reply) => {
+                        failures = 0;
+                        reply
+                    }
+                    Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(error) => {
+                        failures += 1;
+                        if failures >= MAX_FAILURES {
+                            tracing::warn!(%error, ?family, "ICMP receive socket keeps failing; closing it");
+                            self.stop(family);
+                            return;
+                        }
+                        std::thread::sleep(FIRST_BACKOFF * 2_u32.pow(failures - 1));
+                        continue;
+                    }
+                };
+                let key = match family {
+                    Family::V4 => unix_icmp::reply_key(&reply.data),
+                    Family::V6 => unix_icmp::reply_key_v6(&reply.data),
+                };
+                let Some(key) = key else {
+                    continue;
+                };
+                let waiter = self
+                    .waiters()
+                    .get(&key)
+                    .filter(|(wanted, _)| *wanted == family)
+                    .map(|(_, waiter)| waiter.clone());
+                if let Some(waiter) = waiter {
+                    let _ = waiter.send(reply);
+                }
+            }
+        }
+
+        /// 接收线程退出：丢掉该协议族的全部等待者，它们的 `next` 随即返回错误。
+        fn stop(&self, family: Family) {
+            let mut running = self.running.lock().unwrap_or_else(PoisonError::into_inner);
+            running[family.index()] = false;
+            self.waiters().retain(|_, (wanted, _)| *wanted != family);
+        }
+
+        fn register(&'static self, family: Family, id: u16, seq: u16) -> Expected {
+            let (sender, replies) = mpsc::channel();
+            self.waiters().insert((id, seq), (family, sender));
+            Expected {
+                dispatcher: self,
+                key: (id, seq),
+                replies,
+            }
+        }
+    }
+
+    fn recv_v4(socket: &Socket, buf: &mut [MaybeUninit<u8>]) -> std::io::Result<Reply> {
+        let (n, from) = socket.recv_from(buf)?;
+        // SAFETY: recv_from 已初始化前 n 字节。
+        let data = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, n) };
+        Ok(Reply {
+            data: data.to_vec(),
+            from: from.as_socket_ipv4().map(|address| *address.ip()),
+            hop_limit: None,
+        })
+    }
+
+    fn enable_hop_limit(socket: &Socket) -> std::io::Result<()> {
+        use std::os::fd::AsRawFd;
+
+        let on: libc::c_int = 1;
+        // SAFETY: 套接字有效，选项值在调用期间存活。
+        let rc = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                libc::IPPROTO_IPV6,
+                libc::IPV6_RECVHOPLIMIT,
+                &on as *const _ as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+
+    /// 收一个 ICMPv6 报文，连同控制消息里的跳数限制。
+    fn recv_with_hop_limit(socket: &Socket, buf: &mut [MaybeUninit<u8>]) -> std::io::Result<Reply> {
+        use std::os::fd::AsRawFd;
+
+        let mut control = [0u64; 16];
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+        // SAFETY: msghdr 全零是合法的初值，其后只填入在调用期间存活的缓冲。
+        let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+        message.msg_iov = &mut iov;
+        message.msg_iovlen = 1;
+        message.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        message.msg_controllen = std::mem::size_of_val(&control) as _;
+        // SAFETY: 上面的缓冲都有效；recvmsg 只写入其中。
+        let n = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut message, 0) };
+        let n = usize::try_from(n).map_err(|_| std::io::Error::last_os_error())?;
+        let mut hop_limit = None;
+        // SAFETY: 按 CMSG_* 宏遍历 recvmsg 填好的控制消息。
+        unsafe {
+            let mut header = libc::CMSG_FIRSTHDR(&message);
+            while !header.is_null() {
+                if (*header).cmsg_level == libc::IPPROTO_IPV6
+                    && (*header).cmsg_type == libc::IPV6_HOPLIMIT
+                {
+                    let value =
+                        std::ptr::read_unaligned(libc::CMSG_DATA(header) as *const libc::c_int);
+                    hop_limit = u8::try_from(value).ok();
+                }
+                header = libc::CMSG_NXTHDR(&message, header);
+            }
+        }
+        // SAFETY: recvmsg 已初始化前 n 字节。
+        let data = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, n) };
+        Ok(Reply {
+            data: data.to_vec(),
+            from: None,
+            hop_limit,
+        })
+    }
+
+    /// 一次已登记的探测；释放时注销，迟到的回包随之丢弃。
+    pub struct Expected {
+        dispatcher: &'static Dispatcher,
+        key: (u16, u16),
+        replies: mpsc::Receiver<Reply>,
+    }
+
+    /// 在发送前登记 (id, seq)，避免回包先于登记到达而丢失。
+    pub fn expect(family: Family, id: u16, seq: u16) -> Result<Expected, String> {
+        if !DISPATCHER.started(family) {
+            return Err("ICMP receive socket unavailable".into());
+        }
+        Ok(DISPATCHER.register(family, id, seq))
+    }
+
+    impl Expected {
+        /// 下一份回包；到 `deadline` 仍没有则为 `Ok(None)`，接收线程已退出时为错误。
+        pub fn next(&self, deadline: Instant) -> Result<Option<Reply>, ReceiverFailed> {
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return Ok(None);
+            };
+            match self.replies.recv_timeout(left) {
+                Ok(reply) => Ok(Some(reply)),
+                Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+                Err(mpsc::RecvTimeoutError::Disconnected) => Err(ReceiverFailed),
+            }
+        }
+    }
+
+    impl Drop for Expected {
+        fn drop(&mut self) {
+            self.dispatcher.waiters().remove(&self.key);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn a_failing_receive_socket_backs_off_then_fails_its_waiters() {
+            static FAILING: Dispatcher = Dispatcher::new();
+            let expected = FAILING.register(Family::V4, 7, 0);
+            let started = Instant::now();
+            let mut calls = 0;
+            FAILING.receive(Family::V4, || {
+                calls += 1;
+                Err(std::io::ErrorKind::NetworkDown.into())
+            });
+            assert_eq!(calls, MAX_FAILURES);
+            assert!(started.elapsed() >= FIRST_BACKOFF * (2_u32.pow(MAX_FAILURES - 1) - 1));
+            let waited = Instant::now();
+            assert!(expected.next(waited + Duration::from_secs(5)).is_err());
+            assert!(
+                waited.elapsed() < Duration::from_secs(1),
+                "no wait for the timeout"
+            );
+        }
+    }
+}
+
 /// 向 `dest` 发送一个 TTL=`ttl` 的 ICMP Echo（32 字节载荷），等待至多 `timeout_ms`。
+///
+/// Windows 下的各个探测都走 `IcmpSendEcho`：identifier 与序号由系统填写，
+/// 回包也由系统按句柄交回，并发的探测互不干扰，因此不使用 `session`。
 #[cfg(target_os = "windows")]
-pub fn echo_once(dest: Ipv4Addr, ttl: u8, timeout_ms: u32) -> EchoResult {
+pub fn echo_once(_session: &IcmpSession, dest: Ipv4Addr, ttl: u8, timeout_ms: u32) -> EchoResult {
     use std::ffi::c_void;
     use windows::Win32::NetworkManagement::IpHelper::{
         ICMP_ECHO_REPLY, IP_OPTION_INFORMATION, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho,
@@ -154,6 +550,55 @@ pub(crate) mod unix_icmp {
         }
     }
 
+    /// 回包所属探测的 (id, seq)：Echo / Timestamp Reply 取自身字段，
+    /// Time Exceeded 与 Destination Unreachable 取路由器引用的原始报文。
+    pub fn reply_key(buf: &[u8]) -> Option<(u16, u16)> {
+        let icmp = buf.get(ip_header_len(buf)?..)?;
+        let echo = match *icmp.first()? {
+            0 | 14 => icmp,
+            3 | 11 => {
+                let inner = icmp.get(8..)?;
+                inner.get(ip_header_len(inner)?..)?
+            }
+            _ => return None,
+        };
+        Some((
+            u16::from_be_bytes([*echo.get(4)?, *echo.get(5)?]),
+            u16::from_be_bytes([*echo.get(6)?, *echo.get(7)?]),
+        ))
+    }
+
+    /// ICMPv6 Echo Request（type 128）：校验和含伪首部，由内核在发送时填写。
+    pub fn build_echo_request_v6(id: u16, seq: u16, payload_len: usize) -> Vec<u8> {
+        let mut pkt = vec![0u8; 8 + payload_len];
+        pkt[0] = 128;
+        pkt[4..6].copy_from_slice(&id.to_be_bytes());
+        pkt[6..8].copy_from_slice(&seq.to_be_bytes());
+        pkt
+    }
+
+    /// ICMPv6 回包所属探测的 (id, seq)：raw 套接字不交付 IPv6 头。Echo Reply（129）
+    /// 取自身字段，Destination Unreachable（1）与 Time Exceeded（3）取引用的原始报文
+    /// （40 字节 IPv6 头之后紧跟我们的 Echo Request，不处理扩展头）。
+    pub fn reply_key_v6(buf: &[u8]) -> Option<(u16, u16)> {
+        let echo = match *buf.first()? {
+            129 => buf,
+            1 | 3 => {
+                let inner = buf.get(8..)?;
+                if *inner.get(6)? != 58 {
+                    return None;
+                }
+                let echo = inner.get(40..)?;
+                (*echo.first()? == 128).then_some(echo)?
+            }
+            _ => return None,
+        };
+        Some((
+            u16::from_be_bytes([*echo.get(4)?, *echo.get(5)?]),
+            u16::from_be_bytes([*echo.get(6)?, *echo.get(7)?]),
+        ))
+    }
+
     /// 解析内核交付的 raw 套接字缓冲，匹配我们发出的 (id, seq)。不匹配/非关心类型 → None。
     pub fn parse_reply(buf: &[u8], want_id: u16, want_seq: u16) -> Option<ReplyKind> {
         let ihl = ip_header_len(buf)?;
@@ -184,17 +629,147 @@ pub(crate) mod unix_icmp {
             _ => None,
         }
     }
+
+    /// IP Record Route 选项（type 7）：9 个地址槽，末尾补 1 字节 EOL 凑满 40 字节。
+    pub fn record_route_option() -> [u8; 40] {
+        let mut option = [0u8; 40];
+        option[0] = 7;
+        option[1] = 39;
+        option[2] = 4;
+        option
+    }
+
+    /// 从 IPv4 选项区解析 Record Route 已记录的地址（按经过顺序）。
+    pub fn parse_record_route(options: &[u8]) -> Vec<std::net::Ipv4Addr> {
+        let mut i = 0;
+        while i < options.len() {
+            match options[i] {
+                0 => break,
+                1 => i += 1,
+                kind => {
+                    let Some(&len) = options.get(i + 1) else {
+                        break;
+                    };
+                    let len = len as usize;
+                    if len < 2 || i + len > options.len() {
+                        break;
+                    }
+                    if kind == 7 && len >= 3 {
+                        let filled = (options[i + 2] as usize).saturating_sub(4).min(len - 3);
+                        return options[i + 3..i + 3 + filled]
+                            .chunks_exact(4)
+                            .map(|b| std::net::Ipv4Addr::new(b[0], b[1], b[2], b[3]))
+                            .collect();
+                    }
+                    i += len;
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// 构造 ICMP Timestamp Request（type 13）：originate 为 UTC 零点起的毫秒数。
+    pub fn build_timestamp_request(id: u16, seq: u16, originate_ms: u32) -> Vec<u8> {
+        let mut pkt = vec![0u8; 20];
+        pkt[0] = 13;
+        pkt[4..6].copy_from_slice(&id.to_be_bytes());
+        pkt[6..8].copy_from_slice(&seq.to_be_bytes());
+        pkt[8..12].copy_from_slice(&originate_ms.to_be_bytes());
+        let c = checksum(&pkt);
+        pkt[2..4].copy_from_slice(&c.to_be_bytes());
+        pkt
+    }
+
+    /// Destination Unreachable / Fragmentation Needed（type 3 code 4），且内嵌的
+    /// 原始 Echo 是我们发出的 (id, seq)。
+    pub fn is_frag_needed(buf: &[u8], want_id: u16, want_seq: u16) -> bool {
+        let matches = || {
+            let ihl = ip_header_len(buf)?;
+            let icmp = buf.get(ihl..)?;
+            if (*icmp.first()?, *icmp.get(1)?) != (3, 4) {
+                return None;
+            }
+            let inner = icmp.get(8..)?;
+            let orig_icmp = inner.get(ip_header_len(inner)?..)?;
+            let id = u16::from_be_bytes([*orig_icmp.get(4)?, *orig_icmp.get(5)?]);
+            let seq = u16::from_be_bytes([*orig_icmp.get(6)?, *orig_icmp.get(7)?]);
+            Some(id == want_id && seq == want_seq)
+        };
+        matches().unwrap_or(false)
+    }
+
+    /// DSCP 标记探测的回包与所见 TOS：Echo Reply 取外层 IP 头（对端反射的
+    /// 标记），Time Exceeded 取路由器引用的原始 IP 头（到达该跳时的标记）。
+    pub fn parse_tos_reply(buf: &[u8], want_id: u16, want_seq: u16) -> Option<(ReplyKind, u8)> {
+        let kind = parse_reply(buf, want_id, want_seq)?;
+        let tos = match kind {
+            ReplyKind::EchoReply => *buf.get(1)?,
+            ReplyKind::TimeExceeded => *buf.get(ip_header_len(buf)? + 8 + 1)?,
+        };
+        Some((kind, tos))
+    }
+
+    /// 带选项探测的回包：TTL、Record Route 地址与 Timestamp 三元组。
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct OptionReply {
+        pub ttl: u8,
+        pub route: Vec<std::net::Ipv4Addr>,
+        pub timestamps: Option<[u32; 3]>,
+    }
+
+    /// 解析 Echo Reply（type 0）或 Timestamp Reply（type 14），匹配 (id, seq)。
+    pub fn parse_option_reply(buf: &[u8], want_id: u16, want_seq: u16) -> Option<OptionReply> {
+        let ihl = ip_header_len(buf)?;
+        let ttl = *buf.get(8)?;
+        let icmp = buf.get(ihl..)?;
+        let icmp_type = *icmp.first()?;
+        if icmp_type != 0 && icmp_type != 14 {
+            return None;
+        }
+        let id = u16::from_be_bytes([*icmp.get(4)?, *icmp.get(5)?]);
+        let seq = u16::from_be_bytes([*icmp.get(6)?, *icmp.get(7)?]);
+        if id != want_id || seq != want_seq {
+            return None;
+        }
+        let timestamps = if icmp_type == 14 {
+            let field = |at: usize| {
+                Some(u32::from_be_bytes([
+                    *icmp.get(at)?,
+                    *icmp.get(at + 1)?,
+                    *icmp.get(at + 2)?,
+                    *icmp.get(at + 3)?,
+                ]))
+            };
+            Some([field(8)?, field(12)?, field(16)?])
+        } else {
+            None
+        };
+        Some(OptionReply {
+            ttl,
+            route: parse_record_route(&buf[20..ihl]),
+            timestamps,
+        })
+    }
+
+    /// UTC 零点起的毫秒数（ICMP Timestamp 的时间基准）。
+    pub fn ms_since_midnight_utc() -> u32 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        (now.as_millis() % 86_400_000) as u32
+    }
 }
 
 #[cfg(unix)]
-pub fn echo_once(dest: Ipv4Addr, ttl: u8, timeout_ms: u32) -> EchoResult {
-    unix_send(None, dest, ttl, timeout_ms, 32)
+pub fn echo_once(session: &IcmpSession, dest: Ipv4Addr, ttl: u8, timeout_ms: u32) -> EchoResult {
+    unix_send(session, None, dest, ttl, timeout_ms, 32)
 }
 
 /// 从指定源地址 `src` 发送一个 ICMP Echo（绑定出口网卡），载荷 `payload_len` 字节。
 /// 用于链路质量按网卡测量。复用 `IcmpSendEcho2Ex` 的 SourceAddress 参数。
 #[cfg(target_os = "windows")]
 pub fn echo_once_from(
+    _session: &IcmpSession,
     src: Ipv4Addr,
     dest: Ipv4Addr,
     ttl: u8,
@@ -281,19 +856,21 @@ pub fn echo_once_from(
 
 #[cfg(unix)]
 pub fn echo_once_from(
+    session: &IcmpSession,
     src: Ipv4Addr,
     dest: Ipv4Addr,
     ttl: u8,
     timeout_ms: u32,
     payload_len: usize,
 ) -> EchoResult {
-    unix_send(Some(src), dest, ttl, timeout_ms, payload_len)
+    unix_send(session, Some(src), dest, ttl, timeout_ms, payload_len)
 }
 
-/// unix 下用 raw ICMP 套接字发一个 echo 并等回包。`src` 非空时绑定出口源 IP。
+/// unix 下用 raw ICMP 套接字发一个 echo，经 `dispatch` 等回包。`src` 非空时绑定出口源 IP。
 /// 收 Echo Reply → reached；收 Time Exceeded → 中间跳（addr 来自回包源地址）。
 #[cfg(unix)]
 fn unix_send(
+    session: &IcmpSession,
     src: Option<Ipv4Addr>,
     dest: Ipv4Addr,
     ttl: u8,
@@ -301,107 +878,480 @@ fn unix_send(
     payload_len: usize,
 ) -> EchoResult {
     use socket2::{Domain, Protocol, SockAddr, Socket, Type};
-    use std::mem::MaybeUninit;
     use std::net::SocketAddr;
     use std::time::{Duration, Instant};
 
-    let sock = match Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4)) {
-        Ok(s) => s,
-        Err(_) => {
-            return EchoResult {
-                status: u32::MAX,
-                addr: None,
-                rtt_ms: None,
-            };
-        }
+    let failed = EchoResult {
+        status: u32::MAX,
+        addr: None,
+        rtt_ms: None,
     };
-    if sock.set_ttl(ttl as u32).is_err()
-        || sock
-            .set_read_timeout(Some(Duration::from_millis(timeout_ms.max(1) as u64)))
-            .is_err()
-    {
-        return EchoResult {
-            status: u32::MAX,
-            addr: None,
-            rtt_ms: None,
-        };
+    let Ok(sock) = Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4)) else {
+        return failed;
+    };
+    if sock.set_ttl(ttl as u32).is_err() {
+        return failed;
     }
     if let Some(s) = src
         && sock
             .bind(&SockAddr::from(SocketAddr::new(s.into(), 0)))
             .is_err()
     {
-        return EchoResult {
-            status: u32::MAX,
-            addr: None,
-            rtt_ms: None,
-        };
+        return failed;
     }
 
-    let id = (std::process::id() & 0xFFFF) as u16;
-    // seq 取 ttl：raw ICMP 套接字会收到所有 ICMP 流量，trace 逐跳用不同 TTL，
-    // 以 seq=ttl 区分各跳，避免上一跳迟到的 Time-Exceeded 被下一跳的套接字误配。
-    let seq = ttl as u16;
+    // 以会话唯一 id 区分并发的 ping / trace / 链路质量，以会话内递增 seq
+    // 区分同一会话的先后探测，避免上一跳迟到的 Time-Exceeded 被下一次探测误配。
+    let id = session.id();
+    let seq = session.next_seq();
+    let Ok(expected) = dispatch::expect(dispatch::Family::V4, id, seq) else {
+        return failed;
+    };
     let pkt = unix_icmp::build_echo_request(id, seq, payload_len.clamp(0, 1472));
 
     let to = SockAddr::from(SocketAddr::new(dest.into(), 0));
     let start = Instant::now();
     if sock.send_to(&pkt, &to).is_err() {
+        return failed;
+    }
+
+    let deadline = start + Duration::from_millis(timeout_ms.max(1) as u64);
+    loop {
+        let reply = match expected.next(deadline) {
+            Ok(Some(reply)) => reply,
+            Ok(None) => break,
+            Err(_) => return failed,
+        };
+        let status = match unix_icmp::parse_reply(&reply.data, id, seq) {
+            Some(unix_icmp::ReplyKind::EchoReply) => IP_SUCCESS,
+            Some(unix_icmp::ReplyKind::TimeExceeded) => IP_TTL_EXPIRED_TRANSIT,
+            None => continue,
+        };
+        let addr = match status {
+            IP_SUCCESS => dest,
+            _ => reply.from.unwrap_or(dest),
+        };
         return EchoResult {
-            status: u32::MAX,
-            addr: None,
-            rtt_ms: None,
+            status,
+            addr: Some(addr),
+            rtt_ms: Some(start.elapsed().as_millis() as u64),
+        };
+    }
+    EchoResult {
+        status: IP_REQ_TIMED_OUT,
+        addr: None,
+        rtt_ms: None,
+    }
+}
+
+/// 带 IP 选项 / DF / DSCP 的单次探测参数。
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeOptions {
+    pub mode: iptools_core::PingMode,
+    pub dont_fragment: bool,
+    pub dscp: u8,
+    pub payload_len: usize,
+}
+
+/// 带选项探测的结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    Reply {
+        rtt_ms: u64,
+        reply: unix_icmp::OptionReply,
+    },
+    /// DF 包超过本机或途经链路的 MTU（EMSGSIZE 或 ICMP "需要分片"）。
+    TooBig,
+    Lost,
+}
+
+/// unix：raw 套接字发送带选项的 Echo 或 Timestamp 请求。
+/// 回包由 IP 头（含选项区）一并交付，Record Route 地址从中解析。
+#[cfg(unix)]
+pub fn probe_with_options(
+    session: &IcmpSession,
+    dest: Ipv4Addr,
+    options: ProbeOptions,
+    timeout_ms: u32,
+) -> Result<ProbeOutcome, String> {
+    use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+    use std::net::SocketAddr;
+    use std::os::fd::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    let sock = Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4))
+        .map_err(|error| error.to_string())?;
+    if options.dscp != 0 {
+        sock.set_tos((options.dscp as u32) << 2)
+            .map_err(|error| error.to_string())?;
+    }
+    if options.dont_fragment {
+        set_dont_fragment(sock.as_raw_fd())?;
+    }
+    if options.mode == iptools_core::PingMode::RecordRoute {
+        let option = unix_icmp::record_route_option();
+        // SAFETY: 套接字有效，选项缓冲在调用期间存活。
+        let rc = unsafe {
+            libc::setsockopt(
+                sock.as_raw_fd(),
+                libc::IPPROTO_IP,
+                libc::IP_OPTIONS,
+                option.as_ptr() as *const libc::c_void,
+                option.len() as libc::socklen_t,
+            )
+        };
+        if rc != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+    }
+
+    let id = session.id();
+    let seq = session.next_seq();
+    let expected = dispatch::expect(dispatch::Family::V4, id, seq)?;
+    let pkt = if options.mode == iptools_core::PingMode::Timestamp {
+        unix_icmp::build_timestamp_request(id, seq, unix_icmp::ms_since_midnight_utc())
+    } else {
+        unix_icmp::build_echo_request(id, seq, options.payload_len.min(65_500))
+    };
+    let start = Instant::now();
+    if let Err(error) = sock.send_to(&pkt, &SockAddr::from(SocketAddr::new(dest.into(), 0))) {
+        return if error.raw_os_error() == Some(libc::EMSGSIZE) {
+            Ok(ProbeOutcome::TooBig)
+        } else {
+            Err(error.to_string())
         };
     }
 
     let deadline = start + Duration::from_millis(timeout_ms.max(1) as u64);
-    let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
-    loop {
-        if Instant::now() >= deadline {
-            return EchoResult {
-                status: IP_REQ_TIMED_OUT,
-                addr: None,
-                rtt_ms: None,
-            };
+    while let Some(reply) = expected.next(deadline).map_err(|error| error.to_string())? {
+        let data = &reply.data;
+        if let Some(reply) = unix_icmp::parse_option_reply(data, id, seq) {
+            return Ok(ProbeOutcome::Reply {
+                rtt_ms: start.elapsed().as_millis() as u64,
+                reply,
+            });
         }
-        match sock.recv_from(&mut buf) {
-            Ok((n, from)) => {
-                let data = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, n) };
-                match unix_icmp::parse_reply(data, id, seq) {
-                    Some(unix_icmp::ReplyKind::EchoReply) => {
-                        let rtt = start.elapsed().as_millis() as u64;
-                        return EchoResult {
-                            status: IP_SUCCESS,
-                            addr: Some(dest),
-                            rtt_ms: Some(rtt),
-                        };
-                    }
-                    Some(unix_icmp::ReplyKind::TimeExceeded) => {
-                        let rtt = start.elapsed().as_millis() as u64;
-                        let router = from.as_socket_ipv4().map(|s| *s.ip()).unwrap_or(dest);
-                        return EchoResult {
-                            status: IP_TTL_EXPIRED_TRANSIT,
-                            addr: Some(router),
-                            rtt_ms: Some(rtt),
-                        };
-                    }
-                    None => continue,
-                }
-            }
-            Err(_) => {
-                return EchoResult {
-                    status: IP_REQ_TIMED_OUT,
-                    addr: None,
-                    rtt_ms: None,
-                };
-            }
+        if unix_icmp::is_frag_needed(data, id, seq) {
+            return Ok(ProbeOutcome::TooBig);
+        }
+    }
+    Ok(ProbeOutcome::Lost)
+}
+
+/// 置 DF 位发送 `payload_len` 字节载荷的 Echo，供巨帧 / MTU 探测使用。
+#[cfg(unix)]
+pub fn echo_df(
+    session: &IcmpSession,
+    dest: Ipv4Addr,
+    payload_len: usize,
+    timeout_ms: u32,
+) -> Result<ProbeOutcome, String> {
+    probe_with_options(
+        session,
+        dest,
+        ProbeOptions {
+            mode: iptools_core::PingMode::Echo,
+            dont_fragment: true,
+            dscp: 0,
+            payload_len,
+        },
+        timeout_ms,
+    )
+}
+
+/// unix：向 IPv6 目标发一个 `payload_len` 字节载荷的 Echo，经 `dispatch` 等回包。
+/// 回包的跳数限制放在 [`unix_icmp::OptionReply::ttl`]，没有路由与时间戳。
+#[cfg(unix)]
+pub fn echo_v6(
+    session: &IcmpSession,
+    dest: std::net::Ipv6Addr,
+    payload_len: usize,
+    timeout_ms: u32,
+) -> Result<ProbeOutcome, String> {
+    use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+    use std::net::SocketAddr;
+    use std::time::{Duration, Instant};
+
+    let sock = Socket::new(Domain::IPV6, Type::RAW, Some(Protocol::ICMPV6))
+        .map_err(|error| error.to_string())?;
+    let id = session.id();
+    let seq = session.next_seq();
+    let expected = dispatch::expect(dispatch::Family::V6, id, seq)?;
+    let pkt = unix_icmp::build_echo_request_v6(id, seq, payload_len.min(65_500));
+    let start = Instant::now();
+    if let Err(error) = sock.send_to(&pkt, &SockAddr::from(SocketAddr::new(dest.into(), 0))) {
+        return if error.raw_os_error() == Some(libc::EMSGSIZE) {
+            Ok(ProbeOutcome::TooBig)
+        } else {
+            Err(error.to_string())
+        };
+    }
+
+    let deadline = start + Duration::from_millis(timeout_ms.max(1) as u64);
+    while let Some(reply) = expected.next(deadline).map_err(|error| error.to_string())? {
+        if reply.data.first() == Some(&129) {
+            return Ok(ProbeOutcome::Reply {
+                rtt_ms: start.elapsed().as_millis() as u64,
+                reply: unix_icmp::OptionReply {
+                    ttl: reply.hop_limit.unwrap_or_default(),
+                    route: Vec::new(),
+                    timestamps: None,
+                },
+            });
+        }
+    }
+    Ok(ProbeOutcome::Lost)
+}
+
+/// Windows：`IcmpSendEcho` 置 IP_FLAG_DF；`IP_PACKET_TOO_BIG` 即需要分片。
+#[cfg(target_os = "windows")]
+pub fn echo_df(
+    _session: &IcmpSession,
+    dest: Ipv4Addr,
+    payload_len: usize,
+    timeout_ms: u32,
+) -> Result<ProbeOutcome, String> {
+    use std::ffi::c_void;
+    use windows::Win32::NetworkManagement::IpHelper::{
+        ICMP_ECHO_REPLY, IP_OPTION_INFORMATION, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho,
+    };
+    const IP_PACKET_TOO_BIG: u32 = 11009;
+
+    let dest_u32 = u32::from_le_bytes(dest.octets());
+    let payload = vec![0u8; payload_len.min(65_500)];
+    let reply_size = payload.len() + 2048;
+    let handle = unsafe { IcmpCreateFile() }.map_err(|error| error.to_string())?;
+    let opts = IP_OPTION_INFORMATION {
+        Ttl: 128,
+        Tos: 0,
+        Flags: 0x2,
+        OptionsSize: 0,
+        OptionsData: std::ptr::null_mut(),
+    };
+    let mut reply_buffer = vec![0u8; reply_size];
+    let count = unsafe {
+        IcmpSendEcho(
+            handle,
+            dest_u32,
+            payload.as_ptr() as *const c_void,
+            payload.len() as u16,
+            Some(&opts as *const IP_OPTION_INFORMATION),
+            reply_buffer.as_mut_ptr() as *mut c_void,
+            reply_size as u32,
+            timeout_ms,
+        )
+    };
+    unsafe {
+        let _ = IcmpCloseHandle(handle);
+    }
+    if count == 0 {
+        return Ok(ProbeOutcome::Lost);
+    }
+    let reply = unsafe { &*(reply_buffer.as_ptr() as *const ICMP_ECHO_REPLY) };
+    Ok(match reply.Status {
+        IP_SUCCESS => ProbeOutcome::Reply {
+            rtt_ms: reply.RoundTripTime as u64,
+            reply: unix_icmp::OptionReply {
+                ttl: reply.Options.Ttl,
+                route: Vec::new(),
+                timestamps: None,
+            },
+        },
+        IP_PACKET_TOO_BIG => ProbeOutcome::TooBig,
+        _ => ProbeOutcome::Lost,
+    })
+}
+
+#[cfg(all(not(unix), not(target_os = "windows")))]
+pub fn echo_df(
+    _session: &IcmpSession,
+    _dest: Ipv4Addr,
+    _payload_len: usize,
+    _timeout_ms: u32,
+) -> Result<ProbeOutcome, String> {
+    Err("ICMP is not supported on this platform".into())
+}
+
+/// DSCP 标记探测的单跳结果。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkProbe {
+    Reply {
+        from: Ipv4Addr,
+        rtt_ms: u64,
+        reached: bool,
+        /// 该跳回报的 TOS 字节；平台拿不到引用头时为 `None`。
+        tos: Option<u8>,
+    },
+    Lost,
+}
+
+/// unix：以给定 TTL 与 TOS 发送 Echo，从回包解析该跳看到的 TOS。
+#[cfg(unix)]
+pub fn probe_marking(
+    session: &IcmpSession,
+    dest: Ipv4Addr,
+    ttl: u8,
+    dscp: u8,
+    timeout_ms: u32,
+) -> Result<MarkProbe, String> {
+    use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+    use std::net::SocketAddr;
+    use std::time::{Duration, Instant};
+
+    let sock = Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4))
+        .map_err(|error| error.to_string())?;
+    sock.set_ttl(ttl as u32)
+        .map_err(|error| error.to_string())?;
+    sock.set_tos((dscp as u32) << 2)
+        .map_err(|error| error.to_string())?;
+
+    let id = session.id();
+    let seq = session.next_seq();
+    let expected = dispatch::expect(dispatch::Family::V4, id, seq)?;
+    let pkt = unix_icmp::build_echo_request(id, seq, 32);
+    let start = Instant::now();
+    sock.send_to(&pkt, &SockAddr::from(SocketAddr::new(dest.into(), 0)))
+        .map_err(|error| error.to_string())?;
+
+    let deadline = start + Duration::from_millis(timeout_ms.max(1) as u64);
+    while let Some(reply) = expected.next(deadline).map_err(|error| error.to_string())? {
+        if let Some((kind, tos)) = unix_icmp::parse_tos_reply(&reply.data, id, seq) {
+            return Ok(MarkProbe::Reply {
+                from: reply.from.unwrap_or(dest),
+                rtt_ms: start.elapsed().as_millis() as u64,
+                reached: kind == unix_icmp::ReplyKind::EchoReply,
+                tos: Some(tos),
+            });
         }
     }
+    Ok(MarkProbe::Lost)
+}
+
+/// Windows：`IcmpSendEcho` 的选项带 TTL 与 TOS。回包只暴露对端 Echo Reply 的
+/// TOS，中间跳的引用头不可见，因此中间跳的标记为未知。
+#[cfg(target_os = "windows")]
+pub fn probe_marking(
+    _session: &IcmpSession,
+    dest: Ipv4Addr,
+    ttl: u8,
+    dscp: u8,
+    timeout_ms: u32,
+) -> Result<MarkProbe, String> {
+    use std::ffi::c_void;
+    use windows::Win32::NetworkManagement::IpHelper::{
+        ICMP_ECHO_REPLY, IP_OPTION_INFORMATION, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho,
+    };
+
+    let dest_u32 = u32::from_le_bytes(dest.octets());
+    let payload = [0u8; 32];
+    const REPLY_SIZE: usize = 2048;
+    let handle = unsafe { IcmpCreateFile() }.map_err(|error| error.to_string())?;
+    let opts = IP_OPTION_INFORMATION {
+        Ttl: ttl,
+        Tos: dscp << 2,
+        Flags: 0,
+        OptionsSize: 0,
+        OptionsData: std::ptr::null_mut(),
+    };
+    let mut reply_buffer = vec![0u8; REPLY_SIZE];
+    let count = unsafe {
+        IcmpSendEcho(
+            handle,
+            dest_u32,
+            payload.as_ptr() as *const c_void,
+            payload.len() as u16,
+            Some(&opts as *const IP_OPTION_INFORMATION),
+            reply_buffer.as_mut_ptr() as *mut c_void,
+            REPLY_SIZE as u32,
+            timeout_ms,
+        )
+    };
+    unsafe {
+        let _ = IcmpCloseHandle(handle);
+    }
+    if count == 0 {
+        return Ok(MarkProbe::Lost);
+    }
+    let reply = unsafe { &*(reply_buffer.as_ptr() as *const ICMP_ECHO_REPLY) };
+    let o = reply.Address.to_le_bytes();
+    let from = Ipv4Addr::new(o[0], o[1], o[2], o[3]);
+    Ok(match reply.Status {
+        IP_SUCCESS => MarkProbe::Reply {
+            from,
+            rtt_ms: reply.RoundTripTime as u64,
+            reached: true,
+            tos: Some(reply.Options.Tos),
+        },
+        IP_TTL_EXPIRED_TRANSIT => MarkProbe::Reply {
+            from,
+            rtt_ms: reply.RoundTripTime as u64,
+            reached: false,
+            tos: None,
+        },
+        _ => MarkProbe::Lost,
+    })
+}
+
+#[cfg(all(not(unix), not(target_os = "windows")))]
+pub fn probe_marking(
+    _session: &IcmpSession,
+    _dest: Ipv4Addr,
+    _ttl: u8,
+    _dscp: u8,
+    _timeout_ms: u32,
+) -> Result<MarkProbe, String> {
+    Err("ICMP is not supported on this platform".into())
+}
+
+/// 置 DF 位：Linux/Android 用路径 MTU 发现的 DO 模式，其余 unix 用 IP_DONTFRAG。
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn set_dont_fragment(fd: std::os::fd::RawFd) -> Result<(), String> {
+    let value: libc::c_int = libc::IP_PMTUDISC_DO;
+    // SAFETY: fd 有效，value 在调用期间存活。
+    let rc = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            &value as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+fn set_dont_fragment(fd: std::os::fd::RawFd) -> Result<(), String> {
+    let value: libc::c_int = 1;
+    // SAFETY: fd 有效，value 在调用期间存活。
+    let rc = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            libc::IP_DONTFRAG,
+            &value as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
 }
 
 /// 其它平台暂不提供 ICMP 后端。
 #[cfg(all(not(unix), not(target_os = "windows")))]
-pub fn echo_once(_dest: Ipv4Addr, _ttl: u8, _timeout_ms: u32) -> EchoResult {
+pub fn echo_once(
+    _session: &IcmpSession,
+    _dest: Ipv4Addr,
+    _ttl: u8,
+    _timeout_ms: u32,
+) -> EchoResult {
     EchoResult {
         status: u32::MAX,
         addr: None,
@@ -410,6 +1360,7 @@ pub fn echo_once(_dest: Ipv4Addr, _ttl: u8, _timeout_ms: u32) -> EchoResult {
 }
 #[cfg(all(not(unix), not(target_os = "windows")))]
 pub fn echo_once_from(
+    _session: &IcmpSession,
     _src: Ipv4Addr,
     _dest: Ipv4Addr,
     _ttl: u8,
@@ -426,6 +1377,210 @@ pub fn echo_once_from(
 #[cfg(test)]
 mod tests {
     use super::unix_icmp::*;
+    use super::{IcmpSession, SessionIds};
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn fragmentation_needed_matches_only_our_echo() {
+        let mut packet = vec![0u8; 20 + 8 + 20 + 8];
+        packet[0] = 0x45;
+        packet[20] = 3;
+        packet[21] = 4;
+        packet[28] = 0x45;
+        packet[48..56].copy_from_slice(&build_echo_request(0x77, 9, 0));
+        assert!(is_frag_needed(&packet, 0x77, 9));
+        assert!(!is_frag_needed(&packet, 0x77, 10));
+        packet[21] = 1; // host unreachable
+        assert!(!is_frag_needed(&packet, 0x77, 9));
+    }
+
+    #[test]
+    fn tos_is_read_from_the_reply_or_the_quoted_header() {
+        let mut reply = vec![0u8; 20];
+        reply[0] = 0x45;
+        reply[1] = 46 << 2;
+        reply.extend_from_slice(&build_echo_request(0x31, 2, 0));
+        reply[20] = 0;
+        assert_eq!(
+            parse_tos_reply(&reply, 0x31, 2),
+            Some((ReplyKind::EchoReply, 46 << 2))
+        );
+
+        let mut expired = vec![0u8; 20 + 8 + 20];
+        expired[0] = 0x45;
+        expired[1] = 0xB8; // the router's own marking must be ignored
+        expired[20] = 11;
+        expired[28] = 0x45;
+        expired[29] = 0; // bleached before this hop
+        expired.extend_from_slice(&build_echo_request(0x31, 3, 0));
+        assert_eq!(
+            parse_tos_reply(&expired, 0x31, 3),
+            Some((ReplyKind::TimeExceeded, 0))
+        );
+        assert!(parse_tos_reply(&expired, 0x31, 4).is_none());
+    }
+
+    #[test]
+    fn record_route_and_timestamp_replies_decode() {
+        let mut reply = vec![0u8; 20 + 40 + 20];
+        reply[0] = 0x4F; // IPv4, IHL = 15 words (60 bytes)
+        reply[8] = 57;
+        let mut option = record_route_option();
+        option[2] = 12; // two addresses recorded
+        option[3..7].copy_from_slice(&[192, 0, 2, 1]);
+        option[7..11].copy_from_slice(&[198, 51, 100, 7]);
+        reply[20..60].copy_from_slice(&option);
+        let icmp = build_timestamp_request(0x42, 3, 1_000);
+        reply[60..80].copy_from_slice(&icmp);
+        reply[60] = 14;
+        reply[72..76].copy_from_slice(&1_020u32.to_be_bytes());
+        reply[76..80].copy_from_slice(&1_021u32.to_be_bytes());
+
+        let parsed = parse_option_reply(&reply, 0x42, 3).expect("matching reply");
+        assert_eq!(parsed.ttl, 57);
+        assert_eq!(
+            parsed.route,
+            [
+                std::net::Ipv4Addr::new(192, 0, 2, 1),
+                std::net::Ipv4Addr::new(198, 51, 100, 7)
+            ]
+        );
+        assert_eq!(parsed.timestamps, Some([1_000, 1_020, 1_021]));
+        assert!(parse_option_reply(&reply, 0x42, 4).is_none());
+    }
+
+    #[test]
+    fn concurrent_sessions_get_distinct_identifiers_and_own_sequences() {
+        static IDS: SessionIds = SessionIds::new();
+        let ping = IcmpSession::open_in(&IDS).unwrap();
+        let link = IcmpSession::open_in(&IDS).unwrap();
+        assert_ne!(ping.id(), link.id());
+        assert_eq!((ping.next_seq(), ping.next_seq()), (0, 1));
+        assert_eq!(link.next_seq(), 0);
+
+        drop(link);
+        let reopened: Vec<_> = (0..4)
+            .map(|_| IcmpSession::open_in(&IDS).unwrap())
+            .collect();
+        assert!(reopened.iter().all(|session| session.id() != ping.id()));
+    }
+
+    #[test]
+    fn session_identifiers_skip_zero_when_they_wrap() {
+        static IDS: SessionIds = SessionIds::new();
+        IDS.next.store(u16::MAX, Ordering::Relaxed);
+        let sessions: Vec<_> = (0..3)
+            .map(|_| IcmpSession::open_in(&IDS).unwrap())
+            .collect();
+        assert!(sessions.iter().all(|session| session.id() != 0));
+    }
+
+    #[test]
+    fn opening_a_session_fails_once_every_identifier_is_taken() {
+        static IDS: SessionIds = SessionIds::new();
+        let sessions: Vec<_> = (1..=u16::MAX)
+            .map(|_| IcmpSession::open_in(&IDS).unwrap())
+            .collect();
+        assert!(IcmpSession::open_in(&IDS).is_err());
+        drop(sessions);
+        assert!(IcmpSession::open_in(&IDS).is_ok());
+    }
+
+    #[test]
+    fn replies_are_keyed_by_their_own_or_quoted_id_and_seq() {
+        let mut echo = vec![0u8; 20];
+        echo[0] = 0x45;
+        echo.extend_from_slice(&[0, 0, 0, 0, 0x12, 0x34, 0, 7]);
+        assert_eq!(reply_key(&echo), Some((0x1234, 7)));
+
+        let mut exceeded = vec![0u8; 20];
+        exceeded[0] = 0x45;
+        exceeded.extend_from_slice(&[11, 0, 0, 0, 0, 0, 0, 0]);
+        exceeded.extend_from_slice(&echo[..20]);
+        exceeded.extend_from_slice(&[8, 0, 0, 0, 0x56, 0x78, 0, 9]);
+        assert_eq!(reply_key(&exceeded), Some((0x5678, 9)));
+
+        // 别人的 Echo Request（如回环上自己发出的请求）不参与分发。
+        echo[20] = 8;
+        assert_eq!(reply_key(&echo), None);
+    }
+
+    #[test]
+    fn icmpv6_replies_are_keyed_without_an_ip_header() {
+        let echo = build_echo_request_v6(0x1234, 7, 4);
+        assert_eq!(reply_key_v6(&echo), None, "our own request is not a reply");
+        let mut reply = echo.clone();
+        reply[0] = 129;
+        assert_eq!(reply_key_v6(&reply), Some((0x1234, 7)));
+
+        let mut exceeded = vec![3, 0, 0, 0, 0, 0, 0, 0];
+        let mut quoted = vec![0u8; 40];
+        quoted[0] = 0x60;
+        quoted[6] = 58;
+        exceeded.extend_from_slice(&quoted);
+        exceeded.extend_from_slice(&echo);
+        assert_eq!(reply_key_v6(&exceeded), Some((0x1234, 7)));
+        // 引用的报文不是 ICMPv6 时不归任何探测
+        exceeded[8 + 6] = 17;
+        assert_eq!(reply_key_v6(&exceeded), None);
+    }
+
+    /// 需要 raw 套接字权限与 IPv6 回环；没有时跳过。
+    #[cfg(unix)]
+    #[test]
+    fn an_ipv6_echo_goes_through_the_shared_receiver() {
+        use super::{ProbeOutcome, echo_v6};
+        use std::net::Ipv6Addr;
+
+        if socket2::Socket::new(
+            socket2::Domain::IPV6,
+            socket2::Type::RAW,
+            Some(socket2::Protocol::ICMPV6),
+        )
+        .is_err()
+        {
+            return;
+        }
+        let session = IcmpSession::open().unwrap();
+        match echo_v6(&session, Ipv6Addr::LOCALHOST, 32, 2_000) {
+            Ok(ProbeOutcome::Reply { reply, .. }) => assert!(reply.ttl > 0, "{reply:?}"),
+            // 没有配置 IPv6 回环的环境
+            Err(error) if error.contains("unreachable") || error.contains("not available") => {}
+            other => panic!("{other:?}"),
+        }
+    }
+
+    /// 需要 raw 套接字权限；没有时跳过。
+    #[cfg(unix)]
+    #[test]
+    fn concurrent_probes_each_receive_their_own_reply() {
+        use super::{IP_SUCCESS, echo_once};
+        use std::net::Ipv4Addr;
+        use std::sync::Arc;
+
+        if socket2::Socket::new(
+            socket2::Domain::IPV4,
+            socket2::Type::RAW,
+            Some(socket2::Protocol::ICMPV4),
+        )
+        .is_err()
+        {
+            return;
+        }
+        let sessions: Vec<_> = (0..4)
+            .map(|_| Arc::new(IcmpSession::open().unwrap()))
+            .collect();
+        let probes: Vec<_> = sessions
+            .iter()
+            .flat_map(|session| (0..4).map(move |_| Arc::clone(session)))
+            .map(|session| {
+                std::thread::spawn(move || echo_once(&session, Ipv4Addr::LOCALHOST, 64, 2_000))
+            })
+            .collect();
+        for probe in probes {
+            assert_eq!(probe.join().unwrap().status, IP_SUCCESS);
+        }
+    }
 
     #[test]
     fn checksum_known_vector() {
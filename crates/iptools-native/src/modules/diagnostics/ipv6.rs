@@ -0,0 +1,227 @@
+//! Native IPv6 readiness audit for the structured runtime.
+//!
+//! 依次检查：网卡上是否有全局单播地址、是否存在 IPv6 默认路由、目标能否解析
+//! 出 AAAA、能否经 TCP 443 连上纯 IPv6 目标，以及该连接协商出的 MSS 推算的
+//! 路径 MTU。前一项失败时，依赖它的后续项标记为跳过而非失败。
+
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use iptools_core::{
+    Ipv6Check, Ipv6CheckResult, Ipv6Verdict, RuntimeError, RuntimeErrorCode, RuntimeEvent,
+    is_global_ipv6,
+};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// 只用于选路的公共 IPv6 地址（Google Public DNS），UDP connect 不会发包
+const ROUTE_PROBE: SocketAddr = SocketAddr::new(
+    IpAddr::V6(Ipv6Addr::new(0x2001, 0x4860, 0x4860, 0, 0, 0, 0, 0x8888)),
+    53,
+);
+const REACH_PORT: u16 = 443;
+/// IPv6 (40) + TCP (20) 头
+const TCP_IPV6_HEADERS: u32 = 60;
+const IPV6_MIN_MTU: u32 = 1_280;
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::Ipv6Request,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let target = request.target.trim().to_string();
+    if target.is_empty() {
+        return send(RuntimeEvent::Ipv6Failed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::InvalidRequest, "target cannot be empty"),
+        })
+        .await;
+    }
+    send(RuntimeEvent::Ipv6Started { job }).await?;
+
+    let timeout = Duration::from_millis(request.timeout_ms.clamp(100, 30_000));
+    tokio::select! {
+        _ = cancellation.cancelled() => Ok(()),
+        result = audit(job, &target, timeout, &events) => result,
+    }
+}
+
+async fn audit(
+    job: iptools_core::JobId,
+    target: &str,
+    timeout: Duration,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let emit = |check, verdict, detail: String| async move {
+        events
+            .send(RuntimeEvent::Ipv6Result {
+                job,
+                result: Ipv6CheckResult {
+                    check,
+                    verdict,
+                    detail,
+                },
+            })
+            .await
+            .map_err(|error| error.to_string())
+    };
+
+    let addresses = crate::utils::net::interfaces()
+        .await?
+        .into_iter()
+        .flat_map(|interface| interface.ipv6)
+        .collect::<Vec<_>>();
+    let global = addresses
+        .iter()
+        .filter(|address| is_global_ipv6(address))
+        .collect::<Vec<_>>();
+    let (verdict, detail) = match global.as_slice() {
+        [] if addresses.is_empty() => (Ipv6Verdict::Fail, "no IPv6 address".to_string()),
+        [] => (
+            Ipv6Verdict::Fail,
+            "only link-local / ULA addresses".to_string(),
+        ),
+        [first] => (Ipv6Verdict::Pass, first.to_string()),
+        [first, rest @ ..] => (Ipv6Verdict::Pass, format!("{first} (+{} more)", rest.len())),
+    };
+    emit(Ipv6Check::GlobalAddress, verdict, detail).await?;
+
+    let (verdict, detail) = match default_route_source().await {
+        Ok(source) => (Ipv6Verdict::Pass, format!("source {source}")),
+        Err(error) => (Ipv6Verdict::Fail, error.to_string()),
+    };
+    emit(Ipv6Check::DefaultRoute, verdict, detail).await?;
+
+    let resolved = match target.parse::<Ipv6Addr>() {
+        Ok(address) => {
+            emit(
+                Ipv6Check::DnsAaaa,
+                Ipv6Verdict::Skipped,
+                "literal address".into(),
+            )
+            .await?;
+            Some(address)
+        }
+        Err(_) => {
+            let lookup = tokio::time::timeout(timeout, tokio::net::lookup_host((target, 0))).await;
+            let address = match lookup {
+                Ok(Ok(addresses)) => addresses
+                    .map(|address| address.ip())
+                    .find_map(|ip| match ip {
+                        IpAddr::V6(ip) => Some(ip),
+                        IpAddr::V4(_) => None,
+                    })
+                    .ok_or_else(|| "no AAAA record".to_string()),
+                Ok(Err(error)) => Err(error.to_string()),
+                Err(_) => Err("lookup timed out".into()),
+            };
+            match address {
+                Ok(address) => {
+                    emit(
+                        Ipv6Check::DnsAaaa,
+                        Ipv6Verdict::Pass,
+                        format!("{target} → {address}"),
+                    )
+                    .await?;
+                    Some(address)
+                }
+                Err(message) => {
+                    emit(Ipv6Check::DnsAaaa, Ipv6Verdict::Fail, message).await?;
+                    None
+                }
+            }
+        }
+    };
+
+    let stream = match resolved {
+        None => {
+            emit(
+                Ipv6Check::Reachability,
+                Ipv6Verdict::Skipped,
+                "no IPv6 address to try".into(),
+            )
+            .await?;
+            None
+        }
+        Some(address) => {
+            let started = Instant::now();
+            let connect = TcpStream::connect(SocketAddr::new(address.into(), REACH_PORT));
+            match tokio::time::timeout(timeout, connect).await {
+                Ok(Ok(stream)) => {
+                    emit(
+                        Ipv6Check::Reachability,
+                        Ipv6Verdict::Pass,
+                        format!("TCP {REACH_PORT} in {} ms", started.elapsed().as_millis()),
+                    )
+                    .await?;
+                    Some(stream)
+                }
+                Ok(Err(error)) => {
+                    emit(
+                        Ipv6Check::Reachability,
+                        Ipv6Verdict::Fail,
+                        error.to_string(),
+                    )
+                    .await?;
+                    None
+                }
+                Err(_) => {
+                    emit(
+                        Ipv6Check::Reachability,
+                        Ipv6Verdict::Fail,
+                        format!("TCP {REACH_PORT} timed out"),
+                    )
+                    .await?;
+                    None
+                }
+            }
+        }
+    };
+
+    let (verdict, detail) = match stream.as_ref().map(negotiated_mss) {
+        None => (Ipv6Verdict::Skipped, "no IPv6 connection".to_string()),
+        Some(None) => (
+            Ipv6Verdict::Skipped,
+            "not measurable on this platform".to_string(),
+        ),
+        Some(Some(mss)) => {
+            let mtu = mss + TCP_IPV6_HEADERS;
+            let verdict = if mtu >= IPV6_MIN_MTU {
+                Ipv6Verdict::Pass
+            } else {
+                Ipv6Verdict::Fail
+            };
+            (verdict, format!("≈{mtu} (MSS {mss})"))
+        }
+    };
+    emit(Ipv6Check::PathMtu, verdict, detail).await?;
+
+    events
+        .send(RuntimeEvent::Ipv6Finished { job })
+        .await
+        .map_err(|error| error.to_string())
+}
+
+/// 没有 IPv6 默认路由时，UDP connect 会以“网络不可达”失败。
+async fn default_route_source() -> std::io::Result<IpAddr> {
+    let socket = UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0)).await?;
+    socket.connect(ROUTE_PROBE).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+#[cfg(unix)]
+fn negotiated_mss(stream: &TcpStream) -> Option<u32> {
+    socket2::SockRef::from(stream).mss().ok()
+}
+
+#[cfg(not(unix))]
+fn negotiated_mss(_stream: &TcpStream) -> Option<u32> {
+    None
+}
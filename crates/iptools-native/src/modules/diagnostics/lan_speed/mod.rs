@@ -39,7 +39,7 @@ pub(crate) async fn run_shared(
     let endpoint = if request.mode == LanSpeedMode::Server {
         format!(
             "{}:{}",
-            local_ipv4().unwrap_or_else(|| "0.0.0.0".into()),
+            local_ipv4().await.unwrap_or_else(|| "0.0.0.0".into()),
             request.port
         )
     } else {
@@ -190,9 +190,10 @@ pub(crate) async fn run_shared(
 }
 
 /// 取一个活跃物理接口的 IPv4，用于服务端显示监听地址。
-fn local_ipv4() -> Option<String> {
-    let interfaces = net::get_interfaces();
-    interfaces
+async fn local_ipv4() -> Option<String> {
+    net::interfaces()
+        .await
+        .ok()?
         .iter()
         .find(|i| i.is_up && i.is_physical && !i.ipv4.is_empty())
         .and_then(|i| i.ipv4.first().cloned())
@@ -97,12 +97,27 @@ pub(crate) async fn run_shared(
     let mut last_emit = None;
     let mut last_sample = None;
 
+    let session = match super::icmp::IcmpSession::open() {
+        Ok(session) => std::sync::Arc::new(session),
+        Err(message) => {
+            send_link_failure(&events, job, RuntimeErrorCode::Internal, &message).await?;
+            return Ok(());
+        }
+    };
     for sequence in 0..count {
         if cancellation.is_cancelled() {
             return Ok(());
         }
+        let probe_session = std::sync::Arc::clone(&session);
         let mut probe = tokio::task::spawn_blocking(move || {
-            super::icmp::echo_once_from(source, destination, 128, timeout_ms, packet_size)
+            super::icmp::echo_once_from(
+                &probe_session,
+                source,
+                destination,
+                128,
+                timeout_ms,
+                packet_size,
+            )
         });
         let result = tokio::select! {
             _ = cancellation.cancelled() => {
@@ -145,10 +160,7 @@ pub(crate) async fn run_shared(
             }
         } else {
             let guid = adapter.guid.clone();
-            let speed = tokio::task::spawn_blocking(move || net::link_speed_for_guid(&guid))
-                .await
-                .ok()
-                .flatten();
+            let speed = net::link_speed(guid).await;
             (None, None, speed)
         };
         let sample = statistics.observe(sequence + 1, latency, rssi, quality, link_speed);
@@ -201,7 +213,7 @@ async fn send_link_failure(
         .map_err(|error| error.to_string())
 }
 
-fn shared_wireless_snapshot(wireless: WirelessInfo) -> iptools_core::WirelessSnapshot {
+pub(crate) fn shared_wireless_snapshot(wireless: WirelessInfo) -> iptools_core::WirelessSnapshot {
     iptools_core::WirelessSnapshot {
         ssid: wireless.ssid,
         bssid: wireless.bssid,
@@ -301,14 +313,14 @@ async fn resolve_v4(target: &str) -> Option<Ipv4Addr> {
     if let Ok(IpAddr::V4(v4)) = target.parse::<IpAddr>() {
         return Some(v4);
     }
-    if let Ok(addresses) = tokio::net::lookup_host((target, 0_u16)).await {
-        for address in addresses {
-            if let IpAddr::V4(v4) = address.ip() {
-                return Some(v4);
-            }
-        }
-    }
-    None
+    crate::utils::dns_cache::resolve(target)
+        .await
+        .ok()?
+        .into_iter()
+        .find_map(|address| match address {
+            IpAddr::V4(v4) => Some(v4),
+            IpAddr::V6(_) => None,
+        })
 }
 
 #[cfg(test)]
@@ -0,0 +1,448 @@
+//! 邮件服务检查：同时连接 SMTP / IMAP / POP3 的明文与隐式 TLS 端口，读取
+//! 欢迎语和能力列表，明文端口再尝试 STARTTLS 升级。证书按系统信任库校验，
+//! 握手失败本身就是要报告的结果；明文阶段就提供 PLAIN / LOGIN / USER 等
+//! 登录方式时记为明文登录。
+//!
+//! 只读能力列表，从不发送任何凭据。
+
+use std::sync::Arc;
+
+use futures::{StreamExt, stream};
+use iptools_core::{
+    JobId, MAIL_PORTS, MailCheck, MailProtocol, MailRequest, RuntimeError, RuntimeErrorCode,
+    RuntimeEvent, TlsSummary, cleartext_mechanism,
+};
+use rustls_platform_verifier::BuilderVerifierExt;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, timeout_at};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{self, ClientConfig, pki_types::ServerName};
+use tokio_util::sync::CancellationToken;
+
+/// 单行与单个应答的上限，防止对端无休止地输出
+const LINE_LIMIT: u64 = 4 * 1024;
+const REPLY_LINES: usize = 64;
+/// EHLO 需要一个域名，用保留的 .invalid 表明这不是真实的发信主机
+const EHLO_NAME: &str = "iptools.invalid";
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: MailRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let host = request.host.trim();
+    if host.is_empty() {
+        return send(RuntimeEvent::MailFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::InvalidRequest, "host cannot be empty"),
+        })
+        .await;
+    }
+    let connector = match connector() {
+        Ok(connector) => connector,
+        Err(error) => {
+            return send(RuntimeEvent::MailFailed {
+                job,
+                error: RuntimeError::new(RuntimeErrorCode::Internal, error),
+            })
+            .await;
+        }
+    };
+    let budget = Duration::from_millis(request.timeout_ms.clamp(500, 30_000));
+    let mut checks = stream::iter(MAIL_PORTS)
+        .map(|(port, protocol, implicit_tls)| {
+            check(
+                &connector,
+                host,
+                MailCheck::new(port, protocol, implicit_tls),
+                budget,
+            )
+        })
+        .buffer_unordered(MAIL_PORTS.len());
+    loop {
+        let check = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            check = checks.next() => check,
+        };
+        match check {
+            Some(check) => send(RuntimeEvent::MailResult { job, check }).await?,
+            None => break,
+        }
+    }
+    send(RuntimeEvent::MailFinished { job }).await
+}
+
+fn connector() -> Result<TlsConnector, String> {
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_platform_verifier())
+        .map_err(|error| error.to_string())?
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// 每个端口的整段对话共用一个截止时间。
+async fn check(
+    connector: &TlsConnector,
+    host: &str,
+    mut check: MailCheck,
+    budget: Duration,
+) -> MailCheck {
+    let deadline = Instant::now() + budget;
+    if let Err(error) = converse(connector, host, &mut check, deadline).await {
+        check.error = Some(error);
+    }
+    check
+}
+
+async fn converse(
+    connector: &TlsConnector,
+    host: &str,
+    check: &mut MailCheck,
+    deadline: Instant,
+) -> Result<(), String> {
+    let protocol = check.protocol;
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    let stream = timeout_at(deadline, TcpStream::connect((bare, check.port)))
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|error| error.to_string())?;
+
+    if check.implicit_tls {
+        let Some(stream) = handshake(connector, bare, stream, check, deadline).await else {
+            return Ok(());
+        };
+        let mut session = Session::new(stream, deadline);
+        check.banner = Some(session.greeting(protocol).await?);
+        check.auth = session.capabilities(protocol).await?.auth;
+        session.quit(protocol).await;
+        return Ok(());
+    }
+
+    let mut session = Session::new(stream, deadline);
+    check.banner = Some(session.greeting(protocol).await?);
+    let plain = session.capabilities(protocol).await?;
+    check.starttls = Some(plain.starttls);
+    check.cleartext_auth = plain
+        .auth
+        .iter()
+        .any(|mechanism| cleartext_mechanism(mechanism));
+    check.auth = plain.auth;
+    if !plain.starttls {
+        session.quit(protocol).await;
+        return Ok(());
+    }
+    session.start_tls(protocol).await?;
+    let stream = session.reader.into_inner();
+    let Some(stream) = handshake(connector, bare, stream, check, deadline).await else {
+        return Ok(());
+    };
+    let mut session = Session::new(stream, deadline);
+    // 升级后须重新询问能力，服务器常在加密后才提供登录方式
+    check.auth = session.capabilities(protocol).await?.auth;
+    session.quit(protocol).await;
+    Ok(())
+}
+
+/// 握手并记下协议版本与套件；失败时把原因记为证书问题并结束这个端口。
+async fn handshake(
+    connector: &TlsConnector,
+    host: &str,
+    stream: TcpStream,
+    check: &mut MailCheck,
+    deadline: Instant,
+) -> Option<TlsStream<TcpStream>> {
+    let result = match ServerName::try_from(host.to_string()) {
+        Ok(name) => timeout_at(deadline, connector.connect(name, stream))
+            .await
+            .map_err(|_| "TLS handshake timed out".to_string())
+            .and_then(|result| result.map_err(|error| error.to_string())),
+        Err(error) => Err(error.to_string()),
+    };
+    match result {
+        Ok(stream) => {
+            let (_, session) = stream.get_ref();
+            check.tls = Some(TlsSummary {
+                version: session
+                    .protocol_version()
+                    .map(|version| format!("{version:?}"))
+                    .unwrap_or_default(),
+                cipher: session
+                    .negotiated_cipher_suite()
+                    .map(|suite| format!("{:?}", suite.suite()))
+                    .unwrap_or_default(),
+            });
+            Some(stream)
+        }
+        Err(error) => {
+            check.tls_error = Some(error);
+            None
+        }
+    }
+}
+
+struct Session<S> {
+    reader: BufReader<S>,
+    deadline: Instant,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Session<S> {
+    fn new(stream: S, deadline: Instant) -> Self {
+        Self {
+            reader: BufReader::new(stream),
+            deadline,
+        }
+    }
+
+    async fn line(&mut self) -> Result<String, String> {
+        let mut line = Vec::new();
+        let read = timeout_at(
+            self.deadline,
+            (&mut self.reader)
+                .take(LINE_LIMIT)
+                .read_until(b'\n', &mut line),
+        )
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|error| error.to_string())?;
+        if read == 0 {
+            return Err("connection closed".into());
+        }
+        Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+    }
+
+    async fn send(&mut self, command: &str) -> Result<(), String> {
+        let stream = self.reader.get_mut();
+        timeout_at(self.deadline, async {
+            stream
+                .write_all(format!("{command}\r\n").as_bytes())
+                .await?;
+            stream.flush().await
+        })
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|error| error.to_string())
+    }
+
+    /// 读完一个多行应答：SMTP 读到 `250 ` 这样的结束行，IMAP 读到带标签的
+    /// 状态行，POP3 读到单独的 `.` 或 `-ERR`。
+    async fn reply(&mut self, protocol: MailProtocol) -> Result<Vec<String>, String> {
+        let mut lines = Vec::new();
+        while lines.len() < REPLY_LINES {
+            lines.push(self.line().await?);
+            if reply_done(protocol, &lines) {
+                return Ok(lines);
+            }
+        }
+        Err("reply too long".into())
+    }
+
+    async fn greeting(&mut self, protocol: MailProtocol) -> Result<String, String> {
+        let lines = match protocol {
+            MailProtocol::Smtp => self.reply(protocol).await?,
+            MailProtocol::Imap | MailProtocol::Pop3 => vec![self.line().await?],
+        };
+        let first = lines.into_iter().next().unwrap_or_default();
+        let ready = match protocol {
+            MailProtocol::Smtp => first.starts_with("220"),
+            MailProtocol::Imap => first.starts_with("* OK") || first.starts_with("* PREAUTH"),
+            MailProtocol::Pop3 => first.starts_with("+OK"),
+        };
+        if ready { Ok(first) } else { Err(first) }
+    }
+
+    async fn capabilities(&mut self, protocol: MailProtocol) -> Result<Capabilities, String> {
+        let command = match protocol {
+            MailProtocol::Smtp => format!("EHLO {EHLO_NAME}"),
+            MailProtocol::Imap => "c CAPABILITY".to_string(),
+            MailProtocol::Pop3 => "CAPA".to_string(),
+        };
+        self.send(&command).await?;
+        let lines = self.reply(protocol).await?;
+        if protocol == MailProtocol::Smtp && !lines[0].starts_with("250") {
+            return Err(lines[0].clone());
+        }
+        Ok(parse_capabilities(protocol, &lines))
+    }
+
+    async fn start_tls(&mut self, protocol: MailProtocol) -> Result<(), String> {
+        let (command, accepted) = match protocol {
+            MailProtocol::Smtp => ("STARTTLS", "220"),
+            MailProtocol::Imap => ("s STARTTLS", "s OK"),
+            MailProtocol::Pop3 => ("STLS", "+OK"),
+        };
+        self.send(command).await?;
+        let line = match protocol {
+            MailProtocol::Pop3 => self.line().await?,
+            _ => self.reply(protocol).await?.pop().unwrap_or_default(),
+        };
+        if line.starts_with(accepted) {
+            Ok(())
+        } else {
+            Err(format!("STARTTLS refused: {line}"))
+        }
+    }
+
+    /// 礼貌地告别；对端是否回应都不影响结果。
+    async fn quit(&mut self, protocol: MailProtocol) {
+        let command = match protocol {
+            MailProtocol::Imap => "q LOGOUT",
+            MailProtocol::Smtp | MailProtocol::Pop3 => "QUIT",
+        };
+        let _ = self.send(command).await;
+    }
+}
+
+fn reply_done(protocol: MailProtocol, lines: &[String]) -> bool {
+    let Some(last) = lines.last() else {
+        return false;
+    };
+    match protocol {
+        MailProtocol::Smtp => last.as_bytes().get(3) != Some(&b'-'),
+        MailProtocol::Imap => !last.starts_with("* "),
+        MailProtocol::Pop3 => last == "." || lines[0].starts_with("-ERR"),
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Capabilities {
+    starttls: bool,
+    /// SASL 机制，加上协议自带的明文命令（IMAP `LOGIN`、POP3 `USER`）
+    auth: Vec<String>,
+}
+
+fn parse_capabilities(protocol: MailProtocol, lines: &[String]) -> Capabilities {
+    let mut capabilities = Capabilities::default();
+    let mut push = |mechanism: &str| {
+        let mechanism = mechanism.to_ascii_uppercase();
+        if !capabilities.auth.contains(&mechanism) {
+            capabilities.auth.push(mechanism);
+        }
+    };
+    let mut starttls = false;
+    match protocol {
+        MailProtocol::Smtp => {
+            for line in lines.iter().skip(1) {
+                let mut words = line.get(4..).unwrap_or_default().split_whitespace();
+                match words.next().map(str::to_ascii_uppercase).as_deref() {
+                    Some("STARTTLS") => starttls = true,
+                    Some("AUTH") => words.for_each(&mut push),
+                    // 老式 Exchange 的 `AUTH=LOGIN`
+                    Some(word) if word.starts_with("AUTH=") => {
+                        word[5..].split_whitespace().for_each(&mut push)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        MailProtocol::Imap => {
+            let mut login_disabled = false;
+            for line in lines
+                .iter()
+                .filter_map(|line| line.strip_prefix("* CAPABILITY "))
+            {
+                for word in line.split_whitespace() {
+                    let word = word.to_ascii_uppercase();
+                    match word.as_str() {
+                        "STARTTLS" => starttls = true,
+                        "LOGINDISABLED" => login_disabled = true,
+                        _ => {
+                            if let Some(mechanism) = word.strip_prefix("AUTH=") {
+                                push(mechanism);
+                            }
+                        }
+                    }
+                }
+            }
+            // LOGIN 命令是协议本身的一部分，除非服务器明确禁用
+            if !login_disabled && lines.iter().any(|line| line.starts_with("* CAPABILITY")) {
+                push("LOGIN");
+            }
+        }
+        MailProtocol::Pop3 => {
+            if lines.first().is_some_and(|line| line.starts_with("+OK")) {
+                for line in &lines[1..] {
+                    let mut words = line.split_whitespace();
+                    match words.next().map(str::to_ascii_uppercase).as_deref() {
+                        Some("STLS") => starttls = true,
+                        Some("SASL") => words.for_each(&mut push),
+                        Some("USER") => push("USER"),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    capabilities.starttls = starttls;
+    capabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn capability_lists_yield_starttls_and_login_mechanisms() {
+        let ehlo = lines(
+            "250-mx.example Hello\n250-PIPELINING\n250-SIZE 52428800\n250-AUTH PLAIN LOGIN\n250-AUTH=LOGIN\n250 STARTTLS",
+        );
+        assert!(reply_done(MailProtocol::Smtp, &ehlo));
+        assert!(!reply_done(MailProtocol::Smtp, &ehlo[..2]));
+        assert_eq!(
+            parse_capabilities(MailProtocol::Smtp, &ehlo),
+            Capabilities {
+                starttls: true,
+                auth: vec!["PLAIN".into(), "LOGIN".into()],
+            }
+        );
+
+        let imap = lines(
+            "* CAPABILITY IMAP4rev1 SASL-IR STARTTLS LOGINDISABLED\nc OK Pre-login capabilities listed",
+        );
+        assert!(reply_done(MailProtocol::Imap, &imap));
+        assert!(!reply_done(MailProtocol::Imap, &imap[..1]));
+        assert_eq!(
+            parse_capabilities(MailProtocol::Imap, &imap),
+            Capabilities {
+                starttls: true,
+                auth: Vec::new(),
+            }
+        );
+        let open = lines("* CAPABILITY IMAP4rev1 AUTH=PLAIN\nc OK done");
+        assert_eq!(
+            parse_capabilities(MailProtocol::Imap, &open).auth,
+            ["PLAIN", "LOGIN"]
+        );
+
+        let pop = lines("+OK Capability list follows\nTOP\nUSER\nSASL PLAIN\nSTLS\n.");
+        assert!(reply_done(MailProtocol::Pop3, &pop));
+        assert!(!reply_done(MailProtocol::Pop3, &pop[..3]));
+        assert_eq!(
+            parse_capabilities(MailProtocol::Pop3, &pop),
+            Capabilities {
+                starttls: true,
+                auth: vec!["USER".into(), "PLAIN".into()],
+            }
+        );
+        let old = lines("-ERR unknown command");
+        assert!(reply_done(MailProtocol::Pop3, &old));
+        assert_eq!(
+            parse_capabilities(MailProtocol::Pop3, &old),
+            Capabilities::default()
+        );
+    }
+}
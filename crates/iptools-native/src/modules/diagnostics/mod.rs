@@ -1,8 +1,63 @@
 //! Native diagnostic algorithms used by the structured runtime.
 
+pub mod ab_test;
+pub mod cloud;
+pub mod dns_bench;
+pub mod dns_filter;
+mod dns_wire;
+pub mod dscp;
+pub mod gaming;
 pub mod icmp;
+pub mod ipv6;
 pub mod lan_speed;
 pub mod link_quality;
+pub mod mail;
+pub mod mtu;
+pub mod multicast;
+pub mod nat;
 pub mod ping;
 pub mod public_speed;
 pub mod trace;
+pub mod upnp;
+pub mod voip;
+pub mod web_check;
+pub mod wifi;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use iptools_core::{RuntimeError, RuntimeErrorCode, Target};
+
+/// Ping、Trace、端口扫描共用的目标解析：先按核心层 [`Target`] 的规则校验，
+/// 字面地址直接使用，主机名经共用的解析缓存查询，按解析器给出的顺序返回。
+pub(crate) async fn resolve_target(target: &str) -> Result<Vec<IpAddr>, RuntimeError> {
+    let invalid = |message: String| RuntimeError::new(RuntimeErrorCode::InvalidRequest, message);
+    let name = match Target::parse_host(target).map_err(|error| invalid(error.to_string()))? {
+        Target::Address(ip) => return Ok(vec![ip]),
+        Target::Host(name) => name,
+        other => {
+            return Err(invalid(
+                iptools_core::TargetError::NotAHost(other.to_string()).to_string(),
+            ));
+        }
+    };
+    crate::utils::dns_cache::resolve(&name)
+        .await
+        .map_err(|error| RuntimeError::new(RuntimeErrorCode::ResolveTarget, error))
+}
+
+/// 出口可写 IPv4 地址，也可写网卡名（取其第一个 IPv4 地址）。
+/// 路由跟踪与 A/B 网卡对比据此绑定源地址。
+pub(crate) async fn source_address(via: &str) -> Option<Ipv4Addr> {
+    let via = via.trim();
+    if let Ok(address) = via.parse() {
+        return Some(address);
+    }
+    crate::utils::net::interfaces()
+        .await
+        .ok()?
+        .into_iter()
+        .find(|interface| interface.name.eq_ignore_ascii_case(via))?
+        .ipv4
+        .iter()
+        .find_map(|address| address.parse().ok())
+}
@@ -0,0 +1,118 @@
+//! Native jumbo-frame / MTU probe for the structured runtime.
+//!
+//! 对端只需回应 ICMP Echo：以置 DF 位的 Echo 二分包长，找出端到端可承载的
+//! 最大 IP 包。同一包长无回应时重试一次，以免把偶发丢包当成 MTU 上限。
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+
+use iptools_core::{
+    MTU_HEADER_BYTES, MtuOutcome, MtuProbe, MtuSearch, MtuSummary, RuntimeError, RuntimeErrorCode,
+    RuntimeEvent,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::icmp::{IcmpSession, ProbeOutcome, echo_df};
+
+const ATTEMPTS: usize = 2;
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::MtuRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+    let fail = |code, message: String| RuntimeEvent::MtuFailed {
+        job,
+        error: RuntimeError::new(code, message),
+    };
+
+    let target = request.target.trim();
+    if target.is_empty() {
+        return send(fail(
+            RuntimeErrorCode::InvalidRequest,
+            "target cannot be empty".into(),
+        ))
+        .await;
+    }
+    let dest = match resolve_ipv4(target, "the MTU probe needs an IPv4 peer").await {
+        Ok(dest) => dest,
+        Err(message) => return send(fail(RuntimeErrorCode::ResolveTarget, message)).await,
+    };
+    send(RuntimeEvent::MtuStarted { job }).await?;
+
+    let session = match IcmpSession::open() {
+        Ok(session) => Arc::new(session),
+        Err(message) => return send(fail(RuntimeErrorCode::Internal, message)).await,
+    };
+    let timeout = request.timeout_ms.clamp(100, 10_000) as u32;
+    let mut search = MtuSearch::new(request.max_mtu);
+    let mut probes = 0;
+    while let Some(size) = search.next() {
+        let mut outcome = MtuOutcome::Lost;
+        for _ in 0..ATTEMPTS {
+            let session = session.clone();
+            let payload = usize::from(size - MTU_HEADER_BYTES);
+            let result = tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                result = tokio::task::spawn_blocking(move || echo_df(&session, dest, payload, timeout)) => result,
+            };
+            outcome = match result {
+                Ok(Ok(ProbeOutcome::Reply { rtt_ms, .. })) => MtuOutcome::Carried { rtt_ms },
+                Ok(Ok(ProbeOutcome::TooBig)) => MtuOutcome::TooBig,
+                Ok(Ok(ProbeOutcome::Lost)) => continue,
+                Ok(Err(message)) => {
+                    let code = if message.contains("Operation not permitted") {
+                        RuntimeErrorCode::PermissionDenied
+                    } else {
+                        RuntimeErrorCode::Network
+                    };
+                    return send(fail(code, message)).await;
+                }
+                Err(error) => {
+                    return send(fail(RuntimeErrorCode::Internal, error.to_string())).await;
+                }
+            };
+            break;
+        }
+        probes += 1;
+        search.record(size, matches!(outcome, MtuOutcome::Carried { .. }));
+        send(RuntimeEvent::MtuProbe {
+            job,
+            probe: MtuProbe { size, outcome },
+        })
+        .await?;
+    }
+    send(RuntimeEvent::MtuFinished {
+        job,
+        summary: MtuSummary {
+            path_mtu: search.path_mtu(),
+            probes,
+        },
+    })
+    .await
+}
+
+/// DF 与 TOS 引用头都只在 IPv4 下可用：MTU 与 DSCP 探测只解析 IPv4 地址，
+/// 字面 IPv6 目标以 `ipv6_message` 拒绝。
+pub(super) async fn resolve_ipv4(target: &str, ipv6_message: &str) -> Result<Ipv4Addr, String> {
+    if let Ok(ip) = target.parse::<IpAddr>() {
+        return match ip {
+            IpAddr::V4(ip) => Ok(ip),
+            IpAddr::V6(_) => Err(ipv6_message.into()),
+        };
+    }
+    crate::utils::dns_cache::resolve(target)
+        .await?
+        .into_iter()
+        .find_map(|address| match address {
+            IpAddr::V4(ip) => Some(ip),
+            IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| "target resolved to no IPv4 address".into())
+}
@@ -0,0 +1,178 @@
+//! Native multicast listen / send tester for the structured runtime.
+//!
+//! 监听模式加入组播组并统计到达的包、发送方、序号缺口与到达间隔抖动；
+//! 发送模式按固定速率向组播组发送带序号的测试流，供另一端监听。
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use iptools_core::{
+    MulticastMode, MulticastStats, RuntimeError, RuntimeErrorCode, RuntimeEvent,
+    encode_multicast_packet,
+};
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+// 测试流只在本地站点内传播，避免误入上游
+const MULTICAST_HOPS: u32 = 8;
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::MulticastRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+    let fail = |code, message: String| RuntimeEvent::MulticastFailed {
+        job,
+        error: RuntimeError::new(code, message),
+    };
+
+    let group = match request.group.trim().parse::<IpAddr>() {
+        Ok(group) if group.is_multicast() => group,
+        _ => {
+            return send(fail(
+                RuntimeErrorCode::InvalidRequest,
+                format!("{} is not a multicast group address", request.group.trim()),
+            ))
+            .await;
+        }
+    };
+    if request.port == 0 {
+        return send(fail(
+            RuntimeErrorCode::InvalidRequest,
+            "port cannot be 0".into(),
+        ))
+        .await;
+    }
+    let socket = match open_socket(group, request.port, request.mode) {
+        Ok(socket) => socket,
+        Err(error) => {
+            let code = if error.kind() == std::io::ErrorKind::PermissionDenied {
+                RuntimeErrorCode::PermissionDenied
+            } else {
+                RuntimeErrorCode::Network
+            };
+            return send(fail(code, error.to_string())).await;
+        }
+    };
+    send(RuntimeEvent::MulticastStarted { job }).await?;
+
+    let started = Instant::now();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(request.duration_secs.max(1));
+    let elapsed_ms = || started.elapsed().as_millis() as u64;
+    let mut stats = MulticastStats::default();
+    let mut ticker = tokio::time::interval_at(
+        tokio::time::Instant::now() + SAMPLE_INTERVAL,
+        SAMPLE_INTERVAL,
+    );
+    match request.mode {
+        MulticastMode::Listen => {
+            let mut buffer = vec![0u8; 65_536];
+            loop {
+                tokio::select! {
+                    _ = cancellation.cancelled() => return Ok(()),
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    _ = ticker.tick() => {
+                        send(RuntimeEvent::MulticastSample { job, sample: stats.sample(elapsed_ms()) }).await?;
+                    }
+                    received = socket.recv_from(&mut buffer) => match received {
+                        Ok((length, from)) => {
+                            let arrival_ms = started.elapsed().as_secs_f64() * 1_000.0;
+                            stats.record(arrival_ms, &from.ip().to_string(), &buffer[..length]);
+                        }
+                        Err(error) => return send(fail(RuntimeErrorCode::Network, error.to_string())).await,
+                    },
+                }
+            }
+        }
+        MulticastMode::Send => {
+            let destination = SocketAddr::new(group, request.port);
+            let mut pacer = tokio::time::interval(Duration::from_secs_f64(
+                1.0 / f64::from(request.rate_pps.clamp(1, 1_000)),
+            ));
+            let mut sequence = 0u32;
+            loop {
+                tokio::select! {
+                    _ = cancellation.cancelled() => return Ok(()),
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    _ = ticker.tick() => {
+                        send(RuntimeEvent::MulticastSample { job, sample: stats.sample(elapsed_ms()) }).await?;
+                    }
+                    _ = pacer.tick() => {
+                        let packet = encode_multicast_packet(sequence);
+                        if let Err(error) = socket.send_to(&packet, destination).await {
+                            return send(fail(RuntimeErrorCode::Network, error.to_string())).await;
+                        }
+                        stats.record_sent(packet.len());
+                        sequence = sequence.wrapping_add(1);
+                    }
+                }
+            }
+        }
+    }
+    send(RuntimeEvent::MulticastFinished {
+        job,
+        summary: stats.sample(elapsed_ms()),
+    })
+    .await
+}
+
+fn open_socket(group: IpAddr, port: u16, mode: MulticastMode) -> std::io::Result<UdpSocket> {
+    let domain = if group.is_ipv4() {
+        Domain::IPV4
+    } else {
+        Domain::IPV6
+    };
+    let socket = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))?;
+    match mode {
+        MulticastMode::Listen => {
+            // 允许与同机其他监听者（包括另一个 iptools）共用端口
+            socket.set_reuse_address(true)?;
+            #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+            socket.set_reuse_port(true)?;
+            match group {
+                IpAddr::V4(group) => {
+                    socket.bind(&SockAddr::from(SocketAddr::new(
+                        Ipv4Addr::UNSPECIFIED.into(),
+                        port,
+                    )))?;
+                    socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?;
+                }
+                IpAddr::V6(group) => {
+                    socket.bind(&SockAddr::from(SocketAddr::new(
+                        Ipv6Addr::UNSPECIFIED.into(),
+                        port,
+                    )))?;
+                    socket.join_multicast_v6(&group, 0)?;
+                }
+            }
+        }
+        MulticastMode::Send => match group {
+            IpAddr::V4(_) => {
+                socket.bind(&SockAddr::from(SocketAddr::new(
+                    Ipv4Addr::UNSPECIFIED.into(),
+                    0,
+                )))?;
+                socket.set_multicast_ttl_v4(MULTICAST_HOPS)?;
+                socket.set_multicast_loop_v4(true)?;
+            }
+            IpAddr::V6(_) => {
+                socket.bind(&SockAddr::from(SocketAddr::new(
+                    Ipv6Addr::UNSPECIFIED.into(),
+                    0,
+                )))?;
+                socket.set_multicast_hops_v6(MULTICAST_HOPS)?;
+                socket.set_multicast_loop_v6(true)?;
+            }
+        },
+    }
+    socket.set_nonblocking(true)?;
+    UdpSocket::from_std(socket.into())
+}
@@ -0,0 +1,417 @@
+//! Native STUN NAT-type probe for the structured runtime.
+//!
+//! 同一本地 UDP 套接字分别向两个 STUN 服务器发绑定请求：映射一致为锥形，
+//! 不一致为对称型；服务器若通告了备用地址（RFC 5780），再用 CHANGE-REQUEST
+//! 细分过滤行为；最后向自己的映射地址发包测试回环（hairpin）。
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use iptools_core::{
+    NatFiltering, NatSummary, NatType, RuntimeError, RuntimeErrorCode, RuntimeEvent, StunProbe,
+};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+const DEFAULT_STUN_PORT: u16 = 3_478;
+const MAGIC_COOKIE: u32 = 0x2112_A442;
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_CHANGE_REQUEST: u16 = 0x0003;
+const ATTR_CHANGED_ADDRESS: u16 = 0x0005;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+const ATTR_OTHER_ADDRESS: u16 = 0x802C;
+const CHANGE_IP: u32 = 0x04;
+const CHANGE_PORT: u32 = 0x02;
+/// 丢包时的重传次数（含首发）
+const ATTEMPTS: u32 = 3;
+
+type Failure = (RuntimeErrorCode, String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BindingResponse {
+    mapped: SocketAddr,
+    /// RFC 5780 OTHER-ADDRESS（或 RFC 3489 CHANGED-ADDRESS）；存在才可做过滤测试
+    other: Option<SocketAddr>,
+}
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::NatRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+    let fail = |(code, message): Failure| RuntimeEvent::NatFailed {
+        job,
+        error: RuntimeError::new(code, message),
+    };
+
+    let server = request.server.trim();
+    if server.is_empty() {
+        return send(fail((
+            RuntimeErrorCode::InvalidRequest,
+            "STUN server cannot be empty".into(),
+        )))
+        .await;
+    }
+    let primary = match resolve(server).await {
+        Ok(address) => address,
+        Err(message) => return send(fail((RuntimeErrorCode::ResolveTarget, message))).await,
+    };
+    send(RuntimeEvent::NatStarted { job }).await?;
+
+    tokio::select! {
+        _ = cancellation.cancelled() => Ok(()),
+        result = classify(job, &request, primary, &events) => match result {
+            Ok(()) => Ok(()),
+            Err(failure) => send(fail(failure)).await,
+        },
+    }
+}
+
+async fn classify(
+    job: iptools_core::JobId,
+    request: &iptools_core::NatRequest,
+    primary: SocketAddr,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), Failure> {
+    let emit = |event| async move {
+        events
+            .send(event)
+            .await
+            .map_err(|error| (RuntimeErrorCode::Internal, error.to_string()))
+    };
+    let network = |error: std::io::Error| (RuntimeErrorCode::Network, error.to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.clamp(100, 10_000));
+
+    let unspecified: IpAddr = if primary.is_ipv4() {
+        Ipv4Addr::UNSPECIFIED.into()
+    } else {
+        Ipv6Addr::UNSPECIFIED.into()
+    };
+    let socket = UdpSocket::bind((unspecified, 0)).await.map_err(network)?;
+    let local_ip = local_address_towards(unspecified, primary)
+        .await
+        .map_err(network)?;
+    let local = SocketAddr::new(local_ip, socket.local_addr().map_err(network)?.port());
+
+    let first = transact(&socket, primary, 0, timeout)
+        .await
+        .map_err(network)?;
+    emit(probe_event(job, request.server.trim(), first)).await?;
+
+    let secondary = request.secondary.trim();
+    let second = if secondary.is_empty() {
+        None
+    } else {
+        let answer = match resolve(secondary).await {
+            Ok(address) => transact(&socket, address, 0, timeout)
+                .await
+                .map_err(network)?,
+            Err(_) => None,
+        };
+        emit(probe_event(job, secondary, answer)).await?;
+        Some(answer)
+    };
+
+    let answered = [first, second.flatten()]
+        .into_iter()
+        .flatten()
+        .map(|(response, _)| response)
+        .collect::<Vec<_>>();
+    let filtering = match first {
+        Some((BindingResponse { other: Some(_), .. }, _)) => {
+            if transact(&socket, primary, CHANGE_IP | CHANGE_PORT, timeout)
+                .await
+                .map_err(network)?
+                .is_some()
+            {
+                NatFiltering::EndpointIndependent
+            } else if transact(&socket, primary, CHANGE_PORT, timeout)
+                .await
+                .map_err(network)?
+                .is_some()
+            {
+                NatFiltering::AddressDependent
+            } else {
+                NatFiltering::AddressAndPortDependent
+            }
+        }
+        _ => NatFiltering::Untested,
+    };
+    let mapped = answered.first().map(|response| response.mapped.to_string());
+    let compared = answered.get(1).map(|response| response.mapped.to_string());
+    let local_ip_text = local_ip.to_string();
+    let nat_type = NatType::classify(
+        &[local_ip_text.as_str()],
+        mapped.as_deref(),
+        compared.as_deref(),
+        filtering,
+    );
+    let hairpin = match (answered.first(), nat_type) {
+        (_, NatType::Open | NatType::Blocked) | (None, _) => None,
+        (Some(response), _) => Some(
+            hairpin(&socket, response.mapped, timeout)
+                .await
+                .map_err(network)?,
+        ),
+    };
+    emit(RuntimeEvent::NatFinished {
+        job,
+        summary: NatSummary {
+            nat_type,
+            local: local.to_string(),
+            mapped,
+            hairpin,
+        },
+    })
+    .await
+}
+
+fn probe_event(
+    job: iptools_core::JobId,
+    server: &str,
+    answer: Option<(BindingResponse, u64)>,
+) -> RuntimeEvent {
+    RuntimeEvent::NatProbe {
+        job,
+        probe: StunProbe {
+            server: server.to_string(),
+            mapped: answer.map(|(response, _)| response.mapped.to_string()),
+            rtt_ms: answer.map(|(_, rtt)| rtt),
+        },
+    }
+}
+
+/// 解析 host[:port]，未写端口时用 3478；优先 IPv4，与本地套接字族一致。
+async fn resolve(server: &str) -> Result<SocketAddr, String> {
+    let target = match server.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, DEFAULT_STUN_PORT).to_string(),
+        Err(_) if server.parse::<SocketAddr>().is_ok() => server.to_string(),
+        Err(_) => match server.rsplit_once(':') {
+            Some((_, port)) if port.parse::<u16>().is_ok() => server.to_string(),
+            _ => format!("{server}:{DEFAULT_STUN_PORT}"),
+        },
+    };
+    let addresses = tokio::net::lookup_host(&target)
+        .await
+        .map_err(|error| format!("cannot resolve {server}: {error}"))?
+        .collect::<Vec<_>>();
+    addresses
+        .iter()
+        .find(|address| address.is_ipv4())
+        .or(addresses.first())
+        .copied()
+        .ok_or_else(|| format!("{server} has no address"))
+}
+
+async fn local_address_towards(
+    unspecified: IpAddr,
+    destination: SocketAddr,
+) -> std::io::Result<IpAddr> {
+    let socket = UdpSocket::bind((unspecified, 0)).await?;
+    socket.connect(destination).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// 发送绑定请求并等待匹配事务号的成功应答；应答可能来自服务器的备用地址。
+async fn transact(
+    socket: &UdpSocket,
+    destination: SocketAddr,
+    change: u32,
+    timeout: Duration,
+) -> std::io::Result<Option<(BindingResponse, u64)>> {
+    let mut buffer = [0u8; 1_024];
+    for _ in 0..ATTEMPTS {
+        let transaction = transaction_id();
+        let started = Instant::now();
+        socket
+            .send_to(&binding_request(&transaction, change), destination)
+            .await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        while let Ok(received) =
+            tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await
+        {
+            let (length, _) = received?;
+            if let Some(response) = parse_binding_response(&buffer[..length], &transaction) {
+                return Ok(Some((response, started.elapsed().as_millis() as u64)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// 向自己的映射地址发一个绑定请求，若 NAT 支持回环，这个请求会原样回到本套接字。
+async fn hairpin(
+    socket: &UdpSocket,
+    mapped: SocketAddr,
+    timeout: Duration,
+) -> std::io::Result<bool> {
+    let mut buffer = [0u8; 1_024];
+    let transaction = transaction_id();
+    let request = binding_request(&transaction, 0);
+    for _ in 0..ATTEMPTS {
+        socket.send_to(&request, mapped).await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        while let Ok(received) =
+            tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await
+        {
+            let (length, _) = received?;
+            if buffer[..length] == request[..] {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn transaction_id() -> [u8; 12] {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let mut id = [0u8; 12];
+    id[..8].copy_from_slice(&nanos.to_be_bytes());
+    id[8..].copy_from_slice(
+        &(COUNTER.fetch_add(1, Ordering::Relaxed) ^ std::process::id().rotate_left(16))
+            .to_be_bytes(),
+    );
+    id
+}
+
+fn binding_request(transaction: &[u8; 12], change: u32) -> Vec<u8> {
+    let attributes_len: u16 = if change == 0 { 0 } else { 8 };
+    let mut packet = Vec::with_capacity(20 + usize::from(attributes_len));
+    packet.extend_from_slice(&BINDING_REQUEST.to_be_bytes());
+    packet.extend_from_slice(&attributes_len.to_be_bytes());
+    packet.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    packet.extend_from_slice(transaction);
+    if change != 0 {
+        packet.extend_from_slice(&ATTR_CHANGE_REQUEST.to_be_bytes());
+        packet.extend_from_slice(&4u16.to_be_bytes());
+        packet.extend_from_slice(&change.to_be_bytes());
+    }
+    packet
+}
+
+fn parse_binding_response(packet: &[u8], transaction: &[u8; 12]) -> Option<BindingResponse> {
+    if packet.len() < 20
+        || u16::from_be_bytes([packet[0], packet[1]]) != BINDING_SUCCESS
+        || packet[4..8] != MAGIC_COOKIE.to_be_bytes()
+        || packet[8..20] != transaction[..]
+    {
+        return None;
+    }
+    let length = usize::from(u16::from_be_bytes([packet[2], packet[3]]));
+    let mut attributes = packet.get(20..20 + length)?;
+    let (mut xor_mapped, mut mapped, mut other) = (None, None, None);
+    while attributes.len() >= 4 {
+        let kind = u16::from_be_bytes([attributes[0], attributes[1]]);
+        let size = usize::from(u16::from_be_bytes([attributes[2], attributes[3]]));
+        let value = attributes.get(4..4 + size)?;
+        match kind {
+            ATTR_XOR_MAPPED_ADDRESS => xor_mapped = parse_address(value, Some(transaction)),
+            ATTR_MAPPED_ADDRESS => mapped = parse_address(value, None),
+            ATTR_OTHER_ADDRESS | ATTR_CHANGED_ADDRESS => other = parse_address(value, None),
+            _ => {}
+        }
+        // 属性按 4 字节对齐
+        attributes = attributes
+            .get((4 + size).next_multiple_of(4)..)
+            .unwrap_or_default();
+    }
+    Some(BindingResponse {
+        mapped: xor_mapped.or(mapped)?,
+        other,
+    })
+}
+
+/// `transaction` 为 Some 时按 XOR-MAPPED-ADDRESS 解码。
+fn parse_address(value: &[u8], transaction: Option<&[u8; 12]>) -> Option<SocketAddr> {
+    let cookie = MAGIC_COOKIE.to_be_bytes();
+    let mut port = u16::from_be_bytes([*value.get(2)?, *value.get(3)?]);
+    let mut key = [0u8; 16];
+    if let Some(transaction) = transaction {
+        port ^= (MAGIC_COOKIE >> 16) as u16;
+        key[..4].copy_from_slice(&cookie);
+        key[4..].copy_from_slice(transaction);
+    }
+    let ip: IpAddr = match value.get(1)? {
+        0x01 => {
+            let mut octets: [u8; 4] = value.get(4..8)?.try_into().ok()?;
+            octets
+                .iter_mut()
+                .zip(key)
+                .for_each(|(byte, mask)| *byte ^= mask);
+            Ipv4Addr::from(octets).into()
+        }
+        0x02 => {
+            let mut octets: [u8; 16] = value.get(4..20)?.try_into().ok()?;
+            octets
+                .iter_mut()
+                .zip(key)
+                .for_each(|(byte, mask)| *byte ^= mask);
+            Ipv6Addr::from(octets).into()
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(transaction: &[u8; 12], attributes: &[u8]) -> Vec<u8> {
+        let mut packet = BINDING_SUCCESS.to_be_bytes().to_vec();
+        packet.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        packet.extend_from_slice(transaction);
+        packet.extend_from_slice(attributes);
+        packet
+    }
+
+    #[test]
+    fn xor_mapped_and_other_address_are_decoded() {
+        let transaction = [7u8; 12];
+        let request = binding_request(&transaction, CHANGE_IP | CHANGE_PORT);
+        assert_eq!(request.len(), 28);
+        assert_eq!(&request[20..], &[0, 3, 0, 4, 0, 0, 0, 6]);
+
+        // 203.0.113.7:40001 经 XOR 编码；OTHER-ADDRESS 为明文 198.51.100.2:3479
+        let port = 40_001u16 ^ 0x2112;
+        let ip = u32::from(Ipv4Addr::new(203, 0, 113, 7)) ^ MAGIC_COOKIE;
+        let mut attributes = vec![0x00, 0x20, 0x00, 0x08, 0x00, 0x01];
+        attributes.extend_from_slice(&port.to_be_bytes());
+        attributes.extend_from_slice(&ip.to_be_bytes());
+        attributes.extend_from_slice(&[0x80, 0x2C, 0x00, 0x08, 0x00, 0x01, 0x0D, 0x97]);
+        attributes.extend_from_slice(&[198, 51, 100, 2]);
+        let packet = response(&transaction, &attributes);
+        assert_eq!(
+            parse_binding_response(&packet, &transaction),
+            Some(BindingResponse {
+                mapped: "203.0.113.7:40001".parse().unwrap(),
+                other: Some("198.51.100.2:3479".parse().unwrap()),
+            })
+        );
+        assert_eq!(parse_binding_response(&packet, &[8u8; 12]), None);
+
+        // 旧服务器只回 MAPPED-ADDRESS
+        let packet = response(
+            &transaction,
+            &[0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x9C, 0x41, 192, 0, 2, 1],
+        );
+        assert_eq!(
+            parse_binding_response(&packet, &transaction).map(|response| response.mapped),
+            Some("192.0.2.1:40001".parse().unwrap())
+        );
+    }
+}
@@ -9,6 +9,9 @@ struct PingConfig {
     interval_ms: u64,
     timeout_ms: u64,
     packet_size: u64,
+    mode: iptools_core::PingMode,
+    dont_fragment: bool,
+    dscp: u8,
 }
 
 #[derive(Debug)]
@@ -18,6 +21,8 @@ enum PingEvent {
         latency: u64,
         ttl: u8,
         size: usize,
+        route: Vec<String>,
+        timestamps: Option<iptools_core::IcmpTimestamps>,
     },
     Timeout {
         seq: u64,
@@ -36,56 +41,31 @@ pub(crate) async fn run_shared(
     events: mpsc::Sender<iptools_core::RuntimeEvent>,
 ) -> Result<(), String> {
     use iptools_core::{RuntimeError, RuntimeErrorCode, RuntimeEvent};
-    use std::net::IpAddr;
 
-    if request.target.trim().is_empty() {
+    let target_ip = match super::resolve_target(&request.target).await {
+        Ok(addresses) => addresses[0],
+        Err(error) => {
+            events
+                .send(RuntimeEvent::PingFailed { job, error })
+                .await
+                .map_err(|error| error.to_string())?;
+            return Ok(());
+        }
+    };
+
+    if request.has_ip_options() && target_ip.is_ipv6() {
         events
             .send(RuntimeEvent::PingFailed {
                 job,
                 error: RuntimeError::new(
                     RuntimeErrorCode::InvalidRequest,
-                    "target cannot be empty",
+                    "IP options require an IPv4 target",
                 ),
             })
             .await
             .map_err(|error| error.to_string())?;
         return Ok(());
     }
-    let target = request.target.trim().to_string();
-    let target_ip = match target.parse::<IpAddr>() {
-        Ok(ip) => ip,
-        Err(_) => match tokio::net::lookup_host((target.as_str(), 0)).await {
-            Ok(mut values) => match values.next() {
-                Some(value) => value.ip(),
-                None => {
-                    events
-                        .send(RuntimeEvent::PingFailed {
-                            job,
-                            error: RuntimeError::new(
-                                RuntimeErrorCode::ResolveTarget,
-                                "target resolved to no addresses",
-                            ),
-                        })
-                        .await
-                        .map_err(|error| error.to_string())?;
-                    return Ok(());
-                }
-            },
-            Err(error) => {
-                events
-                    .send(RuntimeEvent::PingFailed {
-                        job,
-                        error: RuntimeError::new(
-                            RuntimeErrorCode::ResolveTarget,
-                            error.to_string(),
-                        ),
-                    })
-                    .await
-                    .map_err(|error| error.to_string())?;
-                return Ok(());
-            }
-        },
-    };
 
     events
         .send(RuntimeEvent::PingStarted { job })
@@ -95,6 +75,9 @@ pub(crate) async fn run_shared(
         interval_ms: request.interval_ms.clamp(100, 10_000),
         timeout_ms: request.timeout_ms.clamp(100, 10_000),
         packet_size: request.packet_size.min(65_500),
+        mode: request.mode,
+        dont_fragment: request.dont_fragment,
+        dscp: request.dscp.min(63),
     };
     let (tx, mut rx) = mpsc::channel(32);
     let worker_cancellation = cancellation.child_token();
@@ -114,12 +97,15 @@ pub(crate) async fn run_shared(
             event = rx.recv() => {
                 let Some(event) = event else { return Ok(()); };
                 match event {
-                    PingEvent::Result { seq, latency, ttl, size } => {
+                    PingEvent::Result { seq, latency, ttl, size, route, timestamps } => {
                         stats.observe(Some(latency));
                         let now = tokio::time::Instant::now();
                         if last_emit.is_none_or(|previous| now.duration_since(previous) >= Duration::from_millis(250)) {
                             last_emit = Some(now);
-                            events.send(RuntimeEvent::PingSample { job, sample: stats.sample(seq, Some(latency), Some(ttl), size) }).await.map_err(|error| error.to_string())?;
+                            let mut sample = stats.sample(seq, Some(latency), Some(ttl), size);
+                            sample.route = route;
+                            sample.timestamps = timestamps;
+                            events.send(RuntimeEvent::PingSample { job, sample }).await.map_err(|error| error.to_string())?;
                         }
                     }
                     PingEvent::Timeout { seq } => {
@@ -208,6 +194,8 @@ impl SharedPingStats {
             } else {
                 (self.sent - self.received) as f64 * 100.0 / self.sent as f64
             },
+            route: Vec::new(),
+            timestamps: None,
         }
     }
 }
@@ -250,9 +238,19 @@ async fn run_ping_windows(
 ) {
     use std::ffi::c_void;
     use windows::Win32::NetworkManagement::IpHelper::{
-        ICMP_ECHO_REPLY, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho,
+        ICMP_ECHO_REPLY, IP_OPTION_INFORMATION, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho,
     };
 
+    if config.mode == iptools_core::PingMode::Timestamp {
+        let _ = tx
+            .send(PingEvent::Error {
+                key: "diag_ping_err_generic".into(),
+                detail: "ICMP timestamp is not supported on Windows".into(),
+            })
+            .await;
+        return;
+    }
+
     let ip_u32 = u32::from_le_bytes(target_ip.octets());
     const REPLY_SIZE: usize = 2048 + 65535; // 足够大的缓冲区
     let mut seq = 0;
@@ -264,11 +262,30 @@ async fn run_ping_windows(
 
         let payload = vec![0u8; config.packet_size as usize];
         let timeout = config.timeout_ms as u32;
+        let record_route = config.mode == iptools_core::PingMode::RecordRoute;
+        let tos = config.dscp << 2;
+        let flags = if config.dont_fragment { 0x2 } else { 0 }; // IP_FLAG_DF
 
         let ping_task_result = tokio::task::spawn_blocking(move || {
             let handle = unsafe { IcmpCreateFile() }.map_err(|e| e.to_string())?;
 
             let mut reply_buffer = vec![0u8; REPLY_SIZE];
+            let mut route_option = super::icmp::unix_icmp::record_route_option();
+            let options = IP_OPTION_INFORMATION {
+                Ttl: 128,
+                Tos: tos,
+                Flags: flags,
+                OptionsSize: if record_route {
+                    route_option.len() as u8
+                } else {
+                    0
+                },
+                OptionsData: if record_route {
+                    route_option.as_mut_ptr()
+                } else {
+                    std::ptr::null_mut()
+                },
+            };
 
             let ret_count = unsafe {
                 IcmpSendEcho(
@@ -276,7 +293,7 @@ async fn run_ping_windows(
                     ip_u32,
                     payload.as_ptr() as *const c_void,
                     payload.len() as u16,
-                    None,
+                    Some(&options),
                     reply_buffer.as_mut_ptr() as *mut c_void,
                     REPLY_SIZE as u32,
                     timeout,
@@ -297,12 +314,31 @@ async fn run_ping_windows(
                 if count > 0 {
                     let reply = unsafe { &*(reply_buf.as_ptr() as *const ICMP_ECHO_REPLY) };
                     if reply.Status == 0 {
+                        // 回包选项区指向 reply_buf 内部，随缓冲区一同有效。
+                        let route = if reply.Options.OptionsSize > 0
+                            && !reply.Options.OptionsData.is_null()
+                        {
+                            let data = unsafe {
+                                std::slice::from_raw_parts(
+                                    reply.Options.OptionsData,
+                                    reply.Options.OptionsSize as usize,
+                                )
+                            };
+                            super::icmp::unix_icmp::parse_record_route(data)
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect()
+                        } else {
+                            Vec::new()
+                        };
                         let _ = tx
                             .send(PingEvent::Result {
                                 seq,
                                 latency: reply.RoundTripTime as u64,
                                 ttl: reply.Options.Ttl,
                                 size: sent_size,
+                                route,
+                                timestamps: None,
                             })
                             .await;
                     } else {
@@ -334,6 +370,9 @@ async fn run_ping_windows(
     }
 }
 
+/// 每个序号在阻塞线程里走一次 raw 套接字探测，回包由 [`super::icmp`] 的共享接收端
+/// 按会话 identifier 分发，与同时运行的路由跟踪、链路质量共用同一个接收套接字。
+/// IPv4 可带 IP 选项、DF 与 DSCP；IPv6 只发普通 Echo。
 #[cfg(not(target_os = "windows"))]
 async fn run_ping_unix(
     target_ip: std::net::IpAddr,
@@ -341,69 +380,84 @@ async fn run_ping_unix(
     tx: mpsc::Sender<PingEvent>,
     abort: CancellationToken,
 ) {
-    let payload = vec![0u8; config.packet_size as usize];
-    // surge-ping 0.8：先建 Client（ICMP 套接字，需 root/CAP_NET_RAW），再按目标地址族建 Pinger。
-    let cfg = if target_ip.is_ipv6() {
-        surge_ping::Config::builder()
-            .kind(surge_ping::ICMP::V6)
-            .build()
-    } else {
-        surge_ping::Config::default()
-    };
-    let client = match surge_ping::Client::new(&cfg) {
-        Ok(c) => c,
-        Err(e) => {
-            let evt = if e.to_string().contains("Permission") {
-                PingEvent::Error {
-                    key: "diag_ping_err_perm".into(),
-                    detail: String::new(),
-                }
-            } else {
-                PingEvent::Error {
+    use super::icmp::{IcmpSession, ProbeOptions, ProbeOutcome, echo_v6, probe_with_options};
+    use std::net::IpAddr;
+
+    let session = match IcmpSession::open() {
+        Ok(session) => std::sync::Arc::new(session),
+        Err(error) => {
+            let _ = tx
+                .send(PingEvent::Error {
                     key: "diag_ping_err_generic".into(),
-                    detail: e.to_string(),
-                }
-            };
-            let _ = tx.send(evt).await;
+                    detail: error,
+                })
+                .await;
             return;
         }
     };
-    // 标识符固定（取进程低 16 位），序列号每次自增，匹配回包。
-    let ident = surge_ping::PingIdentifier((std::process::id() & 0xFFFF) as u16);
-    let mut pinger = client.pinger(target_ip, ident).await;
-    pinger.timeout(Duration::from_millis(config.timeout_ms));
-
+    let options = ProbeOptions {
+        mode: config.mode,
+        dont_fragment: config.dont_fragment,
+        dscp: config.dscp,
+        payload_len: config.packet_size as usize,
+    };
+    let timeout = config.timeout_ms as u32;
     let mut seq = 0;
     let mut interval = tokio::time::interval(Duration::from_millis(config.interval_ms));
 
     loop {
-        if abort.is_cancelled() {
-            break;
-        }
         tokio::select! {
             _ = abort.cancelled() => break,
             _ = interval.tick() => {}
         }
-
+        let probe_session = session.clone();
         let result = tokio::select! {
             _ = abort.cancelled() => break,
-            result = pinger.ping(surge_ping::PingSequence(seq as u16), &payload) => result,
+            result = tokio::task::spawn_blocking(move || match target_ip {
+                IpAddr::V4(target) => probe_with_options(&probe_session, target, options, timeout),
+                IpAddr::V6(target) => echo_v6(&probe_session, target, options.payload_len, timeout),
+            }) => result,
         };
         match result {
-            Ok((_packet, duration)) => {
-                let ms = duration.as_millis() as u64;
+            Ok(Ok(ProbeOutcome::Reply { rtt_ms, reply })) => {
+                let timestamps = reply.timestamps.map(|[originate, receive, transmit]| {
+                    iptools_core::IcmpTimestamps {
+                        originate_ms: originate,
+                        receive_ms: receive,
+                        transmit_ms: transmit,
+                    }
+                });
                 let _ = tx
                     .send(PingEvent::Result {
                         seq,
-                        latency: ms,
-                        ttl: 64,
-                        size: payload.len(),
+                        latency: rtt_ms,
+                        ttl: reply.ttl,
+                        size: options.payload_len,
+                        route: reply.route.iter().map(ToString::to_string).collect(),
+                        timestamps,
                     })
                     .await;
             }
-            Err(_) => {
+            Ok(Ok(ProbeOutcome::TooBig | ProbeOutcome::Lost)) => {
                 let _ = tx.send(PingEvent::Timeout { seq }).await;
             }
+            Ok(Err(error)) => {
+                let key = if error.contains("Operation not permitted")
+                    || error.contains("receive socket unavailable")
+                {
+                    "diag_ping_err_perm"
+                } else {
+                    "diag_ping_err_generic"
+                };
+                let _ = tx
+                    .send(PingEvent::Error {
+                        key: key.into(),
+                        detail: error,
+                    })
+                    .await;
+                break;
+            }
+            Err(_) => break,
         }
         seq += 1;
     }
@@ -1,7 +1,9 @@
-//! Native public download-speed algorithm for the structured runtime.
+//! Native public speed (download, upload and latency-under-load) algorithm for
+//! the structured runtime.
 
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 const TEST_ENDPOINTS: &[(&str, &str)] = &[
     ("https://speedtest.zju.edu.cn/1000M", "speedtest.zju.edu.cn"),
@@ -14,8 +16,26 @@ const TEST_ENDPOINTS: &[(&str, &str)] = &[
         "speed.cloudflare.com",
     ),
 ];
+/// 上传阶段只有 Cloudflare 接受任意 POST 负载。
+const UPLOAD_ENDPOINTS: &[&str] = &["https://speed.cloudflare.com/__up"];
+/// 延迟探测对象：按顺序选第一个能完成 TCP 握手的地址。
+const LATENCY_TARGETS: &[&str] = &["speedtest.zju.edu.cn:443", "speed.cloudflare.com:443"];
 const MAX_DURATION_MS: u64 = 15_000;
 const CONNECT_TIMEOUT_SECS: u64 = 6;
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+/// 下载开始前采集空闲延迟基线的时长。
+const IDLE_WINDOW: Duration = Duration::from_millis(1_000);
+const LATENCY_INTERVAL: Duration = Duration::from_millis(200);
+const LATENCY_TIMEOUT: Duration = Duration::from_millis(1_000);
+/// 单次上传请求的负载大小；reqwest 未启用流式请求体，按块多次 POST。
+const UPLOAD_CHUNK_BYTES: usize = 1 << 20;
+
+/// 测速端点集合，测试中替换为本地服务。
+struct SpeedEndpoints<'a> {
+    download: &'a [(&'a str, &'a str)],
+    upload: &'a [&'a str],
+    latency: &'a [&'a str],
+}
 
 /// Bridge the established endpoint selection and streaming algorithm into the
 /// shared runtime protocol without exposing real requests to the Web runtime.
@@ -25,7 +45,18 @@ pub(crate) async fn run_shared(
     cancellation: tokio_util::sync::CancellationToken,
     events: mpsc::Sender<iptools_core::RuntimeEvent>,
 ) -> Result<(), String> {
-    run_shared_with_endpoints(job, request, cancellation, events, TEST_ENDPOINTS).await
+    run_shared_with_endpoints(
+        job,
+        request,
+        cancellation,
+        events,
+        SpeedEndpoints {
+            download: TEST_ENDPOINTS,
+            upload: UPLOAD_ENDPOINTS,
+            latency: LATENCY_TARGETS,
+        },
+    )
+    .await
 }
 
 async fn run_shared_with_endpoints(
@@ -33,9 +64,12 @@ async fn run_shared_with_endpoints(
     request: iptools_core::PublicSpeedRequest,
     cancellation: tokio_util::sync::CancellationToken,
     events: mpsc::Sender<iptools_core::RuntimeEvent>,
-    endpoints: &[(&str, &str)],
+    endpoints: SpeedEndpoints<'_>,
 ) -> Result<(), String> {
-    use iptools_core::{RuntimeError, RuntimeErrorCode, RuntimeEvent, SpeedSample, SpeedSummary};
+    use iptools_core::{
+        BufferbloatSummary, RuntimeError, RuntimeErrorCode, RuntimeEvent, SpeedPhase, SpeedSample,
+        SpeedSummary,
+    };
 
     events
         .send(RuntimeEvent::PublicSpeedStarted { job, server: None })
@@ -59,10 +93,35 @@ async fn run_shared_with_endpoints(
         }
     };
 
+    // 空闲基线必须在任何传输开始前采集，否则接收缓冲里的数据会抬高延迟。
+    let (phase, phase_rx) = watch::channel(SpeedPhase::Idle);
+    let probes = cancellation.child_token();
+    let prober = tokio::select! {
+        _ = cancellation.cancelled() => return Ok(()),
+        target = latency_target(endpoints.latency) => target,
+    }
+    .map(|target| {
+        tokio::spawn(probe_latency(
+            job,
+            target,
+            phase_rx,
+            events.clone(),
+            probes.clone(),
+        ))
+    });
+    if prober.is_some() {
+        tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            () = tokio::time::sleep(IDLE_WINDOW) => {}
+        }
+    }
     let mut response = None;
-    for (url, host) in endpoints {
+    for (url, host) in endpoints.download {
         let attempt = tokio::select! {
-            _ = cancellation.cancelled() => return Ok(()),
+            _ = cancellation.cancelled() => {
+                stop_prober(&probes, prober).await;
+                return Ok(());
+            }
             response = client.get(*url).send() => response,
         };
         if let Ok(candidate) = attempt
@@ -80,6 +139,7 @@ async fn run_shared_with_endpoints(
         }
     }
     let Some(mut response) = response else {
+        stop_prober(&probes, prober).await;
         events
             .send(RuntimeEvent::PublicSpeedFailed {
                 job,
@@ -94,6 +154,7 @@ async fn run_shared_with_endpoints(
     };
 
     let max_duration = Duration::from_millis(request.max_duration_ms.clamp(1_000, MAX_DURATION_MS));
+    phase.send_replace(SpeedPhase::Download);
     let start = Instant::now();
     let mut last = start;
     let mut last_bytes = 0u64;
@@ -101,7 +162,10 @@ async fn run_shared_with_endpoints(
     let mut peak = 0u64;
     loop {
         let chunk = tokio::select! {
-            _ = cancellation.cancelled() => return Ok(()),
+            _ = cancellation.cancelled() => {
+                stop_prober(&probes, prober).await;
+                return Ok(());
+            }
             chunk = response.chunk() => chunk,
         };
         match chunk {
@@ -109,7 +173,7 @@ async fn run_shared_with_endpoints(
                 total = total.saturating_add(chunk.len() as u64);
                 let now = Instant::now();
                 let sample_duration = now.duration_since(last);
-                if sample_duration >= Duration::from_millis(250) {
+                if sample_duration >= SAMPLE_INTERVAL {
                     let bytes_per_second = ((total.saturating_sub(last_bytes)) as f64
                         / sample_duration.as_secs_f64())
                         as u64;
@@ -121,6 +185,7 @@ async fn run_shared_with_endpoints(
                                 elapsed_ms: now.duration_since(start).as_millis() as u64,
                                 bytes: total,
                                 bytes_per_second,
+                                phase: SpeedPhase::Download,
                             },
                         })
                         .await
@@ -134,6 +199,7 @@ async fn run_shared_with_endpoints(
             }
             Ok(None) => break,
             Err(error) => {
+                stop_prober(&probes, prober).await;
                 events
                     .send(RuntimeEvent::PublicSpeedFailed {
                         job,
@@ -145,12 +211,23 @@ async fn run_shared_with_endpoints(
             }
         }
     }
+    drop(response);
     let elapsed = start.elapsed();
     let average = if elapsed.is_zero() {
         0
     } else {
         (total as f64 / elapsed.as_secs_f64()) as u64
     };
+
+    phase.send_replace(SpeedPhase::Upload);
+    let upload = tokio::select! {
+        _ = cancellation.cancelled() => {
+            stop_prober(&probes, prober).await;
+            return Ok(());
+        }
+        upload = run_upload(job, &client, endpoints.upload, max_duration / 2, start, &events) => upload?,
+    };
+    let latency = stop_prober(&probes, prober).await;
     events
         .send(RuntimeEvent::PublicSpeedFinished {
             job,
@@ -158,6 +235,9 @@ async fn run_shared_with_endpoints(
                 average_bytes_per_second: average,
                 peak_bytes_per_second: peak,
                 total_bytes: total,
+                upload_bytes_per_second: upload,
+                bufferbloat: (!latency.is_empty())
+                    .then(|| BufferbloatSummary::from_samples(&latency)),
             },
         })
         .await
@@ -165,10 +245,139 @@ async fn run_shared_with_endpoints(
     Ok(())
 }
 
+/// 按块 POST 到第一个可用的上传端点；上传不可用时返回 `None`，下载结果照常汇报。
+async fn run_upload(
+    job: iptools_core::JobId,
+    client: &reqwest::Client,
+    endpoints: &[&str],
+    duration: Duration,
+    test_start: Instant,
+    events: &mpsc::Sender<iptools_core::RuntimeEvent>,
+) -> Result<Option<u64>, String> {
+    use iptools_core::{RuntimeEvent, SpeedPhase, SpeedSample};
+
+    let payload = vec![0u8; UPLOAD_CHUNK_BYTES];
+    for url in endpoints {
+        let start = Instant::now();
+        let mut total = 0u64;
+        while start.elapsed() < duration {
+            let sent = Instant::now();
+            let accepted = client
+                .post(*url)
+                .body(payload.clone())
+                .send()
+                .await
+                .is_ok_and(|response| response.status().is_success());
+            if !accepted {
+                break;
+            }
+            total = total.saturating_add(UPLOAD_CHUNK_BYTES as u64);
+            let now = Instant::now();
+            events
+                .send(RuntimeEvent::PublicSpeedSample {
+                    job,
+                    sample: SpeedSample {
+                        elapsed_ms: now.duration_since(test_start).as_millis() as u64,
+                        bytes: total,
+                        bytes_per_second: (UPLOAD_CHUNK_BYTES as f64
+                            / now.duration_since(sent).as_secs_f64().max(0.001))
+                            as u64,
+                        phase: SpeedPhase::Upload,
+                    },
+                })
+                .await
+                .map_err(|error| error.to_string())?;
+        }
+        if total > 0 {
+            return Ok(Some(
+                (total as f64 / start.elapsed().as_secs_f64().max(0.001)) as u64,
+            ));
+        }
+    }
+    Ok(None)
+}
+
+/// 出错或完成时都要先停掉探测任务，保证它持有的事件发送端随之释放。
+async fn stop_prober(
+    probes: &tokio_util::sync::CancellationToken,
+    prober: Option<tokio::task::JoinHandle<Vec<iptools_core::LatencySample>>>,
+) -> Vec<iptools_core::LatencySample> {
+    probes.cancel();
+    match prober {
+        Some(handle) => handle.await.unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+async fn latency_target(targets: &[&str]) -> Option<SocketAddr> {
+    for target in targets {
+        let Ok(Ok(mut addresses)) =
+            tokio::time::timeout(LATENCY_TIMEOUT, tokio::net::lookup_host(*target)).await
+        else {
+            continue;
+        };
+        if let Some(address) = addresses.next()
+            && connect_rtt(address).await.is_some()
+        {
+            return Some(address);
+        }
+    }
+    None
+}
+
+/// 以 TCP 握手耗时近似往返延迟，无需 ICMP 权限。
+async fn connect_rtt(address: SocketAddr) -> Option<u64> {
+    let start = Instant::now();
+    tokio::time::timeout(LATENCY_TIMEOUT, tokio::net::TcpStream::connect(address))
+        .await
+        .ok()?
+        .ok()?;
+    Some(start.elapsed().as_millis() as u64)
+}
+
+async fn probe_latency(
+    job: iptools_core::JobId,
+    address: SocketAddr,
+    phase: watch::Receiver<iptools_core::SpeedPhase>,
+    events: mpsc::Sender<iptools_core::RuntimeEvent>,
+    cancellation: tokio_util::sync::CancellationToken,
+) -> Vec<iptools_core::LatencySample> {
+    let mut samples = Vec::new();
+    let mut interval = tokio::time::interval(LATENCY_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = cancellation.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        // 探测跨越阶段切换时不计入任何一方。
+        let before = *phase.borrow();
+        let rtt_ms = tokio::select! {
+            _ = cancellation.cancelled() => break,
+            rtt = connect_rtt(address) => rtt,
+        };
+        if *phase.borrow() != before {
+            continue;
+        }
+        let sample = iptools_core::LatencySample {
+            phase: before,
+            rtt_ms,
+        };
+        samples.push(sample);
+        if events
+            .send(iptools_core::RuntimeEvent::PublicSpeedLatency { job, sample })
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+    samples
+}
+
 #[cfg(test)]
 mod shared_tests {
     use super::*;
-    use iptools_core::{JobId, PublicSpeedRequest, RuntimeEvent, ToolKind};
+    use iptools_core::{JobId, PublicSpeedRequest, RuntimeEvent, SpeedPhase, ToolKind};
     use tokio::io::AsyncWriteExt;
     use tokio_util::sync::CancellationToken;
 
@@ -190,6 +399,9 @@ mod shared_tests {
                 tokio::time::sleep(Duration::from_millis(100)).await;
             }
         });
+        // 延迟探测只需要内核完成握手，不必 accept。
+        let latency = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let latency_address = latency.local_addr().unwrap().to_string();
         let endpoint = format!("http://{address}/speed.bin");
         let endpoints = [(endpoint.as_str(), "local.test")];
         let job = JobId {
@@ -204,7 +416,11 @@ mod shared_tests {
             },
             CancellationToken::new(),
             events,
-            &endpoints,
+            SpeedEndpoints {
+                download: &endpoints,
+                upload: &[],
+                latency: &[latency_address.as_str()],
+            },
         )
         .await
         .unwrap();
@@ -226,13 +442,21 @@ mod shared_tests {
             RuntimeEvent::PublicSpeedSample { job: current, sample }
                 if *current == job && sample.bytes > 0 && sample.bytes_per_second > 0
         )));
+        assert!(received.iter().any(|event| matches!(
+            event,
+            RuntimeEvent::PublicSpeedLatency { job: current, sample }
+                if *current == job && sample.phase == SpeedPhase::Idle && sample.rtt_ms.is_some()
+        )));
         assert!(received.iter().any(|event| matches!(
             event,
             RuntimeEvent::PublicSpeedFinished { job: current, summary }
                 if *current == job
                     && summary.total_bytes == 262_144
                     && summary.average_bytes_per_second > 0
+                    && summary.upload_bytes_per_second.is_none()
+                    && summary.bufferbloat.as_ref().is_some_and(|value| value.idle_ms.is_some())
         )));
+        drop(latency);
     }
 
     #[tokio::test]
@@ -279,7 +503,11 @@ mod shared_tests {
                     PublicSpeedRequest::default(),
                     cancellation.clone(),
                     events,
-                    &endpoints,
+                    SpeedEndpoints {
+                        download: &endpoints,
+                        upload: &[],
+                        latency: &[],
+                    },
                 ),
                 controller
             );
@@ -41,6 +41,27 @@ pub(crate) async fn run_shared(
             .map_err(|error| error.to_string())?;
         return Ok(());
     }
+    let source = match request.via.as_deref() {
+        Some(via) => {
+            match super::source_address(via).await {
+                Some(source) => Some(source),
+                None => {
+                    events
+                    .send(RuntimeEvent::TraceFailed {
+                        job,
+                        error: RuntimeError::new(
+                            RuntimeErrorCode::InvalidRequest,
+                            format!("{via} is neither a local IPv4 address nor an interface with one"),
+                        ),
+                    })
+                    .await
+                    .map_err(|error| error.to_string())?;
+                    return Ok(());
+                }
+            }
+        }
+        None => None,
+    };
     events
         .send(RuntimeEvent::TraceStarted { job })
         .await
@@ -51,6 +72,7 @@ pub(crate) async fn run_shared(
         request.target.trim().to_string(),
         u32::from(request.max_hops.clamp(1, 64)),
         request.timeout_ms.clamp(100, 10_000) as u32,
+        source,
         tx,
         worker_cancellation.clone(),
     );
@@ -150,47 +172,48 @@ async fn run_trace(
     target: String,
     max_hops: u32,
     timeout_ms: u32,
+    source: Option<Ipv4Addr>,
     tx: mpsc::Sender<TraceEvent>,
     abort: CancellationToken,
 ) {
     use std::net::IpAddr;
 
     // 解析目标为 IPv4
-    let dest_v4: Ipv4Addr = match target.parse::<IpAddr>() {
-        Ok(IpAddr::V4(v4)) => v4,
-        Ok(IpAddr::V6(_)) => {
-            let _ = tx.send(TraceEvent::Error("diag_trace_err".into())).await;
+    let resolved = super::resolve_target(&target)
+        .await
+        .ok()
+        .and_then(|addresses| {
+            addresses.into_iter().find_map(|address| match address {
+                IpAddr::V4(v4) => Some(v4),
+                IpAddr::V6(_) => None,
+            })
+        });
+    let Some(dest_v4) = resolved else {
+        let _ = tx.send(TraceEvent::Error("diag_trace_err".into())).await;
+        return;
+    };
+
+    let session = match super::icmp::IcmpSession::open() {
+        Ok(session) => std::sync::Arc::new(session),
+        Err(message) => {
+            let _ = tx.send(TraceEvent::Error(message)).await;
             return;
         }
-        Err(_) => match tokio::net::lookup_host((target.as_str(), 0u16)).await {
-            Ok(mut it) => loop {
-                match it.next() {
-                    Some(sa) => {
-                        if let IpAddr::V4(v4) = sa.ip() {
-                            break v4;
-                        }
-                    }
-                    None => {
-                        let _ = tx.send(TraceEvent::Error("diag_trace_err".into())).await;
-                        return;
-                    }
-                }
-            },
-            Err(_) => {
-                let _ = tx.send(TraceEvent::Error("diag_trace_err".into())).await;
-                return;
-            }
-        },
     };
-
     for ttl in 1..=max_hops as u8 {
         if abort.is_cancelled() {
             return;
         }
 
-        let probe =
-            tokio::task::spawn_blocking(move || super::icmp::echo_once(dest_v4, ttl, timeout_ms))
-                .await;
+        let hop_session = std::sync::Arc::clone(&session);
+        // 指定出口时绑定源地址，探测包从那块网卡发出。
+        let probe = tokio::task::spawn_blocking(move || match source {
+            Some(source) => {
+                super::icmp::echo_once_from(&hop_session, source, dest_v4, ttl, timeout_ms, 32)
+            }
+            None => super::icmp::echo_once(&hop_session, dest_v4, ttl, timeout_ms),
+        })
+        .await;
         let result = match probe {
             Ok(v) => v,
             Err(_) => return,
@@ -200,12 +223,7 @@ async fn run_trace(
 
         // 反向 DNS（best-effort，不阻塞 UI）
         let host = if let Some(a) = addr {
-            tokio::task::spawn_blocking(move || {
-                dns_lookup::lookup_addr(&std::net::IpAddr::V4(a)).ok()
-            })
-            .await
-            .ok()
-            .flatten()
+            crate::utils::dns_cache::reverse(IpAddr::V4(a)).await
         } else {
             None
         };
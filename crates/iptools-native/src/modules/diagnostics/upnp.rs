@@ -0,0 +1,443 @@
+//! Native UPnP IGD port-mapping manager for the structured runtime.
+//!
+//! SSDP 组播发现网关 → 读取设备描述，找到 WANIPConnection / WANPPPConnection
+//! 的 controlURL → 通过 SOAP 查询外网 IP、增删映射，最后逐条读回映射表。
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use iptools_core::{
+    LanProtocol, RuntimeError, RuntimeErrorCode, RuntimeEvent, UpnpAction, UpnpGateway,
+    UpnpMapping, UpnpOutcome,
+};
+use reqwest::Url;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const SEARCH_TARGETS: [&str; 2] = [
+    "urn:schemas-upnp-org:device:InternetGatewayDevice:1",
+    "urn:schemas-upnp-org:device:InternetGatewayDevice:2",
+];
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// 映射表读取上限，防止个别实现对越界索引不报错而无限返回
+const MAX_MAPPINGS: u32 = 256;
+const MAPPING_DESCRIPTION: &str = "iptools";
+
+type Failure = (RuntimeErrorCode, String);
+
+pub(crate) async fn run_shared(
+    job: iptools_core::JobId,
+    request: iptools_core::UpnpRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+    let fail = |(code, message): Failure| RuntimeEvent::UpnpFailed {
+        job,
+        error: RuntimeError::new(code, message),
+    };
+
+    if request.action != UpnpAction::List && request.external_port == 0 {
+        return send(fail((
+            RuntimeErrorCode::InvalidRequest,
+            "external port cannot be 0".into(),
+        )))
+        .await;
+    }
+    if request.action == UpnpAction::Add && request.internal_port == 0 {
+        return send(fail((
+            RuntimeErrorCode::InvalidRequest,
+            "internal port cannot be 0".into(),
+        )))
+        .await;
+    }
+    send(RuntimeEvent::UpnpStarted { job }).await?;
+
+    tokio::select! {
+        _ = cancellation.cancelled() => Ok(()),
+        result = manage(job, &request, &events) => match result {
+            Ok(()) => Ok(()),
+            Err(failure) => send(fail(failure)).await,
+        },
+    }
+}
+
+async fn manage(
+    job: iptools_core::JobId,
+    request: &iptools_core::UpnpRequest,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), Failure> {
+    let emit = |event| async move {
+        events
+            .send(event)
+            .await
+            .map_err(|error| (RuntimeErrorCode::Internal, error.to_string()))
+    };
+    let network = |error: reqwest::Error| (RuntimeErrorCode::Network, error.to_string());
+
+    let location = discover().await?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(network)?;
+    let description = client
+        .get(location.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(network)?
+        .text()
+        .await
+        .map_err(network)?;
+    let Some((service, control)) = find_wan_service(&description) else {
+        return Err((
+            RuntimeErrorCode::Network,
+            "the gateway exposes no WAN connection service".into(),
+        ));
+    };
+    let base = xml_text(&description, "URLBase")
+        .and_then(|base| Url::parse(&base).ok())
+        .unwrap_or_else(|| location.clone());
+    let control = base
+        .join(&control)
+        .map_err(|error| (RuntimeErrorCode::Network, error.to_string()))?;
+    let local_ip = local_address_towards(&location)
+        .await
+        .map_err(|error| (RuntimeErrorCode::Network, error.to_string()))?;
+    let soap = Soap {
+        client: &client,
+        control: &control,
+        service: &service,
+    };
+
+    let external_ip = soap
+        .call("GetExternalIPAddress", &[])
+        .await
+        .ok()
+        .and_then(|body| xml_text(&body, "NewExternalIPAddress"))
+        .filter(|address| !address.is_empty());
+    emit(RuntimeEvent::UpnpGateway {
+        job,
+        gateway: UpnpGateway {
+            name: xml_text(&description, "friendlyName").unwrap_or_else(|| "UPnP gateway".into()),
+            service: service
+                .rsplit_once(":service:")
+                .map_or(service.as_str(), |(_, name)| name)
+                .to_string(),
+            external_ip,
+            local_ip: local_ip.to_string(),
+        },
+    })
+    .await?;
+
+    let protocol = protocol_name(request.protocol);
+    let external_port = request.external_port.to_string();
+    let outcome = match request.action {
+        UpnpAction::List => UpnpOutcome::Listed,
+        UpnpAction::Add => {
+            soap.call(
+                "AddPortMapping",
+                &[
+                    ("NewRemoteHost", ""),
+                    ("NewExternalPort", &external_port),
+                    ("NewProtocol", protocol),
+                    ("NewInternalPort", &request.internal_port.to_string()),
+                    ("NewInternalClient", &local_ip.to_string()),
+                    ("NewEnabled", "1"),
+                    ("NewPortMappingDescription", MAPPING_DESCRIPTION),
+                    ("NewLeaseDuration", &request.lease_secs.to_string()),
+                ],
+            )
+            .await
+            .map_err(|message| (RuntimeErrorCode::Network, message))?;
+            UpnpOutcome::Added {
+                protocol: request.protocol,
+                external_port: request.external_port,
+                internal_port: request.internal_port,
+            }
+        }
+        UpnpAction::Delete => {
+            soap.call(
+                "DeletePortMapping",
+                &[
+                    ("NewRemoteHost", ""),
+                    ("NewExternalPort", &external_port),
+                    ("NewProtocol", protocol),
+                ],
+            )
+            .await
+            .map_err(|message| (RuntimeErrorCode::Network, message))?;
+            UpnpOutcome::Deleted {
+                protocol: request.protocol,
+                external_port: request.external_port,
+            }
+        }
+    };
+
+    // 越界索引返回 SOAP 错误（通常为 713），以此作为表尾
+    let mut mappings = Vec::new();
+    for index in 0..MAX_MAPPINGS {
+        let Ok(body) = soap
+            .call(
+                "GetGenericPortMappingEntry",
+                &[("NewPortMappingIndex", &index.to_string())],
+            )
+            .await
+        else {
+            break;
+        };
+        if let Some(mapping) = parse_mapping(&body) {
+            mappings.push(mapping);
+        }
+    }
+    emit(RuntimeEvent::UpnpMappings { job, mappings }).await?;
+    emit(RuntimeEvent::UpnpFinished { job, outcome }).await
+}
+
+/// 发送 M-SEARCH 并返回第一个应答网关的设备描述地址。
+async fn discover() -> Result<Url, Failure> {
+    let network = |error: std::io::Error| (RuntimeErrorCode::Network, error.to_string());
+    let socket = UdpSocket::bind("0.0.0.0:0").await.map_err(network)?;
+    for target in SEARCH_TARGETS {
+        let search = format!(
+            "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: {target}\r\n\r\n"
+        );
+        socket
+            .send_to(search.as_bytes(), SSDP_ADDR)
+            .await
+            .map_err(network)?;
+    }
+    let mut buffer = [0u8; 2_048];
+    let found = tokio::time::timeout(DISCOVERY_TIMEOUT, async {
+        loop {
+            let (length, _) = socket.recv_from(&mut buffer).await?;
+            if let Some(location) = String::from_utf8_lossy(&buffer[..length])
+                .lines()
+                .find_map(ssdp_location)
+                .and_then(|location| Url::parse(&location).ok())
+            {
+                return Ok::<_, std::io::Error>(location);
+            }
+        }
+    })
+    .await;
+    match found {
+        Ok(result) => result.map_err(network),
+        Err(_) => Err((
+            RuntimeErrorCode::Timeout,
+            "no UPnP gateway answered; UPnP may be disabled on the router".into(),
+        )),
+    }
+}
+
+/// 连接（不发送）到网关以得到本机在该网段上的地址。
+async fn local_address_towards(location: &Url) -> std::io::Result<IpAddr> {
+    let host = location.host_str().unwrap_or_default();
+    let port = location.port_or_known_default().unwrap_or(80);
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect((host, port)).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+struct Soap<'a> {
+    client: &'a reqwest::Client,
+    control: &'a Url,
+    service: &'a str,
+}
+
+impl Soap<'_> {
+    async fn call(&self, action: &str, arguments: &[(&str, &str)]) -> Result<String, String> {
+        let arguments = arguments
+            .iter()
+            .map(|(name, value)| format!("<{name}>{value}</{name}>"))
+            .collect::<String>();
+        let body = format!(
+            "<?xml version=\"1.0\"?>\r\n<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body><u:{action} xmlns:u=\"{}\">{arguments}</u:{action}></s:Body></s:Envelope>",
+            self.service
+        );
+        let response = self
+            .client
+            .post(self.control.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{}#{action}\"", self.service))
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| error.to_string())?;
+        let status = response.status();
+        let text = response.text().await.map_err(|error| error.to_string())?;
+        if status.is_success() {
+            return Ok(text);
+        }
+        Err(
+            match (
+                xml_text(&text, "errorCode"),
+                xml_text(&text, "errorDescription"),
+            ) {
+                (Some(code), Some(description)) => format!("{action} failed: {code} {description}"),
+                _ => format!("{action} failed: HTTP {status}"),
+            },
+        )
+    }
+}
+
+fn ssdp_location(line: &str) -> Option<String> {
+    let (name, value) = line.split_once(':')?;
+    name.trim()
+        .eq_ignore_ascii_case("location")
+        .then(|| value.trim().to_string())
+}
+
+/// 返回 (serviceType, controlURL)；同时存在时优先 WANIPConnection。
+fn find_wan_service(description: &str) -> Option<(String, String)> {
+    let mut services = description
+        .split("<service>")
+        .skip(1)
+        .filter_map(|block| {
+            let block = block.split("</service>").next()?;
+            let service = xml_text(block, "serviceType")?;
+            let control = xml_text(block, "controlURL")?;
+            (service.contains("WANIPConnection") || service.contains("WANPPPConnection"))
+                .then_some((service, control))
+        })
+        .collect::<Vec<_>>();
+    services.sort_by_key(|(service, _)| !service.contains("WANIPConnection"));
+    services.into_iter().next()
+}
+
+fn parse_mapping(body: &str) -> Option<UpnpMapping> {
+    let protocol = match xml_text(body, "NewProtocol")?.to_ascii_uppercase().as_str() {
+        "TCP" => LanProtocol::Tcp,
+        "UDP" => LanProtocol::Udp,
+        _ => return None,
+    };
+    Some(UpnpMapping {
+        protocol,
+        external_port: xml_text(body, "NewExternalPort")?.parse().ok()?,
+        internal_client: xml_text(body, "NewInternalClient").unwrap_or_default(),
+        internal_port: xml_text(body, "NewInternalPort")?.parse().ok()?,
+        description: xml_text(body, "NewPortMappingDescription").unwrap_or_default(),
+        enabled: matches!(
+            xml_text(body, "NewEnabled").as_deref(),
+            Some("1" | "true" | "yes")
+        ),
+        lease_secs: xml_text(body, "NewLeaseDuration")
+            .and_then(|lease| lease.parse().ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// 取第一个同名元素的文本，忽略命名空间前缀；不是通用 XML 解析器，
+/// 只覆盖 IGD 描述与 SOAP 应答里的简单叶子元素。
+fn xml_text(xml: &str, tag: &str) -> Option<String> {
+    let close = format!("{tag}>");
+    let mut rest = xml;
+    while let Some(position) = rest.find(&close) {
+        let before = &rest[..position];
+        let after = &rest[position + close.len()..];
+        rest = after;
+        let opening = match before.rfind('<') {
+            Some(start) => &before[start + 1..],
+            None => continue,
+        };
+        // 跳过闭合标签和 <fooTag> 这类仅后缀相同的元素
+        if opening.starts_with('/') || !(opening.is_empty() || opening.ends_with(':')) {
+            continue;
+        }
+        let value = after.split('<').next().unwrap_or_default().trim();
+        return Some(
+            value
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+    }
+    None
+}
+
+const fn protocol_name(protocol: LanProtocol) -> &'static str {
+    match protocol {
+        LanProtocol::Tcp => "TCP",
+        LanProtocol::Udp => "UDP",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn description_and_soap_replies_are_parsed() {
+        let description = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <friendlyName>Home Router &amp; AP</friendlyName>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:WANPPPConnection:1</serviceType>
+        <controlURL>/ctl/PPP</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+        <controlURL>/ctl/IPConn</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+        assert_eq!(
+            xml_text(description, "friendlyName").as_deref(),
+            Some("Home Router & AP")
+        );
+        assert_eq!(
+            find_wan_service(description),
+            Some((
+                "urn:schemas-upnp-org:service:WANIPConnection:1".into(),
+                "/ctl/IPConn".into()
+            ))
+        );
+        assert_eq!(
+            ssdp_location("LOCATION: http://192.168.1.1:5000/rootDesc.xml").as_deref(),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+
+        let entry = r#"<s:Envelope><s:Body><u:GetGenericPortMappingEntryResponse>
+<NewRemoteHost></NewRemoteHost><NewExternalPort>50505</NewExternalPort>
+<NewProtocol>UDP</NewProtocol><NewInternalPort>5201</NewInternalPort>
+<NewInternalClient>192.168.1.20</NewInternalClient><NewEnabled>1</NewEnabled>
+<NewPortMappingDescription>iptools</NewPortMappingDescription>
+<NewLeaseDuration>3599</NewLeaseDuration>
+</u:GetGenericPortMappingEntryResponse></s:Body></s:Envelope>"#;
+        assert_eq!(
+            parse_mapping(entry),
+            Some(UpnpMapping {
+                protocol: LanProtocol::Udp,
+                external_port: 50_505,
+                internal_client: "192.168.1.20".into(),
+                internal_port: 5_201,
+                description: "iptools".into(),
+                enabled: true,
+                lease_secs: 3_599,
+            })
+        );
+        assert_eq!(
+            xml_text(
+                "<m:NewExternalIPAddress>198.51.100.7</m:NewExternalIPAddress>",
+                "NewExternalIPAddress"
+            )
+            .as_deref(),
+            Some("198.51.100.7")
+        );
+        assert_eq!(
+            xml_text("<OtherNewProtocol>TCP</OtherNewProtocol>", "NewProtocol"),
+            None
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! VoIP 通话质量探测：按编码的包长与 20 ms 间隔向回显端发送 RTP 测试流，
+//! 统计回包的往返、抖动、丢包与乱序，由核心层按 E-model 折算 MOS。
+//!
+//! 回显模式在本机端口上把收到的每个包原样发回，供另一台机器上的 iptools
+//! 探测；任何会回显 UDP 的服务（例如 echo 端口）同样可以作为回显端。
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use iptools_core::{
+    JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent, VOIP_PACKET_INTERVAL_MS, VoipMode,
+    VoipRequest, VoipStats, decode_voip_packet, encode_voip_packet,
+};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// 发完最后一个包后再等这么久，迟到的回包仍计入统计
+const ECHO_GRACE: Duration = Duration::from_secs(1);
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: VoipRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+    let fail = |error: RuntimeError| RuntimeEvent::VoipFailed { job, error };
+    let network = |error: std::io::Error| {
+        let code = if error.kind() == std::io::ErrorKind::PermissionDenied {
+            RuntimeErrorCode::PermissionDenied
+        } else {
+            RuntimeErrorCode::Network
+        };
+        fail(RuntimeError::new(code, error.to_string()))
+    };
+
+    if request.port == 0 {
+        return send(fail(RuntimeError::new(
+            RuntimeErrorCode::InvalidRequest,
+            "port cannot be 0",
+        )))
+        .await;
+    }
+    match request.mode {
+        VoipMode::Reflect => {
+            let socket =
+                match UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), request.port))
+                    .await
+                {
+                    Ok(socket) => socket,
+                    Err(error) => return send(network(error)).await,
+                };
+            let endpoint = socket.local_addr().map_or_else(
+                |_| format!("0.0.0.0:{}", request.port),
+                |addr| addr.to_string(),
+            );
+            send(RuntimeEvent::VoipStarted { job, endpoint }).await?;
+            reflect(job, &socket, &cancellation, &events).await
+        }
+        VoipMode::Probe => {
+            let peer = match super::resolve_target(request.peer.trim()).await {
+                Ok(addresses) => match addresses.first() {
+                    Some(&ip) => SocketAddr::new(ip, request.port),
+                    None => {
+                        return send(fail(RuntimeError::new(
+                            RuntimeErrorCode::ResolveTarget,
+                            format!("{} has no address", request.peer.trim()),
+                        )))
+                        .await;
+                    }
+                },
+                Err(error) => return send(fail(error)).await,
+            };
+            let local = if peer.is_ipv4() {
+                SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)
+            } else {
+                SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)
+            };
+            let socket = match UdpSocket::bind(local).await {
+                Ok(socket) => socket,
+                Err(error) => return send(network(error)).await,
+            };
+            if let Err(error) = socket.connect(peer).await {
+                return send(network(error)).await;
+            }
+            send(RuntimeEvent::VoipStarted {
+                job,
+                endpoint: peer.to_string(),
+            })
+            .await?;
+            probe(job, &request, &socket, &cancellation, &events).await
+        }
+    }
+}
+
+async fn probe(
+    job: JobId,
+    request: &VoipRequest,
+    socket: &UdpSocket,
+    cancellation: &CancellationToken,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| async move { events.send(event).await.map_err(|error| error.to_string()) };
+    // 用当前时间的低位作 SSRC，区分同一端口上先后几次测试的回包
+    let ssrc = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0x1f07_0c5e, |now| now.subsec_nanos() ^ now.as_secs() as u32);
+    let started = Instant::now();
+    let elapsed_ms = || started.elapsed().as_millis() as u64;
+    let sending_until =
+        tokio::time::Instant::now() + Duration::from_secs(request.duration_secs.max(1));
+    let deadline = sending_until + ECHO_GRACE;
+    let mut pacer = tokio::time::interval(Duration::from_millis(VOIP_PACKET_INTERVAL_MS));
+    let mut ticker = tokio::time::interval_at(
+        tokio::time::Instant::now() + SAMPLE_INTERVAL,
+        SAMPLE_INTERVAL,
+    );
+    let mut stats = VoipStats::default();
+    let mut sequence = 0u16;
+    let mut buffer = vec![0u8; 2_048];
+    loop {
+        tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            _ = tokio::time::sleep_until(deadline) => break,
+            _ = ticker.tick() => {
+                send(RuntimeEvent::VoipSample { job, sample: stats.sample(elapsed_ms(), false) }).await?;
+            }
+            _ = pacer.tick(), if tokio::time::Instant::now() < sending_until => {
+                let sent_us = started.elapsed().as_micros() as u64;
+                let packet = encode_voip_packet(request.codec, ssrc, sequence, sent_us);
+                match socket.send(&packet).await {
+                    Ok(_) => stats.record_sent(),
+                    Err(error) => return send(RuntimeEvent::VoipFailed {
+                        job,
+                        error: RuntimeError::new(RuntimeErrorCode::Network, error.to_string()),
+                    }).await,
+                }
+                sequence = sequence.wrapping_add(1);
+            }
+            received = socket.recv(&mut buffer) => match received {
+                Ok(length) => {
+                    if let Some((sequence, sent_us)) = decode_voip_packet(ssrc, &buffer[..length]) {
+                        let now_us = started.elapsed().as_micros() as u64;
+                        stats.record_echo(sequence, now_us.saturating_sub(sent_us) as f64 / 1_000.0);
+                    }
+                }
+                // 对端端口未开放时 ICMP 不可达会以错误形式出现在已连接的套接字上；
+                // 它只说明这一个包没有回显，计入丢包即可
+                Err(error) if error.kind() == std::io::ErrorKind::ConnectionRefused => {}
+                Err(error) => return send(RuntimeEvent::VoipFailed {
+                    job,
+                    error: RuntimeError::new(RuntimeErrorCode::Network, error.to_string()),
+                }).await,
+            },
+        }
+    }
+    send(RuntimeEvent::VoipFinished {
+        job,
+        summary: stats.sample(elapsed_ms(), true),
+    })
+    .await
+}
+
+async fn reflect(
+    job: JobId,
+    socket: &UdpSocket,
+    cancellation: &CancellationToken,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| async move { events.send(event).await.map_err(|error| error.to_string()) };
+    let started = Instant::now();
+    let elapsed_ms = || started.elapsed().as_millis() as u64;
+    let mut ticker = tokio::time::interval_at(
+        tokio::time::Instant::now() + SAMPLE_INTERVAL,
+        SAMPLE_INTERVAL,
+    );
+    let mut stats = VoipStats::default();
+    let mut buffer = vec![0u8; 2_048];
+    // 回显端一直运行到用户停止
+    loop {
+        tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            _ = ticker.tick() => {
+                send(RuntimeEvent::VoipSample { job, sample: stats.reflected(elapsed_ms()) }).await?;
+            }
+            received = socket.recv_from(&mut buffer) => match received {
+                Ok((length, from)) => {
+                    if socket.send_to(&buffer[..length], from).await.is_ok() {
+                        stats.record_sent();
+                    }
+                }
+                // Windows 上对端离开后 ICMP 不可达会让 recv_from 报错，继续等下一个包
+                Err(error) if error.kind() == std::io::ErrorKind::ConnectionReset => {}
+                Err(error) => return send(RuntimeEvent::VoipFailed {
+                    job,
+                    error: RuntimeError::new(RuntimeErrorCode::Network, error.to_string()),
+                }).await,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn probe_scores_echoes_from_a_local_reflector() {
+        let (events, mut received) = mpsc::channel(256);
+        let reflector = JobId {
+            tool: iptools_core::ToolKind::Voip,
+            generation: 1,
+        };
+        let stop = CancellationToken::new();
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let reflecting = tokio::spawn({
+            let (stop, events) = (stop.clone(), events.clone());
+            async move { reflect(reflector, &socket, &stop, &events).await }
+        });
+
+        let prober = JobId {
+            generation: 2,
+            ..reflector
+        };
+        run_shared(
+            prober,
+            VoipRequest {
+                peer: "127.0.0.1".into(),
+                port,
+                duration_secs: 1,
+                ..VoipRequest::default()
+            },
+            CancellationToken::new(),
+            events,
+        )
+        .await
+        .unwrap();
+        stop.cancel();
+        reflecting.await.unwrap().unwrap();
+
+        let mut summary = None;
+        while let Ok(event) = received.try_recv() {
+            if let RuntimeEvent::VoipFinished { job, summary: done } = event
+                && job == prober
+            {
+                summary = Some(done);
+            }
+        }
+        let summary = summary.unwrap();
+        assert!(summary.sent >= 40, "{summary:?}");
+        assert_eq!(summary.lost, 0, "{summary:?}");
+        assert!(summary.score(iptools_core::VoipCodec::G711).unwrap().mos > 4.0);
+    }
+}
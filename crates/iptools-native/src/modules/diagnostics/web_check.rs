@@ -0,0 +1,255 @@
+//! 诊断预设的网页检查：对目标的网页端口各发一次 `GET /`，记下状态码、
+//! `Server` 头和页面标题。443 端口先按系统信任库校验证书完成 TLS 握手，
+//! 记下协议版本与加密套件；证书不通过时只报告握手错误，不再发请求。
+//!
+//! 与扫描器的网页指纹不同，这里检查的是用户指定的目标，证书有问题本身
+//! 就是要报告的结果，因此不跳过校验。
+//!
+//! 设置了 SSH 跳板机时，所有连接都经跳板机发出，见 [`JumpTunnel`]。
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use iptools_core::{
+    JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent, TlsSummary, WebCheckRequest,
+    WebCheckResult, web_port_uses_tls,
+};
+use rustls_platform_verifier::BuilderVerifierExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, timeout_at};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::{self, ClientConfig, pki_types::ServerName};
+use tokio_util::either::Either;
+use tokio_util::sync::CancellationToken;
+
+use crate::utils::{jump::JumpTunnel, web::html_title};
+
+/// 状态行、响应头和标题都在开头，读到这么多就停
+const RESPONSE_LIMIT: usize = 64 * 1024;
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: WebCheckRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    let host = request.target.trim();
+    if host.is_empty() {
+        return send(RuntimeEvent::WebCheckFailed {
+            job,
+            error: RuntimeError::new(RuntimeErrorCode::InvalidRequest, "target cannot be empty"),
+        })
+        .await;
+    }
+    let connector = match connector() {
+        Ok(connector) => connector,
+        Err(error) => {
+            return send(RuntimeEvent::WebCheckFailed {
+                job,
+                error: RuntimeError::new(RuntimeErrorCode::Internal, error),
+            })
+            .await;
+        }
+    };
+    let tunnel = match request.jump_host.as_deref() {
+        Some(jump_host) => {
+            let opened = tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                opened = JumpTunnel::open(jump_host) => opened,
+            };
+            match opened {
+                Ok(tunnel) => Some(tunnel),
+                Err(error) => {
+                    return send(RuntimeEvent::WebCheckFailed {
+                        job,
+                        error: RuntimeError::new(RuntimeErrorCode::Network, error),
+                    })
+                    .await;
+                }
+            }
+        }
+        None => None,
+    };
+    let budget = Duration::from_millis(request.timeout_ms.clamp(500, 30_000));
+    let mut results = Vec::with_capacity(request.ports.len());
+    for port in request.ports {
+        let result = tokio::select! {
+            _ = cancellation.cancelled() => return Ok(()),
+            result = check(&connector, tunnel.as_ref(), host, port, budget) => result,
+        };
+        results.push(result);
+    }
+    send(RuntimeEvent::WebCheckFinished { job, results }).await
+}
+
+fn connector() -> Result<TlsConnector, String> {
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let mut config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_platform_verifier())
+        .map_err(|error| error.to_string())?
+        .with_no_client_auth();
+    // 请求按 HTTP/1.1 手写，不能让服务器选 h2
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+async fn check(
+    connector: &TlsConnector,
+    tunnel: Option<&JumpTunnel>,
+    host: &str,
+    port: u16,
+    budget: Duration,
+) -> WebCheckResult {
+    let mut result = WebCheckResult {
+        port,
+        tls: None,
+        status: None,
+        server: None,
+        title: None,
+        elapsed_ms: None,
+        error: None,
+    };
+    let started = Instant::now();
+    match exchange(connector, tunnel, host, port, budget, &mut result).await {
+        Ok(response) => {
+            result.elapsed_ms = Some(started.elapsed().as_millis() as u64);
+            let (status, server, title) = parse_response(&response);
+            result.status = status;
+            result.server = server;
+            result.title = title;
+            if status.is_none() {
+                result.error = Some("not an HTTP response".into());
+            }
+        }
+        Err(error) => result.error = Some(error),
+    }
+    result
+}
+
+async fn exchange(
+    connector: &TlsConnector,
+    tunnel: Option<&JumpTunnel>,
+    host: &str,
+    port: u16,
+    budget: Duration,
+    result: &mut WebCheckResult,
+) -> Result<Vec<u8>, String> {
+    let deadline = tokio::time::Instant::now() + budget;
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    let connect = async {
+        match tunnel {
+            Some(tunnel) => tunnel.connect(bare, port).await.map(Either::Left),
+            None => TcpStream::connect((bare, port)).await.map(Either::Right),
+        }
+    };
+    let stream = timeout_at(deadline, connect)
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|error| error.to_string())?;
+    if !web_port_uses_tls(port) {
+        return request(stream, host, port, deadline).await;
+    }
+    let name = ServerName::try_from(bare.to_string()).map_err(|error| error.to_string())?;
+    let stream = timeout_at(deadline, connector.connect(name, stream))
+        .await
+        .map_err(|_| "TLS handshake timed out".to_string())?
+        .map_err(|error| error.to_string())?;
+    let (_, session) = stream.get_ref();
+    result.tls = Some(TlsSummary {
+        version: session
+            .protocol_version()
+            .map(|version| format!("{version:?}"))
+            .unwrap_or_default(),
+        cipher: session
+            .negotiated_cipher_suite()
+            .map(|suite| format!("{:?}", suite.suite()))
+            .unwrap_or_default(),
+    });
+    request(stream, host, port, deadline).await
+}
+
+/// 发出请求并读到连接关闭、读满上限或截止时间；截止时已读到的部分照常解析。
+async fn request<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    host: &str,
+    port: u16,
+    deadline: tokio::time::Instant,
+) -> Result<Vec<u8>, String> {
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    let authority = match (bare.contains(':'), port) {
+        (true, 80 | 443) => format!("[{bare}]"),
+        (true, port) => format!("[{bare}]:{port}"),
+        (false, 80 | 443) => bare.to_string(),
+        (false, port) => format!("{bare}:{port}"),
+    };
+    let head = format!(
+        "GET / HTTP/1.1\r\nHost: {authority}\r\nUser-Agent: iptools/{}\r\nAccept: text/html\r\nConnection: close\r\n\r\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    timeout_at(deadline, stream.write_all(head.as_bytes()))
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|error| error.to_string())?;
+
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 8 * 1024];
+    while response.len() < RESPONSE_LIMIT {
+        match timeout_at(deadline, stream.read(&mut buffer)).await {
+            Ok(Ok(0)) => break,
+            Ok(Ok(read)) => response.extend_from_slice(&buffer[..read]),
+            // 不少服务器关连接时不发 close_notify，已有内容就够用
+            Ok(Err(_)) | Err(_) if !response.is_empty() => break,
+            Ok(Err(error)) => return Err(error.to_string()),
+            Err(_) => return Err("timed out".into()),
+        }
+    }
+    Ok(response)
+}
+
+/// 状态码、`Server` 头和页面标题。
+fn parse_response(response: &[u8]) -> (Option<u16>, Option<String>, Option<String>) {
+    let text = String::from_utf8_lossy(response);
+    let (head, body) = text.split_once("\r\n\r\n").unwrap_or((&text, ""));
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .filter(|line| line.starts_with("HTTP/"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok());
+    let server = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("server"))
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    (status, server, html_title(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_server_and_title_come_from_the_response() {
+        let response = b"HTTP/1.1 301 Moved Permanently\r\nserver: nginx/1.24\r\nLocation: https://example.com/\r\n\r\n<html><title>301 Moved</title>";
+        assert_eq!(
+            parse_response(response),
+            (
+                Some(301),
+                Some("nginx/1.24".into()),
+                Some("301 Moved".into())
+            )
+        );
+        assert_eq!(
+            parse_response(b"SSH-2.0-OpenSSH_9.6\r\n"),
+            (None, None, None)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Wi-Fi 监测：按固定间隔查询一块无线网卡当前关联的 AP，把信号、速率、信道和 BSSID
+//! 逐次上报，并附上系统缓存的附近 AP 列表供信道占用图使用；漫游与断连由核心层
+//! 比较相邻两次采样判断。
+
+use iptools_core::{
+    JobId, NearbyNetwork, RuntimeError, RuntimeErrorCode, RuntimeEvent, WifiRequest, WifiSample,
+};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, MissedTickBehavior, interval};
+use tokio_util::sync::CancellationToken;
+
+use super::link_quality::shared_wireless_snapshot;
+use crate::utils::wlan;
+
+pub(crate) async fn run_shared(
+    job: JobId,
+    request: WifiRequest,
+    cancellation: CancellationToken,
+    events: mpsc::Sender<RuntimeEvent>,
+) -> Result<(), String> {
+    let send = |event| {
+        let events = events.clone();
+        async move { events.send(event).await.map_err(|error| error.to_string()) }
+    };
+
+    if request.guid.is_empty() {
+        return send(RuntimeEvent::WifiFailed {
+            job,
+            error: RuntimeError::new(
+                RuntimeErrorCode::InvalidRequest,
+                "no wireless adapter selected",
+            ),
+        })
+        .await;
+    }
+    let (min, max) = iptools_core::WIFI_INTERVAL_SECS_RANGE;
+    let mut ticks = interval(Duration::from_secs(request.interval_secs.clamp(min, max)));
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let started = Instant::now();
+    loop {
+        tokio::select! {
+            _ = cancellation.cancelled() => break,
+            _ = ticks.tick() => {}
+        }
+        let guid = request.guid.clone();
+        // 未关联与平台不支持都查不到无线信息，一律记为未连接
+        let (link, nearby) = tokio::task::spawn_blocking(move || {
+            (
+                wlan::query(&guid).map(shared_wireless_snapshot),
+                wlan::nearby(&guid),
+            )
+        })
+        .await
+        .unwrap_or_default();
+        send(RuntimeEvent::WifiSample {
+            job,
+            sample: WifiSample {
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                link,
+                nearby: nearby.into_iter().filter_map(nearby_network).collect(),
+            },
+        })
+        .await?;
+    }
+    send(RuntimeEvent::WifiFinished { job }).await
+}
+
+/// 不在 2.4/5/6 GHz 的频率无法换算信道，丢弃。
+fn nearby_network(bss: wlan::ScannedBss) -> Option<NearbyNetwork> {
+    let (_, channel) = wlan::band_and_channel(bss.freq_mhz * 1000);
+    (channel != 0).then_some(NearbyNetwork {
+        ssid: bss.ssid,
+        bssid: bss.bssid,
+        channel,
+        frequency_mhz: bss.freq_mhz,
+        rssi_dbm: bss.rssi_dbm,
+    })
+}
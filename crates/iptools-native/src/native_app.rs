@@ -1,105 +1,298 @@
 use anyhow::Result;
 use chrono::Local;
-use iptools_core::{Action, AppModel, Effect, InputEvent, Message};
+use iptools_core::{AppModel, Effect, InputEvent, KioskView, Message, RuntimeEvent, SessionUpdate};
 use iptools_ui::UiState;
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io;
+use std::path::PathBuf;
 
 use crate::{
     config::Config,
     event::{Event, EventHandler},
     frontend,
+    keymap::KeyMap,
+    overrides::OverrideWatcher,
+    record,
     runtime::NativeRuntime,
+    store::HistoryStore,
+    utils::{android, hotplug::InterfaceWatcher, interface_store, power},
 };
 
-const TICK_MS: u64 = 250;
-const TRAFFIC_REFRESH_TICKS: u64 = 4;
-const ADAPTER_REFRESH_TICKS: u64 = 8;
+const TRAFFIC_REFRESH_MS: u64 = 1_000;
+const ADAPTER_REFRESH_MS: u64 = 2_000;
+const POWER_REFRESH_MS: u64 = 30_000;
+const HISTORY_PRUNE_MS: u64 = 3_600_000;
 
-pub async fn run(config_path: Option<String>) -> Result<()> {
-    let mut config = Config::load(config_path.as_deref());
-    let keymap = config.keymap();
-    let mut model = AppModel::default();
-    model.demo = false;
-    model.apply_config(&config);
-    let mut runtime = NativeRuntime::new();
-    dispatch_effects(&mut runtime, &mut config, model.bootstrap_effects())?;
+/// 模型、运行时、配置与历史库，以及定时刷新的进度。终端界面与守护进程共用，
+/// 区别只在输入从哪里来、画面画到哪里去。
+pub(crate) struct Session {
+    pub model: AppModel,
+    runtime: NativeRuntime,
+    config: Config,
+    history: Option<HistoryStore>,
+    interfaces: InterfaceWatcher,
+    overrides: OverrideWatcher,
+    /// 不论当前页面都读取流量计数：守护进程要让流量历史连续。
+    always_poll_traffic: bool,
+    elapsed: u64,
+    last_traffic: u64,
+    last_adapters: u64,
+    last_power: u64,
+    last_prune: u64,
+}
+
+impl Session {
+    /// 读取配置、打开历史库并启动后台模块；`kiosk` 须在首次刷新前生效。
+    pub(crate) fn start(
+        config_path: Option<String>,
+        offline: bool,
+        kiosk: Option<KioskView>,
+    ) -> Result<Self> {
+        let mut config = Config::load(config_path.as_deref());
+        // 在首次仪表盘刷新之前生效，启动时也不会发出公网请求
+        if offline && !config.offline {
+            config.offline = true;
+            config.save();
+        }
+        let history = open_history(&mut config);
+        let mut model = AppModel::default();
+        model.demo = false;
+        model.apply_config(&config);
+        if let Some(history) = &history {
+            restore_history(history, &mut config, &mut model);
+        }
+        model.no_color = frontend::no_color();
+        model.terminal = frontend::terminal_caps();
+        model.show_config_issues(std::mem::take(&mut config.issues));
+        if let Some(view) = kiosk {
+            model.enter_kiosk(view);
+        }
+        model.capabilities = android::capabilities();
+        model.update(Message::PowerSource {
+            on_battery: power::on_battery(),
+        });
+        let overrides = OverrideWatcher::start(config.path());
+        let mut session = Self {
+            model,
+            runtime: NativeRuntime::new().with_config_path(config_path),
+            config,
+            history,
+            interfaces: InterfaceWatcher::start(),
+            overrides,
+            always_poll_traffic: false,
+            elapsed: 0,
+            last_traffic: 0,
+            last_adapters: 0,
+            last_power: 0,
+            last_prune: 0,
+        };
+        let effects = session.model.bootstrap_effects();
+        session.dispatch(effects)?;
+        Ok(session)
+    }
+
+    /// 配置文件的位置；守护进程把远程连接令牌放在它旁边。
+    pub(crate) fn config_path(&self) -> &std::path::Path {
+        self.config.path()
+    }
+
+    pub(crate) fn keymap(&self) -> KeyMap {
+        self.config.keymap()
+    }
+
+    pub(crate) fn always_poll_traffic(mut self) -> Self {
+        self.always_poll_traffic = true;
+        self
+    }
+
+    /// 收取任务结果并按各自的周期刷新。
+    pub(crate) fn tick(&mut self) -> Vec<Effect> {
+        let model = &mut self.model;
+        let mut effects = Vec::new();
+        let tick_ms = model.tick_interval_ms();
+        self.elapsed = self.elapsed.saturating_add(tick_ms);
+        let elapsed = self.elapsed;
+        self.runtime.reap_finished();
+        while let Some(event) = self.runtime.try_recv() {
+            // The problem list keeps what the user can act on; the log keeps the detail.
+            if let Some((job, error)) = event.failure() {
+                tracing::warn!(tool = ?job.tool, generation = job.generation, code = ?error.code, message = %error.message, "tool failed");
+            }
+            if let Some(history) = self.history.as_mut() {
+                record_history(history, &event);
+            }
+            effects.extend(model.update(Message::Runtime(event)));
+        }
+        effects.extend(model.update(Message::Tick(tick_ms)));
+        // Hotplug notifications arrive in bursts; one refresh per tick covers them.
+        if self.interfaces.take_changed() {
+            interface_store::invalidate();
+            effects.extend(model.update(Message::InterfacesChanged));
+        }
+        if let Some(overrides) = self.overrides.take_loaded() {
+            effects.extend(model.update(Message::OverridesLoaded(Box::new(overrides))));
+        }
+        if elapsed - self.last_power >= POWER_REFRESH_MS {
+            self.last_power = elapsed;
+            model.update(Message::PowerSource {
+                on_battery: power::on_battery(),
+            });
+        }
+        if let Some(history) = self.history.as_ref()
+            && elapsed - self.last_prune >= HISTORY_PRUNE_MS
+        {
+            self.last_prune = elapsed;
+            if let Err(error) = history.prune(Local::now().naive_local()) {
+                tracing::warn!(path = %history.path().display(), %error, "failed to prune history database");
+            }
+        }
+        if elapsed - self.last_traffic >= TRAFFIC_REFRESH_MS {
+            self.last_traffic = elapsed;
+            let now = Local::now();
+            effects.extend(model.update(Message::Clock {
+                local: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+                utc_offset_minutes: now.offset().local_minus_utc() / 60,
+            }));
+            // Counters are only read while a page that shows them is visible.
+            if self.always_poll_traffic || model.wants_traffic_polling() {
+                effects.extend(model.refresh_traffic());
+            }
+        }
+        if elapsed - self.last_adapters >= ADAPTER_REFRESH_MS
+            && model.adapters.edit.is_none()
+            && model.wants_adapter_polling()
+        {
+            self.last_adapters = elapsed;
+            effects.extend(model.refresh_adapters());
+        }
+        effects
+    }
 
-    let backend = CrosstermBackend::new(io::stdout());
+    pub(crate) fn input(&mut self, input: InputEvent) -> Vec<Effect> {
+        self.model.update(Message::Input(input))
+    }
+
+    pub(crate) fn dispatch(&mut self, effects: Vec<Effect>) -> Result<()> {
+        dispatch_effects(
+            &mut self.runtime,
+            &mut self.config,
+            self.history.as_ref(),
+            effects,
+        )
+    }
+
+    /// 保存会话但不停止任务（挂起前用）。
+    pub(crate) fn save(&mut self) -> Result<()> {
+        let effects = self.model.shutdown_effects();
+        self.dispatch(effects)
+    }
+
+    /// Stop every job, bounded by the runtime's grace period, and save what
+    /// the session gathered.
+    pub(crate) async fn shutdown(mut self) {
+        shutdown(
+            &mut self.model,
+            &mut self.runtime,
+            &mut self.config,
+            self.history.as_ref(),
+        )
+        .await;
+    }
+}
+
+pub async fn run(
+    config_path: Option<String>,
+    offline: bool,
+    record: Option<PathBuf>,
+    kiosk: Option<KioskView>,
+) -> Result<()> {
+    let mut session = Session::start(config_path, offline, kiosk)?;
+    let keymap = session.keymap();
+
+    let backend = CrosstermBackend::new(record::stdout(record.as_deref())?);
     let mut terminal = Terminal::new(backend)?;
-    let mut events = EventHandler::new(TICK_MS);
+    let mut events = EventHandler::new(session.model.tick_interval_ms());
     let mut ui = UiState::default();
     frontend::enter(&mut terminal)?;
 
-    let mut ticks = 0_u64;
+    let mut resized = false;
     let run_result = async {
-        while model.running {
-            terminal.draw(|frame| iptools_ui::render(frame, &model, &mut ui))?;
-            let mut effects = Vec::new();
-            match events.next().await? {
-                Event::Tick => {
-                    ticks = ticks.saturating_add(1);
-                    runtime.reap_finished();
-                    while let Some(event) = runtime.try_recv() {
-                        effects.extend(model.update(Message::Runtime(event)));
-                    }
-                    effects.extend(model.update(Message::Tick(TICK_MS)));
-                    if ticks.is_multiple_of(TRAFFIC_REFRESH_TICKS) {
-                        effects.extend(model.update(Message::Clock(
-                            Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-                        )));
-                        effects.extend(model.refresh_traffic());
-                    }
-                    if ticks.is_multiple_of(ADAPTER_REFRESH_TICKS) && model.adapters.edit.is_none()
-                    {
-                        effects.extend(model.refresh_adapters());
-                    }
+        while session.model.running {
+            // Only draw when the model reports a visible change or the terminal
+            // was resized; idle ticks leave the previous frame on screen.
+            if std::mem::take(&mut resized) | session.model.take_redraw() {
+                terminal.draw(|frame| iptools_ui::render(frame, &session.model, &mut ui))?;
+                session.model.page_rows = ui.page_rows();
+            }
+            let effects = match events.next().await? {
+                Event::Tick => session.tick(),
+                Event::Key(key) => match frontend::mapped_key(key, &keymap) {
+                    Some(input) => session.input(input),
+                    None => Vec::new(),
+                },
+                Event::Mouse(mouse) => match frontend::mouse_action(mouse, &ui) {
+                    Some(action) => session.input(InputEvent::Action(action)),
+                    None => Vec::new(),
+                },
+                Event::Resize => {
+                    resized = true;
+                    Vec::new()
                 }
-                Event::Key(key) => {
-                    if let Some(input) = frontend::mapped_key(key, &keymap) {
-                        effects.extend(model.update(Message::Input(input)));
-                    }
+                Event::Quit => {
+                    session.model.running = false;
+                    Vec::new()
                 }
-                Event::Mouse(mouse) => {
-                    let action = match mouse.kind {
-                        crossterm::event::MouseEventKind::ScrollUp => Some(Action::Up),
-                        crossterm::event::MouseEventKind::ScrollDown => Some(Action::Down),
-                        crossterm::event::MouseEventKind::Down(
-                            crossterm::event::MouseButton::Left,
-                        ) => ui.hit_test(mouse.column, mouse.row),
-                        _ => None,
-                    };
-                    if let Some(action) = action {
-                        effects.extend(model.update(Message::Input(InputEvent::Action(action))));
-                    }
+                Event::Suspend => {
+                    // A stopped job can still be killed from another shell,
+                    // so save first.
+                    session.save()?;
+                    frontend::suspend(&mut terminal)?;
+                    resized = true;
+                    Vec::new()
                 }
-                Event::Resize => {}
-            }
-            dispatch_effects(&mut runtime, &mut config, effects)?;
+            };
+            session.dispatch(effects)?;
+            events.set_tick_rate(session.model.tick_interval_ms());
         }
         Ok::<(), anyhow::Error>(())
     }
     .await;
 
-    // Restore the user's terminal immediately after Quit. Scanner workers may
-    // still be finishing their current short, blocking OS probe; they no
-    // longer keep the alternate screen visible while structured shutdown
-    // joins them.
-    let exit_result = frontend::exit(&mut terminal);
+    // Stop every job and save what the session gathered before handing the
+    // terminal back.
     events.shutdown().await;
-    runtime.shutdown().await;
+    session.shutdown().await;
+    let exit_result = frontend::exit(&mut terminal);
     run_result?;
     exit_result?;
     Ok(())
 }
 
+/// 退出流程：取消全部任务并等待其结束，把最后的事件交给模型，再保存会话数据。
+/// 此时不再启动新任务，模型产生的非持久化 Effect 直接丢弃。
+async fn shutdown(
+    model: &mut AppModel,
+    runtime: &mut NativeRuntime,
+    config: &mut Config,
+    history: Option<&HistoryStore>,
+) {
+    let mut effects = Vec::new();
+    for event in runtime.shutdown().await {
+        effects.extend(model.update(Message::Runtime(event)));
+    }
+    effects.extend(model.shutdown_effects());
+    for effect in effects {
+        persist(config, history, &effect);
+    }
+}
+
 fn dispatch_effects(
     runtime: &mut NativeRuntime,
     config: &mut Config,
+    history: Option<&HistoryStore>,
     effects: Vec<Effect>,
 ) -> Result<()> {
     for effect in effects {
-        if frontend::persist_effect(config, &effect) {
+        if persist(config, history, &effect) {
             continue;
         }
         runtime.dispatch(effect)?;
@@ -107,6 +300,77 @@ fn dispatch_effects(
     Ok(())
 }
 
+/// 打开配置文件旁的历史库，并把旧配置中的事件与设备清单迁入。
+/// 打不开时返回 `None`，这些数据照旧写进配置文件。
+fn open_history(config: &mut Config) -> Option<HistoryStore> {
+    let opened = HistoryStore::beside(config.path()).and_then(|history| {
+        if history.migrate_session(&mut config.session)? {
+            config.save();
+        }
+        config.session.events.entries = history.events()?;
+        config.session.inventory.devices = history.inventory()?;
+        Ok(history)
+    });
+    match opened {
+        Ok(history) => Some(history),
+        Err(error) => {
+            tracing::warn!(path = %config.path().display(), %error, "history database unavailable; keeping history in the configuration file");
+            None
+        }
+    }
+}
+
+/// 库中的事件与设备清单已由 `apply_config` 交给模型，之后不再随配置保存；
+/// 监视卡片恢复上次运行时的最近结果。
+fn restore_history(history: &HistoryStore, config: &mut Config, model: &mut AppModel) {
+    config.session.events = Default::default();
+    config.session.inventory = Default::default();
+    match history.recent_watch_checks(&model.watch.targets()) {
+        Ok(checks) => {
+            for check in checks {
+                model.watch.record(check);
+            }
+        }
+        Err(error) => {
+            tracing::warn!(path = %history.path().display(), %error, "failed to read watch history");
+        }
+    }
+}
+
+/// 事件日志与设备清单写入历史库，其余持久化 Effect 仍写配置文件。
+fn persist(config: &mut Config, history: Option<&HistoryStore>, effect: &Effect) -> bool {
+    if let (Some(history), Effect::PersistSession(update)) = (history, effect) {
+        let written = match update {
+            SessionUpdate::Events(log) => Some(history.replace_events(&log.entries)),
+            SessionUpdate::Inventory(inventory) => {
+                Some(history.replace_inventory(&inventory.devices))
+            }
+            _ => None,
+        };
+        if let Some(written) = written {
+            if let Err(error) = written {
+                tracing::warn!(path = %history.path().display(), %error, "failed to write history database");
+            }
+            return true;
+        }
+    }
+    frontend::persist_effect(config, effect)
+}
+
+/// 监视结果、测速结果与流量计数在交给模型前写入历史库。
+fn record_history(history: &mut HistoryStore, event: &RuntimeEvent) {
+    let now = Local::now().naive_local();
+    let recorded = match event {
+        RuntimeEvent::WatchChecked { check, .. } => history.record_watch(now, check),
+        RuntimeEvent::PublicSpeedFinished { summary, .. } => history.record_speed(now, summary),
+        RuntimeEvent::TrafficUpdated(rows) => history.record_traffic(now, rows).map(drop),
+        _ => Ok(()),
+    };
+    if let Err(error) = recorded {
+        tracing::warn!(path = %history.path().display(), %error, "failed to write history database");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,7 +379,7 @@ mod tests {
     #[test]
     fn native_dispatch_persists_without_sending_storage_effects_to_runtime() {
         let path = std::env::temp_dir().join(format!(
-            "iptools-native-runner-{}-{}.json",
+            "iptools-native-runner-{}-{}.toml",
             std::process::id(),
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -127,18 +391,112 @@ mod tests {
         dispatch_effects(
             &mut runtime,
             &mut config,
+            None,
             vec![Effect::PersistPreferences(Preferences {
                 language: Language::Zh,
                 theme: iptools_core::ThemeId::Dracula,
                 scan_concurrency: 90,
+                scan_adaptive: true,
+                retention: iptools_core::RetentionLimits::default(),
+                drop_alert_percent: iptools_core::DEFAULT_DROP_ALERT_PERCENT,
+                low_power: iptools_core::LowPowerMode::Auto,
+                offline: false,
+                check_updates: false,
+                color_mode: iptools_core::ColorMode::Standard,
+                chart_style: iptools_core::ChartStyle::Blocks,
+                region_format: iptools_core::RegionFormat::Iso,
+                clock_format: iptools_core::ClockFormat::TwentyFourHour,
+                show_utc: false,
             })],
         )
         .unwrap();
         let saved: iptools_core::ConfigData =
-            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
         assert_eq!(saved.language, Language::Zh);
         assert_eq!(saved.theme, iptools_core::ThemeId::Dracula);
         assert_eq!(saved.scan_concurrency, 90);
         std::fs::remove_file(path).unwrap();
     }
+
+    #[tokio::test]
+    async fn shutdown_saves_the_session_gathered_since_the_last_write() {
+        let path = std::env::temp_dir().join(format!(
+            "iptools-native-shutdown-{}-{}.toml",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let mut config = Config::load(Some(path.to_str().unwrap()));
+        let mut runtime = NativeRuntime::new();
+        let mut model = AppModel::default();
+        model.page = iptools_core::Page::Events;
+        model.events.entries.push(iptools_core::NetworkEvent {
+            at: "2026-01-01 08:00:00".into(),
+            kind: iptools_core::NetworkEventKind::GatewayChanged,
+            subject: "eth0".into(),
+            from: "192.168.1.1".into(),
+            to: "192.168.1.254".into(),
+        });
+
+        shutdown(&mut model, &mut runtime, &mut config, None).await;
+        let saved: iptools_core::ConfigData =
+            toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.session.events.entries, model.events.entries);
+        assert_eq!(saved.session.ui.last_tab, iptools_core::Page::Events as u8);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn events_move_from_the_config_file_into_the_history_database() {
+        let directory = std::env::temp_dir().join(format!(
+            "iptools-native-history-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("config.toml");
+        let event = |subject: &str| iptools_core::NetworkEvent {
+            at: "2026-01-01 08:00:00".into(),
+            kind: iptools_core::NetworkEventKind::GatewayChanged,
+            subject: subject.into(),
+            from: "192.168.1.1".into(),
+            to: "192.168.1.254".into(),
+        };
+        let mut config = Config::load(Some(path.to_str().unwrap()));
+        config.session.events.entries = vec![event("eth0")];
+        config.save();
+
+        let mut config = Config::load(Some(path.to_str().unwrap()));
+        let history = open_history(&mut config).unwrap();
+        let mut model = AppModel::default();
+        model.apply_config(&config);
+        restore_history(&history, &mut config, &mut model);
+        assert_eq!(model.events.entries, [event("eth0")]);
+        let saved = |path: &std::path::Path| {
+            toml::from_str::<iptools_core::ConfigData>(&std::fs::read_to_string(path).unwrap())
+                .unwrap()
+        };
+        assert!(saved(&path).session.events.entries.is_empty());
+
+        dispatch_effects(
+            &mut NativeRuntime::new(),
+            &mut config,
+            Some(&history),
+            vec![Effect::PersistSession(SessionUpdate::Events(
+                iptools_core::EventLogPersist {
+                    entries: vec![event("eth0"), event("wlan0")],
+                },
+            ))],
+        )
+        .unwrap();
+        assert!(saved(&path).session.events.entries.is_empty());
+        assert_eq!(history.events().unwrap(), [event("eth0"), event("wlan0")]);
+        drop(history);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
 }
@@ -0,0 +1,250 @@
+//! 配置文件旁的界面文字与配色覆盖：`locale.toml` 按英文原文替换某种语言下的文字，
+//! `theme.toml` 替换当前主题中的个别颜色。两个文件都是可选的。
+//!
+//! 监视线程用 notify 订阅配置目录（编辑器保存时常以改名替换文件，只盯文件本身会丢事件），
+//! 启动时和每次改动后重新读取，解析结果放进一个槽位，主循环在下一个 tick 取走并交给 core。
+//! 写了一半的文件解析失败时沿用上一次成功读到的内容，错误随结果一起显示；
+//! locale.toml 每次读取后都与界面文字核对一遍（见 [`crate::i18n`]），缺口由底栏提示。
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc},
+    time::Duration,
+};
+
+use iptools_core::{Language, LocaleAudit, LocaleOverrides, Overrides, ThemeOverrides};
+use notify::{RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+
+use crate::config::toml_error;
+
+pub const LOCALE_FILE: &str = "locale.toml";
+pub const THEME_FILE: &str = "theme.toml";
+
+/// 保存一次常触发多个事件（截断、写入、改名），静默这么久后才重新读取。
+const SETTLE: Duration = Duration::from_millis(200);
+
+pub struct OverrideWatcher {
+    loaded: Arc<Mutex<Option<Overrides>>>,
+    /// 丢弃即停止订阅，监视线程随通道关闭退出。
+    _watcher: Option<notify::RecommendedWatcher>,
+}
+
+impl OverrideWatcher {
+    /// 监视 `config` 所在目录；订阅失败时仍读取一次，只是改动要重启才生效。
+    pub fn start(config: &Path) -> Self {
+        let files = OverrideFiles::beside(config);
+        let loaded = Arc::new(Mutex::new(None));
+        let (sender, changes) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event
+                && event.paths.iter().any(|path| is_override_file(path))
+            {
+                let _ = sender.send(());
+            }
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(&files.directory, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        })
+        .inspect_err(|error| {
+            tracing::warn!(%error, directory = %files.directory.display(), "override files are not watched; edits apply after a restart");
+        })
+        .ok();
+        let slot = Arc::clone(&loaded);
+        if let Err(error) = std::thread::Builder::new()
+            .name("iptools-overrides".into())
+            .spawn(move || watch(files, &changes, &slot))
+        {
+            tracing::warn!(%error, "override file watcher failed to start");
+        }
+        Self {
+            loaded,
+            _watcher: watcher,
+        }
+    }
+
+    /// 自上次调用以来重新读取的结果。
+    pub fn take_loaded(&self) -> Option<Overrides> {
+        self.loaded.lock().ok()?.take()
+    }
+}
+
+fn is_override_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == LOCALE_FILE || name == THEME_FILE)
+}
+
+/// 先读一次，之后每批改动读一次，直到订阅随 [`OverrideWatcher`] 一起释放。
+fn watch(mut files: OverrideFiles, changes: &mpsc::Receiver<()>, slot: &Mutex<Option<Overrides>>) {
+    loop {
+        let overrides = files.load();
+        for error in &overrides.errors {
+            tracing::warn!(%error, "override file rejected");
+        }
+        for language in [Language::En, Language::Zh] {
+            let gaps = overrides.audit.get(language);
+            if !gaps.is_empty() {
+                tracing::warn!(
+                    language = language.as_str(),
+                    missing = gaps.missing.len(),
+                    unused = gaps.unused.len(),
+                    "locale file has gaps; `iptools i18n-check` lists them"
+                );
+            }
+        }
+        if let Ok(mut slot) = slot.lock() {
+            *slot = Some(overrides);
+        }
+        if changes.recv().is_err() {
+            return;
+        }
+        while changes.recv_timeout(SETTLE).is_ok() {}
+    }
+}
+
+/// 两个覆盖文件的位置与上一次成功读到的内容。
+pub struct OverrideFiles {
+    directory: PathBuf,
+    locale: LocaleOverrides,
+    theme: ThemeOverrides,
+}
+
+impl OverrideFiles {
+    pub fn beside(config: &Path) -> Self {
+        let directory = match config.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        Self {
+            directory,
+            locale: LocaleOverrides::default(),
+            theme: ThemeOverrides::default(),
+        }
+    }
+
+    pub fn locale_path(&self) -> PathBuf {
+        self.directory.join(LOCALE_FILE)
+    }
+
+    /// 重新读取两个文件；不存在等于没有覆盖，读不了或解析失败则保留上次的内容。
+    pub fn load(&mut self) -> Overrides {
+        let mut errors = Vec::new();
+        let locale_path = self.locale_path();
+        match read(&locale_path) {
+            Ok(locale) => self.locale = locale.unwrap_or_default(),
+            Err(error) => errors.push(format!("{LOCALE_FILE}: {error}")),
+        }
+        match read(&self.directory.join(THEME_FILE)) {
+            Ok(theme) => self.theme = theme.unwrap_or_default(),
+            Err(error) => errors.push(format!("{THEME_FILE}: {error}")),
+        }
+        Overrides {
+            audit: if self.locale.is_empty() {
+                LocaleAudit::default()
+            } else {
+                crate::i18n::audit(&self.locale)
+            },
+            locale: self.locale.clone(),
+            theme: self.theme,
+            errors,
+        }
+    }
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.to_string()),
+    };
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|error| toml_error(&content, &error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iptools_core::Rgb;
+    use std::time::Instant;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("iptools-overrides-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn a_broken_edit_keeps_the_last_good_file() {
+        let directory = temp_dir("load");
+        let mut files = OverrideFiles::beside(&directory.join("config.toml"));
+        assert_eq!(
+            files.load(),
+            Overrides::default(),
+            "both files are optional"
+        );
+
+        fs::write(
+            directory.join(LOCALE_FILE),
+            "[zh-CN]\n\"Dashboard\" = \"总览\"\n",
+        )
+        .unwrap();
+        fs::write(directory.join(THEME_FILE), "cyan = \"#0088cc\"\n").unwrap();
+        let overrides = files.load();
+        assert_eq!(
+            overrides.locale.get(Language::Zh, "Dashboard"),
+            Some("总览")
+        );
+        assert_eq!(overrides.theme.cyan, Some(Rgb(0, 0x88, 0xcc)));
+        assert!(overrides.errors.is_empty());
+
+        fs::write(
+            directory.join(THEME_FILE),
+            "cyan = \"blue\"\nteal = \"#000000\"\n",
+        )
+        .unwrap();
+        let overrides = files.load();
+        assert_eq!(overrides.theme.cyan, Some(Rgb(0, 0x88, 0xcc)));
+        assert_eq!(overrides.errors.len(), 1);
+        assert!(
+            overrides.errors[0].starts_with("theme.toml: "),
+            "{:?}",
+            overrides.errors
+        );
+
+        fs::remove_file(directory.join(LOCALE_FILE)).unwrap();
+        assert!(files.load().locale.is_empty());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn an_edit_on_disk_is_picked_up_without_a_restart() {
+        let directory = temp_dir("watch");
+        let watcher = OverrideWatcher::start(&directory.join("config.toml"));
+        let next = || {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                if let Some(overrides) = watcher.take_loaded() {
+                    return Some(overrides);
+                }
+                if Instant::now() > deadline {
+                    return None;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        };
+        assert_eq!(next(), Some(Overrides::default()), "read once at start");
+        if watcher._watcher.is_none() {
+            // 没有 inotify 等通知机制的环境里只有启动时那一次
+            return;
+        }
+        fs::write(directory.join(THEME_FILE), "red = \"#ff0000\"\n").unwrap();
+        let overrides = next().expect("edit noticed");
+        assert_eq!(overrides.theme.red, Some(Rgb(255, 0, 0)));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
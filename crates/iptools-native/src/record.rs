@@ -0,0 +1,165 @@
+//! `--record`：把会话的终端输出连同时间写成 asciicast v2 文件，
+//! 可用 `asciinema play` 回放，给偶发问题留下可复现的现场。
+//!
+//! 第一行是头部 JSON，之后每行一个 `[秒, "o", 输出]` 事件；终端尺寸变化记为
+//! `"r"` 事件。ratatui 每画完一帧刷新一次，所以一帧正好是一个事件。
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// 终端写入器：原样转发，开启录制时另把每次刷新之间的输出记成一个事件。
+pub struct Tee<W> {
+    inner: W,
+    recording: Option<Recording>,
+}
+
+impl<W: Write> Tee<W> {
+    pub fn new(inner: W, recording: Option<Recording>) -> Self {
+        Self { inner, recording }
+    }
+}
+
+/// 界面使用的标准输出；给出路径时同时录制，文件按当前终端尺寸开头。
+pub fn stdout(path: Option<&Path>) -> anyhow::Result<Tee<io::Stdout>> {
+    let recording =
+        match path {
+            Some(path) => {
+                let size = crossterm::terminal::size().unwrap_or((80, 24));
+                Some(Recording::create(path, size).map_err(|error| {
+                    anyhow::anyhow!("cannot record to {}: {error}", path.display())
+                })?)
+            }
+            None => None,
+        };
+    Ok(Tee::new(io::stdout(), recording))
+}
+
+impl<W: Write> Write for Tee<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(recording) = &mut self.recording {
+            recording.pending.extend_from_slice(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        let size = crossterm::terminal::size().ok();
+        // 录制失败（磁盘满等）只停录，不影响正在进行的诊断。
+        if let Some(Err(error)) = self
+            .recording
+            .as_mut()
+            .map(|recording| recording.frame(size))
+        {
+            tracing::warn!(%error, "terminal recording stopped");
+            self.recording = None;
+        }
+        Ok(())
+    }
+}
+
+/// 一个正在写入的 asciicast 文件。
+pub struct Recording {
+    file: BufWriter<File>,
+    started: Instant,
+    size: (u16, u16),
+    /// 尚未成帧的输出；末尾可能是被拆开的半个 UTF-8 字符。
+    pending: Vec<u8>,
+}
+
+impl Recording {
+    pub fn create(path: &Path, (width, height): (u16, u16)) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let header = serde_json::json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": timestamp,
+            "title": format!("iptools {}", env!("CARGO_PKG_VERSION")),
+            "env": { "TERM": std::env::var("TERM").unwrap_or_default() },
+        });
+        writeln!(file, "{header}")?;
+        file.flush()?;
+        Ok(Self {
+            file,
+            started: Instant::now(),
+            size: (width, height),
+            pending: Vec::new(),
+        })
+    }
+
+    fn frame(&mut self, size: Option<(u16, u16)>) -> io::Result<()> {
+        let time = self.started.elapsed().as_secs_f64();
+        if let Some(size) = size.filter(|size| *size != self.size) {
+            self.size = size;
+            self.event(time, "r", &format!("{}x{}", size.0, size.1))?;
+        }
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if complete > 0 {
+            let output = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+            self.pending.drain(..complete);
+            self.event(time, "o", &output)?;
+        }
+        // 每帧落盘，进程被杀时已录下的部分仍可回放。
+        self.file.flush()
+    }
+
+    fn event(&mut self, time: f64, kind: &str, data: &str) -> io::Result<()> {
+        let line = serde_json::to_string(&((time * 1_000_000.0).round() / 1_000_000.0, kind, data))
+            .map_err(io::Error::other)?;
+        writeln!(self.file, "{line}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_become_events_and_split_characters_wait() {
+        let path = std::env::temp_dir().join(format!("iptools-record-{}.cast", std::process::id()));
+        let mut recording = Recording::create(&path, (80, 24)).unwrap();
+        recording
+            .pending
+            .extend_from_slice("延迟 \x1b[1m".as_bytes());
+        recording.pending.extend_from_slice(&"迟".as_bytes()[..1]);
+        recording.frame(Some((80, 24))).unwrap();
+        recording.pending.extend_from_slice(&"迟".as_bytes()[1..]);
+        recording.frame(Some((100, 30))).unwrap();
+        drop(recording);
+
+        let cast = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines = cast
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "延迟 \x1b[1m");
+        assert_eq!(lines[2][1], "r");
+        assert_eq!(lines[2][2], "100x30");
+        assert_eq!(lines[3][2], "迟");
+        assert!(lines[1][0].as_f64().unwrap() <= lines[3][0].as_f64().unwrap());
+    }
+
+    #[test]
+    fn tee_passes_output_through_without_recording() {
+        let mut tee = Tee::new(Vec::new(), None);
+        tee.write_all(b"frame").unwrap();
+        tee.flush().unwrap();
+        assert_eq!(tee.inner, b"frame");
+    }
+}
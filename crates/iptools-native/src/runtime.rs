@@ -1,16 +1,23 @@
 //! Structured lifecycle management for native background jobs.
 
 mod adapter_edit;
+mod asn;
+mod audit;
+mod bluetooth;
+mod chart;
+mod cve;
 mod dashboard;
 mod diagnostics;
+mod doctor;
 mod network_read;
 mod port_scan;
 mod scanner;
+mod update;
+mod watch;
 
-use std::{collections::HashMap, future::Future};
+use std::{collections::HashMap, future::Future, time::Duration};
 
 use iptools_core::{Effect, JobId, RuntimeEvent};
-use sysinfo::Networks;
 use tokio::{
     sync::{Semaphore, mpsc},
     task::JoinSet,
@@ -18,6 +25,9 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 
 const EVENT_CAPACITY: usize = 512;
+/// How long quitting waits for cancelled jobs before aborting them. Scanner
+/// workers may be inside a short blocking OS probe when the token fires.
+const SHUTDOWN_GRACE: Duration = Duration::from_millis(1_500);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskPhase {
@@ -51,10 +61,12 @@ pub struct NativeRuntime {
     cancellations: HashMap<JobId, CancellationToken>,
     event_tx: mpsc::Sender<RuntimeEvent>,
     event_rx: mpsc::Receiver<RuntimeEvent>,
-    dashboard_networks: Networks,
-    dashboard_sample: Option<dashboard::TrafficSample>,
+    dashboard_counters: crate::utils::counters::CounterSource,
+    dashboard_sample: std::sync::Arc<std::sync::Mutex<Option<dashboard::TrafficSample>>>,
     network_sampler: network_read::NetworkSampler,
     adapter_gate: std::sync::Arc<Semaphore>,
+    /// 自我诊断检查的配置文件，与启动时 `--config` 相同。
+    config_path: Option<String>,
 }
 
 impl Default for NativeRuntime {
@@ -71,13 +83,19 @@ impl NativeRuntime {
             cancellations: HashMap::new(),
             event_tx,
             event_rx,
-            dashboard_networks: Networks::new_with_refreshed_list(),
-            dashboard_sample: None,
+            dashboard_counters: crate::utils::counters::CounterSource::new(),
+            dashboard_sample: Default::default(),
             network_sampler: network_read::NetworkSampler::new(),
             adapter_gate: std::sync::Arc::new(Semaphore::new(1)),
+            config_path: None,
         }
     }
 
+    pub fn with_config_path(mut self, config_path: Option<String>) -> Self {
+        self.config_path = config_path;
+        self
+    }
+
     /// Execute a shared effect through the native runtime boundary.
     ///
     /// During vertical migration this deliberately accepts only effects whose
@@ -108,6 +126,14 @@ impl NativeRuntime {
                 self.cancel(job);
                 Ok(())
             }
+            Effect::StartBluetoothScan { job, request } => {
+                self.spawn_bluetooth_scan(job, request);
+                Ok(())
+            }
+            Effect::StopBluetoothScan(job) => {
+                self.cancel(job);
+                Ok(())
+            }
             Effect::StartPortScan { job, request } => {
                 self.spawn_port_scan(job, request);
                 Ok(())
@@ -152,10 +178,173 @@ impl NativeRuntime {
                 self.cancel(job);
                 Ok(())
             }
+            Effect::StartMtu { job, request } => {
+                self.spawn_mtu(job, request);
+                Ok(())
+            }
+            Effect::StopMtu(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartDscp { job, request } => {
+                self.spawn_dscp(job, request);
+                Ok(())
+            }
+            Effect::StopDscp(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartMulticast { job, request } => {
+                self.spawn_multicast(job, request);
+                Ok(())
+            }
+            Effect::StopMulticast(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartUpnp { job, request } => {
+                self.spawn_upnp(job, request);
+                Ok(())
+            }
+            Effect::StopUpnp(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartNat { job, request } => {
+                self.spawn_nat(job, request);
+                Ok(())
+            }
+            Effect::StopNat(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartIpv6 { job, request } => {
+                self.spawn_ipv6(job, request);
+                Ok(())
+            }
+            Effect::StopIpv6(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartDnsBench { job, request } => {
+                self.spawn_dns_bench(job, request);
+                Ok(())
+            }
+            Effect::StopDnsBench(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartDnsFilter { job, request } => {
+                self.spawn_dns_filter(job, request);
+                Ok(())
+            }
+            Effect::StopDnsFilter(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartMail { job, request } => {
+                self.spawn_mail(job, request);
+                Ok(())
+            }
+            Effect::StopMail(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartVoip { job, request } => {
+                self.spawn_voip(job, request);
+                Ok(())
+            }
+            Effect::StopVoip(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartGaming { job, request } => {
+                self.spawn_gaming(job, request);
+                Ok(())
+            }
+            Effect::StopGaming(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartCloud { job, request } => {
+                self.spawn_cloud(job, request);
+                Ok(())
+            }
+            Effect::StopCloud(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartWifi { job, request } => {
+                self.spawn_wifi(job, request);
+                Ok(())
+            }
+            Effect::StopWifi(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartAbTest { job, request } => {
+                self.spawn_ab_test(job, request);
+                Ok(())
+            }
+            Effect::StopAbTest(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::StartWatch { job, request } => {
+                self.spawn_watch(job, request);
+                Ok(())
+            }
             Effect::StopPortScan(job) => {
                 self.cancel(job);
                 Ok(())
             }
+            Effect::CheckUpdate {
+                job,
+                use_system_proxy,
+            } => {
+                self.spawn_update_check(job, use_system_proxy);
+                Ok(())
+            }
+            Effect::RunDoctor { job } => {
+                self.spawn_doctor(job);
+                Ok(())
+            }
+            Effect::StartWebCheck { job, request } => {
+                self.spawn_web_check(job, request);
+                Ok(())
+            }
+            Effect::StopWebCheck(job) => {
+                self.cancel(job);
+                Ok(())
+            }
+            Effect::ExportChart { job, chart, format } => {
+                self.spawn_chart_export(job, chart, format);
+                Ok(())
+            }
+            Effect::ProbeDhcp { job } => {
+                self.spawn_dhcp_probe(job);
+                Ok(())
+            }
+            Effect::ExportAudit { job, markdown } => {
+                self.spawn_audit_export(job, markdown);
+                Ok(())
+            }
+            Effect::LookupAsn {
+                job,
+                addresses,
+                use_system_proxy,
+            } => {
+                self.spawn_asn_lookup(job, addresses, use_system_proxy);
+                Ok(())
+            }
+            Effect::LookupCves {
+                job,
+                software,
+                use_system_proxy,
+            } => {
+                self.spawn_cve_lookup(job, software, use_system_proxy);
+                Ok(())
+            }
             other => Err(RuntimeDispatchError::UnsupportedEffect(effect_name(&other))),
         }
     }
@@ -236,36 +425,54 @@ impl NativeRuntime {
         }
     }
 
-    pub async fn shutdown(&mut self) {
+    /// Cancel every job and wait up to [`SHUTDOWN_GRACE`] for them to join;
+    /// stragglers are then aborted. Returns the events still queued or sent
+    /// while stopping, so the caller can record final results.
+    pub async fn shutdown(&mut self) -> Vec<RuntimeEvent> {
         for token in self.cancellations.values() {
             token.cancel();
         }
         self.cancellations.clear();
-        while !self.tasks.is_empty() {
-            tokio::select! {
-                result = self.tasks.join_next() => {
-                    match result {
-                        Some(Ok(task)) => {
-                            if let Err(error) = task.result {
-                                tracing::warn!(tool = ?task.job.tool, generation = task.job.generation, %error, "runtime job failed during shutdown");
+        let mut events = Vec::new();
+        let drained = tokio::time::timeout(SHUTDOWN_GRACE, async {
+            while !self.tasks.is_empty() {
+                tokio::select! {
+                    result = self.tasks.join_next() => {
+                        match result {
+                            Some(Ok(task)) => {
+                                if let Err(error) = task.result {
+                                    tracing::warn!(tool = ?task.job.tool, generation = task.job.generation, %error, "runtime job failed during shutdown");
+                                }
                             }
+                            Some(Err(error)) => {
+                                tracing::warn!(%error, "runtime job failed to join during shutdown");
+                            }
+                            None => break,
                         }
-                        Some(Err(error)) => {
-                            tracing::warn!(%error, "runtime job failed to join during shutdown");
-                        }
-                        None => break,
                     }
-                }
-                // A producer may already be awaiting capacity when shutdown
-                // cancels it. Keep draining until every owned task has joined,
-                // otherwise a full bounded queue can deadlock terminal exit.
-                event = self.event_rx.recv() => {
-                    if event.is_none() {
-                        break;
+                    // A producer may already be awaiting capacity when shutdown
+                    // cancels it. Keep draining until every owned task has joined,
+                    // otherwise a full bounded queue can deadlock terminal exit.
+                    event = self.event_rx.recv() => match event {
+                        Some(event) => events.push(event),
+                        None => break,
                     }
                 }
             }
+        })
+        .await;
+        if drained.is_err() {
+            tracing::warn!(
+                remaining = self.tasks.len(),
+                "runtime jobs did not stop in time; aborting"
+            );
+            self.tasks.abort_all();
+            while self.tasks.join_next().await.is_some() {}
+        }
+        while let Ok(event) = self.event_rx.try_recv() {
+            events.push(event);
         }
+        events
     }
 }
 
@@ -273,6 +480,10 @@ fn effect_name(effect: &Effect) -> &'static str {
     match effect {
         Effect::PersistPreferences(_) => "persist-preferences",
         Effect::PersistSession(_) => "persist-session",
+        Effect::PersistPublicIp(_) => "persist-public-ip",
+        Effect::PersistTabs(_) => "persist-tabs",
+        Effect::PersistWatch(_) => "persist-watch",
+        Effect::PersistJumpHost(_) => "persist-jump-host",
         Effect::PersistAdapterEdit { .. } => "persist-adapter-edit",
         Effect::RefreshDashboard { .. } => "refresh-dashboard",
         Effect::RefreshAdapters { .. } => "refresh-adapters",
@@ -280,6 +491,8 @@ fn effect_name(effect: &Effect) -> &'static str {
         Effect::ApplyAdapterConfig { .. } => "apply-adapter-config",
         Effect::StartScan { .. } => "start-scan",
         Effect::CancelScan(_) => "cancel-scan",
+        Effect::StartBluetoothScan { .. } => "start-bluetooth-scan",
+        Effect::StopBluetoothScan(_) => "stop-bluetooth-scan",
         Effect::StartPing { .. } => "start-ping",
         Effect::StopPing(_) => "stop-ping",
         Effect::StartTrace { .. } => "start-trace",
@@ -292,6 +505,44 @@ fn effect_name(effect: &Effect) -> &'static str {
         Effect::StopLinkQuality(_) => "stop-link-quality",
         Effect::StartLanSpeed { .. } => "start-lan-speed",
         Effect::StopLanSpeed(_) => "stop-lan-speed",
+        Effect::StartMtu { .. } => "start-mtu",
+        Effect::StopMtu(_) => "stop-mtu",
+        Effect::StartDscp { .. } => "start-dscp",
+        Effect::StopDscp(_) => "stop-dscp",
+        Effect::StartMulticast { .. } => "start-multicast",
+        Effect::StopMulticast(_) => "stop-multicast",
+        Effect::StartUpnp { .. } => "start-upnp",
+        Effect::StopUpnp(_) => "stop-upnp",
+        Effect::StartNat { .. } => "start-nat",
+        Effect::StopNat(_) => "stop-nat",
+        Effect::StartIpv6 { .. } => "start-ipv6",
+        Effect::StopIpv6(_) => "stop-ipv6",
+        Effect::StartDnsBench { .. } => "start-dns-bench",
+        Effect::StopDnsBench(_) => "stop-dns-bench",
+        Effect::StartDnsFilter { .. } => "start-dns-filter",
+        Effect::StopDnsFilter(_) => "stop-dns-filter",
+        Effect::StartMail { .. } => "start-mail",
+        Effect::StopMail(_) => "stop-mail",
+        Effect::StartVoip { .. } => "start-voip",
+        Effect::StopVoip(_) => "stop-voip",
+        Effect::StartGaming { .. } => "start-gaming",
+        Effect::StopGaming(_) => "stop-gaming",
+        Effect::StartCloud { .. } => "start-cloud",
+        Effect::StopCloud(_) => "stop-cloud",
+        Effect::StartWifi { .. } => "start-wifi",
+        Effect::StopWifi(_) => "stop-wifi",
+        Effect::StartAbTest { .. } => "start-ab-test",
+        Effect::StopAbTest(_) => "stop-ab-test",
+        Effect::CheckUpdate { .. } => "check-update",
+        Effect::RunDoctor { .. } => "run-doctor",
+        Effect::StartWebCheck { .. } => "start-web-check",
+        Effect::StopWebCheck(_) => "stop-web-check",
+        Effect::ExportChart { .. } => "export-chart",
+        Effect::ProbeDhcp { .. } => "probe-dhcp",
+        Effect::ExportAudit { .. } => "export-audit",
+        Effect::LookupAsn { .. } => "lookup-asn",
+        Effect::LookupCves { .. } => "lookup-cves",
+        Effect::StartWatch { .. } => "start-watch",
     }
 }
 
@@ -337,8 +588,13 @@ mod tests {
                 events
                     .send(RuntimeEvent::ScanProgress {
                         job,
-                        current,
-                        total: EVENT_CAPACITY as u64 + 1,
+                        progress: iptools_core::ScanProgress {
+                            probe: iptools_core::PhaseProgress {
+                                done: current,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
                     })
                     .await
                     .map_err(|error| RuntimeTaskError::Operation(error.to_string()))?;
@@ -376,8 +632,13 @@ mod tests {
                 events
                     .send(RuntimeEvent::ScanProgress {
                         job,
-                        current,
-                        total: EVENT_CAPACITY as u64 + 1,
+                        progress: iptools_core::ScanProgress {
+                            probe: iptools_core::PhaseProgress {
+                                done: current,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
                     })
                     .await
                     .map_err(|error| RuntimeTaskError::Operation(error.to_string()))?;
@@ -393,4 +654,34 @@ mod tests {
             .expect("shutdown must drain backpressured producers");
         assert!(runtime.tasks.is_empty());
     }
+
+    #[tokio::test]
+    async fn shutdown_returns_final_events_and_aborts_stuck_jobs() {
+        let mut runtime = NativeRuntime::new();
+        let scan = JobId {
+            tool: ToolKind::Scanner,
+            generation: 1,
+        };
+        runtime.spawn(scan, move |token, events| async move {
+            token.cancelled().await;
+            events
+                .send(RuntimeEvent::ScanCancelled { job: scan })
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+        let stuck = JobId {
+            tool: ToolKind::Ping,
+            generation: 2,
+        };
+        runtime.spawn(stuck, |_, _| std::future::pending());
+
+        let events = tokio::time::timeout(SHUTDOWN_GRACE * 2, runtime.shutdown())
+            .await
+            .expect("the grace period bounds shutdown");
+        assert!(matches!(
+            events.as_slice(),
+            [RuntimeEvent::ScanCancelled { job }] if *job == scan
+        ));
+        assert!(runtime.tasks.is_empty());
+    }
 }
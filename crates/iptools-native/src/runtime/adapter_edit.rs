@@ -21,6 +21,8 @@ impl NativeRuntime {
             let result = tokio::task::spawn_blocking(move || apply(request))
                 .await
                 .map_err(|error| RuntimeTaskError::Operation(error.to_string()))?;
+            // 改了地址或 DHCP，不等热插拔通知就让下一次读取重新枚举。
+            crate::utils::interface_store::invalidate();
             let event = match result {
                 Ok(outcome) => RuntimeEvent::AdapterConfigFinished { job, outcome },
                 Err(error) => RuntimeEvent::AdapterConfigFailed { job, error },
@@ -0,0 +1,225 @@
+//! 用 RIPEstat 公开接口查询路由跟踪各跳的宣告前缀、ASN、持有者与一条 AS 路径。
+//!
+//! 每个地址依次查 network-info（前缀与起源 AS）、as-overview（持有者名称）和
+//! looking-glass（路由收集器看到的 AS 路径）；后两项查不到只留空，不算失败。
+
+use iptools_core::{AsnOrigin, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::{NativeRuntime, RuntimeTaskError};
+
+const RIPESTAT: &str = "https://stat.ripe.net/data";
+
+#[derive(Debug, Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct NetworkInfo {
+    #[serde(default)]
+    asns: Vec<String>,
+    #[serde(default)]
+    prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AsOverview {
+    #[serde(default)]
+    holder: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookingGlass {
+    #[serde(default)]
+    rrcs: Vec<Collector>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Collector {
+    #[serde(default)]
+    peers: Vec<CollectorPeer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectorPeer {
+    #[serde(default)]
+    as_path: String,
+}
+
+impl NativeRuntime {
+    pub(super) fn spawn_asn_lookup(
+        &mut self,
+        job: JobId,
+        addresses: Vec<String>,
+        use_system_proxy: bool,
+    ) {
+        self.spawn(job, move |token, events| async move {
+            let send = |event| {
+                let events = events.clone();
+                async move {
+                    events
+                        .send(event)
+                        .await
+                        .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+                }
+            };
+            let client = match client(use_system_proxy) {
+                Ok(client) => client,
+                Err(error) => return send(RuntimeEvent::AsnLookupFailed { job, error }).await,
+            };
+            let mut failure = None;
+            let mut found = false;
+            for address in addresses {
+                let result = tokio::select! {
+                    _ = token.cancelled() => return Ok(()),
+                    result = lookup(&client, &address) => result,
+                };
+                match result {
+                    Ok(origin) => {
+                        found = true;
+                        send(RuntimeEvent::AsnFound { job, origin }).await?;
+                    }
+                    Err(error) => failure = Some(error),
+                }
+            }
+            // 部分地址查到即算完成；全部失败才报错，通常是网络不通或被限流。
+            match failure.filter(|_| !found) {
+                Some(error) => send(RuntimeEvent::AsnLookupFailed { job, error }).await,
+                None => send(RuntimeEvent::AsnLookupFinished { job }).await,
+            }
+        });
+    }
+}
+
+fn client(use_system_proxy: bool) -> Result<reqwest::Client, RuntimeError> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .user_agent(concat!("iptools/", env!("CARGO_PKG_VERSION")));
+    if !use_system_proxy {
+        builder = builder.no_proxy();
+    }
+    builder
+        .build()
+        .map_err(|error| RuntimeError::new(RuntimeErrorCode::Internal, error.to_string()))
+}
+
+async fn lookup(client: &reqwest::Client, address: &str) -> Result<AsnOrigin, RuntimeError> {
+    let info: NetworkInfo = fetch(client, "network-info", address).await?;
+    let asn = info.asns.iter().find_map(|asn| asn.parse::<u32>().ok());
+    let (holder, path) = match asn {
+        Some(asn) => {
+            let resource = format!("AS{asn}");
+            let holder = fetch::<AsOverview>(client, "as-overview", &resource)
+                .await
+                .ok()
+                .and_then(|overview| overview.holder);
+            let path = match &info.prefix {
+                Some(prefix) => fetch::<LookingGlass>(client, "looking-glass", prefix)
+                    .await
+                    .map(|glass| shortest_path(&glass, asn))
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+            (holder, path)
+        }
+        None => (None, Vec::new()),
+    };
+    Ok(AsnOrigin {
+        address: address.to_string(),
+        prefix: info.prefix,
+        asn,
+        holder,
+        path,
+    })
+}
+
+async fn fetch<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    endpoint: &str,
+    resource: &str,
+) -> Result<T, RuntimeError> {
+    let network =
+        |error: reqwest::Error| RuntimeError::new(RuntimeErrorCode::Network, error.to_string());
+    let body = client
+        // 资源只会是地址、前缀或 ASN，无需转义。
+        .get(format!(
+            "{RIPESTAT}/{endpoint}/data.json?resource={resource}&sourceapp=iptools"
+        ))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(network)?
+        .text()
+        .await
+        .map_err(network)?;
+    parse(&body)
+}
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, RuntimeError> {
+    serde_json::from_str::<Envelope<T>>(body)
+        .map(|envelope| envelope.data)
+        .map_err(|error| {
+            RuntimeError::new(
+                RuntimeErrorCode::Network,
+                format!("unexpected RIPEstat response: {error}"),
+            )
+        })
+}
+
+/// 各收集器对端看到的路径里取最短的一条；AS 集合（`{…}`）等非数字段跳过，
+/// 路径必须止于起源 AS。
+fn shortest_path(glass: &LookingGlass, origin: u32) -> Vec<u32> {
+    glass
+        .rrcs
+        .iter()
+        .flat_map(|collector| &collector.peers)
+        .map(|peer| {
+            let mut path: Vec<u32> = peer
+                .as_path
+                .split_whitespace()
+                .filter_map(|asn| asn.parse().ok())
+                .collect();
+            // 路径预置（prepend）会重复同一 AS，只留一次。
+            path.dedup();
+            path
+        })
+        .filter(|path| path.last() == Some(&origin))
+        .min_by_key(Vec::len)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ripestat_answers_reduce_to_prefix_origin_and_shortest_path() {
+        let info: NetworkInfo =
+            parse(r#"{"status":"ok","data":{"asns":["3333"],"prefix":"193.0.0.0/21"}}"#).unwrap();
+        assert_eq!(info.asns, ["3333"]);
+        assert_eq!(info.prefix.as_deref(), Some("193.0.0.0/21"));
+        let unrouted: NetworkInfo = parse(r#"{"data":{"asns":[],"prefix":null}}"#).unwrap();
+        assert!(unrouted.asns.is_empty() && unrouted.prefix.is_none());
+
+        let overview: AsOverview =
+            parse(r#"{"data":{"holder":"RIPE-NCC-AS","announced":true}}"#).unwrap();
+        assert_eq!(overview.holder.as_deref(), Some("RIPE-NCC-AS"));
+
+        let glass: LookingGlass = parse(
+            r#"{"data":{"rrcs":[
+                {"rrc":"RRC00","peers":[
+                    {"as_path":"34854 6939 1299 3333 3333","asn_origin":"3333"},
+                    {"as_path":"64500 {64501,64502}","asn_origin":"64502"}
+                ]},
+                {"rrc":"RRC01","peers":[{"as_path":"8492 3333","asn_origin":"3333"}]}
+            ]}}"#,
+        )
+        .unwrap();
+        assert_eq!(shortest_path(&glass, 3333), [8492, 3333]);
+        assert!(shortest_path(&glass, 15169).is_empty());
+
+        assert!(parse::<NetworkInfo>("<html>rate limited</html>").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+//! 安全审计的两个副作用：广播探测网段里的 DHCP 服务器，以及把报告写成 Markdown 文件。
+
+use std::time::Duration;
+
+use iptools_core::{JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent};
+
+use super::{NativeRuntime, RuntimeTaskError};
+use crate::utils::dhcp;
+
+/// 收集 DHCPOFFER 的窗口；服务器通常在一秒内应答，慢的嵌入式设备也不超过两秒。
+const DHCP_WAIT: Duration = Duration::from_secs(3);
+
+impl NativeRuntime {
+    pub(super) fn spawn_dhcp_probe(&mut self, job: JobId) {
+        self.spawn(job, move |token, events| async move {
+            let event = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                result = tokio::task::spawn_blocking(|| dhcp::discover(DHCP_WAIT)) => {
+                    match result.map_err(|error| RuntimeTaskError::Operation(error.to_string()))? {
+                        Ok(servers) => RuntimeEvent::DhcpProbeFinished { job, servers },
+                        Err(error) => RuntimeEvent::DhcpProbeFailed { job, error },
+                    }
+                }
+            };
+            events
+                .send(event)
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+    }
+
+    pub(super) fn spawn_audit_export(&mut self, job: JobId, markdown: String) {
+        self.spawn(job, move |token, events| async move {
+            // 带时间的文件名，重复导出不会覆盖上一份。
+            let path = std::env::current_dir().unwrap_or_default().join(format!(
+                "iptools-audit-{}.md",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ));
+            let event = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                result = tokio::task::spawn_blocking(move || std::fs::write(&path, markdown).map(|()| path)) => {
+                    match result.map_err(|error| RuntimeTaskError::Operation(error.to_string()))? {
+                        Ok(path) => RuntimeEvent::AuditExported {
+                            job,
+                            path: path.display().to_string(),
+                        },
+                        Err(error) => RuntimeEvent::AuditExportFailed {
+                            job,
+                            error: RuntimeError::new(RuntimeErrorCode::Internal, error.to_string()),
+                        },
+                    }
+                }
+            };
+            events
+                .send(event)
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+    }
+}
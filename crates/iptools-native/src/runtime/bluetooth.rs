@@ -0,0 +1,122 @@
+//! Native Bluetooth LE scan effect handler.
+//!
+//! 只收广播、不连接设备；同一设备每条广播都重新上报一次，由核心层按地址合并。
+//! 未启用 `bluetooth` feature 的构建直接报告失败（核心层在这种构建里不会提供该视图）。
+
+use iptools_core::{BluetoothRequest, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::{NativeRuntime, RuntimeTaskError};
+
+impl NativeRuntime {
+    pub(super) fn spawn_bluetooth_scan(&mut self, job: JobId, request: BluetoothRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            let event = match scan(job, request, cancellation, &events).await {
+                Ok(()) => RuntimeEvent::BluetoothScanFinished { job },
+                Err(error) => RuntimeEvent::BluetoothScanFailed { job, error },
+            };
+            events
+                .send(event)
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+    }
+}
+
+#[cfg(feature = "bluetooth")]
+async fn scan(
+    job: JobId,
+    request: BluetoothRequest,
+    cancellation: CancellationToken,
+    events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), RuntimeError> {
+    use btleplug::api::{BDAddr, Central, CentralEvent, Manager as _, Peripheral as _, ScanFilter};
+    use btleplug::platform::Manager;
+    use futures::StreamExt;
+    use tokio::time::{Duration, Instant, sleep};
+
+    let unavailable = |error: btleplug::Error| {
+        RuntimeError::new(
+            RuntimeErrorCode::Internal,
+            format!("Bluetooth unavailable: {error}"),
+        )
+    };
+    let manager = Manager::new().await.map_err(unavailable)?;
+    let adapter = manager
+        .adapters()
+        .await
+        .map_err(unavailable)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| RuntimeError::new(RuntimeErrorCode::Internal, "no Bluetooth adapter"))?;
+    let mut advertisements = adapter.events().await.map_err(unavailable)?;
+    adapter
+        .start_scan(ScanFilter::default())
+        .await
+        .map_err(|error| {
+            RuntimeError::new(RuntimeErrorCode::PermissionDenied, error.to_string())
+        })?;
+
+    let started = Instant::now();
+    let deadline = sleep(Duration::from_secs(request.duration_secs.max(1)));
+    tokio::pin!(deadline);
+    loop {
+        let id = tokio::select! {
+            _ = cancellation.cancelled() => break,
+            _ = &mut deadline => break,
+            event = advertisements.next() => match event {
+                Some(
+                    CentralEvent::DeviceDiscovered(id)
+                    | CentralEvent::DeviceUpdated(id)
+                    | CentralEvent::ManufacturerDataAdvertisement { id, .. },
+                ) => id,
+                Some(_) => continue,
+                None => break,
+            },
+        };
+        // 设备可能在两次广播之间已从适配器缓存中移除，跳过即可
+        let Ok(peripheral) = adapter.peripheral(&id).await else {
+            continue;
+        };
+        let Ok(Some(properties)) = peripheral.properties().await else {
+            continue;
+        };
+        // macOS 不公开 MAC 地址，只能用系统分配的标识
+        let address = if properties.address == BDAddr::default() {
+            id.to_string()
+        } else {
+            properties.address.to_string()
+        };
+        let device = iptools_core::BluetoothDevice {
+            address,
+            name: properties.local_name.filter(|name| !name.trim().is_empty()),
+            rssi_dbm: properties.rssi.map(i32::from),
+            tx_power_dbm: properties.tx_power_level.map(i32::from),
+            manufacturer_id: properties.manufacturer_data.keys().min().copied(),
+            last_seen_ms: started.elapsed().as_millis() as u64,
+        };
+        if events
+            .send(RuntimeEvent::BluetoothDeviceSeen { job, device })
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+    let _ = adapter.stop_scan().await;
+    Ok(())
+}
+
+#[cfg(not(feature = "bluetooth"))]
+async fn scan(
+    _job: JobId,
+    _request: BluetoothRequest,
+    _cancellation: CancellationToken,
+    _events: &mpsc::Sender<RuntimeEvent>,
+) -> Result<(), RuntimeError> {
+    Err(RuntimeError::new(
+        RuntimeErrorCode::InvalidRequest,
+        "this build has no Bluetooth support; rebuild with --features bluetooth",
+    ))
+}
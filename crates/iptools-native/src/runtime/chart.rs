@@ -0,0 +1,184 @@
+//! 把测量历史画成 PNG 或 SVG 折线图，写到当前目录，便于直接放进报告。
+
+use std::path::{Path, PathBuf};
+
+use iptools_core::{
+    Chart, ChartAxis, ChartFormat, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent,
+};
+use plotters::{coord::Shift, prelude::*};
+
+use super::{NativeRuntime, RuntimeTaskError};
+
+const SIZE: (u32, u32) = (1200, 600);
+const COLORS: [RGBColor; 3] = [BLUE, RED, GREEN];
+
+impl NativeRuntime {
+    pub(super) fn spawn_chart_export(&mut self, job: JobId, chart: Chart, format: ChartFormat) {
+        self.spawn(job, move |token, events| async move {
+            let path = std::env::current_dir()
+                .unwrap_or_default()
+                .join(file_name(&chart, format, chrono::Local::now()));
+            let event = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                result = tokio::task::spawn_blocking(move || draw(&chart, format, &path).map(|()| path)) => {
+                    match result.map_err(|error| RuntimeTaskError::Operation(error.to_string()))? {
+                        Ok(path) => RuntimeEvent::ChartExported {
+                            job,
+                            path: path.display().to_string(),
+                        },
+                        Err(error) => RuntimeEvent::ChartExportFailed { job, error },
+                    }
+                }
+            };
+            events
+                .send(event)
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+    }
+}
+
+/// 带时间的文件名，重复导出不会覆盖上一张。
+fn file_name(chart: &Chart, format: ChartFormat, now: chrono::DateTime<chrono::Local>) -> PathBuf {
+    PathBuf::from(format!(
+        "iptools-{}-{}.{}",
+        chart.name,
+        now.format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ))
+}
+
+fn draw(chart: &Chart, format: ChartFormat, path: &Path) -> Result<(), RuntimeError> {
+    let result = match format {
+        ChartFormat::Png => plot(BitMapBackend::new(path, SIZE).into_drawing_area(), chart),
+        ChartFormat::Svg => plot(SVGBackend::new(path, SIZE).into_drawing_area(), chart),
+    };
+    result.map_err(|error| RuntimeError::new(RuntimeErrorCode::Internal, error))
+}
+
+fn plot<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, chart: &Chart) -> Result<(), String> {
+    let error = |error: DrawingAreaErrorKind<DB::ErrorType>| error.to_string();
+    let scale = |value: u64, axis: &ChartAxis| value as f64 / axis.scale.max(1) as f64;
+    let xs = chart
+        .series
+        .iter()
+        .flat_map(|series| series.points.iter().map(|(x, _)| scale(*x, &chart.x)));
+    let x_min = xs.clone().fold(f64::INFINITY, f64::min);
+    let x_max = xs.fold(f64::NEG_INFINITY, f64::max);
+    if !x_min.is_finite() {
+        return Err("the chart has no samples".into());
+    }
+    // 单个样本也要有宽度可画。
+    let (x_min, x_max) = if x_min < x_max {
+        (x_min, x_max)
+    } else {
+        (x_min - 1.0, x_max + 1.0)
+    };
+    let y_max = chart
+        .series
+        .iter()
+        .flat_map(|series| series.points.iter().filter_map(|(_, y)| *y))
+        .map(|y| scale(y, &chart.y))
+        .fold(0.0, f64::max);
+
+    root.fill(&WHITE).map_err(error)?;
+    let mut context = ChartBuilder::on(&root)
+        .caption(&chart.title, ("sans-serif", 28))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(x_min..x_max, 0.0..(y_max * 1.1).max(1.0))
+        .map_err(error)?;
+    context
+        .configure_mesh()
+        .x_desc(&chart.x.label)
+        .y_desc(&chart.y.label)
+        .draw()
+        .map_err(error)?;
+    for (index, series) in chart.series.iter().enumerate() {
+        let color = COLORS[index % COLORS.len()];
+        // 丢包处断开，每段单独成线。
+        let mut segments = vec![Vec::new()];
+        for (x, y) in &series.points {
+            match y {
+                Some(y) => segments
+                    .last_mut()
+                    .expect("starts with one segment")
+                    .push((scale(*x, &chart.x), scale(*y, &chart.y))),
+                None if segments.last().is_some_and(|last| !last.is_empty()) => {
+                    segments.push(Vec::new());
+                }
+                None => {}
+            }
+        }
+        let segments = segments.into_iter().filter(|segment| !segment.is_empty());
+        for (number, segment) in segments.enumerate() {
+            let drawn = context
+                .draw_series(LineSeries::new(segment, color.stroke_width(2)).point_size(2))
+                .map_err(error)?;
+            if number == 0 {
+                drawn.label(&series.label).legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+                });
+            }
+        }
+    }
+    context
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(error)?;
+    root.present().map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use iptools_core::ChartSeries;
+
+    use super::*;
+
+    #[test]
+    fn both_formats_are_written_and_lines_break_at_losses() {
+        let chart = Chart {
+            name: "ping",
+            title: "Ping example.com".into(),
+            x: ChartAxis {
+                label: "Probe".into(),
+                scale: 1,
+            },
+            y: ChartAxis {
+                label: "Latency (ms)".into(),
+                scale: 1,
+            },
+            series: vec![ChartSeries {
+                label: "RTT".into(),
+                points: vec![(1, Some(12)), (2, Some(14)), (3, None), (4, Some(11))],
+            }],
+        };
+        let now = chrono::Local::now();
+        let name = file_name(&chart, ChartFormat::Svg, now);
+        assert_eq!(
+            name,
+            PathBuf::from(format!("iptools-ping-{}.svg", now.format("%Y%m%d-%H%M%S")))
+        );
+
+        let path = std::env::temp_dir().join(format!("iptools-chart-{}.svg", std::process::id()));
+        draw(&chart, ChartFormat::Svg, &path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(svg.contains("Ping example.com"));
+        assert_eq!(
+            svg.matches(r##"<polyline fill="none" opacity="1" stroke="#0000FF""##)
+                .count(),
+            2 + 1,
+            "two runs and the legend"
+        );
+
+        let path = path.with_extension("png");
+        draw(&chart, ChartFormat::Png, &path).unwrap();
+        let png = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,237 @@
+//! 用 NVD 的 CVE 接口查询扫描到的软件版本有多少已知漏洞，按严重程度计数。
+//!
+//! 以 CPE 名作 `virtualMatchString`，NVD 会连同版本范围一起匹配。未带 API 密钥时
+//! 每 30 秒最多五次请求，因此逐个版本查询，两次之间停顿六秒。
+
+use std::time::Duration;
+
+use iptools_core::{
+    CveHint, CveSeverity, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent, Software,
+};
+use serde::Deserialize;
+
+use super::{NativeRuntime, RuntimeTaskError};
+
+const NVD_CVES: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+/// 免密钥限额下两次请求的间隔
+const REQUEST_GAP: Duration = Duration::from_secs(6);
+/// NVD 单页上限；超出的部分只计入总数，不分严重程度
+const PAGE_SIZE: u32 = 2000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CveResponse {
+    total_results: u32,
+    #[serde(default)]
+    vulnerabilities: Vec<Vulnerability>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Vulnerability {
+    cve: Cve,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cve {
+    #[serde(default)]
+    metrics: Metrics,
+}
+
+/// 同一 CVE 可能有多个版本的 CVSS 评分，取最新的一版。
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metrics {
+    #[serde(default)]
+    cvss_metric_v40: Vec<Metric>,
+    #[serde(default)]
+    cvss_metric_v31: Vec<Metric>,
+    #[serde(default)]
+    cvss_metric_v30: Vec<Metric>,
+    #[serde(default)]
+    cvss_metric_v2: Vec<Metric>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metric {
+    /// CVSS v2 把严重程度放在评分外面
+    #[serde(default)]
+    base_severity: Option<String>,
+    #[serde(default)]
+    cvss_data: CvssData,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CvssData {
+    #[serde(default)]
+    base_severity: Option<String>,
+}
+
+impl Metrics {
+    fn severity(&self) -> Option<CveSeverity> {
+        [
+            &self.cvss_metric_v40,
+            &self.cvss_metric_v31,
+            &self.cvss_metric_v30,
+            &self.cvss_metric_v2,
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|metric| {
+            match metric
+                .cvss_data
+                .base_severity
+                .as_deref()
+                .or(metric.base_severity.as_deref())?
+            {
+                "CRITICAL" => Some(CveSeverity::Critical),
+                "HIGH" => Some(CveSeverity::High),
+                "MEDIUM" => Some(CveSeverity::Medium),
+                "LOW" => Some(CveSeverity::Low),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl NativeRuntime {
+    pub(super) fn spawn_cve_lookup(
+        &mut self,
+        job: JobId,
+        software: Vec<Software>,
+        use_system_proxy: bool,
+    ) {
+        self.spawn(job, move |token, events| async move {
+            let send = |event| {
+                let events = events.clone();
+                async move {
+                    events
+                        .send(event)
+                        .await
+                        .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+                }
+            };
+            let client = match client(use_system_proxy) {
+                Ok(client) => client,
+                Err(error) => return send(RuntimeEvent::CveLookupFailed { job, error }).await,
+            };
+            let mut failure = None;
+            let mut found = false;
+            for (index, software) in software.into_iter().enumerate() {
+                if index > 0 {
+                    tokio::select! {
+                        _ = token.cancelled() => return Ok(()),
+                        _ = tokio::time::sleep(REQUEST_GAP) => {}
+                    }
+                }
+                let result = tokio::select! {
+                    _ = token.cancelled() => return Ok(()),
+                    result = lookup(&client, &software) => result,
+                };
+                match result {
+                    Ok(response) => {
+                        found = true;
+                        let hint = hint(software, &response);
+                        send(RuntimeEvent::CveHintFound { job, hint }).await?;
+                    }
+                    Err(error) => failure = Some(error),
+                }
+            }
+            // 部分版本查到即算完成；全部失败才报错，通常是网络不通或被限流。
+            match failure.filter(|_| !found) {
+                Some(error) => send(RuntimeEvent::CveLookupFailed { job, error }).await,
+                None => send(RuntimeEvent::CveLookupFinished { job }).await,
+            }
+        });
+    }
+}
+
+fn client(use_system_proxy: bool) -> Result<reqwest::Client, RuntimeError> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(20))
+        .user_agent(concat!("iptools/", env!("CARGO_PKG_VERSION")));
+    if !use_system_proxy {
+        builder = builder.no_proxy();
+    }
+    builder
+        .build()
+        .map_err(|error| RuntimeError::new(RuntimeErrorCode::Internal, error.to_string()))
+}
+
+async fn lookup(
+    client: &reqwest::Client,
+    software: &Software,
+) -> Result<CveResponse, RuntimeError> {
+    let network =
+        |error: reqwest::Error| RuntimeError::new(RuntimeErrorCode::Network, error.to_string());
+    let body = client
+        // CPE 名只由产品表里的小写名与数字版本组成，无需转义。
+        .get(format!(
+            "{NVD_CVES}?virtualMatchString={}&resultsPerPage={PAGE_SIZE}",
+            software.cpe()
+        ))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(network)?
+        .text()
+        .await
+        .map_err(network)?;
+    parse(&body)
+}
+
+fn parse(body: &str) -> Result<CveResponse, RuntimeError> {
+    serde_json::from_str(body).map_err(|error| {
+        RuntimeError::new(
+            RuntimeErrorCode::Network,
+            format!("unexpected NVD response: {error}"),
+        )
+    })
+}
+
+fn hint(software: Software, response: &CveResponse) -> CveHint {
+    let mut by_severity = [0; 4];
+    for severity in response
+        .vulnerabilities
+        .iter()
+        .filter_map(|vulnerability| vulnerability.cve.metrics.severity())
+    {
+        by_severity[severity as usize] += 1;
+    }
+    CveHint {
+        software,
+        total: response.total_results,
+        by_severity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nvd_answers_count_by_newest_severity() {
+        let response = parse(
+            r#"{"resultsPerPage":3,"startIndex":0,"totalResults":3,"vulnerabilities":[
+                {"cve":{"id":"CVE-2016-6210","metrics":{
+                    "cvssMetricV31":[{"cvssData":{"baseScore":5.9,"baseSeverity":"MEDIUM"}}],
+                    "cvssMetricV2":[{"baseSeverity":"HIGH","cvssData":{"baseScore":7.8}}]}}},
+                {"cve":{"id":"CVE-2016-3115","metrics":{
+                    "cvssMetricV2":[{"baseSeverity":"LOW","cvssData":{"baseScore":3.5}}]}}},
+                {"cve":{"id":"CVE-2025-0001","metrics":{}}}
+            ]}"#,
+        )
+        .unwrap();
+        let software = iptools_core::identify("SSH-2.0-OpenSSH_7.2p2").unwrap();
+        let hint = hint(software, &response);
+        assert_eq!(hint.total, 3);
+        assert_eq!(
+            hint.by_severity,
+            [0, 0, 1, 1],
+            "unscored CVEs only count in the total"
+        );
+
+        assert!(parse("<html>rate limited</html>").is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use std::{env, time::Instant};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use chrono::Local;
 use iptools_core::{
@@ -9,10 +13,12 @@ use sysinfo::System;
 use tokio_util::sync::CancellationToken;
 
 use super::{NativeRuntime, RuntimeTaskError};
-use crate::utils::{net, pubip};
+use crate::secrets;
+use crate::utils::{counters, net, pubip};
 
 #[derive(Debug)]
 pub(super) struct TrafficSample {
+    /// 接口 GUID，与计数来源的键一致
     interface: String,
     received: u64,
     transmitted: u64,
@@ -26,8 +32,23 @@ enum FetchFailure {
 
 impl NativeRuntime {
     pub(super) fn spawn_dashboard_refresh(&mut self, job: JobId, request: DashboardRequest) {
-        let snapshot = self.collect_dashboard_snapshot();
+        // 计数在派发时读，与采样时刻一致；网卡枚举和网关查询会阻塞，放进任务里做。
+        let counters = self.dashboard_counters.read();
+        let sampled_at = Instant::now();
+        let previous = Arc::clone(&self.dashboard_sample);
         self.spawn(job, move |token, events| async move {
+            let snapshot = collect_dashboard_snapshot(&counters, sampled_at, &previous).await;
+            // 离线模式：只上报本机信息，不访问任何公网接口
+            if request.offline {
+                events
+                    .send(RuntimeEvent::DashboardRefreshFinished {
+                        job,
+                        snapshot: Box::new(snapshot),
+                    })
+                    .await
+                    .map_err(|error| RuntimeTaskError::Operation(error.to_string()))?;
+                return Ok(());
+            }
             match fetch_public_info(&request, &token).await {
                 Ok(info) => {
                     let mut snapshot = snapshot;
@@ -60,67 +81,80 @@ impl NativeRuntime {
             Ok(())
         });
     }
+}
 
-    fn collect_dashboard_snapshot(&mut self) -> DashboardSnapshot {
-        self.dashboard_networks.refresh(true);
-        let mut interfaces = net::get_interfaces();
-        interfaces.sort_by_key(|interface| std::cmp::Reverse(score_interface(interface)));
-        let active = interfaces.into_iter().next();
-        let now = Instant::now();
-        let mut download_bps = 0;
-        let mut upload_bps = 0;
-        let mut total_download = 0;
-        let mut total_upload = 0;
+async fn collect_dashboard_snapshot(
+    counters: &[counters::InterfaceCounters],
+    now: Instant,
+    previous: &Mutex<Option<TrafficSample>>,
+) -> DashboardSnapshot {
+    let mut interfaces = net::interfaces()
+        .await
+        .inspect_err(|error| tracing::warn!(%error, "dashboard cannot list interfaces"))
+        .unwrap_or_default();
+    interfaces.sort_by_key(|interface| std::cmp::Reverse(score_interface(interface)));
+    let active = interfaces.into_iter().next();
+    let mut download_bps = 0;
+    let mut upload_bps = 0;
+    let mut total_download = 0;
+    let mut total_upload = 0;
 
-        if let Some(interface) = &active
-            && let Some((_, data)) = self
-                .dashboard_networks
-                .iter()
-                .find(|(name, _)| **name == interface.name)
+    if let Some(interface) = &active
+        && let Some(counter) = counters::find_interface(
+            counters,
+            |counter| (counter.guid.as_str(), counter.name.as_str()),
+            &interface.guid,
+            &interface.name,
+        )
+    {
+        total_download = counter.received;
+        total_upload = counter.transmitted;
+        let mut previous = previous.lock().unwrap_or_else(|error| error.into_inner());
+        if let Some(previous) = previous.as_ref()
+            && previous.interface == interface.guid
         {
-            total_download = data.total_received();
-            total_upload = data.total_transmitted();
-            if let Some(previous) = &self.dashboard_sample
-                && previous.interface == interface.name
-            {
-                let elapsed = now.duration_since(previous.sampled_at).as_secs_f64();
-                if elapsed > 0.0 {
-                    download_bps =
-                        (total_download.saturating_sub(previous.received) as f64 / elapsed) as u64;
-                    upload_bps =
-                        (total_upload.saturating_sub(previous.transmitted) as f64 / elapsed) as u64;
-                }
+            let elapsed = now.duration_since(previous.sampled_at).as_secs_f64();
+            if elapsed > 0.0 {
+                download_bps =
+                    (total_download.saturating_sub(previous.received) as f64 / elapsed) as u64;
+                upload_bps =
+                    (total_upload.saturating_sub(previous.transmitted) as f64 / elapsed) as u64;
             }
-            self.dashboard_sample = Some(TrafficSample {
-                interface: interface.name.clone(),
-                received: total_download,
-                transmitted: total_upload,
-                sampled_at: now,
-            });
-        } else {
-            self.dashboard_sample = None;
         }
+        *previous = Some(TrafficSample {
+            interface: interface.guid.clone(),
+            received: total_download,
+            transmitted: total_upload,
+            sampled_at: now,
+        });
+    } else {
+        *previous.lock().unwrap_or_else(|error| error.into_inner()) = None;
+    }
+    let gateway = match &active {
+        Some(interface) => net::gateway(interface.clone()).await,
+        None => None,
+    };
 
-        DashboardSnapshot {
-            observed_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            hostname: System::host_name().unwrap_or_default(),
-            os_name: System::name().unwrap_or_default(),
-            os_version: System::os_version().unwrap_or_default(),
-            active_interface: active.map(|interface| DashboardInterface {
-                name: interface.name,
-                description: interface.description,
-                ipv4: interface.ipv4.first().cloned().unwrap_or_default(),
-                ssid: interface.ssid,
-                is_physical: interface.is_physical,
-                dhcp_enabled: interface.dhcp_enabled,
-            }),
-            proxy: detect_proxy(),
-            public_info: None,
-            download_bps,
-            upload_bps,
-            total_download,
-            total_upload,
-        }
+    DashboardSnapshot {
+        observed_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        hostname: System::host_name().unwrap_or_default(),
+        os_name: System::name().unwrap_or_default(),
+        os_version: System::os_version().unwrap_or_default(),
+        active_interface: active.map(|interface| DashboardInterface {
+            gateway: gateway.map(|gateway| gateway.to_string()),
+            name: interface.name,
+            description: interface.description,
+            ipv4: interface.ipv4.first().cloned().unwrap_or_default(),
+            ssid: interface.ssid,
+            is_physical: interface.is_physical,
+            dhcp_enabled: interface.dhcp_enabled,
+        }),
+        proxy: detect_proxy(),
+        public_info: None,
+        download_bps,
+        upload_bps,
+        total_download,
+        total_upload,
     }
 }
 
@@ -148,14 +182,23 @@ async fn fetch_public_info(
 
     let mut last_error = RuntimeError::new(RuntimeErrorCode::Network, "public IP request failed");
     for endpoint in &request.public_ip.endpoints {
+        let url = match secrets::expand_async(&endpoint.url).await {
+            Ok(url) => url,
+            Err(error) => {
+                last_error = error;
+                continue;
+            }
+        };
+        // 含密钥的地址不能出现在错误信息里。
+        let secret = url != endpoint.url;
         let response = tokio::select! {
             _ = token.cancelled() => return Err(FetchFailure::Cancelled),
-            response = client.get(&endpoint.url).send() => response,
+            response = client.get(&url).send() => response,
         };
         let response = match response {
             Ok(response) => response,
             Err(error) => {
-                last_error = RuntimeError::new(RuntimeErrorCode::Network, error.to_string());
+                last_error = network_error(error, secret);
                 continue;
             }
         };
@@ -174,14 +217,18 @@ async fn fetch_public_info(
                     format!("unable to parse {} response ({status})", endpoint.kind),
                 );
             }
-            Err(error) => {
-                last_error = RuntimeError::new(RuntimeErrorCode::Network, error.to_string());
-            }
+            Err(error) => last_error = network_error(error, secret),
         }
     }
     Err(FetchFailure::Failed(last_error))
 }
 
+/// 请求或读取响应体失败；`secret` 的地址含密钥，错误信息里去掉地址。
+fn network_error(error: reqwest::Error, secret: bool) -> RuntimeError {
+    let error = if secret { error.without_url() } else { error };
+    RuntimeError::new(RuntimeErrorCode::Network, error.to_string())
+}
+
 fn score_interface(interface: &net::InterfaceInfo) -> u8 {
     u8::from(interface.is_up) * 10
         + u8::from(interface.is_physical) * 5
@@ -328,6 +375,33 @@ mod tests {
         assert!(!model.dashboard.snapshot.hostname.is_empty());
     }
 
+    #[tokio::test]
+    async fn a_truncated_body_does_not_reveal_a_secret_url() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0_u8; 1024];
+                let _ = stream.read(&mut request).await.unwrap();
+                // 声明的长度比实际发送的多，读取响应体时出错
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 64\r\n\r\n127.0")
+                    .await
+                    .unwrap();
+            }
+        });
+        let url = format!("http://{address}/json?token=hunter2");
+        let mut messages = Vec::new();
+        for secret in [false, true] {
+            let error = reqwest::get(&url).await.unwrap().text().await.unwrap_err();
+            messages.push(network_error(error, secret).message);
+        }
+        server.await.unwrap();
+        assert!(messages[0].contains("hunter2"), "{messages:?}");
+        assert!(!messages[1].contains("hunter2"), "{messages:?}");
+    }
+
     #[tokio::test]
     async fn empty_endpoint_list_is_a_typed_failure() {
         let mut model = AppModel::default();
@@ -352,4 +426,32 @@ mod tests {
             RuntimeErrorCode::InvalidRequest
         );
     }
+
+    #[tokio::test]
+    async fn offline_mode_finishes_without_a_public_lookup() {
+        let mut model = AppModel::default();
+        model.apply_config(&iptools_core::ConfigData {
+            offline: true,
+            // 若真的发出请求，这个无人监听的端口会让刷新失败
+            public_ip: PublicIpConfig {
+                endpoints: vec![Endpoint {
+                    url: "http://127.0.0.1:9".into(),
+                    kind: "plaintext".into(),
+                }],
+                use_system_proxy: false,
+            },
+            ..iptools_core::ConfigData::default()
+        });
+        let [effect] = model
+            .update(Message::Input(InputEvent::Action(Action::Refresh)))
+            .try_into()
+            .unwrap();
+        let mut runtime = NativeRuntime::new();
+        runtime.dispatch(effect).unwrap();
+        drive_until_terminal(&mut model, &mut runtime).await;
+
+        assert_eq!(model.dashboard.status, TaskStatus::Done);
+        assert!(model.dashboard.snapshot.public_info.is_none());
+        assert!(!model.dashboard.snapshot.hostname.is_empty());
+    }
 }
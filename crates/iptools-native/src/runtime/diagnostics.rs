@@ -1,5 +1,8 @@
 use iptools_core::{
-    JobId, LanSpeedRequest, LinkQualityRequest, PingRequest, PublicSpeedRequest, TraceRequest,
+    AbRequest, CloudRequest, DnsBenchRequest, DnsFilterRequest, DscpRequest, GamingRequest,
+    Ipv6Request, JobId, LanSpeedRequest, LinkQualityRequest, MailRequest, MtuRequest,
+    MulticastRequest, NatRequest, PingRequest, PublicSpeedRequest, TraceRequest, UpnpRequest,
+    VoipRequest, WebCheckRequest, WifiRequest,
 };
 
 use super::{NativeRuntime, RuntimeTaskError};
@@ -54,6 +57,126 @@ impl NativeRuntime {
                 .map_err(RuntimeTaskError::Operation)
         });
     }
+
+    pub(super) fn spawn_mtu(&mut self, job: JobId, request: MtuRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::mtu::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_dscp(&mut self, job: JobId, request: DscpRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::dscp::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_multicast(&mut self, job: JobId, request: MulticastRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::multicast::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_upnp(&mut self, job: JobId, request: UpnpRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::upnp::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_nat(&mut self, job: JobId, request: NatRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::nat::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_ipv6(&mut self, job: JobId, request: Ipv6Request) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::ipv6::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_dns_bench(&mut self, job: JobId, request: DnsBenchRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::dns_bench::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_dns_filter(&mut self, job: JobId, request: DnsFilterRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::dns_filter::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_mail(&mut self, job: JobId, request: MailRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::mail::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_voip(&mut self, job: JobId, request: VoipRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::voip::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_gaming(&mut self, job: JobId, request: GamingRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::gaming::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_cloud(&mut self, job: JobId, request: CloudRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::cloud::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_wifi(&mut self, job: JobId, request: WifiRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::wifi::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_ab_test(&mut self, job: JobId, request: AbRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::ab_test::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
+
+    pub(super) fn spawn_web_check(&mut self, job: JobId, request: WebCheckRequest) {
+        self.spawn(job, move |cancellation, events| async move {
+            crate::modules::diagnostics::web_check::run_shared(job, request, cancellation, events)
+                .await
+                .map_err(RuntimeTaskError::Operation)
+        });
+    }
 }
 
 #[cfg(test)]
@@ -119,13 +242,193 @@ mod tests {
                 },
             })
             .unwrap();
+        let mtu = JobId {
+            tool: ToolKind::Mtu,
+            generation: 5,
+        };
+        runtime
+            .dispatch(Effect::StartMtu {
+                job: mtu,
+                request: MtuRequest {
+                    target: "::1".into(),
+                    ..MtuRequest::default()
+                },
+            })
+            .unwrap();
+        let dscp = JobId {
+            tool: ToolKind::Dscp,
+            generation: 6,
+        };
+        runtime
+            .dispatch(Effect::StartDscp {
+                job: dscp,
+                request: DscpRequest {
+                    target: " ".into(),
+                    ..DscpRequest::default()
+                },
+            })
+            .unwrap();
+        let multicast = JobId {
+            tool: ToolKind::Multicast,
+            generation: 7,
+        };
+        runtime
+            .dispatch(Effect::StartMulticast {
+                job: multicast,
+                request: MulticastRequest {
+                    group: "192.0.2.1".into(),
+                    ..MulticastRequest::default()
+                },
+            })
+            .unwrap();
+        let upnp = JobId {
+            tool: ToolKind::Upnp,
+            generation: 8,
+        };
+        runtime
+            .dispatch(Effect::StartUpnp {
+                job: upnp,
+                request: UpnpRequest {
+                    action: iptools_core::UpnpAction::Delete,
+                    external_port: 0,
+                    ..UpnpRequest::default()
+                },
+            })
+            .unwrap();
+        let nat = JobId {
+            tool: ToolKind::Nat,
+            generation: 9,
+        };
+        runtime
+            .dispatch(Effect::StartNat {
+                job: nat,
+                request: NatRequest {
+                    server: " ".into(),
+                    ..NatRequest::default()
+                },
+            })
+            .unwrap();
+        let ipv6 = JobId {
+            tool: ToolKind::Ipv6,
+            generation: 10,
+        };
+        runtime
+            .dispatch(Effect::StartIpv6 {
+                job: ipv6,
+                request: Ipv6Request {
+                    target: String::new(),
+                    ..Ipv6Request::default()
+                },
+            })
+            .unwrap();
+        let dns = JobId {
+            tool: ToolKind::DnsBench,
+            generation: 11,
+        };
+        runtime
+            .dispatch(Effect::StartDnsBench {
+                job: dns,
+                request: DnsBenchRequest {
+                    custom: "resolver.invalid".into(),
+                    ..DnsBenchRequest::default()
+                },
+            })
+            .unwrap();
+        let filter = JobId {
+            tool: ToolKind::DnsFilter,
+            generation: 12,
+        };
+        runtime
+            .dispatch(Effect::StartDnsFilter {
+                job: filter,
+                request: DnsFilterRequest {
+                    domain: "bad..name".into(),
+                    ..DnsFilterRequest::default()
+                },
+            })
+            .unwrap();
+        let mail = JobId {
+            tool: ToolKind::Mail,
+            generation: 13,
+        };
+        runtime
+            .dispatch(Effect::StartMail {
+                job: mail,
+                request: MailRequest {
+                    host: "  ".into(),
+                    ..MailRequest::default()
+                },
+            })
+            .unwrap();
+        let voip = JobId {
+            tool: ToolKind::Voip,
+            generation: 14,
+        };
+        runtime
+            .dispatch(Effect::StartVoip {
+                job: voip,
+                request: VoipRequest {
+                    port: 0,
+                    ..VoipRequest::default()
+                },
+            })
+            .unwrap();
+        let gaming = JobId {
+            tool: ToolKind::Gaming,
+            generation: 15,
+        };
+        runtime
+            .dispatch(Effect::StartGaming {
+                job: gaming,
+                request: GamingRequest {
+                    custom: "no-port.example".into(),
+                    ..GamingRequest::default()
+                },
+            })
+            .unwrap();
+        let cloud = JobId {
+            tool: ToolKind::Cloud,
+            generation: 16,
+        };
+        runtime
+            .dispatch(Effect::StartCloud {
+                job: cloud,
+                request: CloudRequest {
+                    attempts: 0,
+                    ..CloudRequest::default()
+                },
+            })
+            .unwrap();
+        let wifi = JobId {
+            tool: ToolKind::Wifi,
+            generation: 17,
+        };
+        runtime
+            .dispatch(Effect::StartWifi {
+                job: wifi,
+                request: WifiRequest::default(),
+            })
+            .unwrap();
+        let ab_test = JobId {
+            tool: ToolKind::AbTest,
+            generation: 18,
+        };
+        runtime
+            .dispatch(Effect::StartAbTest {
+                job: ab_test,
+                request: AbRequest {
+                    target: "10.0.0.0/8".into(),
+                    ..AbRequest::default()
+                },
+            })
+            .unwrap();
 
         let mut events = Vec::new();
         for _ in 0..20 {
             while let Some(event) = runtime.try_recv() {
                 events.push(event);
             }
-            if events.len() >= 4 {
+            if events.len() >= 18 {
                 break;
             }
             tokio::time::sleep(Duration::from_millis(5)).await;
@@ -134,6 +437,20 @@ mod tests {
         assert!(events.iter().any(|event| matches!(event, RuntimeEvent::TraceFailed { job, error } if *job == trace && error.code == RuntimeErrorCode::InvalidRequest)));
         assert!(events.iter().any(|event| matches!(event, RuntimeEvent::LinkQualityFailed { job, error } if *job == link && error.code == RuntimeErrorCode::InvalidRequest)));
         assert!(events.iter().any(|event| matches!(event, RuntimeEvent::LanSpeedFailed { job, error } if *job == lan && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::MtuFailed { job, error } if *job == mtu && error.code == RuntimeErrorCode::ResolveTarget)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::DscpFailed { job, error } if *job == dscp && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::MulticastFailed { job, error } if *job == multicast && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::UpnpFailed { job, error } if *job == upnp && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::DnsBenchFailed { job, error } if *job == dns && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::DnsFilterFailed { job, error } if *job == filter && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::MailFailed { job, error } if *job == mail && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::VoipFailed { job, error } if *job == voip && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::GamingFailed { job, error } if *job == gaming && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::CloudFailed { job, error } if *job == cloud && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::WifiFailed { job, error } if *job == wifi && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::Ipv6Failed { job, error } if *job == ipv6 && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::NatFailed { job, error } if *job == nat && error.code == RuntimeErrorCode::InvalidRequest)));
+        assert!(events.iter().any(|event| matches!(event, RuntimeEvent::AbTestFailed { job, error } if *job == ab_test && error.code == RuntimeErrorCode::InvalidRequest)));
         runtime.shutdown().await;
     }
 }
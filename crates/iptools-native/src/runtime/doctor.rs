@@ -0,0 +1,23 @@
+//! 设置页「自我诊断」：在阻塞线程上跑与 `iptools doctor` 相同的检查。
+
+use iptools_core::{JobId, RuntimeEvent};
+
+use super::{NativeRuntime, RuntimeTaskError};
+
+impl NativeRuntime {
+    pub(super) fn spawn_doctor(&mut self, job: JobId) {
+        let config_path = self.config_path.clone();
+        self.spawn(job, move |token, events| async move {
+            let checks = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                result = tokio::task::spawn_blocking(move || crate::doctor::run(config_path.as_deref())) => {
+                    result.map_err(|error| RuntimeTaskError::Operation(error.to_string()))?
+                }
+            };
+            events
+                .send(RuntimeEvent::DoctorFinished { job, checks })
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+    }
+}
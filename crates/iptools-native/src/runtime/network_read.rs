@@ -1,19 +1,22 @@
 use std::{collections::HashMap, time::Instant};
 
 use iptools_core::{AdapterInfo, JobId, RuntimeError, RuntimeErrorCode, RuntimeEvent, TrafficRow};
-use sysinfo::Networks;
 
 use super::{NativeRuntime, RuntimeTaskError};
-use crate::utils::net;
+use crate::utils::{counters, net};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Default)]
 struct NetworkPoint {
+    /// 计数来源的键，与 `InterfaceInfo::guid` 对应
+    guid: String,
+    name: String,
     download_bps: u64,
     upload_bps: u64,
     total_download: u64,
     total_upload: u64,
     session_download: u64,
     session_upload: u64,
+    link: Option<iptools_core::LinkCounters>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -25,56 +28,53 @@ struct CounterSample {
     upload_bps: u64,
 }
 
+/// 历史与会话起点都以接口 GUID 为键，网卡改名后统计不会断开。
 pub(super) struct NetworkSampler {
-    networks: Networks,
+    source: counters::CounterSource,
     history: HashMap<String, CounterSample>,
     initial: HashMap<String, (u64, u64)>,
 }
 
 impl NetworkSampler {
     pub(super) fn new() -> Self {
-        let mut networks = Networks::new_with_refreshed_list();
-        networks.refresh(true);
-        let initial = networks
-            .iter()
-            .map(|(name, data)| {
-                (
-                    name.clone(),
-                    (data.total_received(), data.total_transmitted()),
-                )
-            })
+        let mut source = counters::CounterSource::new();
+        let initial = source
+            .read()
+            .into_iter()
+            .map(|counter| (counter.guid, (counter.received, counter.transmitted)))
             .collect();
         Self {
-            networks,
+            source,
             history: HashMap::new(),
             initial,
         }
     }
 
-    fn sample(&mut self) -> HashMap<String, NetworkPoint> {
-        self.networks.refresh(true);
+    fn sample(&mut self) -> Vec<NetworkPoint> {
         let now = Instant::now();
-        let mut points = HashMap::new();
-        for (name, data) in &self.networks {
-            let received = data.total_received();
-            let transmitted = data.total_transmitted();
+        let mut points = Vec::new();
+        for counter in self.source.read() {
+            let received = counter.received;
+            let transmitted = counter.transmitted;
             let initial = self
                 .initial
-                .entry(name.clone())
+                .entry(counter.guid.clone())
                 .or_insert((received, transmitted));
-            let (download_bps, upload_bps) = self.history.get(name).map_or((0, 0), |previous| {
-                let elapsed = now.duration_since(previous.sampled_at).as_secs_f64();
-                if elapsed > 0.1 {
-                    (
-                        (received.saturating_sub(previous.received) as f64 / elapsed) as u64,
-                        (transmitted.saturating_sub(previous.transmitted) as f64 / elapsed) as u64,
-                    )
-                } else {
-                    (previous.download_bps, previous.upload_bps)
-                }
-            });
+            let (download_bps, upload_bps) =
+                self.history.get(&counter.guid).map_or((0, 0), |previous| {
+                    let elapsed = now.duration_since(previous.sampled_at).as_secs_f64();
+                    if elapsed > 0.1 {
+                        (
+                            (received.saturating_sub(previous.received) as f64 / elapsed) as u64,
+                            (transmitted.saturating_sub(previous.transmitted) as f64 / elapsed)
+                                as u64,
+                        )
+                    } else {
+                        (previous.download_bps, previous.upload_bps)
+                    }
+                });
             self.history.insert(
-                name.clone(),
+                counter.guid.clone(),
                 CounterSample {
                     received,
                     transmitted,
@@ -83,17 +83,17 @@ impl NetworkSampler {
                     upload_bps,
                 },
             );
-            points.insert(
-                name.clone(),
-                NetworkPoint {
-                    download_bps,
-                    upload_bps,
-                    total_download: received,
-                    total_upload: transmitted,
-                    session_download: received.saturating_sub(initial.0),
-                    session_upload: transmitted.saturating_sub(initial.1),
-                },
-            );
+            points.push(NetworkPoint {
+                guid: counter.guid,
+                name: counter.name,
+                download_bps,
+                upload_bps,
+                total_download: received,
+                total_upload: transmitted,
+                session_download: received.saturating_sub(initial.0),
+                session_upload: transmitted.saturating_sub(initial.1),
+                link: counter.link,
+            });
         }
         points
     }
@@ -113,7 +113,7 @@ impl NativeRuntime {
                 permit = gate.acquire_owned() => permit
                     .map_err(|error| RuntimeTaskError::Operation(error.to_string()))?,
             };
-            let result = tokio::task::spawn_blocking(net::get_interfaces).await;
+            let result = net::interfaces().await;
             drop(permit);
             if token.is_cancelled() {
                 events
@@ -127,7 +127,13 @@ impl NativeRuntime {
                     let mut adapters = interfaces
                         .into_iter()
                         .map(|interface| {
-                            let point = points.get(&interface.name).copied();
+                            let point = counters::find_interface(
+                                &points,
+                                |point| (point.guid.as_str(), point.name.as_str()),
+                                &interface.guid,
+                                &interface.name,
+                            )
+                            .cloned();
                             adapter_info(interface, point)
                         })
                         .collect::<Vec<_>>();
@@ -164,15 +170,16 @@ impl NativeRuntime {
             .network_sampler
             .sample()
             .into_iter()
-            .filter(|(name, _)| !ignored_interface(name))
-            .map(|(name, point)| TrafficRow {
-                name,
+            .filter(|point| !ignored_interface(&point.name))
+            .map(|point| TrafficRow {
+                name: point.name,
                 download_bps: point.download_bps,
                 upload_bps: point.upload_bps,
                 total_download: point.total_download,
                 total_upload: point.total_upload,
                 session_download: point.session_download,
                 session_upload: point.session_upload,
+                link: point.link,
             })
             .collect::<Vec<_>>();
         rows.sort_by(|left, right| {
@@ -197,14 +204,7 @@ impl NativeRuntime {
 }
 
 fn adapter_info(interface: net::InterfaceInfo, point: Option<NetworkPoint>) -> AdapterInfo {
-    let point = point.unwrap_or(NetworkPoint {
-        download_bps: 0,
-        upload_bps: 0,
-        total_download: 0,
-        total_upload: 0,
-        session_download: 0,
-        session_upload: 0,
-    });
+    let point = point.unwrap_or_default();
     AdapterInfo {
         name: interface.name,
         description: interface.description,
@@ -219,6 +219,7 @@ fn adapter_info(interface: net::InterfaceInfo, point: Option<NetworkPoint>) -> A
         dhcp_enabled: interface.dhcp_enabled,
         is_physical: interface.is_physical,
         link_speed_bps: interface.link_speed_bps,
+        media: interface.media,
         download_bps: point.download_bps,
         upload_bps: point.upload_bps,
         total_download: point.total_download,
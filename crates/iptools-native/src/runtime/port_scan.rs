@@ -1,7 +1,7 @@
 //! Native Port Scan effect handler.
 
 use std::{
-    net::{IpAddr, SocketAddr},
+    net::SocketAddr,
     sync::{
         Arc,
         atomic::{AtomicU64, Ordering},
@@ -15,6 +15,13 @@ use iptools_core::{
 };
 
 use super::NativeRuntime;
+use crate::{modules::diagnostics::resolve_target, utils::jump::JumpTunnel};
+
+/// 逐端口连接的出口：本机直连已解析的地址，或经跳板机把主机名交给对端解析。
+enum Route {
+    Direct(std::net::IpAddr),
+    Jump(JumpTunnel),
+}
 
 impl NativeRuntime {
     pub(super) fn spawn_port_scan(&mut self, job: JobId, request: PortScanRequest) {
@@ -40,16 +47,25 @@ impl NativeRuntime {
                 .send(RuntimeEvent::PortScanStarted { job, total })
                 .await;
             let target = request.target.trim().to_string();
-            let resolved = async {
-                match target.parse::<IpAddr>() {
-                    Ok(ip) => Some(ip),
-                    Err(_) => tokio::net::lookup_host((target.as_str(), 0u16))
+            let jump_host = request.jump_host.clone();
+            let route = async {
+                match jump_host {
+                    Some(jump_host) => JumpTunnel::open(&jump_host)
                         .await
-                        .ok()
-                        .and_then(|mut addresses| addresses.next().map(|address| address.ip())),
+                        .map(Route::Jump)
+                        .map_err(|error| RuntimeError::new(RuntimeErrorCode::Network, error)),
+                    None => resolve_target(&target)
+                        .await
+                        .map(|addresses| Route::Direct(addresses[0]))
+                        .map_err(|_| {
+                            RuntimeError::new(
+                                RuntimeErrorCode::ResolveTarget,
+                                "diag_port_err_target",
+                            )
+                        }),
                 }
             };
-            let ip = tokio::select! {
+            let route = tokio::select! {
                 _ = token.cancelled() => {
                     let _ = events.send(RuntimeEvent::PortScanFinished {
                         job,
@@ -59,19 +75,22 @@ impl NativeRuntime {
                     }).await;
                     return Ok(());
                 }
-                ip = resolved => ip,
+                route = route => route,
             };
-            let Some(ip) = ip else {
-                let _ = events
-                    .send(RuntimeEvent::PortScanFailed {
-                        job,
-                        error: RuntimeError::new(
-                            RuntimeErrorCode::ResolveTarget,
-                            "diag_port_err_target",
-                        ),
-                    })
-                    .await;
-                return Ok(());
+            let route = match route {
+                Ok(route) => Arc::new(route),
+                Err(error) => {
+                    let _ = events
+                        .send(RuntimeEvent::PortScanFailed { job, error })
+                        .await;
+                    return Ok(());
+                }
+            };
+            let concurrency = match *route {
+                Route::Direct(_) => request.concurrency.clamp(1, 1_024),
+                Route::Jump(_) => request
+                    .concurrency
+                    .clamp(1, crate::utils::jump::MAX_CONCURRENCY),
             };
 
             let ports: Vec<u16> = (request.start_port..=request.end_port).collect();
@@ -81,13 +100,24 @@ impl NativeRuntime {
                     let events = events.clone();
                     let scanned = Arc::clone(&scanned);
                     let token = token.clone();
+                    let route = Arc::clone(&route);
+                    let target = target.as_str();
                     async move {
                         if token.is_cancelled() {
                             return;
                         }
-                        let address = SocketAddr::new(ip, port);
-                        let connect = tokio::net::TcpStream::connect(address);
-                        if let Ok(Ok(_stream)) =
+                        // 只看能否连上，连接随即关闭
+                        let connect = async {
+                            match &*route {
+                                Route::Direct(ip) => {
+                                    tokio::net::TcpStream::connect(SocketAddr::new(*ip, port))
+                                        .await
+                                        .map(drop)
+                                }
+                                Route::Jump(tunnel) => tunnel.connect(target, port).await.map(drop),
+                            }
+                        };
+                        if let Ok(Ok(())) =
                             tokio::time::timeout(Duration::from_millis(request.timeout_ms), connect)
                                 .await
                         {
@@ -102,7 +132,7 @@ impl NativeRuntime {
                         scanned.fetch_add(1, Ordering::Relaxed);
                     }
                 })
-                .buffer_unordered(request.concurrency.clamp(1, 1_024));
+                .buffer_unordered(concurrency);
             let mut ticker = tokio::time::interval(Duration::from_millis(250));
             loop {
                 tokio::select! {
@@ -199,6 +229,7 @@ mod tests {
                     end_port: 0,
                     timeout_ms: 20,
                     concurrency: 1,
+                    jump_host: None,
                 },
             })
             .unwrap();
@@ -238,6 +269,7 @@ mod tests {
             end_port: 9,
             timeout_ms: 20,
             concurrency: 1,
+            jump_host: None,
         };
         runtime
             .dispatch(Effect::StartPortScan {
@@ -1,101 +1,282 @@
 //! Native Scanner effect handler.
 
 use std::{
-    net::{IpAddr, Ipv4Addr},
+    collections::{BTreeMap, HashSet},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream},
     sync::{
-        Arc,
-        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use ipnetwork::Ipv4Network;
-use iptools_core::{JobId, RuntimeEvent, ScanHost, ScanRequest};
+use iptools_core::{
+    AUDIT_PORTS, AdaptiveConcurrency, BANNER_PORTS, HostName, IOT_PORTS, JobId, NameSource,
+    ProbeOutcome, RuntimeEvent, SHARE_PORTS, ScanHost, ScanMethod, ScanProgress, ScanRequest,
+    Target, WEB_PORTS,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use super::NativeRuntime;
-use crate::utils::net;
+use crate::modules::diagnostics::icmp::{self, IcmpSession};
+use crate::utils::{android, banner, discovery, iot, net, shares, web, zone};
+
+const PORT_TIMEOUT: Duration = Duration::from_millis(300);
+/// 被自适应并发挂起的工作线程多久看一次上限。
+const PARKED_WORKER_NAP: Duration = Duration::from_millis(20);
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// 非直连子网的 ICMP Echo 等待时长；跨路由的往返通常远低于此。
+const ROUTED_ECHO_TIMEOUT_MS: u32 = 500;
+const ROUTED_ECHO_TTL: u8 = 64;
+/// PTR 反查的并发上限：查询都落在同一台解析器上，不按扫描并发数放开。
+const PTR_WORKERS: usize = 32;
 
 impl NativeRuntime {
     pub(super) fn spawn_scan(&mut self, job: JobId, request: ScanRequest) {
         self.spawn(job, move |token, events| async move {
-            let network = request.cidr.parse::<Ipv4Network>().ok();
-            let total = network.map_or(0, |network| {
-                if network.prefix() < 31 {
-                    u64::from(network.size().saturating_sub(2))
-                } else {
-                    u64::from(network.size())
-                }
-            });
+            // 核心层启动前已校验；这里仍按同一规则解析，无法解析的请求扫 0 台后结束。
+            let target = Target::parse_hosts(&request.cidr).ok();
+            let total = target.as_ref().map_or(0, Target::host_count);
             let _ = events.send(RuntimeEvent::ScanStarted { job, total }).await;
 
-            if let Some(network) = network {
-                let ips: Vec<Ipv4Addr> = if network.prefix() < 31 {
-                    network
-                        .iter()
-                        .skip(1)
-                        .take(network.size().saturating_sub(2) as usize)
-                        .collect()
-                } else {
-                    network.iter().collect()
-                };
-                let completed = Arc::new(AtomicU64::new(0));
-                let worker_completed = Arc::clone(&completed);
+            if let Some(target) = target {
+                let ips: Vec<Ipv4Addr> = target.ipv4_hosts().collect();
+                let network = target
+                    .enclosing_network()
+                    .and_then(|(address, prefix)| Ipv4Network::new(address, prefix).ok());
+                let progress = Arc::new(ProgressTracker::default());
+                progress.update(|progress| progress.probe.queued = ips.len() as u64);
+                let worker_progress = Arc::clone(&progress);
                 let worker_token = token.clone();
                 let worker_events = events.clone();
                 let worker_count = request.concurrency.max(1).min(ips.len().max(1));
+                // 被动扫描不能碰主机，端口探测与服务发现随之关闭。
+                let enrich = request.enrich && request.dns_zone.is_none();
+                // mDNS / SSDP 只需整网问一次，与逐台探测并行
+                let services = enrich.then(|| tokio::spawn(discovery::discover()));
+                // 补充探测在阻塞线程里跑，网页指纹借当前运行时执行
+                let enrichment = enrich.then(|| (tokio::runtime::Handle::current(), web::client()));
                 let workers = tokio::task::spawn_blocking(move || {
+                    if let Some(zone) = &request.dns_zone {
+                        dns_sweep(
+                            job,
+                            zone,
+                            &ips,
+                            worker_count.min(PTR_WORKERS),
+                            &worker_progress,
+                            &worker_token,
+                            &worker_events,
+                        );
+                        return;
+                    }
+                    // 目标不在本机任何子网时 ARP 无从应答，改为逐台 ICMP Echo + TCP 探测。
+                    let routed = !network.is_some_and(net::is_directly_attached);
+                    // 有原始套接字时先整网批量 ARP，之后只处理应答了的主机；
+                    // 否则每个工作线程逐台探测。
+                    worker_progress.update(|progress| {
+                        if routed {
+                            progress.method = ScanMethod::Routed;
+                        }
+                        progress.probe.in_flight = progress.probe.queued;
+                        progress.probe.queued = 0;
+                    });
+                    let swept = if routed {
+                        None
+                    } else {
+                        net::arp_sweep(&ips, || worker_token.is_cancelled())
+                    };
+                    let ips = match &swept {
+                        Some(found) => {
+                            let kept = !worker_token.is_cancelled();
+                            worker_progress.update(|progress| {
+                                let swept = std::mem::take(&mut progress.probe.in_flight);
+                                if kept {
+                                    progress.probe.done += swept;
+                                } else {
+                                    progress.probe.cancelled += swept;
+                                }
+                            });
+                            ips.into_iter()
+                                .filter(|ip| found.contains_key(ip))
+                                .collect()
+                        }
+                        None => {
+                            worker_progress.update(|progress| {
+                                progress.probe.queued =
+                                    std::mem::take(&mut progress.probe.in_flight);
+                            });
+                            ips
+                        }
+                    };
+                    let swept = swept.as_ref();
+                    // 自适应只调节逐台探测；批量 ARP 自带节奏。
+                    let limiter = (request.adaptive && swept.is_none())
+                        .then(|| Mutex::new(AdaptiveConcurrency::new(worker_count)));
+                    if limiter.is_some() {
+                        worker_progress.update(|progress| progress.workers = worker_count as u32);
+                    }
+                    let limiter = limiter.as_ref();
+                    // 分不到 ICMP 会话（identifier 用尽）时经路由的主机只做 TCP 探测
+                    let session = routed.then(|| {
+                        IcmpSession::open()
+                            .inspect_err(
+                                |error| tracing::warn!(%error, "routed scan without ICMP echo"),
+                            )
+                            .ok()
+                    });
+                    let session = session.as_ref().map(Option::as_ref);
                     let ips = Arc::new(ips);
                     let next = Arc::new(AtomicUsize::new(0));
                     std::thread::scope(|scope| {
-                        for _ in 0..worker_count {
+                        for worker in 0..worker_count {
                             let ips = Arc::clone(&ips);
                             let next = Arc::clone(&next);
-                            let completed = Arc::clone(&worker_completed);
+                            let progress = Arc::clone(&worker_progress);
                             let token = worker_token.clone();
                             let events = worker_events.clone();
-                            scope.spawn(move || loop {
-                                if token.is_cancelled() {
-                                    break;
-                                }
-                                let index = next.fetch_add(1, Ordering::Relaxed);
-                                let Some(&ip) = ips.get(index) else {
-                                    break;
-                                };
-
-                                if let Some(mac) = net::resolve_mac_address(ip)
-                                    && !token.is_cancelled()
-                                {
-                                    let hostname = net::resolve_hostname_until_cancelled(
-                                        IpAddr::V4(ip),
-                                        || token.is_cancelled(),
-                                    )
-                                    .unwrap_or_default();
-                                    if !token.is_cancelled() {
-                                        let _ = events.blocking_send(RuntimeEvent::ScanHostFound {
-                                            job,
-                                            host: ScanHost {
-                                                ip: ip.to_string(),
-                                                vendor: crate::utils::oui::lookup(&mac)
-                                                    .unwrap_or("-")
-                                                    .to_string(),
-                                                mac,
-                                                hostname,
-                                            },
+                            let enrichment = enrichment.as_ref();
+                            scope.spawn(move || {
+                                loop {
+                                    if token.is_cancelled()
+                                        || next.load(Ordering::Relaxed) >= ips.len()
+                                    {
+                                        break;
+                                    }
+                                    // 超出当前并发上限的线程先歇着，链路恢复后再接活。
+                                    if let Some(limiter) = limiter
+                                        && limiter
+                                            .lock()
+                                            .is_ok_and(|limiter| worker >= limiter.limit())
+                                    {
+                                        std::thread::sleep(PARKED_WORKER_NAP);
+                                        continue;
+                                    }
+                                    let index = next.fetch_add(1, Ordering::Relaxed);
+                                    let Some(&ip) = ips.get(index) else {
+                                        break;
+                                    };
+
+                                    let mut other_macs = Vec::new();
+                                    let mac = match swept {
+                                        Some(found) => found.get(&ip).and_then(|macs| {
+                                            let (mac, others) = macs.split_first()?;
+                                            other_macs = others.to_vec();
+                                            Some(mac.clone())
+                                        }),
+                                        None => {
+                                            progress.update(|progress| progress.probe.start());
+                                            let (mac, outcome) = match session {
+                                                Some(session) => routed_probe(session, ip),
+                                                None => net::probe_host(ip),
+                                            };
+                                            let workers = limiter.and_then(|limiter| {
+                                                let mut limiter = limiter.lock().ok()?;
+                                                limiter.record(outcome);
+                                                Some(limiter.limit() as u32)
+                                            });
+                                            progress.update(|progress| {
+                                                progress.probe.finish(!token.is_cancelled());
+                                                if let Some(workers) = workers {
+                                                    progress.workers = workers;
+                                                }
+                                            });
+                                            mac
+                                        }
+                                    };
+                                    if let Some(mac) = mac
+                                        && !token.is_cancelled()
+                                    {
+                                        progress.update(|progress| {
+                                            progress.resolve.queued += 1;
+                                            progress.resolve.start();
+                                            if enrichment.is_some() {
+                                                progress.enrich.queued += 1;
+                                            }
                                         });
+                                        let names = net::resolve_hostnames_until_cancelled(
+                                            IpAddr::V4(ip),
+                                            || token.is_cancelled(),
+                                        );
+                                        progress.update(|progress| {
+                                            progress.resolve.finish(!token.is_cancelled())
+                                        });
+                                        if !token.is_cancelled() {
+                                            let _ =
+                                                events.blocking_send(RuntimeEvent::ScanHostFound {
+                                                    job,
+                                                    host: Box::new(ScanHost {
+                                                        ip: ip.to_string(),
+                                                        vendor: crate::utils::oui::lookup(&mac)
+                                                            .unwrap_or("-")
+                                                            .to_string(),
+                                                        mac,
+                                                        other_macs,
+                                                        hostname: names
+                                                            .first()
+                                                            .map(|name| name.name.clone())
+                                                            .unwrap_or_default(),
+                                                        names,
+                                                        seen_at: chrono::Local::now()
+                                                            .format("%Y-%m-%d %H:%M")
+                                                            .to_string(),
+                                                        ..ScanHost::default()
+                                                    }),
+                                                });
+                                        }
+                                        // 补充探测较慢，主机先上表，结果随后补上
+                                        if let Some((runtime, client)) = enrichment
+                                            && !token.is_cancelled()
+                                        {
+                                            progress.update(|progress| progress.enrich.start());
+                                            let open_ports = open_ports(ip);
+                                            let shares = shares::enumerate(ip, &open_ports);
+                                            let iot = iot::probe(ip, &open_ports);
+                                            let smb1 = open_ports.contains(&SHARE_PORTS[0])
+                                                && shares::accepts_smb1(ip);
+                                            let banners = banner::grab(ip, &open_ports);
+                                            let web =
+                                                client.as_ref().map_or_else(Vec::new, |client| {
+                                                    runtime.block_on(web::fingerprint(
+                                                        client,
+                                                        ip,
+                                                        &open_ports,
+                                                    ))
+                                                });
+                                            progress.update(|progress| {
+                                                progress.enrich.finish(!token.is_cancelled())
+                                            });
+                                            if !token.is_cancelled() {
+                                                let _ = events.blocking_send(
+                                                    RuntimeEvent::ScanHostEnriched {
+                                                        job,
+                                                        ip: ip.to_string(),
+                                                        open_ports,
+                                                        shares,
+                                                        web,
+                                                        iot,
+                                                        smb1,
+                                                        banners,
+                                                    },
+                                                );
+                                            }
+                                        }
                                     }
                                 }
-                                completed.fetch_add(1, Ordering::Relaxed);
                             });
                         }
                     });
                 });
                 tokio::pin!(workers);
                 let mut ticker = tokio::time::interval(Duration::from_millis(250));
+                let mut rate = RateMeter::default();
                 loop {
                     tokio::select! {
                         _ = ticker.tick() => {
-                            let current = completed.load(Ordering::Relaxed);
-                            let _ = events.send(RuntimeEvent::ScanProgress { job, current, total }).await;
+                            let mut progress = progress.snapshot();
+                            progress.rate = rate.sample(progress.probe.settled());
+                            let _ = events.send(RuntimeEvent::ScanProgress { job, progress }).await;
                         }
                         result = &mut workers => {
                             if let Err(error) = result {
@@ -105,14 +286,23 @@ impl NativeRuntime {
                         }
                     }
                 }
-                let current = completed.load(Ordering::Relaxed);
+                // 工作线程都已退出；停止时把没做完的记为已取消
+                let mut progress = progress.snapshot();
+                if token.is_cancelled() {
+                    progress.cancel_remaining();
+                }
                 let _ = events
-                    .send(RuntimeEvent::ScanProgress {
-                        job,
-                        current,
-                        total,
-                    })
+                    .send(RuntimeEvent::ScanProgress { job, progress })
                     .await;
+                if let Some(services) = services {
+                    if token.is_cancelled() {
+                        services.abort();
+                    } else if let Ok(services) = services.await {
+                        let _ = events
+                            .send(RuntimeEvent::ScanServicesFound { job, services })
+                            .await;
+                    }
+                }
             }
 
             let terminal_event = if token.is_cancelled() {
@@ -126,6 +316,177 @@ impl NativeRuntime {
     }
 }
 
+/// 工作线程共享的分阶段进度；整体加锁，快照里各项计数彼此一致。
+#[derive(Default)]
+struct ProgressTracker(Mutex<ScanProgress>);
+
+impl ProgressTracker {
+    fn update(&self, change: impl FnOnce(&mut ScanProgress)) {
+        if let Ok(mut progress) = self.0.lock() {
+            change(&mut progress);
+        }
+    }
+
+    fn snapshot(&self) -> ScanProgress {
+        self.0.lock().map(|progress| *progress).unwrap_or_default()
+    }
+}
+
+/// 每秒扫过的地址数，约每秒更新一次，之间沿用上次的值。
+#[derive(Default)]
+struct RateMeter {
+    last: Option<(Instant, u64)>,
+    rate: u32,
+}
+
+impl RateMeter {
+    fn sample(&mut self, settled: u64) -> u32 {
+        let now = Instant::now();
+        match self.last {
+            Some((at, before)) if now.duration_since(at) >= RATE_WINDOW => {
+                let elapsed = now.duration_since(at).as_secs_f64();
+                self.rate = (settled.saturating_sub(before) as f64 / elapsed).round() as u32;
+                self.last = Some((now, settled));
+            }
+            Some(_) => {}
+            None => self.last = Some((now, settled)),
+        }
+        self.rate
+    }
+}
+
+/// 仅凭 DNS 找出网段内有名字的主机：区域传送允许时一次拿全，否则逐个地址查 PTR。
+/// 只保留 `zone` 之下的名称；不向主机发任何包。
+fn dns_sweep(
+    job: JobId,
+    zone: &str,
+    ips: &[Ipv4Addr],
+    worker_count: usize,
+    progress: &ProgressTracker,
+    token: &CancellationToken,
+    events: &mpsc::Sender<RuntimeEvent>,
+) {
+    let report = |ip: Ipv4Addr, names: Vec<String>| {
+        if !token.is_cancelled() {
+            let _ = events.blocking_send(RuntimeEvent::ScanHostFound {
+                job,
+                host: Box::new(named_host(ip, names)),
+            });
+        }
+    };
+    let resolvers = zone::system_resolvers();
+    let wanted: HashSet<Ipv4Addr> = ips.iter().copied().collect();
+    progress.update(|progress| {
+        progress.method = ScanMethod::ZoneTransfer;
+        progress.probe.in_flight = progress.probe.queued;
+        progress.probe.queued = 0;
+    });
+    if let Some(records) = zone::transfer(
+        zone,
+        &resolvers,
+        |ip| wanted.contains(&ip),
+        || token.is_cancelled(),
+    ) {
+        progress.update(|progress| {
+            progress.probe.done += std::mem::take(&mut progress.probe.in_flight);
+        });
+        let mut hosts: BTreeMap<Ipv4Addr, Vec<String>> = BTreeMap::new();
+        for (name, ip) in records {
+            let names = hosts.entry(ip).or_default();
+            if !names.iter().any(|known| net::dns_names_equal(known, &name)) {
+                names.push(name);
+            }
+        }
+        for (ip, names) in hosts {
+            report(ip, names);
+        }
+        return;
+    }
+
+    progress.update(|progress| {
+        progress.method = ScanMethod::Ptr;
+        progress.probe.queued = std::mem::take(&mut progress.probe.in_flight);
+    });
+    let server = resolvers.first().copied();
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| {
+                let resolver = zone::PtrResolver::new(server);
+                while !token.is_cancelled() {
+                    let Some(&ip) = ips.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    progress.update(|progress| progress.probe.start());
+                    let names: Vec<String> = resolver
+                        .lookup(ip)
+                        .into_iter()
+                        .filter(|name| zone::in_zone(name, zone))
+                        .collect();
+                    progress.update(|progress| progress.probe.finish(!token.is_cancelled()));
+                    if !names.is_empty() {
+                        report(ip, names);
+                    }
+                }
+            });
+        }
+    });
+}
+
+/// DNS 区域扫描发现的主机：没有 MAC 与厂商，名称全部来自 DNS。
+fn named_host(ip: Ipv4Addr, names: Vec<String>) -> ScanHost {
+    ScanHost {
+        ip: ip.to_string(),
+        mac: "-".into(),
+        vendor: "-".into(),
+        hostname: names.first().cloned().unwrap_or_default(),
+        names: names
+            .into_iter()
+            .map(|name| HostName {
+                source: NameSource::Dns,
+                name,
+            })
+            .collect(),
+        seen_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        ..ScanHost::default()
+    }
+}
+
+/// 经路由才能到达的主机：先发一个 ICMP Echo，没有回应（或本机发不了 ICMP）
+/// 再试 TCP 常见端口。路由另一侧的 MAC 不可知，在线时记为 "-"。
+fn routed_probe(session: Option<&IcmpSession>, ip: Ipv4Addr) -> (Option<String>, ProbeOutcome) {
+    let reached = session.is_some_and(|session| {
+        icmp::echo_once(session, ip, ROUTED_ECHO_TTL, ROUTED_ECHO_TIMEOUT_MS).reached()
+    });
+    let outcome = if reached {
+        ProbeOutcome::Clean
+    } else {
+        android::tcp_probe(ip)
+    };
+    (
+        (outcome == ProbeOutcome::Clean).then(|| "-".to_string()),
+        outcome,
+    )
+}
+
+/// 共享、网页、物联网、审计与问候语端口中能建立 TCP 连接的，升序。
+fn open_ports(ip: Ipv4Addr) -> Vec<u16> {
+    let mut ports = SHARE_PORTS
+        .into_iter()
+        .chain(WEB_PORTS)
+        .chain(IOT_PORTS)
+        .chain(AUDIT_PORTS)
+        .chain(BANNER_PORTS)
+        .collect::<Vec<_>>();
+    // FTP 既是审计端口也是问候语端口，只连一次
+    ports.sort_unstable();
+    ports.dedup();
+    ports.retain(|port| {
+        TcpStream::connect_timeout(&SocketAddr::new(IpAddr::V4(ip), *port), PORT_TIMEOUT).is_ok()
+    });
+    ports
+}
+
 #[cfg(test)]
 mod tests {
     use iptools_core::{
@@ -138,14 +499,20 @@ mod tests {
     async fn native_handler_drives_the_shared_scanner_reducer() {
         let mut model = AppModel::default();
         model.page = Page::Scanner;
-        model.scanner.cidr = "invalid-cidr".into();
-        let effect = model
+        model.scanner.cidr = "192.168.0.0/30".into();
+        let Some(iptools_core::Effect::StartScan { job, mut request }) = model
             .update(Message::Input(InputEvent::Action(Action::Toggle)))
             .into_iter()
             .find(|effect| matches!(effect, iptools_core::Effect::StartScan { .. }))
-            .expect("scanner should emit a start effect");
+        else {
+            panic!("scanner should emit a start effect");
+        };
+        // 绕过核心层校验的请求照样要结束任务，不能让扫描器卡在运行中。
+        request.cidr = "invalid-cidr".into();
         let mut runtime = NativeRuntime::new();
-        runtime.dispatch(effect).unwrap();
+        runtime
+            .dispatch(iptools_core::Effect::StartScan { job, request })
+            .unwrap();
 
         for _ in 0..64 {
             tokio::task::yield_now().await;
@@ -181,6 +548,9 @@ mod tests {
                 request: ScanRequest {
                     cidr: "192.0.2.1/32".into(),
                     concurrency: 1,
+                    adaptive: false,
+                    enrich: false,
+                    dns_zone: None,
                 },
             })
             .unwrap();
@@ -190,6 +560,9 @@ mod tests {
                 request: ScanRequest {
                     cidr: "invalid-cidr".into(),
                     concurrency: 1,
+                    adaptive: false,
+                    enrich: false,
+                    dns_zone: None,
                 },
             })
             .unwrap();
@@ -0,0 +1,96 @@
+//! 查询 GitHub 上的最新发行版；是否比当前版本新由模型判断。
+
+use iptools_core::{JobId, ReleaseInfo, RuntimeError, RuntimeErrorCode, RuntimeEvent};
+use serde::Deserialize;
+
+use super::{NativeRuntime, RuntimeTaskError};
+
+/// 与 Cargo.toml 的 `repository` 为同一仓库。
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/newcovid/iptools/releases/latest";
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+impl NativeRuntime {
+    pub(super) fn spawn_update_check(&mut self, job: JobId, use_system_proxy: bool) {
+        self.spawn(job, move |token, events| async move {
+            let event = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                result = fetch_latest(use_system_proxy) => match result {
+                    Ok(release) => RuntimeEvent::UpdateCheckFinished { job, release },
+                    Err(error) => RuntimeEvent::UpdateCheckFailed { job, error },
+                },
+            };
+            events
+                .send(event)
+                .await
+                .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+        });
+    }
+}
+
+async fn fetch_latest(use_system_proxy: bool) -> Result<ReleaseInfo, RuntimeError> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        // GitHub API 拒绝没有 User-Agent 的请求。
+        .user_agent(concat!("iptools/", env!("CARGO_PKG_VERSION")));
+    if !use_system_proxy {
+        builder = builder.no_proxy();
+    }
+    let client = builder
+        .build()
+        .map_err(|error| RuntimeError::new(RuntimeErrorCode::Internal, error.to_string()))?;
+    let network =
+        |error: reqwest::Error| RuntimeError::new(RuntimeErrorCode::Network, error.to_string());
+    let body = client
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(network)?
+        .text()
+        .await
+        .map_err(network)?;
+    parse_release(&body)
+}
+
+fn parse_release(body: &str) -> Result<ReleaseInfo, RuntimeError> {
+    let release: GithubRelease = serde_json::from_str(body).map_err(|error| {
+        RuntimeError::new(
+            RuntimeErrorCode::Network,
+            format!("unexpected release response: {error}"),
+        )
+    })?;
+    Ok(ReleaseInfo {
+        version: release.tag_name,
+        url: release.html_url,
+        notes: release.body.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_release_json_is_reduced_to_tag_page_and_notes() {
+        let body = r#"{"tag_name":"v0.5.0","html_url":"https://github.com/newcovid/iptools/releases/tag/v0.5.0","body":"- Faster scans","draft":false}"#;
+        assert_eq!(
+            parse_release(body),
+            Ok(ReleaseInfo {
+                version: "v0.5.0".into(),
+                url: "https://github.com/newcovid/iptools/releases/tag/v0.5.0".into(),
+                notes: "- Faster scans".into(),
+            })
+        );
+        let no_notes = r#"{"tag_name":"v0.5.0","html_url":"https://x.test","body":null}"#;
+        assert_eq!(parse_release(no_notes).unwrap().notes, "");
+        assert!(parse_release("<html>rate limited</html>").is_err());
+    }
+}
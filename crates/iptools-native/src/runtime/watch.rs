@@ -0,0 +1,178 @@
+//! 仪表盘监视目标：按目标的写法做一次 ICMP Echo、TCP 连接或 HTTP GET，
+//! 每个目标的结果单独上报，一轮检查完后结束任务，下一轮由核心层按间隔发起。
+//!
+//! 监视的多是内网服务，HTTP 检查不走系统代理。
+
+use std::net::{IpAddr, SocketAddr};
+
+use futures::{StreamExt, stream};
+use iptools_core::{JobId, RuntimeEvent, WatchCheck, WatchProbe, WatchRequest, WatchTarget};
+use tokio::net::TcpStream;
+use tokio::time::{Duration, Instant, timeout};
+
+use super::{NativeRuntime, RuntimeTaskError};
+use crate::modules::diagnostics::icmp::{self, IcmpSession};
+use crate::secrets;
+
+/// 同时检查的目标数
+const PARALLEL_TARGETS: usize = 8;
+
+impl NativeRuntime {
+    pub(super) fn spawn_watch(&mut self, job: JobId, request: WatchRequest) {
+        self.spawn(job, move |token, events| async move {
+            let send = |event| {
+                let events = events.clone();
+                async move {
+                    events
+                        .send(event)
+                        .await
+                        .map_err(|error| RuntimeTaskError::Operation(error.to_string()))
+                }
+            };
+            let budget = Duration::from_millis(request.timeout_ms.clamp(100, 10_000));
+            let client = reqwest::Client::builder()
+                .timeout(budget)
+                .user_agent(concat!("iptools/", env!("CARGO_PKG_VERSION")))
+                .no_proxy()
+                .build()
+                .ok();
+            let mut checks = stream::iter(request.targets)
+                .map(|target| check(target, client.as_ref(), budget))
+                .buffer_unordered(PARALLEL_TARGETS);
+            loop {
+                let check = tokio::select! {
+                    _ = token.cancelled() => return Ok(()),
+                    check = checks.next() => check,
+                };
+                match check {
+                    Some(check) => send(RuntimeEvent::WatchChecked { job, check }).await?,
+                    None => break,
+                }
+            }
+            send(RuntimeEvent::WatchFinished { job }).await
+        });
+    }
+}
+
+async fn check(
+    target: WatchTarget,
+    client: Option<&reqwest::Client>,
+    budget: Duration,
+) -> WatchCheck {
+    let result = match target.probe() {
+        Some(WatchProbe::Icmp(host)) => echo(&host, budget).await,
+        Some(WatchProbe::Tcp(host, port)) => match address(&host).await {
+            Ok(ip) => connect(SocketAddr::new(ip, port), budget).await,
+            Err(error) => Err(error),
+        },
+        Some(WatchProbe::Http(url)) => match client {
+            Some(client) => match secrets::expand_async(&url).await {
+                Ok(expanded) => get(client, &expanded, expanded != url).await,
+                Err(error) => Err(error.message),
+            },
+            None => Err("HTTP client unavailable".into()),
+        },
+        None => Err(format!("{} is not a host or URL", target.target)),
+    };
+    let (latency_ms, error) = match result {
+        Ok(latency) => (Some(latency), None),
+        Err(error) => (None, Some(error)),
+    };
+    WatchCheck {
+        target,
+        latency_ms,
+        error,
+    }
+}
+
+async fn address(host: &str) -> Result<IpAddr, String> {
+    crate::modules::diagnostics::resolve_target(host)
+        .await
+        .map_err(|error| error.message)?
+        .first()
+        .copied()
+        .ok_or_else(|| format!("{host} has no address"))
+}
+
+/// ICMP Echo 只支持 IPv4；IPv6 目标请写成 主机:端口 用 TCP 检查。
+async fn echo(host: &str, budget: Duration) -> Result<f64, String> {
+    let IpAddr::V4(ip) = address(host).await? else {
+        return Err("ping is IPv4 only; add a port to check over TCP".into());
+    };
+    let timeout_ms = budget.as_millis() as u32;
+    let echo = tokio::task::spawn_blocking(move || {
+        IcmpSession::open().map(|session| icmp::echo_once(&session, ip, 64, timeout_ms))
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+    match echo.rtt_ms {
+        Some(rtt) if echo.reached() => Ok(rtt as f64),
+        _ => Err("no echo reply".into()),
+    }
+}
+
+async fn connect(address: SocketAddr, budget: Duration) -> Result<f64, String> {
+    let started = Instant::now();
+    match timeout(budget, TcpStream::connect(address)).await {
+        Ok(Ok(_)) => Ok(started.elapsed().as_secs_f64() * 1_000.0),
+        Ok(Err(error)) => Err(error.to_string()),
+        Err(_) => Err("connection timed out".into()),
+    }
+}
+
+/// 4xx 与 5xx 都算失败：服务在，但答不出正常页面。`secret` 的地址含密钥，
+/// 错误信息里去掉地址。
+async fn get(client: &reqwest::Client, url: &str, secret: bool) -> Result<f64, String> {
+    let started = Instant::now();
+    let response = client.get(url).send().await.map_err(|error| {
+        if error.is_timeout() {
+            "request timed out".to_string()
+        } else if secret {
+            error.without_url().to_string()
+        } else {
+            error.to_string()
+        }
+    })?;
+    let status = response.status();
+    if status.as_u16() >= 400 {
+        return Err(format!("HTTP {}", status.as_u16()));
+    }
+    Ok(started.elapsed().as_secs_f64() * 1_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tcp_targets_are_timed_and_refusals_reported() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        tokio::spawn(async move { while listener.accept().await.is_ok() {} });
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let targets =
+            iptools_core::parse_watch_targets(&format!("up={open}, down={closed}")).unwrap();
+        let budget = Duration::from_millis(500);
+
+        let up = check(targets[0].clone(), None, budget).await;
+        assert!(up.latency_ms.is_some(), "{up:?}");
+        let down = check(targets[1].clone(), None, budget).await;
+        assert!(
+            down.latency_ms.is_none() && down.error.is_some(),
+            "{down:?}"
+        );
+        let unparsed = WatchTarget {
+            name: "bad".into(),
+            target: "bad host".into(),
+            ..WatchTarget::default()
+        };
+        assert_eq!(
+            check(unparsed, None, budget).await.error.as_deref(),
+            Some("bad host is not a host or URL")
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! 系统钥匙串中的密钥：Windows 凭据管理器、Linux 的 Secret Service、macOS 钥匙串。
+//!
+//! 配置文件只写 `${secret:名称}` 引用，发请求前才在这里换成真实值，界面、日志与
+//! 导出的归档都见不到明文。钥匙串调用会阻塞，异步代码里经 `spawn_blocking` 调用。
+
+use iptools_core::{RuntimeError, RuntimeErrorCode, SECRET_SERVICE, is_secret_name};
+
+fn entry(name: &str) -> Result<keyring::Entry, String> {
+    if !is_secret_name(name) {
+        return Err(format!(
+            "invalid secret name {name:?}: use 1-64 letters, digits, '-', '_' or '.'"
+        ));
+    }
+    keyring::Entry::new(SECRET_SERVICE, name).map_err(|error| error.to_string())
+}
+
+/// 读取密钥；不存在时返回 `Ok(None)`。
+pub fn get(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+pub fn set(name: &str, value: &str) -> Result<(), String> {
+    entry(name)?
+        .set_password(value)
+        .map_err(|error| error.to_string())
+}
+
+/// 删除密钥；本就不存在时返回 `Ok(false)`。
+pub fn delete(name: &str) -> Result<bool, String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// 把 `text` 里的引用换成钥匙串中的值；没有引用时不碰钥匙串。
+pub fn expand(text: &str) -> Result<String, RuntimeError> {
+    iptools_core::expand_secrets(text, |name| match get(name) {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(RuntimeError::new(
+            RuntimeErrorCode::InvalidRequest,
+            format!(
+                "secret {name:?} is not in the system keyring; run `iptools secret set {name}`"
+            ),
+        )),
+        Err(error) => Err(RuntimeError::new(
+            RuntimeErrorCode::PermissionDenied,
+            format!("cannot read secret {name:?} from the system keyring: {error}"),
+        )),
+    })
+}
+
+/// 异步版 [`expand`]：只有含引用时才转到阻塞线程。
+pub async fn expand_async(text: &str) -> Result<String, RuntimeError> {
+    if iptools_core::secret_names(text).is_empty() {
+        return Ok(text.to_string());
+    }
+    let text = text.to_string();
+    tokio::task::spawn_blocking(move || expand(&text))
+        .await
+        .map_err(|error| RuntimeError::new(RuntimeErrorCode::Internal, error.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_without_references_never_reaches_the_keyring() {
+        assert_eq!(
+            expand("https://ipinfo.io/json").unwrap(),
+            "https://ipinfo.io/json"
+        );
+        assert!(entry("has space").is_err());
+        assert!(get("").is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! 开机自启：`iptools install-service` 把守护进程注册为系统服务，重启后继续后台监视。
+//! Linux 上是 systemd 单元，Windows 上是服务控制管理器里的服务；两者都以安装者的身份
+//! 运行守护进程，登录后直接运行 `iptools` 即连接到它。
+
+#[cfg(windows)]
+mod scm;
+#[cfg(target_os = "linux")]
+mod systemd;
+
+#[cfg(windows)]
+pub(crate) use scm::{ServiceSpec, install, run, uninstall};
+#[cfg(target_os = "linux")]
+pub(crate) use systemd::{ServiceSpec, install, uninstall};
@@ -0,0 +1,398 @@
+//! Windows 服务：在服务控制管理器里注册自动启动的 `iptools` 服务，开机即启动。
+//!
+//! 服务以执行安装的账户运行，不用 LocalSystem：界面能修改网卡配置、导出文件，而同一用户的
+//! 任何进程都能读到端点令牌连上来，以 LocalSystem 运行等于把这些操作交给未提升的进程。
+//! 以用户身份登录需要密码，安装时在控制台输入（不回显），由服务控制管理器保存；
+//! 同时为账户授予“作为服务登录”的权限。
+//!
+//! 服务进程的临时目录与环境变量不一定与登录会话相同，安装时记下单实例锁的路径，
+//! 作为参数交给守护进程，登录后运行的 `iptools` 才能找到它的端点文件。
+//! 服务没有控制台，守护进程异常退出的原因追加到配置文件旁的 [`LOG_FILE`]。
+
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use windows_service::service::{
+    ServiceAccess, ServiceAction, ServiceActionType, ServiceControl, ServiceControlAccept,
+    ServiceErrorControl, ServiceExitCode, ServiceFailureActions, ServiceFailureResetPeriod,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+use crate::{daemon, instance};
+
+const SERVICE_NAME: &str = "iptools";
+const DISPLAY_NAME: &str = "iptools network monitor";
+const LOG_FILE: &str = "iptools-service.log";
+/// 失败后重启前的等待，同 systemd 单元的 `RestartSec`。
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+/// 卸载时最多等待守护进程保存会话并退出这么久。
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+const ERROR_ACCESS_DENIED: i32 = 5;
+const ERROR_SERVICE_DOES_NOT_EXIST: i32 = 1060;
+const ERROR_SERVICE_LOGON_FAILED: i32 = 1069;
+const ERROR_SERVICE_EXISTS: i32 = 1073;
+
+/// 服务里守护进程的启动方式。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ServiceSpec {
+    pub executable: PathBuf,
+    pub config: PathBuf,
+    pub offline: bool,
+    pub listen: Option<String>,
+    /// `计算机名或域\用户名`。
+    pub account: String,
+    /// 安装者会话里的单实例锁路径。
+    pub lock: PathBuf,
+}
+
+impl ServiceSpec {
+    /// 当前二进制、配置文件的绝对路径与安装者账户。
+    pub(crate) fn current(
+        config_path: Option<&str>,
+        offline: bool,
+        listen: Option<String>,
+    ) -> Result<Self> {
+        let executable = std::env::current_exe().context("无法确定 iptools 的路径")?;
+        let config = std::path::absolute(config_path.unwrap_or("config.toml"))
+            .context("无法确定配置文件路径")?;
+        let user = std::env::var("USERNAME")
+            .ok()
+            .filter(|user| !user.is_empty())
+            .context("无法确定运行服务的用户")?;
+        let domain = std::env::var("USERDOMAIN")
+            .ok()
+            .filter(|domain| !domain.is_empty())
+            .unwrap_or_else(|| ".".into());
+        Ok(Self {
+            executable,
+            config,
+            offline,
+            listen,
+            account: format!("{domain}\\{user}"),
+            lock: instance::lock_path(),
+        })
+    }
+
+    /// 服务启动 iptools 时的参数。
+    pub(crate) fn arguments(&self) -> Vec<OsString> {
+        let mut arguments: Vec<OsString> = vec!["--config".into(), self.config.clone().into()];
+        if self.offline {
+            arguments.push("--offline".into());
+        }
+        arguments.extend([
+            "daemon".into(),
+            "--service".into(),
+            "--lock".into(),
+            self.lock.clone().into(),
+        ]);
+        if let Some(listen) = &self.listen {
+            arguments.push("--listen".into());
+            arguments.push(listen.into());
+        }
+        arguments
+    }
+
+    /// `--print` 的内容：将要注册的服务。
+    pub(crate) fn describe(&self) -> String {
+        let command = std::iter::once(self.executable.as_os_str())
+            .chain(self.arguments().iter().map(OsString::as_os_str))
+            .map(|argument| {
+                let argument = argument.to_string_lossy();
+                if argument.contains(char::is_whitespace) {
+                    format!("\"{argument}\"")
+                } else {
+                    argument.into_owned()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "服务名：{SERVICE_NAME}（{DISPLAY_NAME}）\n\
+             启动类型：自动\n\
+             账户：{}\n\
+             命令行：{command}\n\
+             失败后：{} 秒后重启\n",
+            self.account,
+            RESTART_DELAY.as_secs()
+        )
+    }
+}
+
+/// 注册服务并立即启动；需要管理员权限。
+pub(crate) fn install(spec: &ServiceSpec) -> Result<()> {
+    // 先确认有权限，再问密码
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .map_err(scm_error)?;
+    let password = rpassword::prompt_password(format!(
+        "{} 的登录密码（服务以此账户运行，输入不回显）：",
+        spec.account
+    ))
+    .context("无法读取密码")?;
+    grant_service_logon(&spec.account)?;
+    let info = ServiceInfo {
+        name: SERVICE_NAME.into(),
+        display_name: DISPLAY_NAME.into(),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: spec.executable.clone(),
+        launch_arguments: spec.arguments(),
+        dependencies: Vec::new(),
+        account_name: Some(spec.account.clone().into()),
+        account_password: Some(password.into()),
+    };
+    let service = manager
+        .create_service(&info, ServiceAccess::CHANGE_CONFIG | ServiceAccess::START)
+        .map_err(scm_error)?;
+    service
+        .set_description(concat!(
+            "Keeps iptools monitors, scans and traffic collection running in the background. ",
+            env!("CARGO_PKG_REPOSITORY")
+        ))
+        .map_err(scm_error)?;
+    // 同 systemd 的 Restart=on-failure：崩溃与非零退出都重启，一天后重新计数
+    service
+        .update_failure_actions(ServiceFailureActions {
+            reset_period: ServiceFailureResetPeriod::After(Duration::from_secs(24 * 60 * 60)),
+            reboot_msg: None,
+            command: None,
+            actions: Some(vec![
+                ServiceAction {
+                    action_type: ServiceActionType::Restart,
+                    delay: RESTART_DELAY,
+                };
+                3
+            ]),
+        })
+        .map_err(scm_error)?;
+    service
+        .set_failure_actions_on_non_crash_failures(true)
+        .map_err(scm_error)?;
+    service.start::<&OsStr>(&[]).map_err(|error| {
+        scm_error(error).context("服务已注册但无法启动，可用 --uninstall 删除后重新安装")
+    })
+}
+
+/// 停止并删除服务；需要管理员权限。
+pub(crate) fn uninstall() -> Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .map_err(scm_error)?;
+    let service = manager
+        .open_service(
+            SERVICE_NAME,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )
+        .map_err(scm_error)?;
+    if service.query_status().map_err(scm_error)?.current_state == ServiceState::Running {
+        service.stop().map_err(scm_error)?;
+    }
+    let until = Instant::now() + STOP_TIMEOUT;
+    while service.query_status().map_err(scm_error)?.current_state != ServiceState::Stopped {
+        if Instant::now() >= until {
+            bail!("服务 {} 秒内没有停止", STOP_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    service.delete().map_err(scm_error)
+}
+
+/// 服务控制管理器回调的 `service_main` 要运行的守护进程，由 [`run`] 放在这里。
+static LAUNCH: Mutex<Option<Launch>> = Mutex::new(None);
+
+struct Launch {
+    daemon: Box<dyn FnOnce() -> Result<()> + Send>,
+    log: PathBuf,
+}
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// `iptools daemon --service`：把 `daemon` 交给服务控制管理器运行，服务停止后返回。
+/// 守护进程出错时原因追加到 `config` 旁的 [`LOG_FILE`]，服务以非零退出码停止并由它重启。
+pub(crate) fn run(
+    config: &Path,
+    daemon: impl FnOnce() -> Result<()> + Send + 'static,
+) -> Result<()> {
+    *LAUNCH.lock().unwrap_or_else(PoisonError::into_inner) = Some(Launch {
+        daemon: Box::new(daemon),
+        log: config.with_file_name(LOG_FILE),
+    });
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+        .context("daemon --service 只能由服务控制管理器启动")
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    let Some(Launch {
+        daemon: run_daemon,
+        log,
+    }) = LAUNCH.lock().unwrap_or_else(PoisonError::into_inner).take()
+    else {
+        return;
+    };
+    let handler = |control| match control {
+        // 与 iptools daemon --stop 相同：守护进程保存会话后退出，主循环随之结束
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            std::thread::spawn(|| {
+                if let Some(local) = daemon::connect() {
+                    let _ = daemon::stop(local);
+                }
+            });
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    let status = match service_control_handler::register(SERVICE_NAME, handler) {
+        Ok(status) => status,
+        Err(error) => {
+            record_failure(&log, &error.into());
+            return;
+        }
+    };
+    let report = |current_state, controls_accepted, exit_code| {
+        let _ = status.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state,
+            controls_accepted,
+            exit_code,
+            checkpoint: 0,
+            wait_hint: Duration::ZERO,
+            process_id: None,
+        });
+    };
+    report(
+        ServiceState::Running,
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+        ServiceExitCode::NO_ERROR,
+    );
+    let exit_code = match run_daemon() {
+        Ok(()) => ServiceExitCode::NO_ERROR,
+        Err(error) => {
+            record_failure(&log, &error);
+            ServiceExitCode::ServiceSpecific(1)
+        }
+    };
+    report(
+        ServiceState::Stopped,
+        ServiceControlAccept::empty(),
+        exit_code,
+    );
+}
+
+fn record_failure(log: &Path, error: &anyhow::Error) {
+    let line = format!(
+        "{} {error:#}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    let _ = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+}
+
+/// 服务控制管理器的常见错误换成可操作的提示。
+fn scm_error(error: windows_service::Error) -> anyhow::Error {
+    let code = match &error {
+        windows_service::Error::Winapi(error) => error.raw_os_error(),
+        _ => None,
+    };
+    match code {
+        Some(ERROR_ACCESS_DENIED) => {
+            anyhow!("需要管理员权限：请在“以管理员身份运行”的终端里执行 iptools install-service")
+        }
+        Some(ERROR_SERVICE_EXISTS) => {
+            anyhow!("服务 {SERVICE_NAME} 已存在；先执行 iptools install-service --uninstall")
+        }
+        Some(ERROR_SERVICE_DOES_NOT_EXIST) => anyhow!("没有已安装的服务（{SERVICE_NAME}）"),
+        Some(ERROR_SERVICE_LOGON_FAILED) => anyhow!("服务无法以该账户登录，多半是密码不对"),
+        _ => error.into(),
+    }
+}
+
+/// 为账户授予“作为服务登录”（SeServiceLogonRight）；已有时不变。
+/// 服务控制管理器注册以用户身份运行的服务时不会自动授予，缺了它服务启动即失败。
+fn grant_service_logon(account: &str) -> Result<()> {
+    use windows::Win32::Foundation::NTSTATUS;
+    use windows::Win32::Security::Authentication::Identity::{
+        LSA_HANDLE, LSA_OBJECT_ATTRIBUTES, LSA_UNICODE_STRING, LsaAddAccountRights, LsaClose,
+        LsaNtStatusToWinError, LsaOpenPolicy, POLICY_CREATE_ACCOUNT, POLICY_LOOKUP_NAMES,
+    };
+    use windows::Win32::Security::{LookupAccountNameW, PSID, SID_NAME_USE};
+    use windows::core::{HSTRING, PCWSTR, PWSTR};
+
+    fn lsa_result(status: NTSTATUS) -> io::Result<()> {
+        if status.is_ok() {
+            return Ok(());
+        }
+        // SAFETY: 只换算状态码，不涉及指针。
+        let code = unsafe { LsaNtStatusToWinError(status) };
+        Err(io::Error::from_raw_os_error(code as i32))
+    }
+
+    let name = HSTRING::from(account);
+    let (mut sid_len, mut domain_len, mut kind) = (0_u32, 0_u32, SID_NAME_USE::default());
+    // 第一次调用只取缓冲区大小，必然以 ERROR_INSUFFICIENT_BUFFER 失败
+    // SAFETY: 缓冲区为空，长度为 0。
+    let _ = unsafe {
+        LookupAccountNameW(
+            PCWSTR::null(),
+            &name,
+            PSID::default(),
+            &mut sid_len,
+            PWSTR::null(),
+            &mut domain_len,
+            &mut kind,
+        )
+    };
+    // SID 按 DWORD 对齐
+    let mut sid = vec![0_u32; (sid_len as usize).div_ceil(4)];
+    let mut domain = vec![0_u16; domain_len as usize];
+    // SAFETY: 两个缓冲区都按上一次调用给出的长度分配。
+    unsafe {
+        LookupAccountNameW(
+            PCWSTR::null(),
+            &name,
+            PSID(sid.as_mut_ptr().cast()),
+            &mut sid_len,
+            PWSTR(domain.as_mut_ptr()),
+            &mut domain_len,
+            &mut kind,
+        )
+    }
+    .with_context(|| format!("找不到账户 {account}"))?;
+
+    let mut policy = LSA_HANDLE::default();
+    // SAFETY: 对象属性按文档要求全部为零。
+    let status = unsafe {
+        LsaOpenPolicy(
+            None,
+            &LSA_OBJECT_ATTRIBUTES::default(),
+            (POLICY_CREATE_ACCOUNT | POLICY_LOOKUP_NAMES) as u32,
+            &mut policy,
+        )
+    };
+    lsa_result(status).context("无法打开本机安全策略")?;
+    let mut right: Vec<u16> = "SeServiceLogonRight".encode_utf16().collect();
+    let length = (right.len() * 2) as u16;
+    let rights = [LSA_UNICODE_STRING {
+        Length: length,
+        MaximumLength: length,
+        Buffer: PWSTR(right.as_mut_ptr()),
+    }];
+    // SAFETY: 句柄刚打开，SID 与权限名在调用期间有效。
+    let status = unsafe { LsaAddAccountRights(policy, PSID(sid.as_mut_ptr().cast()), &rights) };
+    // SAFETY: 句柄由 LsaOpenPolicy 打开，只关闭一次。
+    let _ = unsafe { LsaClose(policy) };
+    lsa_result(status).with_context(|| format!("无法授予 {account} 作为服务登录的权限"))
+}
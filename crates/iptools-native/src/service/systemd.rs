@@ -0,0 +1,205 @@
+//! systemd 系统服务：单元写在 `/etc/systemd/system`，开机即启动。
+//!
+//! 单元以安装者（`sudo` 前的用户）身份运行，只授予 CAP_NET_RAW，二进制本身不必 setcap。
+//! 单实例锁与套接字在用户运行目录里，单元依赖 `user-runtime-dir@<uid>.service`，
+//! 未登录时也会建好这个目录；登录后直接运行 `iptools` 即连接到服务里的守护进程。
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use nix::unistd::{Uid, User};
+
+const UNIT_NAME: &str = "iptools.service";
+const UNIT_PATH: &str = "/etc/systemd/system/iptools.service";
+
+/// 服务里守护进程的启动方式。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ServiceSpec {
+    pub executable: PathBuf,
+    pub config: PathBuf,
+    pub offline: bool,
+    pub listen: Option<String>,
+    pub user: String,
+    pub uid: u32,
+    pub home: PathBuf,
+}
+
+impl ServiceSpec {
+    /// 当前二进制、配置文件的绝对路径与安装者账户。
+    pub(crate) fn current(
+        config_path: Option<&str>,
+        offline: bool,
+        listen: Option<String>,
+    ) -> Result<Self> {
+        let executable = std::env::current_exe()
+            .and_then(|path| path.canonicalize())
+            .context("无法确定 iptools 的路径")?;
+        let config = std::path::absolute(config_path.unwrap_or("config.toml"))
+            .context("无法确定配置文件路径")?;
+        // sudo 下服务仍以原用户身份运行，配置与历史库的属主不变
+        let account = match std::env::var("SUDO_USER") {
+            Ok(name) if Uid::effective().is_root() && !name.is_empty() => User::from_name(&name),
+            _ => User::from_uid(Uid::current()),
+        }
+        .ok()
+        .flatten()
+        .context("无法确定运行服务的用户")?;
+        Ok(Self {
+            executable,
+            config,
+            offline,
+            listen,
+            user: account.name,
+            uid: account.uid.as_raw(),
+            home: account.dir,
+        })
+    }
+
+    /// systemd 单元文件内容。
+    pub(crate) fn unit(&self) -> String {
+        let mut command = vec![
+            quote(&self.executable.to_string_lossy()),
+            "--config".into(),
+            quote(&self.config.to_string_lossy()),
+        ];
+        if self.offline {
+            command.push("--offline".into());
+        }
+        command.push("daemon".into());
+        if let Some(listen) = &self.listen {
+            command.push("--listen".into());
+            command.push(quote(listen));
+        }
+        let uid = self.uid;
+        format!(
+            "# 由 iptools install-service 生成；卸载：sudo iptools install-service --uninstall\n\
+             [Unit]\n\
+             Description=iptools network monitor daemon\n\
+             Documentation={repository}\n\
+             Wants=network-online.target\n\
+             Requires=user-runtime-dir@{uid}.service\n\
+             After=network-online.target user-runtime-dir@{uid}.service\n\
+             \n\
+             [Service]\n\
+             Type=simple\n\
+             User={user}\n\
+             Environment=XDG_RUNTIME_DIR=/run/user/{uid}\n\
+             WorkingDirectory={home}\n\
+             ExecStart={command}\n\
+             AmbientCapabilities=CAP_NET_RAW\n\
+             CapabilityBoundingSet=CAP_NET_RAW\n\
+             NoNewPrivileges=yes\n\
+             Restart=on-failure\n\
+             RestartSec=5\n\
+             \n\
+             [Install]\n\
+             WantedBy=multi-user.target\n",
+            repository = env!("CARGO_PKG_REPOSITORY"),
+            user = self.user.replace('%', "%%"),
+            home = unit_path(&self.home),
+            command = command.join(" "),
+        )
+    }
+}
+
+/// 写入单元并立即启用；返回单元文件路径。
+pub(crate) fn install(spec: &ServiceSpec) -> Result<&'static Path> {
+    require_systemd()?;
+    std::fs::write(UNIT_PATH, spec.unit()).with_context(|| format!("无法写入 {UNIT_PATH}"))?;
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", UNIT_NAME])?;
+    Ok(Path::new(UNIT_PATH))
+}
+
+/// 停用并删除单元。
+pub(crate) fn uninstall() -> Result<()> {
+    require_systemd()?;
+    if !Path::new(UNIT_PATH).exists() {
+        bail!("没有已安装的服务（{UNIT_PATH} 不存在）");
+    }
+    systemctl(&["disable", "--now", UNIT_NAME])?;
+    std::fs::remove_file(UNIT_PATH).with_context(|| format!("无法删除 {UNIT_PATH}"))?;
+    systemctl(&["daemon-reload"])
+}
+
+fn require_systemd() -> Result<()> {
+    if !Path::new("/run/systemd/system").is_dir() {
+        bail!("本机没有以 systemd 启动，无法注册服务；可用 --print 查看单元内容自行改写");
+    }
+    if !Uid::effective().is_root() {
+        bail!("需要 root 权限，请用：sudo iptools install-service");
+    }
+    Ok(())
+}
+
+fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .args(args)
+        .status()
+        .context("无法运行 systemctl")?;
+    if !status.success() {
+        bail!("systemctl {} 失败（{status}）", args.join(" "));
+    }
+    Ok(())
+}
+
+/// systemd 的命令行引号：含空白、引号、反斜杠或 `%` 时加双引号转义。
+fn quote(value: &str) -> String {
+    let value = value.replace('%', "%%");
+    if !value.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\\' | '\'')) {
+        return value;
+    }
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 单元里的路径设置（如 `WorkingDirectory=`）：不认引号，空白与控制字符写成 `\xNN`，
+/// 反斜杠与 `%` 各自转义。
+fn unit_path(path: &Path) -> String {
+    let mut escaped = String::new();
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '%' => escaped.push_str("%%"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_ascii_whitespace() || c.is_ascii_control() => {
+                escaped.push_str(&format!("\\x{:02x}", c as u32));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_runs_the_daemon_as_the_installing_user_with_raw_sockets() {
+        let spec = ServiceSpec {
+            executable: "/usr/local/bin/iptools".into(),
+            config: "/home/alice/My Config/config.toml".into(),
+            offline: true,
+            listen: Some("0.0.0.0:7878".into()),
+            user: "alice".into(),
+            uid: 1000,
+            home: "/home/alice smith/100%".into(),
+        };
+        let unit = spec.unit();
+        assert!(unit.contains(
+            "\nExecStart=/usr/local/bin/iptools --config \"/home/alice/My Config/config.toml\" --offline daemon --listen 0.0.0.0:7878\n"
+        ));
+        assert!(unit.contains("\nUser=alice\n"));
+        assert!(unit.contains("\nWorkingDirectory=/home/alice\\x20smith/100%%\n"));
+        assert!(unit.contains("\nEnvironment=XDG_RUNTIME_DIR=/run/user/1000\n"));
+        assert!(unit.contains("\nRequires=user-runtime-dir@1000.service\n"));
+        assert!(unit.contains("\nAmbientCapabilities=CAP_NET_RAW\n"));
+        assert_eq!(quote("50%"), "50%%");
+
+        let spec = ServiceSpec {
+            user: "ops%1".into(),
+            ..spec
+        };
+        assert!(spec.unit().contains("\nUser=ops%%1\n"));
+    }
+}
@@ -0,0 +1,706 @@
+//! 本地历史库：与配置文件同目录的 `iptools.db`（SQLite），保存事件日志、设备清单、
+//! 监视结果、测速结果与流量计数采样，取代把这些数据整体写进配置文件或只留在内存里。
+//!
+//! 配置文件只剩设置与少量界面状态；旧版写在 `session.events` 与 `session.inventory`
+//! 中的数据在首次打开时迁入库中。库打不开时（只读目录、文件损坏）退回旧的做法，
+//! 程序照常运行。
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use iptools_core::{
+    DeviceRecord, EVENT_LOG_LIMIT, NetworkEvent, SpeedSummary, TrafficRow, WATCH_HISTORY,
+    WatchCheck, WatchTarget,
+};
+use serde::{Deserialize, Serialize};
+
+use rusqlite::{Connection, Params, Row, Transaction, TransactionBehavior, params};
+
+const DATABASE_FILE: &str = "iptools.db";
+/// 监视结果与流量采样的保留天数
+const RETENTION_DAYS: i64 = 30;
+/// 保留的测速结果条数
+const SPEED_RESULTS_KEPT: i64 = 500;
+/// 同一接口两次流量采样的最小间隔
+const TRAFFIC_SAMPLE_SECONDS: i64 = 60;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+const SCHEMA: &str = "
+PRAGMA journal_mode = WAL;
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY,
+    at TEXT NOT NULL,
+    body TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS inventory (
+    key TEXT PRIMARY KEY,
+    last_ip TEXT NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    note TEXT NOT NULL,
+    device_types TEXT NOT NULL DEFAULT '[]'
+);
+CREATE TABLE IF NOT EXISTS watch_checks (
+    id INTEGER PRIMARY KEY,
+    at TEXT NOT NULL,
+    target TEXT NOT NULL,
+    latency_ms REAL,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS watch_checks_target ON watch_checks (target, id);
+CREATE TABLE IF NOT EXISTS speed_results (
+    id INTEGER PRIMARY KEY,
+    at TEXT NOT NULL,
+    body TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS traffic_samples (
+    id INTEGER PRIMARY KEY,
+    at TEXT NOT NULL,
+    interface TEXT NOT NULL,
+    total_download INTEGER NOT NULL,
+    total_upload INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS traffic_samples_at ON traffic_samples (at);
+";
+
+/// 历史库的全部内容，用于导出与导入。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct HistoryDump {
+    pub events: Vec<NetworkEvent>,
+    pub inventory: Vec<DeviceRecord>,
+    pub watch_checks: Vec<WatchCheckRow>,
+    pub speed_results: Vec<SpeedResultRow>,
+    pub traffic_samples: Vec<TrafficSampleRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WatchCheckRow {
+    pub at: String,
+    pub target: String,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SpeedResultRow {
+    pub at: String,
+    pub summary: SpeedSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TrafficSampleRow {
+    pub at: String,
+    pub interface: String,
+    pub total_download: u64,
+    pub total_upload: u64,
+}
+
+pub(crate) struct HistoryStore {
+    db: Connection,
+    path: PathBuf,
+    /// 上次流量采样的时间，用于节流
+    last_traffic: Option<NaiveDateTime>,
+}
+
+impl HistoryStore {
+    /// 配置文件旁边的历史库路径。
+    pub(crate) fn path_beside(config_path: &Path) -> PathBuf {
+        config_path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .join(DATABASE_FILE)
+    }
+
+    /// 打开配置文件旁边的历史库，不存在时创建。
+    pub(crate) fn beside(config_path: &Path) -> Result<Self, String> {
+        Self::open(&Self::path_beside(config_path))
+    }
+
+    pub(crate) fn open(path: &Path) -> Result<Self, String> {
+        Self::with_connection(
+            Connection::open(path).map_err(db_error)?,
+            path.to_path_buf(),
+        )
+    }
+
+    #[cfg(test)]
+    fn in_memory() -> Result<Self, String> {
+        Self::with_connection(
+            Connection::open_in_memory().map_err(db_error)?,
+            PathBuf::from(":memory:"),
+        )
+    }
+
+    fn with_connection(db: Connection, path: PathBuf) -> Result<Self, String> {
+        // 另一个进程写入时最多等待 2 秒
+        db.busy_timeout(std::time::Duration::from_secs(2))
+            .map_err(db_error)?;
+        db.execute_batch(SCHEMA).map_err(db_error)?;
+        // 早期版本的清单表没有设备类型列
+        let columns = query(&db, "PRAGMA table_info(inventory)", [], |row| {
+            row.get::<_, String>(1)
+        })?;
+        if !columns.iter().any(|column| column == "device_types") {
+            db.execute(
+                "ALTER TABLE inventory ADD COLUMN device_types TEXT NOT NULL DEFAULT '[]'",
+                [],
+            )
+            .map_err(db_error)?;
+        }
+        let store = Self {
+            db,
+            path,
+            last_traffic: None,
+        };
+        store.prune(Local::now().naive_local())?;
+        Ok(store)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 事件日志按时间顺序整体替换；模型每次保存的都是完整列表。
+    pub(crate) fn replace_events(&self, entries: &[NetworkEvent]) -> Result<(), String> {
+        self.transaction(|db| write_events(db, entries))
+    }
+
+    /// 无法解析的行（由更新的版本写入）跳过。
+    pub(crate) fn events(&self) -> Result<Vec<NetworkEvent>, String> {
+        let bodies = query(&self.db, "SELECT body FROM events ORDER BY id", [], |row| {
+            row.get::<_, String>(0)
+        })?;
+        Ok(bodies
+            .iter()
+            .filter_map(|body| serde_json::from_str(body).ok())
+            .collect())
+    }
+
+    pub(crate) fn replace_inventory(&self, devices: &[DeviceRecord]) -> Result<(), String> {
+        self.transaction(|db| write_inventory(db, devices))
+    }
+
+    pub(crate) fn inventory(&self) -> Result<Vec<DeviceRecord>, String> {
+        query(
+            &self.db,
+            "SELECT key, last_ip, first_seen, last_seen, note, device_types \
+             FROM inventory ORDER BY rowid",
+            [],
+            |row| {
+                Ok(DeviceRecord {
+                    key: row.get(0)?,
+                    last_ip: row.get(1)?,
+                    first_seen: row.get(2)?,
+                    last_seen: row.get(3)?,
+                    note: row.get(4)?,
+                    // 较新版本写入的未知类型整列放弃
+                    device_types: row
+                        .get::<_, String>(5)
+                        .ok()
+                        .and_then(|types| serde_json::from_str(&types).ok())
+                        .unwrap_or_default(),
+                })
+            },
+        )
+    }
+
+    pub(crate) fn record_watch(&self, at: NaiveDateTime, check: &WatchCheck) -> Result<(), String> {
+        self.db
+            .execute(
+                "INSERT INTO watch_checks (at, target, latency_ms, error) VALUES (?, ?, ?, ?)",
+                params![
+                    stamp(at),
+                    check.target.target,
+                    check.latency_ms,
+                    check.error
+                ],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// 每个目标最近的结果（按时间顺序），用于启动时恢复仪表盘监视卡片。
+    pub(crate) fn recent_watch_checks(
+        &self,
+        targets: &[WatchTarget],
+    ) -> Result<Vec<WatchCheck>, String> {
+        let mut checks = Vec::new();
+        for target in targets {
+            let mut recent = query(
+                &self.db,
+                "SELECT latency_ms, error FROM watch_checks WHERE target = ? \
+                 ORDER BY id DESC LIMIT ?",
+                params![target.target, WATCH_HISTORY as i64],
+                |row| {
+                    Ok(WatchCheck {
+                        target: target.clone(),
+                        latency_ms: row.get(0)?,
+                        error: row.get(1)?,
+                    })
+                },
+            )?;
+            recent.reverse();
+            checks.extend(recent);
+        }
+        Ok(checks)
+    }
+
+    pub(crate) fn record_speed(
+        &self,
+        at: NaiveDateTime,
+        summary: &SpeedSummary,
+    ) -> Result<(), String> {
+        let body = serde_json::to_string(summary).map_err(|error| error.to_string())?;
+        self.db
+            .execute(
+                "INSERT INTO speed_results (at, body) VALUES (?, ?)",
+                params![stamp(at), body],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// 流量计数每分钟最多采样一次；返回是否写入。
+    pub(crate) fn record_traffic(
+        &mut self,
+        at: NaiveDateTime,
+        rows: &[TrafficRow],
+    ) -> Result<bool, String> {
+        if rows.is_empty()
+            || self.last_traffic.is_some_and(|last| {
+                at.signed_duration_since(last) < Duration::seconds(TRAFFIC_SAMPLE_SECONDS)
+            })
+        {
+            return Ok(false);
+        }
+        self.transaction(|db| {
+            for row in rows {
+                db.execute(
+                    "INSERT INTO traffic_samples (at, interface, total_download, total_upload) \
+                     VALUES (?, ?, ?, ?)",
+                    params![
+                        stamp(at),
+                        row.name,
+                        row.total_download as i64,
+                        row.total_upload as i64,
+                    ],
+                )
+                .map_err(db_error)?;
+            }
+            Ok(())
+        })?;
+        self.last_traffic = Some(at);
+        Ok(true)
+    }
+
+    /// 维护：事件只留最近 [`EVENT_LOG_LIMIT`] 条，监视结果与流量采样只留最近
+    /// [`RETENTION_DAYS`] 天，测速结果只留最近 [`SPEED_RESULTS_KEPT`] 条。
+    /// 打开时执行一次，之后由主循环每小时执行。
+    pub(crate) fn prune(&self, now: NaiveDateTime) -> Result<(), String> {
+        let cutoff = stamp(now - Duration::days(RETENTION_DAYS));
+        self.transaction(|db| {
+            db.execute(
+                "DELETE FROM events WHERE id NOT IN \
+                 (SELECT id FROM events ORDER BY id DESC LIMIT ?)",
+                [EVENT_LOG_LIMIT as i64],
+            )
+            .map_err(db_error)?;
+            db.execute("DELETE FROM watch_checks WHERE at < ?", [&cutoff])
+                .map_err(db_error)?;
+            db.execute("DELETE FROM traffic_samples WHERE at < ?", [&cutoff])
+                .map_err(db_error)?;
+            db.execute(
+                "DELETE FROM speed_results WHERE id NOT IN \
+                 (SELECT id FROM speed_results ORDER BY id DESC LIMIT ?)",
+                [SPEED_RESULTS_KEPT],
+            )
+            .map_err(db_error)?;
+            Ok(())
+        })
+    }
+
+    pub(crate) fn dump(&self) -> Result<HistoryDump, String> {
+        let mut speed_results = Vec::new();
+        for (at, body) in query(
+            &self.db,
+            "SELECT at, body FROM speed_results ORDER BY id",
+            [],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )? {
+            if let Ok(summary) = serde_json::from_str(&body) {
+                speed_results.push(SpeedResultRow { at, summary });
+            }
+        }
+        Ok(HistoryDump {
+            events: self.events()?,
+            inventory: self.inventory()?,
+            watch_checks: query(
+                &self.db,
+                "SELECT at, target, latency_ms, error FROM watch_checks ORDER BY id",
+                [],
+                |row| {
+                    Ok(WatchCheckRow {
+                        at: row.get(0)?,
+                        target: row.get(1)?,
+                        latency_ms: row.get(2)?,
+                        error: row.get(3)?,
+                    })
+                },
+            )?,
+            speed_results,
+            traffic_samples: query(
+                &self.db,
+                "SELECT at, interface, total_download, total_upload FROM traffic_samples \
+                 ORDER BY id",
+                [],
+                |row| {
+                    Ok(TrafficSampleRow {
+                        at: row.get(0)?,
+                        interface: row.get(1)?,
+                        total_download: row.get::<_, i64>(2)?.max(0) as u64,
+                        total_upload: row.get::<_, i64>(3)?.max(0) as u64,
+                    })
+                },
+            )?,
+        })
+    }
+
+    /// 把整个库复制为 `iptools.db.<时间>.bak`，返回副本路径。
+    pub(crate) fn backup(&self) -> Result<PathBuf, String> {
+        let backup = crate::config::backup_path(&self.path);
+        self.db
+            .execute("VACUUM INTO ?", [backup.to_string_lossy()])
+            .map_err(db_error)?;
+        Ok(backup)
+    }
+
+    /// 用 `dump` 在一个事务里整体替换库中的全部数据，失败时库保持原样。
+    pub(crate) fn restore(&self, dump: &HistoryDump) -> Result<(), String> {
+        self.transaction(|db| {
+            write_events(db, &dump.events)?;
+            write_inventory(db, &dump.inventory)?;
+            db.execute_batch(
+                "DELETE FROM watch_checks; DELETE FROM speed_results; DELETE FROM traffic_samples;",
+            )
+            .map_err(db_error)?;
+            for check in &dump.watch_checks {
+                db.execute(
+                    "INSERT INTO watch_checks (at, target, latency_ms, error) VALUES (?, ?, ?, ?)",
+                    params![check.at, check.target, check.latency_ms, check.error],
+                )
+                .map_err(db_error)?;
+            }
+            for result in &dump.speed_results {
+                let body =
+                    serde_json::to_string(&result.summary).map_err(|error| error.to_string())?;
+                db.execute(
+                    "INSERT INTO speed_results (at, body) VALUES (?, ?)",
+                    params![result.at, body],
+                )
+                .map_err(db_error)?;
+            }
+            for sample in &dump.traffic_samples {
+                db.execute(
+                    "INSERT INTO traffic_samples (at, interface, total_download, total_upload) \
+                     VALUES (?, ?, ?, ?)",
+                    params![
+                        sample.at,
+                        sample.interface,
+                        sample.total_download as i64,
+                        sample.total_upload as i64,
+                    ],
+                )
+                .map_err(db_error)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn is_empty(&self) -> Result<bool, String> {
+        let count: i64 = self
+            .db
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM events) + (SELECT COUNT(*) FROM inventory)",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(db_error)?;
+        Ok(count == 0)
+    }
+
+    /// 在 `BEGIN IMMEDIATE` 事务中执行 `body`；`body` 出错时整体回滚。
+    fn transaction(
+        &self,
+        body: impl FnOnce(&Connection) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let transaction = Transaction::new_unchecked(&self.db, TransactionBehavior::Immediate)
+            .map_err(db_error)?;
+        body(&transaction)?;
+        transaction.commit().map_err(db_error)
+    }
+
+    /// 把配置文件中旧版保存的事件与设备清单迁入库中，返回配置是否有改动需要保存。
+    /// 库中已有数据时以库为准，配置里的旧数据直接丢弃。
+    pub(crate) fn migrate_session(
+        &self,
+        session: &mut iptools_core::SessionState,
+    ) -> Result<bool, String> {
+        if session.events.entries.is_empty() && session.inventory.devices.is_empty() {
+            return Ok(false);
+        }
+        if self.is_empty()? {
+            self.replace_events(&session.events.entries)?;
+            self.replace_inventory(&session.inventory.devices)?;
+        }
+        session.events.entries.clear();
+        session.inventory.devices.clear();
+        Ok(true)
+    }
+}
+
+fn stamp(at: NaiveDateTime) -> String {
+    at.format(TIME_FORMAT).to_string()
+}
+
+fn write_events(db: &Connection, entries: &[NetworkEvent]) -> Result<(), String> {
+    db.execute("DELETE FROM events", []).map_err(db_error)?;
+    for entry in entries {
+        let body = serde_json::to_string(entry).map_err(|error| error.to_string())?;
+        db.execute(
+            "INSERT INTO events (at, body) VALUES (?, ?)",
+            params![entry.at, body],
+        )
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+fn write_inventory(db: &Connection, devices: &[DeviceRecord]) -> Result<(), String> {
+    db.execute("DELETE FROM inventory", []).map_err(db_error)?;
+    for device in devices {
+        let device_types =
+            serde_json::to_string(&device.device_types).map_err(|error| error.to_string())?;
+        db.execute(
+            "INSERT OR REPLACE INTO inventory \
+             (key, last_ip, first_seen, last_seen, note, device_types) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                device.key,
+                device.last_ip,
+                device.first_seen,
+                device.last_seen,
+                device.note,
+                device_types,
+            ],
+        )
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+fn db_error(error: rusqlite::Error) -> String {
+    error.to_string()
+}
+
+/// 执行带参数的查询，收集全部行。
+fn query<T>(
+    db: &Connection,
+    sql: &str,
+    params: impl Params,
+    map: impl FnMut(&Row<'_>) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, String> {
+    let mut statement = db.prepare(sql).map_err(db_error)?;
+    let rows = statement.query_map(params, map).map_err(db_error)?;
+    rows.collect::<Result<_, _>>().map_err(db_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, TIME_FORMAT).unwrap()
+    }
+
+    fn event(at: &str) -> NetworkEvent {
+        NetworkEvent {
+            at: at.into(),
+            kind: iptools_core::NetworkEventKind::GatewayChanged,
+            subject: "eth0".into(),
+            from: "192.168.1.1".into(),
+            to: "192.168.1.254".into(),
+        }
+    }
+
+    #[test]
+    fn inventory_from_before_device_types_gains_the_column() {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(
+            "CREATE TABLE inventory (key TEXT PRIMARY KEY, last_ip TEXT NOT NULL, \
+             first_seen TEXT NOT NULL, last_seen TEXT NOT NULL, note TEXT NOT NULL);
+             INSERT INTO inventory VALUES ('AA', '192.168.1.9', '', '', 'camera');",
+        )
+        .unwrap();
+        let store = HistoryStore::with_connection(db, PathBuf::from(":memory:")).unwrap();
+        let devices = store.inventory().unwrap();
+        assert_eq!(devices[0].note, "camera");
+        assert!(devices[0].device_types.is_empty());
+    }
+
+    #[test]
+    fn session_data_moves_out_of_the_config_once() {
+        let store = HistoryStore::in_memory().unwrap();
+        let mut session = iptools_core::SessionState::default();
+        session.events.entries = vec![event("2026-01-01 08:00:00")];
+        session.inventory.devices = vec![DeviceRecord {
+            key: "AA:BB:CC:DD:EE:FF".into(),
+            last_ip: "192.168.1.20".into(),
+            first_seen: "2026-01-01 08:00:00".into(),
+            last_seen: "2026-01-02 08:00:00".into(),
+            note: "printer".into(),
+            device_types: vec![iptools_core::DeviceType::Printer],
+        }];
+        let expected = session.clone();
+
+        assert_eq!(store.migrate_session(&mut session), Ok(true));
+        assert!(session.events.entries.is_empty() && session.inventory.devices.is_empty());
+        assert_eq!(store.events().unwrap(), expected.events.entries);
+        assert_eq!(store.inventory().unwrap(), expected.inventory.devices);
+        assert_eq!(store.migrate_session(&mut session), Ok(false));
+
+        // 库里已有数据时不被旧配置覆盖
+        session.events.entries = vec![event("2025-01-01 08:00:00")];
+        assert_eq!(store.migrate_session(&mut session), Ok(true));
+        assert_eq!(store.events().unwrap(), expected.events.entries);
+    }
+
+    #[test]
+    fn results_are_kept_for_a_month_and_events_up_to_the_log_limit() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let target = iptools_core::parse_watch_targets("nas=192.168.1.10:445").unwrap();
+        let check = |latency_ms, error: Option<&str>| WatchCheck {
+            target: target[0].clone(),
+            latency_ms,
+            error: error.map(Into::into),
+        };
+        store
+            .record_watch(at("2026-01-01 08:00:00"), &check(Some(3.0), None))
+            .unwrap();
+        for minute in 0..WATCH_HISTORY + 2 {
+            let error = (minute % 2 == 0).then_some("connection timed out");
+            let latency = error.is_none().then_some(minute as f64);
+            store
+                .record_watch(
+                    at(&format!("2026-03-01 08:{minute:02}:00")),
+                    &check(latency, error),
+                )
+                .unwrap();
+        }
+        let recent = store.recent_watch_checks(&target).unwrap();
+        assert_eq!(recent.len(), WATCH_HISTORY);
+        assert_eq!(recent.last(), Some(&check(Some(11.0), None)));
+        assert_eq!(recent[0], check(None, Some("connection timed out")));
+
+        let traffic = |total| TrafficRow {
+            name: "eth0".into(),
+            total_download: total,
+            total_upload: total / 2,
+            ..TrafficRow::default()
+        };
+        assert_eq!(
+            store.record_traffic(at("2026-01-01 08:00:00"), &[traffic(100)]),
+            Ok(true)
+        );
+        assert_eq!(
+            store.record_traffic(at("2026-01-01 08:00:30"), &[traffic(150)]),
+            Ok(false)
+        );
+        assert_eq!(
+            store.record_traffic(at("2026-03-01 08:00:00"), &[traffic(900)]),
+            Ok(true)
+        );
+        let summary = SpeedSummary {
+            average_bytes_per_second: 1_000,
+            peak_bytes_per_second: 2_000,
+            total_bytes: 10_000,
+            upload_bytes_per_second: None,
+            bufferbloat: None,
+        };
+        store
+            .record_speed(at("2026-01-01 08:00:00"), &summary)
+            .unwrap();
+        let events = (0..EVENT_LOG_LIMIT + 5)
+            .map(|_| event("2026-03-01 08:00:00"))
+            .collect::<Vec<_>>();
+        store.replace_events(&events).unwrap();
+
+        store.prune(at("2026-03-10 00:00:00")).unwrap();
+        assert_eq!(
+            query(&store.db, "SELECT COUNT(*) FROM watch_checks", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .unwrap(),
+            [WATCH_HISTORY as i64 + 2]
+        );
+        assert_eq!(
+            query(
+                &store.db,
+                "SELECT at, total_download, total_upload FROM traffic_samples",
+                [],
+                |row| Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?))
+            )
+            .unwrap(),
+            [("2026-03-01 08:00:00".to_string(), 900, 450)]
+        );
+        // 测速结果按条数保留，不按时间
+        let speeds = query(&store.db, "SELECT body FROM speed_results", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<SpeedSummary>(&speeds[0]).unwrap(),
+            summary
+        );
+        assert_eq!(store.events().unwrap().len(), EVENT_LOG_LIMIT);
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_previous_rows() {
+        let store = HistoryStore::in_memory().unwrap();
+        store
+            .replace_events(&[event("2026-01-01 08:00:00")])
+            .unwrap();
+        let result = store.transaction(|db| {
+            db.execute("DELETE FROM events", []).map_err(db_error)?;
+            Err("interrupted".into())
+        });
+        assert_eq!(result, Err("interrupted".to_string()));
+        assert_eq!(store.events().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn the_database_sits_beside_the_config_file() {
+        let directory = std::env::temp_dir().join(format!(
+            "iptools-store-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        let store = HistoryStore::beside(&directory.join("config.toml")).unwrap();
+        assert_eq!(store.path(), directory.join(DATABASE_FILE));
+        store
+            .replace_events(&[event("2026-01-01 08:00:00")])
+            .unwrap();
+        drop(store);
+        let reopened = HistoryStore::beside(&directory.join("config.toml")).unwrap();
+        assert_eq!(reopened.events().unwrap().len(), 1);
+        drop(reopened);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+//! Android/Termux 降级模式。
+//!
+//! 非 root 的 Android 进程既不能建 raw socket，也读不到 netlink 接口表，
+//! 原生枚举与 ICMP 都会失败。这里改用 `/proc/net` 文本与 `connect()` 探测：
+//! 网卡列表由 `/proc/net/dev` + `/proc/net/route` + UDP connect 源地址拼出，
+//! 扫描以 TCP 常见端口的连接结果（成功或被拒）判定主机在线。
+//! 解析函数与平台无关，始终编译，便于单测。
+#![allow(dead_code)]
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::Duration;
+
+use iptools_core::{PlatformCapabilities, ProbeOutcome};
+
+use super::net::InterfaceInfo;
+
+/// 存活探测使用的端口：Web、SSH、SMB、DNS 以及 iOS/Android 常驻服务。
+const PROBE_PORTS: [u16; 8] = [80, 443, 22, 445, 139, 53, 8080, 62078];
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// 编译目标为 Android，或在 Termux / Android 用户态中运行的 Linux 构建。
+/// 结果在进程内缓存，扫描热路径可直接调用。
+pub fn is_android() -> bool {
+    static ANDROID: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *ANDROID.get_or_init(|| {
+        cfg!(target_os = "android")
+            || std::env::var_os("TERMUX_VERSION").is_some()
+            || std::path::Path::new("/system/build.prop").exists()
+    })
+}
+
+/// 当前平台能力；非 Android 时除蓝牙（需 `bluetooth` feature）外全部可用。
+pub fn capabilities() -> PlatformCapabilities {
+    if is_android() {
+        PlatformCapabilities {
+            icmp: false,
+            adapter_config: false,
+            bluetooth: false,
+        }
+    } else {
+        PlatformCapabilities {
+            bluetooth: cfg!(feature = "bluetooth"),
+            ..PlatformCapabilities::default()
+        }
+    }
+}
+
+/// 由 `/proc/net` 拼出网卡列表；IPv4 地址只能得到默认路由出口的那一个。
+pub fn interfaces() -> Vec<InterfaceInfo> {
+    let read = |path: &str| std::fs::read_to_string(path).unwrap_or_default();
+    let names = parse_proc_net_dev(&read("/proc/net/dev"));
+    let routes = parse_proc_route(&read("/proc/net/route"));
+    let inet6 = parse_if_inet6(&read("/proc/net/if_inet6"));
+    build_interfaces(&names, &routes, &inet6, local_ipv4())
+}
+
+/// 主机在线探测：任一端口连接成功或被拒（RST）即说明主机存在。
+/// MAC 仅在 `/proc/net/arp` 可读（Android 10 以前）时可得，否则为 "-"。
+pub fn probe_host(ip: Ipv4Addr) -> (Option<String>, ProbeOutcome) {
+    let outcome = tcp_probe(ip);
+    let mac = (outcome == ProbeOutcome::Clean).then(|| {
+        std::fs::read_to_string("/proc/net/arp")
+            .ok()
+            .and_then(|text| parse_proc_arp(&text, ip))
+            .unwrap_or_else(|| "-".to_string())
+    });
+    (mac, outcome)
+}
+
+/// 依次连接常见端口，任一连接成功或被拒即 `Clean`。也供扫描探测非直连子网。
+pub fn tcp_probe(ip: Ipv4Addr) -> ProbeOutcome {
+    // 超时与拒绝都是正常结果；其余错误（如 ENOBUFS、网络不可达）说明本机或链路吃不消。
+    let mut outcome = ProbeOutcome::Silent;
+    for port in PROBE_PORTS {
+        match TcpStream::connect_timeout(&SocketAddr::new(IpAddr::V4(ip), port), PROBE_TIMEOUT) {
+            Ok(_) => {
+                outcome = ProbeOutcome::Clean;
+                break;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::ConnectionRefused => {
+                outcome = ProbeOutcome::Clean;
+                break;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(_) => outcome = ProbeOutcome::Troubled,
+        }
+    }
+    outcome
+}
+
+/// UDP `connect()` 不发包，只让内核选出默认路由的源地址。
+fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:9").ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+/// `/proc/net/route` 中的一条 IPv4 路由。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub iface: String,
+    pub destination: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+    pub mask: Ipv4Addr,
+}
+
+/// `/proc/net/dev`：前两行为表头，其余每行 "name: counters…"。
+pub fn parse_proc_net_dev(text: &str) -> Vec<String> {
+    text.lines()
+        .skip(2)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, _)| name.trim().to_string())
+        .filter(|name| !name.is_empty() && name != "lo")
+        .collect()
+}
+
+/// `/proc/net/route`：地址与掩码为小端十六进制。
+pub fn parse_proc_route(text: &str) -> Vec<Route> {
+    let hex = |field: &str| {
+        u32::from_str_radix(field, 16)
+            .ok()
+            .map(|value| Ipv4Addr::from(value.to_le_bytes()))
+    };
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            Some(Route {
+                iface: fields.first()?.to_string(),
+                destination: hex(fields.get(1)?)?,
+                gateway: hex(fields.get(2)?)?,
+                mask: hex(fields.get(7)?)?,
+            })
+        })
+        .collect()
+}
+
+/// 默认路由（0.0.0.0/0）的下一跳；点对点链路没有网关时返回 None。
+pub fn default_gateway(routes: &[Route]) -> Option<Ipv4Addr> {
+    routes
+        .iter()
+        .find(|route| {
+            route.destination.is_unspecified()
+                && route.mask.is_unspecified()
+                && !route.gateway.is_unspecified()
+        })
+        .map(|route| route.gateway)
+}
+
+/// `/proc/net/if_inet6`：32 位十六进制地址 + 索引/前缀/范围/标志 + 接口名。
+pub fn parse_if_inet6(text: &str) -> Vec<(String, Ipv6Addr)> {
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let raw = u128::from_str_radix(fields.first()?, 16).ok()?;
+            Some((fields.get(5)?.to_string(), Ipv6Addr::from(raw)))
+        })
+        .filter(|(_, ip)| !ip.is_loopback())
+        .collect()
+}
+
+/// `/proc/net/arp`："IP  HW type  Flags  HW address  Mask  Device"。
+pub fn parse_proc_arp(text: &str, ip: Ipv4Addr) -> Option<String> {
+    let wanted = ip.to_string();
+    text.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        (fields.first() == Some(&wanted.as_str()))
+            .then(|| fields.get(3).map(|mac| mac.to_ascii_lowercase()))
+            .flatten()
+            .filter(|mac| mac != "00:00:00:00:00:00")
+    })
+}
+
+/// 组装网卡：源地址归属于包含它的非默认路由所在接口，掩码取该路由。
+pub fn build_interfaces(
+    names: &[String],
+    routes: &[Route],
+    inet6: &[(String, Ipv6Addr)],
+    local: Option<Ipv4Addr>,
+) -> Vec<InterfaceInfo> {
+    let subnet = local.and_then(|ip| {
+        routes.iter().find(|route| {
+            route.mask != Ipv4Addr::UNSPECIFIED
+                && u32::from(ip) & u32::from(route.mask) == u32::from(route.destination)
+        })
+    });
+    let mut result: Vec<InterfaceInfo> = names
+        .iter()
+        .map(|name| {
+            let owns_local = subnet.is_some_and(|route| &route.iface == name);
+            let ipv4: Vec<String> = local
+                .filter(|_| owns_local)
+                .map(|ip| ip.to_string())
+                .into_iter()
+                .collect();
+            let ipv6: Vec<String> = inet6
+                .iter()
+                .filter(|(iface, _)| iface == name)
+                .map(|(_, ip)| ip.to_string())
+                .collect();
+            let cidr = subnet.filter(|_| owns_local).and_then(|route| {
+                let prefix = u32::from(route.mask).leading_ones();
+                local.map(|ip| format!("{ip}/{prefix}"))
+            });
+            let is_wireless = name.starts_with("wlan");
+            InterfaceInfo {
+                name: name.clone(),
+                description: name.clone(),
+                mac: String::new(),
+                is_up: !ipv4.is_empty() || !ipv6.is_empty(),
+                ipv4,
+                ipv6,
+                ssid: None,
+                dhcp_enabled: false,
+                is_physical: is_wireless || name.starts_with("eth"),
+                interface_type: if is_wireless { "Ieee80211" } else { "Other" }.to_string(),
+                cidr,
+                guid: name.clone(),
+                link_speed_bps: None,
+                media: iptools_core::LinkMedia::default(),
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| b.is_up.cmp(&a.is_up).then_with(|| a.name.cmp(&b.name)));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proc_net_tables_build_the_default_interface() {
+        let dev = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes
+    lo:  1000      10    0    0    0     0          0         0  1000
+ wlan0: 98765     120    0    0    0     0          0         0  4321
+rmnet0:     0       0    0    0    0     0          0         0     0
+";
+        let route = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+wlan0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0
+wlan0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+";
+        let inet6 = "\
+fe800000000000000000000000000001 1f 40 20 80    wlan0
+00000000000000000000000000000001 01 80 10 80       lo
+";
+        let names = parse_proc_net_dev(dev);
+        assert_eq!(names, ["wlan0", "rmnet0"]);
+        let routes = parse_proc_route(route);
+        assert_eq!(routes[1].destination, Ipv4Addr::new(192, 168, 1, 0));
+        assert_eq!(routes[1].mask, Ipv4Addr::new(255, 255, 255, 0));
+        assert_eq!(
+            default_gateway(&routes),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+
+        let ifs = build_interfaces(
+            &names,
+            &routes,
+            &parse_if_inet6(inet6),
+            Some(Ipv4Addr::new(192, 168, 1, 23)),
+        );
+        assert_eq!(ifs[0].name, "wlan0");
+        assert_eq!(ifs[0].ipv4, ["192.168.1.23"]);
+        assert_eq!(ifs[0].ipv6, ["fe80::1"]);
+        assert_eq!(ifs[0].cidr.as_deref(), Some("192.168.1.23/24"));
+        assert_eq!(ifs[0].interface_type, "Ieee80211");
+        assert!(!ifs[1].is_up);
+    }
+
+    #[test]
+    fn proc_arp_lookup_skips_incomplete_entries() {
+        let arp = "\
+IP address       HW type     Flags       HW address            Mask     Device
+192.168.1.1      0x1         0x2         AA:BB:CC:DD:EE:FF     *        wlan0
+192.168.1.9      0x1         0x0         00:00:00:00:00:00     *        wlan0
+";
+        assert_eq!(
+            parse_proc_arp(arp, Ipv4Addr::new(192, 168, 1, 1)).as_deref(),
+            Some("aa:bb:cc:dd:ee:ff")
+        );
+        assert!(parse_proc_arp(arp, Ipv4Addr::new(192, 168, 1, 9)).is_none());
+    }
+}
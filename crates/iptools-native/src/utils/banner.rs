@@ -0,0 +1,59 @@
+//! 服务问候语：FTP 与 SSH 服务器一连上就先报家门，扫描器补充探测时记下第一行，
+//! 用来识别软件名与版本。只读不写，连接随即关闭。
+
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+use iptools_core::{BANNER_PORTS, ServiceBanner};
+
+const BANNER_TIMEOUT: Duration = Duration::from_secs(2);
+/// 问候语只取第一行，RFC 4253 规定 SSH 标识行连同 CRLF 不超过 255 字节
+const BANNER_LIMIT: usize = 256;
+
+/// 已开放的问候端口各读一行；不开口或不可打印的不列出。
+pub fn grab(ip: Ipv4Addr, open_ports: &[u16]) -> Vec<ServiceBanner> {
+    BANNER_PORTS
+        .into_iter()
+        .filter(|port| open_ports.contains(port))
+        .filter_map(|port| {
+            let mut stream =
+                TcpStream::connect_timeout(&SocketAddr::new(IpAddr::V4(ip), port), BANNER_TIMEOUT)
+                    .ok()?;
+            stream.set_read_timeout(Some(BANNER_TIMEOUT)).ok()?;
+            let mut buf = [0u8; BANNER_LIMIT];
+            let n = stream.read(&mut buf).ok()?;
+            let text = first_line(&buf[..n])?;
+            Some(ServiceBanner { port, text })
+        })
+        .collect()
+}
+
+/// 第一行去掉行尾；含控制字符的视为二进制协议，不当问候语。
+pub(crate) fn first_line(bytes: &[u8]) -> Option<String> {
+    let line = bytes.split(|byte| *byte == b'\n').next()?;
+    let line = std::str::from_utf8(line)
+        .ok()?
+        .trim_end_matches('\r')
+        .trim();
+    (!line.is_empty() && !line.chars().any(char::is_control)).then(|| line.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_printable_first_line_is_kept() {
+        assert_eq!(
+            first_line(b"SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13\r\n"),
+            Some("SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13".into())
+        );
+        assert_eq!(
+            first_line(b"220-Welcome\r\n220 (vsFTPd 3.0.3)\r\n"),
+            Some("220-Welcome".into())
+        );
+        assert_eq!(first_line(b"\r\n"), None);
+        assert_eq!(first_line(&[0x03, 0x00, 0x00, 0x13]), None);
+    }
+}
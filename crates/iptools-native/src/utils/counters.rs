@@ -0,0 +1,209 @@
+//! 网卡收发字节计数。
+//!
+//! Windows 上 sysinfo 给出的网卡名与 ipconfig 的友好名称常常对不上，
+//! 仪表盘与网卡页的流量因此挂不到对应网卡上。这里在 Windows 上改用
+//! `GetIfTable2` 直接读取每个接口的 `MIB_IF_ROW2` 计数，以接口 GUID
+//! （即 [`InterfaceInfo::guid`](super::net::InterfaceInfo::guid)）为键、
+//! 以 Alias（友好名称）为展示名；其他平台仍取自 sysinfo，GUID 就是接口名。
+//!
+//! 丢包与错误计数同样来自系统：Linux 读 `/sys/class/net/<接口>/statistics`
+//! （含溢出与冲突），Windows 取 `MIB_IF_ROW2` 的丢弃与错误，其他平台只有
+//! sysinfo 提供的包数与错误数。
+
+use iptools_core::LinkCounters;
+
+/// 一个接口自系统启动以来的累计字节数。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceCounters {
+    /// 与 `InterfaceInfo::guid` 对应的键
+    pub guid: String,
+    /// 展示名：Windows 为友好名称，其他平台为接口名
+    pub name: String,
+    pub received: u64,
+    pub transmitted: u64,
+    /// 丢包与错误计数；平台读不到时为 `None`
+    pub link: Option<LinkCounters>,
+}
+
+/// 计数来源；非 Windows 平台复用同一个 sysinfo 列表以免每次重新枚举。
+pub struct CounterSource {
+    #[cfg(not(target_os = "windows"))]
+    networks: sysinfo::Networks,
+}
+
+impl Default for CounterSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CounterSource {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(not(target_os = "windows"))]
+            networks: sysinfo::Networks::new_with_refreshed_list(),
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn read(&mut self) -> Vec<InterfaceCounters> {
+        self.networks.refresh(true);
+        self.networks
+            .iter()
+            .map(|(name, data)| InterfaceCounters {
+                guid: name.clone(),
+                name: name.clone(),
+                received: data.total_received(),
+                transmitted: data.total_transmitted(),
+                #[cfg(target_os = "linux")]
+                link: sysfs_link_counters(name),
+                #[cfg(not(target_os = "linux"))]
+                link: Some(LinkCounters {
+                    packets: data.total_packets_received() + data.total_packets_transmitted(),
+                    errors: data.total_errors_on_received() + data.total_errors_on_transmitted(),
+                    ..LinkCounters::default()
+                }),
+            })
+            .collect()
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn read(&mut self) -> Vec<InterfaceCounters> {
+        read_if_table()
+    }
+}
+
+/// 名称映射：GUID 相同（忽略大小写与花括号）优先，其次名称相同（忽略大小写）。
+pub fn find_interface<'a, T>(
+    items: &'a [T],
+    key: impl Fn(&T) -> (&str, &str),
+    guid: &str,
+    name: &str,
+) -> Option<&'a T> {
+    let normalize = |guid: &str| {
+        guid.trim_matches(|character| character == '{' || character == '}')
+            .to_ascii_uppercase()
+    };
+    let wanted = normalize(guid);
+    items
+        .iter()
+        .find(|item| !wanted.is_empty() && normalize(key(item).0) == wanted)
+        .or_else(|| {
+            items
+                .iter()
+                .find(|item| key(item).1.eq_ignore_ascii_case(name))
+        })
+}
+
+/// `/sys/class/net/<name>/statistics` 下的计数；虚拟接口没有的项按 0 计。
+#[cfg(target_os = "linux")]
+fn sysfs_link_counters(name: &str) -> Option<LinkCounters> {
+    let dir = std::path::Path::new("/sys/class/net")
+        .join(name)
+        .join("statistics");
+    if !dir.is_dir() {
+        return None;
+    }
+    let read = |file: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .ok()
+            .and_then(|text| text.trim().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    Some(LinkCounters {
+        packets: read("rx_packets") + read("tx_packets"),
+        drops: read("rx_dropped") + read("tx_dropped"),
+        errors: read("rx_errors") + read("tx_errors"),
+        overruns: read("rx_over_errors") + read("rx_fifo_errors") + read("tx_fifo_errors"),
+        collisions: read("collisions"),
+    })
+}
+
+/// 读取全部接口的 `MIB_IF_ROW2`；跳过 NDIS 过滤层与回环接口，它们与真实网卡重复计数。
+#[cfg(target_os = "windows")]
+fn read_if_table() -> Vec<InterfaceCounters> {
+    use windows::Win32::NetworkManagement::IpHelper::{FreeMibTable, GetIfTable2, MIB_IF_TABLE2};
+
+    /// IF_TYPE_SOFTWARE_LOOPBACK
+    const LOOPBACK: u32 = 24;
+    /// InterfaceAndOperStatusFlags 中的 FilterInterface 位
+    const FILTER_INTERFACE: u8 = 0b10;
+
+    let mut counters = Vec::new();
+    unsafe {
+        let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+        if GetIfTable2(&mut table).0 != 0 || table.is_null() {
+            return counters;
+        }
+        let rows =
+            std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        for row in rows {
+            if row.Type == LOOPBACK
+                || row.InterfaceAndOperStatusFlags._bitfield & FILTER_INTERFACE != 0
+            {
+                continue;
+            }
+            let alias_len = row
+                .Alias
+                .iter()
+                .position(|unit| *unit == 0)
+                .unwrap_or(row.Alias.len());
+            counters.push(InterfaceCounters {
+                guid: format!("{{{:?}}}", row.InterfaceGuid).to_uppercase(),
+                name: String::from_utf16_lossy(&row.Alias[..alias_len]),
+                received: row.InOctets,
+                transmitted: row.OutOctets,
+                link: Some(LinkCounters {
+                    packets: row.InUcastPkts
+                        + row.InNUcastPkts
+                        + row.OutUcastPkts
+                        + row.OutNUcastPkts,
+                    drops: row.InDiscards + row.OutDiscards,
+                    errors: row.InErrors + row.OutErrors,
+                    ..LinkCounters::default()
+                }),
+            });
+        }
+        FreeMibTable(table as *const std::ffi::c_void);
+    }
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_attach_by_guid_before_falling_back_to_names() {
+        let counters = [
+            InterfaceCounters {
+                guid: "{6B29FC40-CA47-1067-B31D-00DD010662DA}".into(),
+                name: "Ethernet 2".into(),
+                received: 10,
+                transmitted: 1,
+                link: None,
+            },
+            InterfaceCounters {
+                guid: "wlan0".into(),
+                name: "wlan0".into(),
+                received: 20,
+                transmitted: 2,
+                link: None,
+            },
+        ];
+        fn key(counter: &InterfaceCounters) -> (&str, &str) {
+            (&counter.guid, &counter.name)
+        }
+        // sysinfo 可能叫它 "Intel(R) Ethernet Connection"，GUID 仍能对上
+        let found = find_interface(
+            &counters,
+            key,
+            "6b29fc40-ca47-1067-b31d-00dd010662da",
+            "Ethernet",
+        );
+        assert_eq!(found.map(|counter| counter.received), Some(10));
+        let found = find_interface(&counters, key, "", "WLAN0");
+        assert_eq!(found.map(|counter| counter.received), Some(20));
+        assert!(find_interface(&counters, key, "{0000}", "eth1").is_none());
+    }
+}
@@ -0,0 +1,190 @@
+//! DHCP 服务器探测：安全审计广播一个 DHCPDISCOVER，收集等待窗口内的全部 DHCPOFFER。
+//!
+//! 客户端硬件地址用随机的本地管理 MAC，服务器为它预留的地址到期即回收，不影响本机租约。
+//! 只发 DISCOVER、从不 REQUEST，因此不会真正租到地址。绑定 UDP 68 在 Linux / macOS 上
+//! 需要 root 或 `CAP_NET_BIND_SERVICE`。
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use iptools_core::{DhcpServer, RuntimeError, RuntimeErrorCode};
+use socket2::{Domain, Protocol, Socket, Type};
+
+const CLIENT_PORT: u16 = 68;
+const SERVER_PORT: u16 = 67;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+const OPTIONS_AT: usize = 240;
+const BOOTREQUEST: u8 = 1;
+const BOOTREPLY: u8 = 2;
+const OPTION_ROUTER: u8 = 3;
+const OPTION_MESSAGE_TYPE: u8 = 53;
+const OPTION_SERVER_ID: u8 = 54;
+const OPTION_PARAMETERS: u8 = 55;
+const OPTION_END: u8 = 255;
+const DHCPDISCOVER: u8 = 1;
+const DHCPOFFER: u8 = 2;
+
+/// 广播 DISCOVER 并在 `wait` 内收集应答；同一服务器只记一次。
+pub fn discover(wait: Duration) -> Result<Vec<DhcpServer>, RuntimeError> {
+    let socket = bind().map_err(|error| {
+        let code = if error.kind() == std::io::ErrorKind::PermissionDenied {
+            RuntimeErrorCode::PermissionDenied
+        } else {
+            RuntimeErrorCode::Internal
+        };
+        RuntimeError::new(
+            code,
+            format!("cannot listen on UDP port {CLIENT_PORT}: {error}"),
+        )
+    })?;
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let xid = nanos as u32;
+    let mut mac = [0u8; 6];
+    mac[1..].copy_from_slice(&(nanos >> 32).to_be_bytes()[11..]);
+    mac[0] = 0x02;
+    socket
+        .send_to(
+            &discover_packet(xid, mac),
+            SocketAddrV4::new(Ipv4Addr::BROADCAST, SERVER_PORT),
+        )
+        .map_err(|error| RuntimeError::new(RuntimeErrorCode::Network, error.to_string()))?;
+
+    let deadline = Instant::now() + wait;
+    let mut servers: Vec<DhcpServer> = Vec::new();
+    let mut buf = [0u8; 1500];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() || socket.set_read_timeout(Some(remaining)).is_err() {
+            break;
+        }
+        let Ok((n, from)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+        let SocketAddr::V4(from) = from else {
+            continue;
+        };
+        if let Some(server) = parse_offer(&buf[..n], xid, *from.ip())
+            && !servers.iter().any(|known| known.server == server.server)
+        {
+            servers.push(server);
+        }
+    }
+    Ok(servers)
+}
+
+/// 本机的 DHCP 客户端可能已占用 68 端口，复用地址与它共享广播应答。
+fn bind() -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.set_broadcast(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, CLIENT_PORT)).into())?;
+    Ok(socket.into())
+}
+
+/// 置广播标志的 DHCPDISCOVER（RFC 2131），请求子网掩码、路由器、DNS 与服务器标识。
+pub(crate) fn discover_packet(xid: u32, mac: [u8; 6]) -> Vec<u8> {
+    let mut packet = vec![0u8; OPTIONS_AT];
+    packet[0] = BOOTREQUEST;
+    packet[1] = 1; // 以太网
+    packet[2] = 6;
+    packet[4..8].copy_from_slice(&xid.to_be_bytes());
+    packet[10] = 0x80; // 广播标志：没有地址的客户端收不到单播应答
+    packet[28..34].copy_from_slice(&mac);
+    packet[236..240].copy_from_slice(&MAGIC_COOKIE);
+    packet.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, DHCPDISCOVER]);
+    packet.extend_from_slice(&[OPTION_PARAMETERS, 4, 1, OPTION_ROUTER, 6, OPTION_SERVER_ID]);
+    packet.push(OPTION_END);
+    // 部分老服务器拒收短于 BOOTP 最小长度（300 字节）的报文
+    packet.resize(300, 0);
+    packet
+}
+
+/// 属于本次 `xid` 的 DHCPOFFER；没有服务器标识选项时以发送方地址代替。
+pub(crate) fn parse_offer(packet: &[u8], xid: u32, from: Ipv4Addr) -> Option<DhcpServer> {
+    if packet.len() < OPTIONS_AT
+        || packet[0] != BOOTREPLY
+        || packet[4..8] != xid.to_be_bytes()
+        || packet[236..240] != MAGIC_COOKIE
+    {
+        return None;
+    }
+    let address = |bytes: &[u8]| -> Option<Ipv4Addr> {
+        Some(Ipv4Addr::from(<[u8; 4]>::try_from(bytes.get(..4)?).ok()?))
+    };
+    let mut message_type = None;
+    let mut server = None;
+    let mut router = None;
+    let mut options = &packet[OPTIONS_AT..];
+    while let [code, rest @ ..] = options {
+        match *code {
+            0 => {
+                options = rest;
+                continue;
+            }
+            OPTION_END => break,
+            _ => {}
+        }
+        let (&len, rest) = rest.split_first()?;
+        let value = rest.get(..len as usize)?;
+        match *code {
+            OPTION_MESSAGE_TYPE => message_type = value.first().copied(),
+            OPTION_SERVER_ID => server = address(value),
+            OPTION_ROUTER => router = address(value),
+            _ => {}
+        }
+        options = &rest[len as usize..];
+    }
+    (message_type == Some(DHCPOFFER)).then(|| DhcpServer {
+        server: server.unwrap_or(from).to_string(),
+        offered: address(&packet[16..20])
+            .map(|ip| ip.to_string())
+            .unwrap_or_default(),
+        router: router.map(|ip| ip.to_string()).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_offer_names_its_server_and_router() {
+        let discover = discover_packet(0x1234_5678, [2, 0, 0, 0, 0, 1]);
+        assert_eq!(discover.len(), 300);
+        assert_eq!(&discover[240..243], [OPTION_MESSAGE_TYPE, 1, DHCPDISCOVER]);
+
+        let mut offer = discover.clone();
+        offer[0] = BOOTREPLY;
+        offer[16..20].copy_from_slice(&[192, 168, 1, 100]);
+        offer.truncate(OPTIONS_AT);
+        offer.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, DHCPOFFER, 0]);
+        offer.extend_from_slice(&[OPTION_ROUTER, 4, 192, 168, 1, 1]);
+        offer.extend_from_slice(&[OPTION_SERVER_ID, 4, 192, 168, 1, 77, OPTION_END]);
+        let sender = Ipv4Addr::new(192, 168, 1, 2);
+        assert_eq!(
+            parse_offer(&offer, 0x1234_5678, sender),
+            Some(DhcpServer {
+                server: "192.168.1.77".into(),
+                offered: "192.168.1.100".into(),
+                router: "192.168.1.1".into(),
+            })
+        );
+        assert_eq!(
+            parse_offer(&offer, 1, sender),
+            None,
+            "another client's offer"
+        );
+        assert_eq!(parse_offer(&discover, 0x1234_5678, sender), None);
+
+        // 没有服务器标识时退回发送方；截断的选项不解析
+        offer.truncate(OPTIONS_AT + 4);
+        assert_eq!(
+            parse_offer(&offer, 0x1234_5678, sender).map(|server| server.server),
+            Some("192.168.1.2".into())
+        );
+        offer.extend_from_slice(&[OPTION_ROUTER, 4, 192]);
+        assert_eq!(parse_offer(&offer, 0x1234_5678, sender), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! 局域网服务发现：一次 mDNS 服务枚举加一次 SSDP 搜索，
+//! 列出各设备自报的服务类型（AirPlay、IPP 打印、UPnP 媒体渲染器等）。
+//!
+//! 两种查询都从同一个临时端口发出：mDNS 设置 QU 位请求单播应答，
+//! SSDP 的应答本来就是单播，因此无需加入组播组。
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use iptools_core::{DiscoveredService, ServiceProtocol};
+use tokio::net::UdpSocket;
+
+use super::net::{decode_dns_name, dns_names_equal, skip_dns_name};
+
+/// 设备应答 SSDP 前会按 MX 随机延迟，监听时间要比 MX 稍长
+const LISTEN: Duration = Duration::from_secs(3);
+const MDNS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)), 5353);
+const SSDP: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+/// DNS-SD 的服务类型枚举名（RFC 6763 §9）
+const SERVICE_TYPES: &str = "_services._dns-sd._udp.local";
+const TYPE_PTR: u16 = 12;
+const SSDP_SEARCH: &str = "M-SEARCH * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    MAN: \"ssdp:discover\"\r\n\
+    MX: 2\r\n\
+    ST: ssdp:all\r\n\r\n";
+
+/// 发出查询并监听一段时间；按 IP、协议与名称排序并去重。套接字不可用时返回空表。
+pub async fn discover() -> Vec<DiscoveredService> {
+    let Ok(socket) = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await else {
+        return Vec::new();
+    };
+    let _ = socket.send_to(&mdns_query(), MDNS).await;
+    let _ = socket.send_to(SSDP_SEARCH.as_bytes(), SSDP).await;
+
+    let deadline = tokio::time::Instant::now() + LISTEN;
+    let mut services = Vec::new();
+    let mut buffer = [0u8; 9_000];
+    while let Ok(Ok((length, SocketAddr::V4(source)))) =
+        tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await
+    {
+        let packet = &buffer[..length];
+        let found = if source.port() == MDNS.port() {
+            parse_mdns_services(packet)
+                .into_iter()
+                .map(|name| (ServiceProtocol::Mdns, name))
+                .collect()
+        } else {
+            parse_ssdp(&String::from_utf8_lossy(packet))
+                .map(|name| (ServiceProtocol::Ssdp, name))
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        for (protocol, name) in found {
+            services.push(DiscoveredService {
+                ip: source.ip().to_string(),
+                protocol,
+                name,
+            });
+        }
+    }
+    services.sort_by(|left, right| {
+        let key = |service: &DiscoveredService| {
+            (
+                service.ip.parse::<Ipv4Addr>().ok(),
+                service.protocol.label(),
+                service.name.clone(),
+            )
+        };
+        key(left).cmp(&key(right))
+    });
+    services.dedup();
+    services
+}
+
+fn mdns_query() -> Vec<u8> {
+    let mut query = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in SERVICE_TYPES.split('.') {
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    query.extend_from_slice(&[0x80, 0x01]); // Class = IN + QU 位
+    query
+}
+
+/// 取服务类型枚举的 PTR 目标，如 `_ipp._tcp`（去掉 `.local`）。应答区与附加区都看。
+pub(crate) fn parse_mdns_services(packet: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let Some(header) = packet.get(..12) else {
+        return names;
+    };
+    let count = |index: usize| usize::from(u16::from_be_bytes([header[index], header[index + 1]]));
+    let mut offset = 12;
+    for _ in 0..count(4) {
+        match skip_dns_name(packet, offset) {
+            Some(end) => offset = end + 4,
+            None => return names,
+        }
+    }
+    for _ in 0..count(6) + count(8) + count(10) {
+        let Some((owner, end)) = decode_dns_name(packet, offset) else {
+            break;
+        };
+        let Some(fixed) = packet.get(end..end + 10) else {
+            break;
+        };
+        let kind = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let length = usize::from(u16::from_be_bytes([fixed[8], fixed[9]]));
+        if kind == TYPE_PTR
+            && dns_names_equal(&owner, SERVICE_TYPES)
+            && let Some((target, _)) = decode_dns_name(packet, end + 10)
+        {
+            let target = target.trim_end_matches('.').trim_end_matches(".local");
+            if !target.is_empty() && !names.iter().any(|name| name == target) {
+                names.push(target.to_string());
+            }
+        }
+        offset = end + 10 + length;
+    }
+    names
+}
+
+/// 从 SSDP 应答的 ST 头取设备或服务类型，如 `MediaRenderer`；
+/// `upnp:rootdevice` 与 `uuid:` 只标识设备本身，不列出。
+pub(crate) fn parse_ssdp(response: &str) -> Option<String> {
+    let mut lines = response.lines();
+    if !lines
+        .next()?
+        .to_ascii_uppercase()
+        .starts_with("HTTP/1.1 200")
+    {
+        return None;
+    }
+    let target = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("ST").then(|| value.trim())
+    })?;
+    // urn:<域>:device|service:<类型>:<版本>
+    let mut parts = target.strip_prefix("urn:")?.split(':');
+    let kind = parts.nth(2)?;
+    (!kind.is_empty()).then(|| kind.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mdns_service_types_are_read_from_answers_and_additionals() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 1];
+        let owner = packet.len();
+        for label in SERVICE_TYPES.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+        packet.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0x11, 0x94, 0, 12]);
+        packet.extend_from_slice(b"\x04_ipp\x04_tcp\xC0");
+        packet.push((owner + SERVICE_TYPES.find(".local").unwrap() + 1) as u8);
+        // 附加区：同一所有者（压缩指针），另一种服务
+        packet.extend_from_slice(&[0xC0, owner as u8, 0, 12, 0, 1, 0, 0, 0x11, 0x94, 0, 11]);
+        packet.extend_from_slice(b"\x04_smb\x04_tcp");
+        packet.push(0);
+        assert_eq!(parse_mdns_services(&packet), ["_ipp._tcp", "_smb._tcp"]);
+        assert!(parse_mdns_services(&packet[..8]).is_empty());
+    }
+
+    #[test]
+    fn ssdp_search_target_names_the_device_type() {
+        let reply = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\n\
+            st: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n";
+        assert_eq!(parse_ssdp(reply).as_deref(), Some("MediaRenderer"));
+        let root = "HTTP/1.1 200 OK\r\nST: upnp:rootdevice\r\n\r\n";
+        assert_eq!(parse_ssdp(root), None);
+        assert_eq!(parse_ssdp(SSDP_SEARCH), None);
+    }
+}
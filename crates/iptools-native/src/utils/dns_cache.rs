@@ -0,0 +1,191 @@
+//! 全应用共用的主机名解析缓存。
+//!
+//! 扫描器、Ping、Trace、端口扫描会反复解析同一批名字和地址；连续扫描时每轮都去问
+//! 一遍解析器，既慢又给解析器添负担。这里把系统解析器（`getaddrinfo` /
+//! `getnameinfo`）的结果按正向、反向各缓存一份：查到的结果保留 [`POSITIVE_TTL`]，
+//! 查不到的（无记录、解析失败）保留较短的 [`NEGATIVE_TTL`]，过期后下次用到时重查。
+//!
+//! 系统解析器不给出记录自身的 TTL，所以两者都是固定值。DNS 测速与过滤检测
+//! 衡量的正是解析器本身，不走这里。
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// 查到结果的保留时长。
+pub const POSITIVE_TTL: Duration = Duration::from_secs(300);
+/// 查不到结果的保留时长；短一些，让刚上线的设备或恢复的解析器很快生效。
+pub const NEGATIVE_TTL: Duration = Duration::from_secs(30);
+/// 每个方向最多保留的条目数；满了先清过期项，仍满则丢最早写入的。
+const CAPACITY: usize = 4_096;
+
+static FORWARD: LazyLock<TtlCache<String, Result<Vec<IpAddr>, String>>> =
+    LazyLock::new(|| TtlCache::new(CAPACITY));
+static REVERSE: LazyLock<TtlCache<IpAddr, Option<String>>> =
+    LazyLock::new(|| TtlCache::new(CAPACITY));
+
+/// 名字解析到的地址，按解析器给出的顺序。阻塞，供扫描线程调用。
+pub fn resolve_blocking(name: &str) -> Result<Vec<IpAddr>, String> {
+    let key = cache_key(name);
+    if let Some(cached) = FORWARD.get(&key, Instant::now()) {
+        return cached;
+    }
+    let result = dns_lookup::lookup_host(name)
+        .map_err(|error| error.to_string())
+        .and_then(distinct);
+    FORWARD.insert(key, result.clone(), ttl_of(result.is_ok()), Instant::now());
+    result
+}
+
+/// [`resolve_blocking`] 的异步版本。
+pub async fn resolve(name: &str) -> Result<Vec<IpAddr>, String> {
+    let key = cache_key(name);
+    if let Some(cached) = FORWARD.get(&key, Instant::now()) {
+        return cached;
+    }
+    let result = tokio::net::lookup_host((name, 0))
+        .await
+        .map_err(|error| error.to_string())
+        .and_then(|addresses| distinct(addresses.map(|address| address.ip())));
+    FORWARD.insert(key, result.clone(), ttl_of(result.is_ok()), Instant::now());
+    result
+}
+
+/// 地址的反向解析名；无 PTR 记录、解析失败或解析器回填了数字地址时为 `None`。
+/// 阻塞，供扫描线程调用。
+pub fn reverse_blocking(ip: IpAddr) -> Option<String> {
+    if let Some(cached) = REVERSE.get(&ip, Instant::now()) {
+        return cached;
+    }
+    let name = dns_lookup::lookup_addr(&ip)
+        .ok()
+        .filter(|name| name.parse::<IpAddr>().is_err());
+    REVERSE.insert(ip, name.clone(), ttl_of(name.is_some()), Instant::now());
+    name
+}
+
+/// [`reverse_blocking`] 的异步版本，解析放到阻塞线程里跑。
+pub async fn reverse(ip: IpAddr) -> Option<String> {
+    if let Some(cached) = REVERSE.get(&ip, Instant::now()) {
+        return cached;
+    }
+    tokio::task::spawn_blocking(move || reverse_blocking(ip))
+        .await
+        .ok()
+        .flatten()
+}
+
+const NO_ADDRESSES: &str = "target resolved to no addresses";
+
+/// 去掉重复地址（`getaddrinfo` 按套接字类型各返回一遍），保持原有顺序。
+fn distinct(addresses: impl IntoIterator<Item = IpAddr>) -> Result<Vec<IpAddr>, String> {
+    let mut unique: Vec<IpAddr> = Vec::new();
+    for address in addresses {
+        if !unique.contains(&address) {
+            unique.push(address);
+        }
+    }
+    if unique.is_empty() {
+        return Err(NO_ADDRESSES.to_string());
+    }
+    Ok(unique)
+}
+
+/// 名字不分大小写、忽略末尾的点。
+fn cache_key(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+const fn ttl_of(found: bool) -> Duration {
+    if found { POSITIVE_TTL } else { NEGATIVE_TTL }
+}
+
+struct Entry<V> {
+    value: V,
+    inserted: Instant,
+    expires: Instant,
+}
+
+/// 带过期时间和容量上限的简单缓存；时间由调用方传入，便于测试。
+struct TtlCache<K, V> {
+    entries: Mutex<HashMap<K, Entry<V>>>,
+    capacity: usize,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    fn get(&self, key: &K, now: Instant) -> Option<V> {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        match entries.get(key) {
+            Some(entry) if entry.expires > now => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: K, value: V, ttl: Duration, now: Instant) {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.expires > now);
+            if entries.len() >= self.capacity
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                value,
+                inserted: now,
+                expires: now + ttl,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire_after_their_ttl_and_the_oldest_make_room() {
+        let cache = TtlCache::new(2);
+        let start = Instant::now();
+        cache.insert("a", Some(1), POSITIVE_TTL, start);
+        cache.insert("b", None, NEGATIVE_TTL, start + Duration::from_secs(1));
+        assert_eq!(
+            cache.get(&"a", start + Duration::from_secs(60)),
+            Some(Some(1))
+        );
+        assert_eq!(cache.get(&"b", start + Duration::from_secs(60)), None);
+
+        cache.insert("b", None, NEGATIVE_TTL, start + Duration::from_secs(60));
+        cache.insert("c", Some(3), POSITIVE_TTL, start + Duration::from_secs(61));
+        let later = start + Duration::from_secs(62);
+        assert_eq!(cache.get(&"a", later), None, "the oldest entry made room");
+        assert_eq!(cache.get(&"b", later), Some(None));
+        assert_eq!(cache.get(&"c", later), Some(Some(3)));
+        assert_eq!(cache_key(" Router.LAN. "), "router.lan");
+    }
+}
@@ -0,0 +1,246 @@
+//! 网卡热插拔通知：插拔 USB 网卡、开关 Wi-Fi、地址或默认路由变化时置位一个标志，
+//! 主循环在下一个 tick 取走它并刷新网卡列表、仪表盘与扫描默认网段。
+//!
+//! Linux / Android 订阅 netlink 路由组，macOS 读取 PF_ROUTE 套接字，Windows 注册
+//! `NotifyIpInterfaceChange` 与 `NotifyUnicastIpAddressChange`。订阅失败（如 Android
+//! 的 SELinux 禁止绑定 netlink 组）时标志永不置位，界面仍有定时轮询兜底。
+//! 通知往往成串到达（链路、地址、路由各一条），标志天然把它们合并为一次刷新。
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+pub struct InterfaceWatcher {
+    changed: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    #[cfg(target_os = "windows")]
+    handles: Vec<windows::Win32::Foundation::HANDLE>,
+}
+
+impl InterfaceWatcher {
+    pub fn start() -> Self {
+        let changed = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+        {
+            let changed = Arc::clone(&changed);
+            let stop = Arc::clone(&stop);
+            if let Err(error) = std::thread::Builder::new()
+                .name("iptools-hotplug".into())
+                .spawn(move || unix::watch(&changed, &stop))
+            {
+                tracing::warn!(%error, "interface change watcher failed to start");
+            }
+        }
+        #[cfg(target_os = "windows")]
+        let handles = win::register(&changed);
+        Self {
+            changed,
+            stop,
+            #[cfg(target_os = "windows")]
+            handles,
+        }
+    }
+
+    /// 自上次调用以来是否有接口变化。
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Drop for InterfaceWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        #[cfg(target_os = "windows")]
+        win::cancel(&self.handles);
+    }
+}
+
+/// BSD 路由套接字上只有接口状态与地址增删才算接口变化；ARP / 路由缓存条目的
+/// 增删非常频繁，不能每条都触发刷新。消息头第 4 字节为消息类型。
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn route_message_is_interface_change(message: &[u8]) -> bool {
+    /// RTM_NEWADDR、RTM_DELADDR、RTM_IFINFO
+    const INTERFACE_MESSAGES: [u8; 3] = [0xc, 0xd, 0xe];
+    message
+        .get(3)
+        .is_some_and(|kind| INTERFACE_MESSAGES.contains(kind))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+mod unix {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// 阻塞读通知套接字，直到 `stop` 置位；读超时一秒以便及时退出。
+    pub(super) fn watch(changed: &AtomicBool, stop: &AtomicBool) {
+        let Some(fd) = open() else {
+            tracing::debug!("interface change notifications unavailable; relying on polling");
+            return;
+        };
+        let timeout = libc::timeval {
+            tv_sec: 1,
+            tv_usec: 0,
+        };
+        let mut buffer = [0u8; 8192];
+        unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            );
+            while !stop.load(Ordering::Relaxed) {
+                let length = libc::recv(fd, buffer.as_mut_ptr().cast(), buffer.len(), 0);
+                if length > 0 && relevant(&buffer[..length as usize]) {
+                    changed.store(true, Ordering::Relaxed);
+                }
+            }
+            libc::close(fd);
+        }
+    }
+
+    /// 订阅链路、IPv4/IPv6 地址与 IPv4 路由组（后者覆盖默认网关变化）。
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn open() -> Option<libc::c_int> {
+        const RTMGRP_LINK: u32 = 0x1;
+        const RTMGRP_IPV4_IFADDR: u32 = 0x10;
+        const RTMGRP_IPV4_ROUTE: u32 = 0x40;
+        const RTMGRP_IPV6_IFADDR: u32 = 0x100;
+
+        unsafe {
+            let fd = libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            );
+            if fd < 0 {
+                return None;
+            }
+            let mut address: libc::sockaddr_nl = std::mem::zeroed();
+            address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            address.nl_groups =
+                RTMGRP_LINK | RTMGRP_IPV4_IFADDR | RTMGRP_IPV4_ROUTE | RTMGRP_IPV6_IFADDR;
+            let bound = libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            );
+            if bound < 0 {
+                libc::close(fd);
+                return None;
+            }
+            Some(fd)
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn relevant(_message: &[u8]) -> bool {
+        true
+    }
+
+    #[cfg(target_os = "macos")]
+    fn open() -> Option<libc::c_int> {
+        let fd = unsafe { libc::socket(libc::PF_ROUTE, libc::SOCK_RAW, libc::AF_UNSPEC) };
+        (fd >= 0).then_some(fd)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn relevant(message: &[u8]) -> bool {
+        super::route_message_is_interface_change(message)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use windows::Win32::Foundation::{BOOLEAN, HANDLE};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        CancelMibChangeNotify2, MIB_IPINTERFACE_ROW, MIB_NOTIFICATION_TYPE,
+        MIB_UNICASTIPADDRESS_ROW, NotifyIpInterfaceChange, NotifyUnicastIpAddressChange,
+    };
+    use windows::Win32::Networking::WinSock::AF_UNSPEC;
+
+    /// 回调在系统线程池上执行；上下文指向一份泄漏的 `Arc`，注销后仍保持有效。
+    pub(super) fn register(changed: &Arc<AtomicBool>) -> Vec<HANDLE> {
+        let context = Arc::into_raw(Arc::clone(changed)) as *const std::ffi::c_void;
+        let mut handles = Vec::new();
+        unsafe {
+            let mut handle = HANDLE::default();
+            if NotifyIpInterfaceChange(
+                AF_UNSPEC,
+                Some(interface_changed),
+                Some(context),
+                BOOLEAN(0),
+                &mut handle,
+            )
+            .0 == 0
+            {
+                handles.push(handle);
+            }
+            let mut handle = HANDLE::default();
+            if NotifyUnicastIpAddressChange(
+                AF_UNSPEC,
+                Some(address_changed),
+                Some(context),
+                BOOLEAN(0),
+                &mut handle,
+            )
+            .0 == 0
+            {
+                handles.push(handle);
+            }
+        }
+        handles
+    }
+
+    pub(super) fn cancel(handles: &[HANDLE]) {
+        for handle in handles {
+            unsafe {
+                let _ = CancelMibChangeNotify2(*handle);
+            }
+        }
+    }
+
+    unsafe extern "system" fn interface_changed(
+        context: *const std::ffi::c_void,
+        _row: *const MIB_IPINTERFACE_ROW,
+        _kind: MIB_NOTIFICATION_TYPE,
+    ) {
+        unsafe { notify(context) }
+    }
+
+    unsafe extern "system" fn address_changed(
+        context: *const std::ffi::c_void,
+        _row: *const MIB_UNICASTIPADDRESS_ROW,
+        _kind: MIB_NOTIFICATION_TYPE,
+    ) {
+        unsafe { notify(context) }
+    }
+
+    unsafe fn notify(context: *const std::ffi::c_void) {
+        if let Some(changed) = unsafe { (context as *const AtomicBool).as_ref() } {
+            changed.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_interface_and_address_route_messages_count_as_changes() {
+        // rt_msghdr / ifa_msghdr：长度（2 字节）、版本、类型
+        assert!(route_message_is_interface_change(&[0x70, 0, 5, 0xe]));
+        assert!(route_message_is_interface_change(&[0x70, 0, 5, 0xc]));
+        // RTM_ADD（ARP 缓存条目）与截断消息都不算
+        assert!(!route_message_is_interface_change(&[0x70, 0, 5, 0x1]));
+        assert!(!route_message_is_interface_change(&[0x70, 0]));
+    }
+}
@@ -0,0 +1,109 @@
+//! 全应用共用的网卡信息。
+//!
+//! 仪表盘、网卡页、扫描器和几项诊断各自在不同时刻要网卡列表，而一次完整枚举并不便宜：
+//! Windows 上要查 WLAN 与 `GetAdaptersAddresses`，Linux 上要起 `nmcli` / `iw` 子进程。
+//! 这里保存最近一次 [`net::get_interfaces`] 的结果供所有模块读取；热插拔通知
+//! （见 [`super::hotplug`]）或改完网卡配置时调用 [`invalidate`]，下一次读取重新枚举。
+//! 通知订阅失败的平台上，结果最多保留 [`MAX_AGE`]，与界面的定时轮询兜底一致。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use super::net::{self, InterfaceInfo};
+
+/// 没有变化通知时结果的最长保留时间。
+pub const MAX_AGE: Duration = Duration::from_secs(10);
+
+static SHARED: LazyLock<InterfaceStore> =
+    LazyLock::new(|| InterfaceStore::new(net::get_interfaces));
+
+/// 当前网卡列表，必要时重新枚举。会阻塞；异步代码用 [`super::net::interfaces`]。
+pub fn interfaces() -> Vec<InterfaceInfo> {
+    SHARED.get(Instant::now())
+}
+
+/// 网卡有变化：下一次读取重新枚举。
+pub fn invalidate() {
+    SHARED.invalidate();
+}
+
+struct Snapshot {
+    generation: u64,
+    taken: Instant,
+    interfaces: Vec<InterfaceInfo>,
+}
+
+struct InterfaceStore {
+    load: fn() -> Vec<InterfaceInfo>,
+    /// 每次失效加一；快照记下枚举开始时的值，枚举途中失效的结果不再算新。
+    generation: AtomicU64,
+    /// 枚举期间一直持有，同时到来的读取等这一次的结果而不是各自再枚举一遍。
+    snapshot: Mutex<Option<Snapshot>>,
+}
+
+impl InterfaceStore {
+    fn new(load: fn() -> Vec<InterfaceInfo>) -> Self {
+        Self {
+            load,
+            generation: AtomicU64::new(0),
+            snapshot: Mutex::new(None),
+        }
+    }
+
+    fn get(&self, now: Instant) -> Vec<InterfaceInfo> {
+        let mut snapshot = self
+            .snapshot
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let generation = self.generation.load(Ordering::Acquire);
+        if let Some(current) = snapshot.as_ref()
+            && current.generation == generation
+            && now.saturating_duration_since(current.taken) < MAX_AGE
+        {
+            return current.interfaces.clone();
+        }
+        let interfaces = (self.load)();
+        *snapshot = Some(Snapshot {
+            generation,
+            taken: now,
+            interfaces: interfaces.clone(),
+        });
+        interfaces
+    }
+
+    fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    static LOADS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counting_load() -> Vec<InterfaceInfo> {
+        LOADS.fetch_add(1, Ordering::Relaxed);
+        Vec::new()
+    }
+
+    #[test]
+    fn reads_share_one_enumeration_until_invalidated_or_too_old() {
+        let store = InterfaceStore::new(counting_load);
+        let start = Instant::now();
+        store.get(start);
+        store.get(start + Duration::from_secs(1));
+        assert_eq!(LOADS.load(Ordering::Relaxed), 1);
+
+        store.invalidate();
+        store.get(start + Duration::from_secs(2));
+        store.get(start + Duration::from_secs(3));
+        assert_eq!(LOADS.load(Ordering::Relaxed), 2);
+
+        store.get(start + Duration::from_secs(2) + MAX_AGE);
+        assert_eq!(LOADS.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,395 @@
+//! 打印机与物联网协议探测：扫描器补充探测的一步，对开放了 IPP、RTSP、MQTT、
+//! Modbus/TCP 端口的设备各问一句“你是谁”，据应答给设备打上类型标签。
+//!
+//! 每种协议只发一个请求：IPP Get-Printer-Attributes 取打印机名与型号，RTSP
+//! OPTIONS 取 `Server` 头，MQTT 以匿名身份 CONNECT 看代理是否接受，Modbus 读设备
+//! 标识（功能码 43/14）。不订阅、不写寄存器，也不尝试任何口令。
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+use iptools_core::{IotProtocol, IotService};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// 应答只看开头，读到这么多仍不完整就按已有的解析
+const RESPONSE_LIMIT: usize = 64 * 1024;
+
+/// IPP 操作 Get-Printer-Attributes（RFC 8011 §4.2.5）
+const IPP_GET_PRINTER_ATTRIBUTES: u16 = 0x000B;
+const IPP_OPERATION_ATTRIBUTES: u8 = 0x01;
+const IPP_END_OF_ATTRIBUTES: u8 = 0x03;
+const IPP_KEYWORD: u8 = 0x44;
+const IPP_URI: u8 = 0x45;
+const IPP_CHARSET: u8 = 0x47;
+const IPP_NATURAL_LANGUAGE: u8 = 0x48;
+
+const MQTT_CONNECT: u8 = 0x10;
+const MQTT_CONNACK: u8 = 0x20;
+const MQTT_DISCONNECT: [u8; 2] = [0xE0, 0x00];
+
+/// Modbus 封装接口传输（功能码 43）之读设备标识（MEI 14）
+const MODBUS_ENCAPSULATED: u8 = 0x2B;
+const MODBUS_READ_DEVICE_ID: u8 = 0x0E;
+/// 直连 Modbus/TCP 设备不看单元号，网关则把 0xFF 视为自身
+const MODBUS_UNIT: u8 = 0xFF;
+
+/// 按已开放的端口逐个探测；不应答或答非所问的端口不列出。
+pub fn probe(ip: Ipv4Addr, open_ports: &[u16]) -> Vec<IotService> {
+    IotProtocol::ALL
+        .into_iter()
+        .filter(|protocol| open_ports.contains(&protocol.port()))
+        .filter_map(|protocol| {
+            let detail = match protocol {
+                IotProtocol::Ipp => ipp(ip),
+                IotProtocol::Rtsp => rtsp(ip),
+                IotProtocol::Mqtt => mqtt(ip),
+                IotProtocol::Modbus => modbus(ip),
+            }?;
+            Some(IotService { protocol, detail })
+        })
+        .collect()
+}
+
+fn ipp(ip: Ipv4Addr) -> Option<String> {
+    let body = ipp_request(&format!("ipp://{ip}:631/ipp/print"));
+    let mut request = format!(
+        "POST /ipp/print HTTP/1.1\r\nHost: {ip}:631\r\nContent-Type: application/ipp\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    request.extend(body);
+    let response = exchange(ip, IotProtocol::Ipp.port(), &request, |_| false)?;
+    let attributes = parse_ipp_attributes(&http_body(&response)?)?;
+    let value = |name: &str| {
+        attributes
+            .iter()
+            .find(|(key, value)| key == name && !value.trim().is_empty())
+            .map(|(_, value)| value.trim().to_string())
+    };
+    Some(
+        [value("printer-name"), value("printer-make-and-model")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · "),
+    )
+}
+
+fn rtsp(ip: Ipv4Addr) -> Option<String> {
+    let request =
+        format!("OPTIONS rtsp://{ip}:554/ RTSP/1.0\r\nCSeq: 1\r\nUser-Agent: iptools\r\n\r\n");
+    let response = exchange(ip, IotProtocol::Rtsp.port(), request.as_bytes(), |data| {
+        data.windows(4).any(|window| window == b"\r\n\r\n")
+    })?;
+    parse_rtsp_server(&String::from_utf8_lossy(&response))
+}
+
+fn mqtt(ip: Ipv4Addr) -> Option<String> {
+    let mut stream = connect(ip, IotProtocol::Mqtt.port())?;
+    stream.write_all(&mqtt_connect()).ok()?;
+    let code = parse_connack(&read_until(&mut stream, |data| data.len() >= 4)?)?;
+    // 被接受时礼貌地断开，免得代理把这次连接记成异常掉线
+    if code == 0 {
+        let _ = stream.write_all(&MQTT_DISCONNECT);
+    }
+    Some(
+        match code {
+            0 => "anonymous clients accepted",
+            4 | 5 => "authentication required",
+            _ => "connection refused",
+        }
+        .into(),
+    )
+}
+
+fn modbus(ip: Ipv4Addr) -> Option<String> {
+    let request = [
+        0x00,
+        0x01,
+        0x00,
+        0x00,
+        0x00,
+        0x05,
+        MODBUS_UNIT,
+        MODBUS_ENCAPSULATED,
+        MODBUS_READ_DEVICE_ID,
+        0x01,
+        0x00,
+    ];
+    let response = exchange(ip, IotProtocol::Modbus.port(), &request, |data| {
+        data.len() >= 6 && data.len() >= 6 + usize::from(u16::from_be_bytes([data[4], data[5]]))
+    })?;
+    parse_modbus_identification(&response)
+}
+
+/// 发送请求后读到 `done` 成立、对端关闭或超时为止。
+fn exchange(
+    ip: Ipv4Addr,
+    port: u16,
+    request: &[u8],
+    done: impl Fn(&[u8]) -> bool,
+) -> Option<Vec<u8>> {
+    let mut stream = connect(ip, port)?;
+    stream.write_all(request).ok()?;
+    read_until(&mut stream, done)
+}
+
+fn connect(ip: Ipv4Addr, port: u16) -> Option<TcpStream> {
+    let stream =
+        TcpStream::connect_timeout(&SocketAddr::new(IpAddr::V4(ip), port), PROBE_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    Some(stream)
+}
+
+fn read_until(stream: &mut TcpStream, done: impl Fn(&[u8]) -> bool) -> Option<Vec<u8>> {
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 4096];
+    while response.len() < RESPONSE_LIMIT && !done(&response) {
+        match stream.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => response.extend_from_slice(&buffer[..read]),
+        }
+    }
+    (!response.is_empty()).then_some(response)
+}
+
+pub(crate) fn ipp_request(printer_uri: &str) -> Vec<u8> {
+    let mut request = vec![0x01, 0x01];
+    request.extend(IPP_GET_PRINTER_ATTRIBUTES.to_be_bytes());
+    request.extend(1u32.to_be_bytes());
+    request.push(IPP_OPERATION_ATTRIBUTES);
+    for (tag, name, value) in [
+        (IPP_CHARSET, "attributes-charset", "utf-8"),
+        (IPP_NATURAL_LANGUAGE, "attributes-natural-language", "en"),
+        (IPP_URI, "printer-uri", printer_uri),
+        (IPP_KEYWORD, "requested-attributes", "printer-name"),
+        // 名字为空表示上一个属性的又一个取值
+        (IPP_KEYWORD, "", "printer-make-and-model"),
+    ] {
+        request.push(tag);
+        request.extend((name.len() as u16).to_be_bytes());
+        request.extend(name.as_bytes());
+        request.extend((value.len() as u16).to_be_bytes());
+        request.extend(value.as_bytes());
+    }
+    request.push(IPP_END_OF_ATTRIBUTES);
+    request
+}
+
+/// IPP 应答中的 `(属性名, 取值)`；版本号不是 1.x / 2.x 的不当作 IPP。
+pub(crate) fn parse_ipp_attributes(body: &[u8]) -> Option<Vec<(String, String)>> {
+    if body.len() < 8 || !matches!(body[0], 1 | 2) {
+        return None;
+    }
+    let mut attributes = Vec::new();
+    let mut rest = &body[8..];
+    let mut last_name = String::new();
+    let take = |rest: &mut &[u8]| -> Option<Vec<u8>> {
+        let length = usize::from(u16::from_be_bytes([*rest.first()?, *rest.get(1)?]));
+        let value = rest.get(2..2 + length)?.to_vec();
+        *rest = &rest[2 + length..];
+        Some(value)
+    };
+    while let Some((&tag, tail)) = rest.split_first() {
+        rest = tail;
+        if tag == IPP_END_OF_ATTRIBUTES {
+            break;
+        }
+        // 分组标记之后直接是下一个属性
+        if tag < 0x10 {
+            continue;
+        }
+        let Some(name) = take(&mut rest) else { break };
+        let Some(value) = take(&mut rest) else { break };
+        if !name.is_empty() {
+            last_name = String::from_utf8_lossy(&name).into_owned();
+        }
+        attributes.push((
+            last_name.clone(),
+            String::from_utf8_lossy(&value).into_owned(),
+        ));
+    }
+    Some(attributes)
+}
+
+/// 状态 200 的 HTTP 应答正文；分块传输的拼回原样。
+pub(crate) fn http_body(response: &[u8]) -> Option<Vec<u8>> {
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&response[..split]).to_ascii_lowercase();
+    if !head.lines().next()?.contains(" 200") {
+        return None;
+    }
+    let body = &response[split + 4..];
+    if !head.contains("transfer-encoding: chunked") {
+        return Some(body.to_vec());
+    }
+    let mut decoded = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest.windows(2).position(|window| window == b"\r\n")?;
+        let size = std::str::from_utf8(&rest[..line_end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            break;
+        }
+        // 读取上限截断的最后一块也照收
+        let chunk = &rest[line_end + 2..];
+        decoded.extend_from_slice(&chunk[..size.min(chunk.len())]);
+        rest = chunk.get(size + 2..).unwrap_or_default();
+        if rest.is_empty() {
+            break;
+        }
+    }
+    Some(decoded)
+}
+
+/// RTSP 应答的 `Server` 头；没有该头时为空，不是 RTSP 应答则为 `None`。
+pub(crate) fn parse_rtsp_server(response: &str) -> Option<String> {
+    let mut lines = response.lines();
+    if !lines.next()?.starts_with("RTSP/") {
+        return None;
+    }
+    Some(
+        lines
+            .take_while(|line| !line.is_empty())
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim()
+                    .eq_ignore_ascii_case("server")
+                    .then(|| value.trim().to_string())
+            })
+            .unwrap_or_default(),
+    )
+}
+
+/// MQTT 3.1.1 CONNECT：匿名、清除会话、保活 10 秒。
+pub(crate) fn mqtt_connect() -> Vec<u8> {
+    let client_id = b"iptools-probe";
+    let mut variable = vec![0x00, 0x04];
+    variable.extend(b"MQTT");
+    variable.extend([0x04, 0x02, 0x00, 0x0A]);
+    variable.extend((client_id.len() as u16).to_be_bytes());
+    variable.extend(client_id);
+    let mut packet = vec![MQTT_CONNECT, variable.len() as u8];
+    packet.extend(variable);
+    packet
+}
+
+/// CONNACK 的返回码。
+pub(crate) fn parse_connack(response: &[u8]) -> Option<u8> {
+    match response {
+        [MQTT_CONNACK, 0x02, _, code, ..] => Some(*code),
+        _ => None,
+    }
+}
+
+/// 读设备标识应答中的厂商、产品代码与版本；设备以异常码拒绝时仍算 Modbus，
+/// 说明为空。
+pub(crate) fn parse_modbus_identification(response: &[u8]) -> Option<String> {
+    // 事务号 1、协议号 0
+    if response.len() < 9 || response[..4] != [0x00, 0x01, 0x00, 0x00] {
+        return None;
+    }
+    match response[7] {
+        function if function == MODBUS_ENCAPSULATED | 0x80 => return Some(String::new()),
+        MODBUS_ENCAPSULATED if response[8] == MODBUS_READ_DEVICE_ID => {}
+        _ => return None,
+    }
+    let count = *response.get(13)?;
+    let mut rest = response.get(14..)?;
+    let mut values = Vec::new();
+    for _ in 0..count {
+        let [id, length, tail @ ..] = rest else { break };
+        let Some(value) = tail.get(..usize::from(*length)) else {
+            break;
+        };
+        if *id <= 2 {
+            let value = String::from_utf8_lossy(value).trim().to_string();
+            if !value.is_empty() {
+                values.push(value);
+            }
+        }
+        rest = &tail[usize::from(*length)..];
+    }
+    Some(values.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipp_round_trips_the_printer_name_and_model() {
+        let request = ipp_request("ipp://192.168.1.31:631/ipp/print");
+        assert_eq!(&request[..4], &[0x01, 0x01, 0x00, 0x0B]);
+        let attributes = parse_ipp_attributes(&request).unwrap();
+        assert_eq!(
+            attributes[3..],
+            [
+                ("requested-attributes".into(), "printer-name".into()),
+                (
+                    "requested-attributes".into(),
+                    "printer-make-and-model".into()
+                ),
+            ]
+        );
+
+        // 状态 successful-ok，打印机属性组里两项
+        let mut body = vec![0x02, 0x00, 0x00, 0x00, 0, 0, 0, 1, 0x04];
+        for (name, value) in [
+            ("printer-name", "HP-Office"),
+            ("printer-make-and-model", "HP Color LaserJet MFP M283fdw"),
+        ] {
+            body.push(0x42);
+            body.extend((name.len() as u16).to_be_bytes());
+            body.extend(name.as_bytes());
+            body.extend((value.len() as u16).to_be_bytes());
+            body.extend(value.as_bytes());
+        }
+        body.push(IPP_END_OF_ATTRIBUTES);
+        let mut response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+        for chunk in body.chunks(20) {
+            response.extend(format!("{:x}\r\n", chunk.len()).bytes());
+            response.extend(chunk);
+            response.extend(b"\r\n");
+        }
+        response.extend(b"0\r\n\r\n");
+        let attributes = parse_ipp_attributes(&http_body(&response).unwrap()).unwrap();
+        assert_eq!(attributes[1].1, "HP Color LaserJet MFP M283fdw");
+        assert_eq!(http_body(b"HTTP/1.1 404 Not Found\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn rtsp_mqtt_and_modbus_answers_name_the_device() {
+        assert_eq!(
+            parse_rtsp_server(
+                "RTSP/1.0 200 OK\r\nCSeq: 1\r\nServer: Hipcam RealServer/V1.0\r\n\r\n"
+            ),
+            Some("Hipcam RealServer/V1.0".into())
+        );
+        assert_eq!(parse_rtsp_server("HTTP/1.1 400 Bad Request\r\n"), None);
+
+        let connect = mqtt_connect();
+        assert_eq!(connect[1] as usize, connect.len() - 2);
+        assert_eq!(parse_connack(&[0x20, 0x02, 0x00, 0x05]), Some(5));
+        assert_eq!(parse_connack(b"HTTP"), None);
+
+        let mut response = vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x2B, 0x0E];
+        response.extend([0x01, 0x01, 0x00, 0x00, 0x03]);
+        for (id, value) in [(0u8, "Demo Energy"), (1, "SUN-5K"), (2, "")] {
+            response.extend([id, value.len() as u8]);
+            response.extend(value.as_bytes());
+        }
+        assert_eq!(
+            parse_modbus_identification(&response),
+            Some("Demo Energy SUN-5K".into())
+        );
+        let exception = [0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0xFF, 0xAB, 0x01];
+        assert_eq!(parse_modbus_identification(&exception), Some(String::new()));
+    }
+}
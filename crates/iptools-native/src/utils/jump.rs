@@ -0,0 +1,396 @@
+//! SSH 跳板：端口扫描与网页检查经跳板机连接目标，看到的是远端网络里的样子。
+//!
+//! 用 russh 在进程内登录跳板机，每条连接是会话里的一个 `direct-tcpip` 通道
+//! （与 `ssh -W` 相同），目标名交给跳板机一侧解析，内网域名也能用；不需要系统
+//! 装有 ssh。沿用 `~/.ssh/config` 中该主机的 HostName、Port、User、IdentityFile
+//! 与 UserKnownHostsFile（不支持 ProxyJump、ProxyCommand），先试 ssh-agent
+//! 里的密钥，再试未加密的私钥文件。界面占着终端，无法输入密码、口令或确认主机
+//! 指纹，因此与 BatchMode 一样：需要密钥登录，且跳板机已在 known_hosts 中。
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use russh::client::{self, Handle};
+use russh::keys::agent::{AgentIdentity, client::AgentClient};
+use russh::keys::{self, Algorithm, HashAlg, PrivateKeyWithHashAlg, PublicKeyOrCertificate};
+use russh::{ChannelOpenFailure, ChannelStream, Disconnect};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// 登录跳板机的最长等待
+const OPEN_TIMEOUT: Duration = Duration::from_secs(15);
+/// 没有 IdentityFile 时依次尝试的私钥，与 ssh 的默认一致
+const DEFAULT_IDENTITIES: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// 经跳板机时同时打开的连接上限：每条都是一个 SSH 通道，太多会被服务器拒绝
+pub const MAX_CONCURRENCY: usize = 32;
+
+/// 经跳板机打开的一条连接
+pub type JumpStream = ChannelStream<client::Msg>;
+
+/// 已登录的跳板机会话；析构时断开。
+pub struct JumpTunnel {
+    session: Handle<KnownHosts>,
+}
+
+impl JumpTunnel {
+    /// 登录 `jump_host`（`[user@]host[:port]`）；失败时返回可以直接显示的原因。
+    pub async fn open(jump_host: &str) -> Result<Self, String> {
+        let login = Login::resolve(jump_host)?;
+        tokio::time::timeout(OPEN_TIMEOUT, login.open())
+            .await
+            .unwrap_or_else(|_| Err(format!("ssh to {jump_host} timed out")))
+    }
+
+    /// 经跳板机连接 `host:port`；跳板机连上目标后才返回，返回的流可以直接读写。
+    pub async fn connect(&self, host: &str, port: u16) -> io::Result<JumpStream> {
+        let bare = host.trim_start_matches('[').trim_end_matches(']');
+        self.session
+            .channel_open_direct_tcpip(bare, u32::from(port), "127.0.0.1", 0)
+            .await
+            .map(|channel| channel.into_stream())
+            .map_err(channel_error)
+    }
+}
+
+impl Drop for JumpTunnel {
+    fn drop(&mut self) {
+        // 只丢下句柄时会话任务不会退出，跳板机上的连接也一直占着。断开只是把
+        // 一条消息放进会话队列，立即就绪，不必等
+        let disconnect = self.session.disconnect(Disconnect::ByApplication, "", "");
+        let _ = futures::FutureExt::now_or_never(disconnect);
+    }
+}
+
+/// 跳板机拒绝或连不上目标时的错误，种类与直连时一致，扫描据此区分关闭与过滤。
+fn channel_error(error: russh::Error) -> io::Error {
+    let (kind, message) = match error {
+        russh::Error::ChannelOpenFailure(ChannelOpenFailure::ConnectFailed) => (
+            io::ErrorKind::ConnectionRefused,
+            "connection failed from the jump host",
+        ),
+        russh::Error::ChannelOpenFailure(ChannelOpenFailure::AdministrativelyProhibited) => (
+            io::ErrorKind::PermissionDenied,
+            "not allowed by the jump host",
+        ),
+        russh::Error::ChannelOpenFailure(ChannelOpenFailure::ResourceShortage) => (
+            io::ErrorKind::Other,
+            "too many connections through the jump host",
+        ),
+        error => return io::Error::other(error),
+    };
+    io::Error::new(kind, message)
+}
+
+/// 合并 `~/.ssh/config` 之后的登录参数。
+struct Login {
+    host: String,
+    port: u16,
+    user: String,
+    identities: Vec<PathBuf>,
+    known_hosts: PathBuf,
+}
+
+impl Login {
+    fn resolve(jump_host: &str) -> Result<Self, String> {
+        let (destination, port) = iptools_core::ssh_destination(jump_host)
+            .ok_or_else(|| format!("invalid jump host: {jump_host}"))?;
+        let (user, alias) = match destination.rsplit_once('@') {
+            Some((user, alias)) => (Some(user), alias),
+            None => (None, destination.as_str()),
+        };
+        let ssh_home = std::env::home_dir()
+            .map(|home| home.join(".ssh"))
+            .ok_or("no home directory for ~/.ssh")?;
+        // 没有配置文件或其中没有这台主机时全用默认值
+        let mut config = russh_config::parse_path(ssh_home.join("config"), alias)
+            .unwrap_or_else(|_| russh_config::Config::default(alias));
+        // 命令行式的写法优先于配置文件，与 ssh 一致
+        config.user = user.map(str::to_string);
+        let host_config = &config.host_config;
+        Ok(Self {
+            host: config.host().to_string(),
+            port: port.or(host_config.port).unwrap_or(22),
+            user: config.user(),
+            identities: host_config.identity_file.clone().unwrap_or_else(|| {
+                DEFAULT_IDENTITIES
+                    .iter()
+                    .map(|name| ssh_home.join(name))
+                    .collect()
+            }),
+            known_hosts: host_config
+                .user_known_hosts_file
+                .clone()
+                .unwrap_or_else(|| ssh_home.join("known_hosts")),
+        })
+    }
+
+    async fn open(self) -> Result<JumpTunnel, String> {
+        let mut config = client::Config {
+            keepalive_interval: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        // 先协商 known_hosts 里记录过的密钥类型，否则服务器可能出示另一种没记过的
+        let recorded =
+            keys::known_hosts::known_host_keys_path(&self.host, self.port, &self.known_hosts)
+                .unwrap_or_default();
+        let mut algorithms = config.preferred.key.to_vec();
+        algorithms.sort_by_key(|algorithm| {
+            !recorded.iter().any(|(_, key)| {
+                let known = key.algorithm();
+                let rsa = |algorithm: &Algorithm| matches!(algorithm, Algorithm::Rsa { .. });
+                known == *algorithm || rsa(&known) && rsa(algorithm)
+            })
+        });
+        config.preferred.key = algorithms.into();
+
+        let checker = KnownHosts {
+            host: self.host.clone(),
+            port: self.port,
+            path: self.known_hosts.clone(),
+        };
+        let session = client::connect(Arc::new(config), (self.host.as_str(), self.port), checker)
+            .await
+            .map_err(|error| match error {
+                russh::Error::UnknownKey => format!(
+                    "host key of {} is not in {}; connect once with ssh to accept it",
+                    self.host,
+                    self.known_hosts.display()
+                ),
+                russh::Error::Keys(keys::Error::KeyChanged { line }) => format!(
+                    "host key of {} does not match {} line {line}",
+                    self.host,
+                    self.known_hosts.display()
+                ),
+                error => format!("cannot connect to {}: {error}", self.host),
+            })?;
+
+        // 先包起来，登录失败或超时时也由析构断开
+        let mut tunnel = JumpTunnel { session };
+        if self.authenticate(&mut tunnel.session).await? {
+            Ok(tunnel)
+        } else {
+            Err(format!(
+                "{}@{}: permission denied (publickey)",
+                self.user, self.host
+            ))
+        }
+    }
+
+    /// 先试 ssh-agent，再试私钥文件；加密的私钥要口令，只能放进 agent 里用。
+    async fn authenticate(&self, session: &mut Handle<KnownHosts>) -> Result<bool, String> {
+        let hash = session
+            .best_supported_rsa_hash()
+            .await
+            .map_err(|error| error.to_string())?
+            .flatten();
+        if self.agent_login(session, hash).await {
+            return Ok(true);
+        }
+        for path in &self.identities {
+            let Ok(key) = keys::load_secret_key(path, None) else {
+                continue;
+            };
+            let key = PrivateKeyWithHashAlg::new(Arc::new(key), hash);
+            let result = session
+                .authenticate_publickey(&self.user, key)
+                .await
+                .map_err(|error| error.to_string())?;
+            if result.success() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    #[cfg(unix)]
+    async fn agent_login(&self, session: &mut Handle<KnownHosts>, hash: Option<HashAlg>) -> bool {
+        match AgentClient::connect_env().await {
+            Ok(agent) => self.with_agent(session, hash, agent).await,
+            Err(_) => false,
+        }
+    }
+
+    /// Windows 自带的 OpenSSH 代理走命名管道，PuTTY 的 Pageant 另有接口，两个都试。
+    #[cfg(windows)]
+    async fn agent_login(&self, session: &mut Handle<KnownHosts>, hash: Option<HashAlg>) -> bool {
+        if let Ok(agent) = AgentClient::connect_named_pipe(r"\\.\pipe\openssh-ssh-agent").await
+            && self.with_agent(session, hash, agent).await
+        {
+            return true;
+        }
+        match AgentClient::connect_pageant().await {
+            Ok(agent) => self.with_agent(session, hash, agent).await,
+            Err(_) => false,
+        }
+    }
+
+    async fn with_agent<S>(
+        &self,
+        session: &mut Handle<KnownHosts>,
+        hash: Option<HashAlg>,
+        mut agent: AgentClient<S>,
+    ) -> bool
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send,
+    {
+        let Ok(identities) = agent.request_identities().await else {
+            return false;
+        };
+        for identity in identities {
+            let AgentIdentity::PublicKey { key, .. } = identity else {
+                continue;
+            };
+            let hash = if key.algorithm().is_rsa() { hash } else { None };
+            let result = session
+                .authenticate_publickey_with(&self.user, key, hash, &mut agent)
+                .await;
+            if result.is_ok_and(|result| result.success()) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// 按 known_hosts 核对跳板机的主机密钥：没有记录或与记录不符都拒绝连接。
+struct KnownHosts {
+    host: String,
+    port: u16,
+    path: PathBuf,
+}
+
+impl client::Handler for KnownHosts {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        key: &PublicKeyOrCertificate,
+    ) -> Result<bool, Self::Error> {
+        match key {
+            PublicKeyOrCertificate::PublicKey { key, .. } => Ok(keys::check_known_hosts_path(
+                &self.host, self.port, key, &self.path,
+            )?),
+            // 没有请求主机证书，服务器不会出示
+            PublicKeyOrCertificate::Certificate(_) => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use russh::Channel;
+    use russh::keys::PrivateKey;
+    use russh::keys::ssh_key::{LineEnding, private::Ed25519Keypair};
+    use russh::server::{self, Auth, ChannelOpenHandle, Session};
+    use tokio::io::AsyncReadExt;
+
+    /// 只放行测试密钥的跳板机，内网里只有 `inside.corp:22`。
+    struct Bastion {
+        client: keys::PublicKey,
+    }
+
+    impl server::Handler for Bastion {
+        type Error = russh::Error;
+
+        async fn auth_publickey(
+            &mut self,
+            _: &str,
+            key: &keys::PublicKey,
+        ) -> Result<Auth, Self::Error> {
+            Ok(if *key == self.client {
+                Auth::Accept
+            } else {
+                Auth::reject()
+            })
+        }
+
+        async fn channel_open_direct_tcpip(
+            &mut self,
+            channel: Channel<server::Msg>,
+            host: &str,
+            port: u32,
+            _: &str,
+            _: u32,
+            reply: ChannelOpenHandle,
+            _: &mut Session,
+        ) -> Result<(), Self::Error> {
+            if (host, port) != ("inside.corp", 22) {
+                reply.reject(ChannelOpenFailure::ConnectFailed).await;
+                return Ok(());
+            }
+            reply.accept().await;
+            channel.data(&b"SSH-2.0-inside\r\n"[..]).await?;
+            channel.close().await
+        }
+    }
+
+    fn key(seed: u8) -> PrivateKey {
+        PrivateKey::from(Ed25519Keypair::from_seed(&[seed; 32]))
+    }
+
+    #[tokio::test]
+    async fn targets_are_reached_through_channels_and_unknown_hosts_are_refused() {
+        let (host_key, client_key) = (key(1), key(2));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let config = Arc::new(server::Config {
+            keys: vec![host_key.clone()],
+            ..Default::default()
+        });
+        let client = client_key.public_key().clone();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                let bastion = Bastion {
+                    client: client.clone(),
+                };
+                let config = Arc::clone(&config);
+                tokio::spawn(async move {
+                    if let Ok(session) = server::run_stream(config, socket, bastion).await {
+                        let _ = session.await;
+                    }
+                });
+            }
+        });
+
+        let dir = std::env::temp_dir().join(format!("iptools-jump-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let identity = dir.join("id_ed25519");
+        std::fs::write(&identity, client_key.to_openssh(LineEnding::LF).unwrap()).unwrap();
+        let login = || Login {
+            host: "127.0.0.1".into(),
+            port,
+            user: "scanner".into(),
+            identities: vec![identity.clone()],
+            known_hosts: dir.join("known_hosts"),
+        };
+
+        let error = login().open().await.err().unwrap();
+        assert!(
+            error.starts_with("host key of 127.0.0.1 is not in"),
+            "{error}"
+        );
+
+        let recorded = host_key.public_key().to_openssh().unwrap();
+        std::fs::write(
+            dir.join("known_hosts"),
+            format!("[127.0.0.1]:{port} {recorded}\n"),
+        )
+        .unwrap();
+        let tunnel = login().open().await.unwrap();
+        let mut banner = String::new();
+        let mut stream = tunnel.connect("inside.corp", 22).await.unwrap();
+        stream.read_to_string(&mut banner).await.unwrap();
+        assert_eq!(banner, "SSH-2.0-inside\r\n");
+        let error = tunnel.connect("inside.corp", 23).await.err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionRefused);
+
+        std::fs::write(&identity, key(3).to_openssh(LineEnding::LF).unwrap()).unwrap();
+        let error = login().open().await.err().unwrap();
+        assert_eq!(error, "scanner@127.0.0.1: permission denied (publickey)");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,296 @@
+//! 网卡的协商结果：双工、介质和硬件支持的最高速率，用来发现「千兆网卡只协商到百兆」
+//! 这类局域网变慢最常见的原因。
+//!
+//! Linux 经 ethtool ioctl（`ETHTOOL_GSET`）读取，同时得到对端通告的速率，能区分
+//! 是网线 / 端口问题还是交换机本身只有百兆；Windows 在 `\\.\{GUID}` 设备上以
+//! `IOCTL_NDIS_QUERY_GLOBAL_STATS` 查询 NDIS OID。其他平台返回空值。
+
+use iptools_core::{Duplex, LinkMedia, LinkMedium};
+
+/// `guid` 同 [`super::net::InterfaceInfo::guid`]；无线网卡只标注介质，不查询。
+pub fn media(guid: &str, wireless: bool) -> LinkMedia {
+    if wireless {
+        return LinkMedia {
+            medium: Some(LinkMedium::Wireless),
+            ..LinkMedia::default()
+        };
+    }
+    query(guid).unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn query(name: &str) -> Option<LinkMedia> {
+    // 防路径穿越；接口名最长 15 字节
+    if name.is_empty() || name.contains('/') || name.len() >= libc::IFNAMSIZ {
+        return None;
+    }
+    match ethtool::get_settings(name) {
+        Some(settings) => Some(ethtool::media(&settings)),
+        // 虚拟网卡与部分 USB 网卡不支持 ethtool，至少还有 sysfs 的双工
+        None => {
+            let duplex = std::fs::read_to_string(format!("/sys/class/net/{name}/duplex")).ok()?;
+            Some(LinkMedia {
+                duplex: match duplex.trim() {
+                    "full" => Some(Duplex::Full),
+                    "half" => Some(Duplex::Half),
+                    _ => None,
+                },
+                ..LinkMedia::default()
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn query(guid: &str) -> Option<LinkMedia> {
+    use windows::Win32::NetworkManagement::Ndis::{
+        MediaDuplexStateFull, MediaDuplexStateHalf, NDIS_LINK_SPEED, NdisPhysicalMedium802_3,
+        NdisPhysicalMediumNative802_11, NdisPhysicalMediumUnspecified,
+        NdisPhysicalMediumWirelessLan, OID_GEN_MAX_LINK_SPEED, OID_GEN_MEDIA_DUPLEX_STATE,
+        OID_GEN_PHYSICAL_MEDIUM,
+    };
+
+    if !guid.starts_with('{') || guid.contains(['\\', '/']) {
+        return None;
+    }
+    let device = ndis::Device::open(guid)?;
+    let duplex = device.query::<i32>(OID_GEN_MEDIA_DUPLEX_STATE);
+    let medium = device.query::<i32>(OID_GEN_PHYSICAL_MEDIUM);
+    let max = device.query::<NDIS_LINK_SPEED>(OID_GEN_MAX_LINK_SPEED);
+    Some(LinkMedia {
+        duplex: duplex.and_then(|state| match state {
+            state if state == MediaDuplexStateFull.0 => Some(Duplex::Full),
+            state if state == MediaDuplexStateHalf.0 => Some(Duplex::Half),
+            _ => None,
+        }),
+        // 早于 NDIS 6 的以太网驱动不回答这个 OID，按以太网处理
+        medium: Some(match medium {
+            Some(kind)
+                if kind == NdisPhysicalMediumWirelessLan.0
+                    || kind == NdisPhysicalMediumNative802_11.0 =>
+            {
+                LinkMedium::Wireless
+            }
+            Some(kind)
+                if kind != NdisPhysicalMedium802_3.0 && kind != NdisPhysicalMediumUnspecified.0 =>
+            {
+                LinkMedium::Other
+            }
+            _ => LinkMedium::Ethernet,
+        }),
+        max_speed_bps: max
+            .map(|speed| speed.XmitLinkSpeed.max(speed.RcvLinkSpeed))
+            .filter(|speed| *speed != 0 && *speed != u64::MAX),
+        partner_max_speed_bps: None,
+    })
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn query(_guid: &str) -> Option<LinkMedia> {
+    None
+}
+
+/// Linux ethtool 旧版接口。`ETHTOOL_GSET` 的能力位只到 56G，且没有 2.5G / 5GBASE-T，
+/// 但用来判断「是否千兆硬件」已经足够。
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+mod ethtool {
+    use iptools_core::{Duplex, LinkMedia, LinkMedium};
+
+    /// `struct ethtool_cmd`（`linux/ethtool.h`）
+    #[repr(C)]
+    #[derive(Debug, Default)]
+    pub(super) struct Settings {
+        pub cmd: u32,
+        pub supported: u32,
+        pub advertising: u32,
+        pub speed: u16,
+        pub duplex: u8,
+        pub port: u8,
+        pub phy_address: u8,
+        pub transceiver: u8,
+        pub autoneg: u8,
+        pub mdio_support: u8,
+        pub maxtxpkt: u32,
+        pub maxrxpkt: u32,
+        pub speed_hi: u16,
+        pub eth_tp_mdix: u8,
+        pub eth_tp_mdix_ctrl: u8,
+        pub lp_advertising: u32,
+        pub reserved: [u32; 2],
+    }
+
+    const ETHTOOL_GSET: u32 = 0x0000_0001;
+
+    /// 能力位 → 速率（bit/s）；`ETHTOOL_LINK_MODE_*_BIT`，跳过自协商、端口与流控等非速率位。
+    const SPEED_BITS: [(u32, u64); 22] = [
+        (0, 10_000_000),
+        (1, 10_000_000),
+        (2, 100_000_000),
+        (3, 100_000_000),
+        (4, 1_000_000_000),
+        (5, 1_000_000_000),
+        (12, 10_000_000_000),
+        (15, 2_500_000_000),
+        (17, 1_000_000_000),
+        (18, 10_000_000_000),
+        (19, 10_000_000_000),
+        (21, 20_000_000_000),
+        (22, 20_000_000_000),
+        (23, 40_000_000_000),
+        (24, 40_000_000_000),
+        (25, 40_000_000_000),
+        (26, 40_000_000_000),
+        (27, 56_000_000_000),
+        (28, 56_000_000_000),
+        (29, 56_000_000_000),
+        (30, 56_000_000_000),
+        (31, 25_000_000_000),
+    ];
+
+    #[cfg(target_os = "linux")]
+    pub(super) fn get_settings(name: &str) -> Option<Settings> {
+        use std::os::fd::AsRawFd;
+
+        let socket =
+            socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::DGRAM, None).ok()?;
+        let mut settings = Settings {
+            cmd: ETHTOOL_GSET,
+            ..Settings::default()
+        };
+        // SAFETY: ifreq 全零是合法值；名称已确认短于 IFNAMSIZ，末尾保留 NUL。
+        // ifru_data 指向的 settings 在 ioctl 返回前一直有效，大小与内核的 ethtool_cmd 一致。
+        let result = unsafe {
+            let mut request: libc::ifreq = std::mem::zeroed();
+            for (slot, byte) in request.ifr_name.iter_mut().zip(name.bytes()) {
+                *slot = byte as libc::c_char;
+            }
+            request.ifr_ifru.ifru_data = (&raw mut settings).cast();
+            libc::ioctl(socket.as_raw_fd(), libc::SIOCETHTOOL as _, &mut request)
+        };
+        (result == 0).then_some(settings)
+    }
+
+    fn fastest(mask: u32) -> Option<u64> {
+        SPEED_BITS
+            .iter()
+            .filter(|(bit, _)| mask & (1 << bit) != 0)
+            .map(|(_, speed)| *speed)
+            .max()
+    }
+
+    pub(super) fn media(settings: &Settings) -> LinkMedia {
+        LinkMedia {
+            duplex: match settings.duplex {
+                0 => Some(Duplex::Half),
+                1 => Some(Duplex::Full),
+                _ => None,
+            },
+            medium: match settings.port {
+                0 => Some(LinkMedium::TwistedPair),
+                4 => Some(LinkMedium::Fibre),
+                5 => Some(LinkMedium::DirectAttach),
+                // PORT_NONE：虚拟设备或驱动不报告
+                0xef => None,
+                _ => Some(LinkMedium::Other),
+            },
+            max_speed_bps: fastest(settings.supported),
+            partner_max_speed_bps: fastest(settings.lp_advertising),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod ndis {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+    use windows::Win32::System::IO::DeviceIoControl;
+    use windows::core::HSTRING;
+
+    /// `CTL_CODE(FILE_DEVICE_PHYSICAL_NETCARD, 0, METHOD_OUT_DIRECT, FILE_ANY_ACCESS)`
+    const IOCTL_NDIS_QUERY_GLOBAL_STATS: u32 = 0x0017_0002;
+
+    /// NDIS 为每块网卡建立的 `\\.\{GUID}` 设备；查询 OID 不需要管理员权限。
+    pub(super) struct Device(HANDLE);
+
+    impl Device {
+        pub(super) fn open(guid: &str) -> Option<Self> {
+            // SAFETY: 路径是以 NUL 结尾的宽字符串；只读打开，失败时不产生句柄。
+            unsafe {
+                CreateFileW(
+                    &HSTRING::from(format!(r"\\.\{guid}")),
+                    0,
+                    FILE_SHARE_READ | FILE_SHARE_WRITE,
+                    None,
+                    OPEN_EXISTING,
+                    FILE_FLAGS_AND_ATTRIBUTES(0),
+                    None,
+                )
+            }
+            .ok()
+            .map(Self)
+        }
+
+        /// 一个定长 OID 的值；驱动不支持或返回长度不符时为 `None`。
+        pub(super) fn query<T: Copy>(&self, oid: u32) -> Option<T> {
+            let mut value = std::mem::MaybeUninit::<T>::zeroed();
+            let mut returned = 0_u32;
+            // SAFETY: 输入是 4 字节 OID，输出缓冲区正好是 T 的大小；返回长度不符时不读取。
+            unsafe {
+                DeviceIoControl(
+                    self.0,
+                    IOCTL_NDIS_QUERY_GLOBAL_STATS,
+                    Some((&raw const oid).cast()),
+                    size_of::<u32>() as u32,
+                    Some(value.as_mut_ptr().cast()),
+                    size_of::<T>() as u32,
+                    Some(&mut returned),
+                    None,
+                )
+                .ok()?;
+                (returned as usize == size_of::<T>()).then(|| value.assume_init())
+            }
+        }
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            // SAFETY: 句柄由 CreateFileW 打开，只在这里关闭一次。
+            let _ = unsafe { CloseHandle(self.0) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ethtool_settings_give_duplex_port_and_both_ends_top_speed() {
+        // 千兆网卡（10/100/1000 全双工 + 自协商 + TP）协商到百兆，对端只通告 100M
+        let settings = ethtool::Settings {
+            supported: 0b110_1111,
+            speed: 100,
+            duplex: 1,
+            port: 0,
+            lp_advertising: 0b1100,
+            ..ethtool::Settings::default()
+        };
+        assert_eq!(
+            ethtool::media(&settings),
+            LinkMedia {
+                duplex: Some(Duplex::Full),
+                medium: Some(LinkMedium::TwistedPair),
+                max_speed_bps: Some(1_000_000_000),
+                partner_max_speed_bps: Some(100_000_000),
+            }
+        );
+        assert_eq!(std::mem::size_of::<ethtool::Settings>(), 44);
+        assert_eq!(
+            media("wlan0", true).medium,
+            Some(LinkMedium::Wireless),
+            "wireless links are not queried"
+        );
+    }
+}
@@ -1,6 +1,21 @@
+pub mod android;
+pub mod banner;
+pub mod counters;
+pub mod dhcp;
+pub mod discovery;
+pub mod dns_cache;
+pub mod hotplug;
+pub mod interface_store;
+pub mod iot;
 pub mod ipconfig;
+pub mod jump;
+pub mod link;
 pub mod net;
 pub mod oui;
+pub mod power;
 pub mod pubip;
 pub mod services;
+pub mod shares;
+pub mod web;
 pub mod wlan;
+pub mod zone;
@@ -3,6 +3,50 @@ use std::{
     sync::atomic::{AtomicU16, Ordering},
 };
 
+use iptools_core::{HostName, NameSource, ProbeOutcome};
+
+use super::{dns_cache, interface_store};
+
+// -----------------------------------------------------------------------------
+// 异步接口：供运行时任务直接 await。下面的同步函数会阻塞（系统调用、子进程、
+// 收包等待），这里统一放进阻塞线程池并限时，调用方不必各自套 `spawn_blocking`。
+// 扫描器的工作线程本身就在阻塞池里，继续直接用同步版本。
+// -----------------------------------------------------------------------------
+
+/// 单次阻塞查询的时限；超时按失败处理，阻塞线程在后台自行结束。
+const BLOCKING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+async fn off_runtime<T: Send + 'static>(
+    query: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, String> {
+    match tokio::time::timeout(BLOCKING_TIMEOUT, tokio::task::spawn_blocking(query)).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(error.to_string()),
+        Err(_) => Err("timed out".into()),
+    }
+}
+
+/// 当前网卡列表，取自全应用共用的 [`interface_store`]。
+pub async fn interfaces() -> Result<Vec<InterfaceInfo>, String> {
+    off_runtime(interface_store::interfaces).await
+}
+
+/// [`default_gateway`] 的异步版本。
+pub async fn gateway(interface: InterfaceInfo) -> Option<Ipv4Addr> {
+    off_runtime(move || default_gateway(&interface))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// [`link_speed_for_guid`] 的异步版本。
+pub async fn link_speed(guid: String) -> Option<u64> {
+    off_runtime(move || link_speed_for_guid(&guid))
+        .await
+        .ok()
+        .flatten()
+}
+
 #[derive(Debug, Clone)]
 pub struct InterfaceInfo {
     pub name: String,
@@ -21,6 +65,8 @@ pub struct InterfaceInfo {
     pub guid: String,
     /// 协商链路速率（bit/s）；取自 ipconfig 的 TransmitLinkSpeed。非 Windows 为 None。
     pub link_speed_bps: Option<u64>,
+    /// 双工、介质与硬件最高速率，见 [`super::link`]；扫描热路径不查询，保持默认。
+    pub media: iptools_core::LinkMedia,
 }
 
 #[cfg(target_os = "windows")]
@@ -29,101 +75,110 @@ pub fn get_interfaces() -> Vec<InterfaceInfo> {
     let ssid_map = get_ssid_map_via_win32();
     let dhcp_map = get_dhcp_map_via_win32();
 
-    if let Ok(adapters) = ipconfig::get_adapters() {
-        for adapter in adapters {
-            if adapter.if_type() == ipconfig::IfType::SoftwareLoopback
-                || adapter.if_type() == ipconfig::IfType::Tunnel
-            {
-                continue;
-            }
+    let Ok(adapters) = ipconfig::get_adapters() else {
+        return portable_interfaces();
+    };
+    for adapter in adapters {
+        if adapter.if_type() == ipconfig::IfType::SoftwareLoopback
+            || adapter.if_type() == ipconfig::IfType::Tunnel
+        {
+            continue;
+        }
 
-            let is_physical = matches!(
-                adapter.if_type(),
-                ipconfig::IfType::EthernetCsmacd | ipconfig::IfType::Ieee80211
-            );
+        let is_physical = matches!(
+            adapter.if_type(),
+            ipconfig::IfType::EthernetCsmacd | ipconfig::IfType::Ieee80211
+        );
 
-            let mac = adapter
-                .physical_address()
-                .map(|addr| {
-                    addr.iter()
-                        .map(|b| format!("{:02x}", b))
-                        .collect::<Vec<String>>()
-                        .join(":")
-                })
-                .unwrap_or_default();
-
-            let mut ipv4 = Vec::new();
-            let mut ipv6 = Vec::new();
-            let mut cidr = None;
-
-            for ip in adapter.ip_addresses().iter() {
-                match ip {
-                    IpAddr::V4(v4) => {
-                        ipv4.push(v4.to_string());
-                        if cidr.is_none() {
-                            // 找出该 IPv4 所属的“真实子网”前缀，而非 /32 主机路由。
-                            // prefixes 同时包含子网(如 192.168.1.0/24)、主机路由
-                            // (192.168.1.x/32) 与广播(192.168.1.255/32)；只匹配
-                            // prefix_ip==ip 会错取 /32，导致掩码被算成 255.255.255.255，
-                            // 进而使 EnableStatic 返回错误码 66。取网络地址匹配且
-                            // 0<len<32 中最长（最具体）的一个。
-                            let ip_bits = u32::from(*v4);
-                            let mut best: Option<u32> = None;
-                            for (prefix_ip, len) in adapter.prefixes() {
-                                if let IpAddr::V4(net) = prefix_ip {
-                                    if *len == 0 || *len >= 32 {
-                                        continue;
-                                    }
-                                    let mask = u32::MAX << (32 - *len);
-                                    if ip_bits & mask == u32::from(*net) {
-                                        best = Some(best.map_or(*len, |b| b.max(*len)));
-                                    }
+        let mac = adapter
+            .physical_address()
+            .map(|addr| {
+                addr.iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<Vec<String>>()
+                    .join(":")
+            })
+            .unwrap_or_default();
+
+        let mut ipv4 = Vec::new();
+        let mut ipv6 = Vec::new();
+        let mut cidr = None;
+
+        for ip in adapter.ip_addresses().iter() {
+            match ip {
+                IpAddr::V4(v4) => {
+                    ipv4.push(v4.to_string());
+                    if cidr.is_none() {
+                        // 找出该 IPv4 所属的“真实子网”前缀，而非 /32 主机路由。
+                        // prefixes 同时包含子网(如 192.168.1.0/24)、主机路由
+                        // (192.168.1.x/32) 与广播(192.168.1.255/32)；只匹配
+                        // prefix_ip==ip 会错取 /32，导致掩码被算成 255.255.255.255，
+                        // 进而使 EnableStatic 返回错误码 66。取网络地址匹配且
+                        // 0<len<32 中最长（最具体）的一个。
+                        let ip_bits = u32::from(*v4);
+                        let mut best: Option<u32> = None;
+                        for (prefix_ip, len) in adapter.prefixes() {
+                            if let IpAddr::V4(net) = prefix_ip {
+                                if *len == 0 || *len >= 32 {
+                                    continue;
+                                }
+                                let mask = u32::MAX << (32 - *len);
+                                if ip_bits & mask == u32::from(*net) {
+                                    best = Some(best.map_or(*len, |b| b.max(*len)));
                                 }
-                            }
-                            if let Some(len) = best {
-                                cidr = Some(format!("{}/{}", v4, len));
                             }
                         }
+                        if let Some(len) = best {
+                            cidr = Some(format!("{}/{}", v4, len));
+                        }
                     }
-                    IpAddr::V6(v6) => ipv6.push(v6.to_string()),
                 }
+                IpAddr::V6(v6) => ipv6.push(v6.to_string()),
             }
+        }
 
-            let is_up = matches!(adapter.oper_status(), ipconfig::OperStatus::IfOperStatusUp);
-
-            let mut ssid = None;
-            if let (true, Some(s)) = (is_up, ssid_map.get(adapter.adapter_name())) {
-                ssid = Some(s.clone());
-            }
+        let is_up = matches!(adapter.oper_status(), ipconfig::OperStatus::IfOperStatusUp);
 
-            let dhcp_enabled = dhcp_map
-                .get(adapter.friendly_name())
-                .copied()
-                .unwrap_or(false);
-
-            result.push(InterfaceInfo {
-                name: adapter.friendly_name().to_string(),
-                description: adapter.description().to_string(),
-                mac,
-                ipv4,
-                ipv6,
-                is_up,
-                ssid,
-                dhcp_enabled,
-                is_physical,
-                interface_type: format!("{:?}", adapter.if_type()),
-                cidr,
-                guid: adapter.adapter_name().to_string(),
-                link_speed_bps: {
-                    let s = adapter.transmit_link_speed();
-                    if s == 0 || s == u64::MAX {
-                        None
-                    } else {
-                        Some(s)
-                    }
-                },
-            });
+        let mut ssid = None;
+        if let (true, Some(s)) = (is_up, ssid_map.get(adapter.adapter_name())) {
+            ssid = Some(s.clone());
         }
+
+        let dhcp_enabled = dhcp_map
+            .get(adapter.friendly_name())
+            .copied()
+            .unwrap_or(false);
+
+        result.push(InterfaceInfo {
+            name: adapter.friendly_name().to_string(),
+            description: adapter.description().to_string(),
+            mac,
+            ipv4,
+            ipv6,
+            is_up,
+            ssid,
+            dhcp_enabled,
+            is_physical,
+            interface_type: format!("{:?}", adapter.if_type()),
+            cidr,
+            guid: adapter.adapter_name().to_string(),
+            link_speed_bps: {
+                let s = adapter.transmit_link_speed();
+                if s == 0 || s == u64::MAX {
+                    None
+                } else {
+                    Some(s)
+                }
+            },
+            media: if is_physical {
+                crate::utils::link::media(
+                    adapter.adapter_name(),
+                    adapter.if_type() == ipconfig::IfType::Ieee80211,
+                )
+            } else {
+                iptools_core::LinkMedia::default()
+            },
+        });
     }
 
     result.sort_by(|a, b| b.is_up.cmp(&a.is_up).then_with(|| a.name.cmp(&b.name)));
@@ -132,6 +187,9 @@ pub fn get_interfaces() -> Vec<InterfaceInfo> {
 
 #[cfg(target_os = "linux")]
 pub fn get_interfaces() -> Vec<InterfaceInfo> {
+    if crate::utils::android::is_android() {
+        return crate::utils::android::interfaces();
+    }
     let mut ifs = linux_core_interfaces();
     // 富化（含子进程，仅 dashboard(手动/初始)、adapter(2s 节流+阻塞池) 等低频路径调用；
     // 扫描热路径 resolve_mac_address 走 linux_core_interfaces 不富化，避免每个 IP 起子进程）：
@@ -145,6 +203,9 @@ pub fn get_interfaces() -> Vec<InterfaceInfo> {
         if i.interface_type == "Ieee80211" && i.is_up {
             i.ssid = crate::utils::wlan::ssid_of(&i.name);
         }
+        if i.is_physical {
+            i.media = crate::utils::link::media(&i.name, i.interface_type == "Ieee80211");
+        }
     }
     ifs
 }
@@ -205,10 +266,11 @@ fn linux_core_interfaces() -> Vec<InterfaceInfo> {
     }
 
     // 2) 遍历 /sys/class/net，合并 sysfs 元数据 + getifaddrs 地址。
+    //    无 sysfs（精简容器、部分 musl 静态环境）时回退到可移植枚举。
     let mut result = Vec::new();
     let sys = match fs::read_dir("/sys/class/net") {
         Ok(d) => d,
-        Err(_) => return result,
+        Err(_) => return portable_interfaces(),
     };
     for ent in sys.flatten() {
         let name = ent.file_name().to_string_lossy().to_string();
@@ -254,6 +316,7 @@ fn linux_core_interfaces() -> Vec<InterfaceInfo> {
             cidr,
             guid: name.clone(),
             link_speed_bps,
+            media: iptools_core::LinkMedia::default(),
         });
     }
 
@@ -298,9 +361,145 @@ fn linux_dhcp_map() -> std::collections::HashMap<String, bool> {
     map
 }
 
-#[cfg(all(unix, not(target_os = "linux")))]
+/// macOS：可移植枚举 + 系统工具富化（与 Linux 的 iw/nmcli 同一思路）：
+///   - 硬件类型/描述：`networksetup -listallhardwareports`（SystemConfiguration 的硬件端口表）
+///   - DHCP：`ipconfig getpacket <dev>` 有租约即视为 DHCP
+///   - SSID：无线且 up 时经 `networksetup -getairportnetwork`（CoreWLAN）查询
+#[cfg(target_os = "macos")]
+pub fn get_interfaces() -> Vec<InterfaceInfo> {
+    let ports = run_macos_tool("networksetup", &["-listallhardwareports"])
+        .map(|out| macos::parse_hardware_ports(&out))
+        .unwrap_or_default();
+    let mut ifs = portable_interfaces();
+    for i in ifs.iter_mut() {
+        if let Some(port) = ports.iter().find(|port| port.device == i.name) {
+            i.description = port.name.clone();
+            i.interface_type = macos::interface_type(&port.name).to_string();
+            i.is_physical = i.interface_type != "Other";
+        }
+        i.dhcp_enabled = run_macos_tool("ipconfig", &["getpacket", &i.name])
+            .is_some_and(|out| macos::is_dhcp_lease(&out));
+        if i.interface_type == "Ieee80211" && i.is_up {
+            i.ssid = crate::utils::wlan::ssid_of(&i.name);
+        }
+    }
+    ifs
+}
+
+/// 跑 macOS 系统工具，返回 stdout；命令缺失/失败 → None。
+#[cfg(target_os = "macos")]
+fn run_macos_tool(cmd: &str, args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new(cmd).args(args).output().ok()?;
+    if out.status.success() {
+        Some(String::from_utf8_lossy(&out.stdout).into_owned())
+    } else {
+        None
+    }
+}
+
+/// Android：/proc/net 降级枚举，详见 [`crate::utils::android`]。
+#[cfg(target_os = "android")]
 pub fn get_interfaces() -> Vec<InterfaceInfo> {
-    Vec::new()
+    crate::utils::android::interfaces()
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "android"
+)))]
+pub fn get_interfaces() -> Vec<InterfaceInfo> {
+    portable_interfaces()
+}
+
+/// 默认网关：Windows 取 IP Helper 报告的该网卡网关；有 `/proc/net/route` 的
+/// 平台读默认路由；其他平台取网卡所在网段的第一个主机地址，这是家用路由器的惯例。
+pub fn default_gateway(interface: &InterfaceInfo) -> Option<Ipv4Addr> {
+    #[cfg(target_os = "windows")]
+    if let Ok(adapters) = ipconfig::get_adapters()
+        && let Some(adapter) = adapters
+            .iter()
+            .find(|adapter| adapter.adapter_name() == interface.guid)
+    {
+        return adapter.gateways().iter().find_map(|gateway| match gateway {
+            IpAddr::V4(gateway) => Some(*gateway),
+            IpAddr::V6(_) => None,
+        });
+    }
+    if let Ok(table) = std::fs::read_to_string("/proc/net/route") {
+        let routes = crate::utils::android::parse_proc_route(&table);
+        return crate::utils::android::default_gateway(&routes);
+    }
+    let network = interface
+        .cidr
+        .as_deref()?
+        .parse::<ipnetwork::Ipv4Network>()
+        .ok()?;
+    ipnetwork::Ipv4Network::new(network.network(), network.prefix())
+        .ok()?
+        .nth(1)
+}
+
+/// 纯 Rust 回退枚举（sysinfo），不依赖 IP Helper / sysfs / netlink。
+/// 用于原生接口不可用的平台或环境；仅有名称、MAC 与地址，其余字段保守取默认。
+pub fn portable_interfaces() -> Vec<InterfaceInfo> {
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut result: Vec<InterfaceInfo> = networks
+        .list()
+        .iter()
+        .map(|(name, data)| {
+            let addrs: Vec<(IpAddr, u8)> = data
+                .ip_networks()
+                .iter()
+                .map(|network| (network.addr, network.prefix))
+                .collect();
+            portable_interface(name, &data.mac_address().to_string(), &addrs)
+        })
+        .filter(|info| !info.name.is_empty() && info.name != "lo")
+        .collect();
+    result.sort_by(|a, b| b.is_up.cmp(&a.is_up).then_with(|| a.name.cmp(&b.name)));
+    result
+}
+
+/// 由名称、MAC 与地址前缀组装 [`InterfaceInfo`]。有非回环地址即视为已启用。
+fn portable_interface(name: &str, mac: &str, addrs: &[(IpAddr, u8)]) -> InterfaceInfo {
+    let mut ipv4 = Vec::new();
+    let mut ipv6 = Vec::new();
+    let mut cidr = None;
+    for (addr, prefix) in addrs {
+        match addr {
+            IpAddr::V4(v4) if !v4.is_loopback() => {
+                if cidr.is_none() && (1..32).contains(prefix) {
+                    cidr = Some(format!("{v4}/{prefix}"));
+                }
+                ipv4.push(v4.to_string());
+            }
+            IpAddr::V6(v6) if !v6.is_loopback() => ipv6.push(v6.to_string()),
+            _ => {}
+        }
+    }
+    let mac = if mac == "00:00:00:00:00:00" {
+        String::new()
+    } else {
+        mac.to_string()
+    };
+    InterfaceInfo {
+        name: name.to_string(),
+        description: name.to_string(),
+        is_physical: !mac.is_empty(),
+        mac,
+        is_up: !ipv4.is_empty() || !ipv6.is_empty(),
+        ipv4,
+        ipv6,
+        ssid: None,
+        dhcp_enabled: false,
+        interface_type: "Other".to_string(),
+        cidr,
+        guid: name.to_string(),
+        link_speed_bps: None,
+        media: iptools_core::LinkMedia::default(),
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -594,20 +793,73 @@ fn win_neighbor_mac(ip: Ipv4Addr) -> Option<String> {
 
 /// Linux：主动发 ARP request 并等 reply（语义等价 Windows SendARP）。
 /// 自动选出口网卡（按目标 IP 落在哪个本机子网）。需 CAP_NET_RAW，无权限/超时返回 None。
+///
+/// 同时报告这次探测是否顺利：第一轮就应答为 `Clean`，重发后才应答或发送失败为
+/// `Troubled`，始终无应答为 `Silent`。
 #[cfg(target_os = "linux")]
-pub fn resolve_mac_address(ip: Ipv4Addr) -> Option<String> {
+fn arp_probe(ip: Ipv4Addr) -> (Option<String>, ProbeOutcome) {
     use nix::net::if_::if_nametoindex;
     use std::time::{Duration, Instant};
 
-    // 1) 选出口网卡：目标 IP 与某网卡 ipv4 同子网。用廉价 core（不起 iw/nmcli 子进程）。
-    let ifaces = linux_core_interfaces();
-    let (if_name, src_ip, src_mac) = ifaces.iter().find_map(|i| {
+    // 1) 选出口网卡：目标 IP 与某网卡 ipv4 同子网。
+    let Some(source) = linux_arp_source(ip) else {
+        return (None, ProbeOutcome::Silent);
+    };
+    let Ok(ifindex) = if_nametoindex(source.name.as_str()) else {
+        return (None, ProbeOutcome::Silent);
+    };
+    let frame = linux::build_arp_request(source.mac, source.ip, ip);
+    let Some(socket) = PacketSocket::open(ifindex as i32) else {
+        return (None, ProbeOutcome::Silent);
+    };
+
+    // 2) 重试 3 轮：每轮重发一次 ARP request + 持续收最多 ~90ms，总窗口 ~270ms。
+    // 某台繁忙主机若丢了某一轮的请求，下一轮还能命中——比「只发一次」更稳，总耗时
+    // 几乎不变。任一轮收到目标的 reply 即返回。
+    let mut buf = [0u8; 1500];
+    for round in 0..3 {
+        if !socket.send(&frame) {
+            return (None, ProbeOutcome::Troubled);
+        }
+        let round_deadline = Instant::now() + Duration::from_millis(90);
+        // 非目标的 ARP 包 → 继续本轮接收；本轮超时 → 进入下一轮重发
+        while let Some(n) = socket.recv(&mut buf, round_deadline) {
+            if let Some(mac) = linux::parse_arp_reply(&buf[..n], ip) {
+                let outcome = if round == 0 {
+                    ProbeOutcome::Clean
+                } else {
+                    ProbeOutcome::Troubled
+                };
+                return (Some(mac), outcome);
+            }
+        }
+    }
+    (None, ProbeOutcome::Silent)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn resolve_mac_address(_ip: Ipv4Addr) -> Option<String> {
+    None
+}
+
+/// 发出 ARP 请求所用的本机网卡。
+#[cfg(target_os = "linux")]
+struct ArpSource {
+    name: String,
+    ip: Ipv4Addr,
+    mac: [u8; 6],
+    network: ipnetwork::Ipv4Network,
+}
+
+/// 目标 IP 所在子网的已启用网卡。用廉价 core（不起 iw/nmcli 子进程）。
+#[cfg(target_os = "linux")]
+fn linux_arp_source(ip: Ipv4Addr) -> Option<ArpSource> {
+    linux_core_interfaces().iter().find_map(|i| {
         if !i.is_up {
             return None;
         }
-        let cidr = i.cidr.as_ref()?;
-        let net: ipnetwork::Ipv4Network = cidr.parse().ok()?;
-        if !net.contains(ip) {
+        let network: ipnetwork::Ipv4Network = i.cidr.as_ref()?.parse().ok()?;
+        if !network.contains(ip) {
             return None;
         }
         let src_ip: Ipv4Addr = i.ipv4.first()?.parse().ok()?;
@@ -618,83 +870,229 @@ pub fn resolve_mac_address(ip: Ipv4Addr) -> Option<String> {
             }
             mac[k] = u8::from_str_radix(part, 16).ok()?;
         }
-        Some((i.name.clone(), src_ip, mac))
-    })?;
+        Some(ArpSource {
+            name: i.name.clone(),
+            ip: src_ip,
+            mac,
+            network,
+        })
+    })
+}
 
-    let ifindex = if_nametoindex(if_name.as_str()).ok()? as i32;
-    let frame = linux::build_arp_request(src_mac, src_ip, ip);
+/// 每批连发的 ARP 请求数；批间收一次回包，约合每秒一万多个请求，不至于冲垮廉价交换机。
+#[cfg(target_os = "linux")]
+const ARP_SWEEP_BATCH: usize = 64;
+#[cfg(target_os = "linux")]
+const ARP_SWEEP_BATCH_GAP: std::time::Duration = std::time::Duration::from_millis(5);
+/// 对仍未应答的地址重发的轮数；每轮发完再等 `ARP_SWEEP_TAIL` 收迟到的回包。
+#[cfg(target_os = "linux")]
+const ARP_SWEEP_ROUNDS: usize = 3;
+#[cfg(target_os = "linux")]
+const ARP_SWEEP_TAIL: std::time::Duration = std::time::Duration::from_millis(150);
 
-    // 2) AF_PACKET 原始套接字收发（unsafe libc）。
-    unsafe {
-        let fd = libc::socket(
-            libc::AF_PACKET,
-            libc::SOCK_RAW,
-            (libc::ETH_P_ARP as u16).to_be() as i32,
-        );
+/// 用一个 AF_PACKET 套接字成批发出 ARP 请求、边发边收，返回应答了的 IP 与 MAC。
+/// /24 通常一秒内完成，比逐台 [`probe_host`] 快一到两个数量级。同一 IP 有多个 MAC
+/// 应答时按先后全部保留：地址冲突，或有设备在冒充它。
+///
+/// 仅 Linux（非 Android）且有 `CAP_NET_RAW`、全部目标与同一块网卡同子网时可用；
+/// 否则返回 `None`，由调用方退回逐台探测。取消后返回已收到的部分。
+#[cfg(target_os = "linux")]
+pub fn arp_sweep(
+    targets: &[Ipv4Addr],
+    cancelled: impl Fn() -> bool,
+) -> Option<std::collections::HashMap<Ipv4Addr, Vec<String>>> {
+    use nix::net::if_::if_nametoindex;
+    use std::collections::{HashMap, HashSet};
+    use std::time::Instant;
+
+    if crate::utils::android::is_android() {
+        return None;
+    }
+    let source = linux_arp_source(*targets.first()?)?;
+    if !targets.iter().all(|ip| source.network.contains(*ip)) {
+        return None;
+    }
+    let ifindex = if_nametoindex(source.name.as_str()).ok()? as i32;
+    let socket = PacketSocket::open(ifindex)?;
+
+    let wanted: HashSet<Ipv4Addr> = targets.iter().copied().collect();
+    let mut found = HashMap::new();
+    let mut buf = [0u8; 1500];
+    let mut drain = |found: &mut HashMap<Ipv4Addr, Vec<String>>, until: Instant| {
+        while let Some(n) = socket.recv(&mut buf, until) {
+            if let Some((ip, mac)) = linux::parse_arp_sender(&buf[..n])
+                && wanted.contains(&ip)
+            {
+                let macs = found.entry(ip).or_default();
+                if !macs.contains(&mac) {
+                    macs.push(mac);
+                }
+            }
+        }
+    };
+    for _round in 0..ARP_SWEEP_ROUNDS {
+        let pending: Vec<Ipv4Addr> = targets
+            .iter()
+            .copied()
+            .filter(|ip| !found.contains_key(ip))
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+        for batch in pending.chunks(ARP_SWEEP_BATCH) {
+            if cancelled() {
+                return Some(found);
+            }
+            for ip in batch {
+                if !socket.send(&linux::build_arp_request(source.mac, source.ip, *ip)) {
+                    // 发送失败（如网卡刚断开）时不再重试，返回已收到的部分。
+                    return Some(found);
+                }
+            }
+            drain(&mut found, Instant::now() + ARP_SWEEP_BATCH_GAP);
+        }
+        drain(&mut found, Instant::now() + ARP_SWEEP_TAIL);
+    }
+    Some(found)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn arp_sweep(
+    _targets: &[Ipv4Addr],
+    _cancelled: impl Fn() -> bool,
+) -> Option<std::collections::HashMap<Ipv4Addr, Vec<String>>> {
+    None
+}
+
+/// 绑定到一块网卡的 ARP 原始套接字，drop 时关闭。
+#[cfg(target_os = "linux")]
+struct PacketSocket {
+    fd: std::os::fd::OwnedFd,
+    ifindex: i32,
+}
+
+#[cfg(target_os = "linux")]
+impl PacketSocket {
+    /// 绑定到 `ifindex` 后只收这块网卡上的 ARP 包，别的网卡上的回包不会挤占接收。
+    fn open(ifindex: i32) -> Option<Self> {
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+        let protocol = (libc::ETH_P_ARP as u16).to_be();
+        let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW, i32::from(protocol)) };
         if fd < 0 {
             return None;
         }
-        // 每轮 recv 最多 ~90ms；共 3 轮（重发），总窗口 ~270ms，与原 250ms 单次相当。
-        let tv = libc::timeval {
-            tv_sec: 0,
-            tv_usec: 90_000,
-        };
-        libc::setsockopt(
-            fd,
-            libc::SOL_SOCKET,
-            libc::SO_RCVTIMEO,
-            &tv as *const _ as *const libc::c_void,
-            std::mem::size_of::<libc::timeval>() as libc::socklen_t,
-        );
-
-        let mut sll: libc::sockaddr_ll = std::mem::zeroed();
+        // SAFETY: socket() 刚返回的有效描述符，此后只归 OwnedFd 所有。
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let mut sll: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
         sll.sll_family = libc::AF_PACKET as u16;
-        sll.sll_protocol = (libc::ETH_P_ARP as u16).to_be();
+        sll.sll_protocol = protocol;
         sll.sll_ifindex = ifindex;
-        sll.sll_halen = 6;
-        sll.sll_addr[..6].copy_from_slice(&[0xff; 6]);
+        let bound = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                &sll as *const _ as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        (bound == 0).then_some(Self { fd, ifindex })
+    }
 
-        // 重试 3 轮：每轮重发一次 ARP request + 持续收最多 ~90ms。某台繁忙主机若丢了
-        // 某一轮的请求，下一轮还能命中——比「只发一次」更稳，总耗时几乎不变。任一轮
-        // 收到目标的 reply 即返回。
-        let mut result = None;
-        let mut buf = [0u8; 1500];
-        'rounds: for _round in 0..3 {
-            let sent = libc::sendto(
-                fd,
+    fn send(&self, frame: &[u8]) -> bool {
+        use std::os::fd::AsRawFd;
+
+        unsafe {
+            let mut sll: libc::sockaddr_ll = std::mem::zeroed();
+            sll.sll_family = libc::AF_PACKET as u16;
+            sll.sll_protocol = (libc::ETH_P_ARP as u16).to_be();
+            sll.sll_ifindex = self.ifindex;
+            sll.sll_halen = 6;
+            sll.sll_addr[..6].copy_from_slice(&[0xff; 6]);
+            libc::sendto(
+                self.fd.as_raw_fd(),
                 frame.as_ptr() as *const libc::c_void,
                 frame.len(),
                 0,
                 &sll as *const _ as *const libc::sockaddr,
                 std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
-            );
-            if sent < 0 {
-                break;
-            }
-            let round_deadline = Instant::now() + Duration::from_millis(90);
-            loop {
-                if Instant::now() >= round_deadline {
-                    break; // 本轮超时 → 进入下一轮重发
-                }
-                let n = libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0);
-                if n <= 0 {
-                    break; // recv 超时（SO_RCVTIMEO）→ 下一轮
-                }
-                if let Some(mac) = linux::parse_arp_reply(&buf[..n as usize], ip) {
-                    result = Some(mac);
-                    break 'rounds;
-                }
-                // 非目标的 ARP 包 → 继续本轮接收
-            }
+            ) >= 0
         }
-        libc::close(fd);
-        result
+    }
+
+    /// 等到 `until` 为止收下一个包；到期或出错时为 `None`。用 poll 等待，不空转。
+    fn recv(&self, buf: &mut [u8], until: std::time::Instant) -> Option<usize> {
+        use std::os::fd::AsRawFd;
+
+        let remaining = until.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            return None;
+        }
+        let timeout = i32::try_from(remaining.as_micros().div_ceil(1000)).unwrap_or(i32::MAX);
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut pollfd, 1, timeout) } <= 0 {
+            return None;
+        }
+        let n = unsafe {
+            libc::recv(
+                self.fd.as_raw_fd(),
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                libc::MSG_DONTWAIT,
+            )
+        };
+        usize::try_from(n).ok().filter(|n| *n > 0)
     }
 }
 
-#[cfg(all(unix, not(target_os = "linux")))]
-pub fn resolve_mac_address(_ip: Ipv4Addr) -> Option<String> {
-    None
+/// 扫描用的主机探测：通常即 ARP 解析；Android 上无 raw socket，
+/// 改为 TCP connect 探测（MAC 可能为 "-"）。
+///
+/// 附带的 [`ProbeOutcome`] 供自适应并发判断链路是否拥塞。Windows 的邻居表
+/// 查询分不出重试，只报告应答与否。
+pub fn probe_host(ip: Ipv4Addr) -> (Option<String>, ProbeOutcome) {
+    if crate::utils::android::is_android() {
+        return crate::utils::android::probe_host(ip);
+    }
+    #[cfg(target_os = "linux")]
+    {
+        arp_probe(ip)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let mac = resolve_mac_address(ip);
+        let outcome = if mac.is_some() {
+            ProbeOutcome::Clean
+        } else {
+            ProbeOutcome::Silent
+        };
+        (mac, outcome)
+    }
+}
+
+/// 目标网段是否与某块已启用网卡的子网重叠，即二层直连、ARP 可达。
+/// 不重叠（经路由器才能到达）时扫描改用 ICMP Echo + TCP 探测。
+pub fn is_directly_attached(network: ipnetwork::Ipv4Network) -> bool {
+    #[cfg(target_os = "linux")]
+    let interfaces = if crate::utils::android::is_android() {
+        crate::utils::android::interfaces()
+    } else {
+        linux_core_interfaces()
+    };
+    #[cfg(not(target_os = "linux"))]
+    let interfaces = crate::utils::interface_store::interfaces();
+    network_is_attached(network, &interfaces)
+}
+
+fn network_is_attached(network: ipnetwork::Ipv4Network, interfaces: &[InterfaceInfo]) -> bool {
+    interfaces
+        .iter()
+        .filter(|i| i.is_up)
+        .filter_map(|i| i.cidr.as_ref()?.parse::<ipnetwork::Ipv4Network>().ok())
+        .any(|local| local.contains(network.network()) || network.contains(local.network()))
 }
 
 /// 探测当前进程是否具备 `CAP_NET_RAW`（创建原始套接字的能力）。
@@ -713,55 +1111,58 @@ pub fn has_cap_net_raw() -> bool {
     }
 }
 
-/// 解析设备主机名，多路回退以适配「系统 DNS 不可用/被 VPN 接管」的局域网场景：
+/// 解析设备的全部主机名，三路都问一遍以适配「系统 DNS 不可用/被 VPN 接管」的局域网场景：
 ///
-/// 1. **反向 DNS**（`getnameinfo`）：走系统当前 DNS 解析器。最快，但若无 PTR 记录
-///    常直接回填数字 IP；且 TUN/VPN 接管 DNS 时对内网设备多半失败。
+/// 1. **反向 DNS**（`getnameinfo`）：走系统当前 DNS 解析器，经 [`dns_cache`] 缓存。
+///    最快，但若无 PTR 记录常直接回填数字 IP；且 TUN/VPN 接管 DNS 时对内网设备多半失败。
 /// 2. **NetBIOS 节点状态**（UDP/137，等价 `nbtstat -A`）：直接问设备本身要它的
 ///    NetBIOS 名称表，**不经系统 DNS**。Windows 主机、部分设备会响应。
 /// 3. **mDNS 反向**（组播 224.0.0.251:5353 查 in-addr.arpa 的 PTR）：拿设备的
 ///    `xxx.local` 名，同样**绕开系统 DNS**。Apple/打印机/部分安卓会响应。
 ///
-/// 三步均 best-effort、各带短超时；任一步拿到「看起来像名字」（非 IP 文本）的结果即返回。
-pub fn resolve_hostname_until_cancelled(
+/// 三步均 best-effort、各带短超时；只保留「看起来像名字」（非 IP 文本）的结果，
+/// 按上面的顺序返回并去掉重复（忽略大小写与末尾的点）。取消后立即返回已得到的部分。
+pub fn resolve_hostnames_until_cancelled(
     ip: IpAddr,
     cancelled: impl Fn() -> bool,
-) -> Option<String> {
+) -> Vec<HostName> {
+    let mut names: Vec<HostName> = Vec::new();
+    let mut push = |source: NameSource, name: Option<String>| {
+        if let Some(name) = name.filter(|n| looks_like_hostname(n))
+            && !names
+                .iter()
+                .any(|known| dns_names_equal(&known.name, &name))
+        {
+            names.push(HostName { source, name });
+        }
+    };
     if cancelled() {
-        return None;
+        return Vec::new();
     }
     // 1. 反向 DNS：除了过滤数字 IP，还要求正向解析能回到原 IP（FCrDNS）。
     // Windows/Winsock 可能把网关等无有效 PTR 的地址错误映射成本机名；不做
     // forward-confirmation 就会把本机名称贴到其它设备上。
-    if let Some(name) = dns_lookup::lookup_addr(&ip)
-        .ok()
-        .filter(|n| looks_like_hostname(n))
-        .filter(|name| {
-            dns_lookup::lookup_host(name)
-                .ok()
-                .is_some_and(|addresses| forward_lookup_confirms(ip, &addresses))
-        })
-    {
-        return Some(name);
-    }
-
-    if cancelled() {
-        return None;
-    }
+    push(
+        NameSource::Dns,
+        dns_cache::reverse_blocking(ip)
+            .filter(|n| looks_like_hostname(n))
+            .filter(|name| {
+                dns_cache::resolve_blocking(name)
+                    .ok()
+                    .is_some_and(|addresses| forward_lookup_confirms(ip, &addresses))
+            }),
+    );
 
     // 2 & 3. 仅 IPv4 局域网设备适用 NetBIOS / mDNS。
     if let IpAddr::V4(v4) = ip {
-        if let Some(name) = resolve_netbios(v4).filter(|n| looks_like_hostname(n)) {
-            return Some(name);
+        if !cancelled() {
+            push(NameSource::NetBios, resolve_netbios(v4));
         }
-        if cancelled() {
-            return None;
-        }
-        if let Some(name) = resolve_mdns(v4).filter(|n| looks_like_hostname(n)) {
-            return Some(name);
+        if !cancelled() {
+            push(NameSource::Mdns, resolve_mdns(v4));
         }
     }
-    None
+    names
 }
 
 /// 是否「看起来是主机名」：非空且不是纯 IP 文本（反向 DNS 无记录时常回填数字 IP）。
@@ -965,13 +1366,13 @@ fn parse_mdns_ptr(buf: &[u8], expected_owner: &str) -> Option<String> {
     None
 }
 
-fn dns_names_equal(left: &str, right: &str) -> bool {
+pub(crate) fn dns_names_equal(left: &str, right: &str) -> bool {
     left.trim_end_matches('.')
         .eq_ignore_ascii_case(right.trim_end_matches('.'))
 }
 
 /// 跳过一个 DNS 名称（处理压缩指针），返回其后的偏移。
-fn skip_dns_name(buf: &[u8], mut off: usize) -> Option<usize> {
+pub(crate) fn skip_dns_name(buf: &[u8], mut off: usize) -> Option<usize> {
     loop {
         if off >= buf.len() {
             return None;
@@ -988,7 +1389,7 @@ fn skip_dns_name(buf: &[u8], mut off: usize) -> Option<usize> {
 }
 
 /// 解析一个 DNS 名称为点分字符串（支持压缩指针）。返回 (名称, 名称之后的偏移)。
-fn decode_dns_name(buf: &[u8], start: usize) -> Option<(String, usize)> {
+pub(crate) fn decode_dns_name(buf: &[u8], start: usize) -> Option<(String, usize)> {
     let mut labels: Vec<String> = Vec::new();
     let mut off = start;
     let mut next: Option<usize> = None; // 跟随指针前记录「真正下一个偏移」
@@ -1094,6 +1495,13 @@ pub(crate) mod linux {
 
     /// 从收到的帧解析 ARP reply 的 sender MAC（要求 sender IP == want_ip 且 opcode=reply）。
     pub fn parse_arp_reply(frame: &[u8], want_ip: Ipv4Addr) -> Option<String> {
+        parse_arp_sender(frame)
+            .filter(|(ip, _)| *ip == want_ip)
+            .map(|(_, mac)| mac)
+    }
+
+    /// ARP reply 的 sender IP 与 MAC；非 ARP、非 reply 或过短的帧为 None。
+    pub fn parse_arp_sender(frame: &[u8]) -> Option<(Ipv4Addr, String)> {
         if frame.len() < 42 {
             return None;
         }
@@ -1105,16 +1513,64 @@ pub(crate) mod linux {
         }
         let sender_mac = &frame[22..28];
         let sender_ip = Ipv4Addr::new(frame[28], frame[29], frame[30], frame[31]);
-        if sender_ip != want_ip {
-            return None;
-        }
-        Some(
+        Some((
+            sender_ip,
             sender_mac
                 .iter()
                 .map(|b| format!("{:02x}", b))
                 .collect::<Vec<_>>()
                 .join(":"),
-        )
+        ))
+    }
+}
+
+/// macOS 专属辅助：`networksetup` / `ipconfig` 输出解析。纯函数，平台无关，始终编译。
+pub(crate) mod macos {
+    #![allow(dead_code)]
+
+    /// `networksetup -listallhardwareports` 中的一个硬件端口。
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct HardwarePort {
+        pub name: String,
+        pub device: String,
+    }
+
+    /// 解析 "Hardware Port: Wi-Fi" / "Device: en0" 成对出现的块。
+    pub fn parse_hardware_ports(out: &str) -> Vec<HardwarePort> {
+        let mut ports = Vec::new();
+        let mut name: Option<String> = None;
+        for line in out.lines() {
+            let t = line.trim();
+            if let Some(v) = t.strip_prefix("Hardware Port: ") {
+                name = Some(v.trim().to_string());
+            } else if let Some(v) = t.strip_prefix("Device: ")
+                && let Some(name) = name.take()
+            {
+                ports.push(HardwarePort {
+                    name,
+                    device: v.trim().to_string(),
+                });
+            }
+        }
+        ports
+    }
+
+    /// 硬件端口名 → 与 Windows/Linux 一致的类型标签。
+    pub fn interface_type(port: &str) -> &'static str {
+        let lower = port.to_ascii_lowercase();
+        if lower.contains("wi-fi") || lower.contains("airport") {
+            "Ieee80211"
+        } else if lower.contains("ethernet") || lower.contains("lan") {
+            "EthernetCsmacd"
+        } else {
+            "Other"
+        }
+    }
+
+    /// `ipconfig getpacket <dev>` 仅在接口持有 DHCP 租约时输出报文字段。
+    pub fn is_dhcp_lease(out: &str) -> bool {
+        out.lines()
+            .any(|line| line.trim_start().starts_with("yiaddr"))
     }
 }
 
@@ -1122,6 +1578,74 @@ pub(crate) mod linux {
 mod tests {
     use super::*;
 
+    #[test]
+    fn macos_hardware_ports_and_dhcp_lease_parse() {
+        let sample = "\
+Hardware Port: Wi-Fi
+Device: en0
+Ethernet Address: aa:bb:cc:dd:ee:ff
+
+Hardware Port: Thunderbolt Bridge
+Device: bridge0
+Ethernet Address: N/A
+
+Hardware Port: USB 10/100/1000 LAN
+Device: en7
+";
+        let ports = macos::parse_hardware_ports(sample);
+        assert_eq!(ports.len(), 3);
+        assert_eq!(ports[0].device, "en0");
+        assert_eq!(macos::interface_type(&ports[0].name), "Ieee80211");
+        assert_eq!(macos::interface_type(&ports[1].name), "Other");
+        assert_eq!(macos::interface_type(&ports[2].name), "EthernetCsmacd");
+
+        assert!(macos::is_dhcp_lease(
+            "op = BOOTREPLY\nyiaddr = 192.168.1.20\nserver_identifier (ip): 192.168.1.1\n"
+        ));
+        assert!(!macos::is_dhcp_lease(""));
+    }
+
+    #[test]
+    fn portable_interface_keeps_first_subnet_and_drops_loopback() {
+        let info = portable_interface(
+            "eth0",
+            "00:11:22:33:44:55",
+            &[
+                ("127.0.0.1".parse().unwrap(), 8),
+                ("192.168.1.20".parse().unwrap(), 32),
+                ("192.168.1.21".parse().unwrap(), 24),
+                ("fe80::1".parse().unwrap(), 64),
+            ],
+        );
+        assert_eq!(info.ipv4, ["192.168.1.20", "192.168.1.21"]);
+        assert_eq!(info.ipv6, ["fe80::1"]);
+        assert_eq!(info.cidr.as_deref(), Some("192.168.1.21/24"));
+        assert!(info.is_up && info.is_physical);
+
+        let virtual_if = portable_interface("tun0", "00:00:00:00:00:00", &[]);
+        assert!(virtual_if.mac.is_empty());
+        assert!(!virtual_if.is_up && !virtual_if.is_physical);
+    }
+
+    #[test]
+    fn only_subnets_overlapping_an_up_interface_count_as_attached() {
+        let mut lan = portable_interface(
+            "eth0",
+            "00:11:22:33:44:55",
+            &[("192.168.1.21".parse().unwrap(), 24)],
+        );
+        let attached = |cidr: &str, interfaces: &[InterfaceInfo]| {
+            network_is_attached(cidr.parse().unwrap(), interfaces)
+        };
+        assert!(attached("192.168.1.0/24", std::slice::from_ref(&lan)));
+        assert!(attached("192.168.1.128/25", std::slice::from_ref(&lan)));
+        assert!(attached("192.168.0.0/16", std::slice::from_ref(&lan)));
+        assert!(!attached("192.168.2.0/24", std::slice::from_ref(&lan)));
+        assert!(!attached("10.0.0.0/24", &[]));
+        lan.is_up = false;
+        assert!(!attached("192.168.1.0/24", &[lan]));
+    }
+
     #[test]
     fn hostname_filter_and_forward_confirmation_reject_wrong_device_names() {
         assert!(looks_like_hostname("vivo"));
@@ -1255,6 +1779,60 @@ mod tests {
             super::linux::parse_arp_reply(&frame, Ipv4Addr::new(192, 168, 1, 2)),
             None
         );
+        assert_eq!(
+            super::linux::parse_arp_sender(&frame),
+            Some((
+                Ipv4Addr::new(192, 168, 1, 1),
+                "aa:bb:cc:dd:ee:ff".to_string()
+            ))
+        );
+        // 请求（opcode 1）不是应答：扫描时收到别人的 who-has 不能算作主机在线。
+        frame[21] = 0x01;
+        assert_eq!(super::linux::parse_arp_sender(&frame), None);
+    }
+
+    #[test]
+    fn arp_sweep_declines_targets_off_every_local_subnet() {
+        use std::net::Ipv4Addr;
+        // 没有出口网卡时交回逐台探测，而不是报告零台主机。
+        assert_eq!(super::arp_sweep(&[], || false), None);
+        assert_eq!(
+            super::arp_sweep(&[Ipv4Addr::new(203, 0, 113, 1)], || false),
+            None
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn the_packet_socket_waits_in_poll_and_sees_only_its_interface() {
+        use std::time::{Duration, Instant};
+        // 需要 CAP_NET_RAW；没有权限的环境里跳过
+        let Ok(lo) = nix::net::if_::if_nametoindex("lo") else {
+            return;
+        };
+        let Some(socket) = super::PacketSocket::open(lo as i32) else {
+            return;
+        };
+        let mut buf = [0u8; 1500];
+        let started = Instant::now();
+        assert_eq!(
+            socket.recv(&mut buf, started + Duration::from_millis(50)),
+            None
+        );
+        assert!(started.elapsed() >= Duration::from_millis(45));
+        assert_eq!(socket.recv(&mut buf, started), None, "deadline passed");
+
+        // 绑定在 lo 上的套接字收得到自己在 lo 上发出的请求
+        let frame = super::linux::build_arp_request(
+            [0x02, 0, 0, 0, 0, 1],
+            std::net::Ipv4Addr::LOCALHOST,
+            std::net::Ipv4Addr::new(127, 0, 0, 2),
+        );
+        assert!(socket.send(&frame));
+        let n = socket
+            .recv(&mut buf, Instant::now() + Duration::from_millis(500))
+            .expect("own request looped back");
+        assert_eq!(&buf[..n.min(frame.len())], &frame[..n.min(frame.len())]);
     }
 
     #[test]
@@ -1297,8 +1875,8 @@ mod tests {
     fn cancelled_hostname_lookup_returns_before_network_resolution() {
         let started = std::time::Instant::now();
         assert_eq!(
-            resolve_hostname_until_cancelled("192.0.2.1".parse().unwrap(), || true),
-            None
+            resolve_hostnames_until_cancelled("192.0.2.1".parse().unwrap(), || true),
+            Vec::new()
         );
         assert!(started.elapsed() < std::time::Duration::from_millis(50));
     }
@@ -0,0 +1,91 @@
+//! 供电状态探测，用于低功耗模式的“自动”档。
+//!
+//! 只回答一个问题：当前是否由电池供电。探测失败（台式机、容器、无权限）
+//! 一律视为接通电源，避免误降刷新率。
+
+/// 一个 `/sys/class/power_supply/*` 条目中与判定相关的字段。
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PowerSupply {
+    pub kind: String,
+    pub online: Option<bool>,
+    pub status: Option<String>,
+}
+
+/// 有任一外接电源在线 → 未用电池；否则有电池处于放电状态 → 用电池。
+/// 纯函数，平台无关，便于单测。
+pub fn on_battery_from(supplies: &[PowerSupply]) -> bool {
+    let mains_online = supplies
+        .iter()
+        .any(|supply| supply.kind != "Battery" && supply.online == Some(true));
+    !mains_online
+        && supplies.iter().any(|supply| {
+            supply.kind == "Battery" && supply.status.as_deref() == Some("Discharging")
+        })
+}
+
+/// Linux：读取 sysfs 中全部电源条目。
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let read = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|text| text.trim().to_string())
+    };
+    let supplies: Vec<PowerSupply> = entries
+        .flatten()
+        .map(|entry| {
+            let dir = entry.path();
+            PowerSupply {
+                kind: read(dir.join("type")).unwrap_or_default(),
+                online: read(dir.join("online")).map(|value| value == "1"),
+                status: read(dir.join("status")),
+            }
+        })
+        .collect();
+    on_battery_from(&supplies)
+}
+
+/// Windows：`GetSystemPowerStatus` 的 `ACLineStatus == 0` 表示断开交流电。
+#[cfg(target_os = "windows")]
+pub fn on_battery() -> bool {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // SAFETY: 传入本地可写结构体，调用只填充它。
+    unsafe { GetSystemPowerStatus(&mut status) }.is_ok() && status.ACLineStatus == 0
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn on_battery() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supply(kind: &str, online: Option<bool>, status: Option<&str>) -> PowerSupply {
+        PowerSupply {
+            kind: kind.into(),
+            online,
+            status: status.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn discharging_battery_without_mains_counts_as_battery_power() {
+        assert!(on_battery_from(&[
+            supply("Mains", Some(false), None),
+            supply("Battery", None, Some("Discharging")),
+        ]));
+        assert!(!on_battery_from(&[
+            supply("Mains", Some(true), None),
+            supply("Battery", None, Some("Discharging")),
+        ]));
+        assert!(!on_battery_from(&[supply("Battery", None, Some("Full"))]));
+        assert!(!on_battery_from(&[]));
+    }
+}
@@ -0,0 +1,443 @@
+//! SMB / NFS 共享枚举：扫描器可选的“局域网暴露了什么”审计步骤。
+//!
+//! SMB 交给系统自带的客户端匿名列举（Linux `smbclient -N`、macOS
+//! `smbutil view -G`、Windows `net view`），只保留普通可见共享；NFS 则直接
+//! 经 portmapper（TCP 111）找到 mountd，再以 ONC RPC 调用 MOUNTPROC_EXPORT
+//! 取导出列表，不依赖 `showmount`。
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use iptools_core::{NetworkShare, SHARE_PORTS, ShareProtocol};
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+const PORTMAP_PORT: u16 = 111;
+const PORTMAP_PROGRAM: u32 = 100_000;
+const PORTMAP_GETPORT: u32 = 3;
+const MOUNT_PROGRAM: u32 = 100_005;
+const MOUNT_VERSION: u32 = 3;
+const MOUNT_EXPORT: u32 = 5;
+const IPPROTO_TCP: u32 = 6;
+/// 记录标记（RFC 5531 §11）：最高位表示这是最后一个分片
+const LAST_FRAGMENT: u32 = 0x8000_0000;
+
+/// 按已开放的端口枚举共享；任一步失败只是少列几项，不报错。
+pub fn enumerate(ip: Ipv4Addr, open_ports: &[u16]) -> Vec<NetworkShare> {
+    let mut shares = Vec::new();
+    if open_ports.contains(&SHARE_PORTS[0]) {
+        shares.extend(smb_shares(ip));
+    }
+    if open_ports.contains(&SHARE_PORTS[1]) {
+        shares.extend(nfs_exports(ip));
+    }
+    shares
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn smb_shares(ip: Ipv4Addr) -> Vec<NetworkShare> {
+    // 关闭 SMB1 的服务器列完共享后还会因取不到工作组而返回非零，照样解析
+    std::process::Command::new("smbclient")
+        .args(["-N", "-g", "-t", "3", "-L", &format!("//{ip}")])
+        .output()
+        .map(|out| parse_smbclient(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn smb_shares(ip: Ipv4Addr) -> Vec<NetworkShare> {
+    std::process::Command::new("smbutil")
+        .args(["view", "-G", &format!("//{ip}")])
+        .output()
+        .map(|out| parse_share_table(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn smb_shares(ip: Ipv4Addr) -> Vec<NetworkShare> {
+    std::process::Command::new("net")
+        .args(["view", &format!("\\\\{ip}")])
+        .output()
+        .map(|out| parse_share_table(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn smb_shares(_ip: Ipv4Addr) -> Vec<NetworkShare> {
+    Vec::new()
+}
+
+/// 解析 `smbclient -g -L` 的 `类型|名字|注释` 行；工作组与服务器行被忽略。
+#[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+pub(crate) fn parse_smbclient(output: &str) -> Vec<NetworkShare> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '|');
+            let kind = fields.next()?;
+            let name = fields.next()?.trim();
+            let comment = fields.next().unwrap_or_default().trim();
+            smb_share(kind, name, comment)
+        })
+        .collect()
+}
+
+/// 解析 `net view \\host` 与 `smbutil view` 的表格：虚线之后到空行为止，
+/// 每行是“名字 类型 [盘符] 注释”。名字可含空格，以第一个类型词为界。
+#[cfg_attr(not(any(target_os = "macos", windows)), allow(dead_code))]
+pub(crate) fn parse_share_table(output: &str) -> Vec<NetworkShare> {
+    const KINDS: [&str; 6] = ["Disk", "Print", "Printer", "Pipe", "IPC", "Device"];
+    let mut shares = Vec::new();
+    let mut in_table = false;
+    for line in output.lines() {
+        if !in_table {
+            in_table = line.len() > 3 && line.trim().chars().all(|c| c == '-');
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with("The command") {
+            break;
+        }
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        let Some(kind) = tokens
+            .iter()
+            .skip(1)
+            .position(|token| KINDS.contains(token))
+            .map(|position| position + 1)
+        else {
+            continue;
+        };
+        let mut rest = &tokens[kind + 1..];
+        // net view 的“使用为”列：已映射时是一个盘符
+        if rest
+            .first()
+            .is_some_and(|token| token.len() == 2 && token.ends_with(':'))
+        {
+            rest = &rest[1..];
+        }
+        shares.extend(smb_share(
+            tokens[kind],
+            &tokens[..kind].join(" "),
+            &rest.join(" "),
+        ));
+    }
+    shares
+}
+
+/// 只保留磁盘与打印机共享；`$` 结尾的是管理 / 隐藏共享。
+fn smb_share(kind: &str, name: &str, comment: &str) -> Option<NetworkShare> {
+    if name.is_empty() || name.ends_with('$') {
+        return None;
+    }
+    let detail = match kind {
+        "Disk" => comment.to_string(),
+        "Printer" | "Print" if comment.is_empty() => "printer".to_string(),
+        "Printer" | "Print" => format!("printer · {comment}"),
+        _ => return None,
+    };
+    Some(NetworkShare {
+        protocol: ShareProtocol::Smb,
+        name: name.to_string(),
+        detail,
+    })
+}
+
+/// 只提供 SMB1 方言（NT LM 0.12）协商，服务器选中它即仍接受 SMBv1。
+/// 关闭了 SMB1 的 Windows 直接断开，Samba 则回一个不选任何方言的应答。
+pub fn accepts_smb1(ip: Ipv4Addr) -> bool {
+    let server = SocketAddr::new(IpAddr::V4(ip), SHARE_PORTS[0]);
+    let Ok(mut stream) = TcpStream::connect_timeout(&server, RPC_TIMEOUT) else {
+        return false;
+    };
+    if stream.set_read_timeout(Some(RPC_TIMEOUT)).is_err()
+        || stream.write_all(&smb1_negotiate()).is_err()
+    {
+        return false;
+    }
+    let mut reply = [0u8; 64];
+    let mut read = 0;
+    while read < reply.len() {
+        match stream.read(&mut reply[read..]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => read += n,
+        }
+    }
+    smb1_dialect_chosen(&reply[..read])
+}
+
+/// NetBIOS 会话头 + SMB_COM_NEGOTIATE 请求，只列 `NT LM 0.12` 一种方言。
+pub(crate) fn smb1_negotiate() -> Vec<u8> {
+    const DIALECT: &[u8] = b"\x02NT LM 0.12\0";
+    let mut smb = vec![0xff, b'S', b'M', b'B', 0x72];
+    smb.extend_from_slice(&[0; 4]); // 状态
+    smb.push(0x18); // 路径不区分大小写、规范化路径名
+    smb.extend_from_slice(&0xc801_u16.to_le_bytes()); // Unicode、NT 状态码、长文件名
+    smb.extend_from_slice(&[0; 12]); // PID 高位、签名、保留
+    smb.extend_from_slice(&[0; 2]); // TID
+    smb.extend_from_slice(&0xfeff_u16.to_le_bytes()); // PID
+    smb.extend_from_slice(&[0; 4]); // UID、MID
+    smb.push(0); // 参数字数
+    smb.extend_from_slice(&(DIALECT.len() as u16).to_le_bytes());
+    smb.extend_from_slice(DIALECT);
+    let mut packet = vec![0];
+    packet.extend_from_slice(&(smb.len() as u32).to_be_bytes()[1..]);
+    packet.extend(smb);
+    packet
+}
+
+/// 应答是成功的 SMB1 NEGOTIATE，且选中的方言序号不是 0xFFFF（都不支持）。
+pub(crate) fn smb1_dialect_chosen(reply: &[u8]) -> bool {
+    let Some(smb) = reply.get(4..) else {
+        return false;
+    };
+    smb.len() >= 35
+        && smb[..5] == [0xff, b'S', b'M', b'B', 0x72]
+        && smb[5..9] == [0; 4]
+        && smb[32] > 0
+        && smb[33..35] != [0xff, 0xff]
+}
+
+fn nfs_exports(ip: Ipv4Addr) -> Vec<NetworkShare> {
+    let portmap = SocketAddr::new(IpAddr::V4(ip), PORTMAP_PORT);
+    let mount_port = rpc_call(
+        portmap,
+        (PORTMAP_PROGRAM, 2, PORTMAP_GETPORT),
+        &[MOUNT_PROGRAM, MOUNT_VERSION, IPPROTO_TCP, 0],
+    )
+    .and_then(|result| Some(u32::from_be_bytes(result.get(..4)?.try_into().ok()?)))
+    .and_then(|port| u16::try_from(port).ok())
+    .filter(|port| *port != 0);
+    // 只开 NFSv4 的服务器没有 mountd，无从列举
+    let Some(mount_port) = mount_port else {
+        return Vec::new();
+    };
+    rpc_call(
+        SocketAddr::new(IpAddr::V4(ip), mount_port),
+        (MOUNT_PROGRAM, MOUNT_VERSION, MOUNT_EXPORT),
+        &[],
+    )
+    .and_then(|result| parse_exports(&result))
+    .unwrap_or_default()
+    .into_iter()
+    .map(|(path, groups)| NetworkShare {
+        protocol: ShareProtocol::Nfs,
+        name: path,
+        detail: if groups.is_empty() {
+            "*".into()
+        } else {
+            groups.join(", ")
+        },
+    })
+    .collect()
+}
+
+/// 经 TCP 发起一次 AUTH_NONE 的 ONC RPC 调用，返回结果部分。
+fn rpc_call(
+    server: SocketAddr,
+    (program, version, procedure): (u32, u32, u32),
+    args: &[u32],
+) -> Option<Vec<u8>> {
+    let xid = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let call = encode_call(xid, program, version, procedure, args);
+    let mut stream = TcpStream::connect_timeout(&server, RPC_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(RPC_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(RPC_TIMEOUT)).ok()?;
+    stream
+        .write_all(&(LAST_FRAGMENT | call.len() as u32).to_be_bytes())
+        .ok()?;
+    stream.write_all(&call).ok()?;
+
+    let mut reply = Vec::new();
+    loop {
+        let mut marker = [0u8; 4];
+        stream.read_exact(&mut marker).ok()?;
+        let marker = u32::from_be_bytes(marker);
+        let length = (marker & !LAST_FRAGMENT) as usize;
+        // 导出列表再长也不会超过 1 MiB，防止异常长度耗尽内存
+        if reply.len() + length > 1 << 20 {
+            return None;
+        }
+        let start = reply.len();
+        reply.resize(start + length, 0);
+        stream.read_exact(&mut reply[start..]).ok()?;
+        if marker & LAST_FRAGMENT != 0 {
+            break;
+        }
+    }
+    parse_reply(&reply, xid).map(<[u8]>::to_vec)
+}
+
+pub(crate) fn encode_call(
+    xid: u32,
+    program: u32,
+    version: u32,
+    procedure: u32,
+    args: &[u32],
+) -> Vec<u8> {
+    // xid, CALL, RPC 版本 2, 程序/版本/过程, 凭据与校验均为 AUTH_NONE
+    [xid, 0, 2, program, version, procedure, 0, 0, 0, 0]
+        .iter()
+        .chain(args)
+        .flat_map(|word| word.to_be_bytes())
+        .collect()
+}
+
+/// 校验事务号、MSG_ACCEPTED 与 SUCCESS 后返回结果部分。
+pub(crate) fn parse_reply(reply: &[u8], xid: u32) -> Option<&[u8]> {
+    let mut reader = Xdr::new(reply);
+    if reader.word()? != xid || reader.word()? != 1 || reader.word()? != 0 {
+        return None;
+    }
+    reader.word()?;
+    reader.opaque()?;
+    (reader.word()? == 0).then(|| reader.rest())
+}
+
+/// MOUNTPROC_EXPORT 结果：`(路径, 允许的客户端组)` 链表。
+pub(crate) fn parse_exports(result: &[u8]) -> Option<Vec<(String, Vec<String>)>> {
+    let mut reader = Xdr::new(result);
+    let mut exports = Vec::new();
+    while reader.word()? == 1 {
+        let path = reader.string()?;
+        let mut groups = Vec::new();
+        while reader.word()? == 1 {
+            groups.push(reader.string()?);
+        }
+        exports.push((path, groups));
+    }
+    Some(exports)
+}
+
+struct Xdr<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Xdr<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn word(&mut self) -> Option<u32> {
+        let bytes = self.data.get(self.offset..self.offset + 4)?;
+        self.offset += 4;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?))
+    }
+
+    /// 变长数据按 4 字节对齐补齐。
+    fn opaque(&mut self) -> Option<&'a [u8]> {
+        let length = self.word()? as usize;
+        let bytes = self
+            .data
+            .get(self.offset..self.offset.checked_add(length)?)?;
+        self.offset += length.next_multiple_of(4);
+        Some(bytes)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        Some(String::from_utf8_lossy(self.opaque()?).into_owned())
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.data.get(self.offset..).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smb_listings_keep_visible_disk_and_printer_shares() {
+        let smbclient = "Disk|media|Family photos\nIPC|IPC$|IPC Service (nas)\n\
+                         Disk|print$|Printer Drivers\nPrinter|laser|\nWorkgroup|HOME|NAS\n";
+        let shares = parse_smbclient(smbclient);
+        assert_eq!(shares.len(), 2);
+        assert_eq!(shares[0].name, "media");
+        assert_eq!(shares[0].detail, "Family photos");
+        assert_eq!(shares[1].detail, "printer");
+
+        let net_view = "Shared resources at \\\\192.168.1.8\r\n\r\nNAS\r\n\r\n\
+            Share name  Type  Used as  Comment\r\n\r\n\
+            -------------------------------------------------------------------------------\r\n\
+            Home Videos Disk  Z:       Holiday clips\r\n\
+            public      Disk\r\n\
+            The command completed successfully.\r\n";
+        let shares = parse_share_table(net_view);
+        assert_eq!(shares.len(), 2);
+        assert_eq!(shares[0].name, "Home Videos");
+        assert_eq!(shares[0].detail, "Holiday clips");
+        assert_eq!(shares[1].name, "public");
+
+        let smbutil = "Share        Type    Comments\n-------------------------------\n\
+                       media        Disk    Family photos\nIPC$         Pipe    IPC Service\n\n\
+                       2 shares listed\n";
+        assert_eq!(parse_share_table(smbutil).len(), 1);
+    }
+
+    #[test]
+    fn smb1_is_accepted_only_when_a_dialect_is_chosen() {
+        let request = smb1_negotiate();
+        assert_eq!(request.len(), 4 + 32 + 3 + 12);
+        assert_eq!(
+            &request[1..4],
+            &(request.len() as u32 - 4).to_be_bytes()[1..]
+        );
+
+        let reply = |dialect: [u8; 2]| {
+            // 会话头与 32 字节 SMB 头照搬请求，之后是参数字数与选中的方言
+            let mut reply = request[..36].to_vec();
+            reply.push(17);
+            reply.extend_from_slice(&dialect);
+            reply
+        };
+        assert!(smb1_dialect_chosen(&reply([0, 0])));
+        assert!(!smb1_dialect_chosen(&reply([0xff, 0xff])));
+        assert!(!smb1_dialect_chosen(&[]));
+        // SMB2 应答以 0xFE 'SMB' 开头
+        let mut smb2 = reply([0, 0]);
+        smb2[4] = 0xfe;
+        assert!(!smb1_dialect_chosen(&smb2));
+    }
+
+    #[test]
+    fn mount_export_reply_is_decoded_from_xdr() {
+        let call = encode_call(7, MOUNT_PROGRAM, MOUNT_VERSION, MOUNT_EXPORT, &[]);
+        assert_eq!(call.len(), 40);
+        assert_eq!(&call[12..16], &MOUNT_PROGRAM.to_be_bytes());
+
+        let string = |value: &str| {
+            let mut bytes = (value.len() as u32).to_be_bytes().to_vec();
+            bytes.extend_from_slice(value.as_bytes());
+            bytes.resize(4 + value.len().next_multiple_of(4), 0);
+            bytes
+        };
+        let mut reply = [7u32, 1, 0, 0, 0, 0]
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        reply.extend_from_slice(&1u32.to_be_bytes());
+        reply.extend(string("/srv/media"));
+        reply.extend_from_slice(&1u32.to_be_bytes());
+        reply.extend(string("192.168.1.0/24"));
+        reply.extend_from_slice(&0u32.to_be_bytes());
+        reply.extend_from_slice(&1u32.to_be_bytes());
+        reply.extend(string("/export"));
+        reply.extend_from_slice(&0u32.to_be_bytes());
+        reply.extend_from_slice(&0u32.to_be_bytes());
+
+        assert_eq!(parse_reply(&reply, 8), None);
+        let exports = parse_exports(parse_reply(&reply, 7).unwrap()).unwrap();
+        assert_eq!(
+            exports,
+            [
+                ("/srv/media".to_string(), vec!["192.168.1.0/24".to_string()]),
+                ("/export".to_string(), Vec::new()),
+            ]
+        );
+        assert_eq!(parse_exports(&reply[24..30]), None);
+    }
+}
@@ -0,0 +1,104 @@
+//! 局域网网页指纹：取设备管理页的 `<title>` 与 `Server` 头，
+//! 帮用户认出一排 192.168.1.x 里哪台是打印机、哪台是 NAS。
+//!
+//! 设备证书几乎都是自签名的，这里只读标题不提交任何数据，因此不校验证书。
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use iptools_core::{WEB_PORTS, WebService};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+/// 标题一般在页面开头，读到这么多仍没有就放弃
+const BODY_LIMIT: usize = 64 * 1024;
+const TITLE_LIMIT: usize = 80;
+
+/// 整轮扫描共用的客户端；构建失败时跳过网页指纹。
+pub fn client() -> Option<reqwest::Client> {
+    reqwest::Client::builder()
+        .no_proxy()
+        .tls_danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::limited(3))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .ok()
+}
+
+/// 对已开放的网页端口逐个取首页；没有应答的端口不列出。
+pub async fn fingerprint(
+    client: &reqwest::Client,
+    ip: Ipv4Addr,
+    open_ports: &[u16],
+) -> Vec<WebService> {
+    let mut services = Vec::new();
+    for port in WEB_PORTS
+        .into_iter()
+        .filter(|port| open_ports.contains(port))
+    {
+        let url = match port {
+            443 => format!("https://{ip}/"),
+            port => format!("http://{ip}:{port}/"),
+        };
+        if let Some(service) = fetch(client, &url, port).await {
+            services.push(service);
+        }
+    }
+    services
+}
+
+async fn fetch(client: &reqwest::Client, url: &str, port: u16) -> Option<WebService> {
+    let mut response = client.get(url).send().await.ok()?;
+    let server = response
+        .headers()
+        .get(reqwest::header::SERVER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let mut body = Vec::new();
+    while body.len() < BODY_LIMIT {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            _ => break,
+        }
+    }
+    Some(WebService {
+        port,
+        title: html_title(&String::from_utf8_lossy(&body)).unwrap_or_default(),
+        server,
+    })
+}
+
+/// 取第一个 `<title>` 的文本：折叠空白、还原常见实体并截断。
+pub(crate) fn html_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let text = html[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.chars().take(TITLE_LIMIT).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_title_is_collapsed_and_unescaped() {
+        let page = "<html><HEAD><Title lang=\"en\">\n  HP LaserJet &amp; Scan\n\t</TITLE>";
+        assert_eq!(html_title(page).as_deref(), Some("HP LaserJet & Scan"));
+        assert_eq!(html_title("<title>   </title>"), None);
+        assert_eq!(html_title("<title>unterminated"), None);
+        assert_eq!(html_title("<p>no title</p>"), None);
+    }
+}
@@ -1,7 +1,10 @@
 //! 无线网卡信息查询。
 //!
 //! 纯换算/标签函数（频率→频段/信道、PHY 标签、auth/cipher 标签、RSSI 近似）
-//! 与平台无关，便于单测；Windows 使用 WLAN API，Linux 解析 `iw dev link`。
+//! 与平台无关，便于单测；Windows 使用 WLAN API，Linux 解析 `iw dev link`，
+//! macOS 解析 `networksetup -getairportnetwork`（仅 SSID）。
+//! 附近 AP 列表只读系统缓存的扫描结果（Windows BSS 列表、Linux `iw scan dump`），
+//! 不主动触发扫描。
 
 /// 某块无线网卡当前关联的丰富信息。
 #[derive(Debug, Clone)]
@@ -21,6 +24,15 @@ pub struct WirelessInfo {
     pub cipher: String,
 }
 
+/// 最近一次扫描听到的一个 AP。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedBss {
+    pub ssid: String,
+    pub bssid: String,
+    pub freq_mhz: u32,
+    pub rssi_dbm: i32,
+}
+
 /// 由信道中心频率（kHz）推导 (频段, 信道号)。
 pub fn band_and_channel(freq_khz: u32) -> (String, u32) {
     let mhz = freq_khz / 1000;
@@ -232,6 +244,94 @@ pub fn query(guid: &str) -> Option<WirelessInfo> {
     }
 }
 
+/// 指定 GUID 网卡缓存的扫描结果；取不到时为空。
+#[cfg(target_os = "windows")]
+pub fn nearby(guid: &str) -> Vec<ScannedBss> {
+    use std::ptr;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::NetworkManagement::WiFi::{
+        WLAN_BSS_LIST, WLAN_INTERFACE_INFO_LIST, WLAN_SECURITY_ATTRIBUTES, WlanCloseHandle,
+        WlanEnumInterfaces, WlanFreeMemory, WlanGetNetworkBssList, WlanOpenHandle,
+        dot11_BSS_type_infrastructure,
+    };
+
+    let want = guid.to_uppercase();
+    let mut found = Vec::new();
+
+    unsafe {
+        let mut negotiated_version = 0u32;
+        let mut client_handle = HANDLE::default();
+        if WlanOpenHandle(2, None, &mut negotiated_version, &mut client_handle) != 0 {
+            return found;
+        }
+
+        let mut interface_list: *mut WLAN_INTERFACE_INFO_LIST = ptr::null_mut();
+        if WlanEnumInterfaces(client_handle, None, &mut interface_list) == 0
+            && !interface_list.is_null()
+        {
+            let list = &*interface_list;
+            for i in 0..list.dwNumberOfItems {
+                let iface = &*list.InterfaceInfo.as_ptr().offset(i as isize);
+                let iface_guid = iface.InterfaceGuid;
+                if format!("{{{:?}}}", iface_guid).to_uppercase() != want {
+                    continue;
+                }
+                // 不指定 SSID 时返回全部 BSS，是否加密的参数被忽略
+                let any_security = WLAN_SECURITY_ATTRIBUTES::default().bSecurityEnabled;
+                let mut bss_list: *mut WLAN_BSS_LIST = ptr::null_mut();
+                if WlanGetNetworkBssList(
+                    client_handle,
+                    &iface_guid,
+                    None,
+                    dot11_BSS_type_infrastructure,
+                    any_security,
+                    None,
+                    &mut bss_list,
+                ) == 0
+                    && !bss_list.is_null()
+                {
+                    let bl = &*bss_list;
+                    for j in 0..bl.dwNumberOfItems {
+                        let entry = &*bl.wlanBssEntries.as_ptr().offset(j as isize);
+                        let ssid_len = (entry.dot11Ssid.uSSIDLength as usize).min(32);
+                        found.push(ScannedBss {
+                            ssid: String::from_utf8_lossy(&entry.dot11Ssid.ucSSID[..ssid_len])
+                                .to_string(),
+                            bssid: entry
+                                .dot11Bssid
+                                .iter()
+                                .map(|b| format!("{:02x}", b))
+                                .collect::<Vec<_>>()
+                                .join(":"),
+                            freq_mhz: entry.ulChCenterFrequency / 1000,
+                            rssi_dbm: entry.lRssi,
+                        });
+                    }
+                    WlanFreeMemory(bss_list as *mut std::ffi::c_void);
+                }
+                break;
+            }
+            WlanFreeMemory(interface_list as *mut std::ffi::c_void);
+        }
+
+        WlanCloseHandle(client_handle, None);
+    }
+    found
+}
+
+/// Linux：`iw dev <if> scan dump` 读内核缓存的扫描结果，无需 root。
+#[cfg(target_os = "linux")]
+pub fn nearby(guid: &str) -> Vec<ScannedBss> {
+    run_iw(&["dev", guid, "scan", "dump"])
+        .map(|out| linux::parse_iw_scan(&out))
+        .unwrap_or_default()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn nearby(_guid: &str) -> Vec<ScannedBss> {
+    Vec::new()
+}
+
 /// Linux：guid 即接口名。组合 `iw link` + band/channel 纯函数。缺字段降级。
 #[cfg(target_os = "linux")]
 pub fn query(guid: &str) -> Option<WirelessInfo> {
@@ -273,7 +373,17 @@ pub fn ssid_of(iface: &str) -> Option<String> {
     linux::parse_iw_link(&out).and_then(|l| l.ssid)
 }
 
-#[cfg(all(unix, not(target_os = "linux")))]
+/// macOS：经 `networksetup -getairportnetwork <if>` 取当前 SSID。
+#[cfg(target_os = "macos")]
+pub fn ssid_of(iface: &str) -> Option<String> {
+    let out = std::process::Command::new("networksetup")
+        .args(["-getairportnetwork", iface])
+        .output()
+        .ok()?;
+    macos::parse_airport_network(&String::from_utf8_lossy(&out.stdout))
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
 pub fn ssid_of(_iface: &str) -> Option<String> {
     None
 }
@@ -333,6 +443,64 @@ pub(crate) mod linux {
         }
     }
 
+    /// `iw dev <if> scan dump` 中的一个 BSS（缺字段为 None）。
+    #[derive(Debug, Default)]
+    struct IwBss {
+        bssid: String,
+        ssid: Option<String>,
+        freq_mhz: Option<u32>,
+        signal_dbm: Option<i32>,
+    }
+
+    impl IwBss {
+        fn finish(self) -> Option<super::ScannedBss> {
+            Some(super::ScannedBss {
+                ssid: self.ssid.unwrap_or_default(),
+                bssid: self.bssid,
+                freq_mhz: self.freq_mhz?,
+                rssi_dbm: self.signal_dbm?,
+            })
+        }
+    }
+
+    /// 解析 `iw dev <if> scan dump`：每个 `BSS` 行开始一个 AP；缺频率或信号的条目丢弃。
+    pub fn parse_iw_scan(out: &str) -> Vec<super::ScannedBss> {
+        let mut found = Vec::new();
+        let mut current: Option<IwBss> = None;
+        for line in out.lines() {
+            if let Some(rest) = line.strip_prefix("BSS ") {
+                found.extend(current.take().and_then(IwBss::finish));
+                let bssid = rest
+                    .split(|c: char| c == '(' || c.is_whitespace())
+                    .next()
+                    .unwrap_or_default();
+                current = Some(IwBss {
+                    bssid: bssid.to_string(),
+                    ..IwBss::default()
+                });
+                continue;
+            }
+            let Some(bss) = current.as_mut() else {
+                continue;
+            };
+            let t = line.trim();
+            if let Some(v) = t.strip_prefix("SSID: ") {
+                bss.ssid = Some(v.trim().to_string());
+            } else if let Some(v) = t.strip_prefix("freq: ") {
+                // 新版 iw 输出 "2437.0"
+                bss.freq_mhz = v.trim().parse::<f64>().ok().map(|f| f as u32);
+            } else if let Some(v) = t.strip_prefix("signal: ") {
+                bss.signal_dbm = v
+                    .split_whitespace()
+                    .next()
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .map(|f| f.round() as i32);
+            }
+        }
+        found.extend(current.and_then(IwBss::finish));
+        found
+    }
+
     /// "866.7 MBit/s ..." → 866（取整 Mbps）。
     fn parse_bitrate_mbps(s: &str) -> Option<u32> {
         let num = s.split_whitespace().next()?;
@@ -340,6 +508,21 @@ pub(crate) mod linux {
     }
 }
 
+/// macOS 无线信息：解析 `networksetup` 输出的纯函数。平台无关，始终编译。
+pub(crate) mod macos {
+    #![allow(dead_code)]
+
+    /// "Current Wi-Fi Network: MyHome" → Some；未关联或无线关闭 → None。
+    pub fn parse_airport_network(out: &str) -> Option<String> {
+        out.lines()
+            .find_map(|line| {
+                let (_, ssid) = line.trim().split_once("Network: ")?;
+                Some(ssid.trim().to_string())
+            })
+            .filter(|ssid| !ssid.is_empty())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,4 +594,53 @@ Connected to 11:22:33:44:55:66 (on wlan0)
     fn iw_link_not_connected() {
         assert!(super::linux::parse_iw_link("Not connected.\n").is_none());
     }
+
+    #[test]
+    fn iw_scan_dump_lists_each_bss() {
+        let sample = "\
+BSS 11:22:33:44:55:66(on wlan0) -- associated
+\tlast seen: 120 ms ago
+\tfreq: 2437.0
+\tsignal: -45.00 dBm
+\tSSID: MyHome
+BSS aa:bb:cc:dd:ee:ff(on wlan0)
+\tfreq: 5180
+\tsignal: -71.00 dBm
+\tSSID: 
+BSS 00:00:00:00:00:01(on wlan0)
+\tSSID: NoSignal
+";
+        let found = super::linux::parse_iw_scan(sample);
+        assert_eq!(
+            found,
+            vec![
+                ScannedBss {
+                    ssid: "MyHome".into(),
+                    bssid: "11:22:33:44:55:66".into(),
+                    freq_mhz: 2437,
+                    rssi_dbm: -45,
+                },
+                ScannedBss {
+                    ssid: String::new(),
+                    bssid: "aa:bb:cc:dd:ee:ff".into(),
+                    freq_mhz: 5180,
+                    rssi_dbm: -71,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn airport_network_parses_ssid() {
+        assert_eq!(
+            super::macos::parse_airport_network("Current Wi-Fi Network: MyHome-5G\n").as_deref(),
+            Some("MyHome-5G")
+        );
+        assert!(
+            super::macos::parse_airport_network(
+                "You are not associated with an AirPort network.\n"
+            )
+            .is_none()
+        );
+    }
 }
@@ -0,0 +1,411 @@
+//! DNS 区域扫描：只问 DNS、不向目标主机发任何包，适合禁止主动探测的网络。
+//!
+//! 先向区域的权威服务器（NS 记录所指，外加本机解析器，AD 环境里它们往往就是
+//! 权威服务器）请求 AXFR 区域传送，允许时一次拿到全部 A 记录；被拒绝时由
+//! 调用方对网段内每个地址查 PTR。两种方式都只保留落在给定后缀内的名称。
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+use super::dns_cache;
+use super::net::decode_dns_name;
+
+const TYPE_A: u16 = 1;
+const TYPE_NS: u16 = 2;
+const TYPE_SOA: u16 = 6;
+const TYPE_PTR: u16 = 12;
+const TYPE_AXFR: u16 = 252;
+const CLASS_IN: u16 = 1;
+const FLAGS_RECURSION_DESIRED: u16 = 0x0100;
+const DNS_PORT: u16 = 53;
+
+const QUERY_TIMEOUT: Duration = Duration::from_millis(1_500);
+const TRANSFER_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// 整个区域传送的时限；大区域也应在此之内传完。
+const TRANSFER_DEADLINE: Duration = Duration::from_secs(20);
+/// 区域传送最多读取的记录数，防止异常服务器无休止地发送。
+const TRANSFER_RECORD_LIMIT: usize = 200_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RecordData {
+    Address(Ipv4Addr),
+    Name(String),
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DnsRecord {
+    name: String,
+    kind: u16,
+    data: RecordData,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DnsMessage {
+    rcode: u8,
+    answers: Vec<DnsRecord>,
+}
+
+/// 本机配置的 DNS 服务器：unix 读 `/etc/resolv.conf`，Windows 取已连接网卡的设置。
+pub fn system_resolvers() -> Vec<IpAddr> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut servers = Vec::new();
+        for adapter in ipconfig::get_adapters().unwrap_or_default() {
+            if matches!(adapter.oper_status(), ipconfig::OperStatus::IfOperStatusUp) {
+                for server in adapter.dns_servers() {
+                    if !servers.contains(server) {
+                        servers.push(*server);
+                    }
+                }
+            }
+        }
+        servers
+    }
+    #[cfg(unix)]
+    {
+        parse_resolv_conf(&std::fs::read_to_string("/etc/resolv.conf").unwrap_or_default())
+    }
+    #[cfg(not(any(unix, target_os = "windows")))]
+    {
+        Vec::new()
+    }
+}
+
+/// `zone` 本身或其下的名称。
+pub fn in_zone(name: &str, zone: &str) -> bool {
+    let name = name.trim_end_matches('.');
+    let zone = zone.trim_end_matches('.');
+    name.eq_ignore_ascii_case(zone)
+        || name.len() > zone.len()
+            && name[name.len() - zone.len()..].eq_ignore_ascii_case(zone)
+            && name.as_bytes()[name.len() - zone.len() - 1] == b'.'
+}
+
+/// 尝试向区域的服务器请求 AXFR，返回落在 `targets` 内的 A 记录（名称, 地址）。
+/// 没有服务器允许传送时返回 `None`。
+pub fn transfer(
+    zone: &str,
+    resolvers: &[IpAddr],
+    targets: impl Fn(Ipv4Addr) -> bool,
+    cancelled: impl Fn() -> bool,
+) -> Option<Vec<(String, Ipv4Addr)>> {
+    let mut servers: Vec<IpAddr> = Vec::new();
+    for resolver in resolvers {
+        let Some(message) = udp_query(*resolver, zone, TYPE_NS, true) else {
+            continue;
+        };
+        for record in message.answers {
+            if let RecordData::Name(host) = record.data
+                && record.kind == TYPE_NS
+                && let Ok(addresses) = dns_cache::resolve_blocking(&host)
+            {
+                servers.extend(addresses);
+            }
+        }
+        break;
+    }
+    servers.extend_from_slice(resolvers);
+    let mut tried = Vec::new();
+    for server in servers {
+        if cancelled() {
+            return None;
+        }
+        if tried.contains(&server) {
+            continue;
+        }
+        tried.push(server);
+        if let Some(records) = axfr(server, zone, &cancelled) {
+            return Some(
+                records
+                    .into_iter()
+                    .filter(|(name, ip)| targets(*ip) && in_zone(name, zone))
+                    .collect(),
+            );
+        }
+    }
+    None
+}
+
+/// 逐个地址查 PTR 的客户端；每个扫描线程一个，共用同一台解析器。
+pub struct PtrResolver {
+    socket: Option<UdpSocket>,
+}
+
+impl PtrResolver {
+    /// `server` 为 `None`（找不到本机解析器）时退回系统的反向解析。
+    pub fn new(server: Option<IpAddr>) -> Self {
+        let socket = server.and_then(|server| connect(server).ok());
+        Self { socket }
+    }
+
+    /// `ip` 的 PTR 名称；没有记录或查询失败时为空。超时重试一次。
+    pub fn lookup(&self, ip: Ipv4Addr) -> Vec<String> {
+        let Some(socket) = &self.socket else {
+            return dns_cache::reverse_blocking(IpAddr::V4(ip))
+                .into_iter()
+                .collect();
+        };
+        let name = ptr_name(ip);
+        for _attempt in 0..2 {
+            if let Some(message) = exchange(socket, &name, TYPE_PTR, true) {
+                return message
+                    .answers
+                    .into_iter()
+                    .filter(|record| record.kind == TYPE_PTR)
+                    .filter_map(|record| match record.data {
+                        RecordData::Name(name) => Some(name),
+                        _ => None,
+                    })
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
+}
+
+fn ptr_name(ip: Ipv4Addr) -> String {
+    let [a, b, c, d] = ip.octets();
+    format!("{d}.{c}.{b}.{a}.in-addr.arpa")
+}
+
+fn parse_resolv_conf(text: &str) -> Vec<IpAddr> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            (fields.next()? == "nameserver").then_some(())?;
+            // IPv6 链路本地地址可能带 `%网卡` 作用域，这里不支持，直接跳过。
+            fields.next()?.parse().ok()
+        })
+        .collect()
+}
+
+fn connect(server: IpAddr) -> std::io::Result<UdpSocket> {
+    let local: IpAddr = if server.is_ipv4() {
+        Ipv4Addr::UNSPECIFIED.into()
+    } else {
+        std::net::Ipv6Addr::UNSPECIFIED.into()
+    };
+    let socket = UdpSocket::bind((local, 0))?;
+    socket.connect((server, DNS_PORT))?;
+    socket.set_read_timeout(Some(QUERY_TIMEOUT))?;
+    Ok(socket)
+}
+
+fn udp_query(server: IpAddr, name: &str, kind: u16, recursive: bool) -> Option<DnsMessage> {
+    exchange(&connect(server).ok()?, name, kind, recursive)
+}
+
+/// 在已 connect 的套接字上问答一次；迟到的旧应答事务号不同，丢弃后继续等。
+fn exchange(socket: &UdpSocket, name: &str, kind: u16, recursive: bool) -> Option<DnsMessage> {
+    let id = query_id();
+    socket
+        .send(&encode_query(id, name, kind, recursive)?)
+        .ok()?;
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut buf = [0u8; 1_500];
+    while Instant::now() < deadline {
+        let n = socket.recv(&mut buf).ok()?;
+        if let Some(message) = parse_message(&buf[..n], id) {
+            return Some(message);
+        }
+    }
+    None
+}
+
+/// 经 TCP 请求区域传送；被拒绝、不是权威或没有以 SOA 收尾时返回 `None`。
+fn axfr(
+    server: IpAddr,
+    zone: &str,
+    cancelled: &impl Fn() -> bool,
+) -> Option<Vec<(String, Ipv4Addr)>> {
+    let mut stream =
+        TcpStream::connect_timeout(&SocketAddr::new(server, DNS_PORT), TRANSFER_CONNECT_TIMEOUT)
+            .ok()?;
+    stream.set_read_timeout(Some(QUERY_TIMEOUT)).ok()?;
+    let id = query_id();
+    let query = encode_query(id, zone, TYPE_AXFR, false)?;
+    let mut framed = (query.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(&query);
+    stream.write_all(&framed).ok()?;
+
+    let deadline = Instant::now() + TRANSFER_DEADLINE;
+    let mut records = Vec::new();
+    let mut soa_seen = 0;
+    let mut read = 0;
+    while Instant::now() < deadline && !cancelled() {
+        let mut length = [0u8; 2];
+        stream.read_exact(&mut length).ok()?;
+        let mut message = vec![0u8; usize::from(u16::from_be_bytes(length))];
+        stream.read_exact(&mut message).ok()?;
+        let message = parse_message(&message, id)?;
+        if message.rcode != 0 {
+            return None;
+        }
+        for record in message.answers {
+            read += 1;
+            if record.kind == TYPE_SOA {
+                soa_seen += 1;
+                // 区域以 SOA 开头、以同一条 SOA 结尾。
+                if soa_seen == 2 {
+                    return Some(records);
+                }
+            } else if soa_seen == 0 {
+                return None;
+            } else if let RecordData::Address(ip) = record.data {
+                records.push((record.name, ip));
+            }
+        }
+        if read > TRANSFER_RECORD_LIMIT {
+            return None;
+        }
+    }
+    None
+}
+
+fn query_id() -> u16 {
+    use std::sync::atomic::{AtomicU16, Ordering};
+    static COUNTER: AtomicU16 = AtomicU16::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    (nanos as u16 ^ (nanos >> 16) as u16).wrapping_add(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// 名字不合法（空标签或超长）时返回 None。
+fn encode_query(id: u16, name: &str, kind: u16, recursive: bool) -> Option<Vec<u8>> {
+    let name = name.trim_end_matches('.');
+    if name.is_empty() || name.len() > 253 {
+        return None;
+    }
+    let flags = if recursive {
+        FLAGS_RECURSION_DESIRED
+    } else {
+        0
+    };
+    let mut packet = Vec::with_capacity(18 + name.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&flags.to_be_bytes());
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&kind.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Some(packet)
+}
+
+/// 解析应答的回答区；事务号不符、不是应答或报文截断时返回 None。
+fn parse_message(buf: &[u8], id: u16) -> Option<DnsMessage> {
+    let header = buf.get(..12)?;
+    if u16::from_be_bytes([header[0], header[1]]) != id || header[2] & 0x80 == 0 {
+        return None;
+    }
+    let rcode = header[3] & 0x0F;
+    let questions = u16::from_be_bytes([header[4], header[5]]);
+    let answers = u16::from_be_bytes([header[6], header[7]]);
+    let mut off = 12;
+    for _ in 0..questions {
+        off = super::net::skip_dns_name(buf, off)? + 4;
+    }
+    let mut records = Vec::with_capacity(usize::from(answers));
+    for _ in 0..answers {
+        let (name, next) = decode_dns_name(buf, off)?;
+        let fixed = buf.get(next..next + 10)?;
+        let kind = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let class = u16::from_be_bytes([fixed[2], fixed[3]]);
+        let length = usize::from(u16::from_be_bytes([fixed[8], fixed[9]]));
+        let start = next + 10;
+        let data = buf.get(start..start + length)?;
+        let data = match kind {
+            TYPE_A if class == CLASS_IN && length == 4 => {
+                RecordData::Address(Ipv4Addr::new(data[0], data[1], data[2], data[3]))
+            }
+            TYPE_NS | TYPE_PTR => RecordData::Name(decode_dns_name(buf, start)?.0),
+            _ => RecordData::Other,
+        };
+        records.push(DnsRecord { name, kind, data });
+        off = start + length;
+    }
+    Some(DnsMessage {
+        rcode,
+        answers: records,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 应答头 + 问题区（名字 `name`），回答区由调用方追加。
+    fn response(id: u16, rcode: u8, name: &str, kind: u16, answers: u16) -> Vec<u8> {
+        let mut packet = encode_query(id, name, kind, false).unwrap();
+        packet[2] |= 0x80;
+        packet[3] = rcode;
+        packet[6..8].copy_from_slice(&answers.to_be_bytes());
+        packet
+    }
+
+    fn answer(packet: &mut Vec<u8>, kind: u16, data: &[u8]) {
+        // 名字压缩指针指回问题区的名字（偏移 12）
+        packet.extend_from_slice(&[0xC0, 12]);
+        packet.extend_from_slice(&kind.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&300u32.to_be_bytes());
+        packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        packet.extend_from_slice(data);
+    }
+
+    #[test]
+    fn ptr_answers_and_zone_records_are_decoded() {
+        let name = ptr_name(Ipv4Addr::new(10, 1, 2, 3));
+        assert_eq!(name, "3.2.1.10.in-addr.arpa");
+        let mut packet = response(7, 0, &name, TYPE_PTR, 2);
+        answer(&mut packet, TYPE_PTR, b"\x03srv\x04corp\x07example\x00");
+        answer(&mut packet, TYPE_A, &[10, 1, 2, 3]);
+        let message = parse_message(&packet, 7).unwrap();
+        assert_eq!(message.rcode, 0);
+        assert_eq!(
+            message.answers[0].data,
+            RecordData::Name("srv.corp.example".into())
+        );
+        assert_eq!(
+            message.answers[1],
+            DnsRecord {
+                name,
+                kind: TYPE_A,
+                data: RecordData::Address(Ipv4Addr::new(10, 1, 2, 3)),
+            }
+        );
+        assert_eq!(parse_message(&packet, 8), None);
+        assert_eq!(parse_message(&packet[..packet.len() - 1], 7), None);
+    }
+
+    #[test]
+    fn names_are_kept_only_inside_the_zone() {
+        assert!(in_zone("srv.corp.example.com.", "corp.example.com"));
+        assert!(in_zone("CORP.example.com", "corp.example.com"));
+        assert!(!in_zone("notcorp.example.com", "corp.example.com"));
+        assert!(!in_zone("example.com", "corp.example.com"));
+    }
+
+    #[test]
+    fn resolv_conf_lists_plain_nameservers() {
+        let text = "# generated\nsearch corp.example.com\nnameserver 10.0.0.53\n\
+                    nameserver fe80::1%eth0\nnameserver 2001:db8::53\noptions ndots:2\n";
+        assert_eq!(
+            parse_resolv_conf(text),
+            [
+                "10.0.0.53".parse::<IpAddr>().unwrap(),
+                "2001:db8::53".parse().unwrap()
+            ]
+        );
+    }
+}
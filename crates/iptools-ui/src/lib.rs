@@ -1,21 +1,45 @@
 //! Backend-independent Ratatui rendering for iptools.
 
 use iptools_core::{
-    Action, AdapterApplyOutcome, AdapterEditPhase, AdapterField, AdapterValidationError, AppModel,
-    DiagnosticFocus, DiagnosticTool, LanDirection, LanSpeedMode, LanSpeedPhase, Language,
-    LinkQualityDimensionKind, LinkQualityGrade, Page, RuntimeErrorCode, TaskStatus, ThemeId,
+    AbMetric, AbOutcome, AbSide, Action, AdapterApplyOutcome, AdapterEditPhase, AdapterField,
+    AdapterValidationError, AppModel, AuditSeverity, BufferbloatSummary, CURRENT_VERSION,
+    ChartFormat, ChartStyle, ClockFormat, CloudGroup, CloudVerdict, ColorDepth, ColorMode,
+    ConfigIssue, Confirm, Confirmation, CveSeverity, DeviceType, DiagnosticFocus, DiagnosticTool,
+    DnsFilterUpstream, DnsFilterVerdict, DoctorCheck, DoctorCheckKind, DoctorHint, DoctorStatus,
+    Duplex, FTP_PORT, GamingPreset, GuardedScan, IotProtocol, Ipv6Check, Ipv6Verdict, KioskView,
+    LanDirection, LanProtocol, LanSpeedMode, LanSpeedPhase, Language, LinkCounters, LinkMedium,
+    LinkQualityDimensionKind, LinkQualityGrade, LinkWarning, LowPowerMode, MailCheck, MailVerdict,
+    ModuleId, MulticastMode, NatType, NetworkEventKind, Page, Pane, PingMode, PingStats,
+    PipelinePreset, PipelineStage, PipelineState, Popup, RELEASE_NOTES_LINES, RegionFormat, Rgb,
+    RuntimeErrorCode, SCAN_GUARD_MAX_HOSTS, SETTINGS, SHARE_PORTS, SSH_PORT, ScanConcern, ScanHost,
+    ScanMethod, ScanProgress, ScannerView, SettingError, SettingId, SettingKind, SettingsSection,
+    Severity, ShareProtocol, SpeedPhase, StageOutcome, StageStatus, TELNET_PORT, TaskStatus,
+    ThemeId, ThemeOverrides, TimelineKind, TimelineLane, ToolKind, UpnpAction, VoipGrade, VoipMode,
+    VoipSample, WatchHealth, WatchSilence, WatchTile, WebCheckResult, ab_verdict, cloud_endpoints,
+    cloud_verdict, dns_filtering_upstream, doctor_status, dscp_name, encrypted_dns_checks,
+    format_endpoints, format_tabs, format_watch_targets, ipv6_ready, mail_tally, module,
+    release_notes_summary, udp_dns_blocked,
 };
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Clear, Gauge, List, ListItem, Paragraph, Row, Sparkline, Table, Wrap,
+        Axis, Bar, BarChart, BarGroup, Block, BorderType, Borders, Cell, Chart, Clear, Dataset,
+        Gauge, GraphType, List, ListItem, Paragraph, Row, Sparkline, Table, Wrap,
     },
 };
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
 use unicode_width::UnicodeWidthStr;
 
+/// The language switch in the footer is labelled in the language it switches
+/// to, so someone who cannot read the current one can still find it. It is
+/// the one piece of Chinese an English screen carries on purpose.
+pub const LANGUAGE_SWITCH_ZH: &str = "切换语言";
+
 // Keep the established native terminal palette as the visual contract for every backend.
 // Web-specific presentation belongs to the surrounding page, not the TUI.
 const PRIMARY: Color = Color::Green;
@@ -43,12 +67,66 @@ pub struct UiState {
     adapter_fields: Vec<(Rect, AdapterField, u16)>,
     settings_regions: Vec<(Rect, usize)>,
     footer_regions: Vec<(Rect, Action)>,
+    /// The pane without focus in a split view; a click there focuses it.
+    other_pane: Option<Rect>,
     adapter_viewport: usize,
     scanner_viewport: usize,
     traffic_viewport: usize,
+    events_viewport: usize,
+    settings_viewport: usize,
+    scanner_cells: RowCache,
+    traffic_cells: RowCache,
+    /// Rows the current page's list showed in the last frame.
+    page_rows: usize,
+    /// Presenter mode of the last frame; the row caches hold real or
+    /// redacted text accordingly.
+    redacted: bool,
+    /// The search query and its compiled pattern.
+    search: Option<(String, Regex)>,
+}
+
+/// Formatted cells of the visible table rows, reused across frames until the
+/// source revision or the window changes. Only the window is formatted, so a
+/// scan with tens of thousands of hosts costs no more per frame than a short one.
+///
+/// The scanner and traffic tables use one because they are the ones that grow
+/// without a fixed bound and redraw on every tick. The event log and the
+/// Bluetooth list also format only their window, but the log is capped at
+/// [`iptools_core::EVENT_LOG_LIMIT`] and nearby devices number in the tens,
+/// so caching them would not change a frame's cost; the
+/// `render_cost_of_large_tables` benchmark measures all four.
+#[derive(Debug, Default)]
+struct RowCache {
+    key: Option<(u64, usize)>,
+    window: std::ops::Range<usize>,
+    cells: Vec<Vec<String>>,
+}
+
+impl RowCache {
+    fn refresh<T>(
+        &mut self,
+        revision: u64,
+        items: &[T],
+        window: std::ops::Range<usize>,
+        format: impl Fn(&T) -> Vec<String>,
+    ) -> &[Vec<String>] {
+        let key = Some((revision, items.len()));
+        if self.key != key || self.window != window {
+            self.cells = items[window.clone()].iter().map(format).collect();
+            self.key = key;
+            self.window = window;
+        }
+        &self.cells
+    }
 }
 
 impl UiState {
+    /// Rows the list on the current page showed in the last frame; frontends
+    /// copy it into [`AppModel::page_rows`] so paging moves a screen at a time.
+    pub fn page_rows(&self) -> usize {
+        self.page_rows
+    }
+
     pub fn hit_test(&self, column: u16, row: u16) -> Option<Action> {
         if let Some((_, action)) = self
             .overlay_regions
@@ -141,49 +219,439 @@ impl UiState {
         {
             return Some(Action::ActivateScannerPanel);
         }
+        if self
+            .other_pane
+            .is_some_and(|area| contains(area, column, row))
+        {
+            return Some(Action::SwitchPane);
+        }
         None
     }
+
+    /// Forget the click targets a page registered while drawing.
+    fn clear_page_regions(&mut self) {
+        self.diagnostic_regions.clear();
+        self.diagnostic_menu = None;
+        self.diagnostic_main = None;
+        self.diagnostic_config = None;
+        self.diagnostic_fields.clear();
+        self.scanner_input = None;
+        self.scanner_panel = None;
+        self.adapter_regions.clear();
+        self.adapter_fields.clear();
+        self.settings_regions.clear();
+    }
 }
 
 /// Render the shared application model using any Ratatui backend.
 pub fn render(frame: &mut Frame, model: &AppModel, ui: &mut UiState) {
+    REDACT.set(model.redact);
+    TEXT_OVERRIDES.with_borrow_mut(|overrides| overrides.install(&model.overrides.locale));
+    if ui.redacted != model.redact {
+        ui.redacted = model.redact;
+        ui.scanner_cells = RowCache::default();
+        ui.traffic_cells = RowCache::default();
+    }
     ui.overlay_regions.clear();
     ui.page_regions.clear();
-    ui.diagnostic_regions.clear();
-    ui.diagnostic_menu = None;
-    ui.diagnostic_main = None;
-    ui.diagnostic_config = None;
-    ui.diagnostic_fields.clear();
-    ui.scanner_input = None;
-    ui.scanner_panel = None;
-    ui.adapter_regions.clear();
-    ui.adapter_fields.clear();
-    ui.settings_regions.clear();
+    ui.clear_page_regions();
     ui.footer_regions.clear();
-    let areas = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(1),
-        ])
-        .split(frame.area());
+    ui.other_pane = None;
+    if let Some(view) = model.kiosk {
+        render_kiosk(frame, view, model, ui);
+    } else {
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+
+        render_tabs(frame, areas[0], model, ui);
+        let focused = render_body(frame, areas[1], model, ui);
+        if model.search.active() {
+            let matches = highlight_matches(frame, focused, model, ui);
+            render_search(frame, areas[2], model, matches);
+        } else {
+            render_footer(frame, areas[2], model, ui);
+        }
+
+        // Bottom first, so the popup receiving input is drawn on top.
+        for popup in &model.popups {
+            match popup {
+                Popup::Help => render_help(frame, model),
+                Popup::Update => render_update(frame, model),
+                Popup::Doctor => render_doctor(frame, model),
+                Popup::Pipeline => render_pipeline(frame, model),
+                Popup::Export => render_export(frame, model),
+                Popup::Audit => render_audit(frame, model),
+                Popup::Calculator => render_calculator(frame, model),
+                Popup::Timeline => render_timeline(frame, model),
+                Popup::Problems => render_problems(frame, model),
+                Popup::ConfigIssues => render_config_issues(frame, model),
+                Popup::Confirm(confirmation) => render_confirmation(frame, model, confirmation, ui),
+            }
+        }
+    }
+    match model.effective_color_mode() {
+        ColorMode::Standard => apply_theme(frame, model.theme, &model.overrides.theme),
+        ColorMode::ColorBlind => {
+            apply_color_blind_palette(frame);
+            apply_theme(frame, model.theme, &model.overrides.theme);
+        }
+        // Themes are colour too; monochrome ignores them.
+        ColorMode::Monochrome => strip_colors(frame),
+    }
+    reduce_colors(frame, model.terminal.colors);
+    if !model.terminal.unicode {
+        ascii_glyphs(frame);
+    }
+}
+
+/// The current page, or both pages of a split view side by side. The pane
+/// without focus is drawn first and dimmed; its click targets are dropped so
+/// a click there only moves the focus. Returns the area of the focused page.
+fn render_body(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) -> Rect {
+    let Some(split) = model.split else {
+        draw(ModuleId::Page(model.page), frame, area, model, ui);
+        return area;
+    };
+    let halves =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).split(area);
+    let (focused, other) = match split.focus {
+        Pane::Left => (halves[0], halves[1]),
+        Pane::Right => (halves[1], halves[0]),
+    };
+    draw(ModuleId::Page(split.other), frame, other, model, ui);
+    ui.clear_page_regions();
+    frame
+        .buffer_mut()
+        .set_style(other, Style::default().add_modifier(Modifier::DIM));
+    ui.other_pane = Some(other);
+    draw(ModuleId::Page(model.page), frame, focused, model, ui);
+    focused
+}
+
+/// The search query compiled: a regular expression, case-insensitive unless
+/// it has a capital letter, or the plain text when it does not compile. Kept
+/// across frames until the query changes.
+fn search_pattern<'a>(ui: &'a mut UiState, query: &str) -> Option<&'a Regex> {
+    if ui.search.as_ref().is_none_or(|(cached, _)| cached != query) {
+        let ignore_case = !query
+            .chars()
+            .any(|character| character.is_ascii_uppercase());
+        let build = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()
+        };
+        let regex = build(query)
+            .or_else(|_| build(&regex::escape(query)))
+            .ok()?;
+        ui.search = Some((query.to_string(), regex));
+    }
+    ui.search.as_ref().map(|(_, regex)| regex)
+}
 
-    render_tabs(frame, areas[0], model, ui);
-    match model.page {
-        Page::Dashboard => render_dashboard(frame, areas[1], model),
-        Page::Adapters => render_adapters(frame, areas[1], model, ui),
-        Page::Scanner => render_scanner(frame, areas[1], model, ui),
-        Page::Traffic => render_traffic(frame, areas[1], model, ui),
-        Page::Diagnostics => render_diagnostics(frame, areas[1], model, ui),
-        Page::Settings => render_settings(frame, areas[1], model, ui),
+/// Mark every match of the search in what the focused pane drew, the current
+/// one in bold green, and return how many there are. Working on the drawn
+/// cells rather than on each page's data makes every log and table
+/// searchable at once; in presenter mode the masked text is what is searched.
+fn highlight_matches(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) -> usize {
+    if model.search.query.is_empty() {
+        return 0;
+    }
+    let Some(regex) = search_pattern(ui, &model.search.query) else {
+        return 0;
+    };
+    let buffer = frame.buffer_mut();
+    let mut matches = Vec::new();
+    for y in area.top()..area.bottom() {
+        // The row's text, and for each byte the column and width of its cell.
+        let mut text = String::new();
+        let mut cells = Vec::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let symbol = buffer[(x, y)].symbol();
+            let width = symbol.width().max(1) as u16;
+            text.push_str(symbol);
+            cells.extend(std::iter::repeat_n((x, width), symbol.len()));
+            x = x.saturating_add(width);
+        }
+        for found in regex.find_iter(&text).filter(|found| !found.is_empty()) {
+            let (start, _) = cells[found.start()];
+            let (last, width) = cells[found.end() - 1];
+            let end = (last + width).min(area.right());
+            matches.push(Rect::new(start, y, end - start, 1));
+        }
+    }
+    let current = model.search.current(matches.len());
+    for (index, rect) in matches.iter().enumerate() {
+        let style = if Some(index) == current {
+            Style::default()
+                .fg(Color::Black)
+                .bg(PRIMARY)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        };
+        buffer.set_style(*rect, style);
     }
-    render_footer(frame, areas[2], model, ui);
+    matches.len()
+}
 
-    if model.show_help {
-        render_help(frame, model);
+/// The query line in place of the footer, with the match count.
+fn render_search(frame: &mut Frame, area: Rect, model: &AppModel, matches: usize) {
+    let language = model.language;
+    let search = &model.search;
+    let mut spans = vec![Span::styled(
+        " / ",
+        Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD),
+    )];
+    if search.editing {
+        let cursor = search.cursor.min(search.query.len());
+        spans.push(Span::raw(search.query[..cursor].to_string()));
+        spans.push(Span::styled("_", Style::default().fg(PRIMARY)));
+        spans.push(Span::raw(search.query[cursor..].to_string()));
+    } else {
+        spans.push(Span::raw(search.query.clone()));
     }
-    apply_theme(frame, model.theme);
+    let count = match (search.current(matches), language) {
+        _ if search.query.is_empty() => String::new(),
+        (Some(current), _) => format!("{}/{matches}", current + 1),
+        (None, Language::Zh) => "无匹配".to_string(),
+        (None, Language::En) => "no matches".to_string(),
+    };
+    let color = if matches == 0 { Color::Yellow } else { PRIMARY };
+    spans.push(Span::styled(
+        format!("   {count}"),
+        Style::default().fg(color),
+    ));
+    let hint = if search.editing {
+        tr(
+            language,
+            "   Enter 保留高亮   Esc 取消",
+            "   Enter to keep   Esc to cancel",
+        )
+    } else {
+        tr(
+            language,
+            "   n/N 下一个/上一个   Esc 清除",
+            "   n/N next/previous   Esc to clear",
+        )
+    };
+    spans.push(Span::styled(hint, Style::default().fg(MUTED)));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// A tool's parameter rows: label and current value.
+type Fields = Vec<(Cow<'static, str>, String)>;
+
+/// The UI half of a core [`module`]: how one page or diagnostic tool draws
+/// itself and, for tools, its parameter form. Kept in the same order as
+/// [`iptools_core::MODULES`] and looked up by [`ModuleId`] the same way, so a
+/// new tool adds one entry here instead of arms in several `match`es.
+struct View {
+    id: ModuleId,
+    draw: fn(&mut Frame, Rect, &AppModel, &mut UiState),
+    /// Parameter rows: label and current value. Pages have none.
+    fields: fn(&AppModel) -> Fields,
+    /// Whether the parameter row at this index takes typed text; the other
+    /// rows cycle through fixed choices.
+    typed: fn(usize) -> bool,
+}
+
+static VIEWS: [View; 27] = [
+    View {
+        id: ModuleId::Page(Page::Dashboard),
+        draw: |frame, area, model, _| render_dashboard(frame, area, model),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Page(Page::Adapters),
+        draw: |frame, area, model, ui| render_adapters(frame, area, model, ui),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Page(Page::Scanner),
+        draw: |frame, area, model, ui| render_scanner(frame, area, model, ui),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Page(Page::Traffic),
+        draw: |frame, area, model, ui| render_traffic(frame, area, model, ui),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Page(Page::Diagnostics),
+        draw: |frame, area, model, ui| render_diagnostics(frame, area, model, ui),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Page(Page::Events),
+        draw: |frame, area, model, ui| render_events(frame, area, model, ui),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Page(Page::Settings),
+        draw: |frame, area, model, ui| render_settings(frame, area, model, ui),
+        fields: |_| Vec::new(),
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Ping),
+        draw: |frame, area, model, _| render_ping(area, frame, model),
+        fields: ping_fields,
+        typed: |index| index == 0 || index >= 7,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Trace),
+        draw: |frame, area, model, _| render_trace(area, frame, model),
+        fields: trace_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::PortScan),
+        draw: |frame, area, model, _| render_port_scan(area, frame, model),
+        fields: port_scan_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::LinkQuality),
+        draw: |frame, area, model, _| render_link_quality(area, frame, model),
+        fields: link_quality_fields,
+        typed: |index| index >= 1,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::PublicSpeed),
+        draw: |frame, area, model, _| render_public_speed(area, frame, model),
+        fields: public_speed_fields,
+        typed: |_| false,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::LanSpeed),
+        draw: |frame, area, model, _| render_lan_speed(area, frame, model),
+        fields: lan_speed_fields,
+        typed: |index| matches!(index, 1 | 4..),
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Mtu),
+        draw: |frame, area, model, _| render_mtu(area, frame, model),
+        fields: mtu_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Dscp),
+        draw: |frame, area, model, _| render_dscp(area, frame, model),
+        fields: dscp_fields,
+        typed: |index| index != 1,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Multicast),
+        draw: |frame, area, model, _| render_multicast(area, frame, model),
+        fields: multicast_fields,
+        typed: |index| index != 0,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Upnp),
+        draw: |frame, area, model, _| render_upnp(area, frame, model),
+        fields: upnp_fields,
+        typed: |index| index >= 2,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Nat),
+        draw: |frame, area, model, _| render_nat(area, frame, model),
+        fields: nat_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Ipv6),
+        draw: |frame, area, model, _| render_ipv6(area, frame, model),
+        fields: ipv6_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::DnsBench),
+        draw: |frame, area, model, _| render_dns_bench(area, frame, model),
+        fields: dns_bench_fields,
+        typed: |index| index != 1,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::DnsFilter),
+        draw: |frame, area, model, _| render_dns_filter(area, frame, model),
+        fields: dns_filter_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Mail),
+        draw: |frame, area, model, _| render_mail(area, frame, model),
+        fields: mail_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Voip),
+        draw: |frame, area, model, _| render_voip(area, frame, model),
+        fields: voip_fields,
+        typed: |index| matches!(index, 1 | 2 | 4),
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Gaming),
+        draw: |frame, area, model, _| render_gaming(area, frame, model),
+        fields: gaming_fields,
+        typed: |index| index != 0,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Cloud),
+        draw: |frame, area, model, _| render_cloud(area, frame, model),
+        fields: cloud_fields,
+        typed: |_| true,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::Wifi),
+        draw: |frame, area, model, _| render_wifi(area, frame, model),
+        fields: wifi_fields,
+        typed: |index| index == 1,
+    },
+    View {
+        id: ModuleId::Tool(DiagnosticTool::AbTest),
+        draw: |frame, area, model, _| render_ab_test(area, frame, model),
+        fields: ab_test_fields,
+        typed: |_| true,
+    },
+];
+
+fn view(id: ModuleId) -> &'static View {
+    VIEWS
+        .iter()
+        .find(|view| view.id == id)
+        .expect("every registered module has a view")
+}
+
+/// Draw a page or diagnostic tool into the area its host layout assigns.
+fn draw(id: ModuleId, frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    (view(id).draw)(frame, area, model, ui);
+}
+
+#[derive(Clone, Copy)]
+enum PaletteRole {
+    Background,
+    Foreground,
+    Selection,
+    Muted,
+    Subtle,
+    Ghost,
+    Green,
+    Cyan,
+    Yellow,
+    Red,
 }
 
 #[derive(Clone, Copy)]
@@ -200,12 +668,62 @@ struct ThemePalette {
     red: Color,
 }
 
-fn apply_theme(frame: &mut Frame, theme: ThemeId) {
-    if theme == ThemeId::Classic {
+impl ThemePalette {
+    fn get(self, role: PaletteRole) -> Color {
+        match role {
+            PaletteRole::Background => self.background,
+            PaletteRole::Foreground => self.foreground,
+            PaletteRole::Selection => self.selection,
+            PaletteRole::Muted => self.muted,
+            PaletteRole::Subtle => self.subtle,
+            PaletteRole::Ghost => self.ghost,
+            PaletteRole::Green => self.green,
+            PaletteRole::Cyan => self.cyan,
+            PaletteRole::Yellow => self.yellow,
+            PaletteRole::Red => self.red,
+        }
+    }
+}
+
+fn custom_color(custom: &ThemeOverrides, role: PaletteRole) -> Option<Rgb> {
+    match role {
+        PaletteRole::Background => custom.background,
+        PaletteRole::Foreground => custom.foreground,
+        PaletteRole::Selection => custom.selection,
+        PaletteRole::Muted => custom.muted,
+        PaletteRole::Subtle => custom.subtle,
+        PaletteRole::Ghost => custom.ghost,
+        PaletteRole::Green => custom.green,
+        PaletteRole::Cyan => custom.cyan,
+        PaletteRole::Yellow => custom.yellow,
+        PaletteRole::Red => custom.red,
+    }
+}
+
+/// Recolour the frame with the selected theme and then the user's theme
+/// file; a colour the file leaves out keeps the theme's, and under Classic
+/// the colour the page drew.
+fn apply_theme(frame: &mut Frame, theme: ThemeId, custom: &ThemeOverrides) {
+    let palette = theme_palette(theme);
+    if palette.is_none() && custom.is_empty() {
         return;
     }
-    let palette = match theme {
-        ThemeId::Classic => unreachable!(),
+    let remap = |color, background| match palette_role(color, background) {
+        Some(role) => custom_color(custom, role)
+            .map(|Rgb(red, green, blue)| Color::Rgb(red, green, blue))
+            .or_else(|| palette.map(|palette| palette.get(role)))
+            .unwrap_or(color),
+        None => color,
+    };
+    for cell in &mut frame.buffer_mut().content {
+        cell.fg = remap(cell.fg, false);
+        cell.bg = remap(cell.bg, true);
+    }
+}
+
+fn theme_palette(theme: ThemeId) -> Option<ThemePalette> {
+    Some(match theme {
+        ThemeId::Classic => return None,
         ThemeId::Nord => ThemePalette {
             background: Color::Rgb(46, 52, 64),
             foreground: Color::Rgb(236, 239, 244),
@@ -242,41 +760,264 @@ fn apply_theme(frame: &mut Frame, theme: ThemeId) {
             yellow: Color::Rgb(241, 250, 140),
             red: Color::Rgb(255, 85, 85),
         },
+    })
+}
+
+/// Swap the red/green semantics for the Okabe–Ito blue, vermillion and
+/// yellow, which stay apart under protanopia, deuteranopia and tritanopia.
+/// Runs before the theme, which leaves RGB colours alone.
+fn apply_color_blind_palette(frame: &mut Frame) {
+    let remap = |color| match color {
+        Color::Green | Color::LightGreen => Color::Rgb(86, 180, 233),
+        Color::Red | Color::LightRed => Color::Rgb(213, 94, 0),
+        Color::Yellow | Color::LightYellow => Color::Rgb(240, 228, 66),
+        other => other,
     };
     for cell in &mut frame.buffer_mut().content {
-        cell.fg = remap_color(cell.fg, palette, false);
-        cell.bg = remap_color(cell.bg, palette, true);
-    }
-}
-
-fn remap_color(color: Color, palette: ThemePalette, background: bool) -> Color {
-    match color {
-        Color::Reset if background => palette.background,
-        Color::Reset | Color::White => palette.foreground,
-        Color::Black => palette.background,
-        Color::DarkGray if background => palette.selection,
-        Color::DarkGray => palette.subtle,
-        Color::Indexed(244) => palette.ghost,
-        Color::Gray => palette.muted,
-        Color::Green | Color::LightGreen => palette.green,
-        Color::Cyan | Color::LightCyan | Color::Blue | Color::LightBlue => palette.cyan,
-        Color::Yellow | Color::LightYellow => palette.yellow,
-        Color::Red | Color::LightRed => palette.red,
-        other => other,
+        cell.fg = remap(cell.fg);
+        cell.bg = remap(cell.bg);
     }
 }
 
-fn render_tabs(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
-    let block = Block::default().borders(Borders::ALL).title(if model.demo {
-        " IP Tools CLI · DEMO "
+/// Drop every colour. Highlighted cells, which only differ by background,
+/// are shown reversed instead.
+fn strip_colors(frame: &mut Frame) {
+    for cell in &mut frame.buffer_mut().content {
+        if cell.bg != Color::Reset {
+            cell.modifier |= Modifier::REVERSED;
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+/// Latency or throughput history: sparkline blocks, or a braille line at
+/// twice the horizontal resolution when the chart style asks for it and the
+/// terminal can show it.
+fn render_history(
+    frame: &mut Frame,
+    area: Rect,
+    model: &AppModel,
+    block: Block,
+    data: &[u64],
+    color: Color,
+) {
+    if model.chart_style == ChartStyle::Blocks || !model.terminal.unicode {
+        frame.render_widget(
+            Sparkline::default()
+                .block(block)
+                .data(data)
+                .style(Style::default().fg(color)),
+            area,
+        );
+        return;
+    }
+    // Two braille dots per cell: keep the newest samples that fit.
+    let capacity = (block.inner(area).width as usize * 2).max(2);
+    let recent = &data[data.len().saturating_sub(capacity)..];
+    let points = recent
+        .iter()
+        .enumerate()
+        .map(|(index, value)| (index as f64, *value as f64))
+        .collect::<Vec<_>>();
+    let max = recent.iter().copied().max().unwrap_or_default().max(1);
+    frame.render_widget(
+        Chart::new(vec![
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(&points),
+        ])
+        .block(block)
+        .x_axis(Axis::default().bounds([0.0, (capacity - 1) as f64]))
+        .y_axis(Axis::default().bounds([0.0, max as f64])),
+        area,
+    );
+}
+
+/// xterm's defaults for the 16 ANSI colours, which legacy consoles
+/// approximate closely enough to pick the nearest.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Bring RGB and 256-colour cells down to what the terminal can show.
+fn reduce_colors(frame: &mut Frame, depth: ColorDepth) {
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+    for cell in &mut frame.buffer_mut().content {
+        cell.fg = reduce_color(cell.fg, depth);
+        cell.bg = reduce_color(cell.bg, depth);
+    }
+}
+
+fn reduce_color(color: Color, depth: ColorDepth) -> Color {
+    let rgb = match color {
+        Color::Rgb(red, green, blue) => (red, green, blue),
+        Color::Indexed(index) if depth == ColorDepth::Ansi16 => indexed_rgb(index),
+        other => return other,
+    };
+    match depth {
+        ColorDepth::TrueColor => color,
+        ColorDepth::Ansi256 => Color::Indexed(nearest_indexed(rgb)),
+        ColorDepth::Ansi16 => {
+            ANSI16
+                .iter()
+                .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+                .expect("palette is not empty")
+                .0
+        }
+    }
+}
+
+fn distance(left: (u8, u8, u8), right: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    channel(left.0, right.0) + channel(left.1, right.1) + channel(left.2, right.2)
+}
+
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Nearest entry of the 6×6×6 cube or the grey ramp; the 16 base colours
+/// are skipped because terminals redefine them.
+fn nearest_indexed(rgb: (u8, u8, u8)) -> u8 {
+    let level = |value: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| value.abs_diff(**level))
+            .map_or(0, |(index, _)| index as u8)
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+    if distance(rgb, indexed_rgb(grey)) < distance(rgb, indexed_rgb(cube)) {
+        grey
     } else {
-        " IP Tools CLI "
-    });
+        cube
+    }
+}
+
+/// Replace glyphs that legacy console fonts lack with ASCII of the same
+/// width. Box drawing stays: every console code page has it.
+fn ascii_glyphs(frame: &mut Frame) {
+    for cell in &mut frame.buffer_mut().content {
+        let replacement = match cell.symbol() {
+            "↑" | "▲" => "^",
+            "↓" | "▼" => "v",
+            "→" | "▶" | "▸" => ">",
+            "←" | "◀" => "<",
+            "●" | "◆" | "•" | "★" => "*",
+            "·" | "…" => ".",
+            "—" | "–" => "-",
+            "✓" => "+",
+            "✗" | "×" | "⊘" => "x",
+            "▁" | "░" => ".",
+            "▂" => ":",
+            "▃" => "-",
+            "▄" => "=",
+            "▅" => "+",
+            "▆" => "*",
+            "▇" | "█" | "▓" | "▒" => "#",
+            "▏" | "▎" | "▍" => "|",
+            "▌" | "▋" | "▊" | "▉" => "#",
+            _ => continue,
+        };
+        cell.set_symbol(replacement);
+    }
+}
+
+/// Symbol spelling out a state that colour alone shows, in the colour modes
+/// where colour cannot be relied on.
+fn state_mark(model: &AppModel, mark: &'static str) -> &'static str {
+    if model.effective_color_mode() == ColorMode::Standard {
+        ""
+    } else {
+        mark
+    }
+}
+
+/// Gauges draw their empty part with the selection background, which
+/// monochrome would reverse into a full bar.
+fn gauge_style(model: &AppModel, color: Color) -> Style {
+    if model.effective_color_mode() == ColorMode::Monochrome {
+        Style::default()
+    } else {
+        Style::default().fg(color).bg(SELECTED)
+    }
+}
+
+/// The palette role a colour the pages draw with stands for.
+fn palette_role(color: Color, background: bool) -> Option<PaletteRole> {
+    Some(match color {
+        Color::Reset if background => PaletteRole::Background,
+        Color::Reset | Color::White => PaletteRole::Foreground,
+        Color::Black => PaletteRole::Background,
+        Color::DarkGray if background => PaletteRole::Selection,
+        Color::DarkGray => PaletteRole::Subtle,
+        Color::Indexed(244) => PaletteRole::Ghost,
+        Color::Gray => PaletteRole::Muted,
+        Color::Green | Color::LightGreen => PaletteRole::Green,
+        Color::Cyan | Color::LightCyan | Color::Blue | Color::LightBlue => PaletteRole::Cyan,
+        Color::Yellow | Color::LightYellow => PaletteRole::Yellow,
+        Color::Red | Color::LightRed => PaletteRole::Red,
+        _ => return None,
+    })
+}
+
+fn render_tabs(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    let block = Block::default().borders(Borders::ALL).title(
+        [
+            (model.demo, "DEMO"),
+            (model.offline, "OFFLINE"),
+            (model.redact, "REDACTED"),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .fold(" IP Tools CLI ".to_string(), |title, (_, tag)| {
+            format!("{title}· {tag} ")
+        }),
+    );
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     let mut x = inner.x;
-    for (index, page) in Page::ALL.into_iter().enumerate() {
+    let tabs = model.tabs();
+    for (index, page) in tabs.iter().copied().enumerate() {
         if x >= inner.right() {
             break;
         }
@@ -294,13 +1035,17 @@ fn render_tabs(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState
                 )
                 .bg(SELECTED)
                 .add_modifier(Modifier::BOLD)
+        } else if model.shows(page) {
+            Style::default()
+                .fg(PRIMARY)
+                .add_modifier(Modifier::UNDERLINED)
         } else {
             Style::default().fg(PRIMARY)
         };
         frame.render_widget(Paragraph::new(label).style(style), tab);
         ui.page_regions.push((tab, page));
         x = x.saturating_add(width);
-        if index + 1 < Page::ALL.len() && x < inner.right() {
+        if index + 1 < tabs.len() && x < inner.right() {
             frame.render_widget(
                 Paragraph::new("|").style(Style::default().fg(MUTED)),
                 Rect::new(x, inner.y, 1, 1),
@@ -308,9 +1053,30 @@ fn render_tabs(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState
             x = x.saturating_add(1);
         }
     }
+
+    // A newer release is a quiet marker at the right end; it opens the notes.
+    if let Some(release) = &model.update.available {
+        let label = format!(" ↑ {} ", release.version);
+        let width = label.width() as u16;
+        if x.saturating_add(width) < inner.right() {
+            let marker = Rect::new(inner.right() - width, inner.y, width, 1);
+            frame.render_widget(
+                Paragraph::new(label).style(Style::default().fg(SECONDARY)),
+                marker,
+            );
+            ui.overlay_regions.push((marker, Action::ShowUpdate));
+        }
+    }
 }
 
 fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
+    let area = if model.watch.tiles.is_empty() {
+        area
+    } else {
+        let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(4)]).split(area);
+        render_watch_tiles(frame, rows[1], model);
+        rows[0]
+    };
     let cols = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
@@ -323,13 +1089,15 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
                 tr(model.language, "当前时间", "Current Time"),
                 key,
             )),
-            Cell::from(snapshot.observed_at.clone()),
+            Cell::from(model.display_time(&snapshot.observed_at)),
         ]),
         Row::new(vec![
             Cell::from(Span::styled(tr(model.language, "主机名", "Hostname"), key)),
             Cell::from(format!(
                 "{} ({} {})",
-                snapshot.hostname, snapshot.os_name, snapshot.os_version
+                shown_host(&snapshot.hostname),
+                snapshot.os_name,
+                snapshot.os_version
             )),
         ]),
         Row::new(vec![Cell::from(""), Cell::from("")]),
@@ -337,7 +1105,7 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
     if let Some(interface) = &snapshot.active_interface {
         let name = interface.ssid.as_ref().map_or_else(
             || interface.name.clone(),
-            |ssid| format!("{} (SSID: {ssid})", interface.name),
+            |ssid| format!("{} (SSID: {})", interface.name, shown_ssid(ssid)),
         );
         local.push(
             Row::new(vec![
@@ -371,7 +1139,7 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
                     tr(model.language, "虚拟/软件网卡", "Virtual")
                 },
                 if interface.dhcp_enabled {
-                    "DHCP"
+                    "DHCP".into()
                 } else {
                     tr(model.language, "静态 (Static)", "Static")
                 }
@@ -379,8 +1147,14 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
         ]));
         local.push(Row::new(vec![
             Cell::from(Span::styled(tr(model.language, "本机 IP", "Local IP"), key)),
-            Cell::from(interface.ipv4.clone()),
+            Cell::from(shown_ip(&interface.ipv4)),
         ]));
+        if let Some(gateway) = &interface.gateway {
+            local.push(Row::new(vec![
+                Cell::from(Span::styled(tr(model.language, "默认网关", "Gateway"), key)),
+                Cell::from(shown_ip(gateway)),
+            ]));
+        }
         local.push(Row::new(vec![Cell::from(""), Cell::from("")]));
     } else {
         local.push(Row::new(vec![
@@ -403,11 +1177,17 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
             )),
             Cell::from(Line::from(vec![
                 Span::styled(
-                    format!("↓ {:<10}", format_rate(snapshot.download_bps)),
+                    format!(
+                        "↓ {:<10}",
+                        format_rate(model.region_format, snapshot.download_bps)
+                    ),
                     Style::default().fg(Color::Green),
                 ),
                 Span::styled(
-                    format!("↑ {:<10}", format_rate(snapshot.upload_bps)),
+                    format!(
+                        "↑ {:<10}",
+                        format_rate(model.region_format, snapshot.upload_bps)
+                    ),
                     Style::default().fg(Color::Yellow),
                 ),
             ])),
@@ -420,9 +1200,9 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
             Cell::from(format!(
                 "{}: {:<10}{}: {:<10}",
                 tr(model.language, "接收", "RX"),
-                format_bytes(snapshot.total_download),
+                format_bytes(model.region_format, snapshot.total_download),
                 tr(model.language, "发送", "TX"),
-                format_bytes(snapshot.total_upload)
+                format_bytes(model.region_format, snapshot.total_upload)
             )),
         ]),
     ]);
@@ -436,11 +1216,10 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
             ))),
         cols[0],
     );
-    let proxy =
-        snapshot
-            .proxy
-            .as_deref()
-            .unwrap_or(tr(model.language, "无 (直连)", "None (Direct)"));
+    let proxy = snapshot.proxy.as_deref().map_or_else(
+        || tr(model.language, "无 (直连)", "None (Direct)"),
+        Cow::from,
+    );
     let mut public = vec![
         Row::new(vec![
             Cell::from(Span::styled(
@@ -458,9 +1237,39 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
         ]),
         Row::new(vec![Cell::from(""), Cell::from("")]),
     ];
-    if let Some(info) = &snapshot.public_info {
+    if model.offline {
+        let offline = Style::default().fg(SUBTLE);
+        public.extend([
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    tr(model.language, "公网 IP", "Public IP"),
+                    key,
+                )),
+                Cell::from(Span::styled(
+                    tr(
+                        model.language,
+                        "离线模式（未查询）",
+                        "Offline mode (not looked up)",
+                    ),
+                    offline,
+                )),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    tr(model.language, "地理位置", "Location"),
+                    key,
+                )),
+                Cell::from(Span::styled(
+                    tr(model.language, "离线模式", "Offline mode"),
+                    offline,
+                )),
+            ]),
+        ]);
+    } else if let Some(info) = &snapshot.public_info {
         let location = [&info.city, &info.region, &info.country]
             .into_iter()
+            // Presenter mode keeps the country but not where in it.
+            .skip(if REDACT.get() { 2 } else { 0 })
             .filter(|part| !part.is_empty())
             .cloned()
             .collect::<Vec<_>>()
@@ -472,7 +1281,7 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
                     key,
                 )),
                 Cell::from(Span::styled(
-                    info.ip.clone(),
+                    shown_ip(&info.ip),
                     Style::default()
                         .fg(Color::LightCyan)
                         .add_modifier(Modifier::BOLD),
@@ -487,7 +1296,11 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
             ]),
             Row::new(vec![
                 Cell::from(Span::styled(tr(model.language, "运营商", "ISP"), key)),
-                Cell::from(info.isp.clone()),
+                Cell::from(if REDACT.get() && !info.isp.is_empty() {
+                    "Example ISP".into()
+                } else {
+                    info.isp.clone()
+                }),
             ]),
         ]);
     } else {
@@ -539,37 +1352,319 @@ fn render_dashboard(frame: &mut Frame, area: Rect, model: &AppModel) {
     );
 }
 
-fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
-    if let Some(edit) = &model.adapters.edit {
-        render_adapter_edit(frame, area, model, edit, ui);
-        return;
-    }
-    let cols =
-        Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).split(area);
-    let list_inner = Block::bordered().inner(cols[0]);
-    let visible = visible_range(
-        model.adapters.items.len(),
-        model.adapters.selected,
-        list_inner.height as usize,
-        &mut ui.adapter_viewport,
+/// One bordered tile per watch target, coloured by health; the mark keeps
+/// the state readable without colour. A budgeted target shows its latency
+/// against the budget; a silenced one says why instead of its address.
+/// Colour, status line and detail line of a watch tile.
+fn watch_tile_text(model: &AppModel, tile: &WatchTile) -> (Color, String, String) {
+    let language = model.language;
+    let (color, mark) = match tile.health() {
+        WatchHealth::Pending => (MUTED, ""),
+        WatchHealth::Up => (PRIMARY, state_mark(model, "✓ ")),
+        WatchHealth::Degraded => (Color::Yellow, state_mark(model, "! ")),
+        WatchHealth::Down => (Color::Red, state_mark(model, "✗ ")),
+    };
+    let status = match tile.last() {
+        _ if model.offline => tr(language, "已暂停（离线）", "paused (offline)").to_string(),
+        None => "…".to_string(),
+        Some(check) => match (check.latency_ms, tile.target.budget_ms) {
+            (Some(latency), Some(budget)) => format!("{mark}{latency:.0} / {budget} ms"),
+            (Some(latency), None) => format!("{mark}{latency:.0} ms"),
+            (None, _) => format!("{mark}{}", tr(language, "不可达", "down")),
+        },
+    };
+    let silence = model.watch.silence(
+        tile,
+        model.elapsed_ms,
+        &model.dashboard.snapshot.observed_at,
     );
-    for (row, index) in visible.clone().enumerate() {
-        ui.adapter_regions.push((
-            Rect::new(list_inner.x, list_inner.y + row as u16, list_inner.width, 1),
-            index,
-        ));
-    }
-    let items = model
-        .adapters
-        .items
-        .iter()
+    let detail = match silence {
+        Some(WatchSilence::Snoozed { left_ms }) => match language {
+            Language::Zh => format!("告警暂停 {} 分钟", left_ms.div_ceil(60_000)),
+            Language::En => format!("snoozed {} min", left_ms.div_ceil(60_000)),
+        },
+        Some(WatchSilence::Maintenance(window)) => match language {
+            Language::Zh => format!("维护时段 {window}"),
+            Language::En => format!("maintenance {window}"),
+        },
+        None => tile
+            .last()
+            .and_then(|check| check.error.clone())
+            .unwrap_or_else(|| tile.target.target.clone()),
+    };
+    (color, status, detail)
+}
+
+fn render_watch_tiles(frame: &mut Frame, area: Rect, model: &AppModel) {
+    let tiles = &model.watch.tiles;
+    let cells = Layout::horizontal(vec![Constraint::Fill(1); tiles.len()]).split(area);
+    for (tile, cell) in tiles.iter().zip(cells.iter()) {
+        let (color, status, detail) = watch_tile_text(model, tile);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(Span::styled(
+                    status,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(detail, Style::default().fg(MUTED))),
+            ])
+            .block(
+                Block::bordered()
+                    .border_style(Style::default().fg(color))
+                    .title(format!(" {} ", tile.target.name)),
+            ),
+            *cell,
+        );
+    }
+}
+
+/// Rows of the kiosk's large digits.
+const BIG_GLYPH_HEIGHT: u16 = 5;
+
+/// Five-row block glyphs for digits, `:`, `.` and `-`; other characters
+/// become a gap.
+fn big_glyph(c: char) -> [&'static str; 5] {
+    match c {
+        '0' => ["███", "█ █", "█ █", "█ █", "███"],
+        '1' => [" █ ", "██ ", " █ ", " █ ", "███"],
+        '2' => ["███", "  █", "███", "█  ", "███"],
+        '3' => ["███", "  █", "███", "  █", "███"],
+        '4' => ["█ █", "█ █", "███", "  █", "  █"],
+        '5' => ["███", "█  ", "███", "  █", "███"],
+        '6' => ["███", "█  ", "███", "█ █", "███"],
+        '7' => ["███", "  █", "  █", "  █", "  █"],
+        '8' => ["███", "█ █", "███", "█ █", "███"],
+        '9' => ["███", "█ █", "███", "  █", "███"],
+        ':' => [" ", "█", " ", "█", " "],
+        '.' => [" ", " ", " ", " ", "█"],
+        '-' => ["   ", "   ", "███", "   ", "   "],
+        _ => ["  ", "  ", "  ", "  ", "  "],
+    }
+}
+
+/// `text` in large digits, one glyph column apart.
+fn big_text(text: &str, style: Style) -> Vec<Line<'static>> {
+    (0..BIG_GLYPH_HEIGHT as usize)
+        .map(|row| {
+            let line = text
+                .chars()
+                .map(|c| big_glyph(c)[row])
+                .collect::<Vec<_>>()
+                .join(" ");
+            Line::from(Span::styled(line, style))
+        })
+        .collect()
+}
+
+fn big_text_width(text: &str) -> u16 {
+    let glyphs = text.chars().map(|c| big_glyph(c)[0].chars().count());
+    (glyphs.clone().sum::<usize>() + glyphs.count().saturating_sub(1)) as u16
+}
+
+/// Kiosk mode: one view on the whole screen under a title line, with no
+/// tab bar, footer, popups or clickable regions.
+fn render_kiosk(frame: &mut Frame, view: KioskView, model: &AppModel, ui: &mut UiState) {
+    let areas = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(frame.area());
+    let snapshot = &model.dashboard.snapshot;
+    let mut title = vec![Span::styled(
+        format!(" {} ", shown_host(&snapshot.hostname)),
+        Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+    )];
+    if model.offline {
+        title.push(Span::styled(
+            "· OFFLINE ",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(title)), areas[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("{} ", model.display_time(&snapshot.observed_at)),
+            Style::default().fg(MUTED),
+        ))
+        .alignment(Alignment::Right),
+        areas[0],
+    );
+    let area = areas[1];
+    match view {
+        KioskView::Dashboard => {
+            let rows =
+                Layout::vertical([Constraint::Length(BIG_GLYPH_HEIGHT + 1), Constraint::Min(0)])
+                    .split(area);
+            let clock = snapshot
+                .observed_at
+                .split_once(' ')
+                .map_or(String::new(), |(_, time)| model.clock_format.time(time));
+            frame.render_widget(
+                Paragraph::new(big_text(
+                    // The header line above carries AM/PM.
+                    clock.trim_end_matches([' ', 'A', 'P', 'M']),
+                    Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+                ))
+                .alignment(Alignment::Center),
+                rows[0],
+            );
+            render_dashboard(frame, rows[1], model);
+        }
+        KioskView::Traffic => {
+            let rows =
+                Layout::vertical([Constraint::Length(BIG_GLYPH_HEIGHT + 2), Constraint::Min(0)])
+                    .split(area);
+            render_kiosk_rates(frame, rows[0], model);
+            render_traffic(frame, rows[1], model, ui);
+        }
+        KioskView::Monitor => render_kiosk_monitor(frame, area, model),
+    }
+}
+
+/// Download and upload of the active interface, or of all interfaces when
+/// none is active, in large digits.
+fn render_kiosk_rates(frame: &mut Frame, area: Rect, model: &AppModel) {
+    let rows = &model.traffic.rows;
+    let active = model
+        .dashboard
+        .snapshot
+        .active_interface
+        .as_ref()
+        .and_then(|interface| rows.iter().find(|row| row.name == interface.name));
+    let (name, download, upload) = match active {
+        Some(row) => (row.name.clone(), row.download_bps, row.upload_bps),
+        None => (
+            tr(model.language, "全部接口", "All interfaces").to_string(),
+            rows.iter().map(|row| row.download_bps).sum(),
+            rows.iter().map(|row| row.upload_bps).sum(),
+        ),
+    };
+    let cols = Layout::horizontal([Constraint::Fill(1); 2]).split(area);
+    for ((label, rate, color), col) in [
+        (
+            tr(model.language, "下载", "Download"),
+            download,
+            Color::Green,
+        ),
+        (tr(model.language, "上传", "Upload"), upload, Color::Yellow),
+    ]
+    .into_iter()
+    .zip(cols.iter())
+    {
+        let rate = format_rate(model.region_format, rate);
+        let (amount, unit) = rate.split_once(' ').unwrap_or((&rate, ""));
+        let block = Block::bordered()
+            .border_style(Style::default().fg(color))
+            .title(format!(" {label} · {name} "))
+            .title_bottom(Line::from(format!(" {unit} ")).right_aligned());
+        frame.render_widget(
+            Paragraph::new(big_text(
+                amount,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Center)
+            .block(block),
+            *col,
+        );
+    }
+}
+
+/// Watch tiles in a grid filling the screen, latencies in large digits
+/// where a tile has room for them.
+fn render_kiosk_monitor(frame: &mut Frame, area: Rect, model: &AppModel) {
+    let tiles = &model.watch.tiles;
+    if tiles.is_empty() {
+        frame.render_widget(
+            Paragraph::new(tr(
+                model.language,
+                "没有监视目标：在设置页「监视」中添加。",
+                "No watch targets: add some under Settings › Monitoring.",
+            ))
+            .style(Style::default().fg(MUTED))
+            .alignment(Alignment::Center)
+            .block(Block::bordered()),
+            area,
+        );
+        return;
+    }
+    let columns = (1..).find(|n| n * n >= tiles.len()).unwrap_or(1);
+    let grid_rows = tiles.len().div_ceil(columns);
+    let rows = Layout::vertical(vec![Constraint::Fill(1); grid_rows]).split(area);
+    for (row, chunk) in rows.iter().zip(tiles.chunks(columns)) {
+        let cells = Layout::horizontal(vec![Constraint::Fill(1); columns]).split(*row);
+        for (tile, cell) in chunk.iter().zip(cells.iter()) {
+            let (color, status, detail) = watch_tile_text(model, tile);
+            let block = Block::bordered()
+                .border_style(Style::default().fg(color))
+                .title(Span::styled(
+                    format!(" {} ", tile.target.name),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ));
+            let inner = block.inner(*cell);
+            frame.render_widget(block, *cell);
+            let latency = match tile.last() {
+                Some(check) if !model.offline => check
+                    .latency_ms
+                    .map_or_else(|| "-".to_string(), |latency| format!("{latency:.0}")),
+                _ => String::new(),
+            };
+            let mut lines = Vec::new();
+            if !latency.is_empty()
+                && inner.height >= BIG_GLYPH_HEIGHT + 2
+                && inner.width >= big_text_width(&latency)
+            {
+                lines.extend(big_text(
+                    &latency,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ));
+            }
+            lines.push(Line::from(Span::styled(
+                status,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(Span::styled(detail, Style::default().fg(MUTED))));
+            let top = inner.height.saturating_sub(lines.len() as u16) / 2;
+            frame.render_widget(
+                Paragraph::new(lines).alignment(Alignment::Center),
+                Rect::new(
+                    inner.x,
+                    inner.y + top,
+                    inner.width,
+                    inner.height.saturating_sub(top),
+                ),
+            );
+        }
+    }
+}
+
+fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    if let Some(edit) = &model.adapters.edit {
+        render_adapter_edit(frame, area, model, edit, ui);
+        return;
+    }
+    let cols =
+        Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).split(area);
+    let list_inner = Block::bordered().inner(cols[0]);
+    ui.page_rows = list_inner.height as usize;
+    let visible = visible_range(
+        model.adapters.items.len(),
+        model.adapters.selected,
+        list_inner.height as usize,
+        &mut ui.adapter_viewport,
+    );
+    for (row, index) in visible.clone().enumerate() {
+        ui.adapter_regions.push((
+            Rect::new(list_inner.x, list_inner.y + row as u16, list_inner.width, 1),
+            index,
+        ));
+    }
+    let items = model
+        .adapters
+        .items
+        .iter()
         .enumerate()
         .skip(visible.start)
         .take(visible.len())
         .map(|(index, adapter)| {
             let selected = index == model.adapters.selected;
             let prefix = if adapter.is_physical { "[P] " } else { "[V] " };
-            let up = adapter_is_up(adapter);
+            let up = adapter.is_up();
             ListItem::new(Line::from(vec![
                 Span::styled(
                     if selected { "> " } else { "  " },
@@ -580,6 +1675,14 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
                     adapter.name.clone(),
                     Style::default().fg(if up { Color::White } else { SUBTLE }),
                 ),
+                Span::styled(
+                    if adapter.link_warning().is_some() {
+                        " !"
+                    } else {
+                        ""
+                    },
+                    Style::default().fg(Color::Yellow),
+                ),
             ]))
             .style(if selected {
                 Style::default().bg(SELECTED)
@@ -599,14 +1702,25 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
     let detail_block = Block::bordered()
         .title(tr(model.language, " 详细信息 ", " Details "))
         .title(
-            Line::from(Span::styled(
-                tr(
-                    model.language,
-                    " [E/回车/空格] 编辑 IP ",
-                    " [E/Enter/Space] Edit IP ",
-                ),
-                Style::default().fg(SECONDARY),
-            ))
+            Line::from(if model.capabilities.adapter_config {
+                Span::styled(
+                    tr(
+                        model.language,
+                        " [E/回车/空格] 编辑 IP ",
+                        " [E/Enter/Space] Edit IP ",
+                    ),
+                    Style::default().fg(SECONDARY),
+                )
+            } else {
+                Span::styled(
+                    tr(
+                        model.language,
+                        " 当前平台不可编辑 ",
+                        " Editing unavailable ",
+                    ),
+                    Style::default().fg(MUTED),
+                )
+            })
             .alignment(Alignment::Right),
         );
     if let Some(adapter) = model.adapters.items.get(model.adapters.selected) {
@@ -634,16 +1748,20 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
                 Cell::from(Span::styled(tr(model.language, "状态", "Status"), key)),
                 Cell::from(Line::from(vec![
                     Span::styled(
-                        tr(
-                            model.language,
-                            if adapter_is_up(adapter) {
-                                "活跃"
-                            } else {
-                                "不活跃"
-                            },
-                            if adapter_is_up(adapter) { "UP" } else { "DOWN" },
+                        format!(
+                            "{}{}",
+                            state_mark(model, if adapter.is_up() { "▲ " } else { "▼ " }),
+                            tr(
+                                model.language,
+                                if adapter.is_up() {
+                                    "活跃"
+                                } else {
+                                    "不活跃"
+                                },
+                                if adapter.is_up() { "UP" } else { "DOWN" },
+                            )
                         ),
-                        Style::default().fg(if adapter_is_up(adapter) {
+                        Style::default().fg(if adapter.is_up() {
                             Color::Green
                         } else {
                             Color::Red
@@ -651,7 +1769,7 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
                     ),
                     Span::raw("  (SSID: "),
                     Span::styled(
-                        adapter.ssid.clone().unwrap_or_else(|| "-".into()),
+                        adapter.ssid.as_deref().map_or("-".into(), shown_ssid),
                         Style::default().fg(if adapter.ssid.is_some() {
                             Color::Yellow
                         } else {
@@ -682,7 +1800,7 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
                     key,
                 )),
                 Cell::from(if adapter.dhcp_enabled {
-                    "DHCP"
+                    "DHCP".into()
                 } else {
                     tr(model.language, "静态 (Static)", "Static")
                 }),
@@ -692,13 +1810,16 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
                     tr(model.language, "物理地址 (MAC)", "MAC Address"),
                     key,
                 )),
-                Cell::from(adapter.mac.clone()),
+                Cell::from(shown_mac(&adapter.mac)),
             ]),
             Row::new(vec![
                 Cell::from(Span::styled("IPv4", key)),
                 Cell::from(format!(
                     "• {}",
-                    adapter.cidr.as_deref().unwrap_or(&adapter.ipv4)
+                    adapter
+                        .cidr
+                        .as_deref()
+                        .map_or_else(|| shown_ip(&adapter.ipv4), shown_text)
                 )),
             ]),
         ];
@@ -711,7 +1832,7 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
                     adapter
                         .ipv6
                         .iter()
-                        .map(|ip| Line::from(format!("• {ip}")))
+                        .map(|ip| Line::from(format!("• {}", shown_text(ip))))
                         .collect()
                 }),
             ])
@@ -725,11 +1846,17 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
             )),
             Cell::from(Line::from(vec![
                 Span::styled(
-                    format!("↓ {:<10}", format_rate(adapter.download_bps)),
+                    format!(
+                        "↓ {:<10}",
+                        format_rate(model.region_format, adapter.download_bps)
+                    ),
                     Style::default().fg(Color::Green),
                 ),
                 Span::styled(
-                    format!("↑ {:<10}", format_rate(adapter.upload_bps)),
+                    format!(
+                        "↑ {:<10}",
+                        format_rate(model.region_format, adapter.upload_bps)
+                    ),
                     Style::default().fg(Color::Yellow),
                 ),
             ])),
@@ -742,11 +1869,73 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
             Cell::from(format!(
                 "{}: {:<10}{}: {:<10}",
                 tr(model.language, "接收", "RX"),
-                format_bytes(adapter.total_download),
+                format_bytes(model.region_format, adapter.total_download),
                 tr(model.language, "发送", "TX"),
-                format_bytes(adapter.total_upload)
+                format_bytes(model.region_format, adapter.total_upload)
             )),
         ]));
+        if let Some(link) = link_summary(model.language, adapter) {
+            let mut lines = vec![Line::from(link)];
+            if let Some(warning) = adapter.link_warning() {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{}{}",
+                        state_mark(model, "⚠ "),
+                        link_warning_text(model.language, warning)
+                    ),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            rows.push(
+                Row::new(vec![
+                    Cell::from(Span::styled(tr(model.language, "链路", "Link"), key)),
+                    Cell::from(lines.clone()),
+                ])
+                .height(lines.len() as u16),
+            );
+        }
+        if let Some(totals) = model.link_health.totals(&adapter.name) {
+            let recent = match model.link_health.window(&adapter.name) {
+                Some(window) => Line::from(Span::styled(
+                    format!(
+                        "{} {} s: {:.2}%  {}",
+                        tr(model.language, "最近", "Last"),
+                        window.seconds,
+                        window.counts.loss_percent(),
+                        link_counts(model.language, &window.counts)
+                    ),
+                    Style::default().fg(if window.alerting {
+                        Color::Red
+                    } else {
+                        Color::White
+                    }),
+                )),
+                None => Line::from(Span::styled(
+                    tr(model.language, "最近：采样中…", "Last: sampling…"),
+                    Style::default().fg(SUBTLE),
+                )),
+            };
+            rows.push(
+                Row::new(vec![
+                    Cell::from(Span::styled(
+                        tr(model.language, "丢包 / 错误", "Drops / Errors"),
+                        key,
+                    )),
+                    Cell::from(vec![
+                        recent,
+                        Line::from(Span::styled(
+                            format!(
+                                "{}: {}",
+                                tr(model.language, "开机累计", "Since boot"),
+                                link_counts(model.language, &totals)
+                            ),
+                            Style::default().fg(SUBTLE),
+                        )),
+                    ]),
+                ])
+                .height(2),
+            );
+        }
         frame.render_widget(
             Table::new(rows, [Constraint::Length(16), Constraint::Min(0)])
                 .column_spacing(1)
@@ -767,15 +1956,6 @@ fn render_adapters(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiS
     }
 }
 
-fn adapter_is_up(adapter: &iptools_core::AdapterInfo) -> bool {
-    let status = adapter.status.to_ascii_lowercase();
-    !adapter.ipv4.is_empty()
-        && adapter.ipv4 != "—"
-        && !["down", "disconnected", "standby", "internal"]
-            .iter()
-            .any(|marker| status.contains(marker))
-}
-
 fn render_adapter_edit(
     frame: &mut Frame,
     area: Rect,
@@ -945,7 +2125,7 @@ fn render_adapter_edit(
             "模拟配置已应用；未修改真实系统。按任意键返回。",
             "Simulated configuration applied; no real system was changed. Press any key to return.",
         ),
-        AdapterEditPhase::Failed(error) => &error.message,
+        AdapterEditPhase::Failed(error) => error.message.as_str().into(),
     };
     let status_style = match edit.phase {
         AdapterEditPhase::Failed(_) => Style::default().fg(Color::Red),
@@ -1037,6 +2217,10 @@ fn render_adapter_edit(
 }
 
 fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    if model.scanner.view == ScannerView::Bluetooth {
+        render_bluetooth(frame, area, model, ui);
+        return;
+    }
     let rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -1055,7 +2239,8 @@ fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiSt
     } else {
         tr(model.language, "开始", "Start")
     };
-    let status = task_label(&model.scanner.status, model.language).trim();
+    let status = task_label(&model.scanner.status, model.language);
+    let status = status.trim();
     let count = if model.scanner.total == 0 {
         scan_address_count(&model.scanner.cidr)
             .map_or_else(|| "—".into(), |count| count.to_string())
@@ -1082,14 +2267,32 @@ fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiSt
             Style::default().fg(GHOST),
         ));
     }
+    // A DNS zone sweep never probes, so its suffix takes the probes' place.
+    let (mode, mode_value) = if model.scanner.dns_zone.is_empty() {
+        (
+            tr(model.language, "端口探测", "Probes"),
+            if model.scanner.enrich {
+                tr(model.language, "开", "On")
+            } else {
+                tr(model.language, "关", "Off")
+            },
+        )
+    } else {
+        (
+            tr(model.language, "仅 DNS", "DNS only"),
+            model.scanner.dns_zone.as_str().into(),
+        )
+    };
     input_spans.push(Span::styled(
         format!(
-            "   {}: {}   [{}]   {} / {}",
+            "   {}: {}   [{}]   {} / {}   {}: {}",
             tr(model.language, "预计数量", "Estimated"),
             count,
             status,
             tr(model.language, "E 编辑范围", "E Edit Range"),
             action,
+            mode,
+            mode_value,
         ),
         input_style,
     ));
@@ -1107,60 +2310,70 @@ fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiSt
         .saturating_add(label.width() as u16);
     let value_width = rows[0].right().saturating_sub(value_x).min(32);
     ui.scanner_input = Some((Rect::new(value_x, rows[0].y + 1, value_width, 1), value_x));
-    ui.scanner_panel = Some(rows[1]);
+    let (table_area, detail_area) = if model.scanner.detail_open {
+        let columns = Layout::horizontal([Constraint::Percentage(62), Constraint::Percentage(38)])
+            .split(rows[1]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (rows[1], None)
+    };
+    ui.scanner_panel = Some(table_area);
     if model.scanner.editing && !model.scanner.history_open {
         frame.set_cursor_position(Position::new(
             value_x.saturating_add(model.scanner.cursor.min(value_width as usize) as u16),
             rows[0].y + 1,
         ));
     }
-    let ratio = if model.scanner.total == 0 {
-        0.0
-    } else {
-        model.scanner.current as f64 / model.scanner.total as f64
-    };
-    let table_inner_height = rows[1].height.saturating_sub(4) as usize;
+    let progress = model.scanner.progress;
+    let ratio = progress.ratio();
+    let table_inner_height = table_area.height.saturating_sub(4) as usize;
     let visible = visible_range(
         model.scanner.results.len(),
         model.scanner.selected,
         table_inner_height,
         &mut ui.scanner_viewport,
     );
-    let table_rows = model
-        .scanner
-        .results
-        .iter()
-        .enumerate()
-        .skip(visible.start)
-        .take(visible.len())
-        .map(|(index, host)| {
-            Row::new(vec![
-                format!(
-                    "{}{}",
-                    if index == model.scanner.selected {
-                        ">> "
-                    } else {
-                        "   "
-                    },
-                    host.ip
-                ),
-                host.mac.clone(),
+    ui.page_rows = table_inner_height;
+    let cells = ui.scanner_cells.refresh(
+        model.scanner.revision,
+        &model.scanner.results,
+        visible.clone(),
+        |host| {
+            vec![
+                shown_ip(&host.ip).into_owned(),
+                shown_mac(&host.mac).into_owned(),
                 if host.vendor.is_empty() {
                     "-".into()
                 } else {
                     host.vendor.clone()
                 },
-                host.hostname.clone(),
-            ])
-            .style(if index == model.scanner.selected {
-                Style::default()
-                    .fg(SECONDARY)
-                    .bg(SELECTED)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            })
-        });
+                shown_host(&host.hostname).into_owned(),
+            ]
+        },
+    );
+    let table_rows = cells.iter().zip(visible).map(|(cells, index)| {
+        Row::new([
+            Cell::from(Line::from(vec![
+                Span::raw(if index == model.scanner.selected {
+                    ">> "
+                } else {
+                    "   "
+                }),
+                Span::raw(cells[0].as_str()),
+            ])),
+            Cell::from(cells[1].as_str()),
+            Cell::from(cells[2].as_str()),
+            Cell::from(cells[3].as_str()),
+        ])
+        .style(if index == model.scanner.selected {
+            Style::default()
+                .fg(SECONDARY)
+                .bg(SELECTED)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        })
+    });
     frame.render_widget(
         Table::new(
             table_rows,
@@ -1181,20 +2394,32 @@ fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiSt
             .style(Style::default().fg(MUTED))
             .bottom_margin(1),
         )
-        .block(Block::bordered().title(format!(
-            " {} ({}) ",
-            tr(model.language, "发现设备", "Devices Found"),
-            model.scanner.results.len()
-        ))),
-        rows[1],
+        .block(Block::bordered().title(if model.scanner.trimmed > 0 {
+            format!(
+                " {} ({} · {}) ",
+                tr(model.language, "发现设备", "Devices Found"),
+                model.scanner.results.len(),
+                trimmed_note(model.language, model.scanner.trimmed)
+            )
+        } else {
+            format!(
+                " {} ({}) ",
+                tr(model.language, "发现设备", "Devices Found"),
+                model.scanner.results.len()
+            )
+        })),
+        table_area,
     );
+    if let Some(detail_area) = detail_area {
+        render_scanner_details(frame, detail_area, model);
+    }
 
     if matches!(model.scanner.status, TaskStatus::Running | TaskStatus::Done) {
         frame.render_widget(
             Gauge::default()
                 .gauge_style(
                     Style::default()
-                        .fg(if model.scanner.status == TaskStatus::Done && ratio < 1.0 {
+                        .fg(if progress.cancelled() > 0 {
                             Color::Yellow
                         } else {
                             SECONDARY
@@ -1202,12 +2427,12 @@ fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiSt
                         .bg(SELECTED),
                 )
                 .ratio(ratio.clamp(0.0, 1.0))
-                .label(format!(
-                    "{:.1}% ({}/{})",
-                    ratio * 100.0,
-                    model.scanner.current,
-                    model.scanner.total
-                )),
+                .label(scan_progress_label(model.language, &progress, ratio)),
+            rows[2],
+        );
+    } else if let TaskStatus::Failed(message) = &model.scanner.status {
+        frame.render_widget(
+            Paragraph::new(format!(" {message}")).style(Style::default().fg(Color::Red)),
             rows[2],
         );
     }
@@ -1255,1632 +2480,6812 @@ fn render_scanner(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiSt
     }
 }
 
-fn render_traffic(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
-    let visible_rows = area.height.saturating_sub(4) as usize / 2;
+/// Nearby Bluetooth LE devices, strongest signal first.
+fn render_bluetooth(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    let language = model.language;
+    let bluetooth = &model.scanner.bluetooth;
+    let rows = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .split(area);
+    frame.render_widget(
+        Paragraph::new(format!(
+            " [{}]   {} {}   {}",
+            task_label(&bluetooth.status, language).trim(),
+            bluetooth.devices.len(),
+            tr(language, "台设备", "devices"),
+            tr(
+                language,
+                "空格 开始/停止 · R 返回 IP 主机",
+                "Space Start/Stop · R back to IP hosts"
+            ),
+        ))
+        .block(Block::bordered().title(tr(
+            language,
+            " 附近的蓝牙设备 ",
+            " Nearby Bluetooth ",
+        ))),
+        rows[0],
+    );
+    let inner_height = rows[1].height.saturating_sub(4) as usize;
     let visible = visible_range(
-        model.traffic.rows.len(),
-        model.traffic.selected,
-        visible_rows,
-        &mut ui.traffic_viewport,
+        bluetooth.devices.len(),
+        bluetooth.selected,
+        inner_height,
+        &mut ui.scanner_viewport,
     );
-    let rows = model
-        .traffic
-        .rows
-        .iter()
-        .enumerate()
-        .skip(visible.start)
-        .take(visible.len())
-        .map(|(index, row)| {
-            Row::new(vec![
-                Cell::from(row.name.clone()),
-                Cell::from(format!("↓ {}", format_rate(row.download_bps))),
-                Cell::from(format!("↑ {}", format_rate(row.upload_bps))),
-                Cell::from(vec![
-                    Line::from(format!("↓ {}", format_bytes(row.session_download))),
-                    Line::from(format!("↑ {}", format_bytes(row.session_upload))),
-                ]),
-                Cell::from(vec![
-                    Line::from(format!("↓ {}", format_bytes(row.total_download))),
-                    Line::from(format!("↑ {}", format_bytes(row.total_upload))),
-                ]),
-            ])
-            .height(2)
-            .style(if index == model.traffic.selected {
-                Style::default().bg(SELECTED)
-            } else {
-                Style::default()
-            })
-        });
-    let inner_width = area.width.saturating_sub(2);
-    let (rate_width, byte_width) = if inner_width >= 100 {
-        (14, 18)
-    } else {
-        (13, 14)
-    };
-    let name_width = inner_width
-        .saturating_sub(rate_width * 2 + byte_width * 2 + 4)
-        .max(8);
+    ui.page_rows = inner_height;
+    let table_rows =
+        bluetooth.devices[visible.clone()]
+            .iter()
+            .zip(visible)
+            .map(|(device, index)| {
+                let selected = index == bluetooth.selected;
+                Row::new([
+                    Cell::from(format!(
+                        "{}{}",
+                        if selected { ">> " } else { "   " },
+                        device.name.as_deref().unwrap_or("-")
+                    )),
+                    Cell::from(device.address.clone()),
+                    Cell::from(
+                        device
+                            .rssi_dbm
+                            .map_or_else(|| "-".into(), |rssi| format!("{rssi} dBm")),
+                    )
+                    .style(Style::default().fg(match device.rssi_dbm {
+                        Some(rssi) if rssi >= -60 => Color::Green,
+                        Some(rssi) if rssi >= -80 => Color::Yellow,
+                        _ => SUBTLE,
+                    })),
+                    Cell::from(device.manufacturer().unwrap_or("-")),
+                    Cell::from(format!("{:.1}s", device.last_seen_ms as f64 / 1_000.0)),
+                ])
+                .style(if selected {
+                    Style::default()
+                        .fg(SECONDARY)
+                        .bg(SELECTED)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                })
+            });
     frame.render_widget(
         Table::new(
-            rows,
+            table_rows,
             [
-                Constraint::Length(name_width),
-                Constraint::Length(rate_width),
-                Constraint::Length(rate_width),
-                Constraint::Length(byte_width),
-                Constraint::Length(byte_width),
+                Constraint::Percentage(30),
+                Constraint::Percentage(26),
+                Constraint::Percentage(12),
+                Constraint::Percentage(20),
+                Constraint::Percentage(12),
             ],
         )
-        .column_spacing(1)
         .header(
-            Row::new(vec![
-                Cell::from(tr(model.language, "接口名称", "Interface Name"))
-                    .style(Style::default().fg(MUTED)),
-                Cell::from(tr(model.language, "下载速率", "Download"))
-                    .style(Style::default().fg(Color::Green)),
-                Cell::from(tr(model.language, "上传速率", "Upload"))
-                    .style(Style::default().fg(Color::Yellow)),
-                Cell::from(tr(model.language, "本次会话", "Session"))
-                    .style(Style::default().fg(MUTED)),
-                Cell::from(tr(model.language, "开机累计", "Since Boot"))
-                    .style(Style::default().fg(MUTED)),
+            Row::new([
+                format!("   {}", tr(language, "名称", "Name")),
+                tr(language, "地址", "Address").into(),
+                tr(language, "信号", "Signal").into(),
+                tr(language, "厂商", "Vendor").into(),
+                tr(language, "最近", "Seen").into(),
             ])
-            .height(1)
+            .style(Style::default().fg(MUTED))
             .bottom_margin(1),
         )
-        .block(Block::bordered().title(tr(
-            model.language,
-            " 实时流量监控 ",
-            " Real-time Monitor ",
+        .block(Block::bordered().title(format!(
+            " {} ({}) ",
+            tr(language, "蓝牙设备", "Bluetooth Devices"),
+            bluetooth.devices.len()
         ))),
-        area,
+        rows[1],
     );
+    if let TaskStatus::Failed(message) = &bluetooth.status {
+        frame.render_widget(
+            Paragraph::new(format!(" {message}")).style(Style::default().fg(Color::Red)),
+            rows[2],
+        );
+    }
 }
 
-fn render_diagnostics(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
-    let common = model.diagnostics.active_common();
-    let cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(20),
-            Constraint::Percentage(50),
-            Constraint::Percentage(30),
-        ])
-        .split(area);
-
-    let focus_style = |focus| {
-        if model.diagnostics.focused && model.diagnostics.focus == focus {
-            Style::default().fg(Color::Yellow)
-        } else {
-            Style::default().fg(MUTED)
+/// Percentage followed by each phase that has work and, while sweeping, the
+/// measured rate, e.g. `42.0%  Sweep 107/254 · Names 5/6 · 3 in flight ·
+/// 180/s · concurrency 50`.
+fn scan_progress_label(language: Language, progress: &ScanProgress, ratio: f64) -> String {
+    let mut parts = Vec::new();
+    for (phase, zh, en) in [
+        (progress.probe, "扫描", "Sweep"),
+        (progress.resolve, "名称", "Names"),
+        (progress.enrich, "端口", "Probes"),
+    ] {
+        if phase.total() > 0 {
+            parts.push(format!(
+                "{} {}/{}",
+                tr(language, zh, en),
+                phase.done,
+                phase.total()
+            ));
         }
-    };
+    }
+    let in_flight = progress
+        .phases()
+        .iter()
+        .map(|phase| phase.in_flight)
+        .sum::<u64>();
+    if in_flight > 0 {
+        parts.push(format!(
+            "{in_flight} {}",
+            tr(language, "进行中", "in flight")
+        ));
+    }
+    if progress.cancelled() > 0 {
+        parts.push(format!(
+            "{} {}",
+            progress.cancelled(),
+            tr(language, "已取消", "cancelled")
+        ));
+    }
+    match progress.method {
+        ScanMethod::Local => {}
+        ScanMethod::Routed => {
+            parts.push(tr(language, "路由网段 ICMP/TCP", "routed ICMP/TCP").to_string())
+        }
+        ScanMethod::ZoneTransfer => {
+            parts.push(tr(language, "区域传送", "zone transfer").to_string())
+        }
+        ScanMethod::Ptr => parts.push(tr(language, "PTR 反查", "PTR sweep").to_string()),
+    }
+    if progress.rate > 0 {
+        parts.push(format!("{}/s", progress.rate));
+    }
+    if progress.workers > 0 {
+        parts.push(format!(
+            "{} {}",
+            tr(language, "并发", "concurrency"),
+            progress.workers
+        ));
+    }
+    format!("{:.1}%  {}", ratio * 100.0, parts.join(" · "))
+}
 
-    let mut items = Vec::new();
-    ui.diagnostic_menu = Some(cols[0]);
-    let menu_inner = Block::bordered().inner(cols[0]);
-    for (index, tool) in DiagnosticTool::ALL.into_iter().enumerate() {
-        let row = Rect::new(
-            menu_inner.x,
-            menu_inner.y + index as u16,
-            menu_inner.width,
-            1,
-        );
-        ui.diagnostic_regions.push((row, index as u8));
-        let selected = tool == model.diagnostics.tool;
-        items.push(
-            ListItem::new(format!(
-                "{}{}",
-                if selected { "> " } else { "  " },
-                tool_label(tool, model.language)
-            ))
-            .style(if selected {
-                Style::default()
-                    .fg(Color::White)
-                    .bg(SELECTED)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            }),
-        );
+/// Everything known about the selected host: names from every resolver,
+/// probe results, discovered services and its inventory record.
+fn device_type_label(kind: DeviceType, language: Language) -> Cow<'static, str> {
+    match kind {
+        DeviceType::Printer => tr(language, "打印机", "Printer"),
+        DeviceType::Camera => tr(language, "摄像头", "Camera"),
+        DeviceType::MqttBroker => tr(language, "MQTT 代理", "MQTT broker"),
+        DeviceType::Controller => tr(language, "Modbus 控制器", "Modbus controller"),
     }
-    frame.render_widget(
-        List::new(items).block(
-            Block::bordered()
-                .title(tr(model.language, " 工具列表 ", " Tools "))
-                .border_style(focus_style(DiagnosticFocus::Menu)),
-        ),
-        cols[0],
-    );
+}
 
-    ui.diagnostic_main = Some(cols[1]);
-    let main_block = Block::bordered()
-        .title(tr(model.language, " 监控面板 ", " Visualization "))
-        .border_style(focus_style(DiagnosticFocus::Main));
-    let main_inner = main_block.inner(cols[1]);
-    frame.render_widget(main_block, cols[1]);
-    if let Some(failure) = diagnostic_failure(common, model.language) {
-        let mut lines = vec![
-            Line::from(Span::styled(
-                tr(model.language, "诊断失败", "Diagnostic failed"),
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            )),
-            Line::from(failure),
-            Line::from(""),
-        ];
-        lines.extend(
-            common
-                .log
-                .iter()
-                .rev()
-                .take(main_inner.height.saturating_sub(4) as usize)
-                .rev()
-                .cloned()
-                .map(Line::from),
-        );
-        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), main_inner);
-    } else {
-        match model.diagnostics.tool {
-            DiagnosticTool::Ping => render_ping(main_inner, frame, model),
-            DiagnosticTool::Trace => render_trace(main_inner, frame, model),
-            DiagnosticTool::PortScan => render_port_scan(main_inner, frame, model),
-            DiagnosticTool::PublicSpeed => render_public_speed(main_inner, frame, model),
-            DiagnosticTool::LinkQuality => render_link_quality(main_inner, frame, model),
-            DiagnosticTool::LanSpeed => render_lan_speed(main_inner, frame, model),
-        }
+/// Marks a view the retention limits have cut: its oldest rows are gone.
+fn trimmed_note(language: Language, count: usize) -> String {
+    match language {
+        Language::Zh => format!("已丢弃最早的 {count} 条"),
+        Language::En => format!("{count} oldest dropped"),
     }
+}
 
-    ui.diagnostic_config = Some(cols[2]);
-    let config_block = Block::bordered()
-        .title(tr(model.language, " 参数配置 ", " Configuration "))
-        .border_style(focus_style(DiagnosticFocus::Config));
-    let config_inner = config_block.inner(cols[2]);
-    frame.render_widget(config_block, cols[2]);
-    let fields = diagnostic_fields(model);
-    for (index, (label, raw_value)) in fields.into_iter().enumerate() {
-        let y = index as u16 * 2;
-        if y + 1 >= config_inner.height {
-            break;
-        }
-        let label_row = Rect::new(config_inner.x, config_inner.y + y, config_inner.width, 1);
-        let value_row = Rect::new(
-            config_inner.x,
-            config_inner.y + y + 1,
-            config_inner.width,
-            1,
-        );
-        let value_x = value_row.x.saturating_add(3.min(value_row.width));
-        ui.diagnostic_fields.push((
-            Rect::new(label_row.x, label_row.y, label_row.width, 2),
-            index,
-            value_x,
-        ));
-        let selected = model.diagnostics.focused
-            && model.diagnostics.focus == DiagnosticFocus::Config
-            && active_diagnostic_config_index(model) == index;
-        let text_editable = match model.diagnostics.tool {
-            DiagnosticTool::Ping => index == 0,
-            DiagnosticTool::Trace => true,
-            DiagnosticTool::PortScan => true,
-            DiagnosticTool::LinkQuality => index >= 1,
-            DiagnosticTool::LanSpeed => matches!(index, 1 | 4..),
-            DiagnosticTool::PublicSpeed => false,
-        };
-        frame.render_widget(
-            Paragraph::new(format!("{label}:")).style(Style::default().fg(if selected {
-                Color::Yellow
-            } else {
-                MUTED
-            })),
-            label_row,
-        );
+/// Known-CVE counts for the software the host announced. Labelled as a
+/// heuristic: a banner version says nothing about backported fixes.
+fn cve_lines(model: &AppModel, host: &ScanHost) -> Vec<Line<'static>> {
+    let language = model.language;
+    let software = iptools_core::host_software(host);
+    if software.is_empty() {
+        return Vec::new();
+    }
+    let cve = &model.scanner.cve;
+    let mut lines = vec![Line::styled(
+        tr(
+            language,
+            "已知 CVE（按版本号推测）",
+            "Known CVEs (heuristic, by banner version)",
+        ),
+        Style::default().fg(MUTED),
+    )];
+    for software in software {
         let mut spans = vec![Span::styled(
-            if selected { ">> " } else { "   " },
-            if selected {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            },
+            format!("{} ", software.label()),
+            Style::default().fg(PRIMARY),
         )];
-        if selected && common.job.is_none() && text_editable {
-            let cursor = model.diagnostics.cursor.min(raw_value.len());
-            spans.push(Span::styled(
-                raw_value[..cursor].to_string(),
-                Style::default().fg(Color::Yellow),
-            ));
-            spans.push(Span::styled(
-                raw_value[cursor..].to_string(),
-                Style::default().fg(Color::Yellow),
-            ));
-            if index == diagnostic_target_index(model.diagnostics.tool)
-                && !raw_value.is_empty()
-                && cursor == raw_value.len()
-                && let Some(candidate) = model.diagnostics.target_history.iter().find(|candidate| {
-                    candidate.starts_with(&raw_value) && candidate.len() > raw_value.len()
-                })
-            {
+        match cve.hint(&software) {
+            Some(hint) => {
+                let color = match hint.worst() {
+                    Some(CveSeverity::Critical | CveSeverity::High) => Color::Red,
+                    Some(CveSeverity::Medium) => Color::Yellow,
+                    Some(CveSeverity::Low) | None => MUTED,
+                };
                 spans.push(Span::styled(
-                    candidate[raw_value.len()..].to_string(),
-                    Style::default().fg(GHOST),
-                ));
-            }
-            if !model.diagnostics.history_open {
-                frame.set_cursor_position(Position::new(
-                    value_x.saturating_add(cursor.min(value_row.width as usize) as u16),
-                    value_row.y,
+                    format!("{} {}", hint.total, tr(language, "个", "known")),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
                 ));
+                for severity in CveSeverity::ALL {
+                    let count = hint.count(severity);
+                    if count > 0 {
+                        spans.push(Span::styled(
+                            format!(" · {count} {}", severity.label(language)),
+                            Style::default().fg(MUTED),
+                        ));
+                    }
+                }
             }
-        } else {
-            spans.push(Span::styled(
-                raw_value,
-                if common.job.is_some() {
-                    Style::default().fg(MUTED)
-                } else if selected {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(Color::White)
+            None if cve.job.is_some() => spans.push(Span::styled(
+                tr(language, "查询中…", "looking up…"),
+                Style::default().fg(SUBTLE),
+            )),
+            None => spans.push(Span::styled(
+                match &cve.error {
+                    Some(error) => format!(
+                        "{}: {}",
+                        tr(language, "查询失败", "lookup failed"),
+                        error.message
+                    ),
+                    None => format!(
+                        "{} {} {}",
+                        tr(language, "按", "press"),
+                        binding(model, "refresh", "R"),
+                        tr(language, "查询", "to look up")
+                    ),
                 },
-            ));
-        }
-        frame.render_widget(Paragraph::new(Line::from(spans)), value_row);
-    }
-
-    if model.diagnostics.history_open {
-        let popup = centered(cols[2], 90, 55);
-        frame.render_widget(Clear, popup);
-        for index in 0..model.diagnostics.target_history.len().min(8) {
-            ui.overlay_regions.push((
-                Rect::new(popup.x + 1, popup.y + 1 + index as u16, popup.width - 2, 1),
-                Action::SelectDiagnosticHistory(index),
-            ));
+                Style::default().fg(SUBTLE),
+            )),
         }
-        let items: Vec<ListItem> = if model.diagnostics.target_history.is_empty() {
-            vec![
-                ListItem::new(tr(model.language, "暂无历史记录", "No history yet"))
-                    .style(Style::default().fg(SUBTLE)),
-            ]
-        } else {
-            model
-                .diagnostics
-                .target_history
-                .iter()
-                .take(8)
-                .enumerate()
-                .map(|(index, value)| {
-                    ListItem::new(value.clone()).style(
-                        if index == model.diagnostics.history_selected {
-                            Style::default().bg(SELECTED)
-                        } else {
-                            Style::default()
-                        },
-                    )
-                })
-                .collect()
-        };
-        frame.render_widget(
-            List::new(items).block(Block::bordered().title(tr(
-                model.language,
-                " 目标历史（点击或回车选择） ",
-                " Target history (click or Enter) ",
-            ))),
-            popup,
-        );
+        lines.push(Line::from(spans));
     }
+    lines
+}
 
-    if !model.diagnostics.focused {
-        let hint = centered(cols[1], 70, 18);
-        frame.render_widget(
-            Paragraph::new(tr(
-                model.language,
-                "按回车键进入交互模式，或直接点击任一卡片",
-                "Press Enter to interact, or click any panel",
-            ))
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(MUTED)),
-            hint,
+fn render_scanner_details(frame: &mut Frame, area: Rect, model: &AppModel) {
+    let language = model.language;
+    let (area, note_area) = if model.scanner.note_input.is_some() {
+        let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).split(area);
+        (rows[0], Some(rows[1]))
+    } else {
+        (area, None)
+    };
+    let label = |zh, en| {
+        Span::styled(
+            format!("{}: ", tr(language, zh, en)),
+            Style::default().fg(MUTED),
+        )
+    };
+    let mut lines = Vec::new();
+    match model.scanner.selected_host() {
+        None => lines.push(Line::styled(
+            tr(
+                language,
+                "选择设备查看详情",
+                "Select a device to see its details",
+            ),
+            Style::default().fg(SUBTLE),
+        )),
+        Some(host) => {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    shown_ip(&host.ip),
+                    Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  {}", shown_host(&host.hostname)),
+                    Style::default().fg(MUTED),
+                ),
+            ]));
+            lines.push(Line::from(vec![
+                label("MAC", "MAC"),
+                Span::raw(format!("{}  {}", shown_mac(&host.mac), host.vendor)),
+            ]));
+            if host.names.is_empty() {
+                lines.push(Line::from(vec![
+                    label("名称", "Names"),
+                    Span::styled(
+                        tr(language, "没有解析器应答", "no resolver answered"),
+                        Style::default().fg(SUBTLE),
+                    ),
+                ]));
+            }
+            for (index, name) in host.names.iter().enumerate() {
+                lines.push(Line::from(vec![
+                    if index == 0 {
+                        label("名称", "Names")
+                    } else {
+                        Span::raw(" ".repeat(tr(language, "名称: ", "Names: ").width()))
+                    },
+                    Span::styled(
+                        format!("{:<8}", name.source.label()),
+                        Style::default().fg(PRIMARY),
+                    ),
+                    Span::raw(shown_host(&name.name)),
+                ]));
+            }
+            let ports = if !host.enriched {
+                if model.scanner.enrich {
+                    tr(language, "检测中…", "checking…")
+                } else {
+                    tr(
+                        language,
+                        "未探测（←/→ 开启）",
+                        "not probed (Left/Right enables)",
+                    )
+                }
+                .to_string()
+            } else if host.open_ports.is_empty() {
+                tr(language, "未开放", "none open").to_string()
+            } else {
+                host.open_ports
+                    .iter()
+                    .map(|port| match *port {
+                        FTP_PORT => format!("{port} FTP"),
+                        SSH_PORT => format!("{port} SSH"),
+                        TELNET_PORT => format!("{port} Telnet"),
+                        80 | 8080 => format!("{port} HTTP"),
+                        443 => "443 HTTPS".to_string(),
+                        445 => format!("445 {}", ShareProtocol::Smb.label()),
+                        2049 => format!("2049 {}", ShareProtocol::Nfs.label()),
+                        port => IotProtocol::from_port(port).map_or_else(
+                            || port.to_string(),
+                            |protocol| format!("{port} {}", protocol.label()),
+                        ),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" · ")
+            };
+            lines.push(Line::from(vec![
+                label("开放端口", "Open ports"),
+                Span::raw(ports),
+            ]));
+            for service in &host.web {
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} ", if service.port == 443 { "HTTPS" } else { "HTTP" }),
+                        Style::default().fg(PRIMARY),
+                    ),
+                    Span::styled(format!(":{} ", service.port), Style::default().fg(MUTED)),
+                    Span::raw(if service.title.is_empty() {
+                        tr(language, "（无标题）", "(untitled)").to_string()
+                    } else {
+                        service.title.clone()
+                    }),
+                ];
+                if !service.server.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  {}", service.server),
+                        Style::default().fg(MUTED),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+            for banner in &host.banners {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{} ",
+                            if banner.port == SSH_PORT {
+                                "SSH"
+                            } else {
+                                "FTP"
+                            }
+                        ),
+                        Style::default().fg(PRIMARY),
+                    ),
+                    Span::styled(format!(":{} ", banner.port), Style::default().fg(MUTED)),
+                    Span::raw(banner.text.clone()),
+                ]));
+            }
+            let share_ports = host
+                .open_ports
+                .iter()
+                .any(|port| SHARE_PORTS.contains(port));
+            if host.enriched && share_ports && host.shares.is_empty() {
+                lines.push(Line::styled(
+                    tr(
+                        language,
+                        "匿名访问看不到任何共享",
+                        "No shares visible anonymously",
+                    ),
+                    Style::default().fg(SUBTLE),
+                ));
+            }
+            for share in &host.shares {
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} ", share.protocol.label()),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(share.name.clone()),
+                ];
+                if !share.detail.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  {}", share.detail),
+                        Style::default().fg(MUTED),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+            for service in &host.iot {
+                let mut spans = vec![Span::styled(
+                    format!("{} ", service.protocol.label()),
+                    Style::default().fg(Color::Cyan),
+                )];
+                spans.push(if service.detail.is_empty() {
+                    Span::styled(
+                        tr(language, "（协议已确认）", "(protocol confirmed)"),
+                        Style::default().fg(SUBTLE),
+                    )
+                } else {
+                    Span::raw(service.detail.clone())
+                });
+                lines.push(Line::from(spans));
+            }
+            for service in model
+                .scanner
+                .services
+                .iter()
+                .filter(|service| service.ip == host.ip)
+            {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", service.protocol.label()),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::raw(shown_host(&service.name)),
+                ]));
+            }
+            lines.extend(cve_lines(model, host));
+            lines.push(Line::raw(""));
+            let record = model.scanner.device_record(host);
+            // The inventory keeps the tags from the last scan that probed
+            // the device, so they show even with probing off.
+            let types = record
+                .map(|record| record.device_types.clone())
+                .filter(|types| !types.is_empty())
+                .unwrap_or_else(|| iptools_core::device_types(&host.iot));
+            if !types.is_empty() {
+                lines.push(Line::from(vec![
+                    label("设备类型", "Device type"),
+                    Span::styled(
+                        types
+                            .iter()
+                            .map(|kind| device_type_label(*kind, language))
+                            .collect::<Vec<_>>()
+                            .join(" · "),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                ]));
+            }
+            let seen = |value: Option<&String>| {
+                value
+                    .filter(|value| !value.is_empty())
+                    .map_or_else(|| "—".into(), |value| model.display_time(value))
+            };
+            lines.push(Line::from(vec![
+                label("首次发现", "First seen"),
+                Span::raw(seen(record.map(|record| &record.first_seen))),
+            ]));
+            lines.push(Line::from(vec![
+                label("最近发现", "Last seen"),
+                Span::raw(seen(record.map(|record| &record.last_seen))),
+            ]));
+            let note = record
+                .map(|record| record.note.as_str())
+                .unwrap_or_default();
+            lines.push(Line::from(vec![
+                label("备注", "Note"),
+                if note.is_empty() {
+                    Span::styled(
+                        tr(language, "E 添加备注", "E adds a note"),
+                        Style::default().fg(SUBTLE),
+                    )
+                } else {
+                    Span::raw(note.to_string())
+                },
+            ]));
+        }
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(tr(
+                language,
+                " 设备详情（Esc 关闭） ",
+                " Device Details (Esc closes) ",
+            ))),
+        area,
+    );
+    if let (Some(note_area), Some((note, cursor))) = (note_area, &model.scanner.note_input) {
+        frame.render_widget(
+            Paragraph::new(note.as_str())
+                .style(Style::default().fg(PRIMARY))
+                .block(Block::bordered().title(tr(
+                    language,
+                    " 备注（回车保存，Esc 取消） ",
+                    " Note (Enter saves, Esc cancels) ",
+                ))),
+            note_area,
         );
+        let width = note_area.width.saturating_sub(2);
+        frame.set_cursor_position(Position::new(
+            note_area.x + 1 + (*cursor as u16).min(width.saturating_sub(1)),
+            note_area.y + 1,
+        ));
     }
 }
 
-fn render_ping(area: Rect, frame: &mut Frame, model: &AppModel) {
-    let state = &model.diagnostics.ping;
-    let latest = state.samples.last();
-    let summary = state.summary.as_ref();
-    let min = summary
-        .and_then(|value| value.min_ms)
-        .or_else(|| latest.and_then(|value| value.min_ms));
-    let avg = summary
-        .and_then(|value| value.average_ms)
-        .or_else(|| latest.and_then(|value| value.average_ms));
-    let max = summary
-        .and_then(|value| value.max_ms)
-        .or_else(|| latest.and_then(|value| value.max_ms));
-    let loss = summary
-        .map(|value| value.loss_percent)
-        .or_else(|| latest.map(|value| value.loss_percent))
-        .unwrap_or_default();
-    let jitter = if state.samples.len() > 1 {
-        let pairs = state
-            .samples
-            .windows(2)
-            .filter_map(|pair| Some(pair[0].latency_ms?.abs_diff(pair[1].latency_ms?) as f64))
-            .collect::<Vec<_>>();
-        (!pairs.is_empty()).then(|| pairs.iter().sum::<f64>() / pairs.len() as f64)
-    } else {
-        None
-    };
-    let stats_area = Rect::new(area.x, area.y, area.width, area.height.min(4));
-    let status_area = bottom_row(area);
-    let available = status_area.y.saturating_sub(stats_area.bottom());
-    let log_height = available.min(8);
-    let log_area = Rect::new(
-        area.x,
-        status_area.y.saturating_sub(log_height),
-        area.width,
-        log_height,
+fn render_traffic(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    let visible_rows = area.height.saturating_sub(4) as usize / 2;
+    let visible = visible_range(
+        model.traffic.rows.len(),
+        model.traffic.selected,
+        visible_rows,
+        &mut ui.traffic_viewport,
     );
-    let chart_area = Rect::new(
-        area.x,
-        stats_area.bottom(),
-        area.width,
-        log_area.y.saturating_sub(stats_area.bottom()),
+    ui.page_rows = visible_rows;
+    let cells = ui.traffic_cells.refresh(
+        model.traffic.revision,
+        &model.traffic.rows,
+        visible.clone(),
+        |row| {
+            let (loss, counts) = match model.link_health.window(&row.name) {
+                Some(window) => (
+                    format!(
+                        "{:.2}%{}",
+                        window.counts.loss_percent(),
+                        if window.alerting { " !" } else { "" }
+                    ),
+                    link_counts(model.language, &window.counts),
+                ),
+                None => ("—".into(), String::new()),
+            };
+            vec![
+                row.name.clone(),
+                format!("↓ {}", format_rate(model.region_format, row.download_bps)),
+                format!("↑ {}", format_rate(model.region_format, row.upload_bps)),
+                format!(
+                    "↓ {}",
+                    format_bytes(model.region_format, row.session_download)
+                ),
+                format!(
+                    "↑ {}",
+                    format_bytes(model.region_format, row.session_upload)
+                ),
+                format!(
+                    "↓ {}",
+                    format_bytes(model.region_format, row.total_download)
+                ),
+                format!("↑ {}", format_bytes(model.region_format, row.total_upload)),
+                loss,
+                counts,
+            ]
+        },
     );
-    let third = stats_area.width / 3;
-    let stats = [
-        (
-            format!(
-                "{}: {} ms",
-                tr(model.language, "最近", "Last"),
-                format_optional_u64(latest.and_then(|value| value.latency_ms))
-            ),
-            SECONDARY,
-        ),
-        (
-            format!(
-                "{}: {} ms",
-                tr(model.language, "最小", "Min"),
-                format_optional_u64(min)
-            ),
-            Color::Green,
-        ),
-        (
-            format!(
-                "{}: {} ms",
-                tr(model.language, "最大", "Max"),
-                format_optional_u64(max)
-            ),
-            Color::Red,
-        ),
-        (
-            format!(
-                "{}: {} ms",
-                tr(model.language, "平均", "Average"),
-                format_optional_f64(avg)
-            ),
-            Color::White,
-        ),
-        (
-            format!(
-                "{}: {} ms",
-                tr(model.language, "抖动", "Jitter"),
-                format_optional_f64(jitter)
-            ),
-            Color::White,
-        ),
-        (
-            format!("{}: {:.1}%", tr(model.language, "丢包", "Loss"), loss),
-            Color::White,
-        ),
+    let inner_width = area.width.saturating_sub(2);
+    let (rate_width, byte_width) = if inner_width >= 100 {
+        (14, 18)
+    } else {
+        (13, 14)
+    };
+    // Loss needs room the narrowest terminals do not have.
+    let loss_width = if inner_width >= 96 { 16 } else { 0 };
+    let rows = cells.iter().zip(visible).map(|(cells, index)| {
+        let alerting = model
+            .traffic
+            .rows
+            .get(index)
+            .and_then(|row| model.link_health.window(&row.name))
+            .is_some_and(|window| window.alerting);
+        let mut row = vec![
+            Cell::from(cells[0].as_str()),
+            Cell::from(cells[1].as_str()),
+            Cell::from(cells[2].as_str()),
+            Cell::from(vec![
+                Line::from(cells[3].as_str()),
+                Line::from(cells[4].as_str()),
+            ]),
+            Cell::from(vec![
+                Line::from(cells[5].as_str()),
+                Line::from(cells[6].as_str()),
+            ]),
+        ];
+        if loss_width > 0 {
+            row.push(
+                Cell::from(vec![
+                    Line::from(cells[7].as_str()),
+                    Line::from(cells[8].as_str()).style(Style::default().fg(SUBTLE)),
+                ])
+                .style(Style::default().fg(if alerting {
+                    Color::Red
+                } else {
+                    MUTED
+                })),
+            );
+        }
+        Row::new(row)
+            .height(2)
+            .style(if index == model.traffic.selected {
+                Style::default().bg(SELECTED)
+            } else {
+                Style::default()
+            })
+    });
+    let name_width = inner_width
+        .saturating_sub(rate_width * 2 + byte_width * 2 + 4)
+        .saturating_sub(if loss_width > 0 { loss_width + 1 } else { 0 })
+        .max(8);
+    let mut header = vec![
+        Cell::from(tr(model.language, "接口名称", "Interface Name"))
+            .style(Style::default().fg(MUTED)),
+        Cell::from(tr(model.language, "下载速率", "Download"))
+            .style(Style::default().fg(Color::Green)),
+        Cell::from(tr(model.language, "上传速率", "Upload"))
+            .style(Style::default().fg(Color::Yellow)),
+        Cell::from(tr(model.language, "本次会话", "Session")).style(Style::default().fg(MUTED)),
+        Cell::from(tr(model.language, "开机累计", "Since Boot")).style(Style::default().fg(MUTED)),
     ];
-    for (index, (text, color)) in stats.into_iter().enumerate() {
-        frame.render_widget(
-            Paragraph::new(text).style(Style::default().fg(color)),
-            Rect::new(
-                stats_area.x + third * (index % 3) as u16,
-                stats_area.y + (index / 3) as u16,
-                third,
-                1,
-            ),
+    let mut widths = vec![
+        Constraint::Length(name_width),
+        Constraint::Length(rate_width),
+        Constraint::Length(rate_width),
+        Constraint::Length(byte_width),
+        Constraint::Length(byte_width),
+    ];
+    if loss_width > 0 {
+        header.push(
+            Cell::from(tr(model.language, "丢包（1 分钟）", "Loss (1 min)"))
+                .style(Style::default().fg(MUTED)),
         );
+        widths.push(Constraint::Length(loss_width));
     }
     frame.render_widget(
-        Block::default()
-            .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(SUBTLE)),
-        Rect::new(stats_area.x, stats_area.y + 2, stats_area.width, 1),
-    );
-    let history = state
-        .samples
-        .iter()
-        .map(|sample| sample.latency_ms.unwrap_or_default())
-        .collect::<Vec<_>>();
-    frame.render_widget(
-        Sparkline::default()
-            .block(Block::default().title(tr(model.language, "延迟曲线", "Latency History")))
-            .data(&history)
-            .style(Style::default().fg(PRIMARY)),
-        chart_area,
-    );
-    let logs = state
-        .common
-        .log
-        .iter()
-        .rev()
-        .map(|entry| ListItem::new(entry.clone()));
-    frame.render_widget(
-        List::new(logs).block(
-            Block::default()
-                .borders(Borders::TOP)
-                .title(tr(model.language, "日志", "Log"))
-                .style(Style::default().fg(MUTED)),
-        ),
-        log_area,
+        Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header).height(1).bottom_margin(1))
+            .block(Block::bordered().title(tr(
+                model.language,
+                " 实时流量监控 ",
+                " Real-time Monitor ",
+            ))),
+        area,
     );
-    render_diagnostic_status(frame, status_area, &state.common.status, model.language);
 }
 
-fn render_trace(area: Rect, frame: &mut Frame, model: &AppModel) {
-    let state = &model.diagnostics.trace;
-    let status_area = bottom_row(area);
-    let table_area = Rect::new(
-        area.x,
-        area.y,
-        area.width,
-        status_area.y.saturating_sub(area.y),
-    );
-    let hops = state.hops.iter().enumerate().map(|(index, hop)| {
-        Row::new(vec![
-            Cell::from(format!("{:>2}", hop.ttl)).style(Style::default().fg(SECONDARY)),
-            Cell::from(hop.address.clone().unwrap_or_else(|| "*".into())),
-            Cell::from(
-                hop.latency_ms
-                    .map_or_else(|| "*".into(), |value| format!("{value} ms")),
-            )
-            .style(Style::default().fg(PRIMARY)),
-            Cell::from(hop.hostname.clone().unwrap_or_else(|| "-".into()))
-                .style(Style::default().fg(SUBTLE)),
-        ])
-        .style(if index == state.selected {
-            Style::default().bg(SELECTED)
-        } else {
-            Style::default()
-        })
-    });
+/// Negotiated speed, duplex and medium, with the hardware's top speed when
+/// the link runs below it.
+fn link_summary(language: Language, adapter: &iptools_core::AdapterInfo) -> Option<String> {
+    let media = &adapter.media;
+    let mut parts = Vec::new();
+    if let Some(speed) = adapter.link_speed_bps {
+        parts.push(link_speed(speed));
+    }
+    if media.medium != Some(LinkMedium::Wireless) {
+        match media.duplex {
+            Some(Duplex::Full) => parts.push(tr(language, "全双工", "full duplex").into()),
+            Some(Duplex::Half) => parts.push(tr(language, "半双工", "half duplex").into()),
+            None => {}
+        }
+    }
+    if let Some(medium) = media.medium {
+        parts.push(
+            match medium {
+                LinkMedium::Ethernet => tr(language, "以太网", "Ethernet"),
+                LinkMedium::TwistedPair => tr(language, "双绞线", "twisted pair"),
+                LinkMedium::Fibre => tr(language, "光纤", "fibre"),
+                LinkMedium::DirectAttach => tr(language, "直连铜缆", "direct-attach copper"),
+                LinkMedium::Wireless => tr(language, "无线", "wireless"),
+                LinkMedium::Other => tr(language, "其他介质", "other medium"),
+            }
+            .into(),
+        );
+    }
+    if let Some(max) = media
+        .max_speed_bps
+        .filter(|max| adapter.link_speed_bps.is_some_and(|speed| speed < *max))
+    {
+        parts.push(format!(
+            "{} {}",
+            tr(language, "硬件最高", "hardware max"),
+            link_speed(max)
+        ));
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+fn link_warning_text(language: Language, warning: LinkWarning) -> String {
+    let LinkWarning::Downshifted {
+        speed_bps,
+        max_bps,
+        partner_max_bps,
+    } = warning
+    else {
+        return tr(
+            language,
+            "半双工：负载下会冲突丢包，通常是一端固定了速率而另一端在自协商",
+            "Half duplex: collides under load, usually one end forced to a speed while the other autonegotiates",
+        )
+        .into();
+    };
+    let (speed, max) = (link_speed(speed_bps), link_speed(max_bps));
+    match (language, partner_max_bps.map(link_speed)) {
+        (Language::Zh, Some(partner)) if partner_max_bps <= Some(speed_bps) => {
+            format!("对端只支持 {partner}：交换机或路由器的端口不是千兆")
+        }
+        (Language::En, Some(partner)) if partner_max_bps <= Some(speed_bps) => {
+            format!("The other end only offers {partner}: the switch or router port is not gigabit")
+        }
+        (Language::Zh, Some(partner)) => {
+            format!("只协商到 {speed}，两端都支持 {partner}：检查网线（千兆需要 8 芯）或换一个端口")
+        }
+        (Language::En, Some(partner)) => format!(
+            "Only {speed} though both ends support {partner}: check the cable (gigabit needs all 8 wires) or try another port"
+        ),
+        (Language::Zh, None) => {
+            format!("{max} 网卡只协商到 {speed}：常见原因是网线断芯、百兆交换机或端口故障")
+        }
+        (Language::En, None) => format!(
+            "Only {speed} on {max} hardware: usually a cable with a broken pair, a Fast Ethernet switch or a bad port"
+        ),
+    }
+}
+
+fn link_speed(bps: u64) -> String {
+    if bps >= 1_000_000_000 {
+        format!("{} Gbps", bps as f64 / 1_000_000_000.0)
+    } else {
+        format!("{} Mbps", bps / 1_000_000)
+    }
+}
+
+/// Drops, errors and collisions, with overruns when there were any.
+fn link_counts(language: Language, counts: &LinkCounters) -> String {
+    let mut text = format!(
+        "{} {} {} {}",
+        tr(language, "丢", "drop"),
+        counts.drops,
+        tr(language, "错", "err"),
+        counts.errors
+    );
+    if counts.overruns > 0 {
+        text.push_str(&format!(
+            " {} {}",
+            tr(language, "溢", "ovr"),
+            counts.overruns
+        ));
+    }
+    if counts.collisions > 0 {
+        text.push_str(&format!(
+            " {} {}",
+            tr(language, "冲", "col"),
+            counts.collisions
+        ));
+    }
+    text
+}
+
+fn render_events(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    let block = Block::bordered().title(tr(
+        model.language,
+        " 网络变化记录（最新在上） ",
+        " Network Changes (newest first) ",
+    ));
+    let log = &model.events;
+    if log.entries.is_empty() {
+        frame.render_widget(
+            Paragraph::new(tr(
+                model.language,
+                "暂无变化。网卡上下线、地址、默认网关或公网 IP 变化时会记录在这里。",
+                "No changes yet. Interfaces going up or down and address, gateway or public IP changes are recorded here.",
+            ))
+            .style(Style::default().fg(MUTED))
+            .wrap(Wrap { trim: true })
+            .block(block),
+            area,
+        );
+        return;
+    }
+    ui.page_rows = area.height.saturating_sub(4) as usize;
+    let visible = visible_range(
+        log.entries.len(),
+        log.selected,
+        ui.page_rows,
+        &mut ui.events_viewport,
+    );
+    let rows = log
+        .newest_first()
+        .enumerate()
+        .skip(visible.start)
+        .take(visible.len())
+        .map(|(index, event)| {
+            let (label, color) = network_event_label(event.kind, model.language);
+            let mark = state_mark(
+                model,
+                match event.kind {
+                    NetworkEventKind::InterfaceAdded | NetworkEventKind::InterfaceUp => "▲ ",
+                    NetworkEventKind::InterfaceRemoved | NetworkEventKind::InterfaceDown => "▼ ",
+                    NetworkEventKind::WifiRoamed => "⇄ ",
+                    _ => "● ",
+                },
+            );
+            let change = match (event.from.is_empty(), event.to.is_empty()) {
+                (true, true) => String::new(),
+                (true, false) => shown_text(&event.to).into_owned(),
+                (false, true) => shown_text(&event.from).into_owned(),
+                (false, false) => {
+                    format!("{} → {}", shown_text(&event.from), shown_text(&event.to))
+                }
+            };
+            Row::new(vec![
+                Cell::from(model.display_time(&event.at)),
+                Cell::from(Span::styled(
+                    format!("{mark}{label}"),
+                    Style::default().fg(color),
+                )),
+                Cell::from(event.subject.clone()),
+                Cell::from(change),
+            ])
+            .style(if index == log.selected {
+                Style::default().bg(SELECTED)
+            } else {
+                Style::default()
+            })
+        });
     frame.render_widget(
         Table::new(
-            hops,
+            rows,
             [
-                Constraint::Length(4),
-                Constraint::Length(17),
-                Constraint::Length(10),
-                Constraint::Min(0),
+                Constraint::Length(19),
+                Constraint::Length(16),
+                Constraint::Percentage(30),
+                Constraint::Fill(1),
             ],
         )
+        .column_spacing(2)
         .header(
-            Row::new([
-                tr(model.language, "跳数", "Hop"),
-                tr(model.language, "地址", "Address"),
-                "RTT",
-                tr(model.language, "主机", "Host"),
+            Row::new(vec![
+                tr(model.language, "时间", "Time"),
+                tr(model.language, "事件", "Event"),
+                tr(model.language, "网卡", "Interface"),
+                tr(model.language, "变化", "Change"),
             ])
-            .style(Style::default().fg(MUTED)),
-        ),
-        table_area,
+            .style(Style::default().fg(MUTED))
+            .bottom_margin(1),
+        )
+        .block(block),
+        area,
     );
-    render_diagnostic_status(frame, status_area, &state.common.status, model.language);
 }
 
-fn render_port_scan(area: Rect, frame: &mut Frame, model: &AppModel) {
-    let state = &model.diagnostics.port_scan;
-    let stats_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
-    let status_area = bottom_row(area);
-    let progress_area = Rect::new(area.x, status_area.y.saturating_sub(1), area.width, 1);
-    let ports_area = Rect::new(
-        area.x,
-        stats_area.bottom(),
-        area.width,
-        progress_area.y.saturating_sub(stats_area.bottom()),
-    );
-    frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled(
-                format!("{}: ", tr(model.language, "开放端口", "Open")),
-                Style::default().fg(MUTED),
-            ),
-            Span::styled(
-                format!("{:<6}", state.open_ports.len()),
-                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                format!("{}: ", tr(model.language, "已扫描", "Scanned")),
-                Style::default().fg(MUTED),
-            ),
-            Span::styled(
-                format!("{} / {}", state.scanned, state.total),
-                Style::default().fg(SECONDARY),
-            ),
-        ])),
-        stats_area,
-    );
-    let ports = state.open_ports.iter().enumerate().map(|(index, result)| {
-        Row::new(vec![
-            Cell::from(result.port.to_string()),
-            Cell::from(result.service.clone()).style(Style::default().fg(SECONDARY)),
-        ])
-        .style(if index == state.selected {
-            Style::default().bg(SELECTED)
-        } else {
+fn network_event_label(kind: NetworkEventKind, language: Language) -> (Cow<'static, str>, Color) {
+    match kind {
+        NetworkEventKind::InterfaceAdded => (tr(language, "网卡接入", "Added"), Color::Green),
+        NetworkEventKind::InterfaceRemoved => (tr(language, "网卡移除", "Removed"), Color::Red),
+        NetworkEventKind::InterfaceUp => (tr(language, "网卡上线", "Up"), Color::Green),
+        NetworkEventKind::InterfaceDown => (tr(language, "网卡断开", "Down"), Color::Red),
+        NetworkEventKind::AddressChanged => (tr(language, "地址变化", "Address"), Color::Yellow),
+        NetworkEventKind::GatewayChanged => (tr(language, "网关变化", "Gateway"), Color::Yellow),
+        NetworkEventKind::PublicIpChanged => {
+            (tr(language, "公网 IP 变化", "Public IP"), Color::Yellow)
+        }
+        NetworkEventKind::WifiRoamed => (tr(language, "Wi-Fi 漫游", "Roamed"), Color::Cyan),
+    }
+}
+
+fn focus_label(focus: DiagnosticFocus, language: Language) -> Cow<'static, str> {
+    match focus {
+        DiagnosticFocus::Menu => tr(language, "工具列表", "Tools"),
+        DiagnosticFocus::Main => tr(language, "监控面板", "Visualization"),
+        DiagnosticFocus::Config => tr(language, "参数配置", "Configuration"),
+    }
+}
+
+/// Where the keys go: page ▸ tool ▸ focused pane, with the keys that jump
+/// between the panes on the right.
+fn render_diagnostic_breadcrumb(frame: &mut Frame, area: Rect, model: &AppModel) {
+    let language = model.language;
+    let diagnostics = &model.diagnostics;
+    let separator = Span::styled(" ▸ ", Style::default().fg(MUTED));
+    let mut trail = vec![
+        Span::styled(
+            format!(" {}", page_label(Page::Diagnostics, language)),
+            Style::default().fg(SECONDARY),
+        ),
+        separator.clone(),
+        Span::styled(
+            tool_label(diagnostics.tool, language),
+            Style::default().fg(SECONDARY),
+        ),
+    ];
+    if diagnostics.focused {
+        trail.push(separator);
+        trail.push(Span::styled(
+            focus_label(diagnostics.focus, language),
             Style::default()
-        })
-    });
-    frame.render_widget(
-        Table::new(ports, [Constraint::Length(10), Constraint::Min(0)])
-            .header(
-                Row::new([
-                    tr(model.language, "端口", "Port"),
-                    tr(model.language, "服务", "Service"),
-                ])
-                .style(Style::default().fg(MUTED)),
-            )
-            .block(Block::default().borders(Borders::TOP).title(tr(
-                model.language,
-                "开放端口",
-                "Open Ports",
-            ))),
-        ports_area,
-    );
-    let ratio = if state.total == 0 {
-        0.0
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    // Parameters take typed text, so only the Alt spelling jumps from there.
+    let alt = if diagnostics.focused && diagnostics.focus == DiagnosticFocus::Config {
+        "Alt+"
     } else {
-        state.scanned as f64 / state.total as f64
+        ""
     };
-    frame.render_widget(
-        Gauge::default()
-            .gauge_style(Style::default().fg(SECONDARY).bg(SELECTED))
-            .ratio(ratio.clamp(0.0, 1.0))
-            .label(format!("{:.0}%", ratio * 100.0)),
-        progress_area,
-    );
-    render_diagnostic_status(frame, status_area, &state.common.status, model.language);
+    let mut keys = Vec::new();
+    for (key, focus) in [
+        ("M", DiagnosticFocus::Menu),
+        ("V", DiagnosticFocus::Main),
+        ("C", DiagnosticFocus::Config),
+    ] {
+        let style = if diagnostics.focused && diagnostics.focus == focus {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(MUTED)
+        };
+        keys.push(Span::styled(
+            format!("{alt}{key} {}  ", focus_label(focus, language)),
+            style,
+        ));
+    }
+    let trail = Line::from(trail);
+    let keys = Line::from(keys);
+    frame.render_widget(Paragraph::new(trail.clone()), area);
+    if trail.width() + keys.width() < usize::from(area.width) {
+        frame.render_widget(Paragraph::new(keys).alignment(Alignment::Right), area);
+    }
 }
 
-fn render_lan_speed(area: Rect, frame: &mut Frame, model: &AppModel) {
-    let state = &model.diagnostics.lan_speed;
-    let latest = state.samples.last();
-    let status_area = bottom_row(area);
-    let summary_height = status_area.y.saturating_sub(area.y).min(5);
-    let summary_area = Rect::new(
-        area.x,
-        status_area.y.saturating_sub(summary_height),
-        area.width,
-        summary_height,
-    );
-    let endpoint_area = Rect::new(area.x, area.y, area.width, area.height.min(1));
-    let bidirectional = state.request.mode == LanSpeedMode::Client
-        && state.request.direction == LanDirection::Bidirectional;
-    let throughput_height = if bidirectional { 2 } else { 1 };
-    let throughput_area = Rect::new(
-        area.x,
-        endpoint_area.bottom(),
-        area.width,
-        throughput_height.min(summary_area.y.saturating_sub(endpoint_area.bottom())),
-    );
-    let history_area = Rect::new(
-        area.x,
-        throughput_area.bottom(),
-        area.width,
-        summary_area.y.saturating_sub(throughput_area.bottom()),
-    );
+fn render_diagnostics(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    let common = model.diagnostics.active_common();
+    let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(area);
+    render_diagnostic_breadcrumb(frame, rows[0], model);
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(50),
+            Constraint::Percentage(30),
+        ])
+        .split(rows[1]);
 
-    let endpoint = if state.endpoint.is_empty() {
-        match state.request.mode {
-            LanSpeedMode::Server => format!("0.0.0.0:{}", state.request.port),
-            LanSpeedMode::Client => format!("{}:{}", state.request.peer, state.request.port),
+    // The focused pane gets a double border as well as the colour, so the
+    // focus still shows in monochrome.
+    let focused = |focus| model.diagnostics.focused && model.diagnostics.focus == focus;
+    let pane = |focus, title: String| {
+        if focused(focus) {
+            Block::bordered()
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+        } else {
+            Block::bordered()
+                .border_style(Style::default().fg(MUTED))
+                .title(title)
         }
-    } else {
-        state.endpoint.clone()
     };
-    frame.render_widget(
-        Paragraph::new(format!(
-            "{}: {endpoint}",
-            tr(
-                model.language,
-                if state.request.mode == LanSpeedMode::Server {
-                    "本机"
-                } else {
-                    "对端"
-                },
-                if state.request.mode == LanSpeedMode::Server {
-                    "Local"
-                } else {
-                    "Peer"
-                },
-            )
-        ))
-        .style(Style::default().fg(SECONDARY)),
-        endpoint_area,
-    );
 
-    let tx_bps = latest.map_or(0, |sample| sample.tx_bps);
-    let rx_bps = latest.map_or(0, |sample| sample.rx_bps);
-    let mut throughput = Vec::new();
-    if bidirectional || tx_bps > 0 {
-        throughput.push(Line::from(vec![
-            Span::styled("TX ", Style::default().fg(MUTED)),
-            Span::styled(
-                format_speed_dual(tx_bps),
-                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
-            ),
-        ]));
-    }
-    if bidirectional || tx_bps == 0 || rx_bps > 0 {
-        throughput.push(Line::from(vec![
-            Span::styled("RX ", Style::default().fg(MUTED)),
-            Span::styled(
-                format_speed_dual(rx_bps),
-                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
-            ),
-        ]));
-    }
+    let mut items = Vec::new();
+    ui.diagnostic_menu = Some(cols[0]);
+    let menu_inner = Block::bordered().inner(cols[0]);
+    for (index, tool) in DiagnosticTool::ALL.into_iter().enumerate() {
+        let row = Rect::new(
+            menu_inner.x,
+            menu_inner.y + index as u16,
+            menu_inner.width,
+            1,
+        );
+        ui.diagnostic_regions.push((row, index as u8));
+        let selected = tool == model.diagnostics.tool;
+        let available = model.tool_available(tool);
+        let fg = if available { Color::White } else { MUTED };
+        items.push(
+            ListItem::new(format!(
+                "{}{}{}",
+                if selected { "> " } else { "  " },
+                tool_label(tool, model.language),
+                if available {
+                    "".into()
+                } else {
+                    tr(model.language, "（不可用）", " (n/a)")
+                }
+            ))
+            .style(if selected {
+                Style::default()
+                    .fg(fg)
+                    .bg(SELECTED)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(fg)
+            }),
+        );
+    }
+    frame.render_widget(
+        List::new(items).block(pane(
+            DiagnosticFocus::Menu,
+            format!(" {} ", focus_label(DiagnosticFocus::Menu, model.language)),
+        )),
+        cols[0],
+    );
+
+    ui.diagnostic_main = Some(cols[1]);
+    let mut main_title = format!(" {} ", focus_label(DiagnosticFocus::Main, model.language));
+    if common.trimmed > 0 {
+        main_title.push_str(&format!(
+            "· {} ",
+            trimmed_note(model.language, common.trimmed)
+        ));
+    }
+    let main_block = pane(DiagnosticFocus::Main, main_title);
+    let main_inner = main_block.inner(cols[1]);
+    frame.render_widget(main_block, cols[1]);
+    if let Some(failure) = diagnostic_failure(common, model.language) {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                tr(model.language, "诊断失败", "Diagnostic failed"),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(failure),
+            Line::from(""),
+        ];
+        lines.extend(
+            common
+                .log
+                .iter()
+                .rev()
+                .take(main_inner.height.saturating_sub(4) as usize)
+                .rev()
+                .cloned()
+                .map(Line::from),
+        );
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), main_inner);
+    } else {
+        draw(
+            ModuleId::Tool(model.diagnostics.tool),
+            frame,
+            main_inner,
+            model,
+            ui,
+        );
+    }
+
+    ui.diagnostic_config = Some(cols[2]);
+    let config_block = pane(
+        DiagnosticFocus::Config,
+        format!(" {} ", focus_label(DiagnosticFocus::Config, model.language)),
+    );
+    let config_inner = config_block.inner(cols[2]);
+    frame.render_widget(config_block, cols[2]);
+    let fields = (view(ModuleId::Tool(model.diagnostics.tool)).fields)(model);
+    for (index, (label, raw_value)) in fields.into_iter().enumerate() {
+        let y = index as u16 * 2;
+        if y + 1 >= config_inner.height {
+            break;
+        }
+        let label_row = Rect::new(config_inner.x, config_inner.y + y, config_inner.width, 1);
+        let value_row = Rect::new(
+            config_inner.x,
+            config_inner.y + y + 1,
+            config_inner.width,
+            1,
+        );
+        let value_x = value_row.x.saturating_add(3.min(value_row.width));
+        ui.diagnostic_fields.push((
+            Rect::new(label_row.x, label_row.y, label_row.width, 2),
+            index,
+            value_x,
+        ));
+        let selected = model.diagnostics.focused
+            && model.diagnostics.focus == DiagnosticFocus::Config
+            && model.active_diagnostic_config_index() == index;
+        let text_editable = (view(ModuleId::Tool(model.diagnostics.tool)).typed)(index);
+        frame.render_widget(
+            Paragraph::new(format!("{label}:")).style(Style::default().fg(if selected {
+                Color::Yellow
+            } else {
+                MUTED
+            })),
+            label_row,
+        );
+        let mut spans = vec![Span::styled(
+            if selected { ">> " } else { "   " },
+            if selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            },
+        )];
+        if selected && common.job.is_none() && text_editable {
+            let cursor = model.diagnostics.cursor.min(raw_value.len());
+            spans.push(Span::styled(
+                raw_value[..cursor].to_string(),
+                Style::default().fg(Color::Yellow),
+            ));
+            spans.push(Span::styled(
+                raw_value[cursor..].to_string(),
+                Style::default().fg(Color::Yellow),
+            ));
+            if index == diagnostic_target_index(model.diagnostics.tool)
+                && !raw_value.is_empty()
+                && cursor == raw_value.len()
+                && let Some(candidate) = model.diagnostics.target_history.iter().find(|candidate| {
+                    candidate.starts_with(&raw_value) && candidate.len() > raw_value.len()
+                })
+            {
+                spans.push(Span::styled(
+                    candidate[raw_value.len()..].to_string(),
+                    Style::default().fg(GHOST),
+                ));
+            }
+            if !model.diagnostics.history_open {
+                frame.set_cursor_position(Position::new(
+                    value_x.saturating_add(cursor.min(value_row.width as usize) as u16),
+                    value_row.y,
+                ));
+            }
+        } else {
+            spans.push(Span::styled(
+                raw_value,
+                if common.job.is_some() {
+                    Style::default().fg(MUTED)
+                } else if selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                },
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), value_row);
+    }
+
+    if model.diagnostics.history_open {
+        let popup = centered(cols[2], 90, 55);
+        frame.render_widget(Clear, popup);
+        for index in 0..model.diagnostics.target_history.len().min(8) {
+            ui.overlay_regions.push((
+                Rect::new(popup.x + 1, popup.y + 1 + index as u16, popup.width - 2, 1),
+                Action::SelectDiagnosticHistory(index),
+            ));
+        }
+        let items: Vec<ListItem> = if model.diagnostics.target_history.is_empty() {
+            vec![
+                ListItem::new(tr(model.language, "暂无历史记录", "No history yet"))
+                    .style(Style::default().fg(SUBTLE)),
+            ]
+        } else {
+            model
+                .diagnostics
+                .target_history
+                .iter()
+                .take(8)
+                .enumerate()
+                .map(|(index, value)| {
+                    ListItem::new(value.clone()).style(
+                        if index == model.diagnostics.history_selected {
+                            Style::default().bg(SELECTED)
+                        } else {
+                            Style::default()
+                        },
+                    )
+                })
+                .collect()
+        };
+        frame.render_widget(
+            List::new(items).block(Block::bordered().title(tr(
+                model.language,
+                " 目标历史（点击或回车选择） ",
+                " Target history (click or Enter) ",
+            ))),
+            popup,
+        );
+    }
+
+    if !model.diagnostics.focused {
+        let hint = centered(cols[1], 70, 18);
+        frame.render_widget(
+            Paragraph::new(tr(
+                model.language,
+                "按回车键进入交互模式，或直接点击任一卡片",
+                "Press Enter to interact, or click any panel",
+            ))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(MUTED)),
+            hint,
+        );
+    }
+}
+
+fn render_ping(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.ping;
+    let stats = state.stats();
+    // A pinned baseline turns the grid into a table of now, then and the
+    // difference, two rows taller.
+    let grid_height = if state.baseline.is_some() { 4 } else { 2 };
+    let stats_area = Rect::new(area.x, area.y, area.width, area.height.min(grid_height + 2));
+    let status_area = bottom_row(area);
+    let available = status_area.y.saturating_sub(stats_area.bottom());
+    let log_height = available.min(8);
+    let log_area = Rect::new(
+        area.x,
+        status_area.y.saturating_sub(log_height),
+        area.width,
+        log_height,
+    );
+    let chart_area = Rect::new(
+        area.x,
+        stats_area.bottom(),
+        area.width,
+        log_area.y.saturating_sub(stats_area.bottom()),
+    );
+    let grid = Rect::new(
+        stats_area.x,
+        stats_area.y,
+        stats_area.width,
+        stats_area.height.min(grid_height),
+    );
+    let mut rule = Block::default()
+        .borders(Borders::BOTTOM)
+        .border_style(Style::default().fg(SUBTLE));
+    if let Some(baseline) = &state.baseline {
+        render_ping_comparison(frame, grid, model, &stats, &baseline.stats);
+        let at = model.display_time_of_day(&baseline.at);
+        let mut title = vec![Span::styled(
+            match model.language {
+                Language::Zh => format!(" 基线 {at} · P 取消固定 "),
+                Language::En => format!(" Baseline {at} · P to unpin "),
+            },
+            Style::default().fg(MUTED),
+        )];
+        if baseline.target != state.request.target {
+            title.push(Span::styled(
+                match model.language {
+                    Language::Zh => format!(" 基线目标是 {} ", baseline.target),
+                    Language::En => format!(" baseline pinged {} ", baseline.target),
+                },
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        rule = rule.title_bottom(Line::from(title));
+    } else {
+        render_ping_stats(frame, grid, model, &stats);
+    }
+    frame.render_widget(
+        rule,
+        Rect::new(
+            stats_area.x,
+            stats_area.y + grid_height,
+            stats_area.width,
+            1,
+        ),
+    );
+    if let Some(check) = state.geo_check(model.dashboard.snapshot.public_info.as_ref()) {
+        let (text, color) = geo_check_line(model.language, &check);
+        frame.render_widget(
+            Paragraph::new(text).style(Style::default().fg(color)),
+            Rect::new(
+                stats_area.x,
+                stats_area.y + grid_height + 1,
+                stats_area.width,
+                u16::from(stats_area.height > grid_height + 1),
+            ),
+        );
+    }
+    let history = state
+        .samples
+        .iter()
+        .map(|sample| sample.latency_ms.unwrap_or_default())
+        .collect::<Vec<_>>();
+    render_history(
+        frame,
+        chart_area,
+        model,
+        Block::default().title(tr(model.language, "延迟曲线", "Latency History")),
+        &history,
+        PRIMARY,
+    );
+    let logs = state
+        .common
+        .log
+        .iter()
+        .rev()
+        .map(|entry| ListItem::new(entry.clone()));
+    frame.render_widget(
+        List::new(logs).block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(tr(model.language, "日志", "Log"))
+                .style(Style::default().fg(MUTED)),
+        ),
+        log_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+/// Last, min, max, average, jitter and loss in a three-by-two grid.
+fn render_ping_stats(frame: &mut Frame, area: Rect, model: &AppModel, stats: &PingStats) {
+    let third = area.width / 3;
+    let cells = [
+        (
+            format!(
+                "{}: {} ms",
+                tr(model.language, "最近", "Last"),
+                format_optional_u64(stats.last_ms)
+            ),
+            SECONDARY,
+        ),
+        (
+            format!(
+                "{}: {} ms",
+                tr(model.language, "最小", "Min"),
+                format_optional_u64(stats.min_ms)
+            ),
+            Color::Green,
+        ),
+        (
+            format!(
+                "{}: {} ms",
+                tr(model.language, "最大", "Max"),
+                format_optional_u64(stats.max_ms)
+            ),
+            Color::Red,
+        ),
+        (
+            format!(
+                "{}: {} ms",
+                tr(model.language, "平均", "Average"),
+                format_optional_f64(model.region_format, stats.average_ms)
+            ),
+            Color::White,
+        ),
+        (
+            format!(
+                "{}: {} ms",
+                tr(model.language, "抖动", "Jitter"),
+                format_optional_f64(model.region_format, stats.jitter_ms)
+            ),
+            Color::White,
+        ),
+        (
+            format!(
+                "{}: {:.1}%",
+                tr(model.language, "丢包", "Loss"),
+                stats.loss_percent
+            ),
+            Color::White,
+        ),
+    ];
+    for (index, (text, color)) in cells.into_iter().enumerate() {
+        frame.render_widget(
+            Paragraph::new(text).style(Style::default().fg(color)),
+            Rect::new(
+                area.x + third * (index % 3) as u16,
+                area.y + (index / 3) as u16,
+                third,
+                1,
+            ),
+        );
+    }
+}
+
+/// The live statistics over the pinned ones and the difference; all of them
+/// are better lower, so a drop is green.
+fn render_ping_comparison(
+    frame: &mut Frame,
+    area: Rect,
+    model: &AppModel,
+    now: &PingStats,
+    then: &PingStats,
+) {
+    let language = model.language;
+    let as_f64 = |value: Option<u64>| value.map(|value| value as f64);
+    let columns = [
+        (
+            tr(language, "最小", "Min"),
+            as_f64(now.min_ms),
+            as_f64(then.min_ms),
+            Color::Green,
+        ),
+        (
+            tr(language, "最大", "Max"),
+            as_f64(now.max_ms),
+            as_f64(then.max_ms),
+            Color::Red,
+        ),
+        (
+            tr(language, "平均", "Average"),
+            now.average_ms,
+            then.average_ms,
+            Color::White,
+        ),
+        (
+            tr(language, "抖动", "Jitter"),
+            now.jitter_ms,
+            then.jitter_ms,
+            Color::White,
+        ),
+        (
+            tr(language, "丢包 %", "Loss %"),
+            Some(now.loss_percent),
+            Some(then.loss_percent),
+            Color::White,
+        ),
+    ];
+    let figure =
+        |value: Option<f64>| value.map_or_else(|| "—".into(), |value| format!("{value:.1}"));
+    let header =
+        Row::new(std::iter::once(Cell::from("ms")).chain(columns.iter().map(
+            |(label, _, _, color)| Cell::from(label.clone()).style(Style::default().fg(*color)),
+        )));
+    let current = Row::new(
+        std::iter::once(Cell::from(tr(language, "现在", "Now"))).chain(
+            columns
+                .iter()
+                .map(|(_, now, _, _)| Cell::from(figure(*now))),
+        ),
+    );
+    let pinned = Row::new(
+        std::iter::once(Cell::from(tr(language, "基线", "Baseline"))).chain(
+            columns
+                .iter()
+                .map(|(_, _, then, _)| Cell::from(figure(*then))),
+        ),
+    )
+    .style(Style::default().fg(MUTED));
+    let change = Row::new(std::iter::once(Cell::from("Δ")).chain(columns.iter().map(
+        |(_, now, then, _)| match (now, then) {
+            (Some(now), Some(then)) => {
+                let delta = now - then;
+                let color = if delta.abs() < 0.05 {
+                    MUTED
+                } else if delta < 0.0 {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                Cell::from(format!("{delta:+.1}")).style(Style::default().fg(color))
+            }
+            _ => Cell::from("—").style(Style::default().fg(MUTED)),
+        },
+    )));
+    frame.render_widget(
+        Table::new([current, pinned, change], [Constraint::Length(10); 6]).header(header),
+        area,
+    );
+}
+
+/// The verdict on the claimed location, under the ping statistics.
+fn geo_check_line(
+    language: Language,
+    check: &Result<iptools_core::GeoCheck, iptools_core::GeoProblem>,
+) -> (String, Color) {
+    let check = match check {
+        Ok(check) => check,
+        Err(iptools_core::GeoProblem::UnknownPlace(place)) => {
+            return (
+                match language {
+                    Language::Zh => format!("未知地点“{place}”：请填城市名或 纬度,经度"),
+                    Language::En => format!("Unknown place \"{place}\": use a city or lat,lon"),
+                },
+                Color::Yellow,
+            );
+        }
+        Err(iptools_core::GeoProblem::NoOrigin) => {
+            return (
+                tr(
+                    language,
+                    "填写本机位置后才能校验声称位置",
+                    "Set my location to check the claim",
+                )
+                .into(),
+                MUTED,
+            );
+        }
+    };
+    let claimed = &check.claimed.name;
+    let distance = check.distance_km.round();
+    match (check.verdict(), language) {
+        (iptools_core::GeoVerdict::Impossible, Language::Zh) => (
+            format!(
+                "不可能在 {claimed}：{} ms 最远只够 {:.0} km，实际相距 {distance} km",
+                check.rtt_ms,
+                check.reach_km()
+            ),
+            Color::Red,
+        ),
+        (iptools_core::GeoVerdict::Impossible, Language::En) => (
+            format!(
+                "Not in {claimed}: {} ms reaches {:.0} km at most, it is {distance} km away",
+                check.rtt_ms,
+                check.reach_km()
+            ),
+            Color::Red,
+        ),
+        (iptools_core::GeoVerdict::Unlikely, Language::Zh) => (
+            format!(
+                "{claimed} 存疑：光纤往返至少 {:.1} ms，可能是任播节点",
+                check.fibre_floor_ms()
+            ),
+            Color::Yellow,
+        ),
+        (iptools_core::GeoVerdict::Unlikely, Language::En) => (
+            format!(
+                "{claimed} unlikely: fibre needs ≥{:.1} ms, maybe anycast",
+                check.fibre_floor_ms()
+            ),
+            Color::Yellow,
+        ),
+        (iptools_core::GeoVerdict::Plausible, Language::Zh) => (
+            format!(
+                "{claimed} 可信：相距 {distance} km，光纤往返至少 {:.1} ms",
+                check.fibre_floor_ms()
+            ),
+            PRIMARY,
+        ),
+        (iptools_core::GeoVerdict::Plausible, Language::En) => (
+            format!(
+                "{claimed} plausible: {distance} km away, fibre needs ≥{:.1} ms",
+                check.fibre_floor_ms()
+            ),
+            PRIMARY,
+        ),
+    }
+}
+
+fn render_trace(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.trace;
+    if let Some(comparison) = &state.comparison {
+        render_trace_comparison(area, frame, model, comparison);
+        return;
+    }
+    let status_area = bottom_row(area);
+    let asn_area = Rect::new(
+        area.x,
+        status_area.y.saturating_sub(1).max(area.y),
+        area.width,
+        u16::from(status_area.y > area.y),
+    );
+    let table_area = Rect::new(
+        area.x,
+        area.y,
+        area.width,
+        asn_area.y.saturating_sub(area.y),
+    );
+    let origin = |hop: &iptools_core::TraceHop| {
+        hop.address
+            .as_deref()
+            .and_then(|address| state.asn.origin(address))
+    };
+    // The AS column only takes room once a lookup has answered for a hop.
+    let as_width = if state.hops.iter().any(|hop| origin(hop).is_some()) {
+        22
+    } else {
+        0
+    };
+    let hops = state.hops.iter().enumerate().map(|(index, hop)| {
+        Row::new(vec![
+            Cell::from(format!("{:>2}", hop.ttl)).style(Style::default().fg(SECONDARY)),
+            Cell::from(hop.address.as_deref().map_or("*".into(), shown_ip)),
+            Cell::from(
+                hop.latency_ms
+                    .map_or_else(|| "*".into(), |value| format!("{value} ms")),
+            )
+            .style(Style::default().fg(PRIMARY)),
+            Cell::from(
+                origin(hop)
+                    .map(iptools_core::AsnOrigin::label)
+                    .unwrap_or_default(),
+            )
+            .style(Style::default().fg(SECONDARY)),
+            Cell::from(hop.hostname.as_deref().map_or("-".into(), shown_host))
+                .style(Style::default().fg(SUBTLE)),
+        ])
+        .style(if index == state.selected {
+            Style::default().bg(SELECTED)
+        } else {
+            Style::default()
+        })
+    });
+    frame.render_widget(
+        Table::new(
+            hops,
+            [
+                Constraint::Length(4),
+                Constraint::Length(17),
+                Constraint::Length(10),
+                Constraint::Length(as_width),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "跳数", "Hop"),
+                tr(model.language, "地址", "Address"),
+                "RTT".into(),
+                "AS".into(),
+                tr(model.language, "主机", "Host"),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    let selected = state.hops.get(state.selected);
+    let detail = if state.asn.job.is_some() {
+        Span::styled(
+            tr(model.language, "正在查询 ASN…", "Looking up ASNs…"),
+            Style::default().fg(MUTED),
+        )
+    } else if let Some(error) = &state.asn.error {
+        Span::styled(
+            format!(
+                "{}: {}",
+                tr(model.language, "ASN 查询失败", "ASN lookup failed"),
+                error.message
+            ),
+            Style::default().fg(Color::Red),
+        )
+    } else if let Some(origin) = selected.and_then(origin) {
+        Span::styled(
+            asn_detail(model.language, origin),
+            Style::default().fg(SUBTLE),
+        )
+    } else {
+        Span::raw("")
+    };
+    frame.render_widget(Paragraph::new(Line::from(detail)), asn_area);
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+/// Prefix and AS path of the selected hop, under the trace table.
+fn asn_detail(language: Language, origin: &iptools_core::AsnOrigin) -> String {
+    let Some(prefix) = &origin.prefix else {
+        return tr(language, "该地址未被宣告", "Address not announced").into();
+    };
+    let path = origin
+        .path
+        .iter()
+        .map(|asn| format!("AS{asn}"))
+        .collect::<Vec<_>>()
+        .join(" → ");
+    match (path.is_empty(), language) {
+        (true, _) => format!("{prefix}  {}", origin.label()),
+        (false, Language::Zh) => format!("{prefix}  AS 路径 {path}"),
+        (false, Language::En) => format!("{prefix}  AS path {path}"),
+    }
+}
+
+/// Two traces side by side, lined up by TTL, with the hop where the paths
+/// part picked out.
+fn render_trace_comparison(
+    area: Rect,
+    frame: &mut Frame,
+    model: &AppModel,
+    comparison: &iptools_core::TraceComparison,
+) {
+    let state = &model.diagnostics.trace;
+    let language = model.language;
+    let verdict_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        verdict_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(verdict_area.bottom()),
+    );
+    let divergence = iptools_core::divergence(&state.hops, &comparison.hops);
+    let verdict = match (&comparison.error, divergence) {
+        (Some(error), _) => (
+            format!("{}: {}", tr(language, "B 失败", "B failed"), error.message),
+            Color::Red,
+        ),
+        (None, Some(iptools_core::TraceDivergence::At(ttl))) => (
+            match language {
+                Language::Zh => format!("两条路径在第 {ttl} 跳分开"),
+                Language::En => format!("Paths diverge at hop {ttl}"),
+            },
+            Color::Yellow,
+        ),
+        (None, Some(iptools_core::TraceDivergence::Shared(ttl))) => (
+            match language {
+                Language::Zh => format!("前 {ttl} 跳路径相同"),
+                Language::En => format!("Same path through hop {ttl}"),
+            },
+            PRIMARY,
+        ),
+        (None, None) => (
+            tr(
+                language,
+                "等待两侧都有回应的跳",
+                "Waiting for hops both sides hear",
+            )
+            .into(),
+            MUTED,
+        ),
+    };
+    let side = |request: &iptools_core::TraceRequest| match &request.via {
+        Some(via) => format!("{} via {via}", request.target),
+        None => request.target.clone(),
+    };
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(Span::styled(
+                format!(
+                    "A: {}   B: {}",
+                    side(&state.request),
+                    side(&comparison.request)
+                ),
+                Style::default().fg(MUTED),
+            )),
+            Line::from(Span::styled(
+                verdict.0,
+                Style::default().fg(verdict.1).add_modifier(Modifier::BOLD),
+            )),
+        ]),
+        verdict_area,
+    );
+    let address = |hop: Option<&iptools_core::TraceHop>| {
+        hop.map_or("".into(), |hop| {
+            hop.address.as_deref().map_or("*".into(), shown_ip)
+        })
+        .into_owned()
+    };
+    let rtt = |hop: Option<&iptools_core::TraceHop>| {
+        hop.map_or_else(String::new, |hop| {
+            hop.latency_ms
+                .map_or_else(|| "*".into(), |value| format!("{value} ms"))
+        })
+    };
+    let rows = (1..=state.rows()).map(|ttl| {
+        let find = |hops: &[iptools_core::TraceHop]| {
+            hops.iter().find(|hop| usize::from(hop.ttl) == ttl).cloned()
+        };
+        let (left, right) = (find(&state.hops), find(&comparison.hops));
+        let differs = matches!(
+            (left.as_ref().and_then(|hop| hop.address.as_ref()), right.as_ref().and_then(|hop| hop.address.as_ref())),
+            (Some(left), Some(right)) if left != right
+        );
+        let mut style = if differs {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        if divergence == Some(iptools_core::TraceDivergence::At(ttl as u8)) {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if ttl - 1 == state.selected {
+            style = style.bg(SELECTED);
+        }
+        Row::new(vec![
+            Cell::from(format!("{ttl:>2}")).style(Style::default().fg(SECONDARY)),
+            Cell::from(address(left.as_ref())),
+            Cell::from(rtt(left.as_ref())).style(Style::default().fg(PRIMARY)),
+            Cell::from(address(right.as_ref())),
+            Cell::from(rtt(right.as_ref())).style(Style::default().fg(PRIMARY)),
+        ])
+        .style(style)
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Min(17),
+                Constraint::Length(9),
+                Constraint::Min(17),
+                Constraint::Length(9),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(language, "跳数", "Hop"),
+                "A".into(),
+                "RTT".into(),
+                "B".into(),
+                "RTT".into(),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_mtu(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.mtu;
+    let verdict_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        verdict_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(verdict_area.bottom()),
+    );
+    let verdict = state.summary.map(|summary| match summary.path_mtu {
+        Some(_) if summary.jumbo() => (
+            tr(
+                model.language,
+                "巨帧端到端可用",
+                "Jumbo frames carried end to end",
+            ),
+            PRIMARY,
+        ),
+        Some(mtu) if mtu > 1_500 => (
+            tr(
+                model.language,
+                "部分巨帧（小于 9000）",
+                "Partial jumbo (below 9000)",
+            ),
+            Color::Yellow,
+        ),
+        Some(1_500) => (
+            tr(model.language, "标准以太网 MTU", "Standard Ethernet MTU"),
+            SECONDARY,
+        ),
+        Some(_) => (
+            tr(
+                model.language,
+                "低于以太网 MTU（隧道 / PPPoE？）",
+                "Below Ethernet MTU (tunnel / PPPoE?)",
+            ),
+            Color::Yellow,
+        ),
+        None => (
+            tr(model.language, "对端无回应", "Peer did not answer"),
+            Color::Red,
+        ),
+    });
+    let largest = state
+        .probes
+        .iter()
+        .filter(|probe| matches!(probe.outcome, iptools_core::MtuOutcome::Carried { .. }))
+        .map(|probe| probe.size)
+        .max();
+    let mut line = vec![
+        Span::styled(
+            format!("{}: ", tr(model.language, "最大承载", "Largest carried")),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(
+            largest.map_or_else(|| "—".into(), |size| format!("{size} B")),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if let Some((text, color)) = verdict {
+        line.push(Span::raw("  "));
+        line.push(Span::styled(text, Style::default().fg(color)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(line)), verdict_area);
+
+    let rows = state.probes.iter().map(|probe| {
+        let (result, rtt, color) = match probe.outcome {
+            iptools_core::MtuOutcome::Carried { rtt_ms } => (
+                tr(model.language, "通过", "Carried"),
+                format!("{rtt_ms} ms"),
+                PRIMARY,
+            ),
+            iptools_core::MtuOutcome::TooBig => (
+                tr(model.language, "需分片", "Too big"),
+                "-".into(),
+                Color::Yellow,
+            ),
+            iptools_core::MtuOutcome::Lost => {
+                (tr(model.language, "无回应", "No reply"), "*".into(), SUBTLE)
+            }
+        };
+        Row::new(vec![
+            Cell::from(format!("{:>5}", probe.size)).style(Style::default().fg(SECONDARY)),
+            Cell::from(result).style(Style::default().fg(color)),
+            Cell::from(rtt),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "包长", "Size"),
+                tr(model.language, "结果", "Result"),
+                "RTT".into(),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_nat(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.nat;
+    let summary_area = Rect::new(area.x, area.y, area.width, area.height.min(3));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        summary_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(summary_area.bottom()),
+    );
+    let lines = match &state.summary {
+        Some(summary) => {
+            let (label, hint, color) = match summary.nat_type {
+                NatType::Open => (
+                    tr(model.language, "无 NAT（公网地址）", "Open internet"),
+                    tr(
+                        model.language,
+                        "可直接接受入站连接",
+                        "Inbound connections work directly",
+                    ),
+                    PRIMARY,
+                ),
+                NatType::FullCone => (
+                    tr(model.language, "完全锥形", "Full cone"),
+                    tr(
+                        model.language,
+                        "任何外部主机都能通过映射访问",
+                        "Any host can reach the mapping",
+                    ),
+                    PRIMARY,
+                ),
+                NatType::RestrictedCone => (
+                    tr(model.language, "地址限制锥形", "Restricted cone"),
+                    tr(
+                        model.language,
+                        "P2P 打洞通常可行",
+                        "Hole punching usually works",
+                    ),
+                    SECONDARY,
+                ),
+                NatType::PortRestrictedCone => (
+                    tr(model.language, "端口限制锥形", "Port-restricted cone"),
+                    tr(
+                        model.language,
+                        "P2P 打洞通常可行",
+                        "Hole punching usually works",
+                    ),
+                    SECONDARY,
+                ),
+                NatType::Cone => (
+                    tr(
+                        model.language,
+                        "锥形（过滤未测）",
+                        "Cone (filtering untested)",
+                    ),
+                    tr(
+                        model.language,
+                        "服务器不支持变更请求，无法细分过滤行为",
+                        "The server cannot test filtering behaviour",
+                    ),
+                    SECONDARY,
+                ),
+                NatType::Symmetric => (
+                    tr(model.language, "对称型", "Symmetric"),
+                    tr(
+                        model.language,
+                        "每个目标使用不同映射，P2P 通常需要 TURN 中继",
+                        "Mappings differ per destination; peers usually need a TURN relay",
+                    ),
+                    Color::Yellow,
+                ),
+                NatType::Blocked => (
+                    tr(model.language, "UDP 被阻断", "UDP blocked"),
+                    tr(
+                        model.language,
+                        "没有 STUN 服务器应答",
+                        "No STUN server answered",
+                    ),
+                    Color::Red,
+                ),
+                NatType::Unknown => (
+                    tr(model.language, "未知", "Unknown"),
+                    tr(
+                        model.language,
+                        "只有一个服务器应答，无法比较映射",
+                        "Only one server answered; mappings cannot be compared",
+                    ),
+                    MUTED,
+                ),
+            };
+            let hairpin = match summary.hairpin {
+                Some(true) => tr(model.language, "支持", "yes"),
+                Some(false) => tr(model.language, "不支持", "no"),
+                None => "-".into(),
+            };
+            vec![
+                Line::from(vec![
+                    Span::styled(
+                        label,
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format!("  {hint}"), Style::default().fg(MUTED)),
+                ]),
+                Line::from(format!(
+                    "{}: {}    {}: {}    {}: {hairpin}",
+                    tr(model.language, "本地", "Local"),
+                    shown_text(&summary.local),
+                    tr(model.language, "映射", "Mapped"),
+                    summary.mapped.as_deref().map_or("-".into(), shown_text),
+                    tr(model.language, "回环", "Hairpin"),
+                )),
+            ]
+        }
+        None => vec![Line::styled(
+            tr(
+                model.language,
+                "向两个 STUN 服务器发送绑定请求并比较映射",
+                "Sends binding requests to two STUN servers and compares the mappings",
+            ),
+            Style::default().fg(MUTED),
+        )],
+    };
+    frame.render_widget(Paragraph::new(lines), summary_area);
+
+    let rows = state.probes.iter().map(|probe| {
+        Row::new(vec![
+            Cell::from(probe.server.clone()),
+            Cell::from(probe.mapped.as_deref().map_or("*".into(), shown_text)).style(
+                Style::default().fg(if probe.mapped.is_some() {
+                    PRIMARY
+                } else {
+                    SUBTLE
+                }),
+            ),
+            Cell::from(
+                probe
+                    .rtt_ms
+                    .map_or_else(|| "*".into(), |rtt| format!("{rtt} ms")),
+            ),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(28),
+                Constraint::Length(24),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "服务器", "Server"),
+                tr(model.language, "映射地址", "Mapped"),
+                "RTT".into(),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_ipv6(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.ipv6;
+    let verdict_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let list_area = Rect::new(
+        area.x,
+        verdict_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(verdict_area.bottom()),
+    );
+    let verdict = match state.common.status {
+        TaskStatus::Done if ipv6_ready(&state.results) => Line::styled(
+            tr(model.language, "IPv6 已就绪", "IPv6 ready"),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+        TaskStatus::Done => Line::styled(
+            tr(model.language, "IPv6 未就绪", "IPv6 not ready"),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        _ => Line::styled(
+            tr(
+                model.language,
+                "逐项检查本机与网络的 IPv6 能力",
+                "Checks this host and network for working IPv6, item by item",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    frame.render_widget(Paragraph::new(verdict), verdict_area);
+
+    let lines = Ipv6Check::ALL
+        .iter()
+        .map(|check| {
+            let label = match check {
+                Ipv6Check::GlobalAddress => tr(model.language, "全局地址", "Global address"),
+                Ipv6Check::DefaultRoute => tr(model.language, "默认路由", "Default route"),
+                Ipv6Check::DnsAaaa => tr(model.language, "AAAA 解析", "AAAA lookup"),
+                Ipv6Check::Reachability => tr(model.language, "纯 IPv6 连通", "v6-only reach"),
+                Ipv6Check::PathMtu => tr(model.language, "路径 MTU", "Path MTU"),
+            };
+            let result = state.results.iter().find(|result| result.check == *check);
+            let (mark, color) = match result.map(|result| result.verdict) {
+                Some(Ipv6Verdict::Pass) => ("✓", PRIMARY),
+                Some(Ipv6Verdict::Fail) => ("✗", Color::Red),
+                Some(Ipv6Verdict::Skipped) => ("-", SUBTLE),
+                None => ("·", SUBTLE),
+            };
+            Line::from(vec![
+                Span::styled(format!(" {mark} "), Style::default().fg(color)),
+                Span::raw(format!("{}  ", pad_display(&label, 16))),
+                Span::styled(
+                    result
+                        .map_or("", |result| result.detail.as_str())
+                        .to_string(),
+                    Style::default().fg(MUTED),
+                ),
+            ])
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines), list_area);
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_dns_bench(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.dns_bench;
+    let summary_area = Rect::new(area.x, area.y, area.width, area.height.min(3));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        summary_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(summary_area.bottom()),
+    );
+    let finished = state.common.status == TaskStatus::Done;
+    let summary = match state.scores.first() {
+        Some(best) if finished && best.reliable() => Line::from(vec![
+            Span::styled(
+                tr(model.language, "推荐：", "Recommended: "),
+                Style::default().fg(MUTED),
+            ),
+            Span::styled(
+                best.resolver.label(),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        _ if finished => Line::styled(
+            tr(
+                model.language,
+                "没有解析器稳定应答",
+                "No resolver answered reliably",
+            ),
+            Style::default().fg(Color::Red),
+        ),
+        _ => Line::styled(
+            tr(
+                model.language,
+                "按中位延迟与失败率给解析器排名",
+                "Ranks resolvers by median latency and failure rate",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    let mut encrypted = Vec::new();
+    for check in encrypted_dns_checks(&state.scores) {
+        let (text, color) = match (check.works, check.overhead_ms) {
+            (true, Some(overhead)) => (
+                format!(
+                    "{} ✓ {overhead:+.1} ms {}",
+                    check.transport.label(),
+                    tr(model.language, "对比 UDP", "vs UDP")
+                ),
+                PRIMARY,
+            ),
+            (true, None) => (format!("{} ✓", check.transport.label()), PRIMARY),
+            (false, _) => (
+                format!(
+                    "{} ✗ {}",
+                    check.transport.label(),
+                    tr(model.language, "不可用", "unreachable")
+                ),
+                Color::Red,
+            ),
+        };
+        encrypted.push(Span::styled(
+            format!("{text}    "),
+            Style::default().fg(color),
+        ));
+    }
+    if udp_dns_blocked(&state.scores) {
+        encrypted.push(Span::styled(
+            tr(
+                model.language,
+                "UDP 53 被拦截，建议改用加密 DNS",
+                "UDP 53 is blocked; use encrypted DNS",
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(vec![summary, Line::from(encrypted)]),
+        summary_area,
+    );
+
+    let latency = |value: Option<f64>| value.map_or_else(|| "*".into(), |ms| format!("{ms:.1}"));
+    let rows = state.scores.iter().enumerate().map(|(index, score)| {
+        let (color, mark) = if !score.reliable() {
+            (Color::Red, "✗ ")
+        } else if finished && index == 0 {
+            (PRIMARY, "★ ")
+        } else {
+            (Color::Reset, "  ")
+        };
+        let mark = state_mark(model, mark);
+        Row::new(vec![
+            Cell::from(format!("{mark}{}", score.resolver.label()))
+                .style(Style::default().fg(color)),
+            Cell::from(latency(score.cached_ms)),
+            Cell::from(latency(score.uncached_ms)),
+            Cell::from(latency(score.dotcom_ms)),
+            Cell::from(latency(score.median_ms)),
+            Cell::from(format!("{:.0}%", score.failure_rate() * 100.0)),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(22),
+                Constraint::Length(9),
+                Constraint::Length(9),
+                Constraint::Length(9),
+                Constraint::Length(9),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "解析器", "Resolver"),
+                tr(model.language, "缓存", "Cached"),
+                tr(model.language, "未缓存", "Uncached"),
+                ".com".into(),
+                tr(model.language, "中位 ms", "Median ms"),
+                tr(model.language, "失败", "Failed"),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn gaming_preset_label(preset: GamingPreset, language: Language) -> Cow<'static, str> {
+    match preset {
+        GamingPreset::All => tr(language, "全部地区", "All regions"),
+        GamingPreset::Americas => tr(language, "美洲", "Americas"),
+        GamingPreset::Europe => tr(language, "欧洲", "Europe"),
+        GamingPreset::AsiaPacific => tr(language, "亚太", "Asia Pacific"),
+        GamingPreset::Custom => tr(language, "仅自定义", "Custom only"),
+    }
+}
+
+/// Green while a region is fine for fast-paced games, yellow when players
+/// will notice, red beyond.
+fn gaming_latency_color(median_ms: f64) -> Color {
+    if median_ms < 60.0 {
+        PRIMARY
+    } else if median_ms < 120.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+fn render_gaming(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.gaming;
+    let summary_area = Rect::new(area.x, area.y, area.width, area.height.min(3));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        summary_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(summary_area.bottom()),
+    );
+    let finished = state.common.status == TaskStatus::Done;
+    let summary = match state.scores.first() {
+        Some(best) if finished && best.reliable() => Line::from(vec![
+            Span::styled(
+                tr(model.language, "最佳地区：", "Best region: "),
+                Style::default().fg(MUTED),
+            ),
+            Span::styled(
+                best.endpoint.region.clone(),
+                Style::default()
+                    .fg(gaming_latency_color(best.median_ms.unwrap_or_default()))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        _ if finished => Line::styled(
+            tr(
+                model.language,
+                "没有地区稳定应答",
+                "No region answered reliably",
+            ),
+            Style::default().fg(Color::Red),
+        ),
+        _ => Line::styled(
+            tr(
+                model.language,
+                "按中位延迟给游戏服务器地区排名，抖动列在旁边",
+                "Ranks game server regions by median latency, jitter alongside",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    let detail = match state.scores.first() {
+        Some(best) if finished && best.reliable() => Line::styled(
+            format!(
+                "{}: {:.1} ms    {}: {:.1} ms    {}: {:.0}%",
+                tr(model.language, "中位", "Median"),
+                best.median_ms.unwrap_or_default(),
+                tr(model.language, "抖动", "Jitter"),
+                best.jitter_ms.unwrap_or_default(),
+                tr(model.language, "丢包", "Loss"),
+                best.loss_rate() * 100.0
+            ),
+            Style::default().fg(SECONDARY),
+        ),
+        _ => Line::styled(
+            format!(
+                "{}: {}",
+                tr(model.language, "预设", "Preset"),
+                gaming_preset_label(state.request.preset, model.language)
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    frame.render_widget(Paragraph::new(vec![summary, detail]), summary_area);
+
+    let latency = |value: Option<f64>| value.map_or_else(|| "*".into(), |ms| format!("{ms:.1}"));
+    let rows = state.scores.iter().enumerate().map(|(index, score)| {
+        let (color, mark) = if !score.reliable() {
+            (Color::Red, "✗ ")
+        } else if finished && index == 0 {
+            (PRIMARY, "★ ")
+        } else {
+            (Color::Reset, "  ")
+        };
+        let mark = state_mark(model, mark);
+        Row::new(vec![
+            Cell::from(format!("{mark}{}", score.endpoint.region))
+                .style(Style::default().fg(color)),
+            Cell::from(latency(score.median_ms)).style(
+                Style::default().fg(score.median_ms.map_or(Color::Red, gaming_latency_color)),
+            ),
+            Cell::from(latency(score.jitter_ms)),
+            Cell::from(format!("{:.0}%", score.loss_rate() * 100.0)),
+            Cell::from(format!(
+                "{} {}:{}",
+                score.endpoint.protocol.label(),
+                score.endpoint.host,
+                score.endpoint.port
+            ))
+            .style(Style::default().fg(MUTED)),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(25),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(5),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "地区", "Region"),
+                tr(model.language, "中位 ms", "Median ms"),
+                tr(model.language, "抖动 ms", "Jitter ms"),
+                tr(model.language, "丢包", "Loss"),
+                tr(model.language, "服务器", "Server"),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+/// TCP connect times are one round trip plus the provider's accept.
+fn cloud_latency_color(latency_ms: f64) -> Color {
+    if latency_ms < 100.0 {
+        PRIMARY
+    } else if latency_ms < 250.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+fn render_cloud(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.cloud;
+    let summary_area = Rect::new(area.x, area.y, area.width, area.height.min(3));
+    let status_area = bottom_row(area);
+    let matrix_area = Rect::new(
+        area.x,
+        summary_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(summary_area.bottom()),
+    );
+    let finished = state.common.status == TaskStatus::Done;
+    let verdict = match cloud_verdict(&state.probes) {
+        Some(CloudVerdict::AllReachable) if finished => Line::styled(
+            tr(
+                model.language,
+                "全部可达：问题不在上游",
+                "Everything reachable: the problem is not upstream",
+            ),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+        Some(CloudVerdict::ProviderOutage(failed)) if finished => Line::styled(
+            format!(
+                "{}{}",
+                tr(
+                    model.language,
+                    "疑似服务商故障：",
+                    "Likely a provider outage: "
+                ),
+                failed.join(", ")
+            ),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Some(CloudVerdict::LocalProblem) if finished => Line::styled(
+            tr(
+                model.language,
+                "大部分端点不可达：检查本地网络",
+                "Most endpoints unreachable: check your own network",
+            ),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        _ => Line::styled(
+            tr(
+                model.language,
+                "连接各云区域、CDN 与任播 DNS，区分本地故障与区域故障",
+                "Connects to cloud regions, CDNs and anycast DNS to tell a local fault from an outage",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    let reachable = state
+        .probes
+        .iter()
+        .filter(|probe| probe.reachable())
+        .count();
+    let counts = Line::styled(
+        format!(
+            "{}: {reachable}/{}",
+            tr(model.language, "可达", "Reachable"),
+            state.probes.len()
+        ),
+        Style::default().fg(SECONDARY),
+    );
+    frame.render_widget(Paragraph::new(vec![verdict, counts]), summary_area);
+
+    // Every cell is drawn from the start; unanswered ones stay pending.
+    let endpoints = cloud_endpoints();
+    let rows = CloudGroup::ALL.iter().map(|&group| {
+        let mut cells =
+            vec![Cell::from(group.label()).style(Style::default().add_modifier(Modifier::BOLD))];
+        cells.extend(
+            endpoints
+                .iter()
+                .filter(|endpoint| endpoint.group == group)
+                .map(|endpoint| {
+                    let result = match state
+                        .probes
+                        .iter()
+                        .find(|probe| probe.endpoint == *endpoint)
+                    {
+                        Some(probe) => match probe.latency_ms {
+                            Some(ms) => Span::styled(
+                                format!("{}{ms:.0} ms", state_mark(model, "✓ ")),
+                                Style::default().fg(cloud_latency_color(ms)),
+                            ),
+                            None => Span::styled(
+                                format!(
+                                    "{}{}",
+                                    state_mark(model, "✗ "),
+                                    tr(model.language, "不可达", "down")
+                                ),
+                                Style::default().fg(Color::Red),
+                            ),
+                        },
+                        None => Span::styled("·", Style::default().fg(MUTED)),
+                    };
+                    Cell::from(vec![
+                        Line::styled(endpoint.name.clone(), Style::default().fg(MUTED)),
+                        Line::from(result),
+                    ])
+                }),
+        );
+        Row::new(cells).height(2)
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        ),
+        matrix_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_ab_test(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.ab_test;
+    let summary_area = Rect::new(area.x, area.y, area.width, area.height.min(3));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        summary_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(summary_area.bottom()),
+    );
+    let (a, b) = (state.leg(AbSide::A), state.leg(AbSide::B));
+    let verdict = match (a, b) {
+        (Some(a), Some(b)) if state.common.status == TaskStatus::Done => {
+            let verdict = ab_verdict(a, b);
+            match verdict.winner {
+                Some(side) => {
+                    let (leg, wins) = match side {
+                        AbSide::A => (a, verdict.wins_a),
+                        AbSide::B => (b, verdict.wins_b),
+                    };
+                    Line::styled(
+                        format!(
+                            "{} {} ({}) · {wins}/{}",
+                            tr(model.language, "更好：", "Better:"),
+                            side.label(),
+                            leg.via,
+                            AbMetric::ALL.len()
+                        ),
+                        Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+                    )
+                }
+                None => Line::styled(
+                    tr(
+                        model.language,
+                        "两个网卡不相上下",
+                        "No clear winner between the two",
+                    ),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            }
+        }
+        _ => Line::styled(
+            tr(
+                model.language,
+                "分别从两个网卡跑同一组 Ping、下载与 DNS 测试并逐项对比",
+                "Runs the same pings, download and DNS lookups over two interfaces and compares them",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    let source = |side: AbSide, leg: Option<&iptools_core::AbLeg>| match leg {
+        Some(leg) if !leg.source.is_empty() => format!("{} = {}", side.label(), leg.source),
+        _ => format!("{} = —", side.label()),
+    };
+    let sources = Line::styled(
+        format!(
+            "{}: {} · {}",
+            tr(model.language, "源地址", "Source"),
+            source(AbSide::A, a),
+            source(AbSide::B, b)
+        ),
+        Style::default().fg(SECONDARY),
+    );
+    frame.render_widget(Paragraph::new(vec![verdict, sources]), summary_area);
+
+    let value = |metric: AbMetric, leg: Option<&iptools_core::AbLeg>| match leg {
+        None => Span::styled("·", Style::default().fg(MUTED)),
+        Some(leg) => match metric.value(leg) {
+            None => Span::styled("—", Style::default().fg(MUTED)),
+            Some(value) => Span::raw(match metric {
+                AbMetric::Loss => format!("{value:.1} %"),
+                AbMetric::Download => format!("{:.1} Mbps", value * 8.0 / 1_000_000.0),
+                _ => format!("{value:.1} ms"),
+            }),
+        },
+    };
+    let side_header = |side: AbSide, leg: Option<&iptools_core::AbLeg>| {
+        let via = leg.map_or_else(
+            || match side {
+                AbSide::A => state.request.first.clone(),
+                AbSide::B => state.request.second.clone(),
+            },
+            |leg| leg.via.clone(),
+        );
+        Cell::from(format!("{} · {via}", side.label()))
+    };
+    let header = Row::new(vec![
+        Cell::from(tr(model.language, "指标", "Metric")),
+        side_header(AbSide::A, a),
+        side_header(AbSide::B, b),
+        Cell::from(tr(model.language, "更好", "Better")),
+    ])
+    .style(Style::default().fg(MUTED).add_modifier(Modifier::BOLD));
+    let rows = AbMetric::ALL.iter().map(|&metric| {
+        let outcome = a.zip(b).and_then(|(a, b)| metric.outcome(a, b));
+        let winner_style = |side| {
+            if outcome == Some(AbOutcome::Better(side)) {
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            }
+        };
+        Row::new(vec![
+            Cell::from(metric.label(model.language)),
+            Cell::from(value(metric, a)).style(winner_style(AbSide::A)),
+            Cell::from(value(metric, b)).style(winner_style(AbSide::B)),
+            Cell::from(match outcome {
+                Some(AbOutcome::Better(side)) => {
+                    Span::styled(side.label(), Style::default().fg(PRIMARY))
+                }
+                Some(AbOutcome::Tie) => Span::styled(
+                    tr(model.language, "持平", "tie"),
+                    Style::default().fg(MUTED),
+                ),
+                None => Span::styled("—", Style::default().fg(MUTED)),
+            }),
+        ])
+    });
+    let errors = [a, b]
+        .into_iter()
+        .flatten()
+        .filter_map(|leg| Some(format!("{}: {}", leg.side.label(), leg.error.as_ref()?)))
+        .map(|error| Line::styled(error, Style::default().fg(Color::Yellow)))
+        .collect::<Vec<_>>();
+    let [table_area, errors_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(errors.len() as u16)])
+            .areas(table_area);
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header),
+        table_area,
+    );
+    frame.render_widget(Paragraph::new(errors), errors_area);
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_wifi(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.wifi;
+    let header_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let body_height = status_area.y.saturating_sub(header_area.bottom());
+    // The channel charts only appear once a scan has heard other networks.
+    let (chart_height, channels_height) = if state.nearby.is_empty() {
+        ((body_height / 2).min(8), 0)
+    } else {
+        ((body_height / 3).min(6), (body_height / 2).min(10))
+    };
+    let rssi_area = Rect::new(area.x, header_area.bottom(), area.width / 2, chart_height);
+    let rate_area = Rect::new(
+        rssi_area.right(),
+        header_area.bottom(),
+        area.width - rssi_area.width,
+        chart_height,
+    );
+    let band_height = channels_height / 2;
+    let band_areas = [
+        Rect::new(area.x, rssi_area.bottom(), area.width, band_height),
+        Rect::new(
+            area.x,
+            rssi_area.bottom() + band_height,
+            area.width,
+            band_height,
+        ),
+    ];
+    let roams_area = Rect::new(
+        area.x,
+        band_areas[1].bottom(),
+        area.width,
+        status_area.y.saturating_sub(band_areas[1].bottom()),
+    );
+
+    let adapter = if state.request.adapter.is_empty() {
+        tr(model.language, "无无线网卡", "No wireless adapter")
+    } else {
+        state.request.adapter.as_str().into()
+    };
+    let link = state.samples.last().and_then(|sample| sample.link.as_ref());
+    let current = match link {
+        Some(link) => Line::from(vec![
+            Span::styled(
+                format!("{}  {}  ", shown_ssid(&link.ssid), shown_mac(&link.bssid)),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!("{} · ch {}  ", link.band, link.channel),
+                Style::default().fg(SECONDARY),
+            ),
+            Span::styled(
+                format!("{} dBm", link.rssi_dbm),
+                Style::default().fg(if link.rssi_dbm < iptools_core::WEAK_RSSI_DBM {
+                    Color::Yellow
+                } else {
+                    PRIMARY
+                }),
+            ),
+            Span::styled(
+                format!("  tx {} Mbps", link.tx_rate_mbps),
+                Style::default().fg(SECONDARY),
+            ),
+        ]),
+        None if state.samples.is_empty() => Line::styled(
+            tr(
+                model.language,
+                "定时记录信号、速率、信道与 BSSID，发现接入点之间的漫游",
+                "Samples signal, rate, channel and BSSID to catch roams between access points",
+            ),
+            Style::default().fg(MUTED),
+        ),
+        None => Line::styled(
+            tr(model.language, "未连接", "Not associated"),
+            Style::default().fg(Color::Red),
+        ),
+    };
+    let mut range = vec![
+        Span::styled(
+            format!("{}: ", tr(model.language, "网卡", "Adapter")),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(adapter.to_string(), Style::default().fg(SECONDARY)),
+    ];
+    if let Some((min, average, max)) = iptools_core::rssi_range(&state.samples) {
+        range.push(Span::styled(
+            format!(
+                "  RSSI {min}/{average:.0}/{max} dBm  {}: {}",
+                tr(model.language, "漫游", "Roams"),
+                state.roams.len()
+            ),
+            Style::default().fg(MUTED),
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(vec![current, Line::from(range)]),
+        header_area,
+    );
+
+    // RSSI is charted as dB above -100 dBm; a gap is a sample off the air.
+    let rssi = state
+        .samples
+        .iter()
+        .map(|sample| {
+            sample
+                .link
+                .as_ref()
+                .map_or(0, |link| (link.rssi_dbm + 100).max(0) as u64)
+        })
+        .collect::<Vec<_>>();
+    let rates = state
+        .samples
+        .iter()
+        .map(|sample| {
+            sample
+                .link
+                .as_ref()
+                .map_or(0, |link| u64::from(link.tx_rate_mbps))
+        })
+        .collect::<Vec<_>>();
+    render_history(
+        frame,
+        rssi_area,
+        model,
+        Block::default().title(tr(model.language, "信号曲线", "Signal History")),
+        &rssi,
+        PRIMARY,
+    );
+    render_history(
+        frame,
+        rate_area,
+        model,
+        Block::default().title(tr(model.language, "发送速率", "Tx Rate")),
+        &rates,
+        SECONDARY,
+    );
+
+    if !state.nearby.is_empty() {
+        for (band, band_area) in iptools_core::WifiBand::ALL.into_iter().zip(band_areas) {
+            render_channel_load(frame, band_area, model, band);
+        }
+    }
+
+    // Newest first; the pane is narrow, so each roam takes two lines with
+    // the old access point above the new one.
+    let rows = state.roams.iter().rev().map(|roam| {
+        Row::new(vec![
+            Cell::from(model.display_time_of_day(&roam.at)),
+            Cell::from(vec![
+                Line::styled(roam.from_bssid.clone(), Style::default().fg(MUTED)),
+                Line::from(format!("→ {}", roam.to_bssid)),
+            ]),
+            Cell::from(vec![
+                Line::styled(roam.from_channel.to_string(), Style::default().fg(MUTED)),
+                Line::from(format!("→ {}", roam.to_channel)),
+            ]),
+            Cell::from(vec![
+                Line::styled(
+                    format!("{} dBm", roam.rssi_before_dbm),
+                    Style::default().fg(MUTED),
+                ),
+                Line::styled(
+                    format!("→ {} dBm", roam.rssi_after_dbm),
+                    Style::default().fg(if roam.rssi_after_dbm > roam.rssi_before_dbm {
+                        PRIMARY
+                    } else {
+                        Color::Yellow
+                    }),
+                ),
+            ]),
+            Cell::from(
+                roam.ping_sequence
+                    .map_or_else(|| "-".into(), |sequence| format!("#{sequence}")),
+            ),
+        ])
+        .height(2)
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(model.display_time_of_day("00:00:00").width() as u16),
+                Constraint::Fill(1),
+                Constraint::Length(7),
+                Constraint::Length(10),
+                Constraint::Length(6),
+            ],
+        )
+        .column_spacing(1)
+        .header(
+            Row::new(vec![
+                tr(model.language, "时间", "Time"),
+                "BSSID".into(),
+                tr(model.language, "信道", "Channel"),
+                tr(model.language, "信号", "Signal"),
+                "Ping".into(),
+            ])
+            .style(Style::default().fg(MUTED)),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(tr(model.language, "漫游记录", "Roams"))
+                .style(Style::default().fg(MUTED)),
+        ),
+        roams_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+/// Interference per channel of one band from the last scan. Bars are the
+/// overlap- and signal-weighted load, the number on a bar the networks on
+/// exactly that channel.
+fn render_channel_load(
+    frame: &mut Frame,
+    area: Rect,
+    model: &AppModel,
+    band: iptools_core::WifiBand,
+) {
+    let state = &model.diagnostics.wifi;
+    let link = state.samples.last().and_then(|sample| sample.link.as_ref());
+    let current = link
+        .filter(|link| link.band == band.label())
+        .map(|link| link.channel);
+    let loads = iptools_core::channel_occupancy(
+        &state.nearby,
+        band,
+        link.map_or("", |link| link.bssid.as_str()),
+    );
+    let suggested = iptools_core::least_congested_channel(&loads, band);
+    let mut title = band.label().to_string();
+    if let Some(channel) = current {
+        title.push_str(&format!(
+            " · {} ch {channel}",
+            tr(model.language, "当前", "current")
+        ));
+    }
+    if let Some(channel) = suggested {
+        title.push_str(&format!(
+            " · {} ch {channel}",
+            tr(model.language, "最空闲", "least congested")
+        ));
+    }
+    let bar_width = (area.width / loads.len().max(1) as u16)
+        .saturating_sub(1)
+        .clamp(1, 3);
+    let bars = loads
+        .iter()
+        .map(|load| {
+            let color = if Some(load.channel) == current {
+                PRIMARY
+            } else if Some(load.channel) == suggested {
+                Color::Cyan
+            } else {
+                SECONDARY
+            };
+            Bar::default()
+                .value((load.load * 10.0).round() as u64)
+                .text_value(if load.networks == 0 {
+                    String::new()
+                } else {
+                    load.networks.to_string()
+                })
+                .label(Line::from(load.channel.to_string()))
+                .style(Style::default().fg(color))
+                .value_style(Style::default().fg(Color::Black).bg(color))
+        })
+        .collect::<Vec<_>>();
+    // Two loud co-channel networks fill the chart.
+    let max = loads
+        .iter()
+        .map(|load| (load.load * 10.0).round() as u64)
+        .max()
+        .unwrap_or_default()
+        .max(20);
+    frame.render_widget(
+        BarChart::default()
+            .block(Block::default().title(Span::styled(title, Style::default().fg(MUTED))))
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .max(max),
+        area,
+    );
+}
+
+fn render_dns_filter(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.dns_filter;
+    let verdict_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        verdict_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(verdict_area.bottom()),
+    );
+    let verdict = match dns_filtering_upstream(&state.probes) {
+        Some(upstream) if state.common.status == TaskStatus::Done => {
+            let culprit = match upstream {
+                DnsFilterUpstream::System => tr(
+                    model.language,
+                    "系统解析器在过滤（Pi-hole / 本机屏蔽列表）",
+                    "The system resolver filters (Pi-hole / local blocklist)",
+                ),
+                DnsFilterUpstream::Gateway => tr(
+                    model.language,
+                    "路由器或其上游运营商 DNS 在过滤",
+                    "The router or the ISP resolver behind it filters",
+                ),
+                DnsFilterUpstream::Public => tr(
+                    model.language,
+                    "路径上劫持了 53 端口（运营商过滤）",
+                    "Port 53 is intercepted on the path (ISP filter)",
+                ),
+            };
+            Line::styled(
+                culprit,
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        }
+        None if state.common.status == TaskStatus::Done => Line::styled(
+            tr(
+                model.language,
+                "未发现 DNS 过滤",
+                "No DNS filtering detected",
+            ),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+        _ => Line::styled(
+            tr(
+                model.language,
+                "经各级上游解析广告 / 追踪域名，与加密参考应答比对",
+                "Resolves ad / tracker names via each upstream against an encrypted reference",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    frame.render_widget(Paragraph::new(verdict), verdict_area);
+
+    let upstream_label = |upstream: DnsFilterUpstream| match upstream {
+        DnsFilterUpstream::System => tr(model.language, "系统", "System"),
+        DnsFilterUpstream::Gateway => tr(model.language, "路由器", "Router"),
+        DnsFilterUpstream::Public => "1.1.1.1".into(),
+    };
+    let rows = state.domains.iter().map(|domain| {
+        let mut cells = vec![Cell::from(domain.as_str())];
+        cells.extend(state.upstreams.iter().map(|upstream| {
+            let probe = state
+                .probes
+                .iter()
+                .find(|probe| probe.upstream == *upstream && probe.domain == *domain);
+            let (mark, color) = match probe.map(|probe| probe.verdict) {
+                Some(DnsFilterVerdict::Clean) => ("✓", PRIMARY),
+                Some(DnsFilterVerdict::Blocked) => ("⊘", Color::Yellow),
+                Some(DnsFilterVerdict::Failed) => ("✗", Color::Red),
+                None => ("·", SUBTLE),
+            };
+            Cell::from(format!(
+                "{mark} {}",
+                probe.map_or("", |probe| probe.detail.as_str())
+            ))
+            .style(Style::default().fg(color))
+        }));
+        Row::new(cells)
+    });
+    let mut widths = vec![Constraint::Length(26)];
+    widths.extend(state.upstreams.iter().map(|_| Constraint::Length(24)));
+    let mut header = vec![tr(model.language, "域名", "Domain")];
+    header.extend(
+        state
+            .upstreams
+            .iter()
+            .map(|upstream| upstream_label(*upstream)),
+    );
+    frame.render_widget(
+        Table::new(rows, widths).header(Row::new(header).style(Style::default().fg(MUTED))),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_mail(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.mail;
+    let verdict_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        verdict_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(verdict_area.bottom()),
+    );
+    let (open, attention) = mail_tally(&state.checks);
+    let verdict = match state.common.status {
+        TaskStatus::Done if open == 0 => Line::styled(
+            tr(model.language, "没有端口应答", "No mail port answered"),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        TaskStatus::Done if attention > 0 => Line::styled(
+            format!(
+                "{}: {attention}/{open}",
+                tr(
+                    model.language,
+                    "部分端口的登录未受保护",
+                    "Logins are not protected on some ports"
+                )
+            ),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        TaskStatus::Done => Line::styled(
+            tr(
+                model.language,
+                "所有开放端口都以 TLS 保护登录",
+                "Every open port protects logins with TLS",
+            ),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+        _ => Line::styled(
+            tr(
+                model.language,
+                "依次连接 25 / 465 / 587 / 143 / 993 / 110 / 995 端口",
+                "Connects to ports 25 / 465 / 587 / 143 / 993 / 110 / 995",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    // The table is too narrow for handshake errors; the first one gets the
+    // second verdict row.
+    let rejected = state.checks.iter().find_map(|check| {
+        check.tls_error.as_ref().map(|error| {
+            Line::styled(
+                format!("{} {}: {error}", check.port, mail_service(check)),
+                Style::default().fg(Color::Red),
+            )
+        })
+    });
+    frame.render_widget(
+        Paragraph::new(std::iter::once(verdict).chain(rejected).collect::<Vec<_>>()),
+        verdict_area,
+    );
+
+    let rows = state.checks.iter().map(|check| {
+        let (status, color) = match check.verdict() {
+            MailVerdict::Secure => (tr(model.language, "✓ 安全", "✓ Secure"), PRIMARY),
+            MailVerdict::NoStartTls => (
+                tr(model.language, "⚠ 无 STARTTLS", "⚠ No STARTTLS"),
+                Color::Yellow,
+            ),
+            MailVerdict::CleartextLogin => (
+                tr(model.language, "⚠ 明文登录", "⚠ Cleartext"),
+                Color::Yellow,
+            ),
+            MailVerdict::CertificateRejected => {
+                (tr(model.language, "✗ 证书无效", "✗ Bad cert"), Color::Red)
+            }
+            MailVerdict::Failed => (tr(model.language, "✗ 失败", "✗ Failed"), Color::Red),
+            MailVerdict::Closed => (tr(model.language, "· 未开放", "· Closed"), SUBTLE),
+        };
+        let tls = match (&check.tls, &check.tls_error) {
+            (_, Some(_)) => "✗".to_string(),
+            (Some(tls), None) => tls.version.clone(),
+            (None, None) if check.starttls == Some(false) => {
+                tr(model.language, "不支持", "unsupported").to_string()
+            }
+            (None, None) => "-".to_string(),
+        };
+        let auth = if check.auth.is_empty() {
+            "-".to_string()
+        } else {
+            check.auth.join(" ")
+        };
+        Row::new([
+            Cell::from(check.port.to_string()),
+            Cell::from(mail_service(check)),
+            Cell::from(status).style(Style::default().fg(color)),
+            Cell::from(tls),
+            Cell::from(auth),
+            Cell::from(
+                check
+                    .banner
+                    .as_deref()
+                    .or(check.error.as_deref())
+                    .unwrap_or(""),
+            )
+            .style(Style::default().fg(MUTED)),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Length(6),
+                Constraint::Length(13),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "端口", "Port"),
+                tr(model.language, "服务", "Service"),
+                tr(model.language, "状态", "Status"),
+                "TLS".into(),
+                tr(model.language, "登录方式", "Auth"),
+                tr(model.language, "欢迎语", "Banner"),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+/// `SMTP`, or `SMTPS` where TLS starts with the connection.
+fn mail_service(check: &MailCheck) -> String {
+    let suffix = if check.implicit_tls { "S" } else { "" };
+    format!("{}{suffix}", check.protocol.label())
+}
+
+fn protocol_label(protocol: LanProtocol) -> &'static str {
+    match protocol {
+        LanProtocol::Tcp => "TCP",
+        LanProtocol::Udp => "UDP",
+    }
+}
+
+fn render_upnp(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.upnp;
+    let gateway_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        gateway_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(gateway_area.bottom()),
+    );
+    let line = match &state.gateway {
+        Some(gateway) => Line::from(vec![
+            Span::styled(
+                format!("{}  ", gateway.name),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}: ", tr(model.language, "外网 IP", "External IP")),
+                Style::default().fg(MUTED),
+            ),
+            Span::styled(
+                gateway.external_ip.as_deref().map_or("-".into(), shown_ip),
+                Style::default().fg(SECONDARY),
+            ),
+            Span::styled(
+                format!("  {}: ", tr(model.language, "本机", "This host")),
+                Style::default().fg(MUTED),
+            ),
+            Span::raw(gateway.local_ip.clone()),
+        ]),
+        None => Line::styled(
+            tr(
+                model.language,
+                "尚未发现 UPnP 网关",
+                "No UPnP gateway discovered yet",
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    frame.render_widget(Paragraph::new(line), gateway_area);
+
+    let local_ip = state
+        .gateway
+        .as_ref()
+        .map(|gateway| gateway.local_ip.as_str());
+    let rows = state.mappings.iter().map(|mapping| {
+        let color = if Some(mapping.internal_client.as_str()) == local_ip {
+            PRIMARY
+        } else {
+            Color::Reset
+        };
+        Row::new(vec![
+            Cell::from(protocol_label(mapping.protocol)).style(Style::default().fg(SECONDARY)),
+            Cell::from(mapping.external_port.to_string()),
+            Cell::from(format!(
+                "{}:{}",
+                mapping.internal_client, mapping.internal_port
+            ))
+            .style(Style::default().fg(color)),
+            Cell::from(if mapping.lease_secs == 0 {
+                tr(model.language, "永久", "permanent").to_string()
+            } else {
+                format!("{} s", mapping.lease_secs)
+            }),
+            Cell::from(if mapping.enabled {
+                mapping.description.clone()
+            } else {
+                format!(
+                    "{} ({})",
+                    mapping.description,
+                    tr(model.language, "已禁用", "disabled")
+                )
+            }),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Length(9),
+                Constraint::Length(22),
+                Constraint::Length(10),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "协议", "Proto"),
+                tr(model.language, "外部端口", "External"),
+                tr(model.language, "内部地址", "Internal"),
+                tr(model.language, "租期", "Lease"),
+                tr(model.language, "描述", "Description"),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn multicast_mode_label(mode: MulticastMode, language: Language) -> Cow<'static, str> {
+    match mode {
+        MulticastMode::Listen => tr(language, "监听", "Listen"),
+        MulticastMode::Send => tr(language, "发送", "Send"),
+    }
+}
+
+fn voip_mode_label(mode: VoipMode, language: Language) -> Cow<'static, str> {
+    match mode {
+        VoipMode::Probe => tr(language, "探测", "Probe"),
+        VoipMode::Reflect => tr(language, "回显", "Reflect"),
+    }
+}
+
+fn voip_grade_label(grade: VoipGrade, language: Language) -> (Cow<'static, str>, Color) {
+    match grade {
+        VoipGrade::Excellent => (tr(language, "极佳", "Excellent"), PRIMARY),
+        VoipGrade::Good => (tr(language, "良好", "Good"), PRIMARY),
+        VoipGrade::Fair => (tr(language, "一般", "Fair"), Color::Yellow),
+        VoipGrade::Poor => (tr(language, "较差", "Poor"), Color::Red),
+        VoipGrade::Bad => (tr(language, "很差", "Bad"), Color::Red),
+    }
+}
+
+fn render_voip(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.voip;
+    let reflecting = state.request.mode == VoipMode::Reflect;
+    let latest = state.summary.as_ref().or(state.samples.last());
+    let status_area = bottom_row(area);
+    let metric_height = status_area.y.saturating_sub(area.y).min(2);
+    let metrics = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+        .split(Rect::new(area.x, area.y, area.width, metric_height));
+    let chart_area = Rect::new(
+        area.x,
+        area.y + metric_height,
+        area.width,
+        status_area.y.saturating_sub(area.y + metric_height),
+    );
+    let endpoint = state.endpoint.clone().unwrap_or_else(|| {
+        if reflecting {
+            format!(":{}", state.request.port)
+        } else {
+            format!("{}:{}", state.request.peer, state.request.port)
+        }
+    });
+    let mut head = vec![
+        Span::styled(
+            format!("{}  ", voip_mode_label(state.request.mode, model.language)),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(
+            endpoint,
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+    ];
+    let score = latest.and_then(|sample| sample.score(state.request.codec));
+    if reflecting {
+        head.push(Span::styled(
+            format!(
+                "    {}: {}",
+                tr(model.language, "已回显", "Reflected"),
+                latest.map_or(0, |sample| sample.sent)
+            ),
+            Style::default().fg(SECONDARY),
+        ));
+    } else if let Some(score) = score {
+        let (grade, color) = voip_grade_label(score.grade(), model.language);
+        head.push(Span::styled(
+            format!("    MOS {:.2} {grade}", score.mos),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+        head.push(Span::styled(
+            format!("  R {:.0}", score.r_factor),
+            Style::default().fg(MUTED),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(head)), metrics[0]);
+
+    let detail = match latest {
+        _ if reflecting => Line::styled(
+            tr(
+                model.language,
+                "把收到的每个包原样发回，供另一端探测",
+                "Echoes every packet back so the other end can probe",
+            ),
+            Style::default().fg(MUTED),
+        ),
+        Some(sample) if score.is_some() => Line::from(format!(
+            "RTT: {:.1} ms    {}: {:.1} ms    {}: {:.1}% ({}/{})    {}: {}",
+            sample.rtt_ms.unwrap_or_default(),
+            tr(model.language, "抖动", "Jitter"),
+            sample.jitter_ms.unwrap_or_default(),
+            tr(model.language, "丢包", "Loss"),
+            sample.loss_percent(),
+            sample.lost,
+            sample.received + sample.lost,
+            tr(model.language, "乱序", "Reordered"),
+            sample.out_of_order,
+        )),
+        _ => Line::styled(
+            format!(
+                "{} · {}",
+                state.request.codec.label(),
+                tr(
+                    model.language,
+                    "每 20 ms 一个语音包，比 ICMP 抖动更接近真实通话",
+                    "one voice packet every 20 ms, closer to a real call than ICMP jitter",
+                )
+            ),
+            Style::default().fg(MUTED),
+        ),
+    };
+    frame.render_widget(Paragraph::new(detail), metrics[1]);
+
+    // Samples carry running totals; each second's own figure is the
+    // difference between neighbours.
+    let (title, data) = if reflecting {
+        (
+            tr(
+                model.language,
+                "每秒回显包数",
+                "Packets Reflected per Second",
+            ),
+            state
+                .samples
+                .windows(2)
+                .map(|pair| pair[1].sent.saturating_sub(pair[0].sent))
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        let total =
+            |sample: &VoipSample| sample.rtt_ms.unwrap_or_default() * sample.received as f64;
+        (
+            tr(model.language, "每秒平均往返 (ms)", "RTT per Second (ms)"),
+            state
+                .samples
+                .windows(2)
+                .filter_map(|pair| {
+                    let echoes = pair[1].received.checked_sub(pair[0].received)?;
+                    (echoes > 0).then(|| {
+                        ((total(&pair[1]) - total(&pair[0])) / echoes as f64).round() as u64
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )
+    };
+    render_history(
+        frame,
+        chart_area,
+        model,
+        Block::default().borders(Borders::TOP).title(title),
+        &data,
+        PRIMARY,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_multicast(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.multicast;
+    let sending = state.request.mode == MulticastMode::Send;
+    let latest = state.summary.as_ref().or(state.samples.last());
+    let status_area = bottom_row(area);
+    let metric_height = status_area.y.saturating_sub(area.y).min(2);
+    let metrics = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+        .split(Rect::new(area.x, area.y, area.width, metric_height));
+    let chart_area = Rect::new(
+        area.x,
+        area.y + metric_height,
+        area.width,
+        status_area.y.saturating_sub(area.y + metric_height),
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{}  ",
+                    multicast_mode_label(state.request.mode, model.language)
+                ),
+                Style::default().fg(MUTED),
+            ),
+            Span::styled(
+                format!("{}:{}", state.request.group, state.request.port),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "    {}: {}",
+                    if sending {
+                        tr(model.language, "已发送", "Sent")
+                    } else {
+                        tr(model.language, "已接收", "Received")
+                    },
+                    latest.map_or(0, |sample| sample.packets)
+                ),
+                Style::default().fg(SECONDARY),
+            ),
+        ])),
+        metrics[0],
+    );
+    let bytes = format_bytes(model.region_format, latest.map_or(0, |sample| sample.bytes));
+    let detail = if sending {
+        format!(
+            "{}: {bytes}    {}: {} pps",
+            tr(model.language, "字节", "Bytes"),
+            tr(model.language, "速率", "Rate"),
+            state.request.rate_pps
+        )
+    } else {
+        format!(
+            "{}: {bytes}    {}: {}    {}: {}    {}: {}",
+            tr(model.language, "字节", "Bytes"),
+            tr(model.language, "发送方", "Senders"),
+            latest.map_or(0, |sample| sample.senders),
+            tr(model.language, "丢失", "Lost"),
+            latest.map_or(0, |sample| sample.lost),
+            tr(model.language, "抖动", "Jitter"),
+            latest
+                .and_then(|sample| sample.jitter_ms)
+                .map_or_else(|| "--".into(), |jitter| format!("{jitter:.1} ms"))
+        )
+    };
+    frame.render_widget(Paragraph::new(detail), metrics[1]);
+    let rate = state
+        .samples
+        .windows(2)
+        .map(|pair| pair[1].packets.saturating_sub(pair[0].packets))
+        .collect::<Vec<_>>();
+    render_history(
+        frame,
+        chart_area,
+        model,
+        Block::default().borders(Borders::TOP).title(tr(
+            model.language,
+            "每秒包数",
+            "Packets per Second",
+        )),
+        &rate,
+        PRIMARY,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn dscp_label(dscp: u8) -> String {
+    match dscp_name(dscp) {
+        Some(name) => format!("{dscp} ({name})"),
+        None => dscp.to_string(),
+    }
+}
+
+fn render_dscp(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.dscp;
+    let verdict_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let table_area = Rect::new(
+        area.x,
+        verdict_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(verdict_area.bottom()),
+    );
+    let mut line = vec![
+        Span::styled(
+            format!("{}: ", tr(model.language, "发送标记", "Sent marking")),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(
+            dscp_label(state.request.dscp),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if let Some(summary) = state.summary {
+        let (text, color) = match summary.verdict {
+            iptools_core::DscpVerdict::Preserved => (
+                tr(model.language, "沿途保留", "Preserved along the path").to_string(),
+                PRIMARY,
+            ),
+            iptools_core::DscpVerdict::Rewritten { ttl, observed } => (
+                match model.language {
+                    Language::Zh => format!("第 {ttl} 跳起变为 {}", dscp_label(observed)),
+                    Language::En => format!("Rewritten to {} at hop {ttl}", dscp_label(observed)),
+                },
+                Color::Yellow,
+            ),
+            iptools_core::DscpVerdict::Unknown => (
+                tr(model.language, "无跳回报标记", "No hop reported a marking").to_string(),
+                Color::Red,
+            ),
+        };
+        line.push(Span::raw("  "));
+        line.push(Span::styled(text, Style::default().fg(color)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(line)), verdict_area);
+
+    let rows = state.hops.iter().map(|hop| {
+        let (observed, color) = match hop.observed {
+            Some(dscp) if dscp == state.request.dscp => (dscp_label(dscp), PRIMARY),
+            Some(dscp) => (dscp_label(dscp), Color::Yellow),
+            None => ("-".into(), SUBTLE),
+        };
+        Row::new(vec![
+            Cell::from(format!("{:>3}", hop.ttl)).style(Style::default().fg(SECONDARY)),
+            Cell::from(hop.address.as_deref().map_or("*".into(), shown_ip)),
+            Cell::from(
+                hop.rtt_ms
+                    .map_or_else(|| "*".into(), |rtt| format!("{rtt} ms")),
+            ),
+            Cell::from(observed).style(Style::default().fg(color)),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Length(18),
+                Constraint::Length(9),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new([
+                tr(model.language, "跳", "Hop"),
+                tr(model.language, "地址", "Address"),
+                "RTT".into(),
+                tr(model.language, "收到的 DSCP", "DSCP seen"),
+            ])
+            .style(Style::default().fg(MUTED)),
+        ),
+        table_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_port_scan(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.port_scan;
+    let stats_area = Rect::new(area.x, area.y, area.width, area.height.min(2));
+    let status_area = bottom_row(area);
+    let progress_area = Rect::new(area.x, status_area.y.saturating_sub(1), area.width, 1);
+    let ports_area = Rect::new(
+        area.x,
+        stats_area.bottom(),
+        area.width,
+        progress_area.y.saturating_sub(stats_area.bottom()),
+    );
+    let mut stats = vec![
+        Span::styled(
+            format!("{}: ", tr(model.language, "开放端口", "Open")),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(
+            format!("{:<6}", state.open_ports.len()),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("{}: ", tr(model.language, "已扫描", "Scanned")),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(
+            format!("{:<14}", format!("{} / {}", state.scanned, state.total)),
+            Style::default().fg(SECONDARY),
+        ),
+    ];
+    if let Some(jump_host) = &state.request.jump_host {
+        stats.push(Span::styled(
+            format!("{} {jump_host}", tr(model.language, "经跳板", "via")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(stats)), stats_area);
+    let ports = state.open_ports.iter().enumerate().map(|(index, result)| {
+        Row::new(vec![
+            Cell::from(result.port.to_string()),
+            Cell::from(result.service.clone()).style(Style::default().fg(SECONDARY)),
+        ])
+        .style(if index == state.selected {
+            Style::default().bg(SELECTED)
+        } else {
+            Style::default()
+        })
+    });
+    frame.render_widget(
+        Table::new(ports, [Constraint::Length(10), Constraint::Min(0)])
+            .header(
+                Row::new([
+                    tr(model.language, "端口", "Port"),
+                    tr(model.language, "服务", "Service"),
+                ])
+                .style(Style::default().fg(MUTED)),
+            )
+            .block(Block::default().borders(Borders::TOP).title(tr(
+                model.language,
+                "开放端口",
+                "Open Ports",
+            ))),
+        ports_area,
+    );
+    let ratio = if state.total == 0 {
+        0.0
+    } else {
+        state.scanned as f64 / state.total as f64
+    };
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(gauge_style(model, SECONDARY))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(format!("{:.0}%", ratio * 100.0)),
+        progress_area,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_lan_speed(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.lan_speed;
+    let latest = state.samples.last();
+    let status_area = bottom_row(area);
+    let summary_height = status_area.y.saturating_sub(area.y).min(5);
+    let summary_area = Rect::new(
+        area.x,
+        status_area.y.saturating_sub(summary_height),
+        area.width,
+        summary_height,
+    );
+    let endpoint_area = Rect::new(area.x, area.y, area.width, area.height.min(1));
+    let bidirectional = state.request.mode == LanSpeedMode::Client
+        && state.request.direction == LanDirection::Bidirectional;
+    let throughput_height = if bidirectional { 2 } else { 1 };
+    let throughput_area = Rect::new(
+        area.x,
+        endpoint_area.bottom(),
+        area.width,
+        throughput_height.min(summary_area.y.saturating_sub(endpoint_area.bottom())),
+    );
+    let history_area = Rect::new(
+        area.x,
+        throughput_area.bottom(),
+        area.width,
+        summary_area.y.saturating_sub(throughput_area.bottom()),
+    );
+
+    let endpoint = if state.endpoint.is_empty() {
+        match state.request.mode {
+            LanSpeedMode::Server => format!("0.0.0.0:{}", state.request.port),
+            LanSpeedMode::Client => format!("{}:{}", state.request.peer, state.request.port),
+        }
+    } else {
+        state.endpoint.clone()
+    };
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{}: {endpoint}",
+            tr(
+                model.language,
+                if state.request.mode == LanSpeedMode::Server {
+                    "本机"
+                } else {
+                    "对端"
+                },
+                if state.request.mode == LanSpeedMode::Server {
+                    "Local"
+                } else {
+                    "Peer"
+                },
+            )
+        ))
+        .style(Style::default().fg(SECONDARY)),
+        endpoint_area,
+    );
+
+    let tx_bps = latest.map_or(0, |sample| sample.tx_bps);
+    let rx_bps = latest.map_or(0, |sample| sample.rx_bps);
+    let mut throughput = Vec::new();
+    if bidirectional || tx_bps > 0 {
+        throughput.push(Line::from(vec![
+            Span::styled("TX ", Style::default().fg(MUTED)),
+            Span::styled(
+                format_speed_dual(model.region_format, tx_bps),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    if bidirectional || tx_bps == 0 || rx_bps > 0 {
+        throughput.push(Line::from(vec![
+            Span::styled("RX ", Style::default().fg(MUTED)),
+            Span::styled(
+                format_speed_dual(model.region_format, rx_bps),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
     frame.render_widget(Paragraph::new(throughput), throughput_area);
 
-    let history = if state.samples.iter().any(|sample| sample.tx_bps > 0) {
-        state
-            .samples
-            .iter()
-            .map(|sample| sample.tx_bps)
-            .collect::<Vec<_>>()
-    } else {
-        state
-            .samples
-            .iter()
-            .map(|sample| sample.rx_bps)
-            .collect::<Vec<_>>()
+    let history = if state.samples.iter().any(|sample| sample.tx_bps > 0) {
+        state
+            .samples
+            .iter()
+            .map(|sample| sample.tx_bps)
+            .collect::<Vec<_>>()
+    } else {
+        state
+            .samples
+            .iter()
+            .map(|sample| sample.rx_bps)
+            .collect::<Vec<_>>()
+    };
+    render_history(
+        frame,
+        history_area,
+        model,
+        Block::default().borders(Borders::TOP).title(tr(
+            model.language,
+            "吞吐历史",
+            "Throughput History",
+        )),
+        &history,
+        PRIMARY,
+    );
+
+    let summary_lines = if let Some(summary) = &state.summary {
+        let mut lines = Vec::new();
+        if summary.tx_bytes > 0 {
+            lines.push(Line::from(format!(
+                "{} {}   {}: {}",
+                tr(model.language, "TX 平均", "TX average"),
+                format_speed_dual(
+                    model.region_format,
+                    average_bytes_per_second(summary.tx_bytes, summary.elapsed_ms)
+                ),
+                tr(model.language, "总量", "Total"),
+                format_bytes(model.region_format, summary.tx_bytes),
+            )));
+        }
+        if summary.rx_bytes > 0 {
+            lines.push(Line::from(format!(
+                "{} {}   {}: {}",
+                tr(model.language, "RX 平均", "RX average"),
+                format_speed_dual(
+                    model.region_format,
+                    average_bytes_per_second(summary.rx_bytes, summary.elapsed_ms)
+                ),
+                tr(model.language, "总量", "Total"),
+                format_bytes(model.region_format, summary.rx_bytes),
+            )));
+        }
+        lines.push(Line::from(format!(
+            "{}: {:.1}s",
+            tr(model.language, "用时", "Elapsed"),
+            summary.elapsed_ms as f64 / 1_000.0
+        )));
+        if let Some(loss) = summary.loss_percent {
+            lines.push(Line::from(format!(
+                "{}: {:.2}%   {}: {}   {}: {} ms",
+                tr(model.language, "丢包", "Loss"),
+                loss,
+                tr(model.language, "乱序", "Out of order"),
+                summary.out_of_order.unwrap_or_default(),
+                tr(model.language, "抖动", "Jitter"),
+                format_optional_f64(model.region_format, summary.jitter_ms),
+            )));
+        }
+        lines
+    } else {
+        vec![Line::from(Span::styled(
+            tr(model.language, "等待测试结果…", "Waiting for results…"),
+            Style::default().fg(SUBTLE),
+        ))]
+    };
+    frame.render_widget(Paragraph::new(summary_lines), summary_area);
+
+    if state.common.status == TaskStatus::Running {
+        let phase = match state.phase {
+            Some(LanSpeedPhase::Listening) => tr(model.language, "等待客户端", "Listening"),
+            Some(LanSpeedPhase::Connecting) => tr(model.language, "正在连接", "Connecting"),
+            Some(LanSpeedPhase::Connected) => tr(model.language, "测试中", "Connected"),
+            None => tr(model.language, "运行中", "Running"),
+        };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{phase} | {}",
+                tr(model.language, "空格 停止", "Space to stop")
+            ))
+            .style(Style::default().fg(Color::Green)),
+            status_area,
+        );
+    } else {
+        render_diagnostic_status(frame, status_area, &state.common.status, model);
+    }
+}
+
+fn average_bytes_per_second(bytes: u64, elapsed_ms: u64) -> u64 {
+    bytes
+        .saturating_mul(1_000)
+        .checked_div(elapsed_ms)
+        .unwrap_or_default()
+}
+
+fn render_diagnostic_status(frame: &mut Frame, area: Rect, status: &TaskStatus, model: &AppModel) {
+    let language = model.language;
+    let (text, color) = match status {
+        _ if !model.tool_supported(model.diagnostics.tool) => (
+            tr(
+                language,
+                "当前平台不可用（缺少 ICMP 权限）",
+                "Unavailable on this platform (no ICMP access)",
+            )
+            .into(),
+            MUTED,
+        ),
+        _ if !model.tool_available(model.diagnostics.tool) => (
+            tr(
+                language,
+                "已被本机配置的策略关闭",
+                "Turned off by this machine's policy",
+            )
+            .into(),
+            MUTED,
+        ),
+        TaskStatus::Running => (
+            format!(
+                "{} | {}",
+                tr(language, "运行中", "Running"),
+                tr(language, "空格 停止", "Space to stop")
+            ),
+            Color::Green,
+        ),
+        TaskStatus::Done => (
+            tr(language, "完成 | 空格 重新开始", "Done | Space to restart").into(),
+            SECONDARY,
+        ),
+        TaskStatus::Failed(error) => (
+            format!("{}: {error}", tr(language, "失败", "Failed")),
+            Color::Red,
+        ),
+        TaskStatus::Idle => (
+            format!(
+                "{} | {}",
+                tr(language, "已停止", "Stopped"),
+                tr(language, "空格 开始", "Space to start")
+            ),
+            Color::Red,
+        ),
+    };
+    frame.render_widget(Paragraph::new(text).style(Style::default().fg(color)), area);
+}
+
+fn bottom_row(area: Rect) -> Rect {
+    Rect::new(
+        area.x,
+        area.bottom().saturating_sub(1),
+        area.width,
+        area.height.min(1),
+    )
+}
+
+fn render_public_speed(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.public_speed;
+    let latest = state.samples.last();
+    let current = latest.map_or(0, |sample| sample.bytes_per_second);
+    let uploading = latest.is_some_and(|sample| sample.phase == SpeedPhase::Upload);
+    let total = state
+        .samples
+        .iter()
+        .rev()
+        .find(|sample| sample.phase == SpeedPhase::Download)
+        .map(|sample| sample.bytes)
+        .or_else(|| state.summary.as_ref().map(|summary| summary.total_bytes))
+        .unwrap_or_default();
+    let elapsed = latest.map_or(0, |sample| sample.elapsed_ms);
+    let average = state
+        .summary
+        .as_ref()
+        .map_or(0, |summary| summary.average_bytes_per_second);
+    let peak = state.summary.as_ref().map_or(current, |summary| {
+        summary.peak_bytes_per_second.max(current)
+    });
+    let status_area = bottom_row(area);
+    let metric_height = status_area.y.saturating_sub(area.y).min(8);
+    let metric_area = Rect::new(area.x, area.y, area.width, metric_height);
+    let metrics = Layout::vertical([
+        Constraint::Length(metric_height.min(2)),
+        Constraint::Length(metric_height.saturating_sub(2).min(2)),
+        Constraint::Length(metric_height.saturating_sub(4).min(2)),
+        Constraint::Length(metric_height.saturating_sub(6).min(2)),
+    ])
+    .split(metric_area);
+    let chart_area = Rect::new(
+        area.x,
+        metric_area.bottom(),
+        area.width,
+        status_area.y.saturating_sub(metric_area.bottom()),
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                if uploading {
+                    tr(model.language, "上传速率  ", "Upload speed  ")
+                } else {
+                    tr(model.language, "当前速率  ", "Current speed  ")
+                },
+                Style::default().fg(MUTED),
+            ),
+            Span::styled(
+                format_speed_dual(model.region_format, current),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+        ])),
+        metrics[0],
+    );
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{}: {}    {}: {}",
+            tr(model.language, "平均", "Average"),
+            format_speed_dual(model.region_format, average),
+            tr(model.language, "峰值", "Peak"),
+            format_speed_dual(model.region_format, peak)
+        )),
+        metrics[1],
+    );
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{}: {}    {}: {:.1}s    {}: {}",
+            tr(model.language, "已下载", "Downloaded"),
+            format_bytes(model.region_format, total),
+            tr(model.language, "用时", "Elapsed"),
+            elapsed as f64 / 1_000.0,
+            tr(model.language, "状态", "Status"),
+            task_label(&state.common.status, model.language).trim()
+        )),
+        metrics[2],
+    );
+    // Finished runs carry the runtime's summary; running ones grade live probes.
+    let bufferbloat = state
+        .summary
+        .as_ref()
+        .and_then(|summary| summary.bufferbloat.clone())
+        .unwrap_or_else(|| BufferbloatSummary::from_samples(&state.latency));
+    let latency =
+        |value: Option<f64>| value.map_or_else(|| "--".into(), |ms| format!("{ms:.0} ms"));
+    let mut bufferbloat_line = vec![
+        Span::raw(format!(
+            "{}: {}    {}: ↓ {} ↑ {}    ",
+            tr(model.language, "空闲延迟", "Idle latency"),
+            latency(bufferbloat.idle_ms),
+            tr(model.language, "负载延迟", "Loaded"),
+            latency(bufferbloat.download_ms),
+            latency(bufferbloat.upload_ms),
+        )),
+        Span::styled(
+            format!("{}: ", tr(model.language, "缓冲膨胀", "Bufferbloat")),
+            Style::default().fg(MUTED),
+        ),
+    ];
+    bufferbloat_line.push(match bufferbloat.grade {
+        Some(grade) => Span::styled(
+            grade.label(),
+            Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD),
+        ),
+        None => Span::raw("--"),
+    });
+    if let Some(upload) = state
+        .summary
+        .as_ref()
+        .and_then(|summary| summary.upload_bytes_per_second)
+    {
+        bufferbloat_line.push(Span::raw(format!(
+            "    {}: {}",
+            tr(model.language, "上传", "Upload"),
+            format_speed_dual(model.region_format, upload)
+        )));
+    }
+    frame.render_widget(Paragraph::new(Line::from(bufferbloat_line)), metrics[3]);
+    let history = state
+        .samples
+        .iter()
+        .map(|sample| sample.bytes_per_second)
+        .collect::<Vec<_>>();
+    render_history(
+        frame,
+        chart_area,
+        model,
+        Block::default().borders(Borders::TOP).title(tr(
+            model.language,
+            " 速率历史 ",
+            " Speed history ",
+        )),
+        &history,
+        PRIMARY,
+    );
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn render_link_quality(area: Rect, frame: &mut Frame, model: &AppModel) {
+    let state = &model.diagnostics.link_quality;
+    let is_wifi = snapshot_is_wifi(state);
+    let dimension_count = if is_wifi { 6 } else { 3 };
+    let metric_count = if is_wifi { 6 } else { 4 };
+    let status_area = bottom_row(area);
+    let rssi_height = if is_wifi {
+        3.min(status_area.y.saturating_sub(area.y))
+    } else {
+        0
+    };
+    let rssi_area = Rect::new(
+        area.x,
+        status_area.y.saturating_sub(rssi_height),
+        area.width,
+        rssi_height,
+    );
+    let header_area = Rect::new(area.x, area.y, area.width, area.height.min(1));
+    let overall_area = Rect::new(
+        area.x,
+        header_area.bottom(),
+        area.width,
+        area.height.saturating_sub(1).min(1),
+    );
+    let dimensions_area = Rect::new(area.x, overall_area.bottom(), area.width, dimension_count);
+    let metrics_area = Rect::new(area.x, dimensions_area.bottom(), area.width, metric_count);
+    let history_area = Rect::new(
+        area.x,
+        metrics_area.bottom(),
+        area.width,
+        rssi_area.y.saturating_sub(metrics_area.bottom()),
+    );
+
+    let snapshot = state.snapshot.as_ref();
+    let adapter_name = snapshot
+        .map(|value| value.adapter.name.as_str())
+        .or_else(|| {
+            state
+                .adapters
+                .get(state.selected_adapter)
+                .map(|value| value.name.as_str())
+        })
+        .map_or_else(|| tr(model.language, "无可用网卡", "No adapter"), Cow::from);
+    let mut header = vec![
+        Span::styled(
+            format!("{}: ", tr(model.language, "网卡", "Adapter")),
+            Style::default().fg(MUTED),
+        ),
+        Span::styled(adapter_name.to_string(), Style::default().fg(SECONDARY)),
+    ];
+    if let Some(snapshot) = snapshot {
+        header.push(Span::styled(
+            format!(
+                " [{}] · {}",
+                if snapshot.adapter.is_wifi {
+                    tr(model.language, "无线", "Wireless")
+                } else {
+                    tr(model.language, "有线", "Wired")
+                },
+                shown_ip(&snapshot.adapter.ipv4)
+            ),
+            Style::default().fg(SECONDARY),
+        ));
+        if let Some(wireless) = &snapshot.wireless {
+            header.push(Span::styled(
+                format!("  SSID: {}", shown_ssid(&wireless.ssid)),
+                Style::default().fg(Color::White),
+            ));
+        }
+    }
+    frame.render_widget(Paragraph::new(Line::from(header)), header_area);
+
+    if let Some(summary) = &state.summary {
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(gauge_style(model, link_grade_color(summary.grade)))
+                .ratio((summary.score / 100.0).clamp(0.0, 1.0))
+                .label(format!(
+                    "{}: {} ({:.0})",
+                    tr(model.language, "评级", "Grade"),
+                    link_grade(summary.grade, model.language),
+                    summary.score
+                )),
+            overall_area,
+        );
+        let dimensions = summary
+            .dimensions
+            .iter()
+            .take(dimension_count as usize)
+            .map(|dimension| {
+                let color = link_score_color(dimension.score);
+                Line::from(vec![
+                    Span::styled(
+                        pad_display(&link_dimension(dimension.kind, model.language), 8),
+                        Style::default().fg(MUTED),
+                    ),
+                    Span::styled(score_bar(dimension.score, 12), Style::default().fg(color)),
+                    Span::styled(
+                        format!(
+                            " {:>3.0}{}",
+                            dimension.score,
+                            if summary.weakest == Some(dimension.kind) {
+                                " ◀"
+                            } else {
+                                ""
+                            }
+                        ),
+                        Style::default().fg(color),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        frame.render_widget(Paragraph::new(dimensions), dimensions_area);
+
+        let min = state
+            .samples
+            .last()
+            .and_then(|sample| sample.min_latency_ms);
+        let max = state
+            .samples
+            .last()
+            .and_then(|sample| sample.max_latency_ms);
+        let mut metrics = vec![
+            Line::from(format!(
+                "{}: {}/{}/{} ms   {}: {} ms",
+                tr(model.language, "最小/平均/最大", "Min/avg/max"),
+                format_optional_u64(min),
+                format_optional_f64(model.region_format, summary.average_latency_ms),
+                format_optional_u64(max),
+                tr(model.language, "抖动", "Jitter"),
+                format_optional_f64(model.region_format, summary.jitter_ms)
+            )),
+            Line::from(format!(
+                "{}: {:.1}%   {}: {}/{}",
+                tr(model.language, "丢包", "Loss"),
+                summary.loss_percent,
+                tr(model.language, "收发", "Received"),
+                summary.received,
+                summary.sent
+            )),
+        ];
+        if let Some(wireless) = snapshot.and_then(|value| value.wireless.as_ref()) {
+            let sample = state.samples.last();
+            metrics.extend([
+                Line::from(format!(
+                    "RSSI: {}/{}/{} dBm   {}: {} ({}, {} MHz)",
+                    sample
+                        .and_then(|value| value.min_rssi_dbm)
+                        .map_or_else(|| "—".into(), |value| value.to_string()),
+                    format_optional_f64(model.region_format, summary.average_rssi_dbm),
+                    sample
+                        .and_then(|value| value.max_rssi_dbm)
+                        .map_or_else(|| "—".into(), |value| value.to_string()),
+                    tr(model.language, "信道", "Channel"),
+                    wireless.channel,
+                    wireless.band,
+                    wireless.frequency_mhz
+                )),
+                Line::from(format!(
+                    "{}: {}%   {}: {}",
+                    tr(model.language, "信号质量", "Signal quality"),
+                    format_optional_f64(model.region_format, summary.average_signal_quality),
+                    tr(model.language, "制式", "PHY"),
+                    wireless.phy_type
+                )),
+                Line::from(format!(
+                    "Tx/Rx: {}/{} Mbps",
+                    wireless.tx_rate_mbps, wireless.rx_rate_mbps
+                )),
+                Line::from(format!(
+                    "BSSID: {}   {} / {}",
+                    shown_mac(&wireless.bssid),
+                    wireless.authentication,
+                    wireless.cipher
+                )),
+            ]);
+        } else if let Some(snapshot) = snapshot {
+            metrics.extend([
+                Line::from(format!(
+                    "{}: {}",
+                    tr(model.language, "链路速率", "Link speed"),
+                    summary
+                        .link_speed_bps
+                        .map(|speed| format_speed_dual(model.region_format, speed / 8))
+                        .unwrap_or_else(|| "—".into())
+                )),
+                Line::from(format!(
+                    "MAC: {}   IPv4: {}",
+                    shown_mac(&snapshot.adapter.mac),
+                    shown_ip(&snapshot.adapter.ipv4)
+                )),
+            ]);
+        }
+        frame.render_widget(Paragraph::new(metrics), metrics_area);
+    } else {
+        frame.render_widget(
+            Paragraph::new(tr(
+                model.language,
+                "等待链路质量样本…",
+                "Waiting for link-quality samples…",
+            ))
+            .style(Style::default().fg(SUBTLE)),
+            dimensions_area,
+        );
+    }
+
+    let latency = state
+        .samples
+        .iter()
+        .map(|sample| sample.latency_ms.unwrap_or_default())
+        .collect::<Vec<_>>();
+    render_history(
+        frame,
+        history_area,
+        model,
+        Block::default().borders(Borders::TOP).title(tr(
+            model.language,
+            "延迟历史",
+            "Latency History",
+        )),
+        &latency,
+        PRIMARY,
+    );
+    if is_wifi {
+        let rssi = state
+            .samples
+            .iter()
+            .map(|sample| {
+                sample
+                    .rssi_dbm
+                    .map_or(0, |value| (value + 100).max(0) as u64)
+            })
+            .collect::<Vec<_>>();
+        render_history(
+            frame,
+            rssi_area,
+            model,
+            Block::default().borders(Borders::TOP).title(tr(
+                model.language,
+                "RSSI 历史",
+                "RSSI History",
+            )),
+            &rssi,
+            Color::Magenta,
+        );
+    }
+    render_diagnostic_status(frame, status_area, &state.common.status, model);
+}
+
+fn snapshot_is_wifi(state: &iptools_core::LinkQualityState) -> bool {
+    state
+        .snapshot
+        .as_ref()
+        .is_some_and(|snapshot| snapshot.adapter.is_wifi)
+}
+
+fn format_speed_dual(format: RegionFormat, bytes_per_second: u64) -> String {
+    let megabits = format.decimal(bytes_per_second as f64 * 8.0 / 1_000_000.0, 2);
+    if bytes_per_second >= 1_000_000 {
+        format!(
+            "{} MB/s · {megabits} Mbps",
+            format.decimal(bytes_per_second as f64 / 1_000_000.0, 2)
+        )
+    } else {
+        format!(
+            "{} KB/s · {megabits} Mbps",
+            format.decimal(bytes_per_second as f64 / 1_000.0, 1)
+        )
+    }
+}
+
+fn format_optional_f64(format: RegionFormat, value: Option<f64>) -> String {
+    value.map_or_else(|| "—".into(), |value| format.decimal(value, 1))
+}
+
+fn format_optional_u64(value: Option<u64>) -> String {
+    value.map_or_else(|| "—".into(), |value| value.to_string())
+}
+
+fn pad_display(value: &str, width: usize) -> String {
+    let padding = width.saturating_sub(UnicodeWidthStr::width(value));
+    format!("{value}{}", " ".repeat(padding))
+}
+
+fn link_grade(grade: LinkQualityGrade, language: Language) -> Cow<'static, str> {
+    match grade {
+        LinkQualityGrade::Excellent => tr(language, "优秀", "Excellent"),
+        LinkQualityGrade::Good => tr(language, "良好", "Good"),
+        LinkQualityGrade::Fair => tr(language, "一般", "Fair"),
+        LinkQualityGrade::Poor => tr(language, "较差", "Poor"),
+    }
+}
+
+fn link_grade_color(grade: LinkQualityGrade) -> Color {
+    match grade {
+        LinkQualityGrade::Excellent => Color::Green,
+        LinkQualityGrade::Good => Color::Cyan,
+        LinkQualityGrade::Fair => Color::Yellow,
+        LinkQualityGrade::Poor => Color::Red,
+    }
+}
+
+fn link_score_color(score: f64) -> Color {
+    if score >= 85.0 {
+        Color::Green
+    } else if score >= 70.0 {
+        Color::Cyan
+    } else if score >= 50.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+fn link_dimension(kind: LinkQualityDimensionKind, language: Language) -> Cow<'static, str> {
+    match kind {
+        LinkQualityDimensionKind::Loss => tr(language, "丢包", "Loss"),
+        LinkQualityDimensionKind::Latency => tr(language, "延迟", "Latency"),
+        LinkQualityDimensionKind::Jitter => tr(language, "抖动", "Jitter"),
+        LinkQualityDimensionKind::Signal => tr(language, "信号", "Signal"),
+        LinkQualityDimensionKind::Rate => tr(language, "速率", "Rate"),
+        LinkQualityDimensionKind::Phy => tr(language, "制式", "PHY"),
+    }
+}
+
+fn score_bar(score: f64, width: usize) -> String {
+    let filled = ((score / 100.0) * width as f64).round() as usize;
+    let filled = filled.min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn diagnostic_target_index(tool: DiagnosticTool) -> usize {
+    match tool {
+        DiagnosticTool::LinkQuality => 1,
+        DiagnosticTool::LanSpeed => 4,
+        _ => 0,
+    }
+}
+
+fn diagnostic_failure(
+    common: &iptools_core::DiagnosticCommonState,
+    language: Language,
+) -> Option<String> {
+    let TaskStatus::Failed(status_message) = &common.status else {
+        return None;
+    };
+    let label = match common.error.as_ref().map(|error| error.code) {
+        Some(RuntimeErrorCode::InvalidRequest) => tr(language, "参数无效", "Invalid request"),
+        Some(RuntimeErrorCode::ResolveTarget) => {
+            tr(language, "无法解析目标", "Target resolution failed")
+        }
+        Some(RuntimeErrorCode::PermissionDenied) => tr(language, "权限不足", "Permission denied"),
+        Some(RuntimeErrorCode::Timeout) => tr(language, "请求超时", "Request timed out"),
+        Some(RuntimeErrorCode::Network) => tr(language, "网络错误", "Network error"),
+        Some(RuntimeErrorCode::Cancelled) => tr(language, "任务已取消", "Task cancelled"),
+        Some(RuntimeErrorCode::Internal) => tr(language, "内部错误", "Internal error"),
+        None => tr(language, "执行失败", "Operation failed"),
+    };
+    let detail = common
+        .error
+        .as_ref()
+        .map(|error| error.message.as_str())
+        .unwrap_or(status_message);
+    if detail.is_empty() || detail.starts_with("diag_") {
+        Some(label.into())
+    } else {
+        Some(format!("{label}: {detail}"))
+    }
+}
+
+fn ping_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "目标 IP/域名", "Target IP/Domain"),
+            model.diagnostics.ping.request.target.clone(),
+        ),
+        (
+            tr(model.language, "发包间隔 (ms)", "Interval (ms)"),
+            model.diagnostics.ping.request.interval_ms.to_string(),
+        ),
+        (
+            tr(model.language, "超时时间 (ms)", "Timeout (ms)"),
+            model.diagnostics.ping.request.timeout_ms.to_string(),
+        ),
+        (
+            tr(model.language, "包载荷大小", "Packet Size"),
+            model.diagnostics.ping.request.packet_size.to_string(),
+        ),
+        (
+            tr(model.language, "探测模式", "Probe mode"),
+            match model.diagnostics.ping.request.mode {
+                PingMode::Echo => tr(model.language, "回显", "Echo"),
+                PingMode::RecordRoute => tr(model.language, "记录路由", "Record route"),
+                PingMode::Timestamp => tr(model.language, "时间戳", "Timestamp"),
+            }
+            .to_string(),
+        ),
+        (
+            tr(model.language, "禁止分片 (DF)", "Don't fragment (DF)"),
+            if model.diagnostics.ping.request.dont_fragment {
+                tr(model.language, "开启", "On")
+            } else {
+                tr(model.language, "关闭", "Off")
+            }
+            .to_string(),
+        ),
+        (
+            "DSCP".into(),
+            dscp_label(model.diagnostics.ping.request.dscp),
+        ),
+        (
+            tr(model.language, "声称位置", "Claimed location"),
+            model.diagnostics.ping.claimed_location.clone(),
+        ),
+        (
+            tr(model.language, "本机位置", "My location"),
+            model.diagnostics.ping.own_location.clone(),
+        ),
+    ]
+}
+
+fn mtu_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "对端 IP/域名", "Peer IP/Domain"),
+            model.diagnostics.mtu.request.target.clone(),
+        ),
+        (
+            tr(model.language, "最大 MTU", "Max MTU"),
+            model.diagnostics.mtu.max_mtu_input.clone(),
+        ),
+        (
+            tr(model.language, "超时 (ms)", "Timeout (ms)"),
+            model.diagnostics.mtu.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn dscp_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "目标 IP/域名", "Target IP/Domain"),
+            model.diagnostics.dscp.request.target.clone(),
+        ),
+        (
+            "DSCP".into(),
+            dscp_label(model.diagnostics.dscp.request.dscp),
+        ),
+        (
+            tr(model.language, "最大跳数", "Max Hops"),
+            model.diagnostics.dscp.max_hops_input.clone(),
+        ),
+        (
+            tr(model.language, "超时 (ms)", "Timeout (ms)"),
+            model.diagnostics.dscp.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn multicast_fields(model: &AppModel) -> Fields {
+    let state = &model.diagnostics.multicast;
+    let mut fields = vec![
+        (
+            tr(model.language, "模式", "Mode"),
+            multicast_mode_label(state.request.mode, model.language).into(),
+        ),
+        (
+            tr(model.language, "组播组", "Group"),
+            state.request.group.clone(),
+        ),
+        (tr(model.language, "端口", "Port"), state.port_input.clone()),
+        (
+            tr(model.language, "时长 (秒)", "Duration (s)"),
+            state.duration_input.clone(),
+        ),
+    ];
+    if state.request.mode == MulticastMode::Send {
+        fields.push((
+            tr(model.language, "速率 (包/秒)", "Rate (pps)"),
+            state.rate_input.clone(),
+        ));
+    }
+    fields
+}
+
+fn nat_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "STUN 服务器", "STUN Server"),
+            model.diagnostics.nat.request.server.clone(),
+        ),
+        (
+            tr(model.language, "第二服务器", "Second Server"),
+            model.diagnostics.nat.request.secondary.clone(),
+        ),
+        (
+            tr(model.language, "超时 (毫秒)", "Timeout (ms)"),
+            model.diagnostics.nat.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn ipv6_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "纯 IPv6 目标", "IPv6-only Target"),
+            model.diagnostics.ipv6.request.target.clone(),
+        ),
+        (
+            tr(model.language, "超时 (毫秒)", "Timeout (ms)"),
+            model.diagnostics.ipv6.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn gaming_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "地区预设", "Region Preset"),
+            gaming_preset_label(model.diagnostics.gaming.request.preset, model.language).into(),
+        ),
+        (
+            tr(model.language, "自定义服务器", "Custom Servers"),
+            model.diagnostics.gaming.request.custom.clone(),
+        ),
+        (
+            tr(model.language, "每个地区探测次数", "Probes per Region"),
+            model.diagnostics.gaming.rounds_input.clone(),
+        ),
+    ]
+}
+
+fn cloud_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "每个端点连接次数", "Attempts per Endpoint"),
+            model.diagnostics.cloud.attempts_input.clone(),
+        ),
+        (
+            tr(model.language, "超时 (毫秒)", "Timeout (ms)"),
+            model.diagnostics.cloud.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn wifi_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "无线网卡", "Wireless Adapter"),
+            if model.diagnostics.wifi.request.adapter.is_empty() {
+                tr(model.language, "无", "None").to_string()
+            } else {
+                model.diagnostics.wifi.request.adapter.clone()
+            },
+        ),
+        (
+            tr(model.language, "采样间隔 (秒)", "Interval (s)"),
+            model.diagnostics.wifi.interval_input.clone(),
+        ),
+    ]
+}
+
+fn ab_test_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "网卡/源 IP A", "Interface/IP A"),
+            model.diagnostics.ab_test.request.first.clone(),
+        ),
+        (
+            tr(model.language, "网卡/源 IP B", "Interface/IP B"),
+            model.diagnostics.ab_test.request.second.clone(),
+        ),
+        (
+            tr(model.language, "Ping 目标", "Ping target"),
+            model.diagnostics.ab_test.request.target.clone(),
+        ),
+        (
+            tr(model.language, "每侧 Ping 次数", "Pings per side"),
+            model.diagnostics.ab_test.pings_input.clone(),
+        ),
+    ]
+}
+
+fn dns_bench_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "自定义解析器", "Custom Resolver"),
+            model.diagnostics.dns_bench.request.custom.clone(),
+        ),
+        (
+            tr(model.language, "加密 DNS", "Encrypted DNS"),
+            if model.diagnostics.dns_bench.request.encrypted {
+                tr(model.language, "DoH + DoT", "DoH + DoT")
+            } else {
+                tr(model.language, "关闭", "Off")
+            }
+            .to_string(),
+        ),
+        (
+            tr(model.language, "每类查询次数", "Queries per Kind"),
+            model.diagnostics.dns_bench.rounds_input.clone(),
+        ),
+        (
+            tr(model.language, "超时 (毫秒)", "Timeout (ms)"),
+            model.diagnostics.dns_bench.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn dns_filter_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "可疑域名", "Suspect Domain"),
+            model.diagnostics.dns_filter.request.domain.clone(),
+        ),
+        (
+            tr(model.language, "超时 (毫秒)", "Timeout (ms)"),
+            model.diagnostics.dns_filter.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn voip_fields(model: &AppModel) -> Fields {
+    let state = &model.diagnostics.voip;
+    let mut fields = vec![
+        (
+            tr(model.language, "模式", "Mode"),
+            voip_mode_label(state.request.mode, model.language).into(),
+        ),
+        (tr(model.language, "端口", "Port"), state.port_input.clone()),
+    ];
+    if state.request.mode == VoipMode::Probe {
+        fields.extend([
+            (
+                tr(model.language, "回显端", "Reflector"),
+                state.request.peer.clone(),
+            ),
+            (
+                tr(model.language, "编码", "Codec"),
+                state.request.codec.label().into(),
+            ),
+            (
+                tr(model.language, "时长 (秒)", "Duration (s)"),
+                state.duration_input.clone(),
+            ),
+        ]);
+    }
+    fields
+}
+
+fn mail_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "邮件服务器", "Mail Server"),
+            model.diagnostics.mail.request.host.clone(),
+        ),
+        (
+            tr(model.language, "超时 (毫秒)", "Timeout (ms)"),
+            model.diagnostics.mail.timeout_input.clone(),
+        ),
+    ]
+}
+
+fn upnp_fields(model: &AppModel) -> Fields {
+    let state = &model.diagnostics.upnp;
+    let action = match state.request.action {
+        UpnpAction::List => tr(model.language, "列出映射", "List"),
+        UpnpAction::Add => tr(model.language, "添加映射", "Add"),
+        UpnpAction::Delete => tr(model.language, "删除映射", "Delete"),
     };
-    frame.render_widget(
-        Sparkline::default()
-            .block(Block::default().borders(Borders::TOP).title(tr(
-                model.language,
-                "吞吐历史",
-                "Throughput History",
-            )))
-            .data(&history)
-            .style(Style::default().fg(PRIMARY)),
-        history_area,
-    );
+    let mut fields = vec![(tr(model.language, "操作", "Action"), action.to_string())];
+    if state.request.action != UpnpAction::List {
+        fields.push((
+            tr(model.language, "协议", "Protocol"),
+            protocol_label(state.request.protocol).into(),
+        ));
+        fields.push((
+            tr(model.language, "外部端口", "External Port"),
+            state.external_port_input.clone(),
+        ));
+    }
+    if state.request.action == UpnpAction::Add {
+        fields.push((
+            tr(model.language, "内部端口", "Internal Port"),
+            state.internal_port_input.clone(),
+        ));
+        fields.push((
+            tr(model.language, "租期 (秒)", "Lease (s)"),
+            state.lease_input.clone(),
+        ));
+    }
+    fields
+}
 
-    let summary_lines = if let Some(summary) = &state.summary {
-        let mut lines = Vec::new();
-        if summary.tx_bytes > 0 {
-            lines.push(Line::from(format!(
-                "{} {}   {}: {}",
-                tr(model.language, "TX 平均", "TX average"),
-                format_speed_dual(average_bytes_per_second(
-                    summary.tx_bytes,
-                    summary.elapsed_ms
-                )),
-                tr(model.language, "总量", "Total"),
-                format_bytes(summary.tx_bytes),
-            )));
+fn trace_fields(model: &AppModel) -> Fields {
+    vec![
+        (
+            tr(model.language, "目标 IP/域名", "Target IP/Domain"),
+            model.diagnostics.trace.request.target.clone(),
+        ),
+        (
+            tr(model.language, "最大跳数", "Max hops"),
+            model.diagnostics.trace.max_hops_input.clone(),
+        ),
+        (
+            tr(model.language, "超时 (ms)", "Timeout (ms)"),
+            model.diagnostics.trace.timeout_input.clone(),
+        ),
+        (
+            tr(model.language, "出口网卡/源 IP", "Via interface/IP"),
+            model.diagnostics.trace.via_input.clone(),
+        ),
+        (
+            tr(model.language, "对比目标", "Compare target"),
+            model.diagnostics.trace.compare_target.clone(),
+        ),
+        (
+            tr(model.language, "对比出口", "Compare via"),
+            model.diagnostics.trace.compare_via.clone(),
+        ),
+    ]
+}
+
+fn port_scan_fields(model: &AppModel) -> Fields {
+    let state = &model.diagnostics.port_scan.persist;
+    vec![
+        (
+            tr(model.language, "目标 IP/域名", "Target IP/Domain"),
+            state.target.clone(),
+        ),
+        (
+            tr(model.language, "起始端口", "Start port"),
+            state.start_port.clone(),
+        ),
+        (
+            tr(model.language, "结束端口", "End port"),
+            state.end_port.clone(),
+        ),
+        (
+            tr(model.language, "超时 (ms)", "Timeout (ms)"),
+            state.timeout_ms.clone(),
+        ),
+    ]
+}
+
+fn public_speed_fields(model: &AppModel) -> Fields {
+    vec![(
+        tr(model.language, "测速服务器", "Test Server"),
+        model
+            .diagnostics
+            .public_speed
+            .server
+            .clone()
+            .unwrap_or_else(|| tr(model.language, "自动选择", "Automatic").into()),
+    )]
+}
+
+fn link_quality_fields(model: &AppModel) -> Fields {
+    let state = &model.diagnostics.link_quality;
+    let adapter = state
+        .adapters
+        .get(state.selected_adapter)
+        .map(|adapter| format!("{} ({})", adapter.name, adapter.ipv4))
+        .unwrap_or_else(|| tr(model.language, "无可用网卡", "No adapter").into());
+    vec![
+        (tr(model.language, "网卡", "Interface"), adapter),
+        (
+            tr(model.language, "目标 IP/域名", "Target IP/Domain"),
+            state.params.target.clone(),
+        ),
+        (
+            tr(model.language, "探测次数", "Probe Count"),
+            state.params.count.clone(),
+        ),
+        (
+            tr(model.language, "间隔 (ms)", "Interval (ms)"),
+            state.params.interval_ms.clone(),
+        ),
+        (
+            tr(model.language, "超时 (ms)", "Timeout (ms)"),
+            state.params.timeout_ms.clone(),
+        ),
+        (
+            tr(model.language, "包大小 (字节)", "Packet Size (B)"),
+            state.params.packet_size.clone(),
+        ),
+    ]
+}
+
+fn lan_speed_fields(model: &AppModel) -> Fields {
+    let state = &model.diagnostics.lan_speed.persist;
+    let mut fields = vec![
+        (
+            tr(model.language, "模式", "Mode"),
+            tr(
+                model.language,
+                if state.mode == "client" {
+                    "客户端"
+                } else {
+                    "服务端"
+                },
+                if state.mode == "client" {
+                    "Client"
+                } else {
+                    "Server"
+                },
+            )
+            .into(),
+        ),
+        (tr(model.language, "端口", "Port"), state.port.clone()),
+    ];
+    if state.mode == "client" {
+        fields.extend([
+            (
+                tr(model.language, "协议", "Protocol"),
+                state.proto.to_uppercase(),
+            ),
+            (
+                tr(model.language, "方向", "Direction"),
+                match state.direction.as_str() {
+                    "down" => tr(model.language, "下载", "Download"),
+                    "bidir" => tr(model.language, "双向", "Bidirectional"),
+                    _ => tr(model.language, "上传", "Upload"),
+                }
+                .into(),
+            ),
+            (tr(model.language, "对端", "Peer"), state.peer.clone()),
+            (
+                tr(model.language, "时长", "Duration"),
+                state.duration.clone(),
+            ),
+            (tr(model.language, "流数", "Streams"), state.streams.clone()),
+            (tr(model.language, "载荷", "Payload"), state.payload.clone()),
+        ]);
+        if state.proto == "udp" {
+            fields.push((
+                tr(model.language, "限速 Mbps", "Rate Mbps"),
+                state.rate.clone(),
+            ));
         }
-        if summary.rx_bytes > 0 {
-            lines.push(Line::from(format!(
-                "{} {}   {}: {}",
-                tr(model.language, "RX 平均", "RX average"),
-                format_speed_dual(average_bytes_per_second(
-                    summary.rx_bytes,
-                    summary.elapsed_ms
-                )),
-                tr(model.language, "总量", "Total"),
-                format_bytes(summary.rx_bytes),
-            )));
+    }
+    fields
+}
+
+fn render_settings(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    const LABEL_WIDTH: usize = 28;
+    let language = model.language;
+    let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).split(area);
+    let list_inner = Block::bordered().inner(rows[0]);
+
+    // Section headings are interleaved with the rows; only rows are clickable.
+    let mut lines = Vec::new();
+    let mut row_lines = Vec::new();
+    for (index, setting) in SETTINGS.into_iter().enumerate() {
+        if index == 0 || SETTINGS[index - 1].section() != setting.section() {
+            lines.push(Line::styled(
+                settings_section_label(language, setting.section()),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ));
         }
-        lines.push(Line::from(format!(
-            "{}: {:.1}s",
-            tr(model.language, "用时", "Elapsed"),
-            summary.elapsed_ms as f64 / 1_000.0
-        )));
-        if let Some(loss) = summary.loss_percent {
-            lines.push(Line::from(format!(
-                "{}: {:.2}%   {}: {}   {}: {} ms",
-                tr(model.language, "丢包", "Loss"),
-                loss,
-                tr(model.language, "乱序", "Out of order"),
-                summary.out_of_order.unwrap_or_default(),
-                tr(model.language, "抖动", "Jitter"),
-                format_optional_f64(summary.jitter_ms),
-            )));
+        let selected = index == model.settings.selected;
+        let editing = selected && model.settings.input.is_some();
+        let value = match &model.settings.input {
+            Some((text, _)) if editing => text.clone(),
+            _ => setting_value(model, setting),
+        };
+        row_lines.push(lines.len());
+        lines.push(
+            Line::from(vec![
+                Span::styled(
+                    if selected { "> " } else { "  " },
+                    Style::default().fg(PRIMARY),
+                ),
+                Span::styled(
+                    pad_display(&setting_label(language, setting), LABEL_WIDTH),
+                    Style::default().fg(MUTED),
+                ),
+                Span::raw(" : "),
+                Span::styled(
+                    value,
+                    Style::default()
+                        .fg(if editing { PRIMARY } else { SECONDARY })
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ])
+            .style(if selected {
+                Style::default().bg(SELECTED)
+            } else {
+                Style::default()
+            }),
+        );
+    }
+    let selected_line = row_lines
+        .get(model.settings.selected)
+        .copied()
+        .unwrap_or_default();
+    let capacity = list_inner.height as usize;
+    ui.page_rows = capacity;
+    let mut visible = visible_range(
+        lines.len(),
+        selected_line,
+        capacity,
+        &mut ui.settings_viewport,
+    );
+    // Scrolling up onto the first row of a section also shows its heading.
+    if selected_line > 0
+        && selected_line == visible.start
+        && !row_lines.contains(&(selected_line - 1))
+        && capacity > 1
+    {
+        ui.settings_viewport = selected_line - 1;
+        visible = ui.settings_viewport..ui.settings_viewport + visible.len();
+    }
+    for (index, line) in row_lines.iter().enumerate() {
+        if visible.contains(line) {
+            ui.settings_regions.push((
+                Rect::new(
+                    list_inner.x,
+                    list_inner.y + (line - visible.start) as u16,
+                    list_inner.width,
+                    1,
+                ),
+                index,
+            ));
         }
-        lines
+    }
+    if let (Some((_, cursor)), Some(line)) = (
+        &model.settings.input,
+        row_lines.get(model.settings.selected),
+    ) && visible.contains(line)
+    {
+        let x = list_inner.x + 2 + LABEL_WIDTH as u16 + 3 + *cursor as u16;
+        frame.set_cursor_position(Position::new(
+            x.min(list_inner.right().saturating_sub(1)),
+            list_inner.y + (line - visible.start) as u16,
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(lines[visible].to_vec()).block(Block::bordered().title(tr(
+            language,
+            " 设置 ",
+            " Settings ",
+        ))),
+        rows[0],
+    );
+
+    let setting = model.settings.current();
+    let (hint, color) = if let Some(error) = &model.settings.error {
+        (setting_error_label(language, error), Color::Red)
+    } else if model.settings.input.is_some() {
+        (
+            match setting {
+                SettingId::Tabs => tr(
+                    language,
+                    "按顺序列出要显示的标签页，用逗号分隔；未列出的隐藏，设置页始终保留   [回车] 保存   [Esc] 取消",
+                    "Visible tabs in order, comma-separated; unlisted tabs are hidden, Settings always stays   [Enter] Save   [Esc] Cancel",
+                ),
+                SettingId::ScanDnsZone => tr(
+                    language,
+                    "填写域名后缀后，扫描只查 DNS（区域传送或逐个 PTR），不向主机发包；留空恢复主动探测   [回车] 保存   [Esc] 取消",
+                    "With a suffix, scans only ask DNS (zone transfer or PTR per address) and send nothing to hosts; empty probes actively   [Enter] Save   [Esc] Cancel",
+                ),
+                SettingId::JumpHost => tr(
+                    language,
+                    "端口扫描与网页检查经此 SSH 主机连接目标（需免密登录）；留空从本机连接   [回车] 保存   [Esc] 取消",
+                    "Port scans and web checks reach targets through this SSH host (key login needed); empty connects from here   [Enter] Save   [Esc] Cancel",
+                ),
+                SettingId::WatchTargets => tr(
+                    language,
+                    "格式：名称=主机, 名称=主机:端口, 名称=https://地址；可加 <毫秒 作为延迟预算、@03:00-03:30 作为每日维护时段（最多 8 个）   [回车] 保存   [Esc] 取消",
+                    "Format: name=host, name=host:port, name=https://url; add <ms for a latency budget and @03:00-03:30 for a daily maintenance window (up to 8)   [Enter] Save   [Esc] Cancel",
+                ),
+                SettingId::PublicIpEndpoints => tr(
+                    language,
+                    "格式：类型 地址, 类型 地址（类型为 ipsb / ipinfo / plaintext）   [回车] 保存   [Esc] 取消",
+                    "Format: kind url, kind url (kind is ipsb / ipinfo / plaintext)   [Enter] Save   [Esc] Cancel",
+                ),
+                _ => tr(
+                    language,
+                    "[回车] 保存   [Esc] 取消",
+                    "[Enter] Save   [Esc] Cancel",
+                ),
+            }
+            .to_string(),
+            Color::Yellow,
+        )
     } else {
-        vec![Line::from(Span::styled(
-            tr(model.language, "等待测试结果…", "Waiting for results…"),
-            Style::default().fg(SUBTLE),
-        ))]
+        (
+            match setting.kind() {
+                SettingKind::Number { min, max, .. } => match language {
+                    Language::Zh => format!("[←/→] 调整   [回车] 输入数值（{min}–{max}）"),
+                    Language::En => format!("[←/→] Adjust   [Enter] Type a value ({min}–{max})"),
+                },
+                SettingKind::Text => tr(language, "[回车] 编辑", "[Enter] Edit").to_string(),
+                SettingKind::Choice | SettingKind::Toggle => {
+                    tr(language, "[←/→] 或 [回车] 切换", "[←/→] or [Enter] Change").to_string()
+                }
+                SettingKind::Action if setting == SettingId::RestoreDefaults => tr(
+                    language,
+                    "[回车] 把本页设置恢复为默认值，界面语言与参数记忆保持不变",
+                    "[Enter] Restores this page to defaults; language and remembered inputs stay",
+                )
+                .to_string(),
+                SettingKind::Action => tr(
+                    language,
+                    "[回车] 清空各工具记住的参数，设备备注与事件记录保留",
+                    "[Enter] Clears remembered tool inputs; device notes and events stay",
+                )
+                .to_string(),
+            },
+            Color::Yellow,
+        )
     };
-    frame.render_widget(Paragraph::new(summary_lines), summary_area);
+    frame.render_widget(
+        Paragraph::new(hint)
+            .block(Block::bordered())
+            .style(Style::default().fg(color))
+            .alignment(Alignment::Center),
+        rows[1],
+    );
+}
 
-    if state.common.status == TaskStatus::Running {
-        let phase = match state.phase {
-            Some(LanSpeedPhase::Listening) => tr(model.language, "等待客户端", "Listening"),
-            Some(LanSpeedPhase::Connecting) => tr(model.language, "正在连接", "Connecting"),
-            Some(LanSpeedPhase::Connected) => tr(model.language, "测试中", "Connected"),
-            None => tr(model.language, "运行中", "Running"),
-        };
-        frame.render_widget(
-            Paragraph::new(format!(
-                "{phase} | {}",
-                tr(model.language, "空格 停止", "Space to stop")
-            ))
-            .style(Style::default().fg(Color::Green)),
-            status_area,
-        );
-    } else {
-        render_diagnostic_status(frame, status_area, &state.common.status, model.language);
+fn settings_section_label(language: Language, section: SettingsSection) -> Cow<'static, str> {
+    match section {
+        SettingsSection::General => tr(language, "常规", "General"),
+        SettingsSection::Scanner => tr(language, "局域网扫描", "Scanner"),
+        SettingsSection::Ping => tr(language, "Ping 默认值", "Ping defaults"),
+        SettingsSection::Network => tr(language, "网络", "Network"),
+        SettingsSection::Monitoring => tr(language, "监视", "Monitoring"),
+        SettingsSection::Retention => tr(language, "数据保留", "Retention"),
+        SettingsSection::Appearance => tr(language, "外观", "Appearance"),
+        SettingsSection::Accessibility => tr(language, "无障碍", "Accessibility"),
+        SettingsSection::Integrations => tr(language, "外部服务", "Integrations"),
+    }
+}
+
+fn setting_label(language: Language, setting: SettingId) -> Cow<'static, str> {
+    match setting {
+        SettingId::Language => tr(language, "界面语言", "Language"),
+        SettingId::LowPower => tr(language, "低功耗模式", "Low-power mode"),
+        SettingId::ClearSession => tr(language, "清空参数记忆", "Reset remembered parameters"),
+        SettingId::RestoreDefaults => tr(language, "恢复默认设置", "Restore defaults"),
+        SettingId::Doctor => tr(language, "自我诊断", "Self-check"),
+        SettingId::ScanConcurrency => tr(language, "扫描并发数", "Scan concurrency"),
+        SettingId::ScanAdaptive => tr(language, "自动调节并发", "Adaptive concurrency"),
+        SettingId::ScanEnrich => tr(language, "默认识别设备", "Identify devices"),
+        SettingId::ScanDnsZone => tr(language, "DNS 区域扫描", "DNS zone sweep"),
+        SettingId::PingTarget => tr(language, "目标", "Target"),
+        SettingId::PingInterval => tr(language, "间隔 (ms)", "Interval (ms)"),
+        SettingId::PingTimeout => tr(language, "超时 (ms)", "Timeout (ms)"),
+        SettingId::PingPacketSize => tr(language, "包大小 (字节)", "Packet size (bytes)"),
+        SettingId::Offline => tr(language, "离线模式", "Offline mode"),
+        SettingId::SystemProxy => tr(language, "使用系统代理", "Use system proxy"),
+        SettingId::JumpHost => tr(language, "SSH 跳板机", "SSH jump host"),
+        SettingId::WatchTargets => tr(language, "监视目标", "Watch targets"),
+        SettingId::DropAlert => tr(language, "丢包告警阈值 (%)", "Drop alert threshold (%)"),
+        SettingId::RetainSamples => tr(language, "每个工具保留的样本", "Samples kept per tool"),
+        SettingId::RetainScanRows => tr(language, "保留的扫描结果", "Scan results kept"),
+        SettingId::RetainMemory => tr(language, "内存上限 (MiB)", "Memory budget (MiB)"),
+        SettingId::Theme => tr(language, "配色方案", "Color theme"),
+        SettingId::ChartStyle => tr(language, "历史图表", "History charts"),
+        SettingId::RegionFormat => tr(language, "日期与数字", "Dates & numbers"),
+        SettingId::ClockFormat => tr(language, "时钟", "Clock"),
+        SettingId::ShowUtc => tr(language, "同时显示 UTC 时间", "Show UTC alongside"),
+        SettingId::Tabs => tr(language, "标签页", "Tabs"),
+        SettingId::ColorMode => tr(language, "色彩模式", "Color mode"),
+        SettingId::PublicIpEndpoints => tr(language, "公网 IP 接口", "Public IP endpoints"),
+        SettingId::CheckUpdates => tr(language, "检查更新", "Check for updates"),
+    }
+}
+
+fn setting_value(model: &AppModel, setting: SettingId) -> String {
+    let language = model.language;
+    let on_off = |value: bool| {
+        if value {
+            tr(language, "开启", "On")
+        } else {
+            tr(language, "关闭", "Off")
+        }
+        .to_string()
+    };
+    let done = model.settings.done == Some(setting);
+    match setting {
+        SettingId::Language => match language {
+            Language::Zh => "简体中文",
+            Language::En => "English",
+        }
+        .to_string(),
+        SettingId::LowPower => match model.low_power {
+            LowPowerMode::Auto if model.on_battery => {
+                tr(language, "自动（电池供电中）", "Auto (on battery)")
+            }
+            LowPowerMode::Auto => tr(language, "自动", "Auto"),
+            LowPowerMode::On => tr(language, "开启", "On"),
+            LowPowerMode::Off => tr(language, "关闭", "Off"),
+        }
+        .to_string(),
+        SettingId::ClearSession if done => tr(language, "已清空 ✓", "Cleared ✓").to_string(),
+        SettingId::ClearSession => tr(language, "回车清空", "Press Enter to clear").to_string(),
+        SettingId::RestoreDefaults if done => tr(language, "已恢复 ✓", "Restored ✓").to_string(),
+        SettingId::RestoreDefaults => {
+            tr(language, "回车恢复", "Press Enter to restore").to_string()
+        }
+        SettingId::Doctor if model.doctor.running() => {
+            tr(language, "检查中…", "Checking…").to_string()
+        }
+        SettingId::Doctor if !model.doctor.checks.is_empty() => {
+            let label = match doctor_status(&model.doctor.checks) {
+                DoctorStatus::Ok => tr(language, "全部正常", "All good"),
+                DoctorStatus::Warn => tr(language, "有提醒", "Warnings"),
+                DoctorStatus::Fail => tr(language, "发现问题", "Problems found"),
+            };
+            format!(
+                "{label}  {}",
+                tr(language, "回车重新检查", "Enter to re-run")
+            )
+        }
+        SettingId::Doctor => tr(
+            language,
+            "回车检查权限、ICMP、抓包、防火墙、配置与外网连通性",
+            "Enter checks privileges, ICMP, capture, firewall, config and connectivity",
+        )
+        .to_string(),
+        SettingId::ScanAdaptive if model.scan_adaptive => tr(
+            language,
+            "开启（并发数为上限，超时增多时降速）",
+            "On (concurrency is the ceiling; backs off when probes time out)",
+        )
+        .to_string(),
+        SettingId::ScanAdaptive => on_off(false),
+        SettingId::ScanEnrich => on_off(model.scanner.enrich),
+        SettingId::ScanDnsZone if model.scanner.dns_zone.is_empty() => {
+            tr(language, "关闭（主动探测）", "Off (active probing)").to_string()
+        }
+        SettingId::ScanDnsZone => model.scanner.dns_zone.clone(),
+        SettingId::PingTarget => model.diagnostics.ping.request.target.clone(),
+        SettingId::Offline if model.offline => tr(
+            language,
+            "开启（不查询公网 IP 与地理位置）",
+            "On (no public IP or location lookups)",
+        )
+        .to_string(),
+        SettingId::Offline => on_off(false),
+        SettingId::SystemProxy => on_off(model.public_ip_config().use_system_proxy),
+        SettingId::ShowUtc => on_off(model.show_utc),
+        SettingId::JumpHost if model.jump_host.is_empty() => {
+            tr(language, "关闭（从本机连接）", "Off (connect from here)").to_string()
+        }
+        SettingId::JumpHost => model.jump_host.clone(),
+        SettingId::Theme => match model.theme {
+            ThemeId::Classic => tr(language, "经典", "Classic"),
+            ThemeId::Nord => "Nord".into(),
+            ThemeId::CatppuccinMocha => "Catppuccin Mocha".into(),
+            ThemeId::Dracula => "Dracula".into(),
+        }
+        .to_string(),
+        SettingId::ChartStyle => match model.chart_style {
+            ChartStyle::Blocks => tr(language, "方块", "Blocks"),
+            ChartStyle::Braille if !model.terminal.unicode => tr(
+                language,
+                "盲文折线（终端不支持，使用方块）",
+                "Braille line (unsupported here; blocks)",
+            ),
+            ChartStyle::Braille => tr(language, "盲文折线", "Braille line"),
+        }
+        .to_string(),
+        SettingId::RegionFormat => match model.region_format {
+            RegionFormat::Iso => "ISO · 2026-07-12 · 1.5".into(),
+            RegionFormat::European => tr(
+                language,
+                "欧洲 · 12.07.2026 · 1,5",
+                "European · 12.07.2026 · 1,5",
+            ),
+            RegionFormat::Us => tr(language, "美国 · 07/12/2026 · 1.5", "US · 07/12/2026 · 1.5"),
+        }
+        .to_string(),
+        SettingId::ClockFormat => match model.clock_format {
+            ClockFormat::TwentyFourHour => tr(language, "24 小时 · 20:30", "24-hour · 20:30"),
+            ClockFormat::TwelveHour => tr(language, "12 小时 · 8:30 PM", "12-hour · 8:30 PM"),
+        }
+        .to_string(),
+        SettingId::ColorMode if model.no_color => tr(
+            language,
+            "单色（NO_COLOR 环境变量）",
+            "Monochrome (NO_COLOR is set)",
+        )
+        .to_string(),
+        SettingId::ColorMode => match model.color_mode {
+            ColorMode::Standard => tr(language, "标准", "Standard"),
+            ColorMode::ColorBlind => tr(language, "色盲友好", "Color-blind safe"),
+            ColorMode::Monochrome => tr(language, "单色（符号标注状态）", "Monochrome"),
+        }
+        .to_string(),
+        SettingId::Tabs => format_tabs(model.tabs()),
+        SettingId::CheckUpdates => match &model.update.available {
+            Some(release) if model.check_updates => match language {
+                Language::Zh => format!("开启（有新版本 {}）", release.version),
+                Language::En => format!("On ({} available)", release.version),
+            },
+            _ => on_off(model.check_updates),
+        },
+        SettingId::PublicIpEndpoints => match model.public_ip_config().endpoints.as_slice() {
+            [] => tr(
+                language,
+                "未配置（不查询公网 IP）",
+                "None (lookup disabled)",
+            )
+            .to_string(),
+            endpoints => format_endpoints(endpoints),
+        },
+        SettingId::WatchTargets => match model.watch.targets().as_slice() {
+            [] => tr(language, "未配置", "None").to_string(),
+            targets => format_watch_targets(targets),
+        },
+        SettingId::DropAlert => match model.drop_alert_percent {
+            0 => tr(language, "关闭", "Off").to_string(),
+            percent => format!("{percent}%"),
+        },
+        SettingId::ScanConcurrency
+        | SettingId::PingInterval
+        | SettingId::PingTimeout
+        | SettingId::PingPacketSize
+        | SettingId::RetainSamples
+        | SettingId::RetainScanRows
+        | SettingId::RetainMemory => model.setting_number(setting).to_string(),
     }
 }
 
-fn average_bytes_per_second(bytes: u64, elapsed_ms: u64) -> u64 {
-    bytes
-        .saturating_mul(1_000)
-        .checked_div(elapsed_ms)
-        .unwrap_or_default()
-}
-
-fn render_diagnostic_status(
-    frame: &mut Frame,
-    area: Rect,
-    status: &TaskStatus,
-    language: Language,
-) {
-    let (text, color) = match status {
-        TaskStatus::Running => (
-            format!(
-                "{} | {}",
-                tr(language, "运行中", "Running"),
-                tr(language, "空格 停止", "Space to stop")
-            ),
-            Color::Green,
+fn setting_error_label(language: Language, error: &SettingError) -> String {
+    match (error, language) {
+        (SettingError::NotANumber, Language::Zh) => "请输入整数".to_string(),
+        (SettingError::NotANumber, Language::En) => "Enter a whole number".to_string(),
+        (SettingError::OutOfRange { min, max }, Language::Zh) => {
+            format!("取值范围为 {min}–{max}")
+        }
+        (SettingError::OutOfRange { min, max }, Language::En) => {
+            format!("Must be between {min} and {max}")
+        }
+        (SettingError::InvalidHost, Language::Zh) => "请输入不含空格的主机名或 IP".to_string(),
+        (SettingError::InvalidHost, Language::En) => {
+            "Enter a host name or IP without spaces".to_string()
+        }
+        (SettingError::InvalidDomain, Language::Zh) => {
+            "请输入域名后缀，如 corp.example.com".to_string()
+        }
+        (SettingError::InvalidDomain, Language::En) => {
+            "Enter a DNS suffix such as corp.example.com".to_string()
+        }
+        (SettingError::InvalidJumpHost, Language::Zh) => {
+            "格式为 [用户@]主机[:端口]，如 ops@bastion.example.com:2222".to_string()
+        }
+        (SettingError::InvalidJumpHost, Language::En) => {
+            "Use [user@]host[:port], e.g. ops@bastion.example.com:2222".to_string()
+        }
+        (SettingError::InvalidEndpoint(entry), Language::Zh) => {
+            format!("无效的接口 \"{entry}\"：类型须为 ipsb / ipinfo / plaintext，地址须为 http(s)")
+        }
+        (SettingError::InvalidEndpoint(entry), Language::En) => format!(
+            "Invalid endpoint \"{entry}\": kind must be ipsb / ipinfo / plaintext and the URL http(s)"
         ),
-        TaskStatus::Done => (
-            tr(language, "完成 | 空格 重新开始", "Done | Space to restart").into(),
-            SECONDARY,
+        (SettingError::UnknownTab(name), Language::Zh) => format!(
+            "未知标签页 \"{name}\"：可用 dashboard / adapters / scanner / traffic / diagnostics / events / settings"
         ),
-        TaskStatus::Failed(error) => (
-            format!("{}: {error}", tr(language, "失败", "Failed")),
-            Color::Red,
+        (SettingError::UnknownTab(name), Language::En) => format!(
+            "Unknown tab \"{name}\": use dashboard / adapters / scanner / traffic / diagnostics / events / settings"
         ),
-        TaskStatus::Idle => (
+        (SettingError::InvalidWatchTarget(entry), Language::Zh) => {
             format!(
-                "{} | {}",
-                tr(language, "已停止", "Stopped"),
-                tr(language, "空格 开始", "Space to start")
+                "无效的监视目标 \"{entry}\"：须为主机、主机:端口或 http(s) 地址，预算须为正整数毫秒，维护时段形如 03:00-03:30"
+            )
+        }
+        (SettingError::InvalidWatchTarget(entry), Language::En) => {
+            format!(
+                "Invalid watch target \"{entry}\": use a host, host:port or an http(s) URL, a budget in whole milliseconds and a window like 03:00-03:30"
+            )
+        }
+        (SettingError::TooManyWatchTargets { max }, Language::Zh) => {
+            format!("最多监视 {max} 个目标")
+        }
+        (SettingError::TooManyWatchTargets { max }, Language::En) => {
+            format!("Watch at most {max} targets")
+        }
+    }
+}
+
+/// Key hints of the current page on the left, then the global buttons. On a
+/// narrow line tab switching and the language button give way first, then
+/// the last hints; new problems, help and quit always stay.
+fn render_footer(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
+    let next = binding(model, "next_tab", "Tab");
+    let previous = binding(model, "prev_tab", "Shift+Tab");
+    let language = binding(model, "toggle_language", "Ctrl+L");
+    let help = binding(model, "help", "F1");
+    let quit = binding(model, "quit", "Ctrl+C");
+    let buttons = match model.language {
+        Language::Zh => [
+            (format!("[{next}/{previous}] 切换菜单"), Action::NextPage),
+            (format!("[{language}] Language"), Action::ToggleLanguage),
+            (format!("[{help}] 帮助"), Action::Help),
+            (format!("[{quit}] 退出"), Action::Quit),
+        ],
+        Language::En => [
+            (format!("[{next}/{previous}] Switch"), Action::NextPage),
+            (
+                format!("[{language}] {LANGUAGE_SWITCH_ZH}"),
+                Action::ToggleLanguage,
             ),
-            Color::Red,
-        ),
+            (format!("[{help}] Help"), Action::Help),
+            (format!("[{quit}] Quit"), Action::Quit),
+        ],
     };
-    frame.render_widget(Paragraph::new(text).style(Style::default().fg(color)), area);
-}
+    let global = Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD);
+    // (text, click action, style) in display order.
+    let mut items: Vec<(String, Option<Action>, Style)> = Vec::new();
+    // New problems stay announced until the list is opened.
+    if model.problems.unseen > 0 {
+        let key = binding(model, "problems", "F6");
+        let count = model.problems.unseen;
+        let color = match model.problems.items.first().map(|problem| problem.severity) {
+            Some(Severity::Error) => Color::Red,
+            _ => Color::Yellow,
+        };
+        let label = match model.language {
+            Language::Zh => format!("[{key}] {count} 个新问题"),
+            Language::En => format!("[{key}] {count} new problem(s)"),
+        };
+        items.push((
+            label,
+            Some(Action::Problems),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+    // A locale or theme file being edited says right away when it no longer parses.
+    if let Some(error) = model.overrides.errors.first() {
+        let label = match model.language {
+            Language::Zh => format!("未采用 {error}"),
+            Language::En => format!("Not applied: {error}"),
+        };
+        items.push((label, None, Style::default().fg(Color::Yellow)));
+    }
+    // Gaps of the user's locale file in the language on screen; the full list
+    // is what `iptools i18n-check` prints.
+    let gaps = model.overrides.audit.get(model.language);
+    if !gaps.is_empty() {
+        let (missing, unused) = (gaps.missing.len(), gaps.unused.len());
+        let label = match model.language {
+            Language::Zh => format!("locale.toml：缺 {missing} 条，{unused} 条未用上"),
+            Language::En => format!("locale.toml: {missing} missing, {unused} unused"),
+        };
+        items.push((label, None, Style::default().fg(Color::Yellow)));
+    }
+    let pinned = items.len();
+    let page_hints = module(ModuleId::Page(model.page)).hints(model);
+    // A page that takes Tab itself (diagnostics focus) says what it does there.
+    let tab_taken = page_hints
+        .iter()
+        .any(|hint| hint.keys.iter().any(|&(name, _)| name == "next_tab"));
+    for hint in page_hints {
+        let keys = hint
+            .keys
+            .iter()
+            .map(|&(name, fallback)| binding(model, name, fallback))
+            .collect::<Vec<_>>()
+            .join("/");
+        items.push((
+            format!("[{keys}] {}", hint.label),
+            hint.action,
+            Style::default().fg(PRIMARY),
+        ));
+    }
+    let hints = pinned..items.len();
+    for (label, action) in buttons {
+        items.push((label, Some(action), global));
+    }
+    let switch = items.len() - 4;
+    let priority = (0..pinned)
+        .chain([switch + 2, switch + 3])
+        .chain(hints)
+        .chain([switch, switch + 1]);
+    let mut shown = vec![false; items.len()];
+    let mut room = usize::from(area.width);
+    for index in priority.filter(|&index| !(tab_taken && index == switch)) {
+        let width = items[index].0.width() + 2;
+        if width <= room {
+            room -= width;
+            shown[index] = true;
+        }
+    }
 
-fn bottom_row(area: Rect) -> Rect {
-    Rect::new(
-        area.x,
-        area.bottom().saturating_sub(1),
-        area.width,
-        area.height.min(1),
-    )
+    let mut spans = Vec::new();
+    let mut x = area.x;
+    for ((label, action, style), shown) in items.into_iter().zip(shown) {
+        if !shown {
+            continue;
+        }
+        let text = format!(" {label} ");
+        let width = text.width().min(u16::MAX as usize) as u16;
+        if let Some(action) = action
+            && x < area.right()
+        {
+            ui.footer_regions
+                .push((Rect::new(x, area.y, width.min(area.right() - x), 1), action));
+        }
+        spans.push(Span::styled(text, style));
+        x = x.saturating_add(width);
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
-fn render_public_speed(area: Rect, frame: &mut Frame, model: &AppModel) {
-    let state = &model.diagnostics.public_speed;
-    let latest = state.samples.last();
-    let current = latest.map_or(0, |sample| sample.bytes_per_second);
-    let total = latest
-        .map(|sample| sample.bytes)
-        .or_else(|| state.summary.as_ref().map(|summary| summary.total_bytes))
-        .unwrap_or_default();
-    let elapsed = latest.map_or(0, |sample| sample.elapsed_ms);
-    let average = state
-        .summary
-        .as_ref()
-        .map_or(0, |summary| summary.average_bytes_per_second);
-    let peak = state.summary.as_ref().map_or(current, |summary| {
-        summary.peak_bytes_per_second.max(current)
-    });
-    let status_area = bottom_row(area);
-    let metric_height = status_area.y.saturating_sub(area.y).min(6);
-    let metric_area = Rect::new(area.x, area.y, area.width, metric_height);
-    let metrics = Layout::vertical([
-        Constraint::Length(metric_height.min(2)),
-        Constraint::Length(metric_height.saturating_sub(2).min(2)),
-        Constraint::Length(metric_height.saturating_sub(4).min(2)),
-    ])
-    .split(metric_area);
-    let chart_area = Rect::new(
-        area.x,
-        metric_area.bottom(),
-        area.width,
-        status_area.y.saturating_sub(metric_area.bottom()),
-    );
-    frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled(
-                tr(model.language, "当前速率  ", "Current speed  "),
-                Style::default().fg(MUTED),
-            ),
-            Span::styled(
-                format_speed_dual(current),
-                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
-            ),
-        ])),
-        metrics[0],
-    );
-    frame.render_widget(
-        Paragraph::new(format!(
-            "{}: {}    {}: {}",
-            tr(model.language, "平均", "Average"),
-            format_speed_dual(average),
-            tr(model.language, "峰值", "Peak"),
-            format_speed_dual(peak)
-        )),
-        metrics[1],
-    );
-    frame.render_widget(
-        Paragraph::new(format!(
-            "{}: {}    {}: {:.1}s    {}: {}",
-            tr(model.language, "已下载", "Downloaded"),
-            format_bytes(total),
-            tr(model.language, "用时", "Elapsed"),
-            elapsed as f64 / 1_000.0,
-            tr(model.language, "状态", "Status"),
-            task_label(&state.common.status, model.language).trim()
-        )),
-        metrics[2],
-    );
-    let history = state
-        .samples
-        .iter()
-        .map(|sample| sample.bytes_per_second)
-        .collect::<Vec<_>>();
-    frame.render_widget(
-        Sparkline::default()
-            .block(Block::default().borders(Borders::TOP).title(tr(
-                model.language,
-                " 速率历史 ",
-                " Speed history ",
-            )))
-            .data(&history)
-            .style(Style::default().fg(PRIMARY)),
-        chart_area,
-    );
-    render_diagnostic_status(frame, status_area, &state.common.status, model.language);
+fn visible_range(
+    total: usize,
+    selected: usize,
+    capacity: usize,
+    offset: &mut usize,
+) -> std::ops::Range<usize> {
+    if total == 0 || capacity == 0 {
+        *offset = 0;
+        return 0..0;
+    }
+    let capacity = capacity.min(total);
+    let selected = selected.min(total - 1);
+    *offset = (*offset).min(total - capacity);
+    if selected < *offset {
+        *offset = selected;
+    } else if selected >= *offset + capacity {
+        *offset = selected + 1 - capacity;
+    }
+    *offset..(*offset + capacity)
 }
 
-fn render_link_quality(area: Rect, frame: &mut Frame, model: &AppModel) {
-    let state = &model.diagnostics.link_quality;
-    let is_wifi = snapshot_is_wifi(state);
-    let dimension_count = if is_wifi { 6 } else { 3 };
-    let metric_count = if is_wifi { 6 } else { 4 };
-    let status_area = bottom_row(area);
-    let rssi_height = if is_wifi {
-        3.min(status_area.y.saturating_sub(area.y))
-    } else {
-        0
+fn render_help(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 66, 72);
+    frame.render_widget(Clear, area);
+    let next = binding(model, "next_tab", "Tab");
+    let previous = binding(model, "prev_tab", "Shift+Tab");
+    let up = binding(model, "up", "Up");
+    let down = binding(model, "down", "Down");
+    let left = binding(model, "left", "Left");
+    let right = binding(model, "right", "Right");
+    let page_up = binding(model, "page_up", "PageUp");
+    let page_down = binding(model, "page_down", "PageDown");
+    let home = binding(model, "home", "Home");
+    let end = binding(model, "end", "End");
+    let confirm = binding(model, "confirm", "Enter");
+    let toggle = binding(model, "toggle", "Space");
+    let edit = binding(model, "edit", "E");
+    let language_key = binding(model, "toggle_language", "Ctrl+L");
+    let redact = binding(model, "toggle_redaction", "Ctrl+P");
+    let update = binding(model, "show_update", "Ctrl+U");
+    let presets = binding(model, "presets", "F4");
+    let problems = binding(model, "problems", "F6");
+    let audit = binding(model, "audit", "F7");
+    let calculator = binding(model, "calculator", "F8");
+    let search = binding(model, "search", "Ctrl+F");
+    let timeline = binding(model, "timeline", "F9");
+    let export = binding(model, "export", "Ctrl+S");
+    let split = binding(model, "toggle_split", "Ctrl+T");
+    let pane = binding(model, "switch_pane", "Ctrl+W");
+    let help = binding(model, "help", "F1");
+    let back = binding(model, "back", "Esc");
+    let mut detail = match model.language {
+        Language::Zh => format!(
+            "键盘与触控快捷键\n\n{next} / {previous}  切换页面\n1–9 / Alt+1–9     跳到第 N 个标签页\n{up}/{down}/{left}/{right}  导航\n{page_up}/{page_down}/{home}/{end}  翻页、跳到首行或末行\n{confirm} / {toggle}     开始或停止\n{edit}                 编辑\n{language_key}            切换语言\n{redact}            演示打码（隐藏 IP、MAC 和名称）\n{update}            新版本说明（标签栏出现 ↑ 时）\n{presets}                诊断预设（一键检查主机）\n{problems}                问题列表（各工具的失败）\n{audit}                安全审计（扫描结果中的风险）\n{calculator}                地址计算器（IP、IPv6、MAC、CIDR 换算）\n{search}            在当前窗格中搜索（n/N 跳到下一个/上一个）\n{timeline}                时间线（Ping、Wi-Fi、网卡、测速同一时间轴）\n{export}            把 Ping / 测速历史保存为图表\n{split} / {pane}   分屏并排显示两个页面 / 切换焦点\n{help} / {back}          打开或关闭帮助\n\n{}",
+            if model.demo {
+                "演示版本使用确定性模拟数据。"
+            } else {
+                "原生版快捷键从 config.toml 加载。"
+            }
+        ),
+        Language::En => format!(
+            "Keyboard and touch shortcuts\n\n{next} / {previous}  switch pages\n1–9 / Alt+1–9     jump to the Nth tab\n{up}/{down}/{left}/{right}  navigate\n{page_up}/{page_down}/{home}/{end}  page, first or last row\n{confirm} / {toggle}     start or stop\n{edit}                 edit\n{language_key}            toggle language\n{redact}            presenter mode (mask IPs, MACs, names)\n{update}            release notes (when ↑ shows in the tab bar)\n{presets}                diagnostic presets (one-key host check)\n{problems}                problem list (failures of every tool)\n{audit}                security audit of the scan results\n{calculator}                address calculator (IP, IPv6, MAC, CIDR)\n{search}            search the focused pane (n/N to step)\n{timeline}                timeline of ping, Wi-Fi and speed events\n{export}            save ping / speed history as a chart\n{split} / {pane}   split view of two pages / switch focus\n{help} / {back}          open or close help\n\n{}",
+            if model.demo {
+                "The demo uses deterministic simulated data."
+            } else {
+                "Native bindings are loaded from config.toml."
+            }
+        ),
     };
-    let rssi_area = Rect::new(
-        area.x,
-        status_area.y.saturating_sub(rssi_height),
-        area.width,
-        rssi_height,
-    );
-    let header_area = Rect::new(area.x, area.y, area.width, area.height.min(1));
-    let overall_area = Rect::new(
-        area.x,
-        header_area.bottom(),
-        area.width,
-        area.height.saturating_sub(1).min(1),
-    );
-    let dimensions_area = Rect::new(area.x, overall_area.bottom(), area.width, dimension_count);
-    let metrics_area = Rect::new(area.x, dimensions_area.bottom(), area.width, metric_count);
-    let history_area = Rect::new(
-        area.x,
-        metrics_area.bottom(),
-        area.width,
-        rssi_area.y.saturating_sub(metrics_area.bottom()),
+    detail.push_str("\n\n");
+    detail.push_str(module(ModuleId::Page(model.page)).help(model.language));
+    if model.page == Page::Diagnostics {
+        detail.push('\n');
+        detail.push_str(module(ModuleId::Tool(model.diagnostics.tool)).help(model.language));
+    }
+    frame.render_widget(
+        Paragraph::new(detail)
+            .block(Block::bordered().title(tr(model.language, " 帮助 ", " Help ")))
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true }),
+        area,
     );
+}
 
-    let snapshot = state.snapshot.as_ref();
-    let adapter_name = snapshot
-        .map(|value| value.adapter.name.as_str())
-        .or_else(|| {
-            state
-                .adapters
-                .get(state.selected_adapter)
-                .map(|value| value.name.as_str())
-        })
-        .unwrap_or(tr(model.language, "无可用网卡", "No adapter"));
-    let mut header = vec![
-        Span::styled(
-            format!("{}: ", tr(model.language, "网卡", "Adapter")),
-            Style::default().fg(MUTED),
+/// Format choice for the chart of the current tool and where it went.
+fn render_export(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 60, 40);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let export = &model.export;
+    let mut lines = Vec::new();
+    if let Some(chart) = model.current_chart() {
+        lines.push(Line::from(format!(
+            "{}  ({} {})",
+            chart.title,
+            chart.points(),
+            tr(language, "个点", "points")
+        )));
+    }
+    lines.push(Line::default());
+    let mut formats = vec![Span::raw(tr(language, "格式  ", "Format  "))];
+    for format in ChartFormat::ALL {
+        let label = format!(" {} ", format.extension().to_uppercase());
+        formats.push(if format == export.format {
+            Span::styled(
+                label,
+                Style::default().bg(SELECTED).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::raw(label)
+        });
+        formats.push(Span::raw(" "));
+    }
+    lines.push(Line::from(formats));
+    lines.push(Line::default());
+    let (status, color) = match (&export.job, &export.saved, &export.error) {
+        (Some(_), _, _) => (tr(language, "正在保存…", "Saving…").to_string(), SECONDARY),
+        (_, Some(path), _) => (
+            match language {
+                Language::Zh => format!("已保存到 {path}"),
+                Language::En => format!("Saved to {path}"),
+            },
+            PRIMARY,
         ),
-        Span::styled(adapter_name.to_string(), Style::default().fg(SECONDARY)),
-    ];
-    if let Some(snapshot) = snapshot {
-        header.push(Span::styled(
-            format!(
-                " [{}] · {}",
-                if snapshot.adapter.is_wifi {
-                    tr(model.language, "无线", "Wireless")
-                } else {
-                    tr(model.language, "有线", "Wired")
-                },
-                snapshot.adapter.ipv4
+        (_, _, Some(error)) => (error.message.clone(), Color::Red),
+        _ => (
+            tr(
+                language,
+                "图表保存在当前目录，文件名带时间。",
+                "The chart goes to the current directory under a timestamped name.",
+            )
+            .to_string(),
+            MUTED,
+        ),
+    };
+    lines.push(Line::styled(status, Style::default().fg(color)));
+    lines.push(Line::default());
+    lines.push(Line::from(tr(
+        language,
+        "←/→ 选择格式   Enter 保存   Esc 关闭",
+        "←/→ to pick a format   Enter to save   Esc to dismiss",
+    )));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 导出图表 ", " Export chart "))
+                    .border_style(Style::default().fg(SECONDARY)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// Findings over the scan results and the DHCP probe, most severe first.
+fn render_audit(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 80, 70);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let audit = &model.audit;
+    let findings = model.audit_findings();
+    let mut lines = Vec::new();
+    if model.scanner.results.is_empty() {
+        lines.push(Line::styled(
+            tr(
+                language,
+                "还没有扫描结果；先在扫描页扫描网段，开启端口探测可查出更多问题。",
+                "No scan results yet; scan the network on the Scanner page, with port probes for more findings.",
             ),
-            Style::default().fg(SECONDARY),
+            Style::default().fg(MUTED),
         ));
-        if let Some(wireless) = &snapshot.wireless {
-            header.push(Span::styled(
-                format!("  SSID: {}", wireless.ssid),
-                Style::default().fg(Color::White),
-            ));
-        }
+    } else if findings.is_empty() {
+        lines.push(Line::from(tr(language, "未发现问题。", "No findings.")));
     }
-    frame.render_widget(Paragraph::new(Line::from(header)), header_area);
-
-    if let Some(summary) = &state.summary {
-        frame.render_widget(
-            Gauge::default()
-                .gauge_style(
-                    Style::default()
-                        .fg(link_grade_color(summary.grade))
-                        .bg(SELECTED),
-                )
-                .ratio((summary.score / 100.0).clamp(0.0, 1.0))
-                .label(format!(
-                    "{}: {} ({:.0})",
-                    tr(model.language, "评级", "Grade"),
-                    link_grade(summary.grade, model.language),
-                    summary.score
-                )),
-            overall_area,
-        );
-        let dimensions = summary
-            .dimensions
-            .iter()
-            .take(dimension_count as usize)
-            .map(|dimension| {
-                let color = link_score_color(dimension.score);
-                Line::from(vec![
-                    Span::styled(
-                        pad_display(link_dimension(dimension.kind, model.language), 8),
-                        Style::default().fg(MUTED),
-                    ),
-                    Span::styled(score_bar(dimension.score, 12), Style::default().fg(color)),
-                    Span::styled(
-                        format!(
-                            " {:>3.0}{}",
-                            dimension.score,
-                            if summary.weakest == Some(dimension.kind) {
-                                " ◀"
-                            } else {
-                                ""
-                            }
-                        ),
-                        Style::default().fg(color),
-                    ),
-                ])
-            })
-            .collect::<Vec<_>>();
-        frame.render_widget(Paragraph::new(dimensions), dimensions_area);
-
-        let min = state
-            .samples
-            .last()
-            .and_then(|sample| sample.min_latency_ms);
-        let max = state
-            .samples
-            .last()
-            .and_then(|sample| sample.max_latency_ms);
-        let mut metrics = vec![
-            Line::from(format!(
-                "{}: {}/{}/{} ms   {}: {} ms",
-                tr(model.language, "最小/平均/最大", "Min/avg/max"),
-                format_optional_u64(min),
-                format_optional_f64(summary.average_latency_ms),
-                format_optional_u64(max),
-                tr(model.language, "抖动", "Jitter"),
-                format_optional_f64(summary.jitter_ms)
-            )),
-            Line::from(format!(
-                "{}: {:.1}%   {}: {}/{}",
-                tr(model.language, "丢包", "Loss"),
-                summary.loss_percent,
-                tr(model.language, "收发", "Received"),
-                summary.received,
-                summary.sent
+    for finding in findings.iter().skip(audit.scroll) {
+        let color = match finding.severity {
+            AuditSeverity::High => Color::Red,
+            AuditSeverity::Medium => Color::Yellow,
+            AuditSeverity::Low => MUTED,
+        };
+        let severity = finding.severity.label(language);
+        let title = finding.check.title(language);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{severity}{} ",
+                    " ".repeat(6_usize.saturating_sub(severity.width()))
+                ),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                "{title}{}",
+                " ".repeat(32_usize.saturating_sub(title.width()).max(1))
             )),
-        ];
-        if let Some(wireless) = snapshot.and_then(|value| value.wireless.as_ref()) {
-            let sample = state.samples.last();
-            metrics.extend([
-                Line::from(format!(
-                    "RSSI: {}/{}/{} dBm   {}: {} ({}, {} MHz)",
-                    sample
-                        .and_then(|value| value.min_rssi_dbm)
-                        .map_or_else(|| "—".into(), |value| value.to_string()),
-                    format_optional_f64(summary.average_rssi_dbm),
-                    sample
-                        .and_then(|value| value.max_rssi_dbm)
-                        .map_or_else(|| "—".into(), |value| value.to_string()),
-                    tr(model.language, "信道", "Channel"),
-                    wireless.channel,
-                    wireless.band,
-                    wireless.frequency_mhz
-                )),
-                Line::from(format!(
-                    "{}: {}%   {}: {}",
-                    tr(model.language, "信号质量", "Signal quality"),
-                    format_optional_f64(summary.average_signal_quality),
-                    tr(model.language, "制式", "PHY"),
-                    wireless.phy_type
-                )),
-                Line::from(format!(
-                    "Tx/Rx: {}/{} Mbps",
-                    wireless.tx_rate_mbps, wireless.rx_rate_mbps
-                )),
-                Line::from(format!(
-                    "BSSID: {}   {} / {}",
-                    wireless.bssid, wireless.authentication, wireless.cipher
-                )),
-            ]);
-        } else if let Some(snapshot) = snapshot {
-            metrics.extend([
-                Line::from(format!(
-                    "{}: {}",
-                    tr(model.language, "链路速率", "Link speed"),
-                    summary
-                        .link_speed_bps
-                        .map(|speed| format_speed_dual(speed / 8))
-                        .unwrap_or_else(|| "—".into())
-                )),
-                Line::from(format!(
-                    "MAC: {}   IPv4: {}",
-                    snapshot.adapter.mac, snapshot.adapter.ipv4
-                )),
-            ]);
-        }
-        frame.render_widget(Paragraph::new(metrics), metrics_area);
-    } else {
-        frame.render_widget(
-            Paragraph::new(tr(
-                model.language,
-                "等待链路质量样本…",
-                "Waiting for link-quality samples…",
-            ))
-            .style(Style::default().fg(SUBTLE)),
-            dimensions_area,
-        );
+            Span::raw(format!("{:<16} ", finding.ip)),
+            Span::styled(finding.detail.clone(), Style::default().fg(MUTED)),
+        ]));
     }
-
-    let latency = state
-        .samples
-        .iter()
-        .map(|sample| sample.latency_ms.unwrap_or_default())
-        .collect::<Vec<_>>();
+    lines.push(Line::default());
+    let dhcp = match (&audit.dhcp_job, &audit.dhcp, &audit.dhcp_error) {
+        (Some(_), _, _) => tr(language, "DHCP：正在探测…", "DHCP: probing…").to_string(),
+        (_, _, Some(error)) => format!("DHCP: {}", error.message),
+        (_, Some(servers), _) => match language {
+            Language::Zh => format!("DHCP：{} 台服务器应答", servers.len()),
+            Language::En => format!("DHCP: {} server(s) answered", servers.len()),
+        },
+        _ => tr(language, "DHCP：未探测", "DHCP: not probed").to_string(),
+    };
+    lines.push(Line::styled(dhcp, Style::default().fg(SECONDARY)));
+    let export = match (&audit.export_job, &audit.saved, &audit.export_error) {
+        (Some(_), _, _) => Some((tr(language, "正在保存…", "Saving…").to_string(), SECONDARY)),
+        (_, Some(path), _) => Some((
+            match language {
+                Language::Zh => format!("已保存到 {path}"),
+                Language::En => format!("Saved to {path}"),
+            },
+            PRIMARY,
+        )),
+        (_, _, Some(error)) => Some((error.message.clone(), Color::Red)),
+        _ => None,
+    };
+    if let Some((status, color)) = export {
+        lines.push(Line::styled(status, Style::default().fg(color)));
+    }
+    lines.push(Line::default());
+    let refresh = binding(model, "refresh", "R");
+    let export = binding(model, "export", "Ctrl+S");
+    lines.push(Line::from(match language {
+        Language::Zh => format!(
+            "↑/↓ 滚动   {refresh} 重新探测 DHCP   {export} 保存为 Markdown   Esc 关闭"
+        ),
+        Language::En => format!(
+            "↑/↓ to scroll   {refresh} to re-probe DHCP   {export} to save as Markdown   Esc to dismiss"
+        ),
+    }));
+    let color = match findings.first().map(|finding| finding.severity) {
+        Some(AuditSeverity::High) => Color::Red,
+        Some(AuditSeverity::Medium) => Color::Yellow,
+        _ => SECONDARY,
+    };
     frame.render_widget(
-        Sparkline::default()
-            .block(Block::default().borders(Borders::TOP).title(tr(
-                model.language,
-                "延迟历史",
-                "Latency History",
-            )))
-            .data(&latency)
-            .style(Style::default().fg(PRIMARY)),
-        history_area,
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 安全审计 ", " Security audit "))
+                    .border_style(Style::default().fg(color)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
     );
-    if is_wifi {
-        let rssi = state
-            .samples
-            .iter()
-            .map(|sample| {
-                sample
-                    .rssi_dbm
-                    .map_or(0, |value| (value + 100).max(0) as u64)
-            })
-            .collect::<Vec<_>>();
-        frame.render_widget(
-            Sparkline::default()
-                .block(Block::default().borders(Borders::TOP).title(tr(
-                    model.language,
-                    "RSSI 历史",
-                    "RSSI History",
-                )))
-                .data(&rssi)
-                .style(Style::default().fg(Color::Magenta)),
-            rssi_area,
-        );
-    }
-    render_diagnostic_status(frame, status_area, &state.common.status, model.language);
 }
 
-fn snapshot_is_wifi(state: &iptools_core::LinkQualityState) -> bool {
-    state
-        .snapshot
-        .as_ref()
-        .is_some_and(|snapshot| snapshot.adapter.is_wifi)
-}
-
-fn format_speed_dual(bytes_per_second: u64) -> String {
-    if bytes_per_second >= 1_000_000 {
-        format!(
-            "{:.2} MB/s · {:.2} Mbps",
-            bytes_per_second as f64 / 1_000_000.0,
-            bytes_per_second as f64 * 8.0 / 1_000_000.0
-        )
-    } else {
-        format!(
-            "{:.1} KB/s · {:.2} Mbps",
-            bytes_per_second as f64 / 1_000.0,
-            bytes_per_second as f64 * 8.0 / 1_000_000.0
-        )
+/// Conversions of the typed address, network or MAC, updated per key.
+fn render_calculator(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 70, 60);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let calculator = &model.calculator;
+    let cursor = calculator.cursor.min(calculator.input.len());
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("> "),
+            Span::raw(calculator.input[..cursor].to_string()),
+            Span::styled("_", Style::default().fg(PRIMARY)),
+            Span::raw(calculator.input[cursor..].to_string()),
+        ]),
+        Line::default(),
+    ];
+    match calculator.rows(language) {
+        Some(rows) if !rows.is_empty() => {
+            for row in rows {
+                let label = row.field.label(language);
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{label}{}",
+                            " ".repeat(22_usize.saturating_sub(label.width()).max(1))
+                        ),
+                        Style::default().fg(SECONDARY),
+                    ),
+                    Span::raw(row.value),
+                ]));
+            }
+        }
+        rows => {
+            if rows.is_none() {
+                lines.push(Line::styled(
+                    tr(language, "无法识别。", "Not recognised."),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            lines.push(Line::styled(
+                tr(
+                    language,
+                    "输入 IPv4 或 IPv6 地址、整数（0x 开头为十六进制）、MAC、CIDR，\n或“CIDR 地址”检查是否包含，例如 10.0.0.0/8 10.1.2.3。",
+                    "Type an IPv4 or IPv6 address, an integer (0x for hex), a MAC, a CIDR,\nor \"CIDR address\" to check containment, e.g. 10.0.0.0/8 10.1.2.3.",
+                ),
+                Style::default().fg(MUTED),
+            ));
+        }
     }
+    lines.push(Line::default());
+    lines.push(Line::from(tr(
+        language,
+        "←/→ 移动光标   Backspace 删除   Esc 关闭",
+        "←/→ to move   Backspace to delete   Esc to dismiss",
+    )));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 地址计算器 ", " Address calculator "))
+                    .border_style(Style::default().fg(SECONDARY)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
 }
 
-fn format_optional_f64(value: Option<f64>) -> String {
-    value.map_or_else(|| "—".into(), |value| format!("{value:.1}"))
-}
+/// Ping latency and losses, Wi-Fi roams, interface changes and finished
+/// speed tests on one time axis ending now, with the marks in the window
+/// listed newest first underneath.
+fn render_timeline(frame: &mut Frame, model: &AppModel) {
+    const LABEL_WIDTH: u16 = 12;
+    let area = centered(frame.area(), 86, 80);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let timeline = &model.timeline;
+    let span_minutes = timeline.span_ms() / 60_000;
+    let block = Block::bordered()
+        .title(match language {
+            Language::Zh => format!(" 时间线 · 最近 {span_minutes} 分钟 "),
+            Language::En => format!(" Timeline · last {span_minutes} min "),
+        })
+        .border_style(Style::default().fg(SECONDARY));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::vertical([
+        Constraint::Length(5),
+        Constraint::Length(TimelineLane::ALL.len() as u16),
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+    let columns = usize::from(inner.width.saturating_sub(LABEL_WIDTH));
+    let now = model.elapsed_ms;
+    let pings = timeline.ping_columns(now, columns);
 
-fn format_optional_u64(value: Option<u64>) -> String {
-    value.map_or_else(|| "—".into(), |value| value.to_string())
-}
+    let [label, plot] =
+        Layout::horizontal([Constraint::Length(LABEL_WIDTH), Constraint::Fill(1)]).areas(rows[0]);
+    let latencies: Vec<u64> = pings
+        .iter()
+        .map(|(latency, _)| latency.unwrap_or(0))
+        .collect();
+    let slowest = latencies.iter().copied().max().unwrap_or(0);
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::styled(
+                tr(language, "延迟", "Latency"),
+                Style::default().fg(SECONDARY),
+            ),
+            Line::styled(format!("{slowest} ms"), Style::default().fg(MUTED)),
+        ]),
+        label,
+    );
+    frame.render_widget(
+        Sparkline::default()
+            .data(&latencies)
+            .style(Style::default().fg(PRIMARY)),
+        plot,
+    );
 
-fn pad_display(value: &str, width: usize) -> String {
-    let padding = width.saturating_sub(UnicodeWidthStr::width(value));
-    format!("{value}{}", " ".repeat(padding))
-}
+    let lanes = TimelineLane::ALL
+        .iter()
+        .map(|&lane| {
+            let mut cells: Vec<Span> =
+                vec![Span::styled("·", Style::default().fg(MUTED)); columns];
+            if lane == TimelineLane::Loss {
+                for (cell, (_, lost)) in cells.iter_mut().zip(&pings) {
+                    if *lost {
+                        *cell = Span::styled("×", Style::default().fg(Color::Red));
+                    }
+                }
+            }
+            for mark in timeline.marks.iter().filter(|mark| mark.lane() == lane) {
+                if let Some(column) = timeline.column(mark.at_ms, now, columns) {
+                    cells[column] = timeline_glyph(&mark.kind);
+                }
+            }
+            let title = lane.label(language);
+            let mut spans = vec![Span::styled(
+                format!(
+                    "{title}{}",
+                    " ".repeat(usize::from(LABEL_WIDTH).saturating_sub(title.width()))
+                ),
+                Style::default().fg(SECONDARY),
+            )];
+            spans.extend(cells);
+            Line::from(spans)
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lanes), rows[1]);
 
-fn link_grade(grade: LinkQualityGrade, language: Language) -> &'static str {
-    match grade {
-        LinkQualityGrade::Excellent => tr(language, "优秀", "Excellent"),
-        LinkQualityGrade::Good => tr(language, "良好", "Good"),
-        LinkQualityGrade::Fair => tr(language, "一般", "Fair"),
-        LinkQualityGrade::Poor => tr(language, "较差", "Poor"),
-    }
+    let start = match language {
+        Language::Zh => format!("-{span_minutes} 分钟"),
+        Language::En => format!("-{span_minutes} min"),
+    };
+    let end = tr(language, "现在", "now");
+    frame.render_widget(
+        Paragraph::new(Line::styled(
+            format!(
+                "{}{start}{}{end}",
+                " ".repeat(usize::from(LABEL_WIDTH)),
+                " ".repeat(columns.saturating_sub(start.width() + end.width()).max(1))
+            ),
+            Style::default().fg(MUTED),
+        )),
+        rows[2],
+    );
+
+    let window = timeline
+        .marks
+        .iter()
+        .rev()
+        .filter(|mark| timeline.column(mark.at_ms, now, columns.max(1)).is_some())
+        .map(|mark| {
+            let glyph = timeline_glyph(&mark.kind);
+            let detail = match &mark.kind {
+                TimelineKind::Network(event) => {
+                    let (kind, _) = network_event_label(event.kind, language);
+                    let change = match (event.from.is_empty(), event.to.is_empty()) {
+                        (true, true) => String::new(),
+                        (true, false) => shown_text(&event.to).into_owned(),
+                        (false, true) => shown_text(&event.from).into_owned(),
+                        (false, false) => {
+                            format!("{} → {}", shown_text(&event.from), shown_text(&event.to))
+                        }
+                    };
+                    format!("{kind}  {}  {change}", event.subject)
+                }
+                TimelineKind::PublicSpeed(summary) => format!(
+                    "{}  ↓ {}{}",
+                    tr(language, "公网测速", "Internet speed test"),
+                    format_rate(model.region_format, summary.average_bytes_per_second),
+                    summary
+                        .upload_bytes_per_second
+                        .map(|upload| format!("  ↑ {}", format_rate(model.region_format, upload)))
+                        .unwrap_or_default(),
+                ),
+                TimelineKind::LanSpeed(summary) => format!(
+                    "{}  TX {}  RX {}",
+                    tr(language, "局域网测速", "LAN speed test"),
+                    format_rate(
+                        model.region_format,
+                        average_bytes_per_second(summary.tx_bytes, summary.elapsed_ms)
+                    ),
+                    format_rate(
+                        model.region_format,
+                        average_bytes_per_second(summary.rx_bytes, summary.elapsed_ms)
+                    ),
+                ),
+            };
+            Line::from(vec![
+                Span::raw(format!("{}  ", model.display_time_of_day(&mark.at))),
+                glyph,
+                Span::raw(format!(" {detail}")),
+            ])
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        Paragraph::new(if window.is_empty() {
+            vec![Line::styled(
+                tr(
+                    language,
+                    "这段时间内没有 Wi-Fi、网卡或测速事件。",
+                    "No Wi-Fi, interface or speed test events in this window.",
+                ),
+                Style::default().fg(MUTED),
+            )]
+        } else {
+            window
+        }),
+        rows[4],
+    );
+    frame.render_widget(
+        Paragraph::new(tr(
+            language,
+            "←/→ 缩放时间窗口   Esc 关闭",
+            "←/→ to zoom   Esc to dismiss",
+        )),
+        rows[5],
+    );
 }
 
-fn link_grade_color(grade: LinkQualityGrade) -> Color {
-    match grade {
-        LinkQualityGrade::Excellent => Color::Green,
-        LinkQualityGrade::Good => Color::Cyan,
-        LinkQualityGrade::Fair => Color::Yellow,
-        LinkQualityGrade::Poor => Color::Red,
+fn timeline_glyph(kind: &TimelineKind) -> Span<'static> {
+    match kind {
+        TimelineKind::Network(event) => {
+            let (_, color) = network_event_label(event.kind, Language::En);
+            let glyph = match event.kind {
+                NetworkEventKind::InterfaceAdded | NetworkEventKind::InterfaceUp => "▲",
+                NetworkEventKind::InterfaceRemoved | NetworkEventKind::InterfaceDown => "▼",
+                NetworkEventKind::WifiRoamed => "⇄",
+                _ => "◆",
+            };
+            Span::styled(glyph, Style::default().fg(color))
+        }
+        TimelineKind::PublicSpeed(_) | TimelineKind::LanSpeed(_) => {
+            Span::styled("●", Style::default().fg(PRIMARY))
+        }
     }
 }
 
-fn link_score_color(score: f64) -> Color {
-    if score >= 85.0 {
-        Color::Green
-    } else if score >= 70.0 {
-        Color::Cyan
-    } else if score >= 50.0 {
-        Color::Yellow
+/// Preset picker, or the report of the running or last preset.
+fn render_pipeline(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 80, 70);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let pipeline = &model.pipeline;
+    let mut lines = Vec::new();
+    if pipeline.picking {
+        for (index, preset) in PipelinePreset::ALL.into_iter().enumerate() {
+            let marker = if index == pipeline.selected { ">" } else { " " };
+            let name = pipeline_preset_label(language, preset);
+            let stages = preset
+                .stages()
+                .iter()
+                .map(|&stage| pipeline_stage_label(language, stage))
+                .collect::<Vec<_>>()
+                .join(" → ");
+            let padding = 16_usize.saturating_sub(name.width()).max(1);
+            lines.push(format!("{marker} {name}{}{stages}", " ".repeat(padding)));
+        }
+        lines.push(String::new());
+        lines.push(
+            tr(
+                language,
+                "目标取自当前诊断工具（路由跟踪、端口扫描），否则为 Ping 目标。",
+                "The target is that of the selected trace or port scan, otherwise the ping target.",
+            )
+            .to_string(),
+        );
+        lines.push(String::new());
+        lines.push(
+            tr(
+                language,
+                "Enter 运行   Esc 关闭",
+                "Enter to run   Esc to dismiss",
+            )
+            .to_string(),
+        );
     } else {
-        Color::Red
+        lines.extend(pipeline_report_lines(language, pipeline));
+        lines.push(String::new());
+        lines.push(
+            if pipeline.running() {
+                tr(
+                    language,
+                    "Space 停止   Enter / Esc 关闭（继续在后台运行）",
+                    "Space to stop   Enter / Esc to dismiss (keeps running)",
+                )
+            } else {
+                tr(
+                    language,
+                    "R 再次运行   Enter / Esc 关闭",
+                    "R to run again   Enter / Esc to dismiss",
+                )
+            }
+            .to_string(),
+        );
     }
+    let failed = pipeline
+        .stages
+        .iter()
+        .any(|report| matches!(report.status, StageStatus::Failed(_)));
+    let color = if !pipeline.picking && failed {
+        Color::Yellow
+    } else {
+        PRIMARY
+    };
+    frame.render_widget(
+        Paragraph::new(lines.join("\n"))
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 诊断预设 ", " Presets "))
+                    .border_style(Style::default().fg(color)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
 }
 
-fn link_dimension(kind: LinkQualityDimensionKind, language: Language) -> &'static str {
-    match kind {
-        LinkQualityDimensionKind::Loss => tr(language, "丢包", "Loss"),
-        LinkQualityDimensionKind::Latency => tr(language, "延迟", "Latency"),
-        LinkQualityDimensionKind::Jitter => tr(language, "抖动", "Jitter"),
-        LinkQualityDimensionKind::Signal => tr(language, "信号", "Signal"),
-        LinkQualityDimensionKind::Rate => tr(language, "速率", "Rate"),
-        LinkQualityDimensionKind::Phy => tr(language, "制式", "PHY"),
+/// Failures collected from every tool, newest first.
+fn render_problems(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 80, 70);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let problems = &model.problems;
+    let mut lines = Vec::new();
+    if problems.items.is_empty() {
+        lines.push(tr(language, "没有问题。", "No problems.").to_string());
     }
+    for (index, problem) in problems.items.iter().enumerate() {
+        let marker = if index == problems.selected { ">" } else { " " };
+        let severity = match problem.severity {
+            Severity::Warning => tr(language, "警告", "WARN "),
+            Severity::Error => tr(language, "错误", "ERROR"),
+        };
+        let source = problem_source_label(problem.tool, language);
+        let padding = 12_usize.saturating_sub(source.width()).max(1);
+        let repeats = if problem.count > 1 {
+            format!(" ×{}", problem.count)
+        } else {
+            String::new()
+        };
+        lines.push(format!(
+            "{marker} {} {severity} {source}{}{}{repeats}",
+            model.display_time_of_day(&problem.at),
+            " ".repeat(padding),
+            shown_text(&problem.message)
+        ));
+    }
+    lines.push(String::new());
+    lines.push(
+        tr(
+            language,
+            "Del 清空   Enter / Esc 关闭；详细信息见日志。",
+            "Del to clear   Enter / Esc to dismiss; details are in the log.",
+        )
+        .to_string(),
+    );
+    let color = if problems
+        .items
+        .iter()
+        .any(|problem| problem.severity == Severity::Error)
+    {
+        Color::Red
+    } else {
+        Color::Yellow
+    };
+    frame.render_widget(
+        Paragraph::new(lines.join("\n"))
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 问题 ", " Problems "))
+                    .border_style(Style::default().fg(color)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
 }
 
-fn score_bar(score: f64, width: usize) -> String {
-    let filled = ((score / 100.0) * width as f64).round() as usize;
-    let filled = filled.min(width);
-    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
-}
-
-fn diagnostic_target_index(tool: DiagnosticTool) -> usize {
+fn problem_source_label(tool: ToolKind, language: Language) -> Cow<'static, str> {
     match tool {
-        DiagnosticTool::LinkQuality => 1,
-        DiagnosticTool::LanSpeed => 4,
-        _ => 0,
+        ToolKind::Dashboard => page_label(Page::Dashboard, language),
+        ToolKind::Adapters | ToolKind::AdapterEdit => page_label(Page::Adapters, language),
+        ToolKind::Traffic => page_label(Page::Traffic, language),
+        ToolKind::Scanner => page_label(Page::Scanner, language),
+        ToolKind::Update => tr(language, "更新检查", "Update check"),
+        ToolKind::Doctor => tr(language, "自我诊断", "Self-check"),
+        ToolKind::WebCheck => tr(language, "诊断预设", "Presets"),
+        ToolKind::ChartExport => tr(language, "图表导出", "Chart export"),
+        ToolKind::Dhcp => tr(language, "DHCP 探测", "DHCP probe"),
+        ToolKind::AuditExport => tr(language, "审计导出", "Audit export"),
+        ToolKind::Asn => tr(language, "ASN 查询", "ASN lookup"),
+        ToolKind::Cve => tr(language, "CVE 查询", "CVE lookup"),
+        ToolKind::Watch => tr(language, "监视目标", "Watch targets"),
+        tool => DiagnosticTool::ALL
+            .into_iter()
+            .find(|diagnostic| ToolKind::from(*diagnostic) == tool)
+            .map_or("".into(), |diagnostic| tool_label(diagnostic, language)),
     }
 }
 
-fn diagnostic_failure(
-    common: &iptools_core::DiagnosticCommonState,
-    language: Language,
-) -> Option<String> {
-    let TaskStatus::Failed(status_message) = &common.status else {
-        return None;
+/// A heading with the preset and target, one line per stage and an indented
+/// line per web port.
+pub fn pipeline_report_lines(language: Language, pipeline: &PipelineState) -> Vec<String> {
+    let Some(preset) = pipeline.preset else {
+        return Vec::new();
     };
-    let label = match common.error.as_ref().map(|error| error.code) {
-        Some(RuntimeErrorCode::InvalidRequest) => tr(language, "参数无效", "Invalid request"),
-        Some(RuntimeErrorCode::ResolveTarget) => {
-            tr(language, "无法解析目标", "Target resolution failed")
+    let mut lines = vec![
+        format!(
+            "{} · {}",
+            pipeline_preset_label(language, preset),
+            pipeline.target
+        ),
+        String::new(),
+    ];
+    for report in &pipeline.stages {
+        let (status, detail) = match &report.status {
+            StageStatus::Pending => ("    ", tr(language, "等待", "waiting").to_string()),
+            StageStatus::Running => ("RUN ", tr(language, "运行中…", "running…").to_string()),
+            StageStatus::Skipped => ("SKIP", tr(language, "跳过", "skipped").to_string()),
+            StageStatus::Failed(message) => ("FAIL", message.clone()),
+            StageStatus::Done(outcome) => ("OK  ", stage_outcome_summary(language, outcome)),
+        };
+        let name = pipeline_stage_label(language, report.stage);
+        let padding = 12_usize.saturating_sub(name.width()).max(1);
+        lines.push(format!("[{status}] {name}{}{detail}", " ".repeat(padding)));
+        if let StageStatus::Done(StageOutcome::Web(results)) = &report.status {
+            lines.extend(
+                results.iter().map(|result| {
+                    format!("       {:<5} {}", result.port, web_result_detail(result))
+                }),
+            );
         }
-        Some(RuntimeErrorCode::PermissionDenied) => tr(language, "权限不足", "Permission denied"),
-        Some(RuntimeErrorCode::Timeout) => tr(language, "请求超时", "Request timed out"),
-        Some(RuntimeErrorCode::Network) => tr(language, "网络错误", "Network error"),
-        Some(RuntimeErrorCode::Cancelled) => tr(language, "任务已取消", "Task cancelled"),
-        Some(RuntimeErrorCode::Internal) => tr(language, "内部错误", "Internal error"),
-        None => tr(language, "执行失败", "Operation failed"),
-    };
-    let detail = common
-        .error
-        .as_ref()
-        .map(|error| error.message.as_str())
-        .unwrap_or(status_message);
-    if detail.is_empty() || detail.starts_with("diag_") {
-        Some(label.into())
-    } else {
-        Some(format!("{label}: {detail}"))
     }
+    lines
 }
 
-fn active_diagnostic_config_index(model: &AppModel) -> usize {
-    match model.diagnostics.tool {
-        DiagnosticTool::Ping => model.diagnostics.ping.config_selected,
-        DiagnosticTool::Trace => model.diagnostics.trace.config_selected,
-        DiagnosticTool::PortScan => model.diagnostics.port_scan.config_selected,
-        DiagnosticTool::LinkQuality => model.diagnostics.link_quality.config_selected,
-        DiagnosticTool::LanSpeed => model.diagnostics.lan_speed.config_selected,
-        DiagnosticTool::PublicSpeed => 0,
-    }
-}
-
-fn diagnostic_fields(model: &AppModel) -> Vec<(&'static str, String)> {
-    match model.diagnostics.tool {
-        DiagnosticTool::Ping => vec![
-            (
-                tr(model.language, "目标 IP/域名", "Target IP/Domain"),
-                model.diagnostics.ping.request.target.clone(),
-            ),
-            (
-                tr(model.language, "发包间隔 (ms)", "Interval (ms)"),
-                model.diagnostics.ping.request.interval_ms.to_string(),
-            ),
-            (
-                tr(model.language, "超时时间 (ms)", "Timeout (ms)"),
-                model.diagnostics.ping.request.timeout_ms.to_string(),
-            ),
-            (
-                tr(model.language, "包载荷大小", "Packet Size"),
-                model.diagnostics.ping.request.packet_size.to_string(),
-            ),
-        ],
-        DiagnosticTool::Trace => vec![
-            (
-                tr(model.language, "目标 IP/域名", "Target IP/Domain"),
-                model.diagnostics.trace.request.target.clone(),
-            ),
-            (
-                tr(model.language, "最大跳数", "Max hops"),
-                model.diagnostics.trace.max_hops_input.clone(),
-            ),
-            (
-                tr(model.language, "超时 (ms)", "Timeout (ms)"),
-                model.diagnostics.trace.timeout_input.clone(),
-            ),
-        ],
-        DiagnosticTool::PortScan => {
-            let state = &model.diagnostics.port_scan.persist;
-            vec![
-                (
-                    tr(model.language, "目标 IP/域名", "Target IP/Domain"),
-                    state.target.clone(),
-                ),
-                (
-                    tr(model.language, "起始端口", "Start port"),
-                    state.start_port.clone(),
-                ),
-                (
-                    tr(model.language, "结束端口", "End port"),
-                    state.end_port.clone(),
-                ),
-                (
-                    tr(model.language, "超时 (ms)", "Timeout (ms)"),
-                    state.timeout_ms.clone(),
-                ),
-            ]
+fn stage_outcome_summary(language: Language, outcome: &StageOutcome) -> String {
+    match outcome {
+        StageOutcome::Ping {
+            received: 0, sent, ..
+        } => match language {
+            Language::Zh => format!("0/{sent} 回复（目标或途中可能屏蔽 ICMP）"),
+            Language::En => format!("0/{sent} replies (ICMP may be filtered)"),
+        },
+        StageOutcome::Ping {
+            sent,
+            received,
+            average_ms,
+        } => {
+            let average = average_ms.unwrap_or_default();
+            match language {
+                Language::Zh => format!("{received}/{sent} 回复 · 平均 {average:.1} ms"),
+                Language::En => format!("{received}/{sent} replies · avg {average:.1} ms"),
+            }
         }
-        DiagnosticTool::PublicSpeed => vec![(
-            tr(model.language, "测速服务器", "Test Server"),
-            model
-                .diagnostics
-                .public_speed
-                .server
-                .clone()
-                .unwrap_or_else(|| tr(model.language, "自动选择", "Automatic").into()),
-        )],
-        DiagnosticTool::LinkQuality => {
-            let state = &model.diagnostics.link_quality;
-            let adapter = state
-                .adapters
-                .get(state.selected_adapter)
-                .map(|adapter| format!("{} ({})", adapter.name, adapter.ipv4))
-                .unwrap_or_else(|| tr(model.language, "无可用网卡", "No adapter").into());
-            vec![
-                (tr(model.language, "网卡", "Interface"), adapter),
-                (
-                    tr(model.language, "目标 IP/域名", "Target IP/Domain"),
-                    state.params.target.clone(),
-                ),
-                (
-                    tr(model.language, "探测次数", "Probe Count"),
-                    state.params.count.clone(),
-                ),
-                (
-                    tr(model.language, "间隔 (ms)", "Interval (ms)"),
-                    state.params.interval_ms.clone(),
-                ),
-                (
-                    tr(model.language, "超时 (ms)", "Timeout (ms)"),
-                    state.params.timeout_ms.clone(),
-                ),
-                (
-                    tr(model.language, "包大小 (字节)", "Packet Size (B)"),
-                    state.params.packet_size.clone(),
-                ),
-            ]
+        StageOutcome::Trace { hops, last } => match (language, last.as_deref().map(shown_ip)) {
+            (Language::Zh, Some(last)) => format!("{hops} 跳 · 最后应答 {last}"),
+            (Language::Zh, None) => format!("{hops} 跳 · 无应答"),
+            (Language::En, Some(last)) => format!("{hops} hops · last answer from {last}"),
+            (Language::En, None) => format!("{hops} hops · none answered"),
+        },
+        StageOutcome::PortScan { open } if open.is_empty() => {
+            tr(language, "无开放端口", "no open ports").to_string()
         }
-        DiagnosticTool::LanSpeed => {
-            let state = &model.diagnostics.lan_speed.persist;
-            let mut fields = vec![
-                (
-                    tr(model.language, "模式", "Mode"),
-                    tr(
-                        model.language,
-                        if state.mode == "client" {
-                            "客户端"
-                        } else {
-                            "服务端"
-                        },
-                        if state.mode == "client" {
-                            "Client"
-                        } else {
-                            "Server"
-                        },
-                    )
-                    .into(),
-                ),
-                (tr(model.language, "端口", "Port"), state.port.clone()),
-            ];
-            if state.mode == "client" {
-                fields.extend([
-                    (
-                        tr(model.language, "协议", "Protocol"),
-                        state.proto.to_uppercase(),
-                    ),
-                    (
-                        tr(model.language, "方向", "Direction"),
-                        match state.direction.as_str() {
-                            "down" => tr(model.language, "下载", "Download"),
-                            "bidir" => tr(model.language, "双向", "Bidirectional"),
-                            _ => tr(model.language, "上传", "Upload"),
-                        }
-                        .into(),
-                    ),
-                    (tr(model.language, "对端", "Peer"), state.peer.clone()),
-                    (
-                        tr(model.language, "时长", "Duration"),
-                        state.duration.clone(),
-                    ),
-                    (tr(model.language, "流数", "Streams"), state.streams.clone()),
-                    (tr(model.language, "载荷", "Payload"), state.payload.clone()),
-                ]);
-                if state.proto == "udp" {
-                    fields.push((
-                        tr(model.language, "限速 Mbps", "Rate Mbps"),
-                        state.rate.clone(),
-                    ));
-                }
+        StageOutcome::PortScan { open } => {
+            let mut ports = open
+                .iter()
+                .take(8)
+                .map(|port| format!("{} {}", port.port, port.service))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if open.len() > 8 {
+                ports.push_str(", …");
+            }
+            match language {
+                Language::Zh => format!("{} 个开放：{ports}", open.len()),
+                Language::En => format!("{} open: {ports}", open.len()),
+            }
+        }
+        StageOutcome::Web(results) => {
+            let answered = results
+                .iter()
+                .filter(|result| result.status.is_some())
+                .count();
+            match language {
+                Language::Zh => format!("{answered}/{} 个端口应答", results.len()),
+                Language::En => format!("{answered}/{} ports answered", results.len()),
             }
-            fields
         }
     }
 }
 
-fn render_settings(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
-    let language = match model.language {
-        Language::Zh => "简体中文",
-        Language::En => "English",
+/// `TLSv1_3 TLS13_AES_128_GCM_SHA256 · 200 · nginx · "Welcome" · 45 ms`, or
+/// the error.
+fn web_result_detail(result: &WebCheckResult) -> String {
+    let mut parts = Vec::new();
+    if let Some(tls) = &result.tls {
+        parts.push(format!("{} {}", tls.version, tls.cipher));
+    }
+    if let Some(status) = result.status {
+        parts.push(status.to_string());
+    }
+    if let Some(server) = &result.server {
+        parts.push(server.clone());
+    }
+    if let Some(title) = &result.title {
+        parts.push(format!("\"{title}\""));
+    }
+    if let Some(error) = &result.error {
+        parts.push(error.clone());
+    } else if let Some(elapsed) = result.elapsed_ms {
+        parts.push(format!("{elapsed} ms"));
+    }
+    parts.join(" · ")
+}
+
+fn pipeline_preset_label(language: Language, preset: PipelinePreset) -> Cow<'static, str> {
+    match preset {
+        PipelinePreset::FullHostCheck => tr(language, "完整主机检查", "Full host check"),
+        PipelinePreset::Reachability => tr(language, "可达性", "Reachability"),
+        PipelinePreset::WebService => tr(language, "网站服务", "Web service"),
+    }
+}
+
+fn pipeline_stage_label(language: Language, stage: PipelineStage) -> Cow<'static, str> {
+    match stage {
+        PipelineStage::Ping => "Ping".into(),
+        PipelineStage::Trace => tr(language, "路由跟踪", "Trace"),
+        PipelineStage::PortScan => tr(language, "端口扫描", "Port scan"),
+        PipelineStage::Web => tr(language, "网页检查", "Web check"),
+    }
+}
+
+/// Self-diagnostics report opened from the settings page.
+fn render_doctor(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 80, 70);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let mut lines = if model.doctor.running() {
+        vec![tr(language, "检查中…", "Checking…").to_string()]
+    } else {
+        doctor_report_lines(language, &model.doctor.checks)
     };
-    let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).split(area);
-    let list_inner = Block::bordered().inner(rows[0]);
-    for index in 0..4 {
-        ui.settings_regions.push((
-            Rect::new(list_inner.x, list_inner.y + index, list_inner.width, 1),
-            index as usize,
+    lines.push(String::new());
+    lines.push(
+        tr(
+            language,
+            "R 重新检查   Enter / Esc 关闭   命令行：iptools doctor",
+            "R to re-run   Enter / Esc to dismiss   From a shell: iptools doctor",
+        )
+        .to_string(),
+    );
+    let color = match doctor_status(&model.doctor.checks) {
+        DoctorStatus::Ok => PRIMARY,
+        DoctorStatus::Warn => Color::Yellow,
+        DoctorStatus::Fail => Color::Red,
+    };
+    frame.render_widget(
+        Paragraph::new(lines.join("\n"))
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 自我诊断 ", " Self-check "))
+                    .border_style(Style::default().fg(color)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// One line per check and an indented remediation under each that is not
+/// ok. Shared by the settings popup and `iptools doctor`.
+pub fn doctor_report_lines(language: Language, checks: &[DoctorCheck]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for check in checks {
+        let status = match check.status {
+            DoctorStatus::Ok => "OK  ",
+            DoctorStatus::Warn => "WARN",
+            DoctorStatus::Fail => "FAIL",
+        };
+        let name = doctor_check_label(language, check.kind);
+        let padding = 16_usize.saturating_sub(name.width()).max(1);
+        lines.push(format!(
+            "[{status}] {name}{}{}",
+            " ".repeat(padding),
+            check.detail
         ));
+        if check.status != DoctorStatus::Ok
+            && let Some(hint) = check.hint
+        {
+            lines.push(format!("       → {}", doctor_hint_label(language, hint)));
+        }
     }
-    let theme = match model.theme {
-        ThemeId::Classic => tr(model.language, "经典", "Classic"),
-        ThemeId::Nord => "Nord",
-        ThemeId::CatppuccinMocha => "Catppuccin Mocha",
-        ThemeId::Dracula => "Dracula",
-    };
-    let values = [
-        (
-            tr(model.language, "界面语言", "Language"),
-            language.to_string(),
+    lines
+}
+
+fn doctor_check_label(language: Language, kind: DoctorCheckKind) -> Cow<'static, str> {
+    match kind {
+        DoctorCheckKind::Privileges => tr(language, "运行权限", "Privileges"),
+        DoctorCheckKind::Icmp => tr(language, "ICMP", "ICMP"),
+        DoctorCheckKind::PacketCapture => tr(language, "抓包库", "Packet capture"),
+        DoctorCheckKind::Firewall => tr(language, "本机防火墙", "Firewall"),
+        DoctorCheckKind::Config => tr(language, "配置文件", "Configuration"),
+        DoctorCheckKind::Connectivity => tr(language, "外网连通", "Connectivity"),
+    }
+}
+
+fn doctor_hint_label(language: Language, hint: DoctorHint) -> Cow<'static, str> {
+    match hint {
+        DoctorHint::GrantRawSockets => tr(
+            language,
+            "授予原始套接字权限：sudo setcap cap_net_raw+ep $(command -v iptools)，或用 sudo 运行",
+            "Grant raw sockets: sudo setcap cap_net_raw+ep $(command -v iptools), or run with sudo",
         ),
-        (
-            tr(model.language, "扫描并发数", "Scan concurrency"),
-            model.scan_concurrency.to_string(),
+        DoctorHint::RunAsAdministrator => tr(
+            language,
+            "修改网卡配置需要管理员权限：Windows 以管理员身份运行终端，其他系统用 sudo",
+            "Adapter changes need admin rights: run the terminal as administrator on Windows, use sudo elsewhere",
         ),
-        (
-            tr(model.language, "配色方案", "Color theme"),
-            theme.to_string(),
+        DoctorHint::InstallLibpcap => tr(
+            language,
+            "安装 libpcap（如 apt install libpcap0.8 / dnf install libpcap）",
+            "Install libpcap (e.g. apt install libpcap0.8 / dnf install libpcap)",
         ),
-        (
-            tr(
-                model.language,
-                "清空参数记忆",
-                "Reset remembered parameters",
-            ),
-            if model.settings_just_reset {
-                tr(model.language, "已清空 ✓", "Cleared ✓").to_string()
-            } else {
-                tr(model.language, "回车清空", "Press Enter to clear").to_string()
-            },
+        DoctorHint::InstallNpcap => tr(
+            language,
+            "从 https://npcap.com 安装 Npcap",
+            "Install Npcap from https://npcap.com",
         ),
-    ];
-    let items = values
-        .into_iter()
-        .enumerate()
-        .map(|(index, (label, value))| {
-            let selected = index == model.settings_selected;
-            ListItem::new(Line::from(vec![
-                Span::styled(
-                    if selected { "> " } else { "  " },
-                    Style::default().fg(PRIMARY),
-                ),
-                Span::styled(pad_display(label, 20), Style::default().fg(MUTED)),
-                Span::raw(" : "),
-                Span::styled(
-                    value,
-                    Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD),
-                ),
-            ]))
-            .style(if selected {
-                Style::default().bg(SELECTED)
-            } else {
-                Style::default()
-            })
-        });
-    frame.render_widget(
-        List::new(items).block(Block::bordered().title(tr(
-            model.language,
-            " 全局设置 ",
-            " Global Settings ",
-        ))),
-        rows[0],
+        DoctorHint::AllowThroughFirewall => tr(
+            language,
+            "在防火墙中放行 iptools（局域网测速服务端与组播需要入站连接）",
+            "Allow iptools through the firewall (the LAN speed server and multicast need inbound traffic)",
+        ),
+        DoctorHint::FreeLanSpeedPort => tr(
+            language,
+            "端口已被占用：关闭占用它的程序，或在局域网测速中换一个端口",
+            "The port is taken: close the program using it or pick another port in LAN speed",
+        ),
+        DoctorHint::FixConfig => tr(
+            language,
+            "按启动时弹窗的说明修正配置文件，或在设置页「恢复默认设置」",
+            "Fix the file as the startup popup describes, or use Settings › Restore defaults",
+        ),
+        DoctorHint::CheckDns => tr(
+            language,
+            "域名解析失败：检查 DNS 服务器设置，或在诊断页运行 DNS 测速",
+            "Names do not resolve: check the DNS servers, or run the DNS benchmark",
+        ),
+        DoctorHint::CheckUplink => tr(
+            language,
+            "无法访问外网：检查网线 / Wi-Fi、代理与 VPN",
+            "Nothing outside answers: check the cable or Wi-Fi, proxy and VPN",
+        ),
+    }
+}
+
+/// Release notes of the newer version found by the update check.
+fn render_update(frame: &mut Frame, model: &AppModel) {
+    let Some(release) = &model.update.available else {
+        return;
+    };
+    let area = centered(frame.area(), 66, 60);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let mut lines = vec![match language {
+        Language::Zh => format!(
+            "iptools {} 已发布（当前 {CURRENT_VERSION}）",
+            release.version
+        ),
+        Language::En => format!(
+            "iptools {} is available (this is {CURRENT_VERSION})",
+            release.version
+        ),
+    }];
+    lines.push(String::new());
+    lines.extend(release_notes_summary(&release.notes, RELEASE_NOTES_LINES));
+    lines.push(String::new());
+    lines.push(format!(
+        "{} {}",
+        tr(language, "下载：", "Download:"),
+        release.url
+    ));
+    lines.push(String::new());
+    lines.push(
+        tr(
+            language,
+            "Enter / Esc 关闭；可在设置页关闭更新检查。",
+            "Enter / Esc to dismiss; turn the check off on the Settings page.",
+        )
+        .to_string(),
     );
     frame.render_widget(
-        Paragraph::new(tr(
-            model.language,
-            "[←/→] 修改值   [回车] 执行   [↑/↓] 选择配置项；清空不会改变当前页面。",
-            "[←/→] Change value   [Enter] Apply   [↑/↓] Select item; reset keeps this page.",
-        ))
-        .block(Block::bordered())
-        .style(Style::default().fg(Color::Yellow))
-        .alignment(Alignment::Center),
-        rows[1],
+        Paragraph::new(lines.join("\n"))
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 有新版本 ", " Update Available "))
+                    .border_style(Style::default().fg(SECONDARY)),
+            )
+            .wrap(Wrap { trim: true }),
+        area,
     );
 }
 
-fn render_footer(frame: &mut Frame, area: Rect, model: &AppModel, ui: &mut UiState) {
-    let next = binding(model, "next_tab", "Tab");
-    let previous = binding(model, "prev_tab", "Shift+Tab");
-    let language = binding(model, "toggle_language", "Ctrl+L");
-    let help = binding(model, "help", "F1");
-    let quit = binding(model, "quit", "Ctrl+C");
-    let buttons = match model.language {
-        Language::Zh => vec![
-            (format!("[{next}/{previous}] 切换菜单"), Action::NextPage),
-            (format!("[{language}] Language"), Action::ToggleLanguage),
-            (format!("[{help}] 帮助"), Action::Help),
-            (format!("[{quit}] 退出"), Action::Quit),
-        ],
-        Language::En => vec![
-            (format!("[{next}/{previous}] Switch"), Action::NextPage),
-            (format!("[{language}] 切换语言"), Action::ToggleLanguage),
-            (format!("[{help}] Help"), Action::Help),
-            (format!("[{quit}] Quit"), Action::Quit),
-        ],
+/// Startup popup listing what was wrong with the config file and what was done
+/// about it. Input is held by the model until it is dismissed.
+/// The open question above everything else, with "No" selected until the
+/// user moves to "Yes".
+fn render_confirmation(
+    frame: &mut Frame,
+    model: &AppModel,
+    confirmation: &Confirmation,
+    ui: &mut UiState,
+) {
+    // A typed answer needs room for the reason and the input line.
+    let height = if confirmation.confirm.phrase().is_some() {
+        45
+    } else {
+        30
     };
-    let mut spans = Vec::new();
-    let mut x = area.x;
-    for (label, action) in buttons {
-        let text = format!(" {label} ");
-        let width = text.width().min(u16::MAX as usize) as u16;
-        if x < area.right() {
-            ui.footer_regions
-                .push((Rect::new(x, area.y, width.min(area.right() - x), 1), action));
-        }
-        spans.push(Span::styled(
-            text,
-            Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD),
+    let area = centered(frame.area(), 60, height);
+    frame.render_widget(Clear, area);
+    let language = model.language;
+    let block = Block::bordered()
+        .title(tr(language, " 确认 ", " Confirm "))
+        .border_style(Style::default().fg(Color::Red));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let mut text = confirm_question(language, &confirmation.confirm);
+    text.push_str("\n\n");
+    if confirmation.confirm.phrase().is_some() {
+        text.push_str(&format!("> {}_\n\n", confirmation.typed));
+        text.push_str(&tr(
+            language,
+            "输入一致后 Enter 确定   Esc 取消",
+            "Enter once it matches   Esc to cancel",
+        ));
+    } else {
+        text.push_str(&tr(
+            language,
+            "←/→ 选择   Enter 确定   Y 是   N / Esc 否",
+            "←/→ to choose   Enter to answer   Y yes   N / Esc no",
         ));
-        x = x.saturating_add(width);
     }
-    frame.render_widget(Paragraph::new(Line::from(spans)), area);
-}
+    frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), rows[0]);
 
-fn visible_range(
-    total: usize,
-    selected: usize,
-    capacity: usize,
-    offset: &mut usize,
-) -> std::ops::Range<usize> {
-    if total == 0 || capacity == 0 {
-        *offset = 0;
-        return 0..0;
+    let buttons = [
+        (true, format!("[ {} ]", tr(language, "是", "Yes"))),
+        (false, format!("[ {} ]", tr(language, "否", "No"))),
+    ];
+    let width = buttons
+        .iter()
+        .map(|(_, label)| label.width() as u16)
+        .sum::<u16>()
+        + 3;
+    let mut x = rows[1].x + rows[1].width.saturating_sub(width) / 2;
+    for (yes, label) in buttons {
+        let button = Rect::new(x, rows[1].y, label.width() as u16, 1).intersection(rows[1]);
+        let style = if yes == confirmation.yes {
+            Style::default().bg(SELECTED).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        frame.render_widget(Paragraph::new(label).style(style), button);
+        // Checked before the regions of the page underneath.
+        ui.overlay_regions.insert(0, (button, Action::Answer(yes)));
+        x = button.right() + 3;
     }
-    let capacity = capacity.min(total);
-    let selected = selected.min(total - 1);
-    *offset = (*offset).min(total - capacity);
-    if selected < *offset {
-        *offset = selected;
-    } else if selected >= *offset + capacity {
-        *offset = selected + 1 - capacity;
+}
+
+fn confirm_question(language: Language, confirm: &Confirm) -> String {
+    match (confirm, language) {
+        (Confirm::ClearSession, Language::Zh) => {
+            "清空记住的参数、目标历史和扫描状态？设备备注会保留。".to_string()
+        }
+        (Confirm::ClearSession, Language::En) => {
+            "Forget remembered parameters, target history and scanner state? Device notes are kept."
+                .to_string()
+        }
+        (Confirm::RestoreDefaults, Language::Zh) => {
+            "把所有设置恢复为默认值？界面语言会保留。".to_string()
+        }
+        (Confirm::RestoreDefaults, Language::En) => {
+            "Put every setting back to its default? The language is kept.".to_string()
+        }
+        (
+            Confirm::DeleteUpnpMapping {
+                protocol,
+                external_port,
+            },
+            Language::Zh,
+        ) => format!(
+            "从路由器删除 {} {external_port} 端口映射？",
+            protocol_label(*protocol)
+        ),
+        (
+            Confirm::DeleteUpnpMapping {
+                protocol,
+                external_port,
+            },
+            Language::En,
+        ) => format!(
+            "Delete the {} {external_port} port mapping from the router?",
+            protocol_label(*protocol)
+        ),
+        (
+            Confirm::ScanTarget {
+                scan,
+                target,
+                concern,
+            },
+            Language::Zh,
+        ) => {
+            let action = match scan {
+                GuardedScan::LanScan => "扫描",
+                GuardedScan::PortScan => "端口扫描",
+                GuardedScan::Pipeline(_) => "对以下目标运行含端口扫描的预设：",
+            };
+            let reason = match concern {
+                ScanConcern::Public => {
+                    "目标不在私有、链路本地或本机地址范围内，可能属于他人；只扫描你有权扫描的网络。"
+                        .to_string()
+                }
+                ScanConcern::Large { hosts } => format!(
+                    "目标包含 {hosts} 个地址，超过 {SCAN_GUARD_MAX_HOSTS} 个的上限，扫描耗时长、流量大。"
+                ),
+            };
+            format!("{action} {target}？\n{reason}\n输入目标以确认。")
+        }
+        (
+            Confirm::ScanTarget {
+                scan,
+                target,
+                concern,
+            },
+            Language::En,
+        ) => {
+            let action = match scan {
+                GuardedScan::LanScan => "Scan",
+                GuardedScan::PortScan => "Port-scan",
+                GuardedScan::Pipeline(_) => "Run a preset that port-scans",
+            };
+            let reason = match concern {
+                ScanConcern::Public => "It is outside the private, link-local and loopback ranges and may belong to someone else; only scan networks you are allowed to.".to_string(),
+                ScanConcern::Large { hosts } => format!(
+                    "It covers {hosts} addresses, more than the limit of {SCAN_GUARD_MAX_HOSTS}; the scan takes long and sends a lot of traffic."
+                ),
+            };
+            format!("{action} {target}?\n{reason}\nType the target to confirm.")
+        }
     }
-    *offset..(*offset + capacity)
 }
 
-fn render_help(frame: &mut Frame, model: &AppModel) {
-    let area = centered(frame.area(), 66, 72);
+fn render_config_issues(frame: &mut Frame, model: &AppModel) {
+    let area = centered(frame.area(), 72, 60);
     frame.render_widget(Clear, area);
-    let next = binding(model, "next_tab", "Tab");
-    let previous = binding(model, "prev_tab", "Shift+Tab");
-    let up = binding(model, "up", "Up");
-    let down = binding(model, "down", "Down");
-    let left = binding(model, "left", "Left");
-    let right = binding(model, "right", "Right");
-    let confirm = binding(model, "confirm", "Enter");
-    let toggle = binding(model, "toggle", "Space");
-    let edit = binding(model, "edit", "E");
-    let language_key = binding(model, "toggle_language", "Ctrl+L");
-    let help = binding(model, "help", "F1");
-    let back = binding(model, "back", "Esc");
-    let detail = match model.language {
-        Language::Zh => format!(
-            "键盘与触控快捷键\n\n{next} / {previous}  切换页面\n{up}/{down}/{left}/{right}  导航\n{confirm} / {toggle}     开始或停止\n{edit}                 编辑\n{language_key}            切换语言\n{help} / {back}          打开或关闭帮助\n\n{}",
-            if model.demo {
-                "演示版本使用确定性模拟数据。"
-            } else {
-                "原生版快捷键从 config.json 加载。"
-            }
+    let language = model.language;
+    let mut lines = model
+        .config_issues
+        .iter()
+        .map(|issue| format!("• {}", config_issue_label(language, issue)))
+        .collect::<Vec<_>>();
+    lines.push(String::new());
+    lines.push(
+        tr(
+            language,
+            "Enter / Esc 关闭；修正配置文件后重启生效。",
+            "Enter / Esc to dismiss; fix the config file and restart to apply.",
+        )
+        .to_string(),
+    );
+    frame.render_widget(
+        Paragraph::new(lines.join("\n"))
+            .block(
+                Block::bordered()
+                    .title(tr(language, " 配置文件问题 ", " Configuration Problems "))
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+fn config_issue_label(language: Language, issue: &ConfigIssue) -> String {
+    let backup = |backup: &Option<String>| match (backup, language) {
+        (Some(path), Language::Zh) => format!("原文件已备份到 {path}。"),
+        (Some(path), Language::En) => format!("The original was backed up to {path}."),
+        (None, Language::Zh) => "备份原文件失败。".to_string(),
+        (None, Language::En) => "The original could not be backed up.".to_string(),
+    };
+    match (issue, language) {
+        (
+            ConfigIssue::Malformed {
+                error,
+                backup: path,
+            },
+            Language::Zh,
+        ) => {
+            format!(
+                "无法解析配置文件（{error}），已改用默认设置。{}",
+                backup(path)
+            )
+        }
+        (
+            ConfigIssue::Malformed {
+                error,
+                backup: path,
+            },
+            Language::En,
+        ) => format!(
+            "The config file could not be parsed ({error}); defaults are in use. {}",
+            backup(path)
         ),
-        Language::En => format!(
-            "Keyboard and touch shortcuts\n\n{next} / {previous}  switch pages\n{up}/{down}/{left}/{right}  navigate\n{confirm} / {toggle}     start or stop\n{edit}                 edit\n{language_key}            toggle language\n{help} / {back}          open or close help\n\n{}",
-            if model.demo {
-                "The demo uses deterministic simulated data."
-            } else {
-                "Native bindings are loaded from config.json."
-            }
+        (
+            ConfigIssue::NewerVersion {
+                found,
+                backup: path,
+            },
+            Language::Zh,
+        ) => format!(
+            "配置文件来自更新的版本（v{found}），本版本不认识的设置将丢失。{}",
+            backup(path)
+        ),
+        (
+            ConfigIssue::NewerVersion {
+                found,
+                backup: path,
+            },
+            Language::En,
+        ) => format!(
+            "The config file was written by a newer version (v{found}); settings this version does not know will be lost. {}",
+            backup(path)
+        ),
+        (ConfigIssue::ScanConcurrency { found }, Language::Zh) => {
+            format!("scan_concurrency = {found} 超出 10–500，已调整到范围内。")
+        }
+        (ConfigIssue::ScanConcurrency { found }, Language::En) => {
+            format!("scan_concurrency = {found} is outside 10–500 and was clamped.")
+        }
+        (ConfigIssue::Retention { key, found }, Language::Zh) => {
+            format!("retention.{key} = {found} 超出允许范围，已调整到范围内。")
+        }
+        (ConfigIssue::Retention { key, found }, Language::En) => {
+            format!("retention.{key} = {found} is out of range and was clamped.")
+        }
+        (ConfigIssue::DropAlert { found }, Language::Zh) => {
+            format!("drop_alert_percent = {found} 超出 0–20，已调整到范围内。")
+        }
+        (ConfigIssue::DropAlert { found }, Language::En) => {
+            format!("drop_alert_percent = {found} is outside 0–20 and was clamped.")
+        }
+        (ConfigIssue::PublicIpEndpoint { url, kind }, Language::Zh) => {
+            format!(
+                "公网 IP 接口 {url}（{kind}）无效，已忽略；格式须为 ipsb、ipinfo 或 plaintext，地址须为 http(s)。"
+            )
+        }
+        (ConfigIssue::PublicIpEndpoint { url, kind }, Language::En) => format!(
+            "Public IP endpoint {url} ({kind}) is invalid and was skipped; the kind must be ipsb, ipinfo or plaintext and the URL http(s)."
         ),
-    };
-    frame.render_widget(
-        Paragraph::new(detail)
-            .block(Block::bordered().title(tr(model.language, " 帮助 ", " Help ")))
-            .alignment(Alignment::Left)
-            .wrap(Wrap { trim: true }),
-        area,
-    );
+        (ConfigIssue::Keybinding { action, key }, Language::Zh) if key.is_empty() => {
+            format!("快捷键配置中的未知动作 \"{action}\" 已忽略。")
+        }
+        (ConfigIssue::Keybinding { action, key }, Language::En) if key.is_empty() => {
+            format!("Unknown action \"{action}\" in keybindings was ignored.")
+        }
+        (ConfigIssue::Keybinding { action, key }, Language::Zh) => {
+            format!("无法识别 {action} 的按键 \"{key}\"，保留默认绑定。")
+        }
+        (ConfigIssue::Keybinding { action, key }, Language::En) => {
+            format!("Key \"{key}\" for {action} is not recognised; the default binding stays.")
+        }
+        (ConfigIssue::Tab { name }, Language::Zh) => {
+            format!("标签页列表中的未知页面 \"{name}\" 已忽略。")
+        }
+        (ConfigIssue::Tab { name }, Language::En) => {
+            format!("Unknown page \"{name}\" in tabs was ignored.")
+        }
+        (ConfigIssue::PolicyFeature { name }, Language::Zh) => {
+            format!("策略中的未知功能 \"{name}\" 已忽略，该项不会被关闭。")
+        }
+        (ConfigIssue::PolicyFeature { name }, Language::En) => {
+            format!(
+                "Unknown feature \"{name}\" in policy.disabled was ignored; nothing was turned off for it."
+            )
+        }
+        (ConfigIssue::WatchTarget { target }, Language::Zh) => {
+            format!(
+                "监视目标 \"{target}\" 无效、延迟预算为 0、维护时段无法识别或超出 8 个上限，已忽略。"
+            )
+        }
+        (ConfigIssue::WatchTarget { target }, Language::En) => {
+            format!(
+                "Watch target \"{target}\" is invalid, has a zero latency budget or an unreadable maintenance window, or is past the limit of 8 and was skipped."
+            )
+        }
+        (ConfigIssue::JumpHost { found }, Language::Zh) => {
+            format!("SSH 跳板机 \"{found}\" 格式无效（应为 [用户@]主机[:端口]），已关闭跳板。")
+        }
+        (ConfigIssue::JumpHost { found }, Language::En) => {
+            format!(
+                "SSH jump host \"{found}\" is not [user@]host[:port]; the jump host was turned off."
+            )
+        }
+    }
 }
 
 fn binding<'a>(model: &'a AppModel, name: &str, fallback: &'a str) -> &'a str {
@@ -2911,48 +9316,121 @@ fn contains(area: Rect, x: u16, y: u16) -> bool {
     x >= area.x && x < area.right() && y >= area.y && y < area.bottom()
 }
 
-fn tr<'a>(language: Language, zh: &'a str, en: &'a str) -> &'a str {
-    match language {
+fn tr<'a>(language: Language, zh: &'a str, en: &'a str) -> Cow<'a, str> {
+    if OVERRIDES_ACTIVE.get()
+        && let Some(text) = TEXT_OVERRIDES.with_borrow_mut(|overrides| {
+            if let Some(recorded) = &mut overrides.recorded {
+                recorded.insert(en.to_string());
+            }
+            overrides.get(language, en)
+        })
+    {
+        return Cow::Owned(text);
+    }
+    Cow::Borrowed(match language {
         Language::Zh => zh,
         Language::En => en,
-    }
+    })
+}
+
+thread_local! {
+    /// The user's locale file as of the frame being drawn; `tr` looks text
+    /// up here so the override reaches every call without passing the model.
+    static TEXT_OVERRIDES: std::cell::RefCell<TextOverrides> = Default::default();
+    /// Whether `tr` has anything to look up or record, so the common case of
+    /// no locale file skips the table entirely.
+    static OVERRIDES_ACTIVE: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// The locale file `tr` reads, replaced whole whenever the file changes.
+#[derive(Default)]
+struct TextOverrides {
+    source: iptools_core::LocaleOverrides,
+    /// English text of every `tr` call while [`record_interface_text`] runs.
+    recorded: Option<std::collections::BTreeSet<String>>,
 }
 
-fn page_label(page: Page, language: Language) -> &'static str {
-    match (page, language) {
-        (Page::Dashboard, Language::Zh) => "概览",
-        (Page::Adapters, Language::Zh) => "适配器",
-        (Page::Scanner, Language::Zh) => "扫描",
-        (Page::Traffic, Language::Zh) => "流量",
-        (Page::Diagnostics, Language::Zh) => "诊断",
-        (Page::Settings, Language::Zh) => "设置",
-        (Page::Dashboard, Language::En) => "Dashboard",
-        (Page::Adapters, Language::En) => "Adapters",
-        (Page::Scanner, Language::En) => "Scanner",
-        (Page::Traffic, Language::En) => "Traffic",
-        (Page::Diagnostics, Language::En) => "Diagnostics",
-        (Page::Settings, Language::En) => "Settings",
+impl TextOverrides {
+    fn get(&self, language: Language, english: &str) -> Option<String> {
+        self.source.get(language, english).map(str::to_owned)
+    }
+
+    fn install(&mut self, locale: &iptools_core::LocaleOverrides) {
+        if self.source != *locale {
+            self.source = locale.clone();
+        }
+        self.refresh_active();
+    }
+
+    fn refresh_active(&self) {
+        OVERRIDES_ACTIVE.set(!self.source.is_empty() || self.recorded.is_some());
     }
 }
 
-fn tool_label(tool: DiagnosticTool, language: Language) -> &'static str {
-    match (tool, language) {
-        (DiagnosticTool::Ping, Language::Zh) => "多功能 Ping",
-        (DiagnosticTool::Ping, Language::En) => "Advanced Ping",
-        (DiagnosticTool::Trace, Language::Zh) => "路由跟踪",
-        (DiagnosticTool::PortScan, Language::Zh) => "端口扫描",
-        (DiagnosticTool::PublicSpeed, Language::Zh) => "公网测速",
-        (DiagnosticTool::LinkQuality, Language::Zh) => "链路质量",
-        (DiagnosticTool::LanSpeed, Language::Zh) => "内网测速",
-        (DiagnosticTool::Trace, Language::En) => "Trace Route",
-        (DiagnosticTool::PortScan, Language::En) => "Port Scan",
-        (DiagnosticTool::PublicSpeed, Language::En) => "Public Speed",
-        (DiagnosticTool::LinkQuality, Language::En) => "Link Quality",
-        (DiagnosticTool::LanSpeed, Language::En) => "LAN Speed",
+thread_local! {
+    /// Presenter mode as of the frame being drawn. Identifying values pass
+    /// through the `shown_*` helpers below as they are formatted, so the
+    /// model itself is never copied to mask them.
+    static REDACT: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+fn masked(value: &str, redact: fn(&str) -> String) -> Cow<'_, str> {
+    if REDACT.get() {
+        Cow::Owned(redact(value))
+    } else {
+        Cow::Borrowed(value)
     }
 }
 
-fn task_label(status: &TaskStatus, language: Language) -> &'static str {
+fn shown_ip(value: &str) -> Cow<'_, str> {
+    masked(value, iptools_core::redact_ip)
+}
+
+fn shown_mac(value: &str) -> Cow<'_, str> {
+    masked(value, iptools_core::redact_mac)
+}
+
+fn shown_ssid(value: &str) -> Cow<'_, str> {
+    masked(value, iptools_core::redact_ssid)
+}
+
+fn shown_host(value: &str) -> Cow<'_, str> {
+    masked(value, iptools_core::redact_hostname)
+}
+
+fn shown_text(value: &str) -> Cow<'_, str> {
+    masked(value, iptools_core::redact_text)
+}
+
+/// Run `draw` and return, with its result, the English text of every string
+/// it drew through the translation table: the keys a locale file can use.
+pub fn record_interface_text<R>(
+    draw: impl FnOnce() -> R,
+) -> (R, std::collections::BTreeSet<String>) {
+    TEXT_OVERRIDES.with_borrow_mut(|overrides| {
+        overrides.recorded = Some(Default::default());
+        overrides.refresh_active();
+    });
+    let result = draw();
+    let recorded = TEXT_OVERRIDES.with_borrow_mut(|overrides| {
+        let recorded = overrides.recorded.take();
+        overrides.refresh_active();
+        recorded
+    });
+    (result, recorded.unwrap_or_default())
+}
+
+fn page_label(page: Page, language: Language) -> Cow<'static, str> {
+    let title = |language| module(ModuleId::Page(page)).title(language);
+    tr(language, title(Language::Zh), title(Language::En))
+}
+
+fn tool_label(tool: DiagnosticTool, language: Language) -> Cow<'static, str> {
+    let title = |language| module(ModuleId::Tool(tool)).title(language);
+    tr(language, title(Language::Zh), title(Language::En))
+}
+
+fn task_label(status: &TaskStatus, language: Language) -> Cow<'static, str> {
     match status {
         TaskStatus::Idle => tr(language, " 空闲 ", " Idle "),
         TaskStatus::Running => tr(language, " 运行中 · 点击停止 ", " Running · click to stop "),
@@ -2965,72 +9443,663 @@ fn task_label(status: &TaskStatus, language: Language) -> &'static str {
     }
 }
 
-fn dashboard_public_label(status: &TaskStatus, language: Language) -> &'static str {
+fn dashboard_public_label(status: &TaskStatus, language: Language) -> Cow<'static, str> {
     match status {
         TaskStatus::Idle => tr(language, "尚未获取", "Not fetched"),
         TaskStatus::Running => tr(language, "正在获取…", "Fetching…"),
         TaskStatus::Done => tr(language, "无可用数据", "No data"),
         TaskStatus::Failed(_) => tr(language, "获取失败", "Fetch failed"),
     }
-}
+}
+
+fn format_rate(format: RegionFormat, value: u64) -> String {
+    format!("{}/s", format_bytes(format, value))
+}
+
+fn scan_address_count(cidr: &str) -> Option<u64> {
+    iptools_core::Target::parse_hosts(cidr)
+        .ok()
+        .map(|target| target.host_count())
+}
+
+fn format_bytes(format: RegionFormat, value: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut amount = value as f64;
+    let mut unit = 0;
+    while amount >= 1024.0 && unit < UNITS.len() - 1 {
+        amount /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", value, UNITS[unit])
+    } else {
+        format!("{} {}", format.decimal(amount, 1), UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn every_registered_module_has_one_view_in_registry_order() {
+        let ids = VIEWS.iter().map(|view| view.id).collect::<Vec<_>>();
+        let modules = iptools_core::MODULES
+            .iter()
+            .map(|module| module.id())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, modules);
+        for view in &VIEWS {
+            if matches!(view.id, ModuleId::Page(_)) {
+                assert!((view.fields)(&AppModel::default()).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn scanner_estimate_matches_established_usable_host_count() {
+        assert_eq!(scan_address_count("192.168.1.0/24"), Some(254));
+        assert_eq!(scan_address_count("192.168.1.0/31"), Some(2));
+        assert_eq!(scan_address_count("192.168.1.1/32"), Some(1));
+        assert_eq!(scan_address_count("192.168.1.10-19"), Some(10));
+        assert_eq!(scan_address_count("invalid"), None);
+    }
+
+    #[test]
+    fn list_viewport_follows_the_selected_row() {
+        let mut offset = 0;
+        assert_eq!(visible_range(20, 0, 5, &mut offset), 0..5);
+        assert_eq!(visible_range(20, 7, 5, &mut offset), 3..8);
+        assert_eq!(visible_range(20, 19, 5, &mut offset), 15..20);
+        assert_eq!(visible_range(20, 18, 5, &mut offset), 15..20);
+        assert_eq!(visible_range(20, 15, 5, &mut offset), 15..20);
+        assert_eq!(visible_range(20, 14, 5, &mut offset), 14..19);
+        assert_eq!(visible_range(0, 0, 5, &mut offset), 0..0);
+    }
+
+    #[test]
+    fn table_cells_are_reused_until_the_revision_changes() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Traffic;
+        model.traffic.rows = vec![iptools_core::TrafficRow {
+            name: "eth0".into(),
+            download_bps: 1_024,
+            ..iptools_core::TrafficRow::default()
+        }];
+        model.traffic.revision = 1;
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let cached = ui.traffic_cells.cells.as_ptr();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert_eq!(ui.traffic_cells.cells.as_ptr(), cached);
+
+        model.traffic.rows[0].download_bps = 2_048;
+        model.traffic.revision = 2;
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert_eq!(ui.traffic_cells.key, Some((2, 1)));
+        assert!(terminal.backend().to_string().contains("2.0 KiB/s"));
+    }
+
+    #[test]
+    fn huge_scan_results_format_only_the_visible_window() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Scanner;
+        model.scanner.results = (0..65_536)
+            .map(|index| iptools_core::ScanHost {
+                ip: format!("10.1.{}.{}", index / 256, index % 256),
+                ..iptools_core::ScanHost::default()
+            })
+            .collect();
+        model.scanner.revision = 1;
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert!(ui.page_rows() > 0);
+        assert_eq!(ui.scanner_cells.cells.len(), ui.page_rows());
+        assert_eq!(ui.scanner_cells.window, 0..ui.page_rows());
+
+        model.scanner.selected = model.scanner.results.len() - 1;
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert_eq!(ui.scanner_cells.window.end, 65_536);
+        assert!(terminal.backend().to_string().contains("10.1.255.255"));
+    }
+
+    /// Per-frame render cost of each large table, with a handful of rows and
+    /// with the most it can hold. Run with
+    /// `cargo test -p iptools-ui --release -- --ignored --nocapture render_cost`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn render_cost_of_large_tables() {
+        const FRAMES: u32 = 200;
+        fn scanner(model: &mut AppModel, rows: usize) {
+            model.page = Page::Scanner;
+            model.scanner.results = (0..rows)
+                .map(|index| iptools_core::ScanHost {
+                    ip: format!("10.1.{}.{}", index / 256 % 256, index % 256),
+                    mac: format!("02:00:00:00:{:02X}:{:02X}", index / 256 % 256, index % 256),
+                    hostname: format!("host-{index}"),
+                    ..iptools_core::ScanHost::default()
+                })
+                .collect();
+            model.scanner.revision = 1;
+        }
+        fn bluetooth(model: &mut AppModel, rows: usize) {
+            model.page = Page::Scanner;
+            model.scanner.view = ScannerView::Bluetooth;
+            model.scanner.bluetooth.devices = (0..rows)
+                .map(|index| iptools_core::BluetoothDevice {
+                    address: format!("AA:BB:CC:DD:{:02X}:{:02X}", index / 256 % 256, index % 256),
+                    name: Some(format!("device-{index}")),
+                    rssi_dbm: Some(-60),
+                    tx_power_dbm: None,
+                    manufacturer_id: None,
+                    last_seen_ms: 0,
+                })
+                .collect();
+        }
+        fn traffic(model: &mut AppModel, rows: usize) {
+            model.page = Page::Traffic;
+            model.traffic.rows = (0..rows)
+                .map(|index| iptools_core::TrafficRow {
+                    name: format!("veth{index}"),
+                    download_bps: index as u64 * 1_000,
+                    ..iptools_core::TrafficRow::default()
+                })
+                .collect();
+            model.traffic.revision = 1;
+        }
+        fn events(model: &mut AppModel, rows: usize) {
+            model.page = Page::Events;
+            model.events.entries = (0..rows)
+                .map(|index| iptools_core::NetworkEvent {
+                    at: "2026-07-12 20:30:00".into(),
+                    kind: NetworkEventKind::GatewayChanged,
+                    subject: format!("eth{index}"),
+                    from: "192.168.1.1".into(),
+                    to: "192.168.1.254".into(),
+                })
+                .collect();
+        }
+        let frame_cost = |fill: fn(&mut AppModel, usize), rows: usize| {
+            let mut model = AppModel::default();
+            fill(&mut model, rows);
+            let mut terminal = Terminal::new(TestBackend::new(160, 48)).unwrap();
+            let mut ui = UiState::default();
+            let started = std::time::Instant::now();
+            for _ in 0..FRAMES {
+                terminal
+                    .draw(|frame| render(frame, &model, &mut ui))
+                    .unwrap();
+            }
+            started.elapsed() / FRAMES
+        };
+        type Fill = fn(&mut AppModel, usize);
+        let tables: [(&str, Fill, usize); 4] = [
+            ("scanner", scanner, 65_536),
+            ("bluetooth", bluetooth, 1_024),
+            ("traffic", traffic, 4_096),
+            ("events", events, iptools_core::EVENT_LOG_LIMIT),
+        ];
+        println!("{:<10} {:>10} {:>10}", "table", "16 rows", "full");
+        for (name, fill, most) in tables {
+            let small = frame_cost(fill, 16);
+            let large = frame_cost(fill, most);
+            println!("{name:<10} {small:>10.2?} {large:>10.2?}");
+            // A full table may cost a little more to lay out, but nothing in
+            // proportion to its length.
+            assert!(large < small * 4, "{name}: {small:?} -> {large:?}");
+        }
+    }
+
+    #[test]
+    fn scanner_detail_drawer_consolidates_what_is_known_about_a_device() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Scanner;
+        model.scanner.enrich = true;
+        model.scanner.detail_open = true;
+        model.scanner.results = vec![iptools_core::ScanHost {
+            ip: "192.168.1.8".into(),
+            mac: "00:11:32:AA:BB:CC".into(),
+            vendor: "Synology".into(),
+            hostname: "nas".into(),
+            names: vec![
+                iptools_core::HostName {
+                    source: iptools_core::NameSource::Dns,
+                    name: "nas".into(),
+                },
+                iptools_core::HostName {
+                    source: iptools_core::NameSource::Mdns,
+                    name: "DiskStation".into(),
+                },
+            ],
+            open_ports: vec![80, 445, 1883, 2049],
+            web: vec![iptools_core::WebService {
+                port: 80,
+                title: "DiskStation".into(),
+                server: "nginx".into(),
+            }],
+            shares: vec![iptools_core::NetworkShare {
+                protocol: ShareProtocol::Nfs,
+                name: "/volume1/backup".into(),
+                detail: "192.168.1.0/24".into(),
+            }],
+            iot: vec![iptools_core::IotService {
+                protocol: IotProtocol::Mqtt,
+                detail: "authentication required".into(),
+            }],
+            enriched: true,
+            ..iptools_core::ScanHost::default()
+        }];
+        model.scanner.services = vec![iptools_core::DiscoveredService {
+            ip: "192.168.1.8".into(),
+            protocol: iptools_core::ServiceProtocol::Mdns,
+            name: "_smb._tcp".into(),
+        }];
+        model.scanner.inventory = vec![iptools_core::DeviceRecord {
+            key: "00:11:32:AA:BB:CC".into(),
+            last_ip: "192.168.1.8".into(),
+            first_seen: "2025-12-01 09:00".into(),
+            last_seen: "2026-01-15 10:24".into(),
+            note: "Backups, upstairs".into(),
+            device_types: Vec::new(),
+        }];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Probes: On"));
+        assert!(text.contains("MAC: 00:11:32:AA:BB:CC  Synology"));
+        assert!(text.contains("mDNS    DiskStation"));
+        assert!(text.contains("mDNS _smb._tcp"));
+        assert!(text.contains("First seen: 2025-12-01 09:00"));
+        assert!(text.contains("Note: Backups, upstairs"));
+        assert!(text.contains("80 HTTP · 445 SMB · 1883 MQTT"));
+        assert!(text.contains("2049 NFS"));
+        assert!(text.contains("HTTP :80 DiskStation  nginx"));
+        assert!(text.contains("NFS /volume1/backup"));
+        assert!(text.contains("MQTT authentication required"));
+        assert!(text.contains("Device type: MQTT broker"));
+    }
+
+    #[test]
+    fn scanner_detail_drawer_labels_cve_counts_as_heuristic() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Scanner;
+        model.scanner.detail_open = true;
+        model.scanner.results = vec![iptools_core::ScanHost {
+            ip: "192.168.1.8".into(),
+            open_ports: vec![22, 80],
+            banners: vec![iptools_core::ServiceBanner {
+                port: SSH_PORT,
+                text: "SSH-2.0-OpenSSH_7.4".into(),
+            }],
+            web: vec![iptools_core::WebService {
+                port: 80,
+                title: "DiskStation".into(),
+                server: "nginx/1.18.0".into(),
+            }],
+            enriched: true,
+            ..iptools_core::ScanHost::default()
+        }];
+        model.scanner.cve.hints = vec![iptools_core::CveHint {
+            software: iptools_core::identify("SSH-2.0-OpenSSH_7.4").unwrap(),
+            total: 15,
+            by_severity: [1, 4, 7, 2],
+        }];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("22 SSH · 80 HTTP"));
+        assert!(text.contains("SSH :22 SSH-2.0-OpenSSH_7.4"));
+        assert!(text.contains("Known CVEs (heuristic, by banner version)"));
+        assert!(text.contains("OpenSSH 7.4 15 known · 1 critical · 4 high"));
+        assert!(text.contains("nginx 1.18.0 press R to look up"));
+    }
+
+    #[test]
+    fn event_log_lists_the_newest_change_first() {
+        let backend = TestBackend::new(100, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Events;
+        let event =
+            |at: &str, kind, subject: &str, from: &str, to: &str| iptools_core::NetworkEvent {
+                at: at.into(),
+                kind,
+                subject: subject.into(),
+                from: from.into(),
+                to: to.into(),
+            };
+        model.events.entries = vec![
+            event(
+                "2026-01-15 10:24:03",
+                NetworkEventKind::InterfaceDown,
+                "Wi-Fi",
+                "192.168.1.21",
+                "",
+            ),
+            event(
+                "2026-01-15 10:24:09",
+                NetworkEventKind::GatewayChanged,
+                "Ethernet",
+                "192.168.1.1",
+                "192.168.1.254",
+            ),
+        ];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let gateway = text
+            .find("192.168.1.1 → 192.168.1.254")
+            .expect("gateway change");
+        let down = text.find("Wi-Fi").expect("interface down");
+        assert!(gateway < down);
+    }
+
+    #[test]
+    fn config_problems_are_shown_over_the_page() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.show_config_issues(vec![
+            ConfigIssue::Malformed {
+                error: "expected `,` at line 4".into(),
+                backup: Some("config.toml.20260115-102400.bak".into()),
+            },
+            ConfigIssue::ScanConcurrency { found: 4000 },
+        ]);
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Configuration Problems"));
+        assert!(text.contains("config.toml.20260115-102400.bak"));
+        assert!(text.contains("scan_concurrency = 4000"));
+    }
+
+    #[test]
+    fn new_problems_are_announced_in_the_footer_and_listed_in_the_popup() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        let offline =
+            iptools_core::RuntimeError::new(RuntimeErrorCode::Network, "network unreachable");
+        model
+            .problems
+            .record(ToolKind::Dashboard, &offline, "2026-01-15 10:24:00");
+        model
+            .problems
+            .record(ToolKind::Dashboard, &offline, "2026-01-15 10:24:05");
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let footer = text.lines().nth(29).unwrap();
+        let column = footer.find("1 new problem").expect("footer marker");
+        let column = footer[..column].width() as u16;
+        assert_eq!(ui.hit_test(column, 29), Some(Action::Problems));
+
+        model.problems.mark_seen();
+        model.open_popup(Popup::Problems);
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains(" Problems "), "{text}");
+        assert!(
+            text.contains("10:24:05 WARN  Dashboard   network unreachable ×2"),
+            "{text}"
+        );
+        assert!(!text.contains("new problem"), "{text}");
+
+        model.clock_format = ClockFormat::TwelveHour;
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("10:24:05 AM WARN"), "{text}");
+    }
+
+    #[test]
+    fn audit_lists_findings_most_severe_first() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.scanner.results.push(iptools_core::ScanHost {
+            ip: "192.168.1.31".into(),
+            open_ports: vec![FTP_PORT, TELNET_PORT],
+            ..iptools_core::ScanHost::default()
+        });
+        model.audit.dhcp = Some(Vec::new());
+        model.open_popup(Popup::Audit);
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains(" Security audit "), "{text}");
+        let telnet = text.find("High   Telnet open").expect("telnet finding");
+        let ftp = text.find("Medium FTP open").expect("ftp finding");
+        assert!(telnet < ftp, "{text}");
+        assert!(text.contains("DHCP: 0 server(s) answered"), "{text}");
+    }
 
-fn format_rate(value: u64) -> String {
-    format!("{}/s", format_bytes(value))
-}
+    #[test]
+    fn search_highlights_matches_in_the_focused_pane() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.page = Page::Events;
+        model.search.query = "Up|Down".into();
+        model.search.step = 1;
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains(" / Up|Down   no matches"), "{text}");
 
-fn scan_address_count(cidr: &str) -> Option<u64> {
-    let (address, prefix) = cidr.trim().split_once('/')?;
-    address.parse::<std::net::Ipv4Addr>().ok()?;
-    let prefix = prefix.parse::<u32>().ok()?;
-    if prefix > 32 {
-        return None;
+        model.search.query = "up|down".into();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(
+            text.contains(" / up|down   2/2   n/N next/previous"),
+            "{text}"
+        );
+        let buffer = terminal.backend().buffer();
+        let find = |word: &str| {
+            let area = buffer.area;
+            (area.top()..area.bottom())
+                .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+                .find(|&(x, y)| {
+                    word.chars().enumerate().all(|(offset, character)| {
+                        buffer
+                            .cell((x + offset as u16, y))
+                            .is_some_and(|cell| cell.symbol() == character.to_string())
+                    })
+                })
+                .unwrap_or_else(|| panic!("{word:?} not drawn"))
+        };
+        let (x, y) = find("going up");
+        assert_eq!(buffer[(x + 6, y)].bg, Color::Yellow);
+        assert_eq!(buffer[(x, y)].bg, Color::Reset);
+        let (x, y) = find("down");
+        assert_eq!(buffer[(x + 3, y)].bg, PRIMARY, "the current match");
     }
-    let size = 1_u64 << (32 - prefix);
-    Some(if prefix < 31 {
-        size.saturating_sub(2)
-    } else {
-        size
-    })
-}
 
-fn format_bytes(value: u64) -> String {
-    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
-    let mut amount = value as f64;
-    let mut unit = 0;
-    while amount >= 1024.0 && unit < UNITS.len() - 1 {
-        amount /= 1024.0;
-        unit += 1;
-    }
-    if unit == 0 {
-        format!("{} {}", value, UNITS[unit])
-    } else {
-        format!("{amount:.1} {}", UNITS[unit])
+    #[test]
+    fn calculator_shows_the_conversions_of_its_input() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.calculator.input = "192.168.1.77/24".into();
+        model.calculator.cursor = model.calculator.input.len();
+        model.open_popup(Popup::Calculator);
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains(" Address calculator "), "{text}");
+        assert!(text.contains("> 192.168.1.77/24_"), "{text}");
+        assert!(
+            text.contains("Network               192.168.1.0/24"),
+            "{text}"
+        );
+        assert!(text.contains("192.168.1.1 – 192.168.1.254"), "{text}");
+
+        model.calculator.input = "example.com".into();
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Not recognised."), "{text}");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use ratatui::{Terminal, backend::TestBackend};
+    #[test]
+    fn timeline_lays_pings_roams_and_speed_tests_on_one_axis() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.elapsed_ms = 300_000;
+        model.timeline.record_ping(100_000, Some(40));
+        model.timeline.record_ping(150_000, None);
+        model.timeline.record(
+            200_000,
+            "2026-10-17 12:03:20",
+            iptools_core::TimelineKind::Network(iptools_core::NetworkEvent {
+                at: "2026-10-17 12:03:20".into(),
+                kind: NetworkEventKind::WifiRoamed,
+                subject: "Wi-Fi".into(),
+                from: "aa:00:00:00:00:01 (ch 36)".into(),
+                to: "aa:00:00:00:00:02 (ch 149)".into(),
+            }),
+        );
+        model.timeline.record(
+            250_000,
+            "2026-10-17 12:04:10",
+            iptools_core::TimelineKind::PublicSpeed(iptools_core::SpeedSummary {
+                average_bytes_per_second: 4_000_000,
+                peak_bytes_per_second: 5_000_000,
+                total_bytes: 8_000_000,
+                upload_bytes_per_second: None,
+                bufferbloat: None,
+            }),
+        );
+        model.open_popup(Popup::Timeline);
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains(" Timeline · last 5 min "), "{text}");
+        assert!(text.contains("40 ms"), "{text}");
+        let lane = |name: &str| {
+            text.lines()
+                .find(|line| line.contains(name))
+                .unwrap_or_else(|| panic!("no {name} lane in {text}"))
+                .to_string()
+        };
+        assert!(lane("Ping loss").contains('×'), "{text}");
+        assert!(lane("Wi-Fi      ").contains('⇄'), "{text}");
+        assert!(lane("Speed tests").contains('●'), "{text}");
+        assert!(
+            text.contains(
+                "12:03:20  ⇄ Roamed  Wi-Fi  aa:00:00:00:00:01 (ch 36) → aa:00:00:00:00:02"
+            ),
+            "{text}"
+        );
+        let speed = text.find("12:04:10  ● Internet speed test").expect(&text);
+        assert!(speed < text.find("12:03:20").unwrap(), "newest first");
+    }
 
     #[test]
-    fn scanner_estimate_matches_established_usable_host_count() {
-        assert_eq!(scan_address_count("192.168.1.0/24"), Some(254));
-        assert_eq!(scan_address_count("192.168.1.0/31"), Some(2));
-        assert_eq!(scan_address_count("192.168.1.1/32"), Some(1));
-        assert_eq!(scan_address_count("invalid"), None);
+    fn confirmations_are_drawn_on_top_with_clickable_answers() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.open_popup(Popup::Help);
+        model.open_popup(Popup::Confirm(Confirmation::new(
+            Confirm::DeleteUpnpMapping {
+                protocol: LanProtocol::Udp,
+                external_port: 50_505,
+            },
+        )));
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(
+            text.contains("Delete the UDP 50505 port mapping from the router?"),
+            "{text}"
+        );
+        let (row, line) = text
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.contains("[ Yes ]"))
+            .expect("buttons");
+        for (label, yes) in [("[ Yes ]", true), ("[ No ]", false)] {
+            let column = line[..line.find(label).unwrap()].width() as u16;
+            assert_eq!(
+                ui.hit_test(column + 2, row as u16),
+                Some(Action::Answer(yes))
+            );
+        }
     }
 
     #[test]
-    fn list_viewport_follows_the_selected_row() {
-        let mut offset = 0;
-        assert_eq!(visible_range(20, 0, 5, &mut offset), 0..5);
-        assert_eq!(visible_range(20, 7, 5, &mut offset), 3..8);
-        assert_eq!(visible_range(20, 19, 5, &mut offset), 15..20);
-        assert_eq!(visible_range(20, 18, 5, &mut offset), 15..20);
-        assert_eq!(visible_range(20, 15, 5, &mut offset), 15..20);
-        assert_eq!(visible_range(20, 14, 5, &mut offset), 14..19);
-        assert_eq!(visible_range(0, 0, 5, &mut offset), 0..0);
+    fn scan_guard_question_shows_the_reason_and_the_typed_target() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        let mut confirmation = Confirmation::new(Confirm::ScanTarget {
+            scan: GuardedScan::LanScan,
+            target: "10.0.0.0/8".into(),
+            concern: ScanConcern::Large { hosts: 16_777_214 },
+        });
+        confirmation.typed = "10.0.".into();
+        model.open_popup(Popup::Confirm(confirmation));
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Scan 10.0.0.0/8?"), "{text}");
+        assert!(text.contains("16777214 addresses"), "{text}");
+        assert!(text.contains("> 10.0._"), "{text}");
+        assert!(text.contains("Esc to cancel"), "{text}");
     }
 
     #[test]
@@ -3100,6 +10169,145 @@ mod tests {
         assert_eq!(ui.hit_test(2, 1), Some(Action::SelectPage(0)));
     }
 
+    #[test]
+    fn tab_bar_follows_the_configured_order_and_skips_hidden_tabs() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.apply_config(&iptools_core::ConfigData {
+            tabs: vec!["scanner".into(), "dashboard".into()],
+            ..iptools_core::ConfigData::default()
+        });
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let tab_bar = text.lines().nth(1).unwrap();
+        assert!(tab_bar.contains("Scanner"), "{tab_bar}");
+        assert!(!tab_bar.contains("Adapters"), "{tab_bar}");
+        assert!(tab_bar.find("Scanner") < tab_bar.find("Dashboard"));
+        assert_eq!(
+            ui.hit_test(2, 1),
+            Some(Action::SelectPage(Page::Scanner as u8))
+        );
+    }
+
+    #[test]
+    fn available_update_is_a_clickable_marker_that_opens_the_notes() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.update.available = Some(iptools_core::ReleaseInfo {
+            version: "v9.0.0".into(),
+            url: "https://github.com/newcovid/iptools/releases/tag/v9.0.0".into(),
+            notes: "## Highlights\n- **Scanner** rewrite".into(),
+        });
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let tab_bar = text.lines().nth(1).unwrap();
+        let column = tab_bar.find("v9.0.0").expect("marker in the tab bar");
+        let column = tab_bar[..column].width() as u16;
+        assert_eq!(ui.hit_test(column, 1), Some(Action::ShowUpdate));
+
+        model.open_popup(Popup::Update);
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Update Available"), "{text}");
+        assert!(text.contains("• Scanner rewrite"), "{text}");
+        assert!(text.contains("releases/tag/v9.0.0"), "{text}");
+    }
+
+    #[test]
+    fn doctor_report_puts_a_remedy_under_each_problem() {
+        let checks = [
+            DoctorCheck::new(DoctorCheckKind::Privileges, DoctorStatus::Ok, "root")
+                .hint(DoctorHint::RunAsAdministrator),
+            DoctorCheck::new(
+                DoctorCheckKind::PacketCapture,
+                DoctorStatus::Warn,
+                "missing",
+            )
+            .hint(DoctorHint::InstallLibpcap),
+        ];
+        let lines = doctor_report_lines(Language::En, &checks);
+        assert_eq!(
+            lines,
+            [
+                "[OK  ] Privileges      root",
+                "[WARN] Packet capture  missing",
+                "       → Install libpcap (e.g. apt install libpcap0.8 / dnf install libpcap)",
+            ]
+        );
+        let zh = doctor_report_lines(Language::Zh, &checks);
+        assert!(
+            zh[1].starts_with("[WARN] 抓包库          missing"),
+            "{}",
+            zh[1]
+        );
+    }
+
+    #[test]
+    fn preset_report_has_a_line_per_stage_and_per_web_port() {
+        let pipeline = PipelineState {
+            preset: Some(PipelinePreset::WebService),
+            target: "example.com".into(),
+            stages: vec![
+                iptools_core::StageReport {
+                    stage: PipelineStage::Ping,
+                    status: StageStatus::Done(StageOutcome::Ping {
+                        sent: 5,
+                        received: 0,
+                        average_ms: None,
+                    }),
+                },
+                iptools_core::StageReport {
+                    stage: PipelineStage::Web,
+                    status: StageStatus::Done(StageOutcome::Web(vec![
+                        WebCheckResult {
+                            port: 443,
+                            tls: Some(iptools_core::TlsSummary {
+                                version: "TLSv1_3".into(),
+                                cipher: "TLS13_AES_128_GCM_SHA256".into(),
+                            }),
+                            status: Some(200),
+                            server: Some("nginx".into()),
+                            title: Some("Example".into()),
+                            elapsed_ms: Some(45),
+                            error: None,
+                        },
+                        WebCheckResult {
+                            port: 8080,
+                            tls: None,
+                            status: None,
+                            server: None,
+                            title: None,
+                            elapsed_ms: None,
+                            error: Some("Connection refused".into()),
+                        },
+                    ])),
+                },
+            ],
+            ..PipelineState::default()
+        };
+        assert_eq!(
+            pipeline_report_lines(Language::En, &pipeline),
+            [
+                "Web service · example.com",
+                "",
+                "[OK  ] Ping        0/5 replies (ICMP may be filtered)",
+                "[OK  ] Web check   1/2 ports answered",
+                "       443   TLSv1_3 TLS13_AES_128_GCM_SHA256 · 200 · nginx · \"Example\" · 45 ms",
+                "       8080  Connection refused",
+            ]
+        );
+    }
+
     #[test]
     fn dashboard_states_render_in_both_languages_and_compact_sizes() {
         for (width, height) in [(80, 24), (120, 36)] {
@@ -3124,6 +10332,7 @@ mod tests {
                             ssid: None,
                             is_physical: true,
                             dhcp_enabled: true,
+                            gateway: Some("192.168.1.1".into()),
                         });
                     model.dashboard.snapshot.public_info = Some(iptools_core::PublicIpInfo {
                         ip: "203.0.113.10".into(),
@@ -3164,6 +10373,222 @@ mod tests {
         }
     }
 
+    #[test]
+    fn offline_mode_marks_the_title_and_public_section() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.offline = true;
+        model.dashboard.status = TaskStatus::Done;
+        let mut ui = UiState::default();
+
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("OFFLINE"));
+        assert!(text.contains("Offline mode (not looked up)"), "{text}");
+    }
+
+    #[test]
+    fn presenter_mode_masks_identifying_values() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.dashboard.status = TaskStatus::Done;
+        model.dashboard.snapshot.hostname = "alice-laptop".into();
+        model.dashboard.snapshot.public_info = Some(iptools_core::PublicIpInfo {
+            ip: "198.51.7.20".into(),
+            isp: "Home Broadband".into(),
+            ..iptools_core::PublicIpInfo::default()
+        });
+        let mut ui = UiState::default();
+
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert!(terminal.backend().to_string().contains("198.51.7.20"));
+
+        model.redact = true;
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("REDACTED"));
+        for secret in ["198.51.7.20", "alice-laptop", "Home Broadband"] {
+            assert!(!text.contains(secret), "{secret} leaked: {text}");
+        }
+        assert!(text.contains("203.0.113."), "{text}");
+
+        // Adapters and scan results are masked as their rows are formatted;
+        // private LAN addresses stay as they are.
+        model.adapters.items.push(iptools_core::AdapterInfo {
+            name: "Wi-Fi".into(),
+            mac: "3C:22:FB:12:34:56".into(),
+            ssid: Some("Alice Home".into()),
+            ipv4: "192.168.1.20".into(),
+            ..iptools_core::AdapterInfo::default()
+        });
+        model.scanner.results.push(iptools_core::ScanHost {
+            ip: "192.168.1.30".into(),
+            mac: "AA:BB:CC:DD:EE:FF".into(),
+            hostname: "bobs-iphone".into(),
+            ..iptools_core::ScanHost::default()
+        });
+        model.scanner.revision += 1;
+        for page in [Page::Adapters, Page::Scanner] {
+            model.page = page;
+            terminal
+                .draw(|frame| render(frame, &model, &mut ui))
+                .unwrap();
+            let text = terminal.backend().to_string();
+            for secret in [
+                "3C:22:FB:12:34:56",
+                "Alice Home",
+                "AA:BB:CC:DD:EE:FF",
+                "bobs-iphone",
+            ] {
+                assert!(!text.contains(secret), "{secret} leaked: {text}");
+            }
+            assert!(text.contains(if page == Page::Adapters {
+                "192.168.1.20"
+            } else {
+                "192.168.1.30"
+            }));
+        }
+        assert_eq!(model.dashboard.snapshot.hostname, "alice-laptop");
+    }
+
+    #[test]
+    fn accessible_color_modes_recolor_or_drop_color_and_add_marks() {
+        let mut model = AppModel::default();
+        model.page = Page::Adapters;
+        model.adapters.items.push(iptools_core::AdapterInfo {
+            name: "eth0".into(),
+            ipv4: "192.168.1.20".into(),
+            status: "up".into(),
+            ..iptools_core::AdapterInfo::default()
+        });
+        let draw = |model: &AppModel| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+            terminal
+                .draw(|frame| render(frame, model, &mut UiState::default()))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+        let buffer_text = |buffer: &ratatui::buffer::Buffer| {
+            buffer
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        let standard = draw(&model);
+        assert!(standard.content.iter().any(|cell| cell.fg == Color::Green));
+        assert!(!buffer_text(&standard).contains("▲ UP"));
+
+        model.color_mode = ColorMode::ColorBlind;
+        let color_blind = draw(&model);
+        assert!(
+            color_blind
+                .content
+                .iter()
+                .all(|cell| cell.fg != Color::Green)
+        );
+        assert!(
+            color_blind
+                .content
+                .iter()
+                .any(|cell| cell.fg == Color::Rgb(86, 180, 233))
+        );
+        assert!(buffer_text(&color_blind).contains("▲ UP"));
+
+        model.color_mode = ColorMode::Standard;
+        model.no_color = true;
+        let monochrome = draw(&model);
+        assert!(
+            monochrome
+                .content
+                .iter()
+                .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset)
+        );
+        assert!(
+            monochrome
+                .content
+                .iter()
+                .any(|cell| cell.modifier.contains(Modifier::REVERSED))
+        );
+        assert!(buffer_text(&monochrome).contains("▲ UP"));
+    }
+
+    #[test]
+    fn legacy_terminals_get_reduced_colors_and_ascii_glyphs() {
+        let mut model = AppModel::default();
+        model.theme = ThemeId::Nord;
+        model.terminal = iptools_core::TerminalCaps {
+            colors: ColorDepth::Ansi16,
+            unicode: false,
+        };
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content.iter().all(|cell| !matches!(
+            cell.fg,
+            Color::Rgb(..) | Color::Indexed(_)
+        ) && !matches!(
+            cell.bg,
+            Color::Rgb(..) | Color::Indexed(_)
+        )));
+        let text = terminal.backend().to_string();
+        assert!(!text.contains('↓') && !text.contains('↑'));
+        assert!(text.contains("v 0 B/s"), "{text}");
+
+        assert_eq!(
+            reduce_color(Color::Rgb(46, 52, 64), ColorDepth::Ansi16),
+            Color::Black
+        );
+        assert_eq!(
+            reduce_color(Color::Rgb(255, 0, 0), ColorDepth::Ansi256),
+            Color::Indexed(196)
+        );
+        assert_eq!(
+            reduce_color(Color::Rgb(128, 128, 128), ColorDepth::Ansi256),
+            Color::Indexed(244)
+        );
+    }
+
+    #[test]
+    fn braille_charts_are_opt_in_and_need_unicode() {
+        let mut model = AppModel::default();
+        let data = (0..400).map(|value| value % 40).collect::<Vec<u64>>();
+        let draw = |model: &AppModel| {
+            let mut terminal = Terminal::new(TestBackend::new(40, 8)).unwrap();
+            terminal
+                .draw(|frame| {
+                    render_history(
+                        frame,
+                        frame.area(),
+                        model,
+                        Block::bordered(),
+                        &data,
+                        PRIMARY,
+                    )
+                })
+                .unwrap();
+            terminal.backend().to_string()
+        };
+        let is_braille = |ch: char| ('\u{2801}'..='\u{28ff}').contains(&ch);
+
+        assert!(!draw(&model).contains(is_braille));
+        model.chart_style = ChartStyle::Braille;
+        assert!(draw(&model).contains(is_braille));
+        model.terminal.unicode = false;
+        assert!(!draw(&model).contains(is_braille));
+    }
+
     #[test]
     fn adapter_and_traffic_states_render_in_both_languages_and_compact_sizes() {
         for (width, height) in [(80, 24), (120, 36)] {
@@ -3209,6 +10634,7 @@ mod tests {
                     total_upload: 1_610_612_736,
                     session_download: 734_003_200,
                     session_upload: 125_829_120,
+                    link: None,
                 }];
                 model.traffic.status = TaskStatus::Running;
                 terminal
@@ -3227,6 +10653,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn traffic_and_adapters_show_recent_link_loss() {
+        let mut model = AppModel::default();
+        model.page = Page::Traffic;
+        let counters = |packets, drops| LinkCounters {
+            packets,
+            drops,
+            ..LinkCounters::default()
+        };
+        model.traffic.rows = vec![iptools_core::TrafficRow {
+            name: "eth0".into(),
+            link: Some(counters(10_000, 0)),
+            ..iptools_core::TrafficRow::default()
+        }];
+        model
+            .link_health
+            .record(0, [("eth0", counters(10_000, 0))], 1);
+        model
+            .link_health
+            .record(30_000, [("eth0", counters(19_800, 200))], 1);
+        model.traffic.revision = 1;
+        let backend = TestBackend::new(120, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Loss (1 min)"), "{text}");
+        assert!(text.contains("2.00% !"), "{text}");
+        assert!(text.contains("drop 200 err 0"), "{text}");
+
+        model.page = Page::Adapters;
+        model.adapters.items = vec![iptools_core::AdapterInfo {
+            name: "eth0".into(),
+            ..iptools_core::AdapterInfo::default()
+        }];
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Last 30 s: 2.00%  drop 200 err 0"), "{text}");
+        assert!(text.contains("Since boot: drop 200 err 0"), "{text}");
+    }
+
+    #[test]
+    fn adapters_flag_a_link_negotiated_below_the_hardware() {
+        let mut model = AppModel::default();
+        model.page = Page::Adapters;
+        model.adapters.items = vec![iptools_core::AdapterInfo {
+            name: "eth0".into(),
+            ipv4: "192.168.1.20".into(),
+            status: "up".into(),
+            is_physical: true,
+            link_speed_bps: Some(100_000_000),
+            media: iptools_core::LinkMedia {
+                duplex: Some(Duplex::Full),
+                medium: Some(LinkMedium::TwistedPair),
+                max_speed_bps: Some(1_000_000_000),
+                partner_max_speed_bps: Some(1_000_000_000),
+            },
+            ..iptools_core::AdapterInfo::default()
+        }];
+        let backend = TestBackend::new(160, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("eth0 !"), "{text}");
+        assert!(
+            text.contains("100 Mbps · full duplex · twisted pair · hardware max 1 Gbps"),
+            "{text}"
+        );
+        assert!(
+            text.contains("Only 100 Mbps though both ends support 1 Gbps"),
+            "{text}"
+        );
+    }
+
     #[test]
     fn adapter_edit_phases_render_and_mouse_fields_use_fixed_display_columns() {
         for (width, height) in [(80, 24), (120, 36)] {
@@ -3299,13 +10806,19 @@ mod tests {
                     model.page = Page::Scanner;
                     model.language = language;
                     model.scanner.status = status;
-                    model.scanner.current = 42;
                     model.scanner.total = 254;
+                    model.scanner.progress.probe = iptools_core::PhaseProgress {
+                        queued: 200,
+                        in_flight: 12,
+                        done: 42,
+                        cancelled: 0,
+                    };
                     model.scanner.results = vec![iptools_core::ScanHost {
                         ip: "192.168.1.1".into(),
                         mac: "00:11:22:33:44:55".into(),
                         vendor: "Example Networks".into(),
                         hostname: "gateway".into(),
+                        ..iptools_core::ScanHost::default()
                     }];
                     let mut ui = UiState::default();
 
@@ -3332,6 +10845,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn scan_progress_shows_the_measured_rate_and_adaptive_concurrency() {
+        let mut progress = ScanProgress {
+            probe: iptools_core::PhaseProgress {
+                queued: 147,
+                in_flight: 25,
+                done: 82,
+                cancelled: 0,
+            },
+            ..ScanProgress::default()
+        };
+        assert_eq!(
+            scan_progress_label(Language::En, &progress, 0.32),
+            "32.0%  Sweep 82/254 · 25 in flight"
+        );
+        progress.rate = 180;
+        progress.workers = 25;
+        assert_eq!(
+            scan_progress_label(Language::En, &progress, 0.32),
+            "32.0%  Sweep 82/254 · 25 in flight · 180/s · concurrency 25"
+        );
+        assert!(scan_progress_label(Language::Zh, &progress, 0.32).ends_with("180/s · 并发 25"));
+        progress.method = ScanMethod::Routed;
+        assert_eq!(
+            scan_progress_label(Language::En, &progress, 0.32),
+            "32.0%  Sweep 82/254 · 25 in flight · routed ICMP/TCP · 180/s · concurrency 25"
+        );
+    }
+
     #[test]
     fn port_scan_states_render_in_both_languages_and_compact_sizes() {
         for (width, height) in [(80, 24), (120, 36)] {
@@ -3472,85 +11014,819 @@ mod tests {
     }
 
     #[test]
-    fn ping_and_trace_shared_focus_config_and_results_render_in_both_languages() {
-        for language in [Language::En, Language::Zh] {
-            let backend = TestBackend::new(120, 36);
-            let mut terminal = Terminal::new(backend).unwrap();
-            let mut model = AppModel::default();
-            model.page = Page::Diagnostics;
-            model.language = language;
-            model.diagnostics.focused = true;
-            model.diagnostics.focus = DiagnosticFocus::Config;
-            model.diagnostics.ping.common.status = TaskStatus::Running;
-            model.diagnostics.ping.common.primary = "reply 4: 18 ms".into();
-            model.diagnostics.ping.common.detail = "4 / 4 received".into();
-            model.diagnostics.ping.common.log = vec!["reply 3: 20 ms".into()];
-            let mut ui = UiState::default();
-            terminal
-                .draw(|frame| render(frame, &model, &mut ui))
-                .unwrap();
-            let text = terminal.backend().to_string();
-            assert!(text.contains("8.8.8.8"), "{text}");
-            assert!(text.contains("1000"), "{text}");
-            assert!(text.contains("reply 3: 20 ms"), "{text}");
-            assert!(
-                text.contains(if language == Language::Zh {
-                    "运行中"
-                } else {
-                    "Running"
-                }),
-                "{text}"
-            );
-            if language == Language::Zh {
-                assert!(text.contains("空格 停止"), "{text}");
-                assert!(!text.contains("Space 停止"), "{text}");
-            }
-            assert_eq!(
-                ui.hit_test(89, 5),
-                Some(Action::SelectDiagnosticField(0, 1))
-            );
-            assert_eq!(
-                ui.hit_test(25, 5),
-                Some(Action::FocusDiagnostic(DiagnosticFocus::Main))
-            );
+    fn ping_and_trace_shared_focus_config_and_results_render_in_both_languages() {
+        for language in [Language::En, Language::Zh] {
+            let backend = TestBackend::new(120, 36);
+            let mut terminal = Terminal::new(backend).unwrap();
+            let mut model = AppModel::default();
+            model.page = Page::Diagnostics;
+            model.language = language;
+            model.diagnostics.focused = true;
+            model.diagnostics.focus = DiagnosticFocus::Config;
+            model.diagnostics.ping.common.status = TaskStatus::Running;
+            model.diagnostics.ping.common.primary = "reply 4: 18 ms".into();
+            model.diagnostics.ping.common.detail = "4 / 4 received".into();
+            model.diagnostics.ping.common.log = vec!["reply 3: 20 ms".into()];
+            let mut ui = UiState::default();
+            terminal
+                .draw(|frame| render(frame, &model, &mut ui))
+                .unwrap();
+            let text = terminal.backend().to_string();
+            assert!(text.contains("8.8.8.8"), "{text}");
+            assert!(text.contains("1000"), "{text}");
+            assert!(text.contains("reply 3: 20 ms"), "{text}");
+            assert!(
+                text.contains(if language == Language::Zh {
+                    "运行中"
+                } else {
+                    "Running"
+                }),
+                "{text}"
+            );
+            if language == Language::Zh {
+                assert!(text.contains("空格 停止"), "{text}");
+                assert!(!text.contains("Space 停止"), "{text}");
+            }
+            assert_eq!(
+                ui.hit_test(89, 5),
+                Some(Action::SelectDiagnosticField(0, 1))
+            );
+            assert_eq!(
+                ui.hit_test(25, 5),
+                Some(Action::FocusDiagnostic(DiagnosticFocus::Main))
+            );
+
+            model.diagnostics.tool = DiagnosticTool::Trace;
+            model.diagnostics.focus = DiagnosticFocus::Main;
+            model.diagnostics.trace.hops = vec![iptools_core::TraceHop {
+                ttl: 1,
+                address: Some("192.0.2.1".into()),
+                hostname: Some("gateway.example".into()),
+                latency_ms: Some(7),
+            }];
+            terminal
+                .draw(|frame| render(frame, &model, &mut ui))
+                .unwrap();
+            let text = terminal.backend().to_string();
+            assert!(text.contains("192.0.2.1"), "{text}");
+            assert!(text.contains("gateway.example"), "{text}");
+            assert!(text.contains("30"), "{text}");
+
+            let error = iptools_core::RuntimeError::new(
+                RuntimeErrorCode::PermissionDenied,
+                "raw socket permission denied",
+            );
+            model.diagnostics.trace.common.status = TaskStatus::Failed(error.message.clone());
+            model.diagnostics.trace.common.error = Some(error);
+            terminal
+                .draw(|frame| render(frame, &model, &mut ui))
+                .unwrap();
+            let text = terminal.backend().to_string();
+            assert!(
+                text.contains(if language == Language::Zh {
+                    "权限不足"
+                } else {
+                    "Permission denied"
+                }),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn trace_comparison_lines_paths_up_and_names_the_divergence() {
+        let hop = |ttl, address: &str| iptools_core::TraceHop {
+            ttl,
+            address: Some(address.into()),
+            hostname: None,
+            latency_ms: Some(u64::from(ttl) * 4),
+        };
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Trace;
+        model.diagnostics.trace.request.target = "cdn-a.example".into();
+        model.diagnostics.trace.hops = vec![hop(1, "192.168.1.1"), hop(2, "203.0.113.7")];
+        let mut request = model.diagnostics.trace.request.clone();
+        request.target = "cdn-b.example".into();
+        request.via = Some("wlan0".into());
+        model.diagnostics.trace.comparison = Some(iptools_core::TraceComparison {
+            request,
+            job: None,
+            hops: vec![
+                hop(1, "192.168.1.1"),
+                hop(2, "198.51.100.9"),
+                hop(3, "198.51.100.10"),
+            ],
+            error: None,
+        });
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Paths diverge at hop 2"), "{text}");
+        assert!(
+            text.contains("A: cdn-a.example   B: cdn-b.example via wlan0"),
+            "{text}"
+        );
+        assert!(
+            text.lines()
+                .any(|line| line.contains("203.0.113.7") && line.contains("198.51.100.9")),
+            "{text}"
+        );
+        assert!(text.contains("198.51.100.10"), "{text}");
+    }
+
+    #[test]
+    fn ping_flags_a_claimed_location_the_rtt_cannot_reach() {
+        for (language, expected) in [
+            (
+                Language::En,
+                "Not in Frankfurt: 3 ms reaches 306 km at most, it is 8",
+            ),
+            (Language::Zh, "不可能在 Frankfurt：3 ms 最远只够 306 km"),
+        ] {
+            let backend = TestBackend::new(120, 36);
+            let mut terminal = Terminal::new(backend).unwrap();
+            let mut model = AppModel::default();
+            model.page = Page::Diagnostics;
+            model.language = language;
+            model.diagnostics.focused = true;
+            model.diagnostics.focus = DiagnosticFocus::Config;
+            model.diagnostics.tool = DiagnosticTool::Ping;
+            model.diagnostics.ping.claimed_location = "frankfurt".into();
+            model.dashboard.snapshot.public_info = Some(iptools_core::PublicIpInfo {
+                ip: "203.0.113.7".into(),
+                city: "上海".into(),
+                region: String::new(),
+                country: String::new(),
+                isp: String::new(),
+            });
+            model.diagnostics.ping.summary = Some(iptools_core::PingSummary {
+                sent: 3,
+                received: 3,
+                min_ms: Some(3),
+                average_ms: Some(4.0),
+                max_ms: Some(6),
+                loss_percent: 0.0,
+            });
+            let mut ui = UiState::default();
+            terminal
+                .draw(|frame| render(frame, &model, &mut ui))
+                .unwrap();
+            let text = terminal.backend().to_string();
+            assert!(text.contains(expected), "{text}");
+            assert!(
+                text.contains(&*tr(language, "声称位置", "Claimed location")),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn pinned_ping_baseline_is_compared_beside_the_live_statistics() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.tool = DiagnosticTool::Ping;
+        model.diagnostics.ping.summary = Some(iptools_core::PingSummary {
+            sent: 20,
+            received: 20,
+            min_ms: Some(3),
+            average_ms: Some(4.0),
+            max_ms: Some(6),
+            loss_percent: 0.0,
+        });
+        model.diagnostics.ping.baseline = Some(iptools_core::PingBaseline {
+            target: "1.1.1.1".into(),
+            at: "2026-10-17 12:03:20".into(),
+            stats: iptools_core::PingStats {
+                min_ms: Some(9),
+                average_ms: Some(21.5),
+                max_ms: Some(80),
+                loss_percent: 5.0,
+                sent: 20,
+                ..iptools_core::PingStats::default()
+            },
+        });
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let row = |name: &str| {
+            let line = text
+                .lines()
+                .find(|line| line.contains(&format!("│{name} ")))
+                .unwrap_or_else(|| panic!("no {name} row in {text}"));
+            line[line.find(name).unwrap() + name.len()..]
+                .split_whitespace()
+                .take(5)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert_eq!(row("Now"), "3.0 6.0 4.0 — 0.0", "{text}");
+        assert_eq!(row("Baseline"), "9.0 80.0 21.5 — 5.0", "{text}");
+        assert_eq!(row("Δ"), "-6.0 -74.0 -17.5 — -5.0", "{text}");
+        assert!(text.contains("Baseline 12:03:20 · P to unpin"), "{text}");
+        assert!(text.contains("baseline pinged 1.1.1.1"), "{text}");
+    }
+
+    #[test]
+    fn mail_ports_show_their_tls_state_and_login_mechanisms() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Mail;
+        let state = &mut model.diagnostics.mail;
+        state.request.host = "mail.example".into();
+        state.common.status = TaskStatus::Done;
+        state.checks = vec![
+            iptools_core::MailCheck {
+                banner: Some("220 mail.example ESMTP".into()),
+                starttls: Some(true),
+                tls: Some(iptools_core::TlsSummary {
+                    version: "TLSv1_3".into(),
+                    cipher: "TLS13_AES_256_GCM_SHA384".into(),
+                }),
+                auth: vec!["PLAIN".into(), "LOGIN".into()],
+                ..iptools_core::MailCheck::new(587, iptools_core::MailProtocol::Smtp, false)
+            },
+            iptools_core::MailCheck {
+                tls_error: Some("invalid peer certificate: Expired".into()),
+                ..iptools_core::MailCheck::new(993, iptools_core::MailProtocol::Imap, true)
+            },
+            iptools_core::MailCheck {
+                banner: Some("+OK ready".into()),
+                starttls: Some(false),
+                auth: vec!["USER".into()],
+                cleartext_auth: true,
+                ..iptools_core::MailCheck::new(110, iptools_core::MailProtocol::Pop3, false)
+            },
+        ];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "Logins are not protected on some ports: 2/3",
+            "✓ Secure",
+            "TLSv1_3",
+            "PLAIN LOGIN",
+            "IMAPS",
+            "✗ Bad cert",
+            "993 IMAPS: invalid peer certificate: Expired",
+            "⚠ Cleartext",
+            "Mail Server",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+    }
+
+    #[test]
+    fn voip_probe_shows_the_call_score_and_rtt_per_second() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Voip;
+        let state = &mut model.diagnostics.voip;
+        state.request.peer = "voice.example".into();
+        state.endpoint = Some("192.0.2.9:50506".into());
+        state.common.status = TaskStatus::Running;
+        let sample = |second: u64, rtt_ms: f64| VoipSample {
+            elapsed_ms: second * 1_000,
+            sent: second * 50,
+            received: second * 50,
+            rtt_ms: Some(rtt_ms),
+            jitter_ms: Some(1.5),
+            ..VoipSample::default()
+        };
+        // 20 ms for the first second, then 40 ms: the running mean is 30.
+        state.samples = vec![sample(1, 20.0), sample(2, 30.0)];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "192.0.2.9:50506",
+            "Excellent",
+            "RTT: 30.0 ms",
+            "Jitter: 1.5 ms",
+            "Loss: 0.0% (0/100)",
+            "RTT per Second (ms)",
+            "G.711",
+            "VoIP Quality",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+    }
+
+    #[test]
+    fn gaming_regions_are_ranked_with_median_and_jitter() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Gaming;
+        let state = &mut model.diagnostics.gaming;
+        state.request.preset = GamingPreset::AsiaPacific;
+        state.common.status = TaskStatus::Done;
+        let endpoints = state.request.endpoints().unwrap();
+        state.scores = vec![
+            iptools_core::GamingScore::from_samples(
+                endpoints[0].clone(),
+                &[Some(31.0), Some(35.0), Some(33.0)],
+            ),
+            iptools_core::GamingScore::from_samples(endpoints[1].clone(), &[None, None]),
+        ];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "Best region: Asia Pacific (Tokyo)",
+            "Median: 33.0 ms    Jitter: 3.0 ms    Loss: 0%",
+            "Median ms",
+            "UDP",
+            "Asia Pacific (Seoul)",
+            "100%",
+            "Gaming Latency",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+    }
+
+    #[test]
+    fn watch_tiles_show_latency_and_outages_under_the_dashboard() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.color_mode = ColorMode::ColorBlind;
+        model.dashboard.snapshot.observed_at = "2026-10-16 03:10:00".into();
+        model.watch.set_targets(
+            iptools_core::parse_watch_targets(
+                "NAS=192.168.1.10<5, VPN=vpn.example.com:443, Wiki=https://wiki.example.com@02:00-04:00",
+            )
+            .unwrap(),
+        );
+        let targets = model.watch.targets();
+        model.watch.record(iptools_core::WatchCheck {
+            target: targets[0].clone(),
+            latency_ms: Some(3.2),
+            error: None,
+        });
+        model.watch.record(iptools_core::WatchCheck {
+            target: targets[1].clone(),
+            latency_ms: None,
+            error: Some("connection refused".into()),
+        });
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            " NAS ",
+            "✓ 3 / 5 ms",
+            " VPN ",
+            "✗ down",
+            "connection refused",
+            " Wiki ",
+            "maintenance 02:00-04:00",
+            "Local Network",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+    }
+
+    #[test]
+    fn dashboard_dates_and_totals_follow_the_display_formats() {
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.region_format = RegionFormat::European;
+        model.dashboard.snapshot.observed_at = "2026-10-16 03:10:00".into();
+        model.dashboard.snapshot.total_download = 3 * 1024 * 1024 + 512 * 1024;
+        let mut terminal = Terminal::new(TestBackend::new(120, 36)).unwrap();
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("16.10.2026 03:10:00"), "{text}");
+        assert!(text.contains("3,5 MiB"), "{text}");
+        assert!(!text.contains("2026-10-16"), "{text}");
+
+        model.clock_format = ClockFormat::TwelveHour;
+        model.show_utc = true;
+        model.utc_offset_minutes = Some(-240);
+        terminal
+            .draw(|frame| render(frame, &model, &mut UiState::default()))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(
+            text.contains("16.10.2026 3:10:00 AM (7:10:00 AM UTC)"),
+            "{text}"
+        );
+        // Only the numbers take the decimal comma, never the text around them.
+        let european = RegionFormat::European;
+        assert_eq!(
+            format_speed_dual(european, 1_500_000),
+            "1,50 MB/s · 12,00 Mbps"
+        );
+        assert_eq!(format_bytes(european, 1536), "1,5 KiB");
+        assert_eq!(format_optional_f64(european, Some(2.25)), "2,2");
+    }
+
+    #[test]
+    fn kiosk_views_fill_the_screen_without_tabs_or_footer() {
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.dashboard.snapshot.hostname = "wallboard".into();
+        model.dashboard.snapshot.observed_at = "2026-10-16 03:10:00".into();
+        model.watch.set_targets(
+            iptools_core::parse_watch_targets("NAS=192.168.1.10, VPN=vpn.example.com:443").unwrap(),
+        );
+        let targets = model.watch.targets();
+        model.watch.record(iptools_core::WatchCheck {
+            target: targets[0].clone(),
+            latency_ms: Some(18.0),
+            error: None,
+        });
+        model.popups.push(Popup::Help);
+        model.enter_kiosk(KioskView::Monitor);
+        let draw = |model: &AppModel| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+            terminal
+                .draw(|frame| render(frame, model, &mut UiState::default()))
+                .unwrap();
+            terminal.backend().to_string()
+        };
+
+        let text = draw(&model);
+        for expected in [
+            " wallboard ",
+            "2026-10-16 03:10:00",
+            " NAS ",
+            "18 ms",
+            " VPN ",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+        // "18" in block digits: the top rows of 1 and 8
+        assert!(text.contains(" █  ███"), "{text}");
+        assert!(
+            !text.contains("IP Tools CLI") && !text.contains("Help"),
+            "{text}"
+        );
+
+        model.kiosk = Some(KioskView::Dashboard);
+        let text = draw(&model);
+        assert!(
+            text.contains("Local Network") && text.contains("███ ███"),
+            "{text}"
+        );
+
+        model.enter_kiosk(KioskView::Traffic);
+        model.traffic.rows = vec![iptools_core::TrafficRow {
+            name: "eth0".into(),
+            download_bps: 2 * 1024 * 1024,
+            ..Default::default()
+        }];
+        let text = draw(&model);
+        assert!(text.contains(" Download · All interfaces "), "{text}");
+        assert!(text.contains(" MiB/s ") && text.contains("eth0"), "{text}");
+    }
+
+    #[test]
+    fn a_split_view_dims_the_other_pane_and_clicks_there_move_the_focus() {
+        let mut model = AppModel::default();
+        model.language = Language::En;
+        model.page = Page::Diagnostics;
+        model.traffic.rows = vec![iptools_core::TrafficRow {
+            name: "eth0".into(),
+            ..Default::default()
+        }];
+        let act = |model: &mut AppModel, action| {
+            model.update(iptools_core::Message::Input(
+                iptools_core::InputEvent::Action(action),
+            ));
+        };
+        act(&mut model, Action::ToggleSplit);
+        act(&mut model, Action::SwitchPane);
+        let mut terminal = Terminal::new(TestBackend::new(140, 30)).unwrap();
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Ping") && text.contains("eth0"), "{text}");
 
-            model.diagnostics.tool = DiagnosticTool::Trace;
-            model.diagnostics.focus = DiagnosticFocus::Main;
-            model.diagnostics.trace.hops = vec![iptools_core::TraceHop {
-                ttl: 1,
-                address: Some("192.0.2.1".into()),
-                hostname: Some("gateway.example".into()),
-                latency_ms: Some(7),
-            }];
-            terminal
-                .draw(|frame| render(frame, &model, &mut ui))
-                .unwrap();
-            let text = terminal.backend().to_string();
-            assert!(text.contains("192.0.2.1"), "{text}");
-            assert!(text.contains("gateway.example"), "{text}");
-            assert!(text.contains("30"), "{text}");
+        // Diagnostics on the left lost the focus to Traffic on the right.
+        assert!(buffer[(10, 10)].modifier.contains(Modifier::DIM));
+        assert!(!buffer[(100, 10)].modifier.contains(Modifier::DIM));
+        assert_eq!(ui.hit_test(10, 10), Some(Action::SwitchPane));
+        assert_ne!(ui.hit_test(100, 10), Some(Action::SwitchPane));
+        assert!(
+            !(3..28)
+                .flat_map(|row| (0..70).map(move |column| (column, row)))
+                .any(|(column, row)| matches!(
+                    ui.hit_test(column, row),
+                    Some(Action::SelectDiagnostic(_) | Action::FocusDiagnostic(_))
+                ))
+        );
+    }
 
-            let error = iptools_core::RuntimeError::new(
-                RuntimeErrorCode::PermissionDenied,
-                "raw socket permission denied",
-            );
-            model.diagnostics.trace.common.status = TaskStatus::Failed(error.message.clone());
-            model.diagnostics.trace.common.error = Some(error);
-            terminal
-                .draw(|frame| render(frame, &model, &mut ui))
-                .unwrap();
-            let text = terminal.backend().to_string();
-            assert!(
-                text.contains(if language == Language::Zh {
-                    "权限不足"
+    #[test]
+    fn cloud_matrix_marks_the_unreachable_region() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Cloud;
+        let state = &mut model.diagnostics.cloud;
+        state.common.status = TaskStatus::Done;
+        state.probes = cloud_endpoints()
+            .into_iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                let attempt = if index == 0 {
+                    Err("timed out".to_string())
                 } else {
-                    "Permission denied"
-                }),
-                "{text}"
-            );
+                    Ok(18.0)
+                };
+                iptools_core::CloudProbe::from_attempts(endpoint, &[attempt])
+            })
+            .collect();
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "Likely a provider outage: AWS us-east-1",
+            "Reachable: 19/20",
+            "Azure",
+            "westeurope",
+            "18 ms",
+            "down",
+            "Cloud Reachability",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+    }
+
+    #[test]
+    fn ab_test_table_marks_the_better_side_per_metric() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::AbTest;
+        let state = &mut model.diagnostics.ab_test;
+        state.common.status = TaskStatus::Done;
+        let mut wireless = iptools_core::AbLeg::from_replies(
+            AbSide::A,
+            "Wi-Fi",
+            "192.168.1.21",
+            &[Some(30.0), None, Some(34.0)],
+        );
+        wireless.download_bytes_per_second = Some(5_000_000.0);
+        wireless.error = Some("DNS: 1.1.1.1:53 did not answer".into());
+        let mut wired = iptools_core::AbLeg::from_replies(
+            AbSide::B,
+            "Ethernet",
+            "192.168.1.20",
+            &[Some(3.0), Some(3.0), Some(3.0)],
+        );
+        wired.download_bytes_per_second = Some(5_100_000.0);
+        wired.record_dns(vec![12.0]);
+        state.legs = vec![wireless, wired];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "Better: B (Ethernet) · 4/5",
+            "A = 192.168.1.21 · B = 192.168.1.20",
+            "A · Wi-Fi",
+            "33.3 %",
+            "40.0 Mbps",
+            "tie",
+            "A: DNS: 1.1.1.1:53 did not answer",
+            "A/B Interfaces",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+    }
+
+    #[test]
+    fn scanner_bluetooth_view_lists_devices_with_vendor() {
+        let backend = TestBackend::new(120, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Scanner;
+        model.language = Language::En;
+        model.scanner.view = ScannerView::Bluetooth;
+        model.scanner.bluetooth.status = TaskStatus::Running;
+        model.scanner.bluetooth.devices = vec![
+            iptools_core::BluetoothDevice {
+                address: "4C:21:8A:10:22:01".into(),
+                name: Some("iPhone".into()),
+                rssi_dbm: Some(-48),
+                tx_power_dbm: None,
+                manufacturer_id: Some(0x004C),
+                last_seen_ms: 1_200,
+            },
+            iptools_core::BluetoothDevice {
+                address: "DC:A6:32:55:90:04".into(),
+                name: None,
+                rssi_dbm: Some(-79),
+                tx_power_dbm: Some(-12),
+                manufacturer_id: None,
+                last_seen_ms: 400,
+            },
+        ];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "Nearby Bluetooth",
+            "2 devices",
+            "Bluetooth Devices (2)",
+            ">> iPhone",
+            "4C:21:8A:10:22:01",
+            "-48 dBm",
+            "Apple",
+            "1.2s",
+            "DC:A6:32:55:90:04",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
+        }
+        assert!(!text.contains("Scan Range"), "{text}");
+        assert!(ui.scanner_panel.is_none());
+    }
+
+    #[test]
+    fn wifi_monitor_lists_roams_newest_first() {
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Wifi;
+        let link = |bssid: &str, channel, rssi_dbm| iptools_core::WirelessSnapshot {
+            ssid: "HomeLab".into(),
+            bssid: bssid.into(),
+            signal_quality: 0,
+            rssi_dbm,
+            phy_type: "-".into(),
+            wifi_generation: 6,
+            band: "5 GHz".into(),
+            channel,
+            frequency_mhz: 0,
+            rx_rate_mbps: 0,
+            tx_rate_mbps: 780,
+            authentication: "-".into(),
+            cipher: "-".into(),
+        };
+        let state = &mut model.diagnostics.wifi;
+        state.request.adapter = "Wi-Fi".into();
+        state.common.status = TaskStatus::Running;
+        state.samples = vec![
+            iptools_core::WifiSample {
+                elapsed_ms: 0,
+                link: Some(link("aa:00:00:00:00:01", 36, -78)),
+                nearby: Vec::new(),
+            },
+            iptools_core::WifiSample {
+                elapsed_ms: 3_000,
+                link: Some(link("aa:00:00:00:00:02", 149, -52)),
+                nearby: Vec::new(),
+            },
+        ];
+        state.roams = vec![iptools_core::WifiRoam {
+            at: "2026-10-16 21:30:03".into(),
+            elapsed_ms: 3_000,
+            ssid: "HomeLab".into(),
+            from_bssid: "aa:00:00:00:00:01".into(),
+            to_bssid: "aa:00:00:00:00:02".into(),
+            from_channel: 36,
+            to_channel: 149,
+            rssi_before_dbm: -78,
+            rssi_after_dbm: -52,
+            ping_sequence: Some(41),
+        }];
+        state.nearby = [
+            ("aa:00:00:00:00:02", 149, 5_745, -52),
+            ("bb:00:00:00:00:01", 36, 5_180, -50),
+            ("bb:00:00:00:00:02", 1, 2_412, -45),
+            ("bb:00:00:00:00:03", 6, 2_437, -45),
+        ]
+        .map(
+            |(bssid, channel, frequency_mhz, rssi_dbm)| iptools_core::NearbyNetwork {
+                ssid: String::new(),
+                bssid: bssid.into(),
+                channel,
+                frequency_mhz,
+                rssi_dbm,
+            },
+        )
+        .to_vec();
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        for expected in [
+            "HomeLab  aa:00:00:00:00:02",
+            "5 GHz · ch 149",
+            "RSSI -78/-65/-52 dBm  Roams: 1",
+            "21:30:03 aa:00:00:00:00:01",
+            "→ aa:00:00:00:00:02",
+            "→ 149",
+            "→ -52 dBm",
+            "#41",
+            "Signal History",
+            "2.4 GHz · least congested ch 11",
+            "5 GHz · current ch 149 · least congested ch 40",
+        ] {
+            assert!(text.contains(expected), "{expected}: {text}");
         }
     }
 
+    #[test]
+    fn trace_hops_show_their_origin_as_and_the_selected_path() {
+        let hop = |ttl, address: &str| iptools_core::TraceHop {
+            ttl,
+            address: Some(address.into()),
+            hostname: None,
+            latency_ms: Some(u64::from(ttl) * 4),
+        };
+        let backend = TestBackend::new(120, 36);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.language = Language::En;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Main;
+        model.diagnostics.tool = DiagnosticTool::Trace;
+        model.diagnostics.trace.hops = vec![hop(1, "192.168.1.1"), hop(2, "193.0.6.139")];
+        model.diagnostics.trace.selected = 1;
+        model.diagnostics.trace.asn.origins = vec![iptools_core::AsnOrigin {
+            address: "193.0.6.139".into(),
+            prefix: Some("193.0.0.0/21".into()),
+            asn: Some(3333),
+            holder: Some("RIPE-NCC-AS".into()),
+            path: vec![8492, 3333],
+        }];
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(
+            text.lines()
+                .any(|line| line.contains("193.0.6.139") && line.contains("AS3333 RIPE-NCC-AS")),
+            "{text}"
+        );
+        assert!(
+            text.contains("193.0.0.0/21  AS path AS8492 → AS3333"),
+            "{text}"
+        );
+    }
+
     #[test]
     fn public_speed_and_link_quality_render_full_shared_results_in_both_languages() {
         for language in [Language::En, Language::Zh] {
@@ -3567,11 +11843,19 @@ mod tests {
                 elapsed_ms: 2_000,
                 bytes: 16_000_000,
                 bytes_per_second: 8_000_000,
+                phase: SpeedPhase::Download,
             }];
             model.diagnostics.public_speed.summary = Some(iptools_core::SpeedSummary {
                 average_bytes_per_second: 7_000_000,
                 peak_bytes_per_second: 8_000_000,
                 total_bytes: 16_000_000,
+                upload_bytes_per_second: None,
+                bufferbloat: Some(BufferbloatSummary {
+                    idle_ms: Some(18.0),
+                    download_ms: Some(64.0),
+                    upload_ms: None,
+                    grade: Some(iptools_core::BufferbloatGrade::C),
+                }),
             });
             let mut ui = UiState::default();
             terminal
@@ -3580,6 +11864,7 @@ mod tests {
             let text = terminal.backend().to_string();
             assert!(text.contains("64.00 Mbps"), "{text}");
             assert!(text.contains("demo.invalid"), "{text}");
+            assert!(text.contains("18 ms") && text.contains("64 ms"), "{text}");
 
             let adapter = iptools_core::LinkQualityAdapter {
                 key: "wifi-guid".into(),
@@ -3676,7 +11961,7 @@ mod tests {
                             .draw(|frame| render(frame, &model, &mut ui))
                             .unwrap();
                         let text = terminal.backend().to_string();
-                        assert!(text.contains(tool_label(tool, language)), "{text}");
+                        assert!(text.contains(&*tool_label(tool, language)), "{text}");
                         assert!(ui.diagnostic_main.is_some());
                     }
                 }
@@ -3693,8 +11978,8 @@ mod tests {
                 let mut model = AppModel::default();
                 model.page = Page::Settings;
                 model.language = language;
-                model.settings_selected = 3;
-                model.settings_just_reset = true;
+                model.settings.selected = 2;
+                model.settings.done = Some(SettingId::ClearSession);
                 model.scan_concurrency = 120;
                 let mut ui = UiState::default();
                 terminal
@@ -3710,18 +11995,56 @@ mod tests {
                     }),
                     "{text}"
                 );
-                assert_eq!(ui.hit_test(2, 7), Some(Action::SelectSetting(3)));
+                assert_eq!(ui.hit_test(2, 7), Some(Action::SelectSetting(2)));
             }
         }
     }
 
+    #[test]
+    fn settings_scroll_to_the_selection_and_show_validation_errors() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Settings;
+        model.settings.selected = SETTINGS.len() - 1;
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Public IP endpoints"), "{text}");
+        assert!(!text.contains("Low-power mode"), "{text}");
+        let (area, _) = ui
+            .settings_regions
+            .iter()
+            .find(|(_, index)| *index == SETTINGS.len() - 1)
+            .expect("selected row is clickable");
+        assert_eq!(
+            ui.hit_test(area.x + 1, area.y),
+            Some(Action::SelectSetting(SETTINGS.len() - 1))
+        );
+
+        model.settings.selected = 7;
+        model.settings.input = Some(("50".into(), 2));
+        model.settings.error = Some(SettingError::OutOfRange {
+            min: 100,
+            max: 10_000,
+        });
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        assert!(text.contains("Must be between 100 and 10000"), "{text}");
+    }
+
     #[test]
     fn native_footer_and_help_show_configured_keybindings() {
         let backend = TestBackend::new(120, 36);
         let mut terminal = Terminal::new(backend).unwrap();
         let mut model = AppModel::default();
         model.demo = false;
-        model.show_help = true;
+        model.open_popup(Popup::Help);
         model
             .keybindings
             .insert("quit".into(), vec!["Ctrl+x".into()]);
@@ -3738,7 +12061,7 @@ mod tests {
         let text = terminal.backend().to_string();
         assert!(text.contains("[n/p] Switch"), "{text}");
         assert!(text.contains("[Ctrl+x] Quit"), "{text}");
-        assert!(text.contains("Native bindings are loaded from config.json"));
+        assert!(text.contains("Native bindings are loaded from config.toml"));
         let language = ui
             .footer_regions
             .iter()
@@ -3751,6 +12074,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn footer_hints_follow_the_focus_and_give_way_on_narrow_terminals() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut model = AppModel::default();
+        model.page = Page::Diagnostics;
+        model.diagnostics.focused = true;
+        model.diagnostics.focus = DiagnosticFocus::Config;
+        model
+            .keybindings
+            .insert("history".into(), vec!["F3".into()]);
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let footer = text.lines().nth(29).unwrap();
+        assert!(
+            footer.contains("[Up/Down] parameter  [F3] history"),
+            "{footer}"
+        );
+        assert!(footer.contains("[Tab] tools  [Esc] leave"), "{footer}");
+        assert!(!footer.contains("Switch"), "{footer}");
+        let column = footer.find("[F3]").unwrap();
+        let column = footer[..column].width() as u16;
+        assert_eq!(ui.hit_test(column, 29), Some(Action::History));
+
+        let backend = TestBackend::new(60, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let text = terminal.backend().to_string();
+        let footer = text.lines().nth(19).unwrap();
+        assert!(footer.contains("[Up/Down] parameter"), "{footer}");
+        assert!(footer.contains("[F1] Help  [Ctrl+C] Quit"), "{footer}");
+        assert!(!footer.contains("leave"), "{footer}");
+    }
+
     #[test]
     fn full_diagnostic_panels_and_history_rows_are_clickable() {
         let backend = TestBackend::new(120, 36);
@@ -3837,6 +12199,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn override_files_replace_text_and_palette_colours() {
+        let mut model = AppModel::default();
+        model
+            .overrides
+            .locale
+            .en
+            .insert("Dashboard".into(), "Overview".into());
+        model.overrides.theme.cyan = Some(Rgb(1, 2, 3));
+        let mut terminal = Terminal::new(TestBackend::new(120, 36)).unwrap();
+        let mut ui = UiState::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let screen = terminal.backend().to_string();
+        assert!(screen.contains("Overview") && !screen.contains("Dashboard"));
+        let cells = &terminal.backend().buffer().content;
+        assert!(cells.iter().any(|cell| cell.fg == Color::Rgb(1, 2, 3)));
+        // Colours the file leaves out keep the classic ones.
+        assert!(cells.iter().any(|cell| cell.fg == Color::Green));
+
+        model
+            .overrides
+            .errors
+            .push("theme.toml: expected `=` at line 2".into());
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert!(
+            terminal
+                .backend()
+                .to_string()
+                .contains("Not applied: theme.toml: expected `=` at line 2")
+        );
+
+        model.overrides.audit.en.unused.push("Dashbord".into());
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert!(
+            terminal
+                .backend()
+                .to_string()
+                .contains("locale.toml: 0 missing, 1 unused")
+        );
+
+        // An edit shows on the next frame; removing the entry restores the
+        // built-in text and takes `tr` back to its lookup-free path.
+        model
+            .overrides
+            .locale
+            .en
+            .insert("Dashboard".into(), "Summary".into());
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        let screen = terminal.backend().to_string();
+        assert!(screen.contains("Summary") && !screen.contains("Overview"));
+        model.overrides = iptools_core::Overrides::default();
+        terminal
+            .draw(|frame| render(frame, &model, &mut ui))
+            .unwrap();
+        assert!(terminal.backend().to_string().contains("Dashboard"));
+        assert!(!OVERRIDES_ACTIVE.get());
+        assert!(matches!(
+            tr(Language::En, "仪表盘", "Dashboard"),
+            Cow::Borrowed("Dashboard")
+        ));
+    }
+
     #[test]
     fn completion_ghosts_use_a_distinct_theme_semantic_color() {
         for (theme, expected) in [
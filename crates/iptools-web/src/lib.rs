@@ -107,7 +107,7 @@ mod wasm {
             if clock_second != self.last_clock_second {
                 self.last_clock_second = clock_second;
                 self.state_revision = self.state_revision.saturating_add(1);
-                self.model.update(Message::Clock(browser_clock()));
+                self.model.update(browser_clock());
             }
             for event in self.runtime.advance(delta) {
                 self.state_revision = self.state_revision.saturating_add(1);
@@ -140,7 +140,10 @@ mod wasm {
         if let Some(language) = params.get("lang") {
             config.language = parse_language(&language);
         }
-        config.scan_concurrency = config.scan_concurrency.clamp(10, 500);
+        config.migrate();
+        // Browser storage is only written by the app itself, so repairs are
+        // silent here; the native popup is for hand-edited files.
+        config.validate();
         config
     }
 
@@ -222,6 +225,7 @@ mod wasm {
             }
             let WebApp { model, ui, .. } = &mut *state;
             iptools_ui::render(frame, model, ui);
+            model.page_rows = ui.page_rows();
             if should_mark {
                 mark_rendered(input_generation, state_revision);
             }
@@ -243,11 +247,29 @@ mod wasm {
             if inside_terminal {
                 let key = event.key();
                 let controlled_action = event.ctrl_key()
-                    && matches!(key.to_ascii_lowercase().as_str(), "c" | "l" | "q" | "r");
+                    && matches!(
+                        key.to_ascii_lowercase().as_str(),
+                        "c" | "f" | "l" | "p" | "q" | "r" | "s"
+                    );
+                let tab_shortcut = event.alt_key()
+                    && key.len() == 1
+                    && matches!(key.chars().next(), Some('1'..='9'));
                 if controlled_action
+                    || tab_shortcut
                     || matches!(
                         key.as_str(),
-                        "F1" | "Tab" | " " | "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight"
+                        "F1" | "F2"
+                            | "F3"
+                            | "F4"
+                            | "F5"
+                            | "F6"
+                            | "F10"
+                            | "Tab"
+                            | " "
+                            | "ArrowUp"
+                            | "ArrowDown"
+                            | "ArrowLeft"
+                            | "ArrowRight"
                     )
                 {
                     event.prevent_default();
@@ -321,6 +343,8 @@ mod wasm {
             "Delete" => KeyCode::Delete,
             "Home" => KeyCode::Home,
             "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
             "ArrowUp" => KeyCode::Up,
             "ArrowDown" => KeyCode::Down,
             "ArrowLeft" => KeyCode::Left,
@@ -481,17 +505,21 @@ mod wasm {
         )
     }
 
-    fn browser_clock() -> String {
+    fn browser_clock() -> Message {
         let date = js_sys::Date::new_0();
-        format!(
-            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-            date.get_full_year(),
-            date.get_month() + 1,
-            date.get_date(),
-            date.get_hours(),
-            date.get_minutes(),
-            date.get_seconds(),
-        )
+        Message::Clock {
+            local: format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                date.get_full_year(),
+                date.get_month() + 1,
+                date.get_date(),
+                date.get_hours(),
+                date.get_minutes(),
+                date.get_seconds(),
+            ),
+            // getTimezoneOffset is UTC minus local time.
+            utc_offset_minutes: -(date.get_timezone_offset() as i32),
+        }
     }
 
     fn performance_now() -> f64 {